@@ -2,13 +2,15 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use dashmap::DashMap;
+use std::collections::HashMap;
 use std::future::Future;
 use std::pin::Pin;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::task::{Context, Poll};
 use std::time::Instant;
 
 use once_cell::sync::OnceCell;
+use prometheus::proto::{LabelPair, Metric, MetricFamily, MetricType};
 use prometheus::{register_int_gauge_vec_with_registry, IntGaugeVec, Registry};
 use tap::TapFallible;
 use tracing::warn;
@@ -16,6 +18,8 @@ use tracing::warn;
 pub use scopeguard;
 use uuid::Uuid;
 
+pub mod push;
+
 #[derive(Debug)]
 pub struct Metrics {
     pub tasks: IntGaugeVec,
@@ -220,6 +224,39 @@ impl<F: Future> Future for MonitoredScopeFuture<F> {
 
 type RegistryID = Uuid;
 
+/// How a [`RegistryService`] exposes the metrics of a registry added to it.
+#[derive(Clone, Debug, Default)]
+pub struct RegistryOptions {
+    /// Prefixes the names of the metrics, as `<prefix>_<name>`.
+    pub prefix: Option<String>,
+    /// Labels the metrics with the epoch of the registry, unless they already are. The counters
+    /// and the histograms of the registry continue from the ones of the registries of the previous
+    /// epochs removed from the service, so that they don't reset at every epoch change. The gauges
+    /// are exposed as they are.
+    pub epoch: Option<u64>,
+}
+
+/// The label of the epoch of the metrics of the registries added with [`RegistryOptions::epoch`].
+pub const EPOCH_LABEL: &str = "epoch";
+
+struct RegisteredRegistry {
+    registry: Registry,
+    options: RegistryOptions,
+}
+
+/// The value of a counter or a histogram of a removed registry, by name and labels other than
+/// the epoch.
+#[derive(Clone)]
+enum RetiredValue {
+    Counter(f64),
+    Histogram {
+        count: u64,
+        sum: f64,
+        /// The cumulative count of every bucket, by upper bound.
+        buckets: Vec<(f64, u64)>,
+    },
+}
+
 /// A service to manage the prometheus registries. This service allow us to create
 /// a new Registry on demand and keep it accessible for processing/polling.
 /// The service can be freely cloned/shared across threads.
@@ -227,7 +264,9 @@ type RegistryID = Uuid;
 pub struct RegistryService {
     // Holds a Registry that is supposed to be used
     default_registry: Registry,
-    registries_by_id: Arc<DashMap<Uuid, Registry>>,
+    registries_by_id: Arc<DashMap<Uuid, RegisteredRegistry>>,
+    // The last values of the counters and histograms of the removed registries of an epoch
+    retired: Arc<Mutex<HashMap<String, RetiredValue>>>,
 }
 
 impl RegistryService {
@@ -237,6 +276,7 @@ impl RegistryService {
         Self {
             default_registry,
             registries_by_id: Arc::new(DashMap::new()),
+            retired: Arc::new(Mutex::new(HashMap::new())),
         }
     }
 
@@ -251,10 +291,16 @@ impl RegistryService {
     // As this can be quite serious for the operation of the node we don't want to accidentally
     // swap an existing registry - we expected a removal to happen explicitly.
     pub fn add(&self, registry: Registry) -> RegistryID {
+        self.add_with_options(registry, RegistryOptions::default())
+    }
+
+    // Adds a new registry to the service as `add` does, its metrics exposed with the prefix and
+    // the epoch label of the `options`.
+    pub fn add_with_options(&self, registry: Registry, options: RegistryOptions) -> RegistryID {
         let registry_id = Uuid::new_v4();
         if self
             .registries_by_id
-            .insert(registry_id, registry)
+            .insert(registry_id, RegisteredRegistry { registry, options })
             .is_some()
         {
             panic!("Other Registry already detected for the same id {registry_id}");
@@ -264,9 +310,41 @@ impl RegistryService {
     }
 
     // Removes the registry from the service. If Registry existed then this method returns true,
-    // otherwise false is returned instead.
+    // otherwise false is returned instead. The counters and histograms of a registry of an epoch
+    // are carried over to the registries of the next epochs.
     pub fn remove(&self, registry_id: RegistryID) -> bool {
-        self.registries_by_id.remove(&registry_id).is_some()
+        let Some((_, registered)) = self.registries_by_id.remove(&registry_id) else {
+            return false;
+        };
+        if registered.options.epoch.is_some() {
+            let families = self.gather_registered(&registered);
+            let mut retired = self.retired.lock().unwrap();
+            for family in &families {
+                for metric in family.get_metric() {
+                    let key = series_key(family.get_name(), metric);
+                    let value = match family.get_field_type() {
+                        MetricType::COUNTER => {
+                            RetiredValue::Counter(metric.get_counter().get_value())
+                        }
+                        MetricType::HISTOGRAM => {
+                            let histogram = metric.get_histogram();
+                            RetiredValue::Histogram {
+                                count: histogram.get_sample_count(),
+                                sum: histogram.get_sample_sum(),
+                                buckets: histogram
+                                    .get_bucket()
+                                    .iter()
+                                    .map(|b| (b.get_upper_bound(), b.get_cumulative_count()))
+                                    .collect(),
+                            }
+                        }
+                        _ => continue,
+                    };
+                    retired.insert(key, value);
+                }
+            }
+        }
+        true
     }
 
     // Returns all the registries of the service
@@ -274,7 +352,7 @@ impl RegistryService {
         let mut registries: Vec<Registry> = self
             .registries_by_id
             .iter()
-            .map(|r| r.value().clone())
+            .map(|r| r.value().registry.clone())
             .collect();
         registries.push(self.default_registry.clone());
 
@@ -283,14 +361,108 @@ impl RegistryService {
 
     // Returns all the metric families from the registries that a service holds.
     pub fn gather_all(&self) -> Vec<prometheus::proto::MetricFamily> {
-        self.get_all().iter().flat_map(|r| r.gather()).collect()
+        let mut families: Vec<_> = self
+            .registries_by_id
+            .iter()
+            .flat_map(|r| self.gather_registered(r.value()))
+            .collect();
+        families.extend(self.default_registry.gather());
+        families
+    }
+
+    fn gather_registered(&self, registered: &RegisteredRegistry) -> Vec<MetricFamily> {
+        let mut families = registered.registry.gather();
+        let options = &registered.options;
+        if let Some(prefix) = &options.prefix {
+            for family in &mut families {
+                let name = format!("{prefix}_{}", family.get_name());
+                family.set_name(name);
+            }
+        }
+        let Some(epoch) = options.epoch else {
+            return families;
+        };
+
+        let retired = self.retired.lock().unwrap();
+        for family in &mut families {
+            let name = family.get_name().to_string();
+            let field_type = family.get_field_type();
+            for metric in family.mut_metric().iter_mut() {
+                if let Some(retired) = retired.get(&series_key(&name, metric)) {
+                    carry_over(field_type, metric, retired);
+                }
+                // Some metrics are already labelled with their epoch.
+                if metric
+                    .get_label()
+                    .iter()
+                    .any(|l| l.get_name() == EPOCH_LABEL)
+                {
+                    continue;
+                }
+                let mut label = LabelPair::default();
+                label.set_name(EPOCH_LABEL.to_string());
+                label.set_value(epoch.to_string());
+                metric.mut_label().push(label);
+                metric
+                    .mut_label()
+                    .sort_by(|a, b| a.get_name().cmp(b.get_name()));
+            }
+        }
+        families
+    }
+}
+
+/// Identifies a series across the epochs, by its name and its labels other than the epoch.
+fn series_key(name: &str, metric: &Metric) -> String {
+    let mut key = name.to_string();
+    for label in metric.get_label() {
+        if label.get_name() != EPOCH_LABEL {
+            key.push_str(&format!(",{}={}", label.get_name(), label.get_value()));
+        }
+    }
+    key
+}
+
+/// Adds the value of the same series of a removed registry to `metric`. The histograms only
+/// continue from the ones with the same buckets.
+fn carry_over(field_type: MetricType, metric: &mut Metric, retired: &RetiredValue) {
+    match (field_type, retired) {
+        (MetricType::COUNTER, RetiredValue::Counter(value)) => {
+            let counter = metric.mut_counter();
+            counter.set_value(counter.get_value() + value);
+        }
+        (
+            MetricType::HISTOGRAM,
+            RetiredValue::Histogram {
+                count,
+                sum,
+                buckets,
+            },
+        ) => {
+            let histogram = metric.mut_histogram();
+            let same_buckets = histogram.get_bucket().len() == buckets.len()
+                && histogram
+                    .get_bucket()
+                    .iter()
+                    .zip(buckets)
+                    .all(|(bucket, (upper_bound, _))| bucket.get_upper_bound() == *upper_bound);
+            if !same_buckets {
+                return;
+            }
+            histogram.set_sample_count(histogram.get_sample_count() + count);
+            histogram.set_sample_sum(histogram.get_sample_sum() + sum);
+            for (bucket, (_, retired_count)) in histogram.mut_bucket().iter_mut().zip(buckets) {
+                bucket.set_cumulative_count(bucket.get_cumulative_count() + retired_count);
+            }
+        }
+        _ => {}
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::RegistryService;
-    use prometheus::{IntCounter, Registry};
+    use crate::{RegistryOptions, RegistryService, EPOCH_LABEL};
+    use prometheus::{IntCounter, IntGauge, Registry};
 
     #[test]
     fn registry_service() {
@@ -375,4 +547,56 @@ mod tests {
         assert_eq!(metric_1.get_name(), "sui_counter_2");
         assert_eq!(metric_1.get_help(), "counter_2_desc");
     }
+
+    #[test]
+    fn registry_service_epochs() {
+        let registry_service = RegistryService::new(Registry::new());
+        let epoch_registry = |epoch| {
+            let registry = Registry::new();
+            let counter = IntCounter::new("counter", "counter_desc").unwrap();
+            let gauge = IntGauge::new("gauge", "gauge_desc").unwrap();
+            registry.register(Box::new(counter.clone())).unwrap();
+            registry.register(Box::new(gauge.clone())).unwrap();
+            let options = RegistryOptions {
+                prefix: Some("narwhal".to_string()),
+                epoch: Some(epoch),
+            };
+            let id = registry_service.add_with_options(registry, options);
+            (id, counter, gauge)
+        };
+        let gather = || {
+            let mut metrics = registry_service.gather_all();
+            metrics.sort_by(|m1, m2| Ord::cmp(m1.get_name(), m2.get_name()));
+            metrics
+        };
+
+        // GIVEN the registry of the epoch 0
+        let (id, counter, gauge) = epoch_registry(0);
+        counter.inc_by(3);
+        gauge.set(5);
+
+        // THEN its metrics are prefixed and labelled with the epoch
+        let metrics = gather();
+        assert_eq!(metrics.len(), 2);
+        assert_eq!(metrics[0].get_name(), "narwhal_counter");
+        assert_eq!(metrics[1].get_name(), "narwhal_gauge");
+        let labels = metrics[0].get_metric()[0].get_label();
+        assert_eq!(labels.len(), 1);
+        assert_eq!(labels[0].get_name(), EPOCH_LABEL);
+        assert_eq!(labels[0].get_value(), "0");
+
+        // WHEN the epoch changes
+        assert!(registry_service.remove(id));
+        let (_, counter, gauge) = epoch_registry(1);
+        counter.inc();
+        gauge.set(1);
+
+        // THEN the counter continues from its value of the previous epoch, but not the gauge
+        let metrics = gather();
+        assert_eq!(metrics.len(), 2);
+        let counter = &metrics[0].get_metric()[0];
+        assert_eq!(counter.get_counter().get_value(), 4.0);
+        assert_eq!(counter.get_label()[0].get_value(), "1");
+        assert_eq!(metrics[1].get_metric()[0].get_gauge().get_value(), 1.0);
+    }
 }
@@ -0,0 +1,355 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Pushing of the metrics of a [`RegistryService`], for the nodes which can't be scraped (e.g.
+//! behind a NAT).
+//!
+//! The metrics of all the registries are pushed every [`PushConfig::interval_secs`], either to a
+//! Prometheus push gateway in the text format, or to a remote-write endpoint (e.g. Prometheus
+//! with `--web.enable-remote-write-receiver`, Mimir or Grafana Cloud) as snappy-compressed
+//! protobuf.
+use crate::RegistryService;
+use prometheus::proto::{MetricFamily, MetricType};
+use prometheus::{Encoder, TextEncoder};
+use prost::Message;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Where and how often to push the metrics.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct PushConfig {
+    /// The address of the push gateway (e.g. `http://gateway:9091`), or the full url of the
+    /// remote-write endpoint (e.g. `http://prometheus:9090/api/v1/write`).
+    pub url: String,
+    #[serde(default)]
+    pub mode: PushMode,
+    /// The job the metrics are grouped under by the push gateway, and labelled with when
+    /// remote-written.
+    #[serde(default = "default_job")]
+    pub job: String,
+    #[serde(default = "default_interval_secs")]
+    pub interval_secs: u64,
+    /// The credentials of the basic authentication to the endpoint, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub basic_auth: Option<BasicAuth>,
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum PushMode {
+    /// A Prometheus push gateway, the metrics of the job replaced at every push.
+    #[default]
+    Gateway,
+    /// The Prometheus remote-write protocol.
+    RemoteWrite,
+}
+
+#[derive(Clone, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct BasicAuth {
+    pub username: String,
+    pub password: String,
+}
+
+// The password is redacted, since the configs are logged at startup.
+impl fmt::Debug for BasicAuth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BasicAuth")
+            .field("username", &self.username)
+            .field("password", &"<redacted>")
+            .finish()
+    }
+}
+
+fn default_job() -> String {
+    "sui-node".to_string()
+}
+
+fn default_interval_secs() -> u64 {
+    60
+}
+
+impl PushConfig {
+    pub fn interval(&self) -> Duration {
+        Duration::from_secs(self.interval_secs.max(1))
+    }
+
+    /// The url the metrics of the job are pushed to in the gateway mode, with the job escaped as
+    /// a path segment.
+    fn gateway_url(&self) -> Result<reqwest::Url, String> {
+        let mut url = reqwest::Url::parse(&self.url)
+            .map_err(|e| format!("invalid push gateway url {}: {e}", self.url))?;
+        url.path_segments_mut()
+            .map_err(|_| format!("invalid push gateway url {}", self.url))?
+            .pop_if_empty()
+            .extend(["metrics", "job", &self.job]);
+        Ok(url)
+    }
+}
+
+/// Pushes the metrics of `registry_service` as configured by `config` until the task is aborted.
+/// A failed push is logged and retried at the next interval.
+pub fn start_push_task(registry_service: RegistryService, config: PushConfig) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let client = match reqwest::Client::builder().timeout(REQUEST_TIMEOUT).build() {
+            Ok(client) => client,
+            Err(e) => {
+                warn!("Failed to build the client pushing the metrics: {e}");
+                return;
+            }
+        };
+        let mut interval = tokio::time::interval(config.interval());
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            let families = registry_service.gather_all();
+            match push(&client, &config, &families).await {
+                Ok(()) => debug!(
+                    "Pushed {} metric families to {}",
+                    families.len(),
+                    config.url
+                ),
+                Err(e) => warn!("Failed to push the metrics to {}: {e}", config.url),
+            }
+        }
+    })
+}
+
+async fn push(
+    client: &reqwest::Client,
+    config: &PushConfig,
+    families: &[MetricFamily],
+) -> Result<(), String> {
+    let request = match config.mode {
+        PushMode::Gateway => {
+            let mut body = Vec::new();
+            TextEncoder
+                .encode(families, &mut body)
+                .map_err(|e| format!("unable to encode metrics: {e}"))?;
+            client
+                .put(config.gateway_url()?)
+                .header(reqwest::header::CONTENT_TYPE, TextEncoder.format_type())
+                .body(body)
+        }
+        PushMode::RemoteWrite => {
+            let request = write_request(families, &config.job, now_ms());
+            let body = snap::raw::Encoder::new()
+                .compress_vec(&request.encode_to_vec())
+                .map_err(|e| format!("unable to compress metrics: {e}"))?;
+            client
+                .post(&config.url)
+                .header(reqwest::header::CONTENT_TYPE, "application/x-protobuf")
+                .header(reqwest::header::CONTENT_ENCODING, "snappy")
+                .header("X-Prometheus-Remote-Write-Version", "0.1.0")
+                .body(body)
+        }
+    };
+    let request = match &config.basic_auth {
+        Some(auth) => request.basic_auth(&auth.username, Some(&auth.password)),
+        None => request,
+    };
+    let response = request.send().await.map_err(|e| e.to_string())?;
+    let status = response.status();
+    if status.is_success() {
+        Ok(())
+    } else {
+        let body = response.text().await.unwrap_or_default();
+        Err(format!("{status}: {body}"))
+    }
+}
+
+fn now_ms() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis() as i64)
+        .unwrap_or_default()
+}
+
+/// The messages of the remote-write protocol, as defined by `prometheus/prompb/remote.proto`
+/// and `types.proto`.
+#[derive(Clone, PartialEq, Message)]
+struct WriteRequest {
+    #[prost(message, repeated, tag = "1")]
+    timeseries: Vec<TimeSeries>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct TimeSeries {
+    #[prost(message, repeated, tag = "1")]
+    labels: Vec<Label>,
+    #[prost(message, repeated, tag = "2")]
+    samples: Vec<Sample>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct Label {
+    #[prost(string, tag = "1")]
+    name: String,
+    #[prost(string, tag = "2")]
+    value: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct Sample {
+    #[prost(double, tag = "1")]
+    value: f64,
+    #[prost(int64, tag = "2")]
+    timestamp: i64,
+}
+
+/// The series of `families` as remote-written at `timestamp`, with the `job` label. The
+/// histograms and the summaries are split into the series of their buckets or quantiles, sum
+/// and count, as Prometheus does when scraping them.
+fn write_request(families: &[MetricFamily], job: &str, timestamp: i64) -> WriteRequest {
+    let mut timeseries = Vec::new();
+    for family in families {
+        let name = family.get_name();
+        for metric in family.get_metric() {
+            let labels: Vec<_> = metric
+                .get_label()
+                .iter()
+                .map(|label| (label.get_name(), label.get_value().to_string()))
+                .collect();
+            let mut add = |suffix: &str, extra: Option<(&str, String)>, value: f64| {
+                let mut series_labels = vec![
+                    Label {
+                        name: "__name__".to_string(),
+                        value: format!("{name}{suffix}"),
+                    },
+                    Label {
+                        name: "job".to_string(),
+                        value: job.to_string(),
+                    },
+                ];
+                series_labels.extend(labels.iter().chain(extra.as_ref()).map(|(name, value)| {
+                    Label {
+                        name: name.to_string(),
+                        value: value.clone(),
+                    }
+                }));
+                // The receivers expect the labels sorted by name.
+                series_labels.sort_by(|a, b| a.name.cmp(&b.name));
+                timeseries.push(TimeSeries {
+                    labels: series_labels,
+                    samples: vec![Sample { value, timestamp }],
+                });
+            };
+            match family.get_field_type() {
+                MetricType::COUNTER => add("", None, metric.get_counter().get_value()),
+                MetricType::GAUGE => add("", None, metric.get_gauge().get_value()),
+                MetricType::UNTYPED => add("", None, metric.get_untyped().get_value()),
+                MetricType::HISTOGRAM => {
+                    let histogram = metric.get_histogram();
+                    for bucket in histogram.get_bucket() {
+                        let le = bucket.get_upper_bound().to_string();
+                        add(
+                            "_bucket",
+                            Some(("le", le)),
+                            bucket.get_cumulative_count() as f64,
+                        );
+                    }
+                    let count = histogram.get_sample_count() as f64;
+                    add("_bucket", Some(("le", "+Inf".to_string())), count);
+                    add("_sum", None, histogram.get_sample_sum());
+                    add("_count", None, count);
+                }
+                MetricType::SUMMARY => {
+                    let summary = metric.get_summary();
+                    for quantile in summary.get_quantile() {
+                        let q = quantile.get_quantile().to_string();
+                        add("", Some(("quantile", q)), quantile.get_value());
+                    }
+                    add("_sum", None, summary.get_sample_sum());
+                    add("_count", None, summary.get_sample_count() as f64);
+                }
+            }
+        }
+    }
+    WriteRequest { timeseries }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prometheus::{Histogram, HistogramOpts, IntCounterVec, Opts, Registry};
+
+    #[test]
+    fn remote_write_series() {
+        let registry = Registry::new();
+        let counter =
+            IntCounterVec::new(Opts::new("requests", "requests_desc"), &["route"]).unwrap();
+        counter.with_label_values(&["/a"]).inc_by(3);
+        registry.register(Box::new(counter)).unwrap();
+        let histogram =
+            Histogram::with_opts(HistogramOpts::new("latency", "latency_desc").buckets(vec![1.0]))
+                .unwrap();
+        histogram.observe(0.5);
+        histogram.observe(2.0);
+        registry.register(Box::new(histogram)).unwrap();
+
+        let request = write_request(&registry.gather(), "node", 42);
+        let series: Vec<_> = request
+            .timeseries
+            .iter()
+            .map(|series| {
+                let labels: Vec<_> = series
+                    .labels
+                    .iter()
+                    .map(|label| format!("{}={}", label.name, label.value))
+                    .collect();
+                assert_eq!(series.samples.len(), 1);
+                assert_eq!(series.samples[0].timestamp, 42);
+                (labels.join(","), series.samples[0].value)
+            })
+            .collect();
+        assert_eq!(
+            series,
+            vec![
+                ("__name__=latency_bucket,job=node,le=1".to_string(), 1.0),
+                ("__name__=latency_bucket,job=node,le=+Inf".to_string(), 2.0),
+                ("__name__=latency_sum,job=node".to_string(), 2.5),
+                ("__name__=latency_count,job=node".to_string(), 2.0),
+                ("__name__=requests,job=node,route=/a".to_string(), 3.0),
+            ]
+        );
+
+        // The request round-trips through its encoding.
+        let decoded = WriteRequest::decode(request.encode_to_vec().as_slice()).unwrap();
+        assert_eq!(decoded, request);
+    }
+
+    #[test]
+    fn push_config_defaults() {
+        let config: PushConfig = serde_yaml::from_str("url: http://gateway:9091").unwrap();
+        assert_eq!(config.mode, PushMode::Gateway);
+        assert_eq!(config.job, "sui-node");
+        assert_eq!(config.interval(), Duration::from_secs(60));
+        assert_eq!(config.basic_auth, None);
+
+        let config: PushConfig = serde_yaml::from_str(
+            "url: http://prometheus:9090/api/v1/write\nmode: remote-write\ninterval-secs: 15\nbasic-auth:\n  username: user\n  password: secret",
+        )
+        .unwrap();
+        assert_eq!(config.mode, PushMode::RemoteWrite);
+        assert_eq!(config.interval(), Duration::from_secs(15));
+        let basic_auth = config.basic_auth.unwrap();
+        assert_eq!(basic_auth.username, "user");
+        assert!(!format!("{basic_auth:?}").contains("secret"));
+    }
+
+    #[test]
+    fn gateway_url_escapes_the_job() {
+        let config: PushConfig =
+            serde_yaml::from_str("url: http://gateway:9091/\njob: sui node/1").unwrap();
+        assert_eq!(
+            config.gateway_url().unwrap().as_str(),
+            "http://gateway:9091/metrics/job/sui%20node%2F1"
+        );
+    }
+}
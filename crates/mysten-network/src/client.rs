@@ -3,10 +3,12 @@
 
 use crate::{
     config::Config,
-    multiaddr::{parse_dns, parse_ip4, parse_ip6},
+    multiaddr::{is_quic, parse_dns, parse_ip4, parse_ip6, parse_quic},
+    quic::{self, QuicPublicKey, QuicSettings},
 };
 use eyre::{eyre, Context, Result};
 use multiaddr::{Multiaddr, Protocol};
+use std::net::SocketAddr;
 use tonic::transport::{Channel, Endpoint, Uri};
 
 pub async fn connect(address: &Multiaddr) -> Result<Channel> {
@@ -15,22 +17,32 @@ pub async fn connect(address: &Multiaddr) -> Result<Channel> {
 }
 
 pub fn connect_lazy(address: &Multiaddr) -> Result<Channel> {
-    let channel = endpoint_from_multiaddr(address)?.connect_lazy();
+    let channel = endpoint_from_multiaddr(address)?.connect_lazy()?;
     Ok(channel)
 }
 
-pub(crate) async fn connect_with_config(address: &Multiaddr, config: &Config) -> Result<Channel> {
+pub(crate) async fn connect_with_config(
+    address: &Multiaddr,
+    config: &Config,
+    server_key: Option<QuicPublicKey>,
+) -> Result<Channel> {
     let channel = endpoint_from_multiaddr(address)?
         .apply_config(config)
+        .with_quic_server_key(server_key)
         .connect()
         .await?;
     Ok(channel)
 }
 
-pub(crate) fn connect_lazy_with_config(address: &Multiaddr, config: &Config) -> Result<Channel> {
+pub(crate) fn connect_lazy_with_config(
+    address: &Multiaddr,
+    config: &Config,
+    server_key: Option<QuicPublicKey>,
+) -> Result<Channel> {
     let channel = endpoint_from_multiaddr(address)?
         .apply_config(config)
-        .connect_lazy();
+        .with_quic_server_key(server_key)
+        .connect_lazy()?;
     Ok(channel)
 }
 
@@ -43,6 +55,11 @@ fn endpoint_from_multiaddr(addr: &Multiaddr) -> Result<MyEndpoint> {
             let uri = format!("{http_or_https}://{dns_name}:{tcp_port}");
             MyEndpoint::try_from_uri(uri)?
         }
+        Protocol::Ip4(_) | Protocol::Ip6(_) if is_quic(addr) => {
+            let socket_addr = parse_quic(addr)?;
+            let uri = format!("http://{socket_addr}");
+            MyEndpoint::try_from_uri(uri)?.with_quic_connector(socket_addr)
+        }
         Protocol::Ip4(_) => {
             let (socket_addr, http_or_https) = parse_ip4(addr)?;
             let uri = format!("{http_or_https}://{socket_addr}");
@@ -70,6 +87,9 @@ struct MyEndpoint {
     endpoint: Endpoint,
     #[cfg(unix)]
     uds_connector: Option<std::path::PathBuf>,
+    quic_connector: Option<SocketAddr>,
+    quic_server_key: Option<QuicPublicKey>,
+    quic_settings: QuicSettings,
 }
 
 impl MyEndpoint {
@@ -78,6 +98,9 @@ impl MyEndpoint {
             endpoint,
             #[cfg(unix)]
             uds_connector: None,
+            quic_connector: None,
+            quic_server_key: None,
+            quic_settings: QuicSettings::default(),
         }
     }
 
@@ -92,33 +115,81 @@ impl MyEndpoint {
     #[cfg(unix)]
     fn with_uds_connector(self, path: std::path::PathBuf) -> Self {
         Self {
-            endpoint: self.endpoint,
             uds_connector: Some(path),
+            ..self
+        }
+    }
+
+    fn with_quic_connector(self, address: SocketAddr) -> Self {
+        Self {
+            quic_connector: Some(address),
+            ..self
+        }
+    }
+
+    fn with_quic_server_key(self, server_key: Option<QuicPublicKey>) -> Self {
+        Self {
+            quic_server_key: server_key,
+            ..self
+        }
+    }
+
+    /// The address of the QUIC server, if any, along the key it must authenticate with.
+    fn quic_server(&self) -> Result<Option<(SocketAddr, QuicPublicKey)>> {
+        match (self.quic_connector, self.quic_server_key) {
+            (None, _) => Ok(None),
+            (Some(address), Some(server_key)) => Ok(Some((address, server_key))),
+            (Some(address), None) => Err(eyre!(
+                "connecting to the QUIC address {address} requires the public key of its server"
+            )),
         }
     }
 
     fn apply_config(mut self, config: &Config) -> Self {
         self.endpoint = apply_config_to_endpoint(config, self.endpoint);
+        self.quic_settings = QuicSettings::new(config);
         self
     }
 
-    fn connect_lazy(self) -> Channel {
+    fn connect_lazy(self) -> Result<Channel> {
+        if let Some((address, server_key)) = self.quic_server()? {
+            let settings = self.quic_settings;
+            return Ok(self.endpoint.connect_with_connector_lazy(tower::service_fn(
+                move |_: Uri| {
+                    // Open a stream over the QUIC connection to the peer
+                    quic::connect(address, server_key, settings)
+                },
+            )));
+        }
+
         #[cfg(unix)]
         if let Some(path) = self.uds_connector {
-            return self
-                .endpoint
-                .connect_with_connector_lazy(tower::service_fn(move |_: Uri| {
+            return Ok(self.endpoint.connect_with_connector_lazy(tower::service_fn(
+                move |_: Uri| {
                     let path = path.clone();
 
                     // Connect to a Uds socket
                     tokio::net::UnixStream::connect(path)
-                }));
+                },
+            )));
         }
 
-        self.endpoint.connect_lazy()
+        Ok(self.endpoint.connect_lazy())
     }
 
     async fn connect(self) -> Result<Channel> {
+        if let Some((address, server_key)) = self.quic_server()? {
+            let settings = self.quic_settings;
+            return self
+                .endpoint
+                .connect_with_connector(tower::service_fn(move |_: Uri| {
+                    // Open a stream over the QUIC connection to the peer
+                    quic::connect(address, server_key, settings)
+                }))
+                .await
+                .map_err(Into::into);
+        }
+
         #[cfg(unix)]
         if let Some(path) = self.uds_connector {
             return self
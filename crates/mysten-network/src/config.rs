@@ -3,6 +3,7 @@
 use crate::metrics::{DefaultMetricsCallbackProvider, MetricsCallbackProvider};
 use crate::{
     client::{connect_lazy_with_config, connect_with_config},
+    quic::QuicPublicKey,
     server::ServerBuilder,
 };
 use eyre::Result;
@@ -72,6 +73,13 @@ pub struct Config {
 
     // Only affects servers
     pub global_concurrency_limit: Option<usize>,
+
+    /// Set the interval of the keepalive packets of the QUIC connections, used for the
+    /// `/ip{4,6}/-/udp/-/quic` addresses. A QUIC connection to a peer is shared by all the
+    /// channels to it, and keeps the settings of the channel which opened it.
+    ///
+    /// Default is no keepalive (None)
+    pub quic_keep_alive_interval: Option<Duration>,
 }
 
 impl Config {
@@ -91,10 +99,30 @@ impl Config {
     }
 
     pub async fn connect(&self, addr: &Multiaddr) -> Result<Channel> {
-        connect_with_config(addr, self).await
+        connect_with_config(addr, self, None).await
     }
 
     pub fn connect_lazy(&self, addr: &Multiaddr) -> Result<Channel> {
-        connect_lazy_with_config(addr, self)
+        connect_lazy_with_config(addr, self, None)
+    }
+
+    /// Like [`Self::connect`], authenticating the server with its ed25519 public key on the QUIC
+    /// addresses, which require it.
+    pub async fn connect_with_server_key(
+        &self,
+        addr: &Multiaddr,
+        server_key: &QuicPublicKey,
+    ) -> Result<Channel> {
+        connect_with_config(addr, self, Some(*server_key)).await
+    }
+
+    /// Like [`Self::connect_lazy`], authenticating the server with its ed25519 public key on the
+    /// QUIC addresses, which require it.
+    pub fn connect_lazy_with_server_key(
+        &self,
+        addr: &Multiaddr,
+        server_key: &QuicPublicKey,
+    ) -> Result<Channel> {
+        connect_lazy_with_config(addr, self, Some(*server_key))
     }
 }
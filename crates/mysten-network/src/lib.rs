@@ -5,4 +5,5 @@ pub mod codec;
 pub mod config;
 pub mod metrics;
 pub mod multiaddr;
+pub mod quic;
 pub mod server;
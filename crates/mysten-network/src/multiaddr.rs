@@ -112,6 +112,42 @@ pub(crate) fn parse_ip6(address: &Multiaddr) -> Result<(SocketAddr, &'static str
     Ok((socket_addr, http_or_https))
 }
 
+// Whether an /ip{4,6}/ address is a QUIC address.
+pub(crate) fn is_quic(address: &Multiaddr) -> bool {
+    matches!(address.iter().nth(1), Some(Protocol::Udp(_)))
+}
+
+// Parse a full /ip{4,6}/-/udp/-/quic address
+pub(crate) fn parse_quic(address: &Multiaddr) -> Result<SocketAddr> {
+    let mut iter = address.iter();
+
+    let ip_addr = match iter
+        .next()
+        .ok_or_else(|| eyre!("unexpected end of multiaddr"))?
+    {
+        Protocol::Ip4(ip4_addr) => IpAddr::V4(ip4_addr),
+        Protocol::Ip6(ip6_addr) => IpAddr::V6(ip6_addr),
+        other => return Err(eyre!("expected ip4 or ip6 found {other}")),
+    };
+    let udp_port = match iter
+        .next()
+        .ok_or_else(|| eyre!("unexpected end of multiaddr"))?
+    {
+        Protocol::Udp(port) => port,
+        other => return Err(eyre!("expected udp found {other}")),
+    };
+    match iter
+        .next()
+        .ok_or_else(|| eyre!("unexpected end of multiaddr"))?
+    {
+        Protocol::Quic => (),
+        other => return Err(eyre!("expected quic found {other}")),
+    }
+    parse_end(&mut iter)?;
+
+    Ok(SocketAddr::new(ip_addr, udp_port))
+}
+
 // Parse a full /unix/-/{http,https} address
 #[cfg(unix)]
 pub(crate) fn parse_unix(address: &Multiaddr) -> Result<(Cow<'_, str>, &'static str)> {
@@ -0,0 +1,339 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! The QUIC transport of the gRPC connections, used for the `/ip{4,6}/-/udp/-/quic` addresses.
+//!
+//! Each gRPC (HTTP/2) connection is carried by its own bidirectional stream of a QUIC connection
+//! shared by all the connections of the process to the same peer, e.g. the primary and worker
+//! channels of an authority. The channels do not block each other on a packet loss, and the QUIC
+//! connection survives the path changes of the peers. Reconnections to a known peer resume the
+//! previous TLS session, but wait for the handshake before sending their requests: early (0-RTT)
+//! data could be replayed by an attacker, and the requests of the channels are not all idempotent.
+//! A resumed session skips the verification of the certificate, so the sessions are only resumed
+//! with the key of the server that established them: all the servers share the same name.
+//!
+//! As with anemo, the peers are identified by their ed25519 keys: the servers authenticate with a
+//! certificate self-signed with their private key, which the clients check against the public key
+//! they expect for the address.
+use crate::config::Config;
+use eyre::Result;
+use once_cell::sync::Lazy;
+use quinn::{Connection, Endpoint, RecvStream, SendStream, ServerConfig, TransportConfig};
+use rccheck::{ed25519_certgen::Ed25519, Certifiable, Psk};
+use std::{
+    collections::HashMap,
+    io,
+    net::{Ipv4Addr, Ipv6Addr, SocketAddr},
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+    time::Duration,
+};
+use tokio::{
+    io::{AsyncRead, AsyncWrite, ReadBuf},
+    runtime::Runtime,
+    sync::mpsc,
+};
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::transport::server::Connected;
+use tracing::debug;
+
+/// The protocol negotiated by the QUIC connections.
+const ALPN: &[u8] = b"h2";
+
+/// The name of the servers in their certificate.
+const SERVER_NAME: &str = "mysten-network";
+
+/// The number of streams accepted by a server but not yet served.
+const STREAM_BACKLOG: usize = 128;
+
+/// The number of TLS sessions the clients keep for each server key to resume them.
+const SESSION_CACHE_SIZE: usize = 8;
+
+/// The (32 bytes) ed25519 private key a server authenticates with.
+pub type QuicPrivateKey = [u8; 32];
+
+/// The (32 bytes) ed25519 public key a client expects the server of an address to authenticate with.
+pub type QuicPublicKey = [u8; 32];
+
+/// The settings of the QUIC connections.
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct QuicSettings {
+    keep_alive_interval: Option<Duration>,
+}
+
+impl QuicSettings {
+    pub(crate) fn new(config: &Config) -> Self {
+        Self {
+            keep_alive_interval: config.quic_keep_alive_interval,
+        }
+    }
+
+    fn transport_config(&self) -> Arc<TransportConfig> {
+        let mut transport = TransportConfig::default();
+        transport.keep_alive_interval(self.keep_alive_interval);
+        Arc::new(transport)
+    }
+}
+
+/// A bidirectional QUIC stream, carrying a gRPC connection.
+pub struct QuicStream {
+    send: SendStream,
+    recv: RecvStream,
+    remote_addr: SocketAddr,
+}
+
+impl AsyncRead for QuicStream {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.recv).poll_read(cx, buf)
+    }
+}
+
+impl AsyncWrite for QuicStream {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.send).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.send).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.send).poll_shutdown(cx)
+    }
+}
+
+/// The information about the connections of the requests received over QUIC.
+#[derive(Clone, Debug)]
+pub struct QuicConnectInfo {
+    pub remote_addr: SocketAddr,
+}
+
+impl Connected for QuicStream {
+    type ConnectInfo = QuicConnectInfo;
+
+    fn connect_info(&self) -> Self::ConnectInfo {
+        QuicConnectInfo {
+            remote_addr: self.remote_addr,
+        }
+    }
+}
+
+/// Listens for QUIC connections on `address`, returning the bound address and the streams opened
+/// by the clients. The server stops once the streams are dropped.
+pub(crate) fn listen(
+    address: SocketAddr,
+    private_key: &QuicPrivateKey,
+    settings: QuicSettings,
+) -> Result<(SocketAddr, ReceiverStream<io::Result<QuicStream>>)> {
+    let secret = ed25519_dalek::SecretKey::from_bytes(private_key)?;
+    let keypair = ed25519_dalek::Keypair {
+        public: (&secret).into(),
+        secret,
+    };
+    let certificate = Ed25519::keypair_to_certificate(vec![SERVER_NAME.to_string()], keypair)?;
+    let mut crypto = rustls::ServerConfig::builder()
+        .with_safe_defaults()
+        .with_no_client_auth()
+        .with_single_cert(vec![certificate], pkcs8_private_key(private_key))?;
+    crypto.alpn_protocols = vec![ALPN.to_vec()];
+    // Resume the sessions of the known clients, without accepting early data.
+    crypto.ticketer = rustls::Ticketer::new()?;
+
+    let mut config = ServerConfig::with_crypto(Arc::new(crypto));
+    config.transport_config(settings.transport_config());
+    let endpoint = Endpoint::server(config, address)?;
+    let local_addr = endpoint.local_addr()?;
+
+    let (tx_streams, rx_streams) = mpsc::channel(STREAM_BACKLOG);
+    tokio::spawn(accept_connections(endpoint, tx_streams));
+    Ok((local_addr, ReceiverStream::new(rx_streams)))
+}
+
+async fn accept_connections(endpoint: Endpoint, tx_streams: mpsc::Sender<io::Result<QuicStream>>) {
+    loop {
+        let connecting = tokio::select! {
+            connecting = endpoint.accept() => match connecting {
+                Some(connecting) => connecting,
+                None => return,
+            },
+            () = tx_streams.closed() => break,
+        };
+        let tx_streams = tx_streams.clone();
+        tokio::spawn(async move {
+            let connection = match connecting.await {
+                Ok(connection) => connection,
+                Err(e) => {
+                    debug!("Failed to accept QUIC connection: {e}");
+                    return;
+                }
+            };
+            accept_streams(connection, tx_streams).await
+        });
+    }
+    endpoint.close(0u32.into(), b"server stopped");
+}
+
+async fn accept_streams(connection: Connection, tx_streams: mpsc::Sender<io::Result<QuicStream>>) {
+    let remote_addr = connection.remote_address();
+    loop {
+        let stream = tokio::select! {
+            stream = connection.accept_bi() => stream,
+            () = tx_streams.closed() => return,
+        };
+        match stream {
+            Ok((send, recv)) => {
+                let stream = QuicStream {
+                    send,
+                    recv,
+                    remote_addr,
+                };
+                if tx_streams.send(Ok(stream)).await.is_err() {
+                    return;
+                }
+            }
+            Err(e) => {
+                debug!("QUIC connection from {remote_addr} closed: {e}");
+                return;
+            }
+        }
+    }
+}
+
+/// The runtime of the QUIC connections of the clients of the process, which outlive the runtimes
+/// of the tasks using them.
+static RUNTIME: Lazy<Runtime> = Lazy::new(|| {
+    tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(1)
+        .thread_name("quic-client")
+        .enable_all()
+        .build()
+        .expect("Failed to start the runtime of the QUIC clients")
+});
+
+/// The QUIC connections of the process to its peers. Only accessed from [`RUNTIME`].
+static CONNECTOR: Mutex<Option<Connector>> = Mutex::new(None);
+
+struct Connector {
+    ip4_endpoint: Option<Endpoint>,
+    ip6_endpoint: Option<Endpoint>,
+    /// The TLS sessions to resume, per key of the servers that established them.
+    sessions: HashMap<QuicPublicKey, Arc<dyn rustls::client::StoresClientSessions>>,
+    /// The connections to each address, along the key their server authenticated with.
+    connections: HashMap<SocketAddr, (QuicPublicKey, Connection)>,
+}
+
+impl Connector {
+    fn new() -> Self {
+        Self {
+            ip4_endpoint: None,
+            ip6_endpoint: None,
+            sessions: HashMap::new(),
+            connections: HashMap::new(),
+        }
+    }
+
+    /// The TLS configuration of the connections to the server authenticating with `server_key`.
+    fn crypto(&mut self, server_key: &QuicPublicKey) -> io::Result<rustls::ClientConfig> {
+        let public_key = ed25519_dalek::PublicKey::from_bytes(server_key)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        let verifier = Psk::from_der(&Ed25519::public_key_to_spki(&public_key))
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        let mut crypto = rustls::ClientConfig::builder()
+            .with_safe_defaults()
+            .with_custom_certificate_verifier(Arc::new(verifier))
+            .with_no_client_auth();
+        crypto.alpn_protocols = vec![ALPN.to_vec()];
+        crypto.session_storage = self
+            .sessions
+            .entry(*server_key)
+            .or_insert_with(|| rustls::client::ClientSessionMemoryCache::new(SESSION_CACHE_SIZE))
+            .clone();
+        Ok(crypto)
+    }
+
+    /// The endpoint of the connections to `address`, bound to an ip4 or ip6 socket accordingly.
+    fn endpoint(&mut self, address: &SocketAddr) -> io::Result<Endpoint> {
+        let (endpoint, local_addr) = if address.is_ipv6() {
+            (
+                &mut self.ip6_endpoint,
+                SocketAddr::from((Ipv6Addr::UNSPECIFIED, 0)),
+            )
+        } else {
+            (
+                &mut self.ip4_endpoint,
+                SocketAddr::from((Ipv4Addr::UNSPECIFIED, 0)),
+            )
+        };
+        if let Some(endpoint) = endpoint.as_ref() {
+            return Ok(endpoint.clone());
+        }
+        Ok(endpoint.insert(Endpoint::client(local_addr)?).clone())
+    }
+}
+
+/// Opens a new stream to `address`, over the QUIC connection of the process to it if there is one,
+/// once its server authenticated with `server_key`.
+pub(crate) async fn connect(
+    address: SocketAddr,
+    server_key: QuicPublicKey,
+    settings: QuicSettings,
+) -> io::Result<QuicStream> {
+    let connection = RUNTIME
+        .spawn(connection(address, server_key, settings))
+        .await
+        .map_err(|e| io::Error::new(io::ErrorKind::Other, e))??;
+    let (send, recv) = connection.open_bi().await?;
+    Ok(QuicStream {
+        send,
+        recv,
+        remote_addr: address,
+    })
+}
+
+async fn connection(
+    address: SocketAddr,
+    server_key: QuicPublicKey,
+    settings: QuicSettings,
+) -> io::Result<Connection> {
+    let connecting = {
+        let mut connector = CONNECTOR.lock().unwrap();
+        let connector = connector.get_or_insert_with(Connector::new);
+        if let Some((key, connection)) = connector.connections.get(&address) {
+            if *key == server_key && connection.close_reason().is_none() {
+                return Ok(connection.clone());
+            }
+        }
+        let mut config = quinn::ClientConfig::new(Arc::new(connector.crypto(&server_key)?));
+        config.transport_config(settings.transport_config());
+        connector
+            .endpoint(&address)?
+            .connect_with(config, address, SERVER_NAME)
+            .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?
+    };
+    // The server must have authenticated before any request is sent.
+    let connection = connecting.await?;
+    if let Some(connector) = CONNECTOR.lock().unwrap().as_mut() {
+        connector
+            .connections
+            .insert(address, (server_key, connection.clone()));
+    }
+    Ok(connection)
+}
+
+/// The PKCS#8 (v1) encoding of an ed25519 private key, see RFC 8410.
+fn pkcs8_private_key(private_key: &QuicPrivateKey) -> rustls::PrivateKey {
+    const PREFIX: [u8; 16] = [
+        0x30, 0x2e, 0x02, 0x01, 0x00, 0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x70, 0x04, 0x22, 0x04,
+        0x20,
+    ];
+    rustls::PrivateKey([&PREFIX[..], &private_key[..]].concat())
+}
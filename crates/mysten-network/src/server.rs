@@ -6,11 +6,13 @@ use crate::metrics::{
 };
 use crate::{
     config::Config,
-    multiaddr::{parse_dns, parse_ip4, parse_ip6},
+    multiaddr::{is_quic, parse_dns, parse_ip4, parse_ip6, parse_quic},
+    quic::{self, QuicPrivateKey, QuicSettings},
 };
 use eyre::{eyre, Result};
 use futures::FutureExt;
 use multiaddr::{Multiaddr, Protocol};
+use socket2::{Domain, Socket, Type};
 use std::task::{Context, Poll};
 use std::{convert::Infallible, net::SocketAddr};
 use tokio::net::{TcpListener, ToSocketAddrs};
@@ -39,6 +41,8 @@ use tower_http::trace::{DefaultMakeSpan, DefaultOnBodyChunk, DefaultOnEos, Trace
 pub struct ServerBuilder<M: MetricsCallbackProvider = DefaultMetricsCallbackProvider> {
     router: Router<WrapperService<M>>,
     health_reporter: tonic_health::server::HealthReporter,
+    quic_settings: QuicSettings,
+    quic_private_key: Option<QuicPrivateKey>,
 }
 
 type AddPathToHeaderFunction = fn(&Request<Body>) -> Option<HeaderValue>;
@@ -134,9 +138,18 @@ impl<M: MetricsCallbackProvider> ServerBuilder<M> {
         Self {
             router,
             health_reporter,
+            quic_settings: QuicSettings::new(config),
+            quic_private_key: None,
         }
     }
 
+    /// Sets the ed25519 private key the server authenticates with to its clients on the QUIC
+    /// addresses, which cannot be served without it.
+    pub fn with_quic_private_key(mut self, private_key: QuicPrivateKey) -> Self {
+        self.quic_private_key = Some(private_key);
+        self
+    }
+
     pub fn health_reporter(&self) -> tonic_health::server::HealthReporter {
         self.health_reporter.clone()
     }
@@ -173,6 +186,20 @@ impl<M: MetricsCallbackProvider> ServerBuilder<M> {
                     );
                     (local_addr, server)
                 }
+                Protocol::Ip4(_) | Protocol::Ip6(_) if is_quic(addr) => {
+                    let socket_addr = parse_quic(addr)?;
+                    let private_key = self.quic_private_key.as_ref().ok_or_else(|| {
+                        eyre!("serving the QUIC address {addr} requires a private key")
+                    })?;
+                    let (local_addr, incoming) =
+                        quic::listen(socket_addr, private_key, self.quic_settings)?;
+                    let local_addr = update_udp_port_in_multiaddr(addr, local_addr.port());
+                    let server = Box::pin(
+                        self.router
+                            .serve_with_incoming_shutdown(incoming, rx_cancellation),
+                    );
+                    (local_addr, server)
+                }
                 Protocol::Ip4(_) => {
                     let (socket_addr, _http_or_https) = parse_ip4(addr)?;
                     let (local_addr, incoming) =
@@ -185,8 +212,16 @@ impl<M: MetricsCallbackProvider> ServerBuilder<M> {
                 }
                 Protocol::Ip6(_) => {
                     let (socket_addr, _http_or_https) = parse_ip6(addr)?;
-                    let (local_addr, incoming) =
-                        tcp_listener_and_update_multiaddr(addr, socket_addr).await?;
+                    let (local_addr, incoming) = if socket_addr.ip().is_unspecified() {
+                        let listener = dual_stack_tcp_listener(socket_addr)?;
+                        let local_addr = listener.local_addr()?;
+                        (
+                            update_tcp_port_in_multiaddr(addr, local_addr.port()),
+                            TcpListenerStream::new(listener),
+                        )
+                    } else {
+                        tcp_listener_and_update_multiaddr(addr, socket_addr).await?
+                    };
                     let server = Box::pin(
                         self.router
                             .serve_with_incoming_shutdown(incoming, rx_cancellation),
@@ -234,6 +269,20 @@ async fn tcp_listener<T: ToSocketAddrs>(address: T) -> Result<(SocketAddr, TcpLi
     Ok((local_addr, incoming))
 }
 
+/// Listens on `[::]` for both the ip6 and ip4 connections, whatever the default of the OS for
+/// the sockets bound to it.
+fn dual_stack_tcp_listener(address: SocketAddr) -> Result<TcpListener> {
+    let socket = Socket::new(Domain::IPV6, Type::STREAM, Some(socket2::Protocol::TCP))?;
+    socket.set_only_v6(false)?;
+    // As `TcpListener::bind` does.
+    #[cfg(unix)]
+    socket.set_reuse_address(true)?;
+    socket.set_nonblocking(true)?;
+    socket.bind(&address.into())?;
+    socket.listen(1024)?;
+    Ok(TcpListener::from_std(socket.into())?)
+}
+
 pub struct Server {
     server: BoxFuture<(), tonic::transport::Error>,
     cancel_handle: Option<tokio::sync::oneshot::Sender<()>>,
@@ -270,15 +319,30 @@ fn update_tcp_port_in_multiaddr(addr: &Multiaddr, port: u16) -> Multiaddr {
     .expect("tcp protocol at index 1")
 }
 
+fn update_udp_port_in_multiaddr(addr: &Multiaddr, port: u16) -> Multiaddr {
+    addr.replace(1, |protocol| {
+        if let Protocol::Udp(_) = protocol {
+            Some(Protocol::Udp(port))
+        } else {
+            panic!("expected udp protocol at index 1");
+        }
+    })
+    .expect("udp protocol at index 1")
+}
+
 #[cfg(test)]
 mod test {
     use crate::config::Config;
     use crate::metrics::MetricsCallbackProvider;
+    use crate::quic::{QuicPrivateKey, QuicPublicKey};
     use multiaddr::multiaddr;
     use multiaddr::Multiaddr;
+    use multiaddr::Protocol;
     use std::ops::Deref;
     use std::sync::{Arc, Mutex};
     use std::time::Duration;
+    use tokio::sync::oneshot::Sender;
+    use tokio::task::JoinHandle;
     use tonic::Code;
     use tonic_health::proto::health_client::HealthClient;
     use tonic_health::proto::HealthCheckRequest;
@@ -458,6 +522,166 @@ mod test {
         test_multiaddr(address).await;
     }
 
+    #[tokio::test]
+    async fn dual_stack() {
+        let config = Config::new();
+        let address: Multiaddr = "/ip6/::/tcp/0/http".parse().unwrap();
+        let mut server = config.server_builder().bind(&address).await.unwrap();
+        let port = match server.local_addr().iter().nth(1) {
+            Some(Protocol::Tcp(port)) => port,
+            _ => panic!("unexpected address {}", server.local_addr()),
+        };
+        let cancel_handle = server.take_cancel_handle().unwrap();
+        let server_handle = tokio::spawn(server.serve());
+
+        for address in [
+            format!("/ip6/::1/tcp/{port}/http"),
+            format!("/ip4/127.0.0.1/tcp/{port}/http"),
+        ] {
+            let channel = config.connect(&address.parse().unwrap()).await.unwrap();
+            HealthClient::new(channel)
+                .check(HealthCheckRequest {
+                    service: "".to_owned(),
+                })
+                .await
+                .unwrap();
+        }
+
+        cancel_handle.send(()).unwrap();
+        server_handle.await.unwrap().unwrap();
+    }
+
+    const QUIC_PRIVATE_KEY: QuicPrivateKey = [1; 32];
+
+    fn quic_public_key(private_key: &QuicPrivateKey) -> QuicPublicKey {
+        let secret = ed25519_dalek::SecretKey::from_bytes(private_key).unwrap();
+        ed25519_dalek::PublicKey::from(&secret).to_bytes()
+    }
+
+    async fn quic_server(
+        address: &str,
+    ) -> (
+        Multiaddr,
+        Sender<()>,
+        JoinHandle<Result<(), tonic::transport::Error>>,
+    ) {
+        let mut server = Config::new()
+            .server_builder()
+            .with_quic_private_key(QUIC_PRIVATE_KEY)
+            .bind(&address.parse().unwrap())
+            .await
+            .unwrap();
+        let address = server.local_addr().to_owned();
+        let cancel_handle = server.take_cancel_handle().unwrap();
+        (address, cancel_handle, tokio::spawn(server.serve()))
+    }
+
+    async fn test_quic(address: &str) {
+        let (address, cancel_handle, server_handle) = quic_server(address).await;
+        let channel = Config::new()
+            .connect_with_server_key(&address, &quic_public_key(&QUIC_PRIVATE_KEY))
+            .await
+            .unwrap();
+        HealthClient::new(channel)
+            .check(HealthCheckRequest {
+                service: "".to_owned(),
+            })
+            .await
+            .unwrap();
+
+        cancel_handle.send(()).unwrap();
+        server_handle.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn quic() {
+        test_quic("/ip4/127.0.0.1/udp/0/quic").await;
+    }
+
+    #[tokio::test]
+    async fn quic_ip6() {
+        test_quic("/ip6/::1/udp/0/quic").await;
+    }
+
+    #[tokio::test]
+    async fn quic_authenticates_server() {
+        let (address, cancel_handle, server_handle) =
+            quic_server("/ip4/127.0.0.1/udp/0/quic").await;
+        let config = Config::new();
+
+        // The clients need the key of the server, and reject the servers not holding it.
+        assert!(config.connect(&address).await.is_err());
+        assert!(config.connect_lazy(&address).is_err());
+        assert!(config
+            .connect_with_server_key(&address, &quic_public_key(&[2; 32]))
+            .await
+            .is_err());
+        assert!(Config::new()
+            .server_builder()
+            .bind(&"/ip4/127.0.0.1/udp/0/quic".parse().unwrap())
+            .await
+            .is_err());
+
+        cancel_handle.send(()).unwrap();
+        server_handle.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn quic_does_not_resume_sessions_of_other_keys() {
+        let (address, cancel_handle, server_handle) =
+            quic_server("/ip4/127.0.0.1/udp/0/quic").await;
+        let config = Config::new();
+
+        // A session established with the key of the server...
+        let channel = config
+            .connect_with_server_key(&address, &quic_public_key(&QUIC_PRIVATE_KEY))
+            .await
+            .unwrap();
+        HealthClient::new(channel)
+            .check(HealthCheckRequest {
+                service: "".to_owned(),
+            })
+            .await
+            .unwrap();
+
+        // ...does not authenticate it as holding another key.
+        assert!(config
+            .connect_with_server_key(&address, &quic_public_key(&[2; 32]))
+            .await
+            .is_err());
+
+        cancel_handle.send(()).unwrap();
+        server_handle.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn quic_channels_share_connection() {
+        let (address, cancel_handle, server_handle) =
+            quic_server("/ip4/127.0.0.1/udp/0/quic").await;
+        let config = Config::new();
+
+        // Each channel is carried by its own stream of the same QUIC connection.
+        let mut clients = Vec::new();
+        for _ in 0..2 {
+            let channel = config
+                .connect_with_server_key(&address, &quic_public_key(&QUIC_PRIVATE_KEY))
+                .await
+                .unwrap();
+            clients.push(HealthClient::new(channel));
+        }
+        for client in &mut clients {
+            client
+                .check(HealthCheckRequest {
+                    service: "".to_owned(),
+                })
+                .await
+                .unwrap();
+        }
+
+        cancel_handle.send(()).unwrap();
+        server_handle.await.unwrap().unwrap();
+    }
+
     #[cfg(unix)]
     #[tokio::test]
     async fn unix() {
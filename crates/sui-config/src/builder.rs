@@ -306,6 +306,7 @@ impl<R: rand::RngCore + rand::CryptoRng> ConfigBuilder<R> {
                     db_path,
                     network_address,
                     metrics_address: utils::available_local_socket_address(),
+                    metrics_push: None,
                     admin_interface_port: utils::get_available_port(),
                     json_rpc_address: utils::available_local_socket_address(),
                     consensus_config: Some(consensus_config),
@@ -316,6 +317,11 @@ impl<R: rand::RngCore + rand::CryptoRng> ConfigBuilder<R> {
                     grpc_load_shed: initial_accounts_config.grpc_load_shed,
                     grpc_concurrency_limit: initial_accounts_config.grpc_concurrency_limit,
                     p2p_config,
+                    authority_store_pruning_config: None,
+                    state_snapshot_config: None,
+                    quorum_driver_retry_policy: None,
+                    quorum_driver_max_retry_policy: None,
+                    read_only: false,
                 }
             })
             .collect();
@@ -8,6 +8,7 @@ use anyhow::anyhow;
 use anyhow::Result;
 use fastcrypto::traits::ToFromBytes;
 use multiaddr::Multiaddr;
+use mysten_metrics::push::PushConfig;
 use narwhal_config::Parameters as ConsensusParameters;
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
@@ -25,11 +26,17 @@ use sui_types::crypto::NetworkPublicKey;
 use sui_types::crypto::PublicKey as AccountsPublicKey;
 use sui_types::crypto::SuiKeyPair;
 use sui_types::crypto::{AccountKeyPair, AuthorityPublicKey};
+use sui_types::messages::QuorumDriverRetryPolicy;
 use sui_types::sui_serde::KeyPairBase64;
+use sui_types::sui_system_state::SuiSystemState;
+use tracing::warn;
 
 // Default max number of concurrent requests served
 pub const DEFAULT_GRPC_CONCURRENCY_LIMIT: usize = 20000000000;
 
+// The stores under the db path that a read-only fullnode opens, see NodeConfig::check_read_only.
+const READ_ONLY_STORES: [&str; 5] = ["epochs", "checkpoints", "store", "indexes", "node_sync_db"];
+
 #[serde_as]
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "kebab-case")]
@@ -56,6 +63,10 @@ pub struct NodeConfig {
 
     #[serde(default = "default_metrics_address")]
     pub metrics_address: SocketAddr,
+    /// Pushes the metrics to a push gateway or a remote-write endpoint, for the nodes which
+    /// can't be scraped.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metrics_push: Option<PushConfig>,
     #[serde(default = "default_admin_interface_port")]
     pub admin_interface_port: u16,
 
@@ -80,6 +91,31 @@ pub struct NodeConfig {
     #[serde(default)]
     pub p2p_config: P2pConfig,
 
+    /// Deletes the history of the store, which is all kept if unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub authority_store_pruning_config: Option<AuthorityStorePruningConfig>,
+
+    /// Writes the state of the node at the end of each epoch, or restores it from the one of
+    /// another node on the first start.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state_snapshot_config: Option<StateSnapshotConfig>,
+
+    /// How the transaction orchestrator of a fullnode retries the requests that don't set their
+    /// own retry policy. Each step of a request is attempted once if unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quorum_driver_retry_policy: Option<QuorumDriverRetryPolicy>,
+
+    /// The most the requests can retry with their own retry policy, which is clamped to this
+    /// one. Limited only by the quorum driver if unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quorum_driver_max_retry_policy: Option<QuorumDriverRetryPolicy>,
+
+    /// Opens the existing stores of the node in read-only mode, only to serve the reads of their
+    /// history: the node doesn't sync, execute or prune anything. This requires a fullnode whose
+    /// configuration doesn't write to the stores, see [NodeConfig::check_read_only].
+    #[serde(default)]
+    pub read_only: bool,
+
     pub genesis: Genesis,
 }
 
@@ -162,6 +198,46 @@ impl NodeConfig {
         self.consensus_config.as_ref()
     }
 
+    /// Fails if the node is read-only while its configuration requires to write to its stores,
+    /// or while they don't exist yet.
+    pub fn check_read_only(&self) -> Result<()> {
+        if !self.read_only {
+            return Ok(());
+        }
+        if self.consensus_config.is_some() {
+            return Err(anyhow!("A validator cannot be read-only"));
+        }
+        if self.enable_checkpoint || self.enable_reconfig {
+            return Err(anyhow!(
+                "A read-only node cannot enable checkpoints or reconfiguration"
+            ));
+        }
+        if self.authority_store_pruning_config.is_some() {
+            return Err(anyhow!("A read-only node cannot prune its store"));
+        }
+        if self.state_snapshot_config.is_some() {
+            return Err(anyhow!(
+                "A read-only node cannot write or restore state snapshots"
+            ));
+        }
+        if self.quorum_driver_retry_policy.is_some()
+            || self.quorum_driver_max_retry_policy.is_some()
+        {
+            return Err(anyhow!("A read-only node cannot execute transactions"));
+        }
+        let events = self.enable_event_processing.then_some("events.db");
+        for store in READ_ONLY_STORES.iter().copied().chain(events) {
+            let path = self.db_path.join(store);
+            if !path.exists() {
+                return Err(anyhow!(
+                    "The stores of a read-only node must exist, but {} does not",
+                    path.display()
+                ));
+            }
+        }
+        Ok(())
+    }
+
     pub fn genesis(&self) -> Result<&genesis::Genesis> {
         self.genesis.genesis()
     }
@@ -210,6 +286,83 @@ impl NodeConfig {
         }
         .into())
     }
+
+    /// The Narwhal committee and worker cache of `epoch`, of the active validators of
+    /// `system_state` with their keys and stake on-chain, so that Narwhal and Sui agree on the
+    /// names of the authorities once they rotated their keys. The Narwhal addresses are not
+    /// on-chain: they are the ones of the genesis, and the validators which joined since, or
+    /// with keys invalid for Narwhal, are left out.
+    #[allow(clippy::mutable_key_type)]
+    pub fn narwhal_epoch_configuration(
+        &self,
+        system_state: &SuiSystemState,
+        epoch: narwhal_config::Epoch,
+    ) -> Result<(narwhal_config::Committee, narwhal_config::WorkerCache)> {
+        let genesis = self.genesis()?;
+        let consensus_config = self
+            .consensus_config()
+            .ok_or_else(|| anyhow!("cannot generate Narwhal committee without ConsensusConfig"))?;
+        let mut authorities = BTreeMap::new();
+        let mut workers = BTreeMap::new();
+        for validator in &system_state.validators.active_validators {
+            let metadata = &validator.metadata;
+            let Some(info) = genesis
+                .validator_set()
+                .iter()
+                .find(|info| info.sui_address() == metadata.sui_address)
+            else {
+                warn!(
+                    "No Narwhal addresses for validator {}, left out of the committee",
+                    metadata.sui_address
+                );
+                continue;
+            };
+            let keys = (
+                AuthorityPublicKey::from_bytes(&metadata.pubkey_bytes),
+                NetworkPublicKey::from_bytes(&metadata.network_pubkey_bytes),
+                NetworkPublicKey::from_bytes(&metadata.worker_pubkey_bytes),
+            );
+            let (Ok(name), Ok(network_key), Ok(worker_key)) = keys else {
+                warn!(
+                    "Invalid keys of validator {}, left out of the committee",
+                    metadata.sui_address
+                );
+                continue;
+            };
+            let worker_address = if name != *self.protocol_key_pair().public() {
+                info.narwhal_worker_address.clone()
+            } else {
+                // Use internal worker addresses for our own node if configured.
+                consensus_config
+                    .internal_worker_address
+                    .clone()
+                    .unwrap_or_else(|| info.narwhal_worker_address.clone())
+            };
+            let worker = narwhal_config::WorkerInfo {
+                name: worker_key,
+                transactions: consensus_config.address.clone(),
+                worker_address,
+            };
+            workers.insert(
+                name.clone(),
+                narwhal_config::WorkerIndex([(0, worker)].into_iter().collect()),
+            );
+            let authority = narwhal_config::Authority {
+                stake: (validator.stake_amount + validator.delegation_staking_pool.sui_balance)
+                    as narwhal_config::Stake,
+                primary_address: info.narwhal_primary_address.clone(),
+                network_key,
+            };
+            authorities.insert(name, authority);
+        }
+        let committee = narwhal_config::Committee {
+            authorities,
+            epoch,
+            quorum_policy: narwhal_config::QuorumPolicy::default(),
+            protocol_config: narwhal_config::ProtocolConfig::default(),
+        };
+        Ok((committee, narwhal_config::WorkerCache { workers, epoch }))
+    }
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -243,6 +396,44 @@ impl ConsensusConfig {
     }
 }
 
+/// The history kept by the store of a full node, which only deletes the one of executed
+/// checkpoints. The peers can't sync the deleted transactions from the node, or read the deleted
+/// objects. Validators keep all of their history.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct AuthorityStorePruningConfig {
+    /// The number of the latest versions of each object kept, at least 1. All the versions are
+    /// kept if unset.
+    pub num_latest_object_versions_to_retain: Option<u64>,
+    /// The number of the epochs before the current one whose transactions, effects and events
+    /// are kept. All of them are kept if unset, or if the object versions are all kept, since
+    /// the effects tell which versions to prune.
+    pub num_epochs_to_retain: Option<u64>,
+    #[serde(default = "default_pruning_period_secs")]
+    pub pruning_period_secs: u64,
+}
+
+fn default_pruning_period_secs() -> u64 {
+    3600
+}
+
+/// The state snapshots written or restored by a node. A snapshot holds the objects live at the
+/// last checkpoint of an epoch and the certified checkpoints ending the epochs since genesis.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct StateSnapshotConfig {
+    /// The directory to write the snapshot of each epoch in, as a `epoch_<epoch>` subdirectory
+    /// which can be served as is to the other nodes. No snapshot is written if unset.
+    pub snapshot_dir: Option<PathBuf>,
+    /// The number of the latest snapshots kept in `snapshot_dir`. All of them are kept if unset.
+    pub num_latest_snapshots_to_retain: Option<usize>,
+    /// The directory or the http(s) URL of the snapshot to restore the node from, when it has not
+    /// executed any checkpoint yet. The node syncs from genesis if unset. The objects of the
+    /// snapshot are restored as is, so it must be served by a trusted node, and the history before
+    /// it is backfilled from the peers unless the node prunes its transactions.
+    pub restore_from: Option<String>,
+}
+
 /// Publicly known information about a validator
 /// TODO read most of this from on-chain
 #[serde_as]
@@ -417,6 +608,35 @@ mod tests {
         assert_eq!(&genesis, loaded_genesis);
     }
 
+    #[test]
+    fn check_read_only_config() {
+        const TEMPLATE: &str = include_str!("../data/fullnode-template.yaml");
+        let db_dir = tempfile::tempdir().unwrap();
+
+        let mut config: NodeConfig = serde_yaml::from_str(TEMPLATE).unwrap();
+        config.db_path = db_dir.path().to_path_buf();
+        config.enable_event_processing = false;
+        config.check_read_only().unwrap();
+
+        config.read_only = true;
+        assert!(config.check_read_only().is_err());
+        for store in READ_ONLY_STORES {
+            std::fs::create_dir(db_dir.path().join(store)).unwrap();
+        }
+        config.check_read_only().unwrap();
+
+        config.enable_event_processing = true;
+        assert!(config.check_read_only().is_err());
+        config.enable_event_processing = false;
+
+        config.enable_checkpoint = true;
+        assert!(config.check_read_only().is_err());
+        config.enable_checkpoint = false;
+
+        config.db_path = db_dir.path().join("missing");
+        assert!(config.check_read_only().is_err());
+    }
+
     #[test]
     fn fullnode_template() {
         const TEMPLATE: &str = include_str!("../data/fullnode-template.yaml");
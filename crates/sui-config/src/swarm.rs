@@ -4,6 +4,7 @@
 use crate::p2p::{P2pConfig, SeedPeer};
 use crate::{builder, genesis, utils, Config, NodeConfig, ValidatorInfo};
 use fastcrypto::traits::KeyPair;
+use narwhal_config::EpochPolicy;
 use rand::rngs::OsRng;
 use rand::RngCore;
 use serde::{Deserialize, Serialize};
@@ -12,6 +13,7 @@ use std::net::{IpAddr, SocketAddr};
 use std::num::NonZeroUsize;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Duration;
 use sui_types::committee::Committee;
 use sui_types::crypto::{
     get_key_pair_from_rng, AccountKeyPair, AuthorityKeyPair, NetworkKeyPair, SuiKeyPair,
@@ -44,6 +46,17 @@ impl NetworkConfig {
         self.genesis.committee().unwrap()
     }
 
+    /// End the consensus epochs of all validators after `epoch_duration`, instead of only upon an
+    /// explicit reconfiguration.
+    pub fn set_epoch_duration(&mut self, epoch_duration: Duration) {
+        for config in &mut self.validator_configs {
+            if let Some(consensus_config) = &mut config.consensus_config {
+                consensus_config.narwhal_config.epoch_policy =
+                    EpochPolicy::FixedDuration(epoch_duration);
+            }
+        }
+    }
+
     pub fn into_validator_configs(self) -> Vec<NodeConfig> {
         self.validator_configs
     }
@@ -187,6 +200,7 @@ impl<'a> FullnodeConfigBuilder<'a> {
             db_path: db_path.join(dir_name),
             network_address,
             metrics_address: utils::available_local_socket_address(),
+            metrics_push: None,
             admin_interface_port: utils::get_available_port(),
             json_rpc_address,
             consensus_config: None,
@@ -197,6 +211,11 @@ impl<'a> FullnodeConfigBuilder<'a> {
             grpc_load_shed: None,
             grpc_concurrency_limit: None,
             p2p_config,
+            authority_store_pruning_config: None,
+            state_snapshot_config: None,
+            quorum_driver_retry_policy: None,
+            quorum_driver_max_retry_policy: None,
+            read_only: false,
         })
     }
 }
@@ -60,9 +60,13 @@ pub fn udp_multiaddr_to_listen_address(
         (Some(Protocol::Ip4(ipaddr)), Some(Protocol::Udp(port))) => Some((ipaddr, port).into()),
         (Some(Protocol::Ip6(ipaddr)), Some(Protocol::Udp(port))) => Some((ipaddr, port).into()),
 
-        (Some(Protocol::Dns(_)), Some(Protocol::Udp(port))) => {
+        (Some(Protocol::Dns(_)), Some(Protocol::Udp(port)))
+        | (Some(Protocol::Dns4(_)), Some(Protocol::Udp(port))) => {
             Some((std::net::Ipv4Addr::UNSPECIFIED, port).into())
         }
+        (Some(Protocol::Dns6(_)), Some(Protocol::Udp(port))) => {
+            Some((std::net::Ipv6Addr::UNSPECIFIED, port).into())
+        }
 
         _ => None,
     }
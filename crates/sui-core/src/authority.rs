@@ -30,15 +30,12 @@ use std::{
 use tap::TapFallible;
 use tokio::sync::broadcast::error::RecvError;
 use tokio::sync::mpsc::unbounded_channel;
+use tokio::sync::watch;
 use tracing::{debug, error, instrument, warn, Instrument};
 use typed_store::Map;
 
 pub use authority_notify_read::EffectsNotifyRead;
 pub use authority_store::{AuthorityStore, ResolverWrapper, UpdateType};
-use narwhal_config::{
-    Committee as ConsensusCommittee, WorkerCache as ConsensusWorkerCache,
-    WorkerId as ConsensusWorkerId,
-};
 use narwhal_types::CommittedSubDag;
 use sui_adapter::{adapter, execution_mode};
 use sui_config::genesis::Genesis;
@@ -54,7 +51,7 @@ use sui_storage::{
     IndexStore,
 };
 use sui_types::committee::EpochId;
-use sui_types::crypto::{AuthorityKeyPair, NetworkKeyPair};
+use sui_types::crypto::AuthorityKeyPair;
 use sui_types::event::{Event, EventID};
 use sui_types::messages_checkpoint::{CheckpointRequest, CheckpointResponse};
 use sui_types::object::{Owner, PastObjectRead};
@@ -79,9 +76,9 @@ use crate::authority::authority_notifier::TransactionNotifierTicket;
 use crate::authority::authority_notify_read::NotifyRead;
 use crate::authority::authority_per_epoch_store::AuthorityPerEpochStore;
 use crate::authority_aggregator::AuthorityAggregator;
-use crate::checkpoints::CheckpointServiceNotify;
+use crate::checkpoints::{CheckpointServiceNotify, PendingCheckpoint};
 use crate::consensus_handler::{
-    SequencedConsensusTransaction, VerifiedSequencedConsensusTransaction,
+    commit_indices, SequencedConsensusTransaction, VerifiedSequencedConsensusTransaction,
 };
 use crate::epoch::committee_store::CommitteeStore;
 use crate::epoch::reconfiguration::ReconfigState;
@@ -129,6 +126,7 @@ pub mod authority_store_tables;
 pub mod authority_notifier;
 pub(crate) mod authority_notify_read;
 pub(crate) mod authority_store;
+pub mod authority_store_pruner;
 
 pub const MAX_ITEMS_LIMIT: u64 = 1_000;
 const BROADCAST_CAPACITY: usize = 10_000;
@@ -137,13 +135,7 @@ pub(crate) const MAX_TX_RECOVERY_RETRY: u32 = 3;
 type CertTxGuard<'a> =
     DBTxGuard<'a, TrustedCertificate, (InnerTemporaryStore, SignedTransactionEffects)>;
 
-pub type ReconfigConsensusMessage = (
-    AuthorityKeyPair,
-    NetworkKeyPair,
-    ConsensusCommittee,
-    Vec<(ConsensusWorkerId, NetworkKeyPair)>,
-    ConsensusWorkerCache,
-);
+pub type ReconfigConsensusMessage = narwhal_node::restarter::NodeReconfiguration;
 
 /// Prometheus metrics which can be displayed in Grafana, queried and alerted on
 pub struct AuthorityMetrics {
@@ -209,6 +201,7 @@ pub struct AuthorityMetrics {
     /// Consensus handler metrics
     pub consensus_handler_processed_batches: IntCounter,
     pub consensus_handler_processed_bytes: IntCounter,
+    pub consensus_handler_narwhal_epoch: IntGauge,
 }
 
 // Override default Prom buckets for positive numbers in 0-50k range
@@ -510,6 +503,11 @@ impl AuthorityMetrics {
                 "Number of bytes processed by consensus_handler",
                 registry
             ).unwrap(),
+            consensus_handler_narwhal_epoch: register_int_gauge_with_registry!(
+                "consensus_handler_narwhal_epoch",
+                "Narwhal epoch of the last consensus output processed by consensus_handler",
+                registry
+            ).unwrap(),
         }
     }
 }
@@ -561,6 +559,9 @@ pub struct AuthorityState {
     pub(crate) batch_notifier: Arc<authority_notifier::TransactionNotifier>, // TODO: remove pub
 
     pub metrics: Arc<AuthorityMetrics>,
+
+    /// The Narwhal epoch of the last consensus output handled by this authority.
+    consensus_epoch: watch::Sender<u64>,
 }
 
 /// The authority state encapsulates all state, drives execution, and ensures safety.
@@ -591,6 +592,20 @@ impl AuthorityState {
         &self.committee_store
     }
 
+    /// Get a receiver of the Narwhal epoch of the consensus output handled by this authority,
+    /// which moves on within a Sui epoch every time Narwhal changes epoch.
+    pub fn subscribe_consensus_epoch(&self) -> watch::Receiver<u64> {
+        self.consensus_epoch.subscribe()
+    }
+
+    pub(crate) fn set_consensus_epoch(&self, epoch: u64) {
+        self.consensus_epoch.send_if_modified(|current| {
+            let modified = *current != epoch;
+            *current = epoch;
+            modified
+        });
+    }
+
     /// This is a private method and should be kept that way. It doesn't check whether
     /// the provided transaction is a system transaction, and hence can only be called internally.
     async fn handle_transaction_impl(
@@ -602,6 +617,7 @@ impl AuthorityState {
         let (_gas_status, input_objects) = transaction_input_checker::check_transaction_input(
             &self.database,
             &transaction.data().intent_message.value,
+            transaction.gas_owner(),
         )
         .await?;
 
@@ -1163,9 +1179,12 @@ impl AuthorityState {
         transaction: TransactionData,
         transaction_digest: TransactionDigest,
     ) -> Result<SuiTransactionEffects, anyhow::Error> {
-        let (gas_status, input_objects) =
-            transaction_input_checker::check_transaction_input(&self.database, &transaction)
-                .await?;
+        let (gas_status, input_objects) = transaction_input_checker::check_transaction_input(
+            &self.database,
+            &transaction,
+            transaction.signer(),
+        )
+        .await?;
         let shared_object_refs = input_objects.filter_shared_objects();
 
         let transaction_dependencies = input_objects.transaction_dependencies();
@@ -1550,22 +1569,26 @@ impl AuthorityState {
                     .expect("Notifier cannot start."),
             ),
             metrics,
+            consensus_epoch: watch::channel(0).0,
         });
 
         prometheus_registry
             .register(Box::new(ModuleCacheGauge::new(&state.module_cache)))
             .unwrap();
 
-        // Process tx recovery log first, so that the batch and checkpoint recovery (below)
-        // don't observe partially-committed txes.
-        state
-            .process_tx_recovery_log(None)
-            .await
-            .expect("Could not fully process recovery log at startup!");
+        // A read only store is served as it is, the recovery below would write to it.
+        if !state.database.is_read_only() {
+            // Process tx recovery log first, so that the batch and checkpoint recovery (below)
+            // don't observe partially-committed txes.
+            state
+                .process_tx_recovery_log(None)
+                .await
+                .expect("Could not fully process recovery log at startup!");
 
-        state
-            .init_batches_from_database()
-            .expect("Init batches failed!");
+            state
+                .init_batches_from_database()
+                .expect("Init batches failed!");
+        }
 
         // Start a task to execute ready certificates.
         let authority_state = Arc::downgrade(&state);
@@ -1826,8 +1849,9 @@ impl AuthorityState {
     pub fn get_owner_objects_iterator(
         &self,
         owner: Owner,
+        cursor: Option<ObjectID>,
     ) -> SuiResult<impl Iterator<Item = ObjectInfo> + '_> {
-        self.database.get_owner_objects_iterator(owner)
+        self.database.get_owner_objects_iterator(owner, cursor)
     }
 
     pub fn get_total_transaction_number(&self) -> Result<u64, anyhow::Error> {
@@ -2252,6 +2276,21 @@ impl AuthorityState {
             self.metrics.skipped_consensus_txns.inc();
             return Err(());
         }
+        // Narwhal restarts with the committee of a new Sui epoch after Sui reconfigured, so the
+        // certificates of its previous committee may still be sequenced, under the names of the
+        // authorities before they rotated their keys.
+        if !self
+            .epoch_store()
+            .committee()
+            .authority_exists(&transaction.sender_authority())
+        {
+            warn!(
+                "Ignoring the consensus transaction of {}, not in the committee of epoch {}",
+                transaction.certificate.origin(),
+                self.epoch()
+            );
+            return Err(());
+        }
         // Signatures are verified as part of narwhal payload verification in SuiTxValidator
         match &transaction.transaction.kind {
             ConsensusTransactionKind::UserTransaction(_certificate) => {}
@@ -2378,36 +2417,43 @@ impl AuthorityState {
         }
     }
 
-    pub(crate) fn handle_commit_boundary<C: CheckpointServiceNotify>(
+    pub(crate) async fn handle_commit_boundary<C: CheckpointServiceNotify>(
         &self,
         committed_dag: &Arc<CommittedSubDag>,
         checkpoint_service: &Arc<C>,
     ) -> SuiResult {
-        let round = committed_dag.round();
-        debug!("Commit boundary at {}", round);
+        let (round, height) = commit_indices(committed_dag);
+        debug!("Commit boundary at round {}, sub-dag {}", round, height);
         // This exchange is restart safe because of following:
         //
-        // We try to read last checkpoint content and send it to the checkpoint service
-        // CheckpointService::notify_checkpoint is idempotent in case you send same last checkpoint multiple times
-        //
-        // Only after CheckpointService::notify_checkpoint stores checkpoint in it's store we update checkpoint boundary
-        if let Some((index, roots)) = self.database.last_checkpoint(round)? {
-            let final_checkpoint_round = self.database.final_epoch_checkpoint()?;
-            let final_checkpoint = match final_checkpoint_round.map(|r| r.cmp(&round)) {
-                Some(CmpOrdering::Less) => {
-                    debug!(
-                        "Not forming checkpoint for round {} above final checkpoint round {:?}",
-                        round, final_checkpoint_round
-                    );
-                    return Ok(());
-                }
-                Some(CmpOrdering::Equal) => true,
-                Some(CmpOrdering::Greater) => false,
-                None => false,
-            };
-            checkpoint_service.notify_checkpoint(index, roots, final_checkpoint)?;
-        }
-        self.database.record_checkpoint_boundary(round)
+        // The roots of a checkpoint are exactly the transactions sequenced in this commit, so
+        // they are the same when narwhal redelivers the commit after a crash, and
+        // CheckpointService::notify_checkpoint ignores the commits it has already seen.
+        let final_checkpoint_round = self.database.final_epoch_checkpoint()?;
+        let last_of_epoch = match final_checkpoint_round.map(|r| r.cmp(&round)) {
+            Some(CmpOrdering::Less) => {
+                debug!(
+                    "Not forming checkpoint for round {} above final checkpoint round {:?}",
+                    round, final_checkpoint_round
+                );
+                return Ok(());
+            }
+            Some(CmpOrdering::Equal) => true,
+            Some(CmpOrdering::Greater) => false,
+            None => false,
+        };
+        let roots = self
+            .database
+            .epoch_store()
+            .get_transactions_in_commit(round, height)?;
+        checkpoint_service
+            .notify_checkpoint(PendingCheckpoint {
+                epoch: self.epoch(),
+                height,
+                roots,
+                last_of_epoch,
+            })
+            .await
     }
 
     pub async fn create_advance_epoch_tx_cert(
@@ -117,10 +117,11 @@ pub struct AuthorityEpochTables {
     /// every message output by consensus (and in the right order).
     last_consensus_index: DBMap<u64, ExecutionIndicesWithHash>,
 
-    /// This table lists all checkpoint boundaries in the consensus sequence
+    /// This table lists all checkpoint boundaries in the consensus sequence, as written by the
+    /// releases before the checkpoints were built from the narwhal commits.
     ///
-    /// The key in this table is incremental index and value is corresponding narwhal
-    /// consensus output index
+    /// Nothing reads nor writes it anymore, it is only kept for the epoch databases of these
+    /// releases to still open, and can be dropped once no node has any of them.
     checkpoint_boundary: DBMap<u64, u64>,
 
     /// This table contains current reconfiguration state for validator for current epoch
@@ -141,6 +142,14 @@ impl AuthorityEpochTables {
         Self::open_tables_read_write(Self::path(epoch, parent_path), db_options, None)
     }
 
+    pub fn open_read_only_mode(
+        epoch: EpochId,
+        parent_path: &Path,
+        db_options: Option<Options>,
+    ) -> Result<Self, TypedStoreError> {
+        Self::open_tables_read_only_mode(Self::path(epoch, parent_path), db_options, None)
+    }
+
     pub fn open_readonly(epoch: EpochId, parent_path: &Path) -> AuthorityEpochTablesReadOnly {
         Self::get_read_only_handle(Self::path(epoch, parent_path), None, None)
     }
@@ -160,15 +169,35 @@ impl AuthorityEpochTables {
 
 impl AuthorityPerEpochStore {
     pub fn new(committee: Committee, parent_path: &Path, db_options: Option<Options>) -> Self {
-        let epoch_id = committee.epoch;
-        let tables = AuthorityEpochTables::open(epoch_id, parent_path, db_options);
+        let tables = AuthorityEpochTables::open(committee.epoch, parent_path, db_options);
+        let wal = DBWriteAheadLog::new(parent_path.join("recovery_log"));
+        Self::from_tables(committee, tables, wal)
+    }
+
+    /// Opens the existing store of the epoch of `committee` in read only mode, in which any write
+    /// to it fails.
+    pub fn new_read_only_mode(
+        committee: Committee,
+        parent_path: &Path,
+        db_options: Option<Options>,
+    ) -> Result<Self, TypedStoreError> {
+        let tables =
+            AuthorityEpochTables::open_read_only_mode(committee.epoch, parent_path, db_options)?;
+        let wal = DBWriteAheadLog::new_read_only_mode(parent_path.join("recovery_log"))?;
+        Ok(Self::from_tables(committee, tables, wal))
+    }
+
+    fn from_tables(
+        committee: Committee,
+        tables: AuthorityEpochTables,
+        wal: DBWriteAheadLog<TrustedCertificate, (InnerTemporaryStore, SignedTransactionEffects)>,
+    ) -> Self {
         let end_of_publish =
             StakeAggregator::from_iter(committee.clone(), tables.end_of_publish.iter());
         let reconfig_state = tables
             .load_reconfig_state()
             .expect("Load reconfig state at initialization cannot fail");
-        let wal_path = parent_path.join("recovery_log");
-        let wal = Arc::new(DBWriteAheadLog::new(wal_path));
+        let wal = Arc::new(wal);
         let epoch_alive = NotifyOnce::new();
         Self {
             committee,
@@ -255,10 +284,6 @@ impl AuthorityPerEpochStore {
         Ok(self.tables.next_shared_object_versions.multi_get(ids)?)
     }
 
-    pub fn get_last_checkpoint_boundary(&self) -> Option<(u64, u64)> {
-        self.tables.checkpoint_boundary.iter().skip_to_last().next()
-    }
-
     pub fn get_last_consensus_index(&self) -> SuiResult<ExecutionIndicesWithHash> {
         self.tables
             .last_consensus_index
@@ -267,20 +292,26 @@ impl AuthorityPerEpochStore {
             .map_err(SuiError::from)
     }
 
-    pub fn get_transactions_in_checkpoint_range(
+    /// Returns the digests of the certificates first sequenced in the narwhal commit at
+    /// `sub_dag_index`, whose leader is at `round`, in consensus order.
+    pub fn get_transactions_in_commit(
         &self,
-        from_height_excluded: u64,
-        to_height_included: u64,
+        round: u64,
+        sub_dag_index: u64,
     ) -> SuiResult<Vec<TransactionDigest>> {
-        let iter = self.tables.consensus_message_order.iter();
-        let last_previous = ExecutionIndices::end_for_commit(from_height_excluded);
-        let iter = iter.skip_to(&last_previous)?;
-        // skip_to lands to key the last_key or key after it
-        // technically here we need to check if first item in stream has a key equal to last_previous
-        // however in practice this can not happen because number of batches in certificate is
-        // limited and is less then u64::MAX
-        let roots: Vec<_> = iter
-            .take_while(|(idx, _tx)| idx.last_committed_round <= to_height_included)
+        let first = ExecutionIndices {
+            last_committed_round: round,
+            sub_dag_index,
+            transaction_index: 0,
+        };
+        let roots = self
+            .tables
+            .consensus_message_order
+            .iter()
+            .skip_to(&first)?
+            .take_while(|(idx, _tx)| {
+                idx.last_committed_round == round && idx.sub_dag_index == sub_dag_index
+            })
             .map(|(_idx, tx)| tx)
             .collect();
         Ok(roots)
@@ -375,11 +406,6 @@ impl AuthorityPerEpochStore {
         Ok(())
     }
 
-    pub fn insert_checkpoint_boundary(&self, index: u64, height: u64) -> SuiResult {
-        self.tables.checkpoint_boundary.insert(&index, &height)?;
-        Ok(())
-    }
-
     pub fn insert_pending_consensus_transactions(
         &self,
         transaction: &ConsensusTransaction,
@@ -54,6 +54,9 @@ pub struct AuthorityStore {
 
     // Implementation detail to support notify_read_effects().
     pub(crate) effects_notify_read: NotifyRead<TransactionDigest, SignedTransactionEffects>,
+
+    /// Whether the store was opened in read only mode, in which any write to it fails.
+    read_only: bool,
 }
 
 impl AuthorityStore {
@@ -70,7 +73,7 @@ impl AuthorityStore {
         } else {
             perpetual_tables.get_committee()?
         };
-        Self::open_inner(path, db_options, genesis, perpetual_tables, committee).await
+        Self::open_inner(path, db_options, Some(genesis), perpetual_tables, committee).await
     }
 
     pub async fn open_with_committee(
@@ -83,25 +86,51 @@ impl AuthorityStore {
         Self::open_inner(
             path,
             db_options,
-            genesis,
+            Some(genesis),
             perpetual_tables,
             committee.clone(),
         )
         .await
     }
 
+    /// Open an authority store by directory path, without initializing it with the genesis
+    /// objects if it is empty. This is used to restore a state snapshot in it.
+    pub async fn open_without_genesis(
+        path: &Path,
+        db_options: Option<Options>,
+        committee: &Committee,
+    ) -> SuiResult<Self> {
+        let perpetual_tables = AuthorityPerpetualTables::open(path, db_options.clone());
+        Self::open_inner(path, db_options, None, perpetual_tables, committee.clone()).await
+    }
+
+    /// Open an existing authority store by directory path in read only mode, in which any write
+    /// to it fails. This is used to serve the reads of its history without changing it.
+    pub fn open_read_only_mode(path: &Path, db_options: Option<Options>) -> SuiResult<Self> {
+        let perpetual_tables =
+            AuthorityPerpetualTables::open_read_only_mode(path, db_options.clone())?;
+        let committee = perpetual_tables.get_committee()?;
+        let epoch_tables =
+            AuthorityPerEpochStore::new_read_only_mode(committee, path, db_options.clone())?;
+        let lock_service = LockService::new_read_only_mode(path.join("lockdb"), None)?;
+        Ok(Self::new_with_tables(
+            path,
+            db_options,
+            perpetual_tables,
+            epoch_tables,
+            lock_service,
+            true,
+        ))
+    }
+
     async fn open_inner(
         path: &Path,
         db_options: Option<Options>,
-        genesis: &Genesis,
+        genesis: Option<&Genesis>,
         perpetual_tables: AuthorityPerpetualTables,
         committee: Committee,
     ) -> SuiResult<Self> {
-        let epoch_tables = Arc::new(AuthorityPerEpochStore::new(
-            committee,
-            path,
-            db_options.clone(),
-        ));
+        let epoch_tables = AuthorityPerEpochStore::new(committee, path, db_options.clone());
 
         // For now, create one LockService for each SuiDataStore, and we use a specific
         // subdir of the data store directory
@@ -109,20 +138,21 @@ impl AuthorityStore {
         let lock_service =
             LockService::new(lockdb_path, None).expect("Could not initialize lockdb");
 
-        let store = Self {
-            lock_service,
-            mutex_table: MutexTable::new(NUM_SHARDS, SHARD_SIZE),
-            perpetual_tables,
-            epoch_store: epoch_tables.into(),
-            path: path.into(),
+        let store = Self::new_with_tables(
+            path,
             db_options,
-            effects_notify_read: NotifyRead::new(),
-        };
+            perpetual_tables,
+            epoch_tables,
+            lock_service,
+            false,
+        );
         // Only initialize an empty database.
-        if store
-            .database_is_empty()
-            .expect("Database read should not fail at init.")
-        {
+        let genesis = genesis.filter(|_| {
+            store
+                .database_is_empty()
+                .expect("Database read should not fail at init.")
+        });
+        if let Some(genesis) = genesis {
             store
                 .bulk_object_insert(&genesis.objects().iter().collect::<Vec<_>>())
                 .await
@@ -132,6 +162,30 @@ impl AuthorityStore {
         Ok(store)
     }
 
+    fn new_with_tables(
+        path: &Path,
+        db_options: Option<Options>,
+        perpetual_tables: AuthorityPerpetualTables,
+        epoch_tables: AuthorityPerEpochStore,
+        lock_service: LockService,
+        read_only: bool,
+    ) -> Self {
+        Self {
+            lock_service,
+            mutex_table: MutexTable::new(NUM_SHARDS, SHARD_SIZE),
+            perpetual_tables,
+            epoch_store: Arc::new(epoch_tables).into(),
+            path: path.into(),
+            db_options,
+            effects_notify_read: NotifyRead::new(),
+            read_only,
+        }
+    }
+
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
     pub(crate) fn reopen_epoch_db(&self, new_committee: Committee) {
         info!(new_epoch = ?new_committee.epoch, "re-opening AuthorityEpochTables for new epoch");
         let epoch_tables = Arc::new(AuthorityPerEpochStore::new(
@@ -203,21 +257,22 @@ impl AuthorityStore {
     // Methods to read the store
     pub fn get_owner_objects(&self, owner: Owner) -> Result<Vec<ObjectInfo>, SuiError> {
         debug!(?owner, "get_owner_objects");
-        Ok(self.get_owner_objects_iterator(owner)?.collect())
+        Ok(self.get_owner_objects_iterator(owner, None)?.collect())
     }
 
-    // Methods to read the store
+    /// The objects of `owner` in order of object id, from `cursor` on if any.
     pub fn get_owner_objects_iterator(
         &self,
         owner: Owner,
+        cursor: Option<ObjectID>,
     ) -> Result<impl Iterator<Item = ObjectInfo> + '_, SuiError> {
-        debug!(?owner, "get_owner_objects");
+        debug!(?owner, ?cursor, "get_owner_objects");
         Ok(self
             .perpetual_tables
             .owner_index
             .iter()
             // The object id 0 is the smallest possible
-            .skip_to(&(owner, ObjectID::ZERO))?
+            .skip_to(&(owner, cursor.unwrap_or(ObjectID::ZERO)))?
             .take_while(move |((object_owner, _), _)| (object_owner == &owner))
             .map(|(_, object_info)| object_info))
     }
@@ -1208,65 +1263,10 @@ impl AuthorityStore {
         )
     }
 
-    /// Returns transaction digests from consensus_message_order table in the "checkpoint range".
-    ///
-    /// Checkpoint range is defined from the last seen checkpoint(excluded) to the provided
-    /// to_height (included)
-    pub fn last_checkpoint(
-        &self,
-        to_height_included: u64,
-    ) -> SuiResult<Option<(u64, Vec<TransactionDigest>)>> {
-        let epoch_tables = self.epoch_store();
-
-        let Some((index, from_height_excluded)) = epoch_tables.get_last_checkpoint_boundary() else {
-            return Ok(None);
-        };
-        if from_height_excluded >= to_height_included {
-            // Due to crash recovery we might enter this function twice for same boundary
-            debug!("Not returning last checkpoint - already processed");
-            return Ok(None);
-        }
-
-        let roots = epoch_tables
-            .get_transactions_in_checkpoint_range(from_height_excluded, to_height_included)?;
-
-        debug!(
-            "Selected {} roots between narwhal commit rounds {} and {}",
-            roots.len(),
-            from_height_excluded,
-            to_height_included
-        );
-
-        Ok(Some((index, roots)))
-    }
-
     pub fn final_epoch_checkpoint(&self) -> SuiResult<Option<u64>> {
         self.epoch_store().final_epoch_checkpoint()
     }
 
-    pub fn record_checkpoint_boundary(&self, commit_round: u64) -> SuiResult {
-        let epoch_tables = self.epoch_store();
-
-        if let Some((index, height)) = epoch_tables.get_last_checkpoint_boundary() {
-            if height >= commit_round {
-                // Due to crash recovery we might see same boundary twice
-                debug!("Not recording checkpoint boundary - already updated");
-            } else {
-                let index = index + 1;
-                debug!(
-                    "Recording checkpoint boundary {} at {}",
-                    index, commit_round
-                );
-                epoch_tables.insert_checkpoint_boundary(index, commit_round)?;
-            }
-        } else {
-            // Table is empty
-            debug!("Recording first checkpoint boundary at {}", commit_round);
-            epoch_tables.insert_checkpoint_boundary(0, commit_round)?;
-        }
-        Ok(())
-    }
-
     pub fn transactions_in_seq_range(
         &self,
         start: u64,
@@ -1301,6 +1301,27 @@ impl AuthorityStore {
         Ok(transaction.map(|t| t.into()))
     }
 
+    /// Returns a transaction synced before the state snapshot the node was restored from, with
+    /// its effects.
+    pub fn get_backfilled_transaction(
+        &self,
+        transaction_digest: &TransactionDigest,
+    ) -> SuiResult<Option<(VerifiedCertificate, TransactionEffects)>> {
+        let effects_digest = self
+            .perpetual_tables
+            .backfilled_effects
+            .get(transaction_digest)?;
+        let Some(effects_digest) = effects_digest else {
+            return Ok(None);
+        };
+        let transaction = self
+            .perpetual_tables
+            .synced_transactions
+            .get(transaction_digest)?;
+        let effects = self.perpetual_tables.effects.get(&effects_digest)?;
+        Ok(transaction.zip(effects).map(|(t, e)| (t.into(), e)))
+    }
+
     pub fn multi_get_certified_transaction(
         &self,
         transaction_digests: &[TransactionDigest],
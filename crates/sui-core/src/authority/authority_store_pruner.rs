@@ -0,0 +1,350 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Deletes the history kept by the store of a full node, following the retention policies of
+//! the [`AuthorityStorePruningConfig`] of the node.
+//!
+//! Only the history of the executed checkpoints is deleted: the transactions of a checkpoint are
+//! final, so the object versions they modified are not read anymore to execute or revert them.
+//! Each checkpoint is pruned by a single write batch, the reads seeing all or none of its
+//! history deleted, and the latest versions of the objects are always kept for the reads of the
+//! transactions in flight. The executed effects of the transactions are kept, as they tell the
+//! certificates delivered again were already executed.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use mysten_metrics::spawn_monitored_task;
+use prometheus::{
+    register_int_counter_with_registry, register_int_gauge_with_registry, IntCounter, IntGauge,
+    Registry,
+};
+use sui_config::node::AuthorityStorePruningConfig;
+use sui_storage::event_store::{EventStore, EventStoreType};
+use sui_storage::IndexStore;
+use sui_types::base_types::VersionNumber;
+use sui_types::error::SuiResult;
+use sui_types::message_envelope::TrustedEnvelope;
+use sui_types::messages_checkpoint::VerifiedCheckpoint;
+use tokio::sync::oneshot;
+use tracing::{error, info, warn};
+use typed_store::Map;
+
+use super::authority_store::ObjectKey;
+use super::authority_store_tables::AuthorityPerpetualTables;
+use super::AuthorityStore;
+use crate::checkpoints::{CheckpointStore, CheckpointWatermark};
+
+#[cfg(test)]
+#[path = "../unit_tests/authority_store_pruner_tests.rs"]
+mod authority_store_pruner_tests;
+
+pub struct AuthorityStorePrunerMetrics {
+    pub last_pruned_objects_checkpoint: IntGauge,
+    pub last_pruned_transactions_checkpoint: IntGauge,
+    pub num_pruned_objects: IntCounter,
+    pub num_pruned_transactions: IntCounter,
+    pub pruned_bytes: IntCounter,
+}
+
+impl AuthorityStorePrunerMetrics {
+    pub fn new(registry: &Registry) -> Arc<Self> {
+        Arc::new(Self {
+            last_pruned_objects_checkpoint: register_int_gauge_with_registry!(
+                "last_pruned_objects_checkpoint",
+                "Highest checkpoint whose transactions had the versions they modified pruned",
+                registry
+            )
+            .unwrap(),
+            last_pruned_transactions_checkpoint: register_int_gauge_with_registry!(
+                "last_pruned_transactions_checkpoint",
+                "Highest checkpoint whose transactions were pruned",
+                registry
+            )
+            .unwrap(),
+            num_pruned_objects: register_int_counter_with_registry!(
+                "num_pruned_objects",
+                "Number of object versions pruned",
+                registry
+            )
+            .unwrap(),
+            num_pruned_transactions: register_int_counter_with_registry!(
+                "num_pruned_transactions",
+                "Number of transactions pruned with their effects and events",
+                registry
+            )
+            .unwrap(),
+            pruned_bytes: register_int_counter_with_registry!(
+                "pruned_bytes",
+                "Serialized size of the object versions, certificates and effects pruned",
+                registry
+            )
+            .unwrap(),
+        })
+    }
+}
+
+/// Prunes the store periodically until it is dropped.
+pub struct AuthorityStorePruner {
+    _cancel: oneshot::Sender<()>,
+}
+
+impl AuthorityStorePruner {
+    /// Prunes `store` with the entries of the transactions in `indexes` and `event_store`.
+    pub fn new(
+        store: Arc<AuthorityStore>,
+        checkpoint_store: Arc<CheckpointStore>,
+        indexes: Option<Arc<IndexStore>>,
+        event_store: Option<Arc<EventStoreType>>,
+        config: AuthorityStorePruningConfig,
+        registry: &Registry,
+    ) -> Self {
+        let metrics = AuthorityStorePrunerMetrics::new(registry);
+        let (cancel, mut cancelled) = oneshot::channel();
+        info!(?config, "Starting the pruning of the authority store");
+        if config.num_epochs_to_retain.is_some()
+            && config.num_latest_object_versions_to_retain.is_none()
+        {
+            warn!("The transactions are not pruned while all the object versions are retained");
+        }
+        spawn_monitored_task!(async move {
+            let mut interval =
+                tokio::time::interval(Duration::from_secs(config.pruning_period_secs));
+            loop {
+                tokio::select! {
+                    _ = interval.tick() => {
+                        if let Err(e) = Self::prune(
+                            &store.perpetual_tables,
+                            &checkpoint_store,
+                            indexes.as_deref(),
+                            event_store.as_deref(),
+                            &config,
+                            &metrics,
+                        )
+                        .await
+                        {
+                            error!("Failed to prune the authority store: {e}");
+                        }
+                    }
+                    _ = &mut cancelled => break,
+                }
+            }
+        });
+        Self { _cancel: cancel }
+    }
+
+    /// Prunes the history of the executed checkpoints not pruned yet.
+    pub(crate) async fn prune(
+        tables: &AuthorityPerpetualTables,
+        checkpoint_store: &CheckpointStore,
+        indexes: Option<&IndexStore>,
+        event_store: Option<&EventStoreType>,
+        config: &AuthorityStorePruningConfig,
+        metrics: &AuthorityStorePrunerMetrics,
+    ) -> SuiResult {
+        let Some(highest_executed) = checkpoint_store.get_highest_executed_checkpoint()? else {
+            return Ok(());
+        };
+        // The effects of the transactions tell which object versions to prune, so the
+        // transactions are only pruned after their objects.
+        let Some(num_versions) = config.num_latest_object_versions_to_retain else {
+            return Ok(());
+        };
+
+        let num_versions = num_versions.max(1) as usize;
+        let watermark = CheckpointWatermark::HighestPrunedObjects;
+        while let Some(checkpoint) = Self::next_to_prune(
+            checkpoint_store,
+            watermark,
+            highest_executed.sequence_number(),
+        )? {
+            if !Self::prune_objects(tables, checkpoint_store, &checkpoint, num_versions, metrics)? {
+                break;
+            }
+            checkpoint_store.update_highest_pruned_checkpoint(watermark, &checkpoint)?;
+        }
+        let highest_pruned_objects =
+            checkpoint_store.get_highest_pruned_checkpoint_seq_number(watermark)?;
+        metrics
+            .last_pruned_objects_checkpoint
+            .set(highest_pruned_objects.map_or(-1, |seq| seq as i64));
+
+        let (Some(num_epochs), Some(highest_pruned_objects)) =
+            (config.num_epochs_to_retain, highest_pruned_objects) else {
+            return Ok(());
+        };
+        let current_epoch = highest_executed.epoch();
+        let watermark = CheckpointWatermark::HighestPrunedTransactions;
+        while let Some(checkpoint) =
+            Self::next_to_prune(checkpoint_store, watermark, highest_pruned_objects)?
+        {
+            if checkpoint.epoch() + num_epochs >= current_epoch
+                || !Self::prune_transactions(
+                    tables,
+                    checkpoint_store,
+                    indexes,
+                    event_store,
+                    &checkpoint,
+                    metrics,
+                )
+                .await?
+            {
+                break;
+            }
+            checkpoint_store.update_highest_pruned_checkpoint(watermark, &checkpoint)?;
+        }
+        metrics.last_pruned_transactions_checkpoint.set(
+            checkpoint_store
+                .get_highest_pruned_checkpoint_seq_number(watermark)?
+                .map_or(-1, |seq| seq as i64),
+        );
+        Ok(())
+    }
+
+    /// Returns the checkpoint after `watermark`, if it is stored and not after `last`.
+    fn next_to_prune(
+        checkpoint_store: &CheckpointStore,
+        watermark: CheckpointWatermark,
+        last: u64,
+    ) -> SuiResult<Option<VerifiedCheckpoint>> {
+        let next = checkpoint_store
+            .get_highest_pruned_checkpoint_seq_number(watermark)?
+            .map_or(0, |seq| seq + 1);
+        if next > last {
+            return Ok(None);
+        }
+        Ok(checkpoint_store.get_checkpoint_by_sequence_number(next)?)
+    }
+
+    /// Deletes the versions of the objects modified by the transactions of `checkpoint`, but the
+    /// latest `num_versions` of them.
+    fn prune_objects(
+        tables: &AuthorityPerpetualTables,
+        checkpoint_store: &CheckpointStore,
+        checkpoint: &VerifiedCheckpoint,
+        num_versions: usize,
+        metrics: &AuthorityStorePrunerMetrics,
+    ) -> SuiResult<bool> {
+        let contents = checkpoint_store.get_checkpoint_contents(&checkpoint.content_digest())?;
+        let Some(contents) = contents else {
+            return Ok(false);
+        };
+        let effects_digests: Vec<_> = contents.iter().map(|digests| digests.effects).collect();
+
+        let mut pruned_keys = vec![];
+        let mut pruned_refs = vec![];
+        let mut pruned_bytes = 0;
+        for effects in tables
+            .effects
+            .multi_get(&effects_digests)?
+            .into_iter()
+            .flatten()
+        {
+            for (object_id, version) in &effects.modified_at_versions {
+                // The transaction wrote a later version of the object, or deleted it, so the
+                // version it modified is kept with the previous ones if more are retained.
+                let versions: Vec<_> = tables
+                    .objects
+                    .iter()
+                    .skip_to(&ObjectKey(*object_id, VersionNumber::MIN))?
+                    .take_while(|(key, _)| key.0 == *object_id && key.1 <= *version)
+                    .collect();
+                let num_pruned = versions.len().saturating_sub(num_versions - 1);
+                for (key, object) in versions.into_iter().take(num_pruned) {
+                    pruned_bytes += bcs::serialized_size(&object).unwrap_or_default();
+                    pruned_refs.push(object.compute_object_reference());
+                    pruned_keys.push(key);
+                }
+            }
+        }
+
+        metrics.num_pruned_objects.inc_by(pruned_keys.len() as u64);
+        metrics.pruned_bytes.inc_by(pruned_bytes as u64);
+        tables
+            .objects
+            .batch()
+            .delete_batch(&tables.objects, pruned_keys)?
+            .delete_batch(&tables.parent_sync, pruned_refs)?
+            .write()?;
+        Ok(true)
+    }
+
+    /// Deletes the certificates, effects, index entries and events of the transactions of
+    /// `checkpoint`. The index entries and events are deleted first, so the reads never find
+    /// them pointing at deleted transactions, and a pruning interrupted is done again.
+    async fn prune_transactions(
+        tables: &AuthorityPerpetualTables,
+        checkpoint_store: &CheckpointStore,
+        indexes: Option<&IndexStore>,
+        event_store: Option<&EventStoreType>,
+        checkpoint: &VerifiedCheckpoint,
+        metrics: &AuthorityStorePrunerMetrics,
+    ) -> SuiResult<bool> {
+        let contents = checkpoint_store.get_checkpoint_contents(&checkpoint.content_digest())?;
+        let Some(contents) = contents else {
+            return Ok(false);
+        };
+        let (transactions, effects_digests): (Vec<_>, Vec<_>) = contents
+            .iter()
+            .map(|digests| (digests.transaction, digests.effects))
+            .unzip();
+        let certificates: Vec<_> = tables
+            .certificates
+            .multi_get(&transactions)?
+            .into_iter()
+            .map(|cert| cert.map(TrustedEnvelope::into_inner))
+            .collect();
+        let effects = tables.effects.multi_get(&effects_digests)?;
+
+        if let Some(indexes) = indexes {
+            for ((digest, cert), effects) in transactions.iter().zip(&certificates).zip(&effects) {
+                let (Some(cert), Some(effects)) = (cert, effects) else {
+                    continue;
+                };
+                let transaction = &cert.data().intent_message.value;
+                indexes.prune_tx(
+                    cert.sender_address(),
+                    transaction.input_objects()?.iter().map(|o| o.object_id()),
+                    effects
+                        .all_mutated()
+                        .map(|(obj_ref, owner, _kind)| (*obj_ref, *owner)),
+                    transaction
+                        .move_calls()
+                        .iter()
+                        .map(|mc| (mc.package.0, mc.module.clone(), mc.function.clone())),
+                    digest,
+                )?;
+            }
+        }
+        if let Some(event_store) = event_store {
+            event_store
+                .delete_events_of_transactions(&transactions)
+                .await?;
+        }
+
+        let pruned_bytes: usize = certificates
+            .iter()
+            .flatten()
+            .map(|cert| bcs::serialized_size(cert).unwrap_or_default())
+            .chain(
+                effects
+                    .iter()
+                    .flatten()
+                    .map(|effects| bcs::serialized_size(effects).unwrap_or_default()),
+            )
+            .sum();
+
+        metrics
+            .num_pruned_transactions
+            .inc_by(transactions.len() as u64);
+        metrics.pruned_bytes.inc_by(pruned_bytes as u64);
+        tables
+            .certificates
+            .batch()
+            .delete_batch(&tables.certificates, &transactions)?
+            .delete_batch(&tables.synced_transactions, &transactions)?
+            .delete_batch(&tables.effects, &effects_digests)?
+            .write()?;
+        Ok(true)
+    }
+}
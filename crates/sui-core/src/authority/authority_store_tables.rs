@@ -8,7 +8,7 @@ use sui_storage::default_db_options;
 use sui_types::base_types::{ExecutionDigests, SequenceNumber};
 use sui_types::batch::{SignedBatch, TxSequenceNumber};
 use sui_types::messages::TrustedCertificate;
-use typed_store::rocks::{DBMap, DBOptions};
+use typed_store::rocks::{DBMap, DBOptions, TypedStoreError};
 use typed_store::traits::TypedStoreDebug;
 
 use typed_store_derive::DBMapUtils;
@@ -59,6 +59,9 @@ pub struct AuthorityPerpetualTables {
 
     pub(crate) effects: DBMap<TransactionEffectsDigest, TransactionEffects>,
     pub(crate) synced_transactions: DBMap<TransactionDigest, TrustedCertificate>,
+    /// The digests of the effects of the transactions synced before the state snapshot the node
+    /// was restored from, which it did not execute.
+    pub(crate) backfilled_effects: DBMap<TransactionDigest, TransactionEffectsDigest>,
 
     // Tables used for authority batch structure
     // TODO: executed_sequence and batches both conceptually belong in AuthorityEpochTables,
@@ -90,6 +93,13 @@ impl AuthorityPerpetualTables {
         Self::open_tables_read_write(Self::path(parent_path), db_options, None)
     }
 
+    pub fn open_read_only_mode(
+        parent_path: &Path,
+        db_options: Option<Options>,
+    ) -> Result<Self, TypedStoreError> {
+        Self::open_tables_read_only_mode(Self::path(parent_path), db_options, None)
+    }
+
     pub fn open_readonly(parent_path: &Path) -> AuthorityPerpetualTablesReadOnly {
         Self::get_read_only_handle(Self::path(parent_path), None, None)
     }
@@ -6,9 +6,11 @@ use crate::{
     authority::{AuthorityState, ReconfigConsensusMessage},
     consensus_adapter::{ConsensusAdapter, ConsensusAdapterMetrics},
     consensus_validator::SuiTxValidator,
+    signature_verifier::{SignatureVerifier, SignatureVerifierMetrics},
 };
 use anyhow::anyhow;
 use anyhow::Result;
+use arc_swap::ArcSwap;
 use async_trait::async_trait;
 use fastcrypto::traits::KeyPair;
 use futures::{stream::BoxStream, TryStreamExt};
@@ -83,6 +85,7 @@ pub struct AuthorityServer {
     min_batch_size: u64,
     max_delay: Duration,
     pub metrics: Arc<ValidatorServiceMetrics>,
+    signature_verifier: Arc<SignatureVerifier>,
 }
 
 impl AuthorityServer {
@@ -110,6 +113,7 @@ impl AuthorityServer {
             min_batch_size: MIN_BATCH_SIZE,
             max_delay: Duration::from_millis(MAX_DELAY_MILLIS),
             metrics,
+            signature_verifier: SignatureVerifier::new_for_tests(),
         }
     }
 
@@ -148,6 +152,7 @@ impl AuthorityServer {
                 state: self.state,
                 consensus_adapter: self.consensus_adapter,
                 metrics: self.metrics.clone(),
+                signature_verifier: self.signature_verifier,
             }))
             .bind(&address)
             .await
@@ -249,6 +254,7 @@ pub struct ValidatorService {
     state: Arc<AuthorityState>,
     consensus_adapter: Arc<ConsensusAdapter>,
     metrics: Arc<ValidatorServiceMetrics>,
+    signature_verifier: Arc<SignatureVerifier>,
 }
 
 impl ValidatorService {
@@ -297,8 +303,13 @@ impl ValidatorService {
 
         let consensus_keypair = config.protocol_key_pair().copy();
         let consensus_worker_keypair = config.worker_key_pair().copy();
-        let consensus_committee = config.genesis()?.narwhal_committee().load();
-        let consensus_worker_cache = config.narwhal_worker_cache()?;
+        // Narwhal runs with the keys and stake on-chain, which may have changed since the genesis.
+        let system_state = state.get_sui_system_state_object().await?;
+        let (consensus_committee, consensus_worker_cache) = config.narwhal_epoch_configuration(
+            &system_state,
+            config.genesis()?.epoch() as narwhal_config::Epoch,
+        )?;
+        let consensus_worker_cache = Arc::new(ArcSwap::from_pointee(consensus_worker_cache));
         let consensus_storage_base_path = consensus_config.db_path().to_path_buf();
         let consensus_execution_state = ConsensusHandler::new(state.clone(), checkpoint_service);
         let consensus_execution_state = Arc::new(consensus_execution_state);
@@ -325,6 +336,9 @@ impl ValidatorService {
             state,
             consensus_adapter,
             metrics: Arc::new(ValidatorServiceMetrics::new(&prometheus_registry)),
+            signature_verifier: SignatureVerifier::new(Arc::new(SignatureVerifierMetrics::new(
+                &prometheus_registry,
+            ))),
         })
     }
 
@@ -332,6 +346,7 @@ impl ValidatorService {
         state: Arc<AuthorityState>,
         request: tonic::Request<Transaction>,
         metrics: Arc<ValidatorServiceMetrics>,
+        signature_verifier: Arc<SignatureVerifier>,
     ) -> Result<tonic::Response<TransactionInfoResponse>, tonic::Status> {
         let transaction = request.into_inner();
 
@@ -346,9 +361,12 @@ impl ValidatorService {
         };
         let tx_verif_metrics_guard = metrics.tx_verification_latency.start_timer();
 
-        let transaction = transaction.verify().tap_err(|_| {
-            metrics.signature_errors.inc();
-        })?;
+        let transaction = signature_verifier
+            .verify_transaction(transaction)
+            .await
+            .tap_err(|_| {
+                metrics.signature_errors.inc();
+            })?;
         tx_verif_metrics_guard.stop_and_record();
 
         let tx_digest = transaction.digest();
@@ -374,6 +392,7 @@ impl ValidatorService {
         consensus_adapter: Arc<ConsensusAdapter>,
         request: tonic::Request<CertifiedTransaction>,
         metrics: Arc<ValidatorServiceMetrics>,
+        signature_verifier: Arc<SignatureVerifier>,
     ) -> Result<tonic::Response<TransactionInfoResponse>, tonic::Status> {
         let certificate = request.into_inner();
         let shared_object_tx = certificate.contains_shared_object();
@@ -394,10 +413,9 @@ impl ValidatorService {
 
         // 2) Verify cert signatures
         let cert_verif_metrics_guard = metrics.cert_verification_latency.start_timer();
-        let certificate = {
-            let epoch_store = state.epoch_store();
-            certificate.verify(epoch_store.committee())?
-        };
+        let certificate = signature_verifier
+            .verify_certificate(certificate, state.epoch_store().clone())
+            .await?;
         cert_verif_metrics_guard.stop_and_record();
 
         // 3) All certificates are sent to consensus (at least by some authorities)
@@ -478,9 +496,15 @@ impl Validator for ValidatorService {
         // Spawns a task which handles the transaction. The task will unconditionally continue
         // processing in the event that the client connection is dropped.
         let metrics = self.metrics.clone();
-        spawn_monitored_task!(Self::handle_transaction(state, request, metrics))
-            .await
-            .unwrap()
+        let signature_verifier = self.signature_verifier.clone();
+        spawn_monitored_task!(Self::handle_transaction(
+            state,
+            request,
+            metrics,
+            signature_verifier
+        ))
+        .await
+        .unwrap()
     }
 
     async fn handle_certificate(
@@ -493,11 +517,13 @@ impl Validator for ValidatorService {
         // Spawns a task which handles the certificate. The task will unconditionally continue
         // processing in the event that the client connection is dropped.
         let metrics = self.metrics.clone();
+        let signature_verifier = self.signature_verifier.clone();
         spawn_monitored_task!(Self::handle_certificate(
             state,
             consensus_adapter,
             request,
-            metrics
+            metrics,
+            signature_verifier
         ))
         .await
         .unwrap()
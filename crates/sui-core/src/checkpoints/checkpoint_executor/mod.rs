@@ -40,6 +40,7 @@ use typed_store::{rocks::TypedStoreError, Map};
 use crate::{
     authority::{AuthorityState, EffectsNotifyRead},
     checkpoints::CheckpointStore,
+    state_snapshot::{StateCheckpoint, StateSnapshotWriter},
 };
 
 use self::metrics::CheckpointExecutorMetrics;
@@ -77,6 +78,8 @@ pub struct CheckpointExecutor {
     end_of_epoch: bool,
     task_limit: usize,
     metrics: Arc<CheckpointExecutorMetrics>,
+    /// Writes the state at the end of each epoch, if set.
+    state_snapshot_writer: Option<Arc<StateSnapshotWriter>>,
 }
 
 impl CheckpointExecutor {
@@ -95,6 +98,7 @@ impl CheckpointExecutor {
             end_of_epoch: false,
             task_limit: TASKS_PER_CORE * num_cpus::get(),
             metrics: CheckpointExecutorMetrics::new(prometheus_registry),
+            state_snapshot_writer: None,
         })
     }
 
@@ -112,16 +116,26 @@ impl CheckpointExecutor {
             end_of_epoch: false,
             task_limit: TASKS_PER_CORE * num_cpus::get(),
             metrics: CheckpointExecutorMetrics::new_for_tests(),
+            state_snapshot_writer: None,
         })
     }
 
+    /// Writes a snapshot of the state at the last checkpoint of each epoch, from a checkpoint of
+    /// the store taken before executing the transactions of the next epoch.
+    pub fn with_state_snapshot_writer(mut self, writer: StateSnapshotWriter) -> Self {
+        self.state_snapshot_writer = Some(Arc::new(writer));
+        self
+    }
+
     pub async fn run(mut self) {
         self.handle_crash_recovery().unwrap();
 
         while let Some((last_checkpoint, next_committee)) =
             self.execute_checkpoints_for_epoch().await
         {
+            let state = self.checkpoint_state(&last_checkpoint);
             reconfig(next_committee);
+            self.write_state_snapshot(&last_checkpoint, state).await;
             self.checkpoint_store
                 .update_highest_executed_checkpoint(&last_checkpoint)
                 .unwrap();
@@ -130,6 +144,47 @@ impl CheckpointExecutor {
         // Channel closed
     }
 
+    /// Checkpoints the state the snapshot of the epoch ended by `last_checkpoint` is written from,
+    /// before the transactions of the next epoch are executed.
+    fn checkpoint_state(&self, last_checkpoint: &VerifiedCheckpoint) -> Option<StateCheckpoint> {
+        let writer = self.state_snapshot_writer.as_ref()?;
+        writer
+            .checkpoint_state(&self.authority_state.db(), last_checkpoint.epoch())
+            .map_err(|err| {
+                error!(
+                    "Failed to checkpoint the state of epoch {:?}: {:?}",
+                    last_checkpoint.epoch(),
+                    err
+                )
+            })
+            .ok()
+    }
+
+    async fn write_state_snapshot(
+        &self,
+        last_checkpoint: &VerifiedCheckpoint,
+        state: Option<StateCheckpoint>,
+    ) {
+        let (Some(writer), Some(state)) = (self.state_snapshot_writer.clone(), state) else {
+            return;
+        };
+        let checkpoint_store = self.checkpoint_store.clone();
+        let checkpoint = last_checkpoint.clone();
+        let result = tokio::task::spawn_blocking(move || {
+            writer.write(&state, &checkpoint_store, &checkpoint)
+        })
+        .await;
+        match result {
+            Ok(Ok(_)) => {}
+            Ok(Err(err)) => error!(
+                "Failed to write the state snapshot of epoch {:?}: {:?}",
+                last_checkpoint.epoch(),
+                err
+            ),
+            Err(err) => error!("State snapshot task failed: {:?}", err),
+        }
+    }
+
     pub fn handle_crash_recovery(&self) -> SuiResult {
         if let Some(checkpoint) = self.checkpoint_store.get_highest_executed_checkpoint()? {
             // last executed checkpoint before shutdown was last of epoch. Make sure
@@ -14,6 +14,7 @@ pub use crate::checkpoints::checkpoint_output::{
 };
 pub use crate::checkpoints::metrics::CheckpointMetrics;
 use crate::stake_aggregator::{InsertResult, StakeAggregator};
+use async_trait::async_trait;
 use fastcrypto::encoding::{Encoding, Hex};
 use futures::future::{select, Either};
 use futures::FutureExt;
@@ -37,26 +38,48 @@ use sui_types::messages_checkpoint::{
 };
 use tokio::sync::{mpsc, watch, Notify};
 use tracing::{debug, error, info, warn};
-use typed_store::rocks::{DBMap, TypedStoreError};
+use typed_store::rocks::{DBBatch, DBMap, TypedStoreError};
 use typed_store::traits::TypedStoreDebug;
 use typed_store::Map;
 use typed_store_derive::DBMapUtils;
 
-type CheckpointCommitHeight = u64;
+/// The index of a narwhal commit (the `sub_dag_index` of a committed sub-dag) within an epoch.
+pub type CheckpointCommitHeight = u64;
+
+// The number of commits the checkpoint builder can fall behind before consensus waits for it.
+const BUILDER_CHANNEL_CAPACITY: usize = 100;
+
+/// The transactions sequenced by one narwhal commit, from which a checkpoint is built.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PendingCheckpoint {
+    pub epoch: EpochId,
+    pub height: CheckpointCommitHeight,
+    pub roots: Vec<TransactionDigest>,
+    /// Whether this is the last checkpoint of the epoch.
+    pub last_of_epoch: bool,
+}
 
 #[derive(DBMapUtils)]
 pub struct CheckpointStore {
+    /// The pending checkpoints stored by the releases before the checkpoints were built from the
+    /// narwhal commits, keyed by the consensus round of their boundary, along whether they are the
+    /// last checkpoint of the epoch. Nothing is added to it anymore: the checkpoint builder builds
+    /// these first after a restart, and the table can be dropped once all the nodes drained it.
+    pending_checkpoints: DBMap<u64, (Vec<TransactionDigest>, bool)>,
+
     /// This table has information for the checkpoints for which we constructed all the data
     /// from consensus, but not yet constructed actual checkpoint.
     ///
-    /// Key in this table is the narwhal commit height and not a checkpoint sequence number.
+    /// Key in this table is the epoch and narwhal commit height and not a checkpoint sequence number.
     ///
     /// Non-empty list of transactions here might result in empty list when we are forming checkpoint.
     /// Because we don't want to create checkpoints with empty content(see CheckpointBuilder::write_checkpoint),
     /// the sequence number of checkpoint does not match height here.
-    ///
-    /// The boolean value indicates whether this is the last checkpoint of the epoch.
-    pending_checkpoints: DBMap<CheckpointCommitHeight, (Vec<TransactionDigest>, bool)>,
+    pending_commit_checkpoints: DBMap<(EpochId, CheckpointCommitHeight), PendingCheckpoint>,
+
+    /// The highest narwhal commit height of each epoch the checkpoint builder is done with.
+    /// Commits at or below it are not built again when narwhal redelivers them after a restart.
+    checkpoint_builder_watermark: DBMap<EpochId, CheckpointCommitHeight>,
 
     /// Maps checkpoint contents digest to checkpoint contents
     checkpoint_content: DBMap<CheckpointContentsDigest, CheckpointContents>,
@@ -88,6 +111,15 @@ impl CheckpointStore {
         Arc::new(Self::open_tables_read_write(path.to_path_buf(), None, None))
     }
 
+    /// Opens the existing checkpoints at `path` in read only mode, in which any write fails.
+    pub fn new_read_only_mode(path: &Path) -> Result<Arc<Self>, TypedStoreError> {
+        Ok(Arc::new(Self::open_tables_read_only_mode(
+            path.to_path_buf(),
+            None,
+            None,
+        )?))
+    }
+
     pub fn get_checkpoint_by_digest(
         &self,
         digest: &CheckpointDigest,
@@ -120,6 +152,28 @@ impl CheckpointStore {
         Ok(checkpoints)
     }
 
+    /// Returns whether the checkpoint builder is done with the commit at `height` of `epoch`.
+    fn is_commit_built(
+        &self,
+        epoch: EpochId,
+        height: CheckpointCommitHeight,
+    ) -> Result<bool, TypedStoreError> {
+        Ok(self
+            .checkpoint_builder_watermark
+            .get(&epoch)?
+            .map_or(false, |watermark| height <= watermark))
+    }
+
+    /// Returns the last checkpoints of the epochs the store has, in order.
+    pub fn get_end_of_epoch_checkpoints(&self) -> Result<Vec<VerifiedCheckpoint>, TypedStoreError> {
+        Ok(self
+            .certified_checkpoints
+            .values()
+            .filter(|checkpoint| checkpoint.summary.next_epoch_committee.is_some())
+            .map(VerifiedCheckpoint::new_unchecked)
+            .collect())
+    }
+
     pub fn get_highest_verified_checkpoint(
         &self,
     ) -> Result<Option<VerifiedCheckpoint>, TypedStoreError> {
@@ -249,6 +303,43 @@ impl CheckpointStore {
         }
     }
 
+    pub fn get_lowest_backfilled_checkpoint(
+        &self,
+    ) -> Result<Option<VerifiedCheckpoint>, TypedStoreError> {
+        let Some((_, digest)) = self.watermarks.get(&CheckpointWatermark::LowestBackfilled)? else {
+            return Ok(None);
+        };
+        self.get_checkpoint_by_digest(&digest)
+    }
+
+    pub fn update_lowest_backfilled_checkpoint(
+        &self,
+        checkpoint: &VerifiedCheckpoint,
+    ) -> Result<(), TypedStoreError> {
+        self.watermarks.insert(
+            &CheckpointWatermark::LowestBackfilled,
+            &(checkpoint.sequence_number(), checkpoint.digest()),
+        )
+    }
+
+    pub fn get_highest_pruned_checkpoint_seq_number(
+        &self,
+        watermark: CheckpointWatermark,
+    ) -> Result<Option<CheckpointSequenceNumber>, TypedStoreError> {
+        Ok(self.watermarks.get(&watermark)?.map(|(seq, _)| seq))
+    }
+
+    pub fn update_highest_pruned_checkpoint(
+        &self,
+        watermark: CheckpointWatermark,
+        checkpoint: &VerifiedCheckpoint,
+    ) -> Result<(), TypedStoreError> {
+        self.watermarks.insert(
+            &watermark,
+            &(checkpoint.sequence_number(), checkpoint.digest()),
+        )
+    }
+
     pub fn insert_checkpoint_contents(
         &self,
         contents: CheckpointContents,
@@ -263,12 +354,17 @@ pub enum CheckpointWatermark {
     HighestVerified,
     HighestSynced,
     HighestExecuted,
+    /// The highest checkpoint whose transactions had the object versions they modified pruned.
+    HighestPrunedObjects,
+    /// The highest checkpoint whose transactions were pruned.
+    HighestPrunedTransactions,
+    /// The lowest checkpoint whose contents were synced on a node restored from a state snapshot.
+    LowestBackfilled,
 }
 
 pub struct CheckpointBuilder {
     state: Arc<AuthorityState>,
     tables: Arc<CheckpointStore>,
-    notify: Arc<Notify>,
     notify_aggregator: Arc<Notify>,
     effects_store: Box<dyn EffectsNotifyRead>,
     output: Box<dyn CheckpointOutput>,
@@ -298,7 +394,6 @@ impl CheckpointBuilder {
     fn new(
         state: Arc<AuthorityState>,
         tables: Arc<CheckpointStore>,
-        notify: Arc<Notify>,
         effects_store: Box<dyn EffectsNotifyRead>,
         output: Box<dyn CheckpointOutput>,
         exit: watch::Receiver<()>,
@@ -308,7 +403,6 @@ impl CheckpointBuilder {
         Self {
             state,
             tables,
-            notify,
             effects_store,
             output,
             exit,
@@ -317,36 +411,92 @@ impl CheckpointBuilder {
         }
     }
 
-    async fn run(mut self) {
+    /// Builds the checkpoints of the commits stored before a restart, then of the commits
+    /// received from `pending`, one at a time and in commit order.
+    async fn run(mut self, mut pending: mpsc::Receiver<PendingCheckpoint>) {
+        // The iterators are not held across the awaits of building the checkpoints.
+        let legacy: Vec<_> = self.tables.pending_checkpoints.iter().collect();
+        for (height, (roots, last_of_epoch)) in legacy {
+            self.build_legacy(height, roots, last_of_epoch).await;
+        }
+        let recovered: Vec<_> = self.tables.pending_commit_checkpoints.values().collect();
+        for checkpoint in recovered {
+            self.build(checkpoint).await;
+        }
         loop {
-            for (height, (roots, last_checkpoint_of_epoch)) in
-                self.tables.pending_checkpoints.iter()
-            {
-                if let Err(e) = self
-                    .make_checkpoint(height, roots, last_checkpoint_of_epoch)
-                    .await
-                {
-                    error!("Error while making checkpoint, will retry in 1s: {:?}", e);
-                    tokio::time::sleep(Duration::from_secs(1)).await;
-                    self.metrics.checkpoint_errors.inc();
-                    continue;
-                }
-            }
-            match select(self.exit.changed().boxed(), self.notify.notified().boxed()).await {
+            let exit = self.exit.changed().boxed();
+            let checkpoint = match select(exit, pending.recv().boxed()).await {
                 Either::Left(_) => {
                     // return on exit signal
                     return;
                 }
-                Either::Right(_) => {}
+                Either::Right((Some(checkpoint), _)) => checkpoint,
+                Either::Right((None, _)) => return,
+            };
+            self.build(checkpoint).await;
+        }
+    }
+
+    async fn build(&self, checkpoint: PendingCheckpoint) {
+        loop {
+            let result = match self
+                .tables
+                .is_commit_built(checkpoint.epoch, checkpoint.height)
+            {
+                Ok(true) => {
+                    debug!(
+                        "Checkpoint at height {} of epoch {} is already built",
+                        checkpoint.height, checkpoint.epoch
+                    );
+                    return;
+                }
+                Ok(false) => {
+                    let key = (checkpoint.epoch, checkpoint.height);
+                    let done = |batch: DBBatch| {
+                        batch
+                            .delete_batch(&self.tables.pending_commit_checkpoints, [key])?
+                            .insert_batch(&self.tables.checkpoint_builder_watermark, [key])
+                    };
+                    self.make_checkpoint(checkpoint.roots.clone(), checkpoint.last_of_epoch, done)
+                        .await
+                }
+                Err(e) => Err(e.into()),
+            };
+            match result {
+                Ok(()) => return,
+                Err(e) => self.retry_after(e).await,
+            }
+        }
+    }
+
+    /// Builds a checkpoint stored by a previous release, see `CheckpointStore::pending_checkpoints`.
+    async fn build_legacy(&self, height: u64, roots: Vec<TransactionDigest>, last_of_epoch: bool) {
+        loop {
+            let done =
+                |batch: DBBatch| batch.delete_batch(&self.tables.pending_checkpoints, [height]);
+            let result = self
+                .make_checkpoint(roots.clone(), last_of_epoch, done)
+                .await;
+            match result {
+                Ok(()) => return,
+                Err(e) => self.retry_after(e).await,
             }
         }
     }
 
+    async fn retry_after(&self, e: anyhow::Error) {
+        error!("Error while making checkpoint, will retry in 1s: {:?}", e);
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        self.metrics.checkpoint_errors.inc();
+    }
+
+    /// Makes the checkpoint of `roots`, writing it along the changes `done` adds to the batch,
+    /// i.e. the removal of its pending checkpoint.
     async fn make_checkpoint(
         &self,
-        height: CheckpointCommitHeight,
         roots: Vec<TransactionDigest>,
-        last_checkpoint_of_epoch: bool,
+        last_of_epoch: bool,
+        done: impl FnOnce(DBBatch) -> Result<DBBatch, TypedStoreError>,
     ) -> anyhow::Result<()> {
         self.metrics
             .checkpoint_roots_count
@@ -359,19 +509,17 @@ impl CheckpointBuilder {
         let _scope = monitored_scope("CheckpointBuilder");
         let unsorted = self.complete_checkpoint_effects(roots)?;
         let sorted = CasualOrder::casual_sort(unsorted);
-        let new_checkpoint = self
-            .create_checkpoint(sorted, last_checkpoint_of_epoch)
-            .await?;
-        self.write_checkpoint(height, new_checkpoint).await?;
+        let new_checkpoint = self.create_checkpoint(sorted, last_of_epoch).await?;
+        self.write_checkpoint(new_checkpoint, done).await?;
         Ok(())
     }
 
     async fn write_checkpoint(
         &self,
-        height: CheckpointCommitHeight,
         new_checkpoint: Option<(CheckpointSummary, CheckpointContents)>,
+        done: impl FnOnce(DBBatch) -> Result<DBBatch, TypedStoreError>,
     ) -> SuiResult {
-        let mut batch = self.tables.pending_checkpoints.batch();
+        let mut batch = self.tables.checkpoint_summary.batch();
         if let Some((summary, contents)) = new_checkpoint {
             // Only create checkpoint if content is not empty
             self.output.checkpoint_created(&summary, &contents).await?;
@@ -401,8 +549,7 @@ impl CheckpointBuilder {
 
             self.notify_aggregator.notify_waiters();
         }
-        batch = batch.delete_batch(&self.tables.pending_checkpoints, [height])?;
-        batch.write()?;
+        done(batch)?.write()?;
         Ok(())
     }
 
@@ -705,21 +852,18 @@ impl SignatureAggregator {
     }
 }
 
-pub trait CheckpointServiceNotify {
+#[async_trait]
+pub trait CheckpointServiceNotify: Send + Sync {
     fn notify_checkpoint_signature(&self, info: &CheckpointSignatureMessage) -> SuiResult;
 
-    fn notify_checkpoint(
-        &self,
-        index: CheckpointCommitHeight,
-        roots: Vec<TransactionDigest>,
-        last_checkpoint_of_epoch: bool,
-    ) -> SuiResult;
+    /// Waits while the builder is behind by `BUILDER_CHANNEL_CAPACITY` checkpoints.
+    async fn notify_checkpoint(&self, checkpoint: PendingCheckpoint) -> SuiResult;
 }
 
 /// This is a service used to communicate with other pieces of sui(for ex. authority)
 pub struct CheckpointService {
     tables: Arc<CheckpointStore>,
-    notify_builder: Arc<Notify>,
+    builder_sender: mpsc::Sender<PendingCheckpoint>,
     notify_aggregator: Arc<Notify>,
     last_signature_index: Mutex<u64>,
     _exit: watch::Sender<()>, // dropping this will eventually stop checkpoint tasks
@@ -734,7 +878,7 @@ impl CheckpointService {
         certified_checkpoint_output: Box<dyn CertifiedCheckpointOutput>,
         metrics: Arc<CheckpointMetrics>,
     ) -> Arc<Self> {
-        let notify_builder = Arc::new(Notify::new());
+        let (builder_sender, builder_receiver) = mpsc::channel(BUILDER_CHANNEL_CAPACITY);
         let notify_aggregator = Arc::new(Notify::new());
 
         let (exit_snd, exit_rcv) = watch::channel(());
@@ -742,7 +886,6 @@ impl CheckpointService {
         let builder = CheckpointBuilder::new(
             state.clone(),
             checkpoint_store.clone(),
-            notify_builder.clone(),
             effects_store,
             checkpoint_output,
             exit_rcv.clone(),
@@ -750,7 +893,7 @@ impl CheckpointService {
             metrics.clone(),
         );
 
-        spawn_monitored_task!(builder.run());
+        spawn_monitored_task!(builder.run(builder_receiver));
 
         let aggregator = CheckpointAggregator::new(
             checkpoint_store.clone(),
@@ -775,7 +918,7 @@ impl CheckpointService {
 
         Arc::new(Self {
             tables: checkpoint_store,
-            notify_builder,
+            builder_sender,
             notify_aggregator,
             last_signature_index,
             _exit: exit_snd,
@@ -801,6 +944,7 @@ impl CheckpointService {
     }
 }
 
+#[async_trait]
 impl CheckpointServiceNotify for CheckpointService {
     fn notify_checkpoint_signature(&self, info: &CheckpointSignatureMessage) -> SuiResult {
         let sequence = info.summary.summary.sequence_number;
@@ -835,30 +979,40 @@ impl CheckpointServiceNotify for CheckpointService {
         Ok(())
     }
 
-    fn notify_checkpoint(
-        &self,
-        index: CheckpointCommitHeight,
-        roots: Vec<TransactionDigest>,
-        last_checkpoint_of_epoch: bool,
-    ) -> SuiResult {
-        if let Some(pending) = self.tables.pending_checkpoints.get(&index)? {
-            if pending.0 != roots {
-                panic!("Received checkpoint at index {} that contradicts previously stored checkpoint. Old digests: {:?}, new digests: {:?}", index, pending, roots);
+    async fn notify_checkpoint(&self, checkpoint: PendingCheckpoint) -> SuiResult {
+        let (epoch, height) = (checkpoint.epoch, checkpoint.height);
+        if self.tables.is_commit_built(epoch, height)? {
+            debug!(
+                "Ignoring checkpoint notification at height {} of epoch {} - already built",
+                height, epoch
+            );
+            return Ok(());
+        }
+        if let Some(pending) = self
+            .tables
+            .pending_commit_checkpoints
+            .get(&(epoch, height))?
+        {
+            if pending.roots != checkpoint.roots {
+                panic!("Received checkpoint at height {} of epoch {} that contradicts previously stored checkpoint. Old digests: {:?}, new digests: {:?}", height, epoch, pending.roots, checkpoint.roots);
             }
             debug!(
-                "Ignoring duplicate checkpoint notification at height {}",
-                index
+                "Ignoring duplicate checkpoint notification at height {} of epoch {}",
+                height, epoch
             );
             return Ok(());
         }
         debug!(
-            "Transaction roots for pending checkpoint {}: {:?}",
-            index, roots
+            "Transaction roots for pending checkpoint at height {} of epoch {}: {:?}",
+            height, epoch, checkpoint.roots
         );
         self.tables
-            .pending_checkpoints
-            .insert(&index, &(roots, last_checkpoint_of_epoch))?;
-        self.notify_builder.notify_one();
+            .pending_commit_checkpoints
+            .insert(&(epoch, height), &checkpoint)?;
+        if self.builder_sender.send(checkpoint).await.is_err() {
+            // The checkpoint stays pending and is built after a restart
+            warn!("Checkpoint builder has stopped, not building checkpoint at height {height}");
+        }
         Ok(())
     }
 }
@@ -866,17 +1020,13 @@ impl CheckpointServiceNotify for CheckpointService {
 #[cfg(test)]
 pub struct CheckpointServiceNoop {}
 #[cfg(test)]
+#[async_trait]
 impl CheckpointServiceNotify for CheckpointServiceNoop {
     fn notify_checkpoint_signature(&self, _: &CheckpointSignatureMessage) -> SuiResult {
         Ok(())
     }
 
-    fn notify_checkpoint(
-        &self,
-        _: CheckpointCommitHeight,
-        _: Vec<TransactionDigest>,
-        _: bool,
-    ) -> SuiResult {
+    async fn notify_checkpoint(&self, _: PendingCheckpoint) -> SuiResult {
         Ok(())
     }
 }
@@ -971,6 +1121,14 @@ mod tests {
             d(4),
             e(&state, d(4), vec![], GasCostSummary::new(41, 42, 43)),
         );
+        store.insert(
+            d(5),
+            e(&state, d(5), vec![], GasCostSummary::new(51, 52, 53)),
+        );
+        store.insert(
+            d(6),
+            e(&state, d(6), vec![], GasCostSummary::new(61, 62, 63)),
+        );
 
         let (output, mut result) = mpsc::channel::<(CheckpointContents, CheckpointSummary)>(10);
         let (certified_output, mut certified_result) =
@@ -988,14 +1146,17 @@ mod tests {
         );
         let mut tailer = checkpoint_service.subscribe_checkpoints(0);
         checkpoint_service
-            .notify_checkpoint(0, vec![d(4)], false)
+            .notify_checkpoint(p(0, vec![d(4)]))
+            .await
             .unwrap();
         // Verify that sending same digests at same height is noop
         checkpoint_service
-            .notify_checkpoint(0, vec![d(4)], false)
+            .notify_checkpoint(p(0, vec![d(4)]))
+            .await
             .unwrap();
         checkpoint_service
-            .notify_checkpoint(1, vec![d(1), d(3)], false)
+            .notify_checkpoint(p(1, vec![d(1), d(3)]))
+            .await
             .unwrap();
 
         let (c1c, c1s) = result.recv().await.unwrap();
@@ -1040,6 +1201,75 @@ mod tests {
         let (t2s, _content) = tailer.recv().await.unwrap();
         assert_eq!(t1s.sequence_number, 0);
         assert_eq!(t2s.sequence_number, 1);
+
+        // Verify that sending a commit whose checkpoint is already built is noop: the next
+        // checkpoint is the one of the following commit
+        checkpoint_service
+            .notify_checkpoint(p(1, vec![d(5)]))
+            .await
+            .unwrap();
+        checkpoint_service
+            .notify_checkpoint(p(2, vec![d(6)]))
+            .await
+            .unwrap();
+        let (c3c, c3s) = result.recv().await.unwrap();
+        let c3t = c3c.iter().map(|d| d.transaction).collect::<Vec<_>>();
+        assert_eq!(c3t, vec![d(6)]);
+        assert_eq!(c3s.sequence_number, 2);
+    }
+
+    #[tokio::test]
+    pub async fn legacy_pending_checkpoints_test() {
+        let tempdir = tempdir().unwrap();
+        let (keypair, committee) = committee();
+        let state = AuthorityState::new_for_testing(committee.clone(), &keypair, None, None).await;
+
+        let mut store = HashMap::<TransactionDigest, SignedTransactionEffects>::new();
+        store.insert(
+            d(1),
+            e(&state, d(1), vec![], GasCostSummary::new(11, 12, 13)),
+        );
+        store.insert(
+            d(2),
+            e(&state, d(2), vec![], GasCostSummary::new(21, 22, 23)),
+        );
+
+        let (output, mut result) = mpsc::channel::<(CheckpointContents, CheckpointSummary)>(10);
+        let (certified_output, _certified_result) = mpsc::channel::<CertifiedCheckpointSummary>(10);
+
+        // A checkpoint left pending by a previous release, keyed by its consensus round
+        let checkpoint_store = CheckpointStore::new(tempdir.path());
+        checkpoint_store
+            .pending_checkpoints
+            .insert(&15, &(vec![d(1)], false))
+            .unwrap();
+        let checkpoint_service = CheckpointService::spawn(
+            state,
+            checkpoint_store.clone(),
+            Box::new(store),
+            Box::new(output),
+            Box::new(certified_output),
+            CheckpointMetrics::new_for_tests(),
+        );
+        checkpoint_service
+            .notify_checkpoint(p(0, vec![d(2)]))
+            .await
+            .unwrap();
+
+        // It is built before the commits, then removed
+        let (c1c, c1s) = result.recv().await.unwrap();
+        let (c2c, c2s) = result.recv().await.unwrap();
+        assert_eq!(
+            c1c.iter().map(|d| d.transaction).collect::<Vec<_>>(),
+            vec![d(1)]
+        );
+        assert_eq!(c1s.sequence_number, 0);
+        assert_eq!(
+            c2c.iter().map(|d| d.transaction).collect::<Vec<_>>(),
+            vec![d(2)]
+        );
+        assert_eq!(c2s.previous_digest, Some(c1s.digest()));
+        assert!(checkpoint_store.pending_checkpoints.is_empty());
     }
 
     #[async_trait]
@@ -1094,6 +1324,15 @@ mod tests {
         TransactionDigest::new(bytes)
     }
 
+    fn p(height: u64, roots: Vec<TransactionDigest>) -> PendingCheckpoint {
+        PendingCheckpoint {
+            epoch: 0,
+            height,
+            roots,
+            last_of_epoch: false,
+        }
+    }
+
     fn e(
         state: &AuthorityState,
         transaction_digest: TransactionDigest,
@@ -129,7 +129,10 @@ impl SubmitToConsensus for TransactionsClient<sui_network::tonic::transport::Cha
             bincode::serialize(transaction).expect("Serializing consensus transaction cannot fail");
         let bytes = Bytes::from(serialized.clone());
         self.clone()
-            .submit_transaction(TransactionProto { transaction: bytes })
+            .submit_transaction(TransactionProto {
+                transaction: bytes,
+                metadata: Bytes::new(),
+            })
             .await
             .map_err(|e| SuiError::ConsensusConnectionBroken(format!("{:?}", e)))
             .tap_err(|r| {
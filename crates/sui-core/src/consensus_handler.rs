@@ -7,7 +7,7 @@ use crate::checkpoints::CheckpointService;
 use async_trait::async_trait;
 use mysten_metrics::monitored_scope;
 use narwhal_executor::{ExecutionIndices, ExecutionState};
-use narwhal_types::ConsensusOutput;
+use narwhal_types::{CommittedSubDag, ConsensusOutput};
 use std::collections::hash_map::DefaultHasher;
 use std::hash::{Hash, Hasher};
 use std::sync::{Arc, Mutex};
@@ -15,6 +15,20 @@ use sui_types::base_types::AuthorityName;
 use sui_types::messages::ConsensusTransaction;
 use tracing::{debug, instrument, warn};
 
+/// Narwhal starts its rounds and sub-dag indices from zero again in each of its epochs, while a
+/// Sui epoch may span several of them (see `NodeReconfiguration::EndOfEpoch`). The Narwhal epoch
+/// goes in the upper bits of the consensus indices, so that they keep increasing through the Sui
+/// epoch and the commits of a new Narwhal epoch are not mistaken for already processed ones.
+const NARWHAL_EPOCH_SHIFT: u32 = 32;
+const NARWHAL_INDEX_MASK: u64 = (1 << NARWHAL_EPOCH_SHIFT) - 1;
+
+/// Returns the round of the leader and the index of `sub_dag` in the consensus order of the Sui
+/// epoch.
+pub(crate) fn commit_indices(sub_dag: &CommittedSubDag) -> (u64, u64) {
+    let epoch = sub_dag.leader.epoch() << NARWHAL_EPOCH_SHIFT;
+    (epoch | sub_dag.round(), epoch | sub_dag.sub_dag_index)
+}
+
 pub struct ConsensusHandler {
     state: Arc<AuthorityState>,
     last_seen: Mutex<ExecutionIndicesWithHash>,
@@ -75,7 +89,13 @@ impl ExecutionState for ConsensusHandler {
         let mut seq = 0;
 
         let mut bytes = 0usize;
-        let round = consensus_output.sub_dag.round();
+        let (round, sub_dag_index) = commit_indices(&consensus_output.sub_dag);
+        let narwhal_epoch = consensus_output.sub_dag.leader.epoch();
+        self.state
+            .metrics
+            .consensus_handler_narwhal_epoch
+            .set(narwhal_epoch as i64);
+        self.state.set_consensus_epoch(narwhal_epoch);
         for (cert, batches) in consensus_output.batches {
             let author = cert.header.author.clone();
             let output_cert = Arc::new(cert);
@@ -98,7 +118,7 @@ impl ExecutionState for ConsensusHandler {
                     };
                     let index = ExecutionIndices {
                         last_committed_round: round,
-                        sub_dag_index: consensus_output.sub_dag.sub_dag_index,
+                        sub_dag_index,
                         transaction_index: seq,
                     };
 
@@ -146,6 +166,7 @@ impl ExecutionState for ConsensusHandler {
 
         self.state
             .handle_commit_boundary(&consensus_output.sub_dag, &self.checkpoint_service)
+            .await
             .expect("Unrecoverable error in consensus handler when processing commit boundary")
     }
 
@@ -156,7 +177,8 @@ impl ExecutionState for ConsensusHandler {
             .last_consensus_index()
             .expect("Failed to load consensus indices");
 
-        index_with_hash.index.sub_dag_index
+        // Narwhal only knows the index within its current epoch.
+        index_with_hash.index.sub_dag_index & NARWHAL_INDEX_MASK
     }
 }
 
@@ -190,6 +212,16 @@ impl SequencedConsensusTransaction {
             consensus_index: Default::default(),
         }
     }
+
+    /// The transaction sequenced in a certificate of `authority`.
+    pub fn new_test_from(authority: &AuthorityName, transaction: ConsensusTransaction) -> Self {
+        let mut certificate = narwhal_types::Certificate::default();
+        certificate.header.author = (*authority).try_into().unwrap();
+        Self {
+            certificate: Arc::new(certificate),
+            ..Self::new_test(transaction)
+        }
+    }
 }
 
 #[test]
@@ -221,3 +253,32 @@ pub fn test_update_hash() {
     assert!(ConsensusHandler::update_hash(&last_seen, index1, tx).is_none());
     assert!(ConsensusHandler::update_hash(&last_seen, index2, tx).is_some());
 }
+
+#[test]
+pub fn test_commit_indices_across_narwhal_epochs() {
+    let sub_dag = |epoch, round, sub_dag_index| {
+        let mut leader = narwhal_types::Certificate::default();
+        leader.header.epoch = epoch;
+        leader.header.round = round;
+        CommittedSubDag {
+            certificates: vec![],
+            leader,
+            sub_dag_index,
+        }
+    };
+    let last_seen = Mutex::new(ExecutionIndicesWithHash::default());
+    let index = |(last_committed_round, sub_dag_index)| ExecutionIndices {
+        last_committed_round,
+        sub_dag_index,
+        transaction_index: 0,
+    };
+    let tx = &[0];
+
+    let end_of_epoch = index(commit_indices(&sub_dag(0, 100, 50)));
+    assert!(ConsensusHandler::update_hash(&last_seen, end_of_epoch, tx).is_some());
+    // The first commit of the next Narwhal epoch comes after the last one of the previous epoch.
+    let next_epoch = index(commit_indices(&sub_dag(1, 2, 1)));
+    assert!(next_epoch > end_of_epoch);
+    assert!(ConsensusHandler::update_hash(&last_seen, next_epoch, tx).is_some());
+    assert_eq!(next_epoch.sub_dag_index & NARWHAL_INDEX_MASK, 1);
+}
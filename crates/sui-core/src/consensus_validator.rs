@@ -174,6 +174,7 @@ mod tests {
             .map(|cert| {
                 bincode::serialize(&ConsensusTransaction::new_certificate_message(&name1, cert))
                     .unwrap()
+                    .into()
             })
             .collect();
 
@@ -7,7 +7,7 @@ use sui_storage::default_db_options;
 use sui_types::base_types::ObjectID;
 use sui_types::committee::{Committee, EpochId};
 use sui_types::error::{SuiError, SuiResult};
-use typed_store::rocks::{DBMap, DBOptions};
+use typed_store::rocks::{DBMap, DBOptions, TypedStoreError};
 use typed_store::traits::TypedStoreDebug;
 
 use sui_types::fp_ensure;
@@ -41,6 +41,15 @@ impl CommitteeStore {
         committee_store
     }
 
+    /// Opens the existing committees at `path` in read only mode, in which inserting new ones
+    /// fails.
+    pub fn new_read_only_mode(
+        path: PathBuf,
+        db_options: Option<Options>,
+    ) -> Result<Self, TypedStoreError> {
+        Self::open_tables_read_only_mode(path, db_options, None)
+    }
+
     pub fn new_for_testing(genesis_committee: &Committee) -> Self {
         let dir = std::env::temp_dir();
         let path = dir.join(format!("DB_{:?}", nondeterministic!(ObjectID::random())));
@@ -16,12 +16,15 @@ pub mod execution_engine;
 pub mod metrics;
 pub mod quorum_driver;
 pub mod safe_client;
+pub mod signature_verifier;
+pub mod state_snapshot;
 pub mod storage;
 pub mod streamer;
 pub mod tbls;
 pub mod test_utils;
 pub mod transaction_input_checker;
 pub mod transaction_orchestrator;
+pub mod transaction_replay;
 pub mod transaction_streamer;
 pub mod validator_info;
 
@@ -77,7 +77,9 @@ impl QueryHelpers {
         let opt = database.get_certified_transaction(digest)?;
         match opt {
             Some(certificate) => Ok((certificate, database.get_effects(digest)?)),
-            None => Err(anyhow!(SuiError::TransactionNotFound { digest: *digest })),
+            None => database
+                .get_backfilled_transaction(digest)?
+                .ok_or_else(|| anyhow!(SuiError::TransactionNotFound { digest: *digest })),
         }
     }
 }
@@ -28,6 +28,8 @@ pub struct QuorumDriverMetrics {
     pub(crate) total_times_conflicting_transaction_already_finalized_when_retrying: IntCounter,
 
     pub(crate) total_equivocation_detected: IntCounter,
+
+    pub(crate) total_retried_attempts: IntCounter,
 }
 
 const LATENCY_SEC_BUCKETS: &[f64] = &[
@@ -130,6 +132,12 @@ impl QuorumDriverMetrics {
                 registry,
             )
             .unwrap(),
+            total_retried_attempts: register_int_counter_with_registry!(
+                "quorum_driver_total_retried_attempts",
+                "Total number of attempts retried after a transient error",
+                registry,
+            )
+            .unwrap(),
         }
     }
 
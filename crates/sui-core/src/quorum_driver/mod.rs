@@ -6,6 +6,7 @@ pub use metrics::*;
 
 use arc_swap::ArcSwap;
 use std::collections::{BTreeMap, BTreeSet};
+use std::future::Future;
 use std::sync::Arc;
 use std::time::Duration;
 use sui_types::base_types::{AuthorityName, ObjectRef, TransactionDigest};
@@ -15,6 +16,7 @@ use tap::TapFallible;
 
 use tokio::sync::mpsc::{self, Receiver, Sender};
 use tokio::task::JoinHandle;
+use tokio::time::{sleep, timeout};
 use tracing::Instrument;
 use tracing::{debug, error, info, warn};
 
@@ -23,12 +25,18 @@ use crate::authority_client::AuthorityAPI;
 use mysten_metrics::spawn_monitored_task;
 use sui_types::error::{SuiError, SuiResult};
 use sui_types::messages::{
-    QuorumDriverRequest, QuorumDriverRequestType, QuorumDriverResponse, VerifiedCertificate,
-    VerifiedTransaction,
+    QuorumDriverRequest, QuorumDriverRequestType, QuorumDriverResponse, QuorumDriverRetryPolicy,
+    VerifiedCertificate, VerifiedTransaction,
 };
 
 const TASK_QUEUE_SIZE: usize = 5000;
 
+/// The most attempts of each step of a request, whatever the retry policy, so that the callers
+/// cannot hold on to the driver for too long.
+pub const MAX_RETRY_ATTEMPTS: u32 = 10;
+/// The longest delay between two attempts, whatever the retry policy.
+pub const MAX_RETRY_BACKOFF: Duration = Duration::from_secs(30);
+
 pub enum QuorumTask {
     ProcessTransaction(VerifiedTransaction),
     ProcessCertificate(VerifiedCertificate),
@@ -97,6 +105,18 @@ where
     pub async fn execute_transaction(
         &self,
         request: QuorumDriverRequest,
+    ) -> SuiResult<QuorumDriverResponse> {
+        self.execute_transaction_with_retry_policy(request, &QuorumDriverRetryPolicy::default())
+            .await
+    }
+
+    /// Execute the transaction, retrying the steps that fail transiently as set by `retry_policy`.
+    /// The transactions of `ImmediateReturn` requests are processed in the background, with a
+    /// single attempt.
+    pub async fn execute_transaction_with_retry_policy(
+        &self,
+        request: QuorumDriverRequest,
+        retry_policy: &QuorumDriverRetryPolicy,
     ) -> SuiResult<QuorumDriverResponse> {
         let tx_digest = request.transaction.digest();
         debug!(?tx_digest, "Received transaction execution request");
@@ -122,7 +142,9 @@ where
                 self.metrics.total_requests_wait_for_tx_cert.inc();
                 let _timer = self.metrics.latency_sec_wait_for_tx_cert.start_timer();
 
-                let res = self.execute_transaction_wait_for_tx_cert(transaction).await;
+                let res = self
+                    .execute_transaction_wait_for_tx_cert(transaction, retry_policy)
+                    .await;
 
                 (&self.metrics.total_ok_responses_wait_for_tx_cert, res)
             }
@@ -131,7 +153,7 @@ where
                 let _timer = self.metrics.latency_sec_wait_for_effects_cert.start_timer();
 
                 let res = self
-                    .execute_transaction_wait_for_effects_cert(transaction)
+                    .execute_transaction_wait_for_effects_cert(transaction, retry_policy)
                     .await;
 
                 (&self.metrics.total_ok_responses_wait_for_effects_cert, res)
@@ -159,8 +181,11 @@ where
     async fn execute_transaction_wait_for_tx_cert(
         &self,
         transaction: VerifiedTransaction,
+        retry_policy: &QuorumDriverRetryPolicy,
     ) -> SuiResult<QuorumDriverResponse> {
-        let certificate = self.process_transaction(transaction).await?;
+        let certificate = self
+            .process_transaction_with_retries(transaction, retry_policy)
+            .await?;
         self.task_sender
             .send(QuorumTask::ProcessCertificate(certificate.clone()))
             .await
@@ -173,17 +198,88 @@ where
     async fn execute_transaction_wait_for_effects_cert(
         &self,
         transaction: VerifiedTransaction,
+        retry_policy: &QuorumDriverRetryPolicy,
     ) -> SuiResult<QuorumDriverResponse> {
-        let certificate = self.process_transaction(transaction).await?;
-        let response = self.process_certificate(certificate).await?;
+        let certificate = self
+            .process_transaction_with_retries(transaction, retry_policy)
+            .await?;
+        let tx_digest = *certificate.digest();
+        let response = self
+            .with_retries(&tx_digest, retry_policy, || {
+                self.process_certificate(certificate.clone())
+            })
+            .await?;
         Ok(QuorumDriverResponse::EffectsCert(Box::new((
             response.0, response.1,
         ))))
     }
 
+    async fn process_transaction_with_retries(
+        &self,
+        transaction: VerifiedTransaction,
+        retry_policy: &QuorumDriverRetryPolicy,
+    ) -> SuiResult<VerifiedCertificate> {
+        let tx_digest = *transaction.digest();
+        self.with_retries(&tx_digest, retry_policy, || {
+            self.process_transaction_once(
+                transaction.clone(),
+                retry_policy.retry_conflicting_transactions,
+            )
+        })
+        .await
+    }
+
+    /// Run `attempt` until it succeeds, fails for good or runs out of attempts, each of them
+    /// bounded by the timeout of `retry_policy`.
+    async fn with_retries<T, F, Fut>(
+        &self,
+        tx_digest: &TransactionDigest,
+        retry_policy: &QuorumDriverRetryPolicy,
+        mut attempt: F,
+    ) -> SuiResult<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = SuiResult<T>>,
+    {
+        let max_attempts = retry_policy.max_attempts.clamp(1, MAX_RETRY_ATTEMPTS);
+        let mut attempt_number = 1;
+        loop {
+            let result = match retry_policy.attempt_timeout() {
+                Some(attempt_timeout) => timeout(attempt_timeout, attempt())
+                    .await
+                    .unwrap_or(Err(SuiError::TimeoutError)),
+                None => attempt().await,
+            };
+            match result {
+                Err(err) if err.is_transient() && attempt_number < max_attempts => {
+                    let backoff = retry_policy.backoff(attempt_number).min(MAX_RETRY_BACKOFF);
+                    debug!(
+                        ?tx_digest,
+                        attempt_number,
+                        ?backoff,
+                        "Retrying after a transient error: {:?}",
+                        err
+                    );
+                    self.metrics.total_retried_attempts.inc();
+                    sleep(backoff).await;
+                    attempt_number += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
     pub async fn process_transaction(
         &self,
         transaction: VerifiedTransaction,
+    ) -> SuiResult<VerifiedCertificate> {
+        self.process_transaction_once(transaction, true).await
+    }
+
+    async fn process_transaction_once(
+        &self,
+        transaction: VerifiedTransaction,
+        retry_conflicting_transactions: bool,
     ) -> SuiResult<VerifiedCertificate> {
         let tx_digest = *transaction.digest();
         let result = self
@@ -198,7 +294,7 @@ where
                 good_stake,
                 errors: _errors,
                 conflicting_tx_digests,
-            }) if !conflicting_tx_digests.is_empty() => {
+            }) if retry_conflicting_transactions && !conflicting_tx_digests.is_empty() => {
                 self.metrics
                     .total_err_process_tx_responses_with_nonzero_conflicting_transactions
                     .inc();
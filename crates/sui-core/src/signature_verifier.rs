@@ -0,0 +1,401 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Verifies the signatures of the transactions and certificates received by the validator in
+//! batches, gathering what arrives within a short window.
+//!
+//! The authority signatures of the certificates of a batch are verified at once, which costs
+//! about as much as verifying a single certificate. When the batch fails, the certificates are
+//! verified one by one to reject only the invalid ones. The user signatures of the batch are
+//! grouped by scheme, and each group is verified at once in the same way, falling back to single
+//! checks when it fails. The multisigs, the sponsored transactions and the system transactions are
+//! verified on their own.
+
+use crate::authority::authority_per_epoch_store::AuthorityPerEpochStore;
+use mysten_metrics::spawn_monitored_task;
+use parking_lot::Mutex;
+use prometheus::{
+    register_histogram_with_registry, register_int_counter_vec_with_registry,
+    register_int_counter_with_registry, Histogram, IntCounter, IntCounterVec, Registry,
+};
+use std::sync::Arc;
+use std::time::Duration;
+use sui_types::crypto::{
+    AuthoritySignInfoTrait, Signature, SuiSignatureInner, VerificationObligation,
+};
+use sui_types::error::{SuiError, SuiResult};
+use sui_types::message_envelope::Message;
+use sui_types::messages::{
+    CertifiedTransaction, SenderSignedData, Transaction, VerifiedCertificate, VerifiedTransaction,
+};
+use sui_types::multisig::GenericSignature;
+use tokio::sync::oneshot;
+use tokio::time::sleep;
+
+#[cfg(test)]
+#[path = "unit_tests/signature_verifier_tests.rs"]
+mod signature_verifier_tests;
+
+/// A batch is verified as soon as it has this many items.
+const MAX_BATCH_SIZE: usize = 64;
+/// How long the first item of a batch waits for more items before the batch is verified.
+const BATCH_TIMEOUT: Duration = Duration::from_millis(2);
+
+pub struct SignatureVerifierMetrics {
+    pub batch_size: Histogram,
+    pub certificate_batch_failures: IntCounter,
+    pub certificate_verifications_saved: IntCounter,
+    pub user_signature_batch_failures: IntCounterVec,
+    pub user_signature_verifications_saved: IntCounterVec,
+}
+
+const BATCH_SIZE_BUCKETS: &[f64] = &[1., 2., 4., 8., 16., 32., 64.];
+
+impl SignatureVerifierMetrics {
+    pub fn new(registry: &Registry) -> Self {
+        Self {
+            batch_size: register_histogram_with_registry!(
+                "signature_verifier_batch_size",
+                "Number of transactions and certificates verified in a batch",
+                BATCH_SIZE_BUCKETS.to_vec(),
+                registry,
+            )
+            .unwrap(),
+            certificate_batch_failures: register_int_counter_with_registry!(
+                "signature_verifier_certificate_batch_failures",
+                "Number of batches of certificates verified one by one after the batch failed",
+                registry,
+            )
+            .unwrap(),
+            certificate_verifications_saved: register_int_counter_with_registry!(
+                "signature_verifier_certificate_verifications_saved",
+                "Number of certificate signature verifications saved by verifying them in batches",
+                registry,
+            )
+            .unwrap(),
+            user_signature_batch_failures: register_int_counter_vec_with_registry!(
+                "signature_verifier_user_signature_batch_failures",
+                "Number of batches of user signatures verified one by one after the batch failed",
+                &["scheme"],
+                registry,
+            )
+            .unwrap(),
+            user_signature_verifications_saved: register_int_counter_vec_with_registry!(
+                "signature_verifier_user_signature_verifications_saved",
+                "Number of user signature verifications saved by verifying them in batches",
+                &["scheme"],
+                registry,
+            )
+            .unwrap(),
+        }
+    }
+
+    pub fn new_for_tests() -> Self {
+        let registry = Registry::new();
+        Self::new(&registry)
+    }
+}
+
+type PendingCertificate = (
+    CertifiedTransaction,
+    Arc<AuthorityPerEpochStore>,
+    oneshot::Sender<SuiResult<VerifiedCertificate>>,
+);
+
+enum PendingVerification {
+    Transaction(Transaction, oneshot::Sender<SuiResult<VerifiedTransaction>>),
+    Certificate(PendingCertificate),
+}
+
+#[derive(Default)]
+struct PendingBatch {
+    /// Incremented every time the batch is taken, so that the timeout of a batch does not
+    /// verify the next one early.
+    generation: u64,
+    items: Vec<PendingVerification>,
+}
+
+impl PendingBatch {
+    fn take(&mut self) -> Vec<PendingVerification> {
+        self.generation += 1;
+        std::mem::take(&mut self.items)
+    }
+}
+
+pub struct SignatureVerifier {
+    pending: Mutex<PendingBatch>,
+    metrics: Arc<SignatureVerifierMetrics>,
+}
+
+impl SignatureVerifier {
+    pub fn new(metrics: Arc<SignatureVerifierMetrics>) -> Arc<Self> {
+        Arc::new(Self {
+            pending: Mutex::new(PendingBatch::default()),
+            metrics,
+        })
+    }
+
+    pub fn new_for_tests() -> Arc<Self> {
+        Self::new(Arc::new(SignatureVerifierMetrics::new_for_tests()))
+    }
+
+    /// Verifies the user signatures of `transaction`.
+    pub async fn verify_transaction(
+        self: &Arc<Self>,
+        transaction: Transaction,
+    ) -> SuiResult<VerifiedTransaction> {
+        let (sender, receiver) = oneshot::channel();
+        self.push(PendingVerification::Transaction(transaction, sender));
+        receiver
+            .await
+            .unwrap_or_else(|_| Err("Signature verification task failed".into()))
+    }
+
+    /// Verifies the user and authority signatures of `certificate`, against the committee of
+    /// `epoch_store`.
+    pub async fn verify_certificate(
+        self: &Arc<Self>,
+        certificate: CertifiedTransaction,
+        epoch_store: Arc<AuthorityPerEpochStore>,
+    ) -> SuiResult<VerifiedCertificate> {
+        let (sender, receiver) = oneshot::channel();
+        self.push(PendingVerification::Certificate((
+            certificate,
+            epoch_store,
+            sender,
+        )));
+        receiver
+            .await
+            .unwrap_or_else(|_| Err("Signature verification task failed".into()))
+    }
+
+    fn push(self: &Arc<Self>, item: PendingVerification) {
+        let mut pending = self.pending.lock();
+        pending.items.push(item);
+        if pending.items.len() >= MAX_BATCH_SIZE {
+            let batch = pending.take();
+            drop(pending);
+            self.spawn_verify(batch);
+        } else if pending.items.len() == 1 {
+            let generation = pending.generation;
+            let verifier = self.clone();
+            spawn_monitored_task!(async move {
+                sleep(BATCH_TIMEOUT).await;
+                verifier.flush(generation);
+            });
+        }
+    }
+
+    fn flush(&self, generation: u64) {
+        let mut pending = self.pending.lock();
+        if pending.generation != generation {
+            // The batch was already taken when it became full
+            return;
+        }
+        let batch = pending.take();
+        drop(pending);
+        self.spawn_verify(batch);
+    }
+
+    fn spawn_verify(&self, batch: Vec<PendingVerification>) {
+        let metrics = self.metrics.clone();
+        tokio::task::spawn_blocking(move || verify_batch(batch, &metrics));
+    }
+}
+
+fn verify_batch(batch: Vec<PendingVerification>, metrics: &SignatureVerifierMetrics) {
+    metrics.batch_size.observe(batch.len() as f64);
+    let results = verify_user_signatures(&batch, metrics);
+    let mut certificates = Vec::new();
+    for (item, result) in batch.into_iter().zip(results) {
+        match item {
+            PendingVerification::Transaction(transaction, sender) => {
+                let _ = sender
+                    .send(result.map(|()| VerifiedTransaction::new_from_verified(transaction)));
+            }
+            PendingVerification::Certificate((certificate, epoch_store, sender)) => match result {
+                Ok(()) => certificates.push((certificate, epoch_store, sender)),
+                Err(e) => {
+                    let _ = sender.send(Err(e));
+                }
+            },
+        }
+    }
+    verify_certificates(certificates, metrics);
+}
+
+/// Verifies the user signatures of the transactions and certificates of `batch`, and returns
+/// the result of each of them.
+fn verify_user_signatures(
+    batch: &[PendingVerification],
+    metrics: &SignatureVerifierMetrics,
+) -> Vec<SuiResult> {
+    let mut ed25519 = UserSignatureBatch::default();
+    let mut secp256k1 = UserSignatureBatch::default();
+    let mut results: Vec<_> = batch
+        .iter()
+        .enumerate()
+        .map(|(index, item)| {
+            let data = match item {
+                PendingVerification::Transaction(transaction, _) => transaction.data(),
+                PendingVerification::Certificate((certificate, _, _)) => certificate.data(),
+            };
+            match &data.tx_signature {
+                GenericSignature::Signature(signature)
+                    if data.gas_sponsorship.is_none()
+                        && !data.intent_message.value.kind.is_system_tx() =>
+                {
+                    match signature {
+                        Signature::Ed25519SuiSignature(signature) => {
+                            ed25519.add(index, signature, data)
+                        }
+                        Signature::Secp256k1SuiSignature(signature) => {
+                            secp256k1.add(index, signature, data)
+                        }
+                    }
+                }
+                _ => data.verify(),
+            }
+        })
+        .collect();
+    ed25519.verify(&mut results, "ed25519", true, metrics);
+    // There is no batch verification of secp256k1 signatures, the one of fastcrypto verifies
+    // them one by one.
+    secp256k1.verify(&mut results, "secp256k1", false, metrics);
+    results
+}
+
+/// The user signatures of a scheme in a batch, verified at once.
+struct UserSignatureBatch<S: SuiSignatureInner> {
+    /// The index in the batch of the item of each signature.
+    indexes: Vec<usize>,
+    messages: Vec<Vec<u8>>,
+    public_keys: Vec<S::PubKey>,
+    signatures: Vec<S::Sig>,
+}
+
+impl<S: SuiSignatureInner> Default for UserSignatureBatch<S> {
+    fn default() -> Self {
+        Self {
+            indexes: Vec::new(),
+            messages: Vec::new(),
+            public_keys: Vec::new(),
+            signatures: Vec::new(),
+        }
+    }
+}
+
+impl<S: SuiSignatureInner> UserSignatureBatch<S> {
+    /// Adds the signature of the sender of `data`, unless it is not of the sender.
+    fn add(&mut self, index: usize, signature: &S, data: &SenderSignedData) -> SuiResult {
+        let (signature, public_key) =
+            signature.get_verification_inputs(data.intent_message.value.sender)?;
+        let message =
+            bcs::to_bytes(&data.intent_message).expect("Message serialization should not fail");
+        self.indexes.push(index);
+        self.messages.push(message);
+        self.public_keys.push(public_key);
+        self.signatures.push(signature);
+        Ok(())
+    }
+
+    /// Verifies the signatures, at once if `batched`, and sets the results of the invalid ones in
+    /// `results`.
+    fn verify(
+        self,
+        results: &mut [SuiResult],
+        scheme: &str,
+        batched: bool,
+        metrics: &SignatureVerifierMetrics,
+    ) {
+        if self.indexes.is_empty() {
+            return;
+        }
+        if batched {
+            if S::PubKey::verify_batch_empty_fail_different_msg(
+                &self.messages,
+                &self.public_keys,
+                &self.signatures,
+            )
+            .is_ok()
+            {
+                metrics
+                    .user_signature_verifications_saved
+                    .with_label_values(&[scheme])
+                    .inc_by(self.indexes.len() as u64 - 1);
+                return;
+            }
+            metrics
+                .user_signature_batch_failures
+                .with_label_values(&[scheme])
+                .inc();
+        }
+        let signatures = self
+            .messages
+            .iter()
+            .zip(&self.public_keys)
+            .zip(&self.signatures);
+        for (index, ((message, public_key), signature)) in self.indexes.into_iter().zip(signatures)
+        {
+            if let Err(e) = public_key.verify(message, signature) {
+                results[index] = Err(SuiError::InvalidSignature {
+                    error: format!("{}", e),
+                });
+            }
+        }
+    }
+}
+
+fn verify_certificates(certificates: Vec<PendingCertificate>, metrics: &SignatureVerifierMetrics) {
+    let mut obligation = VerificationObligation::default();
+    let mut batched = Vec::new();
+    for (certificate, epoch_store, sender) in certificates {
+        match add_certificate(&certificate, &epoch_store) {
+            Ok(certificate_obligation) => {
+                obligation.append(certificate_obligation);
+                batched.push((certificate, epoch_store, sender));
+            }
+            Err(e) => {
+                let _ = sender.send(Err(e));
+            }
+        }
+    }
+    if batched.is_empty() {
+        return;
+    }
+
+    if obligation.verify_all().is_ok() {
+        metrics
+            .certificate_verifications_saved
+            .inc_by(batched.len() as u64 - 1);
+        for (certificate, _, sender) in batched {
+            let _ = sender.send(Ok(VerifiedCertificate::new_from_verified(certificate)));
+        }
+        return;
+    }
+
+    metrics.certificate_batch_failures.inc();
+    for (certificate, epoch_store, sender) in batched {
+        let result = certificate
+            .auth_sig()
+            .verify(certificate.data(), epoch_store.committee())
+            .map(|()| VerifiedCertificate::new_from_verified(certificate));
+        let _ = sender.send(result);
+    }
+}
+
+/// Returns the obligation of the authority signatures of `certificate`, of which the user
+/// signatures were verified, to be verified in a batch.
+fn add_certificate(
+    certificate: &CertifiedTransaction,
+    epoch_store: &AuthorityPerEpochStore,
+) -> SuiResult<VerificationObligation> {
+    let mut obligation = VerificationObligation::default();
+    let idx = obligation.add_message(certificate.data(), certificate.epoch());
+    certificate.auth_sig().add_to_verification_obligation(
+        epoch_store.committee(),
+        &mut obligation,
+        idx,
+    )?;
+    Ok(obligation)
+}
@@ -0,0 +1,433 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! State snapshots let a new node start from the state of a recent epoch, instead of executing
+//! all the checkpoints since genesis.
+//!
+//! A snapshot is written at the end of an epoch in its own directory, which holds:
+//! - the objects live at the last checkpoint of the epoch, in chunks of BCS serialized objects,
+//! - the certified checkpoints ending each epoch since genesis, so that the committees of the
+//!   epochs, and the last checkpoint itself, can be verified from the genesis committee,
+//! - a `MANIFEST` listing these files with their SHA3-256 digests, to check their integrity.
+//!
+//! The checkpoints don't commit to the objects: the objects restored are the ones of the node
+//! which wrote the snapshot, which must be trusted.
+//!
+//! The snapshot is written from a RocksDB checkpoint of the store taken at the last checkpoint of
+//! the epoch, so that it holds the objects of that checkpoint whatever is executed next.
+//!
+//! The history before the snapshot is not restored: the restored node serves the objects and
+//! transactions from the snapshot on, while state sync backfills the checkpoints before it with
+//! their transactions and effects from the peers. The backfilled transactions are not executed,
+//! so they are not indexed and their events are not stored.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use fastcrypto::encoding::{Encoding, Hex};
+use fastcrypto::hash::{HashFunction, Sha3_256};
+use rocksdb::checkpoint::Checkpoint;
+use serde::{Deserialize, Serialize};
+use sui_config::node::StateSnapshotConfig;
+use sui_types::base_types::ObjectRef;
+use sui_types::committee::{Committee, EpochId};
+use sui_types::error::{SuiError, SuiResult};
+use sui_types::messages_checkpoint::{
+    CertifiedCheckpointSummary, CheckpointSequenceNumber, VerifiedCheckpoint,
+};
+use sui_types::object::Object;
+use tracing::{info, warn};
+use typed_store::Map;
+
+use crate::authority::authority_store::ObjectKey;
+use crate::authority::authority_store_tables::AuthorityPerpetualTables;
+use crate::authority::AuthorityStore;
+use crate::checkpoints::{CheckpointStore, CheckpointWatermark};
+use crate::epoch::committee_store::CommitteeStore;
+
+#[cfg(test)]
+#[path = "unit_tests/state_snapshot_tests.rs"]
+mod state_snapshot_tests;
+
+pub const MANIFEST_FILE_NAME: &str = "MANIFEST";
+const CHECKPOINTS_FILE_NAME: &str = "checkpoints.bcs";
+const SNAPSHOT_DIR_PREFIX: &str = "epoch_";
+const OBJECTS_PER_FILE: usize = 100_000;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct StateSnapshotManifest {
+    /// The epoch ended by the checkpoint of the snapshot.
+    pub epoch: EpochId,
+    pub checkpoint: CheckpointSequenceNumber,
+    /// The hex encoded digest of the checkpoint.
+    pub checkpoint_digest: String,
+    pub checkpoints_file: StateSnapshotFile,
+    pub object_files: Vec<StateSnapshotFile>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct StateSnapshotFile {
+    pub name: String,
+    pub num_entries: u64,
+    /// The hex encoded SHA3-256 digest of the file.
+    pub digest: String,
+}
+
+impl StateSnapshotManifest {
+    /// The names of all the files of the snapshot, but the manifest.
+    pub fn file_names(&self) -> impl Iterator<Item = &str> {
+        std::iter::once(&self.checkpoints_file)
+            .chain(&self.object_files)
+            .map(|file| file.name.as_str())
+    }
+}
+
+fn io_error(path: &Path, error: impl std::fmt::Display) -> SuiError {
+    SuiError::GenericStorageError(format!("State snapshot file {}: {error}", path.display()))
+}
+
+fn invalid_snapshot(error: impl Into<String>) -> SuiError {
+    SuiError::GenericStorageError(format!("Invalid state snapshot: {}", error.into()))
+}
+
+fn digest(bytes: &[u8]) -> String {
+    let mut digest = Sha3_256::default();
+    digest.write_all(bytes).unwrap();
+    let hash: [u8; 32] = digest.finalize().into();
+    Hex::encode(hash)
+}
+
+fn write_file<T: Serialize>(
+    dir: &Path,
+    name: String,
+    entries: &[T],
+) -> SuiResult<StateSnapshotFile> {
+    let path = dir.join(&name);
+    let bytes = bcs::to_bytes(entries).map_err(|e| io_error(&path, e))?;
+    fs::write(&path, &bytes).map_err(|e| io_error(&path, e))?;
+    Ok(StateSnapshotFile {
+        name,
+        num_entries: entries.len() as u64,
+        digest: digest(&bytes),
+    })
+}
+
+fn read_file<T: for<'de> Deserialize<'de>>(
+    dir: &Path,
+    file: &StateSnapshotFile,
+) -> SuiResult<Vec<T>> {
+    let path = dir.join(&file.name);
+    let bytes = fs::read(&path).map_err(|e| io_error(&path, e))?;
+    if digest(&bytes) != file.digest {
+        return Err(invalid_snapshot(format!(
+            "{} has a wrong digest",
+            file.name
+        )));
+    }
+    let entries: Vec<T> = bcs::from_bytes(&bytes).map_err(|e| io_error(&path, e))?;
+    if entries.len() as u64 != file.num_entries {
+        return Err(invalid_snapshot(format!(
+            "{} has a wrong length",
+            file.name
+        )));
+    }
+    Ok(entries)
+}
+
+pub fn read_manifest(dir: &Path) -> SuiResult<StateSnapshotManifest> {
+    let path = dir.join(MANIFEST_FILE_NAME);
+    let bytes = fs::read(&path).map_err(|e| io_error(&path, e))?;
+    serde_json::from_slice(&bytes).map_err(|e| io_error(&path, e))
+}
+
+/// The perpetual tables of a store as of a RocksDB checkpoint, which hard links their files into
+/// a directory deleted once dropped. The tables hold the state of the store when the checkpoint
+/// was taken, whatever is written into the store afterwards.
+pub struct StateCheckpoint {
+    dir: PathBuf,
+}
+
+impl Drop for StateCheckpoint {
+    fn drop(&mut self) {
+        if let Err(e) = fs::remove_dir_all(&self.dir) {
+            warn!(
+                "Failed to remove the state checkpoint {}: {e}",
+                self.dir.display()
+            );
+        }
+    }
+}
+
+/// Writes the snapshots of the state of a node into the directory of its [`StateSnapshotConfig`].
+pub struct StateSnapshotWriter {
+    snapshot_dir: PathBuf,
+    num_latest_snapshots_to_retain: Option<usize>,
+}
+
+impl StateSnapshotWriter {
+    /// Returns a writer if the config has a snapshot directory.
+    pub fn new(config: &StateSnapshotConfig) -> Option<Self> {
+        Some(Self {
+            snapshot_dir: config.snapshot_dir.clone()?,
+            num_latest_snapshots_to_retain: config.num_latest_snapshots_to_retain,
+        })
+    }
+
+    /// Takes a checkpoint of the perpetual tables of `store` to write the snapshot of `epoch` from.
+    /// It must be taken once the last checkpoint of the epoch is executed, and before any
+    /// transaction of the next epoch is, for the snapshot to hold the objects live at the end of
+    /// the epoch.
+    pub fn checkpoint_state(
+        &self,
+        store: &AuthorityStore,
+        epoch: EpochId,
+    ) -> SuiResult<StateCheckpoint> {
+        let dir = self
+            .snapshot_dir
+            .join(format!(".{SNAPSHOT_DIR_PREFIX}{epoch}.db"));
+        if dir.exists() {
+            fs::remove_dir_all(&dir).map_err(|e| io_error(&dir, e))?;
+        }
+        fs::create_dir_all(&dir).map_err(|e| io_error(&dir, e))?;
+        let state = StateCheckpoint { dir };
+        let tables_dir = AuthorityPerpetualTables::path(&state.dir);
+        Checkpoint::new(&*store.perpetual_tables.objects.rocksdb)
+            .and_then(|checkpoint| checkpoint.create_checkpoint(&tables_dir))
+            .map_err(|e| io_error(&tables_dir, e))?;
+        Ok(state)
+    }
+
+    /// Writes the snapshot of the objects live in `state` into the directory of the epoch ended by
+    /// `checkpoint`, the last checkpoint executed when the state was checkpointed. The snapshot is
+    /// written into a temporary directory renamed once complete, so that the snapshot directories
+    /// are always complete.
+    pub fn write(
+        &self,
+        state: &StateCheckpoint,
+        checkpoint_store: &CheckpointStore,
+        checkpoint: &VerifiedCheckpoint,
+    ) -> SuiResult<PathBuf> {
+        if checkpoint.next_epoch_committee().is_none() {
+            return Err(invalid_snapshot("the checkpoint does not end its epoch"));
+        }
+        let epoch = checkpoint.epoch();
+        let dir = self
+            .snapshot_dir
+            .join(format!("{SNAPSHOT_DIR_PREFIX}{epoch}"));
+        if dir.exists() {
+            return Ok(dir);
+        }
+        let tmp_dir = self
+            .snapshot_dir
+            .join(format!(".{SNAPSHOT_DIR_PREFIX}{epoch}"));
+        if tmp_dir.exists() {
+            fs::remove_dir_all(&tmp_dir).map_err(|e| io_error(&tmp_dir, e))?;
+        }
+        fs::create_dir_all(&tmp_dir).map_err(|e| io_error(&tmp_dir, e))?;
+        let tables = AuthorityPerpetualTables::open_read_only_mode(&state.dir, None)?;
+
+        let mut checkpoints: Vec<_> = checkpoint_store
+            .get_end_of_epoch_checkpoints()?
+            .into_iter()
+            .filter(|c| c.sequence_number() < checkpoint.sequence_number())
+            .map(VerifiedCheckpoint::into_inner)
+            .collect();
+        checkpoints.push(checkpoint.inner().clone());
+        let checkpoints_file =
+            write_file(&tmp_dir, CHECKPOINTS_FILE_NAME.to_string(), &checkpoints)?;
+
+        let mut object_files = vec![];
+        let mut objects = vec![];
+        let mut write_object = |object_ref: ObjectRef| -> SuiResult {
+            if !object_ref.2.is_alive() {
+                return Ok(());
+            }
+            let key = ObjectKey(object_ref.0, object_ref.1);
+            let object = tables.objects.get(&key)?;
+            objects.push(object.ok_or(SuiError::ObjectNotFound {
+                object_id: object_ref.0,
+                version: Some(object_ref.1),
+            })?);
+            if objects.len() == OBJECTS_PER_FILE {
+                let name = format!("objects_{}.bcs", object_files.len());
+                object_files.push(write_file(&tmp_dir, name, &objects)?);
+                objects.clear();
+            }
+            Ok(())
+        };
+        // The parent entries are ordered by object and version, the last one of an object being
+        // its latest version, or the one it has been deleted or wrapped at.
+        let mut latest: Option<ObjectRef> = None;
+        for object_ref in tables.parent_sync.keys() {
+            if let Some(previous) = latest.filter(|previous| previous.0 != object_ref.0) {
+                write_object(previous)?;
+            }
+            latest = Some(object_ref);
+        }
+        if let Some(previous) = latest {
+            write_object(previous)?;
+        }
+        if !objects.is_empty() {
+            let name = format!("objects_{}.bcs", object_files.len());
+            object_files.push(write_file(&tmp_dir, name, &objects)?);
+        }
+
+        let manifest = StateSnapshotManifest {
+            epoch,
+            checkpoint: checkpoint.sequence_number(),
+            checkpoint_digest: Hex::encode(checkpoint.digest()),
+            checkpoints_file,
+            object_files,
+        };
+        let manifest_path = tmp_dir.join(MANIFEST_FILE_NAME);
+        let bytes =
+            serde_json::to_vec_pretty(&manifest).map_err(|e| io_error(&manifest_path, e))?;
+        fs::write(&manifest_path, bytes).map_err(|e| io_error(&manifest_path, e))?;
+        fs::rename(&tmp_dir, &dir).map_err(|e| io_error(&dir, e))?;
+        info!(
+            epoch,
+            checkpoint = checkpoint.sequence_number(),
+            "Wrote the state snapshot to {}",
+            dir.display()
+        );
+
+        self.remove_old_snapshots()?;
+        Ok(dir)
+    }
+
+    fn remove_old_snapshots(&self) -> SuiResult {
+        let Some(num_snapshots) = self.num_latest_snapshots_to_retain else {
+            return Ok(());
+        };
+        let entries =
+            fs::read_dir(&self.snapshot_dir).map_err(|e| io_error(&self.snapshot_dir, e))?;
+        let mut snapshots: BTreeMap<EpochId, _> = BTreeMap::new();
+        for entry in entries {
+            let path = entry.map_err(|e| io_error(&self.snapshot_dir, e))?.path();
+            let epoch = path.file_name().and_then(|name| {
+                name.to_str()?
+                    .strip_prefix(SNAPSHOT_DIR_PREFIX)?
+                    .parse()
+                    .ok()
+            });
+            if let Some(epoch) = epoch {
+                snapshots.insert(epoch, path);
+            }
+        }
+        let num_removed = snapshots.len().saturating_sub(num_snapshots);
+        for (_, path) in snapshots.into_iter().take(num_removed) {
+            fs::remove_dir_all(&path).map_err(|e| io_error(&path, e))?;
+        }
+        Ok(())
+    }
+}
+
+/// Restores the snapshot in `snapshot_dir` into an empty store at `store_path`, after checking
+/// the integrity of its files and verifying its checkpoints from the genesis committee.
+///
+/// The committees of the epochs since genesis are inserted into `committee_store`, and the
+/// checkpoint of the snapshot is set as the highest synced and executed one in
+/// `checkpoint_store`, so that the node syncs and executes the checkpoints from the next one. If
+/// `backfill_history`, it is also set as the lowest backfilled one, for state sync to backfill the
+/// checkpoints before it.
+pub async fn restore(
+    snapshot_dir: &Path,
+    store_path: &Path,
+    committee_store: &CommitteeStore,
+    checkpoint_store: &CheckpointStore,
+    backfill_history: bool,
+) -> SuiResult<AuthorityStore> {
+    let manifest = read_manifest(snapshot_dir)?;
+    info!(
+        epoch = manifest.epoch,
+        checkpoint = manifest.checkpoint,
+        "Restoring the state snapshot from {}",
+        snapshot_dir.display()
+    );
+
+    // Each checkpoint ending an epoch is certified by the committee the previous one elected.
+    let checkpoints: Vec<CertifiedCheckpointSummary> =
+        read_file(snapshot_dir, &manifest.checkpoints_file)?;
+    let genesis_committee = committee_store
+        .get_committee(&0)?
+        .ok_or_else(|| invalid_snapshot("the genesis committee is unknown"))?;
+    let mut committees = vec![];
+    let mut verified_checkpoints = vec![];
+    let mut committee = genesis_committee;
+    for checkpoint in checkpoints {
+        if checkpoint.epoch() != committee.epoch {
+            return Err(invalid_snapshot("the checkpoints don't follow the epochs"));
+        }
+        let checkpoint = VerifiedCheckpoint::new(checkpoint, &committee).map_err(|(_, e)| e)?;
+        let next_committee = checkpoint
+            .next_epoch_committee()
+            .ok_or_else(|| invalid_snapshot("a checkpoint does not end its epoch"))?;
+        committee = Committee::new(
+            committee.epoch + 1,
+            next_committee.iter().cloned().collect(),
+        )?;
+        committees.push(committee.clone());
+        verified_checkpoints.push(checkpoint);
+    }
+    let Some(last_checkpoint) = verified_checkpoints.last().cloned() else {
+        return Err(invalid_snapshot("the snapshot has no checkpoint"));
+    };
+    if last_checkpoint.epoch() != manifest.epoch
+        || last_checkpoint.sequence_number() != manifest.checkpoint
+        || Hex::encode(last_checkpoint.digest()) != manifest.checkpoint_digest
+    {
+        return Err(invalid_snapshot(
+            "the manifest does not match its checkpoint",
+        ));
+    }
+
+    let store = AuthorityStore::open_without_genesis(store_path, None, &committee).await?;
+    if !store.database_is_empty()? {
+        return Err(invalid_snapshot("the store to restore it in is not empty"));
+    }
+    for file in &manifest.object_files {
+        let objects: Vec<Object> = read_file(snapshot_dir, file)?;
+        store
+            .bulk_object_insert(&objects.iter().collect::<Vec<_>>())
+            .await?;
+    }
+    if store
+        .get_sui_system_state_object()?
+        .get_current_epoch_committee()
+        .committee
+        != committee
+    {
+        return Err(invalid_snapshot("the objects don't match the checkpoints"));
+    }
+
+    for committee in &committees {
+        if committee_store.get_committee(&committee.epoch)?.is_none() {
+            committee_store.insert_new_committee(committee)?;
+        }
+    }
+    for checkpoint in verified_checkpoints {
+        checkpoint_store.insert_verified_checkpoint(checkpoint)?;
+    }
+    // There is nothing to prune before the snapshot.
+    for watermark in [
+        CheckpointWatermark::HighestPrunedObjects,
+        CheckpointWatermark::HighestPrunedTransactions,
+    ] {
+        checkpoint_store.update_highest_pruned_checkpoint(watermark, &last_checkpoint)?;
+    }
+    checkpoint_store.update_highest_synced_checkpoint(&last_checkpoint)?;
+    checkpoint_store.update_highest_executed_checkpoint(&last_checkpoint)?;
+    if backfill_history {
+        checkpoint_store.update_lowest_backfilled_checkpoint(&last_checkpoint)?;
+    }
+    info!(
+        epoch = manifest.epoch,
+        checkpoint = manifest.checkpoint,
+        "Restored the state snapshot"
+    );
+    Ok(store)
+}
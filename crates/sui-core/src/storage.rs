@@ -70,6 +70,10 @@ impl ReadStore for RocksDbStore {
         self.checkpoint_store.get_highest_synced_checkpoint()
     }
 
+    fn get_lowest_backfilled_checkpoint(&self) -> Result<Option<VerifiedCheckpoint>, Self::Error> {
+        self.checkpoint_store.get_lowest_backfilled_checkpoint()
+    }
+
     fn get_checkpoint_contents(
         &self,
         digest: &CheckpointContentsDigest,
@@ -146,6 +150,27 @@ impl WriteStore for RocksDbStore {
         self.checkpoint_store.insert_checkpoint_contents(contents)
     }
 
+    fn update_lowest_backfilled_checkpoint(
+        &self,
+        checkpoint: &VerifiedCheckpoint,
+    ) -> Result<(), Self::Error> {
+        // The backfilled transactions are not executed, so their effects are found from the
+        // contents of their checkpoint to be served.
+        if let Some(contents) = self
+            .checkpoint_store
+            .get_checkpoint_contents(&checkpoint.content_digest())?
+        {
+            let tables = &self.authority_store.perpetual_tables;
+            tables.backfilled_effects.multi_insert(
+                contents
+                    .iter()
+                    .map(|digests| (digests.transaction, digests.effects)),
+            )?;
+        }
+        self.checkpoint_store
+            .update_lowest_backfilled_checkpoint(checkpoint)
+    }
+
     fn insert_committee(&self, new_committee: Committee) -> Result<(), Self::Error> {
         self.committee_store
             .insert_new_committee(&new_committee)
@@ -49,17 +49,20 @@ async fn get_gas_status(
     Ok(gas_status)
 }
 
+/// `gas_owner` is the address the gas object must belong to, the sponsor of a sponsored
+/// transaction and its sender otherwise.
 #[instrument(level = "trace", skip_all)]
 pub async fn check_transaction_input(
     store: &AuthorityStore,
     transaction: &TransactionData,
+    gas_owner: SuiAddress,
 ) -> SuiResult<(SuiGasStatus<'static>, InputObjects)> {
     transaction.validity_check()?;
     transaction.kind.validity_check()?;
     let gas_status = get_gas_status(store, transaction).await?;
     let input_objects = transaction.input_objects()?;
     let objects = store.check_input_objects(&input_objects)?;
-    let input_objects = check_objects(transaction, input_objects, objects).await?;
+    let input_objects = check_objects(transaction, gas_owner, input_objects, objects).await?;
     Ok((gas_status, input_objects))
 }
 
@@ -79,6 +82,7 @@ pub async fn check_certificate_input(
     };
     let input_objects = check_objects(
         &cert.data().intent_message.value,
+        cert.gas_owner(),
         input_object_kinds,
         input_object_data,
     )
@@ -162,6 +166,7 @@ async fn check_gas(
 #[instrument(level = "trace", skip_all)]
 async fn check_objects(
     transaction: &TransactionData,
+    gas_owner: SuiAddress,
     input_objects: Vec<InputObjectKind>,
     objects: Vec<Object>,
 ) -> Result<InputObjects, SuiError> {
@@ -200,13 +205,21 @@ async fn check_objects(
         })
         .collect();
 
+    let gas_object_id = transaction.gas_payment_object_ref().0;
     for (object_kind, object) in input_objects.into_iter().zip(objects) {
         if transfer_object_ids.contains(&object.id()) {
             object.ensure_public_transfer_eligible()?;
         }
+        // The gas object of a sponsored transaction belongs to the sponsor, and appears only
+        // once in the inputs as it is mutable.
+        let owner = if object.id() == gas_object_id {
+            gas_owner
+        } else {
+            transaction.signer()
+        };
         // Check if the object contents match the type of lock we need for
         // this object.
-        match check_one_object(&transaction.signer(), object_kind, &object) {
+        match check_one_object(&owner, object_kind, &object) {
             Ok(()) => all_objects.push((object_kind, object)),
             Err(e) => {
                 errors.push(e);
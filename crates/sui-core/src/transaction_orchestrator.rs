@@ -13,7 +13,9 @@ use std::time::Duration;
 use crate::authority::AuthorityState;
 use crate::authority_aggregator::AuthorityAggregator;
 use crate::authority_client::AuthorityAPI;
-use crate::quorum_driver::{QuorumDriver, QuorumDriverHandler, QuorumDriverMetrics};
+use crate::quorum_driver::{
+    QuorumDriver, QuorumDriverHandler, QuorumDriverMetrics, MAX_RETRY_ATTEMPTS, MAX_RETRY_BACKOFF,
+};
 use mysten_metrics::spawn_monitored_task;
 use prometheus::{
     register_int_counter_vec_with_registry, register_int_counter_with_registry,
@@ -23,7 +25,7 @@ use sui_types::error::{SuiError, SuiResult};
 use sui_types::messages::{
     CertifiedTransactionEffects, ExecuteTransactionRequest, ExecuteTransactionRequestType,
     ExecuteTransactionResponse, QuorumDriverRequest, QuorumDriverRequestType, QuorumDriverResponse,
-    VerifiedCertificate, VerifiedCertifiedTransactionEffects,
+    QuorumDriverRetryPolicy, VerifiedCertificate, VerifiedCertifiedTransactionEffects,
 };
 use tap::TapFallible;
 use tokio::sync::broadcast::error::RecvError;
@@ -42,6 +44,8 @@ pub struct TransactiondOrchestrator<A> {
     validator_state: Arc<AuthorityState>,
     _local_executor_handle: JoinHandle<()>,
     metrics: Arc<TransactionOrchestratorMetrics>,
+    default_retry_policy: QuorumDriverRetryPolicy,
+    max_retry_policy: QuorumDriverRetryPolicy,
 }
 
 impl<A> TransactiondOrchestrator<A>
@@ -76,13 +80,45 @@ where
             validator_state,
             _local_executor_handle,
             metrics,
+            default_retry_policy: QuorumDriverRetryPolicy::default(),
+            max_retry_policy: QuorumDriverRetryPolicy {
+                max_attempts: MAX_RETRY_ATTEMPTS,
+                attempt_timeout_ms: None,
+                initial_backoff_ms: MAX_RETRY_BACKOFF.as_millis() as u64,
+                max_backoff_ms: MAX_RETRY_BACKOFF.as_millis() as u64,
+                retry_conflicting_transactions: true,
+            },
         }
     }
 
-    #[instrument(name = "tx_orchestrator_execute_transaction", level = "debug", skip_all, fields(request_type = ?request.request_type), err)]
+    /// Set the retry policy of the requests that don't come with their own.
+    pub fn with_default_retry_policy(mut self, retry_policy: QuorumDriverRetryPolicy) -> Self {
+        self.default_retry_policy = retry_policy;
+        self
+    }
+
+    /// Set the most the requests can retry with their own retry policy, the limits of the
+    /// quorum driver by default.
+    pub fn with_max_retry_policy(mut self, retry_policy: QuorumDriverRetryPolicy) -> Self {
+        self.max_retry_policy = retry_policy;
+        self
+    }
+
     pub async fn execute_transaction(
         &self,
         request: ExecuteTransactionRequest,
+    ) -> SuiResult<ExecuteTransactionResponse> {
+        self.execute_transaction_with_retry_policy(request, None)
+            .await
+    }
+
+    /// Execute the transaction with `retry_policy` clamped to the maximum retry policy of the
+    /// orchestrator, or with its default one if unset.
+    #[instrument(name = "tx_orchestrator_execute_transaction", level = "debug", skip_all, fields(request_type = ?request.request_type), err)]
+    pub async fn execute_transaction_with_retry_policy(
+        &self,
+        request: ExecuteTransactionRequest,
+        retry_policy: Option<QuorumDriverRetryPolicy>,
     ) -> SuiResult<ExecuteTransactionResponse> {
         let (_in_flight_metrics_guard, good_response_metrics) =
             self.update_metrics(&request.request_type);
@@ -105,12 +141,19 @@ where
                 QuorumDriverRequestType::WaitForEffectsCert
             }
         };
+        let retry_policy = match retry_policy {
+            Some(retry_policy) => retry_policy.clamp_to(&self.max_retry_policy),
+            None => self.default_retry_policy.clone(),
+        };
         let execution_result = self
             .quorum_driver
-            .execute_transaction(QuorumDriverRequest {
-                transaction,
-                request_type,
-            })
+            .execute_transaction_with_retry_policy(
+                QuorumDriverRequest {
+                    transaction,
+                    request_type,
+                },
+                &retry_policy,
+            )
             .await
             .tap_err(|err| {
                 debug!(
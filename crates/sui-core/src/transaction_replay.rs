@@ -0,0 +1,371 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Re-executes the transactions of the checkpoints of a node's store, read-only, against the
+//! versions of their input objects recorded in the store, and compares the effects of every
+//! transaction with the ones the node committed, e.g. to debug a divergence of execution.
+
+use std::collections::BTreeSet;
+use std::fmt::Debug;
+use std::ops::RangeInclusive;
+use std::path::Path;
+use std::sync::Arc;
+
+use move_vm_runtime::{move_vm::MoveVM, native_functions::NativeFunctionTable};
+use sui_adapter::{adapter, execution_mode};
+use sui_types::base_types::{ExecutionDigests, ObjectDigest, ObjectID, ObjectRef, SequenceNumber};
+use sui_types::committee::EpochId;
+use sui_types::error::{SuiError, SuiResult};
+use sui_types::gas::{self, SuiGasStatus};
+use sui_types::messages::{InputObjectKind, InputObjects, TransactionEffects};
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
+use sui_types::object::{Object, Owner};
+use sui_types::storage::{BackingPackageStore, ChildObjectResolver, ParentSync};
+use sui_types::sui_system_state::SuiSystemState;
+use sui_types::temporary_store::TemporaryStore;
+use sui_types::{MOVE_STDLIB_ADDRESS, SUI_FRAMEWORK_ADDRESS, SUI_SYSTEM_STATE_OBJECT_ID};
+
+use crate::authority::authority_store::ObjectKey;
+use crate::authority::authority_store_tables::{
+    AuthorityPerpetualTables, AuthorityPerpetualTablesReadOnly,
+};
+use crate::checkpoints::{CheckpointStore, CheckpointStoreReadOnly};
+use crate::execution_engine;
+
+#[cfg(test)]
+#[path = "unit_tests/transaction_replay_tests.rs"]
+mod transaction_replay_tests;
+
+/// A field of the effects of a replayed transaction that differs from the committed one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EffectsDiff {
+    pub field: &'static str,
+    pub committed: String,
+    pub replayed: String,
+}
+
+#[derive(Debug)]
+pub struct ReplayedTransaction {
+    pub checkpoint: CheckpointSequenceNumber,
+    pub digests: ExecutionDigests,
+    /// Empty if the replayed effects are the committed ones.
+    pub diffs: Vec<EffectsDiff>,
+}
+
+pub struct TransactionReplayer {
+    tables: Arc<AuthorityPerpetualTablesReadOnly>,
+    checkpoints: CheckpointStoreReadOnly,
+    move_vm: Arc<MoveVM>,
+    native_functions: NativeFunctionTable,
+}
+
+impl TransactionReplayer {
+    /// Opens the store of the node with the database at `db_path` read-only, which the node can
+    /// keep writing to.
+    pub fn open(db_path: &Path) -> SuiResult<Self> {
+        let store_path = db_path.join("store");
+        let checkpoints_path = db_path.join("checkpoints");
+        for path in [&store_path, &checkpoints_path] {
+            if !path.exists() {
+                return Err(SuiError::GenericStorageError(format!(
+                    "No node store at {}",
+                    path.display()
+                )));
+            }
+        }
+        let native_functions =
+            sui_framework::natives::all_natives(MOVE_STDLIB_ADDRESS, SUI_FRAMEWORK_ADDRESS);
+        let move_vm = Arc::new(adapter::new_move_vm(native_functions.clone())?);
+        Ok(Self {
+            tables: Arc::new(AuthorityPerpetualTables::open_readonly(&store_path)),
+            checkpoints: CheckpointStore::get_read_only_handle(checkpoints_path, None, None),
+            move_vm,
+            native_functions,
+        })
+    }
+
+    /// Replays the transactions of the checkpoints in `range`, in order.
+    pub fn replay_checkpoints(
+        &self,
+        range: RangeInclusive<CheckpointSequenceNumber>,
+    ) -> SuiResult<Vec<ReplayedTransaction>> {
+        let mut replayed = Vec::new();
+        for sequence_number in range {
+            replayed.extend(self.replay_checkpoint(sequence_number)?);
+        }
+        Ok(replayed)
+    }
+
+    /// Replays the transactions of the checkpoint `sequence_number`, in the order of its contents.
+    pub fn replay_checkpoint(
+        &self,
+        sequence_number: CheckpointSequenceNumber,
+    ) -> SuiResult<Vec<ReplayedTransaction>> {
+        let checkpoint = self
+            .checkpoints
+            .certified_checkpoints
+            .get(&sequence_number)?
+            .ok_or_else(|| {
+                SuiError::GenericStorageError(format!("Checkpoint {sequence_number} not found"))
+            })?;
+        let contents = self
+            .checkpoints
+            .checkpoint_content
+            .get(&checkpoint.summary.content_digest)?
+            .ok_or_else(|| {
+                SuiError::GenericStorageError(format!(
+                    "Contents of checkpoint {sequence_number} not found"
+                ))
+            })?;
+        contents
+            .iter()
+            .map(|digests| {
+                Ok(ReplayedTransaction {
+                    checkpoint: sequence_number,
+                    digests: *digests,
+                    diffs: self.replay_transaction(digests, checkpoint.summary.epoch)?,
+                })
+            })
+            .collect()
+    }
+
+    /// Re-executes the transaction of `digests` in `epoch`, returning how its effects differ
+    /// from the committed ones.
+    pub fn replay_transaction(
+        &self,
+        digests: &ExecutionDigests,
+        epoch: EpochId,
+    ) -> SuiResult<Vec<EffectsDiff>> {
+        let certificate = match self.tables.certificates.get(&digests.transaction)? {
+            Some(certificate) => certificate,
+            None => self
+                .tables
+                .synced_transactions
+                .get(&digests.transaction)?
+                .ok_or(SuiError::TransactionNotFound {
+                    digest: digests.transaction,
+                })?,
+        }
+        .into_inner();
+        let committed = self.tables.effects.get(&digests.effects)?.ok_or_else(|| {
+            SuiError::GenericStorageError(format!(
+                "Effects of transaction {} not found",
+                digests.transaction
+            ))
+        })?;
+        let tx_data = certificate.data().intent_message.value.clone();
+
+        // The versions of the shared objects were assigned by consensus, and are only recorded
+        // in the effects.
+        let mut input_objects = Vec::new();
+        for kind in tx_data.input_objects()? {
+            let object = match kind {
+                InputObjectKind::MovePackage(id) => self.get_latest_object(&id)?,
+                InputObjectKind::ImmOrOwnedMoveObject((id, version, _)) => {
+                    self.get_object(&id, version)?
+                }
+                InputObjectKind::SharedMoveObject { id, .. } => {
+                    match committed.shared_objects.iter().find(|(i, _, _)| *i == id) {
+                        Some((_, version, _)) => self.get_object(&id, *version)?,
+                        None => None,
+                    }
+                }
+            };
+            let object = object.ok_or_else(|| {
+                SuiError::GenericStorageError(format!(
+                    "Input object {} of transaction {} not found, it may have been pruned",
+                    kind.object_id(),
+                    digests.transaction
+                ))
+            })?;
+            input_objects.push((kind, object));
+        }
+        let input_objects = InputObjects::new(input_objects);
+
+        // The balance of the gas was checked when the transaction was executed.
+        let gas_status = if tx_data.kind.is_system_tx() {
+            SuiGasStatus::new_unmetered()
+        } else {
+            // The storage gas price is set at genesis and never changes, so the latest one is
+            // the one the transaction paid.
+            let storage_gas_price = self.get_sui_system_state()?.parameters.storage_gas_price;
+            let mut gas_status =
+                gas::start_gas_metering(tx_data.gas_budget, tx_data.gas_price, storage_gas_price)?;
+            if tx_data.contains_shared_object() {
+                gas_status.charge_consensus()?;
+            }
+            gas_status
+        };
+
+        let store = ReplayStore {
+            tables: self.tables.clone(),
+            lamport_version: lamport_version(&committed),
+        };
+        let shared_object_refs = input_objects.filter_shared_objects();
+        let transaction_dependencies = input_objects.transaction_dependencies();
+        let temporary_store = TemporaryStore::new(store, input_objects, digests.transaction);
+        let (_, replayed, _) =
+            execution_engine::execute_transaction_to_effects::<execution_mode::Normal, _>(
+                shared_object_refs,
+                temporary_store,
+                tx_data,
+                digests.transaction,
+                transaction_dependencies,
+                &self.move_vm,
+                &self.native_functions,
+                gas_status,
+                epoch,
+            );
+        Ok(diff_effects(&committed, &replayed))
+    }
+
+    fn get_object(&self, id: &ObjectID, version: SequenceNumber) -> SuiResult<Option<Object>> {
+        Ok(self.tables.objects.get(&ObjectKey(*id, version))?)
+    }
+
+    fn get_latest_object(&self, id: &ObjectID) -> SuiResult<Option<Object>> {
+        find_object(&self.tables, id, SequenceNumber::MAX)
+    }
+
+    fn get_sui_system_state(&self) -> SuiResult<SuiSystemState> {
+        let object = self
+            .get_latest_object(&SUI_SYSTEM_STATE_OBJECT_ID)?
+            .ok_or_else(|| {
+                SuiError::GenericStorageError("Sui System State object not found".to_string())
+            })?;
+        let move_object = object.data.try_as_move().ok_or_else(|| {
+            SuiError::GenericStorageError("Sui System State object is not a Move object".into())
+        })?;
+        bcs::from_bytes(move_object.contents())
+            .map_err(|e| SuiError::GenericStorageError(e.to_string()))
+    }
+}
+
+/// The version of all the objects written by the transaction of `effects`.
+fn lamport_version(effects: &TransactionEffects) -> SequenceNumber {
+    effects
+        .all_mutated()
+        .map(|(object_ref, _, _)| object_ref.1)
+        .chain(effects.deleted.iter().map(|object_ref| object_ref.1))
+        .chain(effects.wrapped.iter().map(|object_ref| object_ref.1))
+        .max()
+        .unwrap_or(SequenceNumber::MAX)
+}
+
+/// Returns the latest version of the object `id` up to `version`.
+fn find_object(
+    tables: &AuthorityPerpetualTablesReadOnly,
+    id: &ObjectID,
+    version: SequenceNumber,
+) -> SuiResult<Option<Object>> {
+    Ok(tables
+        .objects
+        .iter()
+        .skip_prior_to(&ObjectKey(*id, version))?
+        .next()
+        .and_then(|(ObjectKey(object_id, _), object)| (object_id == *id).then_some(object)))
+}
+
+/// The objects the transaction reads during its execution, like the packages and the child
+/// objects, as they were when it was executed: the transaction had a greater version than any
+/// object it read, and wrote every object it read with a greater version after its execution.
+struct ReplayStore {
+    tables: Arc<AuthorityPerpetualTablesReadOnly>,
+    lamport_version: SequenceNumber,
+}
+
+impl ReplayStore {
+    fn prior_version(&self) -> SequenceNumber {
+        SequenceNumber::from_u64(self.lamport_version.value().saturating_sub(1))
+    }
+}
+
+impl BackingPackageStore for ReplayStore {
+    fn get_package(&self, package_id: &ObjectID) -> SuiResult<Option<Object>> {
+        let package = find_object(&self.tables, package_id, SequenceNumber::MAX)?;
+        if let Some(object) = &package {
+            if !object.is_package() {
+                return Err(SuiError::BadObjectType {
+                    error: format!("Package expected, Move object found: {package_id}"),
+                });
+            }
+        }
+        Ok(package)
+    }
+}
+
+impl ChildObjectResolver for ReplayStore {
+    fn read_child_object(&self, parent: &ObjectID, child: &ObjectID) -> SuiResult<Option<Object>> {
+        let parent_entry = self.get_latest_parent_entry_ref(*child)?;
+        if !matches!(parent_entry, Some(object_ref) if object_ref.2.is_alive()) {
+            return Ok(None);
+        }
+        let child_object = match find_object(&self.tables, child, self.prior_version())? {
+            None => return Ok(None),
+            Some(object) => object,
+        };
+        if child_object.owner != Owner::ObjectOwner((*parent).into()) {
+            return Err(SuiError::InvalidChildObjectAccess {
+                object: *child,
+                given_parent: *parent,
+                actual_owner: child_object.owner,
+            });
+        }
+        Ok(Some(child_object))
+    }
+}
+
+impl ParentSync for ReplayStore {
+    fn get_latest_parent_entry_ref(&self, object_id: ObjectID) -> SuiResult<Option<ObjectRef>> {
+        Ok(self
+            .tables
+            .parent_sync
+            .iter()
+            .skip_prior_to(&(object_id, self.prior_version(), ObjectDigest::MAX))?
+            .next()
+            .and_then(|(object_ref, _)| (object_ref.0 == object_id).then_some(object_ref)))
+    }
+}
+
+fn diff_effects(committed: &TransactionEffects, replayed: &TransactionEffects) -> Vec<EffectsDiff> {
+    let mut diffs = Vec::new();
+    let mut diff = |field, in_store: &dyn Debug, in_replay: &dyn Debug| {
+        let (committed, replayed) = (format!("{in_store:?}"), format!("{in_replay:?}"));
+        if committed != replayed {
+            diffs.push(EffectsDiff {
+                field,
+                committed,
+                replayed,
+            });
+        }
+    };
+    diff("status", &committed.status, &replayed.status);
+    diff("gas_used", &committed.gas_used, &replayed.gas_used);
+    diff(
+        "modified_at_versions",
+        &committed.modified_at_versions,
+        &replayed.modified_at_versions,
+    );
+    diff(
+        "shared_objects",
+        &committed.shared_objects,
+        &replayed.shared_objects,
+    );
+    diff("created", &committed.created, &replayed.created);
+    diff("mutated", &committed.mutated, &replayed.mutated);
+    diff("unwrapped", &committed.unwrapped, &replayed.unwrapped);
+    diff("deleted", &committed.deleted, &replayed.deleted);
+    diff("wrapped", &committed.wrapped, &replayed.wrapped);
+    diff("gas_object", &committed.gas_object, &replayed.gas_object);
+    diff("events", &committed.events, &replayed.events);
+    diff(
+        "dependencies",
+        &sorted(&committed.dependencies),
+        &sorted(&replayed.dependencies),
+    );
+    diffs
+}
+
+fn sorted<T: Ord + Clone>(items: &[T]) -> BTreeSet<T> {
+    items.iter().cloned().collect()
+}
@@ -0,0 +1,223 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use crate::authority::AuthorityState;
+use crate::test_utils::create_fake_cert_and_effect_digest;
+use fastcrypto::traits::KeyPair;
+use signature::Signer;
+use std::collections::BTreeMap;
+use sui_types::base_types::{ExecutionDigests, ObjectID, SequenceNumber, SuiAddress};
+use sui_types::committee::{Committee, EpochId};
+use sui_types::crypto::{
+    get_key_pair, AuthorityKeyPair, AuthoritySignInfo, AuthoritySignature,
+    AuthorityWeakQuorumSignInfo, SuiAuthoritySignature,
+};
+use sui_types::message_envelope::Message;
+use sui_types::messages::{SignedTransactionEffects, TransactionEffects, VerifiedCertificate};
+use sui_types::messages_checkpoint::{
+    CertifiedCheckpointSummary, CheckpointContents, CheckpointSummary,
+};
+use sui_types::object::Object;
+use tempfile::tempdir;
+
+fn checkpoint(
+    key: &AuthorityKeyPair,
+    committee: &Committee,
+    epoch: EpochId,
+    sequence_number: u64,
+    contents: &CheckpointContents,
+) -> VerifiedCheckpoint {
+    let summary = CheckpointSummary {
+        epoch,
+        sequence_number,
+        content_digest: contents.digest(),
+        previous_digest: None,
+        epoch_rolling_gas_cost_summary: Default::default(),
+        next_epoch_committee: None,
+    };
+    let signature = AuthoritySignInfo {
+        epoch,
+        authority: key.public().into(),
+        signature: AuthoritySignature::new(&summary, epoch, key),
+    };
+    VerifiedCheckpoint::new_unchecked(CertifiedCheckpointSummary {
+        summary,
+        auth_signature: AuthorityWeakQuorumSignInfo::new_from_auth_sign_infos(
+            vec![signature],
+            committee,
+        )
+        .unwrap(),
+    })
+}
+
+#[tokio::test]
+async fn test_prune_objects_and_transactions() {
+    let (_, key): (_, AuthorityKeyPair) = get_key_pair();
+    let committee = Committee::new(0, BTreeMap::from([(key.public().into(), 1)])).unwrap();
+    let state = AuthorityState::new_for_testing(committee.clone(), &key, None, None).await;
+    let store = state.db();
+    let tables = &store.perpetual_tables;
+    let checkpoint_dir = tempdir().unwrap();
+    let checkpoint_store = CheckpointStore::new(checkpoint_dir.path());
+    let metrics = AuthorityStorePrunerMetrics::new(&Registry::new());
+
+    // Versions 1 to 3 of an object, the third one written by a transaction modifying the second.
+    let object_id = ObjectID::random();
+    for version in 1..=3 {
+        let object = Object::with_id_owner_version_for_testing(
+            object_id,
+            SequenceNumber::from(version),
+            SuiAddress::default(),
+        );
+        tables
+            .objects
+            .insert(&ObjectKey(object_id, object.version()), &object)
+            .unwrap();
+    }
+    let name = key.public().into();
+    let (_, cert) = create_fake_cert_and_effect_digest(
+        std::iter::once((
+            &name,
+            &key as &(dyn Signer<AuthoritySignature> + Send + Sync),
+        )),
+        &committee,
+    );
+    let cert = VerifiedCertificate::new_unchecked(cert);
+    let effects = TransactionEffects {
+        transaction_digest: *cert.digest(),
+        modified_at_versions: vec![(object_id, SequenceNumber::from(2))],
+        ..Default::default()
+    };
+    tables
+        .certificates
+        .insert(cert.digest(), cert.serializable_ref())
+        .unwrap();
+    tables.effects.insert(&effects.digest(), &effects).unwrap();
+    let executed_effects =
+        SignedTransactionEffects::new(state.epoch(), effects.clone(), &key, name);
+    tables
+        .executed_effects
+        .insert(cert.digest(), &executed_effects)
+        .unwrap();
+
+    // The transaction is indexed by its sender.
+    let indexes_dir = tempdir().unwrap();
+    let indexes = IndexStore::open_tables_read_write(indexes_dir.path().to_path_buf(), None, None);
+    indexes
+        .index_tx(
+            cert.sender_address(),
+            std::iter::empty(),
+            std::iter::empty(),
+            std::iter::empty(),
+            0,
+            cert.digest(),
+            0,
+        )
+        .unwrap();
+    let indexed = || {
+        indexes
+            .get_transactions_from_addr(cert.sender_address(), 0, None, false)
+            .unwrap()
+    };
+
+    // The transaction is in the checkpoint of epoch 0, followed by one of epoch 1.
+    let contents = CheckpointContents::new_with_causally_ordered_transactions(std::iter::once(
+        ExecutionDigests::new(effects.transaction_digest, effects.digest()),
+    ));
+    let first = checkpoint(&key, &committee, 0, 0, &contents);
+    checkpoint_store
+        .insert_checkpoint_contents(contents)
+        .unwrap();
+    checkpoint_store
+        .insert_verified_checkpoint(first.clone())
+        .unwrap();
+    let empty = CheckpointContents::new_with_causally_ordered_transactions(std::iter::empty());
+    let second = checkpoint(&key, &committee, 1, 1, &empty);
+    checkpoint_store.insert_checkpoint_contents(empty).unwrap();
+    checkpoint_store
+        .insert_verified_checkpoint(second.clone())
+        .unwrap();
+
+    let mut config = AuthorityStorePruningConfig {
+        num_latest_object_versions_to_retain: Some(2),
+        num_epochs_to_retain: Some(0),
+        pruning_period_secs: 1,
+    };
+    let versions = || {
+        tables
+            .objects
+            .keys()
+            .filter(|key| key.0 == object_id)
+            .map(|key| key.1.value())
+            .collect::<Vec<_>>()
+    };
+
+    // Nothing is pruned before the checkpoints are executed.
+    AuthorityStorePruner::prune(
+        tables,
+        &checkpoint_store,
+        Some(&indexes),
+        None,
+        &config,
+        &metrics,
+    )
+    .await
+    .unwrap();
+    assert_eq!(versions(), vec![1, 2, 3]);
+
+    checkpoint_store
+        .update_highest_executed_checkpoint(&first)
+        .unwrap();
+    AuthorityStorePruner::prune(
+        tables,
+        &checkpoint_store,
+        Some(&indexes),
+        None,
+        &config,
+        &metrics,
+    )
+    .await
+    .unwrap();
+    assert_eq!(versions(), vec![2, 3]);
+    // The transaction is in the current epoch.
+    assert!(tables.effects.contains_key(&effects.digest()).unwrap());
+    assert_eq!(indexed(), vec![*cert.digest()]);
+
+    checkpoint_store
+        .update_highest_executed_checkpoint(&second)
+        .unwrap();
+    AuthorityStorePruner::prune(
+        tables,
+        &checkpoint_store,
+        Some(&indexes),
+        None,
+        &config,
+        &metrics,
+    )
+    .await
+    .unwrap();
+    assert!(!tables.effects.contains_key(&effects.digest()).unwrap());
+    assert!(!tables.certificates.contains_key(cert.digest()).unwrap());
+    assert!(indexed().is_empty());
+    // The certificate is not executed again if delivered after its pruning.
+    assert!(tables.executed_effects.contains_key(cert.digest()).unwrap());
+    assert_eq!(metrics.num_pruned_objects.get(), 1);
+    assert_eq!(metrics.num_pruned_transactions.get(), 1);
+    assert_eq!(metrics.last_pruned_objects_checkpoint.get(), 1);
+    assert_eq!(metrics.last_pruned_transactions_checkpoint.get(), 0);
+
+    // The pruned checkpoints are not pruned again, even with a policy keeping fewer versions.
+    config.num_latest_object_versions_to_retain = Some(1);
+    AuthorityStorePruner::prune(
+        tables,
+        &checkpoint_store,
+        Some(&indexes),
+        None,
+        &config,
+        &metrics,
+    )
+    .await
+    .unwrap();
+    assert_eq!(versions(), vec![2, 3]);
+}
@@ -36,6 +36,7 @@ use sui_types::{
     base_types::dbg_addr,
     crypto::{get_key_pair, Signature},
     crypto::{AccountKeyPair, AuthorityKeyPair, KeypairTraits},
+    intent::{Intent, IntentMessage},
     messages::VerifiedTransaction,
     object::{Owner, GAS_VALUE_FOR_TESTING, OBJECT_START_VERSION},
     sui_system_state::SuiSystemState,
@@ -253,7 +254,7 @@ async fn test_handle_transfer_transaction_bad_signature() {
     bad_signature_transfer_transaction
         .data_mut_for_testing()
         .tx_signature =
-        Signature::new_secure(&transfer_transaction.data().intent_message, &unknown_key);
+        Signature::new_secure(&transfer_transaction.data().intent_message, &unknown_key).into();
 
     assert!(client
         .handle_transaction(bad_signature_transfer_transaction)
@@ -489,6 +490,97 @@ async fn test_handle_transfer_transaction_ok() {
     );
 }
 
+#[tokio::test]
+async fn test_handle_sponsored_transaction() {
+    let (sender, sender_key): (_, AccountKeyPair) = get_key_pair();
+    let (sponsor, sponsor_key): (_, AccountKeyPair) = get_key_pair();
+    let recipient = dbg_addr(2);
+    let object_id = ObjectID::random();
+    let gas_object_id = ObjectID::random();
+    let authority_state =
+        init_state_with_ids(vec![(sender, object_id), (sponsor, gas_object_id)]).await;
+    let object_ref = authority_state
+        .get_object(&object_id)
+        .await
+        .unwrap()
+        .unwrap()
+        .compute_object_reference();
+    let gas_object_ref = authority_state
+        .get_object(&gas_object_id)
+        .await
+        .unwrap()
+        .unwrap()
+        .compute_object_reference();
+    let data = TransactionData::new_transfer(recipient, object_ref, sender, gas_object_ref, 10000);
+    let intent_message = IntentMessage::new(Intent::default(), data.clone());
+    let signature = Signature::new_secure(&intent_message, &sender_key);
+    let sponsor_signature = Signature::new_secure(&intent_message, &sponsor_key);
+
+    // The gas object of the sponsor cannot pay for a transaction the sponsor did not sign.
+    let unsponsored_transaction = to_sender_signed_transaction(data.clone(), &sender_key);
+    assert!(matches!(
+        authority_state
+            .handle_transaction(unsponsored_transaction)
+            .await
+            .unwrap_err(),
+        SuiError::TransactionInputObjectsErrors { .. }
+    ));
+
+    // Nor can another address pretend to be the sponsor.
+    let (other, other_key): (_, AccountKeyPair) = get_key_pair();
+    let other_signature = Signature::new_secure(&intent_message, &other_key);
+    let transaction = Transaction::from_sponsored_data(
+        data.clone(),
+        Intent::default(),
+        signature.clone(),
+        GasSponsorship::new(sponsor, other_signature.clone()),
+    );
+    assert!(transaction.verify().is_err());
+    let transaction = Transaction::from_sponsored_data(
+        data.clone(),
+        Intent::default(),
+        signature.clone(),
+        GasSponsorship::new(other, other_signature),
+    )
+    .verify()
+    .unwrap();
+    assert!(authority_state
+        .handle_transaction(transaction)
+        .await
+        .is_err());
+
+    let transaction = Transaction::from_sponsored_data(
+        data,
+        Intent::default(),
+        signature,
+        GasSponsorship::new(sponsor, sponsor_signature),
+    )
+    .verify()
+    .unwrap();
+    authority_state
+        .handle_transaction(transaction.clone())
+        .await
+        .unwrap();
+    let certificate = init_certified_transaction(transaction, &authority_state);
+    let response = authority_state
+        .execute_certificate_internal(&certificate)
+        .await
+        .unwrap();
+    let effects = response.signed_effects.unwrap().into_data();
+    assert!(effects.status.is_ok());
+    // The sponsor paid for the gas of the transfer of the object of the sender.
+    assert_eq!(effects.gas_object.1, Owner::AddressOwner(sponsor));
+    assert_eq!(
+        authority_state
+            .get_object(&object_id)
+            .await
+            .unwrap()
+            .unwrap()
+            .owner,
+        Owner::AddressOwner(recipient)
+    );
+}
+
 #[tokio::test]
 async fn test_transfer_package() {
     let (sender, sender_key): (_, AccountKeyPair) = get_key_pair();
@@ -2484,7 +2576,8 @@ fn init_certified_transaction(
 
 #[cfg(test)]
 async fn send_consensus(authority: &AuthorityState, cert: &VerifiedCertificate) {
-    let transaction = SequencedConsensusTransaction::new_test(
+    let transaction = SequencedConsensusTransaction::new_test_from(
+        &authority.name,
         ConsensusTransaction::new_certificate_message(&authority.name, cert.clone().into_inner()),
     );
 
@@ -2498,7 +2591,8 @@ async fn send_consensus(authority: &AuthorityState, cert: &VerifiedCertificate)
 
 #[cfg(test)]
 async fn send_consensus_no_execution(authority: &AuthorityState, cert: &VerifiedCertificate) {
-    let transaction = SequencedConsensusTransaction::new_test(
+    let transaction = SequencedConsensusTransaction::new_test_from(
+        &authority.name,
         ConsensusTransaction::new_certificate_message(&authority.name, cert.clone().into_inner()),
     );
 
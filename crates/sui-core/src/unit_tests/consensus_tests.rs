@@ -10,7 +10,7 @@ use move_core_types::{account_address::AccountAddress, ident_str};
 use multiaddr::Multiaddr;
 use narwhal_types::Transactions;
 use narwhal_types::TransactionsServer;
-use narwhal_types::{Empty, TransactionProto};
+use narwhal_types::{Empty, SubmitTransactionResponse, TransactionProto};
 use sui_network::tonic;
 use sui_types::{
     base_types::{ObjectID, TransactionDigest},
@@ -167,9 +167,9 @@ impl Transactions for ConsensusMockServer {
     async fn submit_transaction(
         &self,
         request: tonic::Request<TransactionProto>,
-    ) -> Result<tonic::Response<Empty>, tonic::Status> {
+    ) -> Result<tonic::Response<SubmitTransactionResponse>, tonic::Status> {
         self.sender.send(request.into_inner()).await.unwrap();
-        Ok(tonic::Response::new(Empty {}))
+        Ok(tonic::Response::new(SubmitTransactionResponse::default()))
     }
     /// Submit a Transactions
     async fn submit_transaction_stream(
@@ -0,0 +1,119 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use crate::{
+    authority::authority_tests::init_state_with_objects,
+    consensus_adapter::consensus_tests::{test_certificates, test_gas_objects, test_shared_object},
+    test_utils::to_sender_signed_transaction,
+};
+use futures::future::join_all;
+use sui_types::base_types::{random_object_ref, SuiAddress};
+use sui_types::crypto::{get_key_pair, SuiKeyPair};
+use sui_types::messages::TransactionData;
+
+/// A transfer of SUI to the signer of `keypair`, signed by it.
+fn transfer(keypair: &SuiKeyPair, gas_budget: u64) -> Transaction {
+    let sender = SuiAddress::from(&keypair.public());
+    let data =
+        TransactionData::new_transfer_sui(sender, sender, None, random_object_ref(), gas_budget);
+    to_sender_signed_transaction(data, keypair).into_inner()
+}
+
+#[tokio::test]
+async fn test_verify_batch() {
+    let mut objects = test_gas_objects();
+    objects.push(test_shared_object());
+    let state = init_state_with_objects(objects).await;
+    let certificates = test_certificates(&state).await;
+    let verifier = SignatureVerifier::new_for_tests();
+
+    let transaction = certificates[0].clone().into_unsigned();
+    let (verified_transaction, verified_certificates) = futures::join!(
+        verifier.verify_transaction(transaction),
+        join_all(certificates.iter().map(|certificate| {
+            verifier.verify_certificate(certificate.clone(), state.epoch_store().clone())
+        }))
+    );
+
+    assert_eq!(
+        verified_transaction.unwrap().digest(),
+        certificates[0].digest()
+    );
+    for (verified, certificate) in verified_certificates.into_iter().zip(&certificates) {
+        assert_eq!(verified.unwrap().digest(), certificate.digest());
+    }
+    assert_eq!(verifier.metrics.certificate_batch_failures.get(), 0);
+    assert_eq!(
+        verifier.metrics.certificate_verifications_saved.get(),
+        certificates.len() as u64 - 1
+    );
+}
+
+#[tokio::test]
+async fn test_verify_batch_with_invalid_certificate() {
+    let mut objects = test_gas_objects();
+    objects.push(test_shared_object());
+    let state = init_state_with_objects(objects).await;
+    let mut certificates = test_certificates(&state).await;
+    let verifier = SignatureVerifier::new_for_tests();
+
+    // The authority signature of the first certificate does not sign the second transaction
+    let mut invalid = certificates[0].clone();
+    *invalid.data_mut_for_testing() = certificates[1].data().clone();
+    certificates.push(invalid);
+
+    let results = join_all(certificates.iter().map(|certificate| {
+        verifier.verify_certificate(certificate.clone(), state.epoch_store().clone())
+    }))
+    .await;
+
+    let (invalid, valid) = results.split_last().unwrap();
+    assert!(invalid.is_err());
+    assert!(valid.iter().all(|result| result.is_ok()));
+    assert_eq!(verifier.metrics.certificate_batch_failures.get(), 1);
+}
+
+#[tokio::test]
+async fn test_verify_batch_of_user_signatures() {
+    let keypairs = [
+        SuiKeyPair::Ed25519SuiKeyPair(get_key_pair().1),
+        SuiKeyPair::Secp256k1SuiKeyPair(get_key_pair().1),
+    ];
+    let mut transactions: Vec<_> = keypairs
+        .iter()
+        .flat_map(|keypair| (0..4).map(move |i| transfer(keypair, 1000 + i)))
+        .collect();
+    let verifier = SignatureVerifier::new_for_tests();
+
+    // The first transaction carries the signature of another transaction of its sender
+    let signature = transactions[1].data().tx_signature.clone();
+    transactions[0].data_mut_for_testing().tx_signature = signature;
+
+    let results = join_all(
+        transactions
+            .into_iter()
+            .map(|transaction| verifier.verify_transaction(transaction)),
+    )
+    .await;
+
+    let (invalid, valid) = results.split_first().unwrap();
+    assert!(invalid.is_err());
+    assert!(valid.iter().all(|result| result.is_ok()));
+    let metrics = &verifier.metrics;
+    let failures = |scheme: &str| {
+        metrics
+            .user_signature_batch_failures
+            .with_label_values(&[scheme])
+            .get()
+    };
+    let saved = |scheme: &str| {
+        metrics
+            .user_signature_verifications_saved
+            .with_label_values(&[scheme])
+            .get()
+    };
+    assert_eq!((failures("ed25519"), saved("ed25519")), (1, 0));
+    // The secp256k1 signatures are verified one by one.
+    assert_eq!((failures("secp256k1"), saved("secp256k1")), (0, 0));
+}
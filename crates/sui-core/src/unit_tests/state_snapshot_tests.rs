@@ -0,0 +1,163 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use crate::authority::AuthorityState;
+use fastcrypto::traits::KeyPair;
+use sui_types::base_types::{AuthorityName, ObjectID, SequenceNumber};
+use sui_types::committee::StakeUnit;
+use sui_types::crypto::{
+    get_key_pair, AuthorityKeyPair, AuthoritySignInfo, AuthoritySignature,
+    AuthorityWeakQuorumSignInfo, SuiAuthoritySignature,
+};
+use sui_types::messages_checkpoint::CheckpointSummary;
+use sui_types::sui_system_state::SuiSystemState;
+use sui_types::SUI_SYSTEM_STATE_OBJECT_ID;
+use tempfile::tempdir;
+
+fn end_of_epoch_checkpoint(
+    key: &AuthorityKeyPair,
+    committee: &Committee,
+    next_epoch_committee: Vec<(AuthorityName, StakeUnit)>,
+) -> VerifiedCheckpoint {
+    let summary = CheckpointSummary {
+        epoch: committee.epoch,
+        sequence_number: 0,
+        content_digest: Default::default(),
+        previous_digest: None,
+        epoch_rolling_gas_cost_summary: Default::default(),
+        next_epoch_committee: Some(next_epoch_committee),
+    };
+    let signature = AuthoritySignInfo {
+        epoch: committee.epoch,
+        authority: key.public().into(),
+        signature: AuthoritySignature::new(&summary, committee.epoch, key),
+    };
+    VerifiedCheckpoint::new_unchecked(CertifiedCheckpointSummary {
+        summary,
+        auth_signature: AuthorityWeakQuorumSignInfo::new_from_auth_sign_infos(
+            vec![signature],
+            committee,
+        )
+        .unwrap(),
+    })
+}
+
+#[tokio::test]
+async fn test_write_and_restore_snapshot() {
+    let (_, key): (_, AuthorityKeyPair) = get_key_pair();
+    let committee = Committee::new(0, BTreeMap::from([(key.public().into(), 1)])).unwrap();
+    let state = AuthorityState::new_for_testing(committee.clone(), &key, None, None).await;
+    let store = state.db();
+
+    // The last checkpoint of the epoch executed the change to the next one.
+    let mut system_state_object = store
+        .get_object(&SUI_SYSTEM_STATE_OBJECT_ID)
+        .unwrap()
+        .unwrap();
+    let mut system_state = store.get_sui_system_state_object().unwrap();
+    system_state.epoch += 1;
+    let move_object = system_state_object.data.try_as_move_mut().unwrap();
+    move_object
+        .update_contents(bcs::to_bytes(&system_state).unwrap())
+        .unwrap();
+    move_object.increment_version_to(SequenceNumber::from_u64(move_object.version().value() + 1));
+    store
+        .insert_object_direct(
+            system_state_object.compute_object_reference(),
+            &system_state_object,
+        )
+        .await
+        .unwrap();
+    let next_committee = system_state.get_current_epoch_committee().committee;
+    let checkpoint =
+        end_of_epoch_checkpoint(&key, &committee, next_committee.voting_rights.clone());
+
+    let snapshot_dir = tempdir().unwrap();
+    let config = StateSnapshotConfig {
+        snapshot_dir: Some(snapshot_dir.path().to_path_buf()),
+        num_latest_snapshots_to_retain: Some(1),
+        restore_from: None,
+    };
+    let checkpoint_dir = tempdir().unwrap();
+    let checkpoint_store = CheckpointStore::new(checkpoint_dir.path());
+    let writer = StateSnapshotWriter::new(&config).unwrap();
+    let state_checkpoint = writer.checkpoint_state(&store, 0).unwrap();
+    // The objects written after the state is checkpointed are not in the snapshot.
+    let later_object = Object::immutable_with_id_for_testing(ObjectID::random());
+    store
+        .insert_object_direct(later_object.compute_object_reference(), &later_object)
+        .await
+        .unwrap();
+    let dir = writer
+        .write(&state_checkpoint, &checkpoint_store, &checkpoint)
+        .unwrap();
+    drop(state_checkpoint);
+    assert!(!snapshot_dir.path().join(".epoch_0.db").exists());
+    assert_eq!(dir, snapshot_dir.path().join("epoch_0"));
+    let manifest = read_manifest(&dir).unwrap();
+    assert_eq!(manifest.epoch, 0);
+    assert_eq!(manifest.checkpoints_file.num_entries, 1);
+
+    let restore_dir = tempdir().unwrap();
+    let committee_store = CommitteeStore::new(restore_dir.path().join("epochs"), &committee, None);
+    let restored_checkpoint_store = CheckpointStore::new(&restore_dir.path().join("checkpoints"));
+    let restored = restore(
+        &dir,
+        &restore_dir.path().join("store"),
+        &committee_store,
+        &restored_checkpoint_store,
+        true,
+    )
+    .await
+    .unwrap();
+
+    let restored_system_state: SuiSystemState = restored.get_sui_system_state_object().unwrap();
+    assert_eq!(restored_system_state.epoch, 1);
+    assert_eq!(
+        restored.get_object(&SUI_SYSTEM_STATE_OBJECT_ID).unwrap(),
+        Some(system_state_object)
+    );
+    assert_eq!(restored.get_object(&later_object.id()).unwrap(), None);
+    // Only the latest version of the objects is restored.
+    assert_eq!(
+        restored
+            .perpetual_tables
+            .objects
+            .keys()
+            .filter(|key| key.0 == SUI_SYSTEM_STATE_OBJECT_ID)
+            .count(),
+        1
+    );
+    assert_eq!(committee_store.get_latest_committee(), next_committee);
+    assert_eq!(
+        restored_checkpoint_store
+            .get_highest_executed_checkpoint_seq_number()
+            .unwrap(),
+        Some(0)
+    );
+    // The checkpoints before the snapshot are backfilled by state sync.
+    assert_eq!(
+        restored_checkpoint_store
+            .get_lowest_backfilled_checkpoint()
+            .unwrap()
+            .map(|checkpoint| checkpoint.sequence_number()),
+        Some(0)
+    );
+
+    // A tampered snapshot is not restored.
+    let object_file = dir.join(&manifest.object_files[0].name);
+    let mut bytes = fs::read(&object_file).unwrap();
+    bytes.push(0);
+    fs::write(&object_file, bytes).unwrap();
+    let restore_dir = tempdir().unwrap();
+    assert!(restore(
+        &dir,
+        &restore_dir.path().join("store"),
+        &CommitteeStore::new(restore_dir.path().join("epochs"), &committee, None),
+        &CheckpointStore::new(&restore_dir.path().join("checkpoints")),
+        false,
+    )
+    .await
+    .is_err());
+}
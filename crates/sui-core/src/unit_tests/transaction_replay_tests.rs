@@ -0,0 +1,173 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use crate::authority::authority_tests::{init_transfer_transaction, send_and_confirm_transaction};
+use crate::authority::AuthorityState;
+use fastcrypto::traits::KeyPair;
+use std::collections::BTreeMap;
+use std::iter;
+use sui_types::base_types::{SuiAddress, TransactionDigest};
+use sui_types::committee::Committee;
+use sui_types::crypto::{get_key_pair, AccountKeyPair, AuthorityKeyPair};
+use sui_types::gas::GasCostSummary;
+use sui_types::message_envelope::Message;
+use sui_types::messages_checkpoint::{
+    CertifiedCheckpointSummary, CheckpointContents, SignedCheckpointSummary,
+};
+use tempfile::{tempdir, TempDir};
+
+struct TestNode {
+    dir: TempDir,
+    key: AuthorityKeyPair,
+    committee: Committee,
+    state: Arc<AuthorityState>,
+    sender: SuiAddress,
+    sender_key: AccountKeyPair,
+}
+
+impl TestNode {
+    async fn new() -> Self {
+        let (_, key): (_, AuthorityKeyPair) = get_key_pair();
+        let committee = Committee::new(0, BTreeMap::from([(key.public().into(), 1)])).unwrap();
+        let dir = tempdir().unwrap();
+        let state = AuthorityState::new_for_testing(
+            committee.clone(),
+            &key,
+            Some(dir.path().to_path_buf()),
+            None,
+        )
+        .await;
+        let (sender, sender_key) = get_key_pair();
+        Self {
+            dir,
+            key,
+            committee,
+            state,
+            sender,
+            sender_key,
+        }
+    }
+
+    /// Executes the transfer of a new object of the sender.
+    async fn transfer(&self) -> TransactionEffects {
+        let (recipient, _): (_, AccountKeyPair) = get_key_pair();
+        let object = Object::with_id_owner_for_testing(ObjectID::random(), self.sender);
+        let gas = Object::with_id_owner_for_testing(ObjectID::random(), self.sender);
+        self.state.insert_genesis_object(object.clone()).await;
+        self.state.insert_genesis_object(gas.clone()).await;
+        let transaction = init_transfer_transaction(
+            self.sender,
+            &self.sender_key,
+            recipient,
+            object.compute_object_reference(),
+            gas.compute_object_reference(),
+        );
+        send_and_confirm_transaction(&self.state, transaction)
+            .await
+            .unwrap()
+            .signed_effects
+            .unwrap()
+            .into_data()
+    }
+}
+
+fn execution_digests(effects: &TransactionEffects) -> ExecutionDigests {
+    ExecutionDigests::new(effects.transaction_digest, effects.digest())
+}
+
+#[tokio::test]
+async fn test_replay_transaction() {
+    let node = TestNode::new().await;
+    CheckpointStore::new(&node.dir.path().join("checkpoints"));
+    let effects = node.transfer().await;
+
+    // The store is read while the node still has it open.
+    let replayer = TransactionReplayer::open(node.dir.path()).unwrap();
+    let digests = execution_digests(&effects);
+    assert_eq!(replayer.replay_transaction(&digests, 0).unwrap(), vec![]);
+
+    let unknown = ExecutionDigests::new(TransactionDigest::random(), effects.digest());
+    assert!(matches!(
+        replayer.replay_transaction(&unknown, 0),
+        Err(SuiError::TransactionNotFound { .. })
+    ));
+}
+
+#[tokio::test]
+async fn test_replay_transaction_with_diverging_effects() {
+    let node = TestNode::new().await;
+    CheckpointStore::new(&node.dir.path().join("checkpoints"));
+    let effects = node.transfer().await;
+
+    // Effects that charged more gas than the execution does.
+    let mut diverging = effects.clone();
+    diverging.gas_used.computation_cost += 1;
+    node.state
+        .database
+        .perpetual_tables
+        .effects
+        .insert(&diverging.digest(), &diverging)
+        .unwrap();
+
+    let replayer = TransactionReplayer::open(node.dir.path()).unwrap();
+    let diffs = replayer
+        .replay_transaction(&execution_digests(&diverging), 0)
+        .unwrap();
+    assert_eq!(
+        diffs,
+        vec![EffectsDiff {
+            field: "gas_used",
+            committed: format!("{:?}", diverging.gas_used),
+            replayed: format!("{:?}", effects.gas_used),
+        }]
+    );
+}
+
+#[tokio::test]
+async fn test_replay_checkpoints() {
+    let node = TestNode::new().await;
+    let checkpoints = CheckpointStore::new(&node.dir.path().join("checkpoints"));
+
+    // Two checkpoints of one transaction each.
+    let mut transactions = Vec::new();
+    let mut previous_digest = None;
+    for sequence_number in 0..2 {
+        let digests = execution_digests(&node.transfer().await);
+        let contents =
+            CheckpointContents::new_with_causally_ordered_transactions(iter::once(digests));
+        let signed = SignedCheckpointSummary::new(
+            0,
+            sequence_number,
+            node.key.public().into(),
+            &node.key,
+            &contents,
+            previous_digest,
+            GasCostSummary::default(),
+            None,
+        );
+        let checkpoint =
+            CertifiedCheckpointSummary::aggregate(vec![signed], &node.committee).unwrap();
+        checkpoints.insert_checkpoint_contents(contents).unwrap();
+        checkpoints
+            .insert_certified_checkpoint(&checkpoint)
+            .unwrap();
+        previous_digest = Some(checkpoint.digest());
+        transactions.push((sequence_number, digests));
+    }
+
+    let replayer = TransactionReplayer::open(node.dir.path()).unwrap();
+    let replayed = replayer.replay_checkpoints(0..=1).unwrap();
+    assert_eq!(
+        replayed
+            .iter()
+            .map(|transaction| (transaction.checkpoint, transaction.digests))
+            .collect::<Vec<_>>(),
+        transactions
+    );
+    assert!(replayed
+        .iter()
+        .all(|transaction| transaction.diffs.is_empty()));
+
+    assert!(replayer.replay_checkpoints(1..=2).is_err());
+}
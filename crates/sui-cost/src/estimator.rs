@@ -142,6 +142,7 @@ pub async fn estimate_transaction_computation_cost(
     let (_gas_status, input_objects) = transaction_input_checker::check_transaction_input(
         &state.db(),
         &tx.data().intent_message.value,
+        tx.gas_owner(),
     )
     .await?;
     let in_mem_temporary_store =
@@ -28,6 +28,12 @@ pub enum FaucetError {
     #[error("Coin Transfer Failed `{0}`")]
     Transfer(String),
 
+    #[error("Too many requests: {0}")]
+    TooManyRequests(String),
+
+    #[error("Request verification failed: {0}")]
+    VerificationFailed(String),
+
     #[error("Internal error: {0}")]
     Internal(String),
 }
@@ -4,13 +4,17 @@
 mod errors;
 mod faucet;
 mod metrics;
+mod rate_limit;
 mod requests;
 mod responses;
+mod verification;
 
 pub mod metrics_layer;
 pub use metrics_layer::*;
 
 pub use errors::FaucetError;
 pub use faucet::*;
+pub use rate_limit::*;
 pub use requests::*;
 pub use responses::*;
+pub use verification::*;
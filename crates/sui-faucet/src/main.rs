@@ -3,7 +3,8 @@
 
 use axum::{
     error_handling::HandleErrorLayer,
-    http::StatusCode,
+    extract::ConnectInfo,
+    http::{HeaderMap, StatusCode},
     response::IntoResponse,
     routing::{get, post},
     BoxError, Extension, Json, Router,
@@ -20,7 +21,11 @@ use std::{
 };
 use sui::client_commands::WalletContext;
 use sui_config::{sui_config_dir, SUI_CLIENT_CONFIG};
-use sui_faucet::{Faucet, FaucetRequest, FaucetResponse, RequestMetricsLayer, SimpleFaucet};
+use sui_faucet::{
+    CaptchaVerifier, Faucet, FaucetError, FaucetRequest, FaucetResponse, InMemoryStore, RateLimit,
+    RateLimitStore, RateLimiter, RedisStore, RequestMetricsLayer, RequestVerifier, SimpleFaucet,
+};
+use sui_types::base_types::SuiAddress;
 use tower::{limit::RateLimitLayer, ServiceBuilder};
 use tower_http::cors::{Any, CorsLayer};
 use tracing::{info, warn};
@@ -28,6 +33,9 @@ use uuid::Uuid;
 
 const CONCURRENCY_LIMIT: usize = 30;
 
+/// The header of the token verified by the CAPTCHA provider.
+const VERIFICATION_TOKEN_HEADER: &str = "x-faucet-token";
+
 #[derive(Parser)]
 #[clap(
     name = "Sui Faucet",
@@ -55,11 +63,47 @@ struct FaucetConfig {
 
     #[clap(long, default_value_t = 60)]
     wallet_client_timeout_secs: u64,
+
+    /// The number of requests accepted from an IP, or an IPv6 /64, per IP cooldown, unlimited by
+    /// default.
+    #[clap(long)]
+    max_requests_per_ip: Option<u64>,
+
+    #[clap(long, default_value_t = 86400)]
+    ip_cooldown_secs: u64,
+
+    /// The number of requests accepted for a recipient address per address cooldown, unlimited
+    /// by default.
+    #[clap(long)]
+    max_requests_per_address: Option<u64>,
+
+    #[clap(long, default_value_t = 86400)]
+    address_cooldown_secs: u64,
+
+    /// The `host:port` of a Redis server counting the requests, shared by several faucets. They
+    /// are counted in memory by default.
+    #[clap(long)]
+    redis_address: Option<String>,
+
+    /// The `siteverify` url of the CAPTCHA provider verifying the token sent in the
+    /// `X-Faucet-Token` header of the requests, which are not verified by default.
+    #[clap(long, requires = "captcha-secret")]
+    captcha_verify_url: Option<String>,
+
+    #[clap(long, requires = "captcha-verify-url")]
+    captcha_secret: Option<String>,
+
+    /// The header of the client IP set by a proxy in front of the faucet, e.g.
+    /// `X-Forwarded-For`, the IP of the connection being used by default.
+    #[clap(long)]
+    client_ip_header: Option<String>,
 }
 
 struct AppState<F = SimpleFaucet> {
     faucet: F,
     config: FaucetConfig,
+    rate_limiter: RateLimiter,
+    verifier: Option<Box<dyn RequestVerifier>>,
     // TODO: add counter
 }
 
@@ -95,11 +139,38 @@ async fn main() -> Result<(), anyhow::Error> {
     let registry_service = sui_node::metrics::start_prometheus_server(prom_binding);
     let prometheus_registry = registry_service.default_registry();
 
+    let rate_limit_store: Arc<dyn RateLimitStore> = match &config.redis_address {
+        Some(address) => Arc::new(RedisStore::new(address.clone())),
+        None => Arc::new(InMemoryStore::default()),
+    };
+    let rate_limit = |max_requests: Option<u64>, cooldown_secs| {
+        max_requests.map(|max_requests| RateLimit {
+            max_requests,
+            window: Duration::from_secs(cooldown_secs),
+        })
+    };
+    let rate_limiter = RateLimiter::new(
+        rate_limit_store,
+        rate_limit(config.max_requests_per_ip, config.ip_cooldown_secs),
+        rate_limit(
+            config.max_requests_per_address,
+            config.address_cooldown_secs,
+        ),
+    );
+    let verifier = match (&config.captcha_verify_url, &config.captcha_secret) {
+        (Some(url), Some(secret)) => {
+            Some(Box::new(CaptchaVerifier::new(url, secret)) as Box<dyn RequestVerifier>)
+        }
+        _ => None,
+    };
+
     let app_state = Arc::new(AppState {
         faucet: SimpleFaucet::new(context, &prometheus_registry)
             .await
             .unwrap(),
         config,
+        rate_limiter,
+        verifier,
     });
 
     // TODO: restrict access if needed
@@ -130,7 +201,7 @@ async fn main() -> Result<(), anyhow::Error> {
     let addr = SocketAddr::new(IpAddr::V4(host_ip), port);
     info!("listening on {}", addr);
     axum::Server::bind(&addr)
-        .serve(app.into_make_service())
+        .serve(app.into_make_service_with_connect_info::<SocketAddr>())
         .await?;
     Ok(())
 }
@@ -142,14 +213,26 @@ async fn health() -> &'static str {
 
 /// handler for all the request_gas requests
 async fn request_gas(
+    ConnectInfo(remote): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
     Json(payload): Json<FaucetRequest>,
     Extension(state): Extension<Arc<AppState>>,
 ) -> impl IntoResponse {
     // ID for traceability
     let id = Uuid::new_v4();
     info!(uuid = ?id, "Got new gas request.");
+    let ip = client_ip(&state.config, &headers, remote);
     let result = match payload {
         FaucetRequest::FixedAmountRequest(requests) => {
+            if let Err(e) = check_request(&state, &headers, ip, requests.recipient).await {
+                warn!(uuid = ?id, ?ip, "Refused gas request: {:?}", e);
+                let status = match e {
+                    FaucetError::TooManyRequests(_) => StatusCode::TOO_MANY_REQUESTS,
+                    FaucetError::VerificationFailed(_) => StatusCode::FORBIDDEN,
+                    _ => StatusCode::INTERNAL_SERVER_ERROR,
+                };
+                return (status, Json(FaucetResponse::from(e)));
+            }
             // We spawn a tokio task for this such that connection drop will not interrupt
             // it and impact the reclycing of coins
             spawn_monitored_task!(async move {
@@ -181,6 +264,36 @@ async fn request_gas(
     }
 }
 
+/// The IP of the client, from the configured header of the proxy in front of the faucet if set.
+fn client_ip(config: &FaucetConfig, headers: &HeaderMap, remote: SocketAddr) -> IpAddr {
+    config
+        .client_ip_header
+        .as_ref()
+        .and_then(|header| headers.get(header.as_str()))
+        .and_then(|value| value.to_str().ok())
+        // The proxy appends the IP of its client to the ones of the previous proxies.
+        .and_then(|value| value.rsplit(',').next())
+        .and_then(|ip| ip.trim().parse().ok())
+        .unwrap_or_else(|| remote.ip())
+}
+
+/// Verifies the token of the request if the faucet is configured to, and then counts it against
+/// the rate limits of its IP and recipient.
+async fn check_request(
+    state: &AppState,
+    headers: &HeaderMap,
+    ip: IpAddr,
+    recipient: SuiAddress,
+) -> Result<(), FaucetError> {
+    if let Some(verifier) = &state.verifier {
+        let token = headers
+            .get(VERIFICATION_TOKEN_HEADER)
+            .and_then(|value| value.to_str().ok());
+        verifier.verify(token, ip).await?;
+    }
+    state.rate_limiter.check(ip, recipient).await
+}
+
 async fn create_wallet_context(timeout_secs: u64) -> Result<WalletContext, anyhow::Error> {
     let wallet_conf = sui_config_dir()?.join(SUI_CLIENT_CONFIG);
     info!("Initialize wallet from config path: {:?}", wallet_conf);
@@ -0,0 +1,303 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Limits the requests of every client IP and of every recipient address to a number per
+//! window of time. The requests are counted by a store, in the memory of the faucet or in a Redis
+//! server shared by several instances of it.
+use crate::FaucetError;
+use async_trait::async_trait;
+use redis::aio::ConnectionManager;
+use std::{
+    collections::{HashMap, VecDeque},
+    net::{IpAddr, Ipv6Addr},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use sui_types::base_types::SuiAddress;
+use tokio::sync::OnceCell;
+
+/// Counts the requests of a key in windows of time.
+#[async_trait]
+pub trait RateLimitStore: Send + Sync {
+    /// Counts a request of `key`, returning the number of its requests in its current window,
+    /// which starts with its first request and lasts `window`.
+    async fn hit(&self, key: &str, window: Duration) -> Result<u64, FaucetError>;
+}
+
+/// Counts the requests in memory, grouping the counters by the length of their window: the
+/// counters of a window length expire in the order they were started, so the expired ones are
+/// dropped from the front of a queue as the requests come.
+#[derive(Default)]
+pub struct InMemoryStore {
+    windows: Mutex<HashMap<Duration, WindowCounters>>,
+}
+
+#[derive(Default)]
+struct WindowCounters {
+    counters: HashMap<String, u64>,
+    /// The expiry of every counter, in order.
+    expiries: VecDeque<(Instant, String)>,
+}
+
+impl WindowCounters {
+    fn drop_expired(&mut self, now: Instant) {
+        while let Some((expiry, _)) = self.expiries.front() {
+            if *expiry > now {
+                break;
+            }
+            let (_, key) = self.expiries.pop_front().unwrap();
+            self.counters.remove(&key);
+        }
+    }
+}
+
+#[async_trait]
+impl RateLimitStore for InMemoryStore {
+    async fn hit(&self, key: &str, window: Duration) -> Result<u64, FaucetError> {
+        let now = Instant::now();
+        let mut windows = self.windows.lock().unwrap();
+        let window_counters = windows.entry(window).or_default();
+        window_counters.drop_expired(now);
+        let WindowCounters { counters, expiries } = window_counters;
+        let count = counters.entry(key.to_string()).or_insert_with(|| {
+            expiries.push_back((now + window, key.to_string()));
+            0
+        });
+        *count += 1;
+        Ok(*count)
+    }
+}
+
+/// Counts the requests in a Redis server, with keys expiring at the end of their window.
+pub struct RedisStore {
+    address: String,
+    connection: OnceCell<ConnectionManager>,
+}
+
+/// Creates the counter of a key with the expiry of its window, and counts a request. The script
+/// runs atomically, so the counter can not be left without expiry.
+const HIT_SCRIPT: &str = "redis.call('SET', KEYS[1], 0, 'PX', ARGV[1], 'NX') \
+    return redis.call('INCR', KEYS[1])";
+
+impl RedisStore {
+    /// The store of the server at `address`, e.g. `127.0.0.1:6379`, connected to on first use.
+    pub fn new(address: impl Into<String>) -> Self {
+        Self {
+            address: address.into(),
+            connection: OnceCell::new(),
+        }
+    }
+
+    /// The connection to the server, which reconnects whenever it is lost.
+    async fn connection(&self) -> Result<ConnectionManager, FaucetError> {
+        self.connection
+            .get_or_try_init(|| async {
+                let client = redis::Client::open(format!("redis://{}/", self.address))?;
+                ConnectionManager::new(client).await
+            })
+            .await
+            .cloned()
+            .map_err(redis_error)
+    }
+}
+
+fn redis_error(e: redis::RedisError) -> FaucetError {
+    FaucetError::Internal(format!("Redis error: {e}"))
+}
+
+#[async_trait]
+impl RateLimitStore for RedisStore {
+    async fn hit(&self, key: &str, window: Duration) -> Result<u64, FaucetError> {
+        let mut connection = self.connection().await?;
+        redis::cmd("EVAL")
+            .arg(HIT_SCRIPT)
+            .arg(1)
+            .arg(key)
+            .arg(window.as_millis() as u64)
+            .query_async(&mut connection)
+            .await
+            .map_err(redis_error)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RateLimit {
+    pub max_requests: u64,
+    pub window: Duration,
+}
+
+pub struct RateLimiter {
+    store: Arc<dyn RateLimitStore>,
+    per_ip: Option<RateLimit>,
+    per_address: Option<RateLimit>,
+}
+
+impl RateLimiter {
+    pub fn new(
+        store: Arc<dyn RateLimitStore>,
+        per_ip: Option<RateLimit>,
+        per_address: Option<RateLimit>,
+    ) -> Self {
+        Self {
+            store,
+            per_ip,
+            per_address,
+        }
+    }
+
+    /// Counts a request of `ip` for `recipient`, failing when either made too many of them.
+    pub async fn check(&self, ip: IpAddr, recipient: SuiAddress) -> Result<(), FaucetError> {
+        let limits = [
+            (self.per_ip, ip_key(ip), "this IP"),
+            (
+                self.per_address,
+                format!("faucet:address:{recipient}"),
+                "this address",
+            ),
+        ];
+        for (limit, key, client) in limits {
+            let Some(limit) = limit else {
+                continue;
+            };
+            if self.store.hit(&key, limit.window).await? > limit.max_requests {
+                return Err(FaucetError::TooManyRequests(format!(
+                    "{} requests per {}s already made for {client}",
+                    limit.max_requests,
+                    limit.window.as_secs()
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The key of the requests of `ip`. An IPv6 client usually has a whole /64 prefix to itself, so
+/// the addresses of a /64 all count as the same client.
+fn ip_key(ip: IpAddr) -> String {
+    let ip = match ip {
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => IpAddr::V4(ip),
+            None => {
+                let prefix = Ipv6Addr::from(u128::from(ip) & !u128::from(u64::MAX));
+                return format!("faucet:ip:{prefix}/64");
+            }
+        },
+        ip => ip,
+    };
+    format!("faucet:ip:{ip}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::{
+        io::{AsyncBufReadExt, AsyncWriteExt, BufStream},
+        net::TcpListener,
+    };
+
+    #[tokio::test]
+    async fn limits_ips_and_addresses() {
+        let window = Duration::from_millis(200);
+        let limiter = RateLimiter::new(
+            Arc::new(InMemoryStore::default()),
+            Some(RateLimit {
+                max_requests: 3,
+                window,
+            }),
+            Some(RateLimit {
+                max_requests: 1,
+                window,
+            }),
+        );
+        let ip: IpAddr = "10.0.0.1".parse().unwrap();
+        let (first, second) = (
+            SuiAddress::random_for_testing_only(),
+            SuiAddress::random_for_testing_only(),
+        );
+
+        limiter.check(ip, first).await.unwrap();
+        // The address already received coins.
+        assert!(matches!(
+            limiter.check(ip, first).await,
+            Err(FaucetError::TooManyRequests(_))
+        ));
+        limiter.check(ip, second).await.unwrap();
+        // The IP made its 3 requests, even if one of them was rejected.
+        let third = SuiAddress::random_for_testing_only();
+        assert!(limiter.check(ip, third).await.is_err());
+        limiter
+            .check("10.0.0.2".parse().unwrap(), third)
+            .await
+            .unwrap();
+
+        // Both are allowed again after the window.
+        tokio::time::sleep(window).await;
+        limiter.check(ip, first).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn in_memory_store_drops_expired_counters() {
+        let store = InMemoryStore::default();
+        let (short, long) = (Duration::from_millis(100), Duration::from_secs(60));
+        assert_eq!(store.hit("a", short).await.unwrap(), 1);
+        assert_eq!(store.hit("b", long).await.unwrap(), 1);
+        tokio::time::sleep(short).await;
+        assert_eq!(store.hit("c", short).await.unwrap(), 1);
+        assert_eq!(store.hit("b", long).await.unwrap(), 2);
+
+        let windows = store.windows.lock().unwrap();
+        let counters = &windows[&short];
+        assert_eq!(counters.counters.keys().collect::<Vec<_>>(), vec!["c"]);
+        assert_eq!(counters.expiries.len(), 1);
+    }
+
+    #[test]
+    fn ipv6_clients_are_keyed_by_their_prefix() {
+        let key = |ip: &str| ip_key(ip.parse().unwrap());
+        assert_eq!(key("10.0.0.1"), "faucet:ip:10.0.0.1");
+        assert_eq!(key("::ffff:10.0.0.1"), "faucet:ip:10.0.0.1");
+        assert_eq!(key("2001:db8:1:2:3:4:5:6"), "faucet:ip:2001:db8:1:2::/64");
+        assert_eq!(key("2001:db8:1:2::1"), key("2001:db8:1:2:ffff::"));
+        assert_ne!(key("2001:db8:1:2::1"), key("2001:db8:1:3::1"));
+    }
+
+    #[tokio::test]
+    async fn redis_store_counts_with_expiring_keys() {
+        // A server answering the script with the count of its calls, recording the commands.
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        let commands = Arc::new(Mutex::new(Vec::new()));
+        let recorded = commands.clone();
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut stream = BufStream::new(stream);
+            let mut count = 0;
+            loop {
+                let mut line = String::new();
+                if stream.read_line(&mut line).await.unwrap() == 0 {
+                    return;
+                }
+                let args: usize = line.trim_end()[1..].parse().unwrap();
+                let mut command = Vec::new();
+                for _ in 0..args {
+                    let (mut len, mut arg) = (String::new(), String::new());
+                    stream.read_line(&mut len).await.unwrap();
+                    stream.read_line(&mut arg).await.unwrap();
+                    command.push(arg.trim_end().to_string());
+                }
+                count += 1;
+                let reply = format!(":{count}\r\n");
+                recorded.lock().unwrap().push(command.join(" "));
+                stream.write_all(reply.as_bytes()).await.unwrap();
+                stream.flush().await.unwrap();
+            }
+        });
+
+        let store = RedisStore::new(address);
+        let window = Duration::from_secs(60);
+        assert_eq!(store.hit("faucet:ip:10.0.0.1", window).await.unwrap(), 1);
+        assert_eq!(store.hit("faucet:ip:10.0.0.1", window).await.unwrap(), 2);
+        let hit = format!("EVAL {HIT_SCRIPT} 1 faucet:ip:10.0.0.1 60000");
+        assert_eq!(*commands.lock().unwrap(), vec![hit.clone(), hit]);
+    }
+}
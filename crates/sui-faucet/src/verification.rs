@@ -0,0 +1,73 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::FaucetError;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
+
+/// Verifies that a request for coins is made by a person, before dispensing them.
+#[async_trait]
+pub trait RequestVerifier: Send + Sync {
+    /// Verifies the `token` sent with a request of `ip`, if any.
+    async fn verify(&self, token: Option<&str>, ip: IpAddr) -> Result<(), FaucetError>;
+}
+
+/// Verifies the tokens of a CAPTCHA solved by the clients with the `siteverify` endpoint of its
+/// provider, as the ones of reCAPTCHA, hCaptcha and Turnstile do.
+pub struct CaptchaVerifier {
+    client: reqwest::Client,
+    verify_url: String,
+    secret: String,
+}
+
+#[derive(Serialize)]
+struct VerifyRequest<'a> {
+    secret: &'a str,
+    response: &'a str,
+    remoteip: String,
+}
+
+#[derive(Deserialize)]
+struct VerifyResponse {
+    success: bool,
+}
+
+impl CaptchaVerifier {
+    pub fn new(verify_url: impl Into<String>, secret: impl Into<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            verify_url: verify_url.into(),
+            secret: secret.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl RequestVerifier for CaptchaVerifier {
+    async fn verify(&self, token: Option<&str>, ip: IpAddr) -> Result<(), FaucetError> {
+        let token =
+            token.ok_or_else(|| FaucetError::VerificationFailed("No token sent".to_string()))?;
+        let response: VerifyResponse = self
+            .client
+            .post(&self.verify_url)
+            .form(&VerifyRequest {
+                secret: &self.secret,
+                response: token,
+                remoteip: ip.to_string(),
+            })
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| FaucetError::Internal(format!("Unable to verify the token: {e}")))?
+            .json()
+            .await
+            .map_err(|e| FaucetError::Internal(format!("Unable to verify the token: {e}")))?;
+        if !response.success {
+            return Err(FaucetError::VerificationFailed(
+                "The token is invalid".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
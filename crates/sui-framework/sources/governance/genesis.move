@@ -31,6 +31,7 @@ module sui::genesis {
         chain_id: u8,
         validator_pubkeys: vector<vector<u8>>,
         validator_network_pubkeys: vector<vector<u8>>,
+        validator_worker_pubkeys: vector<vector<u8>>,
         validator_proof_of_possessions: vector<vector<u8>>,
         validator_sui_addresses: vector<address>,
         validator_names: vector<vector<u8>>,
@@ -58,6 +59,7 @@ module sui::genesis {
             let sui_address = *vector::borrow(&validator_sui_addresses, i);
             let pubkey = *vector::borrow(&validator_pubkeys, i);
             let network_pubkey = *vector::borrow(&validator_network_pubkeys, i);
+            let worker_pubkey = *vector::borrow(&validator_worker_pubkeys, i);
             let proof_of_possession = *vector::borrow(&validator_proof_of_possessions, i);
             let name = *vector::borrow(&validator_names, i);
             let net_address = *vector::borrow(&validator_net_addresses, i);
@@ -68,6 +70,7 @@ module sui::genesis {
                 sui_address,
                 pubkey,
                 network_pubkey,
+                worker_pubkey,
                 proof_of_possession,
                 name,
                 net_address,
@@ -7,6 +7,7 @@ module sui::sui_system {
     use sui::staking_pool::{Delegation, StakedSui};
     use sui::object::{Self, UID};
     use sui::locked_coin::{Self, LockedCoin};
+    use sui::pay;
     use sui::sui::SUI;
     use sui::transfer;
     use sui::tx_context::{Self, TxContext};
@@ -16,6 +17,7 @@ module sui::sui_system {
     use sui::vec_map::{Self, VecMap};
     use sui::vec_set::{Self, VecSet};
     use std::option;
+    use std::vector;
 
     friend sui::genesis;
 
@@ -112,12 +114,15 @@ module sui::sui_system {
     /// Can be called by anyone who wishes to become a validator in the next epoch.
     /// The `validator` object needs to be created before calling this.
     /// The amount of stake in the `validator` object must meet the requirements.
+    /// Breaking change: `worker_pubkey_bytes` is a new argument, so callers built against the
+    /// previous framework, such as `sui client call` scripts, must pass it.
     // TODO: Does this need to go through a voting process? Any other criteria for
     // someone to become a validator?
     public entry fun request_add_validator(
         self: &mut SuiSystemState,
         pubkey_bytes: vector<u8>,
         network_pubkey_bytes: vector<u8>,
+        worker_pubkey_bytes: vector<u8>,
         proof_of_possession: vector<u8>,
         name: vector<u8>,
         net_address: vector<u8>,
@@ -139,6 +144,7 @@ module sui::sui_system {
             tx_context::sender(ctx),
             pubkey_bytes,
             network_pubkey_bytes,
+            worker_pubkey_bytes,
             proof_of_possession,
             name,
             net_address,
@@ -194,6 +200,40 @@ module sui::sui_system {
         )
     }
 
+    /// A validator can call this entry function to rotate its protocol, network and worker keys,
+    /// updated at the end of the epoch. `proof_of_possession` proves the ownership of the new
+    /// protocol key, as when the validator joined.
+    public entry fun request_rotate_keys(
+        self: &mut SuiSystemState,
+        pubkey_bytes: vector<u8>,
+        proof_of_possession: vector<u8>,
+        network_pubkey_bytes: vector<u8>,
+        worker_pubkey_bytes: vector<u8>,
+        ctx: &mut TxContext,
+    ) {
+        validator_set::request_rotate_keys(
+            &mut self.validators,
+            pubkey_bytes,
+            proof_of_possession,
+            network_pubkey_bytes,
+            worker_pubkey_bytes,
+            ctx
+        )
+    }
+
+    /// A validator can call this entry function to set a new network address, updated at the end of the epoch.
+    public entry fun request_set_net_address(
+        self: &mut SuiSystemState,
+        net_address: vector<u8>,
+        ctx: &mut TxContext,
+    ) {
+        validator_set::request_set_net_address(
+            &mut self.validators,
+            net_address,
+            ctx
+        )
+    }
+
     /// A validator can request adding more stake. This will be processed at the end of epoch.
     public entry fun request_add_stake(
         self: &mut SuiSystemState,
@@ -259,6 +299,32 @@ module sui::sui_system {
         );
     }
 
+    /// Add delegated stake of `stake_amount` SUI, taken from `delegate_stakes`, to a validator's
+    /// staking pool. What is left of the coins is sent back to the sender as a single coin.
+    public entry fun request_add_delegation_mul_coin(
+        self: &mut SuiSystemState,
+        delegate_stakes: vector<Coin<SUI>>,
+        stake_amount: u64,
+        validator_address: address,
+        ctx: &mut TxContext,
+    ) {
+        let coin = vector::pop_back(&mut delegate_stakes);
+        pay::join_vec(&mut coin, delegate_stakes);
+        let stake = balance::split(coin::balance_mut(&mut coin), stake_amount);
+        if (coin::value(&coin) == 0) {
+            coin::destroy_zero(coin);
+        } else {
+            transfer::transfer(coin, tx_context::sender(ctx));
+        };
+        validator_set::request_add_delegation(
+            &mut self.validators,
+            validator_address,
+            stake,
+            option::none(),
+            ctx,
+        );
+    }
+
     /// Add delegated stake to a validator's staking pool using a locked SUI coin.
     public entry fun request_add_delegation_with_locked_coin(
         self: &mut SuiSystemState,
@@ -12,7 +12,7 @@ module sui::validator {
     use sui::stake;
     use sui::stake::Stake;
     use sui::epoch_time_lock::EpochTimeLock;
-    use std::option::Option;
+    use std::option::{Self, Option};
     use sui::bls12381::bls12381_min_sig_verify_with_domain;
     use sui::staking_pool::{Self, Delegation, StakedSui, StakingPool};
 
@@ -27,6 +27,10 @@ module sui::validator {
     #[test_only]
     friend sui::governance_test_utils;
 
+    /// Breaking change: `worker_pubkey_bytes` and the `next_epoch_*` key and address fields
+    /// change the layout of this struct, so a framework with them can't upgrade the objects of an
+    /// existing network, which needs a new genesis. The Rust mirror is
+    /// `sui_types::sui_system_state::ValidatorMetadata`, and the two must change together.
     struct ValidatorMetadata has store, drop, copy {
         /// The Sui Address of the validator. This is the sender that created the Validator object,
         /// and also the address to send validator/coins to during withdraws.
@@ -37,6 +41,9 @@ module sui::validator {
         /// The public key bytes corresponding to the private key that the validator
         /// uses to establish TLS connections
         network_pubkey_bytes: vector<u8>,
+        /// The public key bytes corresponding to the private key that the validator's
+        /// workers use to establish TLS connections
+        worker_pubkey_bytes: vector<u8>,
         /// This is a proof that the validator has ownership of the private key
         proof_of_possession: vector<u8>,
         /// A unique human-readable name of this validator.
@@ -51,6 +58,16 @@ module sui::validator {
         next_epoch_gas_price: u64,
         /// The commission rate of the validator starting the next epoch, in basis point.
         next_epoch_commission_rate: u64,
+        /// The protocol public key of the validator starting the next epoch, if it is rotated.
+        next_epoch_pubkey_bytes: Option<vector<u8>>,
+        /// The proof of possession of the protocol key starting the next epoch, if it is rotated.
+        next_epoch_proof_of_possession: Option<vector<u8>>,
+        /// The network public key of the validator starting the next epoch, if it is rotated.
+        next_epoch_network_pubkey_bytes: Option<vector<u8>>,
+        /// The worker public key of the validator starting the next epoch, if it is rotated.
+        next_epoch_worker_pubkey_bytes: Option<vector<u8>>,
+        /// The network address of the validator starting the next epoch, if it changes.
+        next_epoch_net_address: Option<vector<u8>>,
     }
 
     struct Validator has store {
@@ -90,10 +107,25 @@ module sui::validator {
         );
     }
 
+    /// The length of the Ed25519 public keys of the network and the workers of a validator.
+    const ED25519_PUBLIC_KEY_LENGTH: u64 = 32;
+
+    /// Checks that the network and worker keys are distinct Ed25519 public keys, since they
+    /// authenticate different peers. Narwhal rejects the ones which are not valid points.
+    fun verify_network_keys(network_pubkey_bytes: &vector<u8>, worker_pubkey_bytes: &vector<u8>) {
+        assert!(
+            vector::length(network_pubkey_bytes) == ED25519_PUBLIC_KEY_LENGTH
+                && vector::length(worker_pubkey_bytes) == ED25519_PUBLIC_KEY_LENGTH
+                && network_pubkey_bytes != worker_pubkey_bytes,
+            0
+        );
+    }
+
     public(friend) fun new(
         sui_address: address,
         pubkey_bytes: vector<u8>,
         network_pubkey_bytes: vector<u8>,
+        worker_pubkey_bytes: vector<u8>,
         proof_of_possession: vector<u8>,
         name: vector<u8>,
         net_address: vector<u8>,
@@ -113,6 +145,7 @@ module sui::validator {
             sui_address,
             pubkey_bytes
         );
+        verify_network_keys(&network_pubkey_bytes, &worker_pubkey_bytes);
         // Check that the name is human-readable.
         ascii::string(copy name);
         let stake_amount = balance::value(&stake);
@@ -122,6 +155,7 @@ module sui::validator {
                 sui_address,
                 pubkey_bytes,
                 network_pubkey_bytes,
+                worker_pubkey_bytes,
                 proof_of_possession,
                 name,
                 net_address,
@@ -129,6 +163,11 @@ module sui::validator {
                 next_epoch_delegation: 0,
                 next_epoch_gas_price: gas_price,
                 next_epoch_commission_rate: commission_rate,
+                next_epoch_pubkey_bytes: option::none(),
+                next_epoch_proof_of_possession: option::none(),
+                next_epoch_network_pubkey_bytes: option::none(),
+                next_epoch_worker_pubkey_bytes: option::none(),
+                next_epoch_net_address: option::none(),
             },
             stake_amount,
             pending_stake: 0,
@@ -234,6 +273,48 @@ module sui::validator {
         self.metadata.next_epoch_commission_rate = new_commission_rate;
     }
 
+    /// Request to rotate the protocol, network and worker keys of the validator starting the next epoch.
+    /// The proof of possession of the new protocol key and the new network keys are checked right away.
+    public(friend) fun request_rotate_keys(
+        self: &mut Validator,
+        pubkey_bytes: vector<u8>,
+        proof_of_possession: vector<u8>,
+        network_pubkey_bytes: vector<u8>,
+        worker_pubkey_bytes: vector<u8>,
+    ) {
+        assert!(vector::length(&pubkey_bytes) <= 128, 0);
+        verify_proof_of_possession(
+            copy proof_of_possession,
+            self.metadata.sui_address,
+            copy pubkey_bytes
+        );
+        verify_network_keys(&network_pubkey_bytes, &worker_pubkey_bytes);
+        self.metadata.next_epoch_pubkey_bytes = option::some(pubkey_bytes);
+        self.metadata.next_epoch_proof_of_possession = option::some(proof_of_possession);
+        self.metadata.next_epoch_network_pubkey_bytes = option::some(network_pubkey_bytes);
+        self.metadata.next_epoch_worker_pubkey_bytes = option::some(worker_pubkey_bytes);
+    }
+
+    /// Request to set a new network address for the next epoch.
+    public(friend) fun request_set_net_address(self: &mut Validator, net_address: vector<u8>) {
+        assert!(vector::length(&net_address) <= 128, 0);
+        self.metadata.next_epoch_net_address = option::some(net_address);
+    }
+
+    /// Apply the key rotation and network address change requested during the epoch, called at the end of the epoch.
+    public(friend) fun apply_next_epoch_metadata(self: &mut Validator) {
+        let metadata = &mut self.metadata;
+        if (option::is_some(&metadata.next_epoch_pubkey_bytes)) {
+            metadata.pubkey_bytes = option::extract(&mut metadata.next_epoch_pubkey_bytes);
+            metadata.proof_of_possession = option::extract(&mut metadata.next_epoch_proof_of_possession);
+            metadata.network_pubkey_bytes = option::extract(&mut metadata.next_epoch_network_pubkey_bytes);
+            metadata.worker_pubkey_bytes = option::extract(&mut metadata.next_epoch_worker_pubkey_bytes);
+        };
+        if (option::is_some(&metadata.next_epoch_net_address)) {
+            metadata.net_address = option::extract(&mut metadata.next_epoch_net_address);
+        };
+    }
+
     /// Deposit delegations rewards into the validator's staking pool, called at the end of the epoch.
     public(friend) fun deposit_delegation_rewards(self: &mut Validator, reward: Balance<SUI>) {
         self.metadata.next_epoch_delegation = self.metadata.next_epoch_delegation + balance::value(&reward);
@@ -278,6 +359,31 @@ module sui::validator {
         self.pending_withdraw
     }
 
+    public fun pubkey_bytes(self: &Validator): &vector<u8> {
+        &self.metadata.pubkey_bytes
+    }
+
+    /// The protocol public key of the validator starting the next epoch.
+    public fun next_epoch_pubkey_bytes(self: &Validator): &vector<u8> {
+        if (option::is_some(&self.metadata.next_epoch_pubkey_bytes)) {
+            option::borrow(&self.metadata.next_epoch_pubkey_bytes)
+        } else {
+            &self.metadata.pubkey_bytes
+        }
+    }
+
+    /// Whether the validator uses `key` as its protocol, network or worker key, in this epoch or
+    /// the next one.
+    public fun uses_key(self: &Validator, key: &vector<u8>): bool {
+        let metadata = &self.metadata;
+        &metadata.pubkey_bytes == key
+            || &metadata.network_pubkey_bytes == key
+            || &metadata.worker_pubkey_bytes == key
+            || option::contains(&metadata.next_epoch_pubkey_bytes, key)
+            || option::contains(&metadata.next_epoch_network_pubkey_bytes, key)
+            || option::contains(&metadata.next_epoch_worker_pubkey_bytes, key)
+    }
+
     public fun gas_price(self: &Validator): u64 {
         self.gas_price
     }
@@ -290,7 +396,9 @@ module sui::validator {
          self.metadata.sui_address == other.metadata.sui_address
             || self.metadata.name == other.metadata.name
             || self.metadata.net_address == other.metadata.net_address
-            || self.metadata.pubkey_bytes == other.metadata.pubkey_bytes
+            || uses_key(self, &other.metadata.pubkey_bytes)
+            || uses_key(self, &other.metadata.network_pubkey_bytes)
+            || uses_key(self, &other.metadata.worker_pubkey_bytes)
     }
 
     // CAUTION: THIS CODE IS ONLY FOR TESTING AND THIS MACRO MUST NEVER EVER BE REMOVED.
@@ -300,6 +408,7 @@ module sui::validator {
         sui_address: address,
         pubkey_bytes: vector<u8>,
         network_pubkey_bytes: vector<u8>,
+        worker_pubkey_bytes: vector<u8>,
         proof_of_possession: vector<u8>,
         name: vector<u8>,
         net_address: vector<u8>,
@@ -323,6 +432,7 @@ module sui::validator {
                 sui_address,
                 pubkey_bytes,
                 network_pubkey_bytes,
+                worker_pubkey_bytes,
                 proof_of_possession,
                 name,
                 net_address,
@@ -330,6 +440,11 @@ module sui::validator {
                 next_epoch_delegation: 0,
                 next_epoch_gas_price: gas_price,
                 next_epoch_commission_rate: commission_rate,
+                next_epoch_pubkey_bytes: option::none(),
+                next_epoch_proof_of_possession: option::none(),
+                next_epoch_network_pubkey_bytes: option::none(),
+                next_epoch_worker_pubkey_bytes: option::none(),
+                next_epoch_net_address: option::none(),
             },
             stake_amount,
             pending_stake: 0,
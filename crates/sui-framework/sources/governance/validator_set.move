@@ -256,13 +256,51 @@ module sui::validator_set {
         validator::request_set_commission_rate(validator, new_commission_rate);
     }
 
+    public(friend) fun request_rotate_keys(
+        self: &mut ValidatorSet,
+        pubkey_bytes: vector<u8>,
+        proof_of_possession: vector<u8>,
+        network_pubkey_bytes: vector<u8>,
+        worker_pubkey_bytes: vector<u8>,
+        ctx: &mut TxContext,
+    ) {
+        let validator_address = tx_context::sender(ctx);
+        let keys = vector[copy pubkey_bytes, copy network_pubkey_bytes, copy worker_pubkey_bytes];
+        assert!(
+            !contains_keys(&self.active_validators, validator_address, &keys)
+                && !contains_keys(&self.pending_validators, validator_address, &keys),
+            0
+        );
+        let validator = get_validator_mut(&mut self.active_validators, validator_address);
+        validator::request_rotate_keys(
+            validator,
+            pubkey_bytes,
+            proof_of_possession,
+            network_pubkey_bytes,
+            worker_pubkey_bytes,
+        );
+        self.next_epoch_validators = derive_next_epoch_validators(self);
+    }
+
+    public(friend) fun request_set_net_address(
+        self: &mut ValidatorSet,
+        net_address: vector<u8>,
+        ctx: &mut TxContext,
+    ) {
+        let validator_address = tx_context::sender(ctx);
+        let validator = get_validator_mut(&mut self.active_validators, validator_address);
+        validator::request_set_net_address(validator, net_address);
+        self.next_epoch_validators = derive_next_epoch_validators(self);
+    }
+
 
     // ==== epoch change functions ====
 
     /// Update the validator set at the end of epoch.
     /// It does the following things:
     ///   1. Distribute stake award.
-    ///   2. Process pending stake deposits and withdraws for each validator (`adjust_stake`).
+    ///   2. Process pending stake deposits and withdraws for each validator (`adjust_stake`),
+    ///      and apply their key rotations and network address changes.
     ///   3. Process pending delegation switches, deposits, and withdraws.
     ///   4. Process pending validator application and withdraws.
     ///   5. At the end, we calculate the total stake for the new epoch.
@@ -300,6 +338,8 @@ module sui::validator_set {
 
         adjust_stake_and_gas_price(&mut self.active_validators);
 
+        apply_next_epoch_metadata(&mut self.active_validators);
+
         // Delegation switches must be processed before delgation deposits and withdraws so that the
         // rewards portion of the delegation switch can be added to the new validator's pool when we
         // process pending delegations.
@@ -401,6 +441,31 @@ module sui::validator_set {
         false
     }
 
+    /// Checks whether a validator other than `validator_address` in `validators` uses one of
+    /// `keys` as its protocol, network or worker key, in this epoch or the next one.
+    fun contains_keys(
+        validators: &vector<Validator>,
+        validator_address: address,
+        keys: &vector<vector<u8>>,
+    ): bool {
+        let len = vector::length(validators);
+        let i = 0;
+        while (i < len) {
+            let v = vector::borrow(validators, i);
+            if (validator::sui_address(v) != validator_address) {
+                let j = 0;
+                while (j < vector::length(keys)) {
+                    if (validator::uses_key(v, vector::borrow(keys, j))) {
+                        return true
+                    };
+                    j = j + 1;
+                };
+            };
+            i = i + 1;
+        };
+        false
+    }
+
     /// Find validator by `validator_address`, in `validators`.
     /// Returns (true, index) if the validator is found, and the index is its index in the list.
     /// If not found, returns (false, 0).
@@ -560,6 +625,17 @@ module sui::validator_set {
         }
     }
 
+    /// Apply the key rotations and network address changes of each validator.
+    fun apply_next_epoch_metadata(validators: &mut vector<Validator>) {
+        let length = vector::length(validators);
+        let i = 0;
+        while (i < length) {
+            let validator = vector::borrow_mut(validators, i);
+            validator::apply_next_epoch_metadata(validator);
+            i = i + 1;
+        }
+    }
+
     /// Given the current list of active validators, the total stake and total reward,
     /// calculate the amount of reward each validator should get.
     /// Returns the amount of reward for each validator, as well as a remaining reward
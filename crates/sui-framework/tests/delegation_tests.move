@@ -3,7 +3,8 @@
 
 #[test_only]
 module sui::delegation_tests {
-    use sui::coin;
+    use sui::coin::{Self, Coin};
+    use sui::sui::SUI;
     use sui::test_scenario::{Self, Scenario};
     use sui::sui_system::{Self, SuiSystemState};
     use sui::staking_pool::{Self, Delegation, StakedSui};
@@ -88,6 +89,45 @@ module sui::delegation_tests {
         test_scenario::end(scenario_val);
     }
 
+    #[test]
+    fun test_add_delegation_mul_coin() {
+        let scenario_val = test_scenario::begin(VALIDATOR_ADDR_1);
+        let scenario = &mut scenario_val;
+        set_up_sui_system_state(scenario);
+
+        test_scenario::next_tx(scenario, DELEGATOR_ADDR_1);
+        {
+            let system_state = test_scenario::take_shared<SuiSystemState>(scenario);
+            let ctx = test_scenario::ctx(scenario);
+
+            // Delegate 50 SUI out of two coins of 30 SUI to VALIDATOR_ADDR_1.
+            let coins = vector[coin::mint_for_testing(30, ctx), coin::mint_for_testing(30, ctx)];
+            sui_system::request_add_delegation_mul_coin(
+                &mut system_state, coins, 50, VALIDATOR_ADDR_1, ctx);
+
+            test_scenario::return_shared(system_state);
+        };
+
+        governance_test_utils::advance_epoch(scenario);
+
+        test_scenario::next_tx(scenario, DELEGATOR_ADDR_1);
+        {
+            let staked_sui = test_scenario::take_from_sender<StakedSui>(scenario);
+            assert!(staking_pool::staked_sui_amount(&staked_sui) == 50, 101);
+            test_scenario::return_to_sender(scenario, staked_sui);
+
+            // The rest of the coins is sent back to the delegator.
+            let change = test_scenario::take_from_sender<Coin<SUI>>(scenario);
+            assert!(coin::value(&change) == 10, 102);
+            test_scenario::return_to_sender(scenario, change);
+
+            let system_state = test_scenario::take_shared<SuiSystemState>(scenario);
+            assert!(sui_system::validator_delegate_amount(&mut system_state, VALIDATOR_ADDR_1) == 50, 103);
+            test_scenario::return_shared(system_state);
+        };
+        test_scenario::end(scenario_val);
+    }
+
     #[test]
     fun test_partial_withdraw_delegation() {
         let scenario_val = test_scenario::begin(VALIDATOR_ADDR_1);
@@ -23,6 +23,7 @@ module sui::governance_test_utils {
             x"FF",
             x"FF",
             x"FF",
+            x"FF",
             b"ValidatorName",
             x"FFFF",
             balance::create_for_testing<SUI>(init_stake_amount),
@@ -164,6 +164,7 @@ module sui::validator_set_tests {
             vector[hint],
             vector[hint],
             vector[hint],
+            vector[hint],
             init_stake,
             option::none(),
             1,
@@ -183,6 +184,7 @@ module sui::validator_set_tests {
             vector[hint],
             vector[hint],
             vector[hint],
+            vector[hint],
             init_stake,
             option::none(),
             gas_price,
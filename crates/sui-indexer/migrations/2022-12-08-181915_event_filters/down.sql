@@ -0,0 +1,10 @@
+DROP INDEX events_package_module;
+DROP INDEX events_move_event_type;
+DROP INDEX events_sender;
+DROP INDEX events_event_type;
+
+ALTER TABLE events
+    DROP COLUMN package_id,
+    DROP COLUMN transaction_module,
+    DROP COLUMN sender,
+    DROP COLUMN move_event_type;
@@ -0,0 +1,24 @@
+-- the fields events are filtered by, null for the events without them,
+-- like the epoch changes
+ALTER TABLE events
+    ADD COLUMN package_id TEXT,
+    ADD COLUMN transaction_module TEXT,
+    ADD COLUMN sender VARCHAR(255),
+    -- only non-null for Move events, the struct type of the event
+    ADD COLUMN move_event_type TEXT;
+
+-- backfill the fields of the events ingested before, from their JSON content in which the event
+-- is the only field, named after its kind
+UPDATE events AS e SET
+    package_id = v.value->>'packageId',
+    transaction_module = v.value->>'transactionModule',
+    sender = v.value->>'sender',
+    move_event_type = CASE WHEN v.key = 'moveEvent' THEN v.value->>'type' END
+FROM (SELECT id, (jsonb_each(event_content::jsonb)).* FROM events) AS v
+WHERE e.id = v.id AND jsonb_typeof(v.value) = 'object';
+
+-- the events are returned in the order of their IDs, which follows each index
+CREATE INDEX events_package_module ON events (package_id, transaction_module, transaction_sequence, event_sequence);
+CREATE INDEX events_move_event_type ON events (move_event_type, transaction_sequence, event_sequence);
+CREATE INDEX events_sender ON events (sender, transaction_sequence, event_sequence);
+CREATE INDEX events_event_type ON events (event_type, transaction_sequence, event_sequence);
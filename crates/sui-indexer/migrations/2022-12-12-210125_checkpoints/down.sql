@@ -0,0 +1,3 @@
+DROP TABLE checkpoint_logs;
+DROP INDEX checkpoints_epoch;
+DROP TABLE checkpoints;
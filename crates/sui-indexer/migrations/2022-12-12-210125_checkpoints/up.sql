@@ -0,0 +1,29 @@
+CREATE TABLE checkpoints (
+    sequence_number BIGINT PRIMARY KEY,
+    epoch BIGINT NOT NULL,
+    checkpoint_digest VARCHAR(255) NOT NULL,
+    content_digest VARCHAR(255) NOT NULL,
+    -- only null for the first checkpoint
+    previous_checkpoint_digest VARCHAR(255),
+    -- the digests of the transactions of the checkpoint, in causal order
+    transactions TEXT[] NOT NULL,
+    -- rolling gas costs of the epoch until this checkpoint
+    total_computation_cost BIGINT NOT NULL,
+    total_storage_cost BIGINT NOT NULL,
+    total_storage_rebate BIGINT NOT NULL,
+    -- only true for the last checkpoint of an epoch
+    end_of_epoch BOOLEAN NOT NULL,
+    -- serialized checkpoint
+    checkpoint_content TEXT NOT NULL,
+    UNIQUE(checkpoint_digest)
+);
+
+CREATE INDEX checkpoints_epoch ON checkpoints (epoch, sequence_number);
+
+CREATE TABLE checkpoint_logs (
+    -- the sequence number of the first checkpoint not ingested yet,
+    -- all the checkpoints before it are ingested.
+    next_cursor_sequence_number BIGINT PRIMARY KEY
+);
+
+INSERT INTO checkpoint_logs (next_cursor_sequence_number) VALUES (0);
@@ -0,0 +1,2 @@
+-- the transactions are ingested with their checkpoints, resuming from checkpoint_logs
+DROP TABLE transaction_logs;
@@ -0,0 +1,154 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use jsonrpsee::core::RpcResult;
+use jsonrpsee::RpcModule;
+use jsonrpsee_proc_macros::rpc;
+use sui_json_rpc::api::cap_page_limit;
+use sui_json_rpc::SuiRpcModule;
+use sui_json_rpc_types::{EventPage, SuiCertifiedTransaction, SuiCheckpoint};
+use sui_open_rpc::Module;
+use sui_open_rpc_macros::open_rpc;
+use sui_types::base_types::TransactionDigest;
+use sui_types::event::EventID;
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
+
+use crate::models::checkpoints::{read_checkpoint, read_latest_checkpoint_sequence_number};
+use crate::models::events::{event_to_sui_event_envelope, read_events_by_filter, EventFilter};
+use crate::models::transactions::read_transaction;
+use crate::{get_pg_pool_connection, PgConnectionPool};
+
+#[open_rpc(namespace = "sui", tag = "Indexer Read API")]
+#[rpc(server, client, namespace = "sui")]
+pub trait IndexerReadApi {
+    /// Return the sequence number of the latest checkpoint ingested by the indexer
+    #[method(name = "getLatestCheckpointSequenceNumber")]
+    async fn get_latest_checkpoint_sequence_number(&self) -> RpcResult<CheckpointSequenceNumber>;
+
+    /// Return the checkpoint with the digests of its transactions and of their effects
+    #[method(name = "getCheckpoint")]
+    async fn get_checkpoint(
+        &self,
+        /// the sequence number of the checkpoint.
+        sequence_number: CheckpointSequenceNumber,
+    ) -> RpcResult<SuiCheckpoint>;
+
+    /// Return the certificate of the transaction, as ingested from its checkpoint
+    #[method(name = "getTransactionCertificate")]
+    async fn get_transaction_certificate(
+        &self,
+        /// the digest of the queried transaction.
+        digest: TransactionDigest,
+    ) -> RpcResult<SuiCertifiedTransaction>;
+
+    /// Return the events matching all the fields set in the filter, as ingested by the indexer
+    #[method(name = "getEventsByFilter")]
+    async fn get_events_by_filter(
+        &self,
+        /// the filter of the events, by their package, module, kind, Move type, sender and
+        /// checkpoint range.
+        filter: EventFilter,
+        /// optional paging cursor
+        cursor: Option<EventID>,
+        /// maximum number of items per page
+        limit: Option<usize>,
+        /// event query result ordering, default to false (ascending order), oldest record first.
+        descending_order: Option<bool>,
+    ) -> RpcResult<EventPage>;
+}
+
+/// Serves the reads of [IndexerReadApi] from the PostgresDB of the indexer.
+pub struct ReadApi {
+    pg_connection_pool: Arc<PgConnectionPool>,
+}
+
+impl ReadApi {
+    pub fn new(pg_connection_pool: Arc<PgConnectionPool>) -> Self {
+        Self { pg_connection_pool }
+    }
+}
+
+#[async_trait]
+impl IndexerReadApiServer for ReadApi {
+    async fn get_latest_checkpoint_sequence_number(&self) -> RpcResult<CheckpointSequenceNumber> {
+        let mut pg_pool_conn = get_pg_pool_connection(self.pg_connection_pool.clone())?;
+        let sequence_number = read_latest_checkpoint_sequence_number(&mut pg_pool_conn)?
+            .ok_or_else(|| anyhow!("No checkpoint has been ingested yet"))?;
+        Ok(sequence_number as CheckpointSequenceNumber)
+    }
+
+    async fn get_checkpoint(
+        &self,
+        sequence_number: CheckpointSequenceNumber,
+    ) -> RpcResult<SuiCheckpoint> {
+        let mut pg_pool_conn = get_pg_pool_connection(self.pg_connection_pool.clone())?;
+        let checkpoint = read_checkpoint(&mut pg_pool_conn, sequence_number as i64)?;
+        let checkpoint = serde_json::from_str(&checkpoint.checkpoint_content).map_err(|e| {
+            anyhow!(
+                "Failed deserializing checkpoint {} with error: {:?}",
+                sequence_number,
+                e
+            )
+        })?;
+        Ok(checkpoint)
+    }
+
+    async fn get_transaction_certificate(
+        &self,
+        digest: TransactionDigest,
+    ) -> RpcResult<SuiCertifiedTransaction> {
+        let mut pg_pool_conn = get_pg_pool_connection(self.pg_connection_pool.clone())?;
+        // canonical txn digest string is Base64 encoded
+        let transaction = read_transaction(&mut pg_pool_conn, digest.encode())?;
+        let certificate = serde_json::from_str(&transaction.transaction_content).map_err(|e| {
+            anyhow!(
+                "Failed deserializing transaction {:?} with error: {:?}",
+                digest,
+                e
+            )
+        })?;
+        Ok(certificate)
+    }
+
+    async fn get_events_by_filter(
+        &self,
+        filter: EventFilter,
+        cursor: Option<EventID>,
+        limit: Option<usize>,
+        descending_order: Option<bool>,
+    ) -> RpcResult<EventPage> {
+        let limit = cap_page_limit(limit)?;
+        let mut pg_pool_conn = get_pg_pool_connection(self.pg_connection_pool.clone())?;
+        let page = read_events_by_filter(
+            &mut pg_pool_conn,
+            &filter,
+            cursor,
+            limit,
+            descending_order.unwrap_or_default(),
+        )?;
+        let data = page
+            .data
+            .into_iter()
+            .map(event_to_sui_event_envelope)
+            .collect::<Result<_, _>>()?;
+        Ok(EventPage {
+            data,
+            has_next_page: page.next_cursor.is_some(),
+            next_cursor: page.next_cursor,
+        })
+    }
+}
+
+impl SuiRpcModule for ReadApi {
+    fn rpc(self) -> RpcModule<Self> {
+        self.into_rpc()
+    }
+
+    fn rpc_doc_module() -> Module {
+        IndexerReadApiOpenRpc::module_doc()
+    }
+}
@@ -1,6 +1,8 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use jsonrpsee::core::Error as RpcError;
+use jsonrpsee::types::error::CallError;
 use thiserror::Error;
 
 #[derive(Clone, Debug, Eq, Error, PartialEq)]
@@ -17,6 +19,9 @@ pub enum IndexerError {
     #[error("Indexer failed to convert structs to diesel Insertable with error: `{0}`")]
     InsertableParsingError(String),
 
+    #[error("Indexer failed to start JSON-RPC server with error: `{0}`")]
+    JsonRpcServerError(String),
+
     #[error("Indexer failed to find object mutations, which should never happen.")]
     ObjectMutationNotAvailable,
 
@@ -26,6 +31,9 @@ pub enum IndexerError {
     #[error("Indexer failed to get a pool connection from PG connection pool with error: `{0}`")]
     PgPoolConnectionError(String),
 
+    #[error("Indexer failed to run PostgresDB migrations with error: `{0}`")]
+    PostgresMigrationError(String),
+
     #[error("Indexer failed to read PostgresDB with error: `{0}`")]
     PostgresReadError(String),
 
@@ -55,6 +63,14 @@ impl IndexerError {
             IndexerError::PgConnectionPoolInitError(_) => "PgConnectionPoolInitError".into(),
             IndexerError::RpcClientInitError(_) => "RpcClientInitError".into(),
             IndexerError::PgPoolConnectionError(_) => "PgPoolConnectionError".into(),
+            IndexerError::PostgresMigrationError(_) => "PostgresMigrationError".into(),
+            IndexerError::JsonRpcServerError(_) => "JsonRpcServerError".into(),
         }
     }
 }
+
+impl From<IndexerError> for RpcError {
+    fn from(e: IndexerError) -> Self {
+        RpcError::Call(CallError::Failed(e.into()))
+    }
+}
@@ -0,0 +1,115 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use futures::{pin_mut, StreamExt};
+use std::sync::Arc;
+use sui_json_rpc_types::{SuiCheckpoint, SuiTransactionResponse};
+use sui_sdk::SuiClient;
+use sui_types::base_types::TransactionDigest;
+use tracing::info;
+
+use sui_indexer::errors::IndexerError;
+use sui_indexer::models::checkpoint_logs::read_checkpoint_log;
+use sui_indexer::models::checkpoints::commit_checkpoint;
+use sui_indexer::{get_pg_pool_connection, PgConnectionPool};
+
+/// Number of checkpoints whose transactions are downloaded concurrently, ahead of the checkpoint
+/// being committed.
+const CHECKPOINT_INGESTION_WORKERS: usize = 10;
+
+pub struct CheckpointHandler {
+    rpc_client: SuiClient,
+    pg_connection_pool: Arc<PgConnectionPool>,
+}
+
+impl CheckpointHandler {
+    pub fn new(rpc_client: SuiClient, pg_connection_pool: Arc<PgConnectionPool>) -> Self {
+        Self {
+            rpc_client,
+            pg_connection_pool,
+        }
+    }
+
+    pub async fn start(&self) -> Result<(), IndexerError> {
+        info!("Indexer checkpoint handler started...");
+        let mut pg_pool_conn = get_pg_pool_connection(self.pg_connection_pool.clone())?;
+
+        let checkpoint_log = read_checkpoint_log(&mut pg_pool_conn)?;
+        let next_cursor = checkpoint_log.next_cursor_sequence_number as u64;
+        let subscription = self
+            .rpc_client
+            .read_api()
+            .subscribe_checkpoint(Some(next_cursor), true)
+            .await
+            .map_err(|e| {
+                IndexerError::FullNodeReadingError(format!(
+                    "Failed subscribing to checkpoints from {} with error: {:?}",
+                    next_cursor, e
+                ))
+            })?;
+
+        // NOTE: the transactions of the next checkpoints are downloaded concurrently, while the
+        // checkpoints are committed in order, so that the checkpoint log always points to the
+        // first checkpoint not committed.
+        let downloads = subscription
+            .map(|checkpoint_res| async move {
+                let checkpoint = checkpoint_res.map_err(|e| {
+                    IndexerError::FullNodeReadingError(format!(
+                        "Failed reading checkpoint subscription with error: {:?}",
+                        e
+                    ))
+                })?;
+                self.download_checkpoint(checkpoint).await
+            })
+            .buffered(CHECKPOINT_INGESTION_WORKERS);
+        pin_mut!(downloads);
+
+        while let Some(download_res) = downloads.next().await {
+            let (checkpoint, txn_resps) = download_res?;
+            let sequence_number = checkpoint.sequence_number;
+            commit_checkpoint(&mut pg_pool_conn, checkpoint, txn_resps)?;
+            info!("Indexer committed checkpoint {}", sequence_number);
+        }
+        Err(IndexerError::FullNodeReadingError(
+            "Checkpoint subscription ended".to_string(),
+        ))
+    }
+
+    async fn download_checkpoint(
+        &self,
+        checkpoint: SuiCheckpoint,
+    ) -> Result<(SuiCheckpoint, Vec<SuiTransactionResponse>), IndexerError> {
+        let txn_digests: Vec<TransactionDigest> = checkpoint
+            .contents
+            .iter()
+            .flatten()
+            .map(|digests| digests.transaction)
+            .collect();
+        let txn_response_res_vec = self
+            .rpc_client
+            .read_api()
+            .multi_get_transactions(&txn_digests)
+            .await
+            .map_err(|e| {
+                IndexerError::FullNodeReadingError(format!(
+                    "Failed reading transactions of checkpoint {} with error: {:?}",
+                    checkpoint.sequence_number, e
+                ))
+            })?;
+        // NOTE: a checkpoint is only committed with all its transactions, so that it is
+        // downloaded again after a failure.
+        let txn_resps = txn_response_res_vec
+            .into_iter()
+            .zip(txn_digests)
+            .map(|(txn_response_res, tx_digest)| {
+                txn_response_res.map_err(|e| {
+                    IndexerError::FullNodeReadingError(format!(
+                        "Failed reading transaction response with tx digest {:?} and err: {:?}",
+                        tx_digest, e
+                    ))
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok((checkpoint, txn_resps))
+    }
+}
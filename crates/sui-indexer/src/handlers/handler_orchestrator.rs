@@ -9,8 +9,8 @@ use backoff::future::retry;
 use backoff::ExponentialBackoff;
 use tracing::{error, info, warn};
 
+use crate::handlers::checkpoint_handler::CheckpointHandler;
 use crate::handlers::event_handler::EventHandler;
-use crate::handlers::transaction_handler::TransactionHandler;
 
 #[derive(Clone)]
 pub struct HandlerOrchestrator {
@@ -30,24 +30,24 @@ impl HandlerOrchestrator {
         info!("Handler orchestrator started...");
         let event_handler =
             EventHandler::new(self.rpc_client.clone(), self.pg_connection_pool.clone());
-        let txn_handler =
-            TransactionHandler::new(self.rpc_client.clone(), self.pg_connection_pool.clone());
+        let checkpoint_handler =
+            CheckpointHandler::new(self.rpc_client.clone(), self.pg_connection_pool.clone());
 
         tokio::task::spawn(async move {
-            let txn_res = retry(ExponentialBackoff::default(), || async {
-                let txn_handler_exec_res = txn_handler.start().await;
-                if let Err(e) = txn_handler_exec_res.clone() {
+            let checkpoint_res = retry(ExponentialBackoff::default(), || async {
+                let checkpoint_handler_exec_res = checkpoint_handler.start().await;
+                if let Err(e) = checkpoint_handler_exec_res.clone() {
                     warn!(
-                        "Indexer transaction handler failed with error: {:?}, retrying...",
+                        "Indexer checkpoint handler failed with error: {:?}, retrying...",
                         e
                     );
                 }
-                Ok(txn_handler_exec_res?)
+                Ok(checkpoint_handler_exec_res?)
             })
             .await;
-            if let Err(e) = txn_res {
+            if let Err(e) = checkpoint_res {
                 error!(
-                    "Indexer transaction handler failed after retrials with error: {:?}!",
+                    "Indexer checkpoint handler failed after retrials with error: {:?}!",
                     e
                 );
             }
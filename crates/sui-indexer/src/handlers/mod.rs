@@ -1,6 +1,6 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod checkpoint_handler;
 pub mod event_handler;
 pub mod handler_orchestrator;
-pub mod transaction_handler;
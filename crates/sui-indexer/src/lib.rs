@@ -9,8 +9,10 @@ use backoff::ExponentialBackoff;
 use diesel::pg::PgConnection;
 use diesel::prelude::*;
 use diesel::r2d2::{ConnectionManager, Pool, PooledConnection};
+use diesel_migrations::{embed_migrations, EmbeddedMigrations, MigrationHarness};
 use tracing::info;
 
+pub mod apis;
 pub mod errors;
 pub mod models;
 pub mod schema;
@@ -19,11 +21,13 @@ pub mod utils;
 pub type PgConnectionPool = Pool<ConnectionManager<PgConnection>>;
 pub type PgPoolConnection = PooledConnection<ConnectionManager<PgConnection>>;
 
+pub const MIGRATIONS: EmbeddedMigrations = embed_migrations!("migrations");
+
 use errors::IndexerError;
 
-pub async fn new_rpc_client(http_url: String) -> Result<SuiClient, IndexerError> {
+pub async fn new_rpc_client(http_url: String, ws_url: String) -> Result<SuiClient, IndexerError> {
     info!("Getting new rpc client...");
-    SuiClient::new(http_url.as_str(), None, None)
+    SuiClient::new(http_url.as_str(), Some(ws_url.as_str()), None)
         .await
         .map_err(|e| {
             IndexerError::RpcClientInitError(format!(
@@ -63,3 +67,20 @@ pub fn get_pg_pool_connection(
         ))
     })
 }
+
+/// Runs the migrations of `MIGRATIONS` which were not run yet, so that the schema of the DB is
+/// the one of this indexer version.
+pub fn run_migrations(pg_pool_conn: &mut PgPoolConnection) -> Result<(), IndexerError> {
+    let migrations = pg_pool_conn
+        .run_pending_migrations(MIGRATIONS)
+        .map_err(|e| {
+            IndexerError::PostgresMigrationError(format!(
+                "Failed running pending migrations with error: {:?}",
+                e
+            ))
+        })?;
+    for migration in migrations {
+        info!("Ran PostgresDB migration {}", migration);
+    }
+    Ok(())
+}
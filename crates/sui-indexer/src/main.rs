@@ -1,9 +1,17 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use prometheus::Registry;
 use std::env;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use sui_indexer::apis::ReadApi;
 use sui_indexer::errors::IndexerError;
-use sui_indexer::{new_pg_connection_pool, new_rpc_client};
+use sui_indexer::{
+    get_pg_pool_connection, new_pg_connection_pool, new_rpc_client, run_migrations,
+    PgConnectionPool,
+};
+use sui_json_rpc::{JsonRpcServerBuilder, ServerHandle};
 
 use backoff::future::retry;
 use backoff::ExponentialBackoff;
@@ -25,9 +33,17 @@ async fn main() -> Result<(), IndexerError> {
     info!("Sui indexer started...");
 
     let indexer_config = IndexerConfig::parse();
+    let pg_connection_pool = new_pg_connection_pool(indexer_config.db_url.clone()).await?;
+    run_migrations(&mut get_pg_pool_connection(pg_connection_pool.clone())?)?;
+    let server_handle =
+        start_rpc_server(pg_connection_pool.clone(), indexer_config.rpc_server_port).await?;
+
     retry(ExponentialBackoff::default(), || async {
-        let rpc_client = new_rpc_client(indexer_config.rpc_client_url.clone()).await?;
-        let pg_connection_pool = new_pg_connection_pool(indexer_config.db_url.clone()).await?;
+        let rpc_client = new_rpc_client(
+            indexer_config.rpc_client_url.clone(),
+            indexer_config.rpc_client_ws_url.clone(),
+        )
+        .await?;
         // NOTE: Each handler is responsible for one type of data from nodes,like transactions and events;
         // Handler orchestrator runs these handlers in parallel and manage them upon errors etc.
         HandlerOrchestrator::new(rpc_client.clone(), pg_connection_pool.clone())
@@ -39,7 +55,29 @@ async fn main() -> Result<(), IndexerError> {
 
         Ok(())
     })
-    .await
+    .await?;
+
+    server_handle.stopped().await;
+    Ok(())
+}
+
+async fn start_rpc_server(
+    pg_connection_pool: Arc<PgConnectionPool>,
+    port: u16,
+) -> Result<ServerHandle, IndexerError> {
+    let registry = Registry::default();
+    let start_result: Result<ServerHandle, anyhow::Error> = async {
+        let mut builder = JsonRpcServerBuilder::new(env!("CARGO_PKG_VERSION"), &registry)?;
+        builder.register_module(ReadApi::new(pg_connection_pool))?;
+        builder.start(SocketAddr::from(([0, 0, 0, 0], port))).await
+    }
+    .await;
+    start_result.map_err(|e| {
+        IndexerError::JsonRpcServerError(format!(
+            "Failed starting JSON-RPC server on port {} with error: {:?}",
+            port, e
+        ))
+    })
 }
 
 #[derive(Parser)]
@@ -54,4 +92,10 @@ struct IndexerConfig {
     db_url: String,
     #[clap(long)]
     rpc_client_url: String,
+    /// The websocket URL of the fullnode, the checkpoints are streamed from.
+    #[clap(long)]
+    rpc_client_ws_url: String,
+    /// The port the JSON-RPC server reading from the DB listens on.
+    #[clap(long, default_value = "3030")]
+    rpc_server_port: u16,
 }
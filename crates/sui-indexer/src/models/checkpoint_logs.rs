@@ -0,0 +1,31 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::errors::IndexerError;
+use crate::schema::checkpoint_logs::dsl::*;
+use crate::PgPoolConnection;
+
+use diesel::prelude::*;
+use diesel::result::Error;
+
+#[derive(Queryable, Debug)]
+pub struct CheckpointLog {
+    pub next_cursor_sequence_number: i64,
+}
+
+pub fn read_checkpoint_log(
+    pg_pool_conn: &mut PgPoolConnection,
+) -> Result<CheckpointLog, IndexerError> {
+    // NOTE: always read one row, as checkpoint logs only have one row
+    let checkpoint_log_read_result: Result<CheckpointLog, Error> = pg_pool_conn
+        .build_transaction()
+        .read_only()
+        .run::<_, Error, _>(|conn| checkpoint_logs.limit(1).first::<CheckpointLog>(conn));
+
+    checkpoint_log_read_result.map_err(|e| {
+        IndexerError::PostgresReadError(format!(
+            "Failed reading checkpoint log in PostgresDB with error {:?}",
+            e
+        ))
+    })
+}
@@ -0,0 +1,160 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::errors::IndexerError;
+use crate::models::transactions::{transaction_response_to_new_transaction, NewTransaction};
+use crate::schema::checkpoint_logs::dsl::{
+    checkpoint_logs, next_cursor_sequence_number as next_cursor_sequence_number_column,
+};
+use crate::schema::checkpoints;
+use crate::schema::checkpoints::dsl::{
+    checkpoints as checkpoints_table, sequence_number as sequence_number_column,
+};
+use crate::schema::transactions;
+use crate::schema::transactions::transaction_digest;
+use crate::utils::log_errors_to_pg;
+use crate::PgPoolConnection;
+
+use diesel::dsl::max;
+use diesel::prelude::*;
+use diesel::result::Error;
+use fastcrypto::encoding::{Base64, Encoding};
+use sui_json_rpc_types::{SuiCheckpoint, SuiTransactionResponse};
+
+#[derive(Queryable, Insertable, Debug)]
+#[diesel(table_name = checkpoints)]
+pub struct Checkpoint {
+    pub sequence_number: i64,
+    pub epoch: i64,
+    pub checkpoint_digest: String,
+    pub content_digest: String,
+    pub previous_checkpoint_digest: Option<String>,
+    pub transactions: Vec<Option<String>>,
+    pub total_computation_cost: i64,
+    pub total_storage_cost: i64,
+    pub total_storage_rebate: i64,
+    pub end_of_epoch: bool,
+    pub checkpoint_content: String,
+}
+
+pub fn read_checkpoint(
+    pg_pool_conn: &mut PgPoolConnection,
+    sequence_number: i64,
+) -> Result<Checkpoint, IndexerError> {
+    let checkpoint_read_result: Result<Checkpoint, Error> = pg_pool_conn
+        .build_transaction()
+        .read_only()
+        .run::<_, Error, _>(|conn| {
+            checkpoints_table
+                .filter(sequence_number_column.eq(sequence_number))
+                .first::<Checkpoint>(conn)
+        });
+
+    checkpoint_read_result.map_err(|e| {
+        IndexerError::PostgresReadError(format!(
+            "Failed reading checkpoint with sequence number {} and err: {:?}",
+            sequence_number, e
+        ))
+    })
+}
+
+/// Returns the sequence number of the latest checkpoint ingested, none before the first one.
+pub fn read_latest_checkpoint_sequence_number(
+    pg_pool_conn: &mut PgPoolConnection,
+) -> Result<Option<i64>, IndexerError> {
+    let latest_seq_read_result: Result<Option<i64>, Error> = pg_pool_conn
+        .build_transaction()
+        .read_only()
+        .run::<_, Error, _>(|conn| {
+            checkpoints_table
+                .select(max(sequence_number_column))
+                .first::<Option<i64>>(conn)
+        });
+
+    latest_seq_read_result.map_err(|e| {
+        IndexerError::PostgresReadError(format!(
+            "Failed reading latest checkpoint sequence number with err: {:?}",
+            e
+        ))
+    })
+}
+
+/// Commits the checkpoint, its transactions and the checkpoint log in one DB transaction, so that
+/// the ingestion resumes after the last checkpoint committed with all its transactions. Fails
+/// without committing anything if any of the transactions cannot be converted.
+pub fn commit_checkpoint(
+    pg_pool_conn: &mut PgPoolConnection,
+    checkpoint: SuiCheckpoint,
+    tx_resps: Vec<SuiTransactionResponse>,
+) -> Result<usize, IndexerError> {
+    let new_checkpoint = checkpoint_to_new_checkpoint(checkpoint)?;
+    let next_cursor = new_checkpoint.sequence_number + 1;
+
+    // NOTE: a checkpoint is only committed with all its transactions, so that the ingestion
+    // stops at the checkpoint with a transaction it fails to convert, instead of skipping it.
+    let new_txns = tx_resps
+        .into_iter()
+        .map(transaction_response_to_new_transaction)
+        .collect::<Result<Vec<NewTransaction>, _>>()
+        .map_err(|e| {
+            log_errors_to_pg(pg_pool_conn, vec![e.clone()]);
+            e
+        })?;
+
+    let checkpoint_commit_result: Result<usize, Error> = pg_pool_conn
+        .build_transaction()
+        .read_write()
+        .run::<_, Error, _>(|conn| {
+            diesel::insert_into(transactions::table)
+                .values(&new_txns)
+                .on_conflict(transaction_digest)
+                .do_nothing()
+                .execute(conn)?;
+            diesel::insert_into(checkpoints::table)
+                .values(&new_checkpoint)
+                .on_conflict(sequence_number_column)
+                .do_nothing()
+                .execute(conn)?;
+            diesel::update(checkpoint_logs)
+                .set(next_cursor_sequence_number_column.eq(next_cursor))
+                .execute(conn)
+        });
+
+    checkpoint_commit_result.map_err(|e| {
+        IndexerError::PostgresWriteError(format!(
+            "Failed writing checkpoint to PostgresDB with checkpoint {:?} and error: {:?}",
+            new_checkpoint, e
+        ))
+    })
+}
+
+pub fn checkpoint_to_new_checkpoint(checkpoint: SuiCheckpoint) -> Result<Checkpoint, IndexerError> {
+    let checkpoint_json = serde_json::to_string(&checkpoint).map_err(|err| {
+        IndexerError::InsertableParsingError(format!(
+            "Failed converting checkpoint {:?} to JSON with error: {:?}",
+            checkpoint, err
+        ))
+    })?;
+    // canonical txn digest string is Base64 encoded
+    let transactions = checkpoint
+        .contents
+        .iter()
+        .flatten()
+        .map(|digests| Some(digests.transaction.encode()))
+        .collect();
+    let gas_summary = checkpoint.epoch_rolling_gas_cost_summary;
+
+    Ok(Checkpoint {
+        sequence_number: checkpoint.sequence_number as i64,
+        epoch: checkpoint.epoch as i64,
+        checkpoint_digest: Base64::encode(&checkpoint.digest),
+        content_digest: Base64::encode(&checkpoint.content_digest),
+        previous_checkpoint_digest: checkpoint.previous_digest.map(Base64::encode),
+        transactions,
+        total_computation_cost: gas_summary.computation_cost as i64,
+        total_storage_cost: gas_summary.storage_cost as i64,
+        total_storage_rebate: gas_summary.storage_rebate as i64,
+        end_of_epoch: checkpoint.next_epoch_committee.is_some(),
+        checkpoint_content: checkpoint_json,
+    })
+}
@@ -3,15 +3,27 @@
 
 use crate::errors::IndexerError;
 use crate::schema::events;
-use crate::schema::events::dsl::{events as events_table, id};
+use crate::schema::events::dsl::{
+    event_type as event_type_column, events as events_table, id,
+    move_event_type as move_event_type_column, package_id as package_id_column,
+    sender as sender_column, transaction_module as transaction_module_column,
+};
 use crate::schema::events::{event_sequence, transaction_sequence};
 use crate::utils::log_errors_to_pg;
 use crate::PgPoolConnection;
 
 use chrono::NaiveDateTime;
+use diesel::dsl::sql;
 use diesel::prelude::*;
 use diesel::result::Error;
+use diesel::sql_types::{BigInt, Bool};
+use fastcrypto::encoding::{Encoding, Hex};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 use sui_json_rpc_types::{EventPage, SuiEvent, SuiEventEnvelope};
+use sui_types::base_types::{ObjectID, SuiAddress, TransactionDigest};
+use sui_types::event::EventID;
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
 
 #[derive(Queryable, Debug)]
 pub struct Event {
@@ -22,6 +34,10 @@ pub struct Event {
     pub event_time: Option<NaiveDateTime>,
     pub event_type: String,
     pub event_content: String,
+    pub package_id: Option<String>,
+    pub transaction_module: Option<String>,
+    pub sender: Option<String>,
+    pub move_event_type: Option<String>,
 }
 
 #[derive(Debug, Insertable)]
@@ -33,6 +49,37 @@ pub struct NewEvent {
     pub event_time: Option<NaiveDateTime>,
     pub event_type: String,
     pub event_content: String,
+    pub package_id: Option<String>,
+    pub transaction_module: Option<String>,
+    pub sender: Option<String>,
+    pub move_event_type: Option<String>,
+}
+
+/// The filters of an events query, which returns the events matching all the ones set.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EventFilter {
+    /// The package emitting the event, or the one published.
+    pub package_id: Option<ObjectID>,
+    /// The module emitting the event.
+    pub module: Option<String>,
+    /// The kind of the event, e.g. `MoveEvent` or `TransferObject`.
+    pub event_type: Option<String>,
+    /// The struct type of a Move event, e.g. `0x2::devnet_nft::MintNFTEvent`.
+    pub move_event_type: Option<String>,
+    pub sender: Option<SuiAddress>,
+    /// The events of the transactions of the checkpoints from `start_checkpoint` included to
+    /// `end_checkpoint` excluded, as ingested in the checkpoints table.
+    pub start_checkpoint: Option<CheckpointSequenceNumber>,
+    pub end_checkpoint: Option<CheckpointSequenceNumber>,
+}
+
+/// A page of events in the order of their IDs, with the ID of the first event of the next page
+/// if any.
+#[derive(Debug)]
+pub struct IndexedEventPage {
+    pub data: Vec<Event>,
+    pub next_cursor: Option<EventID>,
 }
 
 pub fn read_events(
@@ -58,6 +105,159 @@ pub fn read_events(
     })
 }
 
+/// Reads at most `limit` events matching `filter`, starting at the one of ID `cursor` included.
+pub fn read_events_by_filter(
+    pg_pool_conn: &mut PgPoolConnection,
+    filter: &EventFilter,
+    cursor: Option<EventID>,
+    limit: usize,
+    descending_order: bool,
+) -> Result<IndexedEventPage, IndexerError> {
+    let event_read_result: Result<Vec<Event>, Error> = pg_pool_conn
+        .build_transaction()
+        .read_only()
+        .run::<_, Error, _>(|conn| {
+            let mut query = events_table.into_boxed();
+            if let Some(package) = filter.package_id {
+                query = query.filter(package_id_column.eq(package.to_string()));
+            }
+            if let Some(module) = &filter.module {
+                query = query.filter(transaction_module_column.eq(module.clone()));
+            }
+            if let Some(event_type) = &filter.event_type {
+                query = query.filter(event_type_column.eq(event_type.clone()));
+            }
+            if let Some(move_event_type) = &filter.move_event_type {
+                query = query.filter(move_event_type_column.eq(move_event_type.clone()));
+            }
+            if let Some(sender) = filter.sender {
+                query = query.filter(sender_column.eq(sender.to_string()));
+            }
+            if filter.start_checkpoint.is_some() || filter.end_checkpoint.is_some() {
+                // The checkpoints store the Base64 digests of their transactions, and the events
+                // the hex ones.
+                let start = filter.start_checkpoint.unwrap_or(0) as i64;
+                let end = filter.end_checkpoint.map_or(i64::MAX, |end| end as i64);
+                query = query.filter(
+                    sql::<Bool>(
+                        "transaction_digest IN (SELECT '0x' || encode(decode(digest, 'base64'), \
+                         'hex') FROM checkpoints, unnest(transactions) AS digest \
+                         WHERE sequence_number >= ",
+                    )
+                    .bind::<BigInt, _>(start)
+                    .sql(" AND sequence_number < ")
+                    .bind::<BigInt, _>(end)
+                    .sql(")"),
+                );
+            }
+            if let Some(EventID { tx_seq, event_seq }) = &cursor {
+                query = if descending_order {
+                    query.filter(
+                        transaction_sequence.lt(*tx_seq).or(transaction_sequence
+                            .eq(*tx_seq)
+                            .and(event_sequence.le(*event_seq))),
+                    )
+                } else {
+                    query.filter(
+                        transaction_sequence.gt(*tx_seq).or(transaction_sequence
+                            .eq(*tx_seq)
+                            .and(event_sequence.ge(*event_seq))),
+                    )
+                };
+            }
+            query = if descending_order {
+                query.order((transaction_sequence.desc(), event_sequence.desc()))
+            } else {
+                query.order((transaction_sequence.asc(), event_sequence.asc()))
+            };
+            // The one after the page, if any, is the next cursor.
+            query.limit(limit as i64 + 1).load::<Event>(conn)
+        });
+
+    let mut data = event_read_result.map_err(|e| {
+        IndexerError::PostgresReadError(format!(
+            "Failed reading events with filter {:?}, cursor {:?} and error: {:?}",
+            filter, cursor, e
+        ))
+    })?;
+    let next_cursor = if data.len() > limit {
+        data.pop().map(|event| EventID {
+            tx_seq: event.transaction_sequence,
+            event_seq: event.event_sequence,
+        })
+    } else {
+        None
+    };
+    Ok(IndexedEventPage { data, next_cursor })
+}
+
+/// The package, module and sender of the event, and its struct type for a Move event.
+fn event_filter_fields(
+    event: &SuiEvent,
+) -> (
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+) {
+    match event {
+        SuiEvent::MoveEvent {
+            package_id,
+            transaction_module,
+            sender,
+            type_,
+            ..
+        } => (
+            Some(package_id.to_string()),
+            Some(transaction_module.clone()),
+            Some(sender.to_string()),
+            Some(type_.clone()),
+        ),
+        SuiEvent::Publish { sender, package_id } => (
+            Some(package_id.to_string()),
+            None,
+            Some(sender.to_string()),
+            None,
+        ),
+        SuiEvent::CoinBalanceChange {
+            package_id,
+            transaction_module,
+            sender,
+            ..
+        }
+        | SuiEvent::TransferObject {
+            package_id,
+            transaction_module,
+            sender,
+            ..
+        }
+        | SuiEvent::MutateObject {
+            package_id,
+            transaction_module,
+            sender,
+            ..
+        }
+        | SuiEvent::DeleteObject {
+            package_id,
+            transaction_module,
+            sender,
+            ..
+        }
+        | SuiEvent::NewObject {
+            package_id,
+            transaction_module,
+            sender,
+            ..
+        } => (
+            Some(package_id.to_string()),
+            Some(transaction_module.clone()),
+            Some(sender.to_string()),
+            None,
+        ),
+        SuiEvent::EpochChange(_) | SuiEvent::Checkpoint(_) => (None, None, None, None),
+    }
+}
+
 // NOTE: no need to retry here b/c errors here are not transient,
 // instead we write them to PG tables for debugging purposes.
 pub fn event_to_new_event(e: SuiEventEnvelope) -> Result<NewEvent, IndexerError> {
@@ -73,6 +273,7 @@ pub fn event_to_new_event(e: SuiEventEnvelope) -> Result<NewEvent, IndexerError>
             e.timestamp
         ))
     })?;
+    let (package_id, transaction_module, sender, move_event_type) = event_filter_fields(&e.event);
     Ok(NewEvent {
         transaction_digest: e.tx_digest.map(|digest| digest.to_string()),
         transaction_sequence: e.id.tx_seq,
@@ -80,6 +281,10 @@ pub fn event_to_new_event(e: SuiEventEnvelope) -> Result<NewEvent, IndexerError>
         event_time: Some(timestamp),
         event_type: e.event.get_event_type(),
         event_content: event_json,
+        package_id,
+        transaction_module,
+        sender,
+        move_event_type,
     })
 }
 
@@ -116,6 +321,43 @@ pub fn commit_events(
     })
 }
 
+/// Converts an event read from the events table back to the envelope it was ingested from.
+pub fn event_to_sui_event_envelope(event: Event) -> Result<SuiEventEnvelope, IndexerError> {
+    let sui_event = serde_json::from_str(&event.event_content).map_err(|e| {
+        IndexerError::EventDeserializationError(format!(
+            "Failed deserializing event {:?} with error: {:?}",
+            event.event_content, e
+        ))
+    })?;
+    let tx_digest = event
+        .transaction_digest
+        .map(|digest| {
+            Hex::decode(digest.trim_start_matches("0x"))
+                .map_err(|e| format!("{:?}", e))
+                .and_then(|bytes| {
+                    TransactionDigest::try_from(bytes.as_slice()).map_err(|e| format!("{:?}", e))
+                })
+                .map_err(|e| {
+                    IndexerError::TransactionDigestParsingError(format!(
+                        "Failed parsing transaction digest {:?} with error: {}",
+                        digest, e
+                    ))
+                })
+        })
+        .transpose()?;
+    Ok(SuiEventEnvelope {
+        timestamp: event
+            .event_time
+            .map_or(0, |time| time.timestamp_millis() as u64),
+        tx_digest,
+        id: EventID {
+            tx_seq: event.transaction_sequence,
+            event_seq: event.event_sequence,
+        },
+        event: sui_event,
+    })
+}
+
 pub fn events_to_sui_events(
     pg_pool_conn: &mut PgPoolConnection,
     events: Vec<Event>,
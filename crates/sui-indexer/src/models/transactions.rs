@@ -83,6 +83,27 @@ pub fn read_transactions(
     })
 }
 
+pub fn read_transaction(
+    pg_pool_conn: &mut PgPoolConnection,
+    txn_digest: String,
+) -> Result<Transaction, IndexerError> {
+    let txn_read_result: Result<Transaction, Error> = pg_pool_conn
+        .build_transaction()
+        .read_only()
+        .run::<_, Error, _>(|conn| {
+        transactions_table
+            .filter(transaction_digest.eq(txn_digest.clone()))
+            .first::<Transaction>(conn)
+    });
+
+    txn_read_result.map_err(|e| {
+        IndexerError::PostgresReadError(format!(
+            "Failed reading transaction with digest {} and err: {:?}",
+            txn_digest, e
+        ))
+    })
+}
+
 pub fn commit_transactions(
     pg_pool_conn: &mut PgPoolConnection,
     tx_resps: Vec<SuiTransactionResponse>,
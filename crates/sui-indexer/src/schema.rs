@@ -15,6 +15,28 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    checkpoint_logs (next_cursor_sequence_number) {
+        next_cursor_sequence_number -> Int8,
+    }
+}
+
+diesel::table! {
+    checkpoints (sequence_number) {
+        sequence_number -> Int8,
+        epoch -> Int8,
+        checkpoint_digest -> Varchar,
+        content_digest -> Varchar,
+        previous_checkpoint_digest -> Nullable<Varchar>,
+        transactions -> Array<Nullable<Text>>,
+        total_computation_cost -> Int8,
+        total_storage_cost -> Int8,
+        total_storage_rebate -> Int8,
+        end_of_epoch -> Bool,
+        checkpoint_content -> Text,
+    }
+}
+
 diesel::table! {
     error_logs (id) {
         id -> Int8,
@@ -41,6 +63,10 @@ diesel::table! {
         event_time -> Nullable<Timestamp>,
         event_type -> Varchar,
         event_content -> Varchar,
+        package_id -> Nullable<Text>,
+        transaction_module -> Nullable<Text>,
+        sender -> Nullable<Varchar>,
+        move_event_type -> Nullable<Text>,
     }
 }
 
@@ -79,13 +105,6 @@ diesel::table! {
     }
 }
 
-diesel::table! {
-    transaction_logs (id) {
-        id -> Int4,
-        next_cursor_tx_digest -> Nullable<Text>,
-    }
-}
-
 diesel::table! {
     transactions (id) {
         id -> Int8,
@@ -113,6 +132,8 @@ diesel::table! {
 diesel::allow_tables_to_appear_in_same_query!(
     address_logs,
     addresses,
+    checkpoint_logs,
+    checkpoints,
     error_logs,
     event_logs,
     events,
@@ -120,6 +141,5 @@ diesel::allow_tables_to_appear_in_same_query!(
     objects,
     package_logs,
     packages,
-    transaction_logs,
     transactions,
 );
@@ -30,12 +30,12 @@ use serde_json::Value;
 use serde_with::serde_as;
 use sui_json::SuiJsonValue;
 use sui_types::base_types::{
-    AuthorityName, ObjectDigest, ObjectID, ObjectInfo, ObjectRef, SequenceNumber, SuiAddress,
-    TransactionDigest, TransactionEffectsDigest,
+    AuthorityName, ExecutionDigests, ObjectDigest, ObjectID, ObjectInfo, ObjectRef, SequenceNumber,
+    SuiAddress, TransactionDigest, TransactionEffectsDigest,
 };
 use sui_types::coin::CoinMetadata;
-use sui_types::committee::EpochId;
-use sui_types::crypto::{AuthorityStrongQuorumSignInfo, Signature};
+use sui_types::committee::{EpochId, StakeUnit};
+use sui_types::crypto::{AuthorityStrongQuorumSignInfo, AuthorityWeakQuorumSignInfo};
 use sui_types::error::SuiError;
 use sui_types::event::{BalanceChangeType, Event, EventID};
 use sui_types::event::{EventEnvelope, EventType};
@@ -48,8 +48,11 @@ use sui_types::messages::{
     SingleTransactionKind, TransactionData, TransactionEffects, TransactionKind,
     VerifiedCertificate,
 };
-use sui_types::messages_checkpoint::CheckpointSequenceNumber;
+use sui_types::messages_checkpoint::{
+    CertifiedCheckpointSummary, CheckpointContents, CheckpointSequenceNumber,
+};
 use sui_types::move_package::{disassemble_modules, MovePackage};
+use sui_types::multisig::GenericSignature;
 use sui_types::object::{
     Data, MoveObject, Object, ObjectFormatOptions, ObjectRead, Owner, PastObjectRead,
 };
@@ -64,6 +67,7 @@ pub type SuiMoveTypeParameterIndex = u16;
 pub type TransactionsPage = Page<TransactionDigest, TransactionDigest>;
 pub type EventPage = Page<SuiEventEnvelope, EventID>;
 pub type CoinPage = Page<Coin, ObjectID>;
+pub type ObjectsPage = Page<SuiObjectInfo, ObjectID>;
 
 #[derive(Serialize, Deserialize, Debug, JsonSchema)]
 #[serde(rename_all = "camelCase")]
@@ -1757,7 +1761,15 @@ pub struct SuiCertifiedTransaction {
     pub transaction_digest: TransactionDigest,
     pub data: SuiTransactionData,
     /// tx_signature is signed by the transaction sender, committing to the intent message containing the transaction data and intent.
-    pub tx_signature: Signature,
+    #[schemars(with = "Base64")]
+    pub tx_signature: GenericSignature,
+    /// gas_sponsor pays for the gas of a sponsored transaction, and owns its gas payment.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gas_sponsor: Option<SuiAddress>,
+    /// sponsor_signature is signed by the gas sponsor, committing to the same intent message as tx_signature.
+    #[schemars(with = "Option<Base64>")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sponsor_signature: Option<GenericSignature>,
     /// authority signature information, if available, is signed by an authority, applied on `data`.
     pub auth_sign_info: AuthorityStrongQuorumSignInfo,
 }
@@ -1767,6 +1779,12 @@ impl Display for SuiCertifiedTransaction {
         let mut writer = String::new();
         writeln!(writer, "Transaction Hash: {:?}", self.transaction_digest)?;
         writeln!(writer, "Transaction Signature: {:?}", self.tx_signature)?;
+        if let (Some(gas_sponsor), Some(sponsor_signature)) =
+            (&self.gas_sponsor, &self.sponsor_signature)
+        {
+            writeln!(writer, "Gas Sponsor: {}", gas_sponsor)?;
+            writeln!(writer, "Sponsor Signature: {:?}", sponsor_signature)?;
+        }
         writeln!(
             writer,
             "Signed Authorities Bitmap: {:?}",
@@ -1783,10 +1801,19 @@ impl TryFrom<CertifiedTransaction> for SuiCertifiedTransaction {
     fn try_from(cert: CertifiedTransaction) -> Result<Self, Self::Error> {
         let digest = *cert.digest();
         let (data, sig) = cert.into_data_and_sig();
+        let (gas_sponsor, sponsor_signature) = match data.gas_sponsorship {
+            Some(gas_sponsorship) => (
+                Some(gas_sponsorship.sponsor),
+                Some(gas_sponsorship.signature),
+            ),
+            None => (None, None),
+        };
         Ok(Self {
             transaction_digest: digest,
             data: data.intent_message.value.try_into()?,
             tx_signature: data.tx_signature,
+            gas_sponsor,
+            sponsor_signature,
             auth_sign_info: sig,
         })
     }
@@ -1843,6 +1870,63 @@ impl SuiCertifiedTransactionEffects {
     }
 }
 
+/// A checkpoint certified by the quorum of the validators, as streamed to the subscribers of
+/// `sui_subscribeCheckpoint`.
+#[serde_as]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename = "Checkpoint", rename_all = "camelCase")]
+pub struct SuiCheckpoint {
+    pub epoch: EpochId,
+    pub sequence_number: CheckpointSequenceNumber,
+    /// Base64 string representing the digest of the checkpoint summary.
+    #[serde_as(as = "Base64")]
+    #[schemars(with = "Base64")]
+    pub digest: Vec<u8>,
+    /// Base64 string representing the digest of the checkpoint contents.
+    #[serde_as(as = "Base64")]
+    #[schemars(with = "Base64")]
+    pub content_digest: Vec<u8>,
+    /// Base64 string representing the digest of the previous checkpoint, none for the first one.
+    #[serde_as(as = "Option<Base64>")]
+    #[schemars(with = "Option<Base64>")]
+    pub previous_digest: Option<Vec<u8>>,
+    /// The running total gas costs of all transactions included in the current epoch so far
+    /// until this checkpoint.
+    pub epoch_rolling_gas_cost_summary: GasCostSummary,
+    /// The committee of the next epoch, if this checkpoint is the last one of its epoch.
+    pub next_epoch_committee: Option<Vec<(AuthorityName, StakeUnit)>>,
+    /// authority signature information signed by the quorum of the validators.
+    pub auth_sign_info: AuthorityWeakQuorumSignInfo,
+    /// The digests of the transactions of the checkpoint and of their effects, in causal order,
+    /// when they were requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub contents: Option<Vec<ExecutionDigests>>,
+}
+
+impl SuiCheckpoint {
+    pub fn new(
+        checkpoint: CertifiedCheckpointSummary,
+        contents: Option<CheckpointContents>,
+    ) -> Self {
+        let digest = checkpoint.digest();
+        let CertifiedCheckpointSummary {
+            summary,
+            auth_signature,
+        } = checkpoint;
+        Self {
+            epoch: summary.epoch,
+            sequence_number: summary.sequence_number,
+            digest: digest.0.to_vec(),
+            content_digest: summary.content_digest.0.to_vec(),
+            previous_digest: summary.previous_digest.map(|digest| digest.0.to_vec()),
+            epoch_rolling_gas_cost_summary: summary.epoch_rolling_gas_cost_summary,
+            next_epoch_committee: summary.next_epoch_committee,
+            auth_sign_info: auth_signature,
+            contents: contents.map(CheckpointContents::into_inner),
+        }
+    }
+}
+
 /// The response from processing a transaction or a certified transaction
 #[derive(Eq, PartialEq, Clone, Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(rename = "TransactionEffects", rename_all = "camelCase")]
@@ -2243,6 +2327,27 @@ impl TryFrom<SuiEvent> for Event {
     }
 }
 
+impl TryFrom<SuiEventEnvelope> for EventEnvelope {
+    type Error = anyhow::Error;
+    fn try_from(envelope: SuiEventEnvelope) -> Result<Self, Self::Error> {
+        let move_struct_json_value = match &envelope.event {
+            SuiEvent::MoveEvent {
+                fields: Some(fields),
+                ..
+            } => Some(fields.clone().to_json_value()?),
+            _ => None,
+        };
+        Ok(EventEnvelope::new(
+            envelope.timestamp,
+            envelope.tx_digest,
+            envelope.id.tx_seq as u64,
+            envelope.id.event_seq as u64,
+            envelope.event.try_into()?,
+            move_struct_json_value,
+        ))
+    }
+}
+
 impl SuiEvent {
     pub fn try_from(event: Event, resolver: &impl GetModule) -> Result<Self, anyhow::Error> {
         Ok(match event {
@@ -2735,11 +2840,17 @@ impl From<SuiTransactionFilter> for TransactionFilter {
     }
 }
 
-#[derive(Serialize, Deserialize, JsonSchema, Debug)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
 #[serde(rename = "EventFilter")]
 pub enum SuiEventFilter {
     Package(ObjectID),
     Module(String),
+    /// Events emitted by the module of the given package, unlike `Module` which matches the
+    /// modules of that name in any package
+    MoveModule {
+        package: ObjectID,
+        module: String,
+    },
     /// Move StructTag string value of the event type e.g. `0x2::devnet_nft::MintNFTEvent`
     MoveEventType(String),
     MoveEventField {
@@ -2763,6 +2874,10 @@ impl TryInto<EventFilter> for SuiEventFilter {
         Ok(match self {
             Package(id) => EventFilter::Package(id),
             Module(module) => EventFilter::Module(Identifier::new(module)?),
+            MoveModule { package, module } => EventFilter::MatchAll(vec![
+                EventFilter::Package(package),
+                EventFilter::Module(Identifier::new(module)?),
+            ]),
             MoveEventType(event_type) => {
                 // parse_sui_struct_tag converts StructTag string e.g. `0x2::devnet_nft::MintNFTEvent` to StructTag object
                 EventFilter::MoveEventType(parse_sui_struct_tag(&event_type)?)
@@ -2824,5 +2939,10 @@ impl TransactionBytes {
 #[serde(rename_all = "camelCase")]
 pub struct Page<T, C> {
     pub data: Vec<T>,
+    /// The cursor of the first item of the next page, to query it with.
     pub next_cursor: Option<C>,
+    /// Whether there are more items after this page, false when read from a server that doesn't
+    /// report it.
+    #[serde(default)]
+    pub has_next_page: bool,
 }
@@ -15,7 +15,7 @@ use sui_types::gas_coin::GasCoin;
 use sui_types::object::MoveObject;
 use sui_types::{MOVE_STDLIB_ADDRESS, SUI_FRAMEWORK_ADDRESS};
 
-use crate::{SuiMoveStruct, SuiMoveValue};
+use crate::{Page, SuiMoveStruct, SuiMoveValue};
 
 #[test]
 fn test_move_value_to_sui_coin() {
@@ -143,3 +143,11 @@ fn test_serde() {
         )
     }
 }
+
+#[test]
+fn test_page_without_has_next_page() {
+    let page: Page<u64, u64> = serde_json::from_str(r#"{"data":[1,2],"nextCursor":3}"#).unwrap();
+    assert_eq!(page.data, vec![1, 2]);
+    assert_eq!(page.next_cursor, Some(3));
+    assert!(!page.has_next_page);
+}
@@ -11,11 +11,12 @@ use fastcrypto::encoding::Base64;
 use sui_json::SuiJsonValue;
 use sui_json_rpc_types::{
     Balance, CoinPage, EventPage, GetObjectDataResponse, GetPastObjectDataResponse,
-    GetRawObjectDataResponse, MoveFunctionArgType, RPCTransactionRequestParams, SuiCoinMetadata,
-    SuiEventEnvelope, SuiEventFilter, SuiExecuteTransactionResponse, SuiMoveNormalizedFunction,
-    SuiMoveNormalizedModule, SuiMoveNormalizedStruct, SuiObjectInfo,
-    SuiTransactionAuthSignersResponse, SuiTransactionEffects, SuiTransactionFilter,
-    SuiTransactionResponse, SuiTypeTag, TransactionBytes, TransactionsPage,
+    GetRawObjectDataResponse, MoveFunctionArgType, ObjectsPage, RPCTransactionRequestParams,
+    SuiCheckpoint, SuiCoinMetadata, SuiEventEnvelope, SuiEventFilter,
+    SuiExecuteTransactionResponse, SuiMoveNormalizedFunction, SuiMoveNormalizedModule,
+    SuiMoveNormalizedStruct, SuiObjectInfo, SuiTransactionAuthSignersResponse,
+    SuiTransactionEffects, SuiTransactionFilter, SuiTransactionResponse, SuiTypeTag,
+    TransactionBytes, TransactionsPage,
 };
 use sui_open_rpc_macros::open_rpc;
 use sui_types::balance::Supply;
@@ -25,7 +26,8 @@ use sui_types::committee::EpochId;
 use sui_types::crypto::SignatureScheme;
 use sui_types::event::EventID;
 use sui_types::messages::CommitteeInfoResponse;
-use sui_types::messages::ExecuteTransactionRequestType;
+use sui_types::messages::{ExecuteTransactionRequestType, QuorumDriverRetryPolicy};
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
 use sui_types::query::{EventQuery, TransactionQuery};
 
 /// Maximum number of events returned in an event query.
@@ -82,6 +84,9 @@ pub trait CoinReadApi {
 #[rpc(server, client, namespace = "sui")]
 pub trait RpcReadApi {
     /// Return the list of objects owned by an address.
+    /// Deprecated, the list of the objects of an address owning many of them may not fit in a
+    /// response: use `sui_getOwnedObjects` to query it page by page instead.
+    #[deprecated]
     #[method(name = "getObjectsOwnedByAddress")]
     async fn get_objects_owned_by_address(
         &self,
@@ -89,6 +94,18 @@ pub trait RpcReadApi {
         address: SuiAddress,
     ) -> RpcResult<Vec<SuiObjectInfo>>;
 
+    /// Return the page of the objects owned by an address, in ascending order of object ID.
+    #[method(name = "getOwnedObjects")]
+    async fn get_owned_objects(
+        &self,
+        /// the owner's Sui address
+        address: SuiAddress,
+        /// optional paging cursor, the ID of the first object of the page
+        cursor: Option<ObjectID>,
+        /// maximum number of items per page
+        limit: Option<usize>,
+    ) -> RpcResult<ObjectsPage>;
+
     /// Return the list of objects owned by an object.
     #[method(name = "getObjectsOwnedByObject")]
     async fn get_objects_owned_by_object(
@@ -423,6 +440,20 @@ pub trait RpcTransactionBuilder {
         /// the gas budget, the transaction will fail if the gas cost exceed the budget
         gas_budget: u64,
     ) -> RpcResult<TransactionBytes>;
+
+    /// Pay for the gas of an unsigned transaction of another address, replacing its gas object
+    /// by one of the sponsor. Both the sender and the sponsor sign the returned transaction, which
+    /// is executed with `sui_executeSponsoredTransaction`.
+    #[method(name = "sponsorTransaction")]
+    async fn sponsor_transaction(
+        &self,
+        /// BCS serialized transaction data bytes without its type tag, as base-64 encoded string.
+        tx_bytes: Base64,
+        /// the gas sponsor's Sui address
+        gas_sponsor: SuiAddress,
+        /// gas object of the sponsor to be used in this transaction, node will pick one from the sponsor's possession if not provided
+        gas: Option<ObjectID>,
+    ) -> RpcResult<TransactionBytes>;
 }
 
 #[open_rpc(namespace = "sui", tag = "BCS API")]
@@ -449,6 +480,20 @@ pub trait TransactionStreamingApi {
     );
 }
 
+#[open_rpc(namespace = "sui", tag = "Checkpoint Subscription")]
+#[rpc(server, client, namespace = "sui")]
+pub trait CheckpointStreamingApi {
+    /// Subscribe to the stream of the checkpoints certified by the network, in order of sequence number
+    #[subscription(name = "subscribeCheckpoint", item = SuiCheckpoint)]
+    fn subscribe_checkpoint(
+        &self,
+        /// the sequence number of the first checkpoint to stream, e.g. the one following the last checkpoint received to resume a stream, default to the next checkpoint to be certified.
+        from_sequence_number: Option<CheckpointSequenceNumber>,
+        /// whether to include the digests of the transactions of every checkpoint and of their effects, default to false.
+        with_contents: Option<bool>,
+    );
+}
+
 #[open_rpc(namespace = "sui", tag = "Event Subscription")]
 #[rpc(server, client, namespace = "sui")]
 pub trait EventStreamingApi {
@@ -458,6 +503,8 @@ pub trait EventStreamingApi {
         &self,
         /// the filter criteria of the event stream, see the [Sui docs](https://docs.sui.io/build/pubsub#event-filters) for detailed examples.
         filter: SuiEventFilter,
+        /// the ID of the last event received, to resume a stream with the events emitted after it, default to the events emitted from now on.
+        cursor: Option<EventID>,
     );
 }
 
@@ -496,6 +543,9 @@ pub trait TransactionExecutionApi {
     ///     makes sure this node is aware of this transaction when client fires subsequent queries.
     ///     However if the node fails to execute the transaction locally in a timely manner,
     ///     a bool type in the response is set to false to indicated the case.
+    ///
+    /// The steps that fail because of the validators rather than the transaction, e.g. on
+    /// timeouts, are retried as set by the retry policy, or the default one of the node if unset.
     // TODO(joyqvq): remove this and rename executeTransactionSerializedSig to executeTransaction
     #[method(name = "executeTransaction")]
     async fn execute_transaction(
@@ -510,6 +560,8 @@ pub trait TransactionExecutionApi {
         pub_key: Base64,
         /// The request type.
         request_type: ExecuteTransactionRequestType,
+        /// The retry policy, the default one of the node if unset.
+        retry_policy: Option<QuorumDriverRetryPolicy>,
     ) -> RpcResult<SuiExecuteTransactionResponse>;
 
     #[method(name = "executeTransactionSerializedSig")]
@@ -518,9 +570,33 @@ pub trait TransactionExecutionApi {
         /// BCS serialized transaction data bytes without its type tag, as base-64 encoded string.
         tx_bytes: Base64,
         /// `flag || signature || pubkey` bytes, as base-64 encoded string, signature is committed to the intent message of the transaction data, as base-64 encoded string.
+        /// A multisig is its flag followed by its BCS serialized bytes.
+        signature: Base64,
+        /// The request type
+        request_type: ExecuteTransactionRequestType,
+        /// The retry policy, the default one of the node if unset.
+        retry_policy: Option<QuorumDriverRetryPolicy>,
+    ) -> RpcResult<SuiExecuteTransactionResponse>;
+
+    /// Execute a transaction whose gas is paid by a sponsor, another address than the sender that
+    /// owns the gas object and signs the transaction too. The request types are the ones of
+    /// `sui_executeTransaction`.
+    #[method(name = "executeSponsoredTransaction")]
+    async fn execute_sponsored_transaction(
+        &self,
+        /// BCS serialized transaction data bytes without its type tag, as base-64 encoded string.
+        tx_bytes: Base64,
+        /// `flag || signature || pubkey` bytes of the sender, as base-64 encoded string, signature is committed to the intent message of the transaction data.
+        /// A multisig is its flag followed by its BCS serialized bytes.
         signature: Base64,
+        /// The gas sponsor's Sui address, the owner of the gas object.
+        gas_sponsor: SuiAddress,
+        /// `flag || signature || pubkey` bytes of the gas sponsor, as base-64 encoded string, signature is committed to the same intent message.
+        sponsor_signature: Base64,
         /// The request type
         request_type: ExecuteTransactionRequestType,
+        /// The retry policy, the default one of the node if unset.
+        retry_policy: Option<QuorumDriverRetryPolicy>,
     ) -> RpcResult<SuiExecuteTransactionResponse>;
 }
 
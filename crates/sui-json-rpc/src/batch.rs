@@ -0,0 +1,115 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::error::Error;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use hyper::service::Service;
+use hyper::{body, http, Body, Request, Response};
+use jsonrpsee::core::__reexports::serde_json;
+use serde::de::IgnoredAny;
+use tower::Layer;
+
+use crate::metrics::is_json;
+
+/// The number of requests a batch can have if `RPC_MAX_BATCH_SIZE` is not set.
+pub const DEFAULT_MAX_BATCH_SIZE: usize = 50;
+
+/// The JSON-RPC error code of a batch with too many requests, in the range reserved for the
+/// errors of the server.
+pub const BATCH_TOO_LARGE_CODE: i32 = -32010;
+
+/// Rejects the batches of more than `max_batch_size` JSON-RPC requests sent over HTTP, before
+/// any of the requests is called. The batches sent over a websocket are not limited.
+#[derive(Debug, Clone)]
+pub struct BatchLimitLayer {
+    max_batch_size: usize,
+}
+
+impl BatchLimitLayer {
+    pub fn new(max_batch_size: usize) -> Self {
+        Self { max_batch_size }
+    }
+}
+
+impl<S> Layer<S> for BatchLimitLayer {
+    type Service = BatchLimitService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        BatchLimitService {
+            inner,
+            max_batch_size: self.max_batch_size,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BatchLimitService<S> {
+    inner: S,
+    max_batch_size: usize,
+}
+
+impl<S> Service<Request<Body>> for BatchLimitService<S>
+where
+    S: Service<Request<Body>, Response = Response<Body>> + Clone + Send + 'static,
+    S::Response: 'static,
+    S::Error: Into<Box<dyn Error + Send + Sync>> + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = Box<dyn Error + Send + Sync + 'static>;
+    type Future =
+        Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send + 'static>>;
+
+    #[inline]
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, req: Request<Body>) -> Self::Future {
+        let mut inner = self.inner.clone();
+        let max_batch_size = self.max_batch_size;
+
+        Box::pin(async move {
+            if !is_json(req.headers().get(http::header::CONTENT_TYPE)) {
+                return inner.call(req).await.map_err(Into::into);
+            }
+            let (part, body) = req.into_parts();
+            let bytes = body::to_bytes(body).await?;
+            // The requests of a batch are not parsed, which is left to the server.
+            let batch_size = serde_json::from_slice::<Vec<IgnoredAny>>(&bytes)
+                .ok()
+                .map(|batch| batch.len());
+            match batch_size {
+                Some(batch_size) if batch_size > max_batch_size => {
+                    Ok(batch_too_large(batch_size, max_batch_size)?)
+                }
+                _ => inner
+                    .call(Request::from_parts(part, Body::from(bytes)))
+                    .await
+                    .map_err(Into::into),
+            }
+        })
+    }
+}
+
+fn batch_too_large(
+    batch_size: usize,
+    max_batch_size: usize,
+) -> Result<Response<Body>, http::Error> {
+    let error = serde_json::json!({
+        "jsonrpc": "2.0",
+        "error": {
+            "code": BATCH_TOO_LARGE_CODE,
+            "message": format!(
+                "The batch has {batch_size} requests, more than the limit of {max_batch_size}"
+            ),
+        },
+        "id": null,
+    });
+    Response::builder()
+        .header(http::header::CONTENT_TYPE, "application/json")
+        .body(Body::from(error.to_string()))
+}
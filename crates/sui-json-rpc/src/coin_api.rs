@@ -63,7 +63,7 @@ impl CoinReadApi {
     ) -> Result<impl Iterator<Item = ObjectID> + '_, Error> {
         Ok(self
             .state
-            .get_owner_objects_iterator(Owner::AddressOwner(owner))?
+            .get_owner_objects_iterator(Owner::AddressOwner(owner), None)?
             .filter(move |o| matches!(&o.type_, ObjectType::Struct(type_) if is_coin_type(type_, coin_type)))
             .map(|info|info.object_id))
     }
@@ -143,7 +143,12 @@ impl CoinReadApiServer for CoinReadApi {
                 balance: coin.balance.value(),
             })
         }
-        Ok(CoinPage { data, next_cursor })
+        let has_next_page = next_cursor.is_some();
+        Ok(CoinPage {
+            data,
+            next_cursor,
+            has_next_page,
+        })
     }
 
     async fn get_balances(
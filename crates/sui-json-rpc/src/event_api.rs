@@ -3,7 +3,7 @@
 use std::sync::Arc;
 
 use async_trait::async_trait;
-use futures::StreamExt;
+use futures::{future, stream, Stream, StreamExt};
 use jsonrpsee::core::RpcResult;
 use jsonrpsee::types::SubscriptionResult;
 use jsonrpsee::{RpcModule, SubscriptionSink};
@@ -14,6 +14,7 @@ use sui_core::event_handler::EventHandler;
 use sui_json_rpc_types::{EventPage, SuiEvent, SuiEventEnvelope, SuiEventFilter};
 use sui_open_rpc::Module;
 use sui_types::event::{EventEnvelope, EventID};
+use sui_types::filter::{EventFilter, Filter};
 use sui_types::query::EventQuery;
 
 use crate::api::EventReadApiServer;
@@ -21,6 +22,9 @@ use crate::api::{cap_page_limit, EventStreamingApiServer};
 use crate::streaming_api::spawn_subscription;
 use crate::SuiRpcModule;
 
+/// The number of stored events read at a time to resume a subscription from a cursor.
+const STORED_EVENTS_PAGE_SIZE: usize = 100;
+
 pub struct EventStreamingApiImpl {
     state: Arc<AuthorityState>,
     event_handler: Arc<EventHandler>,
@@ -41,8 +45,9 @@ impl EventStreamingApiServer for EventStreamingApiImpl {
         &self,
         mut sink: SubscriptionSink,
         filter: SuiEventFilter,
+        cursor: Option<EventID>,
     ) -> SubscriptionResult {
-        let filter = match filter.try_into() {
+        let filter: EventFilter = match filter.try_into() {
             Ok(filter) => filter,
             Err(e) => {
                 let e = jsonrpsee::core::Error::from(e);
@@ -51,22 +56,104 @@ impl EventStreamingApiServer for EventStreamingApiImpl {
             }
         };
 
+        // Subscribing before reading the stored events, so that no event emitted in between is
+        // missed. The events both stored and streamed are only sent once, and so is the cursor.
         let state = self.state.clone();
-        let stream = self.event_handler.subscribe(filter);
-        let stream = stream.map(move |e: EventEnvelope| {
-            let event = SuiEvent::try_from(e.event, state.module_cache.as_ref());
-            event.map(|event| SuiEventEnvelope {
-                timestamp: e.timestamp,
-                tx_digest: e.tx_digest,
-                id: EventID::from((e.seq_num as i64, e.event_num as i64)),
-                event,
-            })
+        let live = self.event_handler.subscribe(filter.clone()).map(
+            move |e| -> Result<_, anyhow::Error> {
+                let event = SuiEvent::try_from(e.event, state.module_cache.as_ref())?;
+                Ok(SuiEventEnvelope {
+                    timestamp: e.timestamp,
+                    tx_digest: e.tx_digest,
+                    id: EventID::from((e.seq_num as i64, e.event_num as i64)),
+                    event,
+                })
+            },
+        );
+        let stored = match &cursor {
+            Some(cursor) => stored_events(self.state.clone(), filter, cursor.clone()).left_stream(),
+            None => stream::empty().right_stream(),
+        };
+        let mut last_sent = cursor.map(|cursor| (cursor.tx_seq, cursor.event_seq));
+        let stream = stored.chain(live).filter(move |e| {
+            let sent = match e {
+                Ok(e) => {
+                    let id = (e.id.tx_seq, e.id.event_seq);
+                    let sent = last_sent.map_or(false, |last_sent| id <= last_sent);
+                    if !sent {
+                        last_sent = Some(id);
+                    }
+                    sent
+                }
+                Err(_) => false,
+            };
+            future::ready(!sent)
         });
-        spawn_subscription(sink, stream);
+        spawn_subscription(sink, Box::pin(stream));
         Ok(())
     }
 }
 
+/// Streams the stored events matching `filter` from `cursor` on, in order, reading them from the
+/// event store a page at a time. The stream fails on the first page that can't be read.
+fn stored_events(
+    state: Arc<AuthorityState>,
+    filter: EventFilter,
+    cursor: EventID,
+) -> impl Stream<Item = Result<SuiEventEnvelope, anyhow::Error>> {
+    // The filters the event store is indexed by are queried as is, the others are matched against
+    // all the events from the cursor on.
+    let (query, filter) = match event_query(&filter) {
+        Some(query) => (query, None),
+        None => (EventQuery::All, Some(filter)),
+    };
+    stream::unfold(Some(cursor), move |cursor| {
+        let state = state.clone();
+        let query = query.clone();
+        let filter = filter.clone();
+        async move {
+            let cursor = cursor?;
+            // Retrieve 1 extra item for next cursor
+            let mut events = match state
+                .get_events(query, Some(cursor), STORED_EVENTS_PAGE_SIZE + 1, false)
+                .await
+            {
+                Ok(events) => events,
+                Err(e) => return Some((vec![Err(e)], None)),
+            };
+            let next_cursor = if events.len() > STORED_EVENTS_PAGE_SIZE {
+                events.pop().map(|(id, _)| id)
+            } else {
+                None
+            };
+            let events = events
+                .into_iter()
+                .map(|(_, e)| e)
+                .filter(|e| match &filter {
+                    Some(filter) => EventEnvelope::try_from(e.clone())
+                        .map_or(false, |envelope| filter.matches(&envelope)),
+                    None => true,
+                })
+                .map(Ok)
+                .collect::<Vec<_>>();
+            Some((events, next_cursor))
+        }
+    })
+    .flat_map(stream::iter)
+}
+
+/// The query of the event store that returns exactly the events matched by `filter`, if any.
+fn event_query(filter: &EventFilter) -> Option<EventQuery> {
+    Some(match filter {
+        EventFilter::MoveEventType(struct_tag) => EventQuery::MoveEvent(struct_tag.to_string()),
+        EventFilter::EventType(event_type) => EventQuery::EventType(event_type.clone()),
+        EventFilter::SenderAddress(sender) => EventQuery::Sender(*sender),
+        EventFilter::Recipient(recipient) => EventQuery::Recipient(*recipient),
+        EventFilter::ObjectId(object_id) => EventQuery::Object(*object_id),
+        _ => return None,
+    })
+}
+
 impl SuiRpcModule for EventStreamingApiImpl {
     fn rpc(self) -> RpcModule<Self> {
         self.into_rpc()
@@ -112,7 +199,12 @@ impl EventReadApiServer for EventReadApiImpl {
         let next_cursor = data.get(limit).map(|(id, _)| id.clone());
         data.truncate(limit);
         let data = data.into_iter().map(|(_, event)| event).collect();
-        Ok(EventPage { data, next_cursor })
+        let has_next_page = next_cursor.is_some();
+        Ok(EventPage {
+            data,
+            next_cursor,
+            has_next_page,
+        })
     }
 }
 
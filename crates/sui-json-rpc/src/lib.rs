@@ -18,9 +18,11 @@ use tracing::{info, warn};
 
 use sui_open_rpc::{Module, Project};
 
+use crate::batch::{BatchLimitLayer, DEFAULT_MAX_BATCH_SIZE};
 use crate::metrics::MetricsLayer;
 
 pub mod api;
+pub mod batch;
 pub mod bcs_api;
 pub mod coin_api;
 pub mod error;
@@ -108,9 +110,20 @@ impl JsonRpcServerBuilder {
         let metrics_layer = option_layer(self.registry.map(|registry| {
             MetricsLayer::new(&registry, &methods_names);
         }));
+
+        let max_batch_size = env::var("RPC_MAX_BATCH_SIZE")
+            .ok()
+            .and_then(|o| {
+                usize::from_str(&o)
+                    .tap_err(|e| warn!("Cannot parse RPC_MAX_BATCH_SIZE to usize: {e}"))
+                    .ok()
+            })
+            .unwrap_or(DEFAULT_MAX_BATCH_SIZE);
+
         let middleware = tower::ServiceBuilder::new()
             .layer(cors)
-            .layer(metrics_layer);
+            .layer(metrics_layer)
+            .layer(BatchLimitLayer::new(max_batch_size));
 
         let max_connection = env::var("RPC_MAX_CONNECTION")
             .ok()
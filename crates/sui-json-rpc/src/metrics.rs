@@ -123,8 +123,8 @@ where
         let whitelist = self.method_whitelist.clone();
 
         let res_fut = async move {
-            // Parse request to retrieve RPC method name.
-            let (rpc_name, req) = if is_json(req.headers().get(http::header::CONTENT_TYPE)) {
+            // Parse request to retrieve RPC method names, one per request of a batch.
+            let (rpc_names, req) = if is_json(req.headers().get(http::header::CONTENT_TYPE)) {
                 let (part, body) = req.into_parts();
                 let bytes = body::to_bytes(body).await?;
                 #[derive(Deserialize)]
@@ -132,20 +132,24 @@ where
                     method: String,
                 }
 
-                let name = serde_json::from_slice::<RPCRequest>(&bytes)
-                    .ok()
-                    .map(|rpc| rpc.method);
+                let names = serde_json::from_slice::<RPCRequest>(&bytes)
+                    .map(|rpc| vec![rpc.method])
+                    .or_else(|_| {
+                        serde_json::from_slice::<Vec<RPCRequest>>(&bytes)
+                            .map(|batch| batch.into_iter().map(|rpc| rpc.method).collect())
+                    })
+                    .unwrap_or_default();
 
-                (name, Request::from_parts(part, Body::from(bytes)))
+                (names, Request::from_parts(part, Body::from(bytes)))
             } else {
-                (None, req)
+                (vec![], req)
             };
 
             let fut = inner.call(req);
             let res = fut.await.map_err(|err| err.into())?;
 
             // Record metrics if the request is a http RPC request.
-            if let Some(name) = rpc_name {
+            for name in rpc_names {
                 if whitelist.contains(&name) {
                     let req_latency_secs = (Instant::now() - started_at).as_secs_f64();
                     metrics.requests_by_route.with_label_values(&[&name]).inc();
@@ -171,7 +175,7 @@ where
     }
 }
 
-fn is_json(content_type: Option<&hyper::header::HeaderValue>) -> bool {
+pub(crate) fn is_json(content_type: Option<&hyper::header::HeaderValue>) -> bool {
     content_type
         .and_then(|val| val.to_str().ok())
         .map_or(false, |content| {
@@ -16,10 +16,10 @@ use fastcrypto::encoding::Base64;
 use jsonrpsee::RpcModule;
 use sui_core::authority::AuthorityState;
 use sui_json_rpc_types::{
-    GetObjectDataResponse, GetPastObjectDataResponse, MoveFunctionArgType, ObjectValueKind, Page,
-    SuiMoveNormalizedFunction, SuiMoveNormalizedModule, SuiMoveNormalizedStruct, SuiObjectInfo,
-    SuiTransactionAuthSignersResponse, SuiTransactionEffects, SuiTransactionResponse,
-    TransactionsPage,
+    GetObjectDataResponse, GetPastObjectDataResponse, MoveFunctionArgType, ObjectValueKind,
+    ObjectsPage, Page, SuiMoveNormalizedFunction, SuiMoveNormalizedModule, SuiMoveNormalizedStruct,
+    SuiObjectInfo, SuiTransactionAuthSignersResponse, SuiTransactionEffects,
+    SuiTransactionResponse, TransactionsPage,
 };
 use sui_open_rpc::Module;
 use sui_types::base_types::SequenceNumber;
@@ -75,6 +75,32 @@ impl RpcReadApiServer for ReadApi {
             .collect())
     }
 
+    async fn get_owned_objects(
+        &self,
+        address: SuiAddress,
+        cursor: Option<ObjectID>,
+        limit: Option<usize>,
+    ) -> RpcResult<ObjectsPage> {
+        let limit = cap_page_limit(limit)?;
+        // Retrieve 1 extra item for next cursor
+        let mut data = self
+            .state
+            .get_owner_objects_iterator(Owner::AddressOwner(address), cursor)
+            .map_err(|e| anyhow!("{e}"))?
+            .take(limit + 1)
+            .map(SuiObjectInfo::from)
+            .collect::<Vec<_>>();
+
+        let next_cursor = data.get(limit).map(|info| info.object_id);
+        data.truncate(limit);
+        let has_next_page = next_cursor.is_some();
+        Ok(ObjectsPage {
+            data,
+            next_cursor,
+            has_next_page,
+        })
+    }
+
     async fn get_objects_owned_by_object(
         &self,
         object_id: ObjectID,
@@ -306,7 +332,12 @@ impl RpcFullNodeReadApiServer for FullNodeApi {
         // extract next cursor
         let next_cursor = data.get(limit).cloned();
         data.truncate(limit);
-        Ok(Page { data, next_cursor })
+        let has_next_page = next_cursor.is_some();
+        Ok(Page {
+            data,
+            next_cursor,
+            has_next_page,
+        })
     }
 
     async fn try_get_past_object(
@@ -1,9 +1,10 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
-use crate::api::TransactionStreamingApiServer;
+use crate::api::{CheckpointStreamingApiServer, TransactionStreamingApiServer};
 use crate::SuiRpcModule;
+use anyhow::anyhow;
 use async_trait::async_trait;
-use futures::{StreamExt, TryStream};
+use futures::{stream, Stream, StreamExt, TryStream};
 use jsonrpsee::core::error::SubscriptionClosed;
 use jsonrpsee::types::SubscriptionResult;
 use jsonrpsee::{RpcModule, SubscriptionSink};
@@ -12,13 +13,18 @@ use serde::Serialize;
 use std::fmt::Display;
 use std::sync::Arc;
 use sui_core::authority::AuthorityState;
+use sui_core::checkpoints::CheckpointStore;
 use sui_core::transaction_streamer::TransactionStreamer;
 use sui_json_rpc_types::SuiCertifiedTransaction;
+use sui_json_rpc_types::SuiCheckpoint;
 use sui_json_rpc_types::SuiTransactionEffects;
 use sui_json_rpc_types::SuiTransactionFilter;
 use sui_json_rpc_types::SuiTransactionResponse;
+use sui_network::state_sync;
 use sui_open_rpc::Module;
 use sui_types::filter::TransactionFilter;
+use sui_types::messages_checkpoint::{CheckpointSequenceNumber, VerifiedCheckpoint};
+use tokio::sync::broadcast::{self, error::RecvError};
 use tracing::warn;
 
 pub struct TransactionStreamingApiImpl {
@@ -80,6 +86,132 @@ impl SuiRpcModule for TransactionStreamingApiImpl {
     }
 }
 
+pub struct CheckpointStreamingApiImpl {
+    checkpoint_store: Arc<CheckpointStore>,
+    state_sync: Option<state_sync::Handle>,
+}
+
+impl CheckpointStreamingApiImpl {
+    pub fn new(checkpoint_store: Arc<CheckpointStore>, state_sync: state_sync::Handle) -> Self {
+        Self {
+            checkpoint_store,
+            state_sync: Some(state_sync),
+        }
+    }
+
+    /// Streams the checkpoints of a store which no checkpoint is synced to anymore, like the one
+    /// of a read-only node: the streams end after the highest synced checkpoint of the store.
+    pub fn new_without_state_sync(checkpoint_store: Arc<CheckpointStore>) -> Self {
+        Self {
+            checkpoint_store,
+            state_sync: None,
+        }
+    }
+}
+
+#[async_trait]
+impl CheckpointStreamingApiServer for CheckpointStreamingApiImpl {
+    fn subscribe_checkpoint(
+        &self,
+        mut sink: SubscriptionSink,
+        from_sequence_number: Option<CheckpointSequenceNumber>,
+        with_contents: Option<bool>,
+    ) -> SubscriptionResult {
+        // Subscribing before reading the watermark, so that no checkpoint synced in between is
+        // missed.
+        let synced = match &self.state_sync {
+            Some(state_sync) => state_sync.subscribe_to_synced_checkpoints(),
+            // A receiver whose sender is dropped, as no checkpoint will be synced.
+            None => broadcast::channel(1).1,
+        };
+        let next = match from_sequence_number {
+            Some(sequence_number) => sequence_number,
+            None => match self
+                .checkpoint_store
+                .get_highest_synced_checkpoint_seq_number()
+            {
+                Ok(highest) => highest.map_or(0, |highest| highest + 1),
+                Err(e) => {
+                    let e = jsonrpsee::core::Error::from(anyhow::Error::from(e));
+                    warn!(error = ?e, "Rejecting subscription request.");
+                    return Ok(sink.reject(e)?);
+                }
+            },
+        };
+        let stream = checkpoint_stream(
+            self.checkpoint_store.clone(),
+            synced,
+            next,
+            with_contents.unwrap_or(false),
+        );
+        spawn_subscription(sink, Box::pin(stream));
+
+        Ok(())
+    }
+}
+
+impl SuiRpcModule for CheckpointStreamingApiImpl {
+    fn rpc(self) -> RpcModule<Self> {
+        self.into_rpc()
+    }
+
+    fn rpc_doc_module() -> Module {
+        crate::api::CheckpointStreamingApiOpenRpc::module_doc()
+    }
+}
+
+/// Streams the checkpoints from `next` on, without gaps, reading them from the store as the
+/// sink takes them: a subscriber catching up from an old checkpoint, or slower than the network,
+/// doesn't buffer more than a checkpoint on the node. The synced checkpoints broadcast by state
+/// sync only wake the stream up once it reached the highest synced one, so lagging behind the
+/// broadcast loses none of them. The stream fails on the first checkpoint no longer in the store.
+fn checkpoint_stream(
+    checkpoint_store: Arc<CheckpointStore>,
+    synced: broadcast::Receiver<VerifiedCheckpoint>,
+    next: CheckpointSequenceNumber,
+    with_contents: bool,
+) -> impl Stream<Item = Result<SuiCheckpoint, anyhow::Error>> {
+    stream::unfold(
+        (checkpoint_store, synced, next),
+        move |(checkpoint_store, mut synced, next)| async move {
+            loop {
+                let highest = match checkpoint_store.get_highest_synced_checkpoint_seq_number() {
+                    Ok(highest) => highest,
+                    Err(e) => return Some((Err(e.into()), (checkpoint_store, synced, next))),
+                };
+                if highest.map_or(false, |highest| highest >= next) {
+                    let checkpoint = read_checkpoint(&checkpoint_store, next, with_contents);
+                    return Some((checkpoint, (checkpoint_store, synced, next + 1)));
+                }
+                match synced.recv().await {
+                    Ok(_) | Err(RecvError::Lagged(_)) => (),
+                    // State sync stopped, no checkpoint will be synced anymore.
+                    Err(RecvError::Closed) => return None,
+                }
+            }
+        },
+    )
+}
+
+fn read_checkpoint(
+    checkpoint_store: &CheckpointStore,
+    sequence_number: CheckpointSequenceNumber,
+    with_contents: bool,
+) -> Result<SuiCheckpoint, anyhow::Error> {
+    let checkpoint = checkpoint_store
+        .get_checkpoint_by_sequence_number(sequence_number)?
+        .ok_or_else(|| anyhow!("Checkpoint {sequence_number} is not available"))?;
+    let contents = if with_contents {
+        let contents = checkpoint_store
+            .get_checkpoint_contents(&checkpoint.content_digest())?
+            .ok_or_else(|| anyhow!("Contents of checkpoint {sequence_number} are not available"))?;
+        Some(contents)
+    } else {
+        None
+    };
+    Ok(SuiCheckpoint::new(checkpoint.into_inner(), contents))
+}
+
 pub fn spawn_subscription<S, T, E>(mut sink: SubscriptionSink, rx: S)
 where
     S: TryStream<Ok = T, Error = E> + Unpin + Send + 'static,
@@ -3,6 +3,7 @@
 
 use crate::api::RpcTransactionBuilderServer;
 use crate::SuiRpcModule;
+use anyhow::anyhow;
 use async_trait::async_trait;
 use jsonrpsee::core::RpcResult;
 use std::sync::Arc;
@@ -244,6 +245,18 @@ impl RpcTransactionBuilderServer for FullNodeTransactionBuilderApi {
             .await?;
         Ok(TransactionBytes::from_data(data)?)
     }
+
+    async fn sponsor_transaction(
+        &self,
+        tx_bytes: Base64,
+        gas_sponsor: SuiAddress,
+        gas: Option<ObjectID>,
+    ) -> RpcResult<TransactionBytes> {
+        let tx_data =
+            bcs::from_bytes(&tx_bytes.to_vec().map_err(|e| anyhow!(e))?).map_err(|e| anyhow!(e))?;
+        let data = self.builder.sponsor(tx_data, gas_sponsor, gas).await?;
+        Ok(TransactionBytes::from_data(data)?)
+    }
 }
 
 impl SuiRpcModule for FullNodeTransactionBuilderApi {
@@ -17,9 +17,14 @@ use sui_core::authority_client::NetworkAuthorityClient;
 use sui_core::transaction_orchestrator::TransactiondOrchestrator;
 use sui_json_rpc_types::SuiExecuteTransactionResponse;
 use sui_open_rpc::Module;
+use sui_types::base_types::SuiAddress;
 use sui_types::crypto::SignatureScheme;
 use sui_types::intent::Intent;
-use sui_types::messages::{ExecuteTransactionRequest, ExecuteTransactionRequestType};
+use sui_types::messages::{
+    ExecuteTransactionRequest, ExecuteTransactionRequestType, GasSponsorship,
+    QuorumDriverRetryPolicy,
+};
+use sui_types::multisig::GenericSignature;
 use sui_types::{crypto, messages::Transaction};
 pub struct FullNodeTransactionExecutionApi {
     pub transaction_orchestrator: Arc<TransactiondOrchestrator<NetworkAuthorityClient>>,
@@ -36,6 +41,40 @@ impl FullNodeTransactionExecutionApi {
             module_cache,
         }
     }
+
+    async fn execute(
+        &self,
+        txn: Transaction,
+        request_type: ExecuteTransactionRequestType,
+        retry_policy: Option<QuorumDriverRetryPolicy>,
+    ) -> RpcResult<SuiExecuteTransactionResponse> {
+        let txn_digest = *txn.digest();
+
+        let transaction_orchestrator = self.transaction_orchestrator.clone();
+        let response = spawn_monitored_task!(transaction_orchestrator
+            .execute_transaction_with_retry_policy(
+                ExecuteTransactionRequest {
+                    transaction: txn,
+                    request_type,
+                },
+                retry_policy,
+            ))
+        .await
+        .map_err(|e| anyhow!(e))? // for JoinError
+        .map_err(|e| anyhow!(e))?; // For Sui transaction execution error (SuiResult<ExecuteTransactionResponse>)
+
+        SuiExecuteTransactionResponse::from_execute_transaction_response(
+            response,
+            txn_digest,
+            self.module_cache.as_ref(),
+        )
+        .map_err(jsonrpsee::core::Error::from)
+    }
+}
+
+fn parse_signature(signature: Base64) -> Result<GenericSignature, anyhow::Error> {
+    GenericSignature::from_bytes(&signature.to_vec().map_err(|e| anyhow!(e))?)
+        .map_err(|e| anyhow!(e))
 }
 
 #[async_trait]
@@ -47,6 +86,7 @@ impl TransactionExecutionApiServer for FullNodeTransactionExecutionApi {
         signature: Base64,
         pub_key: Base64,
         request_type: ExecuteTransactionRequestType,
+        retry_policy: Option<QuorumDriverRetryPolicy>,
     ) -> RpcResult<SuiExecuteTransactionResponse> {
         let tx_data =
             bcs::from_bytes(&tx_bytes.to_vec().map_err(|e| anyhow!(e))?).map_err(|e| anyhow!(e))?;
@@ -61,25 +101,7 @@ impl TransactionExecutionApiServer for FullNodeTransactionExecutionApi {
         )
         .map_err(|e| anyhow!(e))?;
         let txn = Transaction::from_data(tx_data, Intent::default(), signature);
-        let txn_digest = *txn.digest();
-
-        let transaction_orchestrator = self.transaction_orchestrator.clone();
-        let response = spawn_monitored_task!(transaction_orchestrator.execute_transaction(
-            ExecuteTransactionRequest {
-                transaction: txn,
-                request_type,
-            }
-        ))
-        .await
-        .map_err(|e| anyhow!(e))? // for JoinError
-        .map_err(|e| anyhow!(e))?; // For Sui transaction execution error (SuiResult<ExecuteTransactionResponse>)
-
-        SuiExecuteTransactionResponse::from_execute_transaction_response(
-            response,
-            txn_digest,
-            self.module_cache.as_ref(),
-        )
-        .map_err(jsonrpsee::core::Error::from)
+        self.execute(txn, request_type, retry_policy).await
     }
 
     async fn execute_transaction_serialized_sig(
@@ -87,32 +109,37 @@ impl TransactionExecutionApiServer for FullNodeTransactionExecutionApi {
         tx_bytes: Base64,
         signature: Base64,
         request_type: ExecuteTransactionRequestType,
+        retry_policy: Option<QuorumDriverRetryPolicy>,
     ) -> RpcResult<SuiExecuteTransactionResponse> {
         let tx_data =
             bcs::from_bytes(&tx_bytes.to_vec().map_err(|e| anyhow!(e))?).map_err(|e| anyhow!(e))?;
-        let signature = crypto::Signature::from_bytes(&signature.to_vec().map_err(|e| anyhow!(e))?)
-            .map_err(|e| anyhow!(e))?;
+        let signature = parse_signature(signature)?;
 
         let txn = Transaction::from_data(tx_data, Intent::default(), signature);
-        let txn_digest = *txn.digest();
+        self.execute(txn, request_type, retry_policy).await
+    }
 
-        let transaction_orchestrator = self.transaction_orchestrator.clone();
-        let response = spawn_monitored_task!(transaction_orchestrator.execute_transaction(
-            ExecuteTransactionRequest {
-                transaction: txn,
-                request_type,
-            }
-        ))
-        .await
-        .map_err(|e| anyhow!(e))? // for JoinError
-        .map_err(|e| anyhow!(e))?; // For Sui transaction execution error (SuiResult<ExecuteTransactionResponse>)
+    async fn execute_sponsored_transaction(
+        &self,
+        tx_bytes: Base64,
+        signature: Base64,
+        gas_sponsor: SuiAddress,
+        sponsor_signature: Base64,
+        request_type: ExecuteTransactionRequestType,
+        retry_policy: Option<QuorumDriverRetryPolicy>,
+    ) -> RpcResult<SuiExecuteTransactionResponse> {
+        let tx_data =
+            bcs::from_bytes(&tx_bytes.to_vec().map_err(|e| anyhow!(e))?).map_err(|e| anyhow!(e))?;
+        let signature = parse_signature(signature)?;
+        let gas_sponsorship = GasSponsorship::new(gas_sponsor, parse_signature(sponsor_signature)?);
 
-        SuiExecuteTransactionResponse::from_execute_transaction_response(
-            response,
-            txn_digest,
-            self.module_cache.as_ref(),
-        )
-        .map_err(jsonrpsee::core::Error::from)
+        let txn = Transaction::from_sponsored_data(
+            tx_data,
+            Intent::default(),
+            signature,
+            gas_sponsorship,
+        );
+        self.execute(txn, request_type, retry_policy).await
     }
 }
 
@@ -5,6 +5,9 @@ use std::path::Path;
 
 use std::str::FromStr;
 
+use jsonrpsee::core::client::ClientT;
+use jsonrpsee::core::params::BatchRequestBuilder;
+use jsonrpsee::rpc_params;
 use sui_config::utils::get_available_port;
 use sui_config::SUI_KEYSTORE_FILENAME;
 use sui_core::test_utils::to_sender_signed_transaction;
@@ -29,6 +32,7 @@ use test_utils::network::TestClusterBuilder;
 use crate::api::CoinReadApiClient;
 use crate::api::{RpcFullNodeReadApiClient, TransactionExecutionApiClient};
 use crate::api::{RpcReadApiClient, RpcTransactionBuilderClient};
+use crate::batch::DEFAULT_MAX_BATCH_SIZE;
 
 use sui_macros::sim_test;
 
@@ -48,6 +52,48 @@ async fn test_get_objects() -> Result<(), anyhow::Error> {
     Ok(())
 }
 
+#[sim_test]
+async fn test_get_owned_objects_paged() -> Result<(), anyhow::Error> {
+    let port = get_available_port();
+    let cluster = TestClusterBuilder::new()
+        .set_fullnode_rpc_port(port)
+        .build()
+        .await?;
+
+    let http_client = cluster.rpc_client();
+    let address = cluster.accounts.first().unwrap();
+
+    let mut objects = Vec::new();
+    let mut cursor = None;
+    let mut pages = 0;
+    loop {
+        let page = http_client
+            .get_owned_objects(*address, cursor, Some(2))
+            .await?;
+        assert!(page.data.len() <= 2);
+        assert_eq!(page.has_next_page, page.next_cursor.is_some());
+        objects.extend(page.data);
+        pages += 1;
+        if !page.has_next_page {
+            break;
+        }
+        cursor = page.next_cursor;
+    }
+    assert_eq!(3, pages);
+
+    // The pages are in ascending order of object ID, and hold all the objects of the address.
+    let ids: Vec<_> = objects.iter().map(|o| o.object_id).collect();
+    let mut all: Vec<_> = http_client
+        .get_objects_owned_by_address(*address)
+        .await?
+        .into_iter()
+        .map(|o| o.object_id)
+        .collect();
+    all.sort();
+    assert_eq!(all, ids);
+    Ok(())
+}
+
 #[sim_test]
 async fn test_public_transfer_object() -> Result<(), anyhow::Error> {
     let port = get_available_port();
@@ -82,6 +128,7 @@ async fn test_public_transfer_object() -> Result<(), anyhow::Error> {
             tx_bytes1,
             signature_bytes,
             ExecuteTransactionRequestType::WaitForLocalExecution,
+            None,
         )
         .await?;
 
@@ -125,6 +172,7 @@ async fn test_publish() -> Result<(), anyhow::Error> {
             tx_bytes,
             signature_bytes,
             ExecuteTransactionRequestType::WaitForLocalExecution,
+            None,
         )
         .await?;
     matches!(tx_response, SuiExecuteTransactionResponse::EffectsCert {effects, ..} if effects.effects.created.len() == 6);
@@ -179,6 +227,7 @@ async fn test_move_call() -> Result<(), anyhow::Error> {
             tx_bytes,
             signature_bytes,
             ExecuteTransactionRequestType::WaitForLocalExecution,
+            None,
         )
         .await?;
     matches!(tx_response, SuiExecuteTransactionResponse::EffectsCert {effects, ..} if effects.effects.created.len() == 1);
@@ -205,6 +254,59 @@ async fn test_get_object_info() -> Result<(), anyhow::Error> {
     Ok(())
 }
 
+#[sim_test]
+async fn test_batch_requests() -> Result<(), anyhow::Error> {
+    let port = get_available_port();
+    let cluster = TestClusterBuilder::new()
+        .set_fullnode_rpc_port(port)
+        .build()
+        .await?;
+    let http_client = cluster.rpc_client();
+    let address = cluster.accounts.first().unwrap();
+    let object_ids: Vec<_> = http_client
+        .get_objects_owned_by_address(*address)
+        .await?
+        .into_iter()
+        .map(|info| info.object_id)
+        .collect();
+
+    // Each request of a batch has its own result.
+    let mut batch = BatchRequestBuilder::new();
+    batch.insert("sui_getObject", rpc_params![object_ids[0]])?;
+    batch.insert("sui_getObject", rpc_params!["not an object ID"])?;
+    let responses: Vec<_> = http_client
+        .batch_request::<GetObjectDataResponse>(batch)
+        .await?
+        .into_iter()
+        .collect();
+    assert_eq!(responses.len(), 2);
+    assert!(
+        matches!(&responses[0], Ok(GetObjectDataResponse::Exists(object)) if object.id() == object_ids[0])
+    );
+    assert!(responses[1].is_err());
+
+    let client = cluster.wallet.get_client().await?;
+    let objects = client
+        .read_api()
+        .multi_get_parsed_objects(&object_ids)
+        .await?;
+    assert_eq!(objects.len(), object_ids.len());
+    for (object, id) in objects.into_iter().zip(&object_ids) {
+        assert!(matches!(object?, GetObjectDataResponse::Exists(object) if object.id() == *id));
+    }
+
+    // A batch over the limit is rejected as a whole.
+    let mut batch = BatchRequestBuilder::new();
+    for _ in 0..=DEFAULT_MAX_BATCH_SIZE {
+        batch.insert("sui_getObject", rpc_params![object_ids[0]])?;
+    }
+    assert!(http_client
+        .batch_request::<GetObjectDataResponse>(batch)
+        .await
+        .is_err());
+    Ok(())
+}
+
 #[sim_test]
 async fn test_get_coins() -> Result<(), anyhow::Error> {
     let port = get_available_port();
@@ -292,7 +394,7 @@ async fn test_get_metadata() -> Result<(), anyhow::Error> {
     let keystore_path = cluster.swarm.dir().join(SUI_KEYSTORE_FILENAME);
     let keystore = Keystore::from(FileBasedKeystore::new(&keystore_path)?);
     let tx = to_sender_signed_transaction(transaction_bytes.to_data()?, keystore.get_key(address)?);
-    let (tx_bytes, sig_scheme, signature_bytes, pub_key) = tx.to_network_data_for_execution();
+    let (tx_bytes, sig_scheme, signature_bytes, pub_key) = tx.to_network_data_for_execution()?;
 
     let tx_response = http_client
         .execute_transaction(
@@ -301,6 +403,7 @@ async fn test_get_metadata() -> Result<(), anyhow::Error> {
             signature_bytes,
             pub_key,
             ExecuteTransactionRequestType::WaitForLocalExecution,
+            None,
         )
         .await?;
 
@@ -355,7 +458,7 @@ async fn test_get_total_supply() -> Result<(), anyhow::Error> {
     let keystore_path = cluster.swarm.dir().join(SUI_KEYSTORE_FILENAME);
     let keystore = Keystore::from(FileBasedKeystore::new(&keystore_path)?);
     let tx = to_sender_signed_transaction(transaction_bytes.to_data()?, keystore.get_key(address)?);
-    let (tx_bytes, sig_scheme, signature_bytes, pub_key) = tx.to_network_data_for_execution();
+    let (tx_bytes, sig_scheme, signature_bytes, pub_key) = tx.to_network_data_for_execution()?;
 
     let tx_response = http_client
         .execute_transaction(
@@ -364,6 +467,7 @@ async fn test_get_total_supply() -> Result<(), anyhow::Error> {
             signature_bytes,
             pub_key,
             ExecuteTransactionRequestType::WaitForLocalExecution,
+            None,
         )
         .await?;
 
@@ -436,7 +540,7 @@ async fn test_get_total_supply() -> Result<(), anyhow::Error> {
     let tx = transaction_bytes.to_data()?;
 
     let tx = to_sender_signed_transaction(tx, keystore.get_key(address)?);
-    let (tx_bytes, sig_scheme, signature_bytes, pub_key) = tx.to_network_data_for_execution();
+    let (tx_bytes, sig_scheme, signature_bytes, pub_key) = tx.to_network_data_for_execution()?;
 
     let tx_response = http_client
         .execute_transaction(
@@ -445,6 +549,7 @@ async fn test_get_total_supply() -> Result<(), anyhow::Error> {
             signature_bytes,
             pub_key,
             ExecuteTransactionRequestType::WaitForLocalExecution,
+            None,
         )
         .await?;
 
@@ -491,6 +596,7 @@ async fn test_get_transaction() -> Result<(), anyhow::Error> {
                 tx_bytes,
                 signature_bytes,
                 ExecuteTransactionRequestType::WaitForLocalExecution,
+                None,
             )
             .await?;
 
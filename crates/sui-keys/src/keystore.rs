@@ -23,6 +23,7 @@ use sui_types::crypto::{
 };
 
 use crate::key_derive::derive_key_pair_from_path;
+use crate::ledger::{Ledger, LedgerKey};
 
 #[derive(Serialize, Deserialize)]
 #[enum_dispatch(AccountKeystore)]
@@ -38,6 +39,13 @@ pub trait AccountKeystore: Send + Sync {
     fn keys(&self) -> Vec<PublicKey>;
     fn get_key(&self, address: &SuiAddress) -> Result<&SuiKeyPair, anyhow::Error>;
 
+    /// Adds a key kept on a Ledger device, which then signs for its address.
+    fn add_ledger_key(&mut self, _key: LedgerKey) -> Result<(), anyhow::Error> {
+        Err(anyhow!(
+            "Only the file based keystores hold the keys of Ledger devices"
+        ))
+    }
+
     fn sign_secure<T>(
         &self,
         address: &SuiAddress,
@@ -108,9 +116,19 @@ impl Display for Keystore {
 #[derive(Default)]
 pub struct FileBasedKeystore {
     keys: BTreeMap<SuiAddress, SuiKeyPair>,
+    /// The keys of Ledger devices, which sign for them when they are connected.
+    ledger_keys: BTreeMap<SuiAddress, LedgerKey>,
     path: Option<PathBuf>,
 }
 
+/// An entry of a keystore file: the base64 encoding of a keypair, or a key of a Ledger device.
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum KeystoreEntry {
+    KeyPair(String),
+    Ledger { ledger: LedgerKey },
+}
+
 impl Serialize for FileBasedKeystore {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -140,12 +158,10 @@ impl<'de> Deserialize<'de> for FileBasedKeystore {
 impl AccountKeystore for FileBasedKeystore {
     #[warn(deprecated)]
     fn sign(&self, address: &SuiAddress, msg: &[u8]) -> Result<Signature, signature::Error> {
-        self.keys
-            .get(address)
-            .ok_or_else(|| {
-                signature::Error::from_source(format!("Cannot find key for address: [{address}]"))
-            })?
-            .try_sign(msg)
+        match self.keys.get(address) {
+            Some(keypair) => keypair.try_sign(msg),
+            None => self.sign_on_ledger(address, msg),
+        }
     }
 
     fn sign_secure<T>(
@@ -157,12 +173,14 @@ impl AccountKeystore for FileBasedKeystore {
     where
         T: Serialize,
     {
-        Ok(Signature::new_secure(
-            &IntentMessage::new(intent, msg),
-            self.keys.get(address).ok_or_else(|| {
-                signature::Error::from_source(format!("Cannot find key for address: [{address}]"))
-            })?,
-        ))
+        let intent_msg = IntentMessage::new(intent, msg);
+        match self.keys.get(address) {
+            Some(keypair) => Ok(Signature::new_secure(&intent_msg, keypair)),
+            None => self.sign_on_ledger(
+                address,
+                &bcs::to_bytes(&intent_msg).map_err(signature::Error::from_source)?,
+            ),
+        }
     }
 
     fn add_key(&mut self, keypair: SuiKeyPair) -> Result<(), anyhow::Error> {
@@ -173,40 +191,73 @@ impl AccountKeystore for FileBasedKeystore {
     }
 
     fn keys(&self) -> Vec<PublicKey> {
-        self.keys.values().map(|key| key.public()).collect()
+        self.keys
+            .values()
+            .map(|key| key.public())
+            .chain(self.ledger_keys.values().map(|key| key.public_key.clone()))
+            .collect()
     }
 
     fn get_key(&self, address: &SuiAddress) -> Result<&SuiKeyPair, anyhow::Error> {
         match self.keys.get(address) {
             Some(key) => Ok(key),
+            None if self.ledger_keys.contains_key(address) => Err(anyhow!(
+                "The key for address [{address}] is kept on a Ledger device"
+            )),
             None => Err(anyhow!("Cannot find key for address: [{address}]")),
         }
     }
+
+    fn add_ledger_key(&mut self, key: LedgerKey) -> Result<(), anyhow::Error> {
+        self.ledger_keys.insert(key.address(), key);
+        self.save()?;
+        Ok(())
+    }
 }
 
 impl FileBasedKeystore {
     pub fn new(path: &PathBuf) -> Result<Self, anyhow::Error> {
-        let keys = if path.exists() {
+        let mut keys = BTreeMap::new();
+        let mut ledger_keys = BTreeMap::new();
+        if path.exists() {
             let reader = BufReader::new(File::open(path)?);
-            let kp_strings: Vec<String> = serde_json::from_reader(reader)?;
-            kp_strings
-                .iter()
-                .map(|kpstr| {
-                    let key = SuiKeyPair::decode_base64(kpstr);
-                    key.map(|k| (Into::<SuiAddress>::into(&k.public()), k))
-                })
-                .collect::<Result<BTreeMap<_, _>, _>>()
-                .map_err(|e| anyhow::anyhow!("Invalid Keypair file {:#?} {:?}", e, path))?
-        } else {
-            BTreeMap::new()
-        };
+            let entries: Vec<KeystoreEntry> = serde_json::from_reader(reader)?;
+            for entry in entries {
+                match entry {
+                    KeystoreEntry::KeyPair(kpstr) => {
+                        let k = SuiKeyPair::decode_base64(&kpstr).map_err(|e| {
+                            anyhow::anyhow!("Invalid Keypair file {:#?} {:?}", e, path)
+                        })?;
+                        keys.insert(Into::<SuiAddress>::into(&k.public()), k);
+                    }
+                    KeystoreEntry::Ledger { ledger } => {
+                        ledger_keys.insert(ledger.address(), ledger);
+                    }
+                }
+            }
+        }
 
         Ok(Self {
             keys,
+            ledger_keys,
             path: Some(path.to_path_buf()),
         })
     }
 
+    /// Signs `msg` with the key of `address` on the Ledger device, which must be connected.
+    fn sign_on_ledger(
+        &self,
+        address: &SuiAddress,
+        msg: &[u8],
+    ) -> Result<Signature, signature::Error> {
+        let key = self.ledger_keys.get(address).ok_or_else(|| {
+            signature::Error::from_source(format!("Cannot find key for address: [{address}]"))
+        })?;
+        Ledger::connect()
+            .and_then(|ledger| key.sign(&ledger, msg))
+            .map_err(|e| signature::Error::from_source(e.to_string()))
+    }
+
     pub fn set_path(&mut self, path: &Path) {
         self.path = Some(path.to_path_buf());
     }
@@ -217,7 +268,10 @@ impl FileBasedKeystore {
                 &self
                     .keys
                     .values()
-                    .map(EncodeDecodeBase64::encode_base64)
+                    .map(|key| KeystoreEntry::KeyPair(key.encode_base64()))
+                    .chain(self.ledger_keys.values().map(|key| KeystoreEntry::Ledger {
+                        ledger: key.clone(),
+                    }))
                     .collect::<Vec<_>>(),
             )
             .unwrap();
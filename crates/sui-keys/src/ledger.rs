@@ -0,0 +1,298 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Keys kept on a Ledger device running the Sui app. The device derives them from its seed at
+//! their derivation path and signs with them, the keystore only knowing their public keys.
+//!
+//! The device is reached over USB through hidapi, on Linux, macOS and Windows alike, or over TCP
+//! when `SUI_LEDGER_TCP` is set to the `host:port` of e.g. the Speculos emulator.
+use std::env;
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::sync::Mutex;
+
+use anyhow::{anyhow, bail, ensure};
+use bip32::DerivationPath;
+use fastcrypto::ed25519::{Ed25519PublicKey, Ed25519Signature};
+use fastcrypto::traits::ToFromBytes;
+use hidapi::{DeviceInfo, HidApi, HidDevice};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use signature::Verifier;
+use sui_types::base_types::SuiAddress;
+use sui_types::crypto::{
+    Ed25519SuiSignature, PublicKey, Signature, SignatureScheme, SuiSignatureInner,
+};
+
+use crate::key_derive::validate_path;
+
+/// The `host:port` of a device reached over TCP rather than USB.
+pub const LEDGER_TCP_ENV: &str = "SUI_LEDGER_TCP";
+
+const LEDGER_VENDOR_ID: u16 = 0x2c97;
+/// The usage page of the interface of the device exchanging APDUs.
+const LEDGER_USAGE_PAGE: u16 = 0xffa0;
+
+const CLA: u8 = 0x00;
+const INS_GET_PUBLIC_KEY: u8 = 0x02;
+const INS_SIGN: u8 = 0x03;
+/// The first chunk of a payload, and the following ones.
+const P1_FIRST: u8 = 0x00;
+const P1_NEXT: u8 = 0x80;
+/// The last chunk of a payload, and the ones followed by another.
+const P2_LAST: u8 = 0x00;
+const P2_MORE: u8 = 0x80;
+const MAX_CHUNK: usize = 255;
+
+const SW_OK: u16 = 0x9000;
+const SW_DENIED: u16 = 0x6985;
+const SW_WRONG_APP: [u16; 2] = [0x6d00, 0x6e00];
+
+/// Exchanges APDUs with a device, returning its answers with their status word.
+pub trait LedgerTransport: Send {
+    fn exchange(&mut self, apdu: &[u8]) -> Result<Vec<u8>, anyhow::Error>;
+}
+
+pub struct Ledger {
+    transport: Mutex<Box<dyn LedgerTransport>>,
+}
+
+impl Ledger {
+    pub fn new(transport: Box<dyn LedgerTransport>) -> Self {
+        Self {
+            transport: Mutex::new(transport),
+        }
+    }
+
+    /// Connects to the device at `SUI_LEDGER_TCP` if set, and else to the first one on USB.
+    pub fn connect() -> Result<Self, anyhow::Error> {
+        Ok(match env::var(LEDGER_TCP_ENV) {
+            Ok(address) => Self::new(Box::new(TcpTransport::connect(&address)?)),
+            Err(_) => Self::new(Box::new(HidTransport::open_first()?)),
+        })
+    }
+
+    pub fn public_key(&self, path: &DerivationPath) -> Result<Ed25519PublicKey, anyhow::Error> {
+        let answer = self.send(INS_GET_PUBLIC_KEY, &encode_path(path))?;
+        Ed25519PublicKey::from_bytes(&answer).map_err(|e| anyhow!(e))
+    }
+
+    /// Signs `message` with the key at `path`, once the user accepted it on the device.
+    pub fn sign(
+        &self,
+        path: &DerivationPath,
+        message: &[u8],
+    ) -> Result<Ed25519Signature, anyhow::Error> {
+        let mut payload = encode_path(path);
+        payload.extend_from_slice(message);
+        let answer = self.send(INS_SIGN, &payload)?;
+        Ed25519Signature::from_bytes(&answer).map_err(|e| anyhow!(e))
+    }
+
+    /// Sends `payload` in chunks, the device only answering the last one with data.
+    fn send(&self, ins: u8, payload: &[u8]) -> Result<Vec<u8>, anyhow::Error> {
+        let mut transport = self.transport.lock().unwrap();
+        let chunks: Vec<_> = payload.chunks(MAX_CHUNK).collect();
+        let mut answer = Vec::new();
+        for (i, chunk) in chunks.iter().enumerate() {
+            let p1 = if i == 0 { P1_FIRST } else { P1_NEXT };
+            let p2 = if i + 1 == chunks.len() {
+                P2_LAST
+            } else {
+                P2_MORE
+            };
+            let mut apdu = vec![CLA, ins, p1, p2, chunk.len() as u8];
+            apdu.extend_from_slice(chunk);
+            answer = transport.exchange(&apdu)?;
+            ensure!(answer.len() >= 2, "The device sent a truncated answer");
+            let sw = u16::from_be_bytes([answer[answer.len() - 2], answer[answer.len() - 1]]);
+            match sw {
+                SW_OK => answer.truncate(answer.len() - 2),
+                SW_DENIED => bail!("The request was rejected on the device"),
+                sw if SW_WRONG_APP.contains(&sw) => bail!("Open the Sui app on the device"),
+                sw => bail!("The device answered with the status {sw:#06x}"),
+            }
+        }
+        Ok(answer)
+    }
+}
+
+/// The number of levels of the path, followed by each of them with its hardened bit.
+fn encode_path(path: &DerivationPath) -> Vec<u8> {
+    let levels = path.as_ref();
+    let mut bytes = vec![levels.len() as u8];
+    for level in levels {
+        bytes.extend_from_slice(&u32::from(*level).to_be_bytes());
+    }
+    bytes
+}
+
+/// A key of a Ledger device, known by its public key and derived at `derivation_path`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LedgerKey {
+    #[serde(
+        serialize_with = "serialize_path",
+        deserialize_with = "deserialize_path"
+    )]
+    pub derivation_path: DerivationPath,
+    pub public_key: PublicKey,
+}
+
+impl LedgerKey {
+    /// Reads the key at `derivation_path` from the device, by default the one of the first
+    /// address m/44'/784'/0'/0'/0'.
+    pub fn from_device(
+        ledger: &Ledger,
+        derivation_path: Option<DerivationPath>,
+    ) -> Result<Self, anyhow::Error> {
+        let derivation_path = validate_path(&SignatureScheme::ED25519, derivation_path)?;
+        let public_key = PublicKey::Ed25519KeyPair(ledger.public_key(&derivation_path)?);
+        Ok(Self {
+            derivation_path,
+            public_key,
+        })
+    }
+
+    pub fn address(&self) -> SuiAddress {
+        (&self.public_key).into()
+    }
+
+    pub fn sign(&self, ledger: &Ledger, message: &[u8]) -> Result<Signature, anyhow::Error> {
+        let PublicKey::Ed25519KeyPair(pk) = &self.public_key else {
+            bail!("Only the Ed25519 keys are kept on Ledger devices");
+        };
+        let sig = ledger.sign(&self.derivation_path, message)?;
+        // The device holding another seed would sign with another key.
+        pk.verify(message, &sig).map_err(|_| {
+            anyhow!(
+                "The device doesn't hold the key of {} at {}",
+                self.address(),
+                self.derivation_path
+            )
+        })?;
+
+        let mut bytes = vec![Ed25519SuiSignature::SCHEME.flag()];
+        bytes.extend_from_slice(sig.as_ref());
+        bytes.extend_from_slice(pk.as_ref());
+        <Signature as signature::Signature>::from_bytes(&bytes).map_err(|e| anyhow!(e))
+    }
+}
+
+fn serialize_path<S: Serializer>(path: &DerivationPath, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&path.to_string())
+}
+
+fn deserialize_path<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<DerivationPath, D::Error> {
+    use serde::de::Error;
+    String::deserialize(deserializer)?
+        .parse()
+        .map_err(D::Error::custom)
+}
+
+/// A device on USB.
+pub struct HidTransport {
+    device: HidDevice,
+}
+
+const HID_PACKET: usize = 64;
+const HID_CHANNEL: [u8; 2] = [0x01, 0x01];
+const HID_TAG_APDU: u8 = 0x05;
+
+impl HidTransport {
+    pub fn open(api: &HidApi, info: &DeviceInfo) -> Result<Self, anyhow::Error> {
+        let device = info.open_device(api).map_err(|e| {
+            anyhow!(
+                "Unable to open the device {}: {e}",
+                info.path().to_string_lossy()
+            )
+        })?;
+        Ok(Self { device })
+    }
+
+    /// Opens the first Ledger device connected.
+    pub fn open_first() -> Result<Self, anyhow::Error> {
+        let api = HidApi::new().map_err(|e| anyhow!("Unable to list the USB devices: {e}"))?;
+        // Not all the platforms report the usage page, the interface exchanging APDUs is the
+        // first one.
+        let info = api
+            .device_list()
+            .find(|info| {
+                info.vendor_id() == LEDGER_VENDOR_ID
+                    && (info.usage_page() == LEDGER_USAGE_PAGE || info.interface_number() == 0)
+            })
+            .ok_or_else(|| anyhow!("No Ledger device is connected"))?;
+        Self::open(&api, info)
+    }
+}
+
+impl LedgerTransport for HidTransport {
+    fn exchange(&mut self, apdu: &[u8]) -> Result<Vec<u8>, anyhow::Error> {
+        // The APDU is framed in packets, the first one starting with its length.
+        let mut framed = (apdu.len() as u16).to_be_bytes().to_vec();
+        framed.extend_from_slice(apdu);
+        for (seq, chunk) in framed.chunks(HID_PACKET - 5).enumerate() {
+            // Behind the report id.
+            let mut packet = vec![0x00];
+            packet.extend_from_slice(&HID_CHANNEL);
+            packet.push(HID_TAG_APDU);
+            packet.extend_from_slice(&(seq as u16).to_be_bytes());
+            packet.extend_from_slice(chunk);
+            packet.resize(HID_PACKET + 1, 0);
+            self.device.write(&packet)?;
+        }
+
+        let mut answer = Vec::new();
+        let mut length = None;
+        for seq in 0u16.. {
+            let mut packet = [0; HID_PACKET];
+            let read = self.device.read(&mut packet)?;
+            ensure!(
+                read >= 5
+                    && packet[..2] == HID_CHANNEL
+                    && packet[2] == HID_TAG_APDU
+                    && packet[3..5] == seq.to_be_bytes(),
+                "The device sent an unexpected packet"
+            );
+            let mut data = &packet[5..read];
+            if seq == 0 {
+                ensure!(data.len() >= 2, "The device sent an unexpected packet");
+                length = Some(u16::from_be_bytes([data[0], data[1]]) as usize);
+                data = &data[2..];
+            }
+            let length = length.unwrap_or_default();
+            answer.extend_from_slice(&data[..data.len().min(length - answer.len())]);
+            if answer.len() == length {
+                break;
+            }
+        }
+        Ok(answer)
+    }
+}
+
+/// A device reached over TCP, like the Speculos emulator, each APDU and the data of its answer
+/// being preceded by their length.
+pub struct TcpTransport {
+    stream: TcpStream,
+}
+
+impl TcpTransport {
+    pub fn connect(address: &str) -> Result<Self, anyhow::Error> {
+        let stream = TcpStream::connect(address)
+            .map_err(|e| anyhow!("Unable to reach the device at {address}: {e}"))?;
+        Ok(Self { stream })
+    }
+}
+
+impl LedgerTransport for TcpTransport {
+    fn exchange(&mut self, apdu: &[u8]) -> Result<Vec<u8>, anyhow::Error> {
+        self.stream.write_all(&(apdu.len() as u32).to_be_bytes())?;
+        self.stream.write_all(apdu)?;
+        let mut length = [0; 4];
+        self.stream.read_exact(&mut length)?;
+        // The status word follows the data.
+        let mut answer = vec![0; u32::from_be_bytes(length) as usize + 2];
+        self.stream.read_exact(&mut answer)?;
+        Ok(answer)
+    }
+}
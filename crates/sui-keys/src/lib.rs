@@ -3,3 +3,4 @@
 
 pub mod key_derive;
 pub mod keystore;
+pub mod ledger;
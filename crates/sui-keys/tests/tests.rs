@@ -2,11 +2,15 @@
 // SPDX-License-Identifier: Apache-2.0
 use std::str::FromStr;
 
+use fastcrypto::ed25519::{Ed25519KeyPair, Ed25519Signature};
 use fastcrypto::hash::{HashFunction, Sha3_256};
+use fastcrypto::traits::{KeyPair, ToFromBytes};
+use signature::{Signer, Verifier};
 use tempfile::TempDir;
 
 use sui_keys::keystore::{AccountKeystore, FileBasedKeystore, Keystore};
-use sui_types::crypto::{SignatureScheme, SuiSignatureInner};
+use sui_keys::ledger::{Ledger, LedgerKey, LedgerTransport};
+use sui_types::crypto::{get_key_pair, SignatureScheme, SuiSignature, SuiSignatureInner};
 use sui_types::{
     base_types::{SuiAddress, SUI_ADDRESS_LENGTH},
     crypto::Ed25519SuiSignature,
@@ -68,3 +72,91 @@ fn keystore_display_test() -> Result<(), anyhow::Error> {
     assert!(!keystore.to_string().contains("keys:"));
     Ok(())
 }
+
+/// A device holding a single key, answering the APDUs of the Sui app.
+struct FakeLedger {
+    keypair: Ed25519KeyPair,
+    payload: Vec<u8>,
+    reject: bool,
+}
+
+impl LedgerTransport for FakeLedger {
+    fn exchange(&mut self, apdu: &[u8]) -> Result<Vec<u8>, anyhow::Error> {
+        let (ins, p1, p2) = (apdu[1], apdu[2], apdu[3]);
+        if p1 == 0x00 {
+            self.payload.clear();
+        }
+        self.payload.extend_from_slice(&apdu[5..]);
+        if p2 == 0x80 {
+            return Ok(vec![0x90, 0x00]);
+        }
+        // Behind the derivation path.
+        let message = &self.payload[1 + 4 * self.payload[0] as usize..];
+        let mut answer = match ins {
+            _ if self.reject => return Ok(vec![0x69, 0x85]),
+            0x02 => self.keypair.public().as_ref().to_vec(),
+            0x03 => Signer::<Ed25519Signature>::sign(&self.keypair, message)
+                .as_ref()
+                .to_vec(),
+            _ => return Ok(vec![0x6d, 0x00]),
+        };
+        answer.extend_from_slice(&[0x90, 0x00]);
+        Ok(answer)
+    }
+}
+
+fn fake_ledger(keypair: &Ed25519KeyPair, reject: bool) -> Ledger {
+    Ledger::new(Box::new(FakeLedger {
+        keypair: keypair.copy(),
+        payload: Vec::new(),
+        reject,
+    }))
+}
+
+#[test]
+fn ledger_signing_test() -> Result<(), anyhow::Error> {
+    let (address, keypair): (_, Ed25519KeyPair) = get_key_pair();
+    let ledger = fake_ledger(&keypair, false);
+    let key = LedgerKey::from_device(&ledger, None)?;
+    assert_eq!(key.address(), address);
+    assert_eq!(key.derivation_path.to_string(), "m/44'/784'/0'/0'/0'");
+    // Only the Ed25519 paths are supported.
+    assert!(LedgerKey::from_device(&ledger, Some("m/54'/784'/0'/0/0".parse()?)).is_err());
+
+    // A message sent in several chunks.
+    let message = vec![7; 600];
+    let signature = key.sign(&ledger, &message)?;
+    assert_eq!(signature.public_key_bytes(), keypair.public().as_ref());
+    let sig = Ed25519Signature::from_bytes(signature.signature_bytes())?;
+    assert!(keypair.public().verify(&message, &sig).is_ok());
+
+    assert!(key.sign(&fake_ledger(&keypair, true), &message).is_err());
+    // Another device signs with another key.
+    let (_, other): (_, Ed25519KeyPair) = get_key_pair();
+    assert!(key.sign(&fake_ledger(&other, false), &message).is_err());
+    Ok(())
+}
+
+#[test]
+fn ledger_keystore_test() -> Result<(), anyhow::Error> {
+    let temp_dir = TempDir::new().unwrap();
+    let keystore_path = temp_dir.path().join("sui.keystore");
+    let mut keystore = Keystore::from(FileBasedKeystore::new(&keystore_path)?);
+    let (file_address, _, _) = keystore.generate_new_key(SignatureScheme::ED25519, None)?;
+    let (ledger_address, keypair): (_, Ed25519KeyPair) = get_key_pair();
+    keystore.add_ledger_key(LedgerKey::from_device(
+        &fake_ledger(&keypair, false),
+        Some("m/44'/784'/0'/0'/1'".parse()?),
+    )?)?;
+
+    // Both kinds of keys are kept in the same file.
+    let keystore = Keystore::from(FileBasedKeystore::new(&keystore_path)?);
+    let mut addresses = keystore.addresses();
+    addresses.sort();
+    let mut expected = vec![file_address, ledger_address];
+    expected.sort();
+    assert_eq!(addresses, expected);
+    assert!(keystore.get_key(&file_address).is_ok());
+    assert!(keystore.get_key(&ledger_address).is_err());
+    Ok(())
+}
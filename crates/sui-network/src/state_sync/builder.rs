@@ -147,6 +147,7 @@ where
                 tasks: JoinSet::new(),
                 sync_checkpoint_summaries_task: None,
                 sync_checkpoint_contents_task: None,
+                backfill_checkpoints_task: None,
                 store,
                 peer_heights,
                 checkpoint_event_sender,
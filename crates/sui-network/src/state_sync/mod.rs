@@ -46,6 +46,11 @@
 //! indicating that a new checkpoint has been fully downloaded. Notifications on this broadcast
 //! channel will always be made in order. StateSync will also send out a notification to its peers
 //! of the newly synchronized checkpoint so that it can help other peers synchronize.
+//!
+//! A node restored from a state snapshot starts from the checkpoint of the snapshot, without the
+//! checkpoints before it. StateSync backfills them in the background, from the newest to the
+//! oldest: each checkpoint is verified by the digest the following one commits to, and its
+//! contents are downloaded the same way, lowering the lowest_backfilled_checkpoint watermark.
 
 // TODO
 // * When querying a peer make sure that we're sending to peers that are on the same "network" as
@@ -225,6 +230,7 @@ struct StateSyncEventLoop<S> {
     tasks: JoinSet<()>,
     sync_checkpoint_summaries_task: Option<AbortHandle>,
     sync_checkpoint_contents_task: Option<AbortHandle>,
+    backfill_checkpoints_task: Option<AbortHandle>,
 
     store: S,
     peer_heights: Arc<RwLock<PeerHeights>>,
@@ -281,6 +287,10 @@ where
                     if matches!(&self.sync_checkpoint_summaries_task, Some(t) if t.is_finished()) {
                         self.sync_checkpoint_summaries_task = None;
                     }
+
+                    if matches!(&self.backfill_checkpoints_task, Some(t) if t.is_finished()) {
+                        self.backfill_checkpoints_task = None;
+                    }
                 },
             }
 
@@ -419,6 +429,8 @@ where
             self.weak_sender.clone(),
         );
         self.tasks.spawn(task);
+        // Retried on each tick, as the peers having the history may not be connected yet.
+        self.maybe_start_backfill_checkpoints_task();
     }
 
     fn maybe_start_checkpoint_summary_sync_task(&mut self) {
@@ -504,6 +516,33 @@ where
         }
     }
 
+    fn maybe_start_backfill_checkpoints_task(&mut self) {
+        // Only run one backfill task at a time
+        if self.backfill_checkpoints_task.is_some() {
+            return;
+        }
+
+        let lowest_backfilled_checkpoint = self
+            .store
+            .get_lowest_backfilled_checkpoint()
+            .expect("store operation should not fail");
+
+        if let Some(checkpoint) =
+            lowest_backfilled_checkpoint.filter(|checkpoint| checkpoint.sequence_number() > 0)
+        {
+            let task = backfill_checkpoints(
+                self.network.clone(),
+                self.store.clone(),
+                self.peer_heights.clone(),
+                self.config.transaction_download_concurrency(),
+                checkpoint,
+            );
+
+            let task_handle = self.tasks.spawn(task);
+            self.backfill_checkpoints_task = Some(task_handle);
+        }
+    }
+
     fn spawn_notify_peers_of_checkpoint(&mut self, checkpoint: VerifiedCheckpoint) {
         let task =
             notify_peers_of_checkpoint(self.network.clone(), self.peer_heights.clone(), checkpoint);
@@ -833,6 +872,106 @@ async fn sync_checkpoint_contents<S>(
     }
 }
 
+async fn backfill_checkpoints<S>(
+    network: anemo::Network,
+    store: S,
+    peer_heights: Arc<RwLock<PeerHeights>>,
+    transaction_download_concurrency: usize,
+    lowest_backfilled_checkpoint: VerifiedCheckpoint,
+) where
+    S: WriteStore + Clone,
+    <S as ReadStore>::Error: std::error::Error,
+{
+    let mut current = lowest_backfilled_checkpoint;
+    while let (Some(next), Some(previous_digest)) = (
+        current.sequence_number().checked_sub(1),
+        current.previous_digest(),
+    ) {
+        // The checkpoints ending an epoch are restored with the snapshot.
+        let checkpoint = match store
+            .get_checkpoint_by_sequence_number(next)
+            .expect("store operation should not fail")
+        {
+            Some(checkpoint) => Some(checkpoint.into_inner()),
+            None => get_checkpoint_summary(&network, &peer_heights, next).await,
+        };
+        let checkpoint = checkpoint.filter(|checkpoint| checkpoint.digest() == previous_digest);
+        let Some(checkpoint) = checkpoint else {
+            debug!("unable to backfill checkpoint {next}");
+            return;
+        };
+
+        // The checkpoint is verified, as the digest of a verified checkpoint commits to it.
+        let checkpoint = VerifiedCheckpoint::new_unchecked(checkpoint);
+        if checkpoint.next_epoch_committee().is_none() {
+            store
+                .insert_checkpoint(checkpoint.clone())
+                .expect("store operation should not fail");
+        }
+        match sync_one_checkpoint_contents(
+            network.clone(),
+            &store,
+            peer_heights.clone(),
+            transaction_download_concurrency,
+            checkpoint,
+        )
+        .await
+        {
+            Ok(checkpoint) => {
+                store
+                    .update_lowest_backfilled_checkpoint(&checkpoint)
+                    .expect("store operation should not fail");
+                current = checkpoint;
+            }
+            Err(err) => {
+                debug!("unable to backfill contents of checkpoint: {err}");
+                return;
+            }
+        }
+    }
+}
+
+/// Gets the summary of the checkpoint `sequence_number` from the first of our peers able to help,
+/// without verifying it.
+async fn get_checkpoint_summary(
+    network: &anemo::Network,
+    peer_heights: &RwLock<PeerHeights>,
+    sequence_number: CheckpointSequenceNumber,
+) -> Option<Checkpoint> {
+    let peers = peer_heights
+        .read()
+        .unwrap()
+        .heights
+        .iter()
+        // Filter out any peers who can't help with this particular checkpoint
+        .filter(|(_peer_id, &height)| height >= Some(sequence_number))
+        // Filter out any peers who we aren't connected with
+        .flat_map(|(peer_id, _height)| network.peer(*peer_id))
+        .map(StateSyncClient::new)
+        .collect::<Vec<_>>();
+
+    for mut peer in peers {
+        let request = Request::new(GetCheckpointSummaryRequest::BySequenceNumber(
+            sequence_number,
+        ))
+        .with_timeout(DEFAULT_TIMEOUT);
+        if let Some(checkpoint) = peer
+            .get_checkpoint_summary(request)
+            .await
+            .tap_err(|e| trace!("{e:?}"))
+            .ok()
+            .and_then(Response::into_inner)
+            .tap_none(|| trace!("peer unable to help sync"))
+        {
+            if checkpoint.sequence_number() == sequence_number {
+                return Some(checkpoint);
+            }
+        }
+    }
+
+    None
+}
+
 async fn sync_one_checkpoint_contents<S>(
     network: anemo::Network,
     store: S,
@@ -213,6 +213,76 @@ async fn isolated_sync_job() {
     }
 }
 
+#[tokio::test]
+async fn isolated_backfill_job() {
+    let committee = CommitteeFixture::generate(rand::rngs::OsRng, 0, 4);
+
+    // Build and connect two nodes
+    let (builder, server) = Builder::new().store(SharedInMemoryStore::default()).build();
+    let network_1 = build_network(|router| router.add_rpc_service(server));
+    let (mut event_loop_1, _handle_1) = builder.build(network_1.clone());
+    let (builder, server) = Builder::new().store(SharedInMemoryStore::default()).build();
+    let network_2 = build_network(|router| router.add_rpc_service(server));
+    let (event_loop_2, _handle_2) = builder.build(network_2.clone());
+    network_1.connect(network_2.local_addr()).await.unwrap();
+
+    // build mock data
+    let (ordered_checkpoints, sequence_number_to_digest, _checkpoints) =
+        committee.make_checkpoints(10, None);
+    let latest = ordered_checkpoints.last().unwrap().clone();
+
+    // Node 2 will have all the data
+    {
+        let mut store = event_loop_2.store.inner_mut();
+        store.insert_committee(committee.committee().to_owned());
+        store.insert_checkpoint_contents(empty_contents());
+        for checkpoint in ordered_checkpoints.clone() {
+            store.insert_checkpoint(checkpoint);
+        }
+    }
+
+    // Node 1 is restored from a snapshot at the latest checkpoint, and knows that Node 2 has the
+    // data
+    {
+        let mut store = event_loop_1.store.inner_mut();
+        store.insert_committee(committee.committee().to_owned());
+        store.insert_checkpoint(latest.clone());
+        store.update_highest_synced_checkpoint(&latest);
+        store.update_lowest_backfilled_checkpoint(&latest);
+    }
+    event_loop_1
+        .peer_heights
+        .write()
+        .unwrap()
+        .update_peer_height(network_2.peer_id(), Some(latest.inner().clone()));
+
+    // Backfill the data
+    event_loop_1.maybe_start_backfill_checkpoints_task();
+    event_loop_1.tasks.join_next().await.unwrap().unwrap();
+
+    let store = event_loop_1.store.inner();
+    assert_eq!(
+        store
+            .get_lowest_backfilled_checkpoint()
+            .map(|x| x.sequence_number()),
+        Some(0)
+    );
+    assert_eq!(
+        store.checkpoint_sequence_number_to_digest(),
+        &sequence_number_to_digest
+    );
+    assert!(store
+        .get_checkpoint_contents(&empty_contents().digest())
+        .is_some());
+    // The watermarks of the synced checkpoints are unchanged.
+    assert_eq!(
+        store
+            .get_highest_synced_checkpoint()
+            .map(|x| x.sequence_number()),
+        Some(latest.sequence_number())
+    );
+}
+
 #[tokio::test]
 async fn sync_with_checkpoints_being_inserted() {
     telemetry_subscribers::init_for_testing();
@@ -2,17 +2,24 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use axum::{
-    extract::Extension,
+    extract::{Extension, Path, Query},
     http::StatusCode,
     routing::{get, post},
     Router,
 };
 use mysten_metrics::spawn_monitored_task;
+use std::collections::HashMap;
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::time::Duration;
 use telemetry_subscribers::FilterHandle;
-use tracing::info;
+use tracing::{info, warn};
 
 const LOGGING_ROUTE: &str = "/logging";
+const TARGET_ROUTE: &str = "/logging/targets/:target";
+const VERBOSE_ROUTE: &str = "/logging/verbose";
+
+// The verbose logging reverts after this time unless requested otherwise.
+const DEFAULT_VERBOSE_DURATION: Duration = Duration::from_secs(300);
 
 pub fn start_admin_server(port: u16, filter_handle: FilterHandle) {
     let filter = filter_handle.get().unwrap();
@@ -20,6 +27,13 @@ pub fn start_admin_server(port: u16, filter_handle: FilterHandle) {
     let app = Router::new()
         .route(LOGGING_ROUTE, get(get_filter))
         .route(LOGGING_ROUTE, post(set_filter))
+        .route(
+            TARGET_ROUTE,
+            get(get_target_level)
+                .post(set_target_level)
+                .delete(reset_target_level),
+        )
+        .route(VERBOSE_ROUTE, post(enable_verbose_logging))
         .layer(Extension(filter_handle));
 
     let socket_address = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), port);
@@ -56,3 +70,96 @@ async fn set_filter(
         Err(err) => (StatusCode::BAD_REQUEST, err.to_string()),
     }
 }
+
+// Returns the level of the directive of the filter for a target, e.g.
+// `GET /logging/targets/sui_network::state_sync`.
+async fn get_target_level(
+    Extension(filter_handle): Extension<FilterHandle>,
+    Path(target): Path<String>,
+) -> (StatusCode, String) {
+    match filter_handle.target_level(&target) {
+        Ok(Some(level)) => (StatusCode::OK, level),
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            format!("No directive for target {target}"),
+        ),
+        Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()),
+    }
+}
+
+// Sets the level of a target to the body of the request, keeping the directives of the other
+// targets, e.g. `POST /logging/targets/sui_network::state_sync` with `debug`.
+async fn set_target_level(
+    Extension(filter_handle): Extension<FilterHandle>,
+    Path(target): Path<String>,
+    level: String,
+) -> (StatusCode, String) {
+    let level = level.trim();
+    match filter_handle.set_target_level(&target, Some(level)) {
+        Ok(()) => {
+            info!(target = %target, level = %level, "Log level of target updated");
+            (StatusCode::OK, "".into())
+        }
+        Err(err) => (StatusCode::BAD_REQUEST, err.to_string()),
+    }
+}
+
+// Removes the directive of a target, which is then filtered at the level of its parent module
+// or at the default level.
+async fn reset_target_level(
+    Extension(filter_handle): Extension<FilterHandle>,
+    Path(target): Path<String>,
+) -> (StatusCode, String) {
+    match filter_handle.set_target_level(&target, None) {
+        Ok(()) => {
+            info!(target = %target, "Log level of target reset");
+            (StatusCode::OK, "".into())
+        }
+        Err(err) => (StatusCode::BAD_REQUEST, err.to_string()),
+    }
+}
+
+// Temporarily sets the level of a target, or the default level when no target is given, and
+// reverts to the previous filter after `duration_secs` (300 by default), e.g.
+// `POST /logging/verbose?target=sui_core::checkpoints&level=trace&duration_secs=60`. The filter
+// is not reverted if it was changed in the meantime.
+async fn enable_verbose_logging(
+    Extension(filter_handle): Extension<FilterHandle>,
+    Query(params): Query<HashMap<String, String>>,
+) -> (StatusCode, String) {
+    let target = params.get("target").map(String::as_str).unwrap_or_default();
+    let level = params.get("level").map(String::as_str).unwrap_or("debug");
+    let duration = match params.get("duration_secs").map(|secs| secs.parse()) {
+        None => DEFAULT_VERBOSE_DURATION,
+        Some(Ok(secs)) => Duration::from_secs(secs),
+        Some(Err(err)) => return (StatusCode::BAD_REQUEST, format!("Invalid duration: {err}")),
+    };
+
+    let previous = match filter_handle.get() {
+        Ok(filter) => filter,
+        Err(err) => return (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()),
+    };
+    if let Err(err) = filter_handle.set_target_level(target, Some(level)) {
+        return (StatusCode::BAD_REQUEST, err.to_string());
+    }
+    let verbose = filter_handle.get().unwrap_or_default();
+    info!(filter = %verbose, ?duration, "Verbose logging enabled");
+
+    spawn_monitored_task!(async move {
+        tokio::time::sleep(duration).await;
+        match filter_handle.get() {
+            Ok(current) if current == verbose => match filter_handle.update(&previous) {
+                Ok(()) => info!(filter = %previous, "Verbose logging reverted"),
+                Err(err) => warn!("Failed to revert verbose logging: {err}"),
+            },
+            _ => info!("Log filter changed since verbose logging was enabled, not reverting"),
+        }
+    });
+    (
+        StatusCode::OK,
+        format!(
+            "Logging with {verbose} for {} s, then reverting to {previous}",
+            duration.as_secs()
+        ),
+    )
+}
@@ -28,7 +28,7 @@ use sui_core::{
     authority_client::NetworkAuthorityClient,
 };
 use sui_json_rpc::bcs_api::BcsApiImpl;
-use sui_json_rpc::streaming_api::TransactionStreamingApiImpl;
+use sui_json_rpc::streaming_api::{CheckpointStreamingApiImpl, TransactionStreamingApiImpl};
 use sui_json_rpc::transaction_builder_api::FullNodeTransactionBuilderApi;
 use sui_network::api::ValidatorServer;
 use sui_network::discovery;
@@ -40,9 +40,10 @@ use sui_storage::{
 };
 use sui_types::messages::VerifiedCertificate;
 use sui_types::messages::VerifiedCertifiedTransactionEffects;
+use sui_types::sui_system_state::SuiSystemState;
 use tokio::sync::mpsc::channel;
 use tower::ServiceBuilder;
-use tracing::info;
+use tracing::{error, info, warn};
 use typed_store::DBMetrics;
 
 use crate::metrics::GrpcMetrics;
@@ -58,11 +59,14 @@ use sui_types::crypto::KeypairTraits;
 
 pub mod admin;
 pub mod metrics;
+mod state_snapshot;
 
 mod handle;
 pub use handle::SuiNodeHandle;
+use sui_core::authority::authority_store_pruner::AuthorityStorePruner;
 use sui_core::authority::ReconfigConsensusMessage;
 use sui_core::checkpoints::CheckpointStore;
+use sui_core::state_snapshot::StateSnapshotWriter;
 use sui_json_rpc::coin_api::CoinReadApi;
 use sui_types::committee::EpochId;
 
@@ -76,7 +80,7 @@ pub struct SuiNode {
     checkpoint_store: Arc<CheckpointStore>,
     validator_server_info: Option<ValidatorServerInfo>,
     _json_rpc_service: Option<ServerHandle>,
-    _batch_subsystem_handle: tokio::task::JoinHandle<()>,
+    _batch_subsystem_handle: Option<tokio::task::JoinHandle<()>>,
     _post_processing_subsystem_handle: Option<tokio::task::JoinHandle<Result<()>>>,
     _gossip_handle: Option<tokio::task::JoinHandle<()>>,
     state: Arc<AuthorityState>,
@@ -84,10 +88,12 @@ pub struct SuiNode {
     transaction_orchestrator: Option<Arc<TransactiondOrchestrator<NetworkAuthorityClient>>>,
     registry_service: RegistryService,
 
-    _p2p_network: anemo::Network,
-    _discovery: discovery::Handle,
-    _state_sync: state_sync::Handle,
-    _checkpoint_executor_handle: tokio::task::JoinHandle<()>,
+    // None for a read-only node, which neither syncs nor executes checkpoints.
+    _p2p_network: Option<anemo::Network>,
+    _discovery: Option<discovery::Handle>,
+    _state_sync: Option<state_sync::Handle>,
+    _checkpoint_executor_handle: Option<tokio::task::JoinHandle<()>>,
+    _authority_store_pruner: Option<AuthorityStorePruner>,
 
     reconfig_channel: (
         tokio::sync::mpsc::Sender<EpochId>,
@@ -103,6 +109,8 @@ impl SuiNode {
         // TODO: maybe have a config enum that takes care of this for us.
         let is_validator = config.consensus_config().is_some();
         let is_full_node = !is_validator;
+        let read_only = config.read_only;
+        config.check_read_only()?;
         let prometheus_registry = registry_service.default_registry();
 
         info!(node =? config.protocol_public_key(),
@@ -117,15 +125,64 @@ impl SuiNode {
 
         let secret = Arc::pin(config.protocol_key_pair().copy());
         let committee = genesis.committee()?;
-        let store =
-            Arc::new(AuthorityStore::open(&config.db_path().join("store"), None, genesis).await?);
-        let committee_store = Arc::new(CommitteeStore::new(
-            config.db_path().join("epochs"),
-            &committee,
-            None,
-        ));
-        let checkpoint_store = CheckpointStore::new(&config.db_path().join("checkpoints"));
-
+        let committee_store = if read_only {
+            Arc::new(CommitteeStore::new_read_only_mode(
+                config.db_path().join("epochs"),
+                None,
+            )?)
+        } else {
+            Arc::new(CommitteeStore::new(
+                config.db_path().join("epochs"),
+                &committee,
+                None,
+            ))
+        };
+        let checkpoint_path = config.db_path().join("checkpoints");
+        let checkpoint_store = if read_only {
+            CheckpointStore::new_read_only_mode(&checkpoint_path)?
+        } else {
+            CheckpointStore::new(&checkpoint_path)
+        };
+        let store_path = config.db_path().join("store");
+        let restore_from = config
+            .state_snapshot_config
+            .as_ref()
+            .and_then(|snapshot_config| snapshot_config.restore_from.as_deref());
+        let store = match restore_from {
+            _ if read_only => AuthorityStore::open_read_only_mode(&store_path, None)?,
+            Some(source)
+                if checkpoint_store
+                    .get_highest_executed_checkpoint_seq_number()?
+                    .is_none() =>
+            {
+                // The checkpoints are set last by the restore, so the store may have been left
+                // partially restored.
+                if store_path.exists() {
+                    std::fs::remove_dir_all(&store_path)?;
+                }
+                let snapshot_dir = state_snapshot::fetch_state_snapshot(
+                    source,
+                    &config.db_path().join("state_snapshot"),
+                )
+                .await?;
+                // The history pruned right away is not backfilled.
+                let backfill_history = config
+                    .authority_store_pruning_config
+                    .as_ref()
+                    .and_then(|pruning_config| pruning_config.num_epochs_to_retain)
+                    .is_none();
+                sui_core::state_snapshot::restore(
+                    &snapshot_dir,
+                    &store_path,
+                    &committee_store,
+                    &checkpoint_store,
+                    backfill_history,
+                )
+                .await?
+            }
+            _ => AuthorityStore::open(&store_path, None, genesis).await?,
+        };
+        let store = Arc::new(store);
         let state_sync_store = RocksDbStore::new(
             store.clone(),
             committee_store.clone(),
@@ -134,6 +191,12 @@ impl SuiNode {
 
         let index_store = if is_validator {
             None
+        } else if read_only {
+            Some(Arc::new(IndexStore::open_tables_read_only_mode(
+                config.db_path().join("indexes"),
+                None,
+                None,
+            )?))
         } else {
             Some(Arc::new(IndexStore::open_tables_read_write(
                 config.db_path().join("indexes"),
@@ -144,15 +207,45 @@ impl SuiNode {
 
         let event_store = if config.enable_event_processing {
             let path = config.db_path().join("events.db");
-            let db = SqlEventStore::new_from_file(&path).await?;
-            db.initialize().await?;
+            let db = if read_only {
+                SqlEventStore::new_read_only_from_file(&path).await?
+            } else {
+                let db = SqlEventStore::new_from_file(&path).await?;
+                db.initialize().await?;
+                db
+            };
             Some(Arc::new(EventStoreType::SqlEventStore(db)))
         } else {
             None
         };
 
-        let (p2p_network, discovery_handle, state_sync_handle) =
-            Self::create_p2p_network(config, state_sync_store, &prometheus_registry)?;
+        // Validators keep all of their history for the peers to sync it.
+        let authority_store_pruner = config
+            .authority_store_pruning_config
+            .clone()
+            .filter(|_| is_full_node && !read_only)
+            .map(|pruning_config| {
+                AuthorityStorePruner::new(
+                    store.clone(),
+                    checkpoint_store.clone(),
+                    index_store.clone(),
+                    event_store.clone(),
+                    pruning_config,
+                    &prometheus_registry,
+                )
+            });
+
+        let (p2p_network, discovery_handle, state_sync_handle) = if read_only {
+            (None, None, None)
+        } else {
+            let (p2p_network, discovery_handle, state_sync_handle) =
+                Self::create_p2p_network(config, state_sync_store, &prometheus_registry)?;
+            (
+                Some(p2p_network),
+                Some(discovery_handle),
+                Some(state_sync_handle),
+            )
+        };
 
         let net = AuthorityAggregator::new_from_system_state(
             &store,
@@ -162,17 +255,26 @@ impl SuiNode {
 
         let reconfig_channel = channel(1);
 
-        let transaction_streamer = if is_full_node {
+        let transaction_streamer = if is_full_node && !read_only {
             Some(Arc::new(TransactionStreamer::new()))
         } else {
             None
         };
 
-        let node_sync_store = Arc::new(NodeSyncStore::open_tables_read_write(
-            config.db_path().join("node_sync_db"),
-            None,
-            None,
-        ));
+        let node_sync_path = config.db_path().join("node_sync_db");
+        let node_sync_store = if read_only {
+            Arc::new(NodeSyncStore::open_tables_read_only_mode(
+                node_sync_path,
+                None,
+                None,
+            )?)
+        } else {
+            Arc::new(NodeSyncStore::open_tables_read_write(
+                node_sync_path,
+                None,
+                None,
+            ))
+        };
 
         let state = AuthorityState::new(
             config.protocol_public_key(),
@@ -187,14 +289,23 @@ impl SuiNode {
         )
         .await;
 
-        let checkpoint_executor_handle = {
-            let executor = CheckpointExecutor::new(
+        let checkpoint_executor_handle = if let Some(state_sync_handle) = &state_sync_handle {
+            let mut executor = CheckpointExecutor::new(
                 state_sync_handle.subscribe_to_synced_checkpoints(),
                 checkpoint_store.clone(),
                 state.clone(),
                 &prometheus_registry,
             )?;
-            tokio::spawn(executor.run())
+            if let Some(writer) = config
+                .state_snapshot_config
+                .as_ref()
+                .and_then(StateSnapshotWriter::new)
+            {
+                executor = executor.with_state_snapshot_writer(writer);
+            }
+            Some(tokio::spawn(executor.run()))
+        } else {
+            None
         };
 
         let active_authority = Arc::new(ActiveAuthority::new(
@@ -205,28 +316,34 @@ impl SuiNode {
 
         let arc_net = active_authority.agg_aggregator();
 
-        let transaction_orchestrator = if is_full_node {
-            Some(Arc::new(TransactiondOrchestrator::new(
-                arc_net,
-                state.clone(),
-                &prometheus_registry,
-            )))
+        let transaction_orchestrator = if is_full_node && !read_only {
+            let mut orchestrator =
+                TransactiondOrchestrator::new(arc_net, state.clone(), &prometheus_registry);
+            if let Some(retry_policy) = config.quorum_driver_retry_policy.clone() {
+                orchestrator = orchestrator.with_default_retry_policy(retry_policy);
+            }
+            if let Some(retry_policy) = config.quorum_driver_max_retry_policy.clone() {
+                orchestrator = orchestrator.with_max_retry_policy(retry_policy);
+            }
+            Some(Arc::new(orchestrator))
         } else {
             None
         };
 
-        let batch_subsystem_handle = {
+        let batch_subsystem_handle = if read_only {
+            None
+        } else {
             // Start batch system so that this node can be followed
             let batch_state = state.clone();
-            spawn_monitored_task!(async move {
+            Some(spawn_monitored_task!(async move {
                 batch_state
                     .run_batch_service(1000, Duration::from_secs(1))
                     .await
-            })
+            }))
         };
 
         let post_processing_subsystem_handle =
-            if index_store.is_some() || config.enable_event_processing {
+            if !read_only && (index_store.is_some() || config.enable_event_processing) {
                 let indexing_state = state.clone();
                 Some(spawn_monitored_task!(async move {
                     indexing_state
@@ -238,7 +355,7 @@ impl SuiNode {
                 None
             };
 
-        let gossip_handle = if is_full_node {
+        let gossip_handle = if is_full_node && !read_only {
             active_authority.clone().spawn_node_sync_process().await;
             None
         } else {
@@ -249,7 +366,7 @@ impl SuiNode {
             config,
             state.clone(),
             checkpoint_store.clone(),
-            &state_sync_handle,
+            state_sync_handle.as_ref(),
             registry_service.clone(),
         )
         .await?;
@@ -257,6 +374,8 @@ impl SuiNode {
         let json_rpc_service = build_server(
             state.clone(),
             &transaction_orchestrator.clone(),
+            checkpoint_store.clone(),
+            state_sync_handle.as_ref(),
             config,
             &prometheus_registry,
         )
@@ -278,6 +397,7 @@ impl SuiNode {
             _discovery: discovery_handle,
             _state_sync: state_sync_handle,
             _checkpoint_executor_handle: checkpoint_executor_handle,
+            _authority_store_pruner: authority_store_pruner,
             reconfig_channel,
 
             #[cfg(msim)]
@@ -376,12 +496,14 @@ impl SuiNode {
         config: &NodeConfig,
         state: Arc<AuthorityState>,
         checkpoint_store: Arc<CheckpointStore>,
-        state_sync_handle: &state_sync::Handle,
+        state_sync_handle: Option<&state_sync::Handle>,
         registry_service: RegistryService,
     ) -> Result<Option<ValidatorServerInfo>> {
         if state.is_fullnode() {
             return Ok(None);
         }
+        let state_sync_handle =
+            state_sync_handle.ok_or_else(|| anyhow!("A validator requires state sync"))?;
         let prometheus_registry = registry_service.default_registry();
         let (tx_reconfigure_consensus, rx_reconfigure_consensus) = channel(100);
 
@@ -442,6 +564,49 @@ impl SuiNode {
             .ok_or_else(|| anyhow::anyhow!("Transaction Orchestrator is not enabled in this node."))
     }
 
+    /// End the current consensus epoch of this validator now, restarting Narwhal for the next
+    /// epoch with the same committee. The other validators must be asked to do the same.
+    pub async fn force_epoch_change(&self) -> Result<()> {
+        let (_, tx_reconfigure_consensus) = self
+            .validator_server_info
+            .as_ref()
+            .ok_or_else(|| anyhow!("Only validators can change epoch"))?;
+        info!("Forcing the end of the consensus epoch");
+        tx_reconfigure_consensus
+            .send(ReconfigConsensusMessage::EndOfEpoch)
+            .await
+            .map_err(|_| anyhow!("Narwhal is not running"))
+    }
+
+    /// Restarts Narwhal for its next epoch, with the keys, stake and workers of the validators
+    /// of `system_state`: the rotated keys take effect in both Sui and Narwhal at the same epoch.
+    async fn reconfigure_narwhal(&self, system_state: &SuiSystemState) -> Result<()> {
+        let (_, tx_reconfigure_consensus) = self
+            .validator_server_info
+            .as_ref()
+            .ok_or_else(|| anyhow!("Only validators run Narwhal"))?;
+        // The epochs are replaced by the next one of Narwhal.
+        let (committee, worker_cache) = self
+            .config
+            .narwhal_epoch_configuration(system_state, Default::default())?;
+        if !committee
+            .authorities
+            .contains_key(self.config.protocol_key_pair().public())
+        {
+            warn!("Our protocol key is not on-chain: restart the node with the rotated keys");
+        }
+        tx_reconfigure_consensus
+            .send(ReconfigConsensusMessage::NextEpoch((
+                self.config.protocol_key_pair().copy(),
+                self.config.network_key_pair().copy(),
+                committee,
+                vec![(0, self.config.worker_key_pair().copy())],
+                worker_cache,
+            )))
+            .await
+            .map_err(|_| anyhow!("Narwhal is not running"))
+    }
+
     /// This function waits for a signal from the checkpoint executor to indicate that on-chain
     /// epoch has changed. Upon receiving such signal, we reconfigure the entire system.
     pub async fn monitor_reconfiguration(mut self) -> Result<()> {
@@ -456,19 +621,17 @@ impl SuiNode {
                 ?next_epoch,
                 "Received reconfiguration signal. About to reconfigure the system."
             );
-            let new_committee = self
+            let system_state = self
                 .state
                 .get_sui_system_state_object()
                 .await
-                .expect("Reading Sui system state object cannot fail")
-                .get_current_epoch_committee();
+                .expect("Reading Sui system state object cannot fail");
+            let new_committee = system_state.get_current_epoch_committee();
             assert_eq!(next_epoch, new_committee.committee.epoch);
             let was_validator = self.state.is_validator();
             assert_eq!(was_validator, self.validator_server_info.is_some());
             if was_validator {
                 info!("Reconfiguring the validator.");
-                info!("Shutting down Narwhal");
-                // TODO: Shutdown Narwhal here.
             }
 
             self.state
@@ -481,9 +644,11 @@ impl SuiNode {
                 // service is running in the new epoch.
                 if was_validator {
                     // If we were a validator in previous epoch, we don't need to start grpc server.
-                    // Only need to start Narwhal.
-                    // TODO: Start Narwhal here.
-                    info!("Starting Narwhal");
+                    // Only need to restart Narwhal with the committee of the new epoch.
+                    info!("Restarting Narwhal");
+                    if let Err(e) = self.reconfigure_narwhal(&system_state).await {
+                        error!("Failed to restart Narwhal for the new epoch: {e:?}");
+                    }
                 } else {
                     // TODO: It might be easier to require node operator to manually restart the node
                     // for this transition.
@@ -492,7 +657,7 @@ impl SuiNode {
                         &self.config,
                         self.state.clone(),
                         self.checkpoint_store.clone(),
-                        &self._state_sync,
+                        self._state_sync.as_ref(),
                         self.registry_service.clone(),
                     )
                     .await
@@ -509,6 +674,8 @@ impl SuiNode {
 pub async fn build_server(
     state: Arc<AuthorityState>,
     transaction_orchestrator: &Option<Arc<TransactiondOrchestrator<NetworkAuthorityClient>>>,
+    checkpoint_store: Arc<CheckpointStore>,
+    state_sync: Option<&state_sync::Handle>,
     config: &NodeConfig,
     prometheus_registry: &Registry,
 ) -> Result<Option<ServerHandle>> {
@@ -540,6 +707,18 @@ pub async fn build_server(
         server.register_module(TransactionStreamingApiImpl::new(state.clone(), tx_streamer))?;
     }
 
+    // A read-only node streams the checkpoints it has stored, as it doesn't sync new ones.
+    if let Some(state_sync) = state_sync {
+        server.register_module(CheckpointStreamingApiImpl::new(
+            checkpoint_store,
+            state_sync.clone(),
+        ))?;
+    } else {
+        server.register_module(CheckpointStreamingApiImpl::new_without_state_sync(
+            checkpoint_store,
+        ))?;
+    }
+
     if let Some(event_handler) = state.event_handler.clone() {
         server.register_module(EventStreamingApiImpl::new(state.clone(), event_handler))?;
     }
@@ -41,6 +41,10 @@ async fn main() -> Result<()> {
         "Started Prometheus HTTP endpoint at {}",
         config.metrics_address
     );
+    if let Some(push_config) = config.metrics_push.clone() {
+        info!("Pushing the metrics to {}", push_config.url);
+        mysten_metrics::push::start_push_task(registry_service.clone(), push_config);
+    }
 
     // Initialize logging
     let (_guard, filter_handle) =
@@ -0,0 +1,60 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{anyhow, Result};
+use std::path::{Path, PathBuf};
+use sui_core::state_snapshot::{read_manifest, MANIFEST_FILE_NAME};
+use tracing::{info, warn};
+
+/// Returns the directory of the snapshot at `source`, downloading its files into `download_dir`
+/// first if `source` is an http(s) URL. The files are checked when the snapshot is restored.
+pub async fn fetch_state_snapshot(source: &str, download_dir: &Path) -> Result<PathBuf> {
+    if !source.starts_with("http://") && !source.starts_with("https://") {
+        return Ok(PathBuf::from(source));
+    }
+    let base_url = source.trim_end_matches('/');
+    // The digests of the manifest only detect corrupted files: the checkpoints don't commit to
+    // the objects, which are only as trustworthy as the server of the snapshot.
+    warn!(
+        "Restoring the objects of the state snapshot served by {base_url} as is: only restore \
+         from the snapshots of a node you trust"
+    );
+    if source.starts_with("http://") {
+        warn!(
+            "The state snapshot is downloaded over plain http, its objects can be tampered with \
+             on the way"
+        );
+    }
+    if download_dir.exists() {
+        tokio::fs::remove_dir_all(download_dir).await?;
+    }
+    tokio::fs::create_dir_all(download_dir).await?;
+
+    let client = reqwest::Client::new();
+    download(&client, base_url, MANIFEST_FILE_NAME, download_dir).await?;
+    let manifest = read_manifest(download_dir)?;
+    for name in manifest.file_names() {
+        download(&client, base_url, name, download_dir).await?;
+    }
+    info!(
+        epoch = manifest.epoch,
+        "Downloaded the state snapshot from {base_url}"
+    );
+    Ok(download_dir.to_path_buf())
+}
+
+async fn download(client: &reqwest::Client, base_url: &str, name: &str, dir: &Path) -> Result<()> {
+    // The names of the files come from the manifest, which is not trusted.
+    if name.contains(['/', '\\']) || name.starts_with('.') {
+        return Err(anyhow!("Invalid state snapshot file name {name}"));
+    }
+    let bytes = client
+        .get(format!("{base_url}/{name}"))
+        .send()
+        .await?
+        .error_for_status()?
+        .bytes()
+        .await?;
+    tokio::fs::write(dir.join(name), bytes).await?;
+    Ok(())
+}
@@ -24,6 +24,10 @@ use unescape::unescape;
 /// ideally we want to add this to the trait generated by jsonrpsee framework, creating a new struct
 /// to provide access to the method is a workaround.
 ///
+/// A method marked `#[deprecated]` is documented as deprecated, the attribute being removed from
+/// the trait so that the servers implementing the method, and the clients calling it, still
+/// compile without warnings.
+///
 /// TODO: consider contributing the open rpc doc macro to jsonrpsee to simplify the logics.
 #[proc_macro_attribute]
 pub fn open_rpc(attr: TokenStream, item: TokenStream) -> TokenStream {
@@ -65,11 +69,12 @@ pub fn open_rpc(attr: TokenStream, item: TokenStream) -> TokenStream {
             quote! {None;}
         };
         let is_pubsub = method.is_pubsub;
+        let deprecated = method.deprecated;
         methods.push(quote! {
             let mut inputs: Vec<sui_open_rpc::ContentDescriptor> = Vec::new();
             #(#inputs)*
             let result = #returns_ty
-            builder.add_method(#namespace, #name, inputs, result, #doc, #tag, #is_pubsub);
+            builder.add_method(#namespace, #name, inputs, result, #doc, #tag, #is_pubsub, #deprecated);
         })
     }
     let open_rpc_name = quote::format_ident!("{}OpenRpc", &rpc_definition.name);
@@ -127,6 +132,7 @@ struct Method {
     returns: Option<Type>,
     doc: String,
     is_pubsub: bool,
+    deprecated: bool,
 }
 
 fn parse_rpc_method(trait_data: &mut syn::ItemTrait) -> Result<RpcDefinition, syn::Error> {
@@ -154,6 +160,17 @@ fn parse_rpc_method(trait_data: &mut syn::ItemTrait) -> Result<RpcDefinition, sy
                 };
 
             let doc = extract_doc_comments(&method.attrs).to_string();
+            let deprecated = match method
+                .attrs
+                .iter()
+                .position(|a| a.path.is_ident("deprecated"))
+            {
+                Some(pos) => {
+                    method.attrs.remove(pos);
+                    true
+                }
+                None => false,
+            };
 
             let params: Vec<_> = method
                 .sig
@@ -194,6 +211,7 @@ fn parse_rpc_method(trait_data: &mut syn::ItemTrait) -> Result<RpcDefinition, sy
                 returns,
                 doc,
                 is_pubsub,
+                deprecated,
             });
         }
     }
@@ -412,7 +412,11 @@ impl RpcExampleProvider {
         let mut data = self.get_transaction_digests(5..9);
         let next_cursor = data.pop();
 
-        let result = TransactionsPage { data, next_cursor };
+        let result = TransactionsPage {
+            data,
+            next_cursor,
+            has_next_page: true,
+        };
         Examples::new(
             "sui_getTransactions",
             vec![ExamplePairing::new(
@@ -491,6 +495,8 @@ impl RpcExampleProvider {
                 transaction_digest: *tx_digest,
                 data: SuiTransactionData::try_from(data1).unwrap(),
                 tx_signature: signature.clone(),
+                gas_sponsor: None,
+                sponsor_signature: None,
                 auth_sign_info: AuthorityQuorumSignInfo {
                     epoch: 0,
                     signature: Default::default(),
@@ -539,6 +545,7 @@ impl RpcExampleProvider {
         let page = EventPage {
             data: events.clone(),
             next_cursor: Some((1000, 5).into()),
+            has_next_page: true,
         };
         Examples::new(
             "sui_getEvents",
@@ -135,6 +135,8 @@ struct Method {
     params: Vec<ContentDescriptor>,
     #[serde(skip_serializing_if = "Option::is_none")]
     result: Option<ContentDescriptor>,
+    #[serde(skip_serializing_if = "default")]
+    deprecated: bool,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     examples: Vec<ExamplePairing>,
 }
@@ -273,6 +275,7 @@ impl RpcModuleDocBuilder {
         doc: &str,
         tag: Option<String>,
         is_pubsub: bool,
+        deprecated: bool,
     ) {
         let description = if doc.trim().is_empty() {
             None
@@ -310,6 +313,7 @@ impl RpcModuleDocBuilder {
                 params,
                 result,
                 tags,
+                deprecated,
                 examples: Vec::new(),
             },
         );
@@ -9,6 +9,7 @@ use fastcrypto::encoding::{Encoding, Hex};
 use sui_types::base_types::SuiAddress;
 use sui_types::crypto;
 use sui_types::crypto::{SignatureScheme, ToFromBytes};
+use sui_types::gas_coin::GasCoin;
 use sui_types::messages::{
     QuorumDriverRequest, QuorumDriverRequestType, QuorumDriverResponse, Transaction,
     TransactionData,
@@ -23,7 +24,7 @@ use crate::types::{
     ConstructionMetadata, ConstructionMetadataRequest, ConstructionMetadataResponse,
     ConstructionParseRequest, ConstructionParseResponse, ConstructionPayloadsRequest,
     ConstructionPayloadsResponse, ConstructionPreprocessRequest, ConstructionPreprocessResponse,
-    ConstructionSubmitRequest, MetadataOptions, SignatureType, SigningPayload,
+    ConstructionSubmitRequest, MetadataOptions, OperationType, SignatureType, SigningPayload,
     TransactionIdentifier, TransactionIdentifierResponse,
 };
 use crate::ErrorType::InternalError;
@@ -65,13 +66,18 @@ pub async fn payloads(
     let address = data.signer();
     let intent_msg = IntentMessage::new(Intent::default(), data);
     let intent_msg_bytes = bcs::to_bytes(&intent_msg)?;
+    // The signer is offline, so the signature type follows the curve of its public key.
+    let signature_type = request
+        .public_keys
+        .first()
+        .map_or(SignatureType::Ed25519, |key| key.curve_type.into());
 
     Ok(ConstructionPayloadsResponse {
         unsigned_transaction: Hex::from_bytes(&intent_msg_bytes),
         payloads: vec![SigningPayload {
             account_identifier: AccountIdentifier { address },
-            hex_bytes: Hex::encode(bcs::to_bytes(&intent_msg)?),
-            signature_type: Some(SignatureType::Ed25519),
+            hex_bytes: Hex::encode(&intent_msg_bytes),
+            signature_type: Some(signature_type),
         }],
     })
 }
@@ -90,7 +96,10 @@ pub async fn combine(
         .to_vec()
         .map_err(|e| anyhow!(e))?;
     let intent_msg: IntentMessage<TransactionData> = bcs::from_bytes(&unsigned_tx)?;
-    let sig = request.signatures.first().unwrap();
+    let sig = request
+        .signatures
+        .first()
+        .ok_or_else(|| Error::missing_input("signature"))?;
     let sig_bytes = sig.hex_bytes.to_vec().map_err(|e| anyhow!(e))?;
     let pub_key = sig.public_key.hex_bytes.to_vec().map_err(|e| anyhow!(e))?;
     let flag = vec![match sig.signature_type {
@@ -161,8 +170,10 @@ pub async fn preprocess(
     let sender = request
         .operations
         .iter()
-        .find_map(|op| match (&op.account, &op.amount) {
-            (Some(acc), Some(amount)) => {
+        .find_map(|op| match (op.type_, &op.account, &op.amount) {
+            // The withdrawn principal is paid back to the sender.
+            (OperationType::WithdrawDelegation, Some(acc), _) => Some(acc.address),
+            (_, Some(acc), Some(amount)) => {
                 if amount.value.is_negative() {
                     Some(acc.address)
                 } else {
@@ -178,8 +189,25 @@ pub async fn preprocess(
             )
         })?;
 
+    let mut budget = None;
+    let mut input_objects = vec![];
+    for op in &request.operations {
+        match op.type_ {
+            OperationType::GasBudget => budget = Some(op.budget()?),
+            OperationType::WithdrawDelegation => {
+                let (delegation, staked_sui) = op.withdrawn_delegation()?;
+                input_objects.extend([delegation, staked_sui]);
+            }
+            _ => {}
+        }
+    }
+
     Ok(ConstructionPreprocessResponse {
-        options: Some(MetadataOptions { sender }),
+        options: Some(MetadataOptions {
+            sender,
+            budget,
+            input_objects,
+        }),
         required_public_keys: vec![AccountIdentifier { address: sender }],
     })
 }
@@ -205,8 +233,9 @@ pub async fn hash(
 }
 
 /// Get any information required to construct a transaction for a specific network.
-/// For Sui, we are returning the latest object refs for all the input objects,
-/// which will be used in transaction construction.
+/// For Sui, we are returning the latest object refs for all the input objects and the
+/// Sui framework package, which will be used in transaction construction, and the coin paying
+/// for the gas.
 ///
 /// [Rosetta API Spec](https://www.rosetta-api.org/docs/ConstructionApi.html#constructionmetadata)
 pub async fn metadata(
@@ -216,20 +245,68 @@ pub async fn metadata(
 ) -> Result<ConstructionMetadataResponse, Error> {
     env.check_network_identifier(&request.network_identifier)?;
 
-    let sender_coins = if let Some(option) = request.options {
-        context
+    let sui_framework = context.state.get_framework_object_ref().await?;
+    let mut metadata = ConstructionMetadata {
+        sender_coins: vec![],
+        gas: None,
+        input_objects: vec![],
+        sui_framework: Some(sui_framework),
+    };
+
+    if let Some(option) = request.options {
+        let coin_ids = context
             .state
             .get_owner_objects(Owner::AddressOwner(option.sender))?
             .iter()
             .filter(|info| info.type_.is_gas_coin())
-            .map(|info| info.into())
-            .collect::<Vec<_>>()
-    } else {
-        Default::default()
-    };
+            .map(|info| info.object_id)
+            .collect::<Vec<_>>();
+        let coins = context
+            .state
+            .get_objects(&coin_ids)
+            .await?
+            .iter()
+            .flatten()
+            .map(|o| Ok((o.compute_object_reference(), GasCoin::try_from(o)?.value())))
+            .collect::<Result<Vec<_>, anyhow::Error>>()?;
+
+        // The smallest coin covering the budget pays for the gas, leaving the others to the
+        // operations.
+        if let Some(budget) = option.budget {
+            let gas = coins
+                .iter()
+                .filter(|(_, value)| *value >= budget)
+                .min_by_key(|(_, value)| *value)
+                .ok_or_else(|| {
+                    Error::new_with_msg(
+                        ErrorType::InvalidInput,
+                        &format!("No coin of the sender covers the gas budget of {budget}"),
+                    )
+                })?;
+            metadata.gas = Some(gas.0);
+        }
+        metadata.sender_coins = coins.into_iter().map(|(coin, _)| coin).collect();
+
+        let objects = context.state.get_objects(&option.input_objects).await?;
+        metadata.input_objects = option
+            .input_objects
+            .iter()
+            .zip(objects)
+            .map(|(id, object)| {
+                object
+                    .map(|object| object.compute_object_reference())
+                    .ok_or_else(|| {
+                        Error::new_with_msg(
+                            ErrorType::InvalidInput,
+                            &format!("Object {id} not found"),
+                        )
+                    })
+            })
+            .collect::<Result<_, _>>()?;
+    }
 
     Ok(ConstructionMetadataResponse {
-        metadata: ConstructionMetadata { sender_coins },
+        metadata,
         suggested_fee: vec![],
     })
 }
@@ -9,12 +9,22 @@ use serde::Deserialize;
 use serde::Serialize;
 use serde_json::{json, Value};
 
-use sui_types::base_types::{ObjectRef, SuiAddress};
+use move_core_types::identifier::IdentStr;
+use sui_types::base_types::{ObjectID, ObjectRef, SuiAddress};
 use sui_types::event::{BalanceChangeType, Event};
 use sui_types::gas_coin::GAS;
-use sui_types::messages::{ExecutionStatus, SingleTransactionKind, TransactionData};
+use sui_types::messages::{
+    CallArg, ExecutionStatus, MoveCall, ObjectArg, SingleTransactionKind, TransactionData,
+};
 use sui_types::move_package::disassemble_modules;
 use sui_types::object::Owner;
+use sui_types::sui_system_state::{
+    ADD_DELEGATION_MUL_COIN_FUNCTION_NAME, SUI_SYSTEM_MODULE_NAME,
+    WITHDRAW_DELEGATION_FUNCTION_NAME,
+};
+use sui_types::{
+    SUI_FRAMEWORK_OBJECT_ID, SUI_SYSTEM_STATE_OBJECT_ID, SUI_SYSTEM_STATE_OBJECT_SHARED_VERSION,
+};
 
 use crate::types::{
     AccountIdentifier, Amount, CoinAction, CoinChange, CoinIdentifier, ConstructionMetadata,
@@ -202,57 +212,78 @@ impl Operation {
         let mut amounts = vec![];
         let mut sender = None;
         let mut budget = None;
+        let mut validator = None;
+        let mut withdrawal = None;
         for op in operations {
-            // Currently only PaySui is support,
-            if op.type_ != OperationType::PaySui && op.type_ != OperationType::GasBudget {
-                return Err(Error::new_with_msg(
-                    ErrorType::InvalidInput,
-                    &format!("Unsupported operation {:?}", op.type_),
-                ));
+            // Currently only PaySui and the staking operations are supported,
+            if op.type_ != OperationType::PaySui
+                && op.type_ != OperationType::Delegation
+                && op.type_ != OperationType::WithdrawDelegation
+                && op.type_ != OperationType::GasBudget
+            {
+                return Err(Error::unsupported_operation(op.type_));
             }
-            if type_.is_none() && op.type_ != OperationType::GasBudget {
-                type_ = Some(op.type_)
+            if op.type_ != OperationType::GasBudget {
+                match type_ {
+                    None => type_ = Some(op.type_),
+                    Some(type_) if type_ != op.type_ => {
+                        return Err(Error::new_with_msg(
+                            ErrorType::InvalidInput,
+                            &format!("Cannot combine {:?} and {:?} operations", type_, op.type_),
+                        ));
+                    }
+                    Some(_) => {}
+                }
             }
             if op.type_ == OperationType::GasBudget {
-                let budget_value = op
-                    .metadata
-                    .clone()
-                    .and_then(|v| v.pointer("/budget").cloned())
-                    .ok_or_else(|| Error::missing_input("gas budget"))?;
-                budget = Some(
-                    budget_value
-                        .as_u64()
-                        .or_else(|| budget_value.as_str().and_then(|s| u64::from_str(s).ok()))
-                        .ok_or_else(|| {
-                            Error::new_with_msg(
-                                ErrorType::InvalidInput,
-                                format!("Cannot parse gas budget : [{budget_value}]").as_str(),
-                            )
-                        })?,
-                );
+                budget = Some(op.budget()?);
+            } else if op.type_ == OperationType::Delegation {
+                validator = Some(op.metadata_field::<SuiAddress>("validator")?);
+                let (amount, account) = op
+                    .amount
+                    .zip(op.account)
+                    .ok_or_else(|| Error::missing_input("delegation amount and account"))?;
+                if !amount.value.is_negative() {
+                    return Err(Error::new_with_msg(
+                        ErrorType::InvalidInput,
+                        "Delegation amount must be negative, it is taken from the account",
+                    ));
+                }
+                sender = Some(account.address);
+                amounts.push(checked_amount(&amount)?);
+            } else if op.type_ == OperationType::WithdrawDelegation {
+                let (delegation, staked_sui) = op.withdrawn_delegation()?;
+                let amount = op.metadata_field::<u64>("amount")?;
+                let account = op
+                    .account
+                    .ok_or_else(|| Error::missing_input("withdrawal account"))?;
+                sender = Some(account.address);
+                withdrawal = Some((delegation, staked_sui, amount));
             } else if op.type_ == OperationType::PaySui {
                 if let (Some(amount), Some(account)) = (op.amount, op.account) {
                     if amount.value.is_negative() {
                         sender = Some(account.address)
                     } else {
                         recipients.push(account.address);
-                        let amount = amount.value.abs();
-                        if amount > u64::MAX as u128 {
-                            return Err(Error::new_with_msg(
-                                ErrorType::InvalidInput,
-                                "Input amount exceed u64::MAX",
-                            ));
-                        }
-                        amounts.push(amount as u64)
+                        amounts.push(checked_amount(&amount)?)
                     }
                 }
             }
         }
 
         let address = sender.ok_or_else(|| Error::missing_input("Sender address"))?;
-        let gas = metadata.sender_coins[0];
         let budget = budget.ok_or_else(|| Error::missing_input("gas budget"))?;
 
+        if let Some(validator) = validator {
+            return Self::create_delegation_data(address, validator, amounts[0], metadata, budget);
+        }
+        if let Some((delegation, staked_sui, amount)) = withdrawal {
+            return Self::create_withdraw_delegation_data(
+                address, delegation, staked_sui, amount, metadata, budget,
+            );
+        }
+
+        let gas = metadata.gas()?;
         Ok(TransactionData::new_pay_sui(
             address,
             metadata.sender_coins,
@@ -263,6 +294,118 @@ impl Operation {
         ))
     }
 
+    /// The gas budget of a `GasBudget` operation.
+    pub fn budget(&self) -> Result<u64, Error> {
+        self.metadata_field("budget")
+    }
+
+    /// The ids of the `Delegation` and `StakedSui` objects of a `WithdrawDelegation` operation.
+    pub fn withdrawn_delegation(&self) -> Result<(ObjectID, ObjectID), Error> {
+        Ok((
+            self.metadata_field("delegation")?,
+            self.metadata_field("staked_sui")?,
+        ))
+    }
+
+    /// Parses the `field` of the metadata, given either as a string or as a JSON value.
+    fn metadata_field<T: FromStr>(&self, field: &str) -> Result<T, Error> {
+        let value = self
+            .metadata
+            .as_ref()
+            .and_then(|v| v.get(field))
+            .ok_or_else(|| Error::missing_input(field))?;
+        match value {
+            Value::String(s) => T::from_str(s).ok(),
+            value => T::from_str(&value.to_string()).ok(),
+        }
+        .ok_or_else(|| {
+            Error::new_with_msg(
+                ErrorType::InvalidInput,
+                format!("Cannot parse {field} : [{value}]").as_str(),
+            )
+        })
+    }
+
+    /// The coin picked by `/construction/metadata` pays for the gas, as the gas object cannot also
+    /// be an input of the Move call, and the other coins are delegated.
+    fn create_delegation_data(
+        sender: SuiAddress,
+        validator: SuiAddress,
+        amount: u64,
+        metadata: ConstructionMetadata,
+        budget: u64,
+    ) -> Result<TransactionData, Error> {
+        let sui_framework = metadata
+            .sui_framework
+            .ok_or_else(|| Error::missing_input("Sui framework object ref"))?;
+        let gas = metadata.gas()?;
+        let coins: Vec<_> = metadata
+            .sender_coins
+            .iter()
+            .filter(|coin| coin.0 != gas.0)
+            .map(|coin| ObjectArg::ImmOrOwnedObject(*coin))
+            .collect();
+        if coins.is_empty() {
+            return Err(Error::new_with_msg(
+                ErrorType::InvalidInput,
+                "Delegation needs two coins of the sender, one of them pays for the gas",
+            ));
+        }
+        Ok(TransactionData::new_move_call(
+            sender,
+            sui_framework,
+            SUI_SYSTEM_MODULE_NAME.to_owned(),
+            ADD_DELEGATION_MUL_COIN_FUNCTION_NAME.to_owned(),
+            vec![],
+            gas,
+            vec![
+                sui_system_state_arg(),
+                CallArg::ObjVec(coins),
+                CallArg::Pure(bcs::to_bytes(&amount)?),
+                CallArg::Pure(bcs::to_bytes(&validator)?),
+            ],
+            budget,
+        ))
+    }
+
+    /// Withdraws `amount` of the principal of the delegation, which is paid back to the sender at
+    /// the end of the epoch.
+    fn create_withdraw_delegation_data(
+        sender: SuiAddress,
+        delegation: ObjectID,
+        staked_sui: ObjectID,
+        amount: u64,
+        metadata: ConstructionMetadata,
+        budget: u64,
+    ) -> Result<TransactionData, Error> {
+        let sui_framework = metadata
+            .sui_framework
+            .ok_or_else(|| Error::missing_input("Sui framework object ref"))?;
+        let input_object = |id: ObjectID| {
+            metadata
+                .input_objects
+                .iter()
+                .find(|object| object.0 == id)
+                .map(|object| CallArg::Object(ObjectArg::ImmOrOwnedObject(*object)))
+                .ok_or_else(|| Error::missing_input(&format!("object ref of {id}")))
+        };
+        Ok(TransactionData::new_move_call(
+            sender,
+            sui_framework,
+            SUI_SYSTEM_MODULE_NAME.to_owned(),
+            WITHDRAW_DELEGATION_FUNCTION_NAME.to_owned(),
+            vec![],
+            metadata.gas()?,
+            vec![
+                sui_system_state_arg(),
+                input_object(delegation)?,
+                input_object(staked_sui)?,
+                CallArg::Pure(bcs::to_bytes(&amount)?),
+            ],
+            budget,
+        ))
+    }
+
     pub fn gas_budget(
         counter: &mut IndexCounter,
         status: Option<OperationStatus>,
@@ -294,6 +437,35 @@ fn parse_operations(
     counter: &mut IndexCounter,
     status: Option<OperationStatus>,
 ) -> Result<Vec<Operation>, anyhow::Error> {
+    if let Some((delegation, staked_sui, amount)) = parse_withdraw_delegation(tx) {
+        return Ok(vec![Operation {
+            operation_identifier: counter.next_idx().into(),
+            related_operations: vec![],
+            type_: OperationType::WithdrawDelegation,
+            status,
+            account: Some(sender.into()),
+            // The principal is only paid back at the end of the epoch.
+            amount: None,
+            coin_change: None,
+            metadata: Some(json!({
+                "delegation": delegation,
+                "staked_sui": staked_sui,
+                "amount": amount,
+            })),
+        }]);
+    }
+    if let Some((amount, validator)) = parse_delegation(tx) {
+        return Ok(vec![Operation {
+            operation_identifier: counter.next_idx().into(),
+            related_operations: vec![],
+            type_: OperationType::Delegation,
+            status,
+            account: Some(sender.into()),
+            amount: Some(Amount::new(SignedValue::neg(amount as u128))),
+            coin_change: None,
+            metadata: Some(json!({ "validator": validator })),
+        }]);
+    }
     let operations = if let SingleTransactionKind::PaySui(tx) = tx {
         let recipients = tx.recipients.iter().zip(&tx.amounts);
         let mut aggregated_recipients: HashMap<SuiAddress, u64> = HashMap::new();
@@ -346,6 +518,76 @@ fn parse_operations(
     Ok(operations)
 }
 
+/// Returns the arguments of a call of the Sui framework `function` of the system module, which
+/// the staking operations are made of.
+fn sui_system_call<'a>(
+    tx: &'a SingleTransactionKind,
+    function: &IdentStr,
+) -> Option<&'a [CallArg]> {
+    let SingleTransactionKind::Call(MoveCall {
+        package,
+        module,
+        function: called,
+        arguments,
+        ..
+    }) = tx else {
+        return None;
+    };
+    (package.0 == SUI_FRAMEWORK_OBJECT_ID
+        && module.as_ident_str() == SUI_SYSTEM_MODULE_NAME
+        && called.as_ident_str() == function)
+        .then_some(arguments.as_slice())
+}
+
+/// Returns the amount and validator of a delegation made by the Sui framework function called by
+/// the staking operations.
+fn parse_delegation(tx: &SingleTransactionKind) -> Option<(u64, SuiAddress)> {
+    match sui_system_call(tx, ADD_DELEGATION_MUL_COIN_FUNCTION_NAME)? {
+        [_, _, CallArg::Pure(amount), CallArg::Pure(validator)] => Some((
+            bcs::from_bytes(amount).ok()?,
+            bcs::from_bytes(validator).ok()?,
+        )),
+        _ => None,
+    }
+}
+
+/// Returns the `Delegation` and `StakedSui` objects and the principal amount of a withdrawal.
+fn parse_withdraw_delegation(tx: &SingleTransactionKind) -> Option<(ObjectID, ObjectID, u64)> {
+    match sui_system_call(tx, WITHDRAW_DELEGATION_FUNCTION_NAME)? {
+        [_, delegation, staked_sui, CallArg::Pure(amount)] => Some((
+            owned_object_id(delegation)?,
+            owned_object_id(staked_sui)?,
+            bcs::from_bytes(amount).ok()?,
+        )),
+        _ => None,
+    }
+}
+
+fn owned_object_id(arg: &CallArg) -> Option<ObjectID> {
+    match arg {
+        CallArg::Object(ObjectArg::ImmOrOwnedObject(object)) => Some(object.0),
+        _ => None,
+    }
+}
+
+fn sui_system_state_arg() -> CallArg {
+    CallArg::Object(ObjectArg::SharedObject {
+        id: SUI_SYSTEM_STATE_OBJECT_ID,
+        initial_shared_version: SUI_SYSTEM_STATE_OBJECT_SHARED_VERSION,
+    })
+}
+
+fn checked_amount(amount: &Amount) -> Result<u64, Error> {
+    let amount = amount.value.abs();
+    if amount > u64::MAX as u128 {
+        return Err(Error::new_with_msg(
+            ErrorType::InvalidInput,
+            "Input amount exceed u64::MAX",
+        ));
+    }
+    Ok(amount as u64)
+}
+
 fn generic_operation(
     counter: &mut IndexCounter,
     type_: OperationType,
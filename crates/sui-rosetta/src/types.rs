@@ -411,6 +411,8 @@ pub enum OperationType {
     SuiBalanceChange,
     // sui-rosetta supported operation type
     PaySui,
+    Delegation,
+    WithdrawDelegation,
     GasBudget,
     // All other Sui transaction types, readonly
     TransferSUI,
@@ -473,13 +475,22 @@ pub struct SigningPayload {
     pub signature_type: Option<SignatureType>,
 }
 
-#[derive(Deserialize, Serialize)]
+#[derive(Deserialize, Serialize, Copy, Clone)]
 #[serde(rename_all = "lowercase")]
 pub enum SignatureType {
     Ed25519,
     Ecdsa,
 }
 
+impl From<CurveType> for SignatureType {
+    fn from(type_: CurveType) -> Self {
+        match type_ {
+            CurveType::Secp256k1 => SignatureType::Ecdsa,
+            CurveType::Edwards25519 => SignatureType::Ed25519,
+        }
+    }
+}
+
 #[derive(Deserialize)]
 pub struct ConstructionCombineRequest {
     pub network_identifier: NetworkIdentifier,
@@ -552,6 +563,12 @@ pub struct ConstructionPreprocessResponse {
 #[derive(Serialize, Deserialize)]
 pub struct MetadataOptions {
     pub sender: SuiAddress,
+    /// The gas budget of the transaction, to pick a coin of the sender covering it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub budget: Option<u64>,
+    /// The objects other than coins that the transaction takes as input.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub input_objects: Vec<ObjectID>,
 }
 
 impl IntoResponse for ConstructionPreprocessResponse {
@@ -584,6 +601,24 @@ pub struct ConstructionMetadataResponse {
 #[derive(Serialize, Deserialize)]
 pub struct ConstructionMetadata {
     pub sender_coins: Vec<ObjectRef>,
+    /// The smallest coin of the sender covering the gas budget, the first coin if unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gas: Option<ObjectRef>,
+    /// The latest references of the `input_objects` of the metadata options.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub input_objects: Vec<ObjectRef>,
+    /// The Sui framework package, needed for the Move calls of the staking operations.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sui_framework: Option<ObjectRef>,
+}
+
+impl ConstructionMetadata {
+    /// The coin of the sender paying for the gas.
+    pub fn gas(&self) -> Result<ObjectRef, Error> {
+        self.gas
+            .or_else(|| self.sender_coins.first().copied())
+            .ok_or_else(|| Error::missing_input("sender coins"))
+    }
 }
 
 impl IntoResponse for ConstructionMetadataResponse {
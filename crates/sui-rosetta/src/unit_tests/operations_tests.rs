@@ -2,10 +2,11 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use sui_types::base_types::{ObjectDigest, ObjectID, SequenceNumber, SuiAddress};
-use sui_types::messages::TransactionData;
+use sui_types::messages::{CallArg, ObjectArg, SingleTransactionKind, TransactionData};
+use sui_types::SUI_FRAMEWORK_OBJECT_ID;
 
 use crate::operations::Operation;
-use crate::types::ConstructionMetadata;
+use crate::types::{ConstructionMetadata, OperationType};
 
 #[tokio::test]
 async fn test_operation_data_parsing() -> Result<(), anyhow::Error> {
@@ -29,6 +30,9 @@ async fn test_operation_data_parsing() -> Result<(), anyhow::Error> {
     let ops = Operation::from_data(&data)?;
     let metadata = ConstructionMetadata {
         sender_coins: vec![gas],
+        gas: None,
+        input_objects: vec![],
+        sui_framework: None,
     };
 
     let parsed_data = Operation::create_data(ops, metadata).await.unwrap();
@@ -36,3 +40,116 @@ async fn test_operation_data_parsing() -> Result<(), anyhow::Error> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_delegation_data_parsing() -> Result<(), anyhow::Error> {
+    let gas = (
+        ObjectID::random(),
+        SequenceNumber::new(),
+        ObjectDigest::random(),
+    );
+    let coin = (
+        ObjectID::random(),
+        SequenceNumber::new(),
+        ObjectDigest::random(),
+    );
+    let sui_framework = (
+        SUI_FRAMEWORK_OBJECT_ID,
+        SequenceNumber::new(),
+        ObjectDigest::random(),
+    );
+
+    let sender = SuiAddress::random_for_testing_only();
+    let validator = SuiAddress::random_for_testing_only();
+    let ops = serde_json::from_value(serde_json::json!([
+        {
+            "operation_identifier": { "index": 0 },
+            "type": "Delegation",
+            "account": { "address": sender },
+            "amount": { "value": "-10000", "currency": { "symbol": "SUI", "decimals": 9 } },
+            "metadata": { "validator": validator }
+        },
+        {
+            "operation_identifier": { "index": 1 },
+            "type": "GasBudget",
+            "account": { "address": sender },
+            "metadata": { "budget": 1000 }
+        }
+    ]))?;
+    // The coin picked for the gas isn't delegated, wherever it is among the coins.
+    let metadata = || ConstructionMetadata {
+        sender_coins: vec![coin, gas],
+        gas: Some(gas),
+        input_objects: vec![],
+        sui_framework: Some(sui_framework),
+    };
+    let data = Operation::create_data(ops, metadata()).await.unwrap();
+    assert_eq!(data.signer(), sender);
+    assert_eq!(data.gas(), gas);
+    let Some(SingleTransactionKind::Call(call)) = data.kind.single_transactions().next() else {
+        panic!("Expected a Move call");
+    };
+    assert_eq!(
+        call.arguments[1],
+        CallArg::ObjVec(vec![ObjectArg::ImmOrOwnedObject(coin)])
+    );
+
+    let ops = Operation::from_data(&data)?;
+    assert_eq!(ops[0].type_, OperationType::Delegation);
+    let metadata = metadata();
+    let parsed_data = Operation::create_data(ops, metadata).await.unwrap();
+    assert_eq!(data, parsed_data);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_withdraw_delegation_data_parsing() -> Result<(), anyhow::Error> {
+    let object_ref = || {
+        (
+            ObjectID::random(),
+            SequenceNumber::new(),
+            ObjectDigest::random(),
+        )
+    };
+    let (gas, delegation, staked_sui) = (object_ref(), object_ref(), object_ref());
+    let sui_framework = (
+        SUI_FRAMEWORK_OBJECT_ID,
+        SequenceNumber::new(),
+        ObjectDigest::random(),
+    );
+
+    let sender = SuiAddress::random_for_testing_only();
+    let ops: Vec<Operation> = serde_json::from_value(serde_json::json!([
+        {
+            "operation_identifier": { "index": 0 },
+            "type": "WithdrawDelegation",
+            "account": { "address": sender },
+            "metadata": { "delegation": delegation.0, "staked_sui": staked_sui.0, "amount": 10000 }
+        },
+        {
+            "operation_identifier": { "index": 1 },
+            "type": "GasBudget",
+            "account": { "address": sender },
+            "metadata": { "budget": 1000 }
+        }
+    ]))?;
+    assert_eq!(ops[0].withdrawn_delegation()?, (delegation.0, staked_sui.0));
+    let metadata = || ConstructionMetadata {
+        sender_coins: vec![gas],
+        gas: Some(gas),
+        input_objects: vec![staked_sui, delegation],
+        sui_framework: Some(sui_framework),
+    };
+    let data = Operation::create_data(ops, metadata()).await.unwrap();
+    assert_eq!(data.signer(), sender);
+    assert_eq!(data.gas(), gas);
+
+    let ops = Operation::from_data(&data)?;
+    assert_eq!(ops[0].type_, OperationType::WithdrawDelegation);
+    assert!(ops[0].amount.is_none());
+    let parsed_data = Operation::create_data(ops, metadata()).await.unwrap();
+    assert_eq!(data, parsed_data);
+
+    Ok(())
+}
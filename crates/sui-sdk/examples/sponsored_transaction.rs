@@ -0,0 +1,64 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::str::FromStr;
+use sui_keys::keystore::{AccountKeystore, FileBasedKeystore, Keystore};
+use sui_sdk::{
+    types::{
+        base_types::{ObjectID, SuiAddress},
+        messages::{GasSponsorship, Transaction},
+    },
+    SuiClient,
+};
+use sui_types::intent::Intent;
+use sui_types::messages::ExecuteTransactionRequestType;
+
+#[tokio::main]
+async fn main() -> Result<(), anyhow::Error> {
+    let sui = SuiClient::new("https://fullnode.devnet.sui.io:443", None, None).await?;
+    // Load keystore from ~/.sui/sui_config/sui.keystore
+    let keystore_path = match dirs::home_dir() {
+        Some(v) => v.join(".sui").join("sui_config").join("sui.keystore"),
+        None => panic!("Cannot obtain home directory path"),
+    };
+
+    let my_address = SuiAddress::from_str("0x47722589dc23d63e82862f7814070002ffaaa465")?;
+    let object_id = ObjectID::from_str("0x273b2a83f1af1fda3ddbc02ad31367fcb146a814")?;
+    let recipient = SuiAddress::from_str("0xbd42a850e81ebb8f80283266951d4f4f5722e301")?;
+    // The sponsor pays for the gas of the transfer
+    let sponsor = SuiAddress::from_str("0x9c8f2f8d0fbd2b9d6c1c5bbb1ce3976e4dc9cd35")?;
+
+    // Create an object transfer transaction, then pay for its gas from a coin of the sponsor
+    let transfer_tx = sui
+        .transaction_builder()
+        .transfer_object(my_address, object_id, None, 1000, recipient)
+        .await?;
+    let sponsored_tx = sui
+        .transaction_builder()
+        .sponsor(transfer_tx, sponsor, None)
+        .await?;
+
+    // Both the sender and the sponsor sign the transaction
+    let keystore = Keystore::from(FileBasedKeystore::new(&keystore_path)?);
+    let signature = keystore.sign_secure(&my_address, &sponsored_tx, Intent::default())?;
+    let sponsor_signature = keystore.sign_secure(&sponsor, &sponsored_tx, Intent::default())?;
+
+    // Execute the transaction
+    let transaction_response = sui
+        .quorum_driver()
+        .execute_transaction(
+            Transaction::from_sponsored_data(
+                sponsored_tx,
+                Intent::default(),
+                signature,
+                GasSponsorship::new(sponsor, sponsor_signature),
+            )
+            .verify()?,
+            Some(ExecuteTransactionRequestType::WaitForLocalExecution),
+        )
+        .await?;
+
+    println!("{:?}", transaction_response);
+
+    Ok(())
+}
@@ -2,18 +2,21 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::error::{RpcError, SuiRpcResult};
-use crate::{RpcClient, TransactionExecutionResult, WAIT_FOR_TX_TIMEOUT_SEC};
+use crate::{GasEstimate, RpcClient, TransactionExecutionResult, WAIT_FOR_TX_TIMEOUT_SEC};
+use fastcrypto::encoding::Base64;
 use futures::stream;
 use futures_core::Stream;
 use jsonrpsee::core::client::Subscription;
+use jsonrpsee::rpc_params;
+use jsonrpsee::ws_client::WsClient;
 use std::collections::BTreeMap;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use sui_json_rpc_types::{
     Balance, Coin, CoinPage, EventPage, GetObjectDataResponse, GetPastObjectDataResponse,
-    GetRawObjectDataResponse, SuiCoinMetadata, SuiEventEnvelope, SuiEventFilter,
-    SuiExecuteTransactionResponse, SuiMoveNormalizedModule, SuiObjectInfo, SuiTransactionResponse,
-    TransactionsPage,
+    GetRawObjectDataResponse, ObjectsPage, SuiCheckpoint, SuiCoinMetadata, SuiEventEnvelope,
+    SuiEventFilter, SuiExecuteTransactionResponse, SuiExecutionStatus, SuiMoveNormalizedModule,
+    SuiObjectInfo, SuiTransactionEffects, SuiTransactionResponse, TransactionsPage,
 };
 use sui_types::balance::Supply;
 use sui_types::base_types::{ObjectID, SequenceNumber, SuiAddress, TransactionDigest};
@@ -21,17 +24,25 @@ use sui_types::batch::TxSequenceNumber;
 use sui_types::committee::EpochId;
 use sui_types::error::TRANSACTION_NOT_FOUND_MSG_PREFIX;
 use sui_types::event::EventID;
+use sui_types::gas::{MAX_GAS_BUDGET, MIN_GAS_BUDGET};
+use sui_types::gas_coin::GasCoin;
 use sui_types::messages::{
-    CommitteeInfoResponse, ExecuteTransactionRequestType, VerifiedTransaction,
+    CommitteeInfoResponse, ExecuteTransactionRequestType, QuorumDriverRetryPolicy,
+    SingleTransactionKind, TransactionData, TransactionKind, VerifiedTransaction,
 };
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
 use sui_types::query::{EventQuery, TransactionQuery};
 use sui_types::sui_system_state::SuiSystemState;
 
 use futures::StreamExt;
 use sui_json_rpc::api::{
-    CoinReadApiClient, EventReadApiClient, EventStreamingApiClient, RpcBcsApiClient,
-    RpcFullNodeReadApiClient, RpcReadApiClient, TransactionExecutionApiClient,
+    CheckpointStreamingApiClient, CoinReadApiClient, EventReadApiClient, EventStreamingApiClient,
+    RpcBcsApiClient, RpcFullNodeReadApiClient, RpcReadApiClient, TransactionExecutionApiClient,
 };
+
+const MIN_RECONNECT_DELAY: Duration = Duration::from_secs(1);
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(30);
+
 #[derive(Debug)]
 pub struct ReadApi {
     api: Arc<RpcClient>,
@@ -42,11 +53,34 @@ impl ReadApi {
         Self { api }
     }
 
+    /// Return all the objects owned by `address`, querying them page by page.
     pub async fn get_objects_owned_by_address(
         &self,
         address: SuiAddress,
     ) -> SuiRpcResult<Vec<SuiObjectInfo>> {
-        Ok(self.api.http.get_objects_owned_by_address(address).await?)
+        let mut objects = Vec::new();
+        let mut cursor = None;
+        loop {
+            let page = self.get_owned_objects(address, cursor, None).await?;
+            objects.extend(page.data);
+            if !page.has_next_page {
+                return Ok(objects);
+            }
+            cursor = page.next_cursor;
+        }
+    }
+
+    pub async fn get_owned_objects(
+        &self,
+        address: SuiAddress,
+        cursor: Option<ObjectID>,
+        limit: Option<usize>,
+    ) -> SuiRpcResult<ObjectsPage> {
+        Ok(self
+            .api
+            .http
+            .get_owned_objects(address, cursor, limit)
+            .await?)
     }
 
     pub async fn get_objects_owned_by_object(
@@ -63,6 +97,16 @@ impl ReadApi {
         Ok(self.api.http.get_object(object_id).await?)
     }
 
+    /// Returns the objects of `object_ids` in order, fetched with batch requests. An object which
+    /// can't be fetched has the error of its request.
+    pub async fn multi_get_parsed_objects(
+        &self,
+        object_ids: &[ObjectID],
+    ) -> SuiRpcResult<Vec<SuiRpcResult<GetObjectDataResponse>>> {
+        let params = object_ids.iter().map(|id| rpc_params![id]).collect();
+        self.api.batch_request("sui_getObject", params).await
+    }
+
     pub async fn try_get_parsed_past_object(
         &self,
         object_id: ObjectID,
@@ -98,6 +142,37 @@ impl ReadApi {
         Ok(self.api.http.get_transaction(digest).await?)
     }
 
+    /// Returns the transactions of `digests` in order, fetched with batch requests. A transaction
+    /// which can't be fetched has the error of its request.
+    pub async fn multi_get_transactions(
+        &self,
+        digests: &[TransactionDigest],
+    ) -> SuiRpcResult<Vec<SuiRpcResult<SuiTransactionResponse>>> {
+        let params = digests.iter().map(|digest| rpc_params![digest]).collect();
+        self.api.batch_request("sui_getTransaction", params).await
+    }
+
+    /// Subscribes to the checkpoints certified by the network, in order of sequence number and
+    /// starting from `from_sequence_number`, with the digests of their transactions and effects
+    /// when `with_contents` is set.
+    pub async fn subscribe_checkpoint(
+        &self,
+        from_sequence_number: Option<CheckpointSequenceNumber>,
+        with_contents: bool,
+    ) -> SuiRpcResult<impl Stream<Item = SuiRpcResult<SuiCheckpoint>>> {
+        match &self.api.ws {
+            Some(c) => {
+                let subscription: Subscription<SuiCheckpoint> = c
+                    .subscribe_checkpoint(from_sequence_number, Some(with_contents))
+                    .await?;
+                Ok(subscription.map(|item| Ok(item?)))
+            }
+            _ => Err(RpcError::Subscription(
+                "Subscription only supported by WebSocket client.".to_string(),
+            )),
+        }
+    }
+
     pub async fn get_committee_info(
         &self,
         epoch: Option<EpochId>,
@@ -160,6 +235,78 @@ impl ReadApi {
     pub async fn get_sui_system_state(&self) -> SuiRpcResult<SuiSystemState> {
         Ok(self.api.http.get_sui_system_state().await?)
     }
+
+    pub async fn dry_run_transaction(
+        &self,
+        tx: &TransactionData,
+    ) -> SuiRpcResult<SuiTransactionEffects> {
+        let tx_bytes = bcs::to_bytes(tx).map_err(|e| RpcError::DataError(e.to_string()))?;
+        Ok(self
+            .api
+            .http
+            .dry_run_transaction(Base64::from_bytes(&tx_bytes))
+            .await?)
+    }
+
+    /// Estimates the gas used by `tx` by dry running it with the highest budget its gas coin can
+    /// pay, whatever its budget. The suggested budget is the gas used with `safety_margin_percent`
+    /// more, for the cost of the objects to change before the transaction is executed.
+    pub async fn estimate_gas(
+        &self,
+        tx: &TransactionData,
+        safety_margin_percent: u64,
+    ) -> SuiRpcResult<GasEstimate> {
+        let storage_gas_price = self
+            .get_sui_system_state()
+            .await?
+            .parameters
+            .storage_gas_price
+            .max(1);
+        let computation_gas_price = tx.gas_price.max(1);
+        let gas_coin = self
+            .get_parsed_object(tx.gas_payment_object_ref().0)
+            .await?
+            .into_object()
+            .map_err(|e| RpcError::DataError(e.to_string()))?;
+        let balance = GasCoin::try_from(&gas_coin)
+            .map_err(|e| RpcError::DataError(e.to_string()))?
+            .value();
+        // The gas coin also pays the amounts sent by the transaction.
+        let amount = match &tx.kind {
+            TransactionKind::Single(SingleTransactionKind::TransferSui(t)) => {
+                t.amount.unwrap_or_default()
+            }
+            TransactionKind::Single(SingleTransactionKind::PaySui(t)) => t.amounts.iter().sum(),
+            _ => 0,
+        };
+        let mut dry_run_tx = tx.clone();
+        dry_run_tx.gas_budget = (balance.saturating_sub(amount)
+            / computation_gas_price.max(storage_gas_price))
+        .min(*MAX_GAS_BUDGET);
+
+        let effects = self.dry_run_transaction(&dry_run_tx).await?;
+        if let SuiExecutionStatus::Failure { error } = effects.status {
+            return Err(RpcError::DataError(format!(
+                "The dry run of the transaction failed: {error}"
+            )));
+        }
+        // The rebate of the storage is only refunded once the budget pays for the computation
+        // and the storage.
+        let gas_used = effects.gas_used;
+        let gas_units = div_ceil(gas_used.computation_cost, computation_gas_price)
+            + div_ceil(gas_used.storage_cost, storage_gas_price);
+        let suggested_budget = div_ceil(gas_units * (100 + safety_margin_percent), 100)
+            .clamp(*MIN_GAS_BUDGET, *MAX_GAS_BUDGET);
+        Ok(GasEstimate {
+            gas_used,
+            gas_units,
+            suggested_budget,
+        })
+    }
+}
+
+fn div_ceil(a: u64, b: u64) -> u64 {
+    (a + b - 1) / b
 }
 
 #[derive(Debug, Clone)]
@@ -246,7 +393,7 @@ impl EventApi {
         match &self.api.ws {
             Some(c) => {
                 let subscription: Subscription<SuiEventEnvelope> =
-                    c.subscribe_event(filter).await?;
+                    c.subscribe_event(filter, None).await?;
                 Ok(subscription.map(|item| Ok(item?)))
             }
             _ => Err(RpcError::Subscription(
@@ -255,6 +402,70 @@ impl EventApi {
         }
     }
 
+    /// Subscribes to the events matching `filter`, like [Self::subscribe_event], but reconnects
+    /// when the websocket drops and resumes the subscription after the last event received, so
+    /// that no event is missed or received twice. The stream starts after the event `cursor`, or
+    /// with the events emitted from now on, and only ends if the server rejects the subscription.
+    /// The event IDs are local to a fullnode, so the websocket URL must always reach the same one.
+    pub fn subscribe_event_with_reconnect(
+        &self,
+        filter: SuiEventFilter,
+        cursor: Option<EventID>,
+    ) -> impl Stream<Item = SuiRpcResult<SuiEventEnvelope>> + '_ {
+        let state = EventSubscriptionState {
+            filter,
+            cursor,
+            connection: None,
+            retry_delay: MIN_RECONNECT_DELAY,
+        };
+        stream::unfold(Some(state), move |state| async move {
+            let mut state = state?;
+            loop {
+                if state.connection.is_none() {
+                    match self.resubscribe(&state).await {
+                        Ok(connection) => state.connection = Some(connection),
+                        // Retrying doesn't help if the server rejected the subscription.
+                        Err(
+                            e @ (RpcError::Subscription(_)
+                            | RpcError::RpcError(jsonrpsee::core::Error::Call(_))),
+                        ) => return Some((Err(e), None)),
+                        Err(_) => {
+                            tokio::time::sleep(state.retry_delay).await;
+                            state.retry_delay = (state.retry_delay * 2).min(MAX_RECONNECT_DELAY);
+                            continue;
+                        }
+                    }
+                }
+                let Some((_, subscription)) = &mut state.connection else {
+                    continue;
+                };
+                match subscription.next().await {
+                    Some(Ok(event)) => {
+                        state.cursor = Some(event.id.clone());
+                        state.retry_delay = MIN_RECONNECT_DELAY;
+                        return Some((Ok(event), Some(state)));
+                    }
+                    Some(Err(jsonrpsee::core::Error::RestartNeeded(_))) | None => {
+                        state.connection = None;
+                    }
+                    Some(Err(e)) => return Some((Err(e.into()), Some(state))),
+                }
+            }
+        })
+    }
+
+    async fn resubscribe(
+        &self,
+        state: &EventSubscriptionState,
+    ) -> SuiRpcResult<(WsClient, Subscription<SuiEventEnvelope>)> {
+        // The subscriptions end with the client they were made with.
+        let client = self.api.reconnect_ws().await?;
+        let subscription = client
+            .subscribe_event(state.filter.clone(), state.cursor.clone())
+            .await?;
+        Ok((client, subscription))
+    }
+
     pub async fn get_events(
         &self,
         query: EventQuery,
@@ -297,6 +508,14 @@ impl EventApi {
     }
 }
 
+struct EventSubscriptionState {
+    filter: SuiEventFilter,
+    /// The ID of the last event received.
+    cursor: Option<EventID>,
+    connection: Option<(WsClient, Subscription<SuiEventEnvelope>)>,
+    retry_delay: Duration,
+}
+
 #[derive(Clone)]
 pub struct QuorumDriver {
     api: Arc<RpcClient>,
@@ -313,24 +532,54 @@ impl QuorumDriver {
     /// but returned `confirmed_local_execution` is false, the client polls
     /// the fullnode untils the fullnode recognizes this transaction, or
     /// until times out (see WAIT_FOR_TX_TIMEOUT_SEC). If it times out, an
-    /// error is returned from this call.
+    /// error is returned from this call. A sponsored transaction is sent
+    /// with the signature of its gas sponsor.
     pub async fn execute_transaction(
         &self,
         tx: VerifiedTransaction,
         request_type: Option<ExecuteTransactionRequestType>,
     ) -> SuiRpcResult<TransactionExecutionResult> {
-        let (tx_bytes, flag, signature, pub_key) = tx.to_network_data_for_execution();
+        self.execute_transaction_with_retry_policy(tx, request_type, None)
+            .await
+    }
+
+    /// Execute a transaction like [`QuorumDriver::execute_transaction`], asking the FullNode to
+    /// retry the transient failures of the validators as set by `retry_policy`, or by its default
+    /// policy if `None`.
+    pub async fn execute_transaction_with_retry_policy(
+        &self,
+        tx: VerifiedTransaction,
+        request_type: Option<ExecuteTransactionRequestType>,
+        retry_policy: Option<QuorumDriverRetryPolicy>,
+    ) -> SuiRpcResult<TransactionExecutionResult> {
+        // The serialized signature also carries the multisigs.
+        let (tx_bytes, signature) = tx.to_tx_bytes_and_signature();
         let request_type =
             request_type.unwrap_or(ExecuteTransactionRequestType::WaitForLocalExecution);
-        let resp = TransactionExecutionApiClient::execute_transaction(
-            &self.api.http,
-            tx_bytes,
-            flag,
-            signature,
-            pub_key,
-            request_type.clone(),
-        )
-        .await?;
+        let resp = match tx.to_sponsor_and_signature() {
+            Some((gas_sponsor, sponsor_signature)) => {
+                TransactionExecutionApiClient::execute_sponsored_transaction(
+                    &self.api.http,
+                    tx_bytes,
+                    signature,
+                    gas_sponsor,
+                    sponsor_signature,
+                    request_type.clone(),
+                    retry_policy,
+                )
+                .await?
+            }
+            None => {
+                TransactionExecutionApiClient::execute_transaction_serialized_sig(
+                    &self.api.http,
+                    tx_bytes,
+                    signature,
+                    request_type.clone(),
+                    retry_policy,
+                )
+                .await?
+            }
+        };
 
         Ok(match (request_type, resp) {
             (
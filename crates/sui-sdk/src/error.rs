@@ -22,6 +22,8 @@ pub enum RpcError {
     FailToConfirmTransactionStatus(TransactionDigest, u64),
     #[error("Data error: {0}")]
     DataError(String),
+    #[error("Batch request error {code}: {message}")]
+    BatchItemError { code: i32, message: String },
     #[error("Client/Server api version mismatch, client api version : {client_version}, server api version : {server_version}")]
     ServerVersionMismatch {
         client_version: String,
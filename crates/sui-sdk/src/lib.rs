@@ -8,14 +8,20 @@ use std::time::Duration;
 
 use async_trait::async_trait;
 use jsonrpsee::core::client::ClientT;
+use jsonrpsee::core::params::{ArrayParams, BatchRequestBuilder};
 use jsonrpsee::http_client::{HttpClient, HttpClientBuilder};
 use jsonrpsee::rpc_params;
 use jsonrpsee::ws_client::{WsClient, WsClientBuilder};
 
 use crate::error::{RpcError, SuiRpcResult};
-use rpc_types::{SuiCertifiedTransaction, SuiParsedTransactionResponse, SuiTransactionEffects};
+use rpc_types::{
+    SuiCertifiedTransaction, SuiGasCostSummary, SuiParsedTransactionResponse, SuiTransactionEffects,
+};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
 use serde_json::Value;
 pub use sui_json as json;
+use sui_json_rpc::batch::DEFAULT_MAX_BATCH_SIZE;
 
 use crate::apis::{CoinReadApi, EventApi, QuorumDriver, ReadApi};
 pub use sui_json_rpc_types as rpc_types;
@@ -39,6 +45,15 @@ pub struct TransactionExecutionResult {
     pub parsed_data: Option<SuiParsedTransactionResponse>,
 }
 
+/// The gas used by the dry run of a transaction, and the budget to execute it with.
+#[derive(Debug, Clone, Serialize)]
+pub struct GasEstimate {
+    pub gas_used: SuiGasCostSummary,
+    /// The units of the budget the dry run needed, for the computation and the storage.
+    pub gas_units: u64,
+    pub suggested_budget: u64,
+}
+
 #[derive(Clone)]
 pub struct SuiClient {
     api: Arc<RpcClient>,
@@ -52,6 +67,8 @@ pub struct SuiClient {
 pub(crate) struct RpcClient {
     http: HttpClient,
     ws: Option<WsClient>,
+    ws_url: Option<String>,
+    request_timeout: Option<Duration>,
     info: ServerInfo,
 }
 
@@ -83,18 +100,41 @@ impl RpcClient {
         }
         let http = http_builder.build(http)?;
 
-        let ws = if let Some(url) = ws {
-            let mut ws_builder = WsClientBuilder::default();
-            if let Some(request_timeout) = request_timeout {
-                ws_builder = ws_builder.request_timeout(request_timeout);
-            }
-            let ws = ws_builder.build(url).await?;
-            Some(ws)
+        let ws_url = ws.map(str::to_string);
+        let ws = if let Some(url) = &ws_url {
+            Some(Self::connect_ws(url, request_timeout).await?)
         } else {
             None
         };
         let info = Self::get_server_info(&http, &ws).await?;
-        Ok(Self { http, ws, info })
+        Ok(Self {
+            http,
+            ws,
+            ws_url,
+            request_timeout,
+            info,
+        })
+    }
+
+    async fn connect_ws(
+        url: &str,
+        request_timeout: Option<Duration>,
+    ) -> Result<WsClient, RpcError> {
+        let mut ws_builder = WsClientBuilder::default();
+        if let Some(request_timeout) = request_timeout {
+            ws_builder = ws_builder.request_timeout(request_timeout);
+        }
+        Ok(ws_builder.build(url).await?)
+    }
+
+    /// Opens a new websocket connection to the server, e.g. to replace one that was dropped.
+    pub(crate) async fn reconnect_ws(&self) -> Result<WsClient, RpcError> {
+        match &self.ws_url {
+            Some(url) => Self::connect_ws(url, self.request_timeout).await,
+            None => Err(RpcError::Subscription(
+                "Subscription only supported by WebSocket client.".to_string(),
+            )),
+        }
     }
 
     async fn get_server_info(
@@ -125,6 +165,34 @@ impl RpcClient {
         })
     }
 
+    /// Calls `method` once for each of `params` with batch requests, returning the result of each
+    /// call in order. The calls are split in batches small enough for the default limit of the
+    /// server.
+    pub(crate) async fn batch_request<R: DeserializeOwned>(
+        &self,
+        method: &str,
+        params: Vec<ArrayParams>,
+    ) -> SuiRpcResult<Vec<SuiRpcResult<R>>> {
+        let mut results = Vec::with_capacity(params.len());
+        let mut params = params.into_iter().peekable();
+        while params.peek().is_some() {
+            let mut batch = BatchRequestBuilder::new();
+            for params in params.by_ref().take(DEFAULT_MAX_BATCH_SIZE) {
+                batch
+                    .insert(method, params)
+                    .map_err(jsonrpsee::core::Error::ParseError)?;
+            }
+            let responses = self.http.batch_request::<R>(batch).await?;
+            results.extend(responses.into_iter().map(|response| {
+                response.map_err(|error| RpcError::BatchItemError {
+                    code: error.code(),
+                    message: error.message().to_string(),
+                })
+            }));
+        }
+        Ok(results)
+    }
+
     fn parse_methods(server_spec: &Value) -> Result<Vec<String>, RpcError> {
         let methods = server_spec
             .pointer("/methods")
@@ -491,6 +491,14 @@ pub trait EventStore {
         limit: usize,
         descending: bool,
     ) -> Result<Vec<StoredEvent>, SuiError>;
+
+    /// Deletes the events emitted by the given transactions.
+    ///
+    /// Returns Ok(rows_affected).
+    async fn delete_events_of_transactions(
+        &self,
+        digests: &[TransactionDigest],
+    ) -> Result<u64, SuiError>;
 }
 
 /// EventStoreType contains different implementations of EventStores, but implements the EventStore trait.
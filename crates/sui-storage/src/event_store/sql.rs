@@ -134,6 +134,25 @@ impl SqlEventStore {
         Ok(Self { pool })
     }
 
+    /// Opens an existing SQLite EventStore on disk in read-only mode, in which any write fails.
+    /// The database is not initialized, it must have been by the node which wrote it.
+    pub async fn new_read_only_from_file(db_path: &Path) -> Result<Self, SuiError> {
+        let mut options = SqliteConnectOptions::new()
+            .filename(db_path)
+            .read_only(true);
+        options.log_statements(log::LevelFilter::Off);
+
+        let pool = PoolOptions::<Sqlite>::new()
+            .max_connections(100)
+            .connect_with(options)
+            .await
+            .map_err(convert_sqlx_err)?;
+
+        info!(?db_path, "Opened read-only SQLite EventStore on disk");
+
+        Ok(Self { pool })
+    }
+
     /// Starts a WAL truncation/cleanup periodic task at interval duration
     pub async fn wal_cleanup_thread(&self, wal_cleanup_interval: Option<Duration>) {
         if let Some(cleanup_interval) = wal_cleanup_interval {
@@ -618,6 +637,29 @@ impl EventStore for SqlEventStore {
             .map_err(convert_sqlx_err)?;
         Ok(rows)
     }
+
+    #[instrument(level = "debug", skip_all, err)]
+    async fn delete_events_of_transactions(
+        &self,
+        digests: &[TransactionDigest],
+    ) -> Result<u64, SuiError> {
+        let mut rows_affected = 0;
+        for chunk in digests.chunks(MAX_INSERT_BATCH) {
+            let mut query_builder = QueryBuilder::new("DELETE FROM events WHERE tx_digest IN (");
+            let mut separated = query_builder.separated(", ");
+            for digest in chunk {
+                separated.push_bind(digest.to_bytes());
+            }
+            separated.push_unseparated(")");
+            let res = query_builder
+                .build()
+                .execute(&self.pool)
+                .await
+                .map_err(convert_sqlx_err)?;
+            rows_affected += res.rows_affected();
+        }
+        Ok(rows_affected)
+    }
 }
 
 fn convert_sqlx_err(err: sqlx::Error) -> SuiError {
@@ -1350,6 +1392,44 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_eventstore_read_only() -> Result<(), SuiError> {
+        telemetry_subscribers::init_for_testing();
+
+        let dir = tempfile::TempDir::new().unwrap(); // NOTE this must be its own line so dir isn't dropped
+        let db_file = dir.path().join("events.db");
+        let db = SqlEventStore::new_from_file(&db_file).await?;
+        db.initialize().await?;
+        let to_insert = vec![test_utils::new_test_newobj_event(
+            1_000_000,
+            TransactionDigest::random(),
+            1,
+            0, // event_num
+            None,
+            None,
+            None,
+        )];
+        db.add_events(&to_insert).await?;
+
+        let read_only_db = SqlEventStore::new_read_only_from_file(&db_file).await?;
+        let events = read_only_db
+            .events_by_transaction(to_insert[0].tx_digest.unwrap(), (0, 0).into(), 10, false)
+            .await?;
+        assert_eq!(events.len(), 1);
+
+        let to_insert = vec![test_utils::new_test_newobj_event(
+            1_001_000,
+            TransactionDigest::random(),
+            2,
+            0, // event_num
+            None,
+            None,
+            None,
+        )];
+        assert!(read_only_db.add_events(&to_insert).await.is_err());
+        Ok(())
+    }
+
     // Test Idempotency / Sequence Numbering
     #[tokio::test]
     async fn test_eventstore_seq_num() -> Result<(), SuiError> {
@@ -1445,6 +1525,34 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_eventstore_delete_events_of_transactions() -> Result<(), SuiError> {
+        telemetry_subscribers::init_for_testing();
+
+        // Initialize store
+        let db = SqlEventStore::new_memory_only_not_prod().await?;
+        db.initialize().await?;
+
+        let (pruned, kept) = (TransactionDigest::random(), TransactionDigest::random());
+        let to_insert = vec![
+            test_utils::new_test_newobj_event(1_000_000, pruned, 1, 0, None, None, None),
+            test_utils::new_test_deleteobj_event(1_000_000, pruned, 1, 1, None, None),
+            test_utils::new_test_publish_event(1_001_000, kept, 2, 0, None),
+        ];
+        db.add_events(&to_insert).await?;
+
+        assert_eq!(db.delete_events_of_transactions(&[pruned]).await?, 2);
+        let events = db
+            .events_by_transaction(pruned, (0, 0).into(), 10, false)
+            .await?;
+        assert!(events.is_empty());
+        let events = db
+            .events_by_transaction(kept, (0, 0).into(), 10, false)
+            .await?;
+        assert_eq!(events.len(), 1);
+        Ok(())
+    }
+
     #[test]
     fn event_query_test() {
         let query = get_event_query(vec![], false);
@@ -139,6 +139,61 @@ impl IndexStore {
         Ok(())
     }
 
+    /// Deletes the entries added by [`Self::index_tx`] for the transaction `digest`, given the
+    /// same sender, inputs, mutated objects and move calls. Does nothing if the transaction was
+    /// not indexed.
+    pub fn prune_tx(
+        &self,
+        sender: SuiAddress,
+        active_inputs: impl Iterator<Item = ObjectID>,
+        mutated_objects: impl Iterator<Item = (ObjectRef, Owner)> + Clone,
+        move_functions: impl Iterator<Item = (ObjectID, Identifier, Identifier)>,
+        digest: &TransactionDigest,
+    ) -> SuiResult {
+        let Some(sequence) = self.transactions_seq.get(digest)? else {
+            return Ok(());
+        };
+        let batch = self.transactions_from_addr.batch();
+
+        let batch = batch.delete_batch(
+            &self.transactions_from_addr,
+            std::iter::once((sender, sequence)),
+        )?;
+
+        let batch = batch.delete_batch(
+            &self.transactions_by_input_object_id,
+            active_inputs.map(|id| (id, sequence)),
+        )?;
+
+        let batch = batch.delete_batch(
+            &self.transactions_by_mutated_object_id,
+            mutated_objects
+                .clone()
+                .map(|(obj_ref, _)| (obj_ref.0, sequence)),
+        )?;
+
+        let batch = batch.delete_batch(
+            &self.transactions_by_move_function,
+            move_functions.map(|(obj_id, module, function)| {
+                (obj_id, module.to_string(), function.to_string(), sequence)
+            }),
+        )?;
+
+        let batch = batch.delete_batch(
+            &self.transactions_to_addr,
+            mutated_objects.filter_map(|(_, owner)| {
+                owner.get_owner_address().ok().map(|addr| (addr, sequence))
+            }),
+        )?;
+
+        let batch = batch.delete_batch(&self.timestamps, std::iter::once(*digest))?;
+        let batch = batch.delete_batch(&self.transactions_seq, std::iter::once(*digest))?;
+
+        batch.write()?;
+
+        Ok(())
+    }
+
     /// Returns unix timestamp for a transaction if it exists
     pub fn get_timestamp_ms(
         &self,
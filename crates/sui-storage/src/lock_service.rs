@@ -642,8 +642,23 @@ impl LockService {
     /// Create a new instance of LockService.  For now, the caller has to guarantee only one per data store -
     /// namely each SuiDataStore creates its own LockService.
     pub fn new(path: PathBuf, db_options: Option<Options>) -> Result<Self, SuiError> {
-        let inner_service = LockServiceImpl::open_tables_read_write(path, db_options, None);
+        Self::start(LockServiceImpl::open_tables_read_write(
+            path, db_options, None,
+        ))
+    }
+
+    /// Create a new instance of LockService over the existing locks at `path`, opened in read only
+    /// mode: the locks can be read, but acquiring, initializing and deleting them fails.
+    pub fn new_read_only_mode(
+        path: PathBuf,
+        db_options: Option<Options>,
+    ) -> Result<Self, SuiError> {
+        Self::start(LockServiceImpl::open_tables_read_only_mode(
+            path, db_options, None,
+        )?)
+    }
 
+    fn start(inner_service: LockServiceImpl) -> Result<Self, SuiError> {
         // Now, create a sync channel and spawn a thread
         let (sender, receiver) = channel(LOCKSERVICE_QUEUE_LEN);
         let inner2 = inner_service.clone();
@@ -18,7 +18,10 @@ use typed_store_derive::DBMapUtils;
 
 use sui_types::error::{SuiError, SuiResult};
 
-use typed_store::{rocks::DBMap, traits::Map};
+use typed_store::{
+    rocks::{DBMap, TypedStoreError},
+    traits::Map,
+};
 
 use tracing::{debug, error, instrument, trace, warn};
 
@@ -221,8 +224,19 @@ where
     ExecutionOutput: Serialize + DeserializeOwned + Debug,
 {
     pub fn new(path: PathBuf) -> Self {
-        let tables = DBWriteAheadLogTables::open_tables_read_write(path, None, None);
+        Self::from_tables(DBWriteAheadLogTables::open_tables_read_write(
+            path, None, None,
+        ))
+    }
+
+    /// Opens an existing log in read only mode, in which any write to it fails.
+    pub fn new_read_only_mode(path: PathBuf) -> Result<Self, TypedStoreError> {
+        Ok(Self::from_tables(
+            DBWriteAheadLogTables::open_tables_read_only_mode(path, None, None)?,
+        ))
+    }
 
+    fn from_tables(tables: DBWriteAheadLogTables<C, ExecutionOutput>) -> Self {
         // Read in any digests that were left in the log, e.g. due to a crash.
         //
         // This list will normally be small - it will typically only include txes that were
@@ -1,12 +1,12 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use futures::FutureExt;
+use anyhow::{anyhow, Result};
 use prometheus::Registry;
 use std::net::{IpAddr, SocketAddr};
 use sui_config::NodeConfig;
 use sui_node::SuiNode;
-use tracing::trace;
+use tracing::{trace, warn};
 
 use super::node::RuntimeType;
 
@@ -14,6 +14,8 @@ use super::node::RuntimeType;
 pub(crate) struct Container {
     join_handle: Option<ContainerJoinHandle>,
     cancel_sender: Option<tokio::sync::oneshot::Sender<()>>,
+    epoch_change_sender: tokio::sync::mpsc::UnboundedSender<()>,
+    consensus_epoch: tokio::sync::watch::Receiver<u64>,
 }
 
 #[derive(Debug)]
@@ -44,7 +46,10 @@ impl Container {
         _runtime: RuntimeType,
     ) -> (tokio::sync::oneshot::Receiver<()>, Self) {
         let (startup_sender, startup_reciever) = tokio::sync::oneshot::channel();
-        let (cancel_sender, cancel_reciever) = tokio::sync::oneshot::channel();
+        let (cancel_sender, mut cancel_reciever) = tokio::sync::oneshot::channel();
+        let (epoch_change_sender, mut epoch_change_receiver) =
+            tokio::sync::mpsc::unbounded_channel();
+        let (consensus_epoch_sender, consensus_epoch) = tokio::sync::watch::channel(0);
 
         let handle = sui_simulator::runtime::Handle::current();
         let builder = handle.create_node();
@@ -66,12 +71,26 @@ impl Container {
 
         let task_handle = node.spawn(async move {
             let registry_service = mysten_metrics::RegistryService::new(Registry::new());
-            let _server = SuiNode::start(&config, registry_service).await.unwrap();
+            let server = SuiNode::start(&config, registry_service).await.unwrap();
+            let mut server_consensus_epoch = server.state().subscribe_consensus_epoch();
             // Notify that we've successfully started the node
             trace!("node started, sending oneshot");
             let _ = startup_sender.send(());
             // run until canceled
-            cancel_reciever.map(|_| ()).await;
+            loop {
+                tokio::select! {
+                    _ = &mut cancel_reciever => break,
+                    Some(()) = epoch_change_receiver.recv() => {
+                        if let Err(err) = server.force_epoch_change().await {
+                            warn!("failed to force an epoch change: {err}");
+                        }
+                    }
+                    Ok(()) = server_consensus_epoch.changed() => {
+                        let epoch = *server_consensus_epoch.borrow();
+                        let _ = consensus_epoch_sender.send(epoch);
+                    }
+                }
+            }
             trace!("cancellation received; shutting down thread");
         });
 
@@ -83,6 +102,8 @@ impl Container {
                     task_handle,
                 }),
                 cancel_sender: Some(cancel_sender),
+                epoch_change_sender,
+                consensus_epoch,
             },
         )
     }
@@ -97,4 +118,30 @@ impl Container {
             false
         }
     }
+
+    /// Ask the node running in this Container to end its current consensus epoch.
+    pub fn force_epoch_change(&self) -> bool {
+        self.epoch_change_sender.send(()).is_ok()
+    }
+
+    /// The Narwhal epoch of the consensus output handled by the node running in this Container.
+    pub fn consensus_epoch(&self) -> tokio::sync::watch::Receiver<u64> {
+        self.consensus_epoch.clone()
+    }
+
+    /// Cut the node running in this Container off the simulated network, or reconnect it. The
+    /// node keeps running, but none of its packets are delivered while it is isolated.
+    pub fn set_isolated(&self, isolated: bool) -> Result<()> {
+        let handle = self
+            .join_handle
+            .as_ref()
+            .ok_or_else(|| anyhow!("The node is not running"))?;
+        let net = sui_simulator::plugin::simulator::<sui_simulator::net::NetSim>();
+        if isolated {
+            net.clog_node(handle.node_id);
+        } else {
+            net.unclog_node(handle.node_id);
+        }
+        Ok(())
+    }
 }
@@ -1,11 +1,11 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use futures::FutureExt;
+use anyhow::{anyhow, Result};
 use std::thread;
 use sui_config::NodeConfig;
 use sui_node::{metrics, SuiNode};
-use tracing::{info, trace};
+use tracing::{info, trace, warn};
 
 use super::node::RuntimeType;
 
@@ -13,6 +13,8 @@ use super::node::RuntimeType;
 pub(crate) struct Container {
     join_handle: Option<thread::JoinHandle<()>>,
     cancel_sender: Option<tokio::sync::oneshot::Sender<()>>,
+    epoch_change_sender: tokio::sync::mpsc::UnboundedSender<()>,
+    consensus_epoch: tokio::sync::watch::Receiver<u64>,
 }
 
 /// When dropped, stop and wait for the node running in this Container to completely shutdown.
@@ -41,7 +43,10 @@ impl Container {
         runtime: RuntimeType,
     ) -> (tokio::sync::oneshot::Receiver<()>, Self) {
         let (startup_sender, startup_reciever) = tokio::sync::oneshot::channel();
-        let (cancel_sender, cancel_reciever) = tokio::sync::oneshot::channel();
+        let (cancel_sender, mut cancel_reciever) = tokio::sync::oneshot::channel();
+        let (epoch_change_sender, mut epoch_change_receiver) =
+            tokio::sync::mpsc::unbounded_channel();
+        let (consensus_epoch_sender, consensus_epoch) = tokio::sync::watch::channel(0);
 
         let thread = thread::spawn(move || {
             let span = tracing::span!(
@@ -83,11 +88,25 @@ impl Container {
                     "Started Prometheus HTTP endpoint. To query metrics use\n\tcurl -s http://{}/metrics",
                     config.metrics_address
                 );
-                let _server = SuiNode::start(&config, registry_service).await.unwrap();
+                let server = SuiNode::start(&config, registry_service).await.unwrap();
+                let mut server_consensus_epoch = server.state().subscribe_consensus_epoch();
                 // Notify that we've successfully started the node
                 let _ = startup_sender.send(());
                 // run until canceled
-                cancel_reciever.map(|_| ()).await;
+                loop {
+                    tokio::select! {
+                        _ = &mut cancel_reciever => break,
+                        Some(()) = epoch_change_receiver.recv() => {
+                            if let Err(err) = server.force_epoch_change().await {
+                                warn!("failed to force an epoch change: {err}");
+                            }
+                        }
+                        Ok(()) = server_consensus_epoch.changed() => {
+                            let epoch = *server_consensus_epoch.borrow();
+                            let _ = consensus_epoch_sender.send(epoch);
+                        }
+                    }
+                }
 
                 trace!("cancellation received; shutting down thread");
             });
@@ -98,6 +117,8 @@ impl Container {
             Self {
                 join_handle: Some(thread),
                 cancel_sender: Some(cancel_sender),
+                epoch_change_sender,
+                consensus_epoch,
             },
         )
     }
@@ -115,4 +136,22 @@ impl Container {
             false
         }
     }
+
+    /// Ask the node running in this Container to end its current consensus epoch.
+    pub fn force_epoch_change(&self) -> bool {
+        self.epoch_change_sender.send(()).is_ok()
+    }
+
+    /// The Narwhal epoch of the consensus output handled by the node running in this Container.
+    pub fn consensus_epoch(&self) -> tokio::sync::watch::Receiver<u64> {
+        self.consensus_epoch.clone()
+    }
+
+    /// Cut the node running in this Container off the network, or reconnect it. The nodes of a
+    /// threaded swarm share the network of the host, so only the simulator can do it.
+    pub fn set_isolated(&self, _isolated: bool) -> Result<()> {
+        Err(anyhow!(
+            "Isolating a node requires running the swarm in the simulator"
+        ))
+    }
 }
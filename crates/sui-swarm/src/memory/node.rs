@@ -70,6 +70,50 @@ impl Node {
         self.thread = None;
     }
 
+    /// Return true if this Node has been started and has not stopped since.
+    pub fn is_running(&self) -> bool {
+        self.thread
+            .as_ref()
+            .map_or(false, |thread| thread.is_alive())
+    }
+
+    /// Ask this Node to end its current consensus epoch, which only validators can do.
+    pub fn force_epoch_change(&self) -> Result<()> {
+        let thread = self
+            .thread
+            .as_ref()
+            .ok_or_else(|| anyhow!("{} is not running", self.name()))?;
+        if !thread.force_epoch_change() {
+            return Err(anyhow!("{} is not running", self.name()));
+        }
+        Ok(())
+    }
+
+    /// Wait until the consensus output handled by this Node, which only validators run, reaches
+    /// the Narwhal `epoch`.
+    pub async fn wait_for_consensus_epoch(&self, epoch: u64) -> Result<()> {
+        let mut consensus_epoch = self
+            .thread
+            .as_ref()
+            .ok_or_else(|| anyhow!("{} is not running", self.name()))?
+            .consensus_epoch();
+        while *consensus_epoch.borrow_and_update() < epoch {
+            consensus_epoch
+                .changed()
+                .await
+                .map_err(|_| anyhow!("{} stopped", self.name()))?;
+        }
+        Ok(())
+    }
+
+    /// Cut this Node off the network while it keeps running, or reconnect it.
+    pub fn set_isolated(&self, isolated: bool) -> Result<()> {
+        self.thread
+            .as_ref()
+            .ok_or_else(|| anyhow!("{} is not running", self.name()))?
+            .set_isolated(isolated)
+    }
+
     /// Perform a health check on this Node by:
     /// * Checking that the node is running
     /// * Calling the Node's gRPC Health service if it's a validator.
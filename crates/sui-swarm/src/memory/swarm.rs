@@ -2,12 +2,13 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::Node;
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use futures::future::try_join_all;
 use rand::rngs::OsRng;
 use std::collections::HashMap;
 use std::net::SocketAddr;
 use std::num::NonZeroUsize;
+use std::time::Duration;
 use std::{
     mem, ops,
     path::{Path, PathBuf},
@@ -17,6 +18,7 @@ use sui_config::genesis_config::{GenesisConfig, ValidatorConfigInfo};
 use sui_config::NetworkConfig;
 use sui_types::base_types::SuiAddress;
 use tempfile::TempDir;
+use tracing::info;
 
 pub struct SwarmBuilder<R = OsRng> {
     rng: R,
@@ -27,6 +29,7 @@ pub struct SwarmBuilder<R = OsRng> {
     fullnode_count: usize,
     fullnode_rpc_addr: Option<SocketAddr>,
     with_event_store: bool,
+    epoch_duration: Option<Duration>,
 }
 
 impl SwarmBuilder {
@@ -40,6 +43,7 @@ impl SwarmBuilder {
             fullnode_count: 0,
             fullnode_rpc_addr: None,
             with_event_store: false,
+            epoch_duration: None,
         }
     }
 }
@@ -54,6 +58,7 @@ impl<R> SwarmBuilder<R> {
             fullnode_count: self.fullnode_count,
             fullnode_rpc_addr: self.fullnode_rpc_addr,
             with_event_store: false,
+            epoch_duration: self.epoch_duration,
         }
     }
 
@@ -94,6 +99,14 @@ impl<R> SwarmBuilder<R> {
         self.fullnode_rpc_addr = Some(fullnode_rpc_addr);
         self
     }
+
+    /// Set the duration of the consensus epochs of the validators.
+    ///
+    /// Defaults to epochs that only end when forced, see [`Swarm::force_epoch_change`].
+    pub fn with_epoch_duration(mut self, epoch_duration: Duration) -> Self {
+        self.epoch_duration = Some(epoch_duration);
+        self
+    }
 }
 
 impl<R: rand::RngCore + rand::CryptoRng> SwarmBuilder<R> {
@@ -111,11 +124,14 @@ impl<R: rand::RngCore + rand::CryptoRng> SwarmBuilder<R> {
             config_builder = config_builder.initial_accounts_config(initial_accounts_config);
         }
 
-        let network_config = config_builder
+        let mut network_config = config_builder
             .committee(self.committee)
             .with_swarm()
             .rng(self.rng)
             .build();
+        if let Some(epoch_duration) = self.epoch_duration {
+            network_config.set_epoch_duration(epoch_duration);
+        }
 
         let validators = network_config
             .validator_configs()
@@ -152,8 +168,11 @@ impl<R: rand::RngCore + rand::CryptoRng> SwarmBuilder<R> {
         self
     }
 
-    pub fn from_network_config(self, dir: PathBuf, network_config: NetworkConfig) -> Swarm {
+    pub fn from_network_config(self, dir: PathBuf, mut network_config: NetworkConfig) -> Swarm {
         let dir = SwarmDirectory::Persistent(dir);
+        if let Some(epoch_duration) = self.epoch_duration {
+            network_config.set_epoch_duration(epoch_duration);
+        }
 
         let validators = network_config
             .validator_configs()
@@ -282,6 +301,53 @@ impl Swarm {
     pub fn fullnodes_mut(&mut self) -> impl Iterator<Item = &mut Node> {
         self.fullnodes.values_mut()
     }
+
+    fn node_mut(&mut self, name: SuiAddress) -> Result<&mut Node> {
+        self.validators
+            .get_mut(&name)
+            .or_else(|| self.fullnodes.get_mut(&name))
+            .ok_or_else(|| anyhow!("No validator or fullnode named {name} in the swarm"))
+    }
+
+    /// Kill the Validator or Fullnode with the provided `name`, as if it crashed. Its on-disk
+    /// data is kept, so it can be brought back with [`Swarm::restart_node`].
+    pub fn kill_node(&mut self, name: SuiAddress) -> Result<()> {
+        info!(%name, "killing node");
+        self.node_mut(name)?.stop();
+        Ok(())
+    }
+
+    /// Restart the Validator or Fullnode with the provided `name` from its on-disk data, killing
+    /// it first if it is running, and wait until it has started up. A node restarted after the
+    /// network moved to a new epoch recovers the new committee from its stores and catches up.
+    pub async fn restart_node(&mut self, name: SuiAddress) -> Result<()> {
+        info!(%name, "restarting node");
+        let node = self.node_mut(name)?;
+        node.stop();
+        node.start().await
+    }
+
+    /// End the current consensus epoch of all running Validators now, restarting Narwhal for the
+    /// next epoch with the same committee.
+    pub fn force_epoch_change(&self) -> Result<()> {
+        info!("forcing an epoch change");
+        for validator in self.validators().filter(|validator| validator.is_running()) {
+            validator.force_epoch_change()?;
+        }
+        Ok(())
+    }
+
+    /// Cut the Validator or Fullnode with the provided `name` off the network for `duration`,
+    /// then reconnect it. The node keeps running but can neither reach nor be reached by the
+    /// rest of the network in the meantime. This requires running the swarm in the simulator.
+    pub async fn isolate_node(&mut self, name: SuiAddress, duration: Duration) -> Result<()> {
+        info!(%name, ?duration, "isolating node");
+        let node = self.node_mut(name)?;
+        node.set_isolated(true)?;
+        tokio::time::sleep(duration).await;
+        info!(%name, "reconnecting node");
+        self.node_mut(name)?.set_isolated(false)
+    }
 }
 
 #[derive(Debug)]
@@ -334,6 +400,12 @@ impl AsRef<Path> for SwarmDirectory {
 mod test {
     use super::Swarm;
     use std::num::NonZeroUsize;
+    use std::time::Duration;
+    use sui_macros::sim_test;
+    use tokio::time::timeout;
+
+    /// How long the running validators may take to commit in the next consensus epoch.
+    const EPOCH_CHANGE_TIMEOUT: Duration = Duration::from_secs(60);
 
     #[tokio::test]
     async fn launch() {
@@ -353,4 +425,143 @@ mod test {
             fullnode.health_check(false).await.unwrap();
         }
     }
+
+    #[tokio::test]
+    async fn kill_and_restart_nodes() {
+        telemetry_subscribers::init_for_testing();
+        let mut swarm = Swarm::builder().with_fullnode_count(1).build();
+        swarm.launch().await.unwrap();
+
+        let validator = swarm.validators().next().unwrap().name();
+        swarm.kill_node(validator).unwrap();
+        assert!(!swarm.validator(validator).unwrap().is_running());
+        swarm.restart_node(validator).await.unwrap();
+        let node = swarm.validator(validator).unwrap();
+        node.health_check(true).await.unwrap();
+
+        let fullnode = swarm.fullnodes().next().unwrap().name();
+        swarm.kill_node(fullnode).unwrap();
+        swarm.restart_node(fullnode).await.unwrap();
+        swarm
+            .fullnode(fullnode)
+            .unwrap()
+            .health_check(false)
+            .await
+            .unwrap();
+
+        // The other nodes kept running.
+        assert!(swarm.validators().all(|node| node.is_running()));
+        swarm.kill_node(Default::default()).unwrap_err();
+    }
+
+    #[tokio::test]
+    async fn force_epoch_change() {
+        telemetry_subscribers::init_for_testing();
+        let mut swarm = Swarm::builder()
+            .with_epoch_duration(Duration::from_secs(60))
+            .build();
+        let consensus_config = swarm.config().validator_configs()[0]
+            .consensus_config()
+            .unwrap();
+        assert_eq!(
+            consensus_config.narwhal_config().epoch_policy.to_string(),
+            "every 60000 ms"
+        );
+        swarm.launch().await.unwrap();
+
+        swarm.force_epoch_change().unwrap();
+        let validator = swarm.validators().next().unwrap().name();
+        swarm.kill_node(validator).unwrap();
+        let node = swarm.validator(validator).unwrap();
+        node.force_epoch_change().unwrap_err();
+        // The validators that are down are skipped.
+        swarm.force_epoch_change().unwrap();
+    }
+
+    #[tokio::test]
+    async fn restart_node_across_epoch_change() {
+        telemetry_subscribers::init_for_testing();
+        let mut swarm = Swarm::builder()
+            .committee_size(NonZeroUsize::new(4).unwrap())
+            .with_fullnode_count(1)
+            .build();
+        swarm.launch().await.unwrap();
+
+        // The network moves to the next epoch while a validator and a fullnode are down.
+        let validator = swarm.validators().next().unwrap().name();
+        let fullnode = swarm.fullnodes().next().unwrap().name();
+        swarm.kill_node(validator).unwrap();
+        swarm.kill_node(fullnode).unwrap();
+        swarm.force_epoch_change().unwrap();
+        for node in swarm.validators().filter(|node| node.is_running()) {
+            timeout(EPOCH_CHANGE_TIMEOUT, node.wait_for_consensus_epoch(1))
+                .await
+                .unwrap()
+                .unwrap();
+        }
+
+        swarm.restart_node(validator).await.unwrap();
+        swarm.restart_node(fullnode).await.unwrap();
+        for validator in swarm.validators() {
+            validator.health_check(true).await.unwrap();
+        }
+        swarm
+            .fullnode(fullnode)
+            .unwrap()
+            .health_check(false)
+            .await
+            .unwrap();
+        // The restarted validator starts over from the first epoch, and commits again with the
+        // others once it moves to theirs.
+        let restarted = swarm.validator(validator).unwrap();
+        restarted.force_epoch_change().unwrap();
+        timeout(EPOCH_CHANGE_TIMEOUT, restarted.wait_for_consensus_epoch(1))
+            .await
+            .unwrap()
+            .unwrap();
+    }
+
+    #[sim_test]
+    async fn isolate_nodes_across_epoch_change() {
+        let mut swarm = Swarm::builder()
+            .committee_size(NonZeroUsize::new(4).unwrap())
+            .with_fullnode_count(1)
+            .build();
+        swarm.launch().await.unwrap();
+
+        // A validator is cut off the network while the others change epoch, and keeps running.
+        let validator = swarm.validators().next().unwrap().name();
+        let isolated = swarm.validator(validator).unwrap();
+        isolated.set_isolated(true).unwrap();
+        swarm.force_epoch_change().unwrap();
+        for node in swarm.validators().filter(|node| node.name() != validator) {
+            timeout(EPOCH_CHANGE_TIMEOUT, node.wait_for_consensus_epoch(1))
+                .await
+                .unwrap()
+                .unwrap();
+        }
+        assert!(isolated.is_running());
+        // Once reconnected, it commits in the new epoch with the others.
+        isolated.set_isolated(false).unwrap();
+        timeout(EPOCH_CHANGE_TIMEOUT, isolated.wait_for_consensus_epoch(1))
+            .await
+            .unwrap()
+            .unwrap();
+
+        let fullnode = swarm.fullnodes().next().unwrap().name();
+        swarm
+            .isolate_node(fullnode, Duration::from_secs(5))
+            .await
+            .unwrap();
+
+        for validator in swarm.validators() {
+            validator.health_check(true).await.unwrap();
+        }
+        swarm
+            .fullnode(fullnode)
+            .unwrap()
+            .health_check(false)
+            .await
+            .unwrap();
+    }
 }
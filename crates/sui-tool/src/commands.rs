@@ -22,6 +22,7 @@ use futures::stream::StreamExt;
 
 use clap::*;
 use sui_core::authority::MAX_ITEMS_LIMIT;
+use sui_core::transaction_replay::TransactionReplayer;
 use sui_types::messages_checkpoint::{
     CheckpointRequest, CheckpointResponse, CheckpointSequenceNumber,
 };
@@ -144,6 +145,26 @@ pub enum ToolCommand {
         #[clap(long = "genesis")]
         genesis: PathBuf,
     },
+    /// Re-execute the transactions of a range of checkpoints against the input objects recorded
+    /// in the store of a node, and compare their effects with the committed ones. The store is
+    /// opened read-only, so the node can keep running. The transactions whose input objects were
+    /// pruned can't be replayed.
+    #[clap(name = "replay")]
+    Replay {
+        /// Path of the DB of the node, with its store and checkpoints
+        #[clap(long = "db-path")]
+        db_path: PathBuf,
+
+        #[clap(long, help = "The first checkpoint to replay")]
+        start: CheckpointSequenceNumber,
+
+        #[clap(
+            long,
+            help = "The last checkpoint to replay - if not specified, only the first one"
+        )]
+        end: Option<CheckpointSequenceNumber>,
+    },
+
     /// Fetch authenticated checkpoint information at a specific sequence number.
     /// If sequence number is not specified, get the latest authenticated checkpoint.
     #[clap(name = "fetch-checkpoint")]
@@ -688,6 +709,35 @@ impl ToolCommand {
                     None => print_db_all_tables(path)?,
                 }
             }
+            ToolCommand::Replay {
+                db_path,
+                start,
+                end,
+            } => {
+                let replayer = TransactionReplayer::open(&db_path)?;
+                let replayed = replayer.replay_checkpoints(start..=end.unwrap_or(start))?;
+                let total = replayed.len();
+                let mut diverged = 0;
+                for transaction in replayed {
+                    if transaction.diffs.is_empty() {
+                        continue;
+                    }
+                    diverged += 1;
+                    println!(
+                        "checkpoint {} transaction {:?} diverged:",
+                        transaction.checkpoint, transaction.digests.transaction
+                    );
+                    for diff in transaction.diffs {
+                        println!("    {}", diff.field);
+                        println!("        committed: {}", diff.committed);
+                        println!("        replayed:  {}", diff.replayed);
+                    }
+                }
+                println!("Replayed {total} transactions, {diverged} diverged");
+                if diverged > 0 {
+                    return Err(anyhow!("{diverged} transactions diverged"));
+                }
+            }
             ToolCommand::DumpValidators { genesis, concise } => {
                 let genesis = Genesis::load(genesis).unwrap();
                 if !concise {
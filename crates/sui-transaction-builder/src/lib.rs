@@ -533,6 +533,46 @@ impl TransactionBuilder {
         ))
     }
 
+    /// Pays for the gas of the transaction `tx_data` of another address from a gas object of
+    /// `sponsor`, who must sign the returned transaction too.
+    pub async fn sponsor(
+        &self,
+        tx_data: TransactionData,
+        sponsor: SuiAddress,
+        gas: Option<ObjectID>,
+    ) -> anyhow::Result<TransactionData> {
+        fp_ensure!(
+            !tx_data.kind.spends_gas_object(),
+            anyhow!(
+                "A {} transaction pays from its gas object and cannot be sponsored",
+                tx_data.kind_as_str()
+            )
+        );
+        fp_ensure!(
+            sponsor != tx_data.signer(),
+            anyhow!("The sender cannot sponsor its own transaction")
+        );
+        let inputs = tx_data
+            .kind
+            .input_objects()?
+            .iter()
+            .flat_map(|obj| match obj {
+                InputObjectKind::ImmOrOwnedMoveObject((id, _, _)) => Some(*id),
+                _ => None,
+            })
+            .collect();
+        let gas = self
+            .select_gas(sponsor, gas, tx_data.gas_budget, inputs)
+            .await?;
+        Ok(TransactionData::new_with_gas_price(
+            tx_data.kind,
+            tx_data.signer(),
+            gas,
+            tx_data.gas_budget,
+            tx_data.gas_price,
+        ))
+    }
+
     // TODO: we should add retrial to reduce the transaction building error rate
     async fn get_object_ref(&self, object_id: ObjectID) -> anyhow::Result<ObjectRef> {
         Ok(self
@@ -32,6 +32,7 @@ use crate::error::ExecutionError;
 use crate::error::ExecutionErrorKind;
 use crate::error::SuiError;
 use crate::gas_coin::GasCoin;
+use crate::multisig::{MultiSigPublicKey, MULTISIG_FLAG};
 use crate::object::{Object, Owner};
 use crate::sui_serde::Readable;
 use fastcrypto::encoding::{Base58, Base64, Encoding, Hex};
@@ -262,6 +263,27 @@ impl From<&PublicKey> for SuiAddress {
     }
 }
 
+/// The address of a multisig account is the hash of the flag, the threshold, and then of the
+/// flag, the key and the weight of every member in order.
+impl From<&MultiSigPublicKey> for SuiAddress {
+    fn from(multisig_pk: &MultiSigPublicKey) -> Self {
+        let mut hasher = Sha3_256::default();
+        hasher.update([MULTISIG_FLAG]);
+        hasher.update(multisig_pk.threshold().to_le_bytes());
+        for (pk, weight) in multisig_pk.pubkeys() {
+            hasher.update([pk.flag()]);
+            hasher.update(pk);
+            hasher.update([*weight]);
+        }
+        let g_arr = hasher.finalize();
+
+        let mut res = [0u8; SUI_ADDRESS_LENGTH];
+        // OK to access slice because Sha3_256 should never be shorter than SUI_ADDRESS_LENGTH.
+        res.copy_from_slice(&AsRef::<[u8]>::as_ref(&g_arr)[..SUI_ADDRESS_LENGTH]);
+        SuiAddress(res)
+    }
+}
+
 impl TryFrom<&[u8]> for SuiAddress {
     type Error = SuiError;
 
@@ -95,7 +95,7 @@ pub enum SuiKeyPair {
     Secp256k1SuiKeyPair(Secp256k1KeyPair),
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, From)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, From)]
 pub enum PublicKey {
     Ed25519KeyPair(Ed25519PublicKey),
     Secp256k1KeyPair(Secp256k1PublicKey),
@@ -246,6 +246,14 @@ impl EncodeDecodeBase64 for PublicKey {
     }
 }
 
+impl FromStr for PublicKey {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::decode_base64(s)
+    }
+}
+
 impl Serialize for PublicKey {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -1408,6 +1416,13 @@ impl VerificationObligation {
         Ok(())
     }
 
+    /// Moves the messages of `other`, with their signatures and public keys, to this obligation.
+    pub fn append(&mut self, mut other: VerificationObligation) {
+        self.messages.append(&mut other.messages);
+        self.signatures.append(&mut other.signatures);
+        self.public_keys.append(&mut other.public_keys);
+    }
+
     pub fn verify_all(self) -> SuiResult<()> {
         AggregateAuthoritySignature::batch_verify(
             &self.signatures.iter().collect::<Vec<_>>()[..],
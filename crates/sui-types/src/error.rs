@@ -96,6 +96,8 @@ pub enum SuiError {
     SenderSigUnbatchable,
     #[error("Value was not signed by the correct sender: {}", error)]
     IncorrectSigner { error: String },
+    #[error("Multisig is not valid: {}", error)]
+    InvalidMultiSig { error: String },
     #[error("Value was not signed by a known authority")]
     UnknownSigner,
     // Certificate verification
@@ -522,6 +524,9 @@ pub enum SuiError {
     #[error("SUI payment transactions use first input coin for gas payment, but found a different gas object.")]
     UnexpectedGasPaymentObject,
 
+    #[error("Invalid gas sponsorship: {error}")]
+    InvalidGasSponsorship { error: String },
+
     #[error("unknown error: {0}")]
     Unknown(String),
 }
@@ -606,6 +611,27 @@ impl SuiError {
             _ => false,
         }
     }
+
+    /// Whether the error comes from the validators being unavailable rather than from the request,
+    /// so that sending the same request again may succeed.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            SuiError::QuorumFailedToProcessTransaction {
+                errors,
+                conflicting_tx_digests,
+                ..
+            } => {
+                conflicting_tx_digests.is_empty()
+                    && !errors.is_empty()
+                    && errors.iter().all(SuiError::is_transient)
+            }
+            SuiError::QuorumFailedToExecuteCertificate { errors } => {
+                !errors.is_empty() && errors.iter().all(SuiError::is_transient)
+            }
+            SuiError::TimeoutError | SuiError::RpcError(..) => true,
+            _ => self.indicates_epoch_change(),
+        }
+    }
 }
 
 type BoxError = Box<dyn std::error::Error + Send + Sync + 'static>;
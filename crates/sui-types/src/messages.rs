@@ -15,6 +15,7 @@ use crate::message_envelope::{Envelope, Message, TrustedEnvelope, VerifiedEnvelo
 use crate::messages_checkpoint::{
     AuthenticatedCheckpoint, CheckpointSequenceNumber, CheckpointSignatureMessage,
 };
+use crate::multisig::GenericSignature;
 use crate::object::{MoveObject, Object, ObjectFormatOptions, Owner, PACKAGE_VERSION};
 use crate::storage::{DeleteKind, WriteKind};
 use crate::{SUI_SYSTEM_STATE_OBJECT_ID, SUI_SYSTEM_STATE_OBJECT_SHARED_VERSION};
@@ -38,6 +39,7 @@ use std::fmt::{Debug, Display, Formatter};
 use std::{
     collections::{BTreeMap, BTreeSet, HashSet},
     hash::{Hash, Hasher},
+    time::Duration,
 };
 use strum::IntoStaticStr;
 use tracing::debug;
@@ -499,6 +501,18 @@ impl TransactionKind {
         )
     }
 
+    /// Whether the transaction spends its gas object beyond the gas, e.g. by transferring it.
+    pub fn spends_gas_object(&self) -> bool {
+        self.single_transactions().any(|s| {
+            matches!(
+                s,
+                SingleTransactionKind::TransferSui(_)
+                    | SingleTransactionKind::PaySui(_)
+                    | SingleTransactionKind::PayAllSui(_)
+            )
+        })
+    }
+
     pub fn is_system_tx(&self) -> bool {
         matches!(
             self,
@@ -799,17 +813,179 @@ impl TransactionData {
     }
 }
 
+/// The address paying for the gas of a transaction sent by another one, which owns the gas object
+/// and signs the same intent message as the sender.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct GasSponsorship {
+    pub sponsor: SuiAddress,
+    pub signature: GenericSignature,
+}
+
+impl GasSponsorship {
+    pub fn new(sponsor: SuiAddress, signature: impl Into<GenericSignature>) -> Self {
+        Self {
+            sponsor,
+            signature: signature.into(),
+        }
+    }
+
+    fn verify(&self, intent_message: &IntentMessage<TransactionData>) -> SuiResult {
+        let tx_data = &intent_message.value;
+        fp_ensure!(
+            self.sponsor != tx_data.sender,
+            SuiError::InvalidGasSponsorship {
+                error: "The sender cannot sponsor its own transaction".to_string(),
+            }
+        );
+        // Only the owner of the gas object can spend it beyond the gas.
+        fp_ensure!(
+            !tx_data.kind.spends_gas_object(),
+            SuiError::InvalidGasSponsorship {
+                error: format!(
+                    "A {} transaction cannot be sponsored",
+                    tx_data.kind_as_str()
+                ),
+            }
+        );
+        self.signature.verify_secure(intent_message, self.sponsor)
+    }
+}
+
+/// The flag of the signatures of a sponsored transaction, serialized in place of the signature of
+/// its sender.
+pub const SPONSORED_FLAG: u8 = 0xfe;
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(
+    into = "SenderSignedDataEncoding",
+    try_from = "SenderSignedDataEncoding"
+)]
 pub struct SenderSignedData {
     pub intent_message: IntentMessage<TransactionData>,
-    pub tx_signature: Signature,
+    pub tx_signature: GenericSignature,
+    /// Set when the gas is paid by another address than the sender.
+    pub gas_sponsorship: Option<GasSponsorship>,
+}
+
+// The signatures of a sponsored transaction are serialized as the signature of its sender, behind
+// [`SPONSORED_FLAG`], which keeps the other transactions and their certificates serialized as they
+// were before sponsorship, in the stores and on the wire.
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "SenderSignedData")]
+struct SenderSignedDataEncoding {
+    intent_message: IntentMessage<TransactionData>,
+    tx_signature: TxSignatureEncoding,
+}
+
+struct TxSignatureEncoding(Vec<u8>);
+
+impl From<SenderSignedData> for SenderSignedDataEncoding {
+    fn from(data: SenderSignedData) -> Self {
+        let tx_signature = match data.gas_sponsorship {
+            None => data.tx_signature.to_bytes(),
+            Some(gas_sponsorship) => {
+                let mut bytes = vec![SPONSORED_FLAG];
+                bytes.extend(
+                    bcs::to_bytes(&(data.tx_signature, gas_sponsorship))
+                        .expect("Serialization should not fail"),
+                );
+                bytes
+            }
+        };
+        Self {
+            intent_message: data.intent_message,
+            tx_signature: TxSignatureEncoding(tx_signature),
+        }
+    }
+}
+
+impl TryFrom<SenderSignedDataEncoding> for SenderSignedData {
+    type Error = SuiError;
+
+    fn try_from(encoding: SenderSignedDataEncoding) -> Result<Self, Self::Error> {
+        let (tx_signature, gas_sponsorship) = match encoding.tx_signature.0.split_first() {
+            Some((&SPONSORED_FLAG, signatures)) => {
+                let (tx_signature, gas_sponsorship) =
+                    bcs::from_bytes(signatures).map_err(|e| SuiError::InvalidSignature {
+                        error: e.to_string(),
+                    })?;
+                (tx_signature, Some(gas_sponsorship))
+            }
+            _ => (
+                GenericSignature::from_bytes(&encoding.tx_signature.0)?,
+                None,
+            ),
+        };
+        Ok(Self {
+            intent_message: encoding.intent_message,
+            tx_signature,
+            gas_sponsorship,
+        })
+    }
+}
+
+impl Serialize for TxSignatureEncoding {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&Base64::encode(&self.0))
+        } else {
+            serializer.serialize_bytes(&self.0)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for TxSignatureEncoding {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        use serde::de::Error;
+
+        if deserializer.is_human_readable() {
+            let s = String::deserialize(deserializer)?;
+            Ok(Self(
+                Base64::decode(&s).map_err(|e| Error::custom(e.to_string()))?,
+            ))
+        } else {
+            Ok(Self(Vec::<u8>::deserialize(deserializer)?))
+        }
+    }
 }
 
 impl SenderSignedData {
-    pub fn new(tx_data: TransactionData, intent: Intent, tx_signature: Signature) -> Self {
+    pub fn new(
+        tx_data: TransactionData,
+        intent: Intent,
+        tx_signature: impl Into<GenericSignature>,
+    ) -> Self {
         Self {
             intent_message: IntentMessage::new(intent, tx_data),
-            tx_signature,
+            tx_signature: tx_signature.into(),
+            gas_sponsorship: None,
+        }
+    }
+
+    pub fn new_sponsored(
+        tx_data: TransactionData,
+        intent: Intent,
+        tx_signature: impl Into<GenericSignature>,
+        gas_sponsorship: GasSponsorship,
+    ) -> Self {
+        Self {
+            intent_message: IntentMessage::new(intent, tx_data),
+            tx_signature: tx_signature.into(),
+            gas_sponsorship: Some(gas_sponsorship),
+        }
+    }
+
+    /// The address that must own the gas object, the sponsor of a sponsored transaction.
+    pub fn gas_owner(&self) -> SuiAddress {
+        match &self.gas_sponsorship {
+            Some(gas_sponsorship) => gas_sponsorship.sponsor,
+            None => self.intent_message.value.sender,
         }
     }
 }
@@ -826,7 +1002,11 @@ impl Message for SenderSignedData {
             return Ok(());
         }
         self.tx_signature
-            .verify_secure(&self.intent_message, self.intent_message.value.sender)
+            .verify_secure(&self.intent_message, self.intent_message.value.sender)?;
+        if let Some(gas_sponsorship) = &self.gas_sponsorship {
+            gas_sponsorship.verify(&self.intent_message)?;
+        }
+        Ok(())
     }
 }
 
@@ -839,6 +1019,10 @@ impl<S> Envelope<SenderSignedData, S> {
         self.data().intent_message.value.gas_payment_object_ref()
     }
 
+    pub fn gas_owner(&self) -> SuiAddress {
+        self.data().gas_owner()
+    }
+
     pub fn contains_shared_object(&self) -> bool {
         self.shared_input_objects().next().is_some()
     }
@@ -893,30 +1077,68 @@ impl Transaction {
         Self::new(SenderSignedData::new(data1, intent1, signature))
     }
 
-    pub fn from_data(data: TransactionData, intent: Intent, signature: Signature) -> Self {
+    pub fn from_data(
+        data: TransactionData,
+        intent: Intent,
+        signature: impl Into<GenericSignature>,
+    ) -> Self {
         Self::new(SenderSignedData::new(data, intent, signature))
     }
 
+    /// A transaction signed by its sender and by the sponsor paying for its gas.
+    pub fn from_sponsored_data(
+        data: TransactionData,
+        intent: Intent,
+        signature: impl Into<GenericSignature>,
+        gas_sponsorship: GasSponsorship,
+    ) -> Self {
+        Self::new(SenderSignedData::new_sponsored(
+            data,
+            intent,
+            signature,
+            gas_sponsorship,
+        ))
+    }
+
     // TODO(joyqvq): remove and prefer to_tx_bytes_and_signature()
-    pub fn to_network_data_for_execution(&self) -> (Base64, SignatureScheme, Base64, Base64) {
-        (
+    /// Fails for a transaction signed by a multisig, which is only executed with its serialized
+    /// signature.
+    pub fn to_network_data_for_execution(
+        &self,
+    ) -> SuiResult<(Base64, SignatureScheme, Base64, Base64)> {
+        let GenericSignature::Signature(tx_signature) = &self.tx_signature else {
+            return Err(SuiError::UnsupportedFeatureError {
+                error: "A multisig can only be sent serialized".to_string(),
+            });
+        };
+        Ok((
             Base64::from_bytes(
                 bcs::to_bytes(&self.intent_message.value)
                     .unwrap()
                     .as_slice(),
             ),
-            self.tx_signature.scheme(),
-            Base64::from_bytes(self.tx_signature.signature_bytes()),
-            Base64::from_bytes(self.tx_signature.public_key_bytes()),
-        )
+            tx_signature.scheme(),
+            Base64::from_bytes(tx_signature.signature_bytes()),
+            Base64::from_bytes(tx_signature.public_key_bytes()),
+        ))
     }
 
     pub fn to_tx_bytes_and_signature(&self) -> (Base64, Base64) {
         (
             Base64::from_bytes(&bcs::to_bytes(&self.data().intent_message.value).unwrap()),
-            Base64::from_bytes(self.data().tx_signature.as_ref()),
+            Base64::from_bytes(&self.data().tx_signature.to_bytes()),
         )
     }
+
+    /// The sponsor of a sponsored transaction and its serialized signature.
+    pub fn to_sponsor_and_signature(&self) -> Option<(SuiAddress, Base64)> {
+        self.data().gas_sponsorship.as_ref().map(|gas_sponsorship| {
+            (
+                gas_sponsorship.sponsor,
+                Base64::from_bytes(&gas_sponsorship.signature.to_bytes()),
+            )
+        })
+    }
 }
 
 impl VerifiedTransaction {
@@ -943,9 +1165,11 @@ impl VerifiedTransaction {
             // Default intent
             intent_message: IntentMessage::new(Intent::default(), data),
             // Arbitrary keypair
-            tx_signature: Ed25519SuiSignature::from_bytes(&[0; Ed25519SuiSignature::LENGTH])
-                .unwrap()
-                .into(),
+            tx_signature: Signature::from(
+                Ed25519SuiSignature::from_bytes(&[0; Ed25519SuiSignature::LENGTH]).unwrap(),
+            )
+            .into(),
+            gas_sponsorship: None,
         };
         Self::new_from_verified(Transaction::new(signed_data))
     }
@@ -2217,6 +2441,70 @@ pub enum QuorumDriverRequestType {
     WaitForEffectsCert,
 }
 
+/// How the quorum driver retries each step of a request (forming the transaction certificate,
+/// then executing it) when it fails because of the validators rather than the transaction, e.g.
+/// when a quorum of them timed out. Resubmitting the same transaction or certificate cannot cause
+/// an equivocation, so only transient failures are retried and a transaction whose objects are
+/// locked by a conflicting one never is.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase", default)]
+pub struct QuorumDriverRetryPolicy {
+    /// The number of attempts of each step, including the first one. Zero counts as one.
+    pub max_attempts: u32,
+    /// How long to wait for the validators in each attempt, without limit if unset.
+    pub attempt_timeout_ms: Option<u64>,
+    /// The delay before the first retry, which doubles with every following retry.
+    pub initial_backoff_ms: u64,
+    /// The longest delay between two attempts.
+    pub max_backoff_ms: u64,
+    /// Whether to try to finalize the transaction that locks the objects of this one, when
+    /// enough validators report it to not risk an equivocation.
+    pub retry_conflicting_transactions: bool,
+}
+
+impl Default for QuorumDriverRetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 1,
+            attempt_timeout_ms: None,
+            initial_backoff_ms: 100,
+            max_backoff_ms: 5_000,
+            retry_conflicting_transactions: true,
+        }
+    }
+}
+
+impl QuorumDriverRetryPolicy {
+    pub fn attempt_timeout(&self) -> Option<Duration> {
+        self.attempt_timeout_ms.map(Duration::from_millis)
+    }
+
+    /// The delay before the attempt following the `attempt`-th one, counting from 1.
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let backoff_ms = self
+            .initial_backoff_ms
+            .saturating_mul(1 << attempt.saturating_sub(1).min(32))
+            .min(self.max_backoff_ms);
+        Duration::from_millis(backoff_ms)
+    }
+
+    /// This policy, retrying at most as much and waiting at most as long as `max`.
+    pub fn clamp_to(&self, max: &Self) -> Self {
+        let attempt_timeout_ms = match (self.attempt_timeout_ms, max.attempt_timeout_ms) {
+            (Some(timeout), Some(max_timeout)) => Some(timeout.min(max_timeout)),
+            (timeout, max_timeout) => timeout.or(max_timeout),
+        };
+        Self {
+            max_attempts: self.max_attempts.min(max.max_attempts),
+            attempt_timeout_ms,
+            initial_backoff_ms: self.initial_backoff_ms.min(max.initial_backoff_ms),
+            max_backoff_ms: self.max_backoff_ms.min(max.max_backoff_ms),
+            retry_conflicting_transactions: self.retry_conflicting_transactions
+                && max.retry_conflicting_transactions,
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct QuorumDriverRequest {
     pub transaction: VerifiedTransaction,
@@ -0,0 +1,291 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! k-of-n multisig accounts. The address of a multisig account is derived from the public keys
+//! and weights of its members and from its threshold. A transaction sent by it is signed by a
+//! subset of its members, whose partial signatures are combined into a [`MultiSig`]: it is
+//! valid when the weights of the signers add up to the threshold.
+use std::fmt::{Debug, Formatter};
+
+use derive_more::From;
+use fastcrypto::encoding::{Base64, Encoding};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::base_types::SuiAddress;
+use crate::crypto::{PublicKey, Signature, SuiSignature};
+use crate::error::{SuiError, SuiResult};
+use crate::intent::IntentMessage;
+
+#[cfg(test)]
+#[path = "unit_tests/multisig_tests.rs"]
+mod multisig_tests;
+
+/// The flag of the multisig addresses and of the serialized multisig signatures, after the ones
+/// of the signature schemes.
+pub const MULTISIG_FLAG: u8 = 0x03;
+/// The maximum number of members of a multisig account, which fit in the bitmap of its signers.
+pub const MAX_SIGNER_IN_MULTISIG: usize = 10;
+
+pub type WeightUnit = u8;
+pub type ThresholdUnit = u16;
+
+/// The members of a multisig account with their weights, and the total weight of the members
+/// who must sign its transactions.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct MultiSigPublicKey {
+    pk_map: Vec<(PublicKey, WeightUnit)>,
+    threshold: ThresholdUnit,
+}
+
+impl MultiSigPublicKey {
+    pub fn new(
+        pks: Vec<PublicKey>,
+        weights: Vec<WeightUnit>,
+        threshold: ThresholdUnit,
+    ) -> Result<Self, SuiError> {
+        if pks.len() != weights.len() {
+            return Err(SuiError::InvalidMultiSig {
+                error: format!("{} public keys but {} weights", pks.len(), weights.len()),
+            });
+        }
+        let multisig_pk = Self {
+            pk_map: pks.into_iter().zip(weights).collect(),
+            threshold,
+        };
+        multisig_pk.validate()?;
+        Ok(multisig_pk)
+    }
+
+    /// Checks that the members are at most [`MAX_SIGNER_IN_MULTISIG`] distinct keys of non-zero
+    /// weights, which can reach the non-zero threshold.
+    pub fn validate(&self) -> SuiResult {
+        let invalid = |error: String| Err(SuiError::InvalidMultiSig { error });
+        if self.pk_map.is_empty() || self.pk_map.len() > MAX_SIGNER_IN_MULTISIG {
+            return invalid(format!(
+                "A multisig has between 1 and {MAX_SIGNER_IN_MULTISIG} members, not {}",
+                self.pk_map.len()
+            ));
+        }
+        if self.threshold == 0 {
+            return invalid("The threshold must be positive".to_string());
+        }
+        if self.pk_map.iter().any(|(_, weight)| *weight == 0) {
+            return invalid("The weights must be positive".to_string());
+        }
+        for (i, (pk, _)) in self.pk_map.iter().enumerate() {
+            if self.pk_map[..i].iter().any(|(other, _)| other == pk) {
+                return invalid(format!("{} is a member twice", SuiAddress::from(pk)));
+            }
+        }
+        let total: ThresholdUnit = self
+            .pk_map
+            .iter()
+            .map(|(_, weight)| *weight as ThresholdUnit)
+            .sum();
+        if total < self.threshold {
+            return invalid(format!(
+                "The weights add up to {total}, below the threshold {}",
+                self.threshold
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn pubkeys(&self) -> &[(PublicKey, WeightUnit)] {
+        &self.pk_map
+    }
+
+    pub fn threshold(&self) -> ThresholdUnit {
+        self.threshold
+    }
+
+    fn index_of(&self, pk_bytes: &[u8]) -> Option<usize> {
+        self.pk_map
+            .iter()
+            .position(|(pk, _)| pk.as_ref() == pk_bytes)
+    }
+}
+
+/// The signatures of some members of a multisig account, with the account's public key.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct MultiSig {
+    /// The signatures of the signers, in the order of the members.
+    sigs: Vec<Signature>,
+    /// The signers, bit `i` standing for the `i`th member.
+    bitmap: u16,
+    multisig_pk: MultiSigPublicKey,
+}
+
+impl MultiSig {
+    /// Combines the partial signatures of members of `multisig_pk`, each made by a different one.
+    pub fn combine(sigs: Vec<Signature>, multisig_pk: MultiSigPublicKey) -> Result<Self, SuiError> {
+        multisig_pk.validate()?;
+        let mut signed = Vec::with_capacity(sigs.len());
+        let mut bitmap = 0u16;
+        for sig in sigs {
+            let index = multisig_pk
+                .index_of(sig.public_key_bytes())
+                .ok_or_else(|| SuiError::InvalidMultiSig {
+                    error: format!(
+                        "The signature of {} is not the one of a member",
+                        Base64::encode(sig.public_key_bytes())
+                    ),
+                })?;
+            if bitmap & (1 << index) != 0 {
+                return Err(SuiError::InvalidMultiSig {
+                    error: format!("Member {index} signed twice"),
+                });
+            }
+            bitmap |= 1 << index;
+            signed.push((index, sig));
+        }
+        signed.sort_by_key(|(index, _)| *index);
+        Ok(Self {
+            sigs: signed.into_iter().map(|(_, sig)| sig).collect(),
+            bitmap,
+            multisig_pk,
+        })
+    }
+
+    pub fn multisig_pk(&self) -> &MultiSigPublicKey {
+        &self.multisig_pk
+    }
+
+    /// Verifies that `author` is the address of the multisig account, and that the signers have
+    /// signed `value` with enough weight.
+    pub fn verify_secure<T>(&self, value: &IntentMessage<T>, author: SuiAddress) -> SuiResult
+    where
+        T: Serialize,
+    {
+        self.multisig_pk.validate()?;
+        let received_addr = SuiAddress::from(&self.multisig_pk);
+        if received_addr != author {
+            return Err(SuiError::IncorrectSigner {
+                error: format!("Multisig verification failure. Author is {author}, received address is {received_addr}")
+            });
+        }
+        let signers: Vec<_> = (0..self.multisig_pk.pk_map.len())
+            .filter(|i| self.bitmap & (1 << i) != 0)
+            .collect();
+        if signers.len() != self.sigs.len() || self.bitmap >> self.multisig_pk.pk_map.len() != 0 {
+            return Err(SuiError::InvalidSignature {
+                error: "The bitmap of the signers doesn't match the signatures".to_string(),
+            });
+        }
+
+        let mut weight: ThresholdUnit = 0;
+        for (i, sig) in signers.into_iter().zip(&self.sigs) {
+            let (pk, member_weight) = &self.multisig_pk.pk_map[i];
+            sig.verify_secure(value, SuiAddress::from(pk))?;
+            weight += *member_weight as ThresholdUnit;
+        }
+        if weight < self.multisig_pk.threshold {
+            return Err(SuiError::InvalidSignature {
+                error: format!(
+                    "The signers weigh {weight}, below the threshold {}",
+                    self.multisig_pk.threshold
+                ),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// The signature of a transaction, by a single key or by a multisig account.
+#[derive(Clone, PartialEq, Eq, Hash, From)]
+pub enum GenericSignature {
+    Signature(Signature),
+    MultiSig(MultiSig),
+}
+
+impl GenericSignature {
+    /// The scheme flag of a single signature, or [`MULTISIG_FLAG`].
+    pub fn flag(&self) -> u8 {
+        match self {
+            GenericSignature::Signature(sig) => sig.scheme().flag(),
+            GenericSignature::MultiSig(_) => MULTISIG_FLAG,
+        }
+    }
+
+    pub fn verify_secure<T>(&self, value: &IntentMessage<T>, author: SuiAddress) -> SuiResult
+    where
+        T: Serialize,
+    {
+        match self {
+            GenericSignature::Signature(sig) => sig.verify_secure(value, author),
+            GenericSignature::MultiSig(multisig) => multisig.verify_secure(value, author),
+        }
+    }
+
+    /// The bytes of a single signature as they are, and the ones of a multisig as the flag
+    /// followed by its BCS serialization.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        match self {
+            GenericSignature::Signature(sig) => sig.as_ref().to_vec(),
+            GenericSignature::MultiSig(multisig) => {
+                let mut bytes = vec![MULTISIG_FLAG];
+                bytes.extend(bcs::to_bytes(multisig).expect("Serialization should not fail"));
+                bytes
+            }
+        }
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, SuiError> {
+        match bytes.split_first() {
+            Some((&MULTISIG_FLAG, multisig)) => Ok(GenericSignature::MultiSig(
+                bcs::from_bytes(multisig).map_err(|e| SuiError::InvalidSignature {
+                    error: e.to_string(),
+                })?,
+            )),
+            _ => Ok(GenericSignature::Signature(
+                <Signature as signature::Signature>::from_bytes(bytes).map_err(|e| {
+                    SuiError::InvalidSignature {
+                        error: e.to_string(),
+                    }
+                })?,
+            )),
+        }
+    }
+}
+
+impl Debug for GenericSignature {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        match self {
+            GenericSignature::Signature(sig) => Debug::fmt(sig, f),
+            GenericSignature::MultiSig(multisig) => Debug::fmt(multisig, f),
+        }
+    }
+}
+
+// Serialized as its bytes, as a single signature is, which keeps the single signatures
+// serialized as they were before the multisigs.
+impl Serialize for GenericSignature {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let bytes = self.to_bytes();
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&Base64::encode(bytes))
+        } else {
+            serializer.serialize_bytes(&bytes)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for GenericSignature {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        use serde::de::Error;
+
+        let bytes = if deserializer.is_human_readable() {
+            let s = String::deserialize(deserializer)?;
+            Base64::decode(&s).map_err(|e| Error::custom(e.to_string()))?
+        } else {
+            Vec::<u8>::deserialize(deserializer)?
+        };
+        Self::from_bytes(&bytes).map_err(|e| Error::custom(e.to_string()))
+    }
+}
@@ -181,6 +181,10 @@ pub trait ReadStore {
 
     fn get_highest_synced_checkpoint(&self) -> Result<Option<VerifiedCheckpoint>, Self::Error>;
 
+    /// The lowest checkpoint whose contents were synced on a node restored from a state snapshot,
+    /// the ones before it being backfilled from the peers. None if there is nothing to backfill.
+    fn get_lowest_backfilled_checkpoint(&self) -> Result<Option<VerifiedCheckpoint>, Self::Error>;
+
     fn get_checkpoint_contents(
         &self,
         digest: &CheckpointContentsDigest,
@@ -224,6 +228,10 @@ impl<T: ReadStore> ReadStore for &T {
         ReadStore::get_highest_synced_checkpoint(*self)
     }
 
+    fn get_lowest_backfilled_checkpoint(&self) -> Result<Option<VerifiedCheckpoint>, Self::Error> {
+        ReadStore::get_lowest_backfilled_checkpoint(*self)
+    }
+
     fn get_checkpoint_contents(
         &self,
         digest: &CheckpointContentsDigest,
@@ -257,6 +265,11 @@ pub trait WriteStore: ReadStore {
         checkpoint: &VerifiedCheckpoint,
     ) -> Result<(), Self::Error>;
     fn insert_checkpoint_contents(&self, contents: CheckpointContents) -> Result<(), Self::Error>;
+    /// Records that the contents of `checkpoint`, and of the ones after it, are synced.
+    fn update_lowest_backfilled_checkpoint(
+        &self,
+        checkpoint: &VerifiedCheckpoint,
+    ) -> Result<(), Self::Error>;
 
     fn insert_committee(&self, new_committee: Committee) -> Result<(), Self::Error>;
 
@@ -283,6 +296,13 @@ impl<T: WriteStore> WriteStore for &T {
         WriteStore::insert_checkpoint_contents(*self, contents)
     }
 
+    fn update_lowest_backfilled_checkpoint(
+        &self,
+        checkpoint: &VerifiedCheckpoint,
+    ) -> Result<(), Self::Error> {
+        WriteStore::update_lowest_backfilled_checkpoint(*self, checkpoint)
+    }
+
     fn insert_committee(&self, new_committee: Committee) -> Result<(), Self::Error> {
         WriteStore::insert_committee(*self, new_committee)
     }
@@ -303,6 +323,7 @@ impl<T: WriteStore> WriteStore for &T {
 pub struct InMemoryStore {
     highest_verified_checkpoint: Option<(CheckpointSequenceNumber, CheckpointDigest)>,
     highest_synced_checkpoint: Option<(CheckpointSequenceNumber, CheckpointDigest)>,
+    lowest_backfilled_checkpoint: Option<(CheckpointSequenceNumber, CheckpointDigest)>,
     checkpoints: HashMap<CheckpointDigest, VerifiedCheckpoint>,
     sequence_number_to_digest: HashMap<CheckpointSequenceNumber, CheckpointDigest>,
     checkpoint_contents: HashMap<CheckpointContentsDigest, CheckpointContents>,
@@ -341,6 +362,12 @@ impl InMemoryStore {
             .and_then(|(_, digest)| self.get_checkpoint_by_digest(digest))
     }
 
+    pub fn get_lowest_backfilled_checkpoint(&self) -> Option<&VerifiedCheckpoint> {
+        self.lowest_backfilled_checkpoint
+            .as_ref()
+            .and_then(|(_, digest)| self.get_checkpoint_by_digest(digest))
+    }
+
     pub fn get_checkpoint_contents(
         &self,
         digest: &CheckpointContentsDigest,
@@ -381,6 +408,15 @@ impl InMemoryStore {
         self.highest_synced_checkpoint = Some((checkpoint.sequence_number(), checkpoint.digest()));
     }
 
+    pub fn update_lowest_backfilled_checkpoint(&mut self, checkpoint: &VerifiedCheckpoint) {
+        if !self.checkpoints.contains_key(&checkpoint.digest()) {
+            panic!("store should already contain checkpoint");
+        }
+
+        self.lowest_backfilled_checkpoint =
+            Some((checkpoint.sequence_number(), checkpoint.digest()));
+    }
+
     pub fn checkpoints(&self) -> &HashMap<CheckpointDigest, VerifiedCheckpoint> {
         &self.checkpoints
     }
@@ -479,6 +515,13 @@ impl ReadStore for SharedInMemoryStore {
             .pipe(Ok)
     }
 
+    fn get_lowest_backfilled_checkpoint(&self) -> Result<Option<VerifiedCheckpoint>, Self::Error> {
+        self.inner()
+            .get_lowest_backfilled_checkpoint()
+            .cloned()
+            .pipe(Ok)
+    }
+
     fn get_checkpoint_contents(
         &self,
         digest: &CheckpointContentsDigest,
@@ -531,6 +574,15 @@ impl WriteStore for SharedInMemoryStore {
         Ok(())
     }
 
+    fn update_lowest_backfilled_checkpoint(
+        &self,
+        checkpoint: &VerifiedCheckpoint,
+    ) -> Result<(), Self::Error> {
+        self.inner_mut()
+            .update_lowest_backfilled_checkpoint(checkpoint);
+        Ok(())
+    }
+
     fn insert_committee(&self, new_committee: Committee) -> Result<(), Self::Error> {
         self.inner_mut().insert_committee(new_committee);
         Ok(())
@@ -22,6 +22,9 @@ use crate::{
 const SUI_SYSTEM_STATE_STRUCT_NAME: &IdentStr = ident_str!("SuiSystemState");
 pub const SUI_SYSTEM_MODULE_NAME: &IdentStr = ident_str!("sui_system");
 pub const ADVANCE_EPOCH_FUNCTION_NAME: &IdentStr = ident_str!("advance_epoch");
+pub const ADD_DELEGATION_MUL_COIN_FUNCTION_NAME: &IdentStr =
+    ident_str!("request_add_delegation_mul_coin");
+pub const WITHDRAW_DELEGATION_FUNCTION_NAME: &IdentStr = ident_str!("request_withdraw_delegation");
 
 /// Rust version of the Move sui::sui_system::SystemParameters type
 #[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq, JsonSchema)]
@@ -33,16 +36,27 @@ pub struct SystemParameters {
 
 /// Rust version of the Move std::option::Option type.
 /// Putting it in this file because it's only used here.
-#[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq, JsonSchema)]
 pub struct MoveOption<T> {
     pub vec: Vec<T>,
 }
 
+impl<T> MoveOption<T> {
+    pub fn none() -> Self {
+        Self { vec: vec![] }
+    }
+
+    pub fn as_ref(&self) -> Option<&T> {
+        self.vec.first()
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq, JsonSchema)]
 pub struct ValidatorMetadata {
     pub sui_address: SuiAddress,
     pub pubkey_bytes: Vec<u8>,
     pub network_pubkey_bytes: Vec<u8>,
+    pub worker_pubkey_bytes: Vec<u8>,
     pub proof_of_possession_bytes: Vec<u8>,
     pub name: Vec<u8>,
     pub net_address: Vec<u8>,
@@ -50,13 +64,26 @@ pub struct ValidatorMetadata {
     pub next_epoch_delegation: u64,
     pub next_epoch_gas_price: u64,
     pub next_epoch_commission_rate: u64,
+    pub next_epoch_pubkey_bytes: MoveOption<Vec<u8>>,
+    pub next_epoch_proof_of_possession: MoveOption<Vec<u8>>,
+    pub next_epoch_network_pubkey_bytes: MoveOption<Vec<u8>>,
+    pub next_epoch_worker_pubkey_bytes: MoveOption<Vec<u8>>,
+    pub next_epoch_net_address: MoveOption<Vec<u8>>,
 }
 
 impl ValidatorMetadata {
+    /// The protocol public key of the validator starting the next epoch, which differs from
+    /// `pubkey_bytes` if the validator rotated its keys during this epoch.
+    pub fn next_epoch_pubkey_bytes(&self) -> &[u8] {
+        self.next_epoch_pubkey_bytes
+            .as_ref()
+            .unwrap_or(&self.pubkey_bytes)
+    }
+
     pub fn to_next_epoch_validator_and_stake_pair(&self) -> (AuthorityName, StakeUnit) {
         (
             // TODO: Make sure we are actually verifying this on-chain.
-            AuthorityPublicKeyBytes::from_bytes(self.pubkey_bytes.as_ref())
+            AuthorityPublicKeyBytes::from_bytes(self.next_epoch_pubkey_bytes())
                 .expect("Validity of public key bytes should be verified on-chain"),
             self.next_epoch_stake + self.next_epoch_delegation,
         )
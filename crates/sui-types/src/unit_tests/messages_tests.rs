@@ -543,6 +543,58 @@ fn test_user_signature_committed_in_transactions() {
     assert_ne!(transaction_a, transaction_b)
 }
 
+#[test]
+fn test_sponsored_transaction_serialization() {
+    let (sender, sender_sec): (_, AccountKeyPair) = get_key_pair();
+    let (sponsor, sponsor_sec): (_, AccountKeyPair) = get_key_pair();
+    let tx_data = TransactionData::new_transfer(
+        sponsor,
+        random_object_ref(),
+        sender,
+        random_object_ref(),
+        10000,
+    );
+    let message = IntentMessage::new(Intent::default(), tx_data.clone());
+    let tx_signature = Signature::new_secure(&message, &sender_sec);
+
+    // A transaction without a sponsor is serialized as it was before sponsorship.
+    let signed = SenderSignedData::new(tx_data.clone(), Intent::default(), tx_signature.clone());
+    let bytes = bcs::to_bytes(&signed).unwrap();
+    assert_eq!(
+        bytes,
+        bcs::to_bytes(&(&signed.intent_message, &signed.tx_signature)).unwrap()
+    );
+    assert_eq!(bcs::from_bytes::<SenderSignedData>(&bytes).unwrap(), signed);
+
+    // The signatures of a sponsored one are serialized behind their flag.
+    let gas_sponsorship =
+        GasSponsorship::new(sponsor, Signature::new_secure(&message, &sponsor_sec));
+    let sponsored = SenderSignedData::new_sponsored(
+        tx_data,
+        Intent::default(),
+        tx_signature,
+        gas_sponsorship.clone(),
+    );
+    assert!(sponsored.verify().is_ok());
+    let bytes = bcs::to_bytes(&sponsored).unwrap();
+    let (_, signatures): (IntentMessage<TransactionData>, Vec<u8>) =
+        bcs::from_bytes(&bytes).unwrap();
+    assert_eq!(signatures[0], SPONSORED_FLAG);
+    assert_eq!(
+        bcs::from_bytes::<(GenericSignature, GasSponsorship)>(&signatures[1..]).unwrap(),
+        (sponsored.tx_signature.clone(), gas_sponsorship)
+    );
+    assert_eq!(
+        bcs::from_bytes::<SenderSignedData>(&bytes).unwrap(),
+        sponsored
+    );
+    let json = serde_json::to_string(&sponsored).unwrap();
+    assert_eq!(
+        serde_json::from_str::<SenderSignedData>(&json).unwrap(),
+        sponsored
+    );
+}
+
 #[test]
 fn test_user_signature_committed_in_signed_transactions() {
     // TODO: refactor this test to not reuse the same keys for user and authority signing
@@ -655,7 +707,7 @@ fn verify_sender_signature_correctly_with_flag() {
 
     // signature contains the correct Secp256k1 flag
     assert_eq!(
-        transaction.data().tx_signature.scheme().flag(),
+        transaction.data().tx_signature.flag(),
         Secp256k1SuiSignature::SCHEME.flag()
     );
 
@@ -689,7 +741,7 @@ fn verify_sender_signature_correctly_with_flag() {
 
     // signature contains the correct Ed25519 flag
     assert_eq!(
-        transaction_1.data().tx_signature.scheme().flag(),
+        transaction_1.data().tx_signature.flag(),
         Ed25519SuiSignature::SCHEME.flag()
     );
 
@@ -704,3 +756,56 @@ fn verify_sender_signature_correctly_with_flag() {
         .verify(transaction.data(), &committee)
         .is_err());
 }
+
+#[test]
+fn test_quorum_driver_retry_policy() {
+    let policy = QuorumDriverRetryPolicy {
+        max_attempts: 5,
+        initial_backoff_ms: 100,
+        max_backoff_ms: 300,
+        ..Default::default()
+    };
+    assert_eq!(policy.backoff(1), Duration::from_millis(100));
+    assert_eq!(policy.backoff(2), Duration::from_millis(200));
+    assert_eq!(policy.backoff(3), Duration::from_millis(300));
+    assert_eq!(policy.backoff(u32::MAX), Duration::from_millis(300));
+    assert_eq!(policy.attempt_timeout(), None);
+
+    // The unset fields of a policy sent over JSON-RPC take their default value.
+    let policy: QuorumDriverRetryPolicy =
+        serde_json::from_str(r#"{"maxAttempts": 3, "attemptTimeoutMs": 1000}"#).unwrap();
+    assert_eq!(
+        policy,
+        QuorumDriverRetryPolicy {
+            max_attempts: 3,
+            attempt_timeout_ms: Some(1000),
+            ..Default::default()
+        }
+    );
+    assert_eq!(policy.attempt_timeout(), Some(Duration::from_secs(1)));
+
+    // A policy is clamped field by field, an unset timeout taking the one of the maximum.
+    let max = QuorumDriverRetryPolicy {
+        max_attempts: 2,
+        attempt_timeout_ms: Some(500),
+        initial_backoff_ms: 1_000,
+        max_backoff_ms: 1_000,
+        retry_conflicting_transactions: false,
+    };
+    assert_eq!(
+        policy.clamp_to(&max),
+        QuorumDriverRetryPolicy {
+            max_attempts: 2,
+            attempt_timeout_ms: Some(500),
+            initial_backoff_ms: 100,
+            max_backoff_ms: 1_000,
+            retry_conflicting_transactions: false,
+        }
+    );
+    assert_eq!(
+        QuorumDriverRetryPolicy::default()
+            .clamp_to(&max)
+            .attempt_timeout(),
+        Some(Duration::from_millis(500))
+    );
+}
@@ -0,0 +1,171 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{GenericSignature, MultiSig, MultiSigPublicKey, MULTISIG_FLAG};
+use crate::{
+    base_types::{random_object_ref, SuiAddress},
+    crypto::{get_key_pair, PublicKey, Signature, SuiKeyPair},
+    error::SuiError,
+    intent::{Intent, IntentMessage, PersonalMessage},
+    message_envelope::Message,
+    messages::{SenderSignedData, Transaction, TransactionData},
+};
+
+fn keys() -> Vec<SuiKeyPair> {
+    vec![
+        SuiKeyPair::Ed25519SuiKeyPair(get_key_pair().1),
+        SuiKeyPair::Ed25519SuiKeyPair(get_key_pair().1),
+        SuiKeyPair::Secp256k1SuiKeyPair(get_key_pair().1),
+    ]
+}
+
+fn public_keys(keys: &[SuiKeyPair]) -> Vec<PublicKey> {
+    keys.iter().map(|kp| kp.public()).collect()
+}
+
+#[test]
+fn test_multisig_address() {
+    let keys = keys();
+    let pks = public_keys(&keys);
+    let multisig_pk = MultiSigPublicKey::new(pks.clone(), vec![1, 1, 2], 2).unwrap();
+    let address = SuiAddress::from(&multisig_pk);
+    assert_eq!(
+        address,
+        SuiAddress::from(&MultiSigPublicKey::new(pks.clone(), vec![1, 1, 2], 2).unwrap())
+    );
+
+    // The weights, the threshold and the order of the members all make the address.
+    let others = [
+        MultiSigPublicKey::new(pks.clone(), vec![1, 2, 1], 2).unwrap(),
+        MultiSigPublicKey::new(pks.clone(), vec![1, 1, 2], 3).unwrap(),
+        MultiSigPublicKey::new(pks.iter().rev().cloned().collect(), vec![2, 1, 1], 2).unwrap(),
+    ];
+    for other in &others {
+        assert_ne!(SuiAddress::from(other), address);
+    }
+    for pk in &pks {
+        assert_ne!(SuiAddress::from(pk), address);
+    }
+}
+
+#[test]
+fn test_invalid_multisig_public_key() {
+    let pks = public_keys(&keys());
+    for (pks, weights, threshold) in [
+        (pks.clone(), vec![1, 1], 1),
+        (vec![], vec![], 1),
+        (pks.clone(), vec![1, 1, 1], 0),
+        (pks.clone(), vec![1, 0, 1], 1),
+        (pks.clone(), vec![1, 1, 1], 4),
+        (vec![pks[0].clone(), pks[0].clone()], vec![1, 1], 1),
+        (vec![pks[0].clone(); 11], vec![1; 11], 1),
+    ] {
+        assert!(matches!(
+            MultiSigPublicKey::new(pks, weights, threshold),
+            Err(SuiError::InvalidMultiSig { .. })
+        ));
+    }
+}
+
+#[test]
+fn test_multisig_verification() {
+    let keys = keys();
+    let multisig_pk = MultiSigPublicKey::new(public_keys(&keys), vec![1, 1, 2], 2).unwrap();
+    let address = SuiAddress::from(&multisig_pk);
+    let message = IntentMessage::new(
+        Intent::default(),
+        PersonalMessage {
+            message: b"Hello".to_vec(),
+        },
+    );
+    let sigs: Vec<_> = keys
+        .iter()
+        .map(|kp| Signature::new_secure(&message, kp))
+        .collect();
+
+    // The signatures are combined whatever their order.
+    let two_members =
+        MultiSig::combine(vec![sigs[1].clone(), sigs[0].clone()], multisig_pk.clone()).unwrap();
+    assert!(two_members.verify_secure(&message, address).is_ok());
+    let heaviest = MultiSig::combine(vec![sigs[2].clone()], multisig_pk.clone()).unwrap();
+    assert!(heaviest.verify_secure(&message, address).is_ok());
+
+    // Not enough weight.
+    let one_member = MultiSig::combine(vec![sigs[0].clone()], multisig_pk.clone()).unwrap();
+    assert!(one_member.verify_secure(&message, address).is_err());
+
+    // Another message, or another sender.
+    let other_message = IntentMessage::new(
+        Intent::default(),
+        PersonalMessage {
+            message: b"Goodbye".to_vec(),
+        },
+    );
+    assert!(two_members.verify_secure(&other_message, address).is_err());
+    assert!(two_members
+        .verify_secure(&message, SuiAddress::from(&keys[0].public()))
+        .is_err());
+
+    // A signature of a member twice, or of a non-member.
+    assert!(matches!(
+        MultiSig::combine(vec![sigs[0].clone(), sigs[0].clone()], multisig_pk.clone()),
+        Err(SuiError::InvalidMultiSig { .. })
+    ));
+    let stranger = SuiKeyPair::Ed25519SuiKeyPair(get_key_pair().1);
+    assert!(matches!(
+        MultiSig::combine(
+            vec![Signature::new_secure(&message, &stranger)],
+            multisig_pk
+        ),
+        Err(SuiError::InvalidMultiSig { .. })
+    ));
+}
+
+#[test]
+fn test_transaction_signed_by_multisig() {
+    let keys = keys();
+    let multisig_pk = MultiSigPublicKey::new(public_keys(&keys), vec![1, 1, 1], 2).unwrap();
+    let sender = SuiAddress::from(&multisig_pk);
+    let data = TransactionData::new_transfer(
+        SuiAddress::random_for_testing_only(),
+        random_object_ref(),
+        sender,
+        random_object_ref(),
+        10000,
+    );
+    let message = IntentMessage::new(Intent::default(), data.clone());
+    let sigs = keys[1..]
+        .iter()
+        .map(|kp| Signature::new_secure(&message, kp))
+        .collect();
+    let multisig = MultiSig::combine(sigs, multisig_pk).unwrap();
+
+    let signed = SenderSignedData::new(data.clone(), Intent::default(), multisig.clone());
+    assert!(signed.verify().is_ok());
+
+    // The multisig is serialized behind its flag, the single signatures as they were.
+    let signature = GenericSignature::from(multisig);
+    let bytes = signature.to_bytes();
+    assert_eq!(bytes[0], MULTISIG_FLAG);
+    assert_eq!(GenericSignature::from_bytes(&bytes).unwrap(), signature);
+    let decoded: SenderSignedData = bcs::from_bytes(&bcs::to_bytes(&signed).unwrap()).unwrap();
+    assert_eq!(decoded, signed);
+    // Its transaction is only sent with the serialized signature.
+    let transaction = Transaction::new(signed);
+    assert!(matches!(
+        transaction.to_network_data_for_execution(),
+        Err(SuiError::UnsupportedFeatureError { .. })
+    ));
+    let (_, serialized) = transaction.to_tx_bytes_and_signature();
+    assert_eq!(serialized.to_vec().unwrap(), bytes);
+
+    let single = Signature::new_secure(&message, &keys[0]);
+    assert_eq!(
+        bcs::to_bytes(&GenericSignature::from(single.clone())).unwrap(),
+        bcs::to_bytes(&single).unwrap()
+    );
+    // A single member can't send the transaction of the multisig.
+    assert!(SenderSignedData::new(data, Intent::default(), single)
+        .verify()
+        .is_err());
+}
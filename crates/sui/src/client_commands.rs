@@ -14,10 +14,7 @@ use anyhow::{anyhow, ensure};
 use bip32::DerivationPath;
 use clap::*;
 use colored::Colorize;
-use fastcrypto::{
-    encoding::{Base64, Encoding},
-    traits::ToFromBytes,
-};
+use fastcrypto::encoding::{Base64, Encoding};
 use move_core_types::language_storage::TypeTag;
 use move_package::BuildConfig as MoveBuildConfig;
 use serde::Serialize;
@@ -36,19 +33,16 @@ use sui_json_rpc_types::{
 use sui_json_rpc_types::{GetRawObjectDataResponse, SuiData};
 use sui_json_rpc_types::{SuiCertifiedTransaction, SuiExecutionStatus, SuiTransactionEffects};
 use sui_keys::keystore::AccountKeystore;
-use sui_sdk::TransactionExecutionResult;
+use sui_sdk::{GasEstimate, TransactionExecutionResult};
 use sui_types::intent::Intent;
 use sui_types::{
     base_types::{ObjectID, SuiAddress},
     gas_coin::GasCoin,
-    messages::{Transaction, VerifiedTransaction},
+    messages::{Transaction, TransactionData, VerifiedTransaction},
     object::Owner,
     parse_sui_type_tag, SUI_FRAMEWORK_ADDRESS,
 };
-use sui_types::{
-    crypto::{Signature, SignatureScheme},
-    intent::IntentMessage,
-};
+use sui_types::{crypto::SignatureScheme, multisig::GenericSignature};
 
 use sui_sdk::SuiClient;
 
@@ -364,24 +358,30 @@ pub enum SuiClientCommands {
         gas_budget: Option<u64>,
     },
 
-    /// Serialize a transfer that can be signed. This is useful when user prefers to take the data to sign elsewhere.
-    #[clap(name = "serialize-transfer-sui")]
-    SerializeTransferSui {
-        /// Recipient address
-        #[clap(long)]
-        to: SuiAddress,
-
-        /// Sui coin object to transfer, ID in 20 bytes Hex string. This is also the gas object.
-        #[clap(long)]
-        sui_coin_object_id: ObjectID,
-
-        /// Gas budget for this transfer
-        #[clap(long)]
-        gas_budget: u64,
+    /// Serialize the unsigned transaction of a command, e.g. `serialize-tx transfer --to <ADDRESS>
+    /// --object-id <ID> --gas-budget <BUDGET>`. The transaction can then be signed elsewhere, like
+    /// on an offline machine with `sui keytool sign`, and executed with `execute-signed-tx`.
+    #[clap(name = "serialize-tx", trailing_var_arg = true)]
+    SerializeTx {
+        /// The command executing the transaction, among call, transfer, transfer-sui, pay, pay_sui,
+        /// pay_all_sui, split-coin and merge-coin, followed by its arguments.
+        #[clap(required = true, multiple_values = true, allow_hyphen_values = true)]
+        command: Vec<String>,
+    },
 
-        /// The amount to transfer, if not specified, the entire coin object will be transferred.
-        #[clap(long)]
-        amount: Option<u64>,
+    /// Estimate the gas of the transaction of a command by dry running it, e.g. `estimate-gas
+    /// transfer --to <ADDRESS> --object-id <ID> --gas-budget <BUDGET>`, and suggest a gas budget.
+    /// The gas budget of the command is replaced by the most the gas object can pay for.
+    #[clap(name = "estimate-gas", trailing_var_arg = true)]
+    EstimateGas {
+        /// The margin added to the gas units used by the dry run in the suggested budget.
+        #[clap(long, default_value = "10")]
+        safety_margin_percent: u64,
+
+        /// The command executing the transaction, among call, transfer, transfer-sui, pay, pay_sui,
+        /// pay_all_sui, split-coin and merge-coin, followed by its arguments.
+        #[clap(required = true, multiple_values = true, allow_hyphen_values = true)]
+        command: Vec<String>,
     },
 
     /// Execute a Signed Transaction. This is useful when the user prefers to sign elsewhere and use this command to execute.
@@ -390,7 +390,8 @@ pub enum SuiClientCommands {
         #[clap(long)]
         tx_bytes: String,
 
-        /// Base64 encoded signature `flag || signature || pubkey`.
+        /// Base64 encoded signature `flag || signature || pubkey`, or multisig, e.g. from
+        /// `sui keytool multi-sig-combine-partial-sig`.
         #[clap(long)]
         signature: String,
     },
@@ -471,30 +472,9 @@ impl SuiClientCommands {
                 SuiClientCommandResult::Call(cert, effects)
             }
 
-            SuiClientCommands::Transfer {
-                to,
-                object_id,
-                gas,
-                gas_budget,
-            } => {
-                let from = context.get_object_owner(&object_id).await?;
+            command @ SuiClientCommands::Transfer { .. } => {
                 let time_start = Instant::now();
-
-                let client = context.get_client().await?;
-                let data = client
-                    .transaction_builder()
-                    .transfer_object(from, object_id, gas, gas_budget, to)
-                    .await?;
-                let signature =
-                    context
-                        .config
-                        .keystore
-                        .sign_secure(&from, &data, Intent::default())?;
-                let response = context
-                    .execute_transaction(
-                        Transaction::from_data(data, Intent::default(), signature).verify()?,
-                    )
-                    .await?;
+                let response = command.sign_and_execute(context).await?;
                 let cert = response.certificate;
                 let effects = response.effects;
 
@@ -505,29 +485,8 @@ impl SuiClientCommands {
                 SuiClientCommandResult::Transfer(time_total, cert, effects)
             }
 
-            SuiClientCommands::TransferSui {
-                to,
-                sui_coin_object_id: object_id,
-                gas_budget,
-                amount,
-            } => {
-                let from = context.get_object_owner(&object_id).await?;
-
-                let client = context.get_client().await?;
-                let data = client
-                    .transaction_builder()
-                    .transfer_sui(from, object_id, gas_budget, to, amount)
-                    .await?;
-                let signature =
-                    context
-                        .config
-                        .keystore
-                        .sign_secure(&from, &data, Intent::default())?;
-                let response = context
-                    .execute_transaction(
-                        Transaction::from_data(data, Intent::default(), signature).verify()?,
-                    )
-                    .await?;
+            command @ SuiClientCommands::TransferSui { .. } => {
+                let response = command.sign_and_execute(context).await?;
                 let cert = response.certificate;
                 let effects = response.effects;
 
@@ -537,45 +496,8 @@ impl SuiClientCommands {
                 SuiClientCommandResult::TransferSui(cert, effects)
             }
 
-            SuiClientCommands::Pay {
-                input_coins,
-                recipients,
-                amounts,
-                gas,
-                gas_budget,
-            } => {
-                ensure!(
-                    !input_coins.is_empty(),
-                    "Pay transaction requires a non-empty list of input coins"
-                );
-                ensure!(
-                    !recipients.is_empty(),
-                    "Pay transaction requires a non-empty list of recipient addresses"
-                );
-                ensure!(
-                    recipients.len() == amounts.len(),
-                    format!(
-                        "Found {:?} recipient addresses, but {:?} recipient amounts",
-                        recipients.len(),
-                        amounts.len()
-                    ),
-                );
-                let from = context.get_object_owner(&input_coins[0]).await?;
-                let client = context.get_client().await?;
-                let data = client
-                    .transaction_builder()
-                    .pay(from, input_coins, recipients, amounts, gas, gas_budget)
-                    .await?;
-                let signature =
-                    context
-                        .config
-                        .keystore
-                        .sign_secure(&from, &data, Intent::default())?;
-                let response = context
-                    .execute_transaction(
-                        Transaction::from_data(data, Intent::default(), signature).verify()?,
-                    )
-                    .await?;
+            command @ SuiClientCommands::Pay { .. } => {
+                let response = command.sign_and_execute(context).await?;
                 let cert = response.certificate;
                 let effects = response.effects;
                 if matches!(effects.status, SuiExecutionStatus::Failure { .. }) {
@@ -587,45 +509,8 @@ impl SuiClientCommands {
                 SuiClientCommandResult::Pay(cert, effects)
             }
 
-            SuiClientCommands::PaySui {
-                input_coins,
-                recipients,
-                amounts,
-                gas_budget,
-            } => {
-                ensure!(
-                    !input_coins.is_empty(),
-                    "PaySui transaction requires a non-empty list of input coins"
-                );
-                ensure!(
-                    !recipients.is_empty(),
-                    "PaySui transaction requires a non-empty list of recipient addresses"
-                );
-                ensure!(
-                    recipients.len() == amounts.len(),
-                    format!(
-                        "Found {:?} recipient addresses, but {:?} recipient amounts",
-                        recipients.len(),
-                        amounts.len()
-                    ),
-                );
-                let signer = context.get_object_owner(&input_coins[0]).await?;
-                let client = context.get_client().await?;
-                let data = client
-                    .transaction_builder()
-                    .pay_sui(signer, input_coins, recipients, amounts, gas_budget)
-                    .await?;
-                let signature =
-                    context
-                        .config
-                        .keystore
-                        .sign_secure(&signer, &data, Intent::default())?;
-                let response = context
-                    .execute_transaction(
-                        Transaction::from_data(data, Intent::default(), signature).verify()?,
-                    )
-                    .await?;
-
+            command @ SuiClientCommands::PaySui { .. } => {
+                let response = command.sign_and_execute(context).await?;
                 let cert = response.certificate;
                 let effects = response.effects;
                 if matches!(effects.status, SuiExecutionStatus::Failure { .. }) {
@@ -637,33 +522,8 @@ impl SuiClientCommands {
                 SuiClientCommandResult::PaySui(cert, effects)
             }
 
-            SuiClientCommands::PayAllSui {
-                input_coins,
-                recipient,
-                gas_budget,
-            } => {
-                ensure!(
-                    !input_coins.is_empty(),
-                    "PayAllSui transaction requires a non-empty list of input coins"
-                );
-                let signer = context.get_object_owner(&input_coins[0]).await?;
-                let client = context.get_client().await?;
-                let data = client
-                    .transaction_builder()
-                    .pay_all_sui(signer, input_coins, recipient, gas_budget)
-                    .await?;
-
-                let signature =
-                    context
-                        .config
-                        .keystore
-                        .sign_secure(&signer, &data, Intent::default())?;
-                let response = context
-                    .execute_transaction(
-                        Transaction::from_data(data, Intent::default(), signature).verify()?,
-                    )
-                    .await?;
-
+            command @ SuiClientCommands::PayAllSui { .. } => {
+                let response = command.sign_and_execute(context).await?;
                 let cert = response.certificate;
                 let effects = response.effects;
                 if matches!(effects.status, SuiExecutionStatus::Failure { .. }) {
@@ -716,70 +576,12 @@ impl SuiClientCommands {
                     .collect();
                 SuiClientCommandResult::Gas(coins)
             }
-            SuiClientCommands::SplitCoin {
-                coin_id,
-                amounts,
-                count,
-                gas,
-                gas_budget,
-            } => {
-                let signer = context.get_object_owner(&coin_id).await?;
-                let client = context.get_client().await?;
-                let data = match (amounts, count) {
-                    (Some(amounts), None) => {
-                        client
-                            .transaction_builder()
-                            .split_coin(signer, coin_id, amounts, gas, gas_budget)
-                            .await?
-                    }
-                    (None, Some(count)) => {
-                        if count == 0 {
-                            return Err(anyhow!("Coin split count must be greater than 0"));
-                        }
-                        client
-                            .transaction_builder()
-                            .split_coin_equal(signer, coin_id, count, gas, gas_budget)
-                            .await?
-                    }
-                    _ => {
-                        return Err(anyhow!("Exactly one of `count` and `amounts` must be present for split-coin command."));
-                    }
-                };
-                let signature =
-                    context
-                        .config
-                        .keystore
-                        .sign_secure(&signer, &data, Intent::default())?;
-                let response = context
-                    .execute_transaction(
-                        Transaction::from_data(data, Intent::default(), signature).verify()?,
-                    )
-                    .await?;
+            command @ SuiClientCommands::SplitCoin { .. } => {
+                let response = command.sign_and_execute(context).await?;
                 SuiClientCommandResult::SplitCoin(response)
             }
-            SuiClientCommands::MergeCoin {
-                primary_coin,
-                coin_to_merge,
-                gas,
-                gas_budget,
-            } => {
-                let client = context.get_client().await?;
-                let signer = context.get_object_owner(&primary_coin).await?;
-                let data = client
-                    .transaction_builder()
-                    .merge_coins(signer, primary_coin, coin_to_merge, gas, gas_budget)
-                    .await?;
-                let signature =
-                    context
-                        .config
-                        .keystore
-                        .sign_secure(&signer, &data, Intent::default())?;
-                let response = context
-                    .execute_transaction(
-                        Transaction::from_data(data, Intent::default(), signature).verify()?,
-                    )
-                    .await?;
-
+            command @ SuiClientCommands::MergeCoin { .. } => {
+                let response = command.sign_and_execute(context).await?;
                 SuiClientCommandResult::MergeCoin(response)
             }
             SuiClientCommands::Switch { address, env } => {
@@ -839,27 +641,29 @@ impl SuiClientCommands {
                 SuiClientCommandResult::CreateExampleNFT(object_read)
             }
 
-            SuiClientCommands::SerializeTransferSui {
-                to,
-                sui_coin_object_id: object_id,
-                gas_budget,
-                amount,
+            SuiClientCommands::SerializeTx { command } => {
+                let command = SuiClientCommands::try_parse_from(
+                    std::iter::once("serialize-tx".to_string()).chain(command),
+                )?;
+                let (_, data) = command.transaction_data(context).await?;
+                SuiClientCommandResult::SerializeTx(Base64::encode(bcs::to_bytes(&data)?))
+            }
+
+            SuiClientCommands::EstimateGas {
+                safety_margin_percent,
+                command,
             } => {
-                let from = context.get_object_owner(&object_id).await?;
-                let client = context.get_client().await?;
-                let data = client
-                    .transaction_builder()
-                    .transfer_sui(from, object_id, gas_budget, to, amount)
+                let command = SuiClientCommands::try_parse_from(
+                    std::iter::once("estimate-gas".to_string()).chain(command),
+                )?;
+                let (_, data) = command.transaction_data(context).await?;
+                let estimate = context
+                    .get_client()
+                    .await?
+                    .read_api()
+                    .estimate_gas(&data, safety_margin_percent)
                     .await?;
-                let data1 = data.clone();
-                let intent_msg = IntentMessage::new(Intent::default(), data);
-                info!(
-                    "Transaction bytes : {}",
-                    Base64::encode(&bcs::to_bytes(&data1).unwrap())
-                );
-                SuiClientCommandResult::SerializeTransferSui(Base64::encode(
-                    bcs::to_bytes(&intent_msg)?.as_slice(),
-                ))
+                SuiClientCommandResult::EstimateGas(estimate)
             }
 
             SuiClientCommands::ExecuteSignedTx {
@@ -872,7 +676,7 @@ impl SuiClientCommands {
                         .to_vec()
                         .map_err(|e| anyhow!(e))?,
                 )?;
-                let signature = Signature::from_bytes(
+                let signature = GenericSignature::from_bytes(
                     &Base64::try_from(signature)
                         .map_err(|e| anyhow!(e))?
                         .to_vec()
@@ -909,6 +713,224 @@ impl SuiClientCommands {
         ret
     }
 
+    /// Signs the transaction of the command with the key of its signer and executes it.
+    async fn sign_and_execute(
+        self,
+        context: &mut WalletContext,
+    ) -> Result<SuiTransactionResponse, anyhow::Error> {
+        let (signer, data) = self.transaction_data(context).await?;
+        let signature = context
+            .config
+            .keystore
+            .sign_secure(&signer, &data, Intent::default())?;
+        context
+            .execute_transaction(
+                Transaction::from_data(data, Intent::default(), signature).verify()?,
+            )
+            .await
+    }
+
+    /// The signer and the unsigned data of the transaction of the command, for the commands
+    /// executing one.
+    async fn transaction_data(
+        self,
+        context: &mut WalletContext,
+    ) -> Result<(SuiAddress, TransactionData), anyhow::Error> {
+        Ok(match self {
+            SuiClientCommands::Call {
+                package,
+                module,
+                function,
+                type_args,
+                gas,
+                gas_budget,
+                args,
+            } => {
+                let gas_owner = context.try_get_object_owner(&gas).await?;
+                let sender = gas_owner.unwrap_or(context.active_address()?);
+
+                let client = context.get_client().await?;
+                let data = client
+                    .transaction_builder()
+                    .move_call(
+                        sender,
+                        package,
+                        &module,
+                        &function,
+                        type_args
+                            .into_iter()
+                            .map(|arg| arg.try_into())
+                            .collect::<Result<Vec<_>, _>>()?,
+                        args,
+                        gas,
+                        gas_budget,
+                    )
+                    .await?;
+                (sender, data)
+            }
+
+            SuiClientCommands::Transfer {
+                to,
+                object_id,
+                gas,
+                gas_budget,
+            } => {
+                let from = context.get_object_owner(&object_id).await?;
+                let client = context.get_client().await?;
+                let data = client
+                    .transaction_builder()
+                    .transfer_object(from, object_id, gas, gas_budget, to)
+                    .await?;
+                (from, data)
+            }
+
+            SuiClientCommands::TransferSui {
+                to,
+                sui_coin_object_id: object_id,
+                gas_budget,
+                amount,
+            } => {
+                let from = context.get_object_owner(&object_id).await?;
+                let client = context.get_client().await?;
+                let data = client
+                    .transaction_builder()
+                    .transfer_sui(from, object_id, gas_budget, to, amount)
+                    .await?;
+                (from, data)
+            }
+
+            SuiClientCommands::Pay {
+                input_coins,
+                recipients,
+                amounts,
+                gas,
+                gas_budget,
+            } => {
+                ensure!(
+                    !input_coins.is_empty(),
+                    "Pay transaction requires a non-empty list of input coins"
+                );
+                ensure!(
+                    !recipients.is_empty(),
+                    "Pay transaction requires a non-empty list of recipient addresses"
+                );
+                ensure!(
+                    recipients.len() == amounts.len(),
+                    format!(
+                        "Found {:?} recipient addresses, but {:?} recipient amounts",
+                        recipients.len(),
+                        amounts.len()
+                    ),
+                );
+                let from = context.get_object_owner(&input_coins[0]).await?;
+                let client = context.get_client().await?;
+                let data = client
+                    .transaction_builder()
+                    .pay(from, input_coins, recipients, amounts, gas, gas_budget)
+                    .await?;
+                (from, data)
+            }
+
+            SuiClientCommands::PaySui {
+                input_coins,
+                recipients,
+                amounts,
+                gas_budget,
+            } => {
+                ensure!(
+                    !input_coins.is_empty(),
+                    "PaySui transaction requires a non-empty list of input coins"
+                );
+                ensure!(
+                    !recipients.is_empty(),
+                    "PaySui transaction requires a non-empty list of recipient addresses"
+                );
+                ensure!(
+                    recipients.len() == amounts.len(),
+                    format!(
+                        "Found {:?} recipient addresses, but {:?} recipient amounts",
+                        recipients.len(),
+                        amounts.len()
+                    ),
+                );
+                let signer = context.get_object_owner(&input_coins[0]).await?;
+                let client = context.get_client().await?;
+                let data = client
+                    .transaction_builder()
+                    .pay_sui(signer, input_coins, recipients, amounts, gas_budget)
+                    .await?;
+                (signer, data)
+            }
+
+            SuiClientCommands::PayAllSui {
+                input_coins,
+                recipient,
+                gas_budget,
+            } => {
+                ensure!(
+                    !input_coins.is_empty(),
+                    "PayAllSui transaction requires a non-empty list of input coins"
+                );
+                let signer = context.get_object_owner(&input_coins[0]).await?;
+                let client = context.get_client().await?;
+                let data = client
+                    .transaction_builder()
+                    .pay_all_sui(signer, input_coins, recipient, gas_budget)
+                    .await?;
+                (signer, data)
+            }
+
+            SuiClientCommands::SplitCoin {
+                coin_id,
+                amounts,
+                count,
+                gas,
+                gas_budget,
+            } => {
+                let signer = context.get_object_owner(&coin_id).await?;
+                let client = context.get_client().await?;
+                let data = match (amounts, count) {
+                    (Some(amounts), None) => {
+                        client
+                            .transaction_builder()
+                            .split_coin(signer, coin_id, amounts, gas, gas_budget)
+                            .await?
+                    }
+                    (None, Some(count)) => {
+                        if count == 0 {
+                            return Err(anyhow!("Coin split count must be greater than 0"));
+                        }
+                        client
+                            .transaction_builder()
+                            .split_coin_equal(signer, coin_id, count, gas, gas_budget)
+                            .await?
+                    }
+                    _ => {
+                        return Err(anyhow!("Exactly one of `count` and `amounts` must be present for split-coin command."));
+                    }
+                };
+                (signer, data)
+            }
+
+            SuiClientCommands::MergeCoin {
+                primary_coin,
+                coin_to_merge,
+                gas,
+                gas_budget,
+            } => {
+                let client = context.get_client().await?;
+                let signer = context.get_object_owner(&primary_coin).await?;
+                let data = client
+                    .transaction_builder()
+                    .merge_coins(signer, primary_coin, coin_to_merge, gas, gas_budget)
+                    .await?;
+                (signer, data)
+            }
+
+            _ => return Err(anyhow!("The command doesn't execute a transaction.")),
+        })
+    }
+
     pub fn switch_env(config: &mut SuiClientConfig, env: &str) -> Result<(), anyhow::Error> {
         let env = Some(env.into());
         ensure!(config.get_env(&env).is_some(), "Environment config not found for [{env:?}], add new environment config using the `sui client new-env` command.");
@@ -1227,8 +1249,20 @@ impl Display for SuiClientCommandResult {
                     writeln!(writer, "{}", parsed_resp)?;
                 }
             }
-            SuiClientCommandResult::SerializeTransferSui(res) => {
-                write!(writer, "Data to sign: {}", res)?;
+            SuiClientCommandResult::SerializeTx(tx_bytes) => {
+                write!(writer, "Transaction bytes to sign: {}", tx_bytes)?;
+            }
+            SuiClientCommandResult::EstimateGas(estimate) => {
+                let gas_used = &estimate.gas_used;
+                writeln!(writer, "Computation cost: {}", gas_used.computation_cost)?;
+                writeln!(writer, "Storage cost: {}", gas_used.storage_cost)?;
+                writeln!(writer, "Storage rebate: {}", gas_used.storage_rebate)?;
+                writeln!(writer, "Gas units: {}", estimate.gas_units)?;
+                write!(
+                    writer,
+                    "Suggested gas budget: {}",
+                    estimate.suggested_budget
+                )?;
             }
             SuiClientCommandResult::ActiveEnv(env) => {
                 write!(writer, "{}", env.as_deref().unwrap_or("None"))?;
@@ -1260,34 +1294,17 @@ pub async fn call_move(
     args: Vec<SuiJsonValue>,
     context: &mut WalletContext,
 ) -> Result<(SuiCertifiedTransaction, SuiTransactionEffects), anyhow::Error> {
-    let gas_owner = context.try_get_object_owner(&gas).await?;
-    let sender = gas_owner.unwrap_or(context.active_address()?);
-
-    let client = context.get_client().await?;
-    let data = client
-        .transaction_builder()
-        .move_call(
-            sender,
-            package,
-            module,
-            function,
-            type_args
-                .into_iter()
-                .map(|arg| arg.try_into())
-                .collect::<Result<Vec<_>, _>>()?,
-            args,
-            gas,
-            gas_budget,
-        )
-        .await?;
-
-    let signature = context
-        .config
-        .keystore
-        .sign_secure(&sender, &data, Intent::default())?;
-    let transaction = Transaction::from_data(data, Intent::default(), signature).verify()?;
-
-    let response = context.execute_transaction(transaction).await?;
+    let response = SuiClientCommands::Call {
+        package,
+        module: module.to_string(),
+        function: function.to_string(),
+        type_args,
+        gas,
+        gas_budget,
+        args,
+    }
+    .sign_and_execute(context)
+    .await?;
     let cert = response.certificate;
     let effects = response.effects;
 
@@ -1304,7 +1321,7 @@ fn unwrap_or<'a>(val: &'a Option<String>, default: &'a str) -> &'a str {
     }
 }
 
-fn write_cert_and_effects(
+pub(crate) fn write_cert_and_effects(
     cert: &SuiCertifiedTransaction,
     effects: &SuiTransactionEffects,
 ) -> Result<String, fmt::Error> {
@@ -1380,7 +1397,8 @@ pub enum SuiClientCommandResult {
     ActiveEnv(Option<String>),
     Envs(Vec<SuiEnv>, Option<String>),
     CreateExampleNFT(GetObjectDataResponse),
-    SerializeTransferSui(String),
+    SerializeTx(String),
+    EstimateGas(GasEstimate),
     ExecuteSignedTx(SuiTransactionResponse),
     NewEnv(SuiEnv),
 }
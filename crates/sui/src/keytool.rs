@@ -17,10 +17,14 @@ use tracing::info;
 
 use fastcrypto::ed25519::{Ed25519KeyPair, Ed25519PrivateKey, Ed25519PublicKey};
 use sui_keys::keystore::{AccountKeystore, Keystore};
+use sui_keys::ledger::{Ledger, LedgerKey};
 use sui_types::base_types::SuiAddress;
 use sui_types::crypto::{
     get_key_pair, AuthorityKeyPair, Ed25519SuiSignature, EncodeDecodeBase64, NetworkKeyPair,
-    SignatureScheme, SuiKeyPair, SuiSignatureInner,
+    PublicKey, Signature, SignatureScheme, SuiKeyPair, SuiSignatureInner,
+};
+use sui_types::multisig::{
+    GenericSignature, MultiSig, MultiSigPublicKey, ThresholdUnit, WeightUnit,
 };
 #[cfg(test)]
 #[path = "unit_tests/keytool_tests.rs"]
@@ -46,7 +50,12 @@ pub enum KeyToolCommand {
     },
     /// List all keys by its address, public key, key scheme in the keystore
     List,
-    /// Create signature using the sui keystore and provided data.
+    /// Create signature using the sui keystore and provided data, the BCS serialized transaction
+    /// data bytes as base-64 encoded string, e.g. from `sui client serialize-tx`. The keystore is
+    /// all it needs, so that the keys can stay on an offline machine. The printed serialized
+    /// signature is the one to execute the transaction with, using `sui client execute-signed-tx`.
+    /// The address is the one of the sender, or of a member of the multisig account sending the
+    /// transaction, whose partial signature is then combined with `multi-sig-combine-partial-sig`.
     Sign {
         #[clap(long, parse(try_from_str = decode_bytes_hex))]
         address: SuiAddress,
@@ -60,11 +69,42 @@ pub enum KeyToolCommand {
         key_scheme: SignatureScheme,
         derivation_path: Option<DerivationPath>,
     },
+    /// Add the Ed25519 key of a Ledger device running the Sui app to the sui keystore, with
+    /// optional derivation path, default to m/44'/784'/0'/0'/0'. The key stays on the device,
+    /// which signs for its address once the transactions are accepted on it. The device is
+    /// reached over USB, or at the `host:port` set in SUI_LEDGER_TCP, e.g. of an emulator.
+    ImportLedger {
+        derivation_path: Option<DerivationPath>,
+    },
     /// Read keypair from path and show its base64 encoded value with flag. This is useful
     /// to generate protocol, account, worker, network keys in NodeConfig with its expected encoding.
     LoadKeypair {
         file: PathBuf,
     },
+    /// Generate the address of a multisig account from the base64 encoded public keys of its
+    /// members with their weights, and from its threshold: its transactions are executed once
+    /// signed by members whose weights add up to the threshold.
+    MultiSigAddress {
+        #[clap(long)]
+        threshold: ThresholdUnit,
+        #[clap(long, multiple_values = true)]
+        pks: Vec<PublicKey>,
+        #[clap(long, multiple_values = true)]
+        weights: Vec<WeightUnit>,
+    },
+    /// Combine the serialized signatures of members of a multisig account, e.g. from `sui keytool
+    /// sign`, into the serialized multisig to execute the transaction with, using `sui client
+    /// execute-signed-tx`. The account is given as to `multi-sig-address`.
+    MultiSigCombinePartialSig {
+        #[clap(long, multiple_values = true, parse(try_from_str = decode_signature))]
+        sigs: Vec<Signature>,
+        #[clap(long)]
+        threshold: ThresholdUnit,
+        #[clap(long, multiple_values = true)]
+        pks: Vec<PublicKey>,
+        #[clap(long, multiple_values = true)]
+        weights: Vec<WeightUnit>,
+    },
 }
 
 impl KeyToolCommand {
@@ -144,7 +184,10 @@ impl KeyToolCommand {
                 info!("Flag Base64: {}", flag);
                 info!("Public Key Base64: {}", pub_key);
                 info!("Signature : {}", signature);
-                info!("Serialized signature Base64: {:?}", sui_signature);
+                info!(
+                    "Serialized signature Base64: {}",
+                    Base64::encode(sui_signature.as_ref())
+                );
             }
             KeyToolCommand::Import {
                 mnemonic_phrase,
@@ -156,6 +199,13 @@ impl KeyToolCommand {
                 info!("Key imported for address [{address}]");
             }
 
+            KeyToolCommand::ImportLedger { derivation_path } => {
+                let key = LedgerKey::from_device(&Ledger::connect()?, derivation_path)?;
+                let address = key.address();
+                keystore.add_ledger_key(key)?;
+                println!("Ledger key added for address [{address}]");
+            }
+
             KeyToolCommand::LoadKeypair { file } => {
                 match read_keypair_from_file(&file) {
                     Ok(keypair) => {
@@ -178,6 +228,45 @@ impl KeyToolCommand {
                     }
                 }
             }
+
+            KeyToolCommand::MultiSigAddress {
+                threshold,
+                pks,
+                weights,
+            } => {
+                let multisig_pk = MultiSigPublicKey::new(pks, weights, threshold)?;
+                println!("MultiSig address: {}", SuiAddress::from(&multisig_pk));
+                println!("Participating parties:");
+                println!(
+                    " {0: ^42} | {1: ^50} | {2: ^6}",
+                    "Sui Address", "Public Key (Base64)", "Weight"
+                );
+                println!("{}", ["-"; 104].join(""));
+                for (pk, weight) in multisig_pk.pubkeys() {
+                    println!(
+                        " {0: ^42} | {1: ^50} | {2: ^6}",
+                        SuiAddress::from(pk),
+                        pk.encode_base64(),
+                        weight
+                    );
+                }
+            }
+
+            KeyToolCommand::MultiSigCombinePartialSig {
+                sigs,
+                threshold,
+                pks,
+                weights,
+            } => {
+                let multisig_pk = MultiSigPublicKey::new(pks, weights, threshold)?;
+                let address = SuiAddress::from(&multisig_pk);
+                let multisig = GenericSignature::from(MultiSig::combine(sigs, multisig_pk)?);
+                println!("MultiSig address: {address}");
+                println!(
+                    "Serialized signature Base64: {}",
+                    Base64::encode(multisig.to_bytes())
+                );
+            }
         }
 
         Ok(())
@@ -198,6 +287,12 @@ fn store_and_print_keypair(address: SuiAddress, keypair: SuiKeyPair) {
     );
 }
 
+/// Decodes a base64 encoded signature `flag || signature || pubkey`.
+fn decode_signature(s: &str) -> anyhow::Result<Signature> {
+    let bytes = Base64::decode(s).map_err(|e| anyhow!(e))?;
+    Signature::from_bytes(&bytes).map_err(|e| anyhow!(e))
+}
+
 pub fn write_keypair_to_file<P: AsRef<std::path::Path>>(
     keypair: &SuiKeyPair,
     path: P,
@@ -9,5 +9,6 @@ pub mod keytool;
 pub mod shell;
 pub mod sui_commands;
 pub mod sui_move;
+pub mod validator_commands;
 
 pub mod genesis_ceremony;
@@ -4,12 +4,15 @@
 use std::io::{stderr, stdout, Write};
 use std::num::NonZeroUsize;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 use std::{fs, io};
 
 use anyhow::{anyhow, bail};
 use clap::*;
 use fastcrypto::traits::KeyPair;
 use move_package::BuildConfig;
+#[cfg(unix)]
+use tokio::signal::unix::{signal, SignalKind};
 use tracing::info;
 
 use sui_config::{builder::ConfigBuilder, NetworkConfig, SUI_KEYSTORE_FILENAME};
@@ -28,6 +31,7 @@ use crate::console::start_console;
 use crate::genesis_ceremony::{run, Ceremony};
 use crate::keytool::KeyToolCommand;
 use crate::sui_move::{self, execute_move_command};
+use crate::validator_commands::SuiValidatorCommand;
 
 #[allow(clippy::large_enum_variant)]
 #[derive(Parser)]
@@ -46,6 +50,10 @@ pub enum SuiCommand {
         config: Option<PathBuf>,
         #[clap(long = "no-full-node")]
         no_full_node: bool,
+        /// End the consensus epochs of the validators after this many milliseconds. By default,
+        /// an epoch only ends when forced by sending SIGUSR1 to this process.
+        #[clap(long)]
+        epoch_duration_ms: Option<u64>,
     },
     #[clap(name = "network")]
     Network {
@@ -98,6 +106,18 @@ pub enum SuiCommand {
         #[clap(long, global = true)]
         json: bool,
     },
+    /// Operate a validator: join the committee, update its metadata and rotate its keys.
+    #[clap(name = "validator")]
+    Validator {
+        /// Sets the file storing the state of our user accounts (an empty one will be created if missing)
+        #[clap(long = "client.config")]
+        config: Option<PathBuf>,
+        #[clap(subcommand)]
+        cmd: Option<SuiValidatorCommand>,
+        /// Return command outputs in json format.
+        #[clap(long, global = true)]
+        json: bool,
+    },
 
     /// Tool to build and test Move applications.
     #[clap(name = "move")]
@@ -120,6 +140,7 @@ impl SuiCommand {
             SuiCommand::Start {
                 config,
                 no_full_node,
+                epoch_duration_ms,
             } => {
                 // Auto genesis if path is none and sui directory doesn't exists.
                 if config.is_none() && !sui_config_dir()?.join(SUI_NETWORK_CONFIG).exists() {
@@ -138,23 +159,34 @@ impl SuiCommand {
                         ))
                     })?;
 
-                let mut swarm = if no_full_node {
+                let mut builder = if no_full_node {
                     Swarm::builder()
                 } else {
                     Swarm::builder()
                         .with_fullnode_rpc_addr(sui_config::node::default_json_rpc_address())
                         .with_event_store()
+                };
+                if let Some(epoch_duration_ms) = epoch_duration_ms {
+                    builder = builder.with_epoch_duration(Duration::from_millis(epoch_duration_ms));
                 }
-                .from_network_config(sui_config_dir()?, network_config);
+                let mut swarm = builder.from_network_config(sui_config_dir()?, network_config);
 
                 swarm.launch().await?;
 
-                let mut interval = tokio::time::interval(std::time::Duration::from_secs(5));
+                #[cfg(unix)]
+                let mut force_epoch_change = signal(SignalKind::user_defined1())?;
+                let mut interval = tokio::time::interval(Duration::from_secs(5));
                 loop {
                     for node in swarm.validators_mut() {
                         node.health_check(true).await?;
                     }
 
+                    #[cfg(unix)]
+                    tokio::select! {
+                        _ = interval.tick() => {}
+                        _ = force_epoch_change.recv() => swarm.force_epoch_change()?,
+                    }
+                    #[cfg(not(unix))]
                     interval.tick().await;
                 }
             }
@@ -214,6 +246,20 @@ impl SuiCommand {
                 }
                 Ok(())
             }
+            SuiCommand::Validator { config, cmd, json } => {
+                let config_path = config.unwrap_or(sui_config_dir()?.join(SUI_CLIENT_CONFIG));
+                prompt_if_no_config(&config_path).await?;
+                let mut context = WalletContext::new(&config_path, None).await?;
+                if let Some(cmd) = cmd {
+                    cmd.execute(&mut context).await?.print(!json);
+                } else {
+                    // Print help
+                    let mut app: Command = SuiCommand::command();
+                    app.build();
+                    app.find_subcommand_mut("validator").unwrap().print_help()?;
+                }
+                Ok(())
+            }
             SuiCommand::Move {
                 package_path,
                 build_config,
@@ -8,11 +8,13 @@ use move_package::BuildConfig;
 use serde_json::json;
 use tokio::time::sleep;
 
+use fastcrypto::encoding::{Base64, Encoding};
 use sui::client_commands::SwitchResponse;
 use sui::{
     client_commands::{SuiClientCommandResult, SuiClientCommands, WalletContext},
     config::SuiClientConfig,
     sui_commands::SuiCommand,
+    validator_commands::{SuiValidatorCommand, SuiValidatorCommandResult, ValidatorRegistration},
 };
 use sui_config::genesis_config::{AccountConfig, GenesisConfig, ObjectConfig};
 use sui_config::{
@@ -30,6 +32,8 @@ use sui_types::base_types::SuiAddress;
 use sui_types::crypto::{
     Ed25519SuiSignature, Secp256k1SuiSignature, SignatureScheme, SuiKeyPair, SuiSignatureInner,
 };
+use sui_types::intent::Intent;
+use sui_types::messages::TransactionData;
 use sui_types::{base_types::ObjectID, crypto::get_key_pair, gas_coin::GasCoin};
 use sui_types::{sui_framework_address_concat_string, SUI_FRAMEWORK_ADDRESS};
 use test_utils::messages::make_transactions_with_wallet_context;
@@ -47,6 +51,7 @@ async fn test_genesis() -> Result<(), anyhow::Error> {
     let start = SuiCommand::Start {
         config: Some(config),
         no_full_node: false,
+        epoch_duration_ms: None,
     }
     .execute()
     .await;
@@ -1153,7 +1158,7 @@ async fn test_execute_signed_tx() -> Result<(), anyhow::Error> {
 }
 
 #[sim_test]
-async fn test_serialize_tx() -> Result<(), anyhow::Error> {
+async fn test_sign_serialized_tx_offline() -> Result<(), anyhow::Error> {
     let mut test_cluster = TestClusterBuilder::new().build().await?;
     let address = test_cluster.get_address_0();
     let address1 = test_cluster.get_address_1();
@@ -1165,13 +1170,115 @@ async fn test_serialize_tx() -> Result<(), anyhow::Error> {
         .await?;
     let coin = object_refs.get(1).unwrap().object_id;
 
-    SuiClientCommands::SerializeTransferSui {
+    let command = format!(
+        "transfer-sui --to {address1} --sui-coin-object-id {coin} --gas-budget 1000 --amount 1"
+    );
+    let SuiClientCommandResult::SerializeTx(tx_bytes) = SuiClientCommands::SerializeTx {
+        command: command.split(' ').map(str::to_string).collect(),
+    }
+    .execute(context)
+    .await? else {
+        panic!("serialize-tx should return the transaction bytes")
+    };
+
+    // Sign the transaction with the key of the sender only, as offline.
+    let data: TransactionData = bcs::from_bytes(&Base64::decode(&tx_bytes).unwrap())?;
+    assert_eq!(data.signer(), address);
+    let signature = context
+        .config
+        .keystore
+        .sign_secure(&address, &data, Intent::default())?;
+
+    let SuiClientCommandResult::ExecuteSignedTx(response) = SuiClientCommands::ExecuteSignedTx {
+        tx_bytes,
+        signature: Base64::encode(signature.as_ref()),
+    }
+    .execute(context)
+    .await? else {
+        panic!("execute-signed-tx should return the transaction response")
+    };
+    assert!(response.effects.status.is_ok());
+
+    // Only the commands executing a transaction can be serialized.
+    assert!(SuiClientCommands::SerializeTx {
+        command: vec!["addresses".to_string()],
+    }
+    .execute(context)
+    .await
+    .is_err());
+    Ok(())
+}
+
+#[sim_test]
+async fn test_estimate_gas() -> Result<(), anyhow::Error> {
+    let mut test_cluster = TestClusterBuilder::new().build().await?;
+    let address = test_cluster.get_address_0();
+    let address1 = test_cluster.get_address_1();
+    let context = &mut test_cluster.wallet;
+    let client = context.get_client().await?;
+    let object_refs = client
+        .read_api()
+        .get_objects_owned_by_address(address)
+        .await?;
+    let coin = object_refs.get(1).unwrap().object_id;
+
+    // The budget of the command is not used by the dry run.
+    let command = format!(
+        "transfer-sui --to {address1} --sui-coin-object-id {coin} --gas-budget 1 --amount 1"
+    );
+    let SuiClientCommandResult::EstimateGas(estimate) = SuiClientCommands::EstimateGas {
+        safety_margin_percent: 10,
+        command: command.split(' ').map(str::to_string).collect(),
+    }
+    .execute(context)
+    .await? else {
+        panic!("estimate-gas should return the gas estimate")
+    };
+    assert!(estimate.gas_units > 0);
+    assert!(estimate.suggested_budget >= estimate.gas_units);
+
+    // The suggested budget is enough to execute the transaction.
+    let SuiClientCommandResult::TransferSui(_, effects) = SuiClientCommands::TransferSui {
         to: address1,
         sui_coin_object_id: coin,
-        gas_budget: 1000,
+        gas_budget: estimate.suggested_budget,
         amount: Some(1),
     }
     .execute(context)
-    .await?;
+    .await? else {
+        panic!("transfer-sui should return the transaction effects")
+    };
+    assert!(effects.status.is_ok());
+    assert_eq!(
+        effects.gas_used.computation_cost,
+        estimate.gas_used.computation_cost
+    );
+    Ok(())
+}
+
+#[sim_test]
+async fn test_validator_status_command() -> Result<(), anyhow::Error> {
+    let mut test_cluster = TestClusterBuilder::new().build().await?;
+    let validator = test_cluster.swarm.config().validator_set()[0].sui_address();
+    let context = &mut test_cluster.wallet;
+
+    let SuiValidatorCommandResult::Status(status) = SuiValidatorCommand::Status {
+        address: Some(validator),
+    }
+    .execute(context)
+    .await? else {
+        panic!("status should return the validator status")
+    };
+    assert_eq!(status.registration, ValidatorRegistration::Active);
+    assert_eq!(status.validator.unwrap().metadata.sui_address, validator);
+
+    // The address of the wallet is not a validator.
+    let SuiValidatorCommandResult::Status(status) = SuiValidatorCommand::Status { address: None }
+        .execute(context)
+        .await? else {
+        panic!("status should return the validator status")
+    };
+    assert_eq!(status.registration, ValidatorRegistration::NotRegistered);
+    assert!(status.validator.is_none());
     Ok(())
 }
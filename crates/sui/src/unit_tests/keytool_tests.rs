@@ -6,6 +6,7 @@ use crate::keytool::read_keypair_from_file;
 
 use super::write_keypair_to_file;
 use super::KeyToolCommand;
+use fastcrypto::ed25519::Ed25519KeyPair;
 use fastcrypto::encoding::Encoding;
 use fastcrypto::encoding::Hex;
 use rand::rngs::StdRng;
@@ -22,7 +23,7 @@ use sui_types::crypto::Signature;
 use sui_types::crypto::SignatureScheme;
 use sui_types::crypto::SuiKeyPair;
 use sui_types::crypto::SuiSignatureInner;
-use sui_types::intent::Intent;
+use sui_types::intent::{Intent, IntentMessage};
 use tempfile::TempDir;
 
 const TEST_MNEMONIC: &str = "result crisp session latin must fruit genuine question prevent start coconut brave speak student dismiss";
@@ -283,3 +284,56 @@ fn test_valid_derivation_path() -> Result<(), anyhow::Error> {
     .is_ok());
     Ok(())
 }
+
+#[test]
+fn test_multisig_commands() -> Result<(), anyhow::Error> {
+    let mut keystore = Keystore::from(InMemKeystore::new(2));
+    keystore.add_key(SuiKeyPair::Secp256k1SuiKeyPair(get_key_pair().1))?;
+    let pks = keystore.keys();
+    let weights = vec![1, 1, 2];
+
+    KeyToolCommand::MultiSigAddress {
+        threshold: 2,
+        pks: pks.clone(),
+        weights: weights.clone(),
+    }
+    .execute(&mut keystore)?;
+
+    let sigs = pks[..2]
+        .iter()
+        .map(|pk| keystore.sign_secure(&pk.into(), b"hello", Intent::default()))
+        .collect::<Result<Vec<_>, _>>()?;
+    KeyToolCommand::MultiSigCombinePartialSig {
+        sigs: sigs.clone(),
+        threshold: 2,
+        pks: pks.clone(),
+        weights: weights.clone(),
+    }
+    .execute(&mut keystore)?;
+
+    // The signature of a non-member can't be combined.
+    let (_, stranger): (_, Ed25519KeyPair) = get_key_pair();
+    let mut with_stranger = sigs;
+    with_stranger.push(Signature::new_secure(
+        &IntentMessage::new(Intent::default(), b"hello"),
+        &stranger,
+    ));
+    assert!(KeyToolCommand::MultiSigCombinePartialSig {
+        sigs: with_stranger,
+        threshold: 2,
+        pks: pks.clone(),
+        weights: weights.clone(),
+    }
+    .execute(&mut keystore)
+    .is_err());
+
+    // Neither can the members be given without their weights.
+    assert!(KeyToolCommand::MultiSigAddress {
+        threshold: 2,
+        pks,
+        weights: weights[..2].to_vec(),
+    }
+    .execute(&mut keystore)
+    .is_err());
+    Ok(())
+}
@@ -0,0 +1,450 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::fmt::{Debug, Display, Formatter, Write};
+use std::path::PathBuf;
+
+use anyhow::ensure;
+use clap::*;
+use colored::Colorize;
+use fastcrypto::encoding::{Encoding, Hex};
+use fastcrypto::traits::KeyPair;
+use multiaddr::Multiaddr;
+use serde::Serialize;
+use serde_json::json;
+use tracing::info;
+
+use sui_json::SuiJsonValue;
+use sui_json_rpc_types::{SuiCertifiedTransaction, SuiTransactionEffects};
+use sui_types::base_types::{ObjectID, SuiAddress};
+use sui_types::crypto::{
+    generate_proof_of_possession, AuthorityKeyPair, AuthorityPublicKeyBytes, NetworkKeyPair,
+};
+use sui_types::sui_system_state::{Validator, SUI_SYSTEM_MODULE_NAME};
+use sui_types::{SUI_FRAMEWORK_OBJECT_ID, SUI_SYSTEM_STATE_OBJECT_ID};
+
+use crate::client_commands::{call_move, write_cert_and_effects, WalletContext};
+use crate::keytool::{read_authority_keypair_from_file, read_network_keypair_from_file};
+
+/// The validator's address is the active address of the wallet, which signs the requests.
+#[derive(Parser)]
+#[clap(rename_all = "kebab-case")]
+pub enum SuiValidatorCommand {
+    /// Request to join the committee of validators starting the next epoch, staking a SUI coin.
+    #[clap(name = "join-committee")]
+    JoinCommittee {
+        /// Path to the file of the validator's protocol key pair.
+        #[clap(long)]
+        protocol_key_file: PathBuf,
+        /// Path to the file of the validator's network key pair.
+        #[clap(long)]
+        network_key_file: PathBuf,
+        /// Path to the file of the validator's worker key pair.
+        #[clap(long)]
+        worker_key_file: PathBuf,
+        /// Unique human-readable name of the validator.
+        #[clap(long)]
+        name: String,
+        /// Network address of the validator.
+        #[clap(long)]
+        network_address: Multiaddr,
+        /// ID of the SUI coin to stake, which must cover the minimum validator stake.
+        #[clap(long)]
+        stake: ObjectID,
+        /// Gas price quote of the validator.
+        #[clap(long)]
+        gas_price: u64,
+        /// Commission rate of the validator on the rewards of its delegators, in basis point.
+        #[clap(long, default_value = "0")]
+        commission_rate: u64,
+        /// ID of the gas object for gas payment, in 20 bytes Hex string.
+        /// If not provided, a gas object with at least gas_budget value will be selected.
+        #[clap(long)]
+        gas: Option<ObjectID>,
+        /// Gas budget for this call
+        #[clap(long)]
+        gas_budget: u64,
+    },
+
+    /// Update the network address, gas price or commission rate of the validator starting the
+    /// next epoch. Each update is a separate transaction.
+    #[clap(name = "update-metadata")]
+    UpdateMetadata {
+        /// New network address of the validator.
+        #[clap(long)]
+        network_address: Option<Multiaddr>,
+        /// New gas price quote of the validator.
+        #[clap(long)]
+        gas_price: Option<u64>,
+        /// New commission rate of the validator, in basis point.
+        #[clap(long)]
+        commission_rate: Option<u64>,
+        /// ID of the gas object for gas payment, in 20 bytes Hex string.
+        /// If not provided, a gas object with at least gas_budget value will be selected.
+        #[clap(long)]
+        gas: Option<ObjectID>,
+        /// Gas budget for each update
+        #[clap(long)]
+        gas_budget: u64,
+    },
+
+    /// Rotate the protocol, network and worker keys of the validator starting the next epoch.
+    /// A key that is not rotated can be passed as it is.
+    #[clap(name = "rotate-keys")]
+    RotateKeys {
+        /// Path to the file of the new protocol key pair.
+        #[clap(long)]
+        protocol_key_file: PathBuf,
+        /// Path to the file of the new network key pair.
+        #[clap(long)]
+        network_key_file: PathBuf,
+        /// Path to the file of the new worker key pair.
+        #[clap(long)]
+        worker_key_file: PathBuf,
+        /// ID of the gas object for gas payment, in 20 bytes Hex string.
+        /// If not provided, a gas object with at least gas_budget value will be selected.
+        #[clap(long)]
+        gas: Option<ObjectID>,
+        /// Gas budget for this call
+        #[clap(long)]
+        gas_budget: u64,
+    },
+
+    /// Show the registration of a validator in the current system state.
+    #[clap(name = "status")]
+    Status {
+        /// Address of the validator, the active address if not provided.
+        #[clap(long)]
+        address: Option<SuiAddress>,
+    },
+}
+
+impl SuiValidatorCommand {
+    pub async fn execute(
+        self,
+        context: &mut WalletContext,
+    ) -> Result<SuiValidatorCommandResult, anyhow::Error> {
+        let ret = match self {
+            SuiValidatorCommand::JoinCommittee {
+                protocol_key_file,
+                network_key_file,
+                worker_key_file,
+                name,
+                network_address,
+                stake,
+                gas_price,
+                commission_rate,
+                gas,
+                gas_budget,
+            } => {
+                let address = context.active_address()?;
+                let protocol_keypair: AuthorityKeyPair =
+                    read_authority_keypair_from_file(protocol_key_file)?;
+                let network_keypair: NetworkKeyPair =
+                    read_network_keypair_from_file(network_key_file)?;
+                let worker_keypair: NetworkKeyPair =
+                    read_network_keypair_from_file(worker_key_file)?;
+                let pop = generate_proof_of_possession(&protocol_keypair, address);
+                let args = vec![
+                    SuiJsonValue::from_object_id(SUI_SYSTEM_STATE_OBJECT_ID),
+                    pure_arg(&AuthorityPublicKeyBytes::from(protocol_keypair.public()))?,
+                    pure_arg(network_keypair.public())?,
+                    pure_arg(worker_keypair.public())?,
+                    bytes_arg(pop.as_ref())?,
+                    bytes_arg(name.as_bytes())?,
+                    pure_arg(&network_address)?,
+                    SuiJsonValue::from_object_id(stake),
+                    SuiJsonValue::new(json!(gas_price))?,
+                    SuiJsonValue::new(json!(commission_rate))?,
+                ];
+                let (cert, effects) =
+                    call_sui_system("request_add_validator", gas, gas_budget, args, context)
+                        .await?;
+                SuiValidatorCommandResult::JoinCommittee(cert, effects)
+            }
+
+            SuiValidatorCommand::UpdateMetadata {
+                network_address,
+                gas_price,
+                commission_rate,
+                gas,
+                gas_budget,
+            } => {
+                let mut updates = vec![];
+                if let Some(network_address) = network_address {
+                    updates.push(("request_set_net_address", pure_arg(&network_address)?));
+                }
+                if let Some(gas_price) = gas_price {
+                    updates.push((
+                        "request_set_gas_price",
+                        SuiJsonValue::new(json!(gas_price))?,
+                    ));
+                }
+                if let Some(commission_rate) = commission_rate {
+                    updates.push((
+                        "request_set_commission_rate",
+                        SuiJsonValue::new(json!(commission_rate))?,
+                    ));
+                }
+                ensure!(
+                    !updates.is_empty(),
+                    "Provide a network address, gas price or commission rate to update"
+                );
+                let mut responses = vec![];
+                for (function, arg) in updates {
+                    let args = vec![
+                        SuiJsonValue::from_object_id(SUI_SYSTEM_STATE_OBJECT_ID),
+                        arg,
+                    ];
+                    responses
+                        .push(call_sui_system(function, gas, gas_budget, args, context).await?);
+                }
+                SuiValidatorCommandResult::UpdateMetadata(responses)
+            }
+
+            SuiValidatorCommand::RotateKeys {
+                protocol_key_file,
+                network_key_file,
+                worker_key_file,
+                gas,
+                gas_budget,
+            } => {
+                let address = context.active_address()?;
+                let protocol_keypair: AuthorityKeyPair =
+                    read_authority_keypair_from_file(protocol_key_file)?;
+                let network_keypair: NetworkKeyPair =
+                    read_network_keypair_from_file(network_key_file)?;
+                let worker_keypair: NetworkKeyPair =
+                    read_network_keypair_from_file(worker_key_file)?;
+                let pop = generate_proof_of_possession(&protocol_keypair, address);
+                let args = vec![
+                    SuiJsonValue::from_object_id(SUI_SYSTEM_STATE_OBJECT_ID),
+                    pure_arg(&AuthorityPublicKeyBytes::from(protocol_keypair.public()))?,
+                    bytes_arg(pop.as_ref())?,
+                    pure_arg(network_keypair.public())?,
+                    pure_arg(worker_keypair.public())?,
+                ];
+                let (cert, effects) =
+                    call_sui_system("request_rotate_keys", gas, gas_budget, args, context).await?;
+                SuiValidatorCommandResult::RotateKeys(cert, effects)
+            }
+
+            SuiValidatorCommand::Status { address } => {
+                let address = match address {
+                    Some(address) => address,
+                    None => context.active_address()?,
+                };
+                let client = context.get_client().await?;
+                let system_state = client.read_api().get_sui_system_state().await?;
+                let validators = &system_state.validators;
+                let active = validators
+                    .active_validators
+                    .iter()
+                    .position(|v| v.metadata.sui_address == address);
+                let (registration, validator) = if let Some(index) = active {
+                    let registration = if validators.pending_removals.contains(&(index as u64)) {
+                        ValidatorRegistration::PendingRemoval
+                    } else {
+                        ValidatorRegistration::Active
+                    };
+                    (
+                        registration,
+                        Some(validators.active_validators[index].clone()),
+                    )
+                } else if let Some(validator) = validators
+                    .pending_validators
+                    .iter()
+                    .find(|v| v.metadata.sui_address == address)
+                {
+                    (ValidatorRegistration::Pending, Some(validator.clone()))
+                } else {
+                    (ValidatorRegistration::NotRegistered, None)
+                };
+                SuiValidatorCommandResult::Status(ValidatorStatus {
+                    address,
+                    epoch: system_state.epoch,
+                    registration,
+                    validator,
+                })
+            }
+        };
+        Ok(ret)
+    }
+}
+
+async fn call_sui_system(
+    function: &str,
+    gas: Option<ObjectID>,
+    gas_budget: u64,
+    args: Vec<SuiJsonValue>,
+    context: &mut WalletContext,
+) -> Result<(SuiCertifiedTransaction, SuiTransactionEffects), anyhow::Error> {
+    call_move(
+        SUI_FRAMEWORK_OBJECT_ID,
+        SUI_SYSTEM_MODULE_NAME.as_str(),
+        function,
+        vec![],
+        gas,
+        gas_budget,
+        args,
+        context,
+    )
+    .await
+}
+
+fn bytes_arg(bytes: &[u8]) -> Result<SuiJsonValue, anyhow::Error> {
+    SuiJsonValue::new(json!(bytes))
+}
+
+/// Keys and addresses are stored on chain in the encoding genesis passes them in, which is their
+/// BCS serialization read as a vector of bytes.
+fn pure_arg<T: Serialize + ?Sized>(value: &T) -> Result<SuiJsonValue, anyhow::Error> {
+    let bytes: Vec<u8> = bcs::from_bytes(&bcs::to_bytes(value)?)?;
+    bytes_arg(&bytes)
+}
+
+#[derive(Serialize, Clone, Debug, Eq, PartialEq)]
+pub enum ValidatorRegistration {
+    /// In the committee of the current epoch.
+    Active,
+    /// Joining the committee in the next epoch.
+    Pending,
+    /// Leaving the committee in the next epoch.
+    PendingRemoval,
+    NotRegistered,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidatorStatus {
+    pub address: SuiAddress,
+    pub epoch: u64,
+    pub registration: ValidatorRegistration,
+    pub validator: Option<Validator>,
+}
+
+impl Display for ValidatorStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut writer = String::new();
+        writeln!(writer, "{}", "----- Validator ----".bold())?;
+        writeln!(writer, "Address: {}", self.address)?;
+        writeln!(writer, "Epoch: {}", self.epoch)?;
+        writeln!(writer, "Registration: {:?}", self.registration)?;
+        if let Some(validator) = &self.validator {
+            let metadata = &validator.metadata;
+            writeln!(writer, "Name: {}", String::from_utf8_lossy(&metadata.name))?;
+            writeln!(
+                writer,
+                "Network address: {}",
+                format_net_address(&metadata.net_address)
+            )?;
+            writeln!(
+                writer,
+                "Protocol key: {}",
+                Hex::encode(&metadata.pubkey_bytes)
+            )?;
+            writeln!(
+                writer,
+                "Network key: {}",
+                Hex::encode(&metadata.network_pubkey_bytes)
+            )?;
+            writeln!(
+                writer,
+                "Worker key: {}",
+                Hex::encode(&metadata.worker_pubkey_bytes)
+            )?;
+            writeln!(writer, "Stake: {}", validator.stake_amount)?;
+            writeln!(
+                writer,
+                "Delegation: {}",
+                validator.delegation_staking_pool.sui_balance
+            )?;
+            writeln!(writer, "Gas price: {}", validator.gas_price)?;
+            writeln!(writer, "Commission rate: {}", validator.commission_rate)?;
+
+            writeln!(writer, "{}", "----- Next Epoch ----".bold())?;
+            writeln!(writer, "Stake: {}", metadata.next_epoch_stake)?;
+            writeln!(writer, "Delegation: {}", metadata.next_epoch_delegation)?;
+            writeln!(writer, "Gas price: {}", metadata.next_epoch_gas_price)?;
+            writeln!(
+                writer,
+                "Commission rate: {}",
+                metadata.next_epoch_commission_rate
+            )?;
+            if let Some(net_address) = metadata.next_epoch_net_address.as_ref() {
+                writeln!(
+                    writer,
+                    "Network address: {}",
+                    format_net_address(net_address)
+                )?;
+            }
+            if let Some(pubkey_bytes) = metadata.next_epoch_pubkey_bytes.as_ref() {
+                writeln!(writer, "Protocol key: {}", Hex::encode(pubkey_bytes))?;
+            }
+            if let Some(pubkey_bytes) = metadata.next_epoch_network_pubkey_bytes.as_ref() {
+                writeln!(writer, "Network key: {}", Hex::encode(pubkey_bytes))?;
+            }
+            if let Some(pubkey_bytes) = metadata.next_epoch_worker_pubkey_bytes.as_ref() {
+                writeln!(writer, "Worker key: {}", Hex::encode(pubkey_bytes))?;
+            }
+        }
+        write!(f, "{}", writer.trim_end_matches('\n'))
+    }
+}
+
+fn format_net_address(net_address: &[u8]) -> String {
+    Multiaddr::try_from(net_address.to_vec())
+        .map(|address| address.to_string())
+        .unwrap_or_else(|_| Hex::encode(net_address))
+}
+
+#[derive(Serialize)]
+#[serde(untagged)]
+pub enum SuiValidatorCommandResult {
+    JoinCommittee(SuiCertifiedTransaction, SuiTransactionEffects),
+    UpdateMetadata(Vec<(SuiCertifiedTransaction, SuiTransactionEffects)>),
+    RotateKeys(SuiCertifiedTransaction, SuiTransactionEffects),
+    Status(ValidatorStatus),
+}
+
+impl Display for SuiValidatorCommandResult {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut writer = String::new();
+        match self {
+            SuiValidatorCommandResult::JoinCommittee(cert, effects)
+            | SuiValidatorCommandResult::RotateKeys(cert, effects) => {
+                write!(writer, "{}", write_cert_and_effects(cert, effects)?)?;
+            }
+            SuiValidatorCommandResult::UpdateMetadata(responses) => {
+                for (cert, effects) in responses {
+                    write!(writer, "{}", write_cert_and_effects(cert, effects)?)?;
+                }
+            }
+            SuiValidatorCommandResult::Status(status) => {
+                writeln!(writer, "{}", status)?;
+            }
+        }
+        write!(f, "{}", writer.trim_end_matches('\n'))
+    }
+}
+
+impl Debug for SuiValidatorCommandResult {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let s = serde_json::to_string_pretty(self).map_err(|_| std::fmt::Error)?;
+        write!(f, "{}", s)
+    }
+}
+
+impl SuiValidatorCommandResult {
+    pub fn print(&self, pretty: bool) {
+        let line = if pretty {
+            format!("{self}")
+        } else {
+            format!("{:?}", self)
+        };
+        for line in line.lines() {
+            println!("{line}");
+            info!("{line}")
+        }
+    }
+}
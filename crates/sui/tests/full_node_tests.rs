@@ -6,6 +6,8 @@ use std::ops::Neg;
 use std::{collections::BTreeMap, sync::Arc};
 
 use futures::future;
+use futures::Stream;
+use futures::StreamExt;
 use jsonrpsee::core::client::{ClientT, Subscription, SubscriptionClientT};
 use jsonrpsee::rpc_params;
 use move_core_types::parser::parse_struct_tag;
@@ -14,13 +16,14 @@ use mysten_metrics::RegistryService;
 use prometheus::Registry;
 use sui::client_commands::{SuiClientCommandResult, SuiClientCommands};
 use sui_json_rpc_types::{
-    type_and_fields_from_move_struct, EventPage, SuiEvent, SuiEventEnvelope, SuiEventFilter,
-    SuiExecuteTransactionResponse, SuiExecutionStatus, SuiMoveStruct, SuiMoveValue,
-    SuiTransactionFilter, SuiTransactionResponse,
+    type_and_fields_from_move_struct, EventPage, SuiCheckpoint, SuiEvent, SuiEventEnvelope,
+    SuiEventFilter, SuiExecuteTransactionResponse, SuiExecutionStatus, SuiMoveStruct, SuiMoveValue,
+    SuiObjectRead, SuiTransactionFilter, SuiTransactionResponse,
 };
 use sui_keys::keystore::AccountKeystore;
 use sui_macros::*;
 use sui_node::SuiNode;
+use sui_sdk::SuiClient;
 use sui_types::base_types::{ObjectRef, SequenceNumber};
 use sui_types::crypto::{get_key_pair, SuiKeyPair};
 use sui_types::event::BalanceChangeType;
@@ -687,6 +690,132 @@ async fn test_full_node_sub_and_query_move_event_ok() -> Result<(), anyhow::Erro
     Ok(())
 }
 
+#[sim_test]
+async fn test_full_node_resume_event_subscription() -> Result<(), anyhow::Error> {
+    let mut test_cluster = TestClusterBuilder::new()
+        .enable_fullnode_events()
+        .build()
+        .await?;
+
+    let fullnode = start_fullnode_from_config(
+        test_cluster
+            .fullnode_config_builder()
+            .with_event_store()
+            .build()
+            .unwrap(),
+    )
+    .await
+    .unwrap();
+    let node = fullnode.sui_node;
+    let ws_client = fullnode.ws_client;
+    let context = &mut test_cluster.wallet;
+
+    let struct_tag_str = sui_framework_address_concat_string("::devnet_nft::MintNFTEvent");
+    let filter = SuiEventFilter::And(
+        Box::new(SuiEventFilter::MoveModule {
+            package: SUI_FRAMEWORK_OBJECT_ID,
+            module: "devnet_nft".into(),
+        }),
+        Box::new(SuiEventFilter::MoveEventType(struct_tag_str.clone())),
+    );
+
+    // Two events were emitted before the subscription resumes after the first one.
+    let (_, _, digest1) = create_devnet_nft(context).await?;
+    wait_for_tx(digest1, node.state().clone()).await;
+    let (_, _, digest2) = create_devnet_nft(context).await?;
+    wait_for_tx(digest2, node.state().clone()).await;
+    let events = node
+        .state()
+        .get_events(EventQuery::MoveEvent(struct_tag_str), None, 10, false)
+        .await?;
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0].1.tx_digest, Some(digest1));
+    let cursor = events[0].0.clone();
+
+    let mut sub: Subscription<SuiEventEnvelope> = ws_client
+        .subscribe(
+            "sui_subscribeEvent",
+            rpc_params![filter, cursor],
+            "sui_unsubscribeEvent",
+        )
+        .await
+        .unwrap();
+
+    // The stored event after the cursor, then the events emitted after the subscription.
+    let (_, _, digest3) = create_devnet_nft(context).await?;
+    wait_for_tx(digest3, node.state().clone()).await;
+    for digest in [digest2, digest3] {
+        match timeout(Duration::from_secs(5), sub.next()).await {
+            Ok(Some(Ok(event))) => assert_eq!(event.tx_digest, Some(digest)),
+            other => panic!("Failed to get SuiEvent, but {:?}", other),
+        }
+    }
+
+    // No more
+    match timeout(Duration::from_secs(5), sub.next()).await {
+        Err(_) => (),
+        other => panic!(
+            "Expect to time out because no new events are coming in. Got {:?}",
+            other
+        ),
+    }
+
+    Ok(())
+}
+
+#[sim_test]
+async fn test_full_node_event_subscription_reconnects() -> Result<(), anyhow::Error> {
+    let mut test_cluster = TestClusterBuilder::new().build().await?;
+
+    // The fullnode is restarted from the same config, so that it keeps its address and stores.
+    let config = test_cluster
+        .fullnode_config_builder()
+        .with_event_store()
+        .with_dir("event_subscription_reconnects".into())
+        .build()?;
+    let fullnode = start_fullnode_from_config(config.clone()).await?;
+    let client = SuiClient::new(&fullnode.rpc_url, Some(&fullnode.ws_url), None).await?;
+    let context = &mut test_cluster.wallet;
+
+    let struct_tag_str = sui_framework_address_concat_string("::devnet_nft::MintNFTEvent");
+    let filter = SuiEventFilter::MoveEventType(struct_tag_str);
+    let mut events = Box::pin(
+        client
+            .event_api()
+            .subscribe_event_with_reconnect(filter, None),
+    );
+
+    let (_, _, digest1) = create_devnet_nft(context).await?;
+    wait_for_tx(digest1, fullnode.sui_node.state().clone()).await;
+    let event = timeout(Duration::from_secs(5), events.next()).await?;
+    assert_eq!(event.unwrap()?.tx_digest, Some(digest1));
+
+    // An event is emitted while the fullnode is down, and another one once it is back.
+    drop(fullnode);
+    let (_, _, digest2) = create_devnet_nft(context).await?;
+    let fullnode = start_fullnode_from_config(config).await?;
+    wait_for_tx(digest2, fullnode.sui_node.state().clone()).await;
+    let (_, _, digest3) = create_devnet_nft(context).await?;
+    wait_for_tx(digest3, fullnode.sui_node.state().clone()).await;
+
+    // Neither missed nor received twice.
+    for digest in [digest2, digest3] {
+        match timeout(Duration::from_secs(60), events.next()).await {
+            Ok(Some(Ok(event))) => assert_eq!(event.tx_digest, Some(digest)),
+            other => panic!("Failed to get SuiEvent, but {:?}", other),
+        }
+    }
+    match timeout(Duration::from_secs(5), events.next()).await {
+        Err(_) => (),
+        other => panic!(
+            "Expect to time out because no new events are coming in. Got {:?}",
+            other
+        ),
+    }
+
+    Ok(())
+}
+
 // Test fullnode has event read jsonrpc endpoints working
 #[sim_test]
 async fn test_full_node_event_read_api_ok() {
@@ -1365,3 +1494,150 @@ async fn test_get_objects_read() -> Result<(), anyhow::Error> {
 
     Ok(())
 }
+
+/// Reads `checkpoints` up to the one with the transaction `digest`, appending them to `read` and
+/// checking that they follow each other from the first checkpoint on.
+async fn read_checkpoints_until<E: std::fmt::Debug>(
+    checkpoints: &mut (impl Stream<Item = Result<SuiCheckpoint, E>> + Unpin),
+    read: &mut Vec<SuiCheckpoint>,
+    digest: TransactionDigest,
+) {
+    timeout(Duration::from_secs(60), async {
+        loop {
+            let checkpoint = checkpoints.next().await.unwrap().unwrap();
+            match read.last() {
+                Some(previous) => {
+                    assert_eq!(checkpoint.sequence_number, previous.sequence_number + 1);
+                    assert_eq!(checkpoint.previous_digest.as_ref(), Some(&previous.digest));
+                }
+                None => assert_eq!(checkpoint.sequence_number, 0),
+            }
+            let contents = checkpoint.contents.as_ref().unwrap();
+            let found = contents.iter().any(|digests| digests.transaction == digest);
+            read.push(checkpoint);
+            if found {
+                break;
+            }
+        }
+    })
+    .await
+    .unwrap();
+}
+
+#[sim_test]
+async fn test_full_node_checkpoint_subscription_catches_up() -> Result<(), anyhow::Error> {
+    let mut test_cluster = TestClusterBuilder::new().build().await?;
+    let client = &test_cluster.fullnode_handle.sui_client;
+    let context = &mut test_cluster.wallet;
+
+    // Wait until the checkpoint of a first transaction is synced.
+    let (_, _, _, digest, _, _) = transfer_coin(context).await?;
+    let mut checkpoints = client
+        .read_api()
+        .subscribe_checkpoint(Some(0), true)
+        .await?;
+    let mut synced = Vec::new();
+    read_checkpoints_until(&mut checkpoints, &mut synced, digest).await;
+    drop(checkpoints);
+
+    // A new subscription from the first checkpoint catches up with the synced ones, then streams
+    // the ones synced after it, like the checkpoint of a transaction executed once subscribed.
+    let mut checkpoints = client
+        .read_api()
+        .subscribe_checkpoint(Some(0), true)
+        .await?;
+    let (_, _, _, digest, _, _) = transfer_coin(context).await?;
+    let mut read = Vec::new();
+    read_checkpoints_until(&mut checkpoints, &mut read, digest).await;
+    assert!(read.len() > synced.len());
+    for (read, synced) in read.iter().zip(&synced) {
+        assert_eq!(read.digest, synced.digest);
+    }
+
+    Ok(())
+}
+
+#[sim_test]
+async fn test_read_only_full_node() -> Result<(), anyhow::Error> {
+    let mut test_cluster = TestClusterBuilder::new().build().await?;
+
+    // Populate the stores of a regular fullnode first.
+    let config = test_cluster
+        .fullnode_config_builder()
+        .with_event_store()
+        .with_dir("read_only_source".into())
+        .build()?;
+    let node = start_fullnode_from_config(config).await?;
+
+    let context = &mut test_cluster.wallet;
+    let (transferred_object, _, receiver, digest, _, _) = transfer_coin(context).await?;
+    wait_for_tx(digest, node.sui_node.state().clone()).await;
+
+    // Wait until the transaction is part of a checkpoint synced by the fullnode.
+    let mut checkpoints = node
+        .sui_client
+        .read_api()
+        .subscribe_checkpoint(Some(0), true)
+        .await?;
+    let checkpoint = timeout(Duration::from_secs(60), async {
+        loop {
+            let checkpoint = checkpoints.next().await.unwrap().unwrap();
+            let contents = checkpoint.contents.as_ref().unwrap();
+            if contents.iter().any(|digests| digests.transaction == digest) {
+                break checkpoint;
+            }
+        }
+    })
+    .await?;
+
+    // Wait for events to propagate to the event store.
+    sleep(Duration::from_millis(1000)).await;
+    let events = node
+        .sui_node
+        .state()
+        .get_events(EventQuery::Transaction(digest), None, 10, false)
+        .await?;
+    assert!(!events.is_empty());
+    drop(checkpoints);
+    drop(node);
+
+    // Serve the same stores from a read-only fullnode.
+    let mut config = test_cluster
+        .fullnode_config_builder()
+        .with_event_store()
+        .with_dir("read_only_source".into())
+        .build()?;
+    config.read_only = true;
+    let node = start_fullnode_from_config(config).await?;
+    assert!(node.sui_node.transaction_orchestrator().is_none());
+
+    let object_read = node
+        .sui_client
+        .read_api()
+        .get_object(transferred_object)
+        .await?;
+    let SuiObjectRead::Exists(object) = object_read else {
+        anyhow::bail!("Expect object {transferred_object:?} to exist but got {object_read:?}.");
+    };
+    assert_eq!(object.owner, Owner::AddressOwner(receiver));
+
+    let tx = node.sui_client.read_api().get_transaction(digest).await?;
+    assert_eq!(tx.certificate.transaction_digest, digest);
+
+    let page: EventPage = node
+        .sui_client
+        .event_api()
+        .get_events(EventQuery::Transaction(digest), None, None, false)
+        .await?;
+    assert_eq!(page.data.len(), events.len());
+
+    let mut checkpoints = node
+        .sui_client
+        .read_api()
+        .subscribe_checkpoint(Some(checkpoint.sequence_number), false)
+        .await?;
+    let first = checkpoints.next().await.unwrap()?;
+    assert_eq!(first.digest, checkpoint.digest);
+
+    Ok(())
+}
@@ -423,6 +423,7 @@ async fn create_and_register_new_validator(
             }),
             CallArg::Pure(bcs::to_bytes(&new_validator.protocol_key()).unwrap()),
             CallArg::Pure(bcs::to_bytes(&new_validator.network_key()).unwrap()),
+            CallArg::Pure(bcs::to_bytes(&new_validator.worker_key()).unwrap()),
             CallArg::Pure(bcs::to_bytes(&new_validator_pop.as_ref()).unwrap()),
             CallArg::Pure(
                 bcs::to_bytes(format!("Validator{}", new_validator.sui_address()).as_bytes())
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use futures::{stream, StreamExt};
+use std::time::Duration;
 use sui_core::authority_client::AuthorityAPI;
 use sui_types::messages::{
     CallArg, ExecutionStatus, ObjectArg, ObjectInfoRequest, ObjectInfoRequestKind,
@@ -215,6 +216,103 @@ async fn shared_object_flood() {
     assert!(matches!(effects.status, ExecutionStatus::Success { .. }));
 }
 
+/// Shared-object transactions keep being sequenced and executed after the validators force the end
+/// of the Narwhal epoch, although Narwhal starts its rounds and commit indices from zero again.
+#[sim_test]
+async fn shared_object_transaction_after_forced_epoch_change() {
+    telemetry_subscribers::init_for_testing();
+    let mut gas_objects = test_gas_objects();
+
+    // Get the authority configs and spawn them. Note that it is important to not drop
+    // the handles (or the authorities will stop).
+    let configs = test_authority_configs();
+    let handles = spawn_test_authorities(gas_objects.clone(), &configs).await;
+
+    // Publish the move package to all authorities and get the new package ref.
+    let package_ref =
+        publish_counter_package(gas_objects.pop().unwrap(), configs.validator_set()).await;
+
+    // Make a transaction to create a counter.
+    let transaction = move_transaction(
+        gas_objects.pop().unwrap(),
+        "counter",
+        "create",
+        package_ref,
+        /* arguments */ Vec::default(),
+    );
+    let effects = submit_single_owner_transaction(transaction, configs.validator_set()).await;
+    assert!(matches!(effects.status, ExecutionStatus::Success { .. }));
+    let ((counter_id, counter_initial_shared_version, _), _) = effects.created[0];
+    let counter_object_arg = ObjectArg::SharedObject {
+        id: counter_id,
+        initial_shared_version: counter_initial_shared_version,
+    };
+
+    // Increment the counter in the first Narwhal epoch.
+    let transaction = move_transaction(
+        gas_objects.pop().unwrap(),
+        "counter",
+        "increment",
+        package_ref,
+        vec![CallArg::Object(counter_object_arg)],
+    );
+    let effects = submit_shared_object_transaction(transaction, configs.validator_set())
+        .await
+        .unwrap();
+    assert!(matches!(effects.status, ExecutionStatus::Success { .. }));
+
+    // End the Narwhal epoch on all the validators, and wait until they all sequence the output of
+    // the next one.
+    for handle in &handles {
+        handle
+            .with_async(|node| node.force_epoch_change())
+            .await
+            .unwrap();
+    }
+    tokio::time::timeout(Duration::from_secs(60), async {
+        while !handles.iter().all(|handle| {
+            handle.with(|node| node.state().metrics.consensus_handler_narwhal_epoch.get()) == 1
+        }) {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+    })
+    .await
+    .expect("The validators did not move to the next Narwhal epoch");
+
+    // Increment the counter in the new Narwhal epoch.
+    let transaction = move_transaction(
+        gas_objects.pop().unwrap(),
+        "counter",
+        "increment",
+        package_ref,
+        vec![CallArg::Object(counter_object_arg)],
+    );
+    let effects = tokio::time::timeout(
+        Duration::from_secs(60),
+        submit_shared_object_transaction(transaction, configs.validator_set()),
+    )
+    .await
+    .expect("The transaction was not sequenced in the new Narwhal epoch")
+    .unwrap();
+    assert!(matches!(effects.status, ExecutionStatus::Success { .. }));
+
+    // Ensure the value of the counter is `2`.
+    let transaction = move_transaction(
+        gas_objects.pop().unwrap(),
+        "counter",
+        "assert_value",
+        package_ref,
+        vec![
+            CallArg::Object(counter_object_arg),
+            CallArg::Pure(2u64.to_le_bytes().to_vec()),
+        ],
+    );
+    let effects = submit_shared_object_transaction(transaction, configs.validator_set())
+        .await
+        .unwrap();
+    assert!(matches!(effects.status, ExecutionStatus::Success { .. }));
+}
+
 #[sim_test]
 async fn shared_object_sync() {
     telemetry_subscribers::init_for_testing();
@@ -33,6 +33,7 @@
 //!
 //! ## Features
 //! - `jaeger` - this feature is enabled by default as it enables jaeger tracing
+//! - `otlp` - this feature is enabled by default as it enables the export of spans over OTLP
 //! - `json` - Bunyan formatter - JSON log output, optional
 //! - `tokio-console` - [Tokio-console](https://github.com/tokio-rs/console) subscriber, optional
 //!
@@ -59,7 +60,18 @@
 //! 3. Run your app
 //! 4. Browse to `http://localhost:16686/` and select the service you configured using `service_name`
 //!
-//! NOTE: separate spans (which are not nested) are not connected as a single trace for now.
+//! NOTE: separate spans (which are not nested) are not connected as a single trace unless they are
+//! joined with the functions of the [`otel`] module.
+//!
+//! ### OTLP (Tempo, Jaeger and other OpenTelemetry collectors)
+//!
+//! The spans can also be exported with the OpenTelemetry protocol over gRPC, to any collector:
+//!
+//! 1. Set the `otlp_endpoint` config setting, or the standard `OTEL_EXPORTER_OTLP_ENDPOINT` env var, to
+//!    the collector, e.g. `http://localhost:4317`
+//! 2. Run your app
+//!
+//! The spans are batched, and the ones left are flushed when the [`TelemetryGuards`] are dropped.
 //!
 //! Jaeger subscriber is enabled by default but is protected by the jaeger feature flag.  If you'd like to leave
 //! out the Jaeger dependencies, you can turn off the default-features in your dependency:
@@ -120,6 +132,7 @@ use tracing_subscriber::{
 
 use crossterm::tty::IsTty;
 
+pub mod otel;
 pub mod span_latency_prom;
 
 /// Alias for a type-erased error type.
@@ -137,6 +150,8 @@ pub struct TelemetryConfig {
     pub service_name: String,
 
     pub enable_jaeger: bool,
+    /// The OTLP collector to export the spans to over gRPC, if any
+    pub otlp_endpoint: Option<String>,
     /// Enables Tokio Console debugging on port 6669
     pub tokio_console: bool,
     /// Output JSON logs.
@@ -165,6 +180,18 @@ pub struct TelemetryGuards {
 
     #[cfg(feature = "chrome")]
     chrome_guard: Option<tracing_chrome::FlushGuard>,
+
+    /// Whether spans are exported by an OpenTelemetry tracer, to flush on drop.
+    otel_enabled: bool,
+}
+
+impl Drop for TelemetryGuards {
+    fn drop(&mut self) {
+        #[cfg(any(feature = "jaeger", feature = "otlp"))]
+        if self.otel_enabled {
+            opentelemetry::global::shutdown_tracer_provider();
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -182,6 +209,84 @@ impl FilterHandle {
             .with_current(|filter| filter.to_string())
             .map_err(Into::into)
     }
+
+    /// The level of the directive of the filter for `target` (e.g. `sui_core::authority`), if
+    /// any.
+    pub fn target_level(&self, target: &str) -> Result<Option<String>, BoxError> {
+        let filter = self.get()?;
+        Ok(split_directives(&filter)
+            .into_iter()
+            .rfind(|directive| directive_target(directive) == target)
+            .map(|directive| directive_level(directive).to_string()))
+    }
+
+    /// Filters the events of `target` at `level`, replacing the directive of the current filter
+    /// for that target if any, or removes that directive when `level` is `None`. The directives
+    /// of the other targets are kept.
+    pub fn set_target_level(&self, target: &str, level: Option<&str>) -> Result<(), BoxError> {
+        let filter = self.get()?;
+        self.update(with_target_level(&filter, target, level))
+    }
+}
+
+/// The directives of `filter`, split on the commas which are not within the span and field
+/// filters of a directive (e.g. `target[span{field=a,other=b}]=debug`).
+fn split_directives(filter: &str) -> Vec<&str> {
+    let mut directives = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, c) in filter.char_indices() {
+        match c {
+            '[' | '{' => depth += 1,
+            ']' | '}' => depth -= 1,
+            ',' if depth == 0 => {
+                directives.push(&filter[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    directives.push(&filter[start..]);
+    directives
+        .into_iter()
+        .map(str::trim)
+        .filter(|directive| !directive.is_empty())
+        .collect()
+}
+
+/// The target of `directive`, empty for the directives of the default level.
+fn directive_target(directive: &str) -> &str {
+    match directive.find(|c| c == '[' || c == '=') {
+        Some(end) => &directive[..end],
+        None if LevelFilter::from_str(directive).is_ok() => "",
+        None => directive,
+    }
+}
+
+/// The level of `directive`, a directive without one enabling all the levels of its target.
+fn directive_level(directive: &str) -> &str {
+    match directive.rsplit_once('=') {
+        Some((_, level)) if !level.ends_with(']') && !level.ends_with('}') => level,
+        _ if directive_target(directive).is_empty() => directive,
+        _ => "trace",
+    }
+}
+
+/// `filter` with the directive for `target` replaced as [`FilterHandle::set_target_level`] does.
+fn with_target_level(filter: &str, target: &str, level: Option<&str>) -> String {
+    let mut directives: Vec<_> = split_directives(filter)
+        .into_iter()
+        .filter(|directive| directive_target(directive) != target)
+        .map(str::to_string)
+        .collect();
+    if let Some(level) = level {
+        directives.push(if target.is_empty() {
+            level.to_string()
+        } else {
+            format!("{target}={level}")
+        });
+    }
+    directives.join(",")
 }
 
 fn get_output(log_file: Option<String>) -> (NonBlocking, WorkerGuard) {
@@ -238,6 +343,7 @@ impl TelemetryConfig {
         Self {
             service_name: service_name.to_owned(),
             enable_jaeger: false,
+            otlp_endpoint: None,
             tokio_console: false,
             json_log_output: false,
             chrome_trace_output: false,
@@ -265,6 +371,11 @@ impl TelemetryConfig {
         self
     }
 
+    pub fn with_otlp_endpoint(mut self, endpoint: &str) -> Self {
+        self.otlp_endpoint = Some(endpoint.to_owned());
+        self
+    }
+
     pub fn with_prom_registry(mut self, registry: &prometheus::Registry) -> Self {
         self.prom_registry = Some(registry.clone());
         self
@@ -279,6 +390,10 @@ impl TelemetryConfig {
             self.enable_jaeger = true
         }
 
+        if let Ok(endpoint) = env::var("OTEL_EXPORTER_OTLP_ENDPOINT") {
+            self.otlp_endpoint = Some(endpoint);
+        }
+
         if env::var("TOKIO_CHROME").is_ok() {
             self.chrome_trace_output = true;
         }
@@ -370,6 +485,35 @@ impl TelemetryConfig {
             layers.push(telemetry.with_filter(span_filter.clone()).boxed());
         }
 
+        #[cfg(feature = "otlp")]
+        if let Some(endpoint) = &config.otlp_endpoint {
+            use opentelemetry_otlp::WithExportConfig;
+
+            // Batching for better performance, as for Jaeger.
+            let tracer = opentelemetry_otlp::new_pipeline()
+                .tracing()
+                .with_exporter(
+                    opentelemetry_otlp::new_exporter()
+                        .tonic()
+                        .with_endpoint(endpoint),
+                )
+                .with_trace_config(opentelemetry::sdk::trace::config().with_resource(
+                    opentelemetry::sdk::Resource::new(vec![opentelemetry::KeyValue::new(
+                        "service.name",
+                        config.service_name.clone(),
+                    )]),
+                ))
+                .install_batch(opentelemetry::runtime::Tokio)
+                .expect("Could not create the OTLP tracer");
+
+            let telemetry = tracing_opentelemetry::layer().with_tracer(tracer);
+            opentelemetry::global::set_text_map_propagator(
+                opentelemetry::sdk::propagation::TraceContextPropagator::new(),
+            );
+
+            layers.push(telemetry.with_filter(span_filter.clone()).boxed());
+        }
+
         let (nb_output, worker_guard) = get_output(config.log_file.clone());
         if config.json_log_output {
             // See https://www.lpalmieri.com/posts/2020-09-27-zero-to-production-4-are-we-observable-yet/#5-7-tracing-bunyan-formatter
@@ -403,6 +547,7 @@ impl TelemetryConfig {
             worker_guard,
             #[cfg(feature = "chrome")]
             chrome_guard,
+            otel_enabled: config.enable_jaeger || config.otlp_endpoint.is_some(),
         };
 
         (guards, filter_handle)
@@ -438,6 +583,40 @@ mod tests {
     use std::time::Duration;
     use tracing::{debug, debug_span, info, trace_span, warn};
 
+    #[test]
+    fn test_target_levels() {
+        let filter = "info,sui_core::authority=warn,narwhal[commit{round=1,epoch=2}]=trace";
+        assert_eq!(
+            split_directives(filter),
+            vec![
+                "info",
+                "sui_core::authority=warn",
+                "narwhal[commit{round=1,epoch=2}]=trace"
+            ]
+        );
+        assert_eq!(
+            with_target_level(filter, "sui_core::authority", Some("debug")),
+            "info,narwhal[commit{round=1,epoch=2}]=trace,sui_core::authority=debug"
+        );
+        assert_eq!(
+            with_target_level(filter, "sui_network", Some("trace")),
+            format!("{filter},sui_network=trace")
+        );
+        assert_eq!(
+            with_target_level(filter, "sui_core::authority", None),
+            "info,narwhal[commit{round=1,epoch=2}]=trace"
+        );
+        assert_eq!(directive_level("sui_core::authority=warn"), "warn");
+        assert_eq!(directive_level("info"), "info");
+        assert_eq!(directive_level("sui_core"), "trace");
+        assert_eq!(directive_level("narwhal[commit{round=1}]"), "trace");
+        // The empty target is the default level.
+        assert_eq!(
+            with_target_level("warn,sui=debug", "", Some("info")),
+            "sui=debug,info"
+        );
+    }
+
     #[test]
     #[should_panic]
     fn test_telemetry_init() {
@@ -0,0 +1,106 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Grouping of spans into traces spanning several tasks and processes.
+//!
+//! Work handed over a channel or the network loses its parent span, so the spans of an operation
+//! are not connected as a single trace by default. Here an application-level trace id (e.g. one
+//! sent along with a request) is mapped to an OpenTelemetry trace: [`set_trace`] makes a span a
+//! child of the trace whatever its parent span in the process, and [`link_trace`] links a span
+//! to the other traces it is related to, e.g. a batch to the transactions it includes.
+//!
+//! The spans are given the root of the trace as a remote parent, which is never exported itself:
+//! Jaeger and Tempo group them in the trace all the same. Without the `jaeger` and the `otlp`
+//! features both functions do nothing.
+use tracing::Span;
+
+/// Makes `span` a child of the trace `trace_id`, which can be any string: the ids of up to
+/// 32 hex digits are the OpenTelemetry trace id, the others are hashed into one.
+pub fn set_trace(span: &Span, trace_id: &str) {
+    #[cfg(any(feature = "jaeger", feature = "otlp"))]
+    {
+        use opentelemetry::trace::TraceContextExt;
+        use opentelemetry::Context;
+        use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+        let context = Context::new().with_remote_span_context(span_context(trace_id));
+        span.set_parent(context);
+    }
+    #[cfg(not(any(feature = "jaeger", feature = "otlp")))]
+    let _ = (span, trace_id);
+}
+
+/// Links `span` to the trace `trace_id`, as mapped by [`set_trace`].
+pub fn link_trace(span: &Span, trace_id: &str) {
+    #[cfg(any(feature = "jaeger", feature = "otlp"))]
+    {
+        use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+        span.add_link(span_context(trace_id));
+    }
+    #[cfg(not(any(feature = "jaeger", feature = "otlp")))]
+    let _ = (span, trace_id);
+}
+
+/// The OpenTelemetry trace id of `trace_id`, never 0 since that id is invalid.
+#[cfg_attr(not(any(feature = "jaeger", feature = "otlp")), allow(dead_code))]
+fn otel_trace_id(trace_id: &str) -> u128 {
+    let id = if trace_id.len() <= 32 {
+        u128::from_str_radix(trace_id, 16).ok()
+    } else {
+        None
+    };
+    id.unwrap_or_else(|| {
+        let high = hash(trace_id, 0) as u128;
+        let low = hash(trace_id, 1) as u128;
+        (high << 64) | low
+    })
+    .max(1)
+}
+
+/// The id of the unexported root span of the trace, never 0 since that id is invalid.
+#[cfg_attr(not(any(feature = "jaeger", feature = "otlp")), allow(dead_code))]
+fn root_span_id(trace_id: &str) -> u64 {
+    hash(trace_id, 2).max(1)
+}
+
+#[cfg_attr(not(any(feature = "jaeger", feature = "otlp")), allow(dead_code))]
+fn hash(trace_id: &str, seed: u64) -> u64 {
+    use std::hash::{Hash, Hasher};
+
+    // The hasher is deterministic, for every process to map an id to the same trace.
+    #[allow(deprecated)]
+    let mut hasher = std::hash::SipHasher::new_with_keys(seed, 0);
+    trace_id.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(any(feature = "jaeger", feature = "otlp"))]
+fn span_context(trace_id: &str) -> opentelemetry::trace::SpanContext {
+    use opentelemetry::trace::{SpanContext, SpanId, TraceFlags, TraceId, TraceState};
+
+    SpanContext::new(
+        TraceId::from_u128(otel_trace_id(trace_id)),
+        SpanId::from_u64(root_span_id(trace_id)),
+        TraceFlags::SAMPLED,
+        true,
+        TraceState::default(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn map_trace_ids() {
+        assert_eq!(otel_trace_id("00000000000000ff"), 0xff);
+        assert_eq!(otel_trace_id("0"), 1);
+        // The other ids are hashed deterministically.
+        let id = otel_trace_id("not-hex");
+        assert_eq!(id, otel_trace_id("not-hex"));
+        assert_ne!(id, otel_trace_id("not-hex-either"));
+        assert_ne!(otel_trace_id(&"f".repeat(33)), 1);
+        assert_ne!(root_span_id("not-hex"), root_span_id("not-hex-either"));
+    }
+}
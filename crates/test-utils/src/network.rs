@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use std::num::NonZeroUsize;
+use std::time::Duration;
 
 use jsonrpsee::http_client::{HttpClient, HttpClientBuilder};
 use jsonrpsee::ws_client::WsClient;
@@ -84,6 +85,11 @@ impl TestCluster {
         let config = self.fullnode_config_builder().build().unwrap();
         start_fullnode_from_config(config).await
     }
+
+    /// End the current consensus epoch of all validators now.
+    pub fn force_epoch_change(&self) -> Result<(), anyhow::Error> {
+        self.swarm.force_epoch_change()
+    }
 }
 
 pub struct TestClusterBuilder {
@@ -91,6 +97,7 @@ pub struct TestClusterBuilder {
     num_validators: Option<usize>,
     fullnode_rpc_port: Option<u16>,
     enable_fullnode_events: bool,
+    epoch_duration_ms: Option<u64>,
 }
 
 impl TestClusterBuilder {
@@ -100,6 +107,7 @@ impl TestClusterBuilder {
             fullnode_rpc_port: None,
             num_validators: None,
             enable_fullnode_events: false,
+            epoch_duration_ms: None,
         }
     }
 
@@ -123,6 +131,11 @@ impl TestClusterBuilder {
         self
     }
 
+    pub fn with_epoch_duration_ms(mut self, epoch_duration_ms: u64) -> Self {
+        self.epoch_duration_ms = Some(epoch_duration_ms);
+        self
+    }
+
     pub async fn build(self) -> anyhow::Result<TestCluster> {
         let cluster = self.start_test_network_with_customized_ports().await?;
         Ok(cluster)
@@ -181,6 +194,10 @@ impl TestClusterBuilder {
             builder = builder.initial_accounts_config(genesis_config);
         }
 
+        if let Some(epoch_duration_ms) = self.epoch_duration_ms {
+            builder = builder.with_epoch_duration(Duration::from_millis(epoch_duration_ms));
+        }
+
         let mut swarm = builder.build();
         swarm.launch().await?;
 
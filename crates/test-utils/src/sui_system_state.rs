@@ -13,7 +13,7 @@ use sui_types::crypto::{
 use sui_types::id::UID;
 use sui_types::sui_system_state::SystemParameters;
 use sui_types::sui_system_state::{
-    StakingPool, SuiSystemState, Validator, ValidatorMetadata, ValidatorSet,
+    MoveOption, StakingPool, SuiSystemState, Validator, ValidatorMetadata, ValidatorSet,
 };
 use sui_types::SUI_SYSTEM_STATE_OBJECT_ID;
 
@@ -23,10 +23,12 @@ pub fn test_validatdor_metadata(
     net_address: Vec<u8>,
 ) -> ValidatorMetadata {
     let network_keypair: NetworkKeyPair = get_key_pair().1;
+    let worker_keypair: NetworkKeyPair = get_key_pair().1;
     ValidatorMetadata {
         sui_address,
         pubkey_bytes: pubkey_bytes.as_bytes().to_vec(),
         network_pubkey_bytes: network_keypair.public().as_bytes().to_vec(),
+        worker_pubkey_bytes: worker_keypair.public().as_bytes().to_vec(),
         proof_of_possession_bytes: vec![],
         name: to_bytes("zero_commission").unwrap(),
         net_address,
@@ -34,6 +36,11 @@ pub fn test_validatdor_metadata(
         next_epoch_delegation: 1,
         next_epoch_gas_price: 1,
         next_epoch_commission_rate: 0,
+        next_epoch_pubkey_bytes: MoveOption::none(),
+        next_epoch_proof_of_possession: MoveOption::none(),
+        next_epoch_network_pubkey_bytes: MoveOption::none(),
+        next_epoch_worker_pubkey_bytes: MoveOption::none(),
+        next_epoch_net_address: MoveOption::none(),
     }
 }
 
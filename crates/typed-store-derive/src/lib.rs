@@ -339,6 +339,13 @@ pub fn derive_dbmap_utils_general(input: TokenStream) -> TokenStream {
         .get(0)
         .expect("Expected at least one field")
         .clone();
+    // Stores only hold a database when they are created from a `DBMap`, which is always the case
+    // for the tables opened here.
+    let first_field_rocksdb = if simple_field_type_name_str == "Store" {
+        quote! { self.#first_field_name.rocksdb.as_ref().expect("Tables are backed by rocksdb") }
+    } else {
+        quote! { &self.#first_field_name.rocksdb }
+    };
 
     TokenStream::from(quote! {
 
@@ -400,12 +407,26 @@ pub fn derive_dbmap_utils_general(input: TokenStream) -> TokenStream {
             > #intermediate_db_map_struct_name #generics {
             /// Opens a set of tables in read-write mode
             /// If as_secondary_with_path is set, the DB is opened in read only mode with the path specified
+            /// If read_only is set, the DB is opened in read only mode, in which any write fails
             pub fn open_tables_impl(
                 path: std::path::PathBuf,
                 as_secondary_with_path: Option<std::path::PathBuf>,
+                read_only: bool,
                 global_db_options_override: Option<rocksdb::Options>,
                 tables_db_options_override: Option<typed_store::rocks::DBMapTableConfigMap>
             ) -> Self {
+                Self::try_open_tables_impl(path, as_secondary_with_path, read_only, global_db_options_override, tables_db_options_override)
+                    .expect("Cannot open DB.")
+            }
+
+            /// Same as `open_tables_impl`, but fails instead of panicking when the DB or one of its tables can't be opened
+            pub fn try_open_tables_impl(
+                path: std::path::PathBuf,
+                as_secondary_with_path: Option<std::path::PathBuf>,
+                read_only: bool,
+                global_db_options_override: Option<rocksdb::Options>,
+                tables_db_options_override: Option<typed_store::rocks::DBMapTableConfigMap>
+            ) -> Result<Self, typed_store::rocks::TypedStoreError> {
                 let path = &path;
                 let db = {
                     let opt_cfs = match tables_db_options_override {
@@ -424,23 +445,24 @@ pub fn derive_dbmap_utils_general(input: TokenStream) -> TokenStream {
                     let opt_cfs: Vec<_> = opt_cfs.iter().map(|q| (q.0.as_str(), &q.1.options)).collect();
                     let db = match as_secondary_with_path {
                         Some(p) => typed_store::rocks::open_cf_opts_secondary(path, Some(&p), global_db_options_override, &opt_cfs),
+                        None if read_only => typed_store::rocks::open_cf_opts_read_only(path, global_db_options_override, &opt_cfs),
                         None    => typed_store::rocks::open_cf_opts(path, global_db_options_override, &opt_cfs)
                     };
                     db
-                }.expect("Cannot open DB.");
+                }?;
                 let (
                         #(
                             #field_names
                         ),*
                 ) = (#(
-                        DBMap::#inner_types::reopen(&db, Some(stringify!(#field_names))).expect(&format!("Cannot open {} CF.", stringify!(#field_names))[..])
+                        DBMap::#inner_types::reopen(&db, Some(stringify!(#field_names)))?
                     ),*);
 
-                Self {
+                Ok(Self {
                     #(
                         #field_names,
                     )*
-                }
+                })
             }
         }
 
@@ -462,7 +484,7 @@ pub fn derive_dbmap_utils_general(input: TokenStream) -> TokenStream {
                 global_db_options_override: Option<rocksdb::Options>,
                 tables_db_options_override: Option<typed_store::rocks::DBMapTableConfigMap>
             ) -> Self {
-                let inner = #intermediate_db_map_struct_name::open_tables_impl(path, None, global_db_options_override, tables_db_options_override);
+                let inner = #intermediate_db_map_struct_name::open_tables_impl(path, None, false, global_db_options_override, tables_db_options_override);
                 Self {
                     #(
                         #field_names: #post_process_fn(inner.#field_names),
@@ -470,9 +492,27 @@ pub fn derive_dbmap_utils_general(input: TokenStream) -> TokenStream {
                 }
             }
 
+            /// Opens a set of existing tables in read only mode, in which any write to them fails
+            /// Unlike `get_read_only_handle`, this returns the struct itself, for its reads to be used as usual
+            /// The writes of other processes are not seen, so the tables should no longer be written when opened this way
+            /// Fails if the DB or one of the tables doesn't exist, as none are created in read only mode
+            #[allow(unused_parens)]
+            pub fn open_tables_read_only_mode(
+                path: std::path::PathBuf,
+                global_db_options_override: Option<rocksdb::Options>,
+                tables_db_options_override: Option<typed_store::rocks::DBMapTableConfigMap>
+            ) -> Result<Self, typed_store::rocks::TypedStoreError> {
+                let inner = #intermediate_db_map_struct_name::try_open_tables_impl(path, None, true, global_db_options_override, tables_db_options_override)?;
+                Ok(Self {
+                    #(
+                        #field_names: #post_process_fn(inner.#field_names),
+                    )*
+                })
+            }
+
             /// This gives info about memory usage and returns a tuple of total table memory usage and cache memory usage
             pub fn get_memory_usage(&self) -> Result<(u64, u64), typed_store::rocks::TypedStoreError> {
-                let stats = rocksdb::perf::get_memory_usage_stats(Some(&[&self.#first_field_name.rocksdb]), None)
+                let stats = rocksdb::perf::get_memory_usage_stats(Some(&[#first_field_rocksdb]), None)
                     .map_err(|e| typed_store::rocks::TypedStoreError::RocksDBError(e.to_string()))?;
                 Ok((stats.mem_table_total, stats.cache_total))
             }
@@ -516,12 +556,12 @@ pub fn derive_dbmap_utils_general(input: TokenStream) -> TokenStream {
                 global_db_options_override: Option<rocksdb::Options>,
             ) -> Self {
                 let inner = match with_secondary_path {
-                    Some(q) => #intermediate_db_map_struct_name::open_tables_impl(primary_path, Some(q), global_db_options_override, None),
+                    Some(q) => #intermediate_db_map_struct_name::open_tables_impl(primary_path, Some(q), false, global_db_options_override, None),
                     None => {
                         let p: std::path::PathBuf = tempfile::tempdir()
                         .expect("Failed to open temporary directory")
                         .into_path();
-                        #intermediate_db_map_struct_name::open_tables_impl(primary_path, Some(p), global_db_options_override, None)
+                        #intermediate_db_map_struct_name::open_tables_impl(primary_path, Some(p), false, global_db_options_override, None)
                     }
                 };
                 Self {
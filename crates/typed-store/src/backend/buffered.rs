@@ -0,0 +1,234 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+use super::{Direction, KeyValueStore, Seek, TableStats, WriteOp};
+use crate::rocks::TypedStoreError;
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+    sync::{Arc, Mutex},
+};
+use tracing::error;
+
+/// When the writes to a table are made durable.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum SyncPolicy {
+    /// Left to the storage engine and the operating system: a crash of the process loses
+    /// nothing, but a crash of the machine may lose the latest writes.
+    #[default]
+    Never,
+    /// Every write is durable once it returns.
+    Always,
+    /// The writes are made durable periodically, by [`BufferedStore::flush_and_sync`].
+    Interval,
+    /// The writes are made durable along the next write to a commit table.
+    OnCommit,
+}
+
+/// How the writes to a table are applied.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct WritePolicy {
+    /// Whether the writes are buffered in memory, to be applied with others in a single write.
+    pub coalesce: bool,
+    pub sync: SyncPolicy,
+}
+
+/// The writes buffered by a [`BufferedStore`].
+#[derive(Default)]
+struct Pending {
+    ops: Vec<WriteOp>,
+    bytes: usize,
+    /// The latest buffered value of every written key, `None` if it is deleted.
+    values: HashMap<(String, Vec<u8>), Option<Vec<u8>>>,
+}
+
+/// A storage engine coalescing the writes to another one, and making them durable according to
+/// the [`WritePolicy`] of their tables.
+///
+/// Crash safety:
+/// * Batches stay atomic, and are applied in order: a crash loses a suffix of the writes, never
+///   part of a batch nor an older write without the newer ones.
+/// * The buffered writes are lost if the process crashes before they are flushed, i.e. within
+///   the flush interval of the owner (see [`BufferedStore::flush`]) or the byte threshold.
+/// * A batch touching a non-coalesced table is applied directly, after the buffered writes. If it
+///   touches a table synced `Always`, or a commit table while some table is synced `OnCommit`,
+///   it is durable (along all the writes before it) once `write` returns. The writes to the
+///   commit tables are then never coalesced, whatever their policy.
+///
+/// Reads see the buffered writes. Iterations and range deletions flush the buffer first.
+pub struct BufferedStore {
+    inner: Arc<dyn KeyValueStore>,
+    policies: HashMap<String, WritePolicy>,
+    /// The tables whose writes make the `OnCommit` tables durable.
+    commit_tables: HashSet<String>,
+    /// The size of the buffered writes past which they are flushed.
+    flush_bytes: usize,
+    pending: Mutex<Pending>,
+}
+
+impl BufferedStore {
+    /// Buffers the writes to `inner` according to the `policies` of its tables; the tables
+    /// without a policy use the default one.
+    pub fn new(
+        inner: Arc<dyn KeyValueStore>,
+        policies: HashMap<String, WritePolicy>,
+        commit_tables: &[&str],
+        flush_bytes: usize,
+    ) -> Self {
+        Self {
+            inner,
+            policies,
+            commit_tables: commit_tables.iter().map(|t| t.to_string()).collect(),
+            flush_bytes,
+            pending: Mutex::new(Pending::default()),
+        }
+    }
+
+    fn policy(&self, table: &str) -> WritePolicy {
+        self.policies.get(table).copied().unwrap_or_default()
+    }
+
+    /// Whether any table is synced with the given policy.
+    fn any_synced(&self, policy: SyncPolicy) -> bool {
+        self.policies.values().any(|p| p.sync == policy)
+    }
+
+    /// Applies the buffered writes to the underlying store, in a single write.
+    pub fn flush(&self) -> Result<(), TypedStoreError> {
+        let mut pending = self.pending.lock().expect("Write buffer lock poisoned");
+        self.flush_locked(&mut pending)
+    }
+
+    fn flush_locked(&self, pending: &mut Pending) -> Result<(), TypedStoreError> {
+        if pending.ops.is_empty() {
+            return Ok(());
+        }
+        // The buffer is only cleared once written, so a failed flush can be retried.
+        self.inner.write(pending.ops.clone())?;
+        *pending = Pending::default();
+        Ok(())
+    }
+
+    /// Flushes the buffered writes, then makes all the writes durable if any table is synced
+    /// `Interval`. The owner of the store is expected to call it periodically.
+    pub fn flush_and_sync(&self) -> Result<(), TypedStoreError> {
+        self.flush()?;
+        if self.any_synced(SyncPolicy::Interval) {
+            self.inner.sync()?;
+        }
+        Ok(())
+    }
+}
+
+fn table_of(op: &WriteOp) -> &str {
+    match op {
+        WriteOp::Put { table, .. }
+        | WriteOp::Delete { table, .. }
+        | WriteOp::DeleteRange { table, .. } => table,
+    }
+}
+
+impl KeyValueStore for BufferedStore {
+    fn get(&self, table: &str, key: &[u8]) -> Result<Option<Vec<u8>>, TypedStoreError> {
+        {
+            let pending = self.pending.lock().expect("Write buffer lock poisoned");
+            if let Some(value) = pending.values.get(&(table.to_string(), key.to_vec())) {
+                return Ok(value.clone());
+            }
+        }
+        self.inner.get(table, key)
+    }
+
+    fn write(&self, batch: Vec<WriteOp>) -> Result<(), TypedStoreError> {
+        let mut pending = self.pending.lock().expect("Write buffer lock poisoned");
+        // The writes to the commit tables are never buffered while they sync other tables.
+        let commits = self.any_synced(SyncPolicy::OnCommit);
+        let coalesce = batch.iter().all(|op| {
+            let table = table_of(op);
+            !matches!(op, WriteOp::DeleteRange { .. })
+                && self.policy(table).coalesce
+                && !(commits && self.commit_tables.contains(table))
+        });
+        if coalesce {
+            for op in &batch {
+                match op {
+                    WriteOp::Put { table, key, value } => {
+                        pending.bytes += key.len() + value.len();
+                        pending
+                            .values
+                            .insert((table.clone(), key.clone()), Some(value.clone()));
+                    }
+                    WriteOp::Delete { table, key } => {
+                        pending.bytes += key.len();
+                        pending.values.insert((table.clone(), key.clone()), None);
+                    }
+                    WriteOp::DeleteRange { .. } => unreachable!("Range deletions are not buffered"),
+                }
+            }
+            pending.ops.extend(batch);
+            if pending.bytes >= self.flush_bytes {
+                self.flush_locked(&mut pending)?;
+            }
+            return Ok(());
+        }
+
+        // Keep the order of the writes: the buffered ones go first, along this batch.
+        let sync = batch.iter().any(|op| {
+            let table = table_of(op);
+            self.policy(table).sync == SyncPolicy::Always
+                || (commits && self.commit_tables.contains(table))
+        });
+        let mut ops = pending.ops.clone();
+        ops.extend(batch);
+        self.inner.write(ops)?;
+        *pending = Pending::default();
+        if sync {
+            self.inner.sync()?;
+        }
+        Ok(())
+    }
+
+    fn iter<'a>(
+        &'a self,
+        table: &str,
+        seek: Seek,
+        direction: Direction,
+    ) -> Result<Box<dyn Iterator<Item = (Vec<u8>, Vec<u8>)> + 'a>, TypedStoreError> {
+        self.flush()?;
+        self.inner.iter(table, seek, direction)
+    }
+
+    fn tables(&self) -> Vec<String> {
+        self.inner.tables()
+    }
+
+    fn stats(&self, table: &str) -> Result<TableStats, TypedStoreError> {
+        self.inner.stats(table)
+    }
+
+    fn sync(&self) -> Result<(), TypedStoreError> {
+        self.flush()?;
+        self.inner.sync()
+    }
+
+    fn checkpoint(&self, path: &Path) -> Result<(), TypedStoreError> {
+        self.flush()?;
+        self.inner.checkpoint(path)
+    }
+
+    fn compact(&self, table: &str) -> Result<(), TypedStoreError> {
+        self.flush()?;
+        self.inner.compact(table)
+    }
+
+    fn catch_up(&self) -> Result<(), TypedStoreError> {
+        self.inner.catch_up()
+    }
+}
+
+impl Drop for BufferedStore {
+    fn drop(&mut self) {
+        if let Err(e) = self.flush() {
+            error!("Failed to flush the buffered writes: {e}");
+        }
+    }
+}
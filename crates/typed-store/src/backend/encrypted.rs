@@ -0,0 +1,190 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+use super::{Direction, KeyValueStore, Seek, TableStats, WriteOp};
+use crate::rocks::TypedStoreError;
+use aes_gcm::{
+    aead::{Aead, AeadCore, KeyInit, OsRng, Payload},
+    Aes256Gcm, Nonce,
+};
+use std::{collections::BTreeMap, path::Path, sync::Arc};
+use tracing::error;
+
+/// The identifier of an encryption key.
+pub type KeyId = u64;
+
+const KEY_ID_SIZE: usize = 8;
+const NONCE_SIZE: usize = 12;
+
+/// The keys of an [`EncryptedStore`]. New values are encrypted with the active key, and every
+/// value records the id of its key: keys can be rotated by activating a new one, as long as the
+/// previous ones are kept to read the older values.
+#[derive(Clone)]
+pub struct Keyring {
+    ciphers: BTreeMap<KeyId, Aes256Gcm>,
+    active: KeyId,
+}
+
+impl Keyring {
+    /// Creates a keyring of 256-bit keys, encrypting new values with the key `active`.
+    pub fn new(keys: BTreeMap<KeyId, [u8; 32]>, active: KeyId) -> Result<Self, TypedStoreError> {
+        if !keys.contains_key(&active) {
+            return Err(TypedStoreError::EncryptionError(format!(
+                "the active key {active} is not in the keyring"
+            )));
+        }
+        let ciphers = keys
+            .into_iter()
+            .map(|(id, key)| {
+                let cipher = Aes256Gcm::new_from_slice(&key).expect("Keys are 256 bits long");
+                (id, cipher)
+            })
+            .collect();
+        Ok(Self { ciphers, active })
+    }
+
+    /// The id of the key new values are encrypted with.
+    pub fn active(&self) -> KeyId {
+        self.active
+    }
+}
+
+/// A storage engine encrypting the values (but not the keys) of another one with AES-256-GCM.
+/// Values are bound to their table and key, so they cannot be swapped around undetected. The
+/// underlying store must only hold values written through an `EncryptedStore`.
+pub struct EncryptedStore {
+    inner: Arc<dyn KeyValueStore>,
+    keyring: Keyring,
+}
+
+impl EncryptedStore {
+    pub fn new(inner: Arc<dyn KeyValueStore>, keyring: Keyring) -> Self {
+        Self { inner, keyring }
+    }
+
+    /// The data authenticated along a value: its table and key.
+    fn associated_data(table: &str, key: &[u8]) -> Vec<u8> {
+        let mut data = Vec::with_capacity(table.len() + 1 + key.len());
+        data.extend_from_slice(table.as_bytes());
+        data.push(0);
+        data.extend_from_slice(key);
+        data
+    }
+
+    /// Encrypts `value`, returning the id of the active key, the nonce and the ciphertext.
+    fn encrypt(&self, table: &str, key: &[u8], value: &[u8]) -> Result<Vec<u8>, TypedStoreError> {
+        let cipher = &self.keyring.ciphers[&self.keyring.active];
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let aad = Self::associated_data(table, key);
+        let ciphertext = cipher
+            .encrypt(
+                &nonce,
+                Payload {
+                    msg: value,
+                    aad: &aad,
+                },
+            )
+            .map_err(|e| TypedStoreError::EncryptionError(e.to_string()))?;
+
+        let mut data = Vec::with_capacity(KEY_ID_SIZE + NONCE_SIZE + ciphertext.len());
+        data.extend_from_slice(&self.keyring.active.to_be_bytes());
+        data.extend_from_slice(&nonce);
+        data.extend_from_slice(&ciphertext);
+        Ok(data)
+    }
+
+    fn decrypt(&self, table: &str, key: &[u8], data: &[u8]) -> Result<Vec<u8>, TypedStoreError> {
+        if data.len() < KEY_ID_SIZE + NONCE_SIZE {
+            return Err(TypedStoreError::EncryptionError(format!(
+                "value of {} bytes in {table} is too short to be encrypted",
+                data.len()
+            )));
+        }
+        let (id, rest) = data.split_at(KEY_ID_SIZE);
+        let (nonce, ciphertext) = rest.split_at(NONCE_SIZE);
+        let id = KeyId::from_be_bytes(id.try_into().expect("Key ids are 8 bytes long"));
+        let cipher = self.keyring.ciphers.get(&id).ok_or_else(|| {
+            TypedStoreError::EncryptionError(format!("unknown key {id} for a value in {table}"))
+        })?;
+        let aad = Self::associated_data(table, key);
+        cipher
+            .decrypt(
+                Nonce::from_slice(nonce),
+                Payload {
+                    msg: ciphertext,
+                    aad: &aad,
+                },
+            )
+            .map_err(|_| {
+                TypedStoreError::EncryptionError(format!("failed to decrypt a value in {table}"))
+            })
+    }
+}
+
+impl KeyValueStore for EncryptedStore {
+    fn get(&self, table: &str, key: &[u8]) -> Result<Option<Vec<u8>>, TypedStoreError> {
+        self.inner
+            .get(table, key)?
+            .map(|data| self.decrypt(table, key, &data))
+            .transpose()
+    }
+
+    fn write(&self, batch: Vec<WriteOp>) -> Result<(), TypedStoreError> {
+        let batch = batch
+            .into_iter()
+            .map(|op| match op {
+                WriteOp::Put { table, key, value } => {
+                    let value = self.encrypt(&table, &key, &value)?;
+                    Ok(WriteOp::Put { table, key, value })
+                }
+                op => Ok(op),
+            })
+            .collect::<Result<_, TypedStoreError>>()?;
+        self.inner.write(batch)
+    }
+
+    fn iter<'a>(
+        &'a self,
+        table: &str,
+        seek: Seek,
+        direction: Direction,
+    ) -> Result<Box<dyn Iterator<Item = (Vec<u8>, Vec<u8>)> + 'a>, TypedStoreError> {
+        let name = table.to_string();
+        let iter = self.inner.iter(table, seek, direction)?;
+        // As with values that fail to deserialize, the iteration stops at the first value that
+        // fails to decrypt.
+        Ok(Box::new(iter.map_while(
+            move |(key, data)| match self.decrypt(&name, &key, &data) {
+                Ok(value) => Some((key, value)),
+                Err(e) => {
+                    error!("Stopping the iteration over {name}: {e}");
+                    None
+                }
+            },
+        )))
+    }
+
+    fn tables(&self) -> Vec<String> {
+        self.inner.tables()
+    }
+
+    fn stats(&self, table: &str) -> Result<TableStats, TypedStoreError> {
+        self.inner.stats(table)
+    }
+
+    fn sync(&self) -> Result<(), TypedStoreError> {
+        self.inner.sync()
+    }
+
+    /// The checkpoint holds the encrypted values: it can only be read with the same keys.
+    fn checkpoint(&self, path: &Path) -> Result<(), TypedStoreError> {
+        self.inner.checkpoint(path)
+    }
+
+    fn compact(&self, table: &str) -> Result<(), TypedStoreError> {
+        self.inner.compact(table)
+    }
+
+    fn catch_up(&self) -> Result<(), TypedStoreError> {
+        self.inner.catch_up()
+    }
+}
@@ -0,0 +1,135 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+use super::{Direction, KeyValueStore, Seek, TableStats, WriteOp};
+use crate::rocks::TypedStoreError;
+use std::{path::Path, sync::Arc};
+
+/// The size of the prefix of every key of an [`EpochStore`].
+const PREFIX_SIZE: usize = 8;
+
+/// A view over the data of a single epoch in a store shared by all the epochs. Every key is
+/// prefixed with the epoch (big endian), so the data of an epoch is contiguous in each table and
+/// the past epochs can be deleted with a range deletion (see [`EpochStore::delete_epochs_before`]).
+pub struct EpochStore {
+    inner: Arc<dyn KeyValueStore>,
+    epoch: u64,
+}
+
+impl EpochStore {
+    pub fn new(inner: Arc<dyn KeyValueStore>, epoch: u64) -> Self {
+        Self { inner, epoch }
+    }
+
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+
+    /// Deletes the data of all the epochs before `epoch` from all the tables of `store`, a store
+    /// shared through `EpochStore`s.
+    pub fn delete_epochs_before(
+        store: &dyn KeyValueStore,
+        epoch: u64,
+    ) -> Result<(), TypedStoreError> {
+        let batch = store
+            .tables()
+            .into_iter()
+            .map(|table| WriteOp::DeleteRange {
+                table,
+                from: Vec::new(),
+                to: epoch.to_be_bytes().to_vec(),
+            })
+            .collect();
+        store.write(batch)
+    }
+
+    fn prefixed(&self, key: &[u8]) -> Vec<u8> {
+        let mut prefixed = Vec::with_capacity(PREFIX_SIZE + key.len());
+        prefixed.extend_from_slice(&self.epoch.to_be_bytes());
+        prefixed.extend_from_slice(key);
+        prefixed
+    }
+}
+
+impl KeyValueStore for EpochStore {
+    fn get(&self, table: &str, key: &[u8]) -> Result<Option<Vec<u8>>, TypedStoreError> {
+        self.inner.get(table, &self.prefixed(key))
+    }
+
+    fn write(&self, batch: Vec<WriteOp>) -> Result<(), TypedStoreError> {
+        let batch = batch
+            .into_iter()
+            .map(|op| match op {
+                WriteOp::Put { table, key, value } => WriteOp::Put {
+                    table,
+                    key: self.prefixed(&key),
+                    value,
+                },
+                WriteOp::Delete { table, key } => WriteOp::Delete {
+                    table,
+                    key: self.prefixed(&key),
+                },
+                WriteOp::DeleteRange { table, from, to } => WriteOp::DeleteRange {
+                    table,
+                    from: self.prefixed(&from),
+                    to: self.prefixed(&to),
+                },
+            })
+            .collect();
+        self.inner.write(batch)
+    }
+
+    fn iter<'a>(
+        &'a self,
+        table: &str,
+        seek: Seek,
+        direction: Direction,
+    ) -> Result<Box<dyn Iterator<Item = (Vec<u8>, Vec<u8>)> + 'a>, TypedStoreError> {
+        let prefix = self.epoch.to_be_bytes();
+        // The first key of the next epoch, if any.
+        let next = self.epoch.checked_add(1).map(|next| next.to_be_bytes());
+        let seek = match seek {
+            Seek::First => Seek::From(prefix.to_vec()),
+            Seek::Last => match next {
+                Some(next) => Seek::PriorTo(next.to_vec()),
+                None => Seek::Last,
+            },
+            Seek::From(key) => Seek::From(self.prefixed(&key)),
+            Seek::PriorTo(key) => Seek::PriorTo(self.prefixed(&key)),
+        };
+        let iter = self
+            .inner
+            .iter(table, seek, direction)?
+            // Seeking prior to the next epoch lands on its first key, if it is exactly the prefix.
+            .skip_while(move |(key, _)| next.map_or(false, |next| key[..] == next[..]))
+            .take_while(move |(key, _)| key.starts_with(&prefix))
+            .map(|(key, value)| (key[PREFIX_SIZE..].to_vec(), value));
+        Ok(Box::new(iter))
+    }
+
+    fn tables(&self) -> Vec<String> {
+        self.inner.tables()
+    }
+
+    /// The statistics cover the tables of all the epochs.
+    fn stats(&self, table: &str) -> Result<TableStats, TypedStoreError> {
+        self.inner.stats(table)
+    }
+
+    fn sync(&self) -> Result<(), TypedStoreError> {
+        self.inner.sync()
+    }
+
+    /// The checkpoint holds the data of all the epochs, with their prefixes.
+    fn checkpoint(&self, path: &Path) -> Result<(), TypedStoreError> {
+        self.inner.checkpoint(path)
+    }
+
+    /// The compaction covers the tables of all the epochs.
+    fn compact(&self, table: &str) -> Result<(), TypedStoreError> {
+        self.inner.compact(table)
+    }
+
+    fn catch_up(&self) -> Result<(), TypedStoreError> {
+        self.inner.catch_up()
+    }
+}
@@ -0,0 +1,178 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+use super::{Direction, KeyValueStore, Seek, TableStats, WriteOp};
+use crate::rocks::{open_cf, TypedStoreError};
+use rocksdb::WriteBatch;
+use std::{
+    collections::{BTreeMap, HashMap},
+    ops::Bound,
+    path::Path,
+    sync::RwLock,
+};
+
+type Table = BTreeMap<Vec<u8>, Vec<u8>>;
+
+/// A storage engine keeping all the tables in memory, for tests and deployments that do not
+/// need the data to outlive the process.
+pub struct InMemoryStore {
+    tables: RwLock<HashMap<String, Table>>,
+}
+
+impl InMemoryStore {
+    /// Creates an empty store with the given tables.
+    pub fn new(tables: &[&str]) -> Self {
+        Self {
+            tables: RwLock::new(
+                tables
+                    .iter()
+                    .map(|name| (name.to_string(), Table::new()))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+/// An iterator over a table of an [`InMemoryStore`]. It reads one pair at a time, under the lock of
+/// the store, so that it only copies the pairs it returns and the table can be written while it is
+/// iterated: the iteration then sees the writes past its position.
+struct TableIter<'a> {
+    tables: &'a RwLock<HashMap<String, Table>>,
+    table: String,
+    direction: Direction,
+    /// The bound of the next pair to return, or `None` once the iteration ended.
+    next: Option<Bound<Vec<u8>>>,
+}
+
+impl Iterator for TableIter<'_> {
+    type Item = (Vec<u8>, Vec<u8>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let bound = self.next.take()?;
+        let tables = self.tables.read().expect("In-memory store lock poisoned");
+        let table = tables.get(&self.table)?;
+        let (key, value) = match self.direction {
+            Direction::Forward => table.range::<Vec<u8>, _>((bound, Bound::Unbounded)).next(),
+            Direction::Reverse => table
+                .range::<Vec<u8>, _>((Bound::Unbounded, bound))
+                .next_back(),
+        }?;
+        self.next = Some(Bound::Excluded(key.clone()));
+        Some((key.clone(), value.clone()))
+    }
+}
+
+impl KeyValueStore for InMemoryStore {
+    fn get(&self, table: &str, key: &[u8]) -> Result<Option<Vec<u8>>, TypedStoreError> {
+        let tables = self.tables.read().expect("In-memory store lock poisoned");
+        let table = tables
+            .get(table)
+            .ok_or_else(|| TypedStoreError::UnregisteredColumn(table.to_string()))?;
+        Ok(table.get(key).cloned())
+    }
+
+    fn write(&self, batch: Vec<WriteOp>) -> Result<(), TypedStoreError> {
+        let mut tables = self.tables.write().expect("In-memory store lock poisoned");
+        // Check all the tables first, so the batch is applied entirely or not at all.
+        for op in &batch {
+            let (WriteOp::Put { table, .. }
+            | WriteOp::Delete { table, .. }
+            | WriteOp::DeleteRange { table, .. }) = op;
+            if !tables.contains_key(table) {
+                return Err(TypedStoreError::UnregisteredColumn(table.clone()));
+            }
+        }
+        for op in batch {
+            match op {
+                WriteOp::Put { table, key, value } => {
+                    tables.get_mut(&table).unwrap().insert(key, value);
+                }
+                WriteOp::Delete { table, key } => {
+                    tables.get_mut(&table).unwrap().remove(&key);
+                }
+                WriteOp::DeleteRange { table, from, to } => {
+                    if from < to {
+                        let table = tables.get_mut(&table).unwrap();
+                        let keys: Vec<_> = table.range(from..to).map(|(k, _)| k.clone()).collect();
+                        for key in keys {
+                            table.remove(&key);
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn iter<'a>(
+        &'a self,
+        table: &str,
+        seek: Seek,
+        direction: Direction,
+    ) -> Result<Box<dyn Iterator<Item = (Vec<u8>, Vec<u8>)> + 'a>, TypedStoreError> {
+        let tables = self.tables.read().expect("In-memory store lock poisoned");
+        let entries = tables
+            .get(table)
+            .ok_or_else(|| TypedStoreError::UnregisteredColumn(table.to_string()))?;
+
+        // The key the iteration starts from, if any.
+        let start = match seek {
+            Seek::First => entries.keys().next(),
+            Seek::Last => entries.keys().next_back(),
+            Seek::From(key) => entries.range(key..).next().map(|(k, _)| k),
+            Seek::PriorTo(key) => entries.range(..=key).next_back().map(|(k, _)| k),
+        };
+        Ok(Box::new(TableIter {
+            tables: &self.tables,
+            table: table.to_string(),
+            direction,
+            next: start.map(|start| Bound::Included(start.clone())),
+        }))
+    }
+
+    fn tables(&self) -> Vec<String> {
+        let tables = self.tables.read().expect("In-memory store lock poisoned");
+        tables.keys().cloned().collect()
+    }
+
+    /// The size of a table is the total size of its keys and values.
+    fn stats(&self, table: &str) -> Result<TableStats, TypedStoreError> {
+        let tables = self.tables.read().expect("In-memory store lock poisoned");
+        let table = tables
+            .get(table)
+            .ok_or_else(|| TypedStoreError::UnregisteredColumn(table.to_string()))?;
+        Ok(TableStats {
+            size: table.iter().map(|(k, v)| (k.len() + v.len()) as u64).sum(),
+            estimated_keys: table.len() as u64,
+            ..TableStats::default()
+        })
+    }
+
+    /// Nothing is durable in memory.
+    fn sync(&self) -> Result<(), TypedStoreError> {
+        Ok(())
+    }
+
+    fn checkpoint(&self, path: &Path) -> Result<(), TypedStoreError> {
+        if path.exists() {
+            return Err(TypedStoreError::RocksDBError(format!(
+                "Checkpoint directory {} already exists",
+                path.display()
+            )));
+        }
+        // Holding the lock for the whole copy keeps it consistent across the tables.
+        let tables = self.tables.read().expect("In-memory store lock poisoned");
+        let names: Vec<_> = tables.keys().map(|name| name.as_str()).collect();
+        let rocksdb = open_cf(path, None, &names)?;
+        let mut batch = WriteBatch::default();
+        for (name, table) in tables.iter() {
+            let cf = rocksdb
+                .cf_handle(name)
+                .ok_or_else(|| TypedStoreError::UnregisteredColumn(name.clone()))?;
+            for (key, value) in table {
+                batch.put_cf(&cf, key, value);
+            }
+        }
+        rocksdb.write(batch)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,397 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A storage engine agnostic version of the typed maps. The engine only has to implement the raw
+//! (bytes) operations of [`KeyValueStore`]; [`StoreMap`] then offers typed maps on top of it,
+//! with the same serialization, batching and iteration semantics as [`crate::rocks::DBMap`].
+mod buffered;
+mod encrypted;
+mod epoch;
+mod memory;
+mod rocks;
+
+use crate::{
+    rocks::{be_fix_int_ser, TypedStoreError},
+    traits::Map,
+};
+use bincode::Options;
+use serde::{de::DeserializeOwned, Serialize};
+use std::{borrow::Borrow, marker::PhantomData, path::Path, sync::Arc};
+
+pub use buffered::{BufferedStore, SyncPolicy, WritePolicy};
+pub use encrypted::{EncryptedStore, KeyId, Keyring};
+pub use epoch::EpochStore;
+pub use memory::InMemoryStore;
+pub use rocks::RocksDBStore;
+
+#[cfg(test)]
+mod tests;
+
+/// The position at which an iteration over a table starts.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Seek {
+    /// The first key of the table.
+    First,
+    /// The last key of the table.
+    Last,
+    /// The given key, or the first one greater than it.
+    From(Vec<u8>),
+    /// The given key, or the last one smaller than it.
+    PriorTo(Vec<u8>),
+}
+
+/// The order in which an iteration visits the keys.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Direction {
+    Forward,
+    Reverse,
+}
+
+/// A single operation of a write batch.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum WriteOp {
+    Put {
+        table: String,
+        key: Vec<u8>,
+        value: Vec<u8>,
+    },
+    Delete {
+        table: String,
+        key: Vec<u8>,
+    },
+    /// Deletes the keys between `from` (inclusive) and `to` (exclusive).
+    DeleteRange {
+        table: String,
+        from: Vec<u8>,
+        to: Vec<u8>,
+    },
+}
+
+/// Statistics on a table, as estimated by its storage engine. Engines report 0 for the ones they
+/// do not track.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct TableStats {
+    /// The size of the table's data, in bytes. RocksDB only counts the data files, not the
+    /// write buffers.
+    pub size: u64,
+    /// The estimated number of keys of the table.
+    pub estimated_keys: u64,
+    /// The estimated number of bytes compaction has to rewrite to bring the table back in shape.
+    pub pending_compaction_bytes: u64,
+    /// Whether the writes to the table are stopped, until compactions or flushes catch up.
+    pub write_stopped: bool,
+    /// The rate, in bytes per second, the writes to the table are slowed down to, if any.
+    pub delayed_write_rate: u64,
+}
+
+/// The raw operations a storage engine has to support. Data is organised in named tables of
+/// (key, value) pairs, where keys are ordered lexicographically.
+pub trait KeyValueStore: Send + Sync {
+    /// Returns the value of `key` in `table`, if any.
+    fn get(&self, table: &str, key: &[u8]) -> Result<Option<Vec<u8>>, TypedStoreError>;
+
+    /// Applies all the operations of the batch atomically.
+    fn write(&self, batch: Vec<WriteOp>) -> Result<(), TypedStoreError>;
+
+    /// Iterates over the pairs of `table`, starting at `seek` and in the given direction.
+    fn iter<'a>(
+        &'a self,
+        table: &str,
+        seek: Seek,
+        direction: Direction,
+    ) -> Result<Box<dyn Iterator<Item = (Vec<u8>, Vec<u8>)> + 'a>, TypedStoreError>;
+
+    /// Returns the names of the tables of the store.
+    fn tables(&self) -> Vec<String>;
+
+    /// Returns the statistics of `table`.
+    fn stats(&self, table: &str) -> Result<TableStats, TypedStoreError>;
+
+    /// Makes all the writes done so far durable, e.g. by syncing the write-ahead log to disk.
+    fn sync(&self) -> Result<(), TypedStoreError>;
+
+    /// Writes a consistent copy of all the tables, as a RocksDB database, to `path` (which must
+    /// not exist yet).
+    fn checkpoint(&self, path: &Path) -> Result<(), TypedStoreError>;
+
+    /// Compacts the whole of `table`, reclaiming the space of its deleted and overwritten
+    /// entries. Stores without compactions have nothing to do.
+    fn compact(&self, _table: &str) -> Result<(), TypedStoreError> {
+        Ok(())
+    }
+
+    /// Catches up with the latest writes of the process owning the data, for a read-only store
+    /// open alongside it (see [`RocksDBStore::open_secondary`]). Other stores are always up to
+    /// date.
+    fn catch_up(&self) -> Result<(), TypedStoreError> {
+        Ok(())
+    }
+}
+
+/// A typed map stored in a table of a [`KeyValueStore`].
+pub struct StoreMap<K, V> {
+    store: Arc<dyn KeyValueStore>,
+    table: String,
+    _phantom: PhantomData<fn(K) -> V>,
+}
+
+impl<K, V> Clone for StoreMap<K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            store: self.store.clone(),
+            table: self.table.clone(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<K, V> StoreMap<K, V> {
+    /// Opens the map stored in `table`, which must be one of the tables of the store.
+    pub fn new(store: &Arc<dyn KeyValueStore>, table: &str) -> Result<Self, TypedStoreError> {
+        if !store.tables().iter().any(|name| name == table) {
+            return Err(TypedStoreError::UnregisteredColumn(table.to_string()));
+        }
+        Ok(Self {
+            store: store.clone(),
+            table: table.to_string(),
+            _phantom: PhantomData,
+        })
+    }
+
+    /// Creates a batch of writes, to be applied atomically to the maps of this map's store.
+    pub fn batch(&self) -> StoreBatch {
+        StoreBatch {
+            store: self.store.clone(),
+            ops: Vec::new(),
+        }
+    }
+}
+
+/// An atomic batch of writes to the maps of a store.
+pub struct StoreBatch {
+    store: Arc<dyn KeyValueStore>,
+    ops: Vec<WriteOp>,
+}
+
+impl StoreBatch {
+    fn check_store<K, V>(&self, map: &StoreMap<K, V>) -> Result<(), TypedStoreError> {
+        // Only compare the data pointers, vtables may be duplicated across codegen units.
+        if Arc::as_ptr(&self.store) as *const u8 != Arc::as_ptr(&map.store) as *const u8 {
+            return Err(TypedStoreError::CrossDBBatch);
+        }
+        Ok(())
+    }
+
+    /// Deletes a set of keys given as an iterator
+    pub fn delete_batch<J: Borrow<K>, K: Serialize, V>(
+        mut self,
+        map: &StoreMap<K, V>,
+        purged_vals: impl IntoIterator<Item = J>,
+    ) -> Result<Self, TypedStoreError> {
+        self.check_store(map)?;
+        for key in purged_vals {
+            self.ops.push(WriteOp::Delete {
+                table: map.table.clone(),
+                key: be_fix_int_ser(key.borrow())?,
+            });
+        }
+        Ok(self)
+    }
+
+    /// Deletes a range of keys between `from` (inclusive) and `to` (non-inclusive)
+    pub fn delete_range<K: Serialize, V>(
+        mut self,
+        map: &StoreMap<K, V>,
+        from: &K,
+        to: &K,
+    ) -> Result<Self, TypedStoreError> {
+        self.check_store(map)?;
+        self.ops.push(WriteOp::DeleteRange {
+            table: map.table.clone(),
+            from: be_fix_int_ser(from)?,
+            to: be_fix_int_ser(to)?,
+        });
+        Ok(self)
+    }
+
+    /// inserts a range of (key, value) pairs given as an iterator
+    pub fn insert_batch<J: Borrow<K>, K: Serialize, U: Borrow<V>, V: Serialize>(
+        mut self,
+        map: &StoreMap<K, V>,
+        new_vals: impl IntoIterator<Item = (J, U)>,
+    ) -> Result<Self, TypedStoreError> {
+        self.check_store(map)?;
+        for (key, value) in new_vals {
+            self.ops.push(WriteOp::Put {
+                table: map.table.clone(),
+                key: be_fix_int_ser(key.borrow())?,
+                value: bincode::serialize(value.borrow())?,
+            });
+        }
+        Ok(self)
+    }
+
+    /// Consume the batch and write its operations to the store
+    pub fn write(self) -> Result<(), TypedStoreError> {
+        self.store.write(self.ops)
+    }
+}
+
+/// An iterator over the pairs of a [`StoreMap`]. As with [`crate::rocks::DBMap`] iterators, it
+/// can be repositioned before being consumed.
+pub struct StoreMapIter<'a, K, V> {
+    map: &'a StoreMap<K, V>,
+    seek: Seek,
+    direction: Direction,
+    inner: Option<Box<dyn Iterator<Item = (Vec<u8>, Vec<u8>)> + 'a>>,
+}
+
+impl<'a, K: Serialize, V> StoreMapIter<'a, K, V> {
+    /// Skips all the elements that are smaller than the given key,
+    /// and either lands on the key or the first one greater than
+    /// the key.
+    pub fn skip_to(mut self, key: &K) -> Result<Self, TypedStoreError> {
+        self.seek = Seek::From(be_fix_int_ser(key)?);
+        Ok(self)
+    }
+
+    /// Moves the iterator the element given or
+    /// the one prior to it if it does not exist. If there is
+    /// no element prior to it, it returns an empty iterator.
+    pub fn skip_prior_to(mut self, key: &K) -> Result<Self, TypedStoreError> {
+        self.seek = Seek::PriorTo(be_fix_int_ser(key)?);
+        Ok(self)
+    }
+
+    /// Seeks to the last key of the map.
+    pub fn skip_to_last(mut self) -> Self {
+        self.seek = Seek::Last;
+        self
+    }
+
+    /// Reverses the direction of the iteration: every call to `next` will give the previous
+    /// element.
+    pub fn reverse(mut self) -> Self {
+        self.direction = Direction::Reverse;
+        self
+    }
+}
+
+impl<'a, K: DeserializeOwned, V: DeserializeOwned> Iterator for StoreMapIter<'a, K, V> {
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.inner.is_none() {
+            let inner = self
+                .map
+                .store
+                .iter(&self.map.table, self.seek.clone(), self.direction)
+                .expect("Map-keying table should have been checked at map creation");
+            self.inner = Some(inner);
+        }
+        let (raw_key, raw_value) = self.inner.as_mut()?.next()?;
+        let key = bincode::DefaultOptions::new()
+            .with_big_endian()
+            .with_fixint_encoding()
+            .deserialize(&raw_key)
+            .ok();
+        let value = bincode::deserialize(&raw_value).ok();
+        key.and_then(|k| value.map(|v| (k, v)))
+    }
+}
+
+impl<'a, K, V> Map<'a, K, V> for StoreMap<K, V>
+where
+    K: Serialize + DeserializeOwned + 'a,
+    V: Serialize + DeserializeOwned + 'a,
+{
+    type Error = TypedStoreError;
+    type Iterator = StoreMapIter<'a, K, V>;
+    type Keys = std::iter::Map<StoreMapIter<'a, K, V>, fn((K, V)) -> K>;
+    type Values = std::iter::Map<StoreMapIter<'a, K, V>, fn((K, V)) -> V>;
+
+    fn contains_key(&self, key: &K) -> Result<bool, TypedStoreError> {
+        Ok(self.get_raw_bytes(key)?.is_some())
+    }
+
+    fn get(&self, key: &K) -> Result<Option<V>, TypedStoreError> {
+        self.get_raw_bytes(key)?
+            .map(|data| bincode::deserialize(&data).map_err(|e| e.into()))
+            .transpose()
+    }
+
+    fn get_raw_bytes(&self, key: &K) -> Result<Option<Vec<u8>>, TypedStoreError> {
+        self.store.get(&self.table, &be_fix_int_ser(key)?)
+    }
+
+    fn insert(&self, key: &K, value: &V) -> Result<(), TypedStoreError> {
+        self.batch()
+            .insert_batch(self, std::iter::once((key, value)))?
+            .write()
+    }
+
+    fn remove(&self, key: &K) -> Result<(), TypedStoreError> {
+        self.batch()
+            .delete_batch(self, std::iter::once(key))?
+            .write()
+    }
+
+    fn clear(&self) -> Result<(), TypedStoreError> {
+        let ops = self
+            .store
+            .iter(&self.table, Seek::First, Direction::Forward)?
+            .map(|(key, _)| WriteOp::Delete {
+                table: self.table.clone(),
+                key,
+            })
+            .collect();
+        self.store.write(ops)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.store
+            .iter(&self.table, Seek::First, Direction::Forward)
+            .map_or(true, |mut iter| iter.next().is_none())
+    }
+
+    fn iter(&'a self) -> Self::Iterator {
+        StoreMapIter {
+            map: self,
+            seek: Seek::First,
+            direction: Direction::Forward,
+            inner: None,
+        }
+    }
+
+    fn keys(&'a self) -> Self::Keys {
+        let key: fn((K, V)) -> K = |(k, _)| k;
+        self.iter().map(key)
+    }
+
+    fn values(&'a self) -> Self::Values {
+        let value: fn((K, V)) -> V = |(_, v)| v;
+        self.iter().map(value)
+    }
+
+    fn multi_insert<J, U>(
+        &self,
+        key_val_pairs: impl IntoIterator<Item = (J, U)>,
+    ) -> Result<(), Self::Error>
+    where
+        J: Borrow<K>,
+        U: Borrow<V>,
+    {
+        self.batch().insert_batch(self, key_val_pairs)?.write()
+    }
+
+    fn multi_remove<J>(&self, keys: impl IntoIterator<Item = J>) -> Result<(), Self::Error>
+    where
+        J: Borrow<K>,
+    {
+        self.batch().delete_batch(self, keys)?.write()
+    }
+
+    fn try_catch_up_with_primary(&self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
@@ -0,0 +1,199 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+use super::{Direction, KeyValueStore, Seek, TableStats, WriteOp};
+use crate::rocks::{
+    default_db_options, open_cf, open_cf_opts, open_cf_opts_secondary, DBMap, TypedStoreError,
+};
+use rocksdb::{
+    checkpoint::Checkpoint, properties, AsColumnFamilyRef, DBWithThreadMode, MultiThreaded,
+    Options, WriteBatch,
+};
+use std::{ffi::CStr, path::Path, sync::Arc};
+
+/// The default storage engine, keeping every table in a column family of a RocksDB database.
+pub struct RocksDBStore {
+    rocksdb: Arc<DBWithThreadMode<MultiThreaded>>,
+    tables: Vec<String>,
+    /// Whether the database is open as a secondary instance, which cannot write.
+    read_only: bool,
+    // Untyped maps over the tables, only kept for the column family metrics they report.
+    _metrics: Vec<DBMap<Vec<u8>, Vec<u8>>>,
+}
+
+impl RocksDBStore {
+    /// Opens (or creates) the database at `path` with the given tables.
+    pub fn open<P: AsRef<Path>>(path: P, tables: &[&str]) -> Result<Self, TypedStoreError> {
+        Self::new(open_cf(path, None, tables)?, tables)
+    }
+
+    /// Opens (or creates) the database at `path` with the given options, and the given tables
+    /// with their own options.
+    pub fn open_with_options<P: AsRef<Path>>(
+        path: P,
+        db_options: Options,
+        tables: &[(&str, &Options)],
+    ) -> Result<Self, TypedStoreError> {
+        let names: Vec<_> = tables.iter().map(|(name, _)| *name).collect();
+        Self::new(open_cf_opts(path, Some(db_options), tables)?, &names)
+    }
+
+    /// Opens the database at `primary_path`, with the given tables, as a read-only secondary
+    /// instance: it can be opened while another process (e.g. a running node) owns the database,
+    /// and sees the writes of that process up to when it was opened or last caught up (see
+    /// [`KeyValueStore::catch_up`]). The secondary instance keeps its own logs in
+    /// `secondary_path`.
+    pub fn open_secondary<P: AsRef<Path>>(
+        primary_path: P,
+        secondary_path: P,
+        tables: &[&str],
+    ) -> Result<Self, TypedStoreError> {
+        let options = default_db_options().options;
+        let cf_options: Vec<_> = tables.iter().map(|table| (*table, &options)).collect();
+        let rocksdb = open_cf_opts_secondary(
+            primary_path,
+            Some(secondary_path),
+            Some(options.clone()),
+            &cf_options,
+        )?;
+        Ok(Self {
+            read_only: true,
+            ..Self::new(rocksdb, tables)?
+        })
+    }
+
+    /// Uses the given tables of an already open database.
+    pub fn new(
+        rocksdb: Arc<DBWithThreadMode<MultiThreaded>>,
+        tables: &[&str],
+    ) -> Result<Self, TypedStoreError> {
+        let metrics = tables
+            .iter()
+            .map(|table| DBMap::reopen(&rocksdb, Some(*table)))
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            rocksdb,
+            tables: tables.iter().map(|table| table.to_string()).collect(),
+            read_only: false,
+            _metrics: metrics,
+        })
+    }
+
+    pub fn rocksdb(&self) -> &Arc<DBWithThreadMode<MultiThreaded>> {
+        &self.rocksdb
+    }
+
+    fn cf(&self, table: &str) -> Result<Arc<rocksdb::BoundColumnFamily<'_>>, TypedStoreError> {
+        self.rocksdb
+            .cf_handle(table)
+            .ok_or_else(|| TypedStoreError::UnregisteredColumn(table.to_string()))
+    }
+
+    fn int_property(
+        &self,
+        cf: &impl AsColumnFamilyRef,
+        name: &'static CStr,
+    ) -> Result<u64, TypedStoreError> {
+        Ok(self
+            .rocksdb
+            .property_int_value_cf(cf, name)?
+            .unwrap_or_default())
+    }
+}
+
+impl KeyValueStore for RocksDBStore {
+    fn get(&self, table: &str, key: &[u8]) -> Result<Option<Vec<u8>>, TypedStoreError> {
+        let cf = self.cf(table)?;
+        Ok(self
+            .rocksdb
+            .get_pinned_cf(&cf, key)?
+            .map(|data| data.to_vec()))
+    }
+
+    fn write(&self, batch: Vec<WriteOp>) -> Result<(), TypedStoreError> {
+        if self.read_only {
+            return Err(TypedStoreError::ReadOnly);
+        }
+        let mut write_batch = WriteBatch::default();
+        for op in batch {
+            match op {
+                WriteOp::Put { table, key, value } => {
+                    write_batch.put_cf(&self.cf(&table)?, key, value)
+                }
+                WriteOp::Delete { table, key } => write_batch.delete_cf(&self.cf(&table)?, key),
+                WriteOp::DeleteRange { table, from, to } => {
+                    write_batch.delete_range_cf(&self.cf(&table)?, from, to)
+                }
+            }
+        }
+        self.rocksdb.write(write_batch)?;
+        Ok(())
+    }
+
+    fn iter<'a>(
+        &'a self,
+        table: &str,
+        seek: Seek,
+        direction: Direction,
+    ) -> Result<Box<dyn Iterator<Item = (Vec<u8>, Vec<u8>)> + 'a>, TypedStoreError> {
+        let mut db_iter = self.rocksdb.raw_iterator_cf(&self.cf(table)?);
+        match seek {
+            Seek::First => db_iter.seek_to_first(),
+            Seek::Last => db_iter.seek_to_last(),
+            Seek::From(key) => db_iter.seek(key),
+            Seek::PriorTo(key) => db_iter.seek_for_prev(key),
+        }
+        Ok(Box::new(std::iter::from_fn(move || {
+            let item = (db_iter.key()?.to_vec(), db_iter.value()?.to_vec());
+            match direction {
+                Direction::Forward => db_iter.next(),
+                Direction::Reverse => db_iter.prev(),
+            }
+            Some(item)
+        })))
+    }
+
+    fn tables(&self) -> Vec<String> {
+        self.tables.clone()
+    }
+
+    fn stats(&self, table: &str) -> Result<TableStats, TypedStoreError> {
+        let cf = self.cf(table)?;
+        Ok(TableStats {
+            size: self.int_property(&cf, properties::TOTAL_SST_FILES_SIZE)?,
+            estimated_keys: self.int_property(&cf, properties::ESTIMATE_NUM_KEYS)?,
+            pending_compaction_bytes: self
+                .int_property(&cf, properties::ESTIMATE_PENDING_COMPACTION_BYTES)?,
+            write_stopped: self.int_property(&cf, properties::IS_WRITE_STOPPED)? != 0,
+            delayed_write_rate: self.int_property(&cf, properties::ACTUAL_DELAYED_WRITE_RATE)?,
+        })
+    }
+
+    fn sync(&self) -> Result<(), TypedStoreError> {
+        // A secondary instance has no writes of its own to sync.
+        if !self.read_only {
+            self.rocksdb.flush_wal(true)?;
+        }
+        Ok(())
+    }
+
+    fn checkpoint(&self, path: &Path) -> Result<(), TypedStoreError> {
+        Checkpoint::new(&self.rocksdb)?.create_checkpoint(path)?;
+        Ok(())
+    }
+
+    fn compact(&self, table: &str) -> Result<(), TypedStoreError> {
+        if self.read_only {
+            return Err(TypedStoreError::ReadOnly);
+        }
+        self.rocksdb
+            .compact_range_cf(&self.cf(table)?, None::<&[u8]>, None::<&[u8]>);
+        Ok(())
+    }
+
+    fn catch_up(&self) -> Result<(), TypedStoreError> {
+        if self.read_only {
+            self.rocksdb.try_catch_up_with_primary()?;
+        }
+        Ok(())
+    }
+}
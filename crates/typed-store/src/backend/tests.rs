@@ -0,0 +1,565 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+use super::*;
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+const FIRST_TABLE: &str = "first";
+const SECOND_TABLE: &str = "second";
+
+fn keyring(keys: &[KeyId], active: KeyId) -> Keyring {
+    Keyring::new(
+        keys.iter().map(|id| (*id, [*id as u8; 32])).collect(),
+        active,
+    )
+    .unwrap()
+}
+
+fn stores() -> Vec<Arc<dyn KeyValueStore>> {
+    let path = tempfile::tempdir()
+        .expect("Failed to open temporary directory")
+        .into_path();
+    vec![
+        Arc::new(InMemoryStore::new(&[FIRST_TABLE, SECOND_TABLE])),
+        Arc::new(RocksDBStore::open(path, &[FIRST_TABLE, SECOND_TABLE]).unwrap()),
+        Arc::new(EncryptedStore::new(
+            Arc::new(InMemoryStore::new(&[FIRST_TABLE, SECOND_TABLE])),
+            keyring(&[0], 0),
+        )),
+        Arc::new(EpochStore::new(shared_store(), 1)),
+        Arc::new(BufferedStore::new(
+            Arc::new(InMemoryStore::new(&[FIRST_TABLE, SECOND_TABLE])),
+            [FIRST_TABLE, SECOND_TABLE]
+                .iter()
+                .map(|table| (table.to_string(), coalesced(SyncPolicy::Never)))
+                .collect(),
+            &[],
+            1 << 20,
+        )),
+    ]
+}
+
+fn coalesced(sync: SyncPolicy) -> WritePolicy {
+    WritePolicy {
+        coalesce: true,
+        sync,
+    }
+}
+
+/// An in-memory store counting its writes and syncs.
+struct CountingStore {
+    inner: InMemoryStore,
+    writes: AtomicUsize,
+    syncs: AtomicUsize,
+}
+
+impl CountingStore {
+    fn new() -> Self {
+        Self {
+            inner: InMemoryStore::new(&[FIRST_TABLE, SECOND_TABLE]),
+            writes: AtomicUsize::new(0),
+            syncs: AtomicUsize::new(0),
+        }
+    }
+}
+
+impl KeyValueStore for CountingStore {
+    fn get(&self, table: &str, key: &[u8]) -> Result<Option<Vec<u8>>, TypedStoreError> {
+        self.inner.get(table, key)
+    }
+
+    fn write(&self, batch: Vec<WriteOp>) -> Result<(), TypedStoreError> {
+        self.writes.fetch_add(1, Ordering::Relaxed);
+        self.inner.write(batch)
+    }
+
+    fn iter<'a>(
+        &'a self,
+        table: &str,
+        seek: Seek,
+        direction: Direction,
+    ) -> Result<Box<dyn Iterator<Item = (Vec<u8>, Vec<u8>)> + 'a>, TypedStoreError> {
+        self.inner.iter(table, seek, direction)
+    }
+
+    fn tables(&self) -> Vec<String> {
+        self.inner.tables()
+    }
+
+    fn stats(&self, table: &str) -> Result<TableStats, TypedStoreError> {
+        self.inner.stats(table)
+    }
+
+    fn sync(&self) -> Result<(), TypedStoreError> {
+        self.syncs.fetch_add(1, Ordering::Relaxed);
+        Ok(())
+    }
+
+    fn checkpoint(&self, path: &Path) -> Result<(), TypedStoreError> {
+        self.inner.checkpoint(path)
+    }
+}
+
+/// A store shared across epochs, already holding data of the epochs around epoch 1.
+fn shared_store() -> Arc<dyn KeyValueStore> {
+    let store: Arc<dyn KeyValueStore> = Arc::new(InMemoryStore::new(&[FIRST_TABLE, SECOND_TABLE]));
+    for epoch in [0u64, 2] {
+        let neighbour: Arc<dyn KeyValueStore> = Arc::new(EpochStore::new(store.clone(), epoch));
+        let map = StoreMap::<u32, u32>::new(&neighbour, FIRST_TABLE).unwrap();
+        map.multi_insert((0..20).map(|i| (i, i))).unwrap();
+    }
+    store
+}
+
+#[tokio::test]
+async fn test_insert_get_remove() {
+    for store in stores() {
+        let map = StoreMap::<u32, String>::new(&store, FIRST_TABLE).unwrap();
+        assert!(map.is_empty());
+
+        map.insert(&123, &"123".to_string()).unwrap();
+        assert!(map.contains_key(&123).unwrap());
+        assert_eq!(map.get(&123).unwrap(), Some("123".to_string()));
+        assert_eq!(map.get(&321).unwrap(), None);
+
+        map.remove(&123).unwrap();
+        assert!(!map.contains_key(&123).unwrap());
+        assert!(map.is_empty());
+    }
+}
+
+#[tokio::test]
+async fn test_unknown_table() {
+    for store in stores() {
+        assert!(matches!(
+            StoreMap::<u32, String>::new(&store, "unknown"),
+            Err(TypedStoreError::UnregisteredColumn(_))
+        ));
+    }
+}
+
+#[tokio::test]
+async fn test_iteration() {
+    for store in stores() {
+        let map = StoreMap::<u32, u32>::new(&store, FIRST_TABLE).unwrap();
+        map.multi_insert((0..10).map(|i| (i * 2, i))).unwrap();
+
+        let keys: Vec<_> = map.keys().collect();
+        assert_eq!(keys, (0..10).map(|i| i * 2).collect::<Vec<_>>());
+
+        let keys: Vec<_> = map.iter().skip_to(&5).unwrap().map(|(k, _)| k).collect();
+        assert_eq!(keys, vec![6, 8, 10, 12, 14, 16, 18]);
+
+        let keys: Vec<_> = map
+            .iter()
+            .skip_prior_to(&5)
+            .unwrap()
+            .map(|(k, _)| k)
+            .take(2)
+            .collect();
+        assert_eq!(keys, vec![4, 6]);
+        assert_eq!(map.iter().skip_prior_to(&0).unwrap().next(), Some((0, 0)));
+
+        let keys: Vec<_> = map
+            .iter()
+            .skip_to_last()
+            .reverse()
+            .map(|(k, _)| k)
+            .take(3)
+            .collect();
+        assert_eq!(keys, vec![18, 16, 14]);
+
+        assert_eq!(map.iter().skip_to(&19).unwrap().next(), None);
+        assert_eq!(map.values().sum::<u32>(), 45);
+
+        map.clear().unwrap();
+        assert!(map.is_empty());
+    }
+}
+
+#[tokio::test]
+async fn test_in_memory_iteration_while_writing() {
+    let store = InMemoryStore::new(&[FIRST_TABLE]);
+    let put = |key: u8| WriteOp::Put {
+        table: FIRST_TABLE.to_string(),
+        key: vec![key],
+        value: vec![key],
+    };
+    store.write(vec![put(1), put(3)]).unwrap();
+
+    let mut iter = store
+        .iter(FIRST_TABLE, Seek::First, Direction::Forward)
+        .unwrap();
+    assert_eq!(iter.next(), Some((vec![1], vec![1])));
+
+    // The iteration sees the writes past its position, and not the ones before it.
+    store
+        .write(vec![
+            put(0),
+            put(2),
+            WriteOp::Delete {
+                table: FIRST_TABLE.to_string(),
+                key: vec![3],
+            },
+        ])
+        .unwrap();
+    assert_eq!(iter.collect::<Vec<_>>(), vec![(vec![2], vec![2])]);
+}
+
+#[tokio::test]
+async fn test_atomic_batch() {
+    for store in stores() {
+        let first = StoreMap::<u32, String>::new(&store, FIRST_TABLE).unwrap();
+        let second = StoreMap::<String, u32>::new(&store, SECOND_TABLE).unwrap();
+        first.insert(&0, &"zero".to_string()).unwrap();
+
+        first
+            .batch()
+            .insert_batch(&first, (1..4).map(|i| (i, i.to_string())))
+            .unwrap()
+            .insert_batch(&second, [("one".to_string(), 1)])
+            .unwrap()
+            .delete_batch(&first, [0])
+            .unwrap()
+            .delete_range(&first, &2, &3)
+            .unwrap()
+            .write()
+            .unwrap();
+
+        assert_eq!(first.keys().collect::<Vec<_>>(), vec![1, 3]);
+        assert_eq!(second.get(&"one".to_string()).unwrap(), Some(1));
+
+        // batches cannot span several stores
+        let other = stores().remove(0);
+        let other_map = StoreMap::<u32, String>::new(&other, FIRST_TABLE).unwrap();
+        assert!(matches!(
+            first
+                .batch()
+                .insert_batch(&other_map, [(5, "5".to_string())]),
+            Err(TypedStoreError::CrossDBBatch)
+        ));
+    }
+}
+
+#[tokio::test]
+async fn test_stats() {
+    for store in stores() {
+        let map = StoreMap::<u32, String>::new(&store, FIRST_TABLE).unwrap();
+        map.multi_insert((0..10).map(|i| (i, i.to_string())))
+            .unwrap();
+
+        let stats = store.stats(FIRST_TABLE).unwrap();
+        assert!(!stats.write_stopped);
+        assert_eq!(stats.delayed_write_rate, 0);
+        assert!(matches!(
+            store.stats("unknown"),
+            Err(TypedStoreError::UnregisteredColumn(_))
+        ));
+    }
+
+    // The in-memory store has exact statistics.
+    let store: Arc<dyn KeyValueStore> = Arc::new(InMemoryStore::new(&[FIRST_TABLE]));
+    let map = StoreMap::<u32, u32>::new(&store, FIRST_TABLE).unwrap();
+    map.multi_insert((0..10).map(|i| (i, i))).unwrap();
+    assert_eq!(
+        store.stats(FIRST_TABLE).unwrap(),
+        TableStats {
+            size: 80,
+            estimated_keys: 10,
+            ..TableStats::default()
+        }
+    );
+}
+
+#[tokio::test]
+async fn test_checkpoint() {
+    // Checkpoints of encrypted stores hold the encrypted values, which cannot be read as is.
+    for store in stores().into_iter().take(2) {
+        let map = StoreMap::<u32, String>::new(&store, FIRST_TABLE).unwrap();
+        map.multi_insert((0..10).map(|i| (i, i.to_string())))
+            .unwrap();
+
+        let path = tempfile::tempdir()
+            .expect("Failed to open temporary directory")
+            .into_path()
+            .join("checkpoint");
+        store.checkpoint(&path).unwrap();
+        // Later writes are not part of the checkpoint.
+        map.insert(&10, &"10".to_string()).unwrap();
+
+        let copy: Arc<dyn KeyValueStore> =
+            Arc::new(RocksDBStore::open(&path, &[FIRST_TABLE, SECOND_TABLE]).unwrap());
+        let copied_map = StoreMap::<u32, String>::new(&copy, FIRST_TABLE).unwrap();
+        assert_eq!(
+            copied_map.keys().collect::<Vec<_>>(),
+            (0..10).collect::<Vec<_>>()
+        );
+        assert_eq!(copied_map.get(&3).unwrap(), Some("3".to_string()));
+
+        // The target of a checkpoint must not exist.
+        assert!(store.checkpoint(&path).is_err());
+    }
+}
+
+#[tokio::test]
+async fn test_compaction() {
+    for store in stores() {
+        let map = StoreMap::<u32, String>::new(&store, FIRST_TABLE).unwrap();
+        map.multi_insert((0..10).map(|i| (i, i.to_string())))
+            .unwrap();
+        map.multi_remove(0..5).unwrap();
+
+        // Compaction only drops the deleted entries.
+        store.compact(FIRST_TABLE).unwrap();
+        assert_eq!(map.keys().collect::<Vec<_>>(), (5..10).collect::<Vec<_>>());
+        assert_eq!(map.get(&7).unwrap(), Some("7".to_string()));
+    }
+}
+
+#[tokio::test]
+async fn test_secondary_store() {
+    let path = tempfile::tempdir()
+        .expect("Failed to open temporary directory")
+        .into_path();
+    let primary: Arc<dyn KeyValueStore> =
+        Arc::new(RocksDBStore::open(path.join("primary"), &[FIRST_TABLE, SECOND_TABLE]).unwrap());
+    let map = StoreMap::<u32, String>::new(&primary, FIRST_TABLE).unwrap();
+    map.insert(&1, &"1".to_string()).unwrap();
+
+    // The secondary instance opens while the primary one is open.
+    let secondary: Arc<dyn KeyValueStore> = Arc::new(
+        RocksDBStore::open_secondary(
+            path.join("primary"),
+            path.join("secondary"),
+            &[FIRST_TABLE, SECOND_TABLE],
+        )
+        .unwrap(),
+    );
+    let secondary_map = StoreMap::<u32, String>::new(&secondary, FIRST_TABLE).unwrap();
+    assert_eq!(secondary_map.get(&1).unwrap(), Some("1".to_string()));
+
+    // It only sees the later writes once caught up.
+    map.insert(&2, &"2".to_string()).unwrap();
+    secondary.catch_up().unwrap();
+    assert_eq!(secondary_map.get(&2).unwrap(), Some("2".to_string()));
+
+    assert_eq!(
+        secondary_map.insert(&3, &"3".to_string()),
+        Err(TypedStoreError::ReadOnly)
+    );
+    assert_eq!(map.get(&3).unwrap(), None);
+    assert_eq!(
+        secondary.compact(FIRST_TABLE),
+        Err(TypedStoreError::ReadOnly)
+    );
+}
+
+#[tokio::test]
+async fn test_encryption_key_rotation() {
+    let inner: Arc<dyn KeyValueStore> = Arc::new(InMemoryStore::new(&[FIRST_TABLE, SECOND_TABLE]));
+    let store: Arc<dyn KeyValueStore> =
+        Arc::new(EncryptedStore::new(inner.clone(), keyring(&[1], 1)));
+    let map = StoreMap::<u32, String>::new(&store, FIRST_TABLE).unwrap();
+    map.insert(&1, &"one".to_string()).unwrap();
+
+    // The values are not stored in the clear.
+    let raw = inner
+        .get(FIRST_TABLE, &be_fix_int_ser(&1u32).unwrap())
+        .unwrap()
+        .unwrap();
+    assert!(!raw.windows(3).any(|window| window == b"one"));
+
+    // After a rotation, the old values can still be read as long as their key is kept.
+    let rotated: Arc<dyn KeyValueStore> =
+        Arc::new(EncryptedStore::new(inner.clone(), keyring(&[1, 2], 2)));
+    let map = StoreMap::<u32, String>::new(&rotated, FIRST_TABLE).unwrap();
+    map.insert(&2, &"two".to_string()).unwrap();
+    assert_eq!(map.get(&1).unwrap(), Some("one".to_string()));
+    assert_eq!(map.values().collect::<Vec<_>>(), vec!["one", "two"]);
+
+    // Without the key of a value, it cannot be read.
+    let forgotten: Arc<dyn KeyValueStore> =
+        Arc::new(EncryptedStore::new(inner.clone(), keyring(&[2], 2)));
+    let map = StoreMap::<u32, String>::new(&forgotten, FIRST_TABLE).unwrap();
+    assert!(matches!(
+        map.get(&1),
+        Err(TypedStoreError::EncryptionError(_))
+    ));
+    assert_eq!(map.get(&2).unwrap(), Some("two".to_string()));
+
+    // Values moved to another key fail to decrypt.
+    inner
+        .write(vec![WriteOp::Put {
+            table: FIRST_TABLE.to_string(),
+            key: be_fix_int_ser(&3u32).unwrap(),
+            value: raw,
+        }])
+        .unwrap();
+    let map = StoreMap::<u32, String>::new(&store, FIRST_TABLE).unwrap();
+    assert!(map.get(&3).is_err());
+
+    assert!(Keyring::new(BTreeMap::new(), 0).is_err());
+}
+
+#[tokio::test]
+async fn test_epoch_store() {
+    let shared = shared_store();
+    let epoch: Arc<dyn KeyValueStore> = Arc::new(EpochStore::new(shared.clone(), 1));
+    let map = StoreMap::<u32, u32>::new(&epoch, FIRST_TABLE).unwrap();
+
+    // The data of the other epochs is not visible.
+    assert!(map.is_empty());
+    assert_eq!(map.get(&3).unwrap(), None);
+    map.insert(&3, &30).unwrap();
+    assert_eq!(map.keys().collect::<Vec<_>>(), vec![3]);
+    assert_eq!(map.iter().skip_to_last().next(), Some((3, 30)));
+
+    // Range deletions stay within the epoch.
+    map.clear().unwrap();
+    assert!(map.is_empty());
+    let next: Arc<dyn KeyValueStore> = Arc::new(EpochStore::new(shared.clone(), 2));
+    let next_map = StoreMap::<u32, u32>::new(&next, FIRST_TABLE).unwrap();
+    assert_eq!(next_map.keys().count(), 20);
+
+    // Deleting the past epochs keeps the current one.
+    map.insert(&3, &30).unwrap();
+    EpochStore::delete_epochs_before(shared.as_ref(), 1).unwrap();
+    let past: Arc<dyn KeyValueStore> = Arc::new(EpochStore::new(shared.clone(), 0));
+    let past_map = StoreMap::<u32, u32>::new(&past, FIRST_TABLE).unwrap();
+    assert!(past_map.is_empty());
+    assert_eq!(map.get(&3).unwrap(), Some(30));
+    assert_eq!(next_map.keys().count(), 20);
+}
+
+#[tokio::test]
+async fn test_write_coalescing() {
+    let inner = Arc::new(CountingStore::new());
+    let policies = HashMap::from([(FIRST_TABLE.to_string(), coalesced(SyncPolicy::Never))]);
+    let buffered = BufferedStore::new(inner.clone(), policies, &[], 64);
+    let store: Arc<dyn KeyValueStore> = Arc::new(buffered);
+    let map = StoreMap::<u32, u32>::new(&store, FIRST_TABLE).unwrap();
+    let inner_store: Arc<dyn KeyValueStore> = inner.clone();
+    let inner_map = StoreMap::<u32, u32>::new(&inner_store, FIRST_TABLE).unwrap();
+
+    // The writes are buffered, yet visible.
+    map.insert(&1, &1).unwrap();
+    map.insert(&2, &2).unwrap();
+    map.remove(&2).unwrap();
+    assert_eq!(map.get(&1).unwrap(), Some(1));
+    assert_eq!(map.get(&2).unwrap(), None);
+    assert_eq!(inner.writes.load(Ordering::Relaxed), 0);
+    assert_eq!(inner_map.get(&1).unwrap(), None);
+
+    // They are flushed in a single write past the byte threshold.
+    map.multi_insert((3..10).map(|i| (i, i))).unwrap();
+    assert_eq!(inner.writes.load(Ordering::Relaxed), 1);
+    assert_eq!(
+        inner_map.keys().collect::<Vec<_>>(),
+        (1..10).filter(|i| *i != 2).collect::<Vec<_>>()
+    );
+
+    // Writes to the other tables are applied directly, after the buffered ones.
+    map.insert(&10, &10).unwrap();
+    let second = StoreMap::<u32, u32>::new(&store, SECOND_TABLE).unwrap();
+    second.insert(&1, &1).unwrap();
+    assert_eq!(inner.writes.load(Ordering::Relaxed), 2);
+    assert_eq!(inner_map.get(&10).unwrap(), Some(10));
+
+    // A crash loses the buffered writes, and only them.
+    map.insert(&11, &11).unwrap();
+    std::mem::forget(store);
+    assert_eq!(inner_map.get(&10).unwrap(), Some(10));
+    assert_eq!(inner_map.get(&11).unwrap(), None);
+
+    // Dropping the store flushes the buffer.
+    let store: Arc<dyn KeyValueStore> = Arc::new(BufferedStore::new(
+        inner.clone(),
+        HashMap::from([(FIRST_TABLE.to_string(), coalesced(SyncPolicy::Never))]),
+        &[],
+        64,
+    ));
+    StoreMap::<u32, u32>::new(&store, FIRST_TABLE)
+        .unwrap()
+        .insert(&12, &12)
+        .unwrap();
+    drop(store);
+    assert_eq!(inner_map.get(&12).unwrap(), Some(12));
+}
+
+#[tokio::test]
+async fn test_sync_policies() {
+    let inner = Arc::new(CountingStore::new());
+    let policies = HashMap::from([
+        (
+            FIRST_TABLE.to_string(),
+            WritePolicy {
+                coalesce: false,
+                sync: SyncPolicy::Always,
+            },
+        ),
+        (SECOND_TABLE.to_string(), coalesced(SyncPolicy::OnCommit)),
+    ]);
+    let buffered = Arc::new(BufferedStore::new(
+        inner.clone(),
+        policies,
+        &[FIRST_TABLE],
+        1 << 20,
+    ));
+    let store: Arc<dyn KeyValueStore> = buffered.clone();
+    let first = StoreMap::<u32, u32>::new(&store, FIRST_TABLE).unwrap();
+    let second = StoreMap::<u32, u32>::new(&store, SECOND_TABLE).unwrap();
+
+    // The writes synced on commit are buffered until the commit, which is synced.
+    second.insert(&1, &1).unwrap();
+    assert_eq!(inner.writes.load(Ordering::Relaxed), 0);
+    assert_eq!(inner.syncs.load(Ordering::Relaxed), 0);
+    first.insert(&1, &1).unwrap();
+    assert_eq!(inner.writes.load(Ordering::Relaxed), 1);
+    assert_eq!(inner.syncs.load(Ordering::Relaxed), 1);
+    assert!(inner
+        .get(SECOND_TABLE, &be_fix_int_ser(&1u32).unwrap())
+        .unwrap()
+        .is_some());
+
+    // Nothing is synced periodically without an interval policy.
+    second.insert(&2, &2).unwrap();
+    buffered.flush_and_sync().unwrap();
+    assert_eq!(inner.writes.load(Ordering::Relaxed), 2);
+    assert_eq!(inner.syncs.load(Ordering::Relaxed), 1);
+
+    let interval = BufferedStore::new(
+        inner.clone(),
+        HashMap::from([(FIRST_TABLE.to_string(), coalesced(SyncPolicy::Interval))]),
+        &[],
+        1 << 20,
+    );
+    interval.flush_and_sync().unwrap();
+    assert_eq!(inner.syncs.load(Ordering::Relaxed), 2);
+}
+
+#[tokio::test]
+async fn test_coalesced_commit_is_synced() {
+    let inner = Arc::new(CountingStore::new());
+    // The commit table itself asks for its writes to be coalesced.
+    let policies = HashMap::from([
+        (FIRST_TABLE.to_string(), coalesced(SyncPolicy::OnCommit)),
+        (SECOND_TABLE.to_string(), coalesced(SyncPolicy::OnCommit)),
+    ]);
+    let buffered = BufferedStore::new(inner.clone(), policies, &[FIRST_TABLE], 1 << 20);
+    let store: Arc<dyn KeyValueStore> = Arc::new(buffered);
+    let first = StoreMap::<u32, u32>::new(&store, FIRST_TABLE).unwrap();
+    let second = StoreMap::<u32, u32>::new(&store, SECOND_TABLE).unwrap();
+
+    second.insert(&1, &1).unwrap();
+    assert_eq!(inner.syncs.load(Ordering::Relaxed), 0);
+    // The commit is applied and synced right away, along the writes buffered before it.
+    first.insert(&1, &1).unwrap();
+    assert_eq!(inner.writes.load(Ordering::Relaxed), 1);
+    assert_eq!(inner.syncs.load(Ordering::Relaxed), 1);
+    assert!(inner
+        .get(SECOND_TABLE, &be_fix_int_ser(&1u32).unwrap())
+        .unwrap()
+        .is_some());
+}
@@ -24,6 +24,7 @@ use tokio::sync::{
 
 pub mod traits;
 pub use traits::Map;
+pub mod backend;
 pub mod metrics;
 pub mod rocks;
 pub use metrics::DBMetrics;
@@ -55,7 +56,8 @@ pub enum StoreCommand<Key, Value> {
 #[derive(Clone)]
 pub struct Store<K, V> {
     channel: Sender<StoreCommand<K, V>>,
-    pub rocksdb: Arc<rocksdb::DBWithThreadMode<MultiThreaded>>,
+    /// The database holding the store, unless it uses another storage backend.
+    pub rocksdb: Option<Arc<rocksdb::DBWithThreadMode<MultiThreaded>>>,
 }
 
 impl<Key, Value> Store<Key, Value>
@@ -64,8 +66,23 @@ where
     Value: Serialize + DeserializeOwned + Send + Clone + 'static,
 {
     pub fn new(keyed_db: rocks::DBMap<Key, Value>) -> Self {
+        let rocksdb = keyed_db.rocksdb.clone();
+        Self::spawn(keyed_db, Some(rocksdb))
+    }
+
+    /// Creates a store over a map of any storage backend.
+    pub fn from_map<M>(map: M) -> Self
+    where
+        M: for<'a> Map<'a, Key, Value, Error = StoreError> + Send + 'static,
+    {
+        Self::spawn(map, None)
+    }
+
+    fn spawn<M>(keyed_db: M, rocksdb: Option<Arc<rocksdb::DBWithThreadMode<MultiThreaded>>>) -> Self
+    where
+        M: for<'a> Map<'a, Key, Value, Error = StoreError> + Send + 'static,
+    {
         let mut obligations = HashMap::<Key, VecDeque<oneshot::Sender<_>>>::new();
-        let clone_db = keyed_db.rocksdb.clone();
         let (tx, mut rx) = channel(100);
         tokio::spawn(async move {
             while let Some(command) = rx.recv().await {
@@ -158,7 +175,7 @@ where
         });
         Self {
             channel: tx,
-            rocksdb: clone_db,
+            rocksdb,
         }
     }
 }
@@ -21,6 +21,10 @@ pub enum TypedStoreError {
     CrossDBBatch,
     #[error("Metric reporting thread failed with error")]
     MetricsReporting,
+    #[error("encryption error: {0}")]
+    EncryptionError(String),
+    #[error("the database is open read-only")]
+    ReadOnly,
 }
 
 #[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash, Debug, Error)]
@@ -975,6 +975,31 @@ pub fn open_cf_opts<P: AsRef<Path>>(
     Ok(rocksdb)
 }
 
+/// Opens an existing database in read-only mode, with options, and a number of its column families with individual options.
+/// Any write to the database fails. The writes of other processes to the database are not seen, so it should only be
+/// opened this way when nothing writes to it anymore.
+#[instrument(level="debug", skip_all, fields(path = ?path.as_ref()), err)]
+pub fn open_cf_opts_read_only<P: AsRef<Path>>(
+    path: P,
+    db_options: Option<rocksdb::Options>,
+    opt_cfs: &[(&str, &rocksdb::Options)],
+) -> Result<Arc<rocksdb::DBWithThreadMode<MultiThreaded>>, TypedStoreError> {
+    // Customize database options
+    let options = db_options.unwrap_or_else(|| default_db_options().options);
+
+    fdlimit::raise_fd_limit();
+    let rocksdb = rocksdb::DBWithThreadMode::<MultiThreaded>::open_cf_descriptors_read_only(
+        &options,
+        path.as_ref(),
+        opt_cfs
+            .iter()
+            .map(|(name, opts)| ColumnFamilyDescriptor::new(*name, (*opts).clone())),
+        // error_if_log_file_exist
+        false,
+    )?;
+    Ok(Arc::new(rocksdb))
+}
+
 /// Opens a database with options, and a number of column families with individual options that are created if they do not exist.
 pub fn open_cf_opts_secondary<P: AsRef<Path>>(
     primary_path: P,
@@ -124,6 +124,35 @@ async fn macro_test() {
     assert_eq!(format!("\"8\""), *m.get(&"\"8\"".to_string()).unwrap());
 }
 
+#[tokio::test]
+async fn open_tables_read_only_mode_test() {
+    let primary_path = temp_dir();
+    {
+        let tbls_primary = Tables::open_tables_read_write(primary_path.clone(), None, None);
+        tbls_primary
+            .table1
+            .insert(&"1".to_string(), &"1".to_string())
+            .expect("Failed to insert");
+    }
+
+    // Nothing is created in read only mode: a missing DB or table fails to open.
+    assert!(Tables::open_tables_read_only_mode(temp_dir(), None, None).is_err());
+    assert!(
+        TablesCustomOptions::open_tables_read_only_mode(primary_path.clone(), None, None).is_err()
+    );
+
+    let tbls_read_only = Tables::open_tables_read_only_mode(primary_path, None, None).unwrap();
+    assert_eq!(
+        tbls_read_only.table1.get(&"1".to_string()).unwrap(),
+        Some("1".to_string())
+    );
+    assert!(tbls_read_only.table2.is_empty());
+    assert!(tbls_read_only
+        .table1
+        .insert(&"2".to_string(), &"2".to_string())
+        .is_err());
+}
+
 /// We show that custom functions can be applied
 #[derive(DBMapUtils)]
 struct TablesCustomOptions {
@@ -46,6 +46,28 @@ where
     format!("{}ms", duration.as_millis()).serialize(serializer)
 }
 
+/// The same format for optional durations.
+pub mod option {
+    use super::*;
+
+    #[derive(Deserialize, Serialize)]
+    struct Wrapper(#[serde(with = "super")] Duration);
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<Duration>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Ok(Option::<Wrapper>::deserialize(deserializer)?.map(|Wrapper(duration)| duration))
+    }
+
+    pub fn serialize<S>(duration: &Option<Duration>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        duration.map(Wrapper).serialize(serializer)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::duration_format;
@@ -10,7 +10,7 @@
 #![allow(clippy::mutable_key_type)]
 
 use arc_swap::ArcSwap;
-use crypto::{NetworkPublicKey, PublicKey};
+use crypto::{NetworkPublicKey, PublicKey, SignatureScheme};
 use fastcrypto::traits::EncodeDecodeBase64;
 use multiaddr::Multiaddr;
 use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
@@ -19,6 +19,8 @@ use std::{
     collections::{BTreeMap, HashSet},
     fs::{self, OpenOptions},
     io::{BufWriter, Write as _},
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    path::PathBuf,
     sync::Arc,
     time::Duration,
 };
@@ -28,10 +30,19 @@ use utils::get_available_port;
 
 mod duration_format;
 pub mod utils;
+mod validation;
+
+pub use validation::check_duplicate_authorities;
 
 /// The epoch number.
 pub type Epoch = u64;
 
+/// The version of the protocol rules run by a committee.
+pub type ProtocolVersion = u64;
+
+/// The version of the serialized layout of the headers, certificates and batches.
+pub type MessageVersion = u32;
+
 #[derive(Error, Debug)]
 pub enum ConfigError {
     #[error("Node {0} is not in the committee")]
@@ -48,6 +59,46 @@ pub enum ConfigError {
 
     #[error("Failed to write config file '{file}': {message}")]
     ExportError { file: String, message: String },
+
+    #[error("Invalid quorum policy ({0})")]
+    InvalidQuorumPolicy(String),
+
+    #[error("Duplicate key: {0}")]
+    DuplicateKey(String),
+
+    #[error("Address {address} is used by both {first} and {second}")]
+    AddressCollision {
+        address: String,
+        first: String,
+        second: String,
+    },
+
+    #[error("Address {address} of {owner} is not routable")]
+    UnroutableAddress { address: String, owner: String },
+
+    #[error("Invalid stake distribution ({0})")]
+    InvalidStake(String),
+
+    #[error("Worker cache epoch {worker_cache} does not match committee epoch {committee}")]
+    EpochMismatch {
+        committee: Epoch,
+        worker_cache: Epoch,
+    },
+
+    #[error("Invalid parameter {name} ({reason})")]
+    InvalidParameter { name: String, reason: String },
+
+    #[error("Unsupported protocol version {version} (this node supports versions up to {max})")]
+    UnsupportedProtocolVersion {
+        version: ProtocolVersion,
+        max: ProtocolVersion,
+    },
+
+    #[error("Unsupported signature scheme {scheme} (this node is built with {built})")]
+    UnsupportedSignatureScheme {
+        scheme: SignatureScheme,
+        built: SignatureScheme,
+    },
 }
 
 #[derive(Error, Debug)]
@@ -60,6 +111,30 @@ pub enum CommitteeUpdateError {
 
     #[error("Node {0} has a different stake than expected")]
     DifferentStake(String),
+
+    #[error("Node {0} is already in the committee")]
+    AlreadyInCommittee(String),
+
+    #[error("Node {0} is not in the worker cache")]
+    NotInWorkerCache(String),
+
+    #[error("Node {0} is already in the worker cache")]
+    AlreadyInWorkerCache(String),
+
+    #[error("Update for epoch {update} is older than the current epoch {current}")]
+    StaleEpoch { current: Epoch, update: Epoch },
+
+    #[error("The update would leave the committee empty")]
+    EmptyCommittee,
+
+    #[error("The protocol config can only change with a new epoch (current epoch {0})")]
+    ProtocolChangeWithinEpoch(Epoch),
+
+    #[error("Unsupported protocol version {0}")]
+    UnsupportedProtocolVersion(ProtocolVersion),
+
+    #[error("Unsupported signature scheme {0}")]
+    UnsupportedSignatureScheme(SignatureScheme),
 }
 
 pub trait Import: DeserializeOwned {
@@ -143,6 +218,44 @@ pub struct Parameters {
     pub prometheus_metrics: PrometheusMetricsParameters,
     /// Network admin server ports for primary & worker.
     pub network_admin_server: NetworkAdminServerParameters,
+    /// When narwhal should end the current epoch on its own.
+    #[serde(default)]
+    pub epoch_policy: EpochPolicy,
+    /// How often the dns names of the committee and worker addresses are resolved again, to
+    /// reconnect to peers whose address changed.
+    #[serde(
+        with = "duration_format",
+        default = "Parameters::default_dns_refresh_interval"
+    )]
+    pub dns_refresh_interval: Duration,
+    /// The capacities of the internal channels and the limits of the major in-memory buffers.
+    #[serde(default)]
+    pub memory_budget: MemoryBudget,
+    /// Parameters overriding the node-wide values for specific workers.
+    #[serde(default)]
+    pub worker_overrides: BTreeMap<WorkerId, WorkerParameters>,
+    /// The tuning of the node's database.
+    #[serde(default)]
+    pub storage: StorageParameters,
+    /// The pruning of the persisted DAG.
+    #[serde(default)]
+    pub pruning: PruningParameters,
+    /// The expiry of the bookkeeping stores.
+    #[serde(default)]
+    pub expiry: ExpiryParameters,
+    /// The identifier of the chain, on which all the nodes of the committee agree. Peers
+    /// advertising another chain are disconnected.
+    #[serde(default)]
+    pub chain_id: String,
+    /// The settings of the connections to the other nodes and of the gRPC endpoints.
+    #[serde(default)]
+    pub network: NetworkParameters,
+    /// The log of the consensus-critical events, for post-incident analysis.
+    #[serde(default)]
+    pub audit_log: AuditLogParameters,
+    /// The remote signer holding the key of the authority, if the primary does not hold it.
+    #[serde(default)]
+    pub signer: SignerParameters,
 }
 
 impl Parameters {
@@ -153,6 +266,42 @@ impl Parameters {
     fn default_max_header_num_of_batches() -> usize {
         1_000
     }
+
+    fn default_dns_refresh_interval() -> Duration {
+        Duration::from_secs(60)
+    }
+
+    /// Returns the parameters of the worker `id`: the node-wide parameters, with the overrides
+    /// of this worker (if any) applied on top.
+    pub fn for_worker(&self, id: WorkerId) -> Parameters {
+        let mut parameters = self.clone();
+        if let Some(overrides) = self.worker_overrides.get(&id) {
+            if let Some(batch_size) = overrides.batch_size {
+                parameters.batch_size = batch_size;
+            }
+            if let Some(max_batch_delay) = overrides.max_batch_delay {
+                parameters.max_batch_delay = max_batch_delay;
+            }
+            if let Some(max_concurrent_requests) = overrides.max_concurrent_requests {
+                parameters.max_concurrent_requests = max_concurrent_requests;
+            }
+        }
+        parameters
+    }
+}
+
+/// The parameters a worker can override. Unspecified values fall back to the node-wide ones.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, Eq, PartialEq)]
+#[serde(default)]
+pub struct WorkerParameters {
+    /// Overrides `Parameters::batch_size`.
+    pub batch_size: Option<usize>,
+    /// Overrides `Parameters::max_batch_delay`.
+    #[serde(with = "duration_format::option")]
+    pub max_batch_delay: Option<Duration>,
+    /// Overrides `Parameters::max_concurrent_requests`, the limit of concurrent requests
+    /// accepted by the transactions endpoint of the worker.
+    pub max_concurrent_requests: Option<usize>,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -161,6 +310,18 @@ pub struct NetworkAdminServerParameters {
     pub primary_network_admin_server_port: u16,
     /// Worker network admin server base port number
     pub worker_network_admin_server_base_port: u16,
+    /// The authentication of the requests to the admin servers. Without it, anyone who can
+    /// reach the ports can e.g. shut the node down.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth: Option<AdminAuthParameters>,
+    /// Where the admin server of the primary listens, by default on its port of the loopback
+    /// address.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub primary_bind: Option<AdminBindAddress>,
+    /// Where the admin servers of the workers listen, by default on their ports of the loopback
+    /// address. The socket of a worker is suffixed with its id, e.g. `admin.sock.0` for worker 0.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub worker_bind: Option<AdminBindAddress>,
 }
 
 impl Default for NetworkAdminServerParameters {
@@ -169,6 +330,545 @@ impl Default for NetworkAdminServerParameters {
         Self {
             primary_network_admin_server_port: get_available_port(host),
             worker_network_admin_server_base_port: get_available_port(host),
+            auth: None,
+            primary_bind: None,
+            worker_bind: None,
+        }
+    }
+}
+
+impl NetworkAdminServerParameters {
+    /// The address of the admin server of the primary.
+    pub fn primary_address(&self) -> AdminAddress {
+        AdminAddress::new(
+            self.primary_bind.as_ref(),
+            self.primary_network_admin_server_port,
+        )
+    }
+
+    /// The address of the admin server of the worker `id`.
+    pub fn worker_address(&self, id: WorkerId) -> AdminAddress {
+        let port = self
+            .worker_network_admin_server_base_port
+            .checked_add(id as u16)
+            .unwrap();
+        match &self.worker_bind {
+            Some(AdminBindAddress::UnixSocket(path)) => {
+                let mut path = path.clone().into_os_string();
+                path.push(format!(".{id}"));
+                AdminAddress::Unix(path.into())
+            }
+            bind => AdminAddress::new(bind.as_ref(), port),
+        }
+    }
+}
+
+/// Where an admin server listens instead of the loopback address, e.g. to run several nodes
+/// per host or to restrict the access to the server with the permissions of a socket file.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AdminBindAddress {
+    /// The port of the server on this interface, e.g. the one of a management network.
+    Interface(IpAddr),
+    /// A unix domain socket at this path, replaced if it exists.
+    UnixSocket(PathBuf),
+}
+
+/// The address an admin server listens on.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AdminAddress {
+    Tcp(SocketAddr),
+    Unix(PathBuf),
+}
+
+impl AdminAddress {
+    fn new(bind: Option<&AdminBindAddress>, port: u16) -> Self {
+        match bind {
+            None => Self::Tcp(SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), port)),
+            Some(AdminBindAddress::Interface(ip)) => Self::Tcp(SocketAddr::new(*ip, port)),
+            Some(AdminBindAddress::UnixSocket(path)) => Self::Unix(path.clone()),
+        }
+    }
+}
+
+impl std::fmt::Display for AdminAddress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Tcp(address) => write!(f, "{address}"),
+            Self::Unix(path) => write!(f, "unix:{}", path.display()),
+        }
+    }
+}
+
+/// How the requests to the admin servers of the primary and of the workers are authenticated.
+/// The clients of the node itself, such as the node restarter, use the same credentials.
+#[derive(Clone, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AdminAuthParameters {
+    /// The requests must carry the header `Authorization: Bearer <token>`.
+    BearerToken(String),
+    /// The servers are served over TLS, and only accept the clients presenting a certificate
+    /// signed by the certificate authority.
+    MutualTls(AdminTlsParameters),
+}
+
+// The token is kept out of the logs.
+impl std::fmt::Debug for AdminAuthParameters {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::BearerToken(_) => f.write_str("BearerToken(..)"),
+            Self::MutualTls(tls) => f.debug_tuple("MutualTls").field(tls).finish(),
+        }
+    }
+}
+
+/// The PEM files of the mutual TLS of the admin servers.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct AdminTlsParameters {
+    /// The certificate authority signing the certificates of the servers and of their clients.
+    pub ca_cert: PathBuf,
+    /// The certificate chain of the servers, and its key.
+    pub server_cert: PathBuf,
+    pub server_key: PathBuf,
+    /// The certificate chain of the clients of the node itself, and its PKCS #8 key.
+    pub client_cert: PathBuf,
+    pub client_key: PathBuf,
+}
+
+/// Bounds the memory used by a node: the capacity of its internal channels and of its major
+/// in-memory buffers. Lower values reduce the memory footprint, higher values absorb larger bursts
+/// of load.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct MemoryBudget {
+    /// The capacity of the internal channels without an override in `channel_capacities`.
+    pub channel_capacity: usize,
+    /// Per-channel capacity overrides, keyed by channel name. The names are the ones of the
+    /// channel metrics (e.g. `tx_headers`, `tx_batch_maker` or `tx_sequence`).
+    pub channel_capacities: BTreeMap<String, usize>,
+    /// The maximum number of batches a worker is sealing or broadcasting at the same time.
+    pub max_parallel_batches: usize,
+    /// The maximum number of batch digests a worker is delivering to its primary at the same time.
+    pub max_pending_digests: usize,
+    /// The maximum number of committed sub-dags whose payload the executor is fetching at the same
+    /// time.
+    pub max_pending_payloads: usize,
+}
+
+impl MemoryBudget {
+    /// Returns the capacity of the channel named `channel`.
+    pub fn channel_capacity(&self, channel: &str) -> usize {
+        self.channel_capacities
+            .get(channel)
+            .copied()
+            .unwrap_or(self.channel_capacity)
+    }
+}
+
+impl Default for MemoryBudget {
+    fn default() -> Self {
+        Self {
+            channel_capacity: 1_000,
+            channel_capacities: BTreeMap::new(),
+            max_parallel_batches: 25,
+            max_pending_digests: 10_000,
+            max_pending_payloads: 32,
+        }
+    }
+}
+
+/// The RocksDB tuning of the node's database. Unset values keep the defaults of the storage layer,
+/// which suit most deployments; the right settings mostly depend on the disks the node runs on.
+#[derive(Clone, Debug, Deserialize, Serialize, Eq, PartialEq)]
+#[serde(default)]
+pub struct StorageParameters {
+    /// The size in bytes of the block cache, shared by all the column families.
+    pub block_cache_size: Option<usize>,
+    /// The total size in bytes of the write buffers (memtables) of all the column families, past
+    /// which they are flushed to disk.
+    pub db_write_buffer_size: Option<usize>,
+    /// The total size in bytes of the write-ahead logs, past which the column families they cover
+    /// are flushed so the oldest logs can be deleted.
+    pub max_total_wal_size: Option<u64>,
+    /// Makes the write-ahead logs be synced to disk incrementally, every time this many bytes have
+    /// been written, rather than leaving it to the operating system.
+    pub wal_bytes_per_sync: Option<u64>,
+    /// The directory of the write-ahead logs, to keep them on a different disk than the data. They
+    /// are stored along the data by default.
+    pub wal_dir: Option<PathBuf>,
+    /// The options of all the column families without an entry in `column_families`.
+    pub column_family_defaults: ColumnFamilyParameters,
+    /// Per column family options, keyed by column family name (e.g. `headers`, `certificates`
+    /// or `batches`). Unset values fall back to `column_family_defaults`.
+    pub column_families: BTreeMap<String, ColumnFamilyParameters>,
+    /// Enables the encryption of all the stored values with the keys of this file: a json
+    /// object mapping epochs to base64 encoded 256-bit keys. The stores of an epoch are encrypted
+    /// with the key of the latest epoch not after it, so keys are rotated by adding the key of a
+    /// future epoch; older keys must be kept while stores encrypted with them are in use.
+    pub encryption_keys_file: Option<PathBuf>,
+    /// How often the per column family metrics (sizes, estimated number of keys, pending
+    /// compactions and write stalls) are refreshed.
+    #[serde(with = "duration_format")]
+    pub metrics_interval: Duration,
+    /// How the stores of the successive epochs are laid out on disk.
+    pub layout: StorageLayout,
+    /// How the coalesced writes are flushed, and the writes synced `Interval` made durable.
+    pub writes: WriteParameters,
+}
+
+impl Default for StorageParameters {
+    fn default() -> Self {
+        Self {
+            block_cache_size: None,
+            db_write_buffer_size: None,
+            max_total_wal_size: None,
+            wal_bytes_per_sync: None,
+            wal_dir: None,
+            column_family_defaults: ColumnFamilyParameters::default(),
+            column_families: BTreeMap::new(),
+            encryption_keys_file: None,
+            metrics_interval: Duration::from_secs(15),
+            layout: StorageLayout::default(),
+            writes: WriteParameters::default(),
+        }
+    }
+}
+
+impl StorageParameters {
+    /// Returns the options of the column family `name`: its own options, with the defaults
+    /// applied to the unset ones.
+    pub fn for_column_family(&self, name: &str) -> ColumnFamilyParameters {
+        let defaults = &self.column_family_defaults;
+        match self.column_families.get(name) {
+            Some(options) => ColumnFamilyParameters {
+                write_buffer_size: options.write_buffer_size.or(defaults.write_buffer_size),
+                max_write_buffer_number: options
+                    .max_write_buffer_number
+                    .or(defaults.max_write_buffer_number),
+                compression: options.compression.or(defaults.compression),
+                compaction_style: options.compaction_style.or(defaults.compaction_style),
+                coalesce_writes: options.coalesce_writes.or(defaults.coalesce_writes),
+                sync: options.sync.or(defaults.sync),
+            },
+            None => defaults.clone(),
+        }
+    }
+}
+
+/// The layouts available for the stores of the successive epochs.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, Eq, PartialEq)]
+pub enum StorageLayout {
+    /// A database per epoch, in the `epoch<N>` directory of the storage path. The databases of
+    /// the past epochs are left on disk.
+    #[default]
+    PerEpoch,
+    /// A single database at the storage path, shared by all the epochs under epoch-prefixed keys.
+    /// Reconfiguration opens no new database, and the data of the past epochs is range-deleted
+    /// once the next epoch starts.
+    Shared,
+}
+
+/// The RocksDB options of a column family.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, Eq, PartialEq)]
+#[serde(default)]
+pub struct ColumnFamilyParameters {
+    /// The size in bytes of a write buffer (memtable) of the column family.
+    pub write_buffer_size: Option<usize>,
+    /// The maximum number of write buffers of the column family held in memory, including the
+    /// ones being flushed.
+    pub max_write_buffer_number: Option<i32>,
+    /// The compression of the column family's data files.
+    pub compression: Option<Compression>,
+    /// How the data files of the column family are compacted.
+    pub compaction_style: Option<CompactionStyle>,
+    /// Whether the writes to the column family are buffered in memory and applied together with
+    /// others, which saves small writes on slow disks. The buffered writes are lost if the node
+    /// crashes before they are flushed (see `WriteParameters`); writes are never reordered, and
+    /// batches stay atomic.
+    pub coalesce_writes: Option<bool>,
+    /// When the writes to the column family are made durable. By default, this is left to the
+    /// operating system.
+    pub sync: Option<SyncPolicy>,
+}
+
+/// When the writes to a column family are made durable, i.e. survive a crash of the machine.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, Eq, PartialEq)]
+pub enum SyncPolicy {
+    /// Every write is durable once it returns. The column family's writes are not coalesced.
+    Always,
+    /// The writes are made durable every `WriteParameters::sync_interval`.
+    Interval,
+    /// The writes are made durable along the next commit of consensus, itself made durable.
+    OnCommit,
+}
+
+/// How the writes to the stores are coalesced and made durable.
+#[derive(Clone, Debug, Deserialize, Serialize, Eq, PartialEq)]
+#[serde(default)]
+pub struct WriteParameters {
+    /// The longest time coalesced writes are buffered before being flushed.
+    #[serde(with = "duration_format")]
+    pub flush_interval: Duration,
+    /// The size in bytes of the buffered writes past which they are flushed.
+    pub flush_bytes: usize,
+    /// How often the writes of the column families synced `Interval` are made durable.
+    #[serde(with = "duration_format")]
+    pub sync_interval: Duration,
+}
+
+impl Default for WriteParameters {
+    fn default() -> Self {
+        Self {
+            flush_interval: Duration::from_millis(50),
+            flush_bytes: 4 << 20,
+            sync_interval: Duration::from_secs(1),
+        }
+    }
+}
+
+/// The compression algorithms available for the data files.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, Eq, PartialEq)]
+pub enum Compression {
+    None,
+    Snappy,
+    Zlib,
+    Lz4,
+    Zstd,
+}
+
+/// The compaction styles available for a column family.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, Eq, PartialEq)]
+pub enum CompactionStyle {
+    /// Leveled compaction, which keeps the space and read amplification low.
+    Level,
+    /// Universal compaction, which reduces the write amplification at the cost of space.
+    Universal,
+    /// Drops the oldest files once the column family exceeds its maximum size.
+    Fifo,
+}
+
+/// Controls the deletion of the certificates, headers, payload tokens and batches of the rounds the
+/// node no longer needs: the ones below both the garbage collection round of consensus and the
+/// round of the last executed sub-dag.
+#[derive(Clone, Debug, Deserialize, Serialize, Eq, PartialEq)]
+#[serde(default)]
+pub struct PruningParameters {
+    /// Whether the stores are pruned. Pruning only runs along the internal consensus, which
+    /// provides the execution progress.
+    pub enabled: bool,
+    /// How often the stores are pruned.
+    #[serde(with = "duration_format")]
+    pub interval: Duration,
+    /// The number of rounds kept below the pruning watermark, to keep serving the certificates
+    /// and batches of recent rounds to the peers catching up.
+    pub retention_rounds: u64,
+}
+
+impl Default for PruningParameters {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval: Duration::from_secs(30),
+            retention_rounds: 100,
+        }
+    }
+}
+
+/// The expiry of the bookkeeping stores that are only relevant for a few rounds: the digests of
+/// the last votes sent to every authority, and the batches of the certificates being
+/// synchronized. Unlike pruning, it does not depend on the execution progress. It is disabled by
+/// default.
+#[derive(Clone, Debug, Deserialize, Serialize, Eq, PartialEq)]
+#[serde(default)]
+pub struct ExpiryParameters {
+    /// Whether the entries expire, or are kept until the end of the epoch.
+    pub enabled: bool,
+    /// How often the expired entries are deleted.
+    #[serde(with = "duration_format")]
+    pub interval: Duration,
+    /// The entries of the rounds more than this many rounds below the round garbage collected by
+    /// consensus expire. The primary refuses the headers below the garbage collection round, so
+    /// it does not need the digests of its votes for them to refuse voting twice.
+    pub horizon_rounds: u64,
+}
+
+impl Default for ExpiryParameters {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval: Duration::from_secs(30),
+            horizon_rounds: 100,
+        }
+    }
+}
+
+/// The append-only log of the proposals, votes, certificates, commits and reconfigurations of
+/// the node, as JSON lines. It is disabled unless a directory is set.
+#[derive(Clone, Debug, Deserialize, Serialize, Eq, PartialEq)]
+#[serde(default)]
+pub struct AuditLogParameters {
+    /// The directory of the log files.
+    pub dir: Option<PathBuf>,
+    /// The size in bytes above which the current file is rotated.
+    pub max_file_size: u64,
+    /// The number of rotated files kept, the older ones being deleted.
+    pub max_files: usize,
+}
+
+impl Default for AuditLogParameters {
+    fn default() -> Self {
+        Self {
+            dir: None,
+            max_file_size: 64 << 20,
+            max_files: 10,
+        }
+    }
+}
+
+/// The remote signing service (e.g. a KMS) holding the key of the authority, which then signs the
+/// headers and votes of the primary. Disabled unless a URL is set, the primary then signing with
+/// its own keypair.
+#[derive(Clone, Debug, Deserialize, Serialize, Eq, PartialEq)]
+#[serde(default)]
+pub struct SignerParameters {
+    /// The URL of the signing service.
+    pub url: Option<String>,
+    /// The id of the key of the authority in the service.
+    pub key_id: String,
+    /// The public key of the authority, against which the signatures of the service are checked.
+    pub public_key: Option<PublicKey>,
+    /// The timeout of the requests to the service.
+    #[serde(with = "duration_format")]
+    pub request_timeout: Duration,
+}
+
+impl Default for SignerParameters {
+    fn default() -> Self {
+        Self {
+            url: None,
+            key_id: String::new(),
+            public_key: None,
+            request_timeout: Duration::from_secs(5),
+        }
+    }
+}
+
+/// The settings of the connections of the node, to tune them to the links between the nodes
+/// (e.g. lossy WAN links) without recompiling.
+#[derive(Clone, Debug, Deserialize, Serialize, Eq, PartialEq)]
+#[serde(default)]
+pub struct NetworkParameters {
+    /// The interval of the keep-alive packets of the connections to the other primaries and
+    /// workers.
+    #[serde(with = "duration_format")]
+    pub keep_alive_interval: Duration,
+    /// How long a connection to another node may stay without traffic before it is closed, which
+    /// also bounds the time to establish it. Defaults to the value of the networking library.
+    #[serde(with = "duration_format::option")]
+    pub idle_timeout: Option<Duration>,
+    /// The timeout of the requests sent to the other nodes.
+    #[serde(with = "duration_format")]
+    pub request_timeout: Duration,
+    /// The timeout of the handlers of the requests of the gRPC endpoints (the consensus API and
+    /// the transactions endpoints of the workers), if any.
+    #[serde(with = "duration_format::option")]
+    pub grpc_request_timeout: Option<Duration>,
+    /// The interval of the HTTP/2 pings of the connections of the gRPC endpoints, if any.
+    #[serde(with = "duration_format::option")]
+    pub grpc_http2_keepalive_interval: Option<Duration>,
+    /// How long a HTTP/2 ping waits for its acknowledgement before the connection is closed.
+    #[serde(with = "duration_format::option")]
+    pub grpc_http2_keepalive_timeout: Option<Duration>,
+    /// How long the TCP connections of the gRPC endpoints stay idle before keep-alive probes are
+    /// sent, if any.
+    #[serde(with = "duration_format::option")]
+    pub grpc_tcp_keepalive: Option<Duration>,
+    /// Whether the primary and workers listen on `[::]` for both the ip6 and ip4 connections,
+    /// rather than on the interfaces of the ip version of their address only. The anemo
+    /// listeners rely on the default of the OS for such sockets, which on Linux is to be
+    /// dual-stack unless `net.ipv6.bindv6only` is set.
+    pub dual_stack: bool,
+    /// The maximum size of the messages exchanged by the primaries, enforced on both the sent
+    /// and the received ones. It bounds the size of the certificates fetched at once.
+    pub max_primary_message_size: usize,
+    /// The maximum size of the messages exchanged by the workers, enforced on both the sent and
+    /// the received ones. It bounds the size of the batches.
+    pub max_worker_message_size: usize,
+    /// The maximum size of the transactions received by the transactions endpoints of the
+    /// workers, metadata included.
+    pub max_transactions_message_size: usize,
+    /// The egress bandwidth of the node in bytes per second, if limited. The live traffic
+    /// (consensus messages and batch dissemination) is never delayed, while the sync traffic
+    /// (certificates and batches fetched by the peers catching up) gets the remaining bandwidth.
+    pub egress_bandwidth: Option<u64>,
+    /// The egress bandwidth of the sync traffic to each peer in bytes per second, if limited.
+    pub sync_egress_bandwidth_per_peer: Option<u64>,
+}
+
+impl Default for NetworkParameters {
+    fn default() -> Self {
+        Self {
+            keep_alive_interval: Duration::from_secs(5),
+            idle_timeout: None,
+            request_timeout: Duration::from_secs(30),
+            grpc_request_timeout: None,
+            grpc_http2_keepalive_interval: None,
+            grpc_http2_keepalive_timeout: None,
+            grpc_tcp_keepalive: None,
+            dual_stack: false,
+            max_primary_message_size: 8 << 20,
+            max_worker_message_size: 8 << 20,
+            max_transactions_message_size: 6 << 20,
+            egress_bandwidth: None,
+            sync_egress_bandwidth_per_peer: None,
+        }
+    }
+}
+
+/// Decides when an epoch ends without an explicit reconfiguration. Narwhal checks the policy after
+/// every committed sub-dag; once it is met, the execution layer is notified that the epoch is over
+/// (see `ExecutionState::handle_end_of_epoch`) and the node moves to the next epoch.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, Eq, PartialEq)]
+pub enum EpochPolicy {
+    /// The epoch only ends upon an explicit reconfiguration.
+    #[default]
+    Manual,
+    /// The epoch ends with the first sub-dag whose leader was created at least this long after
+    /// the leader of the first sub-dag of the epoch.
+    FixedDuration(#[serde(with = "duration_format")] Duration),
+    /// The epoch ends after this number of sub-dags has been committed.
+    FixedCommits(u64),
+}
+
+impl EpochPolicy {
+    /// Whether the epoch ends with the sub-dag of index `sub_dag_index`, whose leader was created
+    /// at `leader_timestamp`. The `epoch_start` is the creation time of the leader of the first
+    /// sub-dag of the epoch. Timestamps are in milliseconds. The decision only depends on committed
+    /// data, so all honest nodes end the epoch on the same sub-dag.
+    pub fn is_end_of_epoch(
+        &self,
+        sub_dag_index: u64,
+        epoch_start: u64,
+        leader_timestamp: u64,
+    ) -> bool {
+        match self {
+            EpochPolicy::Manual => false,
+            EpochPolicy::FixedDuration(duration) => {
+                leader_timestamp.saturating_sub(epoch_start) >= duration.as_millis() as u64
+            }
+            EpochPolicy::FixedCommits(commits) => sub_dag_index >= *commits,
+        }
+    }
+}
+
+impl std::fmt::Display for EpochPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EpochPolicy::Manual => write!(f, "manual"),
+            EpochPolicy::FixedDuration(duration) => {
+                write!(f, "every {} ms", duration.as_millis())
+            }
+            EpochPolicy::FixedCommits(commits) => write!(f, "every {commits} commits"),
         }
     }
 }
@@ -308,6 +1008,17 @@ impl Default for Parameters {
             max_concurrent_requests: 500_000,
             prometheus_metrics: PrometheusMetricsParameters::default(),
             network_admin_server: NetworkAdminServerParameters::default(),
+            epoch_policy: EpochPolicy::default(),
+            dns_refresh_interval: Parameters::default_dns_refresh_interval(),
+            memory_budget: MemoryBudget::default(),
+            worker_overrides: BTreeMap::new(),
+            storage: StorageParameters::default(),
+            pruning: PruningParameters::default(),
+            expiry: ExpiryParameters::default(),
+            audit_log: AuditLogParameters::default(),
+            signer: SignerParameters::default(),
+            chain_id: String::new(),
+            network: NetworkParameters::default(),
         }
     }
 }
@@ -388,14 +1099,75 @@ impl Parameters {
             self.prometheus_metrics.socket_addr
         );
         info!(
-            "Primary network admin server will run on 127.0.0.1:{}",
-            self.network_admin_server.primary_network_admin_server_port
+            "Primary network admin server will run on {}",
+            self.network_admin_server.primary_address()
+        );
+        info!(
+            "Worker network admin servers will run starting on {}",
+            self.network_admin_server.worker_address(0)
+        );
+        match &self.network_admin_server.auth {
+            None => info!("Network admin servers are not authenticated"),
+            Some(AdminAuthParameters::BearerToken(_)) => {
+                info!("Network admin servers authenticate with a bearer token")
+            }
+            Some(AdminAuthParameters::MutualTls(tls)) => info!(
+                "Network admin servers authenticate with mutual TLS, certificate authority {}",
+                tls.ca_cert.display()
+            ),
+        }
+        info!("Epoch change policy set to {}", self.epoch_policy);
+        info!(
+            "DNS refresh interval set to {} s",
+            self.dns_refresh_interval.as_secs()
         );
         info!(
-            "Worker network admin server will run starting on base port 127.0.0.1:{}",
-            self.network_admin_server
-                .worker_network_admin_server_base_port
+            "Channel capacity set to {}",
+            self.memory_budget.channel_capacity
         );
+        for (channel, capacity) in &self.memory_budget.channel_capacities {
+            info!("Capacity of channel {channel} set to {capacity}");
+        }
+        info!(
+            "Max parallel batches set to {}",
+            self.memory_budget.max_parallel_batches
+        );
+        info!(
+            "Max pending digests set to {}",
+            self.memory_budget.max_pending_digests
+        );
+        info!(
+            "Max pending payloads set to {}",
+            self.memory_budget.max_pending_payloads
+        );
+        for (id, overrides) in &self.worker_overrides {
+            info!("Parameters of worker {id} overridden with {overrides:?}");
+        }
+        info!("Storage parameters set to {:?}", self.storage);
+        if self.pruning.enabled {
+            info!(
+                "Pruning every {} ms, keeping {} rounds",
+                self.pruning.interval.as_millis(),
+                self.pruning.retention_rounds
+            );
+        } else {
+            info!("Pruning disabled");
+        }
+        info!("Chain id set to {:?}", self.chain_id);
+        info!("Network parameters set to {:?}", self.network);
+        match &self.audit_log.dir {
+            Some(dir) => info!(
+                "Audit log in {}, rotated every {} B, keeping {} files",
+                dir.display(),
+                self.audit_log.max_file_size,
+                self.audit_log.max_files
+            ),
+            None => info!("Audit log disabled"),
+        }
+        match &self.signer.url {
+            Some(url) => info!("Signing with the key {} of {url}", self.signer.key_id),
+            None => info!("Signing with the local keypair"),
+        }
     }
 }
 
@@ -550,6 +1322,77 @@ impl WorkerCache {
             })
             .collect()
     }
+
+    /// Returns a new worker cache with the changes of `update` applied on top of this one. The
+    /// changes are applied in order; any invalid change generates no update and all the errors
+    /// met are returned.
+    pub fn apply(
+        &self,
+        update: &WorkerCacheUpdate,
+    ) -> Result<WorkerCache, Vec<CommitteeUpdateError>> {
+        let mut errors = Vec::new();
+        if update.epoch < self.epoch {
+            errors.push(CommitteeUpdateError::StaleEpoch {
+                current: self.epoch,
+                update: update.epoch,
+            });
+        }
+
+        let mut workers = self.workers.clone();
+        for change in &update.changes {
+            match change {
+                WorkerIndexUpdate::Add(name, index) => {
+                    if workers.contains_key(name) {
+                        errors.push(CommitteeUpdateError::AlreadyInWorkerCache(
+                            name.encode_base64(),
+                        ));
+                    } else {
+                        workers.insert(name.clone(), index.clone());
+                    }
+                }
+                WorkerIndexUpdate::Remove(name) => {
+                    if workers.remove(name).is_none() {
+                        errors.push(CommitteeUpdateError::NotInWorkerCache(name.encode_base64()));
+                    }
+                }
+                WorkerIndexUpdate::Update(name, index) => match workers.get_mut(name) {
+                    Some(entry) => *entry = index.clone(),
+                    None => {
+                        errors.push(CommitteeUpdateError::NotInWorkerCache(name.encode_base64()))
+                    }
+                },
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+        Ok(WorkerCache {
+            workers,
+            epoch: update.epoch,
+        })
+    }
+}
+
+/// A single change to the worker cache.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub enum WorkerIndexUpdate {
+    /// Add the workers of an authority that is not yet in the worker cache.
+    Add(PublicKey, WorkerIndex),
+    /// Remove all the workers of an authority.
+    Remove(PublicKey),
+    /// Replace the workers of an authority already in the worker cache.
+    Update(PublicKey, WorkerIndex),
+}
+
+/// A set of changes to apply on top of a worker cache, as an alternative to shipping a complete
+/// new worker cache when only a few authorities change.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct WorkerCacheUpdate {
+    /// The epoch of the worker cache resulting from this update.
+    pub epoch: Epoch,
+    /// The changes to apply, in order.
+    pub changes: Vec<WorkerIndexUpdate>,
 }
 
 #[derive(Clone, Serialize, Deserialize, Debug, Eq, PartialEq)]
@@ -564,12 +1407,177 @@ pub struct Authority {
 
 pub type SharedCommittee = Arc<ArcSwap<Committee>>;
 
+/// The stake thresholds of a committee, expressed as fractions of its total stake. A quorum is
+/// reached with strictly more than `quorum_numerator / denominator` of the total stake, and
+/// validity with at least `validity_numerator / denominator` of it. The default is the classic
+/// BFT setting where a quorum is 2f+1 and validity is f+1 out of 3f+1.
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, Eq, PartialEq)]
+pub struct QuorumPolicy {
+    pub quorum_numerator: u64,
+    pub validity_numerator: u64,
+    pub denominator: u64,
+}
+
+impl Default for QuorumPolicy {
+    fn default() -> Self {
+        Self {
+            quorum_numerator: 2,
+            validity_numerator: 1,
+            denominator: 3,
+        }
+    }
+}
+
+impl QuorumPolicy {
+    /// Checks the policy is consistent: both thresholds are proper fractions of the stake, and
+    /// any two quorums intersect in at least the validity threshold.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let invalid = |reason: &str| {
+            Err(ConfigError::InvalidQuorumPolicy(format!(
+                "{}/{} quorum, {}/{} validity: {reason}",
+                self.quorum_numerator, self.denominator, self.validity_numerator, self.denominator
+            )))
+        };
+        if self.denominator == 0 {
+            return invalid("the denominator must be positive");
+        }
+        if self.validity_numerator == 0 {
+            return invalid("the validity threshold must be positive");
+        }
+        if self.quorum_numerator >= self.denominator {
+            return invalid("the quorum threshold must be smaller than the total stake");
+        }
+        if self.validity_numerator > self.quorum_numerator {
+            return invalid("the validity threshold must not exceed the quorum threshold");
+        }
+        // Two quorums overlap on strictly more than (2 * quorum - 1) of the stake.
+        if 2 * self.quorum_numerator < self.denominator + self.validity_numerator {
+            return invalid("two quorums must intersect in at least the validity threshold");
+        }
+        Ok(())
+    }
+
+    /// Returns the stake required to reach a quorum out of `total_stake`.
+    pub fn quorum_threshold(&self, total_stake: Stake) -> Stake {
+        let threshold =
+            total_stake as u128 * self.quorum_numerator as u128 / self.denominator as u128 + 1;
+        threshold as Stake
+    }
+
+    /// Returns the stake required to reach validity out of `total_stake`.
+    pub fn validity_threshold(&self, total_stake: Stake) -> Stake {
+        let denominator = self.denominator as u128;
+        let threshold =
+            (total_stake as u128 * self.validity_numerator as u128 + denominator - 1) / denominator;
+        threshold as Stake
+    }
+
+    /// Deserializes a policy, rejecting inconsistent ones so they are caught when the committee
+    /// is loaded.
+    fn deserialize_validated<'de, D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let policy = Self::deserialize(deserializer)?;
+        policy.validate().map_err(serde::de::Error::custom)?;
+        Ok(policy)
+    }
+}
+
+/// The protocol rules run by a committee. The configuration is carried by the committee, so all
+/// the authorities of an epoch run the same rules whatever the version of their binary, and a new
+/// version only takes effect at the start of an epoch.
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, Eq, PartialEq)]
+pub struct ProtocolConfig {
+    pub version: ProtocolVersion,
+    /// The scheme of the keys and signatures of the authorities, which must be the one the
+    /// nodes are built with.
+    #[serde(default)]
+    pub signature_scheme: SignatureScheme,
+}
+
+impl Default for ProtocolConfig {
+    fn default() -> Self {
+        Self {
+            version: Self::MIN_SUPPORTED_VERSION,
+            signature_scheme: crypto::SIGNATURE_SCHEME,
+        }
+    }
+}
+
+impl ProtocolConfig {
+    /// The oldest protocol version this node can run.
+    pub const MIN_SUPPORTED_VERSION: ProtocolVersion = 1;
+    /// The newest protocol version this node can run.
+    pub const MAX_SUPPORTED_VERSION: ProtocolVersion = 3;
+
+    /// Checks this node knows the rules of the protocol version, and is built with the signature
+    /// scheme of the committee.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if !(Self::MIN_SUPPORTED_VERSION..=Self::MAX_SUPPORTED_VERSION).contains(&self.version) {
+            return Err(ConfigError::UnsupportedProtocolVersion {
+                version: self.version,
+                max: Self::MAX_SUPPORTED_VERSION,
+            });
+        }
+        if self.signature_scheme != crypto::SIGNATURE_SCHEME {
+            return Err(ConfigError::UnsupportedSignatureScheme {
+                scheme: self.signature_scheme,
+                built: crypto::SIGNATURE_SCHEME,
+            });
+        }
+        Ok(())
+    }
+
+    /// Since version 2, leaders are elected in turn (in the order of their keys) rather than by
+    /// a stake-weighted random choice.
+    pub fn round_robin_leaders(&self) -> bool {
+        self.version >= 2
+    }
+
+    /// The latest version of the layouts of the headers, certificates and batches the nodes may
+    /// write. The messages using the additions of a new layout are only accepted once a protocol
+    /// version selects it, so the nodes that did not upgrade yet can still read the messages of
+    /// the others until then.
+    ///
+    /// Since version 3, batches carry the metadata of their transactions (layout 2).
+    pub fn message_version(&self) -> MessageVersion {
+        if self.version >= 3 {
+            2
+        } else {
+            1
+        }
+    }
+
+    /// Whether the transactions can carry metadata, see [`Self::message_version`].
+    pub fn transaction_metadata(&self) -> bool {
+        self.message_version() >= 2
+    }
+
+    /// Deserializes a configuration, rejecting the versions this node does not support so they
+    /// are caught when the committee is loaded.
+    fn deserialize_validated<'de, D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let config = Self::deserialize(deserializer)?;
+        config.validate().map_err(serde::de::Error::custom)?;
+        Ok(config)
+    }
+}
+
 #[derive(Clone, Serialize, Deserialize, Debug, Eq, PartialEq)]
 pub struct Committee {
     /// The authorities of epoch.
     pub authorities: BTreeMap<PublicKey, Authority>,
     /// The epoch number of this committee
     pub epoch: Epoch,
+    /// The stake thresholds used for quorum and validity checks.
+    #[serde(default, deserialize_with = "QuorumPolicy::deserialize_validated")]
+    pub quorum_policy: QuorumPolicy,
+    /// The protocol rules run during the epoch.
+    #[serde(default, deserialize_with = "ProtocolConfig::deserialize_validated")]
+    pub protocol_config: ProtocolConfig,
 }
 
 impl From<Committee> for SharedCommittee {
@@ -634,24 +1642,52 @@ impl Committee {
             .map_or_else(|| 0, |x| x.stake)
     }
 
-    /// Returns the stake required to reach a quorum (2f+1).
+    /// Returns the total stake of the committee, saturating at `Stake::MAX` for the invalid
+    /// committees whose stakes overflow.
+    pub fn total_stake(&self) -> Stake {
+        self.authorities
+            .values()
+            .fold(0, |total, x| total.saturating_add(x.stake))
+    }
+
+    /// Returns the stake required to reach a quorum (2f+1 with the default policy).
     pub fn quorum_threshold(&self) -> Stake {
         // If N = 3f + 1 + k (0 <= k < 3)
         // then (2 N + 3) / 3 = 2f + 1 + (2k + 2)/3 = 2f + 1 + k = N - f
-        let total_votes: Stake = self.authorities.values().map(|x| x.stake).sum();
-        2 * total_votes / 3 + 1
+        self.quorum_policy.quorum_threshold(self.total_stake())
     }
 
-    /// Returns the stake required to reach availability (f+1).
+    /// Returns the stake required to reach availability (f+1 with the default policy).
     pub fn validity_threshold(&self) -> Stake {
         // If N = 3f + 1 + k (0 <= k < 3)
         // then (N + 2) / 3 = f + 1 + k/3 = f + 1
-        let total_votes: Stake = self.authorities.values().map(|x| x.stake).sum();
-        (total_votes + 2) / 3
+        self.quorum_policy.validity_threshold(self.total_stake())
+    }
+
+    /// Returns true if `stake` is enough to reach a quorum.
+    pub fn reached_quorum(&self, stake: Stake) -> bool {
+        stake >= self.quorum_threshold()
+    }
+
+    /// Returns true if `stake` is enough to reach validity.
+    pub fn reached_validity(&self, stake: Stake) -> bool {
+        stake >= self.validity_threshold()
     }
 
-    /// Returns a leader node as a weighted choice seeded by the provided integer
+    /// Returns a leader node as a weighted choice seeded by the provided integer, or in turn
+    /// when the protocol config uses round-robin leaders.
     pub fn leader(&self, seed: u64) -> PublicKey {
+        if self.protocol_config.round_robin_leaders() {
+            // Leaders are mostly elected on even rounds.
+            let index = (seed / 2) as usize % self.authorities.len();
+            return self
+                .authorities
+                .keys()
+                .nth(index)
+                .expect("Empty authorities table!")
+                .clone();
+        }
+
         let mut seed_bytes = [0u8; 32];
         seed_bytes[32 - 8..].copy_from_slice(&seed.to_le_bytes());
         let mut rng = StdRng::from_seed(seed_bytes);
@@ -783,6 +1819,101 @@ impl Committee {
 
         errors.map(Err).unwrap_or(Ok(()))
     }
+
+    /// Returns a new committee with the changes of `update` applied on top of this one. The
+    /// changes are applied in order; any invalid change generates no update and all the errors
+    /// met are returned.
+    pub fn apply(&self, update: &CommitteeUpdate) -> Result<Committee, Vec<CommitteeUpdateError>> {
+        let mut errors = Vec::new();
+        if update.epoch < self.epoch {
+            errors.push(CommitteeUpdateError::StaleEpoch {
+                current: self.epoch,
+                update: update.epoch,
+            });
+        }
+
+        let mut authorities = self.authorities.clone();
+        for change in &update.changes {
+            match change {
+                AuthorityUpdate::Add(name, authority) => {
+                    if authorities.contains_key(name) {
+                        errors.push(CommitteeUpdateError::AlreadyInCommittee(
+                            name.encode_base64(),
+                        ));
+                    } else {
+                        authorities.insert(name.clone(), authority.clone());
+                    }
+                }
+                AuthorityUpdate::Remove(name) => {
+                    if authorities.remove(name).is_none() {
+                        errors.push(CommitteeUpdateError::NotInCommittee(name.encode_base64()));
+                    }
+                }
+                AuthorityUpdate::Update(name, authority) => match authorities.get_mut(name) {
+                    Some(entry) => *entry = authority.clone(),
+                    None => errors.push(CommitteeUpdateError::NotInCommittee(name.encode_base64())),
+                },
+            }
+        }
+
+        if authorities.is_empty() {
+            errors.push(CommitteeUpdateError::EmptyCommittee);
+        }
+
+        let protocol_config = update.protocol_config.unwrap_or(self.protocol_config);
+        if protocol_config != self.protocol_config {
+            if update.epoch == self.epoch {
+                errors.push(CommitteeUpdateError::ProtocolChangeWithinEpoch(self.epoch));
+            }
+            match protocol_config.validate() {
+                Ok(()) => {}
+                Err(ConfigError::UnsupportedSignatureScheme { scheme, .. }) => {
+                    errors.push(CommitteeUpdateError::UnsupportedSignatureScheme(scheme));
+                }
+                Err(_) => {
+                    errors.push(CommitteeUpdateError::UnsupportedProtocolVersion(
+                        protocol_config.version,
+                    ));
+                }
+            }
+        }
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+        Ok(Committee {
+            authorities,
+            epoch: update.epoch,
+            quorum_policy: self.quorum_policy,
+            protocol_config,
+        })
+    }
+}
+
+/// A single change to the committee.
+#[derive(Clone, Serialize, Deserialize, Debug, Eq, PartialEq)]
+pub enum AuthorityUpdate {
+    /// Add an authority that is not yet in the committee.
+    Add(PublicKey, Authority),
+    /// Remove an authority from the committee.
+    Remove(PublicKey),
+    /// Replace the information (stake, addresses, network key) of an authority already in the
+    /// committee.
+    Update(PublicKey, Authority),
+}
+
+/// A set of changes to apply on top of a committee, as an alternative to shipping a complete
+/// new committee when only a few authorities change.
+#[derive(Clone, Serialize, Deserialize, Debug, Eq, PartialEq)]
+pub struct CommitteeUpdate {
+    /// The epoch of the committee resulting from this update. Using the current epoch updates
+    /// the committee in place, a higher one starts a new epoch.
+    pub epoch: Epoch,
+    /// The changes to apply, in order.
+    pub changes: Vec<AuthorityUpdate>,
+    /// The protocol rules of the resulting committee, if they change. They can only change with
+    /// a new epoch.
+    #[serde(default)]
+    pub protocol_config: Option<ProtocolConfig>,
 }
 
 #[cfg(test)]
@@ -833,5 +1964,13 @@ mod tests {
         assert!(logs_contain(
             "Worker network admin server will run starting on base port 127.0.0.1:"
         ));
+        assert!(logs_contain("Epoch change policy set to manual"));
+        assert!(logs_contain("DNS refresh interval set to 60 s"));
+        assert!(logs_contain("Channel capacity set to 1000"));
+        assert!(logs_contain("Max parallel batches set to 25"));
+        assert!(logs_contain("Max pending digests set to 10000"));
+        assert!(logs_contain("Max pending payloads set to 32"));
+        assert!(logs_contain("Storage parameters set to StorageParameters"));
+        assert!(logs_contain("Pruning disabled"));
     }
 }
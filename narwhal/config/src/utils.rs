@@ -1,11 +1,13 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+#[cfg(not(msim))]
 use std::net::{TcpListener, TcpStream};
 
 /// Return an ephemeral, available port. On unix systems, the port returned will be in the
 /// TIME_WAIT state ensuring that the OS won't hand out this port for some grace period.
 /// Callers should be able to bind to this port given they use SO_REUSEADDR.
+#[cfg(not(msim))]
 pub fn get_available_port(host: &str) -> u16 {
     const MAX_PORT_RETRIES: u32 = 1000;
 
@@ -18,6 +20,7 @@ pub fn get_available_port(host: &str) -> u16 {
     panic!("Error: could not find an available port");
 }
 
+#[cfg(not(msim))]
 fn get_ephemeral_port(host: &str) -> std::io::Result<u16> {
     // Request a random available port from the OS
     let listener = TcpListener::bind((host, 0))?;
@@ -31,3 +34,14 @@ fn get_ephemeral_port(host: &str) -> std::io::Result<u16> {
 
     Ok(addr.port())
 }
+
+/// Return a random port. The simulator binds the sockets in the network of its nodes, so the
+/// ports need not be free on the host. `OsRng` reads from `getrandom()`, which the simulator
+/// intercepts and answers from the seed of the simulation: the ports of a seed are identical
+/// across runs, even within the same process.
+#[cfg(msim)]
+pub fn get_available_port(_host: &str) -> u16 {
+    use rand::{rngs::OsRng, Rng};
+
+    OsRng.gen_range(10000..u16::MAX)
+}
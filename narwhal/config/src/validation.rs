@@ -0,0 +1,437 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Consistency checks of the committee, worker cache and parameters, to catch misconfigurations
+//! (such as two authorities sharing an address) before the node is started.
+use crate::{
+    AdminAuthParameters, AdminBindAddress, Committee, ConfigError, EpochPolicy, Parameters, Stake,
+    WorkerCache,
+};
+use crypto::NetworkPublicKey;
+use fastcrypto::traits::EncodeDecodeBase64;
+use multiaddr::{Multiaddr, Protocol};
+use serde::{
+    de::{IgnoredAny, MapAccess, Visitor},
+    Deserialize, Deserializer,
+};
+use std::{collections::HashMap, fmt, fs, time::Duration};
+
+/// Returns the host, transport and port of an address, or `None` if other nodes cannot reach it.
+fn endpoint(address: &Multiaddr) -> Option<(String, &'static str, u16)> {
+    let mut iter = address.iter();
+    let host = match iter.next()? {
+        Protocol::Ip4(ip) if !ip.is_unspecified() => ip.to_string(),
+        Protocol::Ip6(ip) if !ip.is_unspecified() => ip.to_string(),
+        Protocol::Dns(host) | Protocol::Dns4(host) | Protocol::Dns6(host) => host.to_string(),
+        _ => return None,
+    };
+    let (transport, port) = match iter.next()? {
+        Protocol::Tcp(port) => ("tcp", port),
+        Protocol::Udp(port) => ("udp", port),
+        _ => return None,
+    };
+    (port != 0).then_some((host, transport, port))
+}
+
+/// Collects the addresses and network keys of the nodes, reporting the unroutable addresses and
+/// the ones used more than once.
+#[derive(Default)]
+struct Registry {
+    addresses: HashMap<(String, &'static str, u16), String>,
+    keys: HashMap<NetworkPublicKey, String>,
+    errors: Vec<ConfigError>,
+}
+
+impl Registry {
+    fn address(&mut self, address: &Multiaddr, owner: String) {
+        match endpoint(address) {
+            Some(endpoint) => {
+                if let Some(first) = self.addresses.get(&endpoint) {
+                    self.errors.push(ConfigError::AddressCollision {
+                        address: address.to_string(),
+                        first: first.clone(),
+                        second: owner,
+                    });
+                } else {
+                    self.addresses.insert(endpoint, owner);
+                }
+            }
+            None => self.errors.push(ConfigError::UnroutableAddress {
+                address: address.to_string(),
+                owner,
+            }),
+        }
+    }
+
+    fn key(&mut self, key: &NetworkPublicKey, owner: String) {
+        if let Some(first) = self.keys.get(key) {
+            self.errors.push(ConfigError::DuplicateKey(format!(
+                "network key {} is used by both {first} and {owner}",
+                key.encode_base64()
+            )));
+        } else {
+            self.keys.insert(key.clone(), owner);
+        }
+    }
+
+    fn register_committee(&mut self, committee: &Committee) {
+        for (name, authority) in &committee.authorities {
+            let owner = format!("primary {}", name.encode_base64());
+            self.address(&authority.primary_address, owner.clone());
+            self.key(&authority.network_key, owner);
+        }
+    }
+}
+
+impl Committee {
+    /// Checks that the committee can run: a valid quorum policy, a supported protocol version, a
+    /// sound stake distribution, and distinct, routable addresses and network keys. Returns all
+    /// the problems found.
+    pub fn validate(&self) -> Result<(), Vec<ConfigError>> {
+        let mut registry = Registry::default();
+        registry.register_committee(self);
+        let mut errors = registry.errors;
+
+        if let Err(e) = self.quorum_policy.validate() {
+            errors.push(e);
+        }
+        if let Err(e) = self.protocol_config.validate() {
+            errors.push(e);
+        }
+
+        if self.authorities.is_empty() {
+            errors.push(ConfigError::InvalidStake(
+                "the committee is empty".to_string(),
+            ));
+        } else if self.total_stake() == 0 {
+            errors.push(ConfigError::InvalidStake(
+                "the total stake is zero".to_string(),
+            ));
+        } else if self
+            .authorities
+            .values()
+            .try_fold(0 as Stake, |total, authority| {
+                total.checked_add(authority.stake)
+            })
+            .is_none()
+        {
+            errors.push(ConfigError::InvalidStake(
+                "the total stake overflows".to_string(),
+            ));
+        } else {
+            // Beyond this stake, a single authority can prevent the others from ever forming a
+            // quorum.
+            let max_stake = self.total_stake() - self.quorum_threshold();
+            for (name, authority) in &self.authorities {
+                if authority.stake == 0 {
+                    errors.push(ConfigError::InvalidStake(format!(
+                        "{} has no stake",
+                        name.encode_base64()
+                    )));
+                } else if self.authorities.len() > 1 && authority.stake > max_stake {
+                    errors.push(ConfigError::InvalidStake(format!(
+                        "{} alone holds enough stake ({}) to prevent quorums",
+                        name.encode_base64(),
+                        authority.stake
+                    )));
+                }
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+impl WorkerCache {
+    /// Checks that the worker cache matches the `committee`: same epoch and authorities, and
+    /// distinct, routable addresses and network keys across all primaries and workers. Returns
+    /// all the problems found.
+    pub fn validate(&self, committee: &Committee) -> Result<(), Vec<ConfigError>> {
+        let mut registry = Registry::default();
+        registry.register_committee(committee);
+        // Problems within the committee itself are reported by `Committee::validate`.
+        registry.errors.clear();
+
+        for (name, index) in &self.workers {
+            for (id, worker) in &index.0 {
+                let owner = format!("worker {id} of {}", name.encode_base64());
+                registry.address(&worker.worker_address, owner.clone());
+                registry.address(&worker.transactions, format!("{owner} (transactions)"));
+                registry.key(&worker.name, owner);
+            }
+        }
+        let mut errors = registry.errors;
+
+        if self.epoch != committee.epoch {
+            errors.push(ConfigError::EpochMismatch {
+                committee: committee.epoch,
+                worker_cache: self.epoch,
+            });
+        }
+        for name in committee.authorities.keys() {
+            if self
+                .workers
+                .get(name)
+                .map_or(true, |index| index.0.is_empty())
+            {
+                errors.push(ConfigError::NotInWorkerCache(name.encode_base64()));
+            }
+        }
+        for name in self.workers.keys() {
+            if !committee.authorities.contains_key(name) {
+                errors.push(ConfigError::NotInCommittee(name.encode_base64()));
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+impl Parameters {
+    /// Checks that the parameters are usable: no zero sizes or delays where the node needs them
+    /// to make progress (or where the database cannot work), and consistent header and admin
+    /// server settings. Returns all the
+    /// problems found.
+    pub fn validate(&self) -> Result<(), Vec<ConfigError>> {
+        let mut errors = Vec::new();
+        let mut invalid = |name: &str, reason: &str| {
+            errors.push(ConfigError::InvalidParameter {
+                name: name.to_string(),
+                reason: reason.to_string(),
+            })
+        };
+
+        let budget = &self.memory_budget;
+        let sizes = [
+            ("max_header_num_of_batches", self.max_header_num_of_batches),
+            ("gc_depth", self.gc_depth as usize),
+            ("sync_retry_nodes", self.sync_retry_nodes),
+            ("batch_size", self.batch_size),
+            ("max_concurrent_requests", self.max_concurrent_requests),
+            ("memory_budget.channel_capacity", budget.channel_capacity),
+            (
+                "memory_budget.max_parallel_batches",
+                budget.max_parallel_batches,
+            ),
+            (
+                "memory_budget.max_pending_digests",
+                budget.max_pending_digests,
+            ),
+            (
+                "memory_budget.max_pending_payloads",
+                budget.max_pending_payloads,
+            ),
+        ];
+        for (name, size) in sizes {
+            if size == 0 {
+                invalid(name, "must not be zero");
+            }
+        }
+        for (channel, capacity) in &budget.channel_capacities {
+            if *capacity == 0 {
+                invalid(
+                    &format!("memory_budget.channel_capacities.{channel}"),
+                    "must not be zero",
+                );
+            }
+        }
+
+        let delays = [
+            ("max_header_delay", self.max_header_delay),
+            ("sync_retry_delay", self.sync_retry_delay),
+            ("max_batch_delay", self.max_batch_delay),
+            ("dns_refresh_interval", self.dns_refresh_interval),
+            ("storage.metrics_interval", self.storage.metrics_interval),
+            (
+                "storage.writes.flush_interval",
+                self.storage.writes.flush_interval,
+            ),
+            (
+                "storage.writes.sync_interval",
+                self.storage.writes.sync_interval,
+            ),
+            ("pruning.interval", self.pruning.interval),
+            ("expiry.interval", self.expiry.interval),
+        ];
+        for (name, delay) in delays {
+            if delay == Duration::ZERO {
+                invalid(name, "must not be zero");
+            }
+        }
+
+        for (id, overrides) in &self.worker_overrides {
+            if overrides.batch_size == Some(0) {
+                invalid(
+                    &format!("worker_overrides.{id}.batch_size"),
+                    "must not be zero",
+                );
+            }
+            if overrides.max_batch_delay == Some(Duration::ZERO) {
+                invalid(
+                    &format!("worker_overrides.{id}.max_batch_delay"),
+                    "must not be zero",
+                );
+            }
+            if overrides.max_concurrent_requests == Some(0) {
+                invalid(
+                    &format!("worker_overrides.{id}.max_concurrent_requests"),
+                    "must not be zero",
+                );
+            }
+        }
+
+        let storage = &self.storage;
+        if storage.block_cache_size == Some(0) {
+            invalid("storage.block_cache_size", "must not be zero");
+        }
+        if storage.db_write_buffer_size == Some(0) {
+            invalid("storage.db_write_buffer_size", "must not be zero");
+        }
+        if storage.writes.flush_bytes == 0 {
+            invalid("storage.writes.flush_bytes", "must not be zero");
+        }
+        let column_families = std::iter::once((
+            "column_family_defaults".to_string(),
+            &storage.column_family_defaults,
+        ))
+        .chain(
+            storage
+                .column_families
+                .iter()
+                .map(|(name, options)| (format!("column_families.{name}"), options)),
+        );
+        for (name, options) in column_families {
+            if options.write_buffer_size == Some(0) {
+                invalid(
+                    &format!("storage.{name}.write_buffer_size"),
+                    "must not be zero",
+                );
+            }
+            if matches!(options.max_write_buffer_number, Some(number) if number < 1) {
+                invalid(
+                    &format!("storage.{name}.max_write_buffer_number"),
+                    "must be at least 1",
+                );
+            }
+        }
+
+        if self.header_num_of_batches_threshold > self.max_header_num_of_batches {
+            invalid(
+                "header_num_of_batches_threshold",
+                "must not exceed max_header_num_of_batches",
+            );
+        }
+
+        match self.epoch_policy {
+            EpochPolicy::FixedDuration(duration) if duration == Duration::ZERO => {
+                invalid("epoch_policy", "the epoch duration must not be zero")
+            }
+            EpochPolicy::FixedCommits(0) => {
+                invalid("epoch_policy", "the number of commits must not be zero")
+            }
+            _ => (),
+        }
+
+        let admin = &self.network_admin_server;
+        if admin.primary_address() == admin.worker_address(0) {
+            invalid(
+                "network_admin_server",
+                "the primary and worker admin servers use the same address",
+            );
+        }
+        if matches!(&admin.auth, Some(AdminAuthParameters::BearerToken(token)) if token.is_empty())
+        {
+            invalid("network_admin_server", "the bearer token must not be empty");
+        }
+        let unix_socket =
+            |bind: &Option<AdminBindAddress>| matches!(bind, Some(AdminBindAddress::UnixSocket(_)));
+        if matches!(&admin.auth, Some(AdminAuthParameters::MutualTls(_)))
+            && (unix_socket(&admin.primary_bind) || unix_socket(&admin.worker_bind))
+        {
+            invalid(
+                "network_admin_server",
+                "mutual TLS is not supported on unix sockets",
+            );
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+/// The keys of a json object, including the duplicated ones (which are otherwise silently
+/// overwritten when deserializing the object into a map).
+struct ObjectKeys(Vec<String>);
+
+impl<'de> Deserialize<'de> for ObjectKeys {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct KeysVisitor;
+
+        impl<'de> Visitor<'de> for KeysVisitor {
+            type Value = ObjectKeys;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+                formatter.write_str("a map")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut keys = Vec::new();
+                while let Some((key, IgnoredAny)) = map.next_entry::<String, IgnoredAny>()? {
+                    keys.push(key);
+                }
+                Ok(ObjectKeys(keys))
+            }
+        }
+
+        deserializer.deserialize_map(KeysVisitor)
+    }
+}
+
+#[derive(Deserialize)]
+struct AuthorityKeys {
+    authorities: Option<ObjectKeys>,
+    workers: Option<ObjectKeys>,
+}
+
+/// Reports the authorities listed more than once in a committee or worker cache json file.
+pub fn check_duplicate_authorities(path: &str) -> Result<(), Vec<ConfigError>> {
+    let keys = fs::read(path)
+        .map_err(|e| e.to_string())
+        .and_then(|data| serde_json::from_slice::<AuthorityKeys>(&data).map_err(|e| e.to_string()))
+        .map_err(|message| {
+            vec![ConfigError::ImportError {
+                file: path.to_string(),
+                message,
+            }]
+        })?;
+
+    let mut seen = HashMap::new();
+    let errors: Vec<_> = keys
+        .authorities
+        .into_iter()
+        .chain(keys.workers)
+        .flat_map(|keys| keys.0)
+        .filter_map(|key| {
+            let count = seen.entry(key.clone()).or_insert(0);
+            *count += 1;
+            (*count == 2).then(|| ConfigError::DuplicateKey(format!("authority {key}")))
+        })
+        .collect();
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
@@ -19,10 +19,14 @@
 // 2. Review, accept or reject changes.
 
 use config::{
-    ConsensusAPIGrpcParameters, Import, NetworkAdminServerParameters, Parameters,
-    PrometheusMetricsParameters, Stake,
+    check_duplicate_authorities, AdminAddress, AdminAuthParameters, AdminBindAddress,
+    AdminTlsParameters, AuthorityUpdate, Committee, CommitteeUpdate, CompactionStyle, Compression,
+    ConfigError, ConsensusAPIGrpcParameters, EpochPolicy, Import, MemoryBudget,
+    NetworkAdminServerParameters, Parameters, PrometheusMetricsParameters, ProtocolConfig,
+    QuorumPolicy, Stake, SyncPolicy, WorkerCacheUpdate, WorkerIndexUpdate,
 };
-use crypto::PublicKey;
+use crypto::{PublicKey, SignatureScheme};
+use fastcrypto::traits::EncodeDecodeBase64;
 use insta::assert_json_snapshot;
 use multiaddr::Multiaddr;
 use narwhal_config as config;
@@ -31,6 +35,8 @@ use std::{
     collections::{BTreeMap, HashMap},
     fs::File,
     io::Write,
+    num::NonZeroUsize,
+    time::Duration,
 };
 use tempfile::tempdir;
 use test_utils::CommitteeFixture;
@@ -56,6 +62,115 @@ fn leader_election_rotates_through_all() {
     assert!(leader_counts_stepping_by_2.values().all(|v| *v >= 20));
 }
 
+#[test]
+fn round_robin_leader_election_test() {
+    let fixture = CommitteeFixture::builder()
+        .protocol_config(ProtocolConfig {
+            version: 2,
+            ..Default::default()
+        })
+        .build();
+    let committee = fixture.committee();
+    let keys = committee.keys();
+    // Each authority leads in turn, on even rounds.
+    for (i, key) in keys.iter().enumerate() {
+        assert_eq!(committee.leader(2 * i as u64), **key);
+        assert_eq!(committee.leader(2 * (i + keys.len()) as u64), **key);
+    }
+}
+
+#[test]
+fn protocol_config_test() {
+    let fixture = CommitteeFixture::builder().build();
+    let committee = fixture.committee();
+    assert_eq!(committee.protocol_config, ProtocolConfig::default());
+    assert!(!committee.protocol_config.round_robin_leaders());
+    assert_eq!(committee.protocol_config.message_version(), 1);
+    assert!(!committee.protocol_config.transaction_metadata());
+    assert!(ProtocolConfig {
+        version: 3,
+        ..Default::default()
+    }
+    .transaction_metadata());
+
+    // committees that predate protocol configs run the first version
+    let mut json = serde_json::to_value(&committee).unwrap();
+    json.as_object_mut().unwrap().remove("protocol_config");
+    let imported: Committee = serde_json::from_value(json.clone()).unwrap();
+    assert_eq!(imported.protocol_config.version, 1);
+
+    // committees that predate signature schemes run the scheme of Sui
+    json["protocol_config"] = serde_json::json!({ "version": 1 });
+    let imported = serde_json::from_value::<Committee>(json.clone());
+    if crypto::SIGNATURE_SCHEME == SignatureScheme::Bls12381MinSig {
+        assert_eq!(imported.unwrap().protocol_config, ProtocolConfig::default());
+    } else {
+        assert!(imported.is_err());
+    }
+
+    // unknown versions are rejected when loading
+    json["protocol_config"] =
+        serde_json::json!({ "version": ProtocolConfig::MAX_SUPPORTED_VERSION + 1 });
+    assert!(serde_json::from_value::<Committee>(json.clone()).is_err());
+
+    // as are the signature schemes this node is not built with
+    let other_scheme = if crypto::SIGNATURE_SCHEME == SignatureScheme::Ed25519 {
+        SignatureScheme::Bls12381MinSig
+    } else {
+        SignatureScheme::Ed25519
+    };
+    json["protocol_config"] = serde_json::json!({ "version": 1, "signature_scheme": other_scheme });
+    assert!(serde_json::from_value::<Committee>(json).is_err());
+
+    // the version only changes with the epoch
+    let upgrade = ProtocolConfig {
+        version: 2,
+        ..Default::default()
+    };
+    let update = CommitteeUpdate {
+        epoch: 0,
+        changes: vec![],
+        protocol_config: Some(upgrade),
+    };
+    let errors = committee.apply(&update).unwrap_err();
+    assert!(matches!(
+        errors[..],
+        [config::CommitteeUpdateError::ProtocolChangeWithinEpoch(0)]
+    ));
+
+    let update = CommitteeUpdate {
+        epoch: 1,
+        changes: vec![],
+        protocol_config: Some(upgrade),
+    };
+    let new_committee = committee.apply(&update).unwrap();
+    assert_eq!(new_committee.protocol_config, upgrade);
+    // and is kept by later updates
+    let update = CommitteeUpdate {
+        epoch: 2,
+        changes: vec![],
+        protocol_config: None,
+    };
+    assert_eq!(
+        new_committee.apply(&update).unwrap().protocol_config,
+        upgrade
+    );
+
+    let update = CommitteeUpdate {
+        epoch: 1,
+        changes: vec![],
+        protocol_config: Some(ProtocolConfig {
+            version: 0,
+            ..Default::default()
+        }),
+    };
+    let errors = committee.apply(&update).unwrap_err();
+    assert!(matches!(
+        errors[..],
+        [config::CommitteeUpdateError::UnsupportedProtocolVersion(0)]
+    ));
+}
+
 #[test]
 fn update_primary_network_info_test() {
     let fixture = CommitteeFixture::builder().build();
@@ -134,10 +249,543 @@ fn update_primary_network_info_test() {
     }
 }
 
+#[test]
+fn committee_apply_update_test() {
+    let mut fixture = CommitteeFixture::builder().build();
+    let committee = fixture.committee();
+    let worker_cache = fixture.worker_cache();
+
+    let removed = committee.keys()[0].clone();
+    fixture.add_authority();
+    fixture.bump_epoch();
+    let added = fixture.authorities().last().unwrap();
+
+    let mut updated_authority = committee.authorities.values().nth(1).unwrap().clone();
+    updated_authority.stake += 1;
+    let updated = committee.keys()[1].clone();
+
+    let update = CommitteeUpdate {
+        epoch: 1,
+        changes: vec![
+            AuthorityUpdate::Remove(removed.clone()),
+            AuthorityUpdate::Add(added.public_key(), added.authority()),
+            AuthorityUpdate::Update(updated.clone(), updated_authority.clone()),
+        ],
+        protocol_config: None,
+    };
+    let new_committee = committee.apply(&update).unwrap();
+    assert_eq!(new_committee.epoch(), 1);
+    assert_eq!(new_committee.size(), committee.size());
+    assert!(!new_committee.authorities.contains_key(&removed));
+    assert_eq!(
+        new_committee.authorities.get(&added.public_key()),
+        Some(&added.authority())
+    );
+    assert_eq!(
+        new_committee.authorities.get(&updated),
+        Some(&updated_authority)
+    );
+    // the original committee is left untouched
+    assert!(committee.authorities.contains_key(&removed));
+
+    let worker_update = WorkerCacheUpdate {
+        epoch: 1,
+        changes: vec![
+            WorkerIndexUpdate::Remove(removed.clone()),
+            WorkerIndexUpdate::Add(added.public_key(), added.worker_index()),
+        ],
+    };
+    let new_worker_cache = worker_cache.apply(&worker_update).unwrap();
+    assert_eq!(new_worker_cache.epoch(), 1);
+    assert!(!new_worker_cache.workers.contains_key(&removed));
+    assert!(new_worker_cache.workers.contains_key(&added.public_key()));
+}
+
+#[test]
+fn committee_apply_invalid_update_test() {
+    let fixture = CommitteeFixture::builder().build();
+    let mut committee = fixture.committee();
+    committee.epoch = 2;
+    let existing = fixture.authorities().next().unwrap();
+    let other_fixture = CommitteeFixture::builder().build();
+    let unknown = other_fixture.authorities().next().unwrap();
+
+    let update = CommitteeUpdate {
+        epoch: 1,
+        changes: vec![
+            AuthorityUpdate::Add(existing.public_key(), existing.authority()),
+            AuthorityUpdate::Remove(unknown.public_key()),
+            AuthorityUpdate::Update(unknown.public_key(), unknown.authority()),
+        ],
+        protocol_config: None,
+    };
+    let errors = committee.apply(&update).unwrap_err();
+    assert_eq!(errors.len(), 4);
+    assert!(matches!(
+        errors[0],
+        config::CommitteeUpdateError::StaleEpoch {
+            current: 2,
+            update: 1
+        }
+    ));
+    assert!(matches!(
+        errors[1],
+        config::CommitteeUpdateError::AlreadyInCommittee(_)
+    ));
+    assert!(matches!(
+        errors[2],
+        config::CommitteeUpdateError::NotInCommittee(_)
+    ));
+    assert!(matches!(
+        errors[3],
+        config::CommitteeUpdateError::NotInCommittee(_)
+    ));
+
+    // removing everyone is rejected
+    let update = CommitteeUpdate {
+        epoch: 2,
+        changes: committee
+            .keys()
+            .into_iter()
+            .map(|key| AuthorityUpdate::Remove(key.clone()))
+            .collect(),
+        protocol_config: None,
+    };
+    let errors = committee.apply(&update).unwrap_err();
+    assert!(matches!(
+        errors[..],
+        [config::CommitteeUpdateError::EmptyCommittee]
+    ));
+
+    let worker_cache = fixture.worker_cache();
+    let worker_update = WorkerCacheUpdate {
+        epoch: 0,
+        changes: vec![
+            WorkerIndexUpdate::Add(existing.public_key(), existing.worker_index()),
+            WorkerIndexUpdate::Remove(unknown.public_key()),
+        ],
+    };
+    let errors = worker_cache.apply(&worker_update).unwrap_err();
+    assert!(matches!(
+        errors[..],
+        [
+            config::CommitteeUpdateError::AlreadyInWorkerCache(_),
+            config::CommitteeUpdateError::NotInWorkerCache(_)
+        ]
+    ));
+}
+
+#[test]
+fn default_quorum_policy_thresholds() {
+    let policy = QuorumPolicy::default();
+    assert!(policy.validate().is_ok());
+    for total_stake in 1..100 {
+        // N = 3f + 1 + k (0 <= k < 3): quorum is N - f and validity is f + 1
+        let f = (total_stake - 1) / 3;
+        assert_eq!(policy.quorum_threshold(total_stake), total_stake - f);
+        assert_eq!(policy.validity_threshold(total_stake), f + 1);
+    }
+}
+
+#[test]
+fn custom_quorum_policy_thresholds() {
+    let policy = QuorumPolicy {
+        quorum_numerator: 3,
+        validity_numerator: 1,
+        denominator: 4,
+    };
+    assert!(policy.validate().is_ok());
+    assert_eq!(policy.quorum_threshold(100), 76);
+    assert_eq!(policy.validity_threshold(100), 25);
+    assert_eq!(policy.validity_threshold(101), 26);
+
+    let fixture = CommitteeFixture::builder().quorum_policy(policy).build();
+    let committee = fixture.committee();
+    assert_eq!(committee.total_stake(), 4);
+    assert_eq!(committee.quorum_threshold(), 4);
+    assert!(!committee.reached_quorum(3));
+    assert!(committee.reached_quorum(4));
+    assert!(committee.reached_validity(1));
+}
+
+#[test]
+fn invalid_quorum_policy_is_rejected() {
+    let policies = [
+        (2, 1, 0),
+        (2, 0, 3),
+        (3, 1, 3),
+        (1, 2, 3),
+        // two 1/2 quorums do not necessarily intersect
+        (1, 1, 2),
+    ];
+    for (quorum_numerator, validity_numerator, denominator) in policies {
+        let policy = QuorumPolicy {
+            quorum_numerator,
+            validity_numerator,
+            denominator,
+        };
+        assert!(
+            matches!(
+                policy.validate(),
+                Err(config::ConfigError::InvalidQuorumPolicy(_))
+            ),
+            "{policy:?} should be invalid"
+        );
+    }
+
+    // An invalid policy makes the committee fail to load.
+    let fixture = CommitteeFixture::builder().build();
+    let mut committee = serde_json::to_value(fixture.committee()).unwrap();
+    committee["quorum_policy"] = serde_json::json!({
+        "quorum_numerator": 1,
+        "validity_numerator": 1,
+        "denominator": 2
+    });
+    assert!(serde_json::from_value::<Committee>(committee.clone()).is_err());
+
+    // A committee without a policy uses the default one.
+    committee.as_object_mut().unwrap().remove("quorum_policy");
+    let committee: Committee = serde_json::from_value(committee).unwrap();
+    assert_eq!(committee.quorum_policy, QuorumPolicy::default());
+}
+
+#[test]
+fn committee_validation_test() {
+    let fixture = CommitteeFixture::builder()
+        .committee_size(NonZeroUsize::new(5).unwrap())
+        .randomize_ports(true)
+        .build();
+    let committee = fixture.committee();
+    let worker_cache = fixture.worker_cache();
+    committee.validate().unwrap();
+    worker_cache.validate(&committee).unwrap();
+
+    // Two primaries sharing an address, an unroutable address, and a zero stake.
+    let mut invalid = committee.clone();
+    let mut authorities = invalid.authorities.values_mut();
+    let first = authorities.next().unwrap().primary_address.clone();
+    authorities.next().unwrap().primary_address = first;
+    authorities.next().unwrap().primary_address = "/ip4/0.0.0.0/udp/1234".parse().unwrap();
+    authorities.next().unwrap().stake = 0;
+    let errors = invalid.validate().unwrap_err();
+    assert_eq!(errors.len(), 3, "{errors:?}");
+    assert!(matches!(errors[0], ConfigError::AddressCollision { .. }));
+    assert!(matches!(errors[1], ConfigError::UnroutableAddress { .. }));
+    assert!(matches!(errors[2], ConfigError::InvalidStake(_)));
+
+    // A single authority able to prevent quorums.
+    let mut invalid = committee.clone();
+    invalid.authorities.values_mut().next().unwrap().stake = 2;
+    let errors = invalid.validate().unwrap_err();
+    assert_eq!(errors.len(), 1, "{errors:?}");
+    assert!(matches!(errors[0], ConfigError::InvalidStake(_)));
+
+    // Stakes overflowing the total stake.
+    let mut invalid = committee.clone();
+    for authority in invalid.authorities.values_mut() {
+        authority.stake = Stake::MAX / 2;
+    }
+    assert_eq!(invalid.total_stake(), Stake::MAX);
+    let errors = invalid.validate().unwrap_err();
+    assert_eq!(errors.len(), 1, "{errors:?}");
+    assert!(matches!(errors[0], ConfigError::InvalidStake(_)));
+
+    // A primary and a worker sharing a network key, and an epoch mismatch.
+    let mut invalid = worker_cache.clone();
+    invalid.epoch = committee.epoch() + 1;
+    let (name, index) = invalid.workers.iter_mut().next().unwrap();
+    index.0.values_mut().next().unwrap().name = committee.network_key(name).unwrap();
+    let errors = invalid.validate(&committee).unwrap_err();
+    assert_eq!(errors.len(), 2, "{errors:?}");
+    assert!(matches!(errors[0], ConfigError::DuplicateKey(_)));
+    assert!(matches!(errors[1], ConfigError::EpochMismatch { .. }));
+
+    // An authority without workers.
+    let mut invalid = worker_cache;
+    let name = invalid.workers.keys().next().unwrap().clone();
+    invalid.workers.remove(&name);
+    let errors = invalid.validate(&committee).unwrap_err();
+    assert_eq!(errors.len(), 1, "{errors:?}");
+    assert!(matches!(errors[0], ConfigError::NotInWorkerCache(_)));
+}
+
+#[test]
+fn parameters_validation_test() {
+    let parameters = Parameters {
+        network_admin_server: NetworkAdminServerParameters {
+            primary_network_admin_server_port: 1234,
+            worker_network_admin_server_base_port: 5678,
+            auth: None,
+            primary_bind: None,
+            worker_bind: None,
+        },
+        ..Parameters::default()
+    };
+    parameters.validate().unwrap();
+
+    let invalid = Parameters {
+        batch_size: 0,
+        max_batch_delay: Duration::ZERO,
+        header_num_of_batches_threshold: 2_000,
+        epoch_policy: EpochPolicy::FixedCommits(0),
+        network_admin_server: NetworkAdminServerParameters {
+            auth: Some(AdminAuthParameters::BearerToken(String::new())),
+            ..parameters.network_admin_server.clone()
+        },
+        ..parameters.clone()
+    };
+    let errors = invalid.validate().unwrap_err();
+    assert_eq!(errors.len(), 5, "{errors:?}");
+    assert!(errors
+        .iter()
+        .all(|e| matches!(e, ConfigError::InvalidParameter { .. })));
+
+    // The admin servers can't be served over TLS on unix sockets.
+    let tls = AdminTlsParameters {
+        ca_cert: "ca.pem".into(),
+        server_cert: "server.pem".into(),
+        server_key: "server.key".into(),
+        client_cert: "client.pem".into(),
+        client_key: "client.key".into(),
+    };
+    let invalid = Parameters {
+        network_admin_server: NetworkAdminServerParameters {
+            auth: Some(AdminAuthParameters::MutualTls(tls)),
+            worker_bind: Some(AdminBindAddress::UnixSocket("/run/admin.sock".into())),
+            ..parameters.network_admin_server.clone()
+        },
+        ..parameters
+    };
+    assert_eq!(invalid.validate().unwrap_err().len(), 1);
+}
+
+#[test]
+fn admin_addresses_test() {
+    let mut parameters = NetworkAdminServerParameters {
+        primary_network_admin_server_port: 1234,
+        worker_network_admin_server_base_port: 5678,
+        auth: None,
+        primary_bind: None,
+        worker_bind: None,
+    };
+    assert_eq!(
+        parameters.primary_address(),
+        AdminAddress::Tcp("127.0.0.1:1234".parse().unwrap())
+    );
+    assert_eq!(
+        parameters.worker_address(2),
+        AdminAddress::Tcp("127.0.0.1:5680".parse().unwrap())
+    );
+
+    parameters.primary_bind = Some(AdminBindAddress::Interface("10.0.0.1".parse().unwrap()));
+    parameters.worker_bind = Some(AdminBindAddress::UnixSocket("/run/worker.sock".into()));
+    assert_eq!(
+        parameters.primary_address(),
+        AdminAddress::Tcp("10.0.0.1:1234".parse().unwrap())
+    );
+    assert_eq!(
+        parameters.worker_address(2),
+        AdminAddress::Unix("/run/worker.sock.2".into())
+    );
+    assert_eq!(
+        parameters.worker_address(2).to_string(),
+        "unix:/run/worker.sock.2"
+    );
+
+    let parsed: NetworkAdminServerParameters = serde_json::from_str(
+        r#"{
+            "primary_network_admin_server_port": 1234,
+            "worker_network_admin_server_base_port": 5678,
+            "primary_bind": { "interface": "10.0.0.1" },
+            "worker_bind": { "unix_socket": "/run/worker.sock" }
+        }"#,
+    )
+    .unwrap();
+    assert_eq!(parsed.primary_bind, parameters.primary_bind);
+    assert_eq!(parsed.worker_bind, parameters.worker_bind);
+}
+
+#[test]
+fn duplicate_authorities_test() {
+    let fixture = CommitteeFixture::builder().randomize_ports(true).build();
+    let committee = serde_json::to_string(&fixture.committee()).unwrap();
+    let name = fixture.authorities().next().unwrap().public_key();
+    let authority = serde_json::to_string(&fixture.committee().authorities[&name]).unwrap();
+
+    let dir = tempdir().expect("Couldn't create tempdir");
+    let path = dir.path().join("committee.json");
+    std::fs::write(&path, &committee).unwrap();
+    check_duplicate_authorities(path.to_str().unwrap()).unwrap();
+
+    // The same authority listed twice is silently merged by `Import`.
+    let duplicated = committee.replacen(
+        r#""authorities":{"#,
+        &format!(r#""authorities":{{"{}":{authority},"#, name.encode_base64()),
+        1,
+    );
+    std::fs::write(&path, duplicated).unwrap();
+    let errors = check_duplicate_authorities(path.to_str().unwrap()).unwrap_err();
+    assert_eq!(errors.len(), 1, "{errors:?}");
+    assert!(matches!(errors[0], ConfigError::DuplicateKey(_)));
+}
+
+#[test]
+fn memory_budget_test() {
+    // Unspecified limits keep their default.
+    let budget: MemoryBudget = serde_json::from_str(
+        r#"{"channel_capacity": 100, "channel_capacities": {"tx_headers": 10}}"#,
+    )
+    .unwrap();
+    assert_eq!(budget.channel_capacity("tx_headers"), 10);
+    assert_eq!(budget.channel_capacity("tx_parents"), 100);
+    assert_eq!(
+        budget.max_pending_payloads,
+        MemoryBudget::default().max_pending_payloads
+    );
+}
+
+#[test]
+fn worker_overrides_test() {
+    let parameters: Parameters = serde_json::from_value(serde_json::json!({
+        "header_num_of_batches_threshold": 32,
+        "max_header_num_of_batches": 1000,
+        "max_header_delay": "100ms",
+        "gc_depth": 50,
+        "sync_retry_delay": "5s",
+        "sync_retry_nodes": 3,
+        "batch_size": 500000,
+        "max_batch_delay": "100ms",
+        "block_synchronizer": {},
+        "consensus_api_grpc": {
+            "socket_addr": "/ip4/127.0.0.1/tcp/0/http",
+            "get_collections_timeout": "5_000ms",
+            "remove_collections_timeout": "5_000ms"
+        },
+        "max_concurrent_requests": 500000,
+        "prometheus_metrics": {
+            "socket_addr": "/ip4/127.0.0.1/tcp/0/http"
+        },
+        "network_admin_server": {
+            "primary_network_admin_server_port": 0,
+            "worker_network_admin_server_base_port": 0
+        },
+        "worker_overrides": {
+            "1": { "batch_size": 1000, "max_batch_delay": "10ms" }
+        }
+    }))
+    .unwrap();
+
+    // Workers without overrides use the node-wide parameters.
+    let worker = parameters.for_worker(0);
+    assert_eq!(worker.batch_size, 500_000);
+    assert_eq!(worker.max_batch_delay, Duration::from_millis(100));
+
+    // Overridden values take precedence, the others fall back to the node-wide ones.
+    let worker = parameters.for_worker(1);
+    assert_eq!(worker.batch_size, 1_000);
+    assert_eq!(worker.max_batch_delay, Duration::from_millis(10));
+    assert_eq!(worker.max_concurrent_requests, 500_000);
+}
+
+#[test]
+fn storage_parameters_test() {
+    let parameters: Parameters = serde_json::from_value(serde_json::json!({
+        "header_num_of_batches_threshold": 32,
+        "max_header_num_of_batches": 1000,
+        "max_header_delay": "100ms",
+        "gc_depth": 50,
+        "sync_retry_delay": "5s",
+        "sync_retry_nodes": 3,
+        "batch_size": 500000,
+        "max_batch_delay": "100ms",
+        "block_synchronizer": {},
+        "consensus_api_grpc": {
+            "socket_addr": "/ip4/127.0.0.1/tcp/0/http",
+            "get_collections_timeout": "5_000ms",
+            "remove_collections_timeout": "5_000ms"
+        },
+        "max_concurrent_requests": 500000,
+        "prometheus_metrics": {
+            "socket_addr": "/ip4/127.0.0.1/tcp/0/http"
+        },
+        "network_admin_server": {
+            "primary_network_admin_server_port": 1234,
+            "worker_network_admin_server_base_port": 5678
+        },
+        "storage": {
+            "block_cache_size": 1073741824,
+            "column_family_defaults": {
+                "compression": "Lz4",
+                "write_buffer_size": 67108864,
+                "coalesce_writes": true
+            },
+            "column_families": {
+                "batches": { "compression": "Zstd", "compaction_style": "Universal" },
+                "last_committed": { "coalesce_writes": false, "sync": "Always" }
+            }
+        }
+    }))
+    .unwrap();
+    parameters.validate().unwrap();
+
+    let storage = &parameters.storage;
+    assert_eq!(storage.block_cache_size, Some(1 << 30));
+    assert_eq!(storage.max_total_wal_size, None);
+
+    // Column families without options use the defaults.
+    let headers = storage.for_column_family("headers");
+    assert_eq!(headers, storage.column_family_defaults);
+
+    // Set options take precedence, the others fall back to the defaults.
+    let batches = storage.for_column_family("batches");
+    assert_eq!(batches.compression, Some(Compression::Zstd));
+    assert_eq!(batches.compaction_style, Some(CompactionStyle::Universal));
+    assert_eq!(batches.write_buffer_size, Some(64 << 20));
+    assert_eq!(batches.max_write_buffer_number, None);
+    assert_eq!(batches.coalesce_writes, Some(true));
+    let last_committed = storage.for_column_family("last_committed");
+    assert_eq!(last_committed.coalesce_writes, Some(false));
+    assert_eq!(last_committed.sync, Some(SyncPolicy::Always));
+
+    let mut invalid = parameters;
+    invalid.storage.block_cache_size = Some(0);
+    invalid
+        .storage
+        .column_families
+        .get_mut("batches")
+        .unwrap()
+        .max_write_buffer_number = Some(0);
+    let errors = invalid.validate().unwrap_err();
+    assert_eq!(errors.len(), 2, "{errors:?}");
+}
+
+#[test]
+fn epoch_policy_test() {
+    // The default policy never ends the epoch.
+    assert_eq!(Parameters::default().epoch_policy, EpochPolicy::Manual);
+    assert!(!EpochPolicy::Manual.is_end_of_epoch(u64::MAX, 0, u64::MAX));
+
+    let policy = EpochPolicy::FixedCommits(10);
+    assert!(!policy.is_end_of_epoch(9, 0, 0));
+    assert!(policy.is_end_of_epoch(10, 0, 0));
+
+    let policy = EpochPolicy::FixedDuration(Duration::from_secs(60));
+    assert!(!policy.is_end_of_epoch(100, 1_000, 60_999));
+    assert!(policy.is_end_of_epoch(1, 1_000, 61_000));
+    // A leader older than the start of the epoch does not end it.
+    assert!(!policy.is_end_of_epoch(1, 1_000, 0));
+
+    // Durations use the same human friendly format as the other parameters.
+    let policy: EpochPolicy = serde_json::from_str(r#"{"FixedDuration": "2_000ms"}"#).unwrap();
+    assert_eq!(policy, EpochPolicy::FixedDuration(Duration::from_secs(2)));
+    let policy: EpochPolicy = serde_json::from_str(r#"{"FixedCommits": 100}"#).unwrap();
+    assert_eq!(policy, EpochPolicy::FixedCommits(100));
+}
+
 // If one or both of the parameters_xx_matches() tests are broken by a change, the following additional places are
 // highly likely needed to be updated as well:
 // 1. Docker/validators/parameters.json for starting Narwhal cluster with Docker Compose.
-// 2. benchmark/fabfile.py for benchmarking a Narwhal cluster locally.
+// 2. orchestrator/topologies/*.json for benchmarking a Narwhal cluster locally.
 // 3. Sui configurations & snapshot tests when upgrading Narwhal in Sui to include the change.
 
 #[test]
@@ -157,6 +805,9 @@ fn parameters_snapshot_matches() {
     let network_admin_server_parameters = NetworkAdminServerParameters {
         primary_network_admin_server_port: 1234,
         worker_network_admin_server_base_port: 5678,
+        auth: None,
+        primary_bind: None,
+        worker_bind: None,
     };
 
     let parameters = Parameters {
@@ -132,7 +132,7 @@ impl ConsensusProtocol for Bullshark {
         // If it is the case, we can commit the leader. But first, we need to recursively go back to
         // the last committed leader, and commit all preceding leaders in the right order. Committing
         // a leader block means committing all its dependencies.
-        if stake < self.committee.validity_threshold() {
+        if !self.committee.reached_validity(stake) {
             debug!("Leader {:?} does not have enough support", leader);
             return Ok(Vec::new());
         }
@@ -168,8 +168,10 @@ impl ConsensusProtocol for Bullshark {
             };
 
             // Persist the update.
+            fail::fail_point!("consensus-before-store-commit");
             self.store
                 .write_consensus_state(&state.last_committed, &sub_dag)?;
+            fail::fail_point!("consensus-after-store-commit");
             debug!("Store commit index:{},", &next_sub_dag_index,);
 
             // Increase the global consensus index.
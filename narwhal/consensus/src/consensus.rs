@@ -9,6 +9,7 @@ use config::Committee;
 use crypto::PublicKey;
 use fastcrypto::hash::Hash;
 use mysten_metrics::spawn_logged_monitored_task;
+use network::trace_id;
 use std::{
     cmp::{max, Ordering},
     collections::HashMap,
@@ -16,10 +17,10 @@ use std::{
 };
 use storage::CertificateStore;
 use tokio::{sync::watch, task::JoinHandle};
-use tracing::{debug, info, instrument};
+use tracing::{debug, info, info_span, instrument, Instrument};
 use types::{
-    metered_channel, Certificate, CertificateDigest, CommittedSubDag, ConsensusStore,
-    ReconfigureNotification, Round, StoreResult, Timestamp,
+    metered_channel, AuditEvent, AuditLog, Certificate, CertificateDigest, CommittedSubDag,
+    ConsensusStore, ReconfigureNotification, Round, StoreResult, Timestamp,
 };
 
 #[cfg(test)]
@@ -238,6 +239,9 @@ pub struct Consensus<ConsensusProtocol> {
 
     /// Inner state
     state: ConsensusState,
+
+    /// Records the committed sub-dags.
+    audit_log: AuditLog,
 }
 
 impl<Protocol> Consensus<Protocol>
@@ -257,6 +261,39 @@ where
         protocol: Protocol,
         metrics: Arc<ConsensusMetrics>,
         gc_depth: Round,
+    ) -> JoinHandle<()> {
+        Self::spawn_with_audit_log(
+            committee,
+            store,
+            cert_store,
+            rx_reconfigure,
+            rx_new_certificates,
+            tx_committed_certificates,
+            tx_consensus_round_updates,
+            tx_sequence,
+            protocol,
+            metrics,
+            gc_depth,
+            AuditLog::default(),
+        )
+    }
+
+    /// Spawns the consensus as [`Consensus::spawn`] does, recording the committed sub-dags in
+    /// `audit_log`.
+    #[must_use]
+    pub fn spawn_with_audit_log(
+        committee: Committee,
+        store: Arc<ConsensusStore>,
+        cert_store: CertificateStore,
+        rx_reconfigure: watch::Receiver<ReconfigureNotification>,
+        rx_new_certificates: metered_channel::Receiver<Certificate>,
+        tx_committed_certificates: metered_channel::Sender<(Round, Vec<Certificate>)>,
+        tx_consensus_round_updates: watch::Sender<Round>,
+        tx_sequence: metered_channel::Sender<CommittedSubDag>,
+        protocol: Protocol,
+        metrics: Arc<ConsensusMetrics>,
+        gc_depth: Round,
+        audit_log: AuditLog,
     ) -> JoinHandle<()> {
         // The consensus state (everything else is immutable).
         let genesis = Certificate::genesis(&committee);
@@ -284,6 +321,7 @@ where
             protocol,
             metrics,
             state,
+            audit_log,
         };
 
         spawn_logged_monitored_task!(s.run(), "Consensus", INFO)
@@ -377,9 +415,19 @@ where
                             commited_certificates.push(certificate.clone());
                         }
 
+                        // The commit joins the traces of the committed headers.
+                        let span = info_span!(
+                            "commit",
+                            sub_dag_index = committed_sub_dag.sub_dag_index,
+                            leader_digest = %committed_sub_dag.leader.digest(),
+                            certificates = committed_sub_dag.certificates.len()
+                        );
+                        trace_id::join_certificates(&span, &committed_sub_dag.certificates);
+                        self.audit_log.record(AuditEvent::committed(&committed_sub_dag));
+
                         // NOTE: The size of the sub-dag can be arbitrarily large (depending on the network condition
                         // and Byzantine leaders).
-                        if let Err(e) = self.tx_sequence.send(committed_sub_dag).await {
+                        if let Err(e) = self.tx_sequence.send(committed_sub_dag).instrument(span).await {
                             tracing::warn!("Failed to output sub dag: {e}");
                         }
                     }
@@ -6,6 +6,7 @@ use prometheus::{
     register_int_gauge_with_registry, Histogram, IntCounter, IntCounterVec, IntGauge, IntGaugeVec,
     Registry,
 };
+use types::metered_channel::ChannelCollector;
 
 const LATENCY_SEC_BUCKETS: &[f64] = &[
     0.05, 0.1, 0.2, 0.5, 1.0, 2.0, 3.0, 4.0, 5.0, 8.0, 10.0, 15.0, 20.0, 30.0, 50.0, 100.0, 200.0,
@@ -135,18 +136,35 @@ pub struct ChannelMetrics {
     /// * tx_new_certificates where the newly created certificates are sent
     /// from `primary::Core` to `Consensus`
     pub tx_sequence: IntGauge,
+    /// total received on the channel from the `Consensus` to `SubscriberHandler`.
+    pub tx_sequence_total: IntCounter,
+    /// exports the occupancy and the total above, labelled by channel
+    collector: ChannelCollector,
 }
 
 impl ChannelMetrics {
     pub fn new(registry: &Registry) -> Self {
-        Self {
+        let metrics = Self {
             tx_sequence: register_int_gauge_with_registry!(
                 "tx_sequence",
                 "occupancy of the channel from the `Consensus` to `SubscriberHandler`",
                 registry
             )
             .unwrap(),
-        }
+            tx_sequence_total: register_int_counter_with_registry!(
+                "tx_sequence_total",
+                "total received on the channel from the `Consensus` to `SubscriberHandler`",
+                registry
+            )
+            .unwrap(),
+            collector: ChannelCollector::new("consensus", registry),
+        };
+        metrics.collector.add(
+            "tx_sequence",
+            &metrics.tx_sequence,
+            Some(&metrics.tx_sequence_total),
+        );
+        metrics
     }
 }
 
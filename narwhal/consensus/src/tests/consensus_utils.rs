@@ -1,26 +1,23 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
-use crypto::PublicKey;
 use std::sync::Arc;
 use storage::CertificateStore;
-use store::{reopen, rocks, rocks::DBMap};
-use types::{
-    Certificate, CertificateDigest, CommittedSubDagShell, ConsensusStore, Round, SequenceNumber,
-};
+use store::backend::{KeyValueStore, RocksDBStore, StoreMap};
+use types::ConsensusStore;
 
 pub fn make_consensus_store(store_path: &std::path::Path) -> Arc<ConsensusStore> {
     const LAST_COMMITTED_CF: &str = "last_committed";
     const SEQUENCE_CF: &str = "sequence";
 
-    let rocksdb = rocks::open_cf(store_path, None, &[LAST_COMMITTED_CF, SEQUENCE_CF])
-        .expect("Failed to create database");
-
-    let (last_committed_map, sequence_map) = reopen!(&rocksdb,
-        LAST_COMMITTED_CF;<PublicKey, Round>,
-        SEQUENCE_CF;<SequenceNumber, CommittedSubDagShell>
+    let backend: Arc<dyn KeyValueStore> = Arc::new(
+        RocksDBStore::open(store_path, &[LAST_COMMITTED_CF, SEQUENCE_CF])
+            .expect("Failed to create database"),
     );
 
-    Arc::new(ConsensusStore::new(last_committed_map, sequence_map))
+    Arc::new(ConsensusStore::new(
+        StoreMap::new(&backend, LAST_COMMITTED_CF).unwrap(),
+        StoreMap::new(&backend, SEQUENCE_CF).unwrap(),
+    ))
 }
 
 pub fn make_certificate_store(store_path: &std::path::Path) -> CertificateStore {
@@ -28,25 +25,21 @@ pub fn make_certificate_store(store_path: &std::path::Path) -> CertificateStore
     const CERTIFICATE_DIGEST_BY_ROUND_CF: &str = "certificate_digest_by_round";
     const CERTIFICATE_DIGEST_BY_ORIGIN_CF: &str = "certificate_digest_by_origin";
 
-    let rocksdb = rocks::open_cf(
-        store_path,
-        None,
-        &[
-            CERTIFICATES_CF,
-            CERTIFICATE_DIGEST_BY_ROUND_CF,
-            CERTIFICATE_DIGEST_BY_ORIGIN_CF,
-        ],
-    )
-    .expect("Failed creating database");
-
-    let (certificate_map, certificate_digest_by_round_map, certificate_digest_by_origin_map) = reopen!(&rocksdb,
-        CERTIFICATES_CF;<CertificateDigest, Certificate>,
-        CERTIFICATE_DIGEST_BY_ROUND_CF;<(Round, PublicKey), CertificateDigest>,
-        CERTIFICATE_DIGEST_BY_ORIGIN_CF;<(PublicKey, Round), CertificateDigest>);
+    let backend: Arc<dyn KeyValueStore> = Arc::new(
+        RocksDBStore::open(
+            store_path,
+            &[
+                CERTIFICATES_CF,
+                CERTIFICATE_DIGEST_BY_ROUND_CF,
+                CERTIFICATE_DIGEST_BY_ORIGIN_CF,
+            ],
+        )
+        .expect("Failed creating database"),
+    );
 
     CertificateStore::new(
-        certificate_map,
-        certificate_digest_by_round_map,
-        certificate_digest_by_origin_map,
+        StoreMap::new(&backend, CERTIFICATES_CF).unwrap(),
+        StoreMap::new(&backend, CERTIFICATE_DIGEST_BY_ROUND_CF).unwrap(),
+        StoreMap::new(&backend, CERTIFICATE_DIGEST_BY_ORIGIN_CF).unwrap(),
     )
 }
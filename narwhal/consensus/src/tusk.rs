@@ -74,7 +74,7 @@ impl ConsensusProtocol for Tusk {
         // If it is the case, we can commit the leader. But first, we need to recursively go back to
         // the last committed leader, and commit all preceding leaders in the right order. Committing
         // a leader block means committing all its dependencies.
-        if stake < self.committee.validity_threshold() {
+        if !self.committee.reached_validity(stake) {
             debug!("Leader {:?} does not have enough support", leader);
             return Ok(Vec::new());
         }
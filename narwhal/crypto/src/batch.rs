@@ -0,0 +1,201 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Batch verification of signatures, for the schemes verifying many signatures faster at once
+//! than one by one (e.g. BLS12-381, where a batch costs about as much as a single signature).
+//!
+//! A batch only tells whether all its signatures are valid: when it fails, the signatures are
+//! verified one by one to pinpoint the invalid ones.
+use crate::{AggregateSignature, PublicKey, Signature};
+use fastcrypto::traits::{AggregateAuthenticator, VerifyingKey};
+
+/// Verifies the signatures of the same `message` by different keys, e.g. the votes for a header.
+/// Returns the indices of the invalid signatures, if any.
+pub fn verify_same_message(
+    message: &[u8],
+    signatures: &[(PublicKey, Signature)],
+) -> Result<(), Vec<usize>> {
+    if signatures.is_empty() {
+        return Ok(());
+    }
+    let keys: Vec<_> = signatures.iter().map(|(key, _)| key.clone()).collect();
+    let valid = AggregateSignature::aggregate::<Signature, Vec<_>>(
+        signatures.iter().map(|(_, signature)| signature).collect(),
+    )
+    .map_or(false, |aggregate| aggregate.verify(&keys, message).is_ok());
+    if valid {
+        return Ok(());
+    }
+    fallback(
+        signatures
+            .iter()
+            .map(|(key, signature)| key.verify(message, signature).is_ok()),
+    )
+}
+
+/// A batch of (aggregate) signatures, each of its own message by its own keys, e.g. the headers
+/// and certificates fetched from a peer.
+#[derive(Default)]
+pub struct BatchVerifier {
+    /// The signatures of the batch, `None` for the ones which could not be aggregated.
+    signatures: Vec<Option<AggregateSignature>>,
+    keys: Vec<Vec<PublicKey>>,
+    messages: Vec<Vec<u8>>,
+}
+
+impl BatchVerifier {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the signature of `message` by `key`, returning its index in the batch.
+    pub fn add_signature(
+        &mut self,
+        key: PublicKey,
+        message: &[u8],
+        signature: &Signature,
+    ) -> usize {
+        let aggregate = AggregateSignature::aggregate::<Signature, Vec<_>>(vec![signature]).ok();
+        self.push(aggregate, vec![key], message)
+    }
+
+    /// Adds the aggregate signature of `message` by `keys`, returning its index in the batch.
+    pub fn add_aggregate_signature(
+        &mut self,
+        keys: Vec<PublicKey>,
+        message: &[u8],
+        signature: &AggregateSignature,
+    ) -> usize {
+        self.push(Some(signature.clone()), keys, message)
+    }
+
+    fn push(
+        &mut self,
+        signature: Option<AggregateSignature>,
+        keys: Vec<PublicKey>,
+        message: &[u8],
+    ) -> usize {
+        self.signatures.push(signature);
+        self.keys.push(keys);
+        self.messages.push(message.to_vec());
+        self.signatures.len() - 1
+    }
+
+    pub fn len(&self) -> usize {
+        self.signatures.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.signatures.is_empty()
+    }
+
+    /// Verifies the batch, returning the indices of the invalid signatures, if any.
+    pub fn verify(&self) -> Result<(), Vec<usize>> {
+        if self.is_empty() {
+            return Ok(());
+        }
+        if let Some(signatures) = self
+            .signatures
+            .iter()
+            .map(Option::as_ref)
+            .collect::<Option<Vec<_>>>()
+        {
+            let valid = AggregateSignature::batch_verify(
+                &signatures[..],
+                self.keys.iter().map(|keys| keys.iter()).collect::<Vec<_>>(),
+                &self.messages.iter().map(|m| &m[..]).collect::<Vec<_>>()[..],
+            )
+            .is_ok();
+            if valid {
+                return Ok(());
+            }
+        }
+        fallback(
+            self.signatures
+                .iter()
+                .zip(&self.keys)
+                .zip(&self.messages)
+                .map(|((signature, keys), message)| {
+                    signature
+                        .as_ref()
+                        .map_or(false, |signature| signature.verify(keys, message).is_ok())
+                }),
+        )
+    }
+}
+
+/// The indices of the signatures found invalid one by one, once their batch failed.
+fn fallback(valid: impl Iterator<Item = bool>) -> Result<(), Vec<usize>> {
+    let invalid: Vec<_> = valid
+        .enumerate()
+        .filter(|(_, valid)| !valid)
+        .map(|(index, _)| index)
+        .collect();
+    if invalid.is_empty() {
+        Ok(())
+    } else {
+        Err(invalid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::KeyPair;
+    use fastcrypto::{
+        generate_production_keypair,
+        traits::{KeyPair as _, Signer},
+    };
+
+    fn keypairs(n: usize) -> Vec<KeyPair> {
+        (0..n)
+            .map(|_| generate_production_keypair::<KeyPair>())
+            .collect()
+    }
+
+    #[test]
+    fn verify_signatures_of_same_message() {
+        let message = b"header digest";
+        let mut signatures: Vec<_> = keypairs(4)
+            .iter()
+            .map(|keypair| (keypair.public().clone(), keypair.sign(message)))
+            .collect();
+        assert_eq!(verify_same_message(message, &signatures), Ok(()));
+
+        // Swap the keys of two signatures.
+        let key = signatures[1].0.clone();
+        signatures[1].0 = signatures[3].0.clone();
+        signatures[3].0 = key;
+        assert_eq!(verify_same_message(message, &signatures), Err(vec![1, 3]));
+    }
+
+    #[test]
+    fn verify_batch_of_messages() {
+        let keypairs = keypairs(4);
+        let messages: Vec<_> = (0..4u8).map(|i| vec![i; 32]).collect();
+
+        let mut batch = BatchVerifier::new();
+        for (keypair, message) in keypairs.iter().zip(&messages) {
+            batch.add_signature(keypair.public().clone(), message, &keypair.sign(message));
+        }
+        let signatures: Vec<_> = keypairs
+            .iter()
+            .map(|keypair| keypair.sign(b"certificate"))
+            .collect();
+        let aggregate =
+            AggregateSignature::aggregate::<Signature, Vec<_>>(signatures.iter().collect())
+                .unwrap();
+        let keys: Vec<_> = keypairs
+            .iter()
+            .map(|keypair| keypair.public().clone())
+            .collect();
+        batch.add_aggregate_signature(keys.clone(), b"certificate", &aggregate);
+        assert_eq!(batch.verify(), Ok(()));
+
+        // A signature of another message.
+        let index = batch.add_signature(keys[0].clone(), b"other", &keypairs[0].sign(b"message"));
+        let aggregate_index =
+            batch.add_aggregate_signature(keys[1..].to_vec(), b"certificate", &aggregate);
+        assert_eq!(batch.verify(), Err(vec![index, aggregate_index]));
+    }
+}
@@ -12,10 +12,15 @@ use fastcrypto::{
     bls12381, ed25519,
     hash::{Blake2b256, HashFunction},
 };
+use serde::{Deserialize, Serialize};
 
 // This re-export allows using the trait-defined APIs
 pub use fastcrypto::traits;
 
+pub mod batch;
+pub mod signer;
+pub use signer::{AsyncSigner, SignatureService};
+
 ////////////////////////////////////////////////////////////////////////
 /// Type aliases selecting the signature algorithm for the code base.
 ////////////////////////////////////////////////////////////////////////
@@ -28,12 +33,80 @@ pub use fastcrypto::traits;
 // Beware: if you change those aliases to point to another scheme implementation, you will have
 // to change all four aliases to point to concrete types that work with each other. Failure to do
 // so will result in a ton of compilation errors, and worse: it will not make sense!
+//
+// The protocol scheme defaults to BLS12-381 with minimal signatures (the one of Sui). Another one
+// is selected when building with `RUSTFLAGS='--cfg narwhal_protocol_scheme="<scheme>"'`, where
+// the scheme is `bls12381-min-pk` or `ed25519`. This is a cfg rather than a cargo feature so that
+// the choice is made once for the whole build, as features are additive. The scheme must provide
+// an aggregate signature for the certificates, which rules out secp256k1 for now. The keys and
+// signatures of the schemes do not deserialize as each other, so a committee names the scheme
+// it runs (see `SignatureScheme`), and nodes built with another one refuse to run it.
+
+#[cfg(not(any(
+    narwhal_protocol_scheme = "bls12381-min-pk",
+    narwhal_protocol_scheme = "ed25519"
+)))]
+mod protocol_scheme {
+    use super::bls12381::min_sig::*;
+
+    pub const SIGNATURE_SCHEME: super::SignatureScheme = super::SignatureScheme::Bls12381MinSig;
+    pub type PublicKey = BLS12381PublicKey;
+    pub type Signature = BLS12381Signature;
+    pub type AggregateSignature = BLS12381AggregateSignature;
+    pub type PrivateKey = BLS12381PrivateKey;
+    pub type KeyPair = BLS12381KeyPair;
+}
+
+#[cfg(narwhal_protocol_scheme = "bls12381-min-pk")]
+mod protocol_scheme {
+    use super::bls12381::min_pk::*;
+
+    pub const SIGNATURE_SCHEME: super::SignatureScheme = super::SignatureScheme::Bls12381MinPk;
+    pub type PublicKey = BLS12381PublicKey;
+    pub type Signature = BLS12381Signature;
+    pub type AggregateSignature = BLS12381AggregateSignature;
+    pub type PrivateKey = BLS12381PrivateKey;
+    pub type KeyPair = BLS12381KeyPair;
+}
+
+#[cfg(narwhal_protocol_scheme = "ed25519")]
+mod protocol_scheme {
+    use super::ed25519::*;
+
+    pub const SIGNATURE_SCHEME: super::SignatureScheme = super::SignatureScheme::Ed25519;
+    pub type PublicKey = Ed25519PublicKey;
+    pub type Signature = Ed25519Signature;
+    pub type AggregateSignature = Ed25519AggregateSignature;
+    pub type PrivateKey = Ed25519PrivateKey;
+    pub type KeyPair = Ed25519KeyPair;
+}
+
+/// A protocol signature scheme the code base can be built with.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum SignatureScheme {
+    #[default]
+    #[serde(rename = "bls12381-min-sig")]
+    Bls12381MinSig,
+    #[serde(rename = "bls12381-min-pk")]
+    Bls12381MinPk,
+    #[serde(rename = "ed25519")]
+    Ed25519,
+}
+
+impl std::fmt::Display for SignatureScheme {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            SignatureScheme::Bls12381MinSig => "bls12381-min-sig",
+            SignatureScheme::Bls12381MinPk => "bls12381-min-pk",
+            SignatureScheme::Ed25519 => "ed25519",
+        };
+        f.write_str(name)
+    }
+}
 
-pub type PublicKey = bls12381::min_sig::BLS12381PublicKey;
-pub type Signature = bls12381::min_sig::BLS12381Signature;
-pub type AggregateSignature = bls12381::min_sig::BLS12381AggregateSignature;
-pub type PrivateKey = bls12381::min_sig::BLS12381PrivateKey;
-pub type KeyPair = bls12381::min_sig::BLS12381KeyPair;
+/// The protocol signature scheme the code base is built with.
+pub use protocol_scheme::SIGNATURE_SCHEME;
+pub use protocol_scheme::{AggregateSignature, KeyPair, PrivateKey, PublicKey, Signature};
 
 pub type NetworkPublicKey = ed25519::Ed25519PublicKey;
 pub type NetworkKeyPair = ed25519::Ed25519KeyPair;
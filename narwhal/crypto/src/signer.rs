@@ -0,0 +1,286 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Signing of the headers and votes by a local keypair or by a remote signing backend (e.g. a
+//! KMS or a Vault instance holding the key of the authority).
+//!
+//! The [`SignatureService`] serializes the signing requests of a primary to an [`AsyncSigner`],
+//! coalescing the requests made while a previous batch is in flight so that the round trips to a
+//! remote backend overlap with the progress of the protocol. The network handshakes are not
+//! covered: the TLS stack of anemo needs the network private key itself.
+use crate::{KeyPair, PublicKey, Signature, DIGEST_LENGTH};
+use async_trait::async_trait;
+use fastcrypto::{
+    traits::{KeyPair as _, Signer, ToFromBytes, VerifyingKey},
+    Digest,
+};
+use std::{sync::Arc, time::Duration};
+use thiserror::Error;
+use tokio::sync::{mpsc, oneshot};
+use tracing::warn;
+
+/// The largest number of digests signed at once.
+const MAX_BATCH_SIZE: usize = 256;
+
+/// The delay before retrying a batch the signer failed to sign, doubled on every attempt.
+const INITIAL_RETRY_DELAY: Duration = Duration::from_millis(50);
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(5);
+
+#[derive(Clone, Debug, Error, Eq, PartialEq)]
+pub enum SignerError {
+    #[error("Signing backend unavailable: {0}")]
+    Unavailable(String),
+
+    #[error("Signing backend returned an invalid response: {0}")]
+    InvalidResponse(String),
+}
+
+/// Signs digests with the key of an authority.
+#[async_trait]
+pub trait AsyncSigner: Send + Sync + 'static {
+    /// The public key of the signatures.
+    fn public_key(&self) -> PublicKey;
+
+    /// Signs every digest, returning their signatures in the same order.
+    async fn sign_digests(
+        &self,
+        digests: &[Digest<DIGEST_LENGTH>],
+    ) -> Result<Vec<Signature>, SignerError>;
+}
+
+#[async_trait]
+impl AsyncSigner for KeyPair {
+    fn public_key(&self) -> PublicKey {
+        self.public().clone()
+    }
+
+    async fn sign_digests(
+        &self,
+        digests: &[Digest<DIGEST_LENGTH>],
+    ) -> Result<Vec<Signature>, SignerError> {
+        Ok(digests
+            .iter()
+            .map(|digest| self.sign(digest.as_ref()))
+            .collect())
+    }
+}
+
+#[async_trait]
+impl<S: AsyncSigner + ?Sized> AsyncSigner for Box<S> {
+    fn public_key(&self) -> PublicKey {
+        (**self).public_key()
+    }
+
+    async fn sign_digests(
+        &self,
+        digests: &[Digest<DIGEST_LENGTH>],
+    ) -> Result<Vec<Signature>, SignerError> {
+        (**self).sign_digests(digests).await
+    }
+}
+
+/// A backend holding the private key `key_id` and signing on request, e.g. the client of a KMS.
+#[async_trait]
+pub trait KmsClient: Send + Sync + 'static {
+    /// Signs the messages with the key `key_id`, returning the serialized signatures in the same
+    /// order.
+    async fn sign(&self, key_id: &str, messages: &[&[u8]]) -> Result<Vec<Vec<u8>>, SignerError>;
+}
+
+/// A signer delegating to a [`KmsClient`]. The signatures returned by the backend are checked
+/// against the public key of the authority, so that a misconfigured backend can not make the
+/// primary send invalid headers and votes.
+pub struct KmsSigner<C> {
+    client: C,
+    key_id: String,
+    public_key: PublicKey,
+}
+
+impl<C: KmsClient> KmsSigner<C> {
+    pub fn new(client: C, key_id: String, public_key: PublicKey) -> Self {
+        Self {
+            client,
+            key_id,
+            public_key,
+        }
+    }
+}
+
+#[async_trait]
+impl<C: KmsClient> AsyncSigner for KmsSigner<C> {
+    fn public_key(&self) -> PublicKey {
+        self.public_key.clone()
+    }
+
+    async fn sign_digests(
+        &self,
+        digests: &[Digest<DIGEST_LENGTH>],
+    ) -> Result<Vec<Signature>, SignerError> {
+        let messages: Vec<&[u8]> = digests.iter().map(|digest| digest.as_ref()).collect();
+        let signatures = self.client.sign(&self.key_id, &messages).await?;
+        if signatures.len() != digests.len() {
+            return Err(SignerError::InvalidResponse(format!(
+                "expected {} signatures, got {}",
+                digests.len(),
+                signatures.len()
+            )));
+        }
+        digests
+            .iter()
+            .zip(signatures)
+            .map(|(digest, bytes)| {
+                let signature = Signature::from_bytes(&bytes)
+                    .map_err(|e| SignerError::InvalidResponse(e.to_string()))?;
+                self.public_key
+                    .verify(digest.as_ref(), &signature)
+                    .map_err(|_| {
+                        SignerError::InvalidResponse(format!(
+                            "invalid signature for key {}",
+                            self.key_id
+                        ))
+                    })?;
+                Ok(signature)
+            })
+            .collect()
+    }
+}
+
+type SignatureRequest = (Digest<DIGEST_LENGTH>, oneshot::Sender<Signature>);
+
+/// Serves the signature requests of a primary with an [`AsyncSigner`], in batches.
+#[derive(Clone)]
+pub struct SignatureService {
+    channel: mpsc::Sender<SignatureRequest>,
+}
+
+impl SignatureService {
+    pub fn new(signer: impl AsyncSigner) -> Self {
+        let (tx, rx) = mpsc::channel(MAX_BATCH_SIZE);
+        tokio::spawn(Self::run(Arc::new(signer), rx));
+        Self { channel: tx }
+    }
+
+    async fn run(signer: Arc<dyn AsyncSigner>, mut rx: mpsc::Receiver<SignatureRequest>) {
+        while let Some(request) = rx.recv().await {
+            // Take along the requests queued while the previous batch was signed.
+            let mut batch = vec![request];
+            while batch.len() < MAX_BATCH_SIZE {
+                match rx.try_recv() {
+                    Ok(request) => batch.push(request),
+                    Err(_) => break,
+                }
+            }
+            let digests: Vec<_> = batch.iter().map(|(digest, _)| *digest).collect();
+
+            // The primary can not make progress without its signatures: retry until the signer
+            // is back.
+            let mut delay = INITIAL_RETRY_DELAY;
+            let signatures = loop {
+                match signer.sign_digests(&digests).await {
+                    Ok(signatures) => break signatures,
+                    Err(e) => {
+                        warn!("Failed to sign {} digests, retrying: {e}", digests.len());
+                        tokio::time::sleep(delay).await;
+                        delay = (delay * 2).min(MAX_RETRY_DELAY);
+                    }
+                }
+            };
+            for ((_, sender), signature) in batch.into_iter().zip(signatures) {
+                // The requester may have given up.
+                let _ = sender.send(signature);
+            }
+        }
+    }
+
+    /// Signs `digest` with the key of the authority.
+    pub async fn request_signature(&self, digest: Digest<DIGEST_LENGTH>) -> Signature {
+        let (sender, receiver) = oneshot::channel();
+        self.channel
+            .send((digest, sender))
+            .await
+            .expect("Failed to send signature request");
+        receiver.await.expect("Failed to receive signature")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fastcrypto::generate_production_keypair;
+    use std::sync::Mutex;
+
+    /// A backend signing with a local keypair, recording the size of the batches it signs and
+    /// failing the first `failures` of them.
+    struct TestKms {
+        keypair: KeyPair,
+        batches: Arc<Mutex<Vec<usize>>>,
+        failures: Mutex<usize>,
+    }
+
+    #[async_trait]
+    impl KmsClient for TestKms {
+        async fn sign(
+            &self,
+            _key_id: &str,
+            messages: &[&[u8]],
+        ) -> Result<Vec<Vec<u8>>, SignerError> {
+            {
+                let mut failures = self.failures.lock().unwrap();
+                if *failures > 0 {
+                    *failures -= 1;
+                    return Err(SignerError::Unavailable("throttled".to_string()));
+                }
+            }
+            self.batches.lock().unwrap().push(messages.len());
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            Ok(messages
+                .iter()
+                .map(|message| self.keypair.sign(message).as_ref().to_vec())
+                .collect())
+        }
+    }
+
+    #[tokio::test]
+    async fn kms_signatures_are_batched() {
+        let keypair = generate_production_keypair::<KeyPair>();
+        let public_key = keypair.public().clone();
+        let batches = Arc::new(Mutex::new(Vec::new()));
+        let kms = TestKms {
+            keypair,
+            batches: batches.clone(),
+            failures: Mutex::new(1),
+        };
+        let service =
+            SignatureService::new(KmsSigner::new(kms, "key".to_string(), public_key.clone()));
+
+        let digests: Vec<_> = (0..10u8).map(|i| Digest::new([i; DIGEST_LENGTH])).collect();
+        let signatures = futures::future::join_all(
+            digests
+                .iter()
+                .map(|digest| service.request_signature(*digest)),
+        )
+        .await;
+        for (digest, signature) in digests.iter().zip(&signatures) {
+            assert!(public_key.verify(digest.as_ref(), signature).is_ok());
+        }
+        // The requests are signed in fewer round trips than there are requests.
+        assert!(batches.lock().unwrap().len() < digests.len());
+    }
+
+    #[tokio::test]
+    async fn kms_signatures_are_checked() {
+        let kms = TestKms {
+            keypair: generate_production_keypair::<KeyPair>(),
+            batches: Arc::default(),
+            failures: Mutex::new(0),
+        };
+        let other_key = generate_production_keypair::<KeyPair>().public().clone();
+        let signer = KmsSigner::new(kms, "key".to_string(), other_key);
+        assert!(matches!(
+            signer
+                .sign_digests(&[Digest::new([0; DIGEST_LENGTH])])
+                .await,
+            Err(SignerError::InvalidResponse(_))
+        ));
+    }
+}
@@ -12,7 +12,7 @@ use tracing::info;
 
 use crate::metrics::ExecutorMetrics;
 use async_trait::async_trait;
-use config::{Committee, SharedWorkerCache};
+use config::{Committee, Epoch, Parameters, SharedWorkerCache};
 use crypto::PublicKey;
 
 use prometheus::Registry;
@@ -26,11 +26,11 @@ use tokio::sync::oneshot;
 use tokio::{sync::watch, task::JoinHandle};
 use types::{
     metered_channel, CertificateDigest, CommittedSubDag, ConsensusOutput, ConsensusStore,
-    ReconfigureNotification,
+    ReconfigureNotification, StateDump, TimestampMs, Transaction,
 };
 
 /// Convenience type representing a serialized transaction.
-pub type SerializedTransaction = Vec<u8>;
+pub type SerializedTransaction = Transaction;
 
 /// Convenience type representing a serialized transaction digest.
 pub type SerializedTransactionDigest = u64;
@@ -44,6 +44,11 @@ pub trait ExecutionState {
 
     /// Load the last executed sub-dag index from storage
     async fn last_executed_sub_dag_index(&self) -> u64;
+
+    /// Called after handling the consensus output that ends `epoch` according to the configured
+    /// `EpochPolicy`. No further output of the epoch is delivered afterwards. This may be called
+    /// again for the same epoch if the node restarts before moving to the next epoch.
+    async fn handle_end_of_epoch(&self, _epoch: Epoch) {}
 }
 
 /// A client subscribing to the consensus output and executing every transaction.
@@ -61,6 +66,43 @@ impl Executor {
         rx_sequence: metered_channel::Receiver<CommittedSubDag>,
         registry: &Registry,
         restored_consensus_output: Vec<CommittedSubDag>,
+        parameters: &Parameters,
+        epoch_start: Option<TimestampMs>,
+    ) -> SubscriberResult<Vec<JoinHandle<()>>>
+    where
+        State: ExecutionState + Send + Sync + 'static,
+    {
+        Self::spawn_with_state_dump(
+            name,
+            network,
+            worker_cache,
+            committee,
+            execution_state,
+            tx_reconfigure,
+            rx_sequence,
+            registry,
+            restored_consensus_output,
+            parameters,
+            epoch_start,
+            &StateDump::default(),
+        )
+    }
+
+    /// Spawns the client subscriber as [`Executor::spawn`] does, adding the section of the
+    /// executor to `state_dump`.
+    pub fn spawn_with_state_dump<State>(
+        name: PublicKey,
+        network: oneshot::Receiver<anemo::Network>,
+        worker_cache: SharedWorkerCache,
+        committee: Committee,
+        execution_state: State,
+        tx_reconfigure: &watch::Sender<ReconfigureNotification>,
+        rx_sequence: metered_channel::Receiver<CommittedSubDag>,
+        registry: &Registry,
+        restored_consensus_output: Vec<CommittedSubDag>,
+        parameters: &Parameters,
+        epoch_start: Option<TimestampMs>,
+        state_dump: &StateDump,
     ) -> SubscriberResult<Vec<JoinHandle<()>>>
     where
         State: ExecutionState + Send + Sync + 'static,
@@ -81,6 +123,9 @@ impl Executor {
             arc_metrics,
             restored_consensus_output,
             execution_state,
+            parameters,
+            epoch_start,
+            state_dump,
         );
 
         // Return the handle.
@@ -138,4 +183,8 @@ impl<T: ExecutionState + 'static + Send + Sync> ExecutionState for Arc<T> {
     async fn last_executed_sub_dag_index(&self) -> u64 {
         self.as_ref().last_executed_sub_dag_index().await
     }
+
+    async fn handle_end_of_epoch(&self, epoch: Epoch) {
+        self.as_ref().handle_end_of_epoch(epoch).await
+    }
 }
@@ -4,6 +4,7 @@ use prometheus::{
     default_registry, register_histogram_with_registry, register_int_counter_with_registry,
     register_int_gauge_with_registry, Histogram, IntCounter, IntGauge, Registry,
 };
+use types::metered_channel::ChannelCollector;
 
 // buckets defined in seconds
 const LATENCY_SEC_BUCKETS: &[f64] = &[
@@ -15,6 +16,8 @@ const LATENCY_SEC_BUCKETS: &[f64] = &[
 pub struct ExecutorMetrics {
     /// occupancy of the channel from the `Subscriber` to `Notifier`
     pub tx_notifier: IntGauge,
+    /// total received on the channel from the `Subscriber` to `Notifier`
+    pub tx_notifier_total: IntCounter,
     /// Time it takes to download a payload from local worker peer
     pub subscriber_local_fetch_latency: Histogram,
     /// Time it takes to download a payload from remote peer
@@ -38,17 +41,27 @@ pub struct ExecutorMetrics {
     /// Latency between the time when the batch has been
     /// created and when it has been fetched for execution
     pub batch_execution_latency: Histogram,
+    /// The index of the last sub-dag delivered to the execution state
+    pub last_executed_sub_dag_index: IntGauge,
+    /// exports the occupancy and the total of the channel above, labelled by channel
+    collector: ChannelCollector,
 }
 
 impl ExecutorMetrics {
     pub fn new(registry: &Registry) -> Self {
-        Self {
+        let metrics = Self {
             tx_notifier: register_int_gauge_with_registry!(
                 "tx_notifier",
                 "occupancy of the channel from the `Subscriber` to `Notifier`",
                 registry
             )
             .unwrap(),
+            tx_notifier_total: register_int_counter_with_registry!(
+                "tx_notifier_total",
+                "total received on the channel from the `Subscriber` to `Notifier`",
+                registry
+            )
+            .unwrap(),
             subscriber_local_fetch_latency: register_histogram_with_registry!(
                 "subscriber_local_fetch_latency",
                 "Time it takes to download a payload from local worker peer",
@@ -104,8 +117,20 @@ impl ExecutorMetrics {
                 "Latency between when the certificate has been created and when it reached the executor",
                 LATENCY_SEC_BUCKETS.to_vec(),
                 registry
-            ).unwrap()
-        }
+            ).unwrap(),
+            last_executed_sub_dag_index: register_int_gauge_with_registry!(
+                "last_executed_sub_dag_index",
+                "The index of the last sub-dag delivered to the execution state",
+                registry
+            ).unwrap(),
+            collector: ChannelCollector::new("executor", registry),
+        };
+        metrics.collector.add(
+            "tx_notifier",
+            &metrics.tx_notifier,
+            Some(&metrics.tx_notifier_total),
+        );
+        metrics
     }
 }
 
@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 use crate::{errors::SubscriberResult, metrics::ExecutorMetrics, ExecutionState};
 
-use config::{Committee, SharedWorkerCache, WorkerId};
+use config::{Committee, Epoch, EpochPolicy, Parameters, SharedWorkerCache, WorkerId};
 use crypto::{NetworkPublicKey, PublicKey};
 
 use futures::stream::FuturesOrdered;
@@ -20,16 +20,17 @@ use fastcrypto::hash::Hash;
 use mysten_metrics::spawn_logged_monitored_task;
 use rand::prelude::SliceRandom;
 use rand::rngs::ThreadRng;
+use serde_json::json;
 use tokio::time::Instant;
 use tokio::{
     sync::{oneshot, watch},
     task::JoinHandle,
 };
 use tracing::{debug, error, warn};
-use tracing::{info, instrument};
+use tracing::{info, info_span, instrument, Instrument};
 use types::{
     metered_channel, Batch, BatchDigest, Certificate, CommittedSubDag, ConsensusOutput,
-    ReconfigureNotification, Timestamp,
+    ReconfigureNotification, StateDump, Timestamp, TimestampMs,
 };
 
 /// The `Subscriber` receives certificates sequenced by the consensus and waits until the
@@ -42,6 +43,8 @@ pub struct Subscriber<Network> {
     rx_sequence: metered_channel::Receiver<CommittedSubDag>,
     /// The metrics handler
     metrics: Arc<ExecutorMetrics>,
+    /// The maximum number of sub-dags whose payload is being fetched at the same time.
+    max_pending_payloads: usize,
 
     fetcher: Fetcher<Network>,
 }
@@ -61,21 +64,49 @@ pub fn spawn_subscriber<State: ExecutionState + Send + Sync + 'static>(
     metrics: Arc<ExecutorMetrics>,
     restored_consensus_output: Vec<CommittedSubDag>,
     state: State,
+    parameters: &Parameters,
+    epoch_start: Option<TimestampMs>,
+    state_dump: &StateDump,
 ) -> Vec<JoinHandle<()>> {
     // This is ugly but has to be done this way for now
     // Currently network incorporate both server and client side of RPC interface
     // To construct server side we need to set up routes first, which requires starting Primary
     // Some cleanup is needed
 
-    let (tx_notifier, rx_notifier) =
-        metered_channel::channel(primary::CHANNEL_CAPACITY, &metrics.tx_notifier);
+    let (tx_notifier, rx_notifier) = metered_channel::channel_with_total(
+        parameters.memory_budget.channel_capacity("tx_notifier"),
+        &metrics.tx_notifier,
+        &metrics.tx_notifier_total,
+    );
 
     let rx_reconfigure_notify = tx_reconfigure.subscribe();
     let rx_reconfigure_subscriber = tx_reconfigure.subscribe();
+    let epoch = committee.epoch();
+
+    let executor_metrics = metrics.clone();
+    state_dump.register("executor", move || {
+        let m = &executor_metrics;
+        json!({
+            "epoch": epoch,
+            "last_executed_sub_dag_index": m.last_executed_sub_dag_index.get(),
+            "current_round": m.subscriber_current_round.get(),
+            "tx_notifier": m.tx_notifier.get(),
+            "pending_remote_request_batch": m.pending_remote_request_batch.get(),
+            "waiting_elements": m.waiting_elements_subscriber.get(),
+        })
+    });
 
     vec![
         spawn_logged_monitored_task!(
-            run_notify(state, rx_notifier, rx_reconfigure_notify),
+            run_notify(
+                state,
+                epoch,
+                parameters.epoch_policy,
+                epoch_start,
+                rx_notifier,
+                rx_reconfigure_notify,
+                metrics.clone()
+            ),
             "SubscriberNotifyTask"
         ),
         spawn_logged_monitored_task!(
@@ -87,6 +118,7 @@ pub fn spawn_subscriber<State: ExecutionState + Send + Sync + 'static>(
                 rx_reconfigure_subscriber,
                 rx_sequence,
                 metrics,
+                parameters.memory_budget.max_pending_payloads,
                 restored_consensus_output,
                 tx_notifier,
             ),
@@ -95,15 +127,40 @@ pub fn spawn_subscriber<State: ExecutionState + Send + Sync + 'static>(
     ]
 }
 
+/// Deliver the consensus outputs to the execution state, and notify it when the epoch ends
+/// according to the `epoch_policy`. The `epoch_start` is the creation time of the leader of the
+/// first sub-dag of the epoch, if it was already committed.
 async fn run_notify<State: ExecutionState + Send + Sync + 'static>(
     state: State,
+    epoch: Epoch,
+    epoch_policy: EpochPolicy,
+    mut epoch_start: Option<TimestampMs>,
     mut tr_notify: metered_channel::Receiver<ConsensusOutput>,
     mut rx_reconfigure: watch::Receiver<ReconfigureNotification>,
+    metrics: Arc<ExecutorMetrics>,
 ) {
+    let mut end_of_epoch = false;
     loop {
         tokio::select! {
             Some(message) = tr_notify.recv() => {
-                state.handle_consensus_output(message).await;
+                let sub_dag_index = message.sub_dag.sub_dag_index;
+                if end_of_epoch {
+                    debug!("Dropping sub-dag {sub_dag_index} committed after the end of epoch {epoch}");
+                    continue;
+                }
+
+                let leader_timestamp = message.sub_dag.leader.header.created_at;
+                let epoch_start = *epoch_start.get_or_insert(leader_timestamp);
+                let span = info_span!("execute", sub_dag_index, leader_digest = %message.leader);
+                network::trace_id::join_certificates(&span, &message.sub_dag.certificates);
+                state.handle_consensus_output(message).instrument(span).await;
+                metrics.last_executed_sub_dag_index.set(sub_dag_index as i64);
+
+                if epoch_policy.is_end_of_epoch(sub_dag_index, epoch_start, leader_timestamp) {
+                    info!("Epoch {epoch} ended with sub-dag {sub_dag_index} ({epoch_policy})");
+                    state.handle_end_of_epoch(epoch).await;
+                    end_of_epoch = true;
+                }
             }
 
             // Check for reconfiguration.
@@ -126,6 +183,7 @@ async fn create_and_run_subscriber(
     rx_reconfigure: watch::Receiver<ReconfigureNotification>,
     rx_sequence: metered_channel::Receiver<CommittedSubDag>,
     metrics: Arc<ExecutorMetrics>,
+    max_pending_payloads: usize,
     restored_consensus_output: Vec<CommittedSubDag>,
     tx_notifier: metered_channel::Sender<ConsensusOutput>,
 ) {
@@ -145,6 +203,7 @@ async fn create_and_run_subscriber(
         rx_reconfigure,
         rx_sequence,
         metrics,
+        max_pending_payloads,
         fetcher,
     };
     subscriber
@@ -154,9 +213,6 @@ async fn create_and_run_subscriber(
 }
 
 impl<Network: SubscriberNetwork> Subscriber<Network> {
-    /// Returns the max amount of pending consensus messages we should expect.
-    const MAX_PENDING_PAYLOADS: usize = 32;
-
     /// Main loop connecting to the consensus to listen to sequence messages.
     async fn run(
         mut self,
@@ -184,10 +240,10 @@ impl<Network: SubscriberNetwork> Subscriber<Network> {
         loop {
             tokio::select! {
                 // Receive the ordered sequence of consensus messages from a consensus node.
-                Some(sub_dag) = self.rx_sequence.recv(), if waiting.len() < Self::MAX_PENDING_PAYLOADS => {
-                    // We can schedule more then MAX_PENDING_PAYLOADS payloads but
+                Some(sub_dag) = self.rx_sequence.recv(), if waiting.len() < self.max_pending_payloads => {
+                    // We can schedule more then max_pending_payloads payloads but
                     // don't process more consensus messages when more
-                    // then MAX_PENDING_PAYLOADS is pending
+                    // then max_pending_payloads is pending
                     waiting.push_back(self.fetcher.fetch_payloads(sub_dag));
                 },
 
@@ -227,17 +283,11 @@ impl<Network: SubscriberNetwork> Fetcher<Network> {
         let num_certs = deliver.len();
         if num_batches == 0 {
             debug!("No batches to fetch, payload is empty");
-            return ConsensusOutput {
-                sub_dag: Arc::new(deliver),
-                batches: vec![],
-            };
+            return ConsensusOutput::new(Arc::new(deliver), vec![]);
         }
 
         let sub_dag = Arc::new(deliver);
-        let mut subscriber_output = ConsensusOutput {
-            sub_dag: sub_dag.clone(),
-            batches: Vec::with_capacity(num_certs),
-        };
+        let mut output_batches = Vec::with_capacity(num_certs);
 
         for cert in &sub_dag.certificates {
             let mut batches = Vec::with_capacity(num_batches);
@@ -262,10 +312,10 @@ impl<Network: SubscriberNetwork> Fetcher<Network> {
                 let batch = self.fetch_payload(*digest, *worker_id, workers).await;
                 batches.push(batch);
             }
-            subscriber_output.batches.push((output_cert, batches));
+            output_batches.push((output_cert, batches));
         }
 
-        subscriber_output
+        ConsensusOutput::new(sub_dag, output_batches)
     }
 
     /// Fetches single payload from network
@@ -465,6 +515,7 @@ impl SubscriberNetwork for SubscriberNetworkImpl {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use bytes::Bytes;
     use crypto::NetworkKeyPair;
     use fastcrypto::hash::Hash;
     use fastcrypto::traits::KeyPair;
@@ -474,8 +525,8 @@ mod tests {
     #[tokio::test]
     pub async fn test_fetcher() {
         let mut network = TestSubscriberNetwork::new();
-        let batch1 = Batch::new(vec![vec![1]]);
-        let batch2 = Batch::new(vec![vec![2]]);
+        let batch1 = Batch::new(vec![Bytes::from(vec![1])]);
+        let batch2 = Batch::new(vec![Bytes::from(vec![2])]);
         network.put(&[1, 2], batch1.clone());
         network.put(&[2, 3], batch2.clone());
         let fetcher = Fetcher {
@@ -492,6 +543,55 @@ mod tests {
         assert_eq!(batch, batch2);
     }
 
+    #[tokio::test]
+    pub async fn test_end_of_epoch() {
+        let mut state = crate::MockExecutionState::new();
+        state
+            .expect_handle_consensus_output()
+            .times(3)
+            .return_const(());
+        state
+            .expect_handle_end_of_epoch()
+            .withf(|epoch| *epoch == 5)
+            .times(1)
+            .return_const(());
+
+        let gauge = IntGauge::new("TEST_COUNTER", "test").unwrap();
+        let (tx_notifier, rx_notifier) = metered_channel::channel(10, &gauge);
+        let committee = test_utils::CommitteeFixture::builder().build().committee();
+        let (tx_reconfigure, rx_reconfigure) =
+            watch::channel(ReconfigureNotification::NewEpoch(committee));
+        let handle = tokio::spawn(run_notify(
+            state,
+            5,
+            EpochPolicy::FixedCommits(3),
+            None,
+            rx_notifier,
+            rx_reconfigure,
+            Arc::new(ExecutorMetrics::new(&prometheus::Registry::new())),
+        ));
+
+        // The outputs committed after the end of the epoch are not delivered.
+        for sub_dag_index in 1..=5 {
+            let sub_dag = CommittedSubDag {
+                sub_dag_index,
+                ..CommittedSubDag::default()
+            };
+            let output = ConsensusOutput::new(Arc::new(sub_dag), vec![]);
+            tx_notifier.send(output).await.unwrap();
+        }
+
+        // Let the outputs be processed before shutting down.
+        while tx_notifier.capacity() < 10 {
+            tokio::task::yield_now().await;
+        }
+        tokio::task::yield_now().await;
+        tx_reconfigure
+            .send(ReconfigureNotification::Shutdown)
+            .unwrap();
+        handle.await.unwrap();
+    }
+
     struct TestSubscriberNetwork {
         data: HashMap<BatchDigest, HashMap<NetworkPublicKey, Batch>>,
         my: NetworkPublicKey,
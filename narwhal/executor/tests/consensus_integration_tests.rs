@@ -168,6 +168,7 @@ async fn test_internal_consensus_output() {
         let tr = bincode::serialize(&tx).unwrap();
         let txn = TransactionProto {
             transaction: Bytes::from(tr),
+            metadata: Bytes::new(),
         };
         client.submit_transaction(txn).await.unwrap();
 
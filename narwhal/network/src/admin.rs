@@ -1,44 +1,109 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use anemo::{types::PeerInfo, PeerId};
+use anyhow::{anyhow, Context};
 use axum::routing::post;
-use axum::{extract::Extension, http::StatusCode, routing::get, Json, Router};
+use axum::{
+    body::Body,
+    extract::{Extension, Path as UrlPath},
+    http::{header::AUTHORIZATION, Request, StatusCode},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use axum_server::tls_rustls::RustlsConfig;
+use config::{
+    AdminAddress, AdminAuthParameters, AdminTlsParameters, SharedWorkerCache, WorkerCache,
+    WorkerCacheUpdate, WorkerIndexUpdate,
+};
 use mysten_metrics::{spawn_logged_monitored_task, spawn_monitored_task};
-use std::net::{IpAddr, Ipv4Addr, SocketAddr};
-use tokio::sync::watch;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::net::UnixListener;
+use tokio::sync::{watch, Notify};
 use tokio::task::JoinHandle;
-use tracing::info;
+use tracing::{info, warn};
 use types::metered_channel::Sender;
-use types::ReconfigureNotification;
+use types::{
+    HealthReport, JobId, MaintenanceError, MaintenanceJob, MaintenanceTask, NodeHealth,
+    ReconfigureNotification, ReconfigureRequest, ReconfigureUpdate, StateDump, StorageMaintenance,
+};
 
+/// Starts the admin server on `address`, authenticating the requests as configured by `auth`.
+/// The `/healthz` and `/readyz` probes don't need a bearer token, but still need a client
+/// certificate with mutual TLS since it is checked before any route.
 pub fn start_admin_server(
-    port: u16,
+    address: AdminAddress,
+    auth: Option<AdminAuthParameters>,
     network: anemo::Network,
+    worker_cache: SharedWorkerCache,
+    state_dump: StateDump,
+    health: NodeHealth,
+    maintenance: StorageMaintenance,
     mut rx_reconfigure: watch::Receiver<ReconfigureNotification>,
-    tx_state_handler: Option<Sender<ReconfigureNotification>>,
+    tx_state_handler: Option<Sender<ReconfigureRequest>>,
 ) -> Vec<JoinHandle<()>> {
     let mut router = Router::new()
         .route("/peers", get(get_peers))
-        .route("/known_peers", get(get_known_peers));
+        .route("/known_peers", get(get_known_peers))
+        .route("/state", get(get_state))
+        .route("/status", get(get_status))
+        .route("/update_workers", post(update_workers))
+        .route("/storage/compact", post(compact))
+        .route("/storage/prune", post(prune))
+        .route("/storage/snapshot", post(snapshot))
+        .route("/storage/jobs", get(get_jobs))
+        .route("/storage/jobs/:id", get(get_job))
+        .layer(Extension(worker_cache))
+        .layer(Extension(state_dump))
+        .layer(Extension(health.clone()))
+        .layer(Extension(maintenance));
 
     // Primaries will have this service enabled
     if let Some(tx_state_handler) = tx_state_handler {
         let r = Router::new()
             .route("/reconfigure", post(reconfigure))
+            .route("/reconfigure_update", post(reconfigure_update))
             .layer(Extension(tx_state_handler));
         router = router.merge(r);
     }
 
     router = router.layer(Extension(network));
 
-    let socket_address = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), port);
+    let tls_config = match &auth {
+        Some(AdminAuthParameters::BearerToken(token)) => {
+            let token = Arc::new(token.clone());
+            router = router.layer(middleware::from_fn(move |request, next| {
+                require_bearer_token(token.clone(), request, next)
+            }));
+            None
+        }
+        Some(AdminAuthParameters::MutualTls(tls)) => {
+            Some(server_tls_config(tls).unwrap_or_else(|e| {
+                panic!("Failed to configure the TLS of the admin server: {e:#}")
+            }))
+        }
+        None => None,
+    };
+    let router = router.merge(probes(health.clone()));
+
     info!(
-        address =% socket_address,
+        address =% address,
+        authenticated = auth.is_some(),
         "starting admin server"
     );
 
+    // The servers on unix sockets are served by hyper, which is shut down through `shutdown`.
     let handle = axum_server::Handle::new();
     let shutdown_handle = handle.clone();
+    let shutdown = Arc::new(Notify::new());
+    let notified = shutdown.clone();
 
     let mut handles = Vec::new();
     // Spawn a task to shutdown server.
@@ -46,7 +111,9 @@ pub fn start_admin_server(
         while (rx_reconfigure.changed().await).is_ok() {
             let message = rx_reconfigure.borrow().clone();
             if let ReconfigureNotification::Shutdown = message {
+                health.set_shutting_down();
                 handle.clone().shutdown();
+                shutdown.notify_one();
 
                 return;
             }
@@ -55,11 +122,37 @@ pub fn start_admin_server(
 
     handles.push(spawn_logged_monitored_task!(
         async move {
-            axum_server::bind(socket_address)
-                .handle(shutdown_handle)
-                .serve(router.into_make_service())
-                .await
-                .unwrap();
+            match (address, tls_config) {
+                (AdminAddress::Tcp(address), Some(tls_config)) => {
+                    axum_server::bind_rustls(address, tls_config)
+                        .handle(shutdown_handle)
+                        .serve(router.into_make_service())
+                        .await
+                        .unwrap()
+                }
+                (AdminAddress::Tcp(address), None) => axum_server::bind(address)
+                    .handle(shutdown_handle)
+                    .serve(router.into_make_service())
+                    .await
+                    .unwrap(),
+                (AdminAddress::Unix(_), Some(_)) => {
+                    panic!("The admin server can't be served over TLS on a unix socket")
+                }
+                (AdminAddress::Unix(path), None) => {
+                    let listener = bind_unix_socket(&path).unwrap_or_else(|e| {
+                        panic!("Failed to bind the admin server to {}: {e}", path.display())
+                    });
+                    let incoming = futures::stream::unfold(listener, |listener| async move {
+                        let stream = listener.accept().await.map(|(stream, _)| stream);
+                        Some((stream, listener))
+                    });
+                    axum::Server::builder(hyper::server::accept::from_stream(incoming))
+                        .serve(router.into_make_service())
+                        .with_graceful_shutdown(notified.notified())
+                        .await
+                        .unwrap()
+                }
+            }
         },
         "AdminServerTask"
     ));
@@ -67,6 +160,91 @@ pub fn start_admin_server(
     handles
 }
 
+/// Binds a unix socket at `path`, replacing the socket left by a previous run of the server.
+fn bind_unix_socket(path: &Path) -> std::io::Result<UnixListener> {
+    match std::fs::remove_file(path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e),
+        _ => (),
+    }
+    UnixListener::bind(path)
+}
+
+/// Rejects the requests which don't carry `token` in their `Authorization: Bearer` header.
+async fn require_bearer_token(
+    token: Arc<String>,
+    request: Request<Body>,
+    next: Next<Body>,
+) -> Response {
+    let authorized = request
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map_or(false, |bearer| {
+            constant_time_eq(bearer.as_bytes(), token.as_bytes())
+        });
+    if authorized {
+        next.run(request).await
+    } else {
+        StatusCode::UNAUTHORIZED.into_response()
+    }
+}
+
+/// Compares the tokens in a time independent of the position of their first difference.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+/// The TLS configuration of a server accepting only the clients with a certificate signed by
+/// the certificate authority of `tls`.
+fn server_tls_config(tls: &AdminTlsParameters) -> anyhow::Result<RustlsConfig> {
+    let mut roots = rustls::RootCertStore::empty();
+    for certificate in read_certificates(&tls.ca_cert)? {
+        roots
+            .add(&certificate)
+            .map_err(|e| anyhow!("invalid certificate in {}: {e:?}", tls.ca_cert.display()))?;
+    }
+    let config = rustls::ServerConfig::builder()
+        .with_safe_defaults()
+        .with_client_cert_verifier(rustls::server::AllowAnyAuthenticatedClient::new(roots))
+        .with_single_cert(
+            read_certificates(&tls.server_cert)?,
+            read_private_key(&tls.server_key)?,
+        )?;
+    Ok(RustlsConfig::from_config(Arc::new(config)))
+}
+
+fn read_certificates(path: &Path) -> anyhow::Result<Vec<rustls::Certificate>> {
+    let mut reader = BufReader::new(
+        File::open(path).with_context(|| format!("unable to open {}", path.display()))?,
+    );
+    let certificates = rustls_pemfile::certs(&mut reader)
+        .with_context(|| format!("unable to read {}", path.display()))?;
+    if certificates.is_empty() {
+        return Err(anyhow!("no certificate in {}", path.display()));
+    }
+    Ok(certificates.into_iter().map(rustls::Certificate).collect())
+}
+
+fn read_private_key(path: &Path) -> anyhow::Result<rustls::PrivateKey> {
+    let mut reader = BufReader::new(
+        File::open(path).with_context(|| format!("unable to open {}", path.display()))?,
+    );
+    loop {
+        match rustls_pemfile::read_one(&mut reader)
+            .with_context(|| format!("unable to read {}", path.display()))?
+        {
+            Some(
+                rustls_pemfile::Item::PKCS8Key(key)
+                | rustls_pemfile::Item::RSAKey(key)
+                | rustls_pemfile::Item::ECKey(key),
+            ) => return Ok(rustls::PrivateKey(key)),
+            Some(_) => continue,
+            None => return Err(anyhow!("no private key in {}", path.display())),
+        }
+    }
+}
+
 async fn get_peers(
     Extension(network): Extension<anemo::Network>,
 ) -> (StatusCode, Json<Vec<String>>) {
@@ -92,10 +270,381 @@ async fn get_known_peers(
     )
 }
 
+/// Returns a JSON snapshot of the internal state of the node: the sections of its state dump,
+/// and the connection status of every known peer under `peers`.
+async fn get_state(
+    Extension(network): Extension<anemo::Network>,
+    Extension(state_dump): Extension<StateDump>,
+) -> (StatusCode, Json<Value>) {
+    let mut state = state_dump.snapshot();
+    let peers: Vec<_> = network
+        .known_peers()
+        .get_all()
+        .into_iter()
+        .map(|info| {
+            json!({
+                "peer_id": info.peer_id.to_string(),
+                "address": info.address.iter().map(ToString::to_string).collect::<Vec<_>>(),
+                "connected": network.peer(info.peer_id).is_some(),
+            })
+        })
+        .collect();
+    state["peers"] = Value::Array(peers);
+    (StatusCode::OK, Json(state))
+}
+
+/// The routes of the probes of the orchestrator, which are served without authentication.
+fn probes(health: NodeHealth) -> Router {
+    Router::new()
+        .route("/healthz", get(get_healthz))
+        .route("/readyz", get(get_readyz))
+        .layer(Extension(health))
+}
+
+/// The liveness probe: fails only when a critical component of the node is down, since
+/// restarting a node which is booting or shutting down doesn't help.
+async fn get_healthz(Extension(health): Extension<NodeHealth>) -> (StatusCode, Json<Value>) {
+    let status = health.report().status;
+    (
+        probe_code(status.is_live()),
+        Json(json!({ "status": status })),
+    )
+}
+
+/// The readiness probe: succeeds when the node is healthy or degraded, e.g. with a worker down.
+async fn get_readyz(Extension(health): Extension<NodeHealth>) -> (StatusCode, Json<Value>) {
+    let status = health.report().status;
+    (
+        probe_code(status.is_ready()),
+        Json(json!({ "status": status })),
+    )
+}
+
+/// The status of the node along with the state of every component.
+async fn get_status(Extension(health): Extension<NodeHealth>) -> (StatusCode, Json<HealthReport>) {
+    (StatusCode::OK, Json(health.report()))
+}
+
+fn probe_code(success: bool) -> StatusCode {
+    if success {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    }
+}
+
+async fn compact(
+    Extension(maintenance): Extension<StorageMaintenance>,
+) -> (StatusCode, Json<Value>) {
+    start_job(&maintenance, MaintenanceTask::Compaction)
+}
+
+async fn prune(Extension(maintenance): Extension<StorageMaintenance>) -> (StatusCode, Json<Value>) {
+    start_job(&maintenance, MaintenanceTask::Pruning)
+}
+
+#[derive(Deserialize)]
+struct SnapshotRequest {
+    /// The directory of the node's host where to export the snapshot, which must not exist or
+    /// be empty.
+    target: PathBuf,
+}
+
+async fn snapshot(
+    Extension(maintenance): Extension<StorageMaintenance>,
+    Json(request): Json<SnapshotRequest>,
+) -> (StatusCode, Json<Value>) {
+    let task = MaintenanceTask::Snapshot {
+        target: request.target,
+    };
+    start_job(&maintenance, task)
+}
+
+/// Starts a job running `task`, returning its id to poll `/storage/jobs/<id>` with.
+fn start_job(maintenance: &StorageMaintenance, task: MaintenanceTask) -> (StatusCode, Json<Value>) {
+    match maintenance.start(task) {
+        Ok(job_id) => (StatusCode::ACCEPTED, Json(json!({ "job_id": job_id }))),
+        Err(e @ MaintenanceError::Unavailable(_)) => (
+            StatusCode::NOT_FOUND,
+            Json(json!({ "error": e.to_string() })),
+        ),
+        Err(e @ MaintenanceError::AlreadyRunning { job_id, .. }) => (
+            StatusCode::CONFLICT,
+            Json(json!({ "error": e.to_string(), "job_id": job_id })),
+        ),
+    }
+}
+
+async fn get_jobs(
+    Extension(maintenance): Extension<StorageMaintenance>,
+) -> (StatusCode, Json<Vec<MaintenanceJob>>) {
+    (StatusCode::OK, Json(maintenance.jobs()))
+}
+
+async fn get_job(
+    Extension(maintenance): Extension<StorageMaintenance>,
+    UrlPath(id): UrlPath<JobId>,
+) -> Result<Json<MaintenanceJob>, StatusCode> {
+    maintenance.job(id).map(Json).ok_or(StatusCode::NOT_FOUND)
+}
+
+/// Swaps the information of some workers in the worker cache during the epoch (typically after
+/// they moved to a new address), and re-establishes the connections to them. Only updates of
+/// workers already in the worker cache are accepted; adding or removing authorities requires a
+/// reconfiguration. The update is idempotent, so the same update can be sent to the primary and
+/// to every worker of a node even when they share their worker cache.
+async fn update_workers(
+    Extension(network): Extension<anemo::Network>,
+    Extension(worker_cache): Extension<SharedWorkerCache>,
+    Json(update): Json<WorkerCacheUpdate>,
+) -> (StatusCode, String) {
+    let current = worker_cache.load_full();
+    if update.epoch != current.epoch() {
+        return (
+            StatusCode::BAD_REQUEST,
+            format!(
+                "Update is for epoch {} but the current epoch is {}",
+                update.epoch,
+                current.epoch()
+            ),
+        );
+    }
+    if !update
+        .changes
+        .iter()
+        .all(|change| matches!(change, WorkerIndexUpdate::Update(..)))
+    {
+        return (
+            StatusCode::BAD_REQUEST,
+            "Only existing workers can be updated during the epoch".to_string(),
+        );
+    }
+
+    let new = match current.apply(&update) {
+        Ok(new) => new,
+        Err(errors) => return (StatusCode::BAD_REQUEST, format!("{errors:?}")),
+    };
+    reconnect_updated_workers(&network, &current, &update);
+    worker_cache.store(Arc::new(new));
+
+    (StatusCode::OK, String::new())
+}
+
+/// Points the known peers of `network` that are updated by `update` to their new address, and
+/// drops the existing connections to them so they get re-established.
+fn reconnect_updated_workers(
+    network: &anemo::Network,
+    current: &WorkerCache,
+    update: &WorkerCacheUpdate,
+) {
+    let known_peers = network.known_peers();
+    for change in &update.changes {
+        let (authority, index) = match change {
+            WorkerIndexUpdate::Update(authority, index) => (authority, index),
+            _ => continue,
+        };
+        for (id, info) in &index.0 {
+            let new_peer_id = PeerId(info.name.0.to_bytes());
+            let old_peer_id = current
+                .workers
+                .get(authority)
+                .and_then(|old_index| old_index.0.get(id))
+                .map(|old_info| PeerId(old_info.name.0.to_bytes()))
+                .unwrap_or(new_peer_id);
+
+            let old_peer_info = match known_peers
+                .get(&old_peer_id)
+                .or_else(|| known_peers.get(&new_peer_id))
+            {
+                Some(peer_info) => peer_info,
+                // We don't talk to this worker.
+                None => continue,
+            };
+            let address = match crate::multiaddr_to_address(&info.worker_address) {
+                Ok(address) => address,
+                Err(e) => {
+                    warn!("Ignoring update of worker {id} to an invalid address: {e}");
+                    continue;
+                }
+            };
+
+            known_peers.remove(&old_peer_id);
+            known_peers.insert(PeerInfo {
+                peer_id: new_peer_id,
+                affinity: old_peer_info.affinity,
+                address: vec![address.clone()],
+            });
+            for peer_id in [old_peer_id, new_peer_id] {
+                let _ = network.disconnect(peer_id);
+            }
+            info!("Worker {id} with peer id {new_peer_id} is now reachable at {address}");
+        }
+    }
+}
+
 async fn reconfigure(
-    Extension(tx_state_handler): Extension<Sender<ReconfigureNotification>>,
+    Extension(tx_state_handler): Extension<Sender<ReconfigureRequest>>,
     Json(reconfigure_notification): Json<ReconfigureNotification>,
 ) -> StatusCode {
-    let _ = tx_state_handler.send(reconfigure_notification).await;
+    let _ = tx_state_handler
+        .send(ReconfigureRequest::Notification(reconfigure_notification))
+        .await;
     StatusCode::OK
 }
+
+async fn reconfigure_update(
+    Extension(tx_state_handler): Extension<Sender<ReconfigureRequest>>,
+    Json(reconfigure_update): Json<ReconfigureUpdate>,
+) -> StatusCode {
+    let _ = tx_state_handler
+        .send(ReconfigureRequest::Update(reconfigure_update))
+        .await;
+    StatusCode::OK
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn bearer_token_is_required() {
+        let token = Arc::new("secret".to_string());
+        let health = NodeHealth::default();
+        health.register("primary", true, || types::ComponentState::Running);
+        let router = Router::new()
+            .route("/peers", get(|| async { "peers" }))
+            .layer(middleware::from_fn(move |request, next| {
+                require_bearer_token(token.clone(), request, next)
+            }))
+            .merge(probes(health));
+
+        // The probes are reachable without a token.
+        for path in ["/healthz", "/readyz"] {
+            let request = Request::builder().uri(path).body(Body::empty()).unwrap();
+            let response = router.clone().oneshot(request).await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK, "{path}");
+        }
+
+        for (authorization, status) in [
+            (None, StatusCode::UNAUTHORIZED),
+            (Some("Bearer wrong"), StatusCode::UNAUTHORIZED),
+            (Some("Bearer secret2"), StatusCode::UNAUTHORIZED),
+            (Some("secret"), StatusCode::UNAUTHORIZED),
+            (Some("Bearer secret"), StatusCode::OK),
+        ] {
+            let mut request = Request::builder().uri("/peers");
+            if let Some(authorization) = authorization {
+                request = request.header(AUTHORIZATION, authorization);
+            }
+            let response = router
+                .clone()
+                .oneshot(request.body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+            assert_eq!(response.status(), status, "{authorization:?}");
+        }
+    }
+
+    #[tokio::test]
+    async fn probes_follow_the_health_of_the_node() {
+        let health = NodeHealth::default();
+        let router = Router::new()
+            .route("/status", get(get_status))
+            .layer(Extension(health.clone()))
+            .merge(probes(health.clone()));
+        let codes = || async {
+            let mut codes = Vec::new();
+            for path in ["/healthz", "/readyz", "/status"] {
+                let request = Request::builder().uri(path).body(Body::empty()).unwrap();
+                codes.push(router.clone().oneshot(request).await.unwrap().status());
+            }
+            codes
+        };
+        let (ok, unavailable) = (StatusCode::OK, StatusCode::SERVICE_UNAVAILABLE);
+
+        health.register("primary", true, || types::ComponentState::Starting);
+        assert_eq!(codes().await, vec![ok, unavailable, ok]);
+
+        health.register("primary", true, || types::ComponentState::Running);
+        health.register("worker-0", false, || types::ComponentState::Running);
+        assert_eq!(codes().await, vec![ok, ok, ok]);
+
+        health.mark_down("worker-0", "a task exited");
+        assert_eq!(codes().await, vec![ok, ok, ok]);
+
+        health.mark_down("primary", "a task exited");
+        assert_eq!(codes().await, vec![unavailable, unavailable, ok]);
+    }
+
+    #[tokio::test]
+    async fn maintenance_jobs_are_polled() {
+        let maintenance = StorageMaintenance::default();
+        maintenance.register(types::MaintenanceKind::Snapshot, |task| async move {
+            Ok(json!({ "task": task }))
+        });
+        let router = Router::new()
+            .route("/storage/compact", post(compact))
+            .route("/storage/snapshot", post(snapshot))
+            .route("/storage/jobs/:id", get(get_job))
+            .layer(Extension(maintenance.clone()));
+        let send = |request: Request<Body>| {
+            let router = router.clone();
+            async move {
+                let response = router.oneshot(request).await.unwrap();
+                let status = response.status();
+                let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+                (status, serde_json::from_slice::<Value>(&body).ok())
+            }
+        };
+        let post = |path: &str, body: &str| {
+            Request::post(path)
+                .header("content-type", "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap()
+        };
+
+        // No compaction was registered.
+        let (status, _) = send(post("/storage/compact", "")).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let (status, body) = send(post("/storage/snapshot", r#"{"target":"/snapshot"}"#)).await;
+        assert_eq!(status, StatusCode::ACCEPTED);
+        let job_id = body.unwrap()["job_id"].as_u64().unwrap();
+        loop {
+            let request = Request::get(format!("/storage/jobs/{job_id}"))
+                .body(Body::empty())
+                .unwrap();
+            let (status, job) = send(request).await;
+            assert_eq!(status, StatusCode::OK);
+            let job = job.unwrap();
+            if job["state"] == "running" {
+                tokio::task::yield_now().await;
+                continue;
+            }
+            assert_eq!(job["state"], "succeeded");
+            assert_eq!(job["result"]["task"]["target"], "/snapshot");
+            break;
+        }
+
+        let request = Request::get("/storage/jobs/42")
+            .body(Body::empty())
+            .unwrap();
+        assert_eq!(send(request).await.0, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn unix_socket_replaces_a_stale_one() {
+        let path = std::env::temp_dir().join(format!("admin-{}.sock", std::process::id()));
+        let listener = bind_unix_socket(&path).unwrap();
+        drop(listener);
+        // The socket file outlives its listener, as after a crash or at the end of an epoch.
+        assert!(path.exists());
+
+        let listener = bind_unix_socket(&path).unwrap();
+        let client = tokio::net::UnixStream::connect(&path).await.unwrap();
+        let (_server, _) = listener.accept().await.unwrap();
+        drop(client);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,296 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use anemo::{
+    types::response::{IntoResponse, StatusCode},
+    PeerId, Request, Response,
+};
+use anemo_tower::auth::AuthorizeRequest;
+use arc_swap::ArcSwap;
+use bytes::Bytes;
+use config::{Committee, Epoch, SharedCommittee, SharedWorkerCache, WorkerCache};
+use crypto::{NetworkPublicKey, PublicKey};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    sync::{Arc, Mutex},
+};
+
+/// The peers of the committee, i.e. the only ones allowed to talk to the primaries and workers:
+/// anemo authenticates every connection with the network key of the peer, and the requests of the
+/// peers whose key is in neither the current committee nor the worker cache are rejected before
+/// they are deserialized.
+///
+/// The peers of the previous epoch remain allowed for an epoch, so that the lagging ones can still
+/// catch up, and the peers of the next epoch can be allowed in advance with
+/// [`PeerGrace::allow_next_epoch`]. Both are kept by the [`PeerGrace`] of the authority, which
+/// outlives the primary and workers restarted at every epoch change.
+#[derive(Clone)]
+pub struct CommitteePeers {
+    committee: SharedCommittee,
+    worker_cache: SharedWorkerCache,
+    grace: PeerGrace,
+    snapshot: Arc<ArcSwap<Snapshot>>,
+}
+
+/// The peers of a committee and worker cache.
+struct Snapshot {
+    committee: Arc<Committee>,
+    worker_cache: Arc<WorkerCache>,
+    peers: HashSet<PeerId>,
+    /// The peers of the previous epoch.
+    previous_peers: Arc<HashSet<PeerId>>,
+}
+
+impl Snapshot {
+    fn new(committee: Arc<Committee>, worker_cache: Arc<WorkerCache>, grace: &PeerGrace) -> Self {
+        let primaries = committee
+            .authorities
+            .values()
+            .map(|authority| &authority.network_key);
+        let workers = worker_cache
+            .workers
+            .values()
+            .flat_map(|index| index.0.values())
+            .map(|worker| &worker.name);
+        let peers: HashSet<_> = primaries.chain(workers).map(peer_id).collect();
+        let previous_peers = grace.record(committee.epoch(), &peers);
+        Self {
+            committee,
+            worker_cache,
+            peers,
+            previous_peers,
+        }
+    }
+}
+
+fn peer_id(network_key: &NetworkPublicKey) -> PeerId {
+    PeerId(network_key.0.to_bytes())
+}
+
+/// The peers an authority keeps allowing outside of its current committee: the ones of its
+/// previous epoch, and the ones of its next epoch once known. Cloning it shares the peers.
+#[derive(Clone, Default)]
+pub struct PeerGrace {
+    inner: Arc<GraceInner>,
+}
+
+#[derive(Default)]
+struct GraceInner {
+    /// The peers of the latest epochs seen by the components of the authority, at most two.
+    epochs: Mutex<BTreeMap<Epoch, Arc<HashSet<PeerId>>>>,
+    next_epoch: ArcSwap<HashSet<PeerId>>,
+}
+
+/// The grace of the authorities run by the process, across the restarts of their primary and
+/// workers.
+static AUTHORITY_GRACE: Mutex<Option<HashMap<PublicKey, PeerGrace>>> = Mutex::new(None);
+
+impl PeerGrace {
+    /// The grace of the authority `name`, across epochs.
+    pub fn of_authority(name: &PublicKey) -> Self {
+        AUTHORITY_GRACE
+            .lock()
+            .unwrap()
+            .get_or_insert_with(HashMap::new)
+            .entry(name.clone())
+            .or_default()
+            .clone()
+    }
+
+    /// Moves the grace of the authority `from` to `to`, e.g. when its protocol key rotates at
+    /// an epoch change.
+    pub fn rename_authority(from: &PublicKey, to: &PublicKey) {
+        let mut authorities = AUTHORITY_GRACE.lock().unwrap();
+        let authorities = authorities.get_or_insert_with(HashMap::new);
+        if let Some(grace) = authorities.remove(from) {
+            authorities.insert(to.clone(), grace);
+        }
+    }
+
+    /// Allows the peers with these network keys, e.g. the members of the committee of the next
+    /// epoch, ahead of the reconfiguration. Replaces the previously allowed ones, which are also
+    /// dropped once a later epoch starts.
+    pub fn allow_next_epoch(&self, network_keys: impl IntoIterator<Item = NetworkPublicKey>) {
+        self.inner.next_epoch.store(Arc::new(
+            network_keys.into_iter().map(|key| peer_id(&key)).collect(),
+        ));
+    }
+
+    /// Records the peers of `epoch`, and returns the ones of the epoch before it.
+    fn record(&self, epoch: Epoch, peers: &HashSet<PeerId>) -> Arc<HashSet<PeerId>> {
+        let mut epochs = self.inner.epochs.lock().unwrap();
+        if epochs.keys().all(|latest| *latest < epoch) {
+            self.inner.next_epoch.store(Arc::new(HashSet::new()));
+        }
+        epochs.insert(epoch, Arc::new(peers.clone()));
+        while epochs.len() > 2 {
+            let oldest = *epochs.keys().next().unwrap();
+            epochs.remove(&oldest);
+        }
+        epochs
+            .range(..epoch)
+            .next_back()
+            .map(|(_, peers)| peers.clone())
+            .unwrap_or_default()
+    }
+}
+
+impl CommitteePeers {
+    pub fn new(
+        committee: SharedCommittee,
+        worker_cache: SharedWorkerCache,
+        grace: PeerGrace,
+    ) -> Self {
+        let snapshot = Snapshot::new(committee.load_full(), worker_cache.load_full(), &grace);
+        Self {
+            committee,
+            worker_cache,
+            grace,
+            snapshot: Arc::new(ArcSwap::from_pointee(snapshot)),
+        }
+    }
+
+    /// Whether the peer belongs to the committee, or is in the grace set of the previous or next
+    /// epoch.
+    pub fn contains(&self, peer: &PeerId) -> bool {
+        let snapshot = self.snapshot();
+        snapshot.peers.contains(peer)
+            || snapshot.previous_peers.contains(peer)
+            || self.grace.inner.next_epoch.load().contains(peer)
+    }
+
+    /// The snapshot of the current committee and worker cache, built again once either changed.
+    fn snapshot(&self) -> Arc<Snapshot> {
+        let committee = self.committee.load();
+        let worker_cache = self.worker_cache.load();
+        let snapshot = self.snapshot.load_full();
+        if Arc::ptr_eq(&snapshot.committee, &committee)
+            && Arc::ptr_eq(&snapshot.worker_cache, &worker_cache)
+        {
+            return snapshot;
+        }
+
+        let snapshot = Arc::new(Snapshot::new(
+            Arc::clone(&committee),
+            Arc::clone(&worker_cache),
+            &self.grace,
+        ));
+        self.snapshot.store(snapshot.clone());
+        snapshot
+    }
+}
+
+impl AuthorizeRequest for CommitteePeers {
+    fn authorize(&self, request: &mut Request<Bytes>) -> Result<(), Response<Bytes>> {
+        let peer_id = request.peer_id().ok_or_else(|| {
+            (
+                StatusCode::InternalServerError,
+                "This server requires the anemo::PeerId extension",
+            )
+                .into_response()
+        })?;
+
+        if self.contains(peer_id) {
+            Ok(())
+        } else {
+            Err((StatusCode::NotFound, "peer is not in the committee").into_response())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fastcrypto::traits::KeyPair;
+    use test_utils::CommitteeFixture;
+
+    #[test]
+    fn committee_peers() {
+        let fixture = CommitteeFixture::builder().build();
+        let committee: SharedCommittee = fixture.committee().into();
+        let grace = PeerGrace::default();
+        let peers = CommitteePeers::new(
+            committee.clone(),
+            fixture.shared_worker_cache(),
+            grace.clone(),
+        );
+
+        for authority in fixture.authorities() {
+            assert!(peers.contains(&peer_id(&authority.network_public_key())));
+            for worker in authority.worker_keypairs() {
+                assert!(peers.contains(&peer_id(worker.public())));
+            }
+        }
+
+        // Peers outside of the committee are only allowed once they join the next epoch.
+        let outsider = CommitteeFixture::builder().build();
+        let outsider = outsider.authorities().next().unwrap().network_keypair();
+        let outsider_id = peer_id(outsider.public());
+        assert!(!peers.contains(&outsider_id));
+        grace.allow_next_epoch([outsider.public().clone()]);
+        assert!(peers.contains(&outsider_id));
+        grace.allow_next_epoch([]);
+        assert!(!peers.contains(&outsider_id));
+
+        // The peers of the previous committee are allowed for an epoch.
+        let removed = fixture.authorities().next().unwrap().network_keypair();
+        let removed_id = peer_id(removed.public());
+        let mut next = fixture.committee();
+        next.authorities
+            .retain(|_, authority| authority.network_key != *removed.public());
+        next.epoch += 1;
+        committee.store(Arc::new(next.clone()));
+        assert!(peers.contains(&removed_id));
+
+        next.epoch += 1;
+        committee.store(Arc::new(next));
+        assert!(!peers.contains(&removed_id));
+    }
+
+    #[test]
+    fn committee_peers_across_restarts() {
+        let fixture = CommitteeFixture::builder().build();
+        let name = fixture.authorities().next().unwrap().public_key();
+        let removed = fixture.authorities().last().unwrap().network_keypair();
+        let removed_id = peer_id(removed.public());
+        let outsider = CommitteeFixture::builder().build();
+        let outsider = outsider.authorities().next().unwrap().network_keypair();
+        let outsider_id = peer_id(outsider.public());
+        let mut next = fixture.committee();
+        next.authorities
+            .retain(|_, authority| authority.network_key != *removed.public());
+        next.authorities.values_mut().next().unwrap().network_key = outsider.public().clone();
+        next.epoch += 1;
+
+        // The components of the epoch learn the peers of the next one before they shut down.
+        let peers = CommitteePeers::new(
+            fixture.committee().into(),
+            fixture.shared_worker_cache(),
+            PeerGrace::of_authority(&name),
+        );
+        assert!(!peers.contains(&outsider_id));
+        PeerGrace::of_authority(&name).allow_next_epoch(
+            next.authorities
+                .values()
+                .map(|authority| authority.network_key.clone()),
+        );
+        assert!(peers.contains(&outsider_id));
+        drop(peers);
+
+        // The components restarted for the next epoch keep allowing the peers of the previous
+        // one, until the epoch after.
+        let worker_cache: SharedWorkerCache = fixture.shared_worker_cache();
+        let committee: SharedCommittee = next.clone().into();
+        let peers = CommitteePeers::new(
+            committee.clone(),
+            worker_cache,
+            PeerGrace::of_authority(&name),
+        );
+        assert!(peers.contains(&outsider_id));
+        assert!(peers.contains(&removed_id));
+        next.epoch += 1;
+        committee.store(Arc::new(next));
+        assert!(peers.contains(&outsider_id));
+        assert!(!peers.contains(&removed_id));
+    }
+}
@@ -0,0 +1,312 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Accounting and limiting of the egress bandwidth of a node, by peer and traffic class.
+//!
+//! The traffic of a node is either live (the messages of the consensus and the dissemination of
+//! the batches) or sync (the certificates and batches sent to the peers catching up). The live
+//! traffic is never delayed, but it uses the bandwidth of the node: the sync traffic is delayed
+//! to fit in the remaining bandwidth, and within the bandwidth allowed to each peer, so that a
+//! peer pulling history can not saturate the uplink of the node.
+use crate::metrics::NetworkConnectionMetrics;
+use anemo::{PeerId, Request, Response};
+use bytes::Bytes;
+use futures::future::BoxFuture;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+    time::Duration,
+};
+use tokio::time::Instant;
+use tower::{Layer, Service};
+
+/// How long the traffic may exceed the rate of a limit, to absorb its bursts.
+const BURST_TOLERANCE: Duration = Duration::from_millis(100);
+
+/// The number of peers above which the limits of the idle ones are dropped.
+const MAX_IDLE_PEERS: usize = 1_024;
+
+/// The classes of the traffic of a node.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TrafficClass {
+    Live,
+    Sync,
+}
+
+impl TrafficClass {
+    /// The class of the traffic of a route.
+    pub fn of_route(route: &str) -> Self {
+        match route.rsplit('/').next() {
+            Some("GetCertificates" | "FetchCertificates" | "RequestBatch") => Self::Sync,
+            _ => Self::Live,
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Live => "live",
+            Self::Sync => "sync",
+        }
+    }
+}
+
+/// A limit on the rate of a traffic, in bytes per second.
+#[derive(Debug)]
+struct Limit {
+    bytes_per_second: u64,
+    /// When the traffic sent so far would have been sent at the rate of the limit.
+    theoretical_arrival: Instant,
+}
+
+impl Limit {
+    fn new(bytes_per_second: u64) -> Self {
+        Self {
+            bytes_per_second: bytes_per_second.max(1),
+            theoretical_arrival: Instant::now(),
+        }
+    }
+
+    /// Reserves the bandwidth of a message of `size` bytes, returning when it may be sent.
+    fn reserve(&mut self, now: Instant, size: usize) -> Instant {
+        let arrival = self.theoretical_arrival.max(now);
+        self.theoretical_arrival =
+            arrival + Duration::from_secs_f64(size as f64 / self.bytes_per_second as f64);
+        arrival
+            .checked_sub(BURST_TOLERANCE)
+            .map_or(now, |send_at| send_at.max(now))
+    }
+}
+
+/// The egress limits of a node, shared by its inbound and outbound layers.
+#[derive(Clone)]
+pub struct EgressLimiter {
+    inner: Arc<Mutex<Limits>>,
+    metrics: NetworkConnectionMetrics,
+}
+
+struct Limits {
+    /// The bandwidth of the node, shared by the live and the sync traffic.
+    total: Option<Limit>,
+    /// The bandwidth of the sync traffic allowed to each peer.
+    sync_per_peer: Option<u64>,
+    peers: HashMap<PeerId, Limit>,
+}
+
+impl EgressLimiter {
+    /// A limiter for a node with a bandwidth of `total` bytes per second, out of which each peer
+    /// gets at most `sync_per_peer` bytes per second of sync traffic, if limited.
+    pub fn new(
+        total: Option<u64>,
+        sync_per_peer: Option<u64>,
+        metrics: NetworkConnectionMetrics,
+    ) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(Limits {
+                total: total.map(Limit::new),
+                sync_per_peer,
+                peers: HashMap::new(),
+            })),
+            metrics,
+        }
+    }
+
+    /// Accounts `size` bytes sent to `peer` at `now`, returning when they may be sent.
+    fn reserve(
+        &self,
+        now: Instant,
+        peer: Option<PeerId>,
+        class: TrafficClass,
+        size: usize,
+    ) -> Instant {
+        if let Some(peer) = peer {
+            self.metrics
+                .network_peer_egress_bytes
+                .with_label_values(&[&format!("{peer}"), class.as_str()])
+                .inc_by(size as u64);
+        }
+
+        let mut limits = self.inner.lock().unwrap();
+        let send_at = limits
+            .total
+            .as_mut()
+            .map_or(now, |limit| limit.reserve(now, size));
+        if class == TrafficClass::Live {
+            return now;
+        }
+
+        let (Some(peer), Some(sync_per_peer)) = (peer, limits.sync_per_peer) else {
+            return send_at;
+        };
+        if limits.peers.len() > MAX_IDLE_PEERS {
+            limits
+                .peers
+                .retain(|_, limit| limit.theoretical_arrival > now);
+        }
+        let peer_send_at = limits
+            .peers
+            .entry(peer)
+            .or_insert_with(|| Limit::new(sync_per_peer))
+            .reserve(now, size);
+        send_at.max(peer_send_at)
+    }
+
+    /// Waits until `size` bytes of the class may be sent to `peer`.
+    async fn acquire(&self, peer: Option<PeerId>, class: TrafficClass, size: usize) {
+        let send_at = self.reserve(Instant::now(), peer, class, size);
+        let delay = send_at.saturating_duration_since(Instant::now());
+        if !delay.is_zero() {
+            self.metrics
+                .network_sync_egress_delay
+                .observe(delay.as_secs_f64());
+            tokio::time::sleep_until(send_at).await;
+        }
+    }
+}
+
+/// Limits the egress of the requests sent by a node, or of the responses to the requests it
+/// receives.
+#[derive(Clone)]
+pub struct EgressLimitLayer {
+    limiter: EgressLimiter,
+    direction: Direction,
+}
+
+#[derive(Clone, Copy)]
+enum Direction {
+    Inbound,
+    Outbound,
+}
+
+impl EgressLimitLayer {
+    /// Limits the responses to the inbound requests.
+    pub fn inbound(limiter: EgressLimiter) -> Self {
+        Self {
+            limiter,
+            direction: Direction::Inbound,
+        }
+    }
+
+    /// Limits the outbound requests.
+    pub fn outbound(limiter: EgressLimiter) -> Self {
+        Self {
+            limiter,
+            direction: Direction::Outbound,
+        }
+    }
+}
+
+impl<S> Layer<S> for EgressLimitLayer {
+    type Service = EgressLimit<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        EgressLimit {
+            inner,
+            limiter: self.limiter.clone(),
+            direction: self.direction,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct EgressLimit<S> {
+    inner: S,
+    limiter: EgressLimiter,
+    direction: Direction,
+}
+
+impl<S> Service<Request<Bytes>> for EgressLimit<S>
+where
+    S: Service<Request<Bytes>, Response = Response<Bytes>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response<Bytes>;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Response<Bytes>, S::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<Bytes>) -> Self::Future {
+        let peer = request.peer_id().copied();
+        let class = TrafficClass::of_route(request.route());
+        let limiter = self.limiter.clone();
+        match self.direction {
+            Direction::Inbound => {
+                let response = self.inner.call(request);
+                Box::pin(async move {
+                    let response = response.await?;
+                    limiter.acquire(peer, class, response.body().len()).await;
+                    Ok(response)
+                })
+            }
+            Direction::Outbound => {
+                // Use the service which is ready, leaving its clone in its place.
+                let clone = self.inner.clone();
+                let mut inner = std::mem::replace(&mut self.inner, clone);
+                Box::pin(async move {
+                    limiter.acquire(peer, class, request.body().len()).await;
+                    inner.call(request).await
+                })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prometheus::Registry;
+
+    fn limiter(total: Option<u64>, sync_per_peer: Option<u64>) -> EgressLimiter {
+        let metrics = NetworkConnectionMetrics::new("test", &Registry::new());
+        EgressLimiter::new(total, sync_per_peer, metrics)
+    }
+
+    #[test]
+    fn traffic_classes() {
+        assert_eq!(
+            TrafficClass::of_route("/narwhal.PrimaryToPrimary/FetchCertificates"),
+            TrafficClass::Sync
+        );
+        assert_eq!(
+            TrafficClass::of_route("/narwhal.WorkerToWorker/RequestBatch"),
+            TrafficClass::Sync
+        );
+        assert_eq!(
+            TrafficClass::of_route("/narwhal.WorkerToWorker/ReportBatch"),
+            TrafficClass::Live
+        );
+    }
+
+    #[test]
+    fn live_traffic_delays_sync_traffic() {
+        let limiter = limiter(Some(1_000), None);
+        let peer = Some(PeerId([0; 32]));
+
+        // The live traffic is never delayed, even beyond the bandwidth of the node.
+        let now = Instant::now();
+        assert_eq!(limiter.reserve(now, peer, TrafficClass::Live, 1_000), now);
+        assert_eq!(limiter.reserve(now, peer, TrafficClass::Live, 1_000), now);
+
+        // The sync traffic waits for the bandwidth used by the live traffic.
+        let send_at = limiter.reserve(now, peer, TrafficClass::Sync, 1_000);
+        assert_eq!(send_at, now + Duration::from_secs(2) - BURST_TOLERANCE);
+    }
+
+    #[test]
+    fn sync_traffic_per_peer() {
+        let limiter = limiter(None, Some(1_000));
+        let (a, b) = (Some(PeerId([0; 32])), Some(PeerId([1; 32])));
+
+        let now = Instant::now();
+        assert_eq!(limiter.reserve(now, a, TrafficClass::Sync, 2_000), now);
+        assert_eq!(
+            limiter.reserve(now, a, TrafficClass::Sync, 1_000),
+            now + Duration::from_secs(2) - BURST_TOLERANCE
+        );
+        // The other peers are not affected.
+        assert_eq!(limiter.reserve(now, b, TrafficClass::Sync, 1_000), now);
+    }
+}
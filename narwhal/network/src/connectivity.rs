@@ -3,12 +3,26 @@
 
 use crate::metrics::NetworkConnectionMetrics;
 use anemo::PeerId;
+use futures::{stream::FuturesUnordered, StreamExt};
 use mysten_metrics::spawn_logged_monitored_task;
-use std::collections::HashMap;
-use tokio::task::JoinHandle;
+use std::{
+    collections::{HashMap, HashSet},
+    time::Duration,
+};
+use tokio::{
+    task::JoinHandle,
+    time::{timeout, Instant},
+};
+use types::HandshakeClient;
 
 const PEER_TYPE_NONE: &str = "";
 
+/// How often the connected peers are pinged to measure their round-trip time.
+const PING_INTERVAL: Duration = Duration::from_secs(10);
+
+/// How long a ping waits for its response before it is counted as failed.
+const PING_TIMEOUT: Duration = Duration::from_secs(5);
+
 pub struct ConnectionMonitor {
     network: anemo::NetworkRef,
     connection_metrics: NetworkConnectionMetrics,
@@ -34,6 +48,8 @@ impl ConnectionMonitor {
     }
 
     async fn run(self) {
+        // The peers which have been connected at least once, to count their reconnections.
+        let mut seen_peers = HashSet::new();
         let ((mut subscriber, connected_peers), all_peers) = {
             if let Some(network) = self.network.upgrade() {
                 let Ok((subscriber, connected_peers)) = network.subscribe() else {
@@ -63,21 +79,61 @@ impl ConnectionMonitor {
             self.connection_metrics
                 .network_peer_connected
                 .with_label_values(&[&format!("{peer}"), self.peer_type(peer)])
-                .set(1)
+                .set(1);
+            seen_peers.insert(peer);
         }
 
-        while let Ok(event) = subscriber.recv().await {
-            match event {
-                anemo::types::PeerEvent::NewPeer(peer) => self
-                    .connection_metrics
-                    .network_peer_connected
-                    .with_label_values(&[&format!("{peer}"), self.peer_type(peer)])
-                    .set(1),
-                anemo::types::PeerEvent::LostPeer(peer, _) => self
-                    .connection_metrics
-                    .network_peer_connected
-                    .with_label_values(&[&format!("{peer}"), self.peer_type(peer)])
-                    .set(0),
+        let mut ping_interval = tokio::time::interval(PING_INTERVAL);
+        let mut pings = FuturesUnordered::new();
+        // The peers with a ping inflight, which are skipped by the next rounds of pings.
+        let mut pinged_peers = HashSet::new();
+        loop {
+            tokio::select! {
+                event = subscriber.recv() => match event {
+                    Ok(anemo::types::PeerEvent::NewPeer(peer)) => {
+                        self.connection_metrics
+                            .network_peer_connected
+                            .with_label_values(&[&format!("{peer}"), self.peer_type(peer)])
+                            .set(1);
+                        if !seen_peers.insert(peer) {
+                            self.connection_metrics
+                                .network_peer_reconnects
+                                .with_label_values(&[&format!("{peer}"), self.peer_type(peer)])
+                                .inc();
+                        }
+                    }
+                    Ok(anemo::types::PeerEvent::LostPeer(peer, _)) => self
+                        .connection_metrics
+                        .network_peer_connected
+                        .with_label_values(&[&format!("{peer}"), self.peer_type(peer)])
+                        .set(0),
+                    Err(_) => return,
+                },
+
+                _ = ping_interval.tick() => {
+                    let Some(network) = self.network.upgrade() else {
+                        return;
+                    };
+                    for peer_id in network.peers() {
+                        if pinged_peers.contains(&peer_id) {
+                            continue;
+                        }
+                        if let Some(peer) = network.peer(peer_id) {
+                            pinged_peers.insert(peer_id);
+                            pings.push(ping(peer_id, HandshakeClient::new(peer)));
+                        }
+                    }
+                }
+
+                Some((peer, rtt)) = pings.next() => {
+                    pinged_peers.remove(&peer);
+                    if let Some(rtt) = rtt {
+                        self.connection_metrics
+                            .network_peer_rtt
+                            .with_label_values(&[&format!("{peer}"), self.peer_type(peer)])
+                            .observe(rtt.as_secs_f64());
+                    }
+                }
             }
         }
     }
@@ -90,3 +146,17 @@ impl ConnectionMonitor {
         }
     }
 }
+
+/// Pings a peer, returning the round-trip time of the ping if it succeeded within
+/// `PING_TIMEOUT`.
+async fn ping(
+    peer_id: PeerId,
+    mut client: HandshakeClient<anemo::Peer>,
+) -> (PeerId, Option<Duration>) {
+    let start = Instant::now();
+    let rtt = match timeout(PING_TIMEOUT, client.ping(())).await {
+        Ok(Ok(_)) => Some(start.elapsed()),
+        Ok(Err(_)) | Err(_) => None,
+    };
+    (peer_id, rtt)
+}
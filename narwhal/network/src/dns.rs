@@ -0,0 +1,194 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use anemo::PeerId;
+use config::{SharedCommittee, SharedWorkerCache};
+use multiaddr::{Multiaddr, Protocol};
+use mysten_metrics::spawn_logged_monitored_task;
+use std::{collections::HashSet, net::SocketAddr, time::Duration};
+use tokio::{net::lookup_host, sync::watch, task::JoinHandle};
+use tracing::{debug, info, warn};
+use types::ReconfigureNotification;
+
+/// The ip version a dns name is allowed to resolve to.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum IpVersion {
+    Any,
+    V4,
+    V6,
+}
+
+/// Returns the host name, port and allowed ip version of a multiaddr of the form
+/// `/[dns,dns4,dns6]/{}/udp/{port}`, or `None` if the multiaddr does not use a dns name.
+pub fn dns_host_and_port(multiaddr: &Multiaddr) -> Option<(String, u16, IpVersion)> {
+    let mut iter = multiaddr.iter();
+    let (host, version) = match iter.next()? {
+        Protocol::Dns(host) => (host, IpVersion::Any),
+        Protocol::Dns4(host) => (host, IpVersion::V4),
+        Protocol::Dns6(host) => (host, IpVersion::V6),
+        _ => return None,
+    };
+    match iter.next()? {
+        Protocol::Udp(port) => Some((host.to_string(), port, version)),
+        _ => None,
+    }
+}
+
+/// Resolves the dns name of a multiaddr. Returns an empty set if the name does not resolve.
+pub async fn resolve(multiaddr: &Multiaddr) -> HashSet<SocketAddr> {
+    let Some((host, port, version)) = dns_host_and_port(multiaddr) else {
+        return HashSet::new();
+    };
+    match lookup_host((host.as_str(), port)).await {
+        Ok(addresses) => addresses
+            .filter(|address| match version {
+                IpVersion::Any => true,
+                IpVersion::V4 => address.is_ipv4(),
+                IpVersion::V6 => address.is_ipv6(),
+            })
+            .collect(),
+        Err(e) => {
+            warn!("Failed to resolve {multiaddr}: {e}");
+            HashSet::new()
+        }
+    }
+}
+
+/// Anemo resolves dns names every time it dials a peer, so a connection failure is always
+/// followed by a fresh resolution. Long-lived connections however stick to the address they were
+/// opened with. The `DnsRefresher` periodically re-resolves the dns names of the committee and
+/// worker cache, and drops the connections to peers whose name no longer resolves to the address
+/// we are connected to, so that they are re-dialed with the new address.
+pub struct DnsRefresher {
+    network: anemo::NetworkRef,
+    committee: SharedCommittee,
+    worker_cache: SharedWorkerCache,
+    refresh_interval: Duration,
+    rx_reconfigure: watch::Receiver<ReconfigureNotification>,
+}
+
+impl DnsRefresher {
+    #[must_use]
+    pub fn spawn(
+        network: anemo::NetworkRef,
+        committee: SharedCommittee,
+        worker_cache: SharedWorkerCache,
+        refresh_interval: Duration,
+        rx_reconfigure: watch::Receiver<ReconfigureNotification>,
+    ) -> JoinHandle<()> {
+        spawn_logged_monitored_task!(
+            Self {
+                network,
+                committee,
+                worker_cache,
+                refresh_interval,
+                rx_reconfigure,
+            }
+            .run(),
+            "DnsRefresher"
+        )
+    }
+
+    async fn run(mut self) {
+        let mut interval = tokio::time::interval(self.refresh_interval);
+        // The first tick completes immediately.
+        interval.tick().await;
+        loop {
+            tokio::select! {
+                _ = interval.tick() => (),
+
+                result = self.rx_reconfigure.changed() => {
+                    result.expect("Committee channel dropped");
+                    let message = self.rx_reconfigure.borrow().clone();
+                    if let ReconfigureNotification::Shutdown = message {
+                        return;
+                    }
+                    continue;
+                }
+            }
+
+            let Some(network) = self.network.upgrade() else {
+                return;
+            };
+            for (peer_id, multiaddr) in self.dns_peers() {
+                let Some(peer) = network.peer(peer_id) else {
+                    continue;
+                };
+                let addresses = resolve(&multiaddr).await;
+                // Keep the connection if the name (temporarily) does not resolve.
+                if addresses.is_empty() || addresses.contains(&peer.address()) {
+                    continue;
+                }
+                info!(
+                    "Address of peer {peer_id} ({multiaddr}) changed from {} to {addresses:?}, reconnecting",
+                    peer.address()
+                );
+                if let Err(e) = network.disconnect(peer_id) {
+                    debug!("Failed to disconnect peer {peer_id}: {e}");
+                }
+            }
+        }
+    }
+
+    /// The peers of the committee and worker cache whose address is a dns name.
+    fn dns_peers(&self) -> Vec<(PeerId, Multiaddr)> {
+        let committee = self.committee.load();
+        let primaries = committee
+            .authorities
+            .values()
+            .map(|authority| (&authority.network_key, &authority.primary_address));
+        let worker_cache = self.worker_cache.load();
+        let workers = worker_cache
+            .workers
+            .values()
+            .flat_map(|index| index.0.values())
+            .map(|worker| (&worker.name, &worker.worker_address));
+        primaries
+            .chain(workers)
+            .filter(|(_, address)| dns_host_and_port(address).is_some())
+            .map(|(name, address)| (PeerId(name.0.to_bytes()), address.clone()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dns_multiaddrs() {
+        let address: Multiaddr = "/dns/localhost/udp/1234".parse().unwrap();
+        assert_eq!(
+            dns_host_and_port(&address),
+            Some(("localhost".to_string(), 1234, IpVersion::Any))
+        );
+
+        let address: Multiaddr = "/dns4/example.com/udp/1234".parse().unwrap();
+        assert_eq!(
+            dns_host_and_port(&address),
+            Some(("example.com".to_string(), 1234, IpVersion::V4))
+        );
+
+        let address: Multiaddr = "/dns6/example.com/udp/1234".parse().unwrap();
+        assert_eq!(
+            dns_host_and_port(&address),
+            Some(("example.com".to_string(), 1234, IpVersion::V6))
+        );
+
+        let address: Multiaddr = "/ip4/127.0.0.1/udp/1234".parse().unwrap();
+        assert_eq!(dns_host_and_port(&address), None);
+
+        let address: Multiaddr = "/dns/localhost/tcp/1234".parse().unwrap();
+        assert_eq!(dns_host_and_port(&address), None);
+    }
+
+    #[tokio::test]
+    async fn resolve_dns_multiaddr() {
+        let address: Multiaddr = "/dns4/localhost/udp/1234".parse().unwrap();
+        let addresses = resolve(&address).await;
+        assert!(addresses.contains(&"127.0.0.1:1234".parse().unwrap()));
+
+        let address: Multiaddr = "/ip4/127.0.0.1/udp/1234".parse().unwrap();
+        assert!(resolve(&address).await.is_empty());
+    }
+}
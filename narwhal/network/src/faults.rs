@@ -0,0 +1,276 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Injection of network faults (latency, jitter, request drops and bandwidth caps) on the links
+//! between the nodes, for the tests to validate the protocol under degraded networks.
+//!
+//! The [`FaultInjectionLayer`] is part of the outbound request stack of the primaries and workers,
+//! and does nothing until the tests configure faults with [`set_link_faults`]. The faults are
+//! global to the process, so they apply to all the nodes of the in-process clusters: the tests
+//! running concurrently only interfere through the wildcard links.
+//!
+//! The faults are only injected with the `fault-injection` feature, which the test harness
+//! enables. Without it the layer leaves the stack unchanged, so the nodes built for production
+//! pay nothing for it, and the faults configured are ignored.
+use anemo::PeerId;
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::time::Instant;
+use tower::Layer;
+#[cfg(feature = "fault-injection")]
+use {
+    anemo::{
+        types::response::{IntoResponse, StatusCode},
+        Request, Response,
+    },
+    bytes::Bytes,
+    futures::future::BoxFuture,
+    std::task::{Context, Poll},
+    tower::Service,
+};
+
+/// The faults of a link, applied to both its requests and their responses.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LinkFaults {
+    /// The minimum one-way latency of the link.
+    pub latency: Duration,
+    /// The maximum latency added to `latency`, uniformly distributed.
+    pub jitter: Duration,
+    /// The probability for a request to be dropped. Dropped requests fail with a timeout once
+    /// their latency elapsed.
+    pub drop_rate: f64,
+    /// The bandwidth of the link in bytes per second, if capped.
+    pub bandwidth: Option<u64>,
+}
+
+/// A link from the node `from` to the node `to`, `None` matching any node.
+pub type Link = (Option<PeerId>, Option<PeerId>);
+
+struct LinkState {
+    faults: LinkFaults,
+    /// When the messages previously sent over the link are transmitted, given its bandwidth.
+    transmitted_at: Mutex<Instant>,
+}
+
+#[cfg_attr(not(feature = "fault-injection"), allow(dead_code))]
+impl LinkState {
+    /// Waits until a message of `size` bytes goes through the link.
+    async fn transmit(&self, size: usize) {
+        let now = Instant::now();
+        let mut arrival = now + self.faults.latency + self.faults.jitter.mul_f64(rand::random());
+        if let Some(bandwidth) = self.faults.bandwidth {
+            let mut transmitted_at = self.transmitted_at.lock().unwrap();
+            *transmitted_at = (*transmitted_at).max(now)
+                + Duration::from_secs_f64(size as f64 / bandwidth.max(1) as f64);
+            arrival = arrival.max(*transmitted_at);
+        }
+        tokio::time::sleep_until(arrival).await;
+    }
+
+    fn drops(&self) -> bool {
+        self.faults.drop_rate > 0.0 && rand::random::<f64>() < self.faults.drop_rate
+    }
+}
+
+static FAULTS: Mutex<Vec<(Link, Arc<LinkState>)>> = Mutex::new(Vec::new());
+
+/// Injects `faults` on a link, replacing its previous faults.
+pub fn set_link_faults(link: Link, faults: LinkFaults) {
+    let state = Arc::new(LinkState {
+        faults,
+        transmitted_at: Mutex::new(Instant::now()),
+    });
+    let mut links = FAULTS.lock().unwrap();
+    links.retain(|(other, _)| *other != link);
+    links.push((link, state));
+}
+
+/// Removes the faults of a link.
+pub fn clear_link_faults(link: Link) {
+    FAULTS.lock().unwrap().retain(|(other, _)| *other != link);
+}
+
+/// Removes the faults of all the links.
+pub fn clear_all_faults() {
+    FAULTS.lock().unwrap().clear();
+}
+
+/// The faults of the link from `from` to `to`, the most specific link taking precedence.
+#[cfg(feature = "fault-injection")]
+fn link_state(from: PeerId, to: PeerId) -> Option<Arc<LinkState>> {
+    let links = FAULTS.lock().unwrap();
+    if links.is_empty() {
+        return None;
+    }
+    [
+        (Some(from), Some(to)),
+        (None, Some(to)),
+        (Some(from), None),
+        (None, None),
+    ]
+    .iter()
+    .find_map(|link| {
+        links
+            .iter()
+            .find(|(other, _)| other == link)
+            .map(|(_, state)| state.clone())
+    })
+}
+
+/// Injects the faults of the links from the node `local` on its outbound requests.
+#[derive(Clone)]
+pub struct FaultInjectionLayer {
+    #[cfg_attr(not(feature = "fault-injection"), allow(dead_code))]
+    local: PeerId,
+}
+
+impl FaultInjectionLayer {
+    pub fn new(local: PeerId) -> Self {
+        Self { local }
+    }
+}
+
+#[cfg(not(feature = "fault-injection"))]
+impl<S> Layer<S> for FaultInjectionLayer {
+    type Service = S;
+
+    fn layer(&self, inner: S) -> S {
+        inner
+    }
+}
+
+#[cfg(feature = "fault-injection")]
+impl<S> Layer<S> for FaultInjectionLayer {
+    type Service = FaultInjection<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        FaultInjection {
+            inner,
+            local: self.local,
+        }
+    }
+}
+
+#[cfg(feature = "fault-injection")]
+#[derive(Clone)]
+pub struct FaultInjection<S> {
+    inner: S,
+    local: PeerId,
+}
+
+#[cfg(feature = "fault-injection")]
+impl<S> Service<Request<Bytes>> for FaultInjection<S>
+where
+    S: Service<Request<Bytes>, Response = Response<Bytes>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response<Bytes>;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Response<Bytes>, S::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<Bytes>) -> Self::Future {
+        let link = request
+            .peer_id()
+            .and_then(|peer| link_state(self.local, *peer));
+        let Some(link) = link else {
+            return Box::pin(self.inner.call(request));
+        };
+
+        // Use the service which is ready, leaving its clone in its place.
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        Box::pin(async move {
+            link.transmit(request.body().len()).await;
+            if link.drops() {
+                return Ok((StatusCode::RequestTimeout, "request dropped").into_response());
+            }
+            let response = inner.call(request).await?;
+            link.transmit(response.body().len()).await;
+            Ok(response)
+        })
+    }
+}
+
+#[cfg(all(test, feature = "fault-injection"))]
+mod tests {
+    use super::*;
+    use std::convert::Infallible;
+    use tower::ServiceExt;
+
+    fn peer() -> PeerId {
+        PeerId(rand::random())
+    }
+
+    async fn send(local: PeerId, remote: PeerId, size: usize) -> (Duration, Response<Bytes>) {
+        let service = FaultInjectionLayer::new(local).layer(tower::service_fn(
+            |request: Request<Bytes>| async move {
+                Ok::<_, Infallible>(Response::new(request.into_body()))
+            },
+        ));
+        let mut request = Request::new(Bytes::from(vec![0; size]));
+        request.extensions_mut().insert(remote);
+        let start = Instant::now();
+        let response = service.oneshot(request).await.unwrap();
+        (start.elapsed(), response)
+    }
+
+    #[tokio::test]
+    async fn link_faults() {
+        let (a, b, c) = (peer(), peer(), peer());
+        let latency = Duration::from_millis(200);
+        set_link_faults(
+            (Some(a), Some(b)),
+            LinkFaults {
+                latency,
+                ..LinkFaults::default()
+            },
+        );
+        set_link_faults(
+            (Some(a), Some(c)),
+            LinkFaults {
+                drop_rate: 1.0,
+                ..LinkFaults::default()
+            },
+        );
+
+        // The latency applies to the request and its response.
+        let (elapsed, response) = send(a, b, 10).await;
+        assert!(elapsed >= 2 * latency);
+        assert_eq!(response.status(), StatusCode::Success);
+
+        // The other direction of the link is not affected.
+        let (elapsed, _) = send(b, a, 10).await;
+        assert!(elapsed < latency);
+
+        let (_, response) = send(a, c, 10).await;
+        assert_eq!(response.status(), StatusCode::RequestTimeout);
+
+        clear_link_faults((Some(a), Some(c)));
+        let (_, response) = send(a, c, 10).await;
+        assert_eq!(response.status(), StatusCode::Success);
+        clear_link_faults((Some(a), Some(b)));
+    }
+
+    #[tokio::test]
+    async fn bandwidth_cap() {
+        let (a, b) = (peer(), peer());
+        set_link_faults(
+            (Some(a), Some(b)),
+            LinkFaults {
+                bandwidth: Some(10_000),
+                ..LinkFaults::default()
+            },
+        );
+
+        // 1000 bytes each way at 10 KB/s.
+        let (elapsed, _) = send(a, b, 1_000).await;
+        assert!(elapsed >= Duration::from_millis(200));
+        clear_link_faults((Some(a), Some(b)));
+    }
+}
@@ -0,0 +1,536 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{auth::CommitteePeers, metrics::NetworkConnectionMetrics};
+use anemo::{
+    types::response::{IntoResponse, StatusCode},
+    PeerId, Request, Response,
+};
+use async_trait::async_trait;
+use bytes::Bytes;
+use config::{Epoch, ProtocolConfig, ProtocolVersion, SharedCommittee};
+use futures::{future::BoxFuture, stream::FuturesUnordered, StreamExt};
+use mysten_metrics::spawn_logged_monitored_task;
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    task::{Context, Poll},
+    time::Duration,
+};
+use thiserror::Error;
+use tokio::{sync::watch, task::JoinHandle};
+use tower::{Layer, Service};
+use tracing::{debug, error};
+use types::{Handshake, HandshakeClient, HandshakeMessage};
+
+/// How long the requests to a peer are held for its handshake, before they fail.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long to wait before handshaking again a connected peer which could not be reached.
+const HANDSHAKE_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// The routes let through before the handshake: the handshakes themselves, and the announcements
+/// of network keys, which are signed by the authorities and sent to the peers not knowing the key
+/// of the node yet, hence not answering its handshakes.
+const UNGATED_ROUTES: &[&str] = &["/narwhal.Handshake/", "/narwhal.NetworkKeys/"];
+
+/// The reasons for which two nodes can not talk to each other.
+#[derive(Clone, Debug, Error, Eq, PartialEq)]
+pub enum IncompatiblePeer {
+    #[error("Peer belongs to chain {theirs:?}, while we belong to chain {ours:?}")]
+    ChainId { ours: String, theirs: String },
+
+    #[error("Peer runs protocol version {theirs}, while we support versions {min} to {max}")]
+    UnsupportedVersion {
+        theirs: ProtocolVersion,
+        min: ProtocolVersion,
+        max: ProtocolVersion,
+    },
+
+    #[error("Peer supports protocol versions {min} to {max}, while we run version {ours}")]
+    UnsupportedByPeer {
+        ours: ProtocolVersion,
+        min: ProtocolVersion,
+        max: ProtocolVersion,
+    },
+
+    #[error("Peer runs protocol version {theirs} in epoch {epoch}, while we run version {ours}")]
+    VersionMismatch {
+        epoch: Epoch,
+        ours: ProtocolVersion,
+        theirs: ProtocolVersion,
+    },
+}
+
+/// Checks a peer advertising `theirs` can talk to a node advertising `ours`. The peers may be in
+/// different epochs while the committee changes, as long as they both support the protocol version
+/// of the other; within an epoch they must run the same version.
+pub fn check_compatibility(
+    ours: &HandshakeMessage,
+    theirs: &HandshakeMessage,
+) -> Result<(), IncompatiblePeer> {
+    if ours.chain_id != theirs.chain_id {
+        return Err(IncompatiblePeer::ChainId {
+            ours: ours.chain_id.clone(),
+            theirs: theirs.chain_id.clone(),
+        });
+    }
+    if !(ours.min_supported_version..=ours.max_supported_version).contains(&theirs.protocol_version)
+    {
+        return Err(IncompatiblePeer::UnsupportedVersion {
+            theirs: theirs.protocol_version,
+            min: ours.min_supported_version,
+            max: ours.max_supported_version,
+        });
+    }
+    if !(theirs.min_supported_version..=theirs.max_supported_version)
+        .contains(&ours.protocol_version)
+    {
+        return Err(IncompatiblePeer::UnsupportedByPeer {
+            ours: ours.protocol_version,
+            min: theirs.min_supported_version,
+            max: theirs.max_supported_version,
+        });
+    }
+    if ours.epoch == theirs.epoch && ours.protocol_version != theirs.protocol_version {
+        return Err(IncompatiblePeer::VersionMismatch {
+            epoch: ours.epoch,
+            ours: ours.protocol_version,
+            theirs: theirs.protocol_version,
+        });
+    }
+    Ok(())
+}
+
+/// What this node advertises to its peers, as of the current committee.
+fn local_handshake(committee: &SharedCommittee, chain_id: &str) -> HandshakeMessage {
+    let committee = committee.load();
+    HandshakeMessage {
+        chain_id: chain_id.to_string(),
+        epoch: committee.epoch(),
+        protocol_version: committee.protocol_config.version,
+        min_supported_version: ProtocolConfig::MIN_SUPPORTED_VERSION,
+        max_supported_version: ProtocolConfig::MAX_SUPPORTED_VERSION,
+    }
+}
+
+/// Answers the handshakes of the peers, rejecting the incompatible ones, and their pings.
+#[derive(Clone)]
+pub struct HandshakeHandler {
+    committee: SharedCommittee,
+    chain_id: String,
+}
+
+impl HandshakeHandler {
+    pub fn new(committee: SharedCommittee, chain_id: String) -> Self {
+        Self {
+            committee,
+            chain_id,
+        }
+    }
+}
+
+#[async_trait]
+impl Handshake for HandshakeHandler {
+    async fn handshake(
+        &self,
+        request: anemo::Request<HandshakeMessage>,
+    ) -> Result<anemo::Response<HandshakeMessage>, anemo::rpc::Status> {
+        let ours = local_handshake(&self.committee, &self.chain_id);
+        check_compatibility(&ours, request.body()).map_err(|e| {
+            anemo::rpc::Status::new_with_message(StatusCode::BadRequest, e.to_string())
+        })?;
+        Ok(anemo::Response::new(ours))
+    }
+
+    async fn ping(
+        &self,
+        _request: anemo::Request<()>,
+    ) -> Result<anemo::Response<()>, anemo::rpc::Status> {
+        Ok(anemo::Response::new(()))
+    }
+}
+
+/// The outcome of the handshakes of the connected peers: whether they are compatible, or absent
+/// while their handshake is pending.
+#[derive(Clone)]
+pub struct HandshakenPeers(Arc<watch::Sender<HashMap<PeerId, bool>>>);
+
+impl Default for HandshakenPeers {
+    fn default() -> Self {
+        Self(Arc::new(watch::channel(HashMap::new()).0))
+    }
+}
+
+impl HandshakenPeers {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the outcome of the handshake of `peer`, `None` while it is pending.
+    fn set(&self, peer: PeerId, compatible: Option<bool>) {
+        self.0.send_modify(|peers| match compatible {
+            Some(compatible) => {
+                peers.insert(peer, compatible);
+            }
+            None => {
+                peers.remove(&peer);
+            }
+        });
+    }
+
+    fn is_compatible(&self, peer: &PeerId) -> bool {
+        self.0.borrow().get(peer).copied().unwrap_or(false)
+    }
+
+    /// Waits for the handshake of `peer`, returning whether it is compatible.
+    async fn wait(&self, peer: PeerId) -> bool {
+        let mut receiver = self.0.subscribe();
+        loop {
+            if let Some(compatible) = receiver.borrow_and_update().get(&peer) {
+                return *compatible;
+            }
+            if receiver.changed().await.is_err() {
+                return false;
+            }
+        }
+    }
+}
+
+/// Holds the outbound requests to a peer until its handshake succeeds, so that no traffic reaches
+/// an incompatible peer. The requests fail if the handshake does not succeed in time.
+#[derive(Clone)]
+pub struct HandshakeGateLayer {
+    peers: HandshakenPeers,
+}
+
+impl HandshakeGateLayer {
+    pub fn new(peers: HandshakenPeers) -> Self {
+        Self { peers }
+    }
+}
+
+impl<S> Layer<S> for HandshakeGateLayer {
+    type Service = HandshakeGate<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        HandshakeGate {
+            inner,
+            peers: self.peers.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct HandshakeGate<S> {
+    inner: S,
+    peers: HandshakenPeers,
+}
+
+impl<S> Service<Request<Bytes>> for HandshakeGate<S>
+where
+    S: Service<Request<Bytes>, Response = Response<Bytes>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response<Bytes>;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Response<Bytes>, S::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<Bytes>) -> Self::Future {
+        let ungated = UNGATED_ROUTES
+            .iter()
+            .any(|route| request.route().starts_with(route));
+        let peer = match request.peer_id() {
+            Some(peer) if !ungated && !self.peers.is_compatible(peer) => *peer,
+            _ => return Box::pin(self.inner.call(request)),
+        };
+
+        // Use the service which is ready, leaving its clone in its place.
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let peers = self.peers.clone();
+        Box::pin(async move {
+            match tokio::time::timeout(HANDSHAKE_TIMEOUT, peers.wait(peer)).await {
+                Ok(true) => inner.call(request).await,
+                Ok(false) => {
+                    Ok((StatusCode::BadRequest, "handshake with the peer failed").into_response())
+                }
+                Err(_) => Ok((
+                    StatusCode::RequestTimeout,
+                    "handshake with the peer timed out",
+                )
+                    .into_response()),
+            }
+        })
+    }
+}
+
+/// Handshakes every new peer, disconnecting the incompatible ones as well as the ones outside of
+/// the committee, and reports the protocol versions of the connected peers. The outcome of the
+/// handshakes opens the [`HandshakeGateLayer`] of the outbound requests.
+pub struct HandshakeMonitor {
+    network: anemo::NetworkRef,
+    committee: SharedCommittee,
+    committee_peers: CommitteePeers,
+    chain_id: String,
+    connection_metrics: NetworkConnectionMetrics,
+    handshaken_peers: HandshakenPeers,
+    /// The protocol versions advertised by the connected peers.
+    peer_versions: HashMap<PeerId, ProtocolVersion>,
+}
+
+impl HandshakeMonitor {
+    #[must_use]
+    pub fn spawn(
+        network: anemo::NetworkRef,
+        committee: SharedCommittee,
+        committee_peers: CommitteePeers,
+        chain_id: String,
+        connection_metrics: NetworkConnectionMetrics,
+        handshaken_peers: HandshakenPeers,
+    ) -> JoinHandle<()> {
+        spawn_logged_monitored_task!(
+            Self {
+                network,
+                committee,
+                committee_peers,
+                chain_id,
+                connection_metrics,
+                handshaken_peers,
+                peer_versions: HashMap::new(),
+            }
+            .run(),
+            "HandshakeMonitor"
+        )
+    }
+
+    async fn run(mut self) {
+        let (mut subscriber, connected_peers) = {
+            let Some(network) = self.network.upgrade() else {
+                return;
+            };
+            let Ok((subscriber, connected_peers)) = network.subscribe() else {
+                return;
+            };
+            (subscriber, connected_peers)
+        };
+
+        let mut handshakes = FuturesUnordered::new();
+        for peer in connected_peers {
+            handshakes.push(self.handshake(peer, Duration::ZERO));
+        }
+
+        loop {
+            tokio::select! {
+                event = subscriber.recv() => match event {
+                    Ok(anemo::types::PeerEvent::NewPeer(peer)) => {
+                        self.handshaken_peers.set(peer, None);
+                        handshakes.push(self.handshake(peer, Duration::ZERO));
+                    }
+                    Ok(anemo::types::PeerEvent::LostPeer(peer, _)) => {
+                        // The requests to the peer are held until it reconnects.
+                        self.handshaken_peers.set(peer, None);
+                        if let Some(version) = self.peer_versions.remove(&peer) {
+                            self.connection_metrics
+                                .network_peers_per_version
+                                .with_label_values(&[&version.to_string()])
+                                .dec();
+                        }
+                    }
+                    Err(_) => return,
+                },
+
+                Some((peer, result)) = handshakes.next() => {
+                    let Some(network) = self.network.upgrade() else {
+                        return;
+                    };
+                    // The peer may have disconnected during the handshake.
+                    let connected = network.peer(peer).is_some();
+                    match result {
+                        Ok(None) => {
+                            if connected {
+                                handshakes.push(self.handshake(peer, HANDSHAKE_RETRY_DELAY));
+                            }
+                        }
+                        Ok(Some(version)) => {
+                            if connected {
+                                self.handshaken_peers.set(peer, Some(true));
+                                if self.peer_versions.insert(peer, version).is_none() {
+                                    self.connection_metrics
+                                        .network_peers_per_version
+                                        .with_label_values(&[&version.to_string()])
+                                        .inc();
+                                }
+                            }
+                        }
+                        Err(e) => {
+                            self.handshaken_peers.set(peer, Some(false));
+                            error!("Disconnecting peer {peer}: {e}");
+                            if let Err(e) = network.disconnect(peer) {
+                                debug!("Failed to disconnect peer {peer}: {e}");
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Handshakes `peer` after `delay`, returning the protocol version it runs if it is a
+    /// compatible member of the committee, or `None` if it could not be reached. The latter are
+    /// not rejected, as they are handshaken again while they are connected. The peers not serving
+    /// the handshakes run a release predating them, and are incompatible.
+    fn handshake(
+        &self,
+        peer_id: PeerId,
+        delay: Duration,
+    ) -> impl std::future::Future<Output = (PeerId, Result<Option<ProtocolVersion>, String>)> {
+        let network = self.network.clone();
+        let committee = self.committee.clone();
+        let chain_id = self.chain_id.clone();
+        let committee_peers = self.committee_peers.clone();
+        async move {
+            tokio::time::sleep(delay).await;
+            if !committee_peers.contains(&peer_id) {
+                return (peer_id, Err("peer is not in the committee".to_string()));
+            }
+            let ours = local_handshake(&committee, &chain_id);
+            let Some(peer) = network.upgrade().and_then(|network| network.peer(peer_id)) else {
+                return (peer_id, Ok(None));
+            };
+            let result = match HandshakeClient::new(peer).handshake(ours.clone()).await {
+                Ok(response) => {
+                    let theirs = response.into_body();
+                    check_compatibility(&ours, &theirs)
+                        .map(|()| Some(theirs.protocol_version))
+                        .map_err(|e| e.to_string())
+                }
+                // The peer rejected us.
+                Err(status) if status.status() == StatusCode::BadRequest => {
+                    Err(status.message().unwrap_or("handshake rejected").to_string())
+                }
+                Err(status) if status.status() == StatusCode::NotFound => {
+                    Err("peer does not serve the handshakes".to_string())
+                }
+                Err(status) => {
+                    debug!("Failed to handshake peer {peer_id}: {status:?}");
+                    Ok(None)
+                }
+            };
+            (peer_id, result)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::convert::Infallible;
+    use tower::ServiceExt;
+
+    fn handshake(epoch: Epoch, protocol_version: ProtocolVersion) -> HandshakeMessage {
+        HandshakeMessage {
+            chain_id: "chain".to_string(),
+            epoch,
+            protocol_version,
+            min_supported_version: 1,
+            max_supported_version: 3,
+        }
+    }
+
+    #[test]
+    fn compatible_peers() {
+        assert_eq!(
+            check_compatibility(&handshake(0, 1), &handshake(0, 1)),
+            Ok(())
+        );
+        // The peers may run different versions while changing epochs.
+        assert_eq!(
+            check_compatibility(&handshake(1, 2), &handshake(0, 1)),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn incompatible_peers() {
+        let ours = handshake(0, 1);
+
+        let theirs = HandshakeMessage {
+            chain_id: "other chain".to_string(),
+            ..ours.clone()
+        };
+        assert_eq!(
+            check_compatibility(&ours, &theirs),
+            Err(IncompatiblePeer::ChainId {
+                ours: "chain".to_string(),
+                theirs: "other chain".to_string()
+            })
+        );
+
+        let theirs = HandshakeMessage {
+            min_supported_version: 4,
+            max_supported_version: 4,
+            ..handshake(1, 4)
+        };
+        assert_eq!(
+            check_compatibility(&ours, &theirs),
+            Err(IncompatiblePeer::UnsupportedVersion {
+                theirs: 4,
+                min: 1,
+                max: 3
+            })
+        );
+
+        let theirs = HandshakeMessage {
+            min_supported_version: 2,
+            ..handshake(1, 2)
+        };
+        assert_eq!(
+            check_compatibility(&ours, &theirs),
+            Err(IncompatiblePeer::UnsupportedByPeer {
+                ours: 1,
+                min: 2,
+                max: 3
+            })
+        );
+
+        assert_eq!(
+            check_compatibility(&ours, &handshake(0, 2)),
+            Err(IncompatiblePeer::VersionMismatch {
+                epoch: 0,
+                ours: 1,
+                theirs: 2
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn hold_requests_until_handshake() {
+        let peers = HandshakenPeers::new();
+        let service = HandshakeGateLayer::new(peers.clone()).layer(tower::service_fn(
+            |_request: Request<Bytes>| async { Ok::<_, Infallible>(Response::new(Bytes::new())) },
+        ));
+        let request = |peer: PeerId| {
+            let mut request = Request::new(Bytes::new());
+            request.extensions_mut().insert(peer);
+            request
+        };
+        let (compatible, incompatible) = (PeerId([1; 32]), PeerId([2; 32]));
+
+        // The request is sent once the handshake succeeds.
+        let pending = tokio::spawn(service.clone().oneshot(request(compatible)));
+        tokio::task::yield_now().await;
+        assert!(!pending.is_finished());
+        peers.set(compatible, Some(true));
+        let response = pending.await.unwrap().unwrap();
+        assert_eq!(response.status(), StatusCode::Success);
+
+        peers.set(incompatible, Some(false));
+        let response = service.oneshot(request(incompatible)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BadRequest);
+    }
+}
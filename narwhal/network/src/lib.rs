@@ -11,11 +11,17 @@
 
 pub mod admin;
 pub mod anemo_ext;
+pub mod auth;
+pub mod bandwidth;
 pub mod connectivity;
+pub mod dns;
 pub mod failpoints;
+pub mod faults;
+pub mod handshake;
 pub mod metrics;
 mod p2p;
 mod retry;
+pub mod trace_id;
 mod traits;
 
 pub use crate::{
@@ -49,7 +55,8 @@ impl<T> std::future::Future for CancelOnDropHandler<T> {
     }
 }
 
-/// Attempts to convert a multiaddr of the form `/[ip4,ip6,dns]/{}/udp/{port}` into an anemo address
+/// Attempts to convert a multiaddr of the form `/[ip4,ip6,dns,dns4,dns6]/{}/udp/{port}` into an anemo
+/// address. Dns names are kept as such, so that anemo resolves them every time it dials the peer.
 pub fn multiaddr_to_address(
     multiaddr: &multiaddr::Multiaddr,
 ) -> anyhow::Result<anemo::types::Address> {
@@ -59,7 +66,9 @@ pub fn multiaddr_to_address(
     match (iter.next(), iter.next()) {
         (Some(Protocol::Ip4(ipaddr)), Some(Protocol::Udp(port))) => Ok((ipaddr, port).into()),
         (Some(Protocol::Ip6(ipaddr)), Some(Protocol::Udp(port))) => Ok((ipaddr, port).into()),
-        (Some(Protocol::Dns(hostname)), Some(Protocol::Udp(port))) => {
+        (Some(Protocol::Dns(hostname)), Some(Protocol::Udp(port)))
+        | (Some(Protocol::Dns4(hostname)), Some(Protocol::Udp(port)))
+        | (Some(Protocol::Dns6(hostname)), Some(Protocol::Udp(port))) => {
             Ok((hostname.as_ref(), port).into())
         }
 
@@ -69,3 +78,61 @@ pub fn multiaddr_to_address(
         }
     }
 }
+
+/// The address a node listens on, given the address it is reachable at: the unspecified address of
+/// the same ip version, i.e. all the interfaces, with the same port and transport. Dual-stack
+/// nodes listen on `[::]` to accept the ip6 and ip4 connections alike.
+pub fn listen_address(multiaddr: &multiaddr::Multiaddr, dual_stack: bool) -> multiaddr::Multiaddr {
+    use multiaddr::Protocol;
+    use std::net::{Ipv4Addr, Ipv6Addr};
+    multiaddr
+        .replace(0, |protocol| {
+            let ip6 = matches!(protocol, Protocol::Ip6(_) | Protocol::Dns6(_));
+            Some(if ip6 || dual_stack {
+                Protocol::Ip6(Ipv6Addr::UNSPECIFIED)
+            } else {
+                Protocol::Ip4(Ipv4Addr::UNSPECIFIED)
+            })
+        })
+        .unwrap_or_else(|| multiaddr.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn listen_addresses() {
+        for (address, listen, dual_stack) in [
+            (
+                "/ip4/1.2.3.4/udp/1234",
+                "/ip4/0.0.0.0/udp/1234",
+                "/ip6/::/udp/1234",
+            ),
+            (
+                "/ip6/2001:db8::1/udp/1234",
+                "/ip6/::/udp/1234",
+                "/ip6/::/udp/1234",
+            ),
+            (
+                "/dns/example.com/tcp/1234/http",
+                "/ip4/0.0.0.0/tcp/1234/http",
+                "/ip6/::/tcp/1234/http",
+            ),
+            (
+                "/dns4/example.com/udp/1234",
+                "/ip4/0.0.0.0/udp/1234",
+                "/ip6/::/udp/1234",
+            ),
+            (
+                "/dns6/example.com/udp/1234",
+                "/ip6/::/udp/1234",
+                "/ip6/::/udp/1234",
+            ),
+        ] {
+            let address: multiaddr::Multiaddr = address.parse().unwrap();
+            assert_eq!(listen_address(&address, false).to_string(), listen);
+            assert_eq!(listen_address(&address, true).to_string(), dual_stack);
+        }
+    }
+}
@@ -2,9 +2,9 @@
 // SPDX-License-Identifier: Apache-2.0
 use anemo_tower::callback::{MakeCallbackHandler, ResponseHandler};
 use prometheus::{
-    register_histogram_vec_with_registry, register_int_counter_vec_with_registry,
-    register_int_gauge_vec_with_registry, HistogramTimer, HistogramVec, IntCounterVec, IntGaugeVec,
-    Registry,
+    register_histogram_vec_with_registry, register_histogram_with_registry,
+    register_int_counter_vec_with_registry, register_int_gauge_vec_with_registry, Histogram,
+    HistogramTimer, HistogramVec, IntCounterVec, IntGaugeVec, Registry,
 };
 use std::sync::Arc;
 
@@ -12,6 +12,16 @@ use std::sync::Arc;
 pub struct NetworkConnectionMetrics {
     /// The connection status of a peer. 0 if not connected, 1 if connected.
     pub network_peer_connected: IntGaugeVec,
+    /// The number of connected peers by the protocol version they advertised in their handshake.
+    pub network_peers_per_version: IntGaugeVec,
+    /// The round-trip time of the pings of a peer.
+    pub network_peer_rtt: HistogramVec,
+    /// The number of times a peer got connected again after losing its connection.
+    pub network_peer_reconnects: IntCounterVec,
+    /// The number of bytes sent to a peer, by traffic class (live or sync).
+    pub network_peer_egress_bytes: IntCounterVec,
+    /// How long the sync traffic got delayed by the egress limits.
+    pub network_sync_egress_delay: Histogram,
 }
 
 impl NetworkConnectionMetrics {
@@ -24,6 +34,42 @@ impl NetworkConnectionMetrics {
                 registry
             )
             .unwrap(),
+            network_peers_per_version: register_int_gauge_vec_with_registry!(
+                format!("{node}_network_peers_per_version"),
+                "The number of connected peers by the protocol version they advertised",
+                &["version"],
+                registry
+            )
+            .unwrap(),
+            network_peer_rtt: register_histogram_vec_with_registry!(
+                format!("{node}_network_peer_rtt"),
+                "The round-trip time of the pings of a peer, in seconds",
+                &["peer_id", "type"],
+                LATENCY_SEC_BUCKETS.to_vec(),
+                registry
+            )
+            .unwrap(),
+            network_peer_reconnects: register_int_counter_vec_with_registry!(
+                format!("{node}_network_peer_reconnects"),
+                "The number of times a peer got connected again after losing its connection",
+                &["peer_id", "type"],
+                registry
+            )
+            .unwrap(),
+            network_peer_egress_bytes: register_int_counter_vec_with_registry!(
+                format!("{node}_network_peer_egress_bytes"),
+                "The number of bytes sent to a peer, by traffic class",
+                &["peer_id", "class"],
+                registry
+            )
+            .unwrap(),
+            network_sync_egress_delay: register_histogram_with_registry!(
+                format!("{node}_network_sync_egress_delay"),
+                "How long the sync traffic got delayed by the egress limits, in seconds",
+                LATENCY_SEC_BUCKETS.to_vec(),
+                registry
+            )
+            .unwrap(),
         }
     }
 }
@@ -42,6 +88,12 @@ pub struct NetworkMetrics {
     inflight_requests: IntGaugeVec,
     /// Failed requests by route
     errors: IntCounterVec,
+    /// Bytes of the requests by peer
+    peer_request_bytes: IntCounterVec,
+    /// Bytes of the responses by peer
+    peer_response_bytes: IntCounterVec,
+    /// Gauge of the number of inflight requests at any given time by peer
+    peer_inflight_requests: IntGaugeVec,
 }
 
 const LATENCY_SEC_BUCKETS: &[f64] = &[
@@ -104,6 +156,30 @@ impl NetworkMetrics {
         )
         .unwrap();
 
+        let peer_request_bytes = register_int_counter_vec_with_registry!(
+            format!("{node}_{direction}_peer_request_bytes"),
+            "The number of bytes of the requests by peer",
+            &["peer_id"],
+            registry,
+        )
+        .unwrap();
+
+        let peer_response_bytes = register_int_counter_vec_with_registry!(
+            format!("{node}_{direction}_peer_response_bytes"),
+            "The number of bytes of the responses by peer",
+            &["peer_id"],
+            registry,
+        )
+        .unwrap();
+
+        let peer_inflight_requests = register_int_gauge_vec_with_registry!(
+            format!("{node}_{direction}_peer_inflight_requests"),
+            "The number of inflight network requests by peer",
+            &["peer_id"],
+            registry
+        )
+        .unwrap();
+
         Self {
             requests,
             request_latency,
@@ -111,6 +187,9 @@ impl NetworkMetrics {
             response_size,
             inflight_requests,
             errors,
+            peer_request_bytes,
+            peer_response_bytes,
+            peer_inflight_requests,
         }
     }
 }
@@ -131,6 +210,10 @@ impl MakeCallbackHandler for MetricsMakeCallbackHandler {
 
     fn make_handler(&self, request: &anemo::Request<bytes::Bytes>) -> Self::Handler {
         let route = request.route().to_owned();
+        let peer = request
+            .peer_id()
+            .map(|peer_id| format!("{peer_id}"))
+            .unwrap_or_default();
 
         self.metrics.requests.with_label_values(&[&route]).inc();
         self.metrics
@@ -141,6 +224,14 @@ impl MakeCallbackHandler for MetricsMakeCallbackHandler {
             .request_size
             .with_label_values(&[&route])
             .observe(request.body().len() as f64);
+        self.metrics
+            .peer_request_bytes
+            .with_label_values(&[&peer])
+            .inc_by(request.body().len() as u64);
+        self.metrics
+            .peer_inflight_requests
+            .with_label_values(&[&peer])
+            .inc();
 
         let timer = self
             .metrics
@@ -152,6 +243,7 @@ impl MakeCallbackHandler for MetricsMakeCallbackHandler {
             metrics: self.metrics.clone(),
             timer,
             route,
+            peer,
         }
     }
 }
@@ -162,6 +254,7 @@ pub struct MetricsResponseHandler {
     #[allow(unused)]
     timer: HistogramTimer,
     route: String,
+    peer: String,
 }
 
 impl ResponseHandler for MetricsResponseHandler {
@@ -170,6 +263,10 @@ impl ResponseHandler for MetricsResponseHandler {
             .response_size
             .with_label_values(&[&self.route])
             .observe(response.body().len() as f64);
+        self.metrics
+            .peer_response_bytes
+            .with_label_values(&[&self.peer])
+            .inc_by(response.body().len() as u64);
 
         if !response.status().is_success() {
             let status = response.status().to_u16().to_string();
@@ -194,5 +291,9 @@ impl Drop for MetricsResponseHandler {
             .inflight_requests
             .with_label_values(&[&self.route])
             .dec();
+        self.metrics
+            .peer_inflight_requests
+            .with_label_values(&[&self.peer])
+            .dec();
     }
 }
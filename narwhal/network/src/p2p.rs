@@ -3,6 +3,7 @@
 
 use crate::traits::{PrimaryToPrimaryRpc, PrimaryToWorkerRpc, WorkerRpc};
 use crate::{
+    trace_id,
     traits::{ReliableNetwork, UnreliableNetwork},
     CancelOnDropHandler, RetryConfig,
 };
@@ -36,11 +37,11 @@ where
         anemo::Error::msg(format!("Network has no connection with peer {peer_id}"))
     })?;
 
-    Ok(tokio::spawn(async move {
+    Ok(tokio::spawn(trace_id::in_current_scope(async move {
         f(peer)
             .await
             .map_err(|e| anyhow::anyhow!("RPC error: {e:?}"))
-    }))
+    })))
 }
 
 fn send<F, R, Fut>(
@@ -80,7 +81,7 @@ where
         retrying_max_elapsed_time: None, // retry forever
         ..Default::default()
     };
-    let task = tokio::spawn(retry_config.retry(message_send));
+    let task = tokio::spawn(trace_id::in_current_scope(retry_config.retry(message_send)));
 
     CancelOnDropHandler(task)
 }
@@ -0,0 +1,300 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Propagation of the trace ids correlating the RPCs of the nodes, from the submission of a
+//! transaction to the dissemination of its batch and the proposal of the header including it.
+//!
+//! The trace id of a task is set with [`scope`], and sent along its outbound requests in the
+//! [`TRACE_ID_HEADER`] header by the [`PropagateTraceIdLayer`]. The [`ExtractTraceIdLayer`] runs
+//! the handlers of the inbound requests in the scope of their trace id, within a span recording
+//! it. The requests sent outside of a scope get a trace id of their own, to correlate the logs of
+//! both of their ends.
+//!
+//! The spans of the stages of the pipeline are grouped in the exported OpenTelemetry trace of
+//! their trace id with [`join`], and linked to the traces of the other ids they relate to with
+//! [`link`]. The stages downstream of the network messages, such as the commit of a certificate,
+//! find the trace id of a digest with [`lookup`] after an upstream stage [`record`]ed it.
+use anemo::{Request, Response};
+use bytes::Bytes;
+use futures::future::BoxFuture;
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    fmt,
+    future::Future,
+    sync::Mutex,
+    task::{Context, Poll},
+};
+use tower::{Layer, Service};
+use tracing::{Instrument, Span};
+use types::Certificate;
+
+/// The header (and gRPC metadata key) carrying the trace id of a request.
+pub const TRACE_ID_HEADER: &str = "x-trace-id";
+
+/// The longest trace id accepted from the clients and peers.
+const MAX_TRACE_ID_LEN: usize = 64;
+
+/// The id correlating the requests sent on behalf of a same operation.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct TraceId(String);
+
+impl TraceId {
+    /// A new random trace id.
+    pub fn generate() -> Self {
+        Self(format!("{:016x}", rand::random::<u64>()))
+    }
+
+    /// Parses a trace id received from a client or a peer, ignoring the ones which are empty or
+    /// too long to be logged.
+    pub fn parse(id: &str) -> Option<Self> {
+        let id = id.trim();
+        (!id.is_empty() && id.len() <= MAX_TRACE_ID_LEN && id.is_ascii()).then(|| Self(id.into()))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for TraceId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+tokio::task_local! {
+    static CURRENT: Option<TraceId>;
+}
+
+/// The trace id of the current task, if any.
+pub fn current() -> Option<TraceId> {
+    CURRENT.try_with(Clone::clone).ok().flatten()
+}
+
+/// Runs `future` with the trace id `id`, propagated to the requests it sends.
+pub fn scope<F: Future>(id: Option<TraceId>, future: F) -> impl Future<Output = F::Output> {
+    CURRENT.scope(id, future)
+}
+
+/// Runs `future` with the trace id of the current task, e.g. before spawning it.
+pub fn in_current_scope<F: Future>(future: F) -> impl Future<Output = F::Output> {
+    scope(current(), future)
+}
+
+/// Makes `span` part of the exported trace of `id`, whatever its parent span in the process.
+pub fn join(span: &Span, id: &TraceId) {
+    telemetry_subscribers::otel::set_trace(span, id.as_str());
+}
+
+/// Links `span` to the exported trace of `id`.
+pub fn link(span: &Span, id: &TraceId) {
+    telemetry_subscribers::otel::link_trace(span, id.as_str());
+}
+
+/// Makes `span` part of the trace of the first of `ids`, and links it to the traces of the
+/// others.
+pub fn join_all<'a>(span: &Span, ids: impl IntoIterator<Item = &'a TraceId>) {
+    let mut ids = ids.into_iter();
+    if let Some(id) = ids.next() {
+        join(span, id);
+    }
+    for id in ids {
+        link(span, id);
+    }
+}
+
+/// Makes `span` part of the traces recorded for the headers of `certificates`, e.g. when they
+/// were proposed or voted on.
+pub fn join_certificates<'a>(span: &Span, certificates: impl IntoIterator<Item = &'a Certificate>) {
+    let mut seen = HashSet::new();
+    let ids: Vec<_> = certificates
+        .into_iter()
+        .filter_map(|certificate| lookup(certificate.header.digest()))
+        .filter(|id| seen.insert(id.clone()))
+        .collect();
+    join_all(span, &ids);
+}
+
+/// The number of digests whose trace id is remembered by [`record`].
+const MAX_RECORDED_DIGESTS: usize = 100_000;
+
+/// The trace ids of the most recently recorded digests.
+#[derive(Default)]
+struct Records {
+    ids: HashMap<Vec<u8>, TraceId>,
+    /// The recorded digests, oldest first.
+    order: VecDeque<Vec<u8>>,
+}
+
+static RECORDS: Mutex<Option<Records>> = Mutex::new(None);
+
+/// Remembers that the header (or else) of `digest` is traced by `id`.
+pub fn record(digest: impl AsRef<[u8]>, id: TraceId) {
+    let digest = digest.as_ref().to_vec();
+    let mut records = RECORDS.lock().unwrap();
+    let records = records.get_or_insert_with(Records::default);
+    if records.ids.insert(digest.clone(), id).is_none() {
+        records.order.push_back(digest);
+        if records.order.len() > MAX_RECORDED_DIGESTS {
+            let oldest = records.order.pop_front().unwrap();
+            records.ids.remove(&oldest);
+        }
+    }
+}
+
+/// The trace id recorded for `digest`, if it is recent enough.
+pub fn lookup(digest: impl AsRef<[u8]>) -> Option<TraceId> {
+    let records = RECORDS.lock().unwrap();
+    records.as_ref()?.ids.get(digest.as_ref()).cloned()
+}
+
+/// Sends the trace id of the current task along the outbound requests, or a new one.
+#[derive(Clone, Default)]
+pub struct PropagateTraceIdLayer;
+
+impl PropagateTraceIdLayer {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl<S> Layer<S> for PropagateTraceIdLayer {
+    type Service = PropagateTraceId<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        PropagateTraceId { inner }
+    }
+}
+
+#[derive(Clone)]
+pub struct PropagateTraceId<S> {
+    inner: S,
+}
+
+impl<S> Service<Request<Bytes>> for PropagateTraceId<S>
+where
+    S: Service<Request<Bytes>, Response = Response<Bytes>>,
+{
+    type Response = Response<Bytes>;
+    type Error = S::Error;
+    type Future = S::Future;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: Request<Bytes>) -> Self::Future {
+        if !request.headers().contains_key(TRACE_ID_HEADER) {
+            let id = current().unwrap_or_else(TraceId::generate);
+            request
+                .headers_mut()
+                .insert(TRACE_ID_HEADER.to_string(), id.0);
+        }
+        self.inner.call(request)
+    }
+}
+
+/// Handles the inbound requests in the scope of their trace id, if any.
+#[derive(Clone, Default)]
+pub struct ExtractTraceIdLayer;
+
+impl ExtractTraceIdLayer {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl<S> Layer<S> for ExtractTraceIdLayer {
+    type Service = ExtractTraceId<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ExtractTraceId { inner }
+    }
+}
+
+#[derive(Clone)]
+pub struct ExtractTraceId<S> {
+    inner: S,
+}
+
+impl<S> Service<Request<Bytes>> for ExtractTraceId<S>
+where
+    S: Service<Request<Bytes>, Response = Response<Bytes>>,
+    S::Future: Send + 'static,
+{
+    type Response = Response<Bytes>;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Response<Bytes>, S::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<Bytes>) -> Self::Future {
+        let id = request
+            .headers()
+            .get(TRACE_ID_HEADER)
+            .and_then(|id| TraceId::parse(id));
+        let Some(id) = id else {
+            return Box::pin(self.inner.call(request));
+        };
+        let span = tracing::info_span!("rpc", trace_id = %id);
+        join(&span, &id);
+        Box::pin(scope(Some(id), self.inner.call(request)).instrument(span))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::convert::Infallible;
+    use tower::ServiceExt;
+
+    #[test]
+    fn parse_trace_ids() {
+        assert_eq!(TraceId::parse(" abc ").unwrap().as_str(), "abc");
+        assert_eq!(TraceId::parse(""), None);
+        assert_eq!(TraceId::parse(&"a".repeat(MAX_TRACE_ID_LEN + 1)), None);
+        assert_eq!(TraceId::generate().as_str().len(), 16);
+    }
+
+    #[tokio::test]
+    async fn propagate_trace_ids() {
+        // The handler of the requests answers with the trace id of its scope.
+        let handler = tower::service_fn(|_request: Request<Bytes>| async move {
+            let id = current().map(|id| id.0).unwrap_or_default();
+            Ok::<_, Infallible>(Response::new(Bytes::from(id)))
+        });
+        let server = ExtractTraceIdLayer::new().layer(handler);
+        let client = PropagateTraceIdLayer::new().layer(server);
+
+        let id = TraceId::generate();
+        let response = scope(
+            Some(id.clone()),
+            client.clone().oneshot(Request::new(Bytes::new())),
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.body().as_ref(), id.as_str().as_bytes());
+
+        // The requests sent outside of a scope get a new trace id.
+        let response = client.oneshot(Request::new(Bytes::new())).await.unwrap();
+        assert!(TraceId::parse(std::str::from_utf8(response.body()).unwrap()).is_some());
+    }
+
+    #[test]
+    fn record_trace_ids() {
+        let id = TraceId::generate();
+        record([1; 32], id.clone());
+        assert_eq!(lookup([1; 32]), Some(id));
+        assert_eq!(lookup([2; 32]), None);
+
+        // The oldest digests are forgotten.
+        for i in 0..MAX_RECORDED_DIGESTS as u64 {
+            let mut digest = [3; 32];
+            digest[..8].copy_from_slice(&i.to_be_bytes());
+            record(digest, TraceId::generate());
+        }
+        assert_eq!(lookup([1; 32]), None);
+    }
+}
@@ -0,0 +1,170 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A client of the admin servers of the node itself, reaching them at the addresses configured by
+//! `network_admin_server` in the parameters and authenticated with the credentials of its `auth`.
+use config::{AdminAddress, AdminAuthParameters, NetworkAdminServerParameters};
+use eyre::{eyre, WrapErr};
+use futures::future::BoxFuture;
+use hyper::client::connect::{Connected, Connection};
+use reqwest::{
+    header::{AUTHORIZATION, CONTENT_TYPE},
+    Certificate, Client, Identity,
+};
+use serde::Serialize;
+use std::{
+    fs, io,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    path::PathBuf,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use tokio::{
+    io::{AsyncRead, AsyncWrite, ReadBuf},
+    net::UnixStream,
+};
+
+pub struct AdminClient {
+    client: Client,
+    scheme: &'static str,
+    bearer_token: Option<String>,
+}
+
+impl AdminClient {
+    pub fn new(parameters: &NetworkAdminServerParameters) -> eyre::Result<Self> {
+        let (client, scheme, bearer_token) = match &parameters.auth {
+            None => (Client::new(), "http", None),
+            Some(AdminAuthParameters::BearerToken(token)) => {
+                (Client::new(), "http", Some(token.clone()))
+            }
+            Some(AdminAuthParameters::MutualTls(tls)) => {
+                let read = |path: &std::path::PathBuf| {
+                    fs::read(path).wrap_err_with(|| format!("unable to read {}", path.display()))
+                };
+                let ca = Certificate::from_pem(&read(&tls.ca_cert)?)?;
+                let identity =
+                    Identity::from_pkcs8_pem(&read(&tls.client_cert)?, &read(&tls.client_key)?)?;
+                let client = Client::builder()
+                    .tls_built_in_root_certs(false)
+                    .add_root_certificate(ca)
+                    // The servers are reached by their address rather than a host name, and
+                    // only the certificates signed by the certificate authority are trusted.
+                    .danger_accept_invalid_hostnames(true)
+                    .identity(identity)
+                    .build()?;
+                (client, "https", None)
+            }
+        };
+        Ok(Self {
+            client,
+            scheme,
+            bearer_token,
+        })
+    }
+
+    /// POSTs `body` as JSON to `path` on the admin server listening on `address`, failing unless
+    /// the server accepts the request.
+    pub async fn post<T: Serialize>(
+        &self,
+        address: &AdminAddress,
+        path: &str,
+        body: &T,
+    ) -> eyre::Result<()> {
+        let status = match address {
+            AdminAddress::Tcp(address) => {
+                let url = format!("{}://{}{path}", self.scheme, reachable(*address));
+                let mut request = self.client.post(url).json(body);
+                if let Some(token) = &self.bearer_token {
+                    request = request.header(AUTHORIZATION, format!("Bearer {token}"));
+                }
+                request.send().await?.status()
+            }
+            AdminAddress::Unix(socket) => {
+                // The host is ignored, the connections are all made to the socket.
+                let mut request = hyper::Request::post(format!("http://localhost{path}"))
+                    .header(CONTENT_TYPE, "application/json");
+                if let Some(token) = &self.bearer_token {
+                    request = request.header(AUTHORIZATION, format!("Bearer {token}"));
+                }
+                let request = request.body(hyper::Body::from(serde_json::to_vec(body)?))?;
+                hyper::Client::builder()
+                    .build::<_, hyper::Body>(UnixConnector(socket.clone()))
+                    .request(request)
+                    .await
+                    .wrap_err_with(|| format!("unable to reach {}", socket.display()))?
+                    .status()
+            }
+        };
+        if !status.is_success() {
+            return Err(eyre!(
+                "the admin server at {address} answered {path} with {status}"
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// The address to connect to a server listening on `address`, the loopback one for the servers
+/// listening on all the interfaces.
+fn reachable(address: SocketAddr) -> SocketAddr {
+    match address.ip() {
+        IpAddr::V4(ip) if ip.is_unspecified() => (Ipv4Addr::LOCALHOST, address.port()).into(),
+        IpAddr::V6(ip) if ip.is_unspecified() => (Ipv6Addr::LOCALHOST, address.port()).into(),
+        _ => address,
+    }
+}
+
+/// Connects the requests of a hyper client to the unix socket at its path, whatever their url.
+#[derive(Clone)]
+struct UnixConnector(PathBuf);
+
+impl hyper::service::Service<hyper::Uri> for UnixConnector {
+    type Response = UnixConnection;
+    type Error = io::Error;
+    type Future = BoxFuture<'static, io::Result<UnixConnection>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, _uri: hyper::Uri) -> Self::Future {
+        let path = self.0.clone();
+        Box::pin(async move { UnixStream::connect(path).await.map(UnixConnection) })
+    }
+}
+
+struct UnixConnection(UnixStream);
+
+impl Connection for UnixConnection {
+    fn connected(&self) -> Connected {
+        Connected::new()
+    }
+}
+
+impl AsyncRead for UnixConnection {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.0).poll_read(cx, buf)
+    }
+}
+
+impl AsyncWrite for UnixConnection {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.0).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.0).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.0).poll_shutdown(cx)
+    }
+}
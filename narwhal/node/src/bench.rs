@@ -0,0 +1,212 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Measurement of the submit-to-commit latency and of the throughput of a committee, for the
+//! `narwhal-bench` load generator.
+//!
+//! Every transaction sent by the load generator starts with its id, recorded with the time of its
+//! submission. The latency of a transaction is the time from its submission until the consensus
+//! output including it reaches the execution state of the observing authority.
+use async_trait::async_trait;
+use bytes::{BufMut as _, BytesMut};
+use executor::ExecutionState;
+use serde::Serialize;
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use types::{ConsensusOutput, Transaction};
+
+/// The size of the id starting every benchmark transaction.
+pub const TRANSACTION_ID_SIZE: usize = std::mem::size_of::<u64>();
+
+/// Makes the benchmark transaction `id`, padded to `size` bytes.
+pub fn make_transaction(id: u64, size: usize) -> Transaction {
+    let mut transaction = BytesMut::with_capacity(size.max(TRANSACTION_ID_SIZE));
+    transaction.put_u64(id);
+    transaction.resize(size.max(TRANSACTION_ID_SIZE), 0u8);
+    transaction.freeze()
+}
+
+/// The id of a benchmark transaction.
+fn transaction_id(transaction: &[u8]) -> Option<u64> {
+    let id = transaction.get(..TRANSACTION_ID_SIZE)?;
+    Some(u64::from_be_bytes(id.try_into().unwrap()))
+}
+
+/// Records the submission and the commit of the benchmark transactions.
+#[derive(Default)]
+pub struct LatencyRecorder {
+    inner: Mutex<Records>,
+}
+
+#[derive(Default)]
+struct Records {
+    /// The submission time of the transactions not committed yet, by id.
+    pending: HashMap<u64, Instant>,
+    submitted: u64,
+    latencies: Vec<Duration>,
+    committed_bytes: u64,
+}
+
+impl LatencyRecorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the submission of the transaction `id`.
+    pub fn submitted(&self, id: u64) {
+        let mut records = self.inner.lock().unwrap();
+        records.pending.insert(id, Instant::now());
+        records.submitted += 1;
+    }
+
+    /// Records the commit of `transaction`, ignoring the ones not submitted by the benchmark or
+    /// already committed.
+    pub fn committed(&self, transaction: &[u8]) {
+        let Some(id) = transaction_id(transaction) else {
+            return;
+        };
+        let mut records = self.inner.lock().unwrap();
+        if let Some(submitted) = records.pending.remove(&id) {
+            records.latencies.push(submitted.elapsed());
+            records.committed_bytes += transaction.len() as u64;
+        }
+    }
+
+    /// Reports the transactions committed so far, over a load of `duration`.
+    pub fn report(&self, duration: Duration) -> Report {
+        let records = self.inner.lock().unwrap();
+        let mut latencies = records.latencies.clone();
+        latencies.sort_unstable();
+        let seconds = duration.as_secs_f64().max(f64::EPSILON);
+        Report {
+            duration_secs: duration.as_secs_f64(),
+            submitted: records.submitted,
+            committed: latencies.len() as u64,
+            throughput_tps: latencies.len() as f64 / seconds,
+            throughput_bps: records.committed_bytes as f64 / seconds,
+            p50_ms: percentile_ms(&latencies, 50),
+            p95_ms: percentile_ms(&latencies, 95),
+            p99_ms: percentile_ms(&latencies, 99),
+            max_ms: latencies.last().map_or(0.0, |l| l.as_secs_f64() * 1000.0),
+        }
+    }
+}
+
+/// The `percentile`-th percentile of the `sorted` latencies in milliseconds, by nearest rank.
+fn percentile_ms(sorted: &[Duration], percentile: usize) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let rank = (percentile * sorted.len() + 99) / 100;
+    sorted[rank.clamp(1, sorted.len()) - 1].as_secs_f64() * 1000.0
+}
+
+/// The results of a benchmark.
+#[derive(Clone, Debug, Serialize)]
+pub struct Report {
+    pub duration_secs: f64,
+    pub submitted: u64,
+    pub committed: u64,
+    /// The committed transactions per second.
+    pub throughput_tps: f64,
+    /// The committed bytes per second.
+    pub throughput_bps: f64,
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub p99_ms: f64,
+    pub max_ms: f64,
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Duration: {:.1} s", self.duration_secs)?;
+        writeln!(
+            f,
+            "Committed: {} of {} transactions",
+            self.committed, self.submitted
+        )?;
+        writeln!(
+            f,
+            "Throughput: {:.0} tx/s, {:.0} B/s",
+            self.throughput_tps, self.throughput_bps
+        )?;
+        write!(
+            f,
+            "Latency: p50 {:.1} ms, p95 {:.1} ms, p99 {:.1} ms, max {:.1} ms",
+            self.p50_ms, self.p95_ms, self.p99_ms, self.max_ms
+        )
+    }
+}
+
+/// An execution state feeding the committed transactions to a [`LatencyRecorder`], if any.
+pub struct BenchExecutionState {
+    recorder: Option<Arc<LatencyRecorder>>,
+}
+
+impl BenchExecutionState {
+    pub fn new(recorder: Option<Arc<LatencyRecorder>>) -> Self {
+        Self { recorder }
+    }
+}
+
+#[async_trait]
+impl ExecutionState for BenchExecutionState {
+    async fn handle_consensus_output(&self, consensus_output: ConsensusOutput) {
+        let Some(recorder) = &self.recorder else {
+            return;
+        };
+        for (_, batches) in &consensus_output.batches {
+            for batch in batches {
+                for transaction in &batch.transactions {
+                    recorder.committed(transaction);
+                }
+            }
+        }
+    }
+
+    async fn last_executed_sub_dag_index(&self) -> u64 {
+        0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transactions_carry_their_id() {
+        let transaction = make_transaction(42, 512);
+        assert_eq!(transaction.len(), 512);
+        assert_eq!(transaction_id(&transaction), Some(42));
+        assert_eq!(make_transaction(7, 0).len(), TRANSACTION_ID_SIZE);
+        assert_eq!(transaction_id(&[1, 2, 3]), None);
+    }
+
+    #[test]
+    fn report_percentiles() {
+        let latencies: Vec<_> = (1..=100).map(Duration::from_millis).collect();
+        assert_eq!(percentile_ms(&latencies, 50), 50.0);
+        assert_eq!(percentile_ms(&latencies, 95), 95.0);
+        assert_eq!(percentile_ms(&latencies, 99), 99.0);
+        assert_eq!(percentile_ms(&latencies[..1], 99), 1.0);
+        assert_eq!(percentile_ms(&[], 50), 0.0);
+
+        let recorder = LatencyRecorder::new();
+        for id in 0..4 {
+            recorder.submitted(id);
+        }
+        for id in [0, 1, 1, 2, 100] {
+            recorder.committed(&make_transaction(id, 100));
+        }
+        let report = recorder.report(Duration::from_secs(2));
+        assert_eq!(report.submitted, 4);
+        // The duplicate and the unknown transactions are not counted.
+        assert_eq!(report.committed, 3);
+        assert_eq!(report.throughput_tps, 1.5);
+        assert_eq!(report.throughput_bps, 150.0);
+    }
+}
@@ -1,17 +1,17 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 use async_trait::async_trait;
-use executor::ExecutionState;
+use executor::{ExecutionState, SerializedTransaction};
 use tokio::sync::mpsc::Sender;
 use types::ConsensusOutput;
 
 /// A simple/dumb execution engine.
 pub struct SimpleExecutionState {
-    tx_transaction_confirmation: Sender<Vec<u8>>,
+    tx_transaction_confirmation: Sender<SerializedTransaction>,
 }
 
 impl SimpleExecutionState {
-    pub fn new(tx_transaction_confirmation: Sender<Vec<u8>>) -> Self {
+    pub fn new(tx_transaction_confirmation: Sender<SerializedTransaction>) -> Self {
         Self {
             tx_transaction_confirmation,
         }
@@ -0,0 +1,238 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Background expiry of the bookkeeping stores that are only relevant for a few rounds, which
+//! otherwise grow until the stores of the epoch are disposed of.
+use config::ExpiryParameters;
+use crypto::PublicKey;
+use mysten_metrics::spawn_logged_monitored_task;
+use prometheus::{register_int_counter_vec_with_registry, IntCounterVec, Registry};
+use storage::{CertificateStore, NodeStorage};
+use store::Store;
+use tokio::{sync::watch, task::JoinHandle, time::interval};
+use tracing::{debug, warn};
+use types::{
+    Batch, BatchDigest, CertificateDigest, ReconfigureNotification, Round, StoreResult, VoteInfo,
+};
+
+#[derive(Clone, Debug)]
+pub struct ExpiryMetrics {
+    /// The number of expired entries, per store
+    pub expired_entries: IntCounterVec,
+}
+
+impl ExpiryMetrics {
+    pub fn new(registry: &Registry) -> Self {
+        Self {
+            expired_entries: register_int_counter_vec_with_registry!(
+                "expired_entries",
+                "The number of entries deleted from the bookkeeping stores once past the expiry horizon",
+                &["store"],
+                registry
+            )
+            .unwrap(),
+        }
+    }
+}
+
+/// Periodically deletes the vote digests and the temporary batches of the rounds more than
+/// `ExpiryParameters::horizon_rounds` below the round garbage collected by consensus.
+pub struct Expirer {
+    certificate_store: CertificateStore,
+    vote_digest_store: Store<PublicKey, VoteInfo>,
+    temp_batch_store: Store<(CertificateDigest, BatchDigest), Batch>,
+    /// Receives the last round committed by consensus.
+    rx_consensus_round_updates: watch::Receiver<Round>,
+    gc_depth: Round,
+    parameters: ExpiryParameters,
+    metrics: ExpiryMetrics,
+}
+
+impl Expirer {
+    pub fn new(
+        store: &NodeStorage,
+        rx_consensus_round_updates: watch::Receiver<Round>,
+        gc_depth: Round,
+        parameters: ExpiryParameters,
+        metrics: ExpiryMetrics,
+    ) -> Self {
+        Self {
+            certificate_store: store.certificate_store.clone(),
+            vote_digest_store: store.vote_digest_store.clone(),
+            temp_batch_store: store.temp_batch_store.clone(),
+            rx_consensus_round_updates,
+            gc_depth,
+            parameters,
+            metrics,
+        }
+    }
+
+    /// Expires the entries every `ExpiryParameters::interval`, until the node shuts down.
+    #[must_use]
+    pub fn spawn(self, rx_reconfigure: watch::Receiver<ReconfigureNotification>) -> JoinHandle<()> {
+        spawn_logged_monitored_task!(self.run(rx_reconfigure), "ExpirerTask")
+    }
+
+    async fn run(self, mut rx_reconfigure: watch::Receiver<ReconfigureNotification>) {
+        let mut timer = interval(self.parameters.interval);
+        loop {
+            tokio::select! {
+                _ = timer.tick() => {
+                    if let Err(e) = self.expire().await {
+                        warn!("Failed to expire the bookkeeping stores: {e}");
+                    }
+                },
+
+                result = rx_reconfigure.changed() => {
+                    result.expect("Committee channel dropped");
+                    let message = rx_reconfigure.borrow().clone();
+                    if let ReconfigureNotification::Shutdown = message {
+                        return;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Deletes the expired entries, returning how many were deleted.
+    pub async fn expire(&self) -> StoreResult<usize> {
+        let gc_round = self
+            .rx_consensus_round_updates
+            .borrow()
+            .saturating_sub(self.gc_depth);
+        let horizon = gc_round.saturating_sub(self.parameters.horizon_rounds);
+        if horizon == 0 {
+            return Ok(0);
+        }
+
+        // The primary refuses the headers below the garbage collection round, so it does not
+        // need the digests of its votes for them to refuse voting twice.
+        let votes: Vec<_> = self
+            .vote_digest_store
+            .iter(Some(Box::new(move |(_, vote)| vote.round < horizon)))
+            .await
+            .into_keys()
+            .collect();
+        self.vote_digest_store.remove_all(votes.clone()).await?;
+        self.expired("votes", votes.len());
+
+        // Only the batches of the certificates known to be below the horizon expire: the others
+        // may still be synchronized.
+        let keys: Vec<_> = self.temp_batch_store.iter(None).await.into_keys().collect();
+        let certificates = self
+            .certificate_store
+            .read_all(keys.iter().map(|(certificate, _)| *certificate))?;
+        let batches: Vec<_> = keys
+            .into_iter()
+            .zip(certificates)
+            .filter(|(_, certificate)| matches!(certificate, Some(c) if c.round() < horizon))
+            .map(|(key, _)| key)
+            .collect();
+        self.temp_batch_store.remove_all(batches.clone()).await?;
+        self.expired("temp_batches", batches.len());
+
+        debug!(
+            "Expired {} votes and {} temporary batches below round {horizon}",
+            votes.len(),
+            batches.len()
+        );
+        Ok(votes.len() + batches.len())
+    }
+
+    fn expired(&self, store: &str, entries: usize) {
+        self.metrics
+            .expired_entries
+            .with_label_values(&[store])
+            .inc_by(entries as u64);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Expirer, ExpiryMetrics};
+    use config::ExpiryParameters;
+    use fastcrypto::hash::Hash;
+    use prometheus::Registry;
+    use std::collections::BTreeSet;
+    use storage::NodeStorage;
+    use test_utils::{batch, CommitteeFixture};
+    use tokio::sync::watch;
+    use types::{Certificate, VoteInfo};
+
+    #[tokio::test]
+    async fn test_expiry() {
+        let fixture = CommitteeFixture::builder().build();
+        let committee = fixture.committee();
+        let mut parents = Certificate::genesis(&committee)
+            .iter()
+            .map(|x| x.digest())
+            .collect::<BTreeSet<_>>();
+        let mut certificates = Vec::new();
+        for round in 0..10 {
+            let (_, headers) = fixture.headers_round(round, &parents);
+            let round: Vec<_> = headers.iter().map(|h| fixture.certificate(h)).collect();
+            parents = round.iter().map(|c| c.digest()).collect();
+            certificates.extend(round);
+        }
+
+        let storage = NodeStorage::in_memory();
+        storage
+            .certificate_store
+            .write_all(certificates.iter().cloned())
+            .unwrap();
+        // A vote and a temporary batch at round 2, the same at round 8.
+        let (old, recent) = (&certificates[4], &certificates[28]);
+        assert_eq!((old.round(), recent.round()), (2, 8));
+        for (authority, certificate) in committee.authorities.keys().zip([old, recent]) {
+            storage
+                .vote_digest_store
+                .sync_write(
+                    authority.clone(),
+                    VoteInfo {
+                        epoch: certificate.epoch(),
+                        round: certificate.round(),
+                        vote_digest: Default::default(),
+                    },
+                )
+                .await
+                .unwrap();
+            storage
+                .temp_batch_store
+                .sync_write((certificate.digest(), batch().digest()), batch())
+                .await
+                .unwrap();
+        }
+
+        let parameters = ExpiryParameters {
+            enabled: true,
+            horizon_rounds: 5,
+            ..Default::default()
+        };
+        let (tx_consensus_round_updates, rx_consensus_round_updates) = watch::channel(0);
+        let expirer = Expirer::new(
+            &storage,
+            rx_consensus_round_updates,
+            /* gc_depth */ 2,
+            parameters,
+            ExpiryMetrics::new(&Registry::new()),
+        );
+        // Nothing expires before consensus commits.
+        assert_eq!(expirer.expire().await.unwrap(), 0);
+
+        // Garbage collected up to round 10, so the horizon is round 5.
+        tx_consensus_round_updates.send(12).unwrap();
+        assert_eq!(expirer.expire().await.unwrap(), 2);
+
+        let votes = storage.vote_digest_store.iter(None).await;
+        assert_eq!(votes.len(), 1);
+        assert!(votes.values().all(|vote| vote.round == 8));
+        let batches = storage.temp_batch_store.iter(None).await;
+        assert_eq!(
+            batches.into_keys().collect::<Vec<_>>(),
+            vec![(recent.digest(), batch().digest())]
+        );
+
+        // Nothing else expires.
+        assert_eq!(expirer.expire().await.unwrap(), 0);
+    }
+}
@@ -1,7 +1,7 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
-use config::{Authority, Committee, Epoch, WorkerIndex, WorkerInfo};
-use crypto::{KeyPair, NetworkKeyPair};
+use config::{Authority, Committee, Epoch, ProtocolConfig, QuorumPolicy, WorkerIndex, WorkerInfo};
+use crypto::{KeyPair, NetworkKeyPair, SignatureScheme};
 use fastcrypto::{
     hash::Hash,
     traits::{KeyPair as _, Signer},
@@ -65,6 +65,8 @@ fn get_registry() -> Result<Registry> {
                 )
             })
             .collect(),
+        quorum_policy: QuorumPolicy::default(),
+        protocol_config: ProtocolConfig::default(),
     };
 
     let certificates: Vec<Certificate> = Certificate::genesis(&committee);
@@ -129,6 +131,9 @@ fn get_registry() -> Result<Registry> {
     let shutdown = WorkerReconfigureMessage {
         message: ReconfigureNotification::Shutdown,
     };
+    // The batches without transaction metadata, which have the legacy layout.
+    let batch = Batch::new(vec![vec![0u8; 8].into()]);
+    tracer.trace_value(&mut samples, &batch)?;
     tracer.trace_value(&mut samples, &our_batch)?;
     tracer.trace_value(&mut samples, &others_batch)?;
     tracer.trace_value(&mut samples, &sync)?;
@@ -137,10 +142,10 @@ fn get_registry() -> Result<Registry> {
     tracer.trace_value(&mut samples, &shutdown)?;
 
     // 2. Trace the main entry point(s) + every enum separately.
-    tracer.trace_type::<Batch>(&samples)?;
     tracer.trace_type::<BatchDigest>(&samples)?;
     tracer.trace_type::<HeaderDigest>(&samples)?;
     tracer.trace_type::<CertificateDigest>(&samples)?;
+    tracer.trace_type::<SignatureScheme>(&samples)?;
 
     tracer.registry()
 }
@@ -0,0 +1,364 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A keystore holding the primary, primary network and worker keys of a node, encrypted with a
+//! passphrase so that the keys never sit unencrypted on disk.
+//!
+//! The keys are serialized to JSON and encrypted with AES-256-GCM, under a key derived from the
+//! passphrase with PBKDF2-HMAC-SHA256. The keystore file records the parameters of the derivation,
+//! so that they can be strengthened by rotating the keystore without breaking the older files.
+use aes_gcm::{
+    aead::{Aead, AeadCore, KeyInit, OsRng, Payload},
+    Aes256Gcm, Nonce,
+};
+use config::WorkerId;
+use crypto::{KeyPair, NetworkKeyPair};
+use hmac::Hmac;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::{self, BufRead, Write},
+    path::Path,
+};
+use thiserror::Error;
+use zeroize::Zeroizing;
+
+/// The version of the keystore format.
+const VERSION: u8 = 1;
+
+/// The number of PBKDF2 rounds of the new keystores.
+pub const DEFAULT_KDF_ROUNDS: u32 = 600_000;
+
+/// The most PBKDF2 rounds a keystore may ask for, so that a tampered file can not stall the node
+/// deriving its key.
+const MAX_KDF_ROUNDS: u32 = 10_000_000;
+
+const KDF_ALGORITHM: &str = "pbkdf2-hmac-sha256";
+const CIPHER: &str = "aes-256-gcm";
+const SALT_SIZE: usize = 16;
+
+/// The data authenticated along the keys, binding the ciphertext to the format of the keystore.
+const ASSOCIATED_DATA: &[u8] = b"narwhal-keystore-v1";
+
+#[derive(Debug, Error)]
+pub enum KeystoreError {
+    #[error("Failed to access keystore '{file}': {message}")]
+    Io { file: String, message: String },
+
+    #[error("Invalid keystore '{file}': {message}")]
+    Format { file: String, message: String },
+
+    #[error("Wrong passphrase for keystore '{0}'")]
+    WrongPassphrase(String),
+
+    #[error("Failed to read the passphrase: {0}")]
+    Passphrase(String),
+}
+
+/// The keys of a node, as held by a keystore.
+#[derive(Serialize, Deserialize)]
+pub struct NodeKeys {
+    pub primary: KeyPair,
+    pub primary_network: NetworkKeyPair,
+    pub workers: BTreeMap<WorkerId, NetworkKeyPair>,
+}
+
+impl NodeKeys {
+    /// The keys in the form taken by `Node` and `NodeRestarter::watch`: the primary keypair, the
+    /// primary network keypair, and the ids and keypairs of the workers.
+    pub fn into_parts(self) -> (KeyPair, NetworkKeyPair, Vec<(WorkerId, NetworkKeyPair)>) {
+        (
+            self.primary,
+            self.primary_network,
+            self.workers.into_iter().collect(),
+        )
+    }
+}
+
+/// How the passphrase is derived into the key encrypting the node keys.
+#[derive(Serialize, Deserialize)]
+struct KdfParameters {
+    algorithm: String,
+    rounds: u32,
+    /// Base64 encoded.
+    salt: String,
+}
+
+/// The content of a keystore file.
+#[derive(Serialize, Deserialize)]
+struct KeystoreFile {
+    version: u8,
+    kdf: KdfParameters,
+    cipher: String,
+    /// Base64 encoded.
+    nonce: String,
+    /// The JSON serialized [`NodeKeys`], encrypted and base64 encoded.
+    ciphertext: String,
+}
+
+fn derive_key(passphrase: &str, salt: &[u8], rounds: u32) -> Zeroizing<[u8; 32]> {
+    let mut key = Zeroizing::new([0u8; 32]);
+    pbkdf2::pbkdf2::<Hmac<Sha256>>(passphrase.as_bytes(), salt, rounds, key.as_mut());
+    key
+}
+
+fn encrypt(keys: &NodeKeys, passphrase: &str, rounds: u32) -> KeystoreFile {
+    let mut salt = [0u8; SALT_SIZE];
+    OsRng.fill_bytes(&mut salt);
+    let key = derive_key(passphrase, &salt, rounds);
+    let cipher = Aes256Gcm::new_from_slice(key.as_ref()).expect("Keys are 256 bits long");
+
+    let plaintext = Zeroizing::new(serde_json::to_vec(keys).expect("Keys are serializable"));
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(
+            &nonce,
+            Payload {
+                msg: &plaintext,
+                aad: ASSOCIATED_DATA,
+            },
+        )
+        .expect("Encryption with AES-256-GCM does not fail");
+
+    KeystoreFile {
+        version: VERSION,
+        kdf: KdfParameters {
+            algorithm: KDF_ALGORITHM.to_string(),
+            rounds,
+            salt: base64::encode(salt),
+        },
+        cipher: CIPHER.to_string(),
+        nonce: base64::encode(nonce),
+        ciphertext: base64::encode(ciphertext),
+    }
+}
+
+fn decrypt(file: &KeystoreFile, passphrase: &str, path: &str) -> Result<NodeKeys, KeystoreError> {
+    let format_error = |message: String| KeystoreError::Format {
+        file: path.to_string(),
+        message,
+    };
+    if file.version != VERSION {
+        return Err(format_error(format!(
+            "unsupported version {}",
+            file.version
+        )));
+    }
+    if file.kdf.algorithm != KDF_ALGORITHM || file.cipher != CIPHER {
+        return Err(format_error(format!(
+            "unsupported key derivation {} or cipher {}",
+            file.kdf.algorithm, file.cipher
+        )));
+    }
+    if file.kdf.rounds > MAX_KDF_ROUNDS {
+        return Err(format_error(format!(
+            "{} key derivation rounds, above the maximum of {MAX_KDF_ROUNDS}",
+            file.kdf.rounds
+        )));
+    }
+    let decode = |field: &str, value: &str| {
+        base64::decode(value).map_err(|e| format_error(format!("invalid {field}: {e}")))
+    };
+    let salt = decode("salt", &file.kdf.salt)?;
+    let nonce = decode("nonce", &file.nonce)?;
+    let ciphertext = decode("ciphertext", &file.ciphertext)?;
+    if nonce.len() != 12 {
+        return Err(format_error("invalid nonce length".to_string()));
+    }
+
+    let key = derive_key(passphrase, &salt, file.kdf.rounds);
+    let cipher = Aes256Gcm::new_from_slice(key.as_ref()).expect("Keys are 256 bits long");
+    let plaintext = cipher
+        .decrypt(
+            Nonce::from_slice(&nonce),
+            Payload {
+                msg: &ciphertext,
+                aad: ASSOCIATED_DATA,
+            },
+        )
+        .map(Zeroizing::new)
+        // Authentication fails on a wrong passphrase as well as on a corrupted file.
+        .map_err(|_| KeystoreError::WrongPassphrase(path.to_string()))?;
+    serde_json::from_slice(&plaintext).map_err(|e| format_error(e.to_string()))
+}
+
+/// Reads the keys of the keystore at `path`, encrypted with `passphrase`.
+pub fn read(path: &str, passphrase: &str) -> Result<NodeKeys, KeystoreError> {
+    let data = fs::read(path).map_err(|e| KeystoreError::Io {
+        file: path.to_string(),
+        message: e.to_string(),
+    })?;
+    let file: KeystoreFile = serde_json::from_slice(&data).map_err(|e| KeystoreError::Format {
+        file: path.to_string(),
+        message: e.to_string(),
+    })?;
+    decrypt(&file, passphrase, path)
+}
+
+/// Writes `keys` to a keystore at `path`, encrypted with `passphrase`. An existing keystore is
+/// only replaced once the new one is fully written, so that a failure leaves it intact.
+pub fn write(path: &str, keys: &NodeKeys, passphrase: &str) -> Result<(), KeystoreError> {
+    write_with_rounds(path, keys, passphrase, DEFAULT_KDF_ROUNDS)
+}
+
+fn write_with_rounds(
+    path: &str,
+    keys: &NodeKeys,
+    passphrase: &str,
+    rounds: u32,
+) -> Result<(), KeystoreError> {
+    let io_error = |e: io::Error| KeystoreError::Io {
+        file: path.to_string(),
+        message: e.to_string(),
+    };
+    let data = serde_json::to_string_pretty(&encrypt(keys, passphrase, rounds))
+        .expect("Keystores are serializable");
+    let tmp_path = format!("{path}.tmp");
+    let mut options = fs::OpenOptions::new();
+    options.create(true).write(true).truncate(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let mut file = options.open(&tmp_path).map_err(io_error)?;
+    file.write_all(data.as_bytes()).map_err(io_error)?;
+    file.write_all(b"\n").map_err(io_error)?;
+    file.sync_all().map_err(io_error)?;
+    fs::rename(&tmp_path, path).map_err(io_error)
+}
+
+/// Re-encrypts the keystore at `path` with `new_passphrase`, under a fresh salt.
+pub fn rotate(path: &str, passphrase: &str, new_passphrase: &str) -> Result<(), KeystoreError> {
+    let keys = read(path, passphrase)?;
+    write(path, &keys, new_passphrase)
+}
+
+/// Where the passphrase of a keystore is read from.
+#[derive(Clone, Debug)]
+pub enum PassphraseSource {
+    /// The environment variable of this name, removed once read so that it is not inherited by
+    /// the child processes.
+    Env(String),
+    /// The first line read from this (inherited) file descriptor, e.g. a pipe from a secret
+    /// manager.
+    Fd(i32),
+    /// A prompt on the terminal, answered without echoing the passphrase.
+    Prompt,
+}
+
+impl PassphraseSource {
+    /// Reads the passphrase, prompting with `prompt` if need be.
+    pub fn read(&self, prompt: &str) -> Result<Zeroizing<String>, KeystoreError> {
+        let passphrase = match self {
+            Self::Env(name) => {
+                let passphrase = std::env::var(name).map_err(|e| {
+                    KeystoreError::Passphrase(format!("environment variable {name}: {e}"))
+                })?;
+                std::env::remove_var(name);
+                Zeroizing::new(passphrase)
+            }
+            Self::Fd(fd) => Self::read_fd(*fd)?,
+            Self::Prompt => Zeroizing::new(
+                rpassword::prompt_password(format!("{prompt}: "))
+                    .map_err(|e| KeystoreError::Passphrase(e.to_string()))?,
+            ),
+        };
+        let passphrase = Zeroizing::new(passphrase.trim_end_matches(['\r', '\n']).to_string());
+        if passphrase.is_empty() {
+            return Err(KeystoreError::Passphrase("empty passphrase".to_string()));
+        }
+        Ok(passphrase)
+    }
+
+    #[cfg(unix)]
+    fn read_fd(fd: i32) -> Result<Zeroizing<String>, KeystoreError> {
+        use std::os::unix::io::FromRawFd;
+
+        // Safety: the descriptor is handed to the node by its parent for this sole purpose, and
+        // is not used anywhere else.
+        let file = unsafe { fs::File::from_raw_fd(fd) };
+        let mut line = Zeroizing::new(String::new());
+        io::BufReader::new(file)
+            .read_line(&mut line)
+            .map_err(|e| KeystoreError::Passphrase(format!("file descriptor {fd}: {e}")))?;
+        Ok(line)
+    }
+
+    #[cfg(not(unix))]
+    fn read_fd(fd: i32) -> Result<Zeroizing<String>, KeystoreError> {
+        Err(KeystoreError::Passphrase(format!(
+            "file descriptor {fd}: not supported on this platform"
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fastcrypto::{generate_production_keypair, traits::KeyPair as _};
+
+    fn node_keys() -> NodeKeys {
+        NodeKeys {
+            primary: generate_production_keypair::<KeyPair>(),
+            primary_network: generate_production_keypair::<NetworkKeyPair>(),
+            workers: (0..2)
+                .map(|id| (id, generate_production_keypair::<NetworkKeyPair>()))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn keystore_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("keystore.json");
+        let path = path.to_str().unwrap();
+        let keys = node_keys();
+        write_with_rounds(path, &keys, "passphrase", 1_000).unwrap();
+
+        // The keys are not written in the clear.
+        let data = fs::read_to_string(path).unwrap();
+        assert!(!data.contains(&serde_json::to_string(&keys.primary).unwrap()));
+
+        let read_keys = read(path, "passphrase").unwrap();
+        assert_eq!(read_keys.primary.public(), keys.primary.public());
+        assert_eq!(
+            read_keys.primary_network.public(),
+            keys.primary_network.public()
+        );
+        assert_eq!(
+            read_keys.workers.keys().collect::<Vec<_>>(),
+            keys.workers.keys().collect::<Vec<_>>()
+        );
+        assert_eq!(read_keys.workers[&1].public(), keys.workers[&1].public());
+    }
+
+    #[test]
+    fn wrong_passphrase() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("keystore.json");
+        let path = path.to_str().unwrap();
+        write_with_rounds(path, &node_keys(), "passphrase", 1_000).unwrap();
+
+        assert!(matches!(
+            read(path, "other passphrase"),
+            Err(KeystoreError::WrongPassphrase(_))
+        ));
+    }
+
+    #[test]
+    fn excessive_kdf_rounds() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("keystore.json");
+        let path = path.to_str().unwrap();
+        write_with_rounds(path, &node_keys(), "passphrase", 1_000).unwrap();
+
+        let mut file: KeystoreFile = serde_json::from_slice(&fs::read(path).unwrap()).unwrap();
+        file.kdf.rounds = u32::MAX;
+        fs::write(path, serde_json::to_vec(&file).unwrap()).unwrap();
+        assert!(matches!(
+            read(path, "passphrase"),
+            Err(KeystoreError::Format { .. })
+        ));
+    }
+}
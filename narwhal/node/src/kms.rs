@@ -0,0 +1,199 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! The client of a remote signing service holding the key of the authority, so that the primary
+//! signs its headers and votes without the private key.
+//!
+//! The service is reached over HTTP(S): the digests to sign are posted to `<url>/v1/sign`, as
+//! `{"key_id": "<key id>", "messages": ["<base64>", ...]}`, and the service answers with their
+//! signatures in the same order, as `{"signatures": ["<base64>", ...]}`. A KMS or a Vault instance
+//! is put behind a thin proxy speaking this protocol.
+use async_trait::async_trait;
+use config::SignerParameters;
+use crypto::signer::{KmsClient, KmsSigner, SignerError};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum KmsError {
+    #[error("The signer has no public key")]
+    MissingPublicKey,
+
+    #[error("Failed to create the client of the signer: {0}")]
+    Client(#[from] reqwest::Error),
+}
+
+#[derive(Serialize)]
+struct SignRequest<'a> {
+    key_id: &'a str,
+    messages: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct SignResponse {
+    signatures: Vec<String>,
+}
+
+/// Signs with a key of a remote signing service, see the module documentation for its protocol.
+pub struct HttpKmsClient {
+    client: reqwest::Client,
+    url: String,
+}
+
+impl HttpKmsClient {
+    /// The signer of the `parameters`, if they set the URL of a service.
+    pub fn signer(
+        parameters: &SignerParameters,
+    ) -> Result<Option<KmsSigner<HttpKmsClient>>, KmsError> {
+        let Some(url) = &parameters.url else {
+            return Ok(None);
+        };
+        let public_key = parameters
+            .public_key
+            .clone()
+            .ok_or(KmsError::MissingPublicKey)?;
+        let client = reqwest::Client::builder()
+            .timeout(parameters.request_timeout)
+            .build()?;
+        let client = Self {
+            client,
+            url: format!("{}/v1/sign", url.trim_end_matches('/')),
+        };
+        Ok(Some(KmsSigner::new(
+            client,
+            parameters.key_id.clone(),
+            public_key,
+        )))
+    }
+}
+
+#[async_trait]
+impl KmsClient for HttpKmsClient {
+    async fn sign(&self, key_id: &str, messages: &[&[u8]]) -> Result<Vec<Vec<u8>>, SignerError> {
+        let request = SignRequest {
+            key_id,
+            messages: messages.iter().map(base64::encode).collect(),
+        };
+        let response = self
+            .client
+            .post(&self.url)
+            .json(&request)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| SignerError::Unavailable(e.to_string()))?;
+        let response: SignResponse = response
+            .json()
+            .await
+            .map_err(|e| SignerError::InvalidResponse(e.to_string()))?;
+        response
+            .signatures
+            .iter()
+            .map(|signature| {
+                base64::decode(signature).map_err(|e| SignerError::InvalidResponse(e.to_string()))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{routing::post, Extension, Json, Router};
+    use crypto::{signer::AsyncSigner, KeyPair, DIGEST_LENGTH};
+    use fastcrypto::{
+        generate_production_keypair,
+        traits::{KeyPair as _, Signer, VerifyingKey},
+        Digest,
+    };
+    use std::{net::TcpListener, sync::Arc, time::Duration};
+
+    #[derive(Deserialize)]
+    struct TestRequest {
+        key_id: String,
+        messages: Vec<String>,
+    }
+
+    async fn sign(
+        Extension(keypair): Extension<Arc<KeyPair>>,
+        Json(request): Json<TestRequest>,
+    ) -> Json<serde_json::Value> {
+        assert_eq!(request.key_id, "authority");
+        let signatures: Vec<_> = request
+            .messages
+            .iter()
+            .map(|message| base64::encode(keypair.sign(&base64::decode(message).unwrap())))
+            .collect();
+        Json(serde_json::json!({ "signatures": signatures }))
+    }
+
+    /// Serves the signing protocol with `keypair`, returning the URL of the service.
+    fn serve(keypair: KeyPair) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        let app = Router::new()
+            .route("/v1/sign", post(sign))
+            .layer(Extension(Arc::new(keypair)));
+        tokio::spawn(
+            axum::Server::from_tcp(listener)
+                .unwrap()
+                .serve(app.into_make_service()),
+        );
+        url
+    }
+
+    fn parameters(url: String, public_key: crypto::PublicKey) -> SignerParameters {
+        SignerParameters {
+            url: Some(url),
+            key_id: "authority".to_string(),
+            public_key: Some(public_key),
+            request_timeout: Duration::from_secs(5),
+        }
+    }
+
+    #[tokio::test]
+    async fn sign_with_remote_signer() {
+        let keypair = generate_production_keypair::<KeyPair>();
+        let public_key = keypair.public().clone();
+        let signer = HttpKmsClient::signer(&parameters(serve(keypair), public_key.clone()))
+            .unwrap()
+            .unwrap();
+        assert_eq!(signer.public_key(), public_key);
+
+        let digests: Vec<_> = (0..3u8).map(|i| Digest::new([i; DIGEST_LENGTH])).collect();
+        let signatures = signer.sign_digests(&digests).await.unwrap();
+        for (digest, signature) in digests.iter().zip(&signatures) {
+            assert!(public_key.verify(digest.as_ref(), signature).is_ok());
+        }
+    }
+
+    #[tokio::test]
+    async fn reject_signatures_of_another_key() {
+        let url = serve(generate_production_keypair::<KeyPair>());
+        let other_key = generate_production_keypair::<KeyPair>().public().clone();
+        let signer = HttpKmsClient::signer(&parameters(url, other_key))
+            .unwrap()
+            .unwrap();
+        assert!(matches!(
+            signer
+                .sign_digests(&[Digest::new([0; DIGEST_LENGTH])])
+                .await,
+            Err(SignerError::InvalidResponse(_))
+        ));
+    }
+
+    #[test]
+    fn signer_requires_url_and_public_key() {
+        assert!(HttpKmsClient::signer(&SignerParameters::default())
+            .unwrap()
+            .is_none());
+        let parameters = SignerParameters {
+            url: Some("http://localhost".to_string()),
+            ..SignerParameters::default()
+        };
+        assert!(matches!(
+            HttpKmsClient::signer(&parameters),
+            Err(KmsError::MissingPublicKey)
+        ));
+    }
+}
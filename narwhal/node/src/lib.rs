@@ -8,34 +8,44 @@ use consensus::{
     Consensus,
 };
 
-use crypto::{KeyPair, NetworkKeyPair, PublicKey};
+use crypto::{AsyncSigner, NetworkKeyPair, PublicKey};
 use executor::{get_restored_consensus_output, ExecutionState, Executor, SubscriberResult};
+use expiry::{Expirer, ExpiryMetrics};
 use fastcrypto::traits::{KeyPair as _, VerifyingKey};
 use primary::{NetworkModel, Primary, PrimaryChannelMetrics};
 use prometheus::{IntGauge, Registry};
+use pruner::{Pruner, PrunerMetrics};
+use serde_json::json;
 use std::sync::Arc;
 use storage::NodeStorage;
-use tokio::sync::oneshot;
+use tokio::sync::{mpsc, oneshot};
 use tokio::{sync::watch, task::JoinHandle};
 use tracing::{debug, info};
-use types::{metered_channel, Certificate, ReconfigureNotification, Round};
+use types::{
+    metered_channel, AuditLog, Certificate, MaintenanceKind, MaintenanceTask, NodeHealth,
+    ReconfigureNotification, Round, StateDump, StorageMaintenance, StoreResult,
+};
 use worker::{metrics::initialise_metrics, TransactionValidator, Worker};
 
+pub mod admin_client;
+pub mod bench;
 pub mod execution_state;
+pub mod expiry;
+pub mod keystore;
+pub mod kms;
 pub mod metrics;
+pub mod mnemonic;
+pub mod pruner;
 pub mod restarter;
 
 /// High level functions to spawn the primary and the workers.
 pub struct Node;
 
 impl Node {
-    /// The default channel capacity.
-    pub const CHANNEL_CAPACITY: usize = 1_000;
-
     /// Spawn a new primary. Optionally also spawn the consensus and a client executing transactions.
     pub async fn spawn_primary<State>(
-        // The private-public key pair of this authority.
-        keypair: KeyPair,
+        // The signer of this authority: its private-public key pair, or a remote signer.
+        signer: impl AsyncSigner,
         // The private-public network key pair of this authority.
         network_keypair: NetworkKeyPair,
         // The committee information.
@@ -70,31 +80,61 @@ impl Node {
             PrimaryChannelMetrics::DESC_NEW_CERTS,
         )
         .unwrap();
-        let (tx_new_certificates, rx_new_certificates) =
-            metered_channel::channel(Self::CHANNEL_CAPACITY, &new_certificates_counter);
+        let (tx_new_certificates, rx_new_certificates) = metered_channel::channel(
+            parameters
+                .memory_budget
+                .channel_capacity(PrimaryChannelMetrics::NAME_NEW_CERTS),
+            &new_certificates_counter,
+        );
 
         let committed_certificates_counter = IntGauge::new(
             PrimaryChannelMetrics::NAME_COMMITTED_CERTS,
             PrimaryChannelMetrics::DESC_COMMITTED_CERTS,
         )
         .unwrap();
-        let (tx_committed_certificates, rx_committed_certificates) =
-            metered_channel::channel(Self::CHANNEL_CAPACITY, &committed_certificates_counter);
+        let (tx_committed_certificates, rx_committed_certificates) = metered_channel::channel(
+            parameters
+                .memory_budget
+                .channel_capacity(PrimaryChannelMetrics::NAME_COMMITTED_CERTS),
+            &committed_certificates_counter,
+        );
 
         // Compute the public key of this authority.
-        let name = keypair.public().clone();
+        let name = signer.public_key();
         let mut handles = Vec::new();
         let (tx_executor_network, rx_executor_network) = oneshot::channel();
         let (tx_consensus_round_updates, rx_consensus_round_updates) = watch::channel(0u64);
+        // The components are supervised for the health probes of the admin servers.
+        let health = NodeHealth::of_authority(&name);
+        // The state dump served by the admin server of the primary, shared with the executor.
+        let state_dump = StateDump::default();
+        let mut tx_prune = None;
         let (dag, network_model) = if !internal_consensus {
             debug!("Consensus is disabled: the primary will run w/o Bullshark");
             let consensus_metrics = Arc::new(ConsensusMetrics::new(registry));
             let (handle, dag) = Dag::new(&committee.load(), rx_new_certificates, consensus_metrics);
 
-            handles.push(handle);
+            handles.extend(health.supervise("dag", true, vec![handle]));
 
             (Some(Arc::new(dag)), NetworkModel::Asynchronous)
         } else {
+            // Only the internal consensus provides the execution progress needed to prune.
+            if parameters.pruning.enabled {
+                let (tx, rx_prune) = mpsc::channel(1);
+                tx_prune = Some(tx);
+                let pruner_handle = Pruner::spawn(
+                    store,
+                    execution_state.clone(),
+                    rx_consensus_round_updates.clone(),
+                    tx_reconfigure.subscribe(),
+                    rx_prune,
+                    parameters.gc_depth,
+                    parameters.pruning.clone(),
+                    PrunerMetrics::new(registry),
+                );
+                handles.extend(health.supervise("pruner", false, vec![pruner_handle]));
+            }
+
             let consensus_handles = Self::spawn_consensus(
                 name.clone(),
                 rx_executor_network,
@@ -108,6 +148,7 @@ impl Node {
                 tx_committed_certificates.clone(),
                 tx_consensus_round_updates,
                 registry,
+                &state_dump,
             )
             .await?;
 
@@ -116,10 +157,24 @@ impl Node {
             (None, NetworkModel::PartiallySynchronous)
         };
 
+        if parameters.expiry.enabled {
+            let expirer_handle = Expirer::new(
+                store,
+                rx_consensus_round_updates.clone(),
+                parameters.gc_depth,
+                parameters.expiry.clone(),
+                ExpiryMetrics::new(registry),
+            )
+            .spawn(tx_reconfigure.subscribe());
+            handles.extend(health.supervise("expirer", false, vec![expirer_handle]));
+        }
+
+        Self::register_maintenance(&name, store, tx_prune);
+
         // Spawn the primary.
-        let primary_handles = Primary::spawn(
+        let primary_handles = Primary::spawn_with_state_dump(
             name.clone(),
-            keypair,
+            signer,
             network_keypair,
             committee.clone(),
             worker_cache.clone(),
@@ -138,8 +193,9 @@ impl Node {
             tx_committed_certificates,
             registry,
             Some(tx_executor_network),
+            state_dump,
         );
-        handles.extend(primary_handles);
+        handles.extend(health.supervise("primary", true, primary_handles));
 
         Ok(handles)
     }
@@ -158,6 +214,7 @@ impl Node {
         tx_committed_certificates: metered_channel::Sender<(Round, Vec<Certificate>)>,
         tx_consensus_round_updates: watch::Sender<Round>,
         registry: &Registry,
+        state_dump: &StateDump,
     ) -> SubscriberResult<Vec<JoinHandle<()>>>
     where
         PublicKey: VerifyingKey,
@@ -166,8 +223,11 @@ impl Node {
         let consensus_metrics = Arc::new(ConsensusMetrics::new(registry));
         let channel_metrics = ChannelMetrics::new(registry);
 
-        let (tx_sequence, rx_sequence) =
-            metered_channel::channel(Self::CHANNEL_CAPACITY, &channel_metrics.tx_sequence);
+        let (tx_sequence, rx_sequence) = metered_channel::channel_with_total(
+            parameters.memory_budget.channel_capacity("tx_sequence"),
+            &channel_metrics.tx_sequence,
+            &channel_metrics.tx_sequence_total,
+        );
 
         // Check for any sub-dags that have been sent by consensus but were not processed by the executor.
         let restored_consensus_output = get_restored_consensus_output(
@@ -194,7 +254,7 @@ impl Node {
             parameters.gc_depth,
             consensus_metrics.clone(),
         );
-        let consensus_handles = Consensus::spawn(
+        let consensus_handles = Consensus::spawn_with_audit_log(
             (**committee.load()).clone(),
             store.consensus_store.clone(),
             store.certificate_store.clone(),
@@ -206,11 +266,23 @@ impl Node {
             ordering_engine,
             consensus_metrics.clone(),
             parameters.gc_depth,
+            AuditLog::open(&name, &parameters.audit_log),
         );
 
+        // The creation time of the first leader of the epoch, if it was already committed before
+        // a restart. It is the reference point of the epoch policy.
+        let epoch_start = match store.consensus_store.read_first_committed_sub_dag() {
+            Some(sub_dag) => store
+                .certificate_store
+                .read(sub_dag.leader)?
+                .map(|leader| leader.header.created_at),
+            None => None,
+        };
+
         // Spawn the client executing the transactions. It can also synchronize with the
         // subscriber handler if it missed some transactions.
-        let executor_handles = Executor::spawn(
+        let health = NodeHealth::of_authority(&name);
+        let executor_handles = Executor::spawn_with_state_dump(
             name,
             rx_executor_network,
             worker_cache,
@@ -220,11 +292,15 @@ impl Node {
             rx_sequence,
             registry,
             restored_consensus_output,
+            &parameters,
+            epoch_start,
+            state_dump,
         )?;
 
-        Ok(executor_handles
+        Ok(health
+            .supervise("executor", true, executor_handles)
             .into_iter()
-            .chain(std::iter::once(consensus_handles))
+            .chain(health.supervise("consensus", true, vec![consensus_handles]))
             .collect())
     }
 
@@ -250,6 +326,8 @@ impl Node {
         let mut handles = Vec::new();
 
         let metrics = initialise_metrics(registry);
+        let health = NodeHealth::of_authority(&primary_name);
+        Self::register_maintenance(&primary_name, store, None);
 
         for (id, keypair) in ids_and_keypairs {
             let worker_handles = Worker::spawn(
@@ -263,8 +341,79 @@ impl Node {
                 store.batch_store.clone(),
                 metrics.clone(),
             );
-            handles.extend(worker_handles);
+            handles.extend(health.supervise(format!("worker-{id}"), false, worker_handles));
         }
         handles
     }
+
+    /// Registers the storage maintenance the admin servers of the authority `name` can trigger
+    /// on `store`: compaction, snapshots and, when `tx_prune` reaches a pruner, pruning. The
+    /// pruning registered by the primary is kept when no pruner is given.
+    fn register_maintenance(
+        name: &PublicKey,
+        store: &NodeStorage,
+        tx_prune: Option<mpsc::Sender<oneshot::Sender<StoreResult<Round>>>>,
+    ) {
+        let maintenance = StorageMaintenance::of_authority(name);
+
+        let compacted = store.clone();
+        maintenance.register(MaintenanceKind::Compaction, move |_task| {
+            let store = compacted.clone();
+            async move {
+                spawn_blocking(move || {
+                    store
+                        .compact()
+                        .map(|()| json!({ "tables": store.tables() }))
+                })
+                .await
+            }
+        });
+
+        let exported = store.clone();
+        maintenance.register(MaintenanceKind::Snapshot, move |task| {
+            let store = exported.clone();
+            async move {
+                match task {
+                    MaintenanceTask::Snapshot { target } => {
+                        spawn_blocking(move || {
+                            store.export_snapshot(&target).map(|manifest| {
+                                serde_json::to_value(manifest).expect("Manifests are serializable")
+                            })
+                        })
+                        .await
+                    }
+                    task => Err(format!("{task:?} is not a snapshot")),
+                }
+            }
+        });
+
+        if let Some(tx_prune) = tx_prune {
+            maintenance.register(MaintenanceKind::Pruning, move |_task| {
+                let tx_prune = tx_prune.clone();
+                async move {
+                    let (tx_pruned, rx_pruned) = oneshot::channel();
+                    tx_prune
+                        .send(tx_pruned)
+                        .await
+                        .map_err(|_| "The pruner is not running".to_string())?;
+                    let pruned_round = rx_pruned
+                        .await
+                        .map_err(|_| "The pruner stopped".to_string())?
+                        .map_err(|e| e.to_string())?;
+                    Ok(json!({ "pruned_round": pruned_round }))
+                }
+            });
+        }
+    }
+}
+
+/// Runs the blocking storage operation `f`, its error turned into the error of a job.
+async fn spawn_blocking<F, E>(f: F) -> Result<serde_json::Value, String>
+where
+    F: FnOnce() -> Result<serde_json::Value, E> + Send + 'static,
+    E: std::fmt::Display,
+{
+    tokio::task::spawn_blocking(move || f().map_err(|e| e.to_string()))
+        .await
+        .map_err(|e| e.to_string())?
 }
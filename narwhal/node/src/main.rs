@@ -10,20 +10,22 @@
 
 use arc_swap::ArcSwap;
 use clap::{crate_name, crate_version, App, AppSettings, ArgMatches, SubCommand};
-use config::{Committee, Import, Parameters, WorkerCache, WorkerId};
-use crypto::{KeyPair, NetworkKeyPair};
+use config::{Committee, Epoch, Import, Parameters, StorageLayout, WorkerCache, WorkerId};
+use crypto::{AsyncSigner, KeyPair, NetworkKeyPair};
 use executor::SerializedTransaction;
-use eyre::Context;
+use eyre::{eyre, Context};
 use fastcrypto::{generate_production_keypair, traits::KeyPair as _};
 use futures::future::join_all;
 use narwhal_node as node;
 use node::{
     execution_state::SimpleExecutionState,
+    keystore::{self, NodeKeys, PassphraseSource},
+    kms::HttpKmsClient,
     metrics::{primary_metrics_registry, start_prometheus_server, worker_metrics_registry},
-    Node,
+    mnemonic, Node,
 };
 use prometheus::Registry;
-use std::sync::Arc;
+use std::{path::Path, sync::Arc};
 use storage::NodeStorage;
 use telemetry_subscribers::TelemetryGuards;
 use tokio::sync::mpsc::{channel, Receiver};
@@ -33,6 +35,7 @@ use tracing::{info, warn};
 #[cfg(feature = "benchmark")]
 use tracing_subscriber::filter::{EnvFilter, LevelFilter};
 use worker::TrivialTransactionValidator;
+use zeroize::Zeroizing;
 
 #[tokio::main]
 async fn main() -> Result<(), eyre::Report> {
@@ -50,12 +53,76 @@ async fn main() -> Result<(), eyre::Report> {
                 .about("Print a fresh network key pair (ed25519) to file")
                 .args_from_usage("--filename=<FILE> 'The file where to print the new network key pair'"),
         )
+        .subcommand(
+            SubCommand::with_name("create_keystore")
+                .about("Write the keys of a node to a passphrase-protected keystore, generating the ones not provided")
+                .args_from_usage("--keystore=<FILE> 'The file where to write the keystore'")
+                .args_from_usage("--primary-keys=[FILE] 'The file containing the node's primary keys'")
+                .args_from_usage("--primary-network-keys=[FILE] 'The file containing the node's primary network keys'")
+                .args_from_usage("--worker-keys=[FILE]... 'The files containing the keys of the workers 0, 1, ...'")
+                .args_from_usage("--workers=[INT] 'The number of workers to generate keys for, if no worker keys are provided'")
+                .args_from_usage("--passphrase-env=[VAR] 'The environment variable holding the passphrase, instead of prompting for it'")
+                .args_from_usage("--passphrase-fd=[FD] 'The file descriptor to read the passphrase from, instead of prompting for it'"),
+        )
+        .subcommand(
+            SubCommand::with_name("rotate_keystore")
+                .about("Encrypt a keystore with a new passphrase")
+                .args_from_usage("--keystore=<FILE> 'The keystore to rotate'")
+                .args_from_usage("--passphrase-env=[VAR] 'The environment variable holding the current passphrase'")
+                .args_from_usage("--passphrase-fd=[FD] 'The file descriptor to read the current passphrase from'")
+                .args_from_usage("--new-passphrase-env=[VAR] 'The environment variable holding the new passphrase'")
+                .args_from_usage("--new-passphrase-fd=[FD] 'The file descriptor to read the new passphrase from'"),
+        )
+        .subcommand(
+            SubCommand::with_name("generate_mnemonic")
+                .about("Generate the keys of a node from a fresh mnemonic, printed as their backup, and write them to a keystore")
+                .args_from_usage("--keystore=<FILE> 'The file where to write the keystore'")
+                .args_from_usage("--workers=[INT] 'The number of workers to derive keys for'")
+                .args_from_usage("--words=[INT] 'The number of words of the mnemonic'")
+                .args_from_usage("--passphrase-env=[VAR] 'The environment variable holding the passphrase, instead of prompting for it'")
+                .args_from_usage("--passphrase-fd=[FD] 'The file descriptor to read the passphrase from, instead of prompting for it'"),
+        )
+        .subcommand(
+            SubCommand::with_name("import_mnemonic")
+                .about("Recover the keys of a node from their mnemonic and write them to a keystore")
+                .args_from_usage("--keystore=<FILE> 'The file where to write the keystore'")
+                .args_from_usage("--workers=[INT] 'The number of workers to derive keys for'")
+                .args_from_usage("--mnemonic-env=[VAR] 'The environment variable holding the mnemonic, instead of prompting for it'")
+                .args_from_usage("--mnemonic-fd=[FD] 'The file descriptor to read the mnemonic from, instead of prompting for it'")
+                .args_from_usage("--passphrase-env=[VAR] 'The environment variable holding the passphrase, instead of prompting for it'")
+                .args_from_usage("--passphrase-fd=[FD] 'The file descriptor to read the passphrase from, instead of prompting for it'"),
+        )
+        .subcommand(
+            SubCommand::with_name("export_keystore")
+                .about("Write the keys of a keystore to plaintext key files, as read by 'run'")
+                .args_from_usage("--keystore=<FILE> 'The keystore to export'")
+                .args_from_usage("--target=<PATH> 'The directory where to write the key files'")
+                .args_from_usage("--passphrase-env=[VAR] 'The environment variable holding the passphrase, instead of prompting for it'")
+                .args_from_usage("--passphrase-fd=[FD] 'The file descriptor to read the passphrase from, instead of prompting for it'"),
+        )
+        .subcommand(
+            SubCommand::with_name("export_snapshot")
+                .about("Export a snapshot of the data store of a stopped node")
+                .args_from_usage("--store=<PATH> 'The path of the data store'")
+                .args_from_usage("--parameters=[FILE] 'The file containing the node parameters, which tell how the store is encrypted'")
+                .args_from_usage("--target=<PATH> 'The (empty) directory where to write the snapshot'"),
+        )
+        .subcommand(
+            SubCommand::with_name("import_snapshot")
+                .about("Create the data store of a fresh node from a snapshot")
+                .args_from_usage("--snapshot=<PATH> 'The directory holding the snapshot'")
+                .args_from_usage("--store=<PATH> 'The path where to create the data store'")
+                .args_from_usage("--epoch=[INT] 'Only import the snapshot if it is of this epoch'"),
+        )
         .subcommand(
             SubCommand::with_name("run")
                 .about("Run a node")
-                .args_from_usage("--primary-keys=<FILE> 'The file containing the node's primary keys'")
-                .args_from_usage("--primary-network-keys=<FILE> 'The file containing the node's primary network keys'")
-                .args_from_usage("--worker-keys=<FILE> 'The file containing the node's worker keys'")
+                .args_from_usage("--primary-keys=[FILE] 'The file containing the node's primary keys, unless the parameters set a remote signer'")
+                .args_from_usage("--primary-network-keys=[FILE] 'The file containing the node's primary network keys'")
+                .args_from_usage("--worker-keys=[FILE] 'The file containing the node's worker keys'")
+                .args_from_usage("--keystore=[FILE] 'The keystore containing the node's keys, instead of the key files'")
+                .args_from_usage("--passphrase-env=[VAR] 'The environment variable holding the passphrase of the keystore, instead of prompting for it'")
+                .args_from_usage("--passphrase-fd=[FD] 'The file descriptor to read the passphrase of the keystore from, instead of prompting for it'")
                 .args_from_usage("--committee=<FILE> 'The file containing committee information'")
                 .args_from_usage("--workers=<FILE> 'The file containing worker information'")
                 .args_from_usage("--parameters=[FILE] 'The file containing the node parameters'")
@@ -104,29 +171,182 @@ async fn main() -> Result<(), eyre::Report> {
             config::Export::export(&network_kp, sub_matches.value_of("filename").unwrap())
                 .context("Failed to generate network key pair")?
         }
+        ("create_keystore", Some(sub_matches)) => {
+            let _guard = setup_telemetry(tracing_level, network_tracing_level, None);
+            let primary = match sub_matches.value_of("primary-keys") {
+                Some(file) => {
+                    KeyPair::import(file).context("Failed to load the primary keypair")?
+                }
+                None => generate_production_keypair::<KeyPair>(),
+            };
+            let primary_network = match sub_matches.value_of("primary-network-keys") {
+                Some(file) => NetworkKeyPair::import(file)
+                    .context("Failed to load the primary network keypair")?,
+                None => generate_production_keypair::<NetworkKeyPair>(),
+            };
+            let workers = match sub_matches.values_of("worker-keys") {
+                Some(files) => files
+                    .enumerate()
+                    .map(|(id, file)| {
+                        let keypair = NetworkKeyPair::import(file).with_context(|| {
+                            format!("Failed to load the keypair of worker {id}")
+                        })?;
+                        Ok((id as WorkerId, keypair))
+                    })
+                    .collect::<Result<_, eyre::Report>>()?,
+                None => {
+                    let workers = worker_count(sub_matches)?;
+                    (0..workers)
+                        .map(|id| (id, generate_production_keypair::<NetworkKeyPair>()))
+                        .collect()
+                }
+            };
+            let keys = NodeKeys {
+                primary,
+                primary_network,
+                workers,
+            };
+            write_keystore(sub_matches, &keys)?;
+        }
+        ("generate_mnemonic", Some(sub_matches)) => {
+            let _guard = setup_telemetry(tracing_level, network_tracing_level, None);
+            let words = sub_matches
+                .value_of("words")
+                .map(|words| words.parse::<usize>())
+                .transpose()
+                .context("The number of words must be a positive integer")?
+                .unwrap_or(mnemonic::DEFAULT_WORD_COUNT);
+            let phrase = mnemonic::generate(words)?;
+            let keys = mnemonic::derive_node_keys(&phrase, worker_count(sub_matches)?)?;
+            write_keystore(sub_matches, &keys)?;
+            eprintln!("Write down the mnemonic below: it is the only way to recover the keys if the keystore or its passphrase is lost.");
+            println!("{}", *phrase);
+        }
+        ("import_mnemonic", Some(sub_matches)) => {
+            let _guard = setup_telemetry(tracing_level, network_tracing_level, None);
+            let phrase =
+                passphrase_source(sub_matches, "mnemonic-env", "mnemonic-fd")?.read("Mnemonic")?;
+            let keys = mnemonic::derive_node_keys(&phrase, worker_count(sub_matches)?)?;
+            write_keystore(sub_matches, &keys)?;
+        }
+        ("export_keystore", Some(sub_matches)) => {
+            let _guard = setup_telemetry(tracing_level, network_tracing_level, None);
+            let passphrase = passphrase_source(sub_matches, "passphrase-env", "passphrase-fd")?
+                .read("Keystore passphrase")?;
+            let keys = keystore::read(sub_matches.value_of("keystore").unwrap(), &passphrase)
+                .context("Failed to load the keystore")?;
+            let target = Path::new(sub_matches.value_of("target").unwrap());
+            std::fs::create_dir_all(target).context("Failed to create the target directory")?;
+            let key_file = |name: &str| target.join(name).to_string_lossy().into_owned();
+            config::Export::export(&keys.primary, &key_file("primary.key"))
+                .context("Failed to export the primary keypair")?;
+            config::Export::export(&keys.primary_network, &key_file("primary-network.key"))
+                .context("Failed to export the primary network keypair")?;
+            for (id, keypair) in &keys.workers {
+                config::Export::export(keypair, &key_file(&format!("worker-{id}.key")))
+                    .with_context(|| format!("Failed to export the keypair of worker {id}"))?;
+            }
+            println!(
+                "Exported the keys of primary {} and {} worker(s) to {}",
+                keys.primary.public(),
+                keys.workers.len(),
+                target.display()
+            );
+        }
+        ("rotate_keystore", Some(sub_matches)) => {
+            let _guard = setup_telemetry(tracing_level, network_tracing_level, None);
+            let passphrase = passphrase_source(sub_matches, "passphrase-env", "passphrase-fd")?
+                .read("Current passphrase")?;
+            let new_passphrase = read_new_passphrase(
+                &passphrase_source(sub_matches, "new-passphrase-env", "new-passphrase-fd")?,
+                "New passphrase",
+            )?;
+            let keystore = sub_matches.value_of("keystore").unwrap();
+            keystore::rotate(keystore, &passphrase, &new_passphrase)
+                .context("Failed to rotate the keystore")?;
+        }
+        ("export_snapshot", Some(sub_matches)) => {
+            let _guard = setup_telemetry(tracing_level, network_tracing_level, None);
+            let parameters = match sub_matches.value_of("parameters") {
+                Some(filename) => {
+                    Parameters::import(filename).context("Failed to load the node's parameters")?
+                }
+                None => Parameters::default(),
+            };
+            // The manifest describes the data of a single epoch.
+            if parameters.storage.layout == StorageLayout::Shared {
+                return Err(eyre!(
+                    "Snapshots of stores shared by all the epochs are not supported"
+                ));
+            }
+            // Nothing is written to the store, so the choice of the active key does not matter.
+            let store = NodeStorage::reopen_with_parameters(
+                sub_matches.value_of("store").unwrap(),
+                &parameters.storage,
+                Epoch::MAX,
+            );
+            let manifest = store
+                .export_snapshot(Path::new(sub_matches.value_of("target").unwrap()))
+                .context("Failed to export the snapshot")?;
+            println!("Exported snapshot {manifest:?}");
+        }
+        ("import_snapshot", Some(sub_matches)) => {
+            let _guard = setup_telemetry(tracing_level, network_tracing_level, None);
+            let epoch = sub_matches
+                .value_of("epoch")
+                .map(|epoch| epoch.parse::<Epoch>())
+                .transpose()
+                .context("The epoch must be a positive integer")?;
+            let manifest = NodeStorage::import_snapshot(
+                Path::new(sub_matches.value_of("snapshot").unwrap()),
+                Path::new(sub_matches.value_of("store").unwrap()),
+                epoch,
+            )
+            .context("Failed to import the snapshot")?;
+            println!("Imported snapshot {manifest:?}");
+        }
         ("run", Some(sub_matches)) => {
-            let primary_key_file = sub_matches.value_of("primary-keys").unwrap();
-            let primary_keypair = KeyPair::import(primary_key_file)
-                .context("Failed to load the node's primary keypair")?;
-            let primary_network_key_file = sub_matches.value_of("primary-network-keys").unwrap();
-            let primary_network_keypair = NetworkKeyPair::import(primary_network_key_file)
-                .context("Failed to load the node's primary network keypair")?;
-            let worker_key_file = sub_matches.value_of("worker-keys").unwrap();
-            let worker_keypair = NetworkKeyPair::import(worker_key_file)
-                .context("Failed to load the node's worker keypair")?;
-            let registry = match sub_matches.subcommand() {
-                ("primary", _) => primary_metrics_registry(primary_keypair.public().clone()),
-                ("worker", Some(worker_matches)) => {
-                    let id = worker_matches
+            let worker_id = match sub_matches.subcommand() {
+                ("primary", _) => None,
+                ("worker", Some(worker_matches)) => Some(
+                    worker_matches
                         .value_of("id")
                         .unwrap()
                         .parse::<WorkerId>()
-                        .context("The worker id must be a positive integer")?;
-
-                    worker_metrics_registry(id, primary_keypair.public().clone())
-                }
+                        .context("The worker id must be a positive integer")?,
+                ),
                 _ => unreachable!(),
             };
+            // Load default parameters if none are specified.
+            let parameters = match sub_matches.value_of("parameters") {
+                Some(filename) => {
+                    Parameters::import(filename).context("Failed to load the node's parameters")?
+                }
+                None => Parameters::default(),
+            };
+            let remote_signer = HttpKmsClient::signer(&parameters.signer)
+                .context("Failed to configure the remote signer")?;
+            let (primary_keypair, primary_network_keypair, worker_keypair) =
+                load_keys(sub_matches, worker_id, remote_signer.is_some())?;
+            let primary_signer: Box<dyn AsyncSigner> = match (remote_signer, primary_keypair) {
+                (Some(signer), keypair) => {
+                    // The keystore holds the primary key even when a remote signer is used.
+                    if let Some(keypair) = keypair {
+                        if *keypair.public() != signer.public_key() {
+                            return Err(eyre!(
+                                "The remote signer holds another key than the primary keypair"
+                            ));
+                        }
+                    }
+                    Box::new(signer)
+                }
+                (None, Some(keypair)) => Box::new(keypair),
+                (None, None) => unreachable!("The primary keypair is loaded without remote signer"),
+            };
+            let registry = match worker_id {
+                None => primary_metrics_registry(primary_signer.public_key()),
+                Some(id) => worker_metrics_registry(id, primary_signer.public_key()),
+            };
 
             // In benchmarks, transactions are not deserializable => many errors at the debug level
             // Moreover, we need RFC 3339 timestamps to parse properly => we use a custom subscriber.
@@ -139,7 +359,8 @@ async fn main() -> Result<(), eyre::Report> {
             }
             run(
                 sub_matches,
-                primary_keypair,
+                parameters,
+                primary_signer,
                 primary_network_keypair,
                 worker_keypair,
                 registry,
@@ -151,6 +372,109 @@ async fn main() -> Result<(), eyre::Report> {
     Ok(())
 }
 
+/// Where to read a passphrase from, given the names of the arguments of its environment variable
+/// and file descriptor. Defaults to a prompt.
+fn passphrase_source(
+    matches: &ArgMatches<'_>,
+    env_arg: &str,
+    fd_arg: &str,
+) -> Result<PassphraseSource, eyre::Report> {
+    match (matches.value_of(env_arg), matches.value_of(fd_arg)) {
+        (Some(_), Some(_)) => Err(eyre!("--{env_arg} and --{fd_arg} are exclusive")),
+        (Some(name), None) => Ok(PassphraseSource::Env(name.to_string())),
+        (None, Some(fd)) => Ok(PassphraseSource::Fd(
+            fd.parse()
+                .context("The file descriptor must be an integer")?,
+        )),
+        (None, None) => Ok(PassphraseSource::Prompt),
+    }
+}
+
+/// The number of workers to derive keys for, one by default.
+fn worker_count(matches: &ArgMatches<'_>) -> Result<WorkerId, eyre::Report> {
+    matches
+        .value_of("workers")
+        .unwrap_or("1")
+        .parse::<WorkerId>()
+        .context("The number of workers must be a positive integer")
+}
+
+/// Writes `keys` to the keystore of the `--keystore` argument, under a new passphrase.
+fn write_keystore(matches: &ArgMatches<'_>, keys: &NodeKeys) -> Result<(), eyre::Report> {
+    let passphrase = read_new_passphrase(
+        &passphrase_source(matches, "passphrase-env", "passphrase-fd")?,
+        "Passphrase",
+    )?;
+    let keystore = matches.value_of("keystore").unwrap();
+    keystore::write(keystore, keys, &passphrase).context("Failed to write the keystore")?;
+    println!(
+        "Wrote the keys of primary {} and {} worker(s) to {keystore}",
+        keys.primary.public(),
+        keys.workers.len()
+    );
+    Ok(())
+}
+
+/// Reads a new passphrase, asking for it twice when prompting.
+fn read_new_passphrase(
+    source: &PassphraseSource,
+    prompt: &str,
+) -> Result<Zeroizing<String>, eyre::Report> {
+    let passphrase = source.read(prompt)?;
+    if let PassphraseSource::Prompt = source {
+        if *source.read(&format!("Confirm {}", prompt.to_lowercase()))? != *passphrase {
+            return Err(eyre!("The passphrases do not match"));
+        }
+    }
+    Ok(passphrase)
+}
+
+/// Loads the primary keypair, the primary network keypair, and the keypair of the worker `worker_id`
+/// (if any) of the node, either from its keystore or from the plaintext key files. The primary
+/// key file is not read when the node signs with a `remote_signer`.
+fn load_keys(
+    matches: &ArgMatches<'_>,
+    worker_id: Option<WorkerId>,
+    remote_signer: bool,
+) -> Result<(Option<KeyPair>, NetworkKeyPair, Option<NetworkKeyPair>), eyre::Report> {
+    if let Some(keystore) = matches.value_of("keystore") {
+        let passphrase = passphrase_source(matches, "passphrase-env", "passphrase-fd")?
+            .read("Keystore passphrase")?;
+        let mut keys =
+            keystore::read(keystore, &passphrase).context("Failed to load the node's keystore")?;
+        let worker_keypair = worker_id
+            .map(|id| {
+                keys.workers
+                    .remove(&id)
+                    .ok_or_else(|| eyre!("The keystore has no keys for worker {id}"))
+            })
+            .transpose()?;
+        return Ok((Some(keys.primary), keys.primary_network, worker_keypair));
+    }
+
+    let key_file = |arg: &str| {
+        matches
+            .value_of(arg)
+            .ok_or_else(|| eyre!("Either --keystore or --{arg} must be provided"))
+    };
+    let primary_keypair = if remote_signer {
+        None
+    } else {
+        let keypair = KeyPair::import(key_file("primary-keys")?)
+            .context("Failed to load the node's primary keypair")?;
+        Some(keypair)
+    };
+    let primary_network_keypair = NetworkKeyPair::import(key_file("primary-network-keys")?)
+        .context("Failed to load the node's primary network keypair")?;
+    let worker_keypair = worker_id
+        .map(|_| {
+            NetworkKeyPair::import(key_file("worker-keys")?)
+                .context("Failed to load the node's worker keypair")
+        })
+        .transpose()?;
+    Ok((primary_keypair, primary_network_keypair, worker_keypair))
+}
+
 fn setup_telemetry(
     tracing_level: &str,
     network_tracing_level: &str,
@@ -201,9 +525,10 @@ fn setup_benchmark_telemetry(
 // Runs either a worker or a primary.
 async fn run(
     matches: &ArgMatches<'_>,
-    primary_keypair: KeyPair,
+    parameters: Parameters,
+    primary_signer: Box<dyn AsyncSigner>,
     primary_network_keypair: NetworkKeyPair,
-    worker_keypair: NetworkKeyPair,
+    worker_keypair: Option<NetworkKeyPair>,
     registry: Registry,
 ) -> Result<(), eyre::Report> {
     // Only enabled if failpoints feature flag is set
@@ -217,7 +542,6 @@ async fn run(
 
     let committee_file = matches.value_of("committee").unwrap();
     let workers_file = matches.value_of("workers").unwrap();
-    let parameters_file = matches.value_of("parameters");
     let store_path = matches.value_of("store").unwrap();
 
     // Read the committee, workers and node's keypair from file.
@@ -228,27 +552,35 @@ async fn run(
         WorkerCache::import(workers_file).context("Failed to load the worker information")?,
     ));
 
-    // Load default parameters if none are specified.
-    let parameters = match parameters_file {
-        Some(filename) => {
-            Parameters::import(filename).context("Failed to load the node's parameters")?
-        }
-        None => Parameters::default(),
-    };
-
     // Make the data store.
-    let store = NodeStorage::reopen(store_path);
+    let store = match parameters.storage.layout {
+        StorageLayout::PerEpoch => NodeStorage::reopen_with_metrics(
+            store_path,
+            &parameters.storage,
+            committee.load().epoch(),
+            &registry,
+        ),
+        StorageLayout::Shared => NodeStorage::open_epoch(
+            &NodeStorage::open_database(store_path, &parameters.storage),
+            &parameters.storage,
+            committee.load().epoch(),
+            &registry,
+        ),
+    };
 
     // The channel returning the result for each transaction's execution.
-    let (tx_transaction_confirmation, rx_transaction_confirmation) =
-        channel(Node::CHANNEL_CAPACITY);
+    let (tx_transaction_confirmation, rx_transaction_confirmation) = channel(
+        parameters
+            .memory_budget
+            .channel_capacity("tx_transaction_confirmation"),
+    );
 
     // Check whether to run a primary, a worker, or an entire authority.
     let node_handles = match matches.subcommand() {
         // Spawn the primary and consensus core.
         ("primary", Some(sub_matches)) => {
             Node::spawn_primary(
-                primary_keypair,
+                primary_signer,
                 primary_network_keypair,
                 committee,
                 worker_cache,
@@ -272,8 +604,11 @@ async fn run(
 
             Node::spawn_workers(
                 /* primary_name */
-                primary_keypair.public().clone(),
-                vec![(id, worker_keypair)],
+                primary_signer.public_key(),
+                vec![(
+                    id,
+                    worker_keypair.expect("Workers are run with their keypair"),
+                )],
                 committee,
                 worker_cache,
                 &store,
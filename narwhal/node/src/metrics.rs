@@ -10,7 +10,9 @@ use prometheus::{Registry, TextEncoder};
 use std::collections::HashMap;
 use tokio::task::JoinHandle;
 
-const METRICS_ROUTE: &str = "/metrics";
+pub const METRICS_ROUTE: &str = "/metrics";
+/// The prefix of the metrics of the nodes run by the restarter, primary and workers alike.
+pub const NARWHAL_METRICS_PREFIX: &str = "narwhal";
 const PRIMARY_METRICS_PREFIX: &str = "narwhal_primary";
 const WORKER_METRICS_PREFIX: &str = "narwhal_worker";
 
@@ -0,0 +1,150 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Derivation of the keys of a node from a BIP39 mnemonic, so that operators can generate the keys
+//! offline and recover them from the seed phrase alone.
+//!
+//! The BIP39 seed of the mnemonic is expanded with HMAC-SHA256 into the private key of each key,
+//! labelled by the role of the key (e.g. `narwhal/worker/3`) and followed by a counter byte. The
+//! counter starts at 0 and is only increased to skip the outputs which are not a private key of
+//! the scheme, e.g. the BLS12-381 scalars not below the order of the group. The keys of the
+//! workers are thus recovered for any number of workers, and none of the keys tells anything
+//! about the others. No random generator is involved, so the keys do not change with the releases
+//! of the dependencies: the known-answer test below pins them.
+use crate::keystore::NodeKeys;
+use bip39::{Language, Mnemonic, MnemonicType, Seed};
+use config::WorkerId;
+use fastcrypto::traits::{KeyPair, ToFromBytes};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use thiserror::Error;
+use zeroize::Zeroizing;
+
+/// The number of words of the new mnemonics.
+pub const DEFAULT_WORD_COUNT: usize = 24;
+
+const PRIMARY_LABEL: &str = "narwhal/primary";
+const PRIMARY_NETWORK_LABEL: &str = "narwhal/primary-network";
+const WORKER_LABEL: &str = "narwhal/worker";
+
+#[derive(Debug, Error)]
+pub enum MnemonicError {
+    #[error("Invalid mnemonic: {0}")]
+    InvalidPhrase(String),
+
+    #[error("Mnemonics have 12, 15, 18, 21 or 24 words, not {0}")]
+    InvalidWordCount(usize),
+}
+
+/// Generates a fresh mnemonic of `word_count` words, returning its phrase.
+pub fn generate(word_count: usize) -> Result<Zeroizing<String>, MnemonicError> {
+    let mnemonic_type = MnemonicType::for_word_count(word_count)
+        .map_err(|_| MnemonicError::InvalidWordCount(word_count))?;
+    let mnemonic = Mnemonic::new(mnemonic_type, Language::English);
+    Ok(Zeroizing::new(mnemonic.phrase().to_string()))
+}
+
+/// Derives the keys of a node with `workers` workers from the mnemonic `phrase`.
+pub fn derive_node_keys(phrase: &str, workers: WorkerId) -> Result<NodeKeys, MnemonicError> {
+    // Tolerate the phrases pasted with extra whitespace.
+    let phrase = Zeroizing::new(phrase.split_whitespace().collect::<Vec<_>>().join(" "));
+    let mnemonic = Mnemonic::from_phrase(&phrase, Language::English)
+        .map_err(|e| MnemonicError::InvalidPhrase(e.to_string()))?;
+    let seed = Seed::new(&mnemonic, "");
+
+    Ok(NodeKeys {
+        primary: derive_keypair(seed.as_bytes(), PRIMARY_LABEL),
+        primary_network: derive_keypair(seed.as_bytes(), PRIMARY_NETWORK_LABEL),
+        workers: (0..workers)
+            .map(|id| {
+                let label = format!("{WORKER_LABEL}/{id}");
+                (id, derive_keypair(seed.as_bytes(), &label))
+            })
+            .collect(),
+    })
+}
+
+/// Derives the keypair labelled `label` from the BIP39 `seed`.
+fn derive_keypair<K: KeyPair>(seed: &[u8], label: &str) -> K {
+    (0..=u8::MAX)
+        .find_map(|counter| {
+            let mut mac =
+                Hmac::<Sha256>::new_from_slice(seed).expect("HMAC takes keys of any size");
+            mac.update(label.as_bytes());
+            mac.update(&[counter]);
+            let private_key = Zeroizing::new(<[u8; 32]>::from(mac.finalize().into_bytes()));
+            K::PrivKey::from_bytes(&*private_key).ok()
+        })
+        // Each output is a private key with a probability of nearly one half or more.
+        .map(K::from)
+        .expect("One of the outputs is a private key")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fastcrypto::encoding::{Encoding, Hex};
+
+    #[test]
+    fn keys_are_recovered_from_mnemonic() {
+        let phrase = generate(DEFAULT_WORD_COUNT).unwrap();
+        assert_eq!(phrase.split(' ').count(), DEFAULT_WORD_COUNT);
+
+        let keys = derive_node_keys(&phrase, 2).unwrap();
+        let recovered = derive_node_keys(&format!("  {}\n", *phrase), 4).unwrap();
+        assert_eq!(recovered.primary.public(), keys.primary.public());
+        assert_eq!(
+            recovered.primary_network.public(),
+            keys.primary_network.public()
+        );
+        // Adding workers leaves the keys of the existing ones unchanged.
+        assert_eq!(recovered.workers.len(), 4);
+        for (id, keypair) in &keys.workers {
+            assert_eq!(recovered.workers[id].public(), keypair.public());
+        }
+        assert_ne!(
+            recovered.workers[&0].public(),
+            recovered.workers[&1].public()
+        );
+
+        let other = derive_node_keys(&generate(12).unwrap(), 2).unwrap();
+        assert_ne!(other.primary.public(), keys.primary.public());
+    }
+
+    #[test]
+    fn known_answer() {
+        // The keys must never change, or the mnemonics written down would not recover them.
+        let phrase = [&["abandon"; 11][..], &["about"]].concat().join(" ");
+        let keys = derive_node_keys(&phrase, 2).unwrap();
+        assert_eq!(
+            Hex::encode(keys.primary.copy().private()),
+            "1288983236e9dcbd0ce736b951cdd1539d529e5df4c3c91e2fd068915055915f"
+        );
+        assert_eq!(
+            Hex::encode(keys.primary_network.public()),
+            "f194abe0dd7c1f3522e2f65d79bd862c464d9f0ad5367d863d242eb2417337ed"
+        );
+        assert_eq!(
+            Hex::encode(keys.workers[&0].public()),
+            "5b5b4769ac8df8a30c1422f2b6b194dc118ab0dbb6673d1dac60756e29ce588b"
+        );
+        assert_eq!(
+            Hex::encode(keys.workers[&1].public()),
+            "f3ca679c47140c2675afc8b347edb2652325fe2459ce0846b41cf4c7b06f6822"
+        );
+    }
+
+    #[test]
+    fn invalid_mnemonic() {
+        assert!(matches!(
+            generate(13),
+            Err(MnemonicError::InvalidWordCount(13))
+        ));
+        // A valid word list with a wrong checksum.
+        let phrase = ["abandon"; 12].join(" ");
+        assert!(matches!(
+            derive_node_keys(&phrase, 1),
+            Err(MnemonicError::InvalidPhrase(_))
+        ));
+    }
+}
@@ -0,0 +1,319 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+#![warn(
+    future_incompatible,
+    nonstandard_style,
+    rust_2018_idioms,
+    rust_2021_compatibility
+)]
+
+use arc_swap::ArcSwap;
+use clap::{crate_version, App, ArgMatches};
+use config::{
+    utils::get_available_port, Authority, Committee, Import, NetworkAdminServerParameters,
+    Parameters, PrometheusMetricsParameters, SharedCommittee, SharedWorkerCache, WorkerCache,
+    WorkerId, WorkerIndex, WorkerInfo,
+};
+use crypto::{KeyPair, NetworkKeyPair};
+use eyre::Context;
+use fastcrypto::{generate_production_keypair, traits::KeyPair as _};
+use futures::{future::join_all, StreamExt};
+use multiaddr::Multiaddr;
+use narwhal_node as node;
+use node::{
+    bench::{make_transaction, BenchExecutionState, LatencyRecorder},
+    Node,
+};
+use prometheus::Registry;
+use std::{
+    collections::BTreeMap,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+use storage::NodeStorage;
+use tokio::time::{interval, sleep, Duration, Instant, MissedTickBehavior};
+use tracing::{info, subscriber::set_global_default, warn};
+use tracing_subscriber::filter::EnvFilter;
+use types::{TransactionProto, TransactionsClient};
+use worker::TrivialTransactionValidator;
+
+/// The number of bursts in which the transactions of a second are sent.
+const PRECISION: u64 = 20;
+
+#[tokio::main]
+async fn main() -> Result<(), eyre::Report> {
+    let matches = App::new("narwhal-bench")
+        .version(crate_version!())
+        .about("Load generator measuring the throughput and latency of a local Narwhal committee.")
+        .long_about("Runs a committee of --nodes authorities in the process, submits transactions of --size bytes \
+        at --rate tx/s spread over the workers of the committee for --duration seconds, and reports \
+        the throughput and the percentiles of the latency from submission to commit.")
+        .args_from_usage("--nodes=[INT] 'The number of authorities of the committee (default 4)'")
+        .args_from_usage("--workers=[INT] 'The number of workers of each authority (default 1)'")
+        .args_from_usage("--size=[INT] 'The size of each transaction in bytes (default 512)'")
+        .args_from_usage("--rate=[INT] 'The rate (txs/s) at which to send the transactions (default 1000)'")
+        .args_from_usage("--duration=[INT] 'The duration of the load in seconds (default 30)'")
+        .args_from_usage("--drain=[INT] 'The time left to commit the transactions after the load in seconds (default 5)'")
+        .args_from_usage("--parameters=[FILE] 'The file containing the parameters of the nodes'")
+        .args_from_usage("--output=[FILE] 'The file where to export the report as JSON'")
+        .get_matches();
+
+    let env_filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("warn"));
+    let subscriber = tracing_subscriber::fmt::Subscriber::builder()
+        .with_env_filter(env_filter)
+        .with_writer(std::io::stderr)
+        .finish();
+    set_global_default(subscriber).expect("Failed to set subscriber");
+
+    let nodes = parse(&matches, "nodes", 4)?;
+    let workers = parse(&matches, "workers", 1)?;
+    let size = parse(&matches, "size", 512)?;
+    let rate = parse(&matches, "rate", 1_000)?;
+    let duration = Duration::from_secs(parse(&matches, "duration", 30)?);
+    let drain = Duration::from_secs(parse(&matches, "drain", 5)?);
+    let parameters = match matches.value_of("parameters") {
+        Some(filename) => {
+            Parameters::import(filename).context("Failed to load the nodes' parameters")?
+        }
+        None => Parameters::default(),
+    };
+    eyre::ensure!(nodes > 0, "The committee must have at least one authority");
+    eyre::ensure!(workers > 0, "The authorities must have at least one worker");
+    let clients = nodes * workers as usize;
+    eyre::ensure!(
+        rate >= PRECISION * clients as u64,
+        "Transaction rate is too low, should be at least {} tx/s for {clients} workers",
+        PRECISION * clients as u64
+    );
+
+    let committee = LocalCommittee::new(nodes, workers);
+    let recorder = Arc::new(LatencyRecorder::new());
+    let store_dir = tempfile::tempdir().context("Failed to create the stores directory")?;
+    committee
+        .spawn(store_dir.path(), parameters, recorder.clone())
+        .await?;
+
+    // Spread the load over all the workers of the committee.
+    let running = Arc::new(AtomicBool::new(true));
+    let client_rate = rate / clients as u64;
+    let handles: Vec<_> = committee
+        .transactions_addresses()
+        .into_iter()
+        .enumerate()
+        .map(|(index, address)| {
+            tokio::spawn(run_client(
+                index as u64,
+                address,
+                size,
+                client_rate,
+                recorder.clone(),
+                running.clone(),
+            ))
+        })
+        .collect();
+
+    info!("Sending {rate} tx/s of {size} B for {duration:?}");
+    let start = Instant::now();
+    sleep(duration).await;
+    running.store(false, Ordering::Relaxed);
+    join_all(handles).await;
+    let load = start.elapsed();
+    sleep(drain).await;
+
+    let report = recorder.report(load);
+    println!("{report}");
+    if let Some(output) = matches.value_of("output") {
+        let json = serde_json::to_string_pretty(&report)?;
+        std::fs::write(output, json).with_context(|| format!("Failed to write {output}"))?;
+    }
+    Ok(())
+}
+
+fn parse<T: std::str::FromStr>(
+    matches: &ArgMatches<'_>,
+    name: &str,
+    default: T,
+) -> Result<T, eyre::Report> {
+    matches.value_of(name).map_or(Ok(default), |value| {
+        value
+            .parse()
+            .map_err(|_| eyre::eyre!("The --{name} must be a non-negative integer"))
+    })
+}
+
+/// The keys and the configuration of a committee running on the local host.
+struct LocalCommittee {
+    authorities: Vec<(KeyPair, NetworkKeyPair, Vec<NetworkKeyPair>)>,
+    committee: SharedCommittee,
+    worker_cache: SharedWorkerCache,
+}
+
+impl LocalCommittee {
+    fn new(nodes: usize, workers: WorkerId) -> Self {
+        let host = "127.0.0.1";
+        let address = |protocol: &str| -> Multiaddr {
+            format!("/ip4/{host}/{protocol}/{}", get_available_port(host))
+                .parse()
+                .unwrap()
+        };
+
+        let authorities: Vec<_> = (0..nodes)
+            .map(|_| {
+                (
+                    generate_production_keypair::<KeyPair>(),
+                    generate_production_keypair::<NetworkKeyPair>(),
+                    (0..workers)
+                        .map(|_| generate_production_keypair::<NetworkKeyPair>())
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .collect();
+
+        let committee = Committee {
+            authorities: authorities
+                .iter()
+                .map(|(keypair, network_keypair, _)| {
+                    let authority = Authority {
+                        stake: 1,
+                        primary_address: address("udp"),
+                        network_key: network_keypair.public().clone(),
+                    };
+                    (keypair.public().clone(), authority)
+                })
+                .collect(),
+            epoch: 0,
+            quorum_policy: Default::default(),
+            protocol_config: Default::default(),
+        };
+        let worker_cache = WorkerCache {
+            workers: authorities
+                .iter()
+                .map(|(keypair, _, worker_keypairs)| {
+                    let index = worker_keypairs
+                        .iter()
+                        .enumerate()
+                        .map(|(id, worker_keypair)| {
+                            let info = WorkerInfo {
+                                name: worker_keypair.public().clone(),
+                                transactions: address("tcp").with(multiaddr::Protocol::Http),
+                                worker_address: address("udp"),
+                            };
+                            (id as WorkerId, info)
+                        })
+                        .collect::<BTreeMap<_, _>>();
+                    (keypair.public().clone(), WorkerIndex(index))
+                })
+                .collect(),
+            epoch: 0,
+        };
+
+        Self {
+            authorities,
+            committee: Arc::new(ArcSwap::from_pointee(committee)),
+            worker_cache: Arc::new(ArcSwap::from_pointee(worker_cache)),
+        }
+    }
+
+    /// Spawns the primaries and the workers of the committee, the execution state of the first
+    /// authority feeding `recorder`.
+    async fn spawn(
+        &self,
+        store_dir: &std::path::Path,
+        parameters: Parameters,
+        recorder: Arc<LatencyRecorder>,
+    ) -> Result<(), eyre::Report> {
+        for (i, (keypair, network_keypair, worker_keypairs)) in self.authorities.iter().enumerate()
+        {
+            // The servers of each node listen on their own ports.
+            let parameters = Parameters {
+                network_admin_server: NetworkAdminServerParameters::default(),
+                prometheus_metrics: PrometheusMetricsParameters::default(),
+                ..parameters.clone()
+            };
+            let store = NodeStorage::reopen(store_dir.join(format!("authority-{i}")));
+            let recorder = (i == 0).then(|| recorder.clone());
+
+            Node::spawn_primary(
+                keypair.copy(),
+                network_keypair.copy(),
+                self.committee.clone(),
+                self.worker_cache.clone(),
+                &store,
+                parameters.clone(),
+                /* consensus */ true,
+                Arc::new(BenchExecutionState::new(recorder)),
+                &Registry::new(),
+            )
+            .await
+            .context("Failed to spawn a primary")?;
+
+            Node::spawn_workers(
+                keypair.public().clone(),
+                worker_keypairs
+                    .iter()
+                    .enumerate()
+                    .map(|(id, keypair)| (id as WorkerId, keypair.copy()))
+                    .collect(),
+                self.committee.clone(),
+                self.worker_cache.clone(),
+                &store,
+                parameters,
+                TrivialTransactionValidator::default(),
+                &Registry::new(),
+            );
+        }
+        Ok(())
+    }
+
+    /// The addresses where the workers of the committee receive the transactions.
+    fn transactions_addresses(&self) -> Vec<Multiaddr> {
+        self.worker_cache
+            .load()
+            .workers
+            .values()
+            .flat_map(|index| index.0.values().map(|info| info.transactions.clone()))
+            .collect()
+    }
+}
+
+/// Sends `rate` tx/s of `size` bytes to the worker at `address` while `running`, recording
+/// their submission. The ids of the transactions of the client start with its `index`.
+async fn run_client(
+    index: u64,
+    address: Multiaddr,
+    size: usize,
+    rate: u64,
+    recorder: Arc<LatencyRecorder>,
+    running: Arc<AtomicBool>,
+) {
+    let channel = mysten_network::config::Config::new()
+        .connect_lazy(&address)
+        .expect("The transactions addresses are valid");
+    let mut client = TransactionsClient::new(channel);
+
+    let burst = rate / PRECISION;
+    let mut interval = interval(Duration::from_millis(1000 / PRECISION));
+    interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+    let mut counter = 0u64;
+
+    while running.load(Ordering::Relaxed) {
+        interval.tick().await;
+        let first = counter;
+        counter += burst;
+        let recorder = recorder.clone();
+        let stream = tokio_stream::iter(first..counter).map(move |n| {
+            let id = index << 48 | n;
+            recorder.submitted(id);
+            TransactionProto {
+                transaction: make_transaction(id, size),
+                metadata: Default::default(),
+            }
+        });
+        if let Err(e) = client.submit_transaction_stream(stream).await {
+            // The worker may not be up yet.
+            warn!("Failed to send transactions to {address}: {e}");
+        }
+    }
+}
@@ -0,0 +1,70 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+#![warn(
+    future_incompatible,
+    nonstandard_style,
+    rust_2018_idioms,
+    rust_2021_compatibility
+)]
+
+use clap::{crate_version, App, AppSettings, SubCommand};
+use config::{
+    check_duplicate_authorities, Committee, ConfigError, Import, Parameters, WorkerCache,
+};
+use eyre::{bail, Context};
+
+fn main() -> Result<(), eyre::Report> {
+    let matches = App::new("narwhal-config")
+        .version(crate_version!())
+        .about("Tools to manage the configuration of a Narwhal node.")
+        .subcommand(
+            SubCommand::with_name("check")
+                .about("Check the consistency of the committee, workers and parameters files")
+                .args_from_usage("--committee=<FILE> 'The file containing committee information'")
+                .args_from_usage("--workers=[FILE] 'The file containing worker information'")
+                .args_from_usage("--parameters=[FILE] 'The file containing the node parameters'"),
+        )
+        .setting(AppSettings::SubcommandRequiredElseHelp)
+        .get_matches();
+
+    match matches.subcommand() {
+        ("check", Some(sub_matches)) => {
+            let mut count = 0;
+
+            let committee_file = sub_matches.value_of("committee").unwrap();
+            let committee =
+                Committee::import(committee_file).context("Failed to load the committee")?;
+            count += report(committee_file, check_duplicate_authorities(committee_file));
+            count += report(committee_file, committee.validate());
+
+            if let Some(workers_file) = sub_matches.value_of("workers") {
+                let worker_cache =
+                    WorkerCache::import(workers_file).context("Failed to load the workers")?;
+                count += report(workers_file, check_duplicate_authorities(workers_file));
+                count += report(workers_file, worker_cache.validate(&committee));
+            }
+
+            if let Some(parameters_file) = sub_matches.value_of("parameters") {
+                let parameters =
+                    Parameters::import(parameters_file).context("Failed to load the parameters")?;
+                count += report(parameters_file, parameters.validate());
+            }
+
+            if count > 0 {
+                bail!("Found {count} configuration problem(s)");
+            }
+            println!("Configuration is valid");
+        }
+        _ => unreachable!(),
+    }
+    Ok(())
+}
+
+/// Prints the problems found in a configuration file, and returns their number.
+fn report(file: &str, result: Result<(), Vec<ConfigError>>) -> usize {
+    let errors = result.err().unwrap_or_default();
+    for error in &errors {
+        println!("{file}: {error}");
+    }
+    errors.len()
+}
@@ -0,0 +1,192 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+#![warn(
+    future_incompatible,
+    nonstandard_style,
+    rust_2018_idioms,
+    rust_2021_compatibility
+)]
+
+use clap::{crate_version, App, AppSettings, Arg, ArgMatches, SubCommand};
+use config::{Epoch, Import, Parameters, StorageLayout};
+use eyre::{bail, eyre, Context};
+use prometheus::Registry;
+use std::path::{Path, PathBuf};
+use storage::{ExportOptions, NodeStorage, VerifyOptions};
+use types::Round;
+
+#[tokio::main]
+async fn main() -> Result<(), eyre::Report> {
+    let matches = App::new("narwhal-db")
+        .version(crate_version!())
+        .about("Tools to inspect the data store of a Narwhal node.")
+        .subcommand(
+            SubCommand::with_name("stats")
+                .about("Print the statistics of every table, while the node may be running")
+                .args_from_usage("--store=<PATH> 'The path of the data store'")
+                .args_from_usage("--parameters=[FILE] 'The file containing the node parameters, which tell how the store is laid out and encrypted'")
+                .args_from_usage("--epoch=[INT] 'The epoch to inspect, required when all the epochs share the store'")
+                .args_from_usage("--secondary=[PATH] 'The directory keeping the logs of the read-only instance, a temporary one by default'"),
+        )
+        .subcommand(
+            SubCommand::with_name("export")
+                .about("Export the certificates, their headers and a summary of their batches for analysis, while the node may be running")
+                .args_from_usage("--store=<PATH> 'The path of the data store'")
+                .args_from_usage("--parameters=[FILE] 'The file containing the node parameters, which tell how the store is laid out and encrypted'")
+                .args_from_usage("--epoch=[INT] 'The epoch to export, required when all the epochs share the store'")
+                .args_from_usage("--secondary=[PATH] 'The directory keeping the logs of the read-only instance, a temporary one by default'")
+                .args_from_usage("--output=<PATH> 'The directory to write the tables to'")
+                .arg(
+                    Arg::with_name("format")
+                        .long("format")
+                        .takes_value(true)
+                        .help("The format of the tables")
+                        .possible_values(&["csv", "parquet"])
+                        .default_value("csv"),
+                )
+                .args_from_usage("--from-round=[INT] 'The lowest round to export'")
+                .args_from_usage("--to-round=[INT] 'The highest round to export'"),
+        )
+        .subcommand(
+            SubCommand::with_name("verify")
+                .about("Check that the stores of a stopped node are mutually consistent, and optionally repair them")
+                .args_from_usage("--store=<PATH> 'The path of the data store'")
+                .args_from_usage("--parameters=[FILE] 'The file containing the node parameters, which tell how the store is laid out and encrypted'")
+                .args_from_usage("--epoch=[INT] 'The epoch to check, required when all the epochs share the store'")
+                .args_from_usage("--check-batches 'Also check that the batches of the payload tokens are stored, when the workers share the store of their primary'")
+                .args_from_usage("--repair 'Delete the inconsistent entries, for the node to fetch them again'"),
+        )
+        .setting(AppSettings::SubcommandRequiredElseHelp)
+        .get_matches();
+
+    match matches.subcommand() {
+        ("stats", Some(sub_matches)) => {
+            let store = open_read_only(sub_matches)?;
+            println!("table\tsize (bytes)\testimated keys\tpending compaction (bytes)");
+            for table in store.tables() {
+                let stats = store
+                    .table_stats(&table)
+                    .with_context(|| format!("Failed to read the statistics of {table}"))?;
+                println!(
+                    "{table}\t{}\t{}\t{}",
+                    stats.size, stats.estimated_keys, stats.pending_compaction_bytes
+                );
+            }
+        }
+        ("export", Some(sub_matches)) => {
+            let store = open_read_only(sub_matches)?;
+            let round = |name| {
+                sub_matches
+                    .value_of(name)
+                    .map(|round| round.parse::<Round>())
+                    .transpose()
+                    .context("The rounds must be positive integers")
+            };
+            let options = ExportOptions {
+                format: sub_matches
+                    .value_of("format")
+                    .unwrap()
+                    .parse()
+                    .map_err(|e: String| eyre!(e))?,
+                from_round: round("from-round")?.unwrap_or_default(),
+                to_round: round("to-round")?,
+            };
+            let dir = sub_matches.value_of("output").unwrap();
+            let summary = store
+                .export_analytics(Path::new(dir), &options)
+                .await
+                .context("Failed to export the stores")?;
+            println!(
+                "Exported {} certificates, {} headers and {} batches to {dir}",
+                summary.certificates, summary.headers, summary.batches
+            );
+        }
+        ("verify", Some(sub_matches)) => {
+            let (parameters, epoch) = parameters_and_epoch(sub_matches)?;
+            let store_path = sub_matches.value_of("store").unwrap();
+            let store = match (parameters.storage.layout, epoch) {
+                // Repairs only delete entries, so the choice of the active key does not matter.
+                (StorageLayout::PerEpoch, epoch) => NodeStorage::reopen_with_parameters(
+                    store_path,
+                    &parameters.storage,
+                    epoch.unwrap_or(Epoch::MAX),
+                ),
+                (StorageLayout::Shared, Some(epoch)) => NodeStorage::open_epoch(
+                    &NodeStorage::open_database(store_path, &parameters.storage),
+                    &parameters.storage,
+                    epoch,
+                    &Registry::new(),
+                ),
+                (StorageLayout::Shared, None) => {
+                    bail!("The epoch to check is required when all the epochs share the store")
+                }
+            };
+
+            let options = VerifyOptions {
+                check_batches: sub_matches.is_present("check-batches"),
+                repair: sub_matches.is_present("repair"),
+            };
+            let report = store
+                .verify(&options)
+                .await
+                .context("Failed to read the stores")?;
+            for inconsistency in &report.inconsistencies {
+                println!("{inconsistency}");
+            }
+            println!(
+                "Checked {} certificates, {} headers and {} payload tokens",
+                report.certificates, report.headers, report.payload_tokens
+            );
+
+            let count = report.inconsistencies.len();
+            if count > 0 {
+                if options.repair {
+                    println!("Repaired {count} inconsistencies");
+                } else {
+                    bail!("Found {count} inconsistencies");
+                }
+            } else {
+                println!("The stores are consistent");
+            }
+        }
+        _ => unreachable!(),
+    }
+    Ok(())
+}
+
+/// The node parameters and the epoch given to a subcommand.
+fn parameters_and_epoch(
+    matches: &ArgMatches<'_>,
+) -> Result<(Parameters, Option<Epoch>), eyre::Report> {
+    let parameters = match matches.value_of("parameters") {
+        Some(filename) => {
+            Parameters::import(filename).context("Failed to load the node's parameters")?
+        }
+        None => Parameters::default(),
+    };
+    let epoch = matches
+        .value_of("epoch")
+        .map(|epoch| epoch.parse::<Epoch>())
+        .transpose()
+        .context("The epoch must be a positive integer")?;
+    Ok((parameters, epoch))
+}
+
+/// Opens the store given to a subcommand read-only, alongside the node if it is running.
+fn open_read_only(matches: &ArgMatches<'_>) -> Result<NodeStorage, eyre::Report> {
+    let (parameters, epoch) = parameters_and_epoch(matches)?;
+    if parameters.storage.layout == StorageLayout::Shared && epoch.is_none() {
+        bail!("The epoch is required when all the epochs share the store");
+    }
+    let secondary_path = match matches.value_of("secondary") {
+        Some(path) => PathBuf::from(path),
+        None => std::env::temp_dir().join(format!("narwhal-db-{}", std::process::id())),
+    };
+    NodeStorage::open_read_only(
+        PathBuf::from(matches.value_of("store").unwrap()),
+        secondary_path,
+        &parameters.storage,
+        epoch.unwrap_or(Epoch::MAX),
+    )
+    .context("Failed to open the store")
+}
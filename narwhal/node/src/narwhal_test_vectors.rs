@@ -0,0 +1,31 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+#![warn(
+    future_incompatible,
+    nonstandard_style,
+    rust_2018_idioms,
+    rust_2021_compatibility
+)]
+
+use clap::{crate_version, App};
+use eyre::Context;
+use types::test_vectors::test_vectors;
+
+fn main() -> Result<(), eyre::Report> {
+    let matches = App::new("narwhal-test-vectors")
+        .version(crate_version!())
+        .about(
+            "Emits the canonical test vectors of the wire format of batches, headers and \
+             certificates, as JSON.",
+        )
+        .args_from_usage("--output=[FILE] 'The file to write the vectors to, instead of stdout'")
+        .get_matches();
+
+    let vectors = serde_json::to_string_pretty(&test_vectors())?;
+    match matches.value_of("output") {
+        Some(file) => std::fs::write(file, vectors + "\n")
+            .with_context(|| format!("Failed to write the vectors to {file}"))?,
+        None => println!("{vectors}"),
+    }
+    Ok(())
+}
@@ -0,0 +1,228 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Background pruning of the persisted DAG. Garbage collection only drops the in-memory state of
+//! the old rounds, so without pruning the stores grow for the whole epoch.
+use config::{PruningParameters, WorkerId};
+use executor::ExecutionState;
+use mysten_metrics::spawn_logged_monitored_task;
+use prometheus::{
+    register_int_counter_vec_with_registry, register_int_gauge_with_registry, IntCounterVec,
+    IntGauge, Registry,
+};
+use serde::Serialize;
+use std::sync::Arc;
+use storage::{CertificateStore, NodeStorage, PayloadToken};
+use store::Store;
+use tokio::{
+    sync::{mpsc, oneshot, watch},
+    task::JoinHandle,
+    time::interval,
+};
+use tracing::{debug, warn};
+use types::{
+    Batch, BatchDigest, ConsensusStore, Header, HeaderDigest, ReconfigureNotification, Round,
+    StoreResult,
+};
+
+#[derive(Clone, Debug)]
+pub struct PrunerMetrics {
+    /// The round below which the stores have been pruned
+    pub pruned_round: IntGauge,
+    /// The number of bytes reclaimed by pruning, per store
+    pub reclaimed_bytes: IntCounterVec,
+}
+
+impl PrunerMetrics {
+    pub fn new(registry: &Registry) -> Self {
+        Self {
+            pruned_round: register_int_gauge_with_registry!(
+                "pruned_round",
+                "The round below which the certificates, headers, payload and batches have been pruned",
+                registry
+            )
+            .unwrap(),
+            reclaimed_bytes: register_int_counter_vec_with_registry!(
+                "pruning_reclaimed_bytes",
+                "The number of bytes of serialized data deleted by pruning",
+                &["store"],
+                registry
+            )
+            .unwrap(),
+        }
+    }
+}
+
+/// Periodically deletes the certificates (with their headers, payload tokens and batches) of
+/// the rounds that are both garbage collected by consensus and executed, minus a retention margin.
+pub struct Pruner<State> {
+    certificate_store: CertificateStore,
+    header_store: Store<HeaderDigest, Header>,
+    payload_store: Store<(BatchDigest, WorkerId), PayloadToken>,
+    batch_store: Store<BatchDigest, Batch>,
+    consensus_store: Arc<ConsensusStore>,
+    /// Provides the last executed sub-dag.
+    execution_state: State,
+    /// Receives the last round committed by consensus.
+    rx_consensus_round_updates: watch::Receiver<Round>,
+    /// Receives the reconfiguration notifications, to stop on shutdown.
+    rx_reconfigure: watch::Receiver<ReconfigureNotification>,
+    /// Receives the requests to prune right away, answered with the round below which the
+    /// stores are then pruned.
+    rx_prune: mpsc::Receiver<oneshot::Sender<StoreResult<Round>>>,
+    gc_depth: Round,
+    parameters: PruningParameters,
+    /// All the rounds below this one have been pruned.
+    pruned_round: Round,
+    metrics: PrunerMetrics,
+}
+
+impl<State: ExecutionState + Send + Sync + 'static> Pruner<State> {
+    #[must_use]
+    pub fn spawn(
+        store: &NodeStorage,
+        execution_state: State,
+        rx_consensus_round_updates: watch::Receiver<Round>,
+        rx_reconfigure: watch::Receiver<ReconfigureNotification>,
+        rx_prune: mpsc::Receiver<oneshot::Sender<StoreResult<Round>>>,
+        gc_depth: Round,
+        parameters: PruningParameters,
+        metrics: PrunerMetrics,
+    ) -> JoinHandle<()> {
+        let pruner = Self {
+            certificate_store: store.certificate_store.clone(),
+            header_store: store.header_store.clone(),
+            payload_store: store.payload_store.clone(),
+            batch_store: store.batch_store.clone(),
+            consensus_store: store.consensus_store.clone(),
+            execution_state,
+            rx_consensus_round_updates,
+            rx_reconfigure,
+            rx_prune,
+            gc_depth,
+            parameters,
+            pruned_round: 0,
+            metrics,
+        };
+        spawn_logged_monitored_task!(pruner.run(), "PrunerTask")
+    }
+
+    async fn run(mut self) {
+        let mut timer = interval(self.parameters.interval);
+        loop {
+            tokio::select! {
+                _ = timer.tick() => {
+                    if let Err(e) = self.prune().await {
+                        warn!("Failed to prune the stores: {e}");
+                    }
+                },
+
+                Some(tx_pruned) = self.rx_prune.recv() => {
+                    let result = self.prune().await.map(|()| self.pruned_round);
+                    let _ = tx_pruned.send(result);
+                },
+
+                result = self.rx_reconfigure.changed() => {
+                    result.expect("Committee channel dropped");
+                    let message = self.rx_reconfigure.borrow().clone();
+                    if let ReconfigureNotification::Shutdown = message {
+                        return;
+                    }
+                }
+            }
+        }
+    }
+
+    /// The round of the leader of the last executed sub-dag, or 0 if none was executed.
+    async fn executed_round(&self) -> StoreResult<Round> {
+        let index = self.execution_state.last_executed_sub_dag_index().await;
+        let round = match self.consensus_store.read_committed_sub_dag(&index)? {
+            Some(sub_dag) => self
+                .certificate_store
+                .read(sub_dag.leader)?
+                .map_or(0, |leader| leader.round()),
+            None => 0,
+        };
+        Ok(round)
+    }
+
+    /// Returns the round below which the stores can be pruned.
+    async fn watermark(&self) -> StoreResult<Round> {
+        let gc_round = self
+            .rx_consensus_round_updates
+            .borrow()
+            .saturating_sub(self.gc_depth);
+        let executed_round = self.executed_round().await?;
+        Ok(gc_round
+            .min(executed_round)
+            .saturating_sub(self.parameters.retention_rounds))
+    }
+
+    async fn prune(&mut self) -> StoreResult<()> {
+        let watermark = self.watermark().await?;
+        if watermark <= self.pruned_round {
+            return Ok(());
+        }
+
+        let certificates = self.certificate_store.prune_before_round(watermark)?;
+        self.reclaimed("certificates", &certificates);
+
+        let headers: Vec<_> = certificates.iter().map(|c| c.header.digest()).collect();
+        let payloads: Vec<_> = certificates
+            .iter()
+            .flat_map(|c| c.header.payload.iter().map(|(d, id)| (*d, *id)))
+            .collect();
+        let batches: Vec<_> = payloads.iter().map(|(digest, _)| *digest).collect();
+
+        let stored: Vec<_> = self
+            .header_store
+            .read_all(headers.clone())
+            .await?
+            .into_iter()
+            .flatten()
+            .collect();
+        self.header_store.remove_all(headers).await?;
+        self.reclaimed("headers", &stored);
+
+        let stored: Vec<_> = self
+            .payload_store
+            .read_all(payloads.clone())
+            .await?
+            .into_iter()
+            .zip(&payloads)
+            .filter_map(|(token, key)| token.map(|token| (*key, token)))
+            .collect();
+        self.payload_store.remove_all(payloads).await?;
+        self.reclaimed("payload", &stored);
+
+        let stored: Vec<_> = self
+            .batch_store
+            .read_all(batches.clone())
+            .await?
+            .into_iter()
+            .flatten()
+            .collect();
+        self.batch_store.remove_all(batches).await?;
+        self.reclaimed("batches", &stored);
+
+        debug!(
+            "Pruned {} certificates below round {watermark}",
+            certificates.len()
+        );
+        self.pruned_round = watermark;
+        self.metrics.pruned_round.set(watermark as i64);
+        Ok(())
+    }
+
+    /// Accounts for the serialized size of the deleted `entries` of `store`.
+    fn reclaimed<T: Serialize>(&self, store: &str, entries: &[T]) {
+        let bytes: u64 = entries
+            .iter()
+            .map(|entry| bincode::serialized_size(entry).unwrap_or_default())
+            .sum();
+        self.metrics
+            .reclaimed_bytes
+            .with_label_values(&[store])
+            .inc_by(bytes);
+    }
+}
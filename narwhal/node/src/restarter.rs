@@ -1,25 +1,172 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
-use crate::{Node, NodeStorage};
+use crate::{admin_client::AdminClient, metrics::NARWHAL_METRICS_PREFIX, Node, NodeStorage};
 use arc_swap::ArcSwap;
-use config::{Committee, Parameters, SharedWorkerCache, WorkerCache, WorkerId};
+use async_trait::async_trait;
+use config::{
+    Committee, CommitteeUpdate, CommitteeUpdateError, Epoch, Parameters, SharedWorkerCache,
+    StorageLayout, WorkerCache, WorkerCacheUpdate, WorkerId,
+};
 use crypto::{KeyPair, NetworkKeyPair};
 use executor::ExecutionState;
 use fastcrypto::traits::KeyPair as _;
 use futures::future::join_all;
-use mysten_metrics::RegistryService;
+use mysten_metrics::{RegistryOptions, RegistryService};
+use network::auth::PeerGrace;
 use prometheus::Registry;
 use std::{path::PathBuf, sync::Arc};
-use tokio::sync::mpsc::Receiver;
-use types::ReconfigureNotification;
+use tokio::sync::mpsc::{channel, Receiver, Sender};
+use types::{ConsensusOutput, ReconfigureNotification};
 use worker::TransactionValidator;
 
+/// The keys, committee and worker cache a node runs an epoch with.
+pub type EpochConfiguration = (
+    KeyPair,
+    NetworkKeyPair,
+    Committee,
+    Vec<(WorkerId, NetworkKeyPair)>,
+    WorkerCache,
+);
+
+/// A request to restart the node with a new configuration.
+pub enum NodeReconfiguration {
+    /// The complete configuration of the next epoch.
+    Full(EpochConfiguration),
+    /// The keys of the next epoch, along with the changes to apply on top of the current
+    /// committee and worker cache.
+    Delta {
+        keypair: KeyPair,
+        network_keypair: NetworkKeyPair,
+        committee: CommitteeUpdate,
+        worker_ids_and_keypairs: Vec<(WorkerId, NetworkKeyPair)>,
+        worker_cache: WorkerCacheUpdate,
+    },
+    /// End the current epoch now and move to the next one with the same keys, committee and
+    /// workers, as when the epoch ends because of the epoch policy.
+    EndOfEpoch,
+    /// End the current epoch now and move to the next one with these keys, committee and worker
+    /// cache, of which the epochs are replaced by the one after the current epoch: the caller,
+    /// e.g. Sui at the end of its own epoch, doesn't know how many epochs ended by the policy.
+    NextEpoch(EpochConfiguration),
+}
+
+impl From<EpochConfiguration> for NodeReconfiguration {
+    fn from(configuration: EpochConfiguration) -> Self {
+        NodeReconfiguration::Full(configuration)
+    }
+}
+
+impl NodeReconfiguration {
+    /// Returns the complete configuration of the next epoch, applying the changes (if any) on top
+    /// of the current keys, committee and worker cache.
+    pub fn resolve(
+        self,
+        keypair: &KeyPair,
+        network_keypair: &NetworkKeyPair,
+        committee: &Committee,
+        worker_ids_and_keypairs: &[(WorkerId, NetworkKeyPair)],
+        worker_cache: &WorkerCache,
+    ) -> Result<EpochConfiguration, Vec<CommitteeUpdateError>> {
+        match self {
+            NodeReconfiguration::Full(configuration) => Ok(configuration),
+            NodeReconfiguration::EndOfEpoch => Ok((
+                keypair.copy(),
+                network_keypair.copy(),
+                Committee {
+                    epoch: committee.epoch() + 1,
+                    ..committee.clone()
+                },
+                worker_ids_and_keypairs
+                    .iter()
+                    .map(|(id, keypair)| (*id, keypair.copy()))
+                    .collect(),
+                WorkerCache {
+                    epoch: committee.epoch() + 1,
+                    ..worker_cache.clone()
+                },
+            )),
+            NodeReconfiguration::NextEpoch((
+                keypair,
+                network_keypair,
+                next_committee,
+                worker_ids_and_keypairs,
+                next_worker_cache,
+            )) => Ok((
+                keypair,
+                network_keypair,
+                Committee {
+                    epoch: committee.epoch() + 1,
+                    ..next_committee
+                },
+                worker_ids_and_keypairs,
+                WorkerCache {
+                    epoch: committee.epoch() + 1,
+                    ..next_worker_cache
+                },
+            )),
+            NodeReconfiguration::Delta {
+                keypair,
+                network_keypair,
+                committee: committee_update,
+                worker_ids_and_keypairs,
+                worker_cache: worker_cache_update,
+            } => {
+                let new_committee = committee.apply(&committee_update);
+                let new_worker_cache = worker_cache.apply(&worker_cache_update);
+                match (new_committee, new_worker_cache) {
+                    (Ok(new_committee), Ok(new_worker_cache)) => Ok((
+                        keypair,
+                        network_keypair,
+                        new_committee,
+                        worker_ids_and_keypairs,
+                        new_worker_cache,
+                    )),
+                    (new_committee, new_worker_cache) => Err(new_committee
+                        .err()
+                        .into_iter()
+                        .chain(new_worker_cache.err())
+                        .flatten()
+                        .collect()),
+                }
+            }
+        }
+    }
+}
+
+/// Forwards everything to the execution state of the node, and additionally notifies the restarter
+/// when the epoch ends according to the epoch policy.
+struct EndOfEpochNotifier<State> {
+    execution_state: Arc<State>,
+    tx_end_of_epoch: Sender<Epoch>,
+}
+
+#[async_trait]
+impl<State: ExecutionState + Send + Sync + 'static> ExecutionState for EndOfEpochNotifier<State> {
+    async fn handle_consensus_output(&self, consensus_output: ConsensusOutput) {
+        self.execution_state
+            .handle_consensus_output(consensus_output)
+            .await
+    }
+
+    async fn last_executed_sub_dag_index(&self) -> u64 {
+        self.execution_state.last_executed_sub_dag_index().await
+    }
+
+    async fn handle_end_of_epoch(&self, epoch: Epoch) {
+        self.execution_state.handle_end_of_epoch(epoch).await;
+        // The restarter may already be moving to a new epoch.
+        let _ = self.tx_end_of_epoch.send(epoch).await;
+    }
+}
+
 // Module to start a node (primary, workers and default consensus), keep it running, and restarting it
-/// every time the committee changes.
+/// every time the committee changes. When the epoch ends because of the epoch policy (see
+/// `Parameters::epoch_policy`) or upon a [`NodeReconfiguration::EndOfEpoch`] request, the node
+/// restarts with the same keys, committee and workers for the next epoch.
 pub struct NodeRestarter;
 
 impl NodeRestarter {
-    pub async fn watch<State>(
+    pub async fn watch<State, M>(
         primary_keypair: KeyPair,
         primary_network_keypair: NetworkKeyPair,
         worker_ids_and_keypairs: Vec<(WorkerId, NetworkKeyPair)>,
@@ -29,50 +176,84 @@ impl NodeRestarter {
         execution_state: Arc<State>,
         parameters: Parameters,
         tx_validator: impl TransactionValidator,
-        mut rx_reconfigure: Receiver<(
-            KeyPair,
-            NetworkKeyPair,
-            Committee,
-            Vec<(WorkerId, NetworkKeyPair)>,
-            WorkerCache,
-        )>,
+        mut rx_reconfigure: Receiver<M>,
         registry_service: RegistryService,
     ) where
         State: ExecutionState + Send + Sync + 'static,
+        M: Into<NodeReconfiguration>,
     {
         let mut primary_keypair = primary_keypair;
         let mut primary_network_keypair = primary_network_keypair;
         let mut name = primary_keypair.public().clone();
         let mut worker_ids_and_keypairs = worker_ids_and_keypairs;
         let mut committee = committee.clone();
+        let (tx_end_of_epoch, mut rx_end_of_epoch) = channel(1);
 
         let mut handles = Vec::new();
         let mut registry_id;
 
+        // With the shared layout, all the epochs use the same database.
+        let database = match parameters.storage.layout {
+            StorageLayout::PerEpoch => None,
+            StorageLayout::Shared => Some(NodeStorage::open_database(
+                &storage_base_path,
+                &parameters.storage,
+            )),
+        };
+
         // Listen for new committees.
         loop {
             tracing::info!("Starting epoch E{}", committee.epoch());
 
-            // TODO: eventually replace this with a prefixed version of it
-            // for all metrics can start with narwhal_
+            // The counters of the registry continue from the ones of the previous epochs.
             let registry = Registry::new();
-            registry_id = registry_service.add(registry.clone());
+            registry_id = registry_service.add_with_options(
+                registry.clone(),
+                RegistryOptions {
+                    prefix: Some(NARWHAL_METRICS_PREFIX.to_string()),
+                    epoch: Some(committee.epoch()),
+                },
+            );
 
             // Get a fresh store for the new epoch.
-            let mut store_path = storage_base_path.clone();
-            store_path.push(format!("epoch{}", committee.epoch()));
-            let store = NodeStorage::reopen(store_path);
+            let store = match &database {
+                Some(database) => {
+                    let store = NodeStorage::open_epoch(
+                        database,
+                        &parameters.storage,
+                        committee.epoch(),
+                        &registry,
+                    );
+                    if let Err(e) = NodeStorage::delete_epochs_before(database, committee.epoch()) {
+                        tracing::warn!("Failed to delete the data of the past epochs: {e}");
+                    }
+                    store
+                }
+                None => {
+                    let mut store_path = storage_base_path.clone();
+                    store_path.push(format!("epoch{}", committee.epoch()));
+                    NodeStorage::reopen_with_metrics(
+                        store_path,
+                        &parameters.storage,
+                        committee.epoch(),
+                        &registry,
+                    )
+                }
+            };
 
             // Restart the relevant components.
             let primary_handles = Node::spawn_primary(
-                primary_keypair,
-                primary_network_keypair,
+                primary_keypair.copy(),
+                primary_network_keypair.copy(),
                 Arc::new(ArcSwap::new(Arc::new(committee.clone()))),
                 worker_cache.clone(),
                 &store,
                 parameters.clone(),
                 /* consensus */ true,
-                execution_state.clone(),
+                Arc::new(EndOfEpochNotifier {
+                    execution_state: execution_state.clone(),
+                    tx_end_of_epoch: tx_end_of_epoch.clone(),
+                }),
                 &registry,
             )
             .await
@@ -80,7 +261,10 @@ impl NodeRestarter {
 
             let worker_handles = Node::spawn_workers(
                 name.clone(),
-                worker_ids_and_keypairs,
+                worker_ids_and_keypairs
+                    .iter()
+                    .map(|(id, keypair)| (*id, keypair.copy()))
+                    .collect(),
                 Arc::new(ArcSwap::new(Arc::new(committee.clone()))),
                 worker_cache.clone(),
                 &store,
@@ -96,31 +280,65 @@ impl NodeRestarter {
             // another reconfiguration message
             tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
 
-            // Wait for a committee change.
+            // Wait for a (valid) committee change.
             let (
                 new_keypair,
                 new_network_keypair,
                 new_committee,
                 new_worker_ids_and_keypairs,
                 new_worker_cache,
-            ) = match rx_reconfigure.recv().await {
-                Some(x) => x,
-                None => break,
+            ) = loop {
+                let reconfiguration: NodeReconfiguration = tokio::select! {
+                    message = rx_reconfigure.recv() => match message {
+                        Some(x) => x.into(),
+                        None => return,
+                    },
+                    Some(epoch) = rx_end_of_epoch.recv() => {
+                        // Ignore notifications left over from a previous epoch.
+                        if epoch != committee.epoch() {
+                            continue;
+                        }
+                        tracing::info!("Epoch E{epoch} ended by the epoch policy");
+                        NodeReconfiguration::EndOfEpoch
+                    }
+                };
+                match reconfiguration.resolve(
+                    &primary_keypair,
+                    &primary_network_keypair,
+                    &committee,
+                    &worker_ids_and_keypairs,
+                    &worker_cache.load(),
+                ) {
+                    Ok(x) => break x,
+                    Err(errors) => {
+                        tracing::error!("Ignoring invalid reconfiguration: {errors:?}")
+                    }
+                }
             };
             tracing::info!("Starting reconfiguration with committee {committee}");
 
+            // The peers of the next epoch may restart before this node: serve them until then.
+            let primaries = new_committee
+                .authorities
+                .values()
+                .map(|authority| authority.network_key.clone());
+            let workers = new_worker_cache
+                .workers
+                .values()
+                .flat_map(|index| index.0.values())
+                .map(|worker| worker.name.clone());
+            PeerGrace::of_authority(&name).allow_next_epoch(primaries.chain(workers));
+
             // Shutdown all relevant components.
             // Send shutdown message to the primary, who will forward it to its workers
-            let client = reqwest::Client::new();
+            let client = AdminClient::new(&parameters.network_admin_server)
+                .expect("Failed to build the client of the admin server");
             client
-                .post(format!(
-                    "http://127.0.0.1:{}/reconfigure",
-                    parameters
-                        .network_admin_server
-                        .primary_network_admin_server_port,
-                ))
-                .json(&ReconfigureNotification::Shutdown)
-                .send()
+                .post(
+                    &parameters.network_admin_server.primary_address(),
+                    "/reconfigure",
+                    &ReconfigureNotification::Shutdown,
+                )
                 .await
                 .unwrap();
 
@@ -140,6 +358,8 @@ impl NodeRestarter {
             // Update the settings for the next epoch.
             primary_keypair = new_keypair;
             primary_network_keypair = new_network_keypair;
+            // The next epoch keeps serving the peers of this one, whatever its key.
+            PeerGrace::rename_authority(&name, primary_keypair.public());
             name = primary_keypair.public().clone();
             worker_ids_and_keypairs = new_worker_ids_and_keypairs;
             committee = new_committee;
@@ -0,0 +1,62 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+use config::Parameters;
+use std::time::{Duration, Instant};
+use test_utils::{
+    process_cluster::{NodeId, ProcessCluster},
+    CommitteeFixture,
+};
+
+const ROUND_METRIC: &str = "narwhal_primary_current_round";
+
+/// Waits until every primary of the cluster reaches a round above `round`.
+fn wait_for_round(cluster: &ProcessCluster, round: f64, timeout: Duration) {
+    let deadline = Instant::now() + timeout;
+    let primaries: Vec<_> = cluster
+        .nodes()
+        .into_iter()
+        .filter(|id| matches!(id, NodeId::Primary(_)))
+        .collect();
+    while Instant::now() < deadline {
+        if primaries.iter().all(|id| {
+            cluster
+                .metric(*id, ROUND_METRIC)
+                .map_or(false, |value| value > round)
+        }) {
+            return;
+        }
+        std::thread::sleep(Duration::from_millis(500));
+    }
+    panic!("The primaries did not reach round {round} within {timeout:?}");
+}
+
+#[test]
+fn test_kill_and_restart_processes() {
+    let fixture = CommitteeFixture::builder().randomize_ports(true).build();
+    let parameters = Parameters {
+        max_header_delay: Duration::from_millis(200),
+        ..Parameters::default()
+    };
+    let mut cluster = ProcessCluster::new(env!("CARGO_BIN_EXE_narwhal-node"), fixture, parameters);
+    cluster.start();
+    wait_for_round(&cluster, 2.0, Duration::from_secs(60));
+
+    // A primary crashing comes back on the same ports, and reopens its store once the killed
+    // process released it.
+    let primary = NodeId::Primary(0);
+    cluster.kill(primary);
+    assert!(!cluster.is_running(primary));
+    assert!(cluster.metrics(primary).is_err());
+    let round = cluster.metric(NodeId::Primary(1), ROUND_METRIC).unwrap();
+    cluster.start_node(primary);
+    wait_for_round(&cluster, round, Duration::from_secs(60));
+    assert!(cluster.is_running(primary));
+    assert!(!cluster.logs(primary).is_empty());
+
+    // A worker stops on SIGTERM and restarts.
+    let worker = NodeId::Worker(1, 0);
+    assert!(cluster.terminate(worker, Duration::from_secs(10)));
+    assert!(!cluster.is_running(worker));
+    cluster.restart(worker);
+    assert!(cluster.is_running(worker));
+}
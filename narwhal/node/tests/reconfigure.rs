@@ -10,10 +10,12 @@ use crypto::{KeyPair, NetworkKeyPair, PublicKey};
 use executor::ExecutionState;
 use fastcrypto::traits::KeyPair as _;
 use futures::future::{join_all, try_join_all};
+use multiaddr::Multiaddr;
 use mysten_metrics::RegistryService;
 use narwhal_node as node;
 use node::{restarter::NodeRestarter, Node};
 use prometheus::Registry;
+use std::future::Future;
 use std::num::NonZeroUsize;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::{
@@ -21,6 +23,7 @@ use std::{
     sync::{Arc, Mutex},
 };
 use storage::NodeStorage;
+use sui_macros::sim_test;
 use test_utils::CommitteeFixture;
 use tokio::{
     sync::mpsc::{channel, Receiver, Sender},
@@ -138,6 +141,32 @@ impl SimpleExecutionState {
     }
 }
 
+/// Runs the tasks of the authority at `address`, in its own node of the simulator so the faults
+/// of the simulated network apply between the authorities.
+#[cfg(msim)]
+fn spawn_authority<F>(address: &Multiaddr, authority: F)
+where
+    F: Future<Output = ()> + Send + 'static,
+{
+    let ip = mysten_network::multiaddr::to_socket_addr(address)
+        .expect("The authority has an ip address")
+        .ip();
+    sui_simulator::runtime::Handle::current()
+        .create_node()
+        .ip(ip)
+        .name(address.to_string())
+        .build()
+        .spawn(authority);
+}
+
+#[cfg(not(msim))]
+fn spawn_authority<F>(_address: &Multiaddr, authority: F)
+where
+    F: Future<Output = ()> + Send + 'static,
+{
+    tokio::spawn(authority);
+}
+
 async fn run_client(
     name: PublicKey,
     worker_cache: SharedWorkerCache,
@@ -155,6 +184,7 @@ async fn run_client(
     // Make a transaction to submit for ever.
     let mut tx = TransactionProto {
         transaction: Bytes::from(0u64.to_be_bytes().to_vec()),
+        metadata: Bytes::new(),
     };
 
     // Repeatedly send transactions.
@@ -177,20 +207,21 @@ async fn run_client(
             Some(epoch) = rx_reconfigure.recv() => {
                 tx = TransactionProto {
                     transaction: Bytes::from(epoch.to_le_bytes().to_vec()),
+                    metadata: Bytes::new(),
                 };
             }
         }
     }
 }
 
-#[ignore]
-#[tokio::test]
+// Ten epochs of real time are too slow for CI: the test runs under the simulator, where time is
+// virtual, e.g. with `cargo simtest -p narwhal-node restart`.
+#[sim_test]
 async fn restart() {
-    telemetry_subscribers::init_for_testing();
-
     let fixture = CommitteeFixture::builder()
         .number_of_workers(NonZeroUsize::new(1).unwrap())
         .randomize_ports(true)
+        .host_per_authority(true)
         .build();
     let committee = fixture.committee();
     let worker_cache = fixture.shared_worker_cache();
@@ -238,7 +269,7 @@ async fn restart() {
 
         let keypair = a.keypair().copy();
         let network_keypair = a.network_keypair().copy();
-        tokio::spawn(async move {
+        spawn_authority(a.address(), async move {
             NodeRestarter::watch(
                 keypair,
                 network_keypair,
@@ -342,10 +373,13 @@ async fn restart() {
         .expect("No error should occurred");
 }
 
-#[ignore]
-#[tokio::test]
+// The runs of a seed must be identical for the failures found under the simulator to reproduce.
+#[sim_test(check_determinism)]
 async fn epoch_change() {
-    let fixture = CommitteeFixture::builder().randomize_ports(true).build();
+    let fixture = CommitteeFixture::builder()
+        .randomize_ports(true)
+        .host_per_authority(true)
+        .build();
     let committee = fixture.committee();
     let worker_cache = fixture.shared_worker_cache();
     let parameters = fixture
@@ -382,19 +416,47 @@ async fn epoch_change() {
             tx_node_reconfigure,
         ));
 
-        // Start a task that will broadcast the committee change signal.
-        let parameters_clone = parameters.get(&name).unwrap().clone();
-        tokio::spawn(async move {
-            let client = reqwest::Client::new();
+        let keypair = a.keypair().copy();
+        let network_keypair = a.network_keypair().copy();
+        let worker_keypair = a.worker(0).keypair().copy();
+        let committee = committee.clone();
+        let worker_cache = worker_cache.clone();
+        let p = parameters.get(&name).unwrap().clone();
+        spawn_authority(a.address(), async move {
+            let _primary_handles = Node::spawn_primary(
+                keypair,
+                network_keypair,
+                Arc::new(ArcSwap::new(Arc::new(committee.clone()))),
+                worker_cache.clone(),
+                &store,
+                p.clone(),
+                /* consensus */ true,
+                execution_state,
+                &Registry::new(),
+            )
+            .await
+            .unwrap();
+
+            let _worker_handles = Node::spawn_workers(
+                name,
+                /* worker ids_and_keypairs */ vec![(0, worker_keypair)],
+                Arc::new(ArcSwap::new(Arc::new(committee))),
+                worker_cache,
+                &store,
+                p.clone(),
+                TrivialTransactionValidator::default(),
+                &Registry::new(),
+            );
 
+            // Broadcast the committee change signal, from the authority as its admin server only
+            // listens on the loopback address.
+            let client = reqwest::Client::new();
             while let Some((_, _, committee, _, _)) = rx_node_reconfigure.recv().await {
                 let message = ReconfigureNotification::NewEpoch(committee.clone());
                 client
                     .post(format!(
                         "http://127.0.0.1:{}/reconfigure",
-                        parameters_clone
-                            .network_admin_server
-                            .primary_network_admin_server_port
+                        p.network_admin_server.primary_network_admin_server_port
                     ))
                     .json(&message)
                     .send()
@@ -403,32 +465,6 @@ async fn epoch_change() {
             }
         });
 
-        let p = parameters.get(&name).unwrap().clone();
-        let _primary_handles = Node::spawn_primary(
-            a.keypair().copy(),
-            a.network_keypair().copy(),
-            Arc::new(ArcSwap::new(Arc::new(committee.clone()))),
-            worker_cache.clone(),
-            &store,
-            p.clone(),
-            /* consensus */ true,
-            execution_state,
-            &Registry::new(),
-        )
-        .await
-        .unwrap();
-
-        let _worker_handles = Node::spawn_workers(
-            name,
-            /* worker ids_and_keypairs */ vec![(0, a.worker(0).keypair().copy())],
-            Arc::new(ArcSwap::new(Arc::new(committee.clone()))),
-            worker_cache.clone(),
-            &store,
-            p,
-            TrivialTransactionValidator::default(),
-            &Registry::new(),
-        );
-
         rx_nodes.push(rx_output);
     }
 
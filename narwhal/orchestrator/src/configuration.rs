@@ -0,0 +1,344 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+use crate::topology::Topology;
+use config::{
+    Authority, Committee, Export, Parameters, WorkerCache, WorkerId, WorkerIndex, WorkerInfo,
+};
+use crypto::{KeyPair, NetworkKeyPair};
+use eyre::Context;
+use fastcrypto::{generate_production_keypair, traits::KeyPair as _};
+use multiaddr::Multiaddr;
+use std::{
+    collections::BTreeMap,
+    fs,
+    net::{IpAddr, SocketAddr},
+    path::{Path, PathBuf},
+};
+
+pub const COMMITTEE_FILE: &str = "committee.json";
+pub const WORKERS_FILE: &str = "workers.json";
+
+/// A `narwhal-node` or `narwhal-benchmark-client` process of the benchmark.
+#[derive(Clone, Debug)]
+pub struct Process {
+    /// The name of the process, also the name of its files.
+    pub name: String,
+    /// The name of the host running the process.
+    pub host: String,
+    /// The arguments of the process. The files they refer to are in the working directory of
+    /// the process.
+    pub args: Vec<String>,
+    /// The generated files the process needs in its working directory.
+    pub files: Vec<PathBuf>,
+    /// Where to scrape the metrics of the nodes.
+    pub metrics: Option<SocketAddr>,
+}
+
+/// The configuration of every node and benchmark client of a topology, generated in a
+/// directory.
+pub struct Deployment {
+    pub committee: Committee,
+    pub worker_cache: WorkerCache,
+    pub primaries: Vec<Process>,
+    pub workers: Vec<Process>,
+    pub clients: Vec<Process>,
+}
+
+/// Allocates the ports of every host, from the base port of the topology up.
+struct Ports {
+    base_port: u16,
+    next: BTreeMap<String, u16>,
+}
+
+impl Ports {
+    fn next(&mut self, host: &str) -> u16 {
+        let port = self.next.entry(host.to_string()).or_insert(self.base_port);
+        *port += 1;
+        *port - 1
+    }
+}
+
+impl Deployment {
+    /// Generates fresh keys, the committee, the worker cache and the parameters of every node of
+    /// `topology` in `dir`, and the command lines of the nodes and of the benchmark clients.
+    pub fn generate(topology: &Topology, dir: &Path) -> Result<Self, eyre::Report> {
+        fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+        let path = |name: &str| dir.join(name);
+        let export = |object: &dyn Fn(&str) -> Result<(), config::ConfigError>, name: &str| {
+            let file = path(name);
+            // The configuration files are not truncated when exported.
+            let _ = fs::remove_file(&file);
+            object(&file.to_string_lossy())
+                .with_context(|| format!("Failed to write {}", file.display()))
+                .map(|_| file)
+        };
+
+        let mut ports = Ports {
+            base_port: topology.base_port,
+            next: BTreeMap::new(),
+        };
+        let address = |host: &str| topology.host(host).address;
+
+        let mut authorities = BTreeMap::new();
+        let mut workers = BTreeMap::new();
+        let mut primaries = Vec::new();
+        let mut worker_processes = Vec::new();
+        let mut clients = Vec::new();
+        let mut transactions_urls = Vec::new();
+
+        for (i, authority) in topology.authorities.iter().enumerate() {
+            let keypair = generate_production_keypair::<KeyPair>();
+            let network_keypair = generate_production_keypair::<NetworkKeyPair>();
+            let primary_keys = export(&|f| keypair.export(f), &format!("primary-{i}.key"))?;
+            let primary_network_keys = export(
+                &|f| network_keypair.export(f),
+                &format!("primary-network-{i}.key"),
+            )?;
+
+            let host = &authority.primary;
+            let primary_address = multiaddr(address(host), "udp", ports.next(host), false);
+            authorities.insert(
+                keypair.public().clone(),
+                Authority {
+                    stake: authority.stake,
+                    primary_address,
+                    network_key: network_keypair.public().clone(),
+                },
+            );
+
+            let parameters = node_parameters(topology, host, &mut ports);
+            let metrics = parameters.prometheus_metrics.socket_addr.clone();
+            let name = format!("primary-{i}");
+            let parameters_file = export(
+                &|f| parameters.export(f),
+                &format!("{name}.parameters.json"),
+            )?;
+            primaries.push(Process {
+                args: node_args(
+                    &name,
+                    &primary_keys,
+                    &primary_network_keys,
+                    &parameters_file,
+                )
+                .into_iter()
+                .chain(["primary".to_string()])
+                .collect(),
+                files: vec![
+                    primary_keys.clone(),
+                    primary_network_keys.clone(),
+                    parameters_file,
+                ],
+                metrics: Some(scrape_address(address(host), &metrics)),
+                name,
+                host: host.clone(),
+            });
+
+            let mut index = BTreeMap::new();
+            for (id, host) in authority.workers.iter().enumerate() {
+                let id = id as WorkerId;
+                let worker_keypair = generate_production_keypair::<NetworkKeyPair>();
+                let name = format!("worker-{i}-{id}");
+                let worker_keys = export(&|f| worker_keypair.export(f), &format!("{name}.key"))?;
+                let transactions = multiaddr(address(host), "tcp", ports.next(host), true);
+                transactions_urls.push(url(&transactions));
+                index.insert(
+                    id,
+                    WorkerInfo {
+                        name: worker_keypair.public().clone(),
+                        transactions: transactions.clone(),
+                        worker_address: multiaddr(address(host), "udp", ports.next(host), false),
+                    },
+                );
+
+                let mut parameters = node_parameters(topology, host, &mut ports);
+                // A worker listens to admin messages on the base port plus its id.
+                parameters
+                    .network_admin_server
+                    .worker_network_admin_server_base_port = ports.next(host) - id as u16;
+                let metrics = parameters.prometheus_metrics.socket_addr.clone();
+                let parameters_file = export(
+                    &|f| parameters.export(f),
+                    &format!("{name}.parameters.json"),
+                )?;
+                worker_processes.push(Process {
+                    args: node_args(
+                        &name,
+                        &primary_keys,
+                        &primary_network_keys,
+                        &parameters_file,
+                    )
+                    .into_iter()
+                    .chain([
+                        format!("--worker-keys={}", file_name(&worker_keys)),
+                        "worker".to_string(),
+                        format!("--id={id}"),
+                    ])
+                    .collect(),
+                    files: vec![
+                        primary_keys.clone(),
+                        primary_network_keys.clone(),
+                        worker_keys,
+                        parameters_file,
+                    ],
+                    metrics: Some(scrape_address(address(host), &metrics)),
+                    name: name.clone(),
+                    host: host.clone(),
+                });
+
+                clients.push(Process {
+                    name: format!("client-{i}-{id}"),
+                    host: host.clone(),
+                    args: vec![
+                        url(&transactions),
+                        format!("--size={}", topology.load.size),
+                        format!("--rate={}", topology.load.rate / topology.workers() as u64),
+                    ],
+                    files: Vec::new(),
+                    metrics: None,
+                });
+            }
+            workers.insert(keypair.public().clone(), WorkerIndex(index));
+        }
+
+        // Every client waits for all the workers to be up before sending its load.
+        for client in &mut clients {
+            client.args.push("--nodes".to_string());
+            client.args.extend(transactions_urls.iter().cloned());
+        }
+
+        let committee = Committee {
+            authorities,
+            epoch: 0,
+            quorum_policy: Default::default(),
+            protocol_config: Default::default(),
+        };
+        let worker_cache = WorkerCache { workers, epoch: 0 };
+        let committee_file = export(&|f| committee.export(f), COMMITTEE_FILE)?;
+        let workers_file = export(&|f| worker_cache.export(f), WORKERS_FILE)?;
+        for process in primaries.iter_mut().chain(&mut worker_processes) {
+            process
+                .files
+                .extend([committee_file.clone(), workers_file.clone()]);
+        }
+
+        Ok(Self {
+            committee,
+            worker_cache,
+            primaries,
+            workers: worker_processes,
+            clients,
+        })
+    }
+
+    /// Every node of the deployment.
+    pub fn nodes(&self) -> impl Iterator<Item = &Process> {
+        self.primaries.iter().chain(&self.workers)
+    }
+}
+
+/// The parameters of a node of `host`, its servers listening on ports of their own.
+fn node_parameters(topology: &Topology, host: &str, ports: &mut Ports) -> Parameters {
+    let mut parameters = topology.parameters.clone();
+    // The metrics are scraped from the orchestrator, the other servers are local.
+    let any = match topology.host(host).address {
+        IpAddr::V4(_) => IpAddr::from([0, 0, 0, 0]),
+        IpAddr::V6(_) => IpAddr::from([0u16; 8]),
+    };
+    let local = match topology.host(host).address {
+        IpAddr::V4(_) => IpAddr::from([127, 0, 0, 1]),
+        IpAddr::V6(_) => IpAddr::from([0, 0, 0, 0, 0, 0, 0, 1]),
+    };
+    parameters.prometheus_metrics.socket_addr = multiaddr(any, "tcp", ports.next(host), true);
+    parameters.consensus_api_grpc.socket_addr = multiaddr(local, "tcp", ports.next(host), true);
+    parameters
+        .network_admin_server
+        .primary_network_admin_server_port = ports.next(host);
+    parameters
+}
+
+/// The common arguments of the nodes of an authority.
+fn node_args(
+    name: &str,
+    primary_keys: &Path,
+    primary_network_keys: &Path,
+    parameters: &Path,
+) -> Vec<String> {
+    vec![
+        "-vv".to_string(),
+        "run".to_string(),
+        format!("--primary-keys={}", file_name(primary_keys)),
+        format!("--primary-network-keys={}", file_name(primary_network_keys)),
+        format!("--committee={COMMITTEE_FILE}"),
+        format!("--workers={WORKERS_FILE}"),
+        format!("--parameters={}", file_name(parameters)),
+        format!("--store=db-{name}"),
+    ]
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name().unwrap().to_string_lossy().into_owned()
+}
+
+fn multiaddr(address: IpAddr, protocol: &str, port: u16, http: bool) -> Multiaddr {
+    let ip = match address {
+        IpAddr::V4(_) => "ip4",
+        IpAddr::V6(_) => "ip6",
+    };
+    let suffix = if http { "/http" } else { "" };
+    format!("/{ip}/{address}/{protocol}/{port}{suffix}")
+        .parse()
+        .unwrap()
+}
+
+/// The address where the orchestrator scrapes a server listening on `address` of `host`.
+fn scrape_address(host: IpAddr, address: &Multiaddr) -> SocketAddr {
+    let port = network::multiaddr_to_address(address)
+        .expect("The generated addresses are valid")
+        .port();
+    SocketAddr::new(host, port)
+}
+
+fn url(address: &Multiaddr) -> String {
+    let address =
+        network::multiaddr_to_address(address).expect("The generated addresses are valid");
+    format!("http://{address}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::topology::tests::local_topology;
+    use std::collections::BTreeSet;
+
+    #[test]
+    fn generate_local_deployment() {
+        let topology = local_topology(4, 2);
+        let dir = tempfile::tempdir().unwrap();
+        let deployment = Deployment::generate(&topology, dir.path()).unwrap();
+
+        deployment.committee.validate().unwrap();
+        deployment
+            .worker_cache
+            .validate(&deployment.committee)
+            .unwrap();
+        assert_eq!(deployment.primaries.len(), 4);
+        assert_eq!(deployment.workers.len(), 8);
+        assert_eq!(deployment.clients.len(), 8);
+
+        // Every node serves its metrics on a port of its own.
+        let metrics: BTreeSet<_> = deployment.nodes().map(|node| node.metrics).collect();
+        assert_eq!(metrics.len(), 12);
+
+        for process in deployment.nodes() {
+            for file in &process.files {
+                assert!(file.exists(), "{} is missing", file.display());
+            }
+        }
+        let client = &deployment.clients[0];
+        assert!(client.args.contains(&"--rate=125".to_string()));
+        assert_eq!(
+            client.args.iter().filter(|a| a.starts_with("http")).count(),
+            9
+        );
+    }
+}
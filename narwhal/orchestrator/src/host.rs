@@ -0,0 +1,261 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+use crate::topology::{is_subdirectory, Host, Ssh};
+use eyre::{eyre, Context};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::{Command, Output},
+};
+
+/// Runs commands and copies files on a host of the topology, either the local machine or a
+/// remote one over SSH (with the `ssh` and `scp` commands).
+pub struct Machine {
+    pub name: String,
+    host: Host,
+    /// The directory of the benchmark files of a local host.
+    local_dir: PathBuf,
+}
+
+impl Machine {
+    /// The machine of the host `name`; its files are in `local_dir` if it is the local machine.
+    pub fn new(name: &str, host: &Host, local_dir: PathBuf) -> Self {
+        Self {
+            name: name.to_string(),
+            host: host.clone(),
+            local_dir,
+        }
+    }
+
+    /// The directory of the benchmark files on the machine.
+    pub fn directory(&self) -> String {
+        match &self.host.ssh {
+            Some(ssh) => ssh.directory.clone(),
+            None => self.local_dir.to_string_lossy().into_owned(),
+        }
+    }
+
+    /// Creates the directory of the benchmark files, removing the files of a previous run.
+    pub fn prepare(&self) -> Result<(), eyre::Report> {
+        // The remote directories are relative to the home directory, which mustn't be removed.
+        if let Some(ssh) = &self.host.ssh {
+            if !is_subdirectory(&ssh.directory) {
+                return Err(eyre!(
+                    "Refusing to remove {:?} on {}, which is not within the home directory",
+                    ssh.directory,
+                    self.name
+                ));
+            }
+        }
+        let directory = quote(&self.directory());
+        self.shell(&format!("rm -rf {directory} && mkdir -p {directory}"))
+            .map(|_| ())
+    }
+
+    /// Runs the shell `command` in the directory of the benchmark files, returning its output.
+    pub fn run(&self, command: &str) -> Result<String, eyre::Report> {
+        self.shell(&format!("cd {} && {command}", quote(&self.directory())))
+    }
+
+    /// Copies the local `files` to the directory of the benchmark files.
+    pub fn upload(&self, files: &[PathBuf]) -> Result<(), eyre::Report> {
+        if files.is_empty() {
+            return Ok(());
+        }
+        match &self.host.ssh {
+            Some(ssh) => {
+                let destination = format!("{}:{}/", self.destination(ssh), ssh.directory);
+                let mut scp = self.scp(ssh);
+                scp.args(files).arg(destination);
+                check(scp.output(), "scp").map(|_| ())
+            }
+            None => {
+                for file in files {
+                    let name = file.file_name().ok_or_else(|| eyre!("Not a file"))?;
+                    fs::copy(file, self.local_dir.join(name)).with_context(|| {
+                        format!("Failed to copy {} to {}", file.display(), self.name)
+                    })?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Installs the `binaries`, linked on the local machine rather than copied.
+    pub fn install(&self, binaries: &[PathBuf]) -> Result<(), eyre::Report> {
+        match &self.host.ssh {
+            Some(_) => self.upload(binaries),
+            None => {
+                for binary in binaries {
+                    let name = binary.file_name().ok_or_else(|| eyre!("Not a file"))?;
+                    let binary = binary
+                        .canonicalize()
+                        .with_context(|| format!("The binary {} is missing", binary.display()))?;
+                    std::os::unix::fs::symlink(binary, self.local_dir.join(name))?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Copies the file `name` of the directory of the benchmark files to the local `destination`.
+    pub fn download(&self, name: &str, destination: &Path) -> Result<(), eyre::Report> {
+        match &self.host.ssh {
+            Some(ssh) => {
+                let source = format!("{}:{}/{name}", self.destination(ssh), ssh.directory);
+                let mut scp = self.scp(ssh);
+                scp.arg(source).arg(destination);
+                check(scp.output(), "scp").map(|_| ())
+            }
+            None => fs::copy(self.local_dir.join(name), destination)
+                .map(|_| ())
+                .with_context(|| format!("Failed to copy {name} from {}", self.name)),
+        }
+    }
+
+    /// Starts `program` with `args` in the background, logging to `<name>.log`. Returns the pid
+    /// of the process.
+    pub fn spawn(&self, name: &str, program: &str, args: &[String]) -> Result<u32, eyre::Report> {
+        let args: Vec<_> = args.iter().map(|arg| quote(arg)).collect();
+        let output = self.run(&format!(
+            "nohup ./{program} {} > {} 2>&1 < /dev/null & echo $!",
+            args.join(" "),
+            quote(&format!("{name}.log"))
+        ))?;
+        output
+            .trim()
+            .parse()
+            .with_context(|| format!("Failed to start {name} on {}: {output}", self.name))
+    }
+
+    /// Kills the processes `pids`, ignoring the ones that already exited.
+    pub fn kill(&self, pids: &[u32]) -> Result<(), eyre::Report> {
+        if pids.is_empty() {
+            return Ok(());
+        }
+        let pids: Vec<_> = pids.iter().map(u32::to_string).collect();
+        self.shell(&format!("kill {} 2> /dev/null || true", pids.join(" ")))
+            .map(|_| ())
+    }
+
+    /// Kills every process started from one of the `programs` installed in the directory of the
+    /// benchmark files.
+    pub fn kill_all(&self, programs: &[&str]) -> Result<(), eyre::Report> {
+        for program in programs {
+            // The bracket keeps the pattern from matching the shell running `pkill`.
+            self.shell(&format!(
+                "pkill -f {} || true",
+                quote(&format!("[.]/{program}"))
+            ))?;
+        }
+        Ok(())
+    }
+
+    fn shell(&self, command: &str) -> Result<String, eyre::Report> {
+        let output = match &self.host.ssh {
+            Some(ssh) => {
+                let mut command_line = Command::new("ssh");
+                command_line
+                    .args(["-p", &ssh.port.to_string()])
+                    .args(Self::ssh_options(ssh))
+                    .arg(self.destination(ssh))
+                    .arg(command);
+                command_line.output()
+            }
+            None => Command::new("sh").args(["-c", command]).output(),
+        };
+        check(output, &self.name)
+    }
+
+    fn scp(&self, ssh: &Ssh) -> Command {
+        let mut scp = Command::new("scp");
+        scp.args(["-q", "-P", &ssh.port.to_string()])
+            .args(Self::ssh_options(ssh));
+        scp
+    }
+
+    fn ssh_options(ssh: &Ssh) -> Vec<String> {
+        // The machines must be known hosts, their keys can't be accepted without a prompt.
+        let mut options = vec![
+            "-o".to_string(),
+            "BatchMode=yes".to_string(),
+            "-o".to_string(),
+            "StrictHostKeyChecking=yes".to_string(),
+        ];
+        if let Some(known_hosts) = &ssh.known_hosts {
+            options.extend([
+                "-o".to_string(),
+                format!("UserKnownHostsFile={}", known_hosts.display()),
+            ]);
+        }
+        if let Some(key) = &ssh.key {
+            options.extend(["-i".to_string(), key.to_string_lossy().into_owned()]);
+        }
+        options
+    }
+
+    fn destination(&self, ssh: &Ssh) -> String {
+        format!("{}@{}", ssh.user, self.host.address)
+    }
+}
+
+/// The standard output of a successful command.
+fn check(output: std::io::Result<Output>, context: &str) -> Result<String, eyre::Report> {
+    let output = output.with_context(|| format!("Failed to run a command ({context})"))?;
+    if !output.status.success() {
+        return Err(eyre!(
+            "Command failed ({context}): {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Quotes `arg` for the shell.
+fn quote(arg: &str) -> String {
+    format!("'{}'", arg.replace('\'', r"'\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{os::unix::fs::PermissionsExt, time::Duration};
+
+    #[test]
+    fn run_locally() {
+        let dir = tempfile::tempdir().unwrap();
+        let host = Host {
+            address: "127.0.0.1".parse().unwrap(),
+            ssh: None,
+        };
+        let machine = Machine::new("local", &host, dir.path().join("local"));
+        machine.prepare().unwrap();
+        assert!(machine.run("exit 1").is_err());
+
+        let file = dir.path().join("it's.txt");
+        fs::write(&file, "hello").unwrap();
+        machine.upload(&[file]).unwrap();
+        assert_eq!(machine.run("cat \"it's.txt\"").unwrap(), "hello");
+
+        let program = dir.path().join("program");
+        fs::write(&program, "#!/bin/sh\necho \"$1\"\nsleep 60\n").unwrap();
+        fs::set_permissions(&program, fs::Permissions::from_mode(0o755)).unwrap();
+        machine.install(&[program]).unwrap();
+        let pid = machine
+            .spawn("program", "program", &["started".to_string()])
+            .unwrap();
+        let log = dir.path().join("program.log");
+        for _ in 0..50 {
+            machine.download("program.log", &log).unwrap();
+            if fs::read_to_string(&log).unwrap() == "started\n" {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(100));
+        }
+        assert_eq!(fs::read_to_string(&log).unwrap(), "started\n");
+
+        machine.kill(&[pid]).unwrap();
+        // Killing exited processes is not an error.
+        machine.kill(&[pid]).unwrap();
+    }
+}
@@ -0,0 +1,26 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+#![warn(
+    future_incompatible,
+    nonstandard_style,
+    rust_2018_idioms,
+    rust_2021_compatibility
+)]
+
+//! Benchmarks of Narwhal committees described by a [`Topology`]: generates the configuration of
+//! the nodes, runs them with their benchmark clients on the local machine or over SSH, scrapes
+//! their metrics and summarizes them in a [`Report`].
+//!
+//! ```text
+//! cargo build --release --package narwhal-node --features benchmark
+//! cargo run --release --package narwhal-orchestrator -- run --topology narwhal/orchestrator/topologies/local.json
+//! ```
+
+pub mod configuration;
+pub mod host;
+pub mod metrics;
+pub mod orchestrator;
+pub mod report;
+pub mod topology;
+
+pub use crate::{orchestrator::Orchestrator, report::Report, topology::Topology};
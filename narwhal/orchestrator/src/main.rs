@@ -0,0 +1,90 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+#![warn(
+    future_incompatible,
+    nonstandard_style,
+    rust_2018_idioms,
+    rust_2021_compatibility
+)]
+
+use clap::{crate_version, App, AppSettings, ArgMatches, SubCommand};
+use config::Import;
+use eyre::{eyre, Context};
+use narwhal_orchestrator::{orchestrator, Orchestrator, Topology};
+use std::path::PathBuf;
+use tracing::subscriber::set_global_default;
+use tracing_subscriber::filter::EnvFilter;
+
+fn main() -> Result<(), eyre::Report> {
+    let matches = App::new("narwhal-orchestrator")
+        .version(crate_version!())
+        .about("Benchmarks a Narwhal committee described by a topology file.")
+        .long_about("Generates the configuration of the nodes of the --topology, starts them with their \
+        benchmark clients on the local machine or over SSH, measures them for the duration of the load, \
+        and writes the report, the configuration and the logs to --output.")
+        .subcommand(
+            SubCommand::with_name("generate")
+                .about("Generate the configuration of the nodes of a topology")
+                .args_from_usage("--topology=<FILE> 'The file describing the benchmark'")
+                .args_from_usage("--output=<DIR> 'The directory where to write the configuration'"),
+        )
+        .subcommand(
+            SubCommand::with_name("run")
+                .about("Run a benchmark")
+                .args_from_usage("--topology=<FILE> 'The file describing the benchmark'")
+                .args_from_usage("--output=[DIR] 'The directory of the configuration, the logs and the report (default benchmark)'")
+                .args_from_usage("--binaries=[DIR] 'The directory of the narwhal-node and narwhal-benchmark-client binaries (default target/release)'"),
+        )
+        .subcommand(
+            SubCommand::with_name("kill")
+                .about("Stop the nodes and the benchmark clients left on the hosts of a topology")
+                .args_from_usage("--topology=<FILE> 'The file describing the benchmark'")
+                .args_from_usage("--output=[DIR] 'The directory of the benchmark (default benchmark)'"),
+        )
+        .setting(AppSettings::SubcommandRequiredElseHelp)
+        .get_matches();
+
+    let env_filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+    let subscriber = tracing_subscriber::fmt::Subscriber::builder()
+        .with_env_filter(env_filter)
+        .with_writer(std::io::stderr)
+        .finish();
+    set_global_default(subscriber).expect("Failed to set subscriber");
+
+    match matches.subcommand() {
+        ("generate", Some(sub_matches)) => {
+            let topology = topology(sub_matches)?;
+            let output = PathBuf::from(sub_matches.value_of("output").unwrap());
+            orchestrator::generate(&topology, &output)?;
+            println!("Generated the configuration in {}", output.display());
+        }
+        ("run", Some(sub_matches)) => {
+            let mut orchestrator = Orchestrator::new(
+                topology(sub_matches)?,
+                output(sub_matches),
+                PathBuf::from(sub_matches.value_of("binaries").unwrap_or("target/release")),
+            );
+            let report = orchestrator.run()?;
+            println!("{report}");
+        }
+        ("kill", Some(sub_matches)) => {
+            Orchestrator::new(topology(sub_matches)?, output(sub_matches), PathBuf::new())
+                .kill_all()?;
+        }
+        _ => unreachable!(),
+    }
+    Ok(())
+}
+
+fn topology(matches: &ArgMatches<'_>) -> Result<Topology, eyre::Report> {
+    let file = matches.value_of("topology").unwrap();
+    let topology = Topology::import(file).with_context(|| format!("Failed to load {file}"))?;
+    topology
+        .validate()
+        .map_err(|e| eyre!("Invalid topology {file}: {e}"))?;
+    Ok(topology)
+}
+
+fn output(matches: &ArgMatches<'_>) -> PathBuf {
+    PathBuf::from(matches.value_of("output").unwrap_or("benchmark"))
+}
@@ -0,0 +1,76 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+use eyre::{eyre, Context};
+use node::metrics::METRICS_ROUTE;
+use std::{
+    collections::BTreeMap,
+    io::{Read, Write},
+    net::{SocketAddr, TcpStream},
+    time::Duration,
+};
+
+/// The metrics of a node, by name and summed over their labels. The histograms appear as their
+/// `_sum`, `_count` and `_bucket` series.
+pub type Metrics = BTreeMap<String, f64>;
+
+const SCRAPE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Fetches the metrics served by a node at `address`.
+pub fn scrape(address: SocketAddr) -> Result<Metrics, eyre::Report> {
+    let mut stream = TcpStream::connect_timeout(&address, SCRAPE_TIMEOUT)
+        .with_context(|| format!("Failed to connect to {address}"))?;
+    stream.set_read_timeout(Some(SCRAPE_TIMEOUT))?;
+    write!(
+        stream,
+        "GET {METRICS_ROUTE} HTTP/1.0\r\nHost: {address}\r\n\r\n"
+    )?;
+    let mut response = String::new();
+    stream
+        .read_to_string(&mut response)
+        .with_context(|| format!("Failed to read the metrics of {address}"))?;
+    let (_, body) = response
+        .split_once("\r\n\r\n")
+        .ok_or_else(|| eyre!("Malformed response from {address}"))?;
+    Ok(parse(body))
+}
+
+/// Parses metrics in the Prometheus text format.
+pub fn parse(text: &str) -> Metrics {
+    let mut metrics = Metrics::new();
+    for line in text.lines().filter(|line| !line.starts_with('#')) {
+        let Some((series, value)) = line.rsplit_once(' ') else {
+            continue;
+        };
+        let Ok(value) = value.parse::<f64>() else {
+            continue;
+        };
+        let name = series.split('{').next().unwrap_or(series);
+        *metrics.entry(name.to_string()).or_default() += value;
+    }
+    metrics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_metrics() {
+        let text = "\
+# HELP narwhal_primary_current_round Current round the Primary is in
+# TYPE narwhal_primary_current_round gauge
+narwhal_primary_current_round{epoch=\"0\"} 42
+narwhal_worker_created_batch_size_sum{epoch=\"0\",reason=\"timeout\"} 1000
+narwhal_worker_created_batch_size_sum{epoch=\"0\",reason=\"size_reached\"} 500000
+narwhal_worker_created_batch_size_count{epoch=\"0\",reason=\"timeout\"} 2
+uptime 12.5
+malformed
+";
+        let metrics = parse(text);
+        assert_eq!(metrics["narwhal_primary_current_round"], 42.0);
+        assert_eq!(metrics["narwhal_worker_created_batch_size_sum"], 501000.0);
+        assert_eq!(metrics["narwhal_worker_created_batch_size_count"], 2.0);
+        assert_eq!(metrics["uptime"], 12.5);
+        assert_eq!(metrics.len(), 4);
+    }
+}
@@ -0,0 +1,166 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+use crate::{
+    configuration::{Deployment, Process},
+    host::Machine,
+    metrics::{self, Metrics},
+    report::Report,
+    topology::Topology,
+};
+use eyre::{eyre, Context};
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+    thread::sleep,
+};
+use tracing::{info, warn};
+
+pub const NODE_BINARY: &str = "narwhal-node";
+pub const CLIENT_BINARY: &str = "narwhal-benchmark-client";
+
+/// Runs the benchmark of a topology: deploys its nodes, submits the load, and measures them.
+pub struct Orchestrator {
+    topology: Topology,
+    /// The directory of the generated files, the logs and the report.
+    output: PathBuf,
+    /// The directory of the `narwhal-node` and `narwhal-benchmark-client` binaries.
+    binaries: PathBuf,
+    machines: BTreeMap<String, Machine>,
+    /// The pids of the processes started on every host.
+    pids: BTreeMap<String, Vec<u32>>,
+}
+
+impl Orchestrator {
+    pub fn new(topology: Topology, output: PathBuf, binaries: PathBuf) -> Self {
+        let machines = topology
+            .hosts
+            .iter()
+            .map(|(name, host)| {
+                let local_dir = output.join("hosts").join(name);
+                (name.clone(), Machine::new(name, host, local_dir))
+            })
+            .collect();
+        Self {
+            topology,
+            output,
+            binaries,
+            machines,
+            pids: BTreeMap::new(),
+        }
+    }
+
+    /// Runs the benchmark, writes its report to `report.json` and returns it. The processes are
+    /// stopped and their logs downloaded to `logs` whatever the outcome.
+    pub fn run(&mut self) -> Result<Report, eyre::Report> {
+        self.topology.validate().map_err(|e| eyre!(e))?;
+        let deployment = Deployment::generate(&self.topology, &self.output.join("config"))?;
+
+        let result = self.measure(&deployment);
+        self.kill();
+        self.download_logs(&deployment);
+        let report = result?;
+
+        let file = self.output.join("report.json");
+        fs::write(&file, serde_json::to_string_pretty(&report)?)
+            .with_context(|| format!("Failed to write {}", file.display()))?;
+        Ok(report)
+    }
+
+    /// Stops the processes started by the benchmark.
+    pub fn kill(&mut self) {
+        for (host, pids) in std::mem::take(&mut self.pids) {
+            if let Err(e) = self.machines[&host].kill(&pids) {
+                warn!("Failed to stop the processes of {host}: {e}");
+            }
+        }
+    }
+
+    /// Stops the nodes and the benchmark clients left on the hosts, by a failed run for instance.
+    pub fn kill_all(&self) -> Result<(), eyre::Report> {
+        for machine in self.machines.values() {
+            machine.kill_all(&[NODE_BINARY, CLIENT_BINARY])?;
+        }
+        Ok(())
+    }
+
+    fn measure(&mut self, deployment: &Deployment) -> Result<Report, eyre::Report> {
+        let binaries = [
+            self.binaries.join(NODE_BINARY),
+            self.binaries.join(CLIENT_BINARY),
+        ];
+        for machine in self.machines.values() {
+            info!("Preparing {}", machine.name);
+            machine.prepare()?;
+            machine.install(&binaries)?;
+        }
+        for process in deployment.nodes() {
+            self.machines[&process.host].upload(&process.files)?;
+        }
+
+        info!("Starting {} nodes", deployment.nodes().count());
+        for process in deployment.nodes() {
+            self.spawn(process, NODE_BINARY)?;
+        }
+        sleep(self.topology.load.warmup());
+
+        info!("Starting {} benchmark clients", deployment.clients.len());
+        for process in &deployment.clients {
+            self.spawn(process, CLIENT_BINARY)?;
+        }
+        sleep(self.topology.load.warmup());
+
+        let start = scrape_all(deployment)?;
+        info!("Measuring for {} s", self.topology.load.duration);
+        sleep(self.topology.load.duration());
+        let end = scrape_all(deployment)?;
+
+        let mut measures = start.into_iter().zip(end);
+        let primaries: Vec<_> = measures.by_ref().take(deployment.primaries.len()).collect();
+        let workers: Vec<_> = measures.collect();
+        Ok(Report::new(
+            &self.topology,
+            self.topology.load.duration(),
+            &primaries,
+            &workers,
+        ))
+    }
+
+    fn spawn(&mut self, process: &Process, program: &str) -> Result<(), eyre::Report> {
+        let pid = self.machines[&process.host].spawn(&process.name, program, &process.args)?;
+        self.pids.entry(process.host.clone()).or_default().push(pid);
+        Ok(())
+    }
+
+    fn download_logs(&self, deployment: &Deployment) {
+        let logs = self.output.join("logs");
+        if let Err(e) = fs::create_dir_all(&logs) {
+            warn!("Failed to create {}: {e}", logs.display());
+            return;
+        }
+        for process in deployment.nodes().chain(&deployment.clients) {
+            let name = format!("{}.log", process.name);
+            if let Err(e) = self.machines[&process.host].download(&name, &logs.join(&name)) {
+                warn!("Failed to download {name}: {e}");
+            }
+        }
+    }
+}
+
+/// The metrics of the primaries, then of the workers, of the deployment.
+fn scrape_all(deployment: &Deployment) -> Result<Vec<Metrics>, eyre::Report> {
+    deployment
+        .nodes()
+        .map(|process| {
+            let address = process.metrics.expect("The nodes serve metrics");
+            metrics::scrape(address).with_context(|| format!("Failed to scrape {}", process.name))
+        })
+        .collect()
+}
+
+/// Generates the configuration of the nodes of `topology` in `output`, for deploying them by
+/// other means.
+pub fn generate(topology: &Topology, output: &Path) -> Result<Deployment, eyre::Report> {
+    topology.validate().map_err(|e| eyre!(e))?;
+    Deployment::generate(topology, output)
+}
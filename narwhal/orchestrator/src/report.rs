@@ -0,0 +1,149 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+use crate::{metrics::Metrics, topology::Topology};
+use serde::Serialize;
+use std::{fmt, time::Duration};
+
+const BATCH_SIZE_SUM: &str = "narwhal_worker_created_batch_size_sum";
+const COMMIT_LATENCY_SUM: &str = "narwhal_primary_certificate_commit_latency_sum";
+const COMMIT_LATENCY_COUNT: &str = "narwhal_primary_certificate_commit_latency_count";
+const LAST_COMMITTED_ROUND: &str = "narwhal_primary_last_committed_round";
+
+/// The summary of a benchmark, from the metrics of the nodes at the start and at the end of the
+/// measurement.
+#[derive(Clone, Debug, Serialize)]
+pub struct Report {
+    pub authorities: usize,
+    pub workers: usize,
+    /// The submitted transactions per second.
+    pub rate: u64,
+    /// The size of the transactions in bytes.
+    pub size: usize,
+    pub duration_secs: f64,
+    /// The bytes per second sealed in batches by the workers.
+    pub throughput_bps: f64,
+    /// The transactions per second sealed in batches by the workers.
+    pub throughput_tps: f64,
+    /// The average time from the creation of a certificate to its commit, over the primaries.
+    pub commit_latency_ms: f64,
+    /// The highest round committed by a primary.
+    pub last_committed_round: u64,
+}
+
+impl Report {
+    /// Summarizes the measurement of `topology` over `duration`, from the metrics of the
+    /// primaries and of the workers at its start and at its end.
+    pub fn new(
+        topology: &Topology,
+        duration: Duration,
+        primaries: &[(Metrics, Metrics)],
+        workers: &[(Metrics, Metrics)],
+    ) -> Self {
+        let seconds = duration.as_secs_f64().max(f64::EPSILON);
+        let throughput_bps = delta(workers, BATCH_SIZE_SUM) / seconds;
+        let commits = delta(primaries, COMMIT_LATENCY_COUNT);
+        let commit_latency_ms = if commits > 0.0 {
+            delta(primaries, COMMIT_LATENCY_SUM) / commits * 1000.0
+        } else {
+            0.0
+        };
+        let last_committed_round = primaries
+            .iter()
+            .filter_map(|(_, end)| end.get(LAST_COMMITTED_ROUND))
+            .fold(0.0, |max: f64, round| max.max(*round)) as u64;
+        Self {
+            authorities: topology.authorities.len(),
+            workers: topology.workers(),
+            rate: topology.load.rate,
+            size: topology.load.size,
+            duration_secs: duration.as_secs_f64(),
+            throughput_bps,
+            throughput_tps: throughput_bps / topology.load.size as f64,
+            commit_latency_ms,
+            last_committed_round,
+        }
+    }
+}
+
+/// The total increase of the metric `name` over the nodes.
+fn delta(nodes: &[(Metrics, Metrics)], name: &str) -> f64 {
+    nodes
+        .iter()
+        .map(|(start, end)| {
+            end.get(name).copied().unwrap_or_default()
+                - start.get(name).copied().unwrap_or_default()
+        })
+        .sum()
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Committee: {} authorities, {} workers",
+            self.authorities, self.workers
+        )?;
+        writeln!(
+            f,
+            "Load: {} tx/s of {} B for {:.1} s",
+            self.rate, self.size, self.duration_secs
+        )?;
+        writeln!(
+            f,
+            "Throughput: {:.0} tx/s, {:.0} B/s",
+            self.throughput_tps, self.throughput_bps
+        )?;
+        writeln!(f, "Commit latency: {:.1} ms", self.commit_latency_ms)?;
+        write!(f, "Last committed round: {}", self.last_committed_round)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::topology::tests::local_topology;
+
+    #[test]
+    fn report_from_metrics() {
+        let topology = local_topology(2, 1);
+        let metrics = |entries: &[(&str, f64)]| -> Metrics {
+            entries
+                .iter()
+                .map(|(name, value)| (name.to_string(), *value))
+                .collect()
+        };
+        let primaries = [
+            (
+                metrics(&[(COMMIT_LATENCY_SUM, 1.0), (COMMIT_LATENCY_COUNT, 2.0)]),
+                metrics(&[
+                    (COMMIT_LATENCY_SUM, 3.0),
+                    (COMMIT_LATENCY_COUNT, 6.0),
+                    (LAST_COMMITTED_ROUND, 40.0),
+                ]),
+            ),
+            (
+                metrics(&[]),
+                metrics(&[
+                    (COMMIT_LATENCY_SUM, 2.0),
+                    (COMMIT_LATENCY_COUNT, 4.0),
+                    (LAST_COMMITTED_ROUND, 42.0),
+                ]),
+            ),
+        ];
+        let workers = [
+            (
+                metrics(&[(BATCH_SIZE_SUM, 1024.0)]),
+                metrics(&[(BATCH_SIZE_SUM, 11264.0)]),
+            ),
+            (metrics(&[]), metrics(&[(BATCH_SIZE_SUM, 10240.0)])),
+        ];
+
+        let report = Report::new(&topology, Duration::from_secs(10), &primaries, &workers);
+        assert_eq!(report.authorities, 2);
+        assert_eq!(report.workers, 2);
+        assert_eq!(report.throughput_bps, 2048.0);
+        assert_eq!(report.throughput_tps, 4.0);
+        assert_eq!(report.commit_latency_ms, 500.0);
+        assert_eq!(report.last_committed_round, 42);
+    }
+}
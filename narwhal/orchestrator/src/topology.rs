@@ -0,0 +1,295 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+use config::{Import, Parameters, Stake};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    net::IpAddr,
+    path::{Component, Path, PathBuf},
+    time::Duration,
+};
+
+/// The description of a benchmark: the machines, where every primary and worker runs on them,
+/// and the load to submit.
+///
+/// ```json
+/// {
+///     "hosts": {
+///         "us-east-1": { "address": "10.0.0.1", "ssh": { "user": "ubuntu", "key": "aws.pem" } },
+///         "eu-north-1": { "address": "10.0.1.1", "ssh": { "user": "ubuntu", "key": "aws.pem" } }
+///     },
+///     "authorities": [
+///         { "primary": "us-east-1", "workers": ["us-east-1"] },
+///         { "primary": "eu-north-1", "workers": ["eu-north-1"], "stake": 2 }
+///     ],
+///     "load": { "rate": 50000, "size": 512, "duration": 60 }
+/// }
+/// ```
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Topology {
+    /// The machines of the benchmark, by name.
+    pub hosts: BTreeMap<String, Host>,
+    /// Where the primary and the workers of every authority run.
+    pub authorities: Vec<AuthorityTopology>,
+    /// The first port allocated on every host.
+    #[serde(default = "Topology::default_base_port")]
+    pub base_port: u16,
+    /// The parameters of every node; the ports of their servers are overridden.
+    #[serde(default)]
+    pub parameters: Parameters,
+    pub load: Load,
+}
+
+/// A machine of the benchmark.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Host {
+    /// The address at which the other machines reach this one.
+    pub address: IpAddr,
+    /// How to reach the machine, or `None` to run its nodes on the local machine.
+    #[serde(default)]
+    pub ssh: Option<Ssh>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Ssh {
+    pub user: String,
+    /// The private key authenticating to the machine, the SSH agent's keys by default.
+    #[serde(default)]
+    pub key: Option<PathBuf>,
+    #[serde(default = "Ssh::default_port")]
+    pub port: u16,
+    /// The known hosts file with the key of the machine, `~/.ssh/known_hosts` by default. The
+    /// key of the machine is always checked.
+    #[serde(default)]
+    pub known_hosts: Option<PathBuf>,
+    /// The directory of the benchmark files on the machine, relative to the home directory. It
+    /// is removed at the start of every benchmark.
+    #[serde(default = "Ssh::default_directory")]
+    pub directory: String,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct AuthorityTopology {
+    /// The host of the primary.
+    pub primary: String,
+    /// The host of each worker, by worker id.
+    pub workers: Vec<String>,
+    #[serde(default = "AuthorityTopology::default_stake")]
+    pub stake: Stake,
+}
+
+/// The load submitted by the benchmark clients, one per worker.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Load {
+    /// The total rate of the transactions, in transactions per second.
+    pub rate: u64,
+    /// The size of the transactions in bytes.
+    pub size: usize,
+    /// The duration of the measurement in seconds.
+    pub duration: u64,
+    /// The time left to the nodes to boot, then to the load to stabilize before the measurement,
+    /// in seconds.
+    #[serde(default = "Load::default_warmup")]
+    pub warmup: u64,
+}
+
+impl Topology {
+    fn default_base_port() -> u16 {
+        5000
+    }
+
+    /// Checks that the topology can run: every authority runs workers on declared hosts, and
+    /// every benchmark client can send its share of the load.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.authorities.is_empty() {
+            return Err("The topology has no authorities".to_string());
+        }
+        for (name, host) in &self.hosts {
+            // The files of the local hosts are in a directory named after them.
+            if !is_subdirectory(name) || name.contains('/') {
+                return Err(format!(
+                    "The host name {name:?} is not a valid directory name"
+                ));
+            }
+            if let Some(ssh) = &host.ssh {
+                if !is_subdirectory(&ssh.directory) {
+                    return Err(format!(
+                        "The directory {:?} of host {name} is not within the home directory",
+                        ssh.directory
+                    ));
+                }
+            }
+        }
+        for (i, authority) in self.authorities.iter().enumerate() {
+            if authority.workers.is_empty() {
+                return Err(format!("Authority {i} has no workers"));
+            }
+            if authority.stake == 0 {
+                return Err(format!("Authority {i} has no stake"));
+            }
+            for host in std::iter::once(&authority.primary).chain(&authority.workers) {
+                if !self.hosts.contains_key(host) {
+                    return Err(format!("Authority {i} runs on the unknown host {host}"));
+                }
+            }
+        }
+        // The benchmark clients send their transactions in 20 bursts per second.
+        if self.load.rate / (self.workers() as u64) < 20 {
+            return Err(format!(
+                "The rate of {} tx/s is too low for {} workers, which need 20 tx/s each",
+                self.load.rate,
+                self.workers()
+            ));
+        }
+        if self.load.size < 9 {
+            // The benchmark client tags the transactions with 9 bytes.
+            return Err("The transactions must be larger than 8 bytes".to_string());
+        }
+        Ok(())
+    }
+
+    /// The total number of workers.
+    pub fn workers(&self) -> usize {
+        self.authorities.iter().map(|a| a.workers.len()).sum()
+    }
+
+    pub fn host(&self, name: &str) -> &Host {
+        &self.hosts[name]
+    }
+}
+
+impl Import for Topology {}
+
+/// Whether `directory` is a relative path within the directory it is relative to, and not that
+/// directory itself.
+pub(crate) fn is_subdirectory(directory: &str) -> bool {
+    let mut components = Path::new(directory).components().peekable();
+    components.peek().is_some() && components.all(|c| matches!(c, Component::Normal(_)))
+}
+
+impl Ssh {
+    fn default_port() -> u16 {
+        22
+    }
+
+    fn default_directory() -> String {
+        "narwhal-benchmark".to_string()
+    }
+}
+
+impl AuthorityTopology {
+    fn default_stake() -> Stake {
+        1
+    }
+}
+
+impl Load {
+    fn default_warmup() -> u64 {
+        10
+    }
+
+    pub fn duration(&self) -> Duration {
+        Duration::from_secs(self.duration)
+    }
+
+    pub fn warmup(&self) -> Duration {
+        Duration::from_secs(self.warmup)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    pub fn local_topology(authorities: usize, workers: usize) -> Topology {
+        let hosts = BTreeMap::from([(
+            "local".to_string(),
+            Host {
+                address: "127.0.0.1".parse().unwrap(),
+                ssh: None,
+            },
+        )]);
+        Topology {
+            hosts,
+            authorities: (0..authorities)
+                .map(|_| AuthorityTopology {
+                    primary: "local".to_string(),
+                    workers: vec!["local".to_string(); workers],
+                    stake: 1,
+                })
+                .collect(),
+            base_port: Topology::default_base_port(),
+            parameters: Parameters::default(),
+            load: Load {
+                rate: 1_000,
+                size: 512,
+                duration: 10,
+                warmup: 5,
+            },
+        }
+    }
+
+    #[test]
+    fn parse_topology() {
+        let json = r#"{
+            "hosts": {
+                "a": { "address": "10.0.0.1", "ssh": { "user": "ubuntu", "key": "aws.pem" } },
+                "b": { "address": "10.0.0.2" }
+            },
+            "authorities": [
+                { "primary": "a", "workers": ["a", "b"] },
+                { "primary": "b", "workers": ["b"], "stake": 2 }
+            ],
+            "load": { "rate": 50000, "size": 512, "duration": 60 }
+        }"#;
+        let topology: Topology = serde_json::from_str(json).unwrap();
+        topology.validate().unwrap();
+
+        assert_eq!(topology.workers(), 3);
+        assert_eq!(topology.authorities[0].stake, 1);
+        assert_eq!(topology.authorities[1].stake, 2);
+        let ssh = topology.host("a").ssh.as_ref().unwrap();
+        assert_eq!(ssh.port, 22);
+        assert_eq!(ssh.directory, "narwhal-benchmark");
+        assert!(topology.host("b").ssh.is_none());
+        assert_eq!(topology.load.warmup(), Duration::from_secs(10));
+    }
+
+    #[test]
+    fn invalid_topologies() {
+        let mut topology = local_topology(4, 1);
+        topology.validate().unwrap();
+
+        topology.authorities[1].workers[0] = "remote".to_string();
+        assert!(topology.validate().is_err());
+
+        let mut topology = local_topology(4, 1);
+        topology.authorities[2].workers.clear();
+        assert!(topology.validate().is_err());
+
+        let mut topology = local_topology(4, 1);
+        topology.load.rate = 3;
+        assert!(topology.validate().is_err());
+
+        for directory in ["", ".", "/", "/home/ubuntu", "..", "benchmark/../.."] {
+            let mut topology = local_topology(4, 1);
+            topology.hosts.get_mut("local").unwrap().ssh = Some(Ssh {
+                user: "ubuntu".to_string(),
+                key: None,
+                port: 22,
+                known_hosts: None,
+                directory: directory.to_string(),
+            });
+            assert!(topology.validate().is_err(), "{directory:?}");
+        }
+
+        let mut topology = local_topology(4, 1);
+        let host = topology.hosts.remove("local").unwrap();
+        topology.hosts.insert("../local".to_string(), host);
+        for authority in &mut topology.authorities {
+            authority.primary = "../local".to_string();
+            authority.workers = vec!["../local".to_string()];
+        }
+        assert!(topology.validate().is_err());
+    }
+}
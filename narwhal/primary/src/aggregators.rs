@@ -4,10 +4,14 @@
 
 use crate::metrics::PrimaryMetrics;
 use config::{Committee, Stake};
-use crypto::{PublicKey, Signature};
-use fastcrypto::traits::EncodeDecodeBase64;
+use crypto::{batch, PublicKey, Signature};
+use fastcrypto::{
+    hash::{Digest, Hash as _},
+    traits::EncodeDecodeBase64,
+};
 use std::collections::HashSet;
 use std::sync::Arc;
+use tracing::warn;
 use types::{
     ensure,
     error::{DagError, DagResult},
@@ -48,6 +52,7 @@ impl VotesAggregator {
             DagError::AuthorityReuse(author.encode_base64())
         );
 
+        let vote_digest: Digest<{ crypto::DIGEST_LENGTH }> = vote.digest().into();
         self.votes.push((author.clone(), vote.signature));
         self.weight += committee.stake(&author);
 
@@ -55,7 +60,19 @@ impl VotesAggregator {
             .votes_received_last_round
             .set(self.votes.len() as i64);
 
-        if self.weight >= committee.quorum_threshold() {
+        if committee.reached_quorum(self.weight) {
+            // The votes all sign the same digest: verify their signatures at once, and only
+            // verify them one by one to drop the invalid ones.
+            if let Err(invalid) = batch::verify_same_message(vote_digest.as_ref(), &self.votes) {
+                for i in invalid.into_iter().rev() {
+                    let (author, _) = self.votes.remove(i);
+                    warn!("Dropping vote of {author} with an invalid signature for {header}");
+                    self.weight -= committee.stake(&author);
+                }
+                if !committee.reached_quorum(self.weight) {
+                    return Ok(None);
+                }
+            }
             self.weight = 0; // Ensures quorum is only reached once.
             return Ok(Some(Certificate::new(
                 committee,
@@ -97,7 +114,7 @@ impl CertificatesAggregator {
 
         self.certificates.push(certificate);
         self.weight += committee.stake(&origin);
-        if self.weight >= committee.quorum_threshold() {
+        if committee.reached_quorum(self.weight) {
             // Note that we do not reset the weight here. If this function is called again and
             // the proposer didn't yet advance round, we can add extra certificates as parents.
             // This is required when running Bullshark as consensus and does not harm when running
@@ -22,8 +22,13 @@ use mysten_metrics::{monitored_future, spawn_logged_monitored_task};
 use network::anemo_ext::NetworkExt;
 use network::UnreliableNetwork;
 use rand::{rngs::SmallRng, SeedableRng};
+use serde_json::json;
 use std::{
     collections::{HashMap, HashSet},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
     time::Duration,
 };
 use storage::{CertificateStore, PayloadToken};
@@ -37,7 +42,7 @@ use tokio::{
 use tracing::{debug, error, info, instrument, trace, warn};
 use types::{
     metered_channel, BatchDigest, Certificate, CertificateDigest, GetCertificatesRequest,
-    PayloadAvailabilityRequest, PrimaryToPrimaryClient, ReconfigureNotification,
+    PayloadAvailabilityRequest, PrimaryToPrimaryClient, ReconfigureNotification, StateDump,
     WorkerSynchronizeMessage,
 };
 
@@ -149,6 +154,22 @@ impl PendingIdentifier {
     }
 }
 
+/// The number of pending requests by type, reported in the state dump.
+#[derive(Default)]
+struct PendingSyncs {
+    headers: AtomicUsize,
+    payloads: AtomicUsize,
+}
+
+impl PendingSyncs {
+    fn of(&self, identifier: &PendingIdentifier) -> &AtomicUsize {
+        match identifier {
+            Header(_) => &self.headers,
+            Payload(_) => &self.payloads,
+        }
+    }
+}
+
 pub struct BlockSynchronizer {
     /// The public key of this primary.
     name: PublicKey,
@@ -168,6 +189,9 @@ pub struct BlockSynchronizer {
     /// Pending block requests either for header or payload type
     pending_requests: HashMap<PendingIdentifier, Vec<ResultSender>>,
 
+    /// The number of pending block requests by type
+    pending_syncs: Arc<PendingSyncs>,
+
     /// Send network requests
     network: anemo::Network,
 
@@ -199,7 +223,16 @@ impl BlockSynchronizer {
         payload_store: Store<(BatchDigest, WorkerId), PayloadToken>,
         certificate_store: CertificateStore,
         parameters: Parameters,
+        state_dump: &StateDump,
     ) -> JoinHandle<()> {
+        let pending_syncs = Arc::new(PendingSyncs::default());
+        let syncs = pending_syncs.clone();
+        state_dump.register("block_synchronizer", move || {
+            json!({
+                "pending_header_syncs": syncs.headers.load(Ordering::Relaxed),
+                "pending_payload_syncs": syncs.payloads.load(Ordering::Relaxed),
+            })
+        });
         spawn_logged_monitored_task!(
             async move {
                 let _ = &parameters;
@@ -210,6 +243,7 @@ impl BlockSynchronizer {
                     rx_reconfigure,
                     rx_block_synchronizer_commands,
                     pending_requests: HashMap::new(),
+                    pending_syncs,
                     network,
                     payload_store,
                     certificate_store,
@@ -324,6 +358,9 @@ impl BlockSynchronizer {
     ) {
         // remove the senders & broadcast result
         if let Some(respond_to) = self.pending_requests.remove(&request) {
+            self.pending_syncs
+                .of(&request)
+                .fetch_sub(1, Ordering::Relaxed);
             let futures: Vec<_> = respond_to.iter().map(|s| s.send(result.clone())).collect();
 
             for r in join_all(futures).await {
@@ -346,7 +383,13 @@ impl BlockSynchronizer {
         let e = self.pending_requests.entry(identifier).or_default();
         e.push(respond_to);
 
-        e.len() == 1
+        if e.len() > 1 {
+            return false;
+        }
+        self.pending_syncs
+            .of(&identifier)
+            .fetch_add(1, Ordering::Relaxed);
+        true
     }
 
     /// This method handles the command to synchronize the payload of the
@@ -774,17 +817,12 @@ impl BlockSynchronizer {
             }
 
             let certificates = &response.body().certificates;
-            let mut found_invalid_certificate = false;
-            for certificate in certificates {
-                if let Err(err) = certificate.verify(&committee, worker_cache.clone()) {
-                    error!(
-                        "Ignoring certificates from peer {response_peer:?}: certificate verification failed for digest {} with error {err:?}",
-                        certificate.digest(),
-                    );
-                    found_invalid_certificate = true;
-                }
-            }
-            if found_invalid_certificate {
+            if let Err(err) =
+                Certificate::verify_batch(certificates, &committee, worker_cache.clone())
+            {
+                error!(
+                    "Ignoring certificates from peer {response_peer:?}: certificate verification failed with error {err:?}",
+                );
                 continue;
             }
 
@@ -19,7 +19,7 @@ use tokio::{
 };
 use types::{
     GetCertificatesResponse, MockPrimaryToPrimary, PayloadAvailabilityResponse,
-    PrimaryToPrimaryServer, ReconfigureNotification,
+    PrimaryToPrimaryServer, ReconfigureNotification, StateDump,
 };
 
 use fastcrypto::traits::KeyPair as _;
@@ -88,6 +88,7 @@ async fn test_successful_headers_synchronization() {
         payload_store.clone(),
         certificate_store.clone(),
         Parameters::default(),
+        &StateDump::default(),
     );
 
     // AND the channel to respond to
@@ -230,6 +231,7 @@ async fn test_successful_payload_synchronization() {
         payload_store.clone(),
         certificate_store.clone(),
         Parameters::default(),
+        &StateDump::default(),
     );
 
     // AND the channel to respond to
@@ -399,6 +401,7 @@ async fn test_timeout_while_waiting_for_certificates() {
         },
         ..Default::default()
     };
+    let state_dump = StateDump::default();
     let _synchronizer_handle = BlockSynchronizer::spawn(
         name.clone(),
         committee.clone(),
@@ -409,6 +412,7 @@ async fn test_timeout_while_waiting_for_certificates() {
         payload_store.clone(),
         certificate_store.clone(),
         params.clone(),
+        &state_dump,
     );
 
     // AND the channel to respond to
@@ -424,7 +428,13 @@ async fn test_timeout_while_waiting_for_certificates() {
         .ok()
         .unwrap();
 
-    // THEN
+    // THEN the requests are reported as pending until they time out
+    sleep(Duration::from_millis(200)).await;
+    assert_eq!(
+        state_dump.snapshot()["block_synchronizer"]["pending_header_syncs"],
+        digests.len()
+    );
+
     let timer = sleep(Duration::from_millis(5_000));
     tokio::pin!(timer);
 
@@ -458,6 +468,11 @@ async fn test_timeout_while_waiting_for_certificates() {
             }
         }
     }
+
+    assert_eq!(
+        state_dump.snapshot()["block_synchronizer"]["pending_header_syncs"],
+        0
+    );
 }
 
 #[tokio::test]
@@ -492,6 +507,7 @@ async fn test_reply_with_certificates_already_in_storage() {
         rx_reconfigure,
         rx_block_synchronizer_commands,
         pending_requests: Default::default(),
+        pending_syncs: Default::default(),
         network,
         certificate_store: certificate_store.clone(),
         payload_store,
@@ -591,6 +607,7 @@ async fn test_reply_with_payload_already_in_storage() {
         rx_reconfigure,
         rx_block_synchronizer_commands,
         pending_requests: Default::default(),
+        pending_syncs: Default::default(),
         network,
         certificate_store: certificate_store.clone(),
         payload_store: payload_store.clone(),
@@ -695,6 +712,7 @@ async fn test_reply_with_payload_already_in_storage_for_own_certificates() {
         rx_reconfigure,
         rx_block_synchronizer_commands,
         pending_requests: Default::default(),
+        pending_syncs: Default::default(),
         network,
         certificate_store: certificate_store.clone(),
         payload_store: payload_store.clone(),
@@ -11,12 +11,16 @@ use crate::{
 
 use anyhow::Result;
 use config::{Committee, Epoch, SharedWorkerCache};
-use crypto::{NetworkPublicKey, PublicKey, Signature};
-use fastcrypto::{hash::Hash as _, SignatureService};
+use crypto::{NetworkPublicKey, PublicKey, SignatureService};
+use fastcrypto::hash::Hash as _;
 use futures::StreamExt;
 use futures::{future::OptionFuture, stream::FuturesUnordered};
 use mysten_metrics::{spawn_logged_monitored_task, spawn_monitored_task};
-use network::{anemo_ext::NetworkExt, CancelOnDropHandler, ReliableNetwork};
+use network::{
+    anemo_ext::NetworkExt,
+    trace_id::{self, TraceId},
+    CancelOnDropHandler, ReliableNetwork,
+};
 use std::time::Duration;
 use std::{collections::HashMap, sync::Arc, time::Instant};
 use storage::CertificateStore;
@@ -25,13 +29,13 @@ use tokio::{
     sync::{oneshot, watch},
     task::{JoinHandle, JoinSet},
 };
-use tracing::{debug, error, info, instrument, trace, warn};
+use tracing::{debug, error, info, info_span, instrument, trace, warn, Instrument};
 use types::{
     ensure,
     error::{DagError, DagResult},
     metered_channel::{Receiver, Sender},
-    Certificate, CertificateDigest, Header, HeaderDigest, PrimaryToPrimaryClient,
-    ReconfigureNotification, RequestVoteRequest, Round, Timestamp, Vote,
+    AuditEvent, AuditLog, Certificate, CertificateDigest, Header, HeaderDigest,
+    PrimaryToPrimaryClient, ReconfigureNotification, RequestVoteRequest, Round, Timestamp, Vote,
 };
 
 #[cfg(test)]
@@ -52,7 +56,7 @@ pub struct Core {
     /// Handles synchronization with other nodes and our workers.
     synchronizer: Arc<Synchronizer>,
     /// Service to sign headers.
-    signature_service: SignatureService<Signature, { crypto::DIGEST_LENGTH }>,
+    signature_service: SignatureService,
     /// Get a signal when the consensus round changes
     rx_consensus_round_updates: watch::Receiver<Round>,
     /// Get a signal when the narwhal round changes
@@ -67,7 +71,7 @@ pub struct Core {
     /// Receives loopback certificates from the `CertificateFetcher`.
     rx_certificates_loopback: Receiver<CertificateLoopbackMessage>,
     /// Receives our newly created headers from the `Proposer`.
-    rx_headers: Receiver<Header>,
+    rx_headers: Receiver<(Header, TraceId)>,
     /// Output all certificates to the consensus layer.
     tx_new_certificates: Sender<Certificate>,
     /// Send valid a quorum of certificates' ids to the `Proposer` (along with their round).
@@ -96,6 +100,8 @@ pub struct Core {
     network: anemo::Network,
     /// Metrics handler
     metrics: Arc<PrimaryMetrics>,
+    /// The log of the headers proposed and the certificates formed.
+    audit_log: AuditLog,
 }
 
 impl Core {
@@ -108,18 +114,66 @@ impl Core {
         header_store: Store<HeaderDigest, Header>,
         certificate_store: CertificateStore,
         synchronizer: Arc<Synchronizer>,
-        signature_service: SignatureService<Signature, { crypto::DIGEST_LENGTH }>,
+        signature_service: SignatureService,
         rx_consensus_round_updates: watch::Receiver<Round>,
         rx_narwhal_round_updates: watch::Receiver<Round>,
         gc_depth: Round,
         rx_reconfigure: watch::Receiver<ReconfigureNotification>,
         rx_certificates: Receiver<(Certificate, Option<oneshot::Sender<DagResult<()>>>)>,
         rx_certificates_loopback: Receiver<CertificateLoopbackMessage>,
-        rx_headers: Receiver<Header>,
+        rx_headers: Receiver<(Header, TraceId)>,
         tx_new_certificates: Sender<Certificate>,
         tx_parents: Sender<(Vec<Certificate>, Round, Epoch)>,
         metrics: Arc<PrimaryMetrics>,
         primary_network: anemo::Network,
+    ) -> JoinHandle<()> {
+        Self::spawn_with_audit_log(
+            name,
+            committee,
+            worker_cache,
+            header_store,
+            certificate_store,
+            synchronizer,
+            signature_service,
+            rx_consensus_round_updates,
+            rx_narwhal_round_updates,
+            gc_depth,
+            rx_reconfigure,
+            rx_certificates,
+            rx_certificates_loopback,
+            rx_headers,
+            tx_new_certificates,
+            tx_parents,
+            metrics,
+            primary_network,
+            AuditLog::default(),
+        )
+    }
+
+    /// Spawns the core as [`Core::spawn`] does, recording its headers and certificates in
+    /// `audit_log`.
+    #[allow(clippy::too_many_arguments)]
+    #[must_use]
+    pub fn spawn_with_audit_log(
+        name: PublicKey,
+        committee: Committee,
+        worker_cache: SharedWorkerCache,
+        header_store: Store<HeaderDigest, Header>,
+        certificate_store: CertificateStore,
+        synchronizer: Arc<Synchronizer>,
+        signature_service: SignatureService,
+        rx_consensus_round_updates: watch::Receiver<Round>,
+        rx_narwhal_round_updates: watch::Receiver<Round>,
+        gc_depth: Round,
+        rx_reconfigure: watch::Receiver<ReconfigureNotification>,
+        rx_certificates: Receiver<(Certificate, Option<oneshot::Sender<DagResult<()>>>)>,
+        rx_certificates_loopback: Receiver<CertificateLoopbackMessage>,
+        rx_headers: Receiver<(Header, TraceId)>,
+        tx_new_certificates: Sender<Certificate>,
+        tx_parents: Sender<(Vec<Certificate>, Round, Epoch)>,
+        metrics: Arc<PrimaryMetrics>,
+        primary_network: anemo::Network,
+        audit_log: AuditLog,
     ) -> JoinHandle<()> {
         spawn_logged_monitored_task!(
             async move {
@@ -150,6 +204,7 @@ impl Core {
                     certificates_aggregators: HashMap::with_capacity(2 * gc_depth as usize),
                     network: primary_network,
                     metrics,
+                    audit_log,
                 }
                 .recover()
                 .await
@@ -288,7 +343,8 @@ impl Core {
                 && vote.author == authority,
             DagError::UnexpectedVote(vote.digest)
         );
-        vote.verify(&committee)?;
+        // The signatures of the votes are verified in a batch by the aggregator.
+        vote.verify_without_signature(&committee)?;
         Ok(vote)
     }
 
@@ -298,7 +354,7 @@ impl Core {
         committee: Committee,
         header_store: Store<HeaderDigest, Header>,
         certificate_store: CertificateStore,
-        signature_service: SignatureService<Signature, { crypto::DIGEST_LENGTH }>,
+        signature_service: SignatureService,
         metrics: Arc<PrimaryMetrics>,
         network: anemo::Network,
         header: Header,
@@ -320,6 +376,7 @@ impl Core {
         header_store
             .async_write(header.digest(), header.clone())
             .await;
+        fail::fail_point!("core-after-store-header");
         metrics
             .headers_proposed
             .with_label_values(&[&header.epoch.to_string()])
@@ -381,6 +438,7 @@ impl Core {
         let certificate =
             certificate.ok_or_else(|| DagError::CouldNotFormCertificate(header.digest()))?;
         debug!("Assembled {certificate:?}");
+        fail::fail_point!("core-after-assemble-certificate");
 
         Ok(certificate)
     }
@@ -426,6 +484,9 @@ impl Core {
             result @ Err(DagError::ShuttingDown) => result,
             _ => panic!("Failed to process locally-created certificate"),
         }?;
+        fail::fail_point!("core-before-broadcast-certificate");
+        self.audit_log
+            .record(AuditEvent::certificate_formed(&certificate));
 
         // Broadcast the certificate.
         let epoch = certificate.epoch();
@@ -562,6 +623,7 @@ impl Core {
         }
 
         // Store the certificate.
+        fail::fail_point!("core-before-store-certificate");
         self.certificate_store.write(certificate.clone())?;
 
         // Update metrics for processed certificates.
@@ -611,7 +673,13 @@ impl Core {
         Ok(())
     }
 
-    async fn sanitize_certificate(&mut self, certificate: &Certificate) -> DagResult<()> {
+    /// Checks `certificate` before processing it, and verifies it unless it was `verified` as
+    /// part of a batch.
+    async fn sanitize_certificate(
+        &mut self,
+        certificate: &Certificate,
+        verified: bool,
+    ) -> DagResult<()> {
         if certificate.epoch() > self.committee.epoch() {
             self.try_update_committee().await;
         }
@@ -631,6 +699,9 @@ impl Core {
                 self.gc_round
             )
         );
+        if verified {
+            return Ok(());
+        }
         // Verify the certificate (and the embedded header).
         certificate
             .verify(&self.committee, self.worker_cache.clone())
@@ -683,7 +754,7 @@ impl Core {
         loop {
             let result = tokio::select! {
                 Some((certificate, notify)) = self.rx_certificates.recv() => {
-                    match self.sanitize_certificate(&certificate).await {
+                    match self.sanitize_certificate(&certificate, false).await {
                         Ok(()) =>  self.process_certificate(certificate, notify).await,
                         error => {
                             // `error` is consumed by the notify, so we process it first manually
@@ -700,9 +771,17 @@ impl Core {
                 // Here loopback certificates from the `CertificateFetcher` are received. These are
                 // certificates fetched from other validators that are potentially missing locally.
                 Some(message) = self.rx_certificates_loopback.recv() => {
+                    // Verify the signatures of the fetched certificates in a batch. If any of
+                    // them is invalid, they are verified one by one to process the valid ones.
+                    let verified = Certificate::verify_batch(
+                        &message.certificates,
+                        &self.committee,
+                        self.worker_cache.clone(),
+                    )
+                    .is_ok();
                     let mut result = Ok(());
                     for cert in message.certificates {
-                        result = match self.sanitize_certificate(&cert).await {
+                        result = match self.sanitize_certificate(&cert, verified).await {
                             // TODO: consider moving some checks to CertificateFetcher, and skipping
                             // those checks here?
                             Ok(()) => self.process_certificate(cert, None).await,
@@ -720,7 +799,7 @@ impl Core {
                 },
 
                 // We also receive here our new headers created by the `Proposer`.
-                Some(header) = self.rx_headers.recv() => {
+                Some((header, trace_id)) = self.rx_headers.recv() => {
                     let (tx_cancel, rx_cancel) = oneshot::channel();
                     if let Some(cancel) = self.cancel_proposed_header {
                         let _ = cancel.send(());
@@ -736,17 +815,31 @@ impl Core {
                     let signature_service = self.signature_service.clone();
                     let metrics = self.metrics.clone();
                     let network = self.network.clone();
-                    self.propose_header_future = Some(spawn_monitored_task!(Self::propose_header(
-                        name,
-                        committee,
-                        header_store,
-                        certificate_store,
-                        signature_service,
-                        metrics,
-                        network,
-                        header,
-                        rx_cancel,
-                    ))).into();
+                    // The votes are requested under the trace id of the header, and its commit
+                    // joins its trace.
+                    let span = info_span!(
+                        "propose_header",
+                        header_digest = %header.digest(),
+                        round = header.round,
+                        %trace_id
+                    );
+                    trace_id::join(&span, &trace_id);
+                    trace_id::record(header.digest(), trace_id.clone());
+                    self.audit_log.record(AuditEvent::header_proposed(&header));
+                    self.propose_header_future = Some(spawn_monitored_task!(trace_id::scope(
+                        Some(trace_id),
+                        Self::propose_header(
+                            name,
+                            committee,
+                            header_store,
+                            certificate_store,
+                            signature_service,
+                            metrics,
+                            network,
+                            header,
+                            rx_cancel,
+                        )
+                    ).instrument(span))).into();
                     Ok(())
                 },
 
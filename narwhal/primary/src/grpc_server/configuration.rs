@@ -8,7 +8,7 @@ use multiaddr::Multiaddr;
 use std::collections::BTreeMap;
 use tonic::{Request, Response, Status};
 use types::{
-    Configuration, Empty, GetPrimaryAddressResponse, MultiAddrProto, NewEpochRequest,
+    Configuration, Empty, ErrorCode, GetPrimaryAddressResponse, MultiAddrProto, NewEpochRequest,
     NewNetworkInfoRequest, PublicKeyProto,
 };
 
@@ -31,16 +31,15 @@ impl NarwhalConfiguration {
     /// parsed public key. The Err() will hold a Status message with the
     /// specific error description.
     fn get_public_key(&self, request: Option<&PublicKeyProto>) -> Result<PublicKey, Status> {
-        let proto_key = request
-            .ok_or_else(|| Status::invalid_argument("Invalid public key: no key provided"))?;
+        let proto_key = request.ok_or_else(|| {
+            ErrorCode::InvalidArgument.status("Invalid public key: no key provided")
+        })?;
         let key = PublicKey::from_bytes(proto_key.bytes.as_ref())
-            .map_err(|_| Status::invalid_argument("Invalid public key: couldn't parse"))?;
+            .map_err(|_| ErrorCode::InvalidArgument.status("Invalid public key: couldn't parse"))?;
 
         // ensure provided key is part of the committee
         if self.committee.load().primary(&key).is_err() {
-            return Err(Status::invalid_argument(
-                "Invalid public key: unknown authority",
-            ));
+            return Err(ErrorCode::InvalidArgument.status("Invalid public key: unknown authority"));
         }
 
         Ok(key)
@@ -64,18 +63,18 @@ impl Configuration for NarwhalConfiguration {
             let primary_address: Multiaddr = validator
                 .primary_address
                 .as_ref()
-                .ok_or_else(|| Status::invalid_argument("Missing primary address"))?
+                .ok_or_else(|| ErrorCode::InvalidArgument.status("Missing primary address"))?
                 .address
                 .parse()
                 .map_err(|err| {
-                    Status::invalid_argument(format!("Could not serialize: {:?}", err))
+                    ErrorCode::InvalidArgument.status(format!("Could not serialize: {:?}", err))
                 })?;
             parsed_input.push(format!(
                 "public_key: {:?} stake_weight: {:?} primary address: {:?}",
                 public_key, stake_weight, primary_address
             ));
         }
-        Err(Status::internal(format!(
+        Err(ErrorCode::Internal.status(format!(
             "Not Implemented! But parsed input - epoch_number: {:?} & validator_data: {:?}",
             epoch_number, parsed_input
         )))
@@ -89,7 +88,7 @@ impl Configuration for NarwhalConfiguration {
         let new_network_info_request = request.into_inner();
         let epoch_number: u64 = new_network_info_request.epoch_number.into();
         if epoch_number != self.committee.load().epoch() {
-            return Err(Status::invalid_argument(format!(
+            return Err(ErrorCode::InvalidArgument.status(format!(
                 "Passed in epoch {epoch_number} does not match current epoch {}",
                 self.committee.load().epoch
             )));
@@ -102,15 +101,17 @@ impl Configuration for NarwhalConfiguration {
             let stake_weight: Stake = validator
                 .stake_weight
                 .try_into()
-                .map_err(|_| Status::invalid_argument("Invalid stake weight"))?;
+                .map_err(|_| ErrorCode::InvalidArgument.status("Invalid stake weight"))?;
             let primary_address = validator
                 .primary_address
                 .as_ref()
-                .ok_or_else(|| Status::invalid_argument("Missing primary to primary address"))?
+                .ok_or_else(|| {
+                    ErrorCode::InvalidArgument.status("Missing primary to primary address")
+                })?
                 .address
                 .parse()
                 .map_err(|err| {
-                    Status::invalid_argument(format!("Could not serialize: {:?}", err))
+                    ErrorCode::InvalidArgument.status(format!("Could not serialize: {:?}", err))
                 })?;
             new_network_info.insert(public_key, (stake_weight, primary_address));
         }
@@ -119,7 +120,9 @@ impl Configuration for NarwhalConfiguration {
         if res.is_ok() {
             self.committee.swap(std::sync::Arc::new(new_committee));
         }
-        res.map_err(|err| Status::internal(format!("Could not update network info: {:?}", err)))?;
+        res.map_err(|err| {
+            ErrorCode::Internal.status(format!("Could not update network info: {:?}", err))
+        })?;
 
         Ok(Response::new(Empty {}))
     }
@@ -7,7 +7,7 @@ use crate::{
     grpc_server::{metrics::EndpointMetrics, proposer::NarwhalProposer},
     BlockRemover, BlockWaiter,
 };
-use config::SharedCommittee;
+use config::{NetworkParameters, SharedCommittee};
 use consensus::dag::Dag;
 
 use crypto::PublicKey;
@@ -34,6 +34,7 @@ pub struct ConsensusAPIGrpc<SynchronizerHandler: Handler + Send + Sync + 'static
     block_synchronizer_handler: Arc<SynchronizerHandler>,
     dag: Option<Arc<Dag>>,
     committee: SharedCommittee,
+    network_parameters: NetworkParameters,
     endpoints_metrics: EndpointMetrics,
 }
 
@@ -49,6 +50,7 @@ impl<SynchronizerHandler: Handler + Send + Sync + 'static> ConsensusAPIGrpc<Sync
         block_synchronizer_handler: Arc<SynchronizerHandler>,
         dag: Option<Arc<Dag>>,
         committee: SharedCommittee,
+        network_parameters: NetworkParameters,
         endpoints_metrics: EndpointMetrics,
     ) -> JoinHandle<()> {
         spawn_logged_monitored_task!(
@@ -63,6 +65,7 @@ impl<SynchronizerHandler: Handler + Send + Sync + 'static> ConsensusAPIGrpc<Sync
                     block_synchronizer_handler,
                     dag,
                     committee,
+                    network_parameters,
                     endpoints_metrics,
                 }
                 .run()
@@ -92,7 +95,13 @@ impl<SynchronizerHandler: Handler + Send + Sync + 'static> ConsensusAPIGrpc<Sync
             Arc::clone(&self.committee),
         );
 
-        let config = mysten_network::config::Config::default();
+        let config = mysten_network::config::Config {
+            request_timeout: self.network_parameters.grpc_request_timeout,
+            http2_keepalive_interval: self.network_parameters.grpc_http2_keepalive_interval,
+            http2_keepalive_timeout: self.network_parameters.grpc_http2_keepalive_timeout,
+            tcp_keepalive: self.network_parameters.grpc_tcp_keepalive,
+            ..Default::default()
+        };
         let server = config
             .server_builder_with_metrics(self.endpoints_metrics.clone())
             .add_service(ValidatorServer::new(narwhal_validator))
@@ -7,8 +7,8 @@ use fastcrypto::traits::ToFromBytes;
 use std::sync::Arc;
 use tonic::{Request, Response, Status};
 use types::{
-    NodeReadCausalRequest, NodeReadCausalResponse, Proposer, PublicKeyProto, RoundsRequest,
-    RoundsResponse,
+    ErrorCode, NodeReadCausalRequest, NodeReadCausalResponse, Proposer, PublicKeyProto,
+    RoundsRequest, RoundsResponse,
 };
 
 pub struct NarwhalProposer {
@@ -29,16 +29,15 @@ impl NarwhalProposer {
     /// parsed public key. The Err() will hold a Status message with the
     /// specific error description.
     fn get_public_key(&self, request: Option<PublicKeyProto>) -> Result<PublicKey, Status> {
-        let proto_key = request
-            .ok_or_else(|| Status::invalid_argument("Invalid public key: no key provided"))?;
+        let proto_key = request.ok_or_else(|| {
+            ErrorCode::InvalidArgument.status("Invalid public key: no key provided")
+        })?;
         let key = PublicKey::from_bytes(proto_key.bytes.as_ref())
-            .map_err(|_| Status::invalid_argument("Invalid public key: couldn't parse"))?;
+            .map_err(|_| ErrorCode::InvalidArgument.status("Invalid public key: couldn't parse"))?;
 
         // ensure provided key is part of the committee
         if self.committee.load().primary(&key).is_err() {
-            return Err(Status::invalid_argument(
-                "Invalid public key: unknown authority",
-            ));
+            return Err(ErrorCode::InvalidArgument.status("Invalid public key: unknown authority"));
         }
 
         Ok(key)
@@ -63,12 +62,14 @@ impl Proposer for NarwhalProposer {
                     oldest_round: *r.start() as u64,
                     newest_round: *r.end() as u64,
                 }),
-                Err(err) => Err(Status::internal(format!("Couldn't retrieve rounds: {err}"))),
+                Err(err) => {
+                    Err(ErrorCode::Internal.status(format!("Couldn't retrieve rounds: {err}")))
+                }
             };
             return result.map(Response::new);
         }
 
-        Err(Status::internal("Can not serve request"))
+        Err(ErrorCode::Unsupported.status("Can not serve request"))
     }
 
     async fn node_read_causal(
@@ -85,12 +86,12 @@ impl Proposer for NarwhalProposer {
                 Ok(digests) => Ok(NodeReadCausalResponse {
                     collection_ids: digests.into_iter().map(Into::into).collect(),
                 }),
-                Err(err) => Err(Status::internal(format!(
+                Err(err) => Err(ErrorCode::Internal.status(format!(
                     "Couldn't read causal for provided key & round: {err}"
                 ))),
             };
             return result.map(Response::new);
         }
-        Err(Status::internal("Dag does not exist"))
+        Err(ErrorCode::Unsupported.status("Dag does not exist"))
     }
 }
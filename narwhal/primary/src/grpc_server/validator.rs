@@ -51,10 +51,9 @@ impl<SynchronizerHandler: Handler + Send + Sync + 'static> Validator
         &self,
         request: Request<ReadCausalRequest>,
     ) -> Result<Response<ReadCausalResponse>, Status> {
-        let collection_id = request
-            .into_inner()
-            .collection_id
-            .ok_or_else(|| Status::invalid_argument("No collection id has been provided"))?;
+        let collection_id = request.into_inner().collection_id.ok_or_else(|| {
+            ErrorCode::InvalidArgument.status("No collection id has been provided")
+        })?;
         let ids = parse_certificate_digests(vec![collection_id])?;
 
         let block_header_results = self
@@ -64,7 +63,7 @@ impl<SynchronizerHandler: Handler + Send + Sync + 'static> Validator
 
         for result in block_header_results {
             if let Err(err) = result {
-                return Err(Status::internal(format!(
+                return Err(ErrorCode::Internal.status(format!(
                     "Error when trying to synchronize block headers: {:?}",
                     err
                 )));
@@ -76,11 +75,11 @@ impl<SynchronizerHandler: Handler + Send + Sync + 'static> Validator
                 Ok(digests) => Ok(ReadCausalResponse {
                     collection_ids: digests.into_iter().map(Into::into).collect(),
                 }),
-                Err(err) => Err(Status::internal(format!("Couldn't read causal: {err}"))),
+                Err(err) => Err(ErrorCode::Internal.status(format!("Couldn't read causal: {err}"))),
             };
             return result.map(Response::new);
         }
-        Err(Status::internal("Dag does not exist"))
+        Err(ErrorCode::Unsupported.status("Dag does not exist"))
     }
 
     async fn remove_collections(
@@ -95,15 +94,14 @@ impl<SynchronizerHandler: Handler + Send + Sync + 'static> Validator
                 self.block_remover.remove_blocks(ids),
             )
             .await
-            .map_err(|_err| Status::internal("Timeout, no result has been received in time"))?
-            {
+            .map_err(|_err| {
+                ErrorCode::Timeout.status("Timeout, no result has been received in time")
+            })? {
                 Ok(_) => Ok(Empty {}),
-                Err(e) => Err(Status::internal(format!("Removal Error: {e:?}"))),
+                Err(e) => Err(ErrorCode::Internal.status(format!("Removal Error: {e:?}"))),
             }
         } else {
-            Err(Status::invalid_argument(
-                "Attempted to remove no collections!",
-            ))
+            Err(ErrorCode::InvalidArgument.status("Attempted to remove no collections!"))
         };
         remove_collections_response.map(Response::new)
     }
@@ -117,8 +115,8 @@ impl<SynchronizerHandler: Handler + Send + Sync + 'static> Validator
             let ids = parse_certificate_digests(collection_ids)?;
             let blocks_response = timeout(self.get_collections_timeout, self.block_waiter.get_blocks(ids))
                 .await
-                .map_err(|_err| Status::internal("Timeout, no result has been received in time"))?
-                .map_err(|err| Status::internal(format!(
+                .map_err(|_err| ErrorCode::Timeout.status("Timeout, no result has been received in time"))?
+                .map_err(|err| ErrorCode::Internal.status(format!(
                     "Expected to receive a successful get blocks result, instead got error: {err:?}",
                 )))?;
             let result: Vec<_> = blocks_response
@@ -128,9 +126,7 @@ impl<SynchronizerHandler: Handler + Send + Sync + 'static> Validator
                 .collect();
             Ok(GetCollectionsResponse { result })
         } else {
-            Err(Status::invalid_argument(
-                "Attempted fetch of no collections!",
-            ))
+            Err(ErrorCode::InvalidArgument.status("Attempted fetch of no collections!"))
         };
         get_collections_response.map(Response::new)
     }
@@ -170,11 +166,9 @@ fn parse_certificate_digests(
 ) -> Result<Vec<CertificateDigest>, Status> {
     let mut ids = vec![];
     for collection_id in collection_ids {
-        ids.push(
-            collection_id.try_into().map_err(|err| {
-                Status::invalid_argument(format!("Could not serialize: {:?}", err))
-            })?,
-        );
+        ids.push(collection_id.try_into().map_err(|err| {
+            ErrorCode::InvalidArgument.status(format!("Could not serialize: {:?}", err))
+        })?);
     }
     Ok(ids)
 }
@@ -15,6 +15,7 @@ mod block_waiter;
 mod certificate_fetcher;
 mod core;
 mod grpc_server;
+mod network_keys;
 mod primary;
 mod proposer;
 mod state_handler;
@@ -36,5 +37,6 @@ pub use crate::{
     block_waiter::{BlockWaiter, GetBlockResponse},
     grpc_server::metrics::EndpointMetrics,
     metrics::PrimaryChannelMetrics,
+    network_keys::announce_network_key,
     primary::{NetworkModel, Primary, CHANNEL_CAPACITY},
 };
@@ -10,8 +10,9 @@ use prometheus::{
     register_int_gauge_vec_with_registry, register_int_gauge_with_registry, Histogram,
     HistogramVec, IntCounter, IntCounterVec, IntGauge, IntGaugeVec, Registry,
 };
-use std::time::Duration;
+use std::{collections::BTreeMap, time::Duration};
 use tonic::Code;
+use types::metered_channel::ChannelCollector;
 
 const LATENCY_SEC_BUCKETS: &[f64] = &[
     0.05, 0.1, 0.25, 0.5, 1., 2.5, 5., 7.5, 10., 12.5, 15., 20., 25., 30., 60., 90., 120., 180.,
@@ -112,9 +113,83 @@ pub struct PrimaryChannelMetrics {
     pub tx_new_certificates_total: IntCounter,
     /// total received on the channel signaling own committed headers
     pub tx_commited_own_headers_total: IntCounter,
+
+    /// exports the occupancies and the totals above, labelled by channel
+    collector: ChannelCollector,
 }
 
 impl PrimaryChannelMetrics {
+    /// Every channel by name, with its occupancy and its total received.
+    fn channels(&self) -> Vec<(&'static str, &IntGauge, &IntCounter)> {
+        vec![
+            (
+                "tx_others_digests",
+                &self.tx_others_digests,
+                &self.tx_others_digests_total,
+            ),
+            (
+                "tx_our_digests",
+                &self.tx_our_digests,
+                &self.tx_our_digests_total,
+            ),
+            ("tx_parents", &self.tx_parents, &self.tx_parents_total),
+            ("tx_headers", &self.tx_headers, &self.tx_headers_total),
+            (
+                "tx_certificate_fetcher",
+                &self.tx_certificate_fetcher,
+                &self.tx_certificate_fetcher_total,
+            ),
+            (
+                "tx_certificates_loopback",
+                &self.tx_certificates_loopback,
+                &self.tx_certificates_loopback_total,
+            ),
+            (
+                "tx_certificates",
+                &self.tx_certificates,
+                &self.tx_certificates_total,
+            ),
+            (
+                "tx_block_synchronizer_commands",
+                &self.tx_block_synchronizer_commands,
+                &self.tx_block_synchronizer_commands_total,
+            ),
+            (
+                "tx_state_handler",
+                &self.tx_state_handler,
+                &self.tx_state_handler_total,
+            ),
+            (
+                "tx_reconfigure",
+                &self.tx_reconfigure,
+                &self.tx_reconfigure_total,
+            ),
+            (
+                "tx_committed_certificates",
+                &self.tx_committed_certificates,
+                &self.tx_committed_certificates_total,
+            ),
+            (
+                "tx_new_certificates",
+                &self.tx_new_certificates,
+                &self.tx_new_certificates_total,
+            ),
+            (
+                "tx_commited_own_headers",
+                &self.tx_commited_own_headers,
+                &self.tx_commited_own_headers_total,
+            ),
+        ]
+    }
+
+    /// The occupancy of every channel, by name.
+    pub fn depths(&self) -> BTreeMap<&'static str, i64> {
+        self.channels()
+            .into_iter()
+            .map(|(name, gauge, _)| (name, gauge.get()))
+            .collect()
+    }
+
     // The consistent use of this constant in the below, as well as in `node::spawn_primary` is
     // load-bearing, see `replace_registered_committed_certificates_metric`.
     pub const NAME_COMMITTED_CERTS: &'static str = "tx_committed_certificates";
@@ -138,7 +213,7 @@ impl PrimaryChannelMetrics {
         "total received on channel from the `primary::Core` to the `Consensus`";
 
     pub fn new(registry: &Registry) -> Self {
-        Self {
+        let metrics = Self {
             tx_others_digests: register_int_gauge_with_registry!(
                 "tx_others_digests",
                 "occupancy of the channel from the `primary::WorkerReceiverHandler` to the `primary::PayloadReceiver`",
@@ -272,10 +347,12 @@ impl PrimaryChannelMetrics {
                 registry
             ).unwrap(),
 
-
-
-
+            collector: ChannelCollector::new("primary", registry),
+        };
+        for (name, gauge, total) in metrics.channels() {
+            metrics.collector.add(name, gauge, Some(total));
         }
+        metrics
     }
 
     pub fn replace_registered_new_certificates_metric(
@@ -289,8 +366,11 @@ impl PrimaryChannelMetrics {
         registry
             .unregister(Box::new(new_certificates_counter.clone()))
             .unwrap();
+        let gauge = (*collector).clone();
         registry.register(collector).unwrap();
-        self.tx_new_certificates = new_certificates_counter;
+        // The channel is created by the node, without a total.
+        self.collector.add(Self::NAME_NEW_CERTS, &gauge, None);
+        self.tx_new_certificates = gauge;
     }
 
     pub fn replace_registered_committed_certificates_metric(
@@ -304,8 +384,11 @@ impl PrimaryChannelMetrics {
         registry
             .unregister(Box::new(committed_certificates_counter.clone()))
             .unwrap();
+        let gauge = (*collector).clone();
         registry.register(collector).unwrap();
-        self.tx_committed_certificates = committed_certificates_counter;
+        // The channel is created by the node, without a total.
+        self.collector.add(Self::NAME_COMMITTED_CERTS, &gauge, None);
+        self.tx_committed_certificates = gauge;
     }
 }
 
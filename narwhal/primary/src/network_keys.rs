@@ -0,0 +1,336 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+use anemo::{
+    types::{PeerEvent, PeerInfo},
+    PeerId,
+};
+use async_trait::async_trait;
+use config::{AuthorityUpdate, Committee, CommitteeUpdate, SharedCommittee, WorkerCacheUpdate};
+use crypto::{NetworkPublicKey, PublicKey, SignatureService};
+use futures::future::join_all;
+use mysten_metrics::spawn_monitored_task;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use tokio::{
+    sync::{broadcast::error::RecvError, watch},
+    task::JoinHandle,
+    time::MissedTickBehavior,
+};
+use tracing::{debug, info, warn};
+use types::{
+    metered_channel::Sender, NetworkKeyAnnouncement, NetworkKeys, NetworkKeysClient,
+    ReconfigureNotification, ReconfigureRequest, ReconfigureUpdate,
+};
+
+#[cfg(test)]
+#[path = "tests/network_keys_tests.rs"]
+mod network_keys_tests;
+
+/// Applies the network key rotations announced by the authorities of the committee, as in-place
+/// updates of the committee: the primary and its workers then authenticate the primary of the
+/// authority with its new network key, and send their messages to it.
+///
+/// The announcements are authenticated by the signature of the authority rather than by the
+/// network key of the peer, so they are served to any peer: the primary of the authority may
+/// announce its new key before switching to it, or after restarting with it.
+#[derive(Clone)]
+pub struct NetworkKeysHandler {
+    committee: SharedCommittee,
+    tx_state_handler: Sender<ReconfigureRequest>,
+    /// The sequence number of the last rotation applied, by authority.
+    sequences: Arc<Mutex<HashMap<PublicKey, u64>>>,
+}
+
+impl NetworkKeysHandler {
+    pub fn new(committee: SharedCommittee, tx_state_handler: Sender<ReconfigureRequest>) -> Self {
+        Self {
+            committee,
+            tx_state_handler,
+            sequences: Arc::default(),
+        }
+    }
+}
+
+#[async_trait]
+impl NetworkKeys for NetworkKeysHandler {
+    async fn announce_network_key(
+        &self,
+        request: anemo::Request<NetworkKeyAnnouncement>,
+    ) -> Result<anemo::Response<()>, anemo::rpc::Status> {
+        let network = request
+            .extensions()
+            .get::<anemo::NetworkRef>()
+            .and_then(anemo::NetworkRef::upgrade);
+        let announcement = request.into_body();
+        let committee = self.committee.load_full();
+        announcement.verify(&committee).map_err(|e| {
+            anemo::rpc::Status::new_with_message(
+                anemo::types::response::StatusCode::BadRequest,
+                format!("{e:?}"),
+            )
+        })?;
+
+        {
+            let mut sequences = self.sequences.lock().unwrap();
+            if let Some(last) = sequences.get(&announcement.authority) {
+                if announcement.sequence <= *last {
+                    debug!(
+                        "Ignoring stale network key announcement {} of {}",
+                        announcement.sequence, announcement.authority
+                    );
+                    return Ok(anemo::Response::new(()));
+                }
+            }
+            sequences.insert(announcement.authority.clone(), announcement.sequence);
+        }
+
+        let mut authority = committee.authorities[&announcement.authority].clone();
+        if authority.network_key == announcement.network_key {
+            return Ok(anemo::Response::new(()));
+        }
+        info!(
+            "Authority {} rotated its network key from {:?} to {:?}",
+            announcement.authority, authority.network_key, announcement.network_key
+        );
+
+        // Reach the primary of the authority under its new key.
+        if let Some(network) = network {
+            let old_peer_id = PeerId(authority.network_key.0.to_bytes());
+            let new_peer_id = PeerId(announcement.network_key.0.to_bytes());
+            network.known_peers().remove(&old_peer_id);
+            match network::multiaddr_to_address(&authority.primary_address) {
+                Ok(address) => {
+                    network.known_peers().insert(PeerInfo {
+                        peer_id: new_peer_id,
+                        affinity: anemo::types::PeerAffinity::High,
+                        address: vec![address],
+                    });
+                }
+                Err(e) => warn!(
+                    "Invalid address of authority {}: {e}",
+                    announcement.authority
+                ),
+            }
+        }
+
+        authority.network_key = announcement.network_key;
+        let update = ReconfigureUpdate {
+            committee: CommitteeUpdate {
+                epoch: committee.epoch(),
+                changes: vec![AuthorityUpdate::Update(announcement.authority, authority)],
+                protocol_config: None,
+            },
+            worker_cache: WorkerCacheUpdate {
+                epoch: committee.epoch(),
+                changes: Vec::new(),
+            },
+        };
+        self.tx_state_handler
+            .send(ReconfigureRequest::Update(update))
+            .await
+            .map_err(|e| anemo::rpc::Status::internal(e.to_string()))?;
+        Ok(anemo::Response::new(()))
+    }
+}
+
+/// Sends `announcement` to the connected primaries of the committee, returning how many of them
+/// accepted it. The primaries not reached learn the new key when they connect to the announcing
+/// one, as long as it announces it again.
+pub async fn announce_network_key(
+    network: &anemo::Network,
+    committee: &Committee,
+    announcement: NetworkKeyAnnouncement,
+) -> usize {
+    let requests = committee
+        .others_primaries(&announcement.authority)
+        .into_iter()
+        .filter_map(|(_, _, network_key)| network.peer(PeerId(network_key.0.to_bytes())))
+        .map(|peer| announce_network_key_to(peer, announcement.clone()));
+    join_all(requests)
+        .await
+        .into_iter()
+        .filter(|accepted| *accepted)
+        .count()
+}
+
+/// Sends `announcement` to `peer`, returning whether it accepted it.
+async fn announce_network_key_to(peer: anemo::Peer, announcement: NetworkKeyAnnouncement) -> bool {
+    match NetworkKeysClient::new(peer)
+        .announce_network_key(announcement)
+        .await
+    {
+        Ok(_) => true,
+        Err(e) => {
+            debug!("Failed to announce network key: {e:?}");
+            false
+        }
+    }
+}
+
+/// How often a primary announces its new network key until all the primaries accepted it.
+const ANNOUNCEMENT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Announces the rotation of the network key of the primary: when it starts with a network key
+/// other than the one the committee knows, e.g. after the operator generated a new one, it signs
+/// an announcement of the new key, applies it to its own committee and sends it to the other
+/// primaries until all of them accepted it.
+///
+/// The primaries only keep the rotations in memory, and one restarting within the epoch knows the
+/// old key from its committee again: once accepted, the announcement is sent again to every
+/// primary (re)connecting, until the end of the epoch.
+pub struct NetworkKeyAnnouncer {
+    name: PublicKey,
+    network_key: NetworkPublicKey,
+    committee: SharedCommittee,
+    signature_service: SignatureService,
+    handler: NetworkKeysHandler,
+    network: anemo::Network,
+    rx_reconfigure: watch::Receiver<ReconfigureNotification>,
+}
+
+impl NetworkKeyAnnouncer {
+    #[must_use]
+    pub fn spawn(
+        name: PublicKey,
+        network_key: NetworkPublicKey,
+        committee: SharedCommittee,
+        signature_service: SignatureService,
+        handler: NetworkKeysHandler,
+        network: anemo::Network,
+        rx_reconfigure: watch::Receiver<ReconfigureNotification>,
+    ) -> JoinHandle<()> {
+        spawn_monitored_task!(Self {
+            name,
+            network_key,
+            committee,
+            signature_service,
+            handler,
+            network,
+            rx_reconfigure,
+        }
+        .run())
+    }
+
+    async fn run(mut self) {
+        let Ok((mut peer_events, _)) = self.network.subscribe() else {
+            return;
+        };
+        let mut interval = tokio::time::interval(ANNOUNCEMENT_INTERVAL);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        let mut pending: Option<NetworkKeyAnnouncement> = None;
+        // The announcement all the primaries accepted, to send again to the reconnecting ones.
+        let mut accepted: Option<NetworkKeyAnnouncement> = None;
+        loop {
+            tokio::select! {
+                _ = interval.tick() => (),
+                event = peer_events.recv() => {
+                    match event {
+                        Ok(PeerEvent::NewPeer(peer_id)) => self.announce_again(&accepted, peer_id),
+                        Ok(PeerEvent::LostPeer(..)) => (),
+                        // Announce again to all the primaries, which may have missed the
+                        // connections of some.
+                        Err(RecvError::Lagged(_)) => pending = pending.or(accepted.take()),
+                        Err(RecvError::Closed) => return,
+                    }
+                    continue;
+                }
+                result = self.rx_reconfigure.changed() => {
+                    let shutdown = matches!(
+                        *self.rx_reconfigure.borrow(),
+                        ReconfigureNotification::Shutdown
+                    );
+                    if result.is_err() || shutdown {
+                        return;
+                    }
+                    continue;
+                }
+            }
+
+            let committee = self.committee.load_full();
+            let epoch = committee.epoch();
+            if pending.is_none() && accepted.as_ref().map(|a| a.epoch) == Some(epoch) {
+                continue;
+            }
+            let announcement = match pending.take() {
+                // The announcements are only accepted within their epoch.
+                Some(announcement) if announcement.epoch == epoch => announcement,
+                _ => {
+                    accepted = None;
+                    if committee.network_key(&self.name).ok().as_ref() == Some(&self.network_key) {
+                        // The committee of the epoch already knows the key.
+                        return;
+                    }
+                    self.announcement(&committee).await
+                }
+            };
+
+            let others = committee.others_primaries(&self.name).len();
+            let count = announce_network_key(&self.network, &committee, announcement.clone()).await;
+            if count == others {
+                info!(
+                    "All the primaries accepted the network key {:?} of {}",
+                    self.network_key, self.name
+                );
+                accepted = Some(announcement);
+                continue;
+            }
+            debug!("{count} of {others} primaries accepted our network key, retrying");
+            pending = Some(announcement);
+        }
+    }
+
+    /// Sends the `accepted` announcement of the epoch to the peer if it is a primary, which may
+    /// have restarted with the old key.
+    fn announce_again(&self, accepted: &Option<NetworkKeyAnnouncement>, peer_id: PeerId) {
+        let committee = self.committee.load();
+        let Some(announcement) = accepted.clone() else {
+            return;
+        };
+        let is_primary = committee
+            .others_primaries(&self.name)
+            .into_iter()
+            .any(|(_, _, network_key)| PeerId(network_key.0.to_bytes()) == peer_id);
+        if announcement.epoch != committee.epoch() || !is_primary {
+            return;
+        }
+        let Some(peer) = self.network.peer(peer_id) else {
+            return;
+        };
+        spawn_monitored_task!(async move {
+            announce_network_key_to(peer, announcement).await;
+        });
+    }
+
+    /// Signs the announcement of the key within the epoch of `committee`, and applies it to the
+    /// committee of the primary and its workers.
+    async fn announcement(&self, committee: &Committee) -> NetworkKeyAnnouncement {
+        // A primary restarted with another key announces it with a higher sequence number.
+        let sequence = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("The clock is after the unix epoch")
+            .as_millis() as u64;
+        let announcement = NetworkKeyAnnouncement::new(
+            self.name.clone(),
+            committee.epoch(),
+            sequence,
+            self.network_key.clone(),
+            &self.signature_service,
+        )
+        .await;
+        info!(
+            "Announcing the rotation of our network key to {:?}",
+            self.network_key
+        );
+        if let Err(e) = self
+            .handler
+            .announce_network_key(anemo::Request::new(announcement.clone()))
+            .await
+        {
+            warn!("Failed to apply the rotation of our network key: {e:?}");
+        }
+        announcement
+    }
+}
@@ -7,7 +7,8 @@ use crate::{
     certificate_fetcher::CertificateFetcher,
     core::Core,
     grpc_server::ConsensusAPIGrpc,
-    metrics::{initialise_metrics, PrimaryMetrics},
+    metrics::{initialise_metrics, PrimaryChannelMetrics, PrimaryMetrics},
+    network_keys::{NetworkKeyAnnouncer, NetworkKeysHandler},
     proposer::{OurDigestMessage, Proposer},
     state_handler::StateHandler,
     synchronizer::Synchronizer,
@@ -22,24 +23,34 @@ use anemo_tower::{
     trace::{DefaultMakeSpan, TraceLayer},
 };
 use async_trait::async_trait;
-use config::{Parameters, SharedCommittee, SharedWorkerCache, WorkerId, WorkerInfo};
+use config::{Epoch, Parameters, SharedCommittee, SharedWorkerCache, Stake, WorkerId, WorkerInfo};
 use consensus::dag::Dag;
-use crypto::{KeyPair, NetworkKeyPair, NetworkPublicKey, PublicKey, Signature};
+use crypto::{AsyncSigner, NetworkKeyPair, NetworkPublicKey, PublicKey, SignatureService};
 use dashmap::DashSet;
 use fastcrypto::{
     hash::Hash,
     traits::{EncodeDecodeBase64, KeyPair as _, ToFromBytes},
-    SignatureService,
 };
-use multiaddr::{Multiaddr, Protocol};
-use network::{failpoints::FailpointsMakeCallbackHandler, metrics::MetricsMakeCallbackHandler};
-use prometheus::Registry;
+use multiaddr::Multiaddr;
+use network::{
+    auth::{CommitteePeers, PeerGrace},
+    bandwidth::{EgressLimitLayer, EgressLimiter},
+    failpoints::FailpointsMakeCallbackHandler,
+    faults::FaultInjectionLayer,
+    handshake::{HandshakeGateLayer, HandshakeHandler, HandshakenPeers},
+    metrics::MetricsMakeCallbackHandler,
+    trace_id::{self, ExtractTraceIdLayer, PropagateTraceIdLayer},
+};
+use prometheus::{IntGaugeVec, Registry};
+use serde_json::json;
 use std::collections::HashMap;
 use std::{
     cmp::Reverse,
     collections::{BTreeMap, BTreeSet, BinaryHeap},
-    net::Ipv4Addr,
-    sync::Arc,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
     time::Duration,
 };
 use storage::{CertificateStore, PayloadToken, ProposerStore};
@@ -54,12 +65,13 @@ use types::{
     ensure,
     error::{DagError, DagResult},
     metered_channel::{channel_with_total, Receiver, Sender},
-    now, BatchDigest, Certificate, CertificateDigest, FetchCertificatesRequest,
-    FetchCertificatesResponse, GetCertificatesRequest, GetCertificatesResponse, Header,
-    HeaderDigest, PayloadAvailabilityRequest, PayloadAvailabilityResponse, PrimaryToPrimary,
+    now, AuditEvent, AuditLog, BatchDigest, Certificate, CertificateDigest, ComponentState,
+    FetchCertificatesRequest, FetchCertificatesResponse, GetCertificatesRequest,
+    GetCertificatesResponse, HandshakeServer, Header, HeaderDigest, NetworkKeysServer, NodeHealth,
+    PayloadAvailabilityRequest, PayloadAvailabilityResponse, PrimaryToPrimary,
     PrimaryToPrimaryServer, ReconfigureNotification, RequestVoteRequest, RequestVoteResponse,
-    Round, Vote, VoteInfo, WorkerInfoResponse, WorkerOthersBatchMessage, WorkerOurBatchMessage,
-    WorkerToPrimary, WorkerToPrimaryServer,
+    Round, StateDump, StorageMaintenance, Vote, VoteInfo, WorkerInfoResponse,
+    WorkerOthersBatchMessage, WorkerOurBatchMessage, WorkerToPrimary, WorkerToPrimaryServer,
 };
 
 #[cfg(any(test))]
@@ -85,7 +97,58 @@ impl Primary {
     #[allow(clippy::too_many_arguments)]
     pub fn spawn(
         name: PublicKey,
-        signer: KeyPair,
+        signer: impl AsyncSigner,
+        network_signer: NetworkKeyPair,
+        committee: SharedCommittee,
+        worker_cache: SharedWorkerCache,
+        parameters: Parameters,
+        header_store: Store<HeaderDigest, Header>,
+        certificate_store: CertificateStore,
+        proposer_store: ProposerStore,
+        payload_store: Store<(BatchDigest, WorkerId), PayloadToken>,
+        vote_digest_store: Store<PublicKey, VoteInfo>,
+        tx_new_certificates: Sender<Certificate>,
+        rx_committed_certificates: Receiver<(Round, Vec<Certificate>)>,
+        rx_consensus_round_updates: watch::Receiver<Round>,
+        dag: Option<Arc<Dag>>,
+        network_model: NetworkModel,
+        tx_reconfigure: watch::Sender<ReconfigureNotification>,
+        tx_committed_certificates: Sender<(Round, Vec<Certificate>)>,
+        registry: &Registry,
+        // See comments in Subscriber::spawn
+        tx_executor_network: Option<oneshot::Sender<anemo::Network>>,
+    ) -> Vec<JoinHandle<()>> {
+        Self::spawn_with_state_dump(
+            name,
+            signer,
+            network_signer,
+            committee,
+            worker_cache,
+            parameters,
+            header_store,
+            certificate_store,
+            proposer_store,
+            payload_store,
+            vote_digest_store,
+            tx_new_certificates,
+            rx_committed_certificates,
+            rx_consensus_round_updates,
+            dag,
+            network_model,
+            tx_reconfigure,
+            tx_committed_certificates,
+            registry,
+            tx_executor_network,
+            StateDump::default(),
+        )
+    }
+
+    /// Spawns the primary as [`Primary::spawn`] does, adding the sections of its components to
+    /// `state_dump`, which its admin server serves.
+    #[allow(clippy::too_many_arguments)]
+    pub fn spawn_with_state_dump(
+        name: PublicKey,
+        signer: impl AsyncSigner,
         network_signer: NetworkKeyPair,
         committee: SharedCommittee,
         worker_cache: SharedWorkerCache,
@@ -105,10 +168,22 @@ impl Primary {
         registry: &Registry,
         // See comments in Subscriber::spawn
         tx_executor_network: Option<oneshot::Sender<anemo::Network>>,
+        state_dump: StateDump,
     ) -> Vec<JoinHandle<()>> {
         // Write the parameters to the logs.
         parameters.tracing();
 
+        // Running rules other than the ones of the rest of the committee would make us diverge.
+        let protocol_config = committee.load().protocol_config;
+        if let Err(e) = protocol_config.validate() {
+            panic!("Cannot run the committee protocol: {e}");
+        }
+        info!(
+            "Running protocol version {} with {} signatures",
+            protocol_config.version,
+            crypto::SIGNATURE_SCHEME
+        );
+
         // Some info statements
         info!(
             "Boot primary node with peer id {} and public key {}",
@@ -126,22 +201,24 @@ impl Primary {
         let network_connection_metrics = metrics.network_connection_metrics.unwrap();
 
         let (tx_our_digests, rx_our_digests) = channel_with_total(
-            CHANNEL_CAPACITY,
+            parameters.memory_budget.channel_capacity("tx_our_digests"),
             &primary_channel_metrics.tx_our_digests,
             &primary_channel_metrics.tx_our_digests_total,
         );
         let (tx_parents, rx_parents) = channel_with_total(
-            CHANNEL_CAPACITY,
+            parameters.memory_budget.channel_capacity("tx_parents"),
             &primary_channel_metrics.tx_parents,
             &primary_channel_metrics.tx_parents_total,
         );
         let (tx_headers, rx_headers) = channel_with_total(
-            CHANNEL_CAPACITY,
+            parameters.memory_budget.channel_capacity("tx_headers"),
             &primary_channel_metrics.tx_headers,
             &primary_channel_metrics.tx_headers_total,
         );
         let (tx_certificate_fetcher, rx_certificate_fetcher) = channel_with_total(
-            CHANNEL_CAPACITY,
+            parameters
+                .memory_budget
+                .channel_capacity("tx_certificate_fetcher"),
             &primary_channel_metrics.tx_certificate_fetcher,
             &primary_channel_metrics.tx_certificate_fetcher_total,
         );
@@ -151,22 +228,28 @@ impl Primary {
             &primary_channel_metrics.tx_certificates_loopback_total,
         );
         let (tx_certificates, rx_certificates) = channel_with_total(
-            CHANNEL_CAPACITY,
+            parameters.memory_budget.channel_capacity("tx_certificates"),
             &primary_channel_metrics.tx_certificates,
             &primary_channel_metrics.tx_certificates_total,
         );
         let (tx_block_synchronizer_commands, rx_block_synchronizer_commands) = channel_with_total(
-            CHANNEL_CAPACITY,
+            parameters
+                .memory_budget
+                .channel_capacity("tx_block_synchronizer_commands"),
             &primary_channel_metrics.tx_block_synchronizer_commands,
             &primary_channel_metrics.tx_block_synchronizer_commands_total,
         );
         let (tx_state_handler, rx_state_handler) = channel_with_total(
-            CHANNEL_CAPACITY,
+            parameters
+                .memory_budget
+                .channel_capacity("tx_state_handler"),
             &primary_channel_metrics.tx_state_handler,
             &primary_channel_metrics.tx_state_handler_total,
         );
         let (tx_committed_own_headers, rx_committed_own_headers) = channel_with_total(
-            CHANNEL_CAPACITY,
+            parameters
+                .memory_budget
+                .channel_capacity("tx_commited_own_headers"),
             &primary_channel_metrics.tx_commited_own_headers,
             &primary_channel_metrics.tx_commited_own_headers_total,
         );
@@ -183,6 +266,15 @@ impl Primary {
         primary_channel_metrics
             .replace_registered_new_certificates_metric(registry, Box::new(new_certificates_gauge));
 
+        let audit_log = AuditLog::open(&name, &parameters.audit_log);
+        Self::register_state_dump(
+            &state_dump,
+            committee.clone(),
+            primary_channel_metrics.clone(),
+            node_metrics.clone(),
+            rx_consensus_round_updates.clone(),
+        );
+
         let (tx_narwhal_round_updates, rx_narwhal_round_updates) = watch::channel(0u64);
 
         let synchronizer = Arc::new(Synchronizer::new(
@@ -211,9 +303,7 @@ impl Primary {
             .load()
             .primary(&name)
             .expect("Our public key or worker id is not in the committee");
-        let address = address
-            .replace(0, |_protocol| Some(Protocol::Ip4(Ipv4Addr::UNSPECIFIED)))
-            .unwrap();
+        let address = network::listen_address(&address, parameters.network.dual_stack);
         let primary_service = PrimaryToPrimaryServer::new(PrimaryReceiverHandler {
             name: name.clone(),
             committee: committee.clone(),
@@ -228,6 +318,7 @@ impl Primary {
             rx_narwhal_round_updates: rx_narwhal_round_updates.clone(),
             metrics: node_metrics.clone(),
             request_vote_inflight: Arc::new(DashSet::new()),
+            audit_log: audit_log.clone(),
         });
         let worker_service = WorkerToPrimaryServer::new(WorkerReceiverHandler {
             tx_our_digests,
@@ -250,40 +341,83 @@ impl Primary {
                 our_worker_peer_ids,
             )));
 
+        let handshake_service = HandshakeServer::new(HandshakeHandler::new(
+            committee.clone(),
+            parameters.chain_id.clone(),
+        ));
+
+        let network_keys_handler =
+            NetworkKeysHandler::new(committee.clone(), tx_state_handler.clone());
+        let network_keys_service = NetworkKeysServer::new(network_keys_handler.clone());
+
+        // Only the peers of the committee are served, except for the announcements of network
+        // keys: they are signed by the authorities, and come from the keys not yet known.
+        let committee_peers = CommitteePeers::new(
+            committee.clone(),
+            worker_cache.clone(),
+            PeerGrace::of_authority(&name),
+        );
         let routes = anemo::Router::new()
             .add_rpc_service(primary_service)
-            .merge(worker_to_primary_router);
+            .add_rpc_service(handshake_service)
+            .merge(worker_to_primary_router)
+            .route_layer(RequireAuthorizationLayer::new(committee_peers.clone()))
+            .add_rpc_service(network_keys_service);
+
+        // The responses and the requests of the node share its egress bandwidth.
+        let egress_limiter = EgressLimiter::new(
+            parameters.network.egress_bandwidth,
+            parameters.network.sync_egress_bandwidth_per_peer,
+            network_connection_metrics.clone(),
+        );
 
         let service = ServiceBuilder::new()
             .layer(
                 TraceLayer::new_for_server_errors()
                     .make_span_with(DefaultMakeSpan::new().level(tracing::Level::INFO)),
             )
+            .layer(ExtractTraceIdLayer::new())
+            .layer(EgressLimitLayer::inbound(egress_limiter.clone()))
             .layer(CallbackLayer::new(MetricsMakeCallbackHandler::new(
                 inbound_network_metrics,
             )))
             .layer(CallbackLayer::new(FailpointsMakeCallbackHandler::new()))
             .service(routes);
 
+        // The requests to a peer are held until its handshake succeeds.
+        let handshaken_peers = HandshakenPeers::new();
         let outbound_layer = ServiceBuilder::new()
             .layer(
                 TraceLayer::new_for_client_and_server_errors()
                     .make_span_with(DefaultMakeSpan::new().level(tracing::Level::INFO)),
             )
+            .layer(PropagateTraceIdLayer::new())
+            .layer(HandshakeGateLayer::new(handshaken_peers.clone()))
+            .layer(EgressLimitLayer::outbound(egress_limiter))
             .layer(CallbackLayer::new(MetricsMakeCallbackHandler::new(
                 outbound_network_metrics,
             )))
             .layer(CallbackLayer::new(FailpointsMakeCallbackHandler::new()))
+            .layer(FaultInjectionLayer::new(PeerId(
+                network_signer.public().0.to_bytes(),
+            )))
             .into_inner();
 
         let anemo_config = {
+            let network_parameters = &parameters.network;
             let mut quic_config = anemo::QuicConfig::default();
-            // Enable keep alives every 5s
-            quic_config.keep_alive_interval_ms = Some(5_000);
+            quic_config.keep_alive_interval_ms =
+                Some(network_parameters.keep_alive_interval.as_millis() as u64);
+            quic_config.max_idle_timeout_ms = network_parameters
+                .idle_timeout
+                .map(|timeout| timeout.as_millis() as u64);
             let mut config = anemo::Config::default();
             config.quic = Some(quic_config);
-            // Set a default timeout of 30s for all outbound RPC requests
-            config.outbound_request_timeout_ms = Some(30_000);
+            // The timeout of all outbound RPC requests
+            config.outbound_request_timeout_ms =
+                Some(network_parameters.request_timeout.as_millis() as u64);
+            // Bounds the size of both the sent and received messages.
+            config.max_frame_size = Some(network_parameters.max_primary_message_size);
             config
         };
 
@@ -343,23 +477,61 @@ impl Primary {
 
         let connection_monitor_handle = network::connectivity::ConnectionMonitor::spawn(
             network.downgrade(),
-            network_connection_metrics,
+            network_connection_metrics.clone(),
             peer_types,
         );
 
+        let handshake_monitor_handle = network::handshake::HandshakeMonitor::spawn(
+            network.downgrade(),
+            committee.clone(),
+            committee_peers,
+            parameters.chain_id.clone(),
+            network_connection_metrics,
+            handshaken_peers,
+        );
+
+        let dns_refresher_handle = network::dns::DnsRefresher::spawn(
+            network.downgrade(),
+            committee.clone(),
+            worker_cache.clone(),
+            parameters.dns_refresh_interval,
+            tx_reconfigure.subscribe(),
+        );
+
+        // Announces our network key to the other primaries if it was rotated.
+        let network_key_announcer_handle = NetworkKeyAnnouncer::spawn(
+            name.clone(),
+            network_signer.public().clone(),
+            committee.clone(),
+            signature_service.clone(),
+            network_keys_handler,
+            network.clone(),
+            tx_reconfigure.subscribe(),
+        );
+
         info!(
-            "Primary {} listening to network admin messages on 127.0.0.1:{}",
+            "Primary {} listening to network admin messages on {}",
             name.encode_base64(),
-            parameters
-                .network_admin_server
-                .primary_network_admin_server_port
+            parameters.network_admin_server.primary_address()
+        );
+
+        let health = NodeHealth::of_authority(&name);
+        Self::register_health(
+            &health,
+            name.clone(),
+            committee.clone(),
+            worker_cache.clone(),
+            network.downgrade(),
         );
 
         let admin_handles = network::admin::start_admin_server(
-            parameters
-                .network_admin_server
-                .primary_network_admin_server_port,
+            parameters.network_admin_server.primary_address(),
+            parameters.network_admin_server.auth.clone(),
             network.clone(),
+            worker_cache.clone(),
+            state_dump.clone(),
+            health,
+            StorageMaintenance::of_authority(&name),
             tx_reconfigure.subscribe(),
             Some(tx_state_handler),
         );
@@ -370,7 +542,7 @@ impl Primary {
             }
         }
 
-        let core_handle = Core::spawn(
+        let core_handle = Core::spawn_with_audit_log(
             name.clone(),
             (**committee.load()).clone(),
             worker_cache.clone(),
@@ -389,6 +561,7 @@ impl Primary {
             tx_parents,
             node_metrics.clone(),
             network.clone(),
+            audit_log.clone(),
         );
 
         let block_synchronizer_handler = Arc::new(BlockSynchronizerHandler::new(
@@ -415,6 +588,7 @@ impl Primary {
             payload_store.clone(),
             certificate_store.clone(),
             parameters.clone(),
+            &state_dump,
         );
 
         // The `CertificateFetcher` waits to receive all the ancestors of a certificate before looping it back to the
@@ -463,6 +637,7 @@ impl Primary {
             tx_reconfigure,
             Some(tx_committed_own_headers),
             network.clone(),
+            audit_log.clone(),
         );
 
         let consensus_api_handle = if !internal_consensus {
@@ -498,6 +673,7 @@ impl Primary {
                 block_synchronizer_handler,
                 dag,
                 committee.clone(),
+                parameters.network.clone(),
                 endpoint_metrics,
             ))
         } else {
@@ -521,6 +697,9 @@ impl Primary {
             proposer_handle,
             state_handler_handle,
             connection_monitor_handle,
+            handshake_monitor_handle,
+            dns_refresher_handle,
+            network_key_announcer_handle,
         ];
 
         handles.extend(admin_handles);
@@ -532,6 +711,125 @@ impl Primary {
         handles
     }
 
+    /// Adds the sections of the primary to the state dump of the node, from the gauges the
+    /// components maintain at the current epoch.
+    fn register_state_dump(
+        state_dump: &StateDump,
+        committee: SharedCommittee,
+        channel_metrics: PrimaryChannelMetrics,
+        metrics: Arc<PrimaryMetrics>,
+        rx_consensus_round_updates: watch::Receiver<Round>,
+    ) {
+        state_dump.register("channels", move || json!(channel_metrics.depths()));
+
+        let (proposer_committee, proposer_metrics) = (committee.clone(), metrics.clone());
+        state_dump.register("proposer", move || {
+            let m = &proposer_metrics;
+            let mut section = gauge_section(
+                proposer_committee.load().epoch(),
+                &[
+                    ("round", &m.current_round),
+                    ("proposed_header_round", &m.proposed_header_round),
+                    ("pending_batches", &m.num_of_pending_batches_in_proposer),
+                ],
+            );
+            section["votes_received_last_round"] = json!(m.votes_received_last_round.get());
+            section
+        });
+
+        state_dump.register("core", move || {
+            let m = &metrics;
+            gauge_section(
+                committee.load().epoch(),
+                &[
+                    ("highest_received_round", &m.highest_received_round),
+                    ("highest_processed_round", &m.highest_processed_round),
+                    ("last_parent_missing_round", &m.last_parent_missing_round),
+                    ("certificate_created_round", &m.certificate_created_round),
+                    // Whether missing parents of certificates are being synchronized.
+                    (
+                        "inflight_certificate_fetch",
+                        &m.certificate_fetcher_inflight_fetch,
+                    ),
+                ],
+            )
+        });
+
+        state_dump.register(
+            "consensus",
+            move || json!({ "last_committed_round": *rx_consensus_round_updates.borrow() }),
+        );
+    }
+
+    /// Adds the probes of the primary and of its workers to the health of the node: the primary
+    /// runs once connected to a quorum of the committee, and is degraded when it loses it, and
+    /// each worker runs while connected to the primary.
+    fn register_health(
+        health: &NodeHealth,
+        name: PublicKey,
+        committee: SharedCommittee,
+        worker_cache: SharedWorkerCache,
+        network: anemo::NetworkRef,
+    ) {
+        // The probes must not keep the network alive once the primary shut down.
+        let (primary_name, primary_network) = (name.clone(), network.clone());
+        let reached_quorum = AtomicBool::new(false);
+        health.register("primary", true, move || {
+            let Some(network) = primary_network.upgrade() else {
+                return ComponentState::Down;
+            };
+            let committee = committee.load();
+            let connected_stake: Stake = committee
+                .authorities()
+                .filter(|(authority, info)| {
+                    **authority == primary_name
+                        || network
+                            .peer(PeerId(info.network_key.0.to_bytes()))
+                            .is_some()
+                })
+                .map(|(_, info)| info.stake)
+                .sum();
+            if connected_stake >= committee.quorum_threshold() {
+                reached_quorum.store(true, Ordering::Relaxed);
+                ComponentState::Running
+            } else if reached_quorum.load(Ordering::Relaxed) {
+                ComponentState::Degraded
+            } else {
+                ComponentState::Starting
+            }
+        });
+
+        let worker_ids: Vec<_> = worker_cache
+            .load()
+            .workers
+            .get(&name)
+            .map(|index| index.0.keys().copied().collect())
+            .unwrap_or_default();
+        for id in worker_ids {
+            let (name, worker_cache, network) =
+                (name.clone(), worker_cache.clone(), network.clone());
+            let connected_once = AtomicBool::new(false);
+            health.register(format!("worker-{id}"), false, move || {
+                let connected = worker_cache
+                    .load()
+                    .worker(&name, &id)
+                    .ok()
+                    .zip(network.upgrade())
+                    .map_or(false, |(info, network)| {
+                        network.peer(PeerId(info.name.0.to_bytes())).is_some()
+                    });
+                if connected {
+                    connected_once.store(true, Ordering::Relaxed);
+                    ComponentState::Running
+                } else if connected_once.load(Ordering::Relaxed) {
+                    ComponentState::Down
+                } else {
+                    ComponentState::Starting
+                }
+            });
+        }
+    }
+
     fn add_peer_in_network(
         network: &Network,
         peer_name: NetworkPublicKey,
@@ -550,6 +848,22 @@ impl Primary {
     }
 }
 
+/// The values of `gauges` at `epoch`, by name, in a section of the state dump.
+fn gauge_section(epoch: Epoch, gauges: &[(&str, &IntGaugeVec)]) -> serde_json::Value {
+    let label = epoch.to_string();
+    let mut section: serde_json::Map<_, _> = gauges
+        .iter()
+        .map(|(name, gauge)| {
+            (
+                name.to_string(),
+                json!(gauge.with_label_values(&[&label]).get()),
+            )
+        })
+        .collect();
+    section.insert("epoch".to_string(), json!(epoch));
+    serde_json::Value::Object(section)
+}
+
 /// Defines how the network receiver handles incoming primary messages.
 #[derive(Clone)]
 struct PrimaryReceiverHandler {
@@ -559,7 +873,7 @@ struct PrimaryReceiverHandler {
     worker_cache: SharedWorkerCache,
     synchronizer: Arc<Synchronizer>,
     /// Service to sign headers.
-    signature_service: SignatureService<Signature, { crypto::DIGEST_LENGTH }>,
+    signature_service: SignatureService,
     tx_certificates: Sender<(Certificate, Option<oneshot::Sender<DagResult<()>>>)>,
     header_store: Store<HeaderDigest, Header>,
     certificate_store: CertificateStore,
@@ -571,6 +885,8 @@ struct PrimaryReceiverHandler {
     metrics: Arc<PrimaryMetrics>,
     /// Used to ensure a maximum of one inflight vote request per header.
     request_vote_inflight: Arc<DashSet<PublicKey>>,
+    /// The log of the votes sent.
+    audit_log: AuditLog,
 }
 
 #[allow(clippy::result_large_err)]
@@ -611,6 +927,10 @@ impl PrimaryReceiverHandler {
         let header = &request.body().header;
         let committee = self.committee.load();
         header.verify(&committee, self.worker_cache.clone())?;
+        // The commit of the header joins the trace under which it was proposed.
+        if let Some(id) = trace_id::current() {
+            trace_id::record(header.digest(), id);
+        }
 
         // Vote request must come from the Header's author.
         let peer_id = request
@@ -720,7 +1040,7 @@ impl PrimaryReceiverHandler {
             stake += committee.stake(&parent.origin());
         }
         ensure!(
-            stake >= committee.quorum_threshold(),
+            committee.reached_quorum(stake),
             DagError::HeaderRequiresQuorum(header.digest())
         );
 
@@ -817,6 +1137,7 @@ impl PrimaryReceiverHandler {
                 },
             )
             .await?;
+        self.audit_log.record(AuditEvent::vote_sent(header));
 
         Ok(RequestVoteResponse {
             vote: Some(vote),
@@ -1083,6 +1404,7 @@ impl WorkerToPrimary for WorkerReceiverHandler {
                 digest: message.digest,
                 worker_id: message.worker_id,
                 timestamp: message.metadata.created_at,
+                trace_id: trace_id::current(),
                 ack_channel: tx_ack,
             })
             .await
@@ -3,10 +3,11 @@
 // SPDX-License-Identifier: Apache-2.0
 use crate::{metrics::PrimaryMetrics, NetworkModel};
 use config::{Committee, Epoch, WorkerId};
-use crypto::{PublicKey, Signature};
-use fastcrypto::{hash::Hash as _, SignatureService};
+use crypto::{PublicKey, SignatureService};
+use fastcrypto::hash::Hash as _;
 use mysten_metrics::spawn_logged_monitored_task;
-use std::collections::BTreeMap;
+use network::trace_id::TraceId;
+use std::collections::{BTreeMap, HashMap};
 use std::{cmp::Ordering, sync::Arc};
 use storage::ProposerStore;
 use tokio::time::Instant;
@@ -29,6 +30,8 @@ pub struct OurDigestMessage {
     pub digest: BatchDigest,
     pub worker_id: WorkerId,
     pub timestamp: TimestampMs,
+    /// The trace id under which the worker reported the batch, if any.
+    pub trace_id: Option<TraceId>,
     /// A channel to send an () as an ack after this digest is processed by the primary.
     pub ack_channel: oneshot::Sender<()>,
 }
@@ -46,7 +49,7 @@ pub struct Proposer {
     /// The committee information.
     committee: Committee,
     /// Service to sign headers.
-    signature_service: SignatureService<Signature, { crypto::DIGEST_LENGTH }>,
+    signature_service: SignatureService,
     /// The threshold number of batches that can trigger
     /// a header creation. When there are available at least
     /// `header_num_of_batches_threshold` batches we are ok
@@ -70,7 +73,7 @@ pub struct Proposer {
     /// Receives the batches' digests from our workers.
     rx_our_digests: Receiver<OurDigestMessage>,
     /// Sends newly created headers to the `Core`.
-    tx_headers: Sender<Header>,
+    tx_headers: Sender<(Header, TraceId)>,
 
     /// The proposer store for persisting the last header.
     proposer_store: ProposerStore,
@@ -84,6 +87,8 @@ pub struct Proposer {
     last_leader: Option<Certificate>,
     /// Holds the batches' digests waiting to be included in the next header.
     digests: Vec<(BatchDigest, WorkerId, TimestampMs)>,
+    /// The trace ids of the batches waiting to be included in the next header.
+    trace_ids: HashMap<BatchDigest, TraceId>,
 
     /// Holds the map of proposed previous round headers, used to ensure that
     /// all batches' digest included will eventually be re-sent.
@@ -102,7 +107,7 @@ impl Proposer {
     pub fn spawn(
         name: PublicKey,
         committee: Committee,
-        signature_service: SignatureService<Signature, { crypto::DIGEST_LENGTH }>,
+        signature_service: SignatureService,
         proposer_store: ProposerStore,
         header_num_of_batches_threshold: usize,
         max_header_num_of_batches: usize,
@@ -112,7 +117,7 @@ impl Proposer {
         rx_reconfigure: watch::Receiver<ReconfigureNotification>,
         rx_parents: Receiver<(Vec<Certificate>, Round, Epoch)>,
         rx_our_digests: Receiver<OurDigestMessage>,
-        tx_headers: Sender<Header>,
+        tx_headers: Sender<(Header, TraceId)>,
         tx_narwhal_round_updates: watch::Sender<Round>,
         rx_commited_own_headers: Receiver<(Round, Vec<Round>)>,
         metrics: Arc<PrimaryMetrics>,
@@ -139,6 +144,7 @@ impl Proposer {
                     last_parents: genesis,
                     last_leader: None,
                     digests: Vec::with_capacity(2 * max_header_num_of_batches),
+                    trace_ids: HashMap::new(),
                     proposed_headers: BTreeMap::new(),
                     rx_commited_own_headers,
                     metrics,
@@ -152,13 +158,15 @@ impl Proposer {
 
     /// make_header creates a new Header, persists it to database
     /// and sends it to core for processing. If successful, it returns
-    /// the number of batch digests included in header.
-    async fn make_header(&mut self) -> DagResult<(Header, usize)> {
+    /// its trace id and the number of batch digests included in header.
+    async fn make_header(&mut self) -> DagResult<(Header, TraceId, usize)> {
         // Make a new header.
         let header = self.create_new_header().await?;
 
         // Store the last header.
+        fail::fail_point!("proposer-before-store-header");
         self.proposer_store.write_last_proposed(&header)?;
+        fail::fail_point!("proposer-after-store-header");
 
         #[cfg(feature = "benchmark")]
         for digest in header.payload.keys() {
@@ -167,14 +175,33 @@ impl Proposer {
         }
 
         let num_of_included_digests = header.payload.len();
+        let trace_id = self.header_trace_id(&header);
 
         // Send the new header to the `Core` that will broadcast and process it.
         self.tx_headers
-            .send(header.clone())
+            .send((header.clone(), trace_id.clone()))
             .await
             .map_err(|_| DagError::ShuttingDown)?;
 
-        Ok((header, num_of_included_digests))
+        Ok((header, trace_id, num_of_included_digests))
+    }
+
+    /// The trace id under which the header is proposed, i.e. the one of its first traced batch,
+    /// logged along the trace ids of all its batches.
+    fn header_trace_id(&mut self, header: &Header) -> TraceId {
+        let trace_ids: Vec<_> = header
+            .payload
+            .keys()
+            .filter_map(|digest| self.trace_ids.remove(digest))
+            .collect();
+        let trace_id = trace_ids.first().cloned().unwrap_or_else(TraceId::generate);
+        debug!(
+            %trace_id,
+            "Header {} contains the batches traced as {:?}",
+            header.digest(),
+            trace_ids.iter().map(TraceId::as_str).collect::<Vec<_>>()
+        );
+        trace_id
     }
 
     // Creates a new header. Also the method ensures we are protected against equivocation.
@@ -301,7 +328,7 @@ impl Proposer {
             }
         }
 
-        let mut enough_votes = votes_for_leader >= self.committee.validity_threshold();
+        let mut enough_votes = self.committee.reached_validity(votes_for_leader);
         if enough_votes {
             if let Some(leader) = self.last_leader.as_ref() {
                 debug!(
@@ -311,7 +338,7 @@ impl Proposer {
                 );
             }
         }
-        enough_votes |= no_votes >= self.committee.quorum_threshold();
+        enough_votes |= self.committee.reached_quorum(no_votes);
         enough_votes
     }
 
@@ -382,7 +409,7 @@ impl Proposer {
                 match self.make_header().await {
                     Err(e @ DagError::ShuttingDown) => debug!("{e}"),
                     Err(e) => panic!("Unexpected error: {e}"),
-                    Ok((header, digests)) => {
+                    Ok((header, trace_id, digests)) => {
                         let reason = if timer_expired {
                             "timeout"
                         } else {
@@ -390,7 +417,7 @@ impl Proposer {
                         };
 
                         // Save the header
-                        opt_latest_header = Some(header);
+                        opt_latest_header = Some((header, trace_id));
                         header_repeat_timer = Box::pin(sleep(header_resend_timeout));
 
                         self.metrics
@@ -411,10 +438,10 @@ impl Proposer {
                 () = &mut header_repeat_timer => {
                     // If the round has not advanced within header_resend_timeout then try to
                     // re-process our own header.
-                    if let Some(header) = &opt_latest_header {
-                        debug!("resend header {:?}", header);
+                    if let Some((header, trace_id)) = &opt_latest_header {
+                        debug!(%trace_id, "resend header {:?}", header);
 
-                        if let Err(err) = self.tx_headers.send(header.clone()).await.map_err(|_| DagError::ShuttingDown) {
+                        if let Err(err) = self.tx_headers.send((header.clone(), trace_id.clone())).await.map_err(|_| DagError::ShuttingDown) {
                             error!("failed to resend header {:?} : {:?}", header, err);
                         }
 
@@ -536,10 +563,14 @@ impl Proposer {
                     digest,
                     worker_id,
                     timestamp,
+                    trace_id,
                     ack_channel,
                 }) = self.rx_our_digests.recv() => {
                     let digest_record = (digest, worker_id, timestamp, );
                     self.digests.push(digest_record);
+                    if let Some(trace_id) = trace_id {
+                        self.trace_ids.insert(digest, trace_id);
+                    }
                     // Signal back to the worker that the batch is recorded on the
                     // primary, and will be tracked until inclusion. This means that
                     // if the primary does not fail it will attempt to send the digest
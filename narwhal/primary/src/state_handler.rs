@@ -11,7 +11,8 @@ use tokio::{sync::watch, task::JoinHandle};
 use tracing::{debug, error, info, warn};
 use types::{
     metered_channel::{Receiver, Sender},
-    Certificate, ReconfigureNotification, Round, WorkerReconfigureMessage,
+    AuditEvent, AuditLog, Certificate, ReconfigureNotification, ReconfigureRequest,
+    ReconfigureUpdate, Round, WorkerReconfigureMessage,
 };
 
 /// Receives the highest round reached by consensus and update it for all tasks.
@@ -24,27 +25,31 @@ pub struct StateHandler {
     worker_cache: SharedWorkerCache,
     /// Receives the ordered certificates from consensus.
     rx_committed_certificates: Receiver<(Round, Vec<Certificate>)>,
-    /// Receives notifications to reconfigure the system.
-    rx_state_handler: Receiver<ReconfigureNotification>,
+    /// Receives requests to reconfigure the system.
+    rx_state_handler: Receiver<ReconfigureRequest>,
     /// Channel to signal committee changes.
     tx_reconfigure: watch::Sender<ReconfigureNotification>,
     /// A channel to update the committed rounds
     tx_commited_own_headers: Option<Sender<(Round, Vec<Round>)>>,
 
     network: anemo::Network,
+    /// The log of the reconfigurations.
+    audit_log: AuditLog,
 }
 
 impl StateHandler {
+    #[allow(clippy::too_many_arguments)]
     #[must_use]
     pub fn spawn(
         name: PublicKey,
         committee: SharedCommittee,
         worker_cache: SharedWorkerCache,
         rx_committed_certificates: Receiver<(Round, Vec<Certificate>)>,
-        rx_state_handler: Receiver<ReconfigureNotification>,
+        rx_state_handler: Receiver<ReconfigureRequest>,
         tx_reconfigure: watch::Sender<ReconfigureNotification>,
         tx_commited_own_headers: Option<Sender<(Round, Vec<Round>)>>,
         network: anemo::Network,
+        audit_log: AuditLog,
     ) -> JoinHandle<()> {
         spawn_logged_monitored_task!(
             async move {
@@ -57,6 +62,7 @@ impl StateHandler {
                     tx_reconfigure,
                     tx_commited_own_headers,
                     network,
+                    audit_log,
                 }
                 .run()
                 .await;
@@ -121,6 +127,35 @@ impl StateHandler {
         tracing::debug!("Committee updated to {}", self.committee);
     }
 
+    /// Applies the changes of `update` on top of the current committee and worker cache, and
+    /// returns the notification to broadcast. Returns `None` (and changes nothing) if the update
+    /// is invalid.
+    fn apply_update(&mut self, update: ReconfigureUpdate) -> Option<ReconfigureNotification> {
+        let current_epoch = self.committee.load().epoch();
+        let committee = self
+            .committee
+            .load()
+            .apply(&update.committee)
+            .tap_err(|errors| warn!("Rejecting committee update: {errors:?}"))
+            .ok()?;
+        let worker_cache = self
+            .worker_cache
+            .load()
+            .apply(&update.worker_cache)
+            .tap_err(|errors| warn!("Rejecting worker cache update: {errors:?}"))
+            .ok()?;
+
+        // The worker cache needs to be in place before the committee is updated, so the new
+        // members keep their worker information.
+        self.worker_cache.swap(Arc::new(worker_cache));
+
+        if committee.epoch() > current_epoch {
+            Some(ReconfigureNotification::NewEpoch(committee))
+        } else {
+            Some(ReconfigureNotification::UpdateCommittee(committee))
+        }
+    }
+
     fn notify_our_workers(
         &mut self,
         message: ReconfigureNotification,
@@ -149,7 +184,31 @@ impl StateHandler {
                     self.handle_sequenced(commit_round, certificates).await;
                 },
 
-                Some(message) = self.rx_state_handler.recv() => {
+                Some(request) = self.rx_state_handler.recv() => {
+                    let message = match request {
+                        ReconfigureRequest::Notification(message) => message,
+                        ReconfigureRequest::Update(update) => match self.apply_update(update) {
+                            Some(message) => message,
+                            None => continue,
+                        },
+                    };
+
+                    let (epoch, notification) = match &message {
+                        ReconfigureNotification::NewEpoch(committee) => {
+                            (committee.epoch(), "NewEpoch")
+                        }
+                        ReconfigureNotification::UpdateCommittee(committee) => {
+                            (committee.epoch(), "UpdateCommittee")
+                        }
+                        ReconfigureNotification::Shutdown => {
+                            (self.committee.load().epoch(), "Shutdown")
+                        }
+                    };
+                    self.audit_log.record(AuditEvent::Reconfigured {
+                        epoch,
+                        notification: notification.to_string(),
+                    });
+
                     // Notify our workers
                     let notify_handlers = self.notify_our_workers(message.to_owned());
 
@@ -6,6 +6,7 @@ use crate::{
     BlockWaiter,
 };
 use anemo::PeerId;
+use bytes::Bytes;
 use crypto::traits::KeyPair as _;
 use fastcrypto::hash::Hash;
 use mockall::*;
@@ -55,7 +56,10 @@ async fn test_successfully_retrieve_block() {
             .withf(move |request| request.body().batch == batch_digest)
             .returning(|_| {
                 Ok(anemo::Response::new(RequestBatchResponse {
-                    batch: Some(Batch::new(vec![vec![10u8, 5u8, 2u8], vec![8u8, 2u8, 3u8]])),
+                    batch: Some(Batch::new(vec![
+                        Bytes::from(vec![10u8, 5u8, 2u8]),
+                        Bytes::from(vec![8u8, 2u8, 3u8]),
+                    ])),
                 }))
             });
     }
@@ -7,8 +7,8 @@ use crate::{
 use anemo::async_trait;
 use anyhow::Result;
 use config::{Epoch, WorkerId};
-use crypto::{PublicKey, Signature};
-use fastcrypto::{hash::Hash, traits::KeyPair, SignatureService};
+use crypto::{PublicKey, Signature, SignatureService};
+use fastcrypto::{hash::Hash, traits::KeyPair};
 use indexmap::IndexMap;
 use itertools::Itertools;
 use once_cell::sync::OnceCell;
@@ -10,7 +10,7 @@ use rand::{
     SeedableRng,
 };
 use std::num::NonZeroUsize;
-use test_utils::CommitteeFixture;
+use test_utils::{skewed_stakes, CommitteeFixture};
 use types::{Certificate, Vote};
 
 #[test]
@@ -119,6 +119,68 @@ fn test_unknown_signature_in_certificate() {
     assert!(Certificate::new(&committee, header, signatures).is_err());
 }
 
+#[test]
+fn test_certificate_with_dominant_authority() {
+    // The first authority holds 40% of the stake: it needs the votes of two other authorities to
+    // reach the quorum of 7.
+    let fixture = CommitteeFixture::builder().stakes(vec![4, 2, 2, 2]).build();
+    let committee = fixture.committee();
+    let header = fixture.header();
+    assert_eq!(committee.quorum_threshold(), 7);
+    assert_eq!(fixture.quorum().len(), 3);
+
+    let votes: Vec<_> = fixture
+        .authorities()
+        .map(|authority| {
+            let vote = authority.vote(&header);
+            (vote.author, vote.signature)
+        })
+        .collect();
+    assert!(Certificate::new(&committee, header.clone(), votes[..2].to_vec()).is_err());
+    // The three authorities of small stake together do not reach the quorum either.
+    assert!(Certificate::new(&committee, header.clone(), votes[1..].to_vec()).is_err());
+
+    let certificate = Certificate::new(&committee, header, votes[..3].to_vec()).unwrap();
+    assert!(certificate
+        .verify(&committee, fixture.worker_cache().into())
+        .is_ok());
+}
+
+#[test]
+fn test_certificate_batch_verification() {
+    let fixture = CommitteeFixture::builder().build();
+    let committee = fixture.committee();
+    let headers = fixture.headers();
+
+    let mut certificates: Vec<_> = headers
+        .iter()
+        .map(|header| fixture.certificate(header))
+        .collect();
+    certificates.extend(Certificate::genesis(&committee));
+    assert!(
+        Certificate::verify_batch(&certificates, &committee, fixture.worker_cache().into()).is_ok()
+    );
+
+    // A certificate aggregating the votes for another header.
+    let votes = fixture
+        .votes(&headers[0])
+        .into_iter()
+        .map(|vote| (vote.author, vote.signature))
+        .collect();
+    certificates[1] = Certificate::new(&committee, headers[1].clone(), votes).unwrap();
+    assert!(
+        Certificate::verify_batch(&certificates, &committee, fixture.worker_cache().into())
+            .is_err()
+    );
+    // The other certificates are still valid on their own.
+    assert!(Certificate::verify_batch(
+        &certificates[2..],
+        &committee,
+        fixture.worker_cache().into()
+    )
+    .is_ok());
+}
+
 proptest::proptest! {
     #[test]
     fn test_certificate_verification(
@@ -132,9 +194,7 @@ proptest::proptest! {
 
         let mut signatures = Vec::new();
 
-        let quorum_threshold = committee.quorum_threshold() as usize;
-
-        for authority in fixture.authorities().take(quorum_threshold) {
+        for authority in fixture.quorum() {
             let vote = authority.vote(&header);
             signatures.push((vote.author.clone(), vote.signature.clone()));
         }
@@ -145,4 +205,33 @@ proptest::proptest! {
             .verify(&committee, fixture.worker_cache().into())
             .is_ok());
     }
+
+    #[test]
+    fn test_certificate_verification_with_skewed_stakes(
+        committee_size in 4..35_usize,
+        seed: [u8; 32]
+    ) {
+        let stakes = skewed_stakes(&mut StdRng::from_seed(seed), committee_size);
+        let fixture = CommitteeFixture::builder().stakes(stakes).build();
+        let committee = fixture.committee();
+        let header = fixture.header();
+
+        let quorum = fixture.quorum();
+        let mut signatures: Vec<_> = quorum
+            .iter()
+            .map(|authority| {
+                let vote = authority.vote(&header);
+                (vote.author, vote.signature)
+            })
+            .collect();
+
+        let certificate = Certificate::new(&committee, header.clone(), signatures.clone()).unwrap();
+        assert!(certificate
+            .verify(&committee, fixture.worker_cache().into())
+            .is_ok());
+
+        // The quorum is the smallest prefix of the authorities reaching the threshold.
+        signatures.pop();
+        assert!(Certificate::new(&committee, header, signatures).is_err());
+    }
 }
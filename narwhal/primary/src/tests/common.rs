@@ -2,16 +2,20 @@
 // SPDX-License-Identifier: Apache-2.0
 use config::WorkerId;
 use crypto::NetworkKeyPair;
-use std::time::Duration;
+use std::{sync::Arc, time::Duration};
 use storage::CertificateStore;
-use store::{reopen, rocks, rocks::DBMap, Store};
+use store::{
+    backend::{KeyValueStore, RocksDBStore, StoreMap},
+    reopen, rocks,
+    rocks::DBMap,
+    Store,
+};
 use test_utils::{
     temp_dir, PrimaryToWorkerMockServer, CERTIFICATES_CF, CERTIFICATE_DIGEST_BY_ORIGIN_CF,
     CERTIFICATE_DIGEST_BY_ROUND_CF, HEADERS_CF, PAYLOAD_CF, VOTES_CF,
 };
 use types::{
-    BatchDigest, Certificate, CertificateDigest, Header, HeaderDigest, Round, VoteInfo,
-    WorkerReconfigureMessage, WorkerSynchronizeMessage,
+    BatchDigest, Header, HeaderDigest, VoteInfo, WorkerReconfigureMessage, WorkerSynchronizeMessage,
 };
 
 use crypto::PublicKey;
@@ -37,25 +41,27 @@ pub fn create_db_stores() -> (
     )
     .expect("Failed creating database");
 
-    let (
-        header_map,
-        certificate_map,
-        certificate_digest_by_round_map,
-        certificate_digest_by_origin_map,
-        payload_map,
-    ) = reopen!(&rocksdb,
+    let (header_map, payload_map) = reopen!(&rocksdb,
         HEADERS_CF;<HeaderDigest, Header>,
-        CERTIFICATES_CF;<CertificateDigest, Certificate>,
-        CERTIFICATE_DIGEST_BY_ROUND_CF;<(Round, PublicKey), CertificateDigest>,
-        CERTIFICATE_DIGEST_BY_ORIGIN_CF;<(PublicKey, Round), CertificateDigest>,
         PAYLOAD_CF;<(BatchDigest, WorkerId), PayloadToken>);
+    let backend: Arc<dyn KeyValueStore> = Arc::new(
+        RocksDBStore::new(
+            rocksdb,
+            &[
+                CERTIFICATES_CF,
+                CERTIFICATE_DIGEST_BY_ROUND_CF,
+                CERTIFICATE_DIGEST_BY_ORIGIN_CF,
+            ],
+        )
+        .expect("Failed creating database"),
+    );
 
     (
         Store::new(header_map),
         CertificateStore::new(
-            certificate_map,
-            certificate_digest_by_round_map,
-            certificate_digest_by_origin_map,
+            StoreMap::new(&backend, CERTIFICATES_CF).unwrap(),
+            StoreMap::new(&backend, CERTIFICATE_DIGEST_BY_ROUND_CF).unwrap(),
+            StoreMap::new(&backend, CERTIFICATE_DIGEST_BY_ORIGIN_CF).unwrap(),
         ),
         Store::new(payload_map),
     )
@@ -120,7 +120,10 @@ async fn propose_header() {
     // Propose header and ensure that a certificate is formed by pulling it out of the
     // consensus channel.
     let proposed_digest = proposed_header.digest();
-    tx_headers.send(proposed_header).await.unwrap();
+    tx_headers
+        .send((proposed_header, TraceId::generate()))
+        .await
+        .unwrap();
     let certificate = rx_consensus.recv().await.unwrap();
     assert_eq!(certificate.header.digest(), proposed_digest);
 }
@@ -216,7 +219,10 @@ async fn propose_header_failure() {
     );
 
     // Propose header and verify we get no certificate back.
-    tx_headers.send(proposed_header).await.unwrap();
+    tx_headers
+        .send((proposed_header, TraceId::generate()))
+        .await
+        .unwrap();
     if let Ok(result) = tokio::time::timeout(Duration::from_secs(5), rx_consensus.recv()).await {
         panic!("expected no certificate to form; got {result:?}");
     }
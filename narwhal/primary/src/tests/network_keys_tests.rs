@@ -0,0 +1,177 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+use super::*;
+use crypto::SignatureService;
+use fastcrypto::{generate_production_keypair, traits::KeyPair};
+use std::num::NonZeroUsize;
+use test_utils::CommitteeFixture;
+use types::NetworkKeysServer;
+
+#[tokio::test]
+async fn rotate_network_key() {
+    let fixture = CommitteeFixture::builder().build();
+    let committee = fixture.committee();
+    let authority = fixture.authorities().next().unwrap();
+    let name = authority.public_key();
+    let signature_service = SignatureService::new(authority.keypair().copy());
+
+    let (tx_state_handler, mut rx_state_handler) = test_utils::test_channel!(10);
+    let handler = NetworkKeysHandler::new(committee.clone().into(), tx_state_handler);
+
+    let new_key = generate_production_keypair::<crypto::NetworkKeyPair>()
+        .public()
+        .clone();
+    let announcement = NetworkKeyAnnouncement::new(
+        name.clone(),
+        committee.epoch(),
+        1,
+        new_key.clone(),
+        &signature_service,
+    )
+    .await;
+    handler
+        .announce_network_key(anemo::Request::new(announcement.clone()))
+        .await
+        .unwrap();
+
+    // The rotation is applied as an in-place update of the committee.
+    let Some(ReconfigureRequest::Update(update)) = rx_state_handler.recv().await else {
+        panic!("Expected a committee update");
+    };
+    assert_eq!(update.committee.epoch, committee.epoch());
+    let updated = committee.apply(&update.committee).unwrap();
+    assert_eq!(updated.network_key(&name).unwrap(), new_key);
+    assert_eq!(updated.stake(&name), committee.stake(&name));
+
+    // Replayed announcements are ignored.
+    handler
+        .announce_network_key(anemo::Request::new(announcement))
+        .await
+        .unwrap();
+    assert!(rx_state_handler.try_recv().is_err());
+}
+
+#[tokio::test]
+async fn reject_forged_network_key_announcement() {
+    let fixture = CommitteeFixture::builder().build();
+    let committee = fixture.committee();
+    let mut authorities = fixture.authorities();
+    let victim = authorities.next().unwrap().public_key();
+    let forger = authorities.next().unwrap();
+
+    let (tx_state_handler, mut rx_state_handler) = test_utils::test_channel!(10);
+    let handler = NetworkKeysHandler::new(committee.clone().into(), tx_state_handler);
+
+    // An announcement for an authority, signed by another.
+    let announcement = NetworkKeyAnnouncement::new(
+        victim,
+        committee.epoch(),
+        1,
+        forger.network_keypair().public().clone(),
+        &SignatureService::new(forger.keypair().copy()),
+    )
+    .await;
+    let status = handler
+        .announce_network_key(anemo::Request::new(announcement))
+        .await
+        .unwrap_err();
+    assert_eq!(
+        status.status(),
+        anemo::types::response::StatusCode::BadRequest
+    );
+    assert!(rx_state_handler.try_recv().is_err());
+}
+
+#[tokio::test]
+async fn announce_rotated_network_key() {
+    let fixture = CommitteeFixture::builder()
+        .committee_size(NonZeroUsize::new(2).unwrap())
+        .randomize_ports(true)
+        .build();
+    let committee = fixture.committee();
+    let mut authorities = fixture.authorities();
+    let peer = authorities.next().unwrap();
+    let rotating = authorities.next().unwrap();
+    let name = rotating.public_key();
+
+    // A primary of the committee, knowing the rotating one under its old key.
+    let peer_router = |committee: Committee| {
+        let (tx_state_handler, rx_state_handler) = test_utils::test_channel!(10);
+        let router = anemo::Router::new().add_rpc_service(NetworkKeysServer::new(
+            NetworkKeysHandler::new(committee.into(), tx_state_handler),
+        ));
+        (router, rx_state_handler)
+    };
+    let (router, mut rx_peer_state_handler) = peer_router(committee.clone());
+    let peer_network = peer.new_network(router);
+
+    // The rotating primary restarts with a new network key, and keeps connecting to the other.
+    let new_keypair = generate_production_keypair::<crypto::NetworkKeyPair>();
+    let new_key = new_keypair.public().clone();
+    let (tx_state_handler, mut rx_state_handler) = test_utils::test_channel!(10);
+    let committee: SharedCommittee = committee.clone().into();
+    let handler = NetworkKeysHandler::new(committee.clone(), tx_state_handler);
+    let network = anemo::Network::bind(network::multiaddr_to_address(rotating.address()).unwrap())
+        .server_name("narwhal")
+        .private_key(new_keypair.private().0.to_bytes())
+        .start(anemo::Router::new().add_rpc_service(NetworkKeysServer::new(handler.clone())))
+        .unwrap();
+    network.known_peers().insert(PeerInfo {
+        peer_id: PeerId(peer.network_keypair().public().0.to_bytes()),
+        affinity: anemo::types::PeerAffinity::High,
+        address: vec![network::multiaddr_to_address(peer.address()).unwrap()],
+    });
+
+    let (tx_reconfigure, rx_reconfigure) = watch::channel(ReconfigureNotification::NewEpoch(
+        committee.load_full().as_ref().clone(),
+    ));
+    let announcer = NetworkKeyAnnouncer::spawn(
+        name.clone(),
+        new_key.clone(),
+        committee,
+        SignatureService::new(rotating.keypair().copy()),
+        handler,
+        network.clone(),
+        rx_reconfigure,
+    );
+
+    // Both the rotating primary and the other one apply the new key.
+    for rx in [&mut rx_state_handler, &mut rx_peer_state_handler] {
+        let Some(ReconfigureRequest::Update(update)) = rx.recv().await else {
+            panic!("Expected a committee update");
+        };
+        let updated = fixture.committee().apply(&update.committee).unwrap();
+        assert_eq!(updated.network_key(&name).unwrap(), new_key);
+    }
+
+    // And the other primary keeps talking to the rotating one, under its new key.
+    let new_peer_id = PeerId(new_key.0.to_bytes());
+    let old_peer_id = PeerId(rotating.network_keypair().public().0.to_bytes());
+    assert!(peer_network.known_peers().get(&old_peer_id).is_none());
+    assert!(peer_network.known_peers().get(&new_peer_id).is_some());
+    let peer_id = peer_network
+        .connect(network::multiaddr_to_address(rotating.address()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(peer_id, new_peer_id);
+
+    // The other primary restarts, and knows the old key from its committee again: the rotating
+    // one announces its key again once reconnected to it.
+    peer_network.shutdown().await.unwrap();
+    drop(peer_network);
+    let (router, mut rx_peer_state_handler) = peer_router(fixture.committee());
+    let peer_network = peer.new_network(router);
+    let Some(ReconfigureRequest::Update(update)) = rx_peer_state_handler.recv().await else {
+        panic!("Expected a committee update");
+    };
+    let updated = fixture.committee().apply(&update.committee).unwrap();
+    assert_eq!(updated.network_key(&name).unwrap(), new_key);
+    assert!(peer_network.known_peers().get(&new_peer_id).is_some());
+
+    // The announcer only stops with the primary.
+    assert!(!announcer.is_finished());
+    tx_reconfigure
+        .send(ReconfigureNotification::Shutdown)
+        .unwrap();
+    announcer.await.unwrap();
+}
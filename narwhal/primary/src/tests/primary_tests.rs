@@ -8,15 +8,14 @@ use crate::{
 };
 use arc_swap::ArcSwap;
 use bincode::Options;
-use config::{Parameters, WorkerId};
+use config::{Parameters, WorkerCacheUpdate, WorkerId, WorkerIndexUpdate};
 use consensus::{dag::Dag, metrics::ConsensusMetrics};
-use crypto::PublicKey;
+use crypto::{PublicKey, SignatureService};
 use dashmap::DashSet;
 use fastcrypto::{
     encoding::{Encoding, Hex},
     hash::Hash,
     traits::KeyPair,
-    SignatureService,
 };
 use itertools::Itertools;
 use prometheus::Registry;
@@ -30,15 +29,16 @@ use std::{
 use storage::CertificateStore;
 use storage::NodeStorage;
 use storage::PayloadToken;
+use store::backend::{KeyValueStore, RocksDBStore, StoreMap};
 use store::rocks::DBMap;
 use store::Store;
 use test_utils::{temp_dir, CommitteeFixture};
 use tokio::sync::watch;
 
 use types::{
-    error::DagError, now, BatchDigest, Certificate, CertificateDigest, FetchCertificatesRequest,
-    MockPrimaryToWorker, PayloadAvailabilityRequest, PrimaryToPrimary, PrimaryToWorkerServer,
-    ReconfigureNotification, RequestVoteRequest, Round,
+    error::DagError, now, AuditLog, BatchDigest, Certificate, CertificateDigest,
+    FetchCertificatesRequest, MockPrimaryToWorker, PayloadAvailabilityRequest, PrimaryToPrimary,
+    PrimaryToWorkerServer, ReconfigureNotification, RequestVoteRequest,
 };
 use worker::{metrics::initialise_metrics, TrivialTransactionValidator, Worker};
 
@@ -272,6 +272,137 @@ async fn get_network_peers_from_admin_server() {
     assert!(expected_peer_ids.iter().all(|e| resp.contains(e)));
 }
 
+#[tokio::test]
+async fn update_workers_from_admin_server() {
+    let parameters = Parameters::default();
+    let fixture = CommitteeFixture::builder().randomize_ports(true).build();
+    let committee = fixture.committee();
+    let worker_cache = fixture.shared_worker_cache();
+    let authority_1 = fixture.authorities().next().unwrap();
+    let authority_2 = fixture.authorities().nth(1).unwrap();
+
+    let store = NodeStorage::reopen(temp_dir());
+
+    let (tx_new_certificates, rx_new_certificates) = types::metered_channel::channel(
+        CHANNEL_CAPACITY,
+        &prometheus::IntGauge::new(
+            PrimaryChannelMetrics::NAME_NEW_CERTS,
+            PrimaryChannelMetrics::DESC_NEW_CERTS,
+        )
+        .unwrap(),
+    );
+    let (tx_feedback, rx_feedback) = types::metered_channel::channel(
+        CHANNEL_CAPACITY,
+        &prometheus::IntGauge::new(
+            PrimaryChannelMetrics::NAME_COMMITTED_CERTS,
+            PrimaryChannelMetrics::DESC_COMMITTED_CERTS,
+        )
+        .unwrap(),
+    );
+    let (_tx_consensus_round_updates, rx_consensus_round_updates) = watch::channel(0);
+    let initial_committee = ReconfigureNotification::NewEpoch(committee.clone());
+    let (tx_reconfigure, _rx_reconfigure) = watch::channel(initial_committee);
+    let consensus_metrics = Arc::new(ConsensusMetrics::new(&Registry::new()));
+
+    Primary::spawn(
+        authority_1.public_key(),
+        authority_1.keypair().copy(),
+        authority_1.network_keypair().copy(),
+        Arc::new(ArcSwap::from_pointee(committee.clone())),
+        worker_cache.clone(),
+        parameters.clone(),
+        store.header_store.clone(),
+        store.certificate_store.clone(),
+        store.proposer_store.clone(),
+        store.payload_store.clone(),
+        store.vote_digest_store.clone(),
+        tx_new_certificates,
+        rx_feedback,
+        rx_consensus_round_updates,
+        /* dag */
+        Some(Arc::new(
+            Dag::new(&committee, rx_new_certificates, consensus_metrics).1,
+        )),
+        NetworkModel::Asynchronous,
+        tx_reconfigure,
+        tx_feedback,
+        &Registry::new(),
+        None,
+    );
+
+    // Wait for tasks to start
+    tokio::time::sleep(Duration::from_secs(1)).await;
+
+    // Move the first worker of the second authority to a new address.
+    let mut worker_index = authority_2.worker_index();
+    let new_address: multiaddr::Multiaddr = format!(
+        "/ip4/127.0.0.1/udp/{}",
+        config::utils::get_available_port("127.0.0.1")
+    )
+    .parse()
+    .unwrap();
+    worker_index.0.get_mut(&0).unwrap().worker_address = new_address.clone();
+    let update = WorkerCacheUpdate {
+        epoch: committee.epoch(),
+        changes: vec![WorkerIndexUpdate::Update(
+            authority_2.public_key(),
+            worker_index,
+        )],
+    };
+
+    let admin_url = format!(
+        "http://127.0.0.1:{}",
+        parameters
+            .network_admin_server
+            .primary_network_admin_server_port
+    );
+    let client = reqwest::Client::new();
+    let response = client
+        .post(format!("{admin_url}/update_workers"))
+        .json(&update)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+    // The worker cache and the known peers now point to the new address.
+    assert_eq!(
+        worker_cache
+            .load()
+            .worker(&authority_2.public_key(), &0)
+            .unwrap()
+            .worker_address,
+        new_address
+    );
+    let known_peers = reqwest::get(format!("{admin_url}/known_peers"))
+        .await
+        .unwrap()
+        .json::<Vec<String>>()
+        .await
+        .unwrap();
+    let expected_address = network::multiaddr_to_address(&new_address).unwrap();
+    assert!(known_peers
+        .iter()
+        .any(|peer| peer.contains(&format!("{expected_address:?}"))));
+
+    // Authorities cannot be removed during the epoch.
+    let update = WorkerCacheUpdate {
+        epoch: committee.epoch(),
+        changes: vec![WorkerIndexUpdate::Remove(authority_2.public_key())],
+    };
+    let response = client
+        .post(format!("{admin_url}/update_workers"))
+        .json(&update)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+    assert!(worker_cache
+        .load()
+        .workers
+        .contains_key(&authority_2.public_key()));
+}
+
 #[tokio::test]
 async fn test_request_vote_missing_parents() {
     telemetry_subscribers::init_for_testing();
@@ -317,6 +448,7 @@ async fn test_request_vote_missing_parents() {
         rx_narwhal_round_updates,
         metrics: metrics.clone(),
         request_vote_inflight: Arc::new(DashSet::new()),
+        audit_log: AuditLog::default(),
     };
 
     // Make some mock certificates that are parents of our new header.
@@ -484,6 +616,7 @@ async fn test_request_vote_missing_batches() {
         rx_narwhal_round_updates,
         metrics: metrics.clone(),
         request_vote_inflight: Arc::new(DashSet::new()),
+        audit_log: AuditLog::default(),
     };
 
     // Make some mock certificates that are parents of our new header.
@@ -603,6 +736,7 @@ async fn test_request_vote_already_voted() {
         rx_narwhal_round_updates,
         metrics: metrics.clone(),
         request_vote_inflight: Arc::new(DashSet::new()),
+        audit_log: AuditLog::default(),
     };
 
     // Make some mock certificates that are parents of our new header.
@@ -755,6 +889,7 @@ async fn test_fetch_certificates_handler() {
         rx_narwhal_round_updates,
         metrics: metrics.clone(),
         request_vote_inflight: Arc::new(DashSet::new()),
+        audit_log: AuditLog::default(),
     };
 
     let mut current_round: Vec<_> = Certificate::genesis(&fixture.committee())
@@ -917,6 +1052,7 @@ async fn test_process_payload_availability_success() {
         rx_narwhal_round_updates,
         metrics: metrics.clone(),
         request_vote_inflight: Arc::new(DashSet::new()),
+        audit_log: AuditLog::default(),
     };
 
     // GIVEN some mock certificates
@@ -1001,21 +1137,24 @@ async fn test_process_payload_availability_when_failures() {
     )
     .expect("Failed creating database");
 
-    let (
-        certificate_map,
-        certificate_digest_by_round_map,
-        certificate_digest_by_origin_map,
-        payload_map,
-    ) = store::reopen!(&rocksdb,
-        test_utils::CERTIFICATES_CF;<CertificateDigest, Certificate>,
-        test_utils::CERTIFICATE_DIGEST_BY_ROUND_CF;<(Round, PublicKey), CertificateDigest>,
-        test_utils::CERTIFICATE_DIGEST_BY_ORIGIN_CF;<(PublicKey, Round), CertificateDigest>,
+    let payload_map = store::reopen!(&rocksdb,
         test_utils::PAYLOAD_CF;<(BatchDigest, WorkerId), PayloadToken>);
+    let backend: Arc<dyn KeyValueStore> = Arc::new(
+        RocksDBStore::new(
+            rocksdb.clone(),
+            &[
+                test_utils::CERTIFICATES_CF,
+                test_utils::CERTIFICATE_DIGEST_BY_ROUND_CF,
+                test_utils::CERTIFICATE_DIGEST_BY_ORIGIN_CF,
+            ],
+        )
+        .expect("Failed creating database"),
+    );
 
     let certificate_store = CertificateStore::new(
-        certificate_map,
-        certificate_digest_by_round_map,
-        certificate_digest_by_origin_map,
+        StoreMap::new(&backend, test_utils::CERTIFICATES_CF).unwrap(),
+        StoreMap::new(&backend, test_utils::CERTIFICATE_DIGEST_BY_ROUND_CF).unwrap(),
+        StoreMap::new(&backend, test_utils::CERTIFICATE_DIGEST_BY_ORIGIN_CF).unwrap(),
     );
     let payload_store: Store<(BatchDigest, WorkerId), PayloadToken> = Store::new(payload_map);
 
@@ -1061,6 +1200,7 @@ async fn test_process_payload_availability_when_failures() {
         rx_narwhal_round_updates,
         metrics: metrics.clone(),
         request_vote_inflight: Arc::new(DashSet::new()),
+        audit_log: AuditLog::default(),
     };
 
     // AND some mock certificates
@@ -1153,6 +1293,7 @@ async fn test_request_vote_created_at_in_future() {
         rx_narwhal_round_updates,
         metrics: metrics.clone(),
         request_vote_inflight: Arc::new(DashSet::new()),
+        audit_log: AuditLog::default(),
     };
 
     // Make some mock certificates that are parents of our new header.
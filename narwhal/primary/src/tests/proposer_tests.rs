@@ -47,7 +47,7 @@ async fn propose_empty() {
     );
 
     // Ensure the proposer makes a correct empty header.
-    let header = rx_headers.recv().await.unwrap();
+    let (header, _trace_id) = rx_headers.recv().await.unwrap();
     assert_eq!(header.round, 1);
     assert!(header.payload.is_empty());
     assert!(header.verify(&committee, shared_worker_cache).is_ok());
@@ -103,21 +103,25 @@ async fn propose_payload_and_repropose_after_n_seconds() {
     let digest = BatchDigest(name_bytes);
     let worker_id = 0;
     let (tx_ack, rx_ack) = tokio::sync::oneshot::channel();
+    let batch_trace_id = TraceId::generate();
     tx_our_digests
         .send(OurDigestMessage {
             digest,
             worker_id,
             timestamp: 0,
+            trace_id: Some(batch_trace_id.clone()),
             ack_channel: tx_ack,
         })
         .await
         .unwrap();
 
-    // Ensure the proposer makes a correct header from the provided payload.
-    let header = rx_headers.recv().await.unwrap();
+    // Ensure the proposer makes a correct header from the provided payload, proposed under the
+    // trace id of its batch.
+    let (header, trace_id) = rx_headers.recv().await.unwrap();
     assert_eq!(header.round, 1);
     assert_eq!(header.payload.get(&digest), Some(&worker_id));
     assert!(header.verify(&committee, shared_worker_cache).is_ok());
+    assert_eq!(trace_id, batch_trace_id);
 
     // WHEN available batches are more than the maximum ones
     let batches: IndexMap<BatchDigest, WorkerId> = fixture_payload((max_num_of_batches * 2) as u8);
@@ -130,6 +134,7 @@ async fn propose_payload_and_repropose_after_n_seconds() {
                 digest: batch_id,
                 worker_id,
                 timestamp: 0,
+                trace_id: None,
                 ack_channel: tx_ack,
             })
             .await
@@ -152,7 +157,7 @@ async fn propose_payload_and_repropose_after_n_seconds() {
     assert!(result.is_ok());
 
     // THEN the header should contain max_num_of_batches
-    let header = rx_headers.recv().await.unwrap();
+    let (header, _trace_id) = rx_headers.recv().await.unwrap();
     assert_eq!(header.round, 2);
     assert_eq!(header.payload.len(), max_num_of_batches);
     assert!(rx_ack.await.is_ok());
@@ -166,7 +171,7 @@ async fn propose_payload_and_repropose_after_n_seconds() {
     // In theory after header_resend_delay we should receive again
     // the last created header.
     for _ in 0..3 {
-        let resent_header = rx_headers.recv().await.unwrap();
+        let (resent_header, _trace_id) = rx_headers.recv().await.unwrap();
 
         // THEN should be the exact same as the last sent
         assert_eq!(header, resent_header);
@@ -225,6 +230,7 @@ async fn equivocation_protection() {
             digest,
             worker_id,
             timestamp: 0,
+            trace_id: None,
             ack_channel: tx_ack,
         })
         .await
@@ -243,7 +249,7 @@ async fn equivocation_protection() {
     assert!(rx_ack.await.is_ok());
 
     // Ensure the proposer makes a correct header from the provided payload.
-    let header = rx_headers.recv().await.unwrap();
+    let (header, _trace_id) = rx_headers.recv().await.unwrap();
     assert_eq!(header.payload.get(&digest), Some(&worker_id));
     assert!(header.verify(&committee, shared_worker_cache).is_ok());
 
@@ -293,6 +299,7 @@ async fn equivocation_protection() {
             digest,
             worker_id,
             timestamp: 0,
+            trace_id: None,
             ack_channel: tx_ack,
         })
         .await
@@ -311,7 +318,7 @@ async fn equivocation_protection() {
     assert!(rx_ack.await.is_ok());
 
     // Ensure the proposer makes the same header as before
-    let new_header = rx_headers.recv().await.unwrap();
+    let (new_header, _trace_id) = rx_headers.recv().await.unwrap();
     if new_header.round == header.round {
         assert_eq!(header, new_header);
     }
@@ -0,0 +1,66 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+use std::{collections::HashSet, time::Duration};
+use test_utils::{
+    byzantine::{Behavior, ByzantinePrimary},
+    cluster::{setup_tracing, Cluster},
+};
+
+/// The id of the authority replaced by a Byzantine primary, the others being honest.
+const BYZANTINE_AUTHORITY: usize = 3;
+
+async fn start_cluster_with_byzantine_primary(behavior: Behavior) -> (Cluster, ByzantinePrimary) {
+    let mut cluster = Cluster::new(None, true);
+    cluster
+        .start(Some(BYZANTINE_AUTHORITY), Some(1), None)
+        .await;
+    let byzantine = ByzantinePrimary::spawn(
+        cluster
+            .fixture()
+            .authorities()
+            .nth(BYZANTINE_AUTHORITY)
+            .unwrap(),
+        cluster.committee_shared.load_full().as_ref().clone(),
+        behavior,
+    );
+    (cluster, byzantine)
+}
+
+#[tokio::test]
+async fn test_equivocating_primary() {
+    let _guard = setup_tracing();
+    let (cluster, byzantine) = start_cluster_with_byzantine_primary(Behavior {
+        equivocate_headers: true,
+        withhold_votes: true,
+        ..Behavior::default()
+    })
+    .await;
+
+    tokio::time::sleep(Duration::from_secs(30)).await;
+
+    // Liveness: the honest primaries commit without the votes of the Byzantine one.
+    cluster.assert_progress(3, 2).await;
+
+    // Safety: the honest primaries never vote for two headers of the same round, so at most one
+    // of the conflicting headers is certified.
+    let certificates = byzantine.certificates();
+    let rounds: HashSet<_> = certificates.iter().map(|c| c.round()).collect();
+    assert_eq!(rounds.len(), certificates.len());
+}
+
+#[tokio::test]
+async fn test_stale_and_slow_primary() {
+    let _guard = setup_tracing();
+    let (cluster, byzantine) = start_cluster_with_byzantine_primary(Behavior {
+        stale_epoch: true,
+        delay: Some(Duration::from_millis(500)),
+        ..Behavior::default()
+    })
+    .await;
+
+    tokio::time::sleep(Duration::from_secs(30)).await;
+
+    cluster.assert_progress(3, 2).await;
+    // The headers of another epoch are never voted for.
+    assert!(byzantine.certificates().is_empty());
+}
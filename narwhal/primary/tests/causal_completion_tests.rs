@@ -43,6 +43,7 @@ async fn test_restore_from_disk() {
             let tr = bincode::serialize(&tx).unwrap();
             let txn = TransactionProto {
                 transaction: Bytes::from(tr),
+                metadata: Bytes::new(),
             };
 
             c.submit_transaction(txn).await.unwrap();
@@ -93,6 +93,8 @@ async fn test_rounds_errors() {
             .iter()
             .filter_map(|(pk, a)| (*pk != name).then_some((pk.clone(), a.clone())))
             .collect::<BTreeMap<_, _>>(),
+        quorum_policy: committee.quorum_policy,
+        protocol_config: committee.protocol_config,
     };
 
     let consensus_metrics = Arc::new(ConsensusMetrics::new(&Registry::new()));
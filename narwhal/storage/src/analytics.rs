@@ -0,0 +1,413 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Export of the DAG of a node for offline analysis. The certificates, the headers they certify
+//! and a summary of their batches are written to one file per table, as CSV or Parquet, with
+//! the stable schema below (Parquet types in parentheses). Digests and keys are base64 encoded.
+//!
+//! `certificates`: digest (UTF8), header_digest (UTF8), round (INT64), epoch (INT64),
+//! origin (UTF8), created_at (INT64, ms since the epoch), parents (UTF8, space separated digests).
+//!
+//! `headers`: digest (UTF8), round (INT64), epoch (INT64), author (UTF8), created_at (INT64),
+//! parent_count (INT64), batch_count (INT64).
+//!
+//! `batches`: digest (UTF8), worker_id (INT64), header_digest (UTF8), round (INT64),
+//! stored (BOOLEAN), transaction_count (INT64), size_bytes (INT64). The counts are 0 for the
+//! batches that are not stored, e.g. when the workers have their own storage.
+use crate::NodeStorage;
+use fastcrypto::hash::Hash;
+use parquet::{
+    data_type::{BoolType, ByteArray, ByteArrayType, Int64Type},
+    errors::ParquetError,
+    file::{properties::WriterProperties, writer::SerializedFileWriter},
+    schema::parser::parse_message_type,
+};
+use std::{
+    fmt,
+    fs::{self, File},
+    io::{self, BufWriter, Write},
+    path::Path,
+    str::FromStr,
+    sync::Arc,
+};
+use store::rocks::TypedStoreError;
+use thiserror::Error;
+use types::{Certificate, Round};
+
+#[derive(Debug, Error)]
+pub enum ExportError {
+    #[error("Export I/O error: {0}")]
+    Io(#[from] io::Error),
+
+    #[error(transparent)]
+    Store(#[from] TypedStoreError),
+
+    #[error("Parquet error: {0}")]
+    Parquet(#[from] ParquetError),
+}
+
+/// The file format of an export.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum ExportFormat {
+    #[default]
+    Csv,
+    Parquet,
+}
+
+impl ExportFormat {
+    fn extension(&self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Parquet => "parquet",
+        }
+    }
+}
+
+impl FromStr for ExportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "csv" => Ok(Self::Csv),
+            "parquet" => Ok(Self::Parquet),
+            _ => Err(format!(
+                "unknown export format {s}, expected csv or parquet"
+            )),
+        }
+    }
+}
+
+/// What [`NodeStorage::export_analytics`] exports, and how.
+#[derive(Clone, Debug, Default)]
+pub struct ExportOptions {
+    pub format: ExportFormat,
+    /// The lowest round to export.
+    pub from_round: Round,
+    /// The highest round to export, all the rounds from `from_round` if `None`.
+    pub to_round: Option<Round>,
+}
+
+/// The number of rows of every exported table.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ExportSummary {
+    pub certificates: usize,
+    pub headers: usize,
+    pub batches: usize,
+}
+
+/// The values of a column of an exported table.
+enum Column {
+    Int(Vec<i64>),
+    Text(Vec<String>),
+    Bool(Vec<bool>),
+}
+
+impl Column {
+    fn len(&self) -> usize {
+        match self {
+            Self::Int(values) => values.len(),
+            Self::Text(values) => values.len(),
+            Self::Bool(values) => values.len(),
+        }
+    }
+
+    /// The value of the given row, as a CSV field.
+    fn csv_field(&self, row: usize) -> String {
+        match self {
+            Self::Int(values) => values[row].to_string(),
+            Self::Bool(values) => values[row].to_string(),
+            Self::Text(values) => {
+                let value = &values[row];
+                if value.contains(|c| matches!(c, ',' | '"' | '\n' | '\r')) {
+                    format!("\"{}\"", value.replace('"', "\"\""))
+                } else {
+                    value.clone()
+                }
+            }
+        }
+    }
+}
+
+/// An exported table, stored by column.
+struct Table {
+    name: &'static str,
+    columns: Vec<(&'static str, Column)>,
+}
+
+impl Table {
+    fn rows(&self) -> usize {
+        self.columns.first().map_or(0, |(_, column)| column.len())
+    }
+
+    fn write(&self, dir: &Path, format: ExportFormat) -> Result<(), ExportError> {
+        let path = dir.join(format!("{}.{}", self.name, format.extension()));
+        match format {
+            ExportFormat::Csv => self.write_csv(&path)?,
+            ExportFormat::Parquet => self.write_parquet(&path)?,
+        }
+        Ok(())
+    }
+
+    fn write_csv(&self, path: &Path) -> io::Result<()> {
+        let mut out = BufWriter::new(File::create(path)?);
+        let names: Vec<_> = self.columns.iter().map(|(name, _)| *name).collect();
+        writeln!(out, "{}", names.join(","))?;
+        for row in 0..self.rows() {
+            let fields: Vec<_> = self
+                .columns
+                .iter()
+                .map(|(_, column)| column.csv_field(row))
+                .collect();
+            writeln!(out, "{}", fields.join(","))?;
+        }
+        out.flush()
+    }
+
+    /// Writes the table as a single row group, all the columns being required.
+    fn write_parquet(&self, path: &Path) -> Result<(), ParquetError> {
+        let fields: Vec<_> = self
+            .columns
+            .iter()
+            .map(|(name, column)| match column {
+                Column::Int(_) => format!("REQUIRED INT64 {name};"),
+                Column::Text(_) => format!("REQUIRED BYTE_ARRAY {name} (UTF8);"),
+                Column::Bool(_) => format!("REQUIRED BOOLEAN {name};"),
+            })
+            .collect();
+        let schema =
+            parse_message_type(&format!("message {} {{ {} }}", self.name, fields.join(" ")))?;
+        let properties = WriterProperties::builder().build();
+        let mut writer =
+            SerializedFileWriter::new(File::create(path)?, Arc::new(schema), Arc::new(properties))?;
+
+        let mut row_group = writer.next_row_group()?;
+        let mut columns = self.columns.iter();
+        while let Some(mut column_writer) = row_group.next_column()? {
+            let (_, column) = columns
+                .next()
+                .expect("The schema has one column per column");
+            match column {
+                Column::Int(values) => {
+                    column_writer
+                        .typed::<Int64Type>()
+                        .write_batch(values, None, None)?;
+                }
+                Column::Text(values) => {
+                    let values: Vec<_> = values
+                        .iter()
+                        .map(|value| ByteArray::from(value.as_str()))
+                        .collect();
+                    column_writer
+                        .typed::<ByteArrayType>()
+                        .write_batch(&values, None, None)?;
+                }
+                Column::Bool(values) => {
+                    column_writer
+                        .typed::<BoolType>()
+                        .write_batch(values, None, None)?;
+                }
+            }
+            column_writer.close()?;
+        }
+        row_group.close()?;
+        writer.close()?;
+        Ok(())
+    }
+}
+
+/// The full base64 encoding of a digest, which `Display` truncates.
+fn encode(digest: impl fmt::Debug) -> String {
+    format!("{digest:?}")
+}
+
+fn certificates_table(certificates: &[Certificate]) -> Table {
+    let ints = |f: fn(&Certificate) -> i64| Column::Int(certificates.iter().map(f).collect());
+    let texts = |f: fn(&Certificate) -> String| Column::Text(certificates.iter().map(f).collect());
+    Table {
+        name: "certificates",
+        columns: vec![
+            ("digest", texts(|c| encode(c.digest()))),
+            ("header_digest", texts(|c| encode(c.header.digest()))),
+            ("round", ints(|c| c.round() as i64)),
+            ("epoch", ints(|c| c.epoch() as i64)),
+            ("origin", texts(|c| c.origin().to_string())),
+            ("created_at", ints(|c| c.metadata.created_at as i64)),
+            (
+                "parents",
+                texts(|c| {
+                    let parents: Vec<_> = c.header.parents.iter().map(encode).collect();
+                    parents.join(" ")
+                }),
+            ),
+        ],
+    }
+}
+
+fn headers_table(certificates: &[Certificate]) -> Table {
+    let ints = |f: fn(&Certificate) -> i64| Column::Int(certificates.iter().map(f).collect());
+    let texts = |f: fn(&Certificate) -> String| Column::Text(certificates.iter().map(f).collect());
+    Table {
+        name: "headers",
+        columns: vec![
+            ("digest", texts(|c| encode(c.header.digest()))),
+            ("round", ints(|c| c.header.round as i64)),
+            ("epoch", ints(|c| c.header.epoch as i64)),
+            ("author", texts(|c| c.header.author.to_string())),
+            ("created_at", ints(|c| c.header.created_at as i64)),
+            ("parent_count", ints(|c| c.header.parents.len() as i64)),
+            ("batch_count", ints(|c| c.header.payload.len() as i64)),
+        ],
+    }
+}
+
+impl NodeStorage {
+    /// Exports the certificates of the rounds selected by `options`, their headers and a summary
+    /// of their batches to `dir` (created if needed), overwriting previous exports. Storage open
+    /// with [`NodeStorage::open_read_only`] can be exported while the node runs.
+    pub async fn export_analytics(
+        &self,
+        dir: &Path,
+        options: &ExportOptions,
+    ) -> Result<ExportSummary, ExportError> {
+        let certificates: Vec<_> = self
+            .certificate_store
+            .after_round(options.from_round)?
+            .into_iter()
+            .filter(|c| options.to_round.map_or(true, |to| c.round() <= to))
+            .collect();
+
+        let (mut digests, mut worker_ids, mut header_digests, mut rounds) =
+            (Vec::new(), Vec::new(), Vec::new(), Vec::new());
+        for certificate in &certificates {
+            for (batch, worker_id) in &certificate.header.payload {
+                digests.push(*batch);
+                worker_ids.push(*worker_id as i64);
+                header_digests.push(encode(certificate.header.digest()));
+                rounds.push(certificate.round() as i64);
+            }
+        }
+        let batches = self.batch_store.read_all(digests.clone()).await?;
+        let transaction_counts = batches
+            .iter()
+            .map(|batch| batch.as_ref().map_or(0, |b| b.transactions.len() as i64))
+            .collect();
+        let sizes = batches
+            .iter()
+            .map(|batch| {
+                batch
+                    .as_ref()
+                    .map_or(0, |b| b.transactions.iter().map(|tx| tx.len() as i64).sum())
+            })
+            .collect();
+        let batches_table = Table {
+            name: "batches",
+            columns: vec![
+                ("digest", Column::Text(digests.iter().map(encode).collect())),
+                ("worker_id", Column::Int(worker_ids)),
+                ("header_digest", Column::Text(header_digests)),
+                ("round", Column::Int(rounds)),
+                (
+                    "stored",
+                    Column::Bool(batches.iter().map(Option::is_some).collect()),
+                ),
+                ("transaction_count", Column::Int(transaction_counts)),
+                ("size_bytes", Column::Int(sizes)),
+            ],
+        };
+
+        fs::create_dir_all(dir)?;
+        let tables = [
+            certificates_table(&certificates),
+            headers_table(&certificates),
+            batches_table,
+        ];
+        for table in &tables {
+            table.write(dir, options.format)?;
+        }
+        Ok(ExportSummary {
+            certificates: tables[0].rows(),
+            headers: tables[1].rows(),
+            batches: tables[2].rows(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{ExportFormat, ExportOptions, ExportSummary, NodeStorage};
+    use fastcrypto::hash::Hash;
+    use std::collections::BTreeSet;
+    use test_utils::{temp_dir, CommitteeFixture};
+    use types::Certificate;
+
+    #[tokio::test]
+    async fn test_export_analytics() {
+        let fixture = CommitteeFixture::builder().build();
+        let committee = fixture.committee();
+        let genesis = Certificate::genesis(&committee)
+            .iter()
+            .map(|x| x.digest())
+            .collect::<BTreeSet<_>>();
+        let (_, headers) = fixture.headers_round(0, &genesis);
+        let round_1: Vec<_> = headers.iter().map(|h| fixture.certificate(h)).collect();
+        let parents = round_1.iter().map(|c| c.digest()).collect();
+        let (_, headers) = fixture.headers_round(1, &parents);
+        let round_2: Vec<_> = headers.iter().map(|h| fixture.certificate(h)).collect();
+
+        let storage = NodeStorage::in_memory();
+        storage
+            .certificate_store
+            .write_all(round_1.iter().chain(&round_2).cloned())
+            .unwrap();
+
+        // Only round 2 is exported.
+        let dir = temp_dir();
+        let options = ExportOptions {
+            format: ExportFormat::Csv,
+            from_round: 2,
+            to_round: Some(2),
+        };
+        let summary = storage.export_analytics(&dir, &options).await.unwrap();
+        let batches = round_2.iter().map(|c| c.header.payload.len()).sum();
+        assert_eq!(
+            summary,
+            ExportSummary {
+                certificates: round_2.len(),
+                headers: round_2.len(),
+                batches,
+            }
+        );
+
+        let csv = std::fs::read_to_string(dir.join("certificates.csv")).unwrap();
+        let mut lines = csv.lines();
+        assert_eq!(
+            lines.next(),
+            Some("digest,header_digest,round,epoch,origin,created_at,parents")
+        );
+        let digests: BTreeSet<_> = lines
+            .map(|line| line.split(',').next().unwrap().to_string())
+            .collect();
+        let expected: BTreeSet<_> = round_2
+            .iter()
+            .map(|c| format!("{:?}", c.digest()))
+            .collect();
+        assert_eq!(digests, expected);
+
+        let csv = std::fs::read_to_string(dir.join("batches.csv")).unwrap();
+        // None of the batches is stored.
+        assert!(csv.lines().skip(1).all(|line| line.contains(",false,0,0")));
+        assert_eq!(csv.lines().count(), batches + 1);
+
+        // The same tables can be exported as Parquet.
+        let dir = temp_dir();
+        let options = ExportOptions {
+            format: ExportFormat::Parquet,
+            ..Default::default()
+        };
+        let summary = storage.export_analytics(&dir, &options).await.unwrap();
+        assert_eq!(summary.certificates, round_1.len() + round_2.len());
+        for table in ["certificates", "headers", "batches"] {
+            assert!(dir.join(format!("{table}.parquet")).exists());
+        }
+    }
+}
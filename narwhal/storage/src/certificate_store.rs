@@ -1,22 +1,24 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
+use crate::Inconsistency;
 use crypto::PublicKey;
 use dashmap::DashMap;
 use fastcrypto::hash::Hash;
 use std::{
     cmp::Ordering,
-    collections::{BTreeMap, VecDeque},
+    collections::{BTreeMap, HashMap, VecDeque},
     iter,
     sync::Arc,
 };
-use store::{
-    rocks::{DBMap, TypedStoreError::RocksDBError},
-    Map,
-};
+use store::{backend::StoreMap, rocks::TypedStoreError::RocksDBError, Map};
 use tokio::sync::{oneshot, oneshot::Sender};
 use tracing::warn;
 use types::{Certificate, CertificateDigest, Round, StoreResult};
 
+/// The names of the secondary indexes, as reported by [`CertificateStore::check_indexes`].
+const BY_ROUND_INDEX: &str = "by round";
+const BY_ORIGIN_INDEX: &str = "by origin";
+
 /// The main storage when we have to deal with certificates. It maintains
 /// two storages, one main which saves the certificates by their ids, and a
 /// secondary one which acts as an index to allow us fast retrieval based
@@ -27,17 +29,17 @@ use types::{Certificate, CertificateDigest, Round, StoreResult};
 #[derive(Clone)]
 pub struct CertificateStore {
     /// Holds the certificates by their digest id
-    certificates_by_id: DBMap<CertificateDigest, Certificate>,
+    certificates_by_id: StoreMap<CertificateDigest, Certificate>,
     /// A secondary index that keeps the certificate digest ids
     /// by the certificate rounds. Certificate origin is used to produce unique keys.
     /// This helps us to perform range requests based on rounds. We avoid storing again the
     /// certificate here to not waste space. To dereference we use the certificates_by_id storage.
-    certificate_id_by_round: DBMap<(Round, PublicKey), CertificateDigest>,
+    certificate_id_by_round: StoreMap<(Round, PublicKey), CertificateDigest>,
     /// A secondary index that keeps the certificate digest ids
     /// by the certificate origins. Certificate rounds are used to produce unique keys.
     /// This helps us to perform range requests based on rounds. We avoid storing again the
     /// certificate here to not waste space. To dereference we use the certificates_by_id storage.
-    certificate_id_by_origin: DBMap<(PublicKey, Round), CertificateDigest>,
+    certificate_id_by_origin: StoreMap<(PublicKey, Round), CertificateDigest>,
     /// Senders to notify for a write that happened for
     /// the specified certificate digest id
     notify_on_write_subscribers: Arc<DashMap<CertificateDigest, VecDeque<Sender<Certificate>>>>,
@@ -45,9 +47,9 @@ pub struct CertificateStore {
 
 impl CertificateStore {
     pub fn new(
-        certificates_by_id: DBMap<CertificateDigest, Certificate>,
-        certificate_id_by_round: DBMap<(Round, PublicKey), CertificateDigest>,
-        certificate_id_by_origin: DBMap<(PublicKey, Round), CertificateDigest>,
+        certificates_by_id: StoreMap<CertificateDigest, Certificate>,
+        certificate_id_by_round: StoreMap<(Round, PublicKey), CertificateDigest>,
+        certificate_id_by_origin: StoreMap<(PublicKey, Round), CertificateDigest>,
     ) -> CertificateStore {
         Self {
             certificates_by_id,
@@ -305,6 +307,41 @@ impl CertificateStore {
             .collect()
     }
 
+    /// Deletes all the certificates with round < the provided round, from the main storage and
+    /// both secondary indexes, in an atomic way. Returns the deleted certificates.
+    pub fn prune_before_round(&self, round: Round) -> StoreResult<Vec<Certificate>> {
+        let keys: Vec<_> = self
+            .certificate_id_by_round
+            .iter()
+            .take_while(|((r, _), _)| *r < round)
+            .collect();
+        if keys.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let digests: Vec<_> = keys.iter().map(|(_, digest)| *digest).collect();
+        let certificates: Vec<_> = self
+            .certificates_by_id
+            .multi_get(&digests)?
+            .into_iter()
+            .flatten()
+            .collect();
+
+        let mut batch = self.certificates_by_id.batch();
+        batch = batch.delete_batch(&self.certificates_by_id, digests)?;
+        batch = batch.delete_batch(
+            &self.certificate_id_by_round,
+            keys.iter().map(|(key, _)| key.clone()),
+        )?;
+        batch = batch.delete_batch(
+            &self.certificate_id_by_origin,
+            keys.into_iter().map(|((r, origin), _)| (origin, r)),
+        )?;
+        batch.write()?;
+
+        Ok(certificates)
+    }
+
     /// Retrieves origins with certificates in each round >= the provided round.
     pub fn origins_after_round(
         &self,
@@ -409,6 +446,91 @@ impl CertificateStore {
         self.certificates_by_id.is_empty()
     }
 
+    /// Retrieves all the certificates of the main storage, whether they are indexed or not.
+    pub fn all(&self) -> Vec<Certificate> {
+        self.certificates_by_id.values().collect()
+    }
+
+    /// Checks that both secondary indexes match the main storage: every certificate must be
+    /// indexed, and every index entry must point to a stored certificate of its round and origin.
+    pub fn check_indexes(&self) -> StoreResult<Vec<Inconsistency>> {
+        let certificates: HashMap<_, _> = self
+            .certificates_by_id
+            .iter()
+            .map(|(digest, certificate)| (digest, (certificate.round(), certificate.origin())))
+            .collect();
+        let points_to = |digest: &CertificateDigest, round: Round, origin: &PublicKey| {
+            certificates.get(digest) == Some(&(round, origin.clone()))
+        };
+
+        let mut inconsistencies = Vec::new();
+        for ((round, origin), digest) in self.certificate_id_by_round.iter() {
+            if !points_to(&digest, round, &origin) {
+                inconsistencies.push(Inconsistency::DanglingCertificateIndex {
+                    index: BY_ROUND_INDEX,
+                    round,
+                    origin,
+                });
+            }
+        }
+        for ((origin, round), digest) in self.certificate_id_by_origin.iter() {
+            if !points_to(&digest, round, &origin) {
+                inconsistencies.push(Inconsistency::DanglingCertificateIndex {
+                    index: BY_ORIGIN_INDEX,
+                    round,
+                    origin,
+                });
+            }
+        }
+        for (digest, (round, origin)) in &certificates {
+            if self
+                .certificate_id_by_round
+                .get(&(*round, origin.clone()))?
+                .as_ref()
+                != Some(digest)
+            {
+                inconsistencies.push(Inconsistency::UnindexedCertificate {
+                    index: BY_ROUND_INDEX,
+                    digest: *digest,
+                });
+            }
+            if self
+                .certificate_id_by_origin
+                .get(&(origin.clone(), *round))?
+                .as_ref()
+                != Some(digest)
+            {
+                inconsistencies.push(Inconsistency::UnindexedCertificate {
+                    index: BY_ORIGIN_INDEX,
+                    digest: *digest,
+                });
+            }
+        }
+        Ok(inconsistencies)
+    }
+
+    /// Rebuilds both secondary indexes from the main storage.
+    pub fn rebuild_indexes(&self) -> StoreResult<()> {
+        self.certificate_id_by_round.clear()?;
+        self.certificate_id_by_origin.clear()?;
+        let certificates = self.all();
+        self.certificate_id_by_round
+            .batch()
+            .insert_batch(
+                &self.certificate_id_by_round,
+                certificates
+                    .iter()
+                    .map(|c| ((c.round(), c.origin()), c.digest())),
+            )?
+            .insert_batch(
+                &self.certificate_id_by_origin,
+                certificates
+                    .iter()
+                    .map(|c| ((c.origin(), c.round()), c.digest())),
+            )?
+            .write()
+    }
+
     /// Notifies the subscribed ones that listen on updates for the
     /// certificate with the provided id. The obligations are notified
     /// with the provided value. The obligation entries under the certificate id
@@ -433,44 +555,36 @@ mod test {
     use futures::future::join_all;
     use std::{
         collections::{BTreeSet, HashSet},
+        sync::Arc,
         time::Instant,
     };
-    use store::{
-        reopen,
-        rocks::{open_cf, DBMap},
-    };
+    use store::backend::{InMemoryStore, KeyValueStore, RocksDBStore, StoreMap};
     use test_utils::{temp_dir, CommitteeFixture};
     use types::{Certificate, CertificateDigest, Round};
 
-    fn new_store(path: std::path::PathBuf) -> CertificateStore {
-        const CERTIFICATES_CF: &str = "certificates";
-        const CERTIFICATE_ID_BY_ROUND_CF: &str = "certificate_id_by_round";
-        const CERTIFICATE_ID_BY_ORIGIN_CF: &str = "certificate_id_by_origin";
-
-        let rocksdb = open_cf(
-            path,
-            None,
-            &[
-                CERTIFICATES_CF,
-                CERTIFICATE_ID_BY_ROUND_CF,
-                CERTIFICATE_ID_BY_ORIGIN_CF,
-            ],
-        )
-        .expect("Cannot open database");
-
-        let (certificate_map, certificate_id_by_round_map, certificate_id_by_origin_map) = reopen!(&rocksdb,
-            CERTIFICATES_CF;<CertificateDigest, Certificate>,
-            CERTIFICATE_ID_BY_ROUND_CF;<(Round, PublicKey), CertificateDigest>,
-            CERTIFICATE_ID_BY_ORIGIN_CF;<(PublicKey, Round), CertificateDigest>
-        );
+    const CERTIFICATES_CF: &str = "certificates";
+    const CERTIFICATE_ID_BY_ROUND_CF: &str = "certificate_id_by_round";
+    const CERTIFICATE_ID_BY_ORIGIN_CF: &str = "certificate_id_by_origin";
+    const TABLES: [&str; 3] = [
+        CERTIFICATES_CF,
+        CERTIFICATE_ID_BY_ROUND_CF,
+        CERTIFICATE_ID_BY_ORIGIN_CF,
+    ];
 
+    fn store_with(backend: Arc<dyn KeyValueStore>) -> CertificateStore {
         CertificateStore::new(
-            certificate_map,
-            certificate_id_by_round_map,
-            certificate_id_by_origin_map,
+            StoreMap::new(&backend, CERTIFICATES_CF).unwrap(),
+            StoreMap::new(&backend, CERTIFICATE_ID_BY_ROUND_CF).unwrap(),
+            StoreMap::new(&backend, CERTIFICATE_ID_BY_ORIGIN_CF).unwrap(),
         )
     }
 
+    fn new_store(path: std::path::PathBuf) -> CertificateStore {
+        store_with(Arc::new(
+            RocksDBStore::open(path, &TABLES).expect("Cannot open database"),
+        ))
+    }
+
     // helper method that creates certificates for the provided
     // number of rounds.
     fn certificates(rounds: u64) -> Vec<Certificate> {
@@ -585,6 +699,41 @@ mod test {
         assert!(last_round_number_not_exist.is_none());
     }
 
+    #[tokio::test]
+    async fn test_in_memory_backend() {
+        // GIVEN
+        let rocks_store = new_store(temp_dir());
+        let memory_store = store_with(Arc::new(InMemoryStore::new(&TABLES)));
+
+        // create certificates for 20 rounds
+        let certs = certificates(20);
+        let origin = certs[0].origin();
+        rocks_store.write_all(certs.clone()).unwrap();
+        memory_store.write_all(certs).unwrap();
+
+        // THEN both backends give the same answers
+        for store in [&rocks_store, &memory_store] {
+            let mut rounds: Vec<_> = store
+                .last_two_rounds_certs()
+                .unwrap()
+                .iter()
+                .map(|c| c.round())
+                .collect();
+            rounds.dedup();
+            assert_eq!(rounds, vec![20, 19]);
+            assert_eq!(store.last_round_number(&origin).unwrap(), Some(20));
+            assert_eq!(store.next_round_number(&origin, 5).unwrap(), Some(6));
+            assert_eq!(store.after_round(15).unwrap().len(), 6 * 4);
+        }
+        assert_eq!(
+            rocks_store.origins_after_round(10).unwrap(),
+            memory_store.origins_after_round(10).unwrap()
+        );
+
+        memory_store.clear().unwrap();
+        assert!(memory_store.is_empty());
+    }
+
     #[tokio::test]
     async fn test_last_round_in_empty_store() {
         // GIVEN
@@ -800,4 +949,36 @@ mod test {
         assert!(store.read(to_delete[0]).unwrap().is_none());
         assert!(store.read(to_delete[1]).unwrap().is_none());
     }
+
+    #[tokio::test]
+    async fn test_prune_before_round() {
+        // GIVEN
+        let store = new_store(temp_dir());
+
+        // create certificates for 10 rounds
+        let certs = certificates(10);
+        let origin = certs[0].origin();
+        store.write_all(certs.clone()).unwrap();
+
+        // WHEN
+        let pruned = store.prune_before_round(4).unwrap();
+
+        // THEN the certificates of rounds 1 to 3 are gone
+        assert_eq!(pruned.len(), 3 * 4);
+        assert!(pruned.iter().all(|c| c.round() < 4));
+        for certificate in &pruned {
+            assert!(store.read(certificate.digest()).unwrap().is_none());
+        }
+
+        // AND the others are kept, in every index
+        assert_eq!(store.after_round(0).unwrap().len(), 7 * 4);
+        assert_eq!(
+            store.origins_after_round(0).unwrap().keys().next(),
+            Some(&4)
+        );
+        assert_eq!(store.last_round_number(&origin).unwrap(), Some(10));
+
+        // AND pruning again is a no-op
+        assert!(store.prune_before_round(4).unwrap().is_empty());
+    }
 }
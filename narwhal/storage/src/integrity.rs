@@ -0,0 +1,296 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Cross-checks of the stores of a node, e.g. after an unclean shutdown: the certificates against
+//! their indexes and parents, the headers against the payload tokens, and the payload tokens
+//! against the batches.
+use crate::NodeStorage;
+use config::WorkerId;
+use crypto::PublicKey;
+use fastcrypto::hash::Hash;
+use std::{collections::HashSet, fmt};
+use types::{BatchDigest, CertificateDigest, HeaderDigest, Round, StoreResult};
+
+/// A reference between the stores that does not resolve.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Inconsistency {
+    /// An entry of a secondary index of the certificates points to a missing certificate, or to
+    /// one of another round or origin.
+    DanglingCertificateIndex {
+        index: &'static str,
+        round: Round,
+        origin: PublicKey,
+    },
+    /// A certificate is missing from a secondary index.
+    UnindexedCertificate {
+        index: &'static str,
+        digest: CertificateDigest,
+    },
+    /// A certificate has a parent that is not stored, while the round of the parent is.
+    MissingParent {
+        certificate: CertificateDigest,
+        parent: CertificateDigest,
+    },
+    /// A header has a batch that has no payload token.
+    MissingPayload {
+        header: HeaderDigest,
+        batch: BatchDigest,
+        worker_id: WorkerId,
+    },
+    /// A payload token is for a batch that is not stored.
+    MissingBatch {
+        batch: BatchDigest,
+        worker_id: WorkerId,
+    },
+}
+
+impl fmt::Display for Inconsistency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DanglingCertificateIndex {
+                index,
+                round,
+                origin,
+            } => write!(
+                f,
+                "The certificate index {index} has a dangling entry for round {round} of {origin}"
+            ),
+            Self::UnindexedCertificate { index, digest } => {
+                write!(f, "Certificate {digest} is missing from the index {index}")
+            }
+            Self::MissingParent {
+                certificate,
+                parent,
+            } => write!(f, "Certificate {certificate} has missing parent {parent}"),
+            Self::MissingPayload {
+                header,
+                batch,
+                worker_id,
+            } => write!(
+                f,
+                "Header {header} has batch {batch} of worker {worker_id} without payload token"
+            ),
+            Self::MissingBatch { batch, worker_id } => write!(
+                f,
+                "The payload token of batch {batch} of worker {worker_id} has no batch"
+            ),
+        }
+    }
+}
+
+/// What [`NodeStorage::verify`] checks, and whether it repairs the stores.
+#[derive(Clone, Debug, Default)]
+pub struct VerifyOptions {
+    /// Whether the batches referenced by the payload tokens must be in the same storage, i.e.
+    /// the workers share the storage of their primary.
+    pub check_batches: bool,
+    /// Whether to delete the inconsistent entries. The node then fetches the deleted
+    /// certificates, payloads and batches again from its peers and workers.
+    pub repair: bool,
+}
+
+/// The result of [`NodeStorage::verify`].
+#[derive(Clone, Debug, Default)]
+pub struct IntegrityReport {
+    pub certificates: usize,
+    pub headers: usize,
+    pub payload_tokens: usize,
+    /// What was found, before any repair.
+    pub inconsistencies: Vec<Inconsistency>,
+}
+
+impl NodeStorage {
+    /// Checks that the stores are mutually consistent, and optionally repairs them. The node must
+    /// not be running.
+    ///
+    /// Repairing rebuilds the certificate indexes, and deletes the certificates with missing
+    /// parents (along their descendants), the headers with missing payload tokens and the payload
+    /// tokens with missing batches.
+    pub async fn verify(&self, options: &VerifyOptions) -> StoreResult<IntegrityReport> {
+        let mut inconsistencies = self.certificate_store.check_indexes()?;
+
+        // Parents are stored before their children, except below the lowest stored round: those
+        // were pruned, or never fetched.
+        let mut certificates = self.certificate_store.all();
+        certificates.sort_by_key(|certificate| certificate.round());
+        let lowest_round = certificates.first().map_or(0, |c| c.round());
+        let mut present: HashSet<_> = certificates.iter().map(|c| c.digest()).collect();
+        let mut orphans = Vec::new();
+        for certificate in &certificates {
+            if certificate.round() == lowest_round {
+                continue;
+            }
+            let missing: Vec<_> = certificate
+                .header
+                .parents
+                .iter()
+                .filter(|parent| !present.contains(parent))
+                .collect();
+            if !missing.is_empty() {
+                let digest = certificate.digest();
+                inconsistencies.extend(missing.into_iter().map(|parent| {
+                    Inconsistency::MissingParent {
+                        certificate: digest,
+                        parent: *parent,
+                    }
+                }));
+                // The descendants of an orphan are orphans as well once it is deleted.
+                present.remove(&digest);
+                orphans.push(digest);
+            }
+        }
+
+        let headers = self.header_store.iter(None).await;
+        let tokens = self.payload_store.iter(None).await;
+        let mut bad_headers = Vec::new();
+        for (digest, header) in &headers {
+            let missing: Vec<_> = header
+                .payload
+                .iter()
+                .filter(|(batch, worker_id)| !tokens.contains_key(&(**batch, **worker_id)))
+                .map(|(batch, worker_id)| Inconsistency::MissingPayload {
+                    header: *digest,
+                    batch: *batch,
+                    worker_id: *worker_id,
+                })
+                .collect();
+            if !missing.is_empty() {
+                inconsistencies.extend(missing);
+                bad_headers.push(*digest);
+            }
+        }
+
+        let mut bad_tokens = Vec::new();
+        if options.check_batches {
+            let keys: Vec<_> = tokens.keys().cloned().collect();
+            let batches = self
+                .batch_store
+                .read_all(keys.iter().map(|(batch, _)| *batch))
+                .await?;
+            for ((batch, worker_id), stored) in keys.into_iter().zip(batches) {
+                if stored.is_none() {
+                    inconsistencies.push(Inconsistency::MissingBatch { batch, worker_id });
+                    bad_tokens.push((batch, worker_id));
+                }
+            }
+        }
+
+        if options.repair {
+            self.certificate_store.delete_all(orphans)?;
+            self.certificate_store.rebuild_indexes()?;
+            self.header_store.remove_all(bad_headers).await?;
+            self.payload_store.remove_all(bad_tokens).await?;
+        }
+
+        Ok(IntegrityReport {
+            certificates: certificates.len(),
+            headers: headers.len(),
+            payload_tokens: tokens.len(),
+            inconsistencies,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Inconsistency, NodeStorage, VerifyOptions};
+    use fastcrypto::hash::Hash;
+    use std::collections::BTreeSet;
+    use test_utils::{batch, CommitteeFixture};
+    use types::Certificate;
+
+    #[tokio::test]
+    async fn test_verify_and_repair() {
+        let fixture = CommitteeFixture::builder().build();
+        let committee = fixture.committee();
+        let genesis = Certificate::genesis(&committee)
+            .iter()
+            .map(|x| x.digest())
+            .collect::<BTreeSet<_>>();
+        let (_, headers) = fixture.headers_round(0, &genesis);
+        let round_1: Vec<_> = headers.iter().map(|h| fixture.certificate(h)).collect();
+        let parents = round_1.iter().map(|c| c.digest()).collect();
+        let (_, headers) = fixture.headers_round(1, &parents);
+        let round_2: Vec<_> = headers.iter().map(|h| fixture.certificate(h)).collect();
+
+        let storage = NodeStorage::in_memory();
+        storage
+            .certificate_store
+            .write_all(round_1.iter().chain(&round_2).cloned())
+            .unwrap();
+        let options = VerifyOptions {
+            check_batches: true,
+            repair: false,
+        };
+        assert!(storage
+            .verify(&options)
+            .await
+            .unwrap()
+            .inconsistencies
+            .is_empty());
+
+        // Lose a certificate of round 1, along with its index entries.
+        let lost = round_1[0].digest();
+        storage.certificate_store.delete(lost).unwrap();
+        // Store a header without its payload token, and a payload token without its batch.
+        let header = &headers[0];
+        storage
+            .header_store
+            .sync_write(header.digest(), header.clone())
+            .await
+            .unwrap();
+        let (digest, worker_id) = (batch().digest(), 0);
+        storage
+            .payload_store
+            .sync_write((digest, worker_id), 0u8)
+            .await
+            .unwrap();
+
+        let report = storage.verify(&options).await.unwrap();
+        assert_eq!(report.certificates, round_1.len() + round_2.len() - 1);
+        // The deletion left the origin index dangling.
+        assert!(report.inconsistencies.iter().any(|i| matches!(
+            i,
+            Inconsistency::DanglingCertificateIndex {
+                index: "by origin",
+                ..
+            }
+        )));
+        // All the certificates of round 2 have the lost certificate as parent.
+        for certificate in &round_2 {
+            assert!(report
+                .inconsistencies
+                .contains(&Inconsistency::MissingParent {
+                    certificate: certificate.digest(),
+                    parent: lost,
+                }));
+        }
+        for (batch, worker_id) in &header.payload {
+            assert!(report
+                .inconsistencies
+                .contains(&Inconsistency::MissingPayload {
+                    header: header.digest(),
+                    batch: *batch,
+                    worker_id: *worker_id,
+                }));
+        }
+        assert!(report
+            .inconsistencies
+            .contains(&Inconsistency::MissingBatch {
+                batch: digest,
+                worker_id,
+            }));
+
+        // Once repaired, the stores are consistent.
+        let repair = VerifyOptions {
+            repair: true,
+            ..options.clone()
+        };
+        storage.verify(&repair).await.unwrap();
+        let report = storage.verify(&options).await.unwrap();
+        assert!(report.inconsistencies.is_empty(), "{:?}", report);
+        assert_eq!(report.certificates, round_1.len() - 1);
+        assert_eq!(report.headers, 0);
+        assert_eq!(report.payload_tokens, 0);
+    }
+}
@@ -1,10 +1,18 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+mod analytics;
 mod certificate_store;
+mod integrity;
+mod metrics;
 mod node_store;
 mod proposer_store;
+mod snapshot;
 
+pub use analytics::*;
 pub use certificate_store::*;
+pub use integrity::*;
+pub use metrics::*;
 pub use node_store::*;
 pub use proposer_store::*;
+pub use snapshot::*;
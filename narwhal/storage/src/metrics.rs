@@ -0,0 +1,246 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Metrics of the node's storage, per table (i.e. per RocksDB column family): the statistics
+//! reported by the storage engine, refreshed periodically, and the latency of the reads and
+//! writes.
+use prometheus::{
+    register_histogram_vec_with_registry, register_int_counter_vec_with_registry,
+    register_int_gauge_vec_with_registry, HistogramVec, IntCounterVec, IntGaugeVec, Registry,
+};
+use std::{
+    collections::BTreeSet,
+    path::Path,
+    sync::{Arc, Weak},
+    time::{Duration, Instant},
+};
+use store::{
+    backend::{Direction, KeyValueStore, Seek, TableStats, WriteOp},
+    rocks::TypedStoreError,
+};
+use tokio::{task::JoinHandle, time::interval};
+use tracing::warn;
+
+const LATENCY_SEC_BUCKETS: &[f64] = &[
+    0.000_01, 0.000_05, 0.000_1, 0.000_5, 0.001, 0.005, 0.01, 0.05, 0.1, 0.5, 1.0, 5.0,
+];
+
+#[derive(Clone, Debug)]
+pub struct StorageMetrics {
+    /// The size of the data of each table
+    pub table_size: IntGaugeVec,
+    /// The estimated number of keys of each table
+    pub estimated_keys: IntGaugeVec,
+    /// The estimated number of bytes compaction has to rewrite, per table
+    pub pending_compaction_bytes: IntGaugeVec,
+    /// Whether the writes to each table are stopped
+    pub write_stopped: IntGaugeVec,
+    /// The rate the writes to each table are slowed down to, 0 when they are not
+    pub delayed_write_rate: IntGaugeVec,
+    /// The number of refreshes that found the writes to each table stopped or slowed down
+    pub write_stalls: IntCounterVec,
+    /// The latency of the reads (lookups and iterator creations), per table
+    pub read_latency: HistogramVec,
+    /// The latency of the write batches, per table they write to
+    pub write_latency: HistogramVec,
+}
+
+impl StorageMetrics {
+    pub fn new(registry: &Registry) -> Self {
+        Self {
+            table_size: register_int_gauge_vec_with_registry!(
+                "storage_table_size_bytes",
+                "The size of the data of the table; for RocksDB, of its data files",
+                &["table"],
+                registry
+            )
+            .unwrap(),
+            estimated_keys: register_int_gauge_vec_with_registry!(
+                "storage_estimated_keys",
+                "The estimated number of keys of the table",
+                &["table"],
+                registry
+            )
+            .unwrap(),
+            pending_compaction_bytes: register_int_gauge_vec_with_registry!(
+                "storage_pending_compaction_bytes",
+                "The estimated number of bytes compaction has to rewrite to bring the table back in shape",
+                &["table"],
+                registry
+            )
+            .unwrap(),
+            write_stopped: register_int_gauge_vec_with_registry!(
+                "storage_write_stopped",
+                "A flag set to 1 while the writes to the table are stopped",
+                &["table"],
+                registry
+            )
+            .unwrap(),
+            delayed_write_rate: register_int_gauge_vec_with_registry!(
+                "storage_delayed_write_rate",
+                "The rate in bytes per second the writes to the table are slowed down to, 0 when they are not",
+                &["table"],
+                registry
+            )
+            .unwrap(),
+            write_stalls: register_int_counter_vec_with_registry!(
+                "storage_write_stalls",
+                "The number of metrics refreshes that found the writes to the table stopped or slowed down",
+                &["table"],
+                registry
+            )
+            .unwrap(),
+            read_latency: register_histogram_vec_with_registry!(
+                "storage_read_latency",
+                "The latency of the reads of the table, in seconds",
+                &["table"],
+                // buckets in seconds
+                LATENCY_SEC_BUCKETS.to_vec(),
+                registry
+            )
+            .unwrap(),
+            write_latency: register_histogram_vec_with_registry!(
+                "storage_write_latency",
+                "The latency of the write batches to the table, in seconds",
+                &["table"],
+                // buckets in seconds
+                LATENCY_SEC_BUCKETS.to_vec(),
+                registry
+            )
+            .unwrap(),
+        }
+    }
+
+    /// Updates the metrics of all the tables of `store` with their latest statistics.
+    pub fn refresh(&self, store: &dyn KeyValueStore) {
+        for table in store.tables() {
+            match store.stats(&table) {
+                Ok(stats) => self.report(&table, &stats),
+                Err(e) => warn!("Failed to read the statistics of {table}: {e}"),
+            }
+        }
+    }
+
+    fn report(&self, table: &str, stats: &TableStats) {
+        let labels = &[table];
+        self.table_size
+            .with_label_values(labels)
+            .set(stats.size as i64);
+        self.estimated_keys
+            .with_label_values(labels)
+            .set(stats.estimated_keys as i64);
+        self.pending_compaction_bytes
+            .with_label_values(labels)
+            .set(stats.pending_compaction_bytes as i64);
+        self.write_stopped
+            .with_label_values(labels)
+            .set(stats.write_stopped as i64);
+        self.delayed_write_rate
+            .with_label_values(labels)
+            .set(stats.delayed_write_rate as i64);
+        if stats.write_stopped || stats.delayed_write_rate > 0 {
+            self.write_stalls.with_label_values(labels).inc();
+        }
+    }
+
+    /// Refreshes the metrics of `store` every `period`, until the store is dropped.
+    pub(crate) fn spawn_refresher(
+        self: Arc<Self>,
+        store: Weak<dyn KeyValueStore>,
+        period: Duration,
+    ) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut timer = interval(period);
+            loop {
+                timer.tick().await;
+                match store.upgrade() {
+                    Some(store) => self.refresh(store.as_ref()),
+                    None => return,
+                }
+            }
+        })
+    }
+}
+
+/// A storage engine measuring the latency of the operations of another one.
+pub struct MeteredStore {
+    inner: Arc<dyn KeyValueStore>,
+    metrics: Arc<StorageMetrics>,
+}
+
+impl MeteredStore {
+    pub fn new(inner: Arc<dyn KeyValueStore>, metrics: Arc<StorageMetrics>) -> Self {
+        Self { inner, metrics }
+    }
+}
+
+impl KeyValueStore for MeteredStore {
+    fn get(&self, table: &str, key: &[u8]) -> Result<Option<Vec<u8>>, TypedStoreError> {
+        let _timer = self
+            .metrics
+            .read_latency
+            .with_label_values(&[table])
+            .start_timer();
+        self.inner.get(table, key)
+    }
+
+    fn write(&self, batch: Vec<WriteOp>) -> Result<(), TypedStoreError> {
+        let tables: BTreeSet<_> = batch
+            .iter()
+            .map(|op| match op {
+                WriteOp::Put { table, .. }
+                | WriteOp::Delete { table, .. }
+                | WriteOp::DeleteRange { table, .. } => table.clone(),
+            })
+            .collect();
+        let start = Instant::now();
+        let result = self.inner.write(batch);
+        let elapsed = start.elapsed().as_secs_f64();
+        for table in tables {
+            self.metrics
+                .write_latency
+                .with_label_values(&[&table])
+                .observe(elapsed);
+        }
+        result
+    }
+
+    /// Only the creation of the iterator is measured, not the iteration itself.
+    fn iter<'a>(
+        &'a self,
+        table: &str,
+        seek: Seek,
+        direction: Direction,
+    ) -> Result<Box<dyn Iterator<Item = (Vec<u8>, Vec<u8>)> + 'a>, TypedStoreError> {
+        let _timer = self
+            .metrics
+            .read_latency
+            .with_label_values(&[table])
+            .start_timer();
+        self.inner.iter(table, seek, direction)
+    }
+
+    fn tables(&self) -> Vec<String> {
+        self.inner.tables()
+    }
+
+    fn stats(&self, table: &str) -> Result<TableStats, TypedStoreError> {
+        self.inner.stats(table)
+    }
+
+    fn sync(&self) -> Result<(), TypedStoreError> {
+        self.inner.sync()
+    }
+
+    fn checkpoint(&self, path: &Path) -> Result<(), TypedStoreError> {
+        self.inner.checkpoint(path)
+    }
+
+    fn compact(&self, table: &str) -> Result<(), TypedStoreError> {
+        self.inner.compact(table)
+    }
+
+    fn catch_up(&self) -> Result<(), TypedStoreError> {
+        self.inner.catch_up()
+    }
+}
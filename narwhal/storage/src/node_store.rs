@@ -1,22 +1,34 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
-use crate::proposer_store::ProposerKey;
-use crate::{CertificateStore, ProposerStore};
-use config::WorkerId;
+use crate::{CertificateStore, MeteredStore, ProposerStore, StorageMetrics};
+use config::{
+    ColumnFamilyParameters, CompactionStyle, Compression, Epoch, StorageLayout, StorageParameters,
+    SyncPolicy, WorkerId,
+};
 use crypto::PublicKey;
-use std::sync::Arc;
-use store::rocks::open_cf;
-use store::rocks::DBMap;
-use store::{reopen, Store};
+use prometheus::Registry;
+use rocksdb::{BlockBasedOptions, Cache, DBCompactionStyle, DBCompressionType, Options};
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+};
+use store::backend::{
+    BufferedStore, EncryptedStore, EpochStore, InMemoryStore, KeyValueStore, Keyring, RocksDBStore,
+    StoreMap, TableStats, WritePolicy,
+};
+use store::rocks::{default_db_options, TypedStoreError};
+use store::Store;
+use tokio::time::interval;
+use tracing::warn;
 use types::{
-    Batch, BatchDigest, Certificate, CertificateDigest, CommittedSubDagShell, ConsensusStore,
-    Header, HeaderDigest, Round, SequenceNumber, VoteInfo,
+    Batch, BatchDigest, CertificateDigest, ConsensusStore, Header, HeaderDigest, VoteInfo,
 };
 
 // A type alias marking the "payload" tokens sent by workers to their primary as batch acknowledgements
 pub type PayloadToken = u8;
 
-/// All the data stores of the node.
+/// All the data stores of the node. Cloning it shares the stores.
+#[derive(Clone)]
 pub struct NodeStorage {
     pub proposer_store: ProposerStore,
     pub vote_digest_store: Store<PublicKey, VoteInfo>,
@@ -26,6 +38,8 @@ pub struct NodeStorage {
     pub batch_store: Store<BatchDigest, Batch>,
     pub consensus_store: Arc<ConsensusStore>,
     pub temp_batch_store: Store<(CertificateDigest, BatchDigest), Batch>,
+    /// The storage engine holding all the stores.
+    backend: Arc<dyn KeyValueStore>,
 }
 
 impl NodeStorage {
@@ -42,65 +56,292 @@ impl NodeStorage {
     const SUB_DAG_INDEX_CF: &'static str = "sub_dag";
     const TEMP_BATCH_CF: &'static str = "temp_batches";
 
-    /// Open or reopen all the storage of the node.
+    /// The tables of all the stores of the node.
+    pub const TABLES: [&'static str; 11] = [
+        Self::LAST_PROPOSED_CF,
+        Self::VOTES_CF,
+        Self::HEADERS_CF,
+        Self::CERTIFICATES_CF,
+        Self::CERTIFICATE_DIGEST_BY_ROUND_CF,
+        Self::CERTIFICATE_DIGEST_BY_ORIGIN_CF,
+        Self::PAYLOAD_CF,
+        Self::BATCHES_CF,
+        Self::LAST_COMMITTED_CF,
+        Self::SUB_DAG_INDEX_CF,
+        Self::TEMP_BATCH_CF,
+    ];
+
+    /// Open or reopen all the storage of the node, in a RocksDB database at `store_path`.
     pub fn reopen<Path: AsRef<std::path::Path>>(store_path: Path) -> Self {
-        let rocksdb = open_cf(
+        // Without encryption, the epoch does not matter.
+        Self::reopen_with_parameters(store_path, &StorageParameters::default(), 0)
+    }
+
+    /// Open or reopen all the storage of the node of `epoch`, in a RocksDB database at
+    /// `store_path` tuned and (optionally) encrypted according to `parameters`. Stores encrypted
+    /// with keys from elsewhere (e.g. a KMS) can be opened with an `EncryptedStore` backend.
+    pub fn reopen_with_parameters<Path: AsRef<std::path::Path>>(
+        store_path: Path,
+        parameters: &StorageParameters,
+        epoch: Epoch,
+    ) -> Self {
+        Self::open(Self::open_backend(store_path, parameters, epoch))
+    }
+
+    /// Like [`NodeStorage::reopen_with_parameters`], also reporting the metrics of every table
+    /// to `registry`. The statistics of the tables are refreshed every
+    /// `parameters.metrics_interval`, as long as the storage is in use.
+    pub fn reopen_with_metrics<Path: AsRef<std::path::Path>>(
+        store_path: Path,
+        parameters: &StorageParameters,
+        epoch: Epoch,
+        registry: &Registry,
+    ) -> Self {
+        let backend = Self::open_backend(store_path, parameters, epoch);
+        Self::open_with_metrics(backend, parameters, registry)
+    }
+
+    /// Opens all the storage of the node of `epoch` in `database`, a database shared by all the
+    /// epochs (see [`NodeStorage::open_database`]), reporting its metrics to `registry`.
+    pub fn open_epoch(
+        database: &Arc<dyn KeyValueStore>,
+        parameters: &StorageParameters,
+        epoch: Epoch,
+        registry: &Registry,
+    ) -> Self {
+        let backend = Self::with_encryption(
+            Arc::new(EpochStore::new(database.clone(), epoch)),
+            parameters,
+            epoch,
+        );
+        let backend = Self::with_write_policies(backend, parameters);
+        Self::open_with_metrics(backend, parameters, registry)
+    }
+
+    /// Opens the storage of the node of `epoch` at `store_path` read-only, while the node may be
+    /// running: the database is open as a RocksDB secondary instance, keeping its logs in
+    /// `secondary_path`, so it takes no lock and never writes. The stores see the writes of the
+    /// node up to the opening, then up to every [`NodeStorage::catch_up`]. All writes fail.
+    pub fn open_read_only<Path: AsRef<std::path::Path>>(
+        store_path: Path,
+        secondary_path: Path,
+        parameters: &StorageParameters,
+        epoch: Epoch,
+    ) -> Result<Self, TypedStoreError> {
+        let database: Arc<dyn KeyValueStore> = Arc::new(RocksDBStore::open_secondary(
             store_path,
-            None,
-            &[
-                Self::LAST_PROPOSED_CF,
-                Self::VOTES_CF,
-                Self::HEADERS_CF,
-                Self::CERTIFICATES_CF,
-                Self::CERTIFICATE_DIGEST_BY_ROUND_CF,
-                Self::CERTIFICATE_DIGEST_BY_ORIGIN_CF,
-                Self::PAYLOAD_CF,
-                Self::BATCHES_CF,
-                Self::LAST_COMMITTED_CF,
-                Self::SUB_DAG_INDEX_CF,
-                Self::TEMP_BATCH_CF,
-            ],
+            secondary_path,
+            &Self::TABLES,
+        )?);
+        let backend = match parameters.layout {
+            StorageLayout::PerEpoch => database,
+            StorageLayout::Shared => Arc::new(EpochStore::new(database, epoch)),
+        };
+        Ok(Self::open(Self::with_encryption(
+            backend, parameters, epoch,
+        )))
+    }
+
+    /// Catches up with the latest writes of the node, for storage open with
+    /// [`NodeStorage::open_read_only`]. Does nothing for other storage, always up to date.
+    pub fn catch_up(&self) -> Result<(), TypedStoreError> {
+        self.backend.catch_up()
+    }
+
+    /// The names of the tables of all the stores, i.e. [`NodeStorage::TABLES`].
+    pub fn tables(&self) -> Vec<String> {
+        self.backend.tables()
+    }
+
+    /// The statistics of `table`, as estimated by the storage engine.
+    pub fn table_stats(&self, table: &str) -> Result<TableStats, TypedStoreError> {
+        self.backend.stats(table)
+    }
+
+    /// Compacts all the tables, e.g. after pruning to reclaim the space of the deleted data.
+    /// This rewrites the whole database, so it can take a while.
+    pub fn compact(&self) -> Result<(), TypedStoreError> {
+        for table in self.backend.tables() {
+            self.backend.compact(&table)?;
+        }
+        Ok(())
+    }
+
+    /// Deletes the data of all the epochs before `epoch` from `database`, a database shared by
+    /// all the epochs.
+    pub fn delete_epochs_before(
+        database: &Arc<dyn KeyValueStore>,
+        epoch: Epoch,
+    ) -> Result<(), TypedStoreError> {
+        EpochStore::delete_epochs_before(database.as_ref(), epoch)
+    }
+
+    fn open_backend<Path: AsRef<std::path::Path>>(
+        store_path: Path,
+        parameters: &StorageParameters,
+        epoch: Epoch,
+    ) -> Arc<dyn KeyValueStore> {
+        let backend = Self::with_encryption(
+            Self::open_database(store_path, parameters),
+            parameters,
+            epoch,
+        );
+        Self::with_write_policies(backend, parameters)
+    }
+
+    fn open_with_metrics(
+        backend: Arc<dyn KeyValueStore>,
+        parameters: &StorageParameters,
+        registry: &Registry,
+    ) -> Self {
+        let metrics = Arc::new(StorageMetrics::new(registry));
+        metrics
+            .clone()
+            .spawn_refresher(Arc::downgrade(&backend), parameters.metrics_interval);
+        Self::open(Arc::new(MeteredStore::new(backend, metrics)))
+    }
+
+    /// Encrypts the values of `backend` if `parameters` enable encryption, with the key of
+    /// `epoch`.
+    fn with_encryption(
+        backend: Arc<dyn KeyValueStore>,
+        parameters: &StorageParameters,
+        epoch: Epoch,
+    ) -> Arc<dyn KeyValueStore> {
+        match &parameters.encryption_keys_file {
+            Some(path) => {
+                let keyring = load_keyring(path, epoch).expect("Cannot load the encryption keys");
+                Arc::new(EncryptedStore::new(backend, keyring))
+            }
+            None => backend,
+        }
+    }
+
+    /// Coalesces and syncs the writes to `backend` according to the column family `parameters`.
+    /// The buffered writes are flushed, and the ones synced `Interval` made durable, by a
+    /// background task as long as the storage is in use.
+    fn with_write_policies(
+        backend: Arc<dyn KeyValueStore>,
+        parameters: &StorageParameters,
+    ) -> Arc<dyn KeyValueStore> {
+        let policies: HashMap<_, _> = Self::TABLES
+            .iter()
+            .map(|name| {
+                (
+                    name.to_string(),
+                    write_policy(&parameters.for_column_family(name)),
+                )
+            })
+            .filter(|(_, policy)| *policy != WritePolicy::default())
+            .collect();
+        if policies.is_empty() {
+            return backend;
+        }
+
+        let buffered = Arc::new(BufferedStore::new(
+            backend,
+            policies,
+            // Consensus commits by writing the last committed rounds and the committed sub-dag.
+            &[Self::LAST_COMMITTED_CF, Self::SUB_DAG_INDEX_CF],
+            parameters.writes.flush_bytes,
+        ));
+        let store = Arc::downgrade(&buffered);
+        let (flush_interval, sync_interval) = (
+            parameters.writes.flush_interval,
+            parameters.writes.sync_interval,
+        );
+        tokio::spawn(async move {
+            let mut flush_timer = interval(flush_interval);
+            let mut sync_timer = interval(sync_interval);
+            loop {
+                let sync = tokio::select! {
+                    _ = flush_timer.tick() => false,
+                    _ = sync_timer.tick() => true,
+                };
+                let store = match store.upgrade() {
+                    Some(store) => store,
+                    None => return,
+                };
+                let result = if sync {
+                    store.flush_and_sync()
+                } else {
+                    store.flush()
+                };
+                if let Err(e) = result {
+                    warn!("Failed to flush the buffered writes: {e}");
+                }
+            }
+        });
+        buffered
+    }
+
+    /// Opens (or creates) the RocksDB database at `store_path`, with the tables of all the stores
+    /// tuned according to `parameters`, without encryption.
+    pub fn open_database<Path: AsRef<std::path::Path>>(
+        store_path: Path,
+        parameters: &StorageParameters,
+    ) -> Arc<dyn KeyValueStore> {
+        for name in parameters.column_families.keys() {
+            if Self::TABLES.iter().all(|table| *table != name.as_str()) {
+                warn!("Ignoring the options of unknown column family {name}");
+            }
+        }
+
+        let db_options = db_options(parameters);
+        // The block cache is shared by all the column families.
+        let block_cache = parameters
+            .block_cache_size
+            .map(|size| Cache::new_lru_cache(size).expect("Cannot create the block cache"));
+        let cf_options: Vec<_> = Self::TABLES
+            .iter()
+            .map(|name| {
+                let options = column_family_options(
+                    &db_options,
+                    &parameters.for_column_family(name),
+                    block_cache.as_ref(),
+                );
+                (*name, options)
+            })
+            .collect();
+        let cf_options: Vec<_> = cf_options
+            .iter()
+            .map(|(name, options)| (*name, options))
+            .collect();
+
+        Arc::new(
+            RocksDBStore::open_with_options(store_path, db_options, &cf_options)
+                .expect("Cannot open database"),
         )
-        .expect("Cannot open database");
-
-        let (
-            last_proposed_map,
-            votes_map,
-            header_map,
-            certificate_map,
-            certificate_digest_by_round_map,
-            certificate_digest_by_origin_map,
-            payload_map,
-            batch_map,
-            last_committed_map,
-            sub_dag_index_map,
-            temp_batch_map,
-        ) = reopen!(&rocksdb,
-            Self::LAST_PROPOSED_CF;<ProposerKey, Header>,
-            Self::VOTES_CF;<PublicKey, VoteInfo>,
-            Self::HEADERS_CF;<HeaderDigest, Header>,
-            Self::CERTIFICATES_CF;<CertificateDigest, Certificate>,
-            Self::CERTIFICATE_DIGEST_BY_ROUND_CF;<(Round, PublicKey), CertificateDigest>,
-            Self::CERTIFICATE_DIGEST_BY_ORIGIN_CF;<(PublicKey, Round), CertificateDigest>,
-            Self::PAYLOAD_CF;<(BatchDigest, WorkerId), PayloadToken>,
-            Self::BATCHES_CF;<BatchDigest, Batch>,
-            Self::LAST_COMMITTED_CF;<PublicKey, Round>,
-            Self::SUB_DAG_INDEX_CF;<SequenceNumber, CommittedSubDagShell>,
-            Self::TEMP_BATCH_CF;<(CertificateDigest, BatchDigest), Batch>
-        );
-
-        let proposer_store = ProposerStore::new(last_proposed_map);
-        let vote_digest_store = Store::new(votes_map);
-        let header_store = Store::new(header_map);
+    }
+
+    /// Creates storage for the node that is only kept in memory, and lost when the node stops.
+    pub fn in_memory() -> Self {
+        Self::open(Arc::new(InMemoryStore::new(&Self::TABLES)))
+    }
+
+    /// Opens all the storage of the node on the given backend, which must hold the tables of
+    /// [`NodeStorage::TABLES`].
+    pub fn open(backend: Arc<dyn KeyValueStore>) -> Self {
+        // Maps of different types cannot share a closure.
+        fn map<K, V>(backend: &Arc<dyn KeyValueStore>, table: &str) -> StoreMap<K, V> {
+            StoreMap::new(backend, table).expect("Cannot open database")
+        }
+
+        let proposer_store = ProposerStore::new(map(&backend, Self::LAST_PROPOSED_CF));
+        let vote_digest_store = Store::from_map(map(&backend, Self::VOTES_CF));
+        let header_store = Store::from_map(map(&backend, Self::HEADERS_CF));
         let certificate_store = CertificateStore::new(
-            certificate_map,
-            certificate_digest_by_round_map,
-            certificate_digest_by_origin_map,
+            map(&backend, Self::CERTIFICATES_CF),
+            map(&backend, Self::CERTIFICATE_DIGEST_BY_ROUND_CF),
+            map(&backend, Self::CERTIFICATE_DIGEST_BY_ORIGIN_CF),
         );
-        let payload_store = Store::new(payload_map);
-        let batch_store = Store::new(batch_map);
-        let consensus_store = Arc::new(ConsensusStore::new(last_committed_map, sub_dag_index_map));
-        let temp_batch_store = Store::new(temp_batch_map);
+        let payload_store = Store::from_map(map(&backend, Self::PAYLOAD_CF));
+        let batch_store = Store::from_map(map(&backend, Self::BATCHES_CF));
+        let consensus_store = Arc::new(ConsensusStore::new(
+            map(&backend, Self::LAST_COMMITTED_CF),
+            map(&backend, Self::SUB_DAG_INDEX_CF),
+        ));
+        let temp_batch_store = Store::from_map(map(&backend, Self::TEMP_BATCH_CF));
 
         Self {
             proposer_store,
@@ -111,6 +352,386 @@ impl NodeStorage {
             batch_store,
             consensus_store,
             temp_batch_store,
+            backend,
+        }
+    }
+}
+
+/// Loads the encryption keys of the file at `path`, activating the key of the latest epoch not
+/// after `epoch`.
+fn load_keyring(path: &std::path::Path, epoch: Epoch) -> Result<Keyring, String> {
+    let context = |e: String| format!("{}: {e}", path.display());
+    let data = std::fs::read(path).map_err(|e| context(e.to_string()))?;
+    let encoded: BTreeMap<Epoch, String> =
+        serde_json::from_slice(&data).map_err(|e| context(e.to_string()))?;
+    let keys = encoded
+        .into_iter()
+        .map(|(key_epoch, key)| {
+            let key = base64::decode(key)
+                .map_err(|e| e.to_string())
+                .and_then(|key| {
+                    <[u8; 32]>::try_from(key).map_err(|_| "keys must be 256 bits long".to_string())
+                })
+                .map_err(|e| context(format!("invalid key of epoch {key_epoch}: {e}")))?;
+            Ok((key_epoch, key))
+        })
+        .collect::<Result<BTreeMap<_, _>, String>>()?;
+    let active = keys
+        .range(..=epoch)
+        .next_back()
+        .map(|(key_epoch, _)| *key_epoch)
+        .ok_or_else(|| context(format!("no key for epoch {epoch}")))?;
+    Keyring::new(keys, active).map_err(|e| context(e.to_string()))
+}
+
+/// The options of the database: the storage layer's defaults, with the set parameters applied.
+fn db_options(parameters: &StorageParameters) -> Options {
+    let mut options = default_db_options().options;
+    if let Some(size) = parameters.db_write_buffer_size {
+        options.set_db_write_buffer_size(size);
+    }
+    if let Some(size) = parameters.max_total_wal_size {
+        options.set_max_total_wal_size(size);
+    }
+    if let Some(bytes) = parameters.wal_bytes_per_sync {
+        options.set_wal_bytes_per_sync(bytes);
+    }
+    if let Some(dir) = &parameters.wal_dir {
+        options.set_wal_dir(dir);
+    }
+    options
+}
+
+/// The write policy of a column family. Writes synced on every write are never coalesced, and
+/// neither are the writes to the commit tables while a column family is synced on commit (see
+/// `BufferedStore`).
+fn write_policy(parameters: &ColumnFamilyParameters) -> WritePolicy {
+    let sync = match parameters.sync {
+        None => store::backend::SyncPolicy::Never,
+        Some(SyncPolicy::Always) => store::backend::SyncPolicy::Always,
+        Some(SyncPolicy::Interval) => store::backend::SyncPolicy::Interval,
+        Some(SyncPolicy::OnCommit) => store::backend::SyncPolicy::OnCommit,
+    };
+    WritePolicy {
+        coalesce: parameters.coalesce_writes.unwrap_or_default()
+            && sync != store::backend::SyncPolicy::Always,
+        sync,
+    }
+}
+
+/// The options of a column family, on top of the database options.
+fn column_family_options(
+    db_options: &Options,
+    parameters: &ColumnFamilyParameters,
+    block_cache: Option<&Cache>,
+) -> Options {
+    let mut options = db_options.clone();
+    if let Some(cache) = block_cache {
+        let mut table_options = BlockBasedOptions::default();
+        table_options.set_block_cache(cache);
+        options.set_block_based_table_factory(&table_options);
+    }
+    if let Some(size) = parameters.write_buffer_size {
+        options.set_write_buffer_size(size);
+    }
+    if let Some(number) = parameters.max_write_buffer_number {
+        options.set_max_write_buffer_number(number);
+    }
+    if let Some(compression) = parameters.compression {
+        options.set_compression_type(match compression {
+            Compression::None => DBCompressionType::None,
+            Compression::Snappy => DBCompressionType::Snappy,
+            Compression::Zlib => DBCompressionType::Zlib,
+            Compression::Lz4 => DBCompressionType::Lz4,
+            Compression::Zstd => DBCompressionType::Zstd,
+        });
+    }
+    if let Some(style) = parameters.compaction_style {
+        options.set_compaction_style(match style {
+            CompactionStyle::Level => DBCompactionStyle::Level,
+            CompactionStyle::Universal => DBCompactionStyle::Universal,
+            CompactionStyle::Fifo => DBCompactionStyle::Fifo,
+        });
+    }
+    options
+}
+
+#[cfg(test)]
+mod test {
+    use super::load_keyring;
+    use crate::NodeStorage;
+    use config::{
+        ColumnFamilyParameters, CompactionStyle, Compression, StorageParameters, SyncPolicy,
+        WriteParameters,
+    };
+    use prometheus::Registry;
+    use std::time::Duration;
+    use test_utils::CommitteeFixture;
+
+    #[tokio::test]
+    async fn test_in_memory_storage() {
+        let fixture = CommitteeFixture::builder().build();
+        let header = fixture.header();
+
+        let storage = NodeStorage::in_memory();
+        storage
+            .header_store
+            .sync_write(header.digest(), header.clone())
+            .await
+            .unwrap();
+        storage.proposer_store.write_last_proposed(&header).unwrap();
+
+        assert_eq!(
+            storage.header_store.read(header.digest()).await.unwrap(),
+            Some(header.clone())
+        );
+        assert_eq!(
+            storage.proposer_store.get_last_proposed().unwrap(),
+            Some(header)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_tuned_storage() {
+        let fixture = CommitteeFixture::builder().build();
+        let header = fixture.header();
+
+        let parameters = StorageParameters {
+            block_cache_size: Some(8 << 20),
+            db_write_buffer_size: Some(16 << 20),
+            column_family_defaults: ColumnFamilyParameters {
+                compression: Some(Compression::Lz4),
+                ..Default::default()
+            },
+            column_families: [(
+                "headers".to_string(),
+                ColumnFamilyParameters {
+                    write_buffer_size: Some(1 << 20),
+                    compression: Some(Compression::Zstd),
+                    compaction_style: Some(CompactionStyle::Universal),
+                    ..Default::default()
+                },
+            )]
+            .into_iter()
+            .collect(),
+            ..Default::default()
+        };
+
+        let path = tempfile::tempdir().unwrap();
+        let storage = NodeStorage::reopen_with_parameters(&path, &parameters, 0);
+        storage
+            .header_store
+            .sync_write(header.digest(), header.clone())
+            .await
+            .unwrap();
+        assert_eq!(
+            storage.header_store.read(header.digest()).await.unwrap(),
+            Some(header)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_buffered_storage() {
+        let fixture = CommitteeFixture::builder().build();
+        let header = fixture.header();
+
+        let parameters = StorageParameters {
+            column_family_defaults: ColumnFamilyParameters {
+                coalesce_writes: Some(true),
+                sync: Some(SyncPolicy::OnCommit),
+                ..Default::default()
+            },
+            column_families: [(
+                "last_proposed".to_string(),
+                ColumnFamilyParameters {
+                    sync: Some(SyncPolicy::Always),
+                    ..Default::default()
+                },
+            )]
+            .into_iter()
+            .collect(),
+            writes: WriteParameters {
+                flush_interval: Duration::from_millis(10),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let path = tempfile::tempdir().unwrap();
+        let storage = NodeStorage::reopen_with_parameters(&path, &parameters, 0);
+        storage
+            .header_store
+            .sync_write(header.digest(), header.clone())
+            .await
+            .unwrap();
+        // The buffered header is read back before and after it is flushed.
+        assert_eq!(
+            storage.header_store.read(header.digest()).await.unwrap(),
+            Some(header.clone())
+        );
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(
+            storage.header_store.read(header.digest()).await.unwrap(),
+            Some(header.clone())
+        );
+
+        storage.proposer_store.write_last_proposed(&header).unwrap();
+        assert_eq!(
+            storage.proposer_store.get_last_proposed().unwrap(),
+            Some(header)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_read_only_storage() {
+        let fixture = CommitteeFixture::builder().build();
+        let header = fixture.header();
+
+        let dir = tempfile::tempdir().unwrap();
+        let parameters = StorageParameters::default();
+        let storage = NodeStorage::reopen_with_parameters(dir.path().join("db"), &parameters, 0);
+        let reader = NodeStorage::open_read_only(
+            dir.path().join("db"),
+            dir.path().join("secondary"),
+            &parameters,
+            0,
+        )
+        .unwrap();
+        assert_eq!(reader.tables().len(), NodeStorage::TABLES.len());
+
+        // The reader sees the writes of the running node once caught up.
+        storage.proposer_store.write_last_proposed(&header).unwrap();
+        reader.catch_up().unwrap();
+        assert_eq!(
+            reader.proposer_store.get_last_proposed().unwrap(),
+            Some(header.clone())
+        );
+        assert!(reader.proposer_store.write_last_proposed(&header).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_encrypted_storage() {
+        let fixture = CommitteeFixture::builder().build();
+        let header = fixture.header();
+
+        let dir = tempfile::tempdir().unwrap();
+        let keys_file = dir.path().join("keys.json");
+        let keys = serde_json::json!({
+            "0": base64::encode([0u8; 32]),
+            "5": base64::encode([5u8; 32]),
+        });
+        std::fs::write(&keys_file, keys.to_string()).unwrap();
+
+        // Each epoch uses the key of the latest epoch not after it.
+        assert_eq!(load_keyring(&keys_file, 3).unwrap().active(), 0);
+        assert_eq!(load_keyring(&keys_file, 7).unwrap().active(), 5);
+
+        let parameters = StorageParameters {
+            encryption_keys_file: Some(keys_file),
+            ..Default::default()
+        };
+        let storage = NodeStorage::reopen_with_parameters(dir.path().join("db"), &parameters, 7);
+        storage
+            .header_store
+            .sync_write(header.digest(), header.clone())
+            .await
+            .unwrap();
+        assert_eq!(
+            storage.header_store.read(header.digest()).await.unwrap(),
+            Some(header)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_storage_metrics() {
+        let fixture = CommitteeFixture::builder().build();
+        let header = fixture.header();
+
+        let parameters = StorageParameters {
+            metrics_interval: Duration::from_millis(10),
+            ..Default::default()
+        };
+        let registry = Registry::new();
+        let path = tempfile::tempdir().unwrap();
+        let storage = NodeStorage::reopen_with_metrics(&path, &parameters, 0, &registry);
+        storage
+            .header_store
+            .sync_write(header.digest(), header.clone())
+            .await
+            .unwrap();
+        storage.header_store.read(header.digest()).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        // Every metric is reported per table.
+        let families = registry.gather();
+        let headers_metric = |name: &str| {
+            families
+                .iter()
+                .find(|family| family.get_name() == name)
+                .unwrap_or_else(|| panic!("Missing metric {name}"))
+                .get_metric()
+                .iter()
+                .find(|metric| {
+                    metric
+                        .get_label()
+                        .iter()
+                        .any(|label| label.get_name() == "table" && label.get_value() == "headers")
+                })
+                .cloned()
+                .unwrap_or_else(|| panic!("Missing headers {name}"))
+        };
+        assert!(
+            headers_metric("storage_read_latency")
+                .get_histogram()
+                .get_sample_count()
+                > 0
+        );
+        assert!(
+            headers_metric("storage_write_latency")
+                .get_histogram()
+                .get_sample_count()
+                > 0
+        );
+        for name in [
+            "storage_table_size_bytes",
+            "storage_estimated_keys",
+            "storage_pending_compaction_bytes",
+            "storage_write_stopped",
+            "storage_delayed_write_rate",
+        ] {
+            headers_metric(name);
         }
     }
+
+    #[tokio::test]
+    async fn test_shared_epochs() {
+        let fixture = CommitteeFixture::builder().build();
+        let header = fixture.header();
+
+        let path = tempfile::tempdir().unwrap();
+        let parameters = StorageParameters::default();
+        let database = NodeStorage::open_database(&path, &parameters);
+
+        let storage = NodeStorage::open_epoch(&database, &parameters, 0, &Registry::new());
+        storage
+            .header_store
+            .sync_write(header.digest(), header.clone())
+            .await
+            .unwrap();
+
+        // The next epoch starts empty, in the same database.
+        let next = NodeStorage::open_epoch(&database, &parameters, 1, &Registry::new());
+        assert_eq!(next.header_store.read(header.digest()).await.unwrap(), None);
+        assert_eq!(
+            storage.header_store.read(header.digest()).await.unwrap(),
+            Some(header.clone())
+        );
+
+        // Deleting the past epochs drops their data.
+        NodeStorage::delete_epochs_before(&database, 1).unwrap();
+        assert_eq!(
+            storage.header_store.read(header.digest()).await.unwrap(),
+            None
+        );
+    }
 }
@@ -1,8 +1,9 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use store::rocks::open_cf;
-use store::{reopen, rocks::DBMap, Map};
+use std::sync::Arc;
+use store::backend::{InMemoryStore, KeyValueStore, StoreMap};
+use store::{rocks::TypedStoreError::RocksDBError, Map};
 use types::{Header, StoreResult};
 
 pub type ProposerKey = u32;
@@ -13,24 +14,30 @@ pub const LAST_PROPOSAL_KEY: ProposerKey = 0;
 #[derive(Clone)]
 pub struct ProposerStore {
     /// Holds the Last Header that was proposed by the Proposer.
-    last_proposed: DBMap<ProposerKey, Header>,
+    last_proposed: StoreMap<ProposerKey, Header>,
 }
 
 impl ProposerStore {
-    pub fn new(last_proposed: DBMap<ProposerKey, Header>) -> ProposerStore {
+    pub fn new(last_proposed: StoreMap<ProposerKey, Header>) -> ProposerStore {
         Self { last_proposed }
     }
 
     pub fn new_for_tests() -> ProposerStore {
         const LAST_PROPOSED_CF: &str = "last_proposed";
-        let rocksdb = open_cf(tempfile::tempdir().unwrap(), None, &[LAST_PROPOSED_CF])
-            .expect("Cannot open database");
-        let last_proposed_map = reopen!(&rocksdb, LAST_PROPOSED_CF;<ProposerKey, Header>);
+        let backend: Arc<dyn KeyValueStore> = Arc::new(InMemoryStore::new(&[LAST_PROPOSED_CF]));
+        let last_proposed_map =
+            StoreMap::new(&backend, LAST_PROPOSED_CF).expect("Cannot open database");
         ProposerStore::new(last_proposed_map)
     }
 
     /// Inserts a proposed header into the store
     pub fn write_last_proposed(&self, header: &Header) -> StoreResult<()> {
+        fail::fail_point!("proposer-store", |_| {
+            Err(RocksDBError(
+                "Injected error in proposer store write".to_string(),
+            ))
+        });
+
         self.last_proposed.insert(&LAST_PROPOSAL_KEY, header)
     }
 
@@ -0,0 +1,215 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Snapshots of the storage of a node, to bootstrap a fresh node without syncing the whole DAG
+//! from its peers. A snapshot is a directory holding a RocksDB checkpoint of all the stores
+//! (`db`) and a manifest describing its content (`manifest.json`).
+use crate::NodeStorage;
+use config::Epoch;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+use store::rocks::TypedStoreError;
+use thiserror::Error;
+use types::{Round, SequenceNumber};
+
+const MANIFEST_FILE: &str = "manifest.json";
+const DB_DIR: &str = "db";
+
+#[derive(Debug, Error)]
+pub enum SnapshotError {
+    #[error("Snapshot I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error(transparent)]
+    Store(#[from] TypedStoreError),
+
+    #[error("Invalid snapshot manifest: {0}")]
+    InvalidManifest(String),
+
+    #[error("Directory {0} is not empty")]
+    NotEmpty(PathBuf),
+
+    #[error("The snapshot is of epoch {found:?}, expected epoch {expected}")]
+    EpochMismatch {
+        expected: Epoch,
+        found: Option<Epoch>,
+    },
+}
+
+/// Describes the content of a snapshot. The watermarks are read before the checkpoint is taken,
+/// so the snapshot holds at least the data up to them.
+#[derive(Clone, Debug, Deserialize, Serialize, Eq, PartialEq)]
+pub struct SnapshotManifest {
+    /// The version of the snapshot format.
+    pub version: u32,
+    /// The epoch of the latest certificate, if any.
+    pub epoch: Option<Epoch>,
+    /// The round of the latest certificate.
+    pub highest_certificate_round: Round,
+    /// The highest round committed by consensus.
+    pub last_committed_round: Round,
+    /// The index of the latest committed sub-dag.
+    pub latest_sub_dag_index: SequenceNumber,
+    /// The tables of the database.
+    pub tables: Vec<String>,
+}
+
+impl SnapshotManifest {
+    /// The version of the snapshots written by this node.
+    pub const VERSION: u32 = 1;
+
+    /// Reads the manifest of the snapshot in `dir`.
+    pub fn read(dir: &Path) -> Result<Self, SnapshotError> {
+        let data = fs::read(dir.join(MANIFEST_FILE))?;
+        let manifest: Self = serde_json::from_slice(&data)
+            .map_err(|e| SnapshotError::InvalidManifest(e.to_string()))?;
+        if manifest.version != Self::VERSION {
+            return Err(SnapshotError::InvalidManifest(format!(
+                "unsupported version {}",
+                manifest.version
+            )));
+        }
+        if let Some(table) = NodeStorage::TABLES
+            .iter()
+            .find(|table| !manifest.tables.iter().any(|name| name == *table))
+        {
+            return Err(SnapshotError::InvalidManifest(format!(
+                "missing table {table}"
+            )));
+        }
+        Ok(manifest)
+    }
+}
+
+/// Fails unless `dir` does not exist or is an empty directory.
+fn ensure_empty(dir: &Path) -> Result<(), SnapshotError> {
+    if dir.exists() && fs::read_dir(dir)?.next().is_some() {
+        return Err(SnapshotError::NotEmpty(dir.to_path_buf()));
+    }
+    Ok(())
+}
+
+impl NodeStorage {
+    /// Exports a consistent snapshot of all the stores to `dir`, which must not exist or be
+    /// empty. The node can keep running while the snapshot is taken.
+    pub fn export_snapshot(&self, dir: &Path) -> Result<SnapshotManifest, SnapshotError> {
+        ensure_empty(dir)?;
+
+        let last_certificate = self
+            .certificate_store
+            .last_two_rounds_certs()?
+            .into_iter()
+            .max_by_key(|certificate| certificate.round());
+        let manifest = SnapshotManifest {
+            version: SnapshotManifest::VERSION,
+            epoch: last_certificate.as_ref().map(|c| c.epoch()),
+            highest_certificate_round: last_certificate.map_or(0, |c| c.round()),
+            last_committed_round: self
+                .consensus_store
+                .read_last_committed()
+                .into_values()
+                .max()
+                .unwrap_or_default(),
+            latest_sub_dag_index: self.consensus_store.get_latest_sub_dag_index(),
+            tables: self.backend.tables(),
+        };
+
+        fs::create_dir_all(dir)?;
+        self.backend.checkpoint(&dir.join(DB_DIR))?;
+        // The manifest is written last, so incomplete snapshots are rejected on import.
+        let data = serde_json::to_vec_pretty(&manifest)
+            .map_err(|e| SnapshotError::InvalidManifest(e.to_string()))?;
+        fs::write(dir.join(MANIFEST_FILE), data)?;
+        Ok(manifest)
+    }
+
+    /// Imports the snapshot in `dir` into `store_path`, which must not exist or be empty. The
+    /// node can then be started on `store_path`. If an `epoch` is given, the snapshot must
+    /// be of that epoch.
+    pub fn import_snapshot(
+        dir: &Path,
+        store_path: &Path,
+        epoch: Option<Epoch>,
+    ) -> Result<SnapshotManifest, SnapshotError> {
+        let manifest = SnapshotManifest::read(dir)?;
+        if let Some(expected) = epoch {
+            if manifest.epoch != Some(expected) {
+                return Err(SnapshotError::EpochMismatch {
+                    expected,
+                    found: manifest.epoch,
+                });
+            }
+        }
+        ensure_empty(store_path)?;
+
+        fs::create_dir_all(store_path)?;
+        for entry in fs::read_dir(dir.join(DB_DIR))? {
+            let entry = entry?;
+            fs::copy(entry.path(), store_path.join(entry.file_name()))?;
+        }
+        Ok(manifest)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{NodeStorage, SnapshotError, SnapshotManifest};
+    use fastcrypto::hash::Hash;
+    use std::collections::BTreeSet;
+    use test_utils::{temp_dir, CommitteeFixture};
+    use types::Certificate;
+
+    #[tokio::test]
+    async fn test_export_import() {
+        let fixture = CommitteeFixture::builder().build();
+        let committee = fixture.committee();
+        let genesis = Certificate::genesis(&committee)
+            .iter()
+            .map(|x| x.digest())
+            .collect::<BTreeSet<_>>();
+        let (_, headers) = fixture.headers_round(0, &genesis);
+        let certificates: Vec<_> = headers
+            .iter()
+            .map(|header| fixture.certificate(header))
+            .collect();
+
+        let storage = NodeStorage::in_memory();
+        storage
+            .certificate_store
+            .write_all(certificates.clone())
+            .unwrap();
+
+        // Export the snapshot.
+        let dir = temp_dir().join("snapshot");
+        let manifest = storage.export_snapshot(&dir).unwrap();
+        assert_eq!(manifest.epoch, Some(committee.epoch()));
+        assert_eq!(manifest.highest_certificate_round, 1);
+        assert_eq!(SnapshotManifest::read(&dir).unwrap(), manifest);
+        assert!(matches!(
+            storage.export_snapshot(&dir),
+            Err(SnapshotError::NotEmpty(_))
+        ));
+
+        // Import it on a fresh node.
+        let store_path = temp_dir().join("store");
+        assert!(matches!(
+            NodeStorage::import_snapshot(&dir, &store_path, Some(committee.epoch() + 1)),
+            Err(SnapshotError::EpochMismatch { .. })
+        ));
+        NodeStorage::import_snapshot(&dir, &store_path, Some(committee.epoch())).unwrap();
+
+        let imported = NodeStorage::reopen(&store_path);
+        for certificate in certificates {
+            assert_eq!(
+                imported
+                    .certificate_store
+                    .read(certificate.digest())
+                    .unwrap(),
+                Some(certificate)
+            );
+        }
+    }
+}
@@ -0,0 +1,394 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A Byzantine primary, to test how the honest nodes react to misbehavior.
+//!
+//! A [`ByzantinePrimary`] takes the place of the primary of an authority of the committee: it
+//! serves the primary-to-primary RPCs under the keys of the authority and proposes its own
+//! headers, misbehaving as configured by its [`Behavior`]. It has no workers, so its headers
+//! are empty, and it trusts the certificates it receives.
+use crate::AuthorityFixture;
+use anemo::{async_trait, types::PeerInfo, PeerId};
+use config::{Committee, Epoch};
+use crypto::{KeyPair, NetworkPublicKey, PublicKey};
+use fastcrypto::{
+    hash::{Digest, Hash as _},
+    traits::{KeyPair as _, Signer as _},
+};
+use futures::future::join_all;
+use network::anemo_ext::NetworkExt;
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::task::JoinHandle;
+use tracing::{debug, info};
+use types::{
+    now, Certificate, CertificateDigest, FetchCertificatesRequest, FetchCertificatesResponse,
+    GetCertificatesRequest, GetCertificatesResponse, Header, HeaderBuilder,
+    PayloadAvailabilityRequest, PayloadAvailabilityResponse, PrimaryMessage, PrimaryToPrimary,
+    PrimaryToPrimaryClient, PrimaryToPrimaryServer, RequestVoteRequest, RequestVoteResponse, Round,
+    Vote,
+};
+
+/// How long the Byzantine primary waits for the votes on its headers.
+const VOTE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How often the Byzantine primary checks whether it has the parents of its next header.
+const PARENTS_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// The misbehaviors of a [`ByzantinePrimary`], all disabled by default: a default behavior is
+/// that of an honest primary without workers.
+#[derive(Clone, Debug, Default)]
+pub struct Behavior {
+    /// Proposes two conflicting headers in every round, each sent for votes to its own half of
+    /// the committee.
+    pub equivocate_headers: bool,
+    /// Never votes for the headers of the other authorities.
+    pub withhold_votes: bool,
+    /// Tags its headers and votes with the epoch before the current one (the one after it in
+    /// the first epoch).
+    pub stale_epoch: bool,
+    /// Delays every message it sends, and every response it gives.
+    pub delay: Option<Duration>,
+}
+
+impl Behavior {
+    async fn delay(&self) {
+        if let Some(delay) = self.delay {
+            tokio::time::sleep(delay).await;
+        }
+    }
+
+    fn epoch(&self, committee: &Committee) -> Epoch {
+        if self.stale_epoch {
+            committee
+                .epoch()
+                .checked_sub(1)
+                .unwrap_or(committee.epoch() + 1)
+        } else {
+            committee.epoch()
+        }
+    }
+}
+
+/// The certificates known to the Byzantine primary.
+#[derive(Clone, Default)]
+struct State {
+    /// The certificates received or formed, by round and origin.
+    certificates: Arc<Mutex<BTreeMap<Round, HashMap<PublicKey, Certificate>>>>,
+    /// The certificates formed by the Byzantine primary, several per round when equivocating.
+    formed: Arc<Mutex<Vec<Certificate>>>,
+}
+
+impl State {
+    fn insert(&self, certificate: Certificate) {
+        self.certificates
+            .lock()
+            .unwrap()
+            .entry(certificate.round())
+            .or_default()
+            .insert(certificate.origin(), certificate);
+    }
+
+    fn get(&self, digests: &[CertificateDigest]) -> Vec<Certificate> {
+        let certificates = self.certificates.lock().unwrap();
+        certificates
+            .values()
+            .flat_map(|round| round.values())
+            .filter(|certificate| digests.contains(&certificate.digest()))
+            .cloned()
+            .collect()
+    }
+
+    /// The certificates of the highest round from `round` on with a quorum of them, if any.
+    fn parents(&self, committee: &Committee, round: Round) -> Option<(Round, Vec<Certificate>)> {
+        let certificates = self.certificates.lock().unwrap();
+        certificates
+            .range(round..)
+            .rev()
+            .find(|(_, certificates)| {
+                committee.reached_quorum(
+                    certificates
+                        .keys()
+                        .map(|origin| committee.stake(origin))
+                        .sum(),
+                )
+            })
+            .map(|(round, certificates)| (*round, certificates.values().cloned().collect()))
+    }
+}
+
+/// A primary misbehaving in place of the primary of an authority.
+pub struct ByzantinePrimary {
+    network: anemo::Network,
+    state: State,
+    handle: JoinHandle<()>,
+}
+
+impl ByzantinePrimary {
+    /// Starts the Byzantine primary of `authority`, listening on its primary address.
+    pub fn spawn(authority: &AuthorityFixture, committee: Committee, behavior: Behavior) -> Self {
+        let keypair = Arc::new(authority.keypair().copy());
+        let state = State::default();
+        let handler = ByzantineHandler {
+            keypair: keypair.clone(),
+            committee: committee.clone(),
+            behavior: behavior.clone(),
+            state: state.clone(),
+        };
+        let network = authority.new_network(
+            anemo::Router::new().add_rpc_service(PrimaryToPrimaryServer::new(handler)),
+        );
+        for (_, address, network_key) in committee.others_primaries(keypair.public()) {
+            network.known_peers().insert(PeerInfo {
+                peer_id: peer_id(&network_key),
+                affinity: anemo::types::PeerAffinity::High,
+                address: vec![network::multiaddr_to_address(&address).unwrap()],
+            });
+        }
+        info!(
+            "Byzantine primary {} started with {behavior:?}",
+            keypair.public()
+        );
+
+        let handle = tokio::spawn(Self::run(
+            network.clone(),
+            keypair,
+            committee,
+            behavior,
+            state.clone(),
+        ));
+        Self {
+            network,
+            state,
+            handle,
+        }
+    }
+
+    /// The certificates formed by the Byzantine primary, from the votes of the honest ones.
+    pub fn certificates(&self) -> Vec<Certificate> {
+        self.state.formed.lock().unwrap().clone()
+    }
+
+    pub fn network(&self) -> &anemo::Network {
+        &self.network
+    }
+
+    /// Proposes the headers of the Byzantine primary, round after round.
+    async fn run(
+        network: anemo::Network,
+        keypair: Arc<KeyPair>,
+        committee: Committee,
+        behavior: Behavior,
+        state: State,
+    ) {
+        let name = keypair.public().clone();
+        let peers: Vec<_> = committee
+            .others_primaries(&name)
+            .into_iter()
+            .map(|(_, _, network_key)| network_key)
+            .collect();
+        let mut round = 1;
+        loop {
+            // Follow the honest primaries when they are ahead.
+            let parents = if round == 1 {
+                Certificate::genesis(&committee)
+            } else {
+                let Some((parents_round, parents)) = state.parents(&committee, round - 1) else {
+                    tokio::time::sleep(PARENTS_POLL_INTERVAL).await;
+                    continue;
+                };
+                round = parents_round + 1;
+                parents
+            };
+
+            let headers: Vec<_> = (0..if behavior.equivocate_headers { 2 } else { 1 })
+                .map(|i| {
+                    HeaderBuilder::default()
+                        .author(name.clone())
+                        .round(round)
+                        .epoch(behavior.epoch(&committee))
+                        // Make the conflicting headers differ.
+                        .created_at(now() + i)
+                        .payload(Default::default())
+                        .parents(parents.iter().map(|parent| parent.digest()).collect())
+                        .build(keypair.as_ref())
+                        .unwrap()
+                })
+                .collect();
+
+            // Every header is sent to its own share of the committee.
+            let requests = headers.iter().enumerate().map(|(i, header)| {
+                let targets = peers
+                    .iter()
+                    .enumerate()
+                    .filter(|(j, _)| j % headers.len() == i)
+                    .map(|(_, peer)| peer.clone())
+                    .collect();
+                Self::request_votes(&network, &behavior, header, &parents, targets)
+            });
+            let votes = join_all(requests).await;
+
+            for (header, votes) in headers.into_iter().zip(votes) {
+                let own_vote = Vote::new_with_signer(&header, &name, keypair.as_ref());
+                let signatures = std::iter::once(own_vote)
+                    .chain(votes)
+                    .map(|vote| (vote.author, vote.signature))
+                    .collect();
+                match Certificate::new(&committee, header, signatures) {
+                    Ok(certificate) => {
+                        info!("Byzantine primary formed {certificate:?}");
+                        state.insert(certificate.clone());
+                        state.formed.lock().unwrap().push(certificate.clone());
+                        Self::broadcast(&network, &behavior, &certificate, &peers).await;
+                    }
+                    Err(e) => debug!("Byzantine primary failed to form a certificate: {e}"),
+                }
+            }
+            round += 1;
+        }
+    }
+
+    /// Requests the votes of `targets` on `header`, returning the ones received in time.
+    async fn request_votes(
+        network: &anemo::Network,
+        behavior: &Behavior,
+        header: &Header,
+        parents: &[Certificate],
+        targets: Vec<NetworkPublicKey>,
+    ) -> Vec<Vote> {
+        let requests = targets.into_iter().map(|target| async move {
+            behavior.delay().await;
+            let request = anemo::Request::new(RequestVoteRequest {
+                header: header.clone(),
+                parents: parents.to_vec(),
+            })
+            .with_timeout(VOTE_TIMEOUT);
+            let mut client = PrimaryToPrimaryClient::new(network.waiting_peer(peer_id(&target)));
+            match client.request_vote(request).await {
+                Ok(response) => response.into_body().vote,
+                Err(e) => {
+                    debug!("Byzantine primary got no vote from {target}: {e:?}");
+                    None
+                }
+            }
+        });
+        join_all(requests).await.into_iter().flatten().collect()
+    }
+
+    async fn broadcast(
+        network: &anemo::Network,
+        behavior: &Behavior,
+        certificate: &Certificate,
+        peers: &[NetworkPublicKey],
+    ) {
+        let messages = peers.iter().map(|peer| async move {
+            behavior.delay().await;
+            let mut client = PrimaryToPrimaryClient::new(network.waiting_peer(peer_id(peer)));
+            let _ = client
+                .send_message(PrimaryMessage::Certificate(certificate.clone()))
+                .await;
+        });
+        join_all(messages).await;
+    }
+}
+
+impl Drop for ByzantinePrimary {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+fn peer_id(network_key: &NetworkPublicKey) -> PeerId {
+    PeerId(network_key.0.to_bytes())
+}
+
+/// Serves the primary-to-primary RPCs of the honest primaries.
+#[derive(Clone)]
+struct ByzantineHandler {
+    keypair: Arc<KeyPair>,
+    committee: Committee,
+    behavior: Behavior,
+    state: State,
+}
+
+#[async_trait]
+impl PrimaryToPrimary for ByzantineHandler {
+    async fn send_message(
+        &self,
+        request: anemo::Request<PrimaryMessage>,
+    ) -> Result<anemo::Response<()>, anemo::rpc::Status> {
+        self.behavior.delay().await;
+        let PrimaryMessage::Certificate(certificate) = request.into_body();
+        self.state.insert(certificate);
+        Ok(anemo::Response::new(()))
+    }
+
+    async fn request_vote(
+        &self,
+        request: anemo::Request<RequestVoteRequest>,
+    ) -> Result<anemo::Response<RequestVoteResponse>, anemo::rpc::Status> {
+        self.behavior.delay().await;
+        if self.behavior.withhold_votes {
+            return Err(anemo::rpc::Status::internal("Vote withheld"));
+        }
+        let RequestVoteRequest { header, parents } = request.into_body();
+        for parent in parents {
+            self.state.insert(parent);
+        }
+
+        let mut vote = Vote::new_with_signer(&header, self.keypair.public(), self.keypair.as_ref());
+        let epoch = self.behavior.epoch(&self.committee);
+        if vote.epoch != epoch {
+            vote.epoch = epoch;
+            let digest: Digest<{ crypto::DIGEST_LENGTH }> = vote.digest().into();
+            vote.signature = self.keypair.sign(digest.as_ref());
+        }
+        Ok(anemo::Response::new(RequestVoteResponse {
+            vote: Some(vote),
+            missing: Vec::new(),
+        }))
+    }
+
+    async fn get_certificates(
+        &self,
+        request: anemo::Request<GetCertificatesRequest>,
+    ) -> Result<anemo::Response<GetCertificatesResponse>, anemo::rpc::Status> {
+        self.behavior.delay().await;
+        Ok(anemo::Response::new(GetCertificatesResponse {
+            certificates: self.state.get(&request.body().digests),
+        }))
+    }
+
+    async fn fetch_certificates(
+        &self,
+        _request: anemo::Request<FetchCertificatesRequest>,
+    ) -> Result<anemo::Response<FetchCertificatesResponse>, anemo::rpc::Status> {
+        self.behavior.delay().await;
+        Ok(anemo::Response::new(FetchCertificatesResponse {
+            certificates: Vec::new(),
+        }))
+    }
+
+    async fn get_payload_availability(
+        &self,
+        request: anemo::Request<PayloadAvailabilityRequest>,
+    ) -> Result<anemo::Response<PayloadAvailabilityResponse>, anemo::rpc::Status> {
+        self.behavior.delay().await;
+        let digests = request.into_body().certificate_digests;
+        let known: Vec<_> = self
+            .state
+            .get(&digests)
+            .iter()
+            .map(|certificate| certificate.digest())
+            .collect();
+        Ok(anemo::Response::new(PayloadAvailabilityResponse {
+            payload_availability: digests
+                .into_iter()
+                .map(|digest| (digest, known.contains(&digest)))
+                .collect(),
+        }))
+    }
+}
@@ -0,0 +1,130 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Schedules of failpoint configurations, to crash or stall the nodes at awkward moments of a
+//! test (e.g. after a header is stored but before the votes for it are requested).
+//!
+//! The failpoints are only compiled in with the `failpoints` feature; a schedule panics when run
+//! without it. The failpoints placed at the state transitions of the nodes are:
+//!
+//! - primary: `proposer-before-store-header`, `proposer-after-store-header`,
+//!   `core-after-store-header`, `core-after-assemble-certificate`,
+//!   `core-before-broadcast-certificate`, `core-before-store-certificate`, `request-vote`,
+//!   `report-our-batch`
+//! - worker: `batch-maker-before-store-batch`, `batch-maker-before-report-batch`,
+//!   `request-batch`
+//! - consensus: `consensus-before-store-commit`, `consensus-after-store-commit`
+//! - storage: `certificate-store`, `certificate-store-panic`, `proposer-store`
+//! - network: `rpc-response-delay`
+//!
+//! The actions follow the syntax of the [`fail`] crate, e.g. `panic`, `return`, `sleep(100)`,
+//! `pause`, `50%return` or `1*panic`.
+use std::time::Duration;
+use tokio::{task::JoinHandle, time::Instant};
+use tracing::info;
+
+#[cfg(all(test, feature = "failpoints"))]
+#[path = "tests/chaos_tests.rs"]
+pub mod chaos_tests;
+
+/// A change of the configuration of a failpoint.
+#[derive(Clone, Debug)]
+struct ChaosStep {
+    /// The time of the change, since the start of the schedule.
+    at: Duration,
+    failpoint: String,
+    /// The actions of the failpoint from then on, `None` to disable it.
+    actions: Option<String>,
+}
+
+/// The changes of the configuration of the failpoints over the course of a test.
+#[derive(Clone, Debug, Default)]
+pub struct ChaosSchedule {
+    steps: Vec<ChaosStep>,
+}
+
+impl ChaosSchedule {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Configures the `failpoint` to run `actions` from `at` after the start of the schedule.
+    pub fn enable(mut self, at: Duration, failpoint: &str, actions: &str) -> Self {
+        self.steps.push(ChaosStep {
+            at,
+            failpoint: failpoint.to_string(),
+            actions: Some(actions.to_string()),
+        });
+        self
+    }
+
+    /// Disables the `failpoint` from `at` after the start of the schedule.
+    pub fn disable(mut self, at: Duration, failpoint: &str) -> Self {
+        self.steps.push(ChaosStep {
+            at,
+            failpoint: failpoint.to_string(),
+            actions: None,
+        });
+        self
+    }
+
+    /// Starts applying the schedule. The failpoints stay configured until the returned handle
+    /// is dropped, which also excludes the other schedules of the process in the meantime, as
+    /// the failpoints are global.
+    pub fn run(mut self) -> ChaosHandle {
+        assert!(
+            fail::has_failpoints(),
+            "The chaos schedules need the failpoints feature"
+        );
+        // Clears the failpoints left by a previous test, and waits for its schedule to end.
+        let scenario = fail::FailScenario::setup();
+
+        // The steps at the same time apply in the order they were added.
+        self.steps.sort_by_key(|step| step.at);
+        let start = Instant::now();
+        let task = tokio::spawn(async move {
+            for step in self.steps {
+                tokio::time::sleep_until(start + step.at).await;
+                match &step.actions {
+                    Some(actions) => {
+                        info!("Enabling failpoint {} with {actions}", step.failpoint);
+                        fail::cfg(&step.failpoint, actions).unwrap_or_else(|e| {
+                            panic!("Invalid actions for failpoint {}: {e}", step.failpoint)
+                        });
+                    }
+                    None => {
+                        info!("Disabling failpoint {}", step.failpoint);
+                        fail::remove(&step.failpoint);
+                    }
+                }
+            }
+        });
+
+        ChaosHandle {
+            task,
+            _scenario: scenario,
+        }
+    }
+}
+
+/// A running [`ChaosSchedule`]. Dropping it stops the schedule and disables every failpoint.
+pub struct ChaosHandle {
+    task: JoinHandle<()>,
+    _scenario: fail::FailScenario<'static>,
+}
+
+impl ChaosHandle {
+    /// Waits until every step of the schedule is applied. Must be called at most once.
+    pub async fn applied(&mut self) {
+        (&mut self.task)
+            .await
+            .expect("Failed to apply the chaos schedule");
+    }
+}
+
+impl Drop for ChaosHandle {
+    fn drop(&mut self) {
+        // The scenario disables the failpoints once dropped in turn.
+        self.task.abort();
+    }
+}
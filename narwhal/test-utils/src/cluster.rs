@@ -1,6 +1,7 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
-use crate::{temp_dir, CommitteeFixture};
+use crate::{test_store::TestStore, CommitteeFixture};
+use anemo::PeerId;
 use arc_swap::ArcSwap;
 use config::{Parameters, SharedCommittee, SharedWorkerCache, WorkerId};
 use crypto::{KeyPair, NetworkKeyPair, PublicKey};
@@ -8,13 +9,14 @@ use executor::SerializedTransaction;
 use fastcrypto::traits::KeyPair as _;
 use itertools::Itertools;
 use multiaddr::Multiaddr;
+use network::faults::{self, Link, LinkFaults};
 use node::{
     execution_state::SimpleExecutionState,
     metrics::{primary_metrics_registry, worker_metrics_registry},
     Node,
 };
 use prometheus::{proto::Metric, Registry};
-use std::{cell::RefCell, collections::HashMap, path::PathBuf, rc::Rc, sync::Arc, time::Duration};
+use std::{cell::RefCell, collections::HashMap, rc::Rc, sync::Arc, time::Duration};
 use storage::NodeStorage;
 use telemetry_subscribers::TelemetryGuards;
 use tokio::{
@@ -31,15 +33,23 @@ use worker::TrivialTransactionValidator;
 pub mod cluster_tests;
 
 pub struct Cluster {
-    #[allow(unused)]
     fixture: CommitteeFixture,
     authorities: HashMap<usize, AuthorityDetails>,
+    /// The links cut by `partition`.
+    partitioned_links: Vec<Link>,
     pub committee_shared: SharedCommittee,
     pub worker_cache_shared: SharedWorkerCache,
     #[allow(dead_code)]
     parameters: Parameters,
 }
 
+impl Drop for Cluster {
+    fn drop(&mut self) {
+        // The faults are global to the process: leave none behind for the other tests.
+        self.heal();
+    }
+}
+
 impl Cluster {
     /// Initialises a new cluster by the provided parameters. The cluster will
     /// create all the authorities (primaries & workers) that are defined under
@@ -51,6 +61,17 @@ impl Cluster {
     /// DAG externally.
     pub fn new(parameters: Option<Parameters>, internal_consensus_enabled: bool) -> Self {
         let fixture = CommitteeFixture::builder().randomize_ports(true).build();
+        Self::from_fixture(fixture, parameters, internal_consensus_enabled)
+    }
+
+    /// Initialises a new cluster of the authorities of `fixture`, none of them started. The
+    /// authorities with parameters in the fixture run with those rather than with `parameters`.
+    /// The ports of the fixture should be randomized.
+    pub fn from_fixture(
+        fixture: CommitteeFixture,
+        parameters: Option<Parameters>,
+        internal_consensus_enabled: bool,
+    ) -> Self {
         let c = fixture.committee();
         let shared_worker_cache = fixture.shared_worker_cache();
         let shared_committee = Arc::new(ArcSwap::from_pointee(c));
@@ -68,7 +89,10 @@ impl Cluster {
                 authority_fixture.keypair().copy(),
                 authority_fixture.network_keypair().copy(),
                 authority_fixture.worker_keypairs(),
-                params.clone(),
+                authority_fixture
+                    .parameters()
+                    .cloned()
+                    .unwrap_or_else(|| params.clone()),
                 shared_committee.clone(),
                 shared_worker_cache.clone(),
                 internal_consensus_enabled,
@@ -79,6 +103,7 @@ impl Cluster {
         Self {
             fixture,
             authorities: nodes,
+            partitioned_links: Vec::new(),
             committee_shared: shared_committee,
             worker_cache_shared: shared_worker_cache,
             parameters: params,
@@ -92,8 +117,9 @@ impl Cluster {
     /// If a number higher than the available ones in the committee is provided then
     /// the method will panic.
     /// The workers_per_authority dictates how many workers per authority should
-    /// also be started (the same number will be started for each authority). If none
-    /// is provided then the maximum number of workers will be started.
+    /// also be started (the same number will be started for each authority, or all of
+    /// its workers if it has fewer). If none is provided then the maximum number of
+    /// workers will be started.
     /// If the `boot_wait_time` is provided then between node starts we'll wait for this
     /// time before the next node is started. This is useful to simulate staggered
     /// node starts. If none is provided then the nodes will be started immediately
@@ -138,8 +164,8 @@ impl Cluster {
     /// When the preserve_store is false, then authority will start with an empty
     /// storage.
     /// If the `workers_per_authority` is provided then the corresponding number of
-    /// workers will be started per authority. Otherwise if not provided, then maximum
-    /// number of workers will be started per authority.
+    /// workers will be started per authority (at most as many as it has). Otherwise if
+    /// not provided, then maximum number of workers will be started per authority.
     pub async fn start_node(
         &mut self,
         id: usize,
@@ -156,6 +182,7 @@ impl Cluster {
 
         // start the workers
         if let Some(workers) = workers_per_authority {
+            let workers = workers.min(authority.internal.read().await.workers.len());
             for worker_id in 0..workers {
                 authority
                     .start_worker(worker_id as WorkerId, preserve_store)
@@ -194,6 +221,69 @@ impl Cluster {
         result
     }
 
+    /// Cuts the traffic between the authorities of different `groups`, in both directions and for
+    /// their primaries and workers alike: their requests are dropped at the network layer. The
+    /// authorities of no group keep reaching all the others. Replaces any previous partition.
+    pub fn partition(&mut self, groups: &[&[usize]]) {
+        self.heal();
+        let peer_ids: Vec<Vec<PeerId>> = groups
+            .iter()
+            .map(|group| group.iter().flat_map(|id| self.peer_ids(*id)).collect())
+            .collect();
+        for (i, group) in peer_ids.iter().enumerate() {
+            for (j, other) in peer_ids.iter().enumerate() {
+                if i == j {
+                    continue;
+                }
+                for from in group {
+                    for to in other {
+                        let link = (Some(*from), Some(*to));
+                        faults::set_link_faults(
+                            link,
+                            LinkFaults {
+                                drop_rate: 1.0,
+                                ..LinkFaults::default()
+                            },
+                        );
+                        self.partitioned_links.push(link);
+                    }
+                }
+            }
+        }
+        info!("Partitioned the cluster into {groups:?}");
+    }
+
+    /// Restores the traffic cut by `partition`.
+    pub fn heal(&mut self) {
+        for link in self.partitioned_links.drain(..) {
+            faults::clear_link_faults(link);
+        }
+    }
+
+    /// The peer ids of the primary and the workers of the authority `id`.
+    fn peer_ids(&self, id: usize) -> Vec<PeerId> {
+        let authority = self
+            .fixture
+            .authorities()
+            .nth(id)
+            .unwrap_or_else(|| panic!("Authority with id {} not found", id));
+        std::iter::once(authority.network_public_key())
+            .chain(
+                authority
+                    .worker_keypairs()
+                    .iter()
+                    .map(|keypair| keypair.public().clone()),
+            )
+            .map(|key| PeerId(key.0.to_bytes()))
+            .collect()
+    }
+
+    /// Returns the fixture of the committee of the cluster, e.g. to run a Byzantine primary in
+    /// place of one of the authorities.
+    pub fn fixture(&self) -> &CommitteeFixture {
+        &self.fixture
+    }
+
     /// Returns the authority identified by the provided id. Will panic if the
     /// authority with the id is not found. The returned authority can be freely
     /// cloned and managed without having the need to fetch again.
@@ -276,7 +366,7 @@ pub struct PrimaryNodeDetails {
     pub network_key_pair: Arc<NetworkKeyPair>,
     pub tx_transaction_confirmation: Sender<SerializedTransaction>,
     registry: Registry,
-    store_path: PathBuf,
+    store: Arc<TestStore>,
     committee: SharedCommittee,
     worker_cache: SharedWorkerCache,
     parameters: Parameters,
@@ -302,7 +392,7 @@ impl PrimaryNodeDetails {
             key_pair: Arc::new(key_pair),
             network_key_pair: Arc::new(network_key_pair),
             registry: Registry::new(),
-            store_path: temp_dir(),
+            store: Arc::new(TestStore::new()),
             tx_transaction_confirmation: tx,
             committee,
             worker_cache,
@@ -329,24 +419,23 @@ impl PrimaryNodeDetails {
         let registry = primary_metrics_registry(self.key_pair.public().clone());
 
         // Make the data store.
-        let store_path = if preserve_store {
-            self.store_path.clone()
+        let store = if preserve_store {
+            self.store.clone()
         } else {
-            temp_dir()
+            Arc::new(TestStore::new())
         };
 
-        info!(
-            "Primary Node {} will use path {:?}",
-            self.id,
-            store_path.clone()
-        );
+        info!("Primary Node {} will use path {:?}", self.id, store.path());
 
         // The channel returning the result for each transaction's execution.
-        let (tx_transaction_confirmation, mut rx_transaction_confirmation) =
-            channel(Node::CHANNEL_CAPACITY);
+        let (tx_transaction_confirmation, mut rx_transaction_confirmation) = channel(
+            self.parameters
+                .memory_budget
+                .channel_capacity("tx_transaction_confirmation"),
+        );
 
         // Primary node
-        let primary_store: NodeStorage = NodeStorage::reopen(store_path.clone());
+        let primary_store: NodeStorage = store.open();
         let mut primary_handlers = Node::spawn_primary(
             self.key_pair.copy(),
             self.network_key_pair.copy(),
@@ -378,7 +467,7 @@ impl PrimaryNodeDetails {
         primary_handlers.push(h);
 
         self.handlers.replace(primary_handlers);
-        self.store_path = store_path;
+        self.store = store;
         self.registry = registry;
         self.tx_transaction_confirmation = tx;
     }
@@ -410,7 +499,7 @@ pub struct WorkerNodeDetails {
     committee: SharedCommittee,
     worker_cache: SharedWorkerCache,
     parameters: Parameters,
-    store_path: PathBuf,
+    store: Arc<TestStore>,
     handlers: Arc<ArcSwap<Vec<JoinHandle<()>>>>,
 }
 
@@ -427,7 +516,7 @@ impl WorkerNodeDetails {
             id,
             name,
             registry: Registry::new(),
-            store_path: temp_dir(),
+            store: Arc::new(TestStore::new()),
             transactions_address,
             committee,
             worker_cache,
@@ -448,13 +537,13 @@ impl WorkerNodeDetails {
         let registry = worker_metrics_registry(self.id, self.name.clone());
 
         // Make the data store.
-        let store_path = if preserve_store {
-            self.store_path.clone()
+        let store = if preserve_store {
+            self.store.clone()
         } else {
-            temp_dir()
+            Arc::new(TestStore::new())
         };
 
-        let worker_store = NodeStorage::reopen(store_path.clone());
+        let worker_store = store.open();
         let worker_handlers = Node::spawn_workers(
             self.name.clone(),
             vec![(self.id, keypair)],
@@ -467,7 +556,7 @@ impl WorkerNodeDetails {
         );
 
         self.handlers.swap(Arc::new(worker_handlers));
-        self.store_path = store_path;
+        self.store = store;
         self.registry = registry;
     }
 
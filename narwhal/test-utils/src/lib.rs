@@ -4,8 +4,8 @@
 
 use anemo::async_trait;
 use config::{
-    utils::get_available_port, Authority, Committee, Epoch, SharedWorkerCache, Stake, WorkerCache,
-    WorkerId, WorkerIndex, WorkerInfo,
+    utils::get_available_port, Authority, Committee, Epoch, Parameters, ProtocolConfig,
+    QuorumPolicy, SharedWorkerCache, Stake, WorkerCache, WorkerId, WorkerIndex, WorkerInfo,
 };
 use crypto::{KeyPair, NetworkKeyPair, NetworkPublicKey, PublicKey};
 use fastcrypto::{
@@ -24,21 +24,29 @@ use std::{
     ops::RangeInclusive,
     sync::Arc,
 };
-use store::{reopen, rocks, rocks::DBMap, Store};
+use store::{
+    backend::{KeyValueStore, RocksDBStore, StoreMap},
+    rocks::DBMap,
+    Store,
+};
 use tokio::sync::mpsc::{channel, Receiver, Sender};
 use tracing::info;
 use types::{
-    Batch, BatchDigest, Certificate, CertificateDigest, CommittedSubDagShell, ConsensusStore,
-    FetchCertificatesRequest, FetchCertificatesResponse, GetCertificatesRequest,
-    GetCertificatesResponse, Header, HeaderBuilder, PayloadAvailabilityRequest,
-    PayloadAvailabilityResponse, PrimaryMessage, PrimaryToPrimary, PrimaryToPrimaryServer,
-    PrimaryToWorker, PrimaryToWorkerServer, RequestBatchRequest, RequestBatchResponse,
-    RequestVoteRequest, RequestVoteResponse, Round, SequenceNumber, Transaction, Vote,
-    WorkerBatchMessage, WorkerDeleteBatchesMessage, WorkerReconfigureMessage,
+    Batch, BatchDigest, Certificate, CertificateDigest, ConsensusStore, FetchCertificatesRequest,
+    FetchCertificatesResponse, GetCertificatesRequest, GetCertificatesResponse, Header,
+    HeaderBuilder, PayloadAvailabilityRequest, PayloadAvailabilityResponse, PrimaryMessage,
+    PrimaryToPrimary, PrimaryToPrimaryServer, PrimaryToWorker, PrimaryToWorkerServer,
+    RequestBatchRequest, RequestBatchResponse, RequestVoteRequest, RequestVoteResponse, Round,
+    Transaction, Vote, WorkerBatchMessage, WorkerDeleteBatchesMessage, WorkerReconfigureMessage,
     WorkerSynchronizeMessage, WorkerToWorker, WorkerToWorkerServer,
 };
 
+pub mod byzantine;
+pub mod chaos;
 pub mod cluster;
+pub mod process_cluster;
+pub mod strategies;
+pub mod test_store;
 
 pub const VOTES_CF: &str = "votes";
 pub const HEADERS_CF: &str = "headers";
@@ -47,6 +55,8 @@ pub const CERTIFICATE_DIGEST_BY_ROUND_CF: &str = "certificate_digest_by_round";
 pub const CERTIFICATE_DIGEST_BY_ORIGIN_CF: &str = "certificate_digest_by_origin";
 pub const PAYLOAD_CF: &str = "payload";
 
+/// A temporary directory left behind by the test, see [`test_store::TestStore`] for one removed
+/// at the end of the test.
 pub fn temp_dir() -> std::path::PathBuf {
     tempfile::tempdir()
         .expect("Failed to open temporary directory")
@@ -125,15 +135,15 @@ pub fn make_consensus_store(store_path: &std::path::Path) -> Arc<ConsensusStore>
     const LAST_COMMITTED_CF: &str = "last_committed";
     const SEQUENCE_CF: &str = "sequence";
 
-    let rocksdb = rocks::open_cf(store_path, None, &[LAST_COMMITTED_CF, SEQUENCE_CF])
-        .expect("Failed creating database");
-
-    let (last_committed_map, sequence_map) = reopen!(&rocksdb,
-        LAST_COMMITTED_CF;<PublicKey, Round>,
-        SEQUENCE_CF;<SequenceNumber, CommittedSubDagShell>
+    let backend: Arc<dyn KeyValueStore> = Arc::new(
+        RocksDBStore::open(store_path, &[LAST_COMMITTED_CF, SEQUENCE_CF])
+            .expect("Failed creating database"),
     );
 
-    Arc::new(ConsensusStore::new(last_committed_map, sequence_map))
+    Arc::new(ConsensusStore::new(
+        StoreMap::new(&backend, LAST_COMMITTED_CF).unwrap(),
+        StoreMap::new(&backend, SEQUENCE_CF).unwrap(),
+    ))
 }
 
 pub fn fixture_payload(number_of_batches: u8) -> IndexMap<BatchDigest, WorkerId> {
@@ -161,7 +171,10 @@ pub fn fixture_batch_with_transactions(number_of_transactions: u32) -> Batch {
 // Fixture
 pub fn transaction() -> Transaction {
     // generate random value transactions, but the length will be always 100 bytes
-    (0..100).map(|_v| rand::random::<u8>()).collect()
+    (0..100)
+        .map(|_v| rand::random::<u8>())
+        .collect::<Vec<_>>()
+        .into()
 }
 
 #[derive(Clone)]
@@ -568,11 +581,25 @@ pub fn mock_signed_certificate(
     (cert.digest(), cert)
 }
 
+/// Draws the stakes of a committee of `committee_size` authorities, skewed towards a few large
+/// stakes: most authorities hold a small stake, and a few of them hold up to 1000 times as much.
+pub fn skewed_stakes<R: Rng>(rng: &mut R, committee_size: usize) -> Vec<Stake> {
+    (0..committee_size)
+        .map(|_| 1 + (rng.gen::<f64>().powi(4) * 1000.0) as Stake)
+        .collect()
+}
+
 pub struct Builder<R = OsRng> {
     rng: R,
     committee_size: NonZeroUsize,
     number_of_workers: NonZeroUsize,
     randomize_ports: bool,
+    host_per_authority: bool,
+    quorum_policy: QuorumPolicy,
+    protocol_config: ProtocolConfig,
+    stakes: Option<Vec<Stake>>,
+    workers_per_authority: Option<Vec<NonZeroUsize>>,
+    parameters: Option<Vec<Parameters>>,
 }
 
 impl Default for Builder {
@@ -588,6 +615,12 @@ impl Builder {
             committee_size: NonZeroUsize::new(4).unwrap(),
             number_of_workers: NonZeroUsize::new(4).unwrap(),
             randomize_ports: false,
+            host_per_authority: false,
+            quorum_policy: QuorumPolicy::default(),
+            protocol_config: ProtocolConfig::default(),
+            stakes: None,
+            workers_per_authority: None,
+            parameters: None,
         }
     }
 }
@@ -608,23 +641,106 @@ impl<R> Builder<R> {
         self
     }
 
+    /// Gives the i-th authority and its workers the address `10.10.0.<i + 1>`, instead of the
+    /// loopback address shared by all the authorities, e.g. to run each one in its own simulator
+    /// node.
+    pub fn host_per_authority(mut self, host_per_authority: bool) -> Self {
+        self.host_per_authority = host_per_authority;
+        self
+    }
+
+    pub fn quorum_policy(mut self, quorum_policy: QuorumPolicy) -> Self {
+        self.quorum_policy = quorum_policy;
+        self
+    }
+
+    pub fn protocol_config(mut self, protocol_config: ProtocolConfig) -> Self {
+        self.protocol_config = protocol_config;
+        self
+    }
+
+    /// Assigns `stakes[i]` to the i-th authority, instead of an equal stake of 1 to every one.
+    /// The size of the committee is the number of stakes.
+    pub fn stakes(mut self, stakes: Vec<Stake>) -> Self {
+        self.committee_size =
+            NonZeroUsize::new(stakes.len()).expect("The committee must have authorities");
+        self.stakes = Some(stakes);
+        self
+    }
+
+    /// Gives `workers[i]` workers to the i-th authority, instead of `number_of_workers` to every
+    /// one. The size of the committee is the number of worker counts.
+    pub fn workers_per_authority(mut self, workers: Vec<NonZeroUsize>) -> Self {
+        self.committee_size =
+            NonZeroUsize::new(workers.len()).expect("The committee must have authorities");
+        self.workers_per_authority = Some(workers);
+        self
+    }
+
+    /// Runs the i-th authority with `parameters[i]`, instead of the parameters chosen by the
+    /// test harness. The size of the committee is the number of parameters.
+    pub fn parameters(mut self, parameters: Vec<Parameters>) -> Self {
+        self.committee_size =
+            NonZeroUsize::new(parameters.len()).expect("The committee must have authorities");
+        self.parameters = Some(parameters);
+        self
+    }
+
     pub fn rng<N: rand::RngCore + rand::CryptoRng>(self, rng: N) -> Builder<N> {
         Builder {
             rng,
             committee_size: self.committee_size,
             number_of_workers: self.number_of_workers,
             randomize_ports: self.randomize_ports,
+            host_per_authority: self.host_per_authority,
+            quorum_policy: self.quorum_policy,
+            protocol_config: self.protocol_config,
+            stakes: self.stakes,
+            workers_per_authority: self.workers_per_authority,
+            parameters: self.parameters,
         }
     }
 }
 
 impl<R: rand::RngCore + rand::CryptoRng> Builder<R> {
     pub fn build(mut self) -> CommitteeFixture {
-        let authorities = (0..self.committee_size.get())
-            .map(|_| {
+        let committee_size = self.committee_size.get();
+        for (setting, len) in [
+            ("stakes", self.stakes.as_ref().map(Vec::len)),
+            (
+                "worker counts",
+                self.workers_per_authority.as_ref().map(Vec::len),
+            ),
+            ("parameters", self.parameters.as_ref().map(Vec::len)),
+        ] {
+            if let Some(len) = len {
+                assert_eq!(
+                    len, committee_size,
+                    "The {setting} do not match the size of the committee"
+                );
+            }
+        }
+
+        let authorities = (0..committee_size)
+            .map(|i| {
+                let stake = self.stakes.as_ref().map_or(1, |stakes| stakes[i]);
+                let number_of_workers = self
+                    .workers_per_authority
+                    .as_ref()
+                    .map_or(self.number_of_workers, |workers| workers[i]);
+                let parameters = self
+                    .parameters
+                    .as_ref()
+                    .map(|parameters| parameters[i].clone());
+                let host = if self.host_per_authority {
+                    format!("10.10.0.{}", i + 1)
+                } else {
+                    "127.0.0.1".to_string()
+                };
                 AuthorityFixture::generate(
                     StdRng::from_rng(&mut self.rng).unwrap(),
-                    self.number_of_workers,
+                    number_of_workers,
+                    &host,
                     |host| {
                         if self.randomize_ports {
                             get_available_port(host)
@@ -633,12 +749,16 @@ impl<R: rand::RngCore + rand::CryptoRng> Builder<R> {
                         }
                     },
                 )
+                .with_stake(stake)
+                .with_parameters(parameters)
             })
             .collect();
 
         CommitteeFixture {
             authorities,
             epoch: Epoch::default(),
+            quorum_policy: self.quorum_policy,
+            protocol_config: self.protocol_config,
         }
     }
 }
@@ -646,6 +766,8 @@ impl<R: rand::RngCore + rand::CryptoRng> Builder<R> {
 pub struct CommitteeFixture {
     authorities: Vec<AuthorityFixture>,
     epoch: Epoch,
+    quorum_policy: QuorumPolicy,
+    protocol_config: ProtocolConfig,
 }
 
 impl CommitteeFixture {
@@ -669,6 +791,8 @@ impl CommitteeFixture {
                     (pubkey, authority)
                 })
                 .collect(),
+            quorum_policy: self.quorum_policy,
+            protocol_config: self.protocol_config,
         }
     }
 
@@ -687,6 +811,14 @@ impl CommitteeFixture {
         self.worker_cache().into()
     }
 
+    /// The number of workers of the whole committee.
+    pub fn number_of_workers(&self) -> usize {
+        self.authorities
+            .iter()
+            .map(AuthorityFixture::number_of_workers)
+            .sum()
+    }
+
     // pub fn header(&self, author: PublicKey) -> Header {
     // Currently sign with the last authority
     pub fn header(&self) -> Header {
@@ -749,6 +881,20 @@ impl CommitteeFixture {
             .collect()
     }
 
+    /// The first authorities (in the order of `authorities`) whose stake reaches a quorum.
+    pub fn quorum(&self) -> Vec<&AuthorityFixture> {
+        let committee = self.committee();
+        let mut stake = 0;
+        self.authorities
+            .iter()
+            .take_while(|a| {
+                let reached = committee.reached_quorum(stake);
+                stake += a.stake;
+                !reached
+            })
+            .collect()
+    }
+
     pub fn certificate(&self, header: &Header) -> Certificate {
         let committee = self.committee();
         let votes: Vec<_> = self
@@ -780,6 +926,7 @@ pub struct AuthorityFixture {
     stake: Stake,
     address: Multiaddr,
     workers: BTreeMap<WorkerId, WorkerFixture>,
+    parameters: Option<Parameters>,
 }
 
 impl AuthorityFixture {
@@ -804,7 +951,18 @@ impl AuthorityFixture {
     }
 
     pub fn worker(&self, id: WorkerId) -> &WorkerFixture {
-        self.workers.get(&id).unwrap()
+        self.workers
+            .get(&id)
+            .unwrap_or_else(|| panic!("The authority has no worker {id}"))
+    }
+
+    /// The workers of the authority, by id.
+    pub fn workers(&self) -> impl Iterator<Item = &WorkerFixture> {
+        self.workers.values()
+    }
+
+    pub fn number_of_workers(&self) -> usize {
+        self.workers.len()
     }
 
     pub fn worker_keypairs(&self) -> Vec<NetworkKeyPair> {
@@ -822,6 +980,23 @@ impl AuthorityFixture {
         self.network_keypair.public().clone()
     }
 
+    pub fn stake(&self) -> Stake {
+        self.stake
+    }
+
+    fn with_stake(self, stake: Stake) -> Self {
+        Self { stake, ..self }
+    }
+
+    /// The parameters the authority should run with, if set by the fixture builder.
+    pub fn parameters(&self) -> Option<&Parameters> {
+        self.parameters.as_ref()
+    }
+
+    fn with_parameters(self, parameters: Option<Parameters>) -> Self {
+        Self { parameters, ..self }
+    }
+
     pub fn authority(&self) -> Authority {
         Authority {
             stake: self.stake,
@@ -871,21 +1046,25 @@ impl AuthorityFixture {
         Vote::new_with_signer(header, self.keypair.public(), &self.keypair)
     }
 
-    fn generate<R, P>(mut rng: R, number_of_workers: NonZeroUsize, mut get_port: P) -> Self
+    fn generate<R, P>(
+        mut rng: R,
+        number_of_workers: NonZeroUsize,
+        host: &str,
+        mut get_port: P,
+    ) -> Self
     where
         R: AllowedRng,
         P: FnMut(&str) -> u16,
     {
         let keypair = KeyPair::generate(&mut rng);
         let network_keypair = NetworkKeyPair::generate(&mut rng);
-        let host = "127.0.0.1";
         let address: Multiaddr = format!("/ip4/{}/udp/{}", host, get_port(host))
             .parse()
             .unwrap();
 
         let workers = (0..number_of_workers.get())
             .map(|idx| {
-                let worker = WorkerFixture::generate(&mut rng, idx as u32, &mut get_port);
+                let worker = WorkerFixture::generate(&mut rng, idx as u32, host, &mut get_port);
 
                 (idx as u32, worker)
             })
@@ -897,18 +1076,22 @@ impl AuthorityFixture {
             stake: 1,
             address,
             workers,
+            parameters: None,
         }
     }
 }
 
 pub struct WorkerFixture {
     keypair: NetworkKeyPair,
-    #[allow(dead_code)]
     id: WorkerId,
     info: WorkerInfo,
 }
 
 impl WorkerFixture {
+    pub fn id(&self) -> WorkerId {
+        self.id
+    }
+
     pub fn keypair(&self) -> NetworkKeyPair {
         self.keypair.copy()
     }
@@ -925,14 +1108,13 @@ impl WorkerFixture {
             .unwrap()
     }
 
-    fn generate<R, P>(rng: R, id: WorkerId, mut get_port: P) -> Self
+    fn generate<R, P>(rng: R, id: WorkerId, host: &str, mut get_port: P) -> Self
     where
         R: rand::RngCore + rand::CryptoRng,
         P: FnMut(&str) -> u16,
     {
         let keypair = NetworkKeyPair::generate(&mut StdRng::from_rng(rng).unwrap());
         let worker_name = keypair.public().clone();
-        let host = "127.0.0.1";
         let worker_address = format!("/ip4/{}/udp/{}", host, get_port(host))
             .parse()
             .unwrap();
@@ -0,0 +1,301 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A cluster running every primary and worker as a child process of the `narwhal-node` binary,
+//! to exercise what a single runtime hides: port reuse across restarts, signal handling, locking
+//! of the stores by the processes.
+//!
+//! The configuration of the cluster (committee, workers, parameters and keys) is generated in a
+//! temporary directory, along with the store and the log file of every process.
+use crate::CommitteeFixture;
+use config::{Export, Parameters, WorkerId};
+use multiaddr::Multiaddr;
+use node::metrics::METRICS_ROUTE;
+use std::{
+    collections::BTreeMap,
+    fs::{self, OpenOptions},
+    io::{Read, Write},
+    net::TcpStream,
+    path::{Path, PathBuf},
+    process::{Child, Command, Stdio},
+};
+use tempfile::TempDir;
+use tracing::info;
+
+/// A process of the cluster.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum NodeId {
+    /// The primary of the authority.
+    Primary(usize),
+    /// A worker of the authority.
+    Worker(usize, WorkerId),
+}
+
+pub struct ProcessCluster {
+    /// The `narwhal-node` binary.
+    binary: PathBuf,
+    fixture: CommitteeFixture,
+    dir: TempDir,
+    processes: BTreeMap<NodeId, NodeProcess>,
+}
+
+struct NodeProcess {
+    /// The command line of the process, from the binary on.
+    args: Vec<String>,
+    parameters: Parameters,
+    log: PathBuf,
+    child: Option<Child>,
+}
+
+impl ProcessCluster {
+    /// Generates the configuration of a cluster of the authorities of `fixture`, run by the
+    /// `narwhal-node` `binary` with `parameters` unless the fixture sets their own. None of the
+    /// processes is started.
+    pub fn new(
+        binary: impl Into<PathBuf>,
+        fixture: CommitteeFixture,
+        parameters: Parameters,
+    ) -> Self {
+        let dir = tempfile::tempdir().expect("Failed to create the cluster directory");
+        let path = |name: &str| dir.path().join(name).to_string_lossy().into_owned();
+
+        let committee = path("committee.json");
+        let workers = path("workers.json");
+        fixture.committee().export(&committee).unwrap();
+        fixture.worker_cache().export(&workers).unwrap();
+
+        let mut processes = BTreeMap::new();
+        for (i, authority) in fixture.authorities().enumerate() {
+            let primary_keys = path(&format!("primary-{i}.key"));
+            let primary_network_keys = path(&format!("primary-network-{i}.key"));
+            authority.keypair().export(&primary_keys).unwrap();
+            authority
+                .network_keypair()
+                .export(&primary_network_keys)
+                .unwrap();
+            let worker_keypairs = authority.worker_keypairs();
+            let authority_parameters = authority.parameters().unwrap_or(&parameters);
+
+            let ids = std::iter::once(NodeId::Primary(i))
+                .chain((0..worker_keypairs.len()).map(|id| NodeId::Worker(i, id as WorkerId)));
+            for id in ids {
+                let name = id.name();
+                // Each process serves its metrics and admin endpoints on its own ports.
+                let parameters = Parameters {
+                    prometheus_metrics: Default::default(),
+                    network_admin_server: Default::default(),
+                    ..authority_parameters.clone()
+                };
+                let parameters_file = path(&format!("{name}.parameters.json"));
+                parameters.export(&parameters_file).unwrap();
+
+                let mut args = vec![
+                    "-vv".to_string(),
+                    "run".to_string(),
+                    format!("--primary-keys={primary_keys}"),
+                    format!("--primary-network-keys={primary_network_keys}"),
+                    format!("--committee={committee}"),
+                    format!("--workers={workers}"),
+                    format!("--parameters={parameters_file}"),
+                    format!("--store={}", path(&format!("{name}.db"))),
+                ];
+                match id {
+                    NodeId::Primary(_) => args.push("primary".to_string()),
+                    NodeId::Worker(_, worker_id) => {
+                        let worker_keys = path(&format!("{name}.key"));
+                        worker_keypairs[worker_id as usize]
+                            .export(&worker_keys)
+                            .unwrap();
+                        args.push(format!("--worker-keys={worker_keys}"));
+                        args.extend(["worker".to_string(), format!("--id={worker_id}")]);
+                    }
+                }
+
+                let process = NodeProcess {
+                    args,
+                    parameters,
+                    log: dir.path().join(format!("{name}.log")),
+                    child: None,
+                };
+                processes.insert(id, process);
+            }
+        }
+
+        Self {
+            binary: binary.into(),
+            fixture,
+            dir,
+            processes,
+        }
+    }
+
+    pub fn fixture(&self) -> &CommitteeFixture {
+        &self.fixture
+    }
+
+    /// The directory holding the configuration, the stores and the logs of the cluster.
+    pub fn dir(&self) -> &Path {
+        self.dir.path()
+    }
+
+    /// Every process of the cluster.
+    pub fn nodes(&self) -> Vec<NodeId> {
+        self.processes.keys().copied().collect()
+    }
+
+    /// Starts every process of the cluster not running yet.
+    pub fn start(&mut self) {
+        for id in self.nodes() {
+            if !self.is_running(id) {
+                self.start_node(id);
+            }
+        }
+    }
+
+    /// Starts the process `id`, on the store left by its previous run if any.
+    pub fn start_node(&mut self, id: NodeId) {
+        assert!(!self.is_running(id), "{id:?} is already running");
+        let binary = self.binary.clone();
+        let process = self.process_mut(id);
+        // The logs of the successive runs follow each other.
+        let log = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&process.log)
+            .expect("Failed to open the log file");
+        let child = Command::new(binary)
+            .args(&process.args)
+            .stdin(Stdio::null())
+            .stdout(log.try_clone().unwrap())
+            .stderr(log)
+            .spawn()
+            .expect("Failed to start the node process");
+        info!("Started {id:?} as process {}", child.id());
+        process.child = Some(child);
+    }
+
+    /// Kills the process `id` with SIGKILL, as a crash would, and reaps it.
+    pub fn kill(&mut self, id: NodeId) {
+        if let Some(mut child) = self.process_mut(id).child.take() {
+            let _ = child.kill();
+            let _ = child.wait();
+            info!("Killed {id:?}");
+        }
+    }
+
+    /// Sends SIGTERM to the process `id`, returning whether it exited before `timeout`. The
+    /// process is killed if it did not.
+    #[cfg(unix)]
+    pub fn terminate(&mut self, id: NodeId, timeout: std::time::Duration) -> bool {
+        let Some(child) = self.process_mut(id).child.as_mut() else {
+            return true;
+        };
+        let _ = Command::new("kill")
+            .args(["-TERM", &child.id().to_string()])
+            .status();
+        let deadline = std::time::Instant::now() + timeout;
+        while std::time::Instant::now() < deadline {
+            if matches!(child.try_wait(), Ok(Some(_))) {
+                self.process_mut(id).child = None;
+                return true;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(50));
+        }
+        self.kill(id);
+        false
+    }
+
+    /// Kills then starts again the process `id`, which keeps its store and ports.
+    pub fn restart(&mut self, id: NodeId) {
+        self.kill(id);
+        self.start_node(id);
+    }
+
+    /// Whether the process `id` was started and did not exit since.
+    pub fn is_running(&mut self, id: NodeId) -> bool {
+        let process = self.process_mut(id);
+        match process.child.as_mut().map(Child::try_wait) {
+            Some(Ok(None)) => true,
+            Some(_) => {
+                process.child = None;
+                false
+            }
+            None => false,
+        }
+    }
+
+    /// The logs of every run of the process `id`.
+    pub fn logs(&self, id: NodeId) -> String {
+        fs::read_to_string(&self.process(id).log).unwrap_or_default()
+    }
+
+    /// The metrics served by the process `id`, in the Prometheus text format.
+    pub fn metrics(&self, id: NodeId) -> std::io::Result<String> {
+        let address = &self.process(id).parameters.prometheus_metrics.socket_addr;
+        let address = network::multiaddr_to_address(address)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e.to_string()))?;
+        let mut stream = TcpStream::connect(address.to_string())?;
+        write!(
+            stream,
+            "GET {METRICS_ROUTE} HTTP/1.0\r\nHost: {address}\r\n\r\n"
+        )?;
+        let mut response = String::new();
+        stream.read_to_string(&mut response)?;
+        Ok(response
+            .split_once("\r\n\r\n")
+            .map_or_else(|| response.clone(), |(_, body)| body.to_string()))
+    }
+
+    /// The value of the metric `name` served by the process `id`, summed over its labels.
+    pub fn metric(&self, id: NodeId, name: &str) -> Option<f64> {
+        let metrics = self.metrics(id).ok()?;
+        let values: Vec<f64> = metrics
+            .lines()
+            .filter(|line| !line.starts_with('#'))
+            .filter_map(|line| {
+                let (metric, value) = line.rsplit_once(' ')?;
+                let metric = metric.split('{').next()?;
+                (metric == name).then(|| value.parse().ok()).flatten()
+            })
+            .collect();
+        (!values.is_empty()).then(|| values.iter().sum())
+    }
+
+    /// The address where the worker `id` of the authority `authority` receives transactions.
+    pub fn transactions_address(&self, authority: usize, id: WorkerId) -> Multiaddr {
+        let authority = self.fixture.authorities().nth(authority).unwrap();
+        self.fixture.worker_cache().workers[&authority.public_key()].0[&id]
+            .transactions
+            .clone()
+    }
+
+    fn process(&self, id: NodeId) -> &NodeProcess {
+        self.processes
+            .get(&id)
+            .unwrap_or_else(|| panic!("{id:?} is not part of the cluster"))
+    }
+
+    fn process_mut(&mut self, id: NodeId) -> &mut NodeProcess {
+        self.processes
+            .get_mut(&id)
+            .unwrap_or_else(|| panic!("{id:?} is not part of the cluster"))
+    }
+}
+
+impl Drop for ProcessCluster {
+    fn drop(&mut self) {
+        for id in self.nodes() {
+            self.kill(id);
+        }
+    }
+}
+
+impl NodeId {
+    /// The name of the files of the process.
+    fn name(&self) -> String {
+        match self {
+            NodeId::Primary(i) => format!("primary-{i}"),
+            NodeId::Worker(i, id) => format!("worker-{i}-{id}"),
+        }
+    }
+}
@@ -0,0 +1,156 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Proptest strategies for committees, worker caches and DAGs, to check invariants (quorum
+//! math, garbage collection, ordering) over arbitrary inputs rather than a few hand-built ones.
+//!
+//! The committees are built by the [`CommitteeFixture`] from a drawn seed, so they shrink
+//! towards fewer authorities with smaller stakes. Their ports are randomized, for them to pass
+//! the validation of the configuration.
+use crate::{mock_certificate, CommitteeFixture};
+use config::{Committee, Stake, WorkerCache};
+use fastcrypto::hash::Hash as _;
+use proptest::{collection::vec, prelude::*};
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use std::{collections::BTreeSet, num::NonZeroUsize, ops::RangeInclusive};
+use types::{Certificate, CertificateDigest, Round};
+
+#[cfg(test)]
+#[path = "tests/strategies_tests.rs"]
+pub mod strategies_tests;
+
+/// The largest stake of an authority drawn by the strategies.
+pub const MAX_STAKE: Stake = 1_000;
+
+/// The stakes of a committee of 1 to `max_size` authorities.
+pub fn arb_stakes(max_size: usize) -> impl Strategy<Value = Vec<Stake>> {
+    vec(1..=MAX_STAKE, 1..=max_size)
+}
+
+/// A committee of 1 to `max_size` authorities with arbitrary stakes.
+pub fn arb_committee(max_size: usize) -> impl Strategy<Value = Committee> {
+    arb_committee_with_workers(max_size, 1).prop_map(|(committee, _)| committee)
+}
+
+/// A committee of 1 to `max_size` authorities with arbitrary stakes, and its worker cache where
+/// each authority runs 1 to `max_workers` workers.
+pub fn arb_committee_with_workers(
+    max_size: usize,
+    max_workers: usize,
+) -> impl Strategy<Value = (Committee, WorkerCache)> {
+    arb_stakes(max_size)
+        .prop_flat_map(move |stakes| {
+            let workers = vec(1..=max_workers, stakes.len());
+            (Just(stakes), workers, any::<[u8; 32]>())
+        })
+        .prop_map(|(stakes, workers, seed)| {
+            let fixture = CommitteeFixture::builder()
+                .rng(StdRng::from_seed(seed))
+                .stakes(stakes)
+                .workers_per_authority(
+                    workers
+                        .into_iter()
+                        .map(|n| NonZeroUsize::new(n).unwrap())
+                        .collect(),
+                )
+                .randomize_ports(true)
+                .build();
+            (fixture.committee(), fixture.worker_cache())
+        })
+}
+
+/// A committee of 1 to `max_size` authorities and a valid DAG of its (unsigned) certificates
+/// over 1 to `max_rounds` rounds from genesis, as made by [`make_random_dag`].
+pub fn arb_dag(
+    max_size: usize,
+    max_rounds: Round,
+) -> impl Strategy<Value = (Committee, Vec<Certificate>)> {
+    (arb_committee(max_size), 1..=max_rounds, any::<u64>()).prop_map(|(committee, rounds, seed)| {
+        let genesis = Certificate::genesis(&committee)
+            .iter()
+            .map(|certificate| certificate.digest())
+            .collect();
+        let (certificates, _) = make_random_dag(
+            &committee,
+            1..=rounds,
+            &genesis,
+            &mut StdRng::seed_from_u64(seed),
+        );
+        (committee, certificates)
+    })
+}
+
+/// Makes rounds worth of unsigned certificates forming a valid DAG: in each round, a random
+/// subset of the authorities holding a quorum of stake certify a header, whose parents are a
+/// random subset of the certificates of the previous round holding a quorum of stake. The
+/// certificates of the first round have all the `initial_parents` as parents.
+///
+/// Outputs the certificates by increasing round, and the digests of the last round to be used
+/// as parents by the next one.
+pub fn make_random_dag<R: Rng>(
+    committee: &Committee,
+    range: RangeInclusive<Round>,
+    initial_parents: &BTreeSet<CertificateDigest>,
+    rng: &mut R,
+) -> (Vec<Certificate>, BTreeSet<CertificateDigest>) {
+    let authorities: Vec<_> = committee.authorities.keys().cloned().collect();
+    let mut certificates = Vec::new();
+    let mut previous_round: Vec<Certificate> = Vec::new();
+
+    for round in range {
+        let origins = quorum_subset(committee, &authorities, |name| committee.stake(name), rng);
+        let mut this_round = Vec::new();
+        for origin in origins {
+            let parents = if previous_round.is_empty() {
+                initial_parents.clone()
+            } else {
+                quorum_subset(
+                    committee,
+                    &previous_round,
+                    |certificate| committee.stake(&certificate.origin()),
+                    rng,
+                )
+                .into_iter()
+                .map(|certificate| certificate.digest())
+                .collect()
+            };
+            let (_, certificate) = mock_certificate(committee, origin.clone(), round, parents);
+            this_round.push(certificate);
+        }
+        certificates.extend(this_round.iter().cloned());
+        previous_round = this_round;
+    }
+
+    let last_parents = if previous_round.is_empty() {
+        initial_parents.clone()
+    } else {
+        previous_round
+            .iter()
+            .map(|certificate| certificate.digest())
+            .collect()
+    };
+    (certificates, last_parents)
+}
+
+/// A random subset of `items` holding a quorum of stake: a shuffled prefix reaching the quorum,
+/// then each of the others with probability 1/2.
+fn quorum_subset<'a, T, R: Rng>(
+    committee: &Committee,
+    items: &'a [T],
+    stake: impl Fn(&T) -> Stake,
+    rng: &mut R,
+) -> Vec<&'a T> {
+    let mut shuffled: Vec<_> = items.iter().collect();
+    shuffled.shuffle(rng);
+    let mut total = 0;
+    shuffled
+        .into_iter()
+        .filter(|item| {
+            let keep = !committee.reached_quorum(total) || rng.gen_bool(0.5);
+            if keep {
+                total += stake(item);
+            }
+            keep
+        })
+        .collect()
+}
@@ -0,0 +1,97 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A temporary directory holding the storage of a node for the duration of a test.
+//!
+//! Unlike [`crate::temp_dir`], the directory is removed when the [`TestStore`] is dropped, and
+//! the storage can be reopened from it to simulate a restart of the node:
+//!
+//! ```ignore
+//! let store = TestStore::new();
+//! let storage = store.open();
+//! // ... run the node, then stop it and drop its storage ...
+//! drop(storage);
+//! let storage = store.open(); // The storage as left by the node.
+//! ```
+//!
+//! The directory of a failed test is kept for inspection when the store is built with
+//! [`TestStore::keep_on_failure`], or when the `NARWHAL_KEEP_TEST_STORES` environment variable is
+//! set.
+use config::{Epoch, StorageParameters};
+use std::path::Path;
+use storage::NodeStorage;
+use tempfile::TempDir;
+
+#[cfg(test)]
+#[path = "tests/test_store_tests.rs"]
+pub mod test_store_tests;
+
+/// Keeps the directories of the stores of the failed tests when set.
+pub const KEEP_TEST_STORES_VAR: &str = "NARWHAL_KEEP_TEST_STORES";
+
+/// The directory of the storage of a node, removed on drop.
+pub struct TestStore {
+    /// `None` once dropped.
+    dir: Option<TempDir>,
+    keep_on_failure: bool,
+}
+
+impl TestStore {
+    pub fn new() -> Self {
+        Self {
+            dir: Some(
+                tempfile::Builder::new()
+                    .prefix("narwhal-test-store")
+                    .tempdir()
+                    .expect("Failed to open temporary directory"),
+            ),
+            keep_on_failure: std::env::var_os(KEEP_TEST_STORES_VAR).is_some(),
+        }
+    }
+
+    /// Keeps the directory when the store is dropped by a panicking thread, as a failing test
+    /// does, and prints its path.
+    pub fn keep_on_failure(mut self) -> Self {
+        self.keep_on_failure = true;
+        self
+    }
+
+    pub fn path(&self) -> &Path {
+        self.dir.as_ref().expect("The store is not dropped").path()
+    }
+
+    /// Opens the storage of a node in the directory, as left by the previous node which opened
+    /// it if any. That storage must be dropped first, since RocksDB locks its database.
+    pub fn open(&self) -> NodeStorage {
+        NodeStorage::reopen(self.path())
+    }
+
+    /// Opens the storage of a node of `epoch` with `parameters`, as [`TestStore::open`] does.
+    pub fn open_with_parameters(
+        &self,
+        parameters: &StorageParameters,
+        epoch: Epoch,
+    ) -> NodeStorage {
+        NodeStorage::reopen_with_parameters(self.path(), parameters, epoch)
+    }
+}
+
+impl Default for TestStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for TestStore {
+    fn drop(&mut self) {
+        let Some(dir) = self.dir.take() else {
+            return;
+        };
+        if self.keep_on_failure && std::thread::panicking() {
+            eprintln!(
+                "Keeping the store of the failed test in {}",
+                dir.into_path().display()
+            );
+        }
+    }
+}
@@ -0,0 +1,36 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+use super::ChaosSchedule;
+use std::time::Duration;
+
+fn injected() -> bool {
+    fail::fail_point!("chaos-test", |_| true);
+    false
+}
+
+#[tokio::test]
+async fn schedule_applies_steps_in_order() {
+    let mut handle = ChaosSchedule::new()
+        .disable(Duration::from_millis(50), "chaos-test")
+        .enable(Duration::ZERO, "chaos-test", "return")
+        .enable(Duration::from_millis(100), "chaos-test", "1*return")
+        .run();
+    handle.applied().await;
+
+    // Only the last configuration holds, which triggers once.
+    assert!(injected());
+    assert!(!injected());
+}
+
+#[tokio::test]
+async fn dropping_the_handle_disables_the_failpoints() {
+    let mut handle = ChaosSchedule::new()
+        .enable(Duration::ZERO, "chaos-test", "return")
+        .run();
+    handle.applied().await;
+    assert!(injected());
+    assert!(injected());
+
+    drop(handle);
+    assert!(!injected());
+}
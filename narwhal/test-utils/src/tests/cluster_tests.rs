@@ -1,8 +1,8 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 use crate::cluster::Cluster;
-use crate::ensure_test_environment;
-use std::time::Duration;
+use crate::{ensure_test_environment, CommitteeFixture};
+use std::{num::NonZeroUsize, time::Duration};
 use types::{PublicKeyProto, RoundsRequest};
 
 #[tokio::test]
@@ -65,3 +65,64 @@ async fn cluster_setup_with_consensus_disabled() {
     assert_eq!(0, r.oldest_round);
     assert_eq!(0, r.newest_round);
 }
+
+#[tokio::test]
+async fn partition_and_heal() {
+    ensure_test_environment();
+    let mut cluster = Cluster::new(None, true);
+    cluster.start(None, Some(1), None).await;
+    tokio::time::sleep(Duration::from_secs(20)).await;
+    cluster.assert_progress(4, 2).await;
+
+    // With a 2-2 split neither side has a quorum: once the commits in flight settle, none happen.
+    cluster.partition(&[&[0, 1], &[2, 3]]);
+    tokio::time::sleep(Duration::from_secs(5)).await;
+    let partitioned = cluster.assert_progress(4, u64::MAX).await;
+    tokio::time::sleep(Duration::from_secs(20)).await;
+    assert_eq!(cluster.assert_progress(4, u64::MAX).await, partitioned);
+
+    // The committee recovers once healed.
+    cluster.heal();
+    tokio::time::sleep(Duration::from_secs(20)).await;
+    let healed = cluster.assert_progress(4, 2).await;
+    for (id, round) in partitioned {
+        assert!(
+            healed[&id] > round,
+            "Node {id} did not commit after healing"
+        );
+    }
+}
+
+#[tokio::test]
+async fn cluster_with_heterogeneous_workers() {
+    ensure_test_environment();
+    let workers: Vec<_> = [1, 2, 3, 4]
+        .into_iter()
+        .map(|n| NonZeroUsize::new(n).unwrap())
+        .collect();
+    let fixture = CommitteeFixture::builder()
+        .workers_per_authority(workers)
+        .randomize_ports(true)
+        .build();
+    assert_eq!(fixture.number_of_workers(), 10);
+    let worker_cache = fixture.worker_cache();
+    for (authority, expected) in fixture.authorities().zip(1..) {
+        assert_eq!(authority.number_of_workers(), expected);
+        assert_eq!(
+            worker_cache.workers[&authority.public_key()].0.len(),
+            expected
+        );
+    }
+
+    // Start at most 2 workers per authority.
+    let mut cluster = Cluster::from_fixture(fixture, None, true);
+    cluster.start(None, Some(2), None).await;
+    tokio::time::sleep(Duration::from_secs(2)).await;
+
+    let mut started: Vec<_> = Vec::new();
+    for authority in cluster.authorities().await {
+        started.push(authority.worker_transaction_addresses().await.len());
+    }
+    started.sort_unstable();
+    assert_eq!(started, vec![1, 2, 2, 2]);
+}
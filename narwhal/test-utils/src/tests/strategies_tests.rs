@@ -0,0 +1,61 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+use super::*;
+use std::collections::BTreeMap;
+
+proptest! {
+    // Generating the keys and reserving the ports of the committees is slow.
+    #![proptest_config(ProptestConfig::with_cases(16))]
+
+    #[test]
+    fn committees_are_valid(committee in arb_committee(10)) {
+        let result = committee.validate();
+        prop_assert!(result.is_ok(), "{:?}", result);
+        prop_assert!(committee.validity_threshold() <= committee.quorum_threshold());
+        prop_assert!(committee.quorum_threshold() <= committee.total_stake());
+    }
+
+    #[test]
+    fn worker_caches_match_their_committee(
+        (committee, worker_cache) in arb_committee_with_workers(7, 3)
+    ) {
+        let result = worker_cache.validate(&committee);
+        prop_assert!(result.is_ok(), "{:?}", result);
+        for index in worker_cache.workers.values() {
+            prop_assert!((1..=3).contains(&index.0.len()));
+        }
+    }
+
+    #[test]
+    fn dags_are_valid((committee, certificates) in arb_dag(7, 10)) {
+        let mut rounds: BTreeMap<Round, Vec<&Certificate>> = BTreeMap::new();
+        for certificate in &certificates {
+            rounds.entry(certificate.round()).or_default().push(certificate);
+        }
+        prop_assert!(certificates.windows(2).all(|w| w[0].round() <= w[1].round()));
+
+        for (round, certificates) in &rounds {
+            let origins: BTreeSet<_> = certificates.iter().map(|c| c.origin()).collect();
+            prop_assert_eq!(origins.len(), certificates.len());
+            let stake = origins.iter().map(|name| committee.stake(name)).sum();
+            prop_assert!(committee.reached_quorum(stake));
+
+            if *round == 1 {
+                continue;
+            }
+            let previous: BTreeMap<_, _> = rounds[&(round - 1)]
+                .iter()
+                .map(|c| (c.digest(), c.origin()))
+                .collect();
+            for certificate in certificates {
+                let stake = certificate
+                    .header
+                    .parents
+                    .iter()
+                    .map(|parent| previous.get(parent).map(|name| committee.stake(name)))
+                    .sum::<Option<Stake>>();
+                prop_assert!(stake.map_or(false, |stake| committee.reached_quorum(stake)));
+            }
+        }
+    }
+}
@@ -0,0 +1,51 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+use super::*;
+use types::BatchDigest;
+
+#[tokio::test]
+async fn reopen_after_restart() {
+    let store = TestStore::new();
+    let key = (BatchDigest([7; 32]), 0);
+
+    let storage = store.open();
+    storage.payload_store.sync_write(key, 1).await.unwrap();
+    drop(storage);
+
+    // The restarted node finds the payload written before.
+    let storage = store.open();
+    assert_eq!(storage.payload_store.read(key).await.unwrap(), Some(1));
+}
+
+#[test]
+fn remove_on_drop() {
+    let store = TestStore::new();
+    drop(store.open());
+    let path = store.path().to_path_buf();
+    assert!(path.exists());
+
+    drop(store);
+    assert!(!path.exists());
+}
+
+#[test]
+fn keep_on_failure() {
+    let (tx, rx) = std::sync::mpsc::channel();
+    let failed = std::thread::spawn(move || {
+        let store = TestStore::new().keep_on_failure();
+        tx.send(store.path().to_path_buf()).unwrap();
+        panic!("The test failed");
+    })
+    .join();
+    assert!(failed.is_err());
+
+    let path = rx.recv().unwrap();
+    assert!(path.exists());
+    std::fs::remove_dir_all(path).unwrap();
+
+    // The store of a passing test is removed.
+    let store = TestStore::new().keep_on_failure();
+    let path = store.path().to_path_buf();
+    drop(store);
+    assert!(!path.exists());
+}
@@ -187,6 +187,43 @@ fn build_anemo_services(out_dir: &Path) {
         )
         .build();
 
+    let handshake = anemo_build::manual::Service::builder()
+        .name("Handshake")
+        .package("narwhal")
+        .method(
+            anemo_build::manual::Method::builder()
+                .name("handshake")
+                .route_name("Handshake")
+                .request_type("crate::HandshakeMessage")
+                .response_type("crate::HandshakeMessage")
+                .codec_path("anemo::rpc::codec::BincodeCodec")
+                .build(),
+        )
+        .method(
+            anemo_build::manual::Method::builder()
+                .name("ping")
+                .route_name("Ping")
+                .request_type("()")
+                .response_type("()")
+                .codec_path("anemo::rpc::codec::BincodeCodec")
+                .build(),
+        )
+        .build();
+
+    let network_keys = anemo_build::manual::Service::builder()
+        .name("NetworkKeys")
+        .package("narwhal")
+        .method(
+            anemo_build::manual::Method::builder()
+                .name("announce_network_key")
+                .route_name("AnnounceNetworkKey")
+                .request_type("crate::NetworkKeyAnnouncement")
+                .response_type("()")
+                .codec_path("anemo::rpc::codec::BincodeCodec")
+                .build(),
+        )
+        .build();
+
     anemo_build::manual::Builder::new()
         .out_dir(out_dir)
         .compile(&[
@@ -194,6 +231,8 @@ fn build_anemo_services(out_dir: &Path) {
             primary_to_worker,
             worker_to_primary,
             worker_to_worker,
+            handshake,
+            network_keys,
         ]);
 }
 
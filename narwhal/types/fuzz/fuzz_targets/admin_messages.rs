@@ -0,0 +1,43 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+#![no_main]
+
+use config::WorkerCacheUpdate;
+use libfuzzer_sys::fuzz_target;
+use narwhal_types_fuzz::{COMMITTEE, WORKER_CACHE};
+use types::{ReconfigureNotification, ReconfigureUpdate};
+
+// The JSON bodies of the admin servers. The first byte selects the endpoint.
+fuzz_target!(|data: &[u8]| {
+    let Some((selector, bytes)) = data.split_first() else {
+        return;
+    };
+    match selector % 3 {
+        0 => {
+            if let Ok(
+                ReconfigureNotification::NewEpoch(committee)
+                | ReconfigureNotification::UpdateCommittee(committee),
+            ) = serde_json::from_slice(bytes)
+            {
+                let _ = committee.validate();
+                let _ = committee.quorum_threshold();
+                let _ = committee.validity_threshold();
+            }
+        }
+        1 => {
+            if let Ok(update) = serde_json::from_slice::<ReconfigureUpdate>(bytes) {
+                if let Ok(committee) = COMMITTEE.apply(&update.committee) {
+                    let _ = committee.validate();
+                }
+                let _ = WORKER_CACHE.load().apply(&update.worker_cache);
+            }
+        }
+        _ => {
+            if let Ok(update) = serde_json::from_slice::<WorkerCacheUpdate>(bytes) {
+                if let Ok(worker_cache) = WORKER_CACHE.load().apply(&update) {
+                    let _ = worker_cache.validate(&COMMITTEE);
+                }
+            }
+        }
+    }
+});
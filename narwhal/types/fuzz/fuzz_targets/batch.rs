@@ -0,0 +1,16 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+#![no_main]
+
+use fastcrypto::hash::Hash as _;
+use libfuzzer_sys::fuzz_target;
+use narwhal_types_fuzz::check_round_trip;
+use types::Batch;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(batch) = bincode::deserialize::<Batch>(data) {
+        let _ = batch.digest();
+        let _ = batch.transactions_with_metadata().count();
+        check_round_trip(&batch);
+    }
+});
@@ -0,0 +1,23 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+#![no_main]
+
+use fastcrypto::hash::Hash as _;
+use libfuzzer_sys::fuzz_target;
+use narwhal_types_fuzz::{check_round_trip, COMMITTEE, WORKER_CACHE};
+use types::Certificate;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(certificate) = bincode::deserialize::<Certificate>(data) {
+        let _ = certificate.digest();
+        if certificate.verify(&COMMITTEE, WORKER_CACHE.clone()).is_ok() {
+            let _ = certificate.signed_authorities(&COMMITTEE);
+        }
+        let _ = Certificate::verify_batch(
+            std::slice::from_ref(&certificate),
+            &COMMITTEE,
+            WORKER_CACHE.clone(),
+        );
+        check_round_trip(&certificate);
+    }
+});
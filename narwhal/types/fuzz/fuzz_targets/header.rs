@@ -0,0 +1,15 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use narwhal_types_fuzz::{check_round_trip, COMMITTEE, WORKER_CACHE};
+use types::Header;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(header) = bincode::deserialize::<Header>(data) {
+        let _ = header.digest();
+        let _ = header.verify(&COMMITTEE, WORKER_CACHE.clone());
+        check_round_trip(&header);
+    }
+});
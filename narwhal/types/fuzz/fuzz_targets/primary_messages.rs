@@ -0,0 +1,56 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use narwhal_types_fuzz::{COMMITTEE, WORKER_CACHE};
+use types::{
+    FetchCertificatesRequest, GetCertificatesRequest, HandshakeMessage, NetworkKeyAnnouncement,
+    PayloadAvailabilityRequest, PrimaryMessage, RequestVoteRequest, Vote,
+};
+
+// The first byte selects the message, the others are its encoding.
+fuzz_target!(|data: &[u8]| {
+    let Some((selector, bytes)) = data.split_first() else {
+        return;
+    };
+    match selector % 8 {
+        0 => {
+            if let Ok(request) = bincode::deserialize::<RequestVoteRequest>(bytes) {
+                let _ = request.header.verify(&COMMITTEE, WORKER_CACHE.clone());
+                for parent in &request.parents {
+                    let _ = parent.verify(&COMMITTEE, WORKER_CACHE.clone());
+                }
+            }
+        }
+        1 => {
+            let _ = bincode::deserialize::<GetCertificatesRequest>(bytes);
+        }
+        2 => {
+            if let Ok(request) = bincode::deserialize::<FetchCertificatesRequest>(bytes) {
+                let _ = request.get_bounds();
+            }
+        }
+        3 => {
+            let _ = bincode::deserialize::<PayloadAvailabilityRequest>(bytes);
+        }
+        4 => {
+            if let Ok(PrimaryMessage::Certificate(certificate)) = bincode::deserialize(bytes) {
+                let _ = certificate.verify(&COMMITTEE, WORKER_CACHE.clone());
+            }
+        }
+        5 => {
+            let _ = bincode::deserialize::<HandshakeMessage>(bytes);
+        }
+        6 => {
+            if let Ok(announcement) = bincode::deserialize::<NetworkKeyAnnouncement>(bytes) {
+                let _ = announcement.verify(&COMMITTEE);
+            }
+        }
+        _ => {
+            if let Ok(vote) = bincode::deserialize::<Vote>(bytes) {
+                let _ = vote.verify(&COMMITTEE);
+            }
+        }
+    }
+});
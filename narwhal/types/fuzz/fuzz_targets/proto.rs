@@ -0,0 +1,65 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+#![no_main]
+
+use crypto::PublicKey;
+use fastcrypto::traits::ToFromBytes;
+use libfuzzer_sys::fuzz_target;
+use prost::Message;
+use types::{
+    CertificateDigest, GetCollectionsRequest, NewEpochRequest, ReadCausalRequest,
+    RemoveCollectionsRequest, RoundsRequest, Transaction, TransactionProto,
+};
+
+// The requests of the gRPC endpoints. The first byte selects the request.
+fuzz_target!(|data: &[u8]| {
+    let Some((selector, bytes)) = data.split_first() else {
+        return;
+    };
+    match selector % 6 {
+        0 => {
+            if let Ok(transaction) = TransactionProto::decode(bytes) {
+                let _ = Transaction::from(transaction);
+            }
+        }
+        1 => {
+            if let Ok(request) = GetCollectionsRequest::decode(bytes) {
+                for id in request.collection_ids {
+                    let _ = CertificateDigest::try_from(id);
+                }
+            }
+        }
+        2 => {
+            if let Ok(request) = RemoveCollectionsRequest::decode(bytes) {
+                for id in request.collection_ids {
+                    let _ = CertificateDigest::try_from(id);
+                }
+            }
+        }
+        3 => {
+            if let Ok(ReadCausalRequest {
+                collection_id: Some(id),
+            }) = ReadCausalRequest::decode(bytes)
+            {
+                let _ = CertificateDigest::try_from(id);
+            }
+        }
+        4 => {
+            if let Ok(RoundsRequest {
+                public_key: Some(key),
+            }) = RoundsRequest::decode(bytes)
+            {
+                let _ = PublicKey::from_bytes(key.bytes.as_ref());
+            }
+        }
+        _ => {
+            if let Ok(request) = NewEpochRequest::decode(bytes) {
+                for validator in request.validators {
+                    if let Some(key) = validator.public_key {
+                        let _ = PublicKey::from_bytes(key.bytes.as_ref());
+                    }
+                }
+            }
+        }
+    }
+});
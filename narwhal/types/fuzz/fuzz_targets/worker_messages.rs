@@ -0,0 +1,48 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+#![no_main]
+
+use fastcrypto::hash::Hash as _;
+use libfuzzer_sys::fuzz_target;
+use types::{
+    RequestBatchRequest, RequestBatchResponse, WorkerBatchMessage, WorkerDeleteBatchesMessage,
+    WorkerOthersBatchMessage, WorkerOurBatchMessage, WorkerReconfigureMessage,
+    WorkerSynchronizeMessage,
+};
+
+// The first byte selects the message, the others are its encoding.
+fuzz_target!(|data: &[u8]| {
+    let Some((selector, bytes)) = data.split_first() else {
+        return;
+    };
+    match selector % 8 {
+        0 => {
+            if let Ok(message) = bincode::deserialize::<WorkerBatchMessage>(bytes) {
+                let _ = message.batch.digest();
+            }
+        }
+        1 => {
+            let _ = bincode::deserialize::<RequestBatchRequest>(bytes);
+        }
+        2 => {
+            if let Ok(RequestBatchResponse { batch: Some(batch) }) = bincode::deserialize(bytes) {
+                let _ = batch.digest();
+            }
+        }
+        3 => {
+            let _ = bincode::deserialize::<WorkerReconfigureMessage>(bytes);
+        }
+        4 => {
+            let _ = bincode::deserialize::<WorkerSynchronizeMessage>(bytes);
+        }
+        5 => {
+            let _ = bincode::deserialize::<WorkerDeleteBatchesMessage>(bytes);
+        }
+        6 => {
+            let _ = bincode::deserialize::<WorkerOurBatchMessage>(bytes);
+        }
+        _ => {
+            let _ = bincode::deserialize::<WorkerOthersBatchMessage>(bytes);
+        }
+    }
+});
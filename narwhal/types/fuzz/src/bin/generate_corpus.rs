@@ -0,0 +1,216 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Writes the seed corpus of the fuzz targets: the encodings of the test vectors, of the messages
+//! carrying them, and of batches generated by their proptest strategy, so the fuzzers start from
+//! well-formed messages rather than from random bytes.
+//!
+//! Usage: `cargo run --bin generate_corpus [corpus directory, by default "corpus"]`
+use fastcrypto::{
+    encoding::{Encoding, Hex},
+    hash::Hash as _,
+};
+use narwhal_types_fuzz::COMMITTEE;
+use proptest::{
+    arbitrary::any,
+    strategy::{Strategy, ValueTree},
+    test_runner::TestRunner,
+};
+use prost::Message;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    path::Path,
+};
+use types::{
+    test_vectors::{test_vectors, EncodingVector},
+    Batch, Certificate, CertificateDigestProto, FetchCertificatesRequest, GetCertificatesRequest,
+    GetCollectionsRequest, Header, PayloadAvailabilityRequest, PrimaryMessage,
+    ReconfigureNotification, RequestBatchRequest, RequestBatchResponse, RequestVoteRequest,
+    TransactionProto, WorkerBatchMessage,
+};
+
+/// The number of batches generated by the proptest strategy.
+const GENERATED_BATCHES: usize = 32;
+
+fn main() {
+    let dir = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| "corpus".to_string());
+    let dir = Path::new(&dir);
+    let vectors = test_vectors();
+    let decode = |encodings: &[EncodingVector]| -> Vec<Vec<u8>> {
+        encodings
+            .iter()
+            .map(|encoding| Hex::decode(&encoding.bytes).expect("The vectors are hex encoded"))
+            .collect()
+    };
+
+    let batch_encodings: Vec<_> = vectors
+        .batches
+        .iter()
+        .flat_map(|batch| decode(&batch.encodings))
+        .collect();
+    let header_encodings: Vec<_> = vectors
+        .headers
+        .iter()
+        .flat_map(|header| decode(&header.encodings))
+        .collect();
+    let certificate_encodings: Vec<_> = vectors
+        .certificates
+        .iter()
+        .flat_map(|certificate| decode(&certificate.encodings))
+        .collect();
+
+    let batches: Vec<Batch> = batch_encodings
+        .iter()
+        .map(|bytes| bincode::deserialize(bytes).unwrap())
+        .collect();
+    let headers: Vec<Header> = header_encodings
+        .iter()
+        .map(|bytes| bincode::deserialize(bytes).unwrap())
+        .collect();
+    let certificates: Vec<Certificate> = certificate_encodings
+        .iter()
+        .map(|bytes| bincode::deserialize(bytes).unwrap())
+        .collect();
+
+    let mut runner = TestRunner::deterministic();
+    let generated: Vec<Vec<u8>> = (0..GENERATED_BATCHES)
+        .map(|_| {
+            let batch = any::<Batch>().new_tree(&mut runner).unwrap().current();
+            bincode::serialize(&batch).unwrap()
+        })
+        .collect();
+
+    write(dir, "batch", batch_encodings.iter().chain(&generated));
+    write(dir, "header", &header_encodings);
+    write(dir, "certificate", &certificate_encodings);
+
+    let digests: Vec<_> = certificates.iter().map(|c| c.digest()).collect();
+    let skip_rounds: BTreeMap<_, _> = certificates
+        .iter()
+        .map(|certificate| (certificate.origin(), BTreeSet::from([certificate.round()])))
+        .collect();
+    write(
+        dir,
+        "primary_messages",
+        [
+            selected(
+                0,
+                &RequestVoteRequest {
+                    header: headers[0].clone(),
+                    parents: certificates.clone(),
+                },
+            ),
+            selected(
+                1,
+                &GetCertificatesRequest {
+                    digests: digests.clone(),
+                },
+            ),
+            selected(
+                2,
+                &FetchCertificatesRequest::default()
+                    .set_bounds(0, skip_rounds)
+                    .set_max_items(100),
+            ),
+            selected(
+                3,
+                &PayloadAvailabilityRequest {
+                    certificate_digests: digests.clone(),
+                },
+            ),
+            selected(4, &PrimaryMessage::Certificate(certificates[0].clone())),
+        ],
+    );
+
+    write(
+        dir,
+        "worker_messages",
+        [
+            selected(
+                0,
+                &WorkerBatchMessage {
+                    batch: batches[1].clone(),
+                },
+            ),
+            selected(
+                1,
+                &RequestBatchRequest {
+                    batch: batches[1].digest(),
+                },
+            ),
+            selected(
+                2,
+                &RequestBatchResponse {
+                    batch: Some(batches[1].clone()),
+                },
+            ),
+        ],
+    );
+
+    let json = |notification: &ReconfigureNotification| {
+        let mut bytes = vec![0];
+        bytes.extend(serde_json::to_vec(notification).unwrap());
+        bytes
+    };
+    write(
+        dir,
+        "admin_messages",
+        [
+            json(&ReconfigureNotification::NewEpoch(COMMITTEE.clone())),
+            json(&ReconfigureNotification::UpdateCommittee(COMMITTEE.clone())),
+            json(&ReconfigureNotification::Shutdown),
+        ],
+    );
+
+    write(
+        dir,
+        "proto",
+        [
+            encoded(
+                0,
+                &TransactionProto {
+                    transaction: batches[1].transactions[0].clone(),
+                    metadata: Default::default(),
+                },
+            ),
+            encoded(
+                1,
+                &GetCollectionsRequest {
+                    collection_ids: digests
+                        .iter()
+                        .map(|digest| CertificateDigestProto::from(*digest))
+                        .collect(),
+                },
+            ),
+        ],
+    );
+}
+
+/// The bincode encoding of `message`, prefixed by the byte selecting its type in the target.
+fn selected<T: serde::Serialize>(selector: u8, message: &T) -> Vec<u8> {
+    let mut bytes = vec![selector];
+    bytes.extend(bincode::serialize(message).unwrap());
+    bytes
+}
+
+/// The protobuf encoding of `message`, prefixed by the byte selecting its type in the target.
+fn encoded(selector: u8, message: &impl Message) -> Vec<u8> {
+    let mut bytes = vec![selector];
+    bytes.extend(message.encode_to_vec());
+    bytes
+}
+
+/// Writes the `inputs` of the corpus of `target`.
+fn write<I: AsRef<[u8]>>(dir: &Path, target: &str, inputs: impl IntoIterator<Item = I>) {
+    let dir = dir.join(target);
+    fs::create_dir_all(&dir).expect("Failed to create the corpus directory");
+    let mut count = 0;
+    for (i, input) in inputs.into_iter().enumerate() {
+        fs::write(dir.join(format!("seed-{i}")), input).expect("Failed to write the corpus");
+        count += 1;
+    }
+    println!("Wrote {count} inputs to {}", dir.display());
+}
@@ -0,0 +1,62 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Helpers of the fuzz targets of the messages received from the peers and the admin clients.
+//!
+//! The targets decode the bytes as the nodes do (bincode for the peers, JSON for the admin
+//! servers, protobuf for the gRPC clients), then run the checks the nodes run on the decoded
+//! messages: none of them may panic, whatever the bytes.
+use config::{Committee, SharedWorkerCache, WorkerCache, WorkerIndex, WorkerInfo};
+use crypto::NetworkKeyPair;
+use fastcrypto::traits::KeyPair as _;
+use once_cell::sync::Lazy;
+use rand::{rngs::StdRng, SeedableRng};
+use serde::{de::DeserializeOwned, Serialize};
+use types::test_vectors;
+
+/// The committee of the test vectors, which signed the certificates of the seed corpus.
+pub static COMMITTEE: Lazy<Committee> = Lazy::new(test_vectors::committee);
+
+/// The workers 0 and 1 of every authority of [`COMMITTEE`], as referenced by the payloads of the
+/// headers of the seed corpus.
+pub static WORKER_CACHE: Lazy<SharedWorkerCache> = Lazy::new(|| {
+    let mut rng = StdRng::from_seed([1; 32]);
+    let workers = COMMITTEE
+        .authorities
+        .keys()
+        .enumerate()
+        .map(|(i, name)| {
+            let index = (0..2)
+                .map(|id| {
+                    let port = 1000 + 10 * i + id as usize;
+                    let info = WorkerInfo {
+                        name: NetworkKeyPair::generate(&mut rng).public().clone(),
+                        transactions: format!("/ip4/127.0.0.1/tcp/{port}/http").parse().unwrap(),
+                        worker_address: format!("/ip4/127.0.0.1/udp/{port}").parse().unwrap(),
+                    };
+                    (id, info)
+                })
+                .collect();
+            (name.clone(), WorkerIndex(index))
+        })
+        .collect();
+    WorkerCache {
+        workers,
+        epoch: COMMITTEE.epoch,
+    }
+    .into()
+});
+
+/// Checks that the encoding of a decoded `value` decodes to a value of the same encoding, when
+/// the value can be encoded (e.g. a batch with transaction metadata has no layout 1).
+pub fn check_round_trip<T: Serialize + DeserializeOwned>(value: &T) {
+    let Ok(bytes) = bincode::serialize(value) else {
+        return;
+    };
+    let decoded: T = bincode::deserialize(&bytes).expect("An encoded value must decode");
+    assert_eq!(
+        bincode::serialize(&decoded).unwrap(),
+        bytes,
+        "The encoding must be stable"
+    );
+}
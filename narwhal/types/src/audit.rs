@@ -0,0 +1,260 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! An append-only log of the consensus-critical events of a node, for post-incident analysis.
+//!
+//! Every event is a JSON object on its own line, with the time it was recorded, the authority
+//! which recorded it and the round and digests it is about:
+//!
+//! ```text
+//! {"time_ms":1670000000000,"authority":"...","event":"vote_sent","epoch":0,"round":12,...}
+//! ```
+//!
+//! Unlike the tracing output, the events are neither filtered nor sampled, their shape is stable,
+//! and every event is synced to disk as it is recorded. The log is enabled by setting
+//! `audit_log.dir` in the parameters, the current file `audit.jsonl` being rotated to
+//! `audit.jsonl.1` (and the older files shifted) when it exceeds `audit_log.max_file_size`.
+use crate::{now, Certificate, CommittedSubDag, Header, Round, TimestampMs};
+use config::{AuditLogParameters, Epoch};
+use crypto::PublicKey;
+use fastcrypto::{hash::Hash, traits::EncodeDecodeBase64};
+use serde::Serialize;
+use std::{
+    collections::HashMap,
+    fs::{self, File, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+use tracing::warn;
+
+#[cfg(test)]
+#[path = "tests/audit_tests.rs"]
+mod audit_tests;
+
+/// The name of the current file of the audit log.
+pub const AUDIT_LOG_FILE: &str = "audit.jsonl";
+
+/// A consensus-critical event.
+#[derive(Clone, Debug, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum AuditEvent {
+    /// The primary proposed a header.
+    HeaderProposed {
+        epoch: Epoch,
+        round: Round,
+        header_digest: String,
+        batches: usize,
+        parents: usize,
+    },
+    /// The primary voted for the header of another authority.
+    VoteSent {
+        epoch: Epoch,
+        round: Round,
+        header_digest: String,
+        header_author: String,
+    },
+    /// The primary formed the certificate of its header from the votes of a quorum.
+    CertificateFormed {
+        epoch: Epoch,
+        round: Round,
+        certificate_digest: String,
+        header_digest: String,
+    },
+    /// Consensus committed a sub-dag.
+    Committed {
+        epoch: Epoch,
+        round: Round,
+        sub_dag_index: u64,
+        leader_digest: String,
+        certificate_digests: Vec<String>,
+    },
+    /// The node received a reconfiguration notification.
+    Reconfigured { epoch: Epoch, notification: String },
+}
+
+impl AuditEvent {
+    pub fn header_proposed(header: &Header) -> Self {
+        Self::HeaderProposed {
+            epoch: header.epoch,
+            round: header.round,
+            header_digest: encode_digest(header.digest()),
+            batches: header.payload.len(),
+            parents: header.parents.len(),
+        }
+    }
+
+    pub fn vote_sent(header: &Header) -> Self {
+        Self::VoteSent {
+            epoch: header.epoch,
+            round: header.round,
+            header_digest: encode_digest(header.digest()),
+            header_author: header.author.encode_base64(),
+        }
+    }
+
+    pub fn certificate_formed(certificate: &Certificate) -> Self {
+        Self::CertificateFormed {
+            epoch: certificate.epoch(),
+            round: certificate.round(),
+            certificate_digest: encode_digest(certificate.digest()),
+            header_digest: encode_digest(certificate.header.digest()),
+        }
+    }
+
+    pub fn committed(sub_dag: &CommittedSubDag) -> Self {
+        Self::Committed {
+            epoch: sub_dag.leader.epoch(),
+            round: sub_dag.leader.round(),
+            sub_dag_index: sub_dag.sub_dag_index,
+            leader_digest: encode_digest(sub_dag.leader.digest()),
+            certificate_digests: sub_dag
+                .certificates
+                .iter()
+                .map(|certificate| encode_digest(certificate.digest()))
+                .collect(),
+        }
+    }
+}
+
+/// The full base64 encoding of a digest, as the digests are displayed truncated.
+fn encode_digest(digest: impl AsRef<[u8]>) -> String {
+    base64::encode(digest.as_ref())
+}
+
+#[derive(Serialize)]
+struct Record<'a> {
+    time_ms: TimestampMs,
+    authority: &'a str,
+    #[serde(flatten)]
+    event: &'a AuditEvent,
+}
+
+/// The audit log of an authority, disabled unless opened. Cloning it shares the log, which is
+/// how the components of the authority get it.
+#[derive(Clone, Default)]
+pub struct AuditLog {
+    inner: Option<(String, Arc<Mutex<Writer>>)>,
+}
+
+/// The writers opened by the process, by directory. The authorities sharing a directory (e.g. in
+/// tests) share its writer.
+static WRITERS: Mutex<Option<HashMap<PathBuf, Arc<Mutex<Writer>>>>> = Mutex::new(None);
+
+impl AuditLog {
+    /// Opens the audit log of the authority `name` as configured by `parameters`. The log is
+    /// disabled when no directory is configured, or when it can't be opened.
+    pub fn open(name: &PublicKey, parameters: &AuditLogParameters) -> Self {
+        match &parameters.dir {
+            None => Self::default(),
+            Some(dir) => match Self::writer(dir, parameters) {
+                Ok(writer) => Self {
+                    inner: Some((name.encode_base64(), writer)),
+                },
+                Err(e) => {
+                    warn!("Failed to open the audit log in {}: {e}", dir.display());
+                    Self::default()
+                }
+            },
+        }
+    }
+
+    fn writer(dir: &Path, parameters: &AuditLogParameters) -> io::Result<Arc<Mutex<Writer>>> {
+        let mut writers = WRITERS.lock().unwrap();
+        let writers = writers.get_or_insert_with(HashMap::new);
+        if let Some(writer) = writers.get(dir) {
+            return Ok(writer.clone());
+        }
+        let writer = Arc::new(Mutex::new(Writer::open(dir, parameters)?));
+        writers.insert(dir.to_path_buf(), writer.clone());
+        Ok(writer)
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.inner.is_some()
+    }
+
+    /// Appends `event` to the log and syncs it to disk. A failure to write is logged, but
+    /// doesn't stop the node.
+    pub fn record(&self, event: AuditEvent) {
+        let Some((authority, writer)) = &self.inner else {
+            return;
+        };
+        let record = Record {
+            time_ms: now(),
+            authority,
+            event: &event,
+        };
+        let mut line = serde_json::to_vec(&record).expect("Audit events are serializable");
+        line.push(b'\n');
+        if let Err(e) = writer.lock().unwrap().append(&line) {
+            warn!("Failed to write to the audit log: {e}");
+        }
+    }
+}
+
+/// The writer of the files of an audit log.
+struct Writer {
+    dir: PathBuf,
+    file: File,
+    size: u64,
+    max_file_size: u64,
+    max_files: usize,
+}
+
+impl Writer {
+    fn open(dir: &Path, parameters: &AuditLogParameters) -> io::Result<Self> {
+        fs::create_dir_all(dir)?;
+        let file = Self::open_current(dir)?;
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            size: file.metadata()?.len(),
+            file,
+            max_file_size: parameters.max_file_size,
+            max_files: parameters.max_files,
+        })
+    }
+
+    fn open_current(dir: &Path) -> io::Result<File> {
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(dir.join(AUDIT_LOG_FILE))
+    }
+
+    /// Writes `line` in a single write, so that a crash leaves at most the last line truncated,
+    /// and syncs it. The events are recorded at most a few times per round, so syncing every
+    /// one of them is cheap.
+    fn append(&mut self, line: &[u8]) -> io::Result<()> {
+        if self.size > 0 && self.size + line.len() as u64 > self.max_file_size {
+            self.rotate()?;
+        }
+        self.file.write_all(line)?;
+        self.file.sync_data()?;
+        self.size += line.len() as u64;
+        Ok(())
+    }
+
+    /// Shifts `audit.jsonl.<n>` to `audit.jsonl.<n + 1>` and the current file to
+    /// `audit.jsonl.1`, deleting the files beyond `max_files`, and starts a new current file.
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.sync_all()?;
+        let path = |n: usize| match n {
+            0 => self.dir.join(AUDIT_LOG_FILE),
+            n => self.dir.join(format!("{AUDIT_LOG_FILE}.{n}")),
+        };
+        if self.max_files == 0 {
+            fs::remove_file(path(0))?;
+        } else {
+            let _ = fs::remove_file(path(self.max_files));
+            for n in (0..self.max_files).rev() {
+                if path(n).exists() {
+                    fs::rename(path(n), path(n + 1))?;
+                }
+            }
+        }
+        self.file = Self::open_current(&self.dir)?;
+        self.size = 0;
+        Ok(())
+    }
+}
@@ -2,27 +2,97 @@
 // SPDX-License-Identifier: Apache-2.0
 #![allow(clippy::mutable_key_type)]
 
-use crate::{Batch, Certificate, CertificateDigest, Round};
+use crate::{Batch, BatchDigest, Certificate, CertificateDigest, Round, Transaction};
+use config::WorkerId;
 use crypto::PublicKey;
 use fastcrypto::hash::Hash;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
-use store::{
-    rocks::{DBMap, TypedStoreError},
-    traits::Map,
-};
+use store::{backend::StoreMap, rocks::TypedStoreError, traits::Map};
 use tokio::sync::mpsc;
 
 /// A global sequence number assigned to every CommittedSubDag.
 pub type SequenceNumber = u64;
 
+#[cfg(test)]
+#[path = "tests/consensus_tests.rs"]
+mod consensus_tests;
+
 #[derive(Clone, Debug)]
 /// The output of Consensus, which includes all the batches for each certificate in the sub dag
 /// It is sent to the the ExecutionState handle_consensus_transactions
 pub struct ConsensusOutput {
     pub sub_dag: Arc<CommittedSubDag>,
     pub batches: Vec<(Certificate, Vec<Batch>)>,
+    /// The digest of the leader certificate that committed the sub dag.
+    pub leader: CertificateDigest,
+    /// The round of the leader certificate.
+    pub round: Round,
+    /// Where each batch comes from, in the order of the batches (certificate by certificate).
+    pub provenance: Vec<BatchProvenance>,
+}
+
+/// The origin of a batch of a [`ConsensusOutput`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BatchProvenance {
+    /// The authority whose worker created the batch.
+    pub author: PublicKey,
+    /// The worker that created the batch.
+    pub worker_id: WorkerId,
+    /// The certificate that included the batch.
+    pub certificate: CertificateDigest,
+    pub digest: BatchDigest,
+}
+
+impl ConsensusOutput {
+    /// Pairs the certificates of the sub dag with their batches, which are expected in the order
+    /// of the payloads of the certificates.
+    pub fn new(sub_dag: Arc<CommittedSubDag>, batches: Vec<(Certificate, Vec<Batch>)>) -> Self {
+        let provenance = batches
+            .iter()
+            .flat_map(|(certificate, _)| {
+                let digest = certificate.digest();
+                certificate
+                    .header
+                    .payload
+                    .iter()
+                    .map(move |(batch, worker_id)| BatchProvenance {
+                        author: certificate.header.author.clone(),
+                        worker_id: *worker_id,
+                        certificate: digest,
+                        digest: *batch,
+                    })
+            })
+            .collect();
+        Self {
+            leader: sub_dag.leader.digest(),
+            round: sub_dag.round(),
+            sub_dag,
+            batches,
+            provenance,
+        }
+    }
+
+    /// The batches of the output, along their origin.
+    pub fn batches_with_provenance(&self) -> impl Iterator<Item = (&BatchProvenance, &Batch)> {
+        self.provenance
+            .iter()
+            .zip(self.batches.iter().flat_map(|(_, batches)| batches.iter()))
+    }
+
+    /// The transactions of the output in order, along the origin of their batch and their
+    /// metadata, if any.
+    pub fn transactions(
+        &self,
+    ) -> impl Iterator<Item = (&BatchProvenance, &Transaction, Option<&[u8]>)> {
+        self.batches_with_provenance()
+            .flat_map(|(provenance, batch)| {
+                batch
+                    .transactions_with_metadata()
+                    .map(move |(transaction, metadata)| (provenance, transaction, metadata))
+            })
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, Default)]
@@ -92,16 +162,16 @@ pub type StoreResult<T> = Result<T, TypedStoreError>;
 /// The persistent storage of the sequencer.
 pub struct ConsensusStore {
     /// The latest committed round of each validator.
-    last_committed: DBMap<PublicKey, Round>,
+    last_committed: StoreMap<PublicKey, Round>,
     /// The global consensus sequence.
-    committed_sub_dags_by_index: DBMap<SequenceNumber, CommittedSubDagShell>,
+    committed_sub_dags_by_index: StoreMap<SequenceNumber, CommittedSubDagShell>,
 }
 
 impl ConsensusStore {
     /// Create a new consensus store structure by using already loaded maps.
     pub fn new(
-        last_committed: DBMap<PublicKey, Round>,
-        sequence: DBMap<SequenceNumber, CommittedSubDagShell>,
+        last_committed: StoreMap<PublicKey, Round>,
+        sequence: StoreMap<SequenceNumber, CommittedSubDagShell>,
     ) -> Self {
         Self {
             last_committed,
@@ -150,6 +220,22 @@ impl ConsensusStore {
         s
     }
 
+    /// Load the first sub dag committed in the store, if any.
+    pub fn read_first_committed_sub_dag(&self) -> Option<CommittedSubDagShell> {
+        self.committed_sub_dags_by_index
+            .iter()
+            .next()
+            .map(|(_, sub_dag)| sub_dag)
+    }
+
+    /// Load the sub dag committed with sequence number `index`, if any.
+    pub fn read_committed_sub_dag(
+        &self,
+        index: &SequenceNumber,
+    ) -> StoreResult<Option<CommittedSubDagShell>> {
+        self.committed_sub_dags_by_index.get(index)
+    }
+
     /// Load all the sub dags committed with sequence number of at least `from`.
     pub fn read_committed_sub_dags_from(
         &self,
@@ -0,0 +1,244 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! The health of a node, aggregated from the states of its components for the liveness and
+//! readiness probes of the admin servers.
+//!
+//! A component's state is either computed by a probe when the health is queried (e.g. the
+//! connectivity of the primary to the committee), or reported by the supervision of its tasks by
+//! the node, which marks it down when one of them exits. A component is critical when the node
+//! can't work without it, like the primary, as opposed to e.g. one of its workers.
+use crypto::PublicKey;
+use serde::Serialize;
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{Arc, Mutex},
+};
+use tokio::task::JoinHandle;
+
+#[cfg(test)]
+#[path = "tests/health_tests.rs"]
+mod health_tests;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ComponentState {
+    /// The component is not working yet, e.g. still connecting to its peers.
+    Starting,
+    Running,
+    /// The component works, but with reduced capacity or redundancy.
+    Degraded,
+    /// The component stopped, or can't work.
+    Down,
+}
+
+/// The overall status of a node.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NodeStatus {
+    /// A critical component is starting.
+    Booting,
+    Healthy,
+    /// A component is degraded, or a non-critical one is down.
+    Degraded,
+    /// A critical component is down.
+    Unhealthy,
+    /// The node received a shutdown notification, e.g. at the end of the epoch.
+    ShuttingDown,
+}
+
+impl NodeStatus {
+    /// Whether the node is alive, i.e. should not be restarted.
+    pub fn is_live(&self) -> bool {
+        !matches!(self, Self::Unhealthy)
+    }
+
+    /// Whether the node can serve.
+    pub fn is_ready(&self) -> bool {
+        matches!(self, Self::Healthy | Self::Degraded)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ComponentHealth {
+    pub state: ComponentState,
+    pub critical: bool,
+    /// Why the component is down, if it is.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct HealthReport {
+    pub status: NodeStatus,
+    pub components: BTreeMap<String, ComponentHealth>,
+}
+
+type Probe = Arc<dyn Fn() -> ComponentState + Send + Sync>;
+
+struct Component {
+    critical: bool,
+    /// The state of a component without a probe is running.
+    probe: Option<Probe>,
+    down: Option<String>,
+}
+
+#[derive(Default)]
+struct Inner {
+    shutting_down: bool,
+    components: BTreeMap<String, Component>,
+}
+
+/// The health of the components of a node. Cloning it shares the components.
+#[derive(Clone, Default)]
+pub struct NodeHealth {
+    inner: Arc<Mutex<Inner>>,
+}
+
+/// The health of the authorities run by the process, shared by their primary, their workers and
+/// the node supervising them.
+static AUTHORITY_HEALTH: Mutex<Option<HashMap<PublicKey, NodeHealth>>> = Mutex::new(None);
+
+impl NodeHealth {
+    /// The health of the authority `name`, across epochs.
+    pub fn of_authority(name: &PublicKey) -> Self {
+        AUTHORITY_HEALTH
+            .lock()
+            .unwrap()
+            .get_or_insert_with(HashMap::new)
+            .entry(name.clone())
+            .or_default()
+            .clone()
+    }
+
+    /// Adds the component `name`, whose state is computed by `probe`. A component of the same
+    /// name is replaced, e.g. by the one of the next epoch, which also ends the shutdown.
+    pub fn register<F>(&self, name: impl Into<String>, critical: bool, probe: F)
+    where
+        F: Fn() -> ComponentState + Send + Sync + 'static,
+    {
+        self.insert(name.into(), critical, Some(Arc::new(probe)));
+    }
+
+    fn insert(&self, name: String, critical: bool, probe: Option<Probe>) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.shutting_down = false;
+        inner.components.insert(
+            name,
+            Component {
+                critical,
+                probe,
+                down: None,
+            },
+        );
+    }
+
+    /// Supervises the tasks of the component `name`, added if it doesn't exist yet: the
+    /// component is marked down when one of the tasks exits. The returned handles are the ones
+    /// to join or abort instead of `handles`, aborting one of them aborts its task.
+    pub fn supervise(
+        &self,
+        name: impl Into<String>,
+        critical: bool,
+        handles: Vec<JoinHandle<()>>,
+    ) -> Vec<JoinHandle<()>> {
+        let name = name.into();
+        {
+            let mut inner = self.inner.lock().unwrap();
+            inner.shutting_down = false;
+            inner
+                .components
+                .entry(name.clone())
+                .and_modify(|component| component.down = None)
+                .or_insert(Component {
+                    critical,
+                    probe: None,
+                    down: None,
+                });
+        }
+        handles
+            .into_iter()
+            .map(|handle| {
+                let (health, name) = (self.clone(), name.clone());
+                tokio::spawn(async move {
+                    let mut guard = AbortOnDrop(Some(handle));
+                    let result = guard.0.as_mut().unwrap().await;
+                    guard.0 = None;
+                    let reason = match result {
+                        Ok(()) => "a task exited".to_string(),
+                        Err(e) if e.is_panic() => "a task panicked".to_string(),
+                        Err(_) => "a task was cancelled".to_string(),
+                    };
+                    health.mark_down(&name, reason);
+                })
+            })
+            .collect()
+    }
+
+    /// Marks the component `name` down for `reason`, until it is registered again.
+    pub fn mark_down(&self, name: &str, reason: impl Into<String>) {
+        if let Some(component) = self.inner.lock().unwrap().components.get_mut(name) {
+            component.down.get_or_insert(reason.into());
+        }
+    }
+
+    /// Records that the node is shutting down, until a component is registered again.
+    pub fn set_shutting_down(&self) {
+        self.inner.lock().unwrap().shutting_down = true;
+    }
+
+    /// The state of every component and the overall status of the node.
+    pub fn report(&self) -> HealthReport {
+        // The probes are run outside of the lock, since they may take their own.
+        let (shutting_down, components): (_, Vec<_>) = {
+            let inner = self.inner.lock().unwrap();
+            let components = inner
+                .components
+                .iter()
+                .map(|(name, c)| (name.clone(), c.critical, c.probe.clone(), c.down.clone()))
+                .collect();
+            (inner.shutting_down, components)
+        };
+        let components: BTreeMap<_, _> = components
+            .into_iter()
+            .map(|(name, critical, probe, down)| {
+                let state = match (&down, probe) {
+                    (Some(_), _) => ComponentState::Down,
+                    (None, Some(probe)) => probe(),
+                    (None, None) => ComponentState::Running,
+                };
+                let health = ComponentHealth {
+                    state,
+                    critical,
+                    reason: down,
+                };
+                (name, health)
+            })
+            .collect();
+
+        let any = |f: &dyn Fn(&ComponentHealth) -> bool| components.values().any(f);
+        let status = if shutting_down {
+            NodeStatus::ShuttingDown
+        } else if any(&|c| c.critical && c.state == ComponentState::Down) {
+            NodeStatus::Unhealthy
+        } else if any(&|c| c.critical && c.state == ComponentState::Starting) {
+            NodeStatus::Booting
+        } else if any(&|c| matches!(c.state, ComponentState::Degraded | ComponentState::Down)) {
+            NodeStatus::Degraded
+        } else {
+            NodeStatus::Healthy
+        };
+        HealthReport { status, components }
+    }
+}
+
+/// Aborts the task of the handle when dropped, e.g. when the supervising task is aborted.
+struct AbortOnDrop(Option<JoinHandle<()>>);
+
+impl Drop for AbortOnDrop {
+    fn drop(&mut self) {
+        if let Some(handle) = &self.0 {
+            handle.abort();
+        }
+    }
+}
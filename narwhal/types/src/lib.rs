@@ -21,4 +21,23 @@ pub use worker::*;
 
 mod serde;
 
+mod versioned;
+
 pub mod metered_channel;
+
+mod state_dump;
+pub use state_dump::StateDump;
+
+mod audit;
+pub use audit::{AuditEvent, AuditLog, AUDIT_LOG_FILE};
+
+mod health;
+pub use health::{ComponentHealth, ComponentState, HealthReport, NodeHealth, NodeStatus};
+
+mod maintenance;
+pub use maintenance::{
+    JobId, JobState, MaintenanceError, MaintenanceJob, MaintenanceKind, MaintenanceTask,
+    StorageMaintenance,
+};
+
+pub mod test_vectors;
@@ -0,0 +1,215 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Storage maintenance triggered on demand, e.g. by the operators during low-traffic windows
+//! rather than waiting for the automatic schedules. Every task runs in the background as a job,
+//! whose progress is polled by its id on the admin servers.
+//!
+//! The node registers the tasks it can run, since only it owns the stores and the pruner.
+use crate::{now, TimestampMs};
+use crypto::PublicKey;
+use futures::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+    collections::{BTreeMap, HashMap},
+    future::Future,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+use thiserror::Error;
+
+#[cfg(test)]
+#[path = "tests/maintenance_tests.rs"]
+mod maintenance_tests;
+
+/// The number of finished jobs kept for polling, the older ones being forgotten.
+const FINISHED_JOBS: usize = 64;
+
+pub type JobId = u64;
+
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MaintenanceKind {
+    Compaction,
+    Pruning,
+    Snapshot,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum MaintenanceTask {
+    /// Compacts all the tables of the stores.
+    Compaction,
+    /// Prunes the stores up to the current watermark of the pruner.
+    Pruning,
+    /// Exports a snapshot of the stores to `target`, which must not exist or be empty.
+    Snapshot { target: PathBuf },
+}
+
+impl MaintenanceTask {
+    pub fn kind(&self) -> MaintenanceKind {
+        match self {
+            Self::Compaction => MaintenanceKind::Compaction,
+            Self::Pruning => MaintenanceKind::Pruning,
+            Self::Snapshot { .. } => MaintenanceKind::Snapshot,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobState {
+    Running,
+    Succeeded,
+    Failed,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct MaintenanceJob {
+    pub id: JobId,
+    pub task: MaintenanceTask,
+    pub state: JobState,
+    pub started_at: TimestampMs,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub finished_at: Option<TimestampMs>,
+    /// What the task did, once it succeeded (e.g. the manifest of a snapshot).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Error)]
+pub enum MaintenanceError {
+    #[error("{0:?} is not available on this node")]
+    Unavailable(MaintenanceKind),
+
+    #[error("{kind:?} is already running as job {job_id}")]
+    AlreadyRunning {
+        kind: MaintenanceKind,
+        job_id: JobId,
+    },
+}
+
+type Handler =
+    Arc<dyn Fn(MaintenanceTask) -> BoxFuture<'static, Result<Value, String>> + Send + Sync>;
+
+#[derive(Default)]
+struct Inner {
+    handlers: HashMap<MaintenanceKind, Handler>,
+    jobs: BTreeMap<JobId, MaintenanceJob>,
+    next_id: JobId,
+}
+
+/// The maintenance tasks of a node and their jobs. Cloning it shares them.
+#[derive(Clone, Default)]
+pub struct StorageMaintenance {
+    inner: Arc<Mutex<Inner>>,
+}
+
+/// The maintenance of the authorities run by the process, shared by the node running the tasks
+/// and the admin servers of its primary and of its workers.
+static AUTHORITY_MAINTENANCE: Mutex<Option<HashMap<PublicKey, StorageMaintenance>>> =
+    Mutex::new(None);
+
+impl StorageMaintenance {
+    /// The maintenance of the authority `name`, across epochs.
+    pub fn of_authority(name: &PublicKey) -> Self {
+        AUTHORITY_MAINTENANCE
+            .lock()
+            .unwrap()
+            .get_or_insert_with(HashMap::new)
+            .entry(name.clone())
+            .or_default()
+            .clone()
+    }
+
+    /// Makes `handler` run the tasks of `kind`. A handler of the same kind is replaced, e.g. by
+    /// the one of the next epoch.
+    pub fn register<F, Fut>(&self, kind: MaintenanceKind, handler: F)
+    where
+        F: Fn(MaintenanceTask) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<Value, String>> + Send + 'static,
+    {
+        let handler: Handler = Arc::new(move |task| Box::pin(handler(task)));
+        self.inner.lock().unwrap().handlers.insert(kind, handler);
+    }
+
+    /// Starts a job running `task` in the background, unless a job of the same kind is running.
+    pub fn start(&self, task: MaintenanceTask) -> Result<JobId, MaintenanceError> {
+        let kind = task.kind();
+        let (id, handler) = {
+            let mut inner = self.inner.lock().unwrap();
+            let Some(handler) = inner.handlers.get(&kind).cloned() else {
+                return Err(MaintenanceError::Unavailable(kind));
+            };
+            if let Some(job) = inner
+                .jobs
+                .values()
+                .find(|job| job.task.kind() == kind && job.state == JobState::Running)
+            {
+                return Err(MaintenanceError::AlreadyRunning {
+                    kind,
+                    job_id: job.id,
+                });
+            }
+            let id = inner.next_id;
+            inner.next_id += 1;
+            let job = MaintenanceJob {
+                id,
+                task: task.clone(),
+                state: JobState::Running,
+                started_at: now(),
+                finished_at: None,
+                result: None,
+                error: None,
+            };
+            inner.jobs.insert(id, job);
+            (id, handler)
+        };
+
+        let maintenance = self.clone();
+        tokio::spawn(async move {
+            let result = handler(task).await;
+            maintenance.finish(id, result);
+        });
+        Ok(id)
+    }
+
+    fn finish(&self, id: JobId, result: Result<Value, String>) {
+        let mut inner = self.inner.lock().unwrap();
+        if let Some(job) = inner.jobs.get_mut(&id) {
+            job.finished_at = Some(now());
+            match result {
+                Ok(value) => {
+                    job.state = JobState::Succeeded;
+                    job.result = Some(value);
+                }
+                Err(e) => {
+                    job.state = JobState::Failed;
+                    job.error = Some(e);
+                }
+            }
+        }
+        let finished: Vec<_> = inner
+            .jobs
+            .values()
+            .filter(|job| job.state != JobState::Running)
+            .map(|job| job.id)
+            .collect();
+        for id in finished.iter().rev().skip(FINISHED_JOBS) {
+            inner.jobs.remove(id);
+        }
+    }
+
+    /// The job `id`, unless it is unknown or was forgotten.
+    pub fn job(&self, id: JobId) -> Option<MaintenanceJob> {
+        self.inner.lock().unwrap().jobs.get(&id).cloned()
+    }
+
+    /// The running jobs and the latest finished ones, oldest first.
+    pub fn jobs(&self) -> Vec<MaintenanceJob> {
+        self.inner.lock().unwrap().jobs.values().cloned().collect()
+    }
+}
@@ -6,8 +6,16 @@ use async_trait::async_trait;
 use std::future::Future;
 // TODO: complete tests - This kinda sorta facades the whole tokio::mpsc::{Sender, Receiver}: without tests, this will be fragile to maintain.
 use futures::{FutureExt, Stream, TryFutureExt};
-use prometheus::{IntCounter, IntGauge};
-use std::task::{Context, Poll};
+use prometheus::{
+    core::{Collector, Desc},
+    proto::MetricFamily,
+    IntCounter, IntCounterVec, IntGauge, IntGaugeVec, Opts, Registry,
+};
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+};
 use tokio::sync::mpsc::{
     self,
     error::{SendError, TryRecvError, TrySendError},
@@ -323,3 +331,92 @@ impl<T: Send> WithPermit<T> for Sender<T> {
         Some((permit, f.await))
     }
 }
+
+////////////////////////////////////////////////////////////////
+/// Labelled metrics
+////////////////////////////////////////////////////////////////
+
+/// Exports the gauges and the totals of the metered channels of a component as the
+/// `channel_depth` and `channel_received_total` metrics, labelled with the `component` and the
+/// `channel`, so that the channels can be compared in a single query. The values are read from
+/// the gauges and the counters of the channels at every scrape.
+#[derive(Clone, Debug)]
+pub struct ChannelCollector {
+    component: String,
+    descs: Vec<Desc>,
+    channels: Arc<Mutex<BTreeMap<String, (IntGauge, Option<IntCounter>)>>>,
+}
+
+impl ChannelCollector {
+    pub const NAME_DEPTH: &'static str = "channel_depth";
+    pub const NAME_TOTAL: &'static str = "channel_received_total";
+
+    /// Registers the collector of the channels of `component` in `registry`. The collectors of
+    /// several components can share a registry.
+    pub fn new(component: &str, registry: &Registry) -> Self {
+        let collector = Self::unregistered(component);
+        registry.register(Box::new(collector.clone())).unwrap();
+        collector
+    }
+
+    pub fn unregistered(component: &str) -> Self {
+        let (depth, total) = Self::vectors(component);
+        Self {
+            component: component.to_string(),
+            descs: depth
+                .desc()
+                .into_iter()
+                .chain(total.desc())
+                .cloned()
+                .collect(),
+            channels: Arc::new(Mutex::new(BTreeMap::new())),
+        }
+    }
+
+    fn vectors(component: &str) -> (IntGaugeVec, IntCounterVec) {
+        let opts =
+            |name: &str, help: &str| Opts::new(name, help).const_label("component", component);
+        (
+            IntGaugeVec::new(
+                opts(Self::NAME_DEPTH, "occupancy of the channels of a component"),
+                &["channel"],
+            )
+            .unwrap(),
+            IntCounterVec::new(
+                opts(
+                    Self::NAME_TOTAL,
+                    "total received on the channels of a component",
+                ),
+                &["channel"],
+            )
+            .unwrap(),
+        )
+    }
+
+    /// Exports the gauge of the channel `name`, and its total if it has one, replacing those
+    /// of the channel of the same name.
+    pub fn add(&self, name: &str, gauge: &IntGauge, total: Option<&IntCounter>) {
+        self.channels
+            .lock()
+            .unwrap()
+            .insert(name.to_string(), (gauge.clone(), total.cloned()));
+    }
+}
+
+impl Collector for ChannelCollector {
+    fn desc(&self) -> Vec<&Desc> {
+        self.descs.iter().collect()
+    }
+
+    fn collect(&self) -> Vec<MetricFamily> {
+        // The values are set on fresh vectors, for the concurrent scrapes not to interfere.
+        let (depth, total) = Self::vectors(&self.component);
+        for (name, (gauge, counter)) in self.channels.lock().unwrap().iter() {
+            depth.with_label_values(&[name]).set(gauge.get());
+            if let Some(counter) = counter {
+                total.with_label_values(&[name]).inc_by(counter.get());
+            }
+        }
+        depth.collect().into_iter().chain(total.collect()).collect()
+    }
+}
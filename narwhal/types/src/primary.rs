@@ -4,24 +4,35 @@
 use crate::{
     error::{DagError, DagResult},
     serde::NarwhalBitmap,
+    versioned::{self, Versioned},
     CertificateDigestProto,
 };
 use bytes::Bytes;
-use config::{Committee, Epoch, SharedWorkerCache, Stake, WorkerId, WorkerInfo};
-use crypto::{AggregateSignature, PublicKey, Signature};
+use config::{
+    Committee, CommitteeUpdate, Epoch, MessageVersion, ProtocolVersion, SharedWorkerCache, Stake,
+    WorkerCacheUpdate, WorkerId, WorkerInfo,
+};
+use crypto::{
+    batch::BatchVerifier, AggregateSignature, NetworkPublicKey, PublicKey, Signature,
+    SignatureService,
+};
 use dag::node_dag::Affiliated;
 use derive_builder::Builder;
 use fastcrypto::{
     hash::{Digest, Hash, HashFunction},
     traits::{AggregateAuthenticator, EncodeDecodeBase64, Signer, VerifyingKey},
-    SignatureService, Verifier,
+    Verifier,
 };
 use indexmap::IndexMap;
 use mysten_util_mem::MallocSizeOf;
 use once_cell::sync::OnceCell;
+use proptest::{
+    collection,
+    prelude::{any, Strategy},
+};
 use proptest_derive::Arbitrary;
 use roaring::RoaringBitmap;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use serde_with::serde_as;
 use std::time::{Duration, SystemTime};
 use std::{
@@ -81,11 +92,98 @@ impl Default for Metadata {
     }
 }
 
-pub type Transaction = Vec<u8>;
+/// A transaction, reference-counted so the batches and their copies share its bytes along the
+/// pipeline rather than copy them.
+pub type Transaction = Bytes;
+
+/// The digest identifying a transaction from its submission on, in the acknowledgment of its
+/// submission and in the APIs reporting on it.
+pub fn transaction_digest(transaction: &[u8]) -> [u8; crypto::DIGEST_LENGTH] {
+    crypto::DefaultHashFunction::digest(transaction).into()
+}
+
+/// Opaque metadata attached to a transaction by its submitter, e.g. for routing; empty if none.
+pub type TransactionMetadata = Bytes;
+
 #[derive(Clone, Serialize, Deserialize, Default, Debug, PartialEq, Eq, Arbitrary)]
+#[serde(remote = "Self")]
 pub struct Batch {
+    #[proptest(strategy = "arb_transactions()")]
     pub transactions: Vec<Transaction>,
     pub metadata: Metadata,
+    /// The metadata of each transaction, or empty if none of them has metadata.
+    #[proptest(value = "Vec::new()")]
+    #[serde(default)]
+    pub transaction_metadata: Vec<TransactionMetadata>,
+}
+
+fn arb_transactions() -> impl Strategy<Value = Vec<Transaction>> {
+    collection::vec(any::<Vec<u8>>().prop_map(Bytes::from), 0..100)
+}
+
+/// The legacy layout of a batch, before transactions carried metadata.
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "Batch")]
+struct BatchV1<T> {
+    transactions: T,
+    metadata: Metadata,
+}
+
+impl Versioned for Batch {
+    const NAME: &'static str = "Batch";
+    const LATEST_VERSION: MessageVersion = 2;
+
+    fn min_version(&self) -> MessageVersion {
+        if self.transaction_metadata.is_empty() {
+            1
+        } else {
+            2
+        }
+    }
+
+    fn serialize_version<S: Serializer>(
+        &self,
+        version: MessageVersion,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match version {
+            1 => BatchV1 {
+                transactions: &self.transactions,
+                metadata: self.metadata.clone(),
+            }
+            .serialize(serializer),
+            _ => Batch::serialize(self, serializer),
+        }
+    }
+
+    fn deserialize_version<'de, D: Deserializer<'de>>(
+        version: MessageVersion,
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        match version {
+            1 => {
+                let batch = BatchV1::<Vec<Transaction>>::deserialize(deserializer)?;
+                Ok(Batch {
+                    transactions: batch.transactions,
+                    metadata: batch.metadata,
+                    transaction_metadata: Vec::new(),
+                })
+            }
+            _ => Batch::deserialize(deserializer),
+        }
+    }
+}
+
+impl Serialize for Batch {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        versioned::serialize(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Batch {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        versioned::deserialize(deserializer)
+    }
 }
 
 impl Batch {
@@ -93,7 +191,45 @@ impl Batch {
         Batch {
             transactions,
             metadata: Metadata::default(),
+            transaction_metadata: Vec::new(),
+        }
+    }
+
+    /// A batch of transactions along their metadata, `None` if they have none.
+    pub fn with_metadata(
+        transactions: impl IntoIterator<Item = (Transaction, Option<TransactionMetadata>)>,
+    ) -> Self {
+        let (transactions, metadata): (Vec<_>, Vec<_>) = transactions.into_iter().unzip();
+        let mut batch = Self::new(transactions);
+        if metadata.iter().any(Option::is_some) {
+            batch.transaction_metadata = metadata
+                .into_iter()
+                .map(Option::unwrap_or_default)
+                .collect();
         }
+        batch
+    }
+
+    /// Whether every transaction of the batch has metadata, or none has.
+    pub fn has_valid_metadata(&self) -> bool {
+        self.transaction_metadata.is_empty()
+            || self.transaction_metadata.len() == self.transactions.len()
+    }
+
+    /// The transactions of the batch along their metadata, `None` if they have none.
+    pub fn transactions_with_metadata(
+        &self,
+    ) -> impl Iterator<Item = (&Transaction, Option<&[u8]>)> {
+        self.transactions
+            .iter()
+            .enumerate()
+            .map(|(i, transaction)| {
+                let metadata = self
+                    .transaction_metadata
+                    .get(i)
+                    .filter(|metadata| !metadata.is_empty());
+                (transaction, metadata.map(|metadata| &metadata[..]))
+            })
     }
 }
 
@@ -145,14 +281,42 @@ impl Hash<{ crypto::DIGEST_LENGTH }> for Batch {
     type TypedDigest = BatchDigest;
 
     fn digest(&self) -> Self::TypedDigest {
-        BatchDigest::new(
-            crypto::DefaultHashFunction::digest_iterator(self.transactions.iter()).into(),
+        batch_digest(
+            self.transactions.iter().map(|transaction| &transaction[..]),
+            self.transaction_metadata
+                .iter()
+                .map(|metadata| &metadata[..]),
         )
     }
 }
 
+/// Hashes the transactions of a batch and their metadata. Every sequence is hashed along its
+/// length and every element along its size, so that no two batches share a digest by moving
+/// bytes from one element to the next. The metadata is only hashed when present, so the digests
+/// of the batches without metadata are the same in both layouts.
+pub(crate) fn batch_digest<'a>(
+    transactions: impl ExactSizeIterator<Item = &'a [u8]>,
+    transaction_metadata: impl ExactSizeIterator<Item = &'a [u8]>,
+) -> BatchDigest {
+    let mut hasher = crypto::DefaultHashFunction::new();
+    hasher.update((transactions.len() as u64).to_le_bytes());
+    for transaction in transactions {
+        hasher.update((transaction.len() as u64).to_le_bytes());
+        hasher.update(transaction);
+    }
+    if transaction_metadata.len() > 0 {
+        hasher.update((transaction_metadata.len() as u64).to_le_bytes());
+        for metadata in transaction_metadata {
+            hasher.update((metadata.len() as u64).to_le_bytes());
+            hasher.update(metadata);
+        }
+    }
+    BatchDigest::new(hasher.finalize().into())
+}
+
 #[derive(Builder, Clone, Default, Deserialize, MallocSizeOf, Serialize)]
 #[builder(pattern = "owned", build_fn(skip))]
+#[serde(remote = "Self")]
 pub struct Header {
     pub author: PublicKey,
     pub round: Round,
@@ -204,6 +368,38 @@ impl HeaderBuilder {
     }
 }
 
+impl Versioned for Header {
+    const NAME: &'static str = "Header";
+    const LATEST_VERSION: MessageVersion = 1;
+
+    fn serialize_version<S: Serializer>(
+        &self,
+        _version: MessageVersion,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        Header::serialize(self, serializer)
+    }
+
+    fn deserialize_version<'de, D: Deserializer<'de>>(
+        _version: MessageVersion,
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        Header::deserialize(deserializer)
+    }
+}
+
+impl Serialize for Header {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        versioned::serialize(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Header {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        versioned::deserialize(deserializer)
+    }
+}
+
 impl Header {
     pub async fn new(
         author: PublicKey,
@@ -211,7 +407,7 @@ impl Header {
         epoch: Epoch,
         payload: IndexMap<BatchDigest, WorkerId>,
         parents: BTreeSet<CertificateDigest>,
-        signature_service: &SignatureService<Signature, { crypto::DIGEST_LENGTH }>,
+        signature_service: &SignatureService,
     ) -> Self {
         let header = Self {
             author,
@@ -237,6 +433,21 @@ impl Header {
     }
 
     pub fn verify(&self, committee: &Committee, worker_cache: SharedWorkerCache) -> DagResult<()> {
+        self.verify_without_signature(committee, worker_cache)?;
+
+        // Check the signature.
+        let digest: Digest<{ crypto::DIGEST_LENGTH }> = Digest::from(self.digest());
+        self.author
+            .verify(digest.as_ref(), &self.signature)
+            .map_err(DagError::from)
+    }
+
+    /// Verifies the header but its signature, which is left to a batch verification.
+    fn verify_without_signature(
+        &self,
+        committee: &Committee,
+        worker_cache: SharedWorkerCache,
+    ) -> DagResult<()> {
         // Ensure the header is from the correct epoch.
         ensure!(
             self.epoch == committee.epoch(),
@@ -266,12 +477,7 @@ impl Header {
                 .worker(&self.author, worker_id)
                 .map_err(|_| DagError::MalformedHeader(self.digest()))?;
         }
-
-        // Check the signature.
-        let digest: Digest<{ crypto::DIGEST_LENGTH }> = Digest::from(self.digest());
-        self.author
-            .verify(digest.as_ref(), &self.signature)
-            .map_err(DagError::from)
+        Ok(())
     }
 }
 
@@ -291,6 +497,12 @@ impl Header {
 )]
 pub struct HeaderDigest([u8; crypto::DIGEST_LENGTH]);
 
+impl AsRef<[u8]> for HeaderDigest {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
 impl From<HeaderDigest> for Digest<{ crypto::DIGEST_LENGTH }> {
     fn from(hd: HeaderDigest) -> Self {
         Digest::new(hd.0)
@@ -376,7 +588,7 @@ impl Vote {
     pub async fn new(
         header: &Header,
         author: &PublicKey,
-        signature_service: &SignatureService<Signature, { crypto::DIGEST_LENGTH }>,
+        signature_service: &SignatureService,
     ) -> Self {
         let vote = Self {
             digest: header.digest(),
@@ -412,6 +624,18 @@ impl Vote {
     }
 
     pub fn verify(&self, committee: &Committee) -> DagResult<()> {
+        self.verify_without_signature(committee)?;
+
+        // Check the signature.
+        let vote_digest: Digest<{ crypto::DIGEST_LENGTH }> = self.digest().into();
+        self.author
+            .verify(vote_digest.as_ref(), &self.signature)
+            .map_err(DagError::from)
+    }
+
+    /// Verifies the vote but its signature, for the votes whose signatures are verified in a
+    /// batch once they form a quorum.
+    pub fn verify_without_signature(&self, committee: &Committee) -> DagResult<()> {
         // Ensure the header is from the correct epoch.
         ensure!(
             self.epoch == committee.epoch(),
@@ -426,12 +650,7 @@ impl Vote {
             committee.stake(&self.author) > 0,
             DagError::UnknownAuthority(self.author.encode_base64())
         );
-
-        // Check the signature.
-        let vote_digest: Digest<{ crypto::DIGEST_LENGTH }> = self.digest().into();
-        self.author
-            .verify(vote_digest.as_ref(), &self.signature)
-            .map_err(DagError::from)
+        Ok(())
     }
 }
 #[derive(
@@ -496,6 +715,7 @@ impl PartialEq for Vote {
 
 #[serde_as]
 #[derive(Clone, Serialize, Deserialize, Default, MallocSizeOf)]
+#[serde(remote = "Self")]
 pub struct Certificate {
     pub header: Header,
     aggregated_signature: AggregateSignature,
@@ -504,6 +724,38 @@ pub struct Certificate {
     pub metadata: Metadata,
 }
 
+impl Versioned for Certificate {
+    const NAME: &'static str = "Certificate";
+    const LATEST_VERSION: MessageVersion = 1;
+
+    fn serialize_version<S: Serializer>(
+        &self,
+        _version: MessageVersion,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        Certificate::serialize(self, serializer)
+    }
+
+    fn deserialize_version<'de, D: Deserializer<'de>>(
+        _version: MessageVersion,
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        Certificate::deserialize(deserializer)
+    }
+}
+
+impl Serialize for Certificate {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        versioned::serialize(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Certificate {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        versioned::deserialize(deserializer)
+    }
+}
+
 impl Certificate {
     pub fn genesis(committee: &Committee) -> Vec<Self> {
         committee
@@ -589,7 +841,7 @@ impl Certificate {
 
         // Ensure that the authorities have enough weight
         ensure!(
-            !check_stake || weight >= committee.quorum_threshold(),
+            !check_stake || committee.reached_quorum(weight),
             DagError::CertificateRequiresQuorum
         );
 
@@ -641,6 +893,73 @@ impl Certificate {
     }
 
     pub fn verify(&self, committee: &Committee, worker_cache: SharedWorkerCache) -> DagResult<()> {
+        let Some(pks) = self.verify_without_signatures(committee, worker_cache.clone())? else {
+            return Ok(());
+        };
+
+        // Check the signature of the embedded header.
+        let header_digest: Digest<{ crypto::DIGEST_LENGTH }> = Digest::from(self.header.digest());
+        self.header
+            .author
+            .verify(header_digest.as_ref(), &self.header.signature)
+            .map_err(DagError::from)?;
+
+        // Verify the signatures
+        let certificate_digest: Digest<{ crypto::DIGEST_LENGTH }> = Digest::from(self.digest());
+        self.aggregated_signature
+            .verify(&pks[..], certificate_digest.as_ref())
+            .map_err(|_| signature::Error::new())
+            .map_err(DagError::from)?;
+
+        Ok(())
+    }
+
+    /// Verifies `certificates`, checking the signatures of their headers and their aggregated
+    /// signatures in a single batch. When the batch fails, the error is the one of the first
+    /// invalid certificate.
+    pub fn verify_batch(
+        certificates: &[Certificate],
+        committee: &Committee,
+        worker_cache: SharedWorkerCache,
+    ) -> DagResult<()> {
+        let mut batch = BatchVerifier::new();
+        // The certificate of every signature of the batch.
+        let mut signed = Vec::new();
+        for (i, certificate) in certificates.iter().enumerate() {
+            let Some(pks) = certificate.verify_without_signatures(committee, worker_cache.clone())? else {
+                continue;
+            };
+            let header_digest: Digest<{ crypto::DIGEST_LENGTH }> =
+                Digest::from(certificate.header.digest());
+            batch.add_signature(
+                certificate.header.author.clone(),
+                header_digest.as_ref(),
+                &certificate.header.signature,
+            );
+            let certificate_digest: Digest<{ crypto::DIGEST_LENGTH }> =
+                Digest::from(certificate.digest());
+            batch.add_aggregate_signature(
+                pks,
+                certificate_digest.as_ref(),
+                &certificate.aggregated_signature,
+            );
+            signed.extend([i, i]);
+        }
+
+        match batch.verify() {
+            Ok(()) => Ok(()),
+            // Report the error of the first invalid certificate.
+            Err(invalid) => certificates[signed[invalid[0]]].verify(committee, worker_cache),
+        }
+    }
+
+    /// Verifies the certificate but its signatures, returning the keys of its signers, or `None`
+    /// for the genesis certificates, which are not signed.
+    fn verify_without_signatures(
+        &self,
+        committee: &Committee,
+        worker_cache: SharedWorkerCache,
+    ) -> DagResult<Option<Vec<PublicKey>>> {
         // Ensure the header is from the correct epoch.
         ensure!(
             self.epoch() == committee.epoch(),
@@ -652,27 +971,20 @@ impl Certificate {
 
         // Genesis certificates are always valid.
         if Self::genesis(committee).contains(self) {
-            return Ok(());
+            return Ok(None);
         }
 
         // Check the embedded header.
-        self.header.verify(committee, worker_cache)?;
+        self.header
+            .verify_without_signature(committee, worker_cache)?;
 
         let (weight, pks) = self.signed_by(committee);
 
         ensure!(
-            weight >= committee.quorum_threshold(),
+            committee.reached_quorum(weight),
             DagError::CertificateRequiresQuorum
         );
-
-        // Verify the signatures
-        let certificate_digest: Digest<{ crypto::DIGEST_LENGTH }> = Digest::from(self.digest());
-        self.aggregated_signature
-            .verify(&pks[..], certificate_digest.as_ref())
-            .map_err(|_| signature::Error::new())
-            .map_err(DagError::from)?;
-
-        Ok(())
+        Ok(Some(pks))
     }
 
     pub fn round(&self) -> Round {
@@ -686,6 +998,11 @@ impl Certificate {
     pub fn origin(&self) -> PublicKey {
         self.header.author.clone()
     }
+
+    /// The aggregated signature of the signers of the certificate over its digest.
+    pub fn aggregated_signature(&self) -> &AggregateSignature {
+        &self.aggregated_signature
+    }
 }
 
 #[derive(
@@ -856,9 +1173,10 @@ impl FetchCertificatesRequest {
             .filter_map(|(k, serialized)| {
                 match RoaringBitmap::deserialize_from(&mut &serialized[..]) {
                     Ok(bitmap) => {
+                        // The rounds past the last one are sent by faulty peers only.
                         let rounds: BTreeSet<Round> = bitmap
                             .into_iter()
-                            .map(|r| self.exclusive_lower_bound + r as Round)
+                            .filter_map(|r| self.exclusive_lower_bound.checked_add(r as Round))
                             .collect();
                         Some((k.clone(), rounds))
                     }
@@ -938,6 +1256,27 @@ pub enum ReconfigureNotification {
     Shutdown,
 }
 
+/// Changes to apply on top of the current committee and worker cache. This is a lighter
+/// alternative to a `ReconfigureNotification` carrying a complete new committee, for the common
+/// case where only a few authorities join or leave. This message must be sent by a trusted source.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ReconfigureUpdate {
+    /// The changes to the committee. Its epoch decides whether this is a new epoch or an
+    /// in-place update of the current one.
+    pub committee: CommitteeUpdate,
+    /// The changes to the worker cache.
+    pub worker_cache: WorkerCacheUpdate,
+}
+
+/// Reconfiguration requests accepted by the primary (e.g. through its admin server).
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum ReconfigureRequest {
+    /// Reconfigure with a complete committee (or shut down).
+    Notification(ReconfigureNotification),
+    /// Reconfigure by applying a set of changes to the current committee and worker cache.
+    Update(ReconfigureUpdate),
+}
+
 /// Used by the primary to reconfigure the worker.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct WorkerReconfigureMessage {
@@ -1022,6 +1361,89 @@ pub struct WorkerInfoResponse {
     pub workers: BTreeMap<WorkerId, WorkerInfo>,
 }
 
+/// What a node (primary or worker) advertises to its peers when connecting to them, to check they
+/// can talk to each other.
+#[derive(Clone, Serialize, Deserialize, Eq, PartialEq, Debug)]
+pub struct HandshakeMessage {
+    /// The chain the node belongs to.
+    pub chain_id: String,
+    /// The epoch of the committee of the node.
+    pub epoch: Epoch,
+    /// The protocol version run by the node in its epoch.
+    pub protocol_version: ProtocolVersion,
+    /// The range of protocol versions the node can run.
+    pub min_supported_version: ProtocolVersion,
+    pub max_supported_version: ProtocolVersion,
+}
+
+/// The announcement by an authority, signed with its protocol key, that its primary now uses the
+/// network key `network_key`. The peers accept it within the epoch: they authenticate the primary
+/// of the authority with its new key without waiting for a reconfiguration.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct NetworkKeyAnnouncement {
+    pub authority: PublicKey,
+    pub epoch: Epoch,
+    /// Increases with every rotation of the authority within the epoch, so that its previous
+    /// announcements can not be replayed.
+    pub sequence: u64,
+    pub network_key: NetworkPublicKey,
+    pub signature: Signature,
+}
+
+impl NetworkKeyAnnouncement {
+    pub async fn new(
+        authority: PublicKey,
+        epoch: Epoch,
+        sequence: u64,
+        network_key: NetworkPublicKey,
+        signature_service: &SignatureService,
+    ) -> Self {
+        let announcement = Self {
+            authority,
+            epoch,
+            sequence,
+            network_key,
+            signature: Signature::default(),
+        };
+        let signature = signature_service
+            .request_signature(announcement.digest())
+            .await;
+        Self {
+            signature,
+            ..announcement
+        }
+    }
+
+    /// The digest signed by the authority.
+    pub fn digest(&self) -> Digest<{ crypto::DIGEST_LENGTH }> {
+        let mut hasher = crypto::DefaultHashFunction::default();
+        // Domain separation from the digests of the headers and votes.
+        hasher.update(b"NetworkKeyAnnouncement");
+        hasher.update(&self.authority);
+        hasher.update(self.epoch.to_le_bytes());
+        hasher.update(self.sequence.to_le_bytes());
+        hasher.update(&self.network_key);
+        hasher.finalize()
+    }
+
+    pub fn verify(&self, committee: &Committee) -> DagResult<()> {
+        ensure!(
+            self.epoch == committee.epoch(),
+            DagError::InvalidEpoch {
+                expected: committee.epoch(),
+                received: self.epoch
+            }
+        );
+        ensure!(
+            committee.stake(&self.authority) > 0,
+            DagError::UnknownAuthority(self.authority.encode_base64())
+        );
+        self.authority
+            .verify(self.digest().as_ref(), &self.signature)
+            .map_err(DagError::from)
+    }
+}
+
 #[derive(Clone, Serialize, Deserialize, Eq, PartialEq, Debug)]
 pub struct VoteInfo {
     /// The latest Epoch for which a vote was sent to given authority
@@ -1040,7 +1462,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_elapsed() {
-        let batch = Batch::new(vec![]);
+        let batch = Batch::new(Vec::new());
         assert!(batch.metadata.created_at > 0);
 
         sleep(Duration::from_secs(2)).await;
@@ -1055,6 +1477,7 @@ mod tests {
             metadata: Metadata {
                 created_at: 2999309726980, // something in the future - Fri Jan 16 2065 05:35:26
             },
+            transaction_metadata: vec![],
         };
 
         assert_eq!(batch.metadata.created_at.elapsed().as_secs_f64(), 0.0);
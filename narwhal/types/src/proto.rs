@@ -8,6 +8,8 @@ mod narwhal {
     include!(concat!(env!("OUT_DIR"), "/narwhal.PrimaryToWorker.rs"));
     include!(concat!(env!("OUT_DIR"), "/narwhal.WorkerToPrimary.rs"));
     include!(concat!(env!("OUT_DIR"), "/narwhal.WorkerToWorker.rs"));
+    include!(concat!(env!("OUT_DIR"), "/narwhal.Handshake.rs"));
+    include!(concat!(env!("OUT_DIR"), "/narwhal.NetworkKeys.rs"));
 }
 
 use std::{array::TryFromSliceError, ops::Deref};
@@ -15,12 +17,18 @@ use std::{array::TryFromSliceError, ops::Deref};
 use crate::{BlockError, BlockErrorKind, CertificateDigest, Transaction};
 use bytes::Bytes;
 use crypto::PublicKey;
+use prost::Message;
+use tonic::{Code, Status};
 
 pub use narwhal::{
     collection_error::CollectionErrorType,
     collection_retrieval_result::RetrievalResult,
     configuration_client::ConfigurationClient,
     configuration_server::{Configuration, ConfigurationServer},
+    handshake_client::HandshakeClient,
+    handshake_server::{Handshake, HandshakeServer},
+    network_keys_client::NetworkKeysClient,
+    network_keys_server::{NetworkKeys, NetworkKeysServer},
     primary_to_primary_client::PrimaryToPrimaryClient,
     primary_to_primary_server::{MockPrimaryToPrimary, PrimaryToPrimary, PrimaryToPrimaryServer},
     primary_to_worker_client::PrimaryToWorkerClient,
@@ -36,11 +44,12 @@ pub use narwhal::{
     worker_to_worker_client::WorkerToWorkerClient,
     worker_to_worker_server::{MockWorkerToWorker, WorkerToWorker, WorkerToWorkerServer},
     CertificateDigest as CertificateDigestProto, Collection, CollectionError,
-    CollectionRetrievalResult, Empty, GetCollectionsRequest, GetCollectionsResponse,
-    GetPrimaryAddressResponse, MultiAddr as MultiAddrProto, NewEpochRequest, NewNetworkInfoRequest,
-    NodeReadCausalRequest, NodeReadCausalResponse, PublicKey as PublicKeyProto, ReadCausalRequest,
-    ReadCausalResponse, RemoveCollectionsRequest, RoundsRequest, RoundsResponse,
-    Transaction as TransactionProto, ValidatorData,
+    CollectionRetrievalResult, Empty, ErrorCode, ErrorDetails, ErrorLimit, GetCollectionsRequest,
+    GetCollectionsResponse, GetPrimaryAddressResponse, MultiAddr as MultiAddrProto,
+    NewEpochRequest, NewNetworkInfoRequest, NodeReadCausalRequest, NodeReadCausalResponse,
+    PublicKey as PublicKeyProto, ReadCausalRequest, ReadCausalResponse, RemoveCollectionsRequest,
+    RoundsRequest, RoundsResponse, SubmitTransactionResponse, Transaction as TransactionProto,
+    ValidatorData,
 };
 
 impl From<PublicKey> for PublicKeyProto {
@@ -54,14 +63,15 @@ impl From<PublicKey> for PublicKeyProto {
 impl From<Transaction> for TransactionProto {
     fn from(transaction: Transaction) -> Self {
         TransactionProto {
-            transaction: Bytes::from(transaction),
+            transaction,
+            metadata: Bytes::new(),
         }
     }
 }
 
 impl From<TransactionProto> for Transaction {
     fn from(transaction: TransactionProto) -> Self {
-        transaction.transaction.to_vec()
+        transaction.transaction
     }
 }
 
@@ -91,3 +101,67 @@ impl TryFrom<CertificateDigestProto> for CertificateDigest {
         Ok(CertificateDigest::new(digest.digest.deref().try_into()?))
     }
 }
+
+impl ErrorCode {
+    /// A status of the code, with its default details.
+    pub fn status(self, message: impl Into<String>) -> Status {
+        ErrorDetails::new(self).status(message)
+    }
+
+    fn grpc_code(self) -> Code {
+        match self {
+            ErrorCode::Unspecified => Code::Unknown,
+            ErrorCode::InvalidArgument | ErrorCode::InvalidTransaction => Code::InvalidArgument,
+            ErrorCode::TransactionTooLarge => Code::ResourceExhausted,
+            ErrorCode::Unsupported => Code::FailedPrecondition,
+            ErrorCode::ShuttingDown | ErrorCode::NotIncluded => Code::Unavailable,
+            ErrorCode::Timeout => Code::DeadlineExceeded,
+            ErrorCode::Internal => Code::Internal,
+        }
+    }
+
+    /// Whether the requests failing with the code may succeed if retried.
+    fn retryable(self) -> bool {
+        matches!(
+            self,
+            ErrorCode::ShuttingDown | ErrorCode::NotIncluded | ErrorCode::Timeout
+        )
+    }
+}
+
+impl ErrorDetails {
+    pub fn new(code: ErrorCode) -> Self {
+        Self {
+            code: code.into(),
+            retryable: code.retryable(),
+            limits: Vec::new(),
+        }
+    }
+
+    /// Adds a limit the request exceeded.
+    pub fn with_limit(mut self, name: &str, limit: u64, actual: u64) -> Self {
+        self.limits.push(ErrorLimit {
+            name: name.to_string(),
+            limit,
+            actual,
+        });
+        self
+    }
+
+    /// A status carrying the details.
+    pub fn status(self, message: impl Into<String>) -> Status {
+        Status::with_details(
+            self.code().grpc_code(),
+            message,
+            Bytes::from(self.encode_to_vec()),
+        )
+    }
+
+    /// The details of a status, if it carries any.
+    pub fn from_status(status: &Status) -> Option<Self> {
+        if status.details().is_empty() {
+            return None;
+        }
+        Self::decode(status.details()).ok()
+    }
+}
@@ -0,0 +1,55 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A snapshot of the internal state of a node, for diagnosing a node which stopped making
+//! progress. The components register sections computed when the snapshot is taken, typically
+//! from the gauges they already maintain, so that taking no snapshot costs nothing.
+use serde_json::{Map, Value};
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex},
+};
+
+#[cfg(test)]
+#[path = "tests/state_dump_tests.rs"]
+mod state_dump_tests;
+
+type Section = Arc<dyn Fn() -> Value + Send + Sync>;
+
+/// The sections of the state dump of a node, by name. The node creates it and passes it to its
+/// primary and its executor; cloning it shares the sections.
+#[derive(Clone, Default)]
+pub struct StateDump {
+    sections: Arc<Mutex<BTreeMap<String, Section>>>,
+}
+
+impl StateDump {
+    /// Adds the section `name`, computed by `section` at every snapshot. A section of the same
+    /// name is replaced, e.g. by the component of the next epoch.
+    pub fn register<F>(&self, name: impl Into<String>, section: F)
+    where
+        F: Fn() -> Value + Send + Sync + 'static,
+    {
+        self.sections
+            .lock()
+            .unwrap()
+            .insert(name.into(), Arc::new(section));
+    }
+
+    /// The sections of the dump as a JSON object.
+    pub fn snapshot(&self) -> Value {
+        // The sections are computed outside of the lock, since they may take their own.
+        let sections: Vec<_> = self
+            .sections
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(name, section)| (name.clone(), section.clone()))
+            .collect();
+        let snapshot: Map<_, _> = sections
+            .into_iter()
+            .map(|(name, section)| (name, section()))
+            .collect();
+        Value::Object(snapshot)
+    }
+}
@@ -0,0 +1,311 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Canonical test vectors of the wire format of batches, headers and certificates, for the
+//! clients and light verifiers implemented outside of this repository.
+//!
+//! The messages are encoded with bincode: integers are little-endian and of fixed size, while
+//! strings, byte strings and sequences are prefixed by their length as a `u64`. Each message is
+//! the sequence of the fields of its layout, in order. The layout 1 is written without prefix, as
+//! the releases before versioning did, while the newer layouts are wrapped in an envelope (see
+//! `crate::versioned`): they are prefixed by `u64::MAX`, which no length of the first field of the
+//! layout 1 can be, then by their version as a `u32`. The layouts are:
+//! * a batch is `transactions: [bytes]` and `created_at: u64` and, from version 2,
+//!   `transaction_metadata: [bytes]`, either empty or holding the metadata of each transaction;
+//! * a header is `author: string` (the base64 encoding of the public key), `round: u64`,
+//!   `epoch: u64`, `created_at: u64`, `payload: [([u8; 32], u32)]` (the digests of the batches
+//!   and the ids of their workers), `parents: [[u8; 32]]` (sorted) and `signature: bytes`;
+//! * a certificate is its header, then `aggregated_signature: option<bytes>`,
+//!   `signed_authorities: bytes`, a [roaring bitmap](https://github.com/RoaringBitmap/RoaringFormatSpec)
+//!   of the indexes of the signers in the committee, and `created_at: u64`.
+//!
+//! The digests are Blake2b-256 hashes:
+//! * of a batch, over the number of its transactions as a `u64`, then each transaction prefixed by
+//!   its length as a `u64`, then, if any, the metadata of the transactions the same way;
+//! * of a header, over the bytes of the public key of its author, `round`, `epoch` and
+//!   `created_at`, then each digest of its payload followed by the worker id as a `u32`, then
+//!   its parents;
+//! * of a certificate, over the digest of its header, `round`, `epoch` and the bytes of the
+//!   public key of the author of the header.
+//!
+//! Headers are signed by their author, and certificates by a quorum of the committee, over their
+//! digest with BLS12-381 (minimal signature size), the signatures of a certificate being
+//! aggregated.
+use crate::{
+    versioned::{Envelope, Versioned},
+    Batch, Certificate, HeaderBuilder, Round, TimestampMs, Vote,
+};
+use bytes::Bytes;
+use config::{
+    Authority, Committee, Epoch, MessageVersion, ProtocolConfig, QuorumPolicy, Stake, WorkerId,
+};
+use crypto::{KeyPair, NetworkKeyPair};
+use fastcrypto::{
+    encoding::{Encoding, Hex},
+    hash::{Digest, Hash},
+    traits::{KeyPair as _, ToFromBytes},
+};
+use serde::{Deserialize, Serialize};
+
+#[cfg(test)]
+#[path = "tests/test_vectors_tests.rs"]
+mod test_vectors_tests;
+
+/// The private keys of the committee signing the vectors. They are written out, rather than
+/// generated from a seed, so that the vectors don't depend on the random number generator.
+const PRIVATE_KEYS: [&str; 4] = [
+    "0e6517f6aea165ed9f6314e71d6d48a9b3059c25f8165ab3862a02f33133ed3d",
+    "0d395fab4453bac7ea3822d6d2f8e3d5a497ed73e3d1a8cb785b44f4ff18de18",
+    "03dd15bcbff8ef7fa263d53686e31ac78507b46ae45e27de4490e6607f21c68f",
+    "0d1bbf78a4ec6f86e1f2b7d028cf05e1846902569bfe2957da5806850c509205",
+];
+
+/// The private network keys of the committee, in the order of the committee.
+const NETWORK_PRIVATE_KEYS: [&str; 4] = [
+    "718450c27e412afa4cc7f2e4f13edc628e8be3f847c98bbea99fe351e7f2299c",
+    "384661f7436ebe9236df8b23ecdc112e6fd5e37c48f569d7c691de2badb13779",
+    "1966467c7b948b3741c36157789f9b8024ce4e4caf503c488fa5b883d70c7856",
+    "073e64626cef3f9f27646ae059e19c85c81c002b3626ee4d71c92fb76723f035",
+];
+
+/// The creation time of all the messages of the vectors.
+const CREATED_AT: TimestampMs = 1666205365890;
+
+/// The test vectors, all the byte strings being hex encoded.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TestVectors {
+    /// The committee signing the headers and certificates, in the order of its public keys (the
+    /// indexes of the signed authorities of the certificates).
+    pub committee: Vec<AuthorityVector>,
+    pub batches: Vec<BatchVector>,
+    /// The headers of the first round, including the batches above in their payload.
+    pub headers: Vec<HeaderVector>,
+    /// The certificates of the headers above.
+    pub certificates: Vec<CertificateVector>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuthorityVector {
+    /// The private key, for the implementations testing their signatures.
+    pub private_key: String,
+    pub public_key: String,
+    pub stake: Stake,
+}
+
+/// A message encoded with the layout of a given version.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EncodingVector {
+    pub version: MessageVersion,
+    pub bytes: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BatchVector {
+    pub transactions: Vec<String>,
+    pub transaction_metadata: Vec<String>,
+    pub created_at: TimestampMs,
+    pub digest: String,
+    /// The encodings of the batch with each layout able to represent it.
+    pub encodings: Vec<EncodingVector>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HeaderVector {
+    pub author: String,
+    pub round: Round,
+    pub epoch: Epoch,
+    pub created_at: TimestampMs,
+    pub payload: Vec<(String, WorkerId)>,
+    pub parents: Vec<String>,
+    pub digest: String,
+    pub signature: String,
+    pub encodings: Vec<EncodingVector>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CertificateVector {
+    /// The digest of the header of the certificate.
+    pub header: String,
+    /// The public keys of the signers, in the order of the committee.
+    pub signers: Vec<String>,
+    pub created_at: TimestampMs,
+    pub digest: String,
+    pub aggregated_signature: String,
+    pub encodings: Vec<EncodingVector>,
+}
+
+/// The committee signing the vectors.
+pub fn committee() -> Committee {
+    committee_and_keys().0
+}
+
+/// The committee signing the vectors, and the keys of its authorities in the order of the
+/// committee.
+fn committee_and_keys() -> (Committee, Vec<KeyPair>) {
+    let mut keys: Vec<KeyPair> = PRIVATE_KEYS.iter().map(|key| key_pair(key)).collect();
+    keys.sort_by(|a, b| a.public().cmp(b.public()));
+    let committee = Committee {
+        epoch: Epoch::default(),
+        authorities: keys
+            .iter()
+            .zip(NETWORK_PRIVATE_KEYS)
+            .enumerate()
+            .map(|(i, (key, network_key))| {
+                let network_key: NetworkKeyPair = key_pair(network_key);
+                let authority = Authority {
+                    stake: 1,
+                    primary_address: format!("/ip4/127.0.0.1/udp/{}", 100 + i).parse().unwrap(),
+                    network_key: network_key.public().clone(),
+                };
+                (key.public().clone(), authority)
+            })
+            .collect(),
+        quorum_policy: QuorumPolicy::default(),
+        protocol_config: ProtocolConfig::default(),
+    };
+    (committee, keys)
+}
+
+/// Generates the test vectors. They are deterministic, so they only change along the wire format.
+pub fn test_vectors() -> TestVectors {
+    let (committee, keys) = committee_and_keys();
+
+    let mut batches = vec![
+        Batch::new(Vec::new()),
+        Batch::new(vec![
+            Bytes::from_static(b"transaction 1"),
+            Bytes::from_static(b"transaction 2"),
+        ]),
+        Batch::with_metadata([
+            (
+                Bytes::from_static(b"transaction 3"),
+                Some(Bytes::from_static(b"metadata 3")),
+            ),
+            (Bytes::from_static(b"transaction 4"), None),
+        ]),
+    ];
+    for batch in &mut batches {
+        batch.metadata.created_at = CREATED_AT;
+    }
+
+    let genesis: Vec<_> = Certificate::genesis(&committee)
+        .iter()
+        .map(|certificate| certificate.digest())
+        .collect();
+    let payloads = [vec![(0, 0), (1, 0)], vec![(2, 1)]];
+    let headers: Vec<_> = keys
+        .iter()
+        .zip(payloads)
+        .map(|(key, payload)| {
+            HeaderBuilder::default()
+                .author(key.public().clone())
+                .round(1)
+                .epoch(committee.epoch())
+                .created_at(CREATED_AT)
+                .payload(
+                    payload
+                        .into_iter()
+                        .map(|(batch, worker_id)| (batches[batch].digest(), worker_id))
+                        .collect(),
+                )
+                .parents(genesis.iter().cloned().collect())
+                .build(key)
+                .unwrap()
+        })
+        .collect();
+
+    // Each header is certified by the first authorities reaching a quorum.
+    let quorum = committee.quorum_threshold() as usize;
+    let certificates: Vec<_> = headers
+        .iter()
+        .map(|header| {
+            let votes = keys[..quorum]
+                .iter()
+                .map(|key| {
+                    let vote = Vote::new_with_signer(header, key.public(), key);
+                    (vote.author, vote.signature)
+                })
+                .collect();
+            let mut certificate = Certificate::new(&committee, header.clone(), votes).unwrap();
+            certificate.metadata.created_at = CREATED_AT;
+            certificate
+        })
+        .collect();
+
+    TestVectors {
+        committee: keys
+            .iter()
+            .map(|key| AuthorityVector {
+                private_key: Hex::encode(key.copy().private()),
+                public_key: Hex::encode(key.public()),
+                stake: committee.stake(key.public()),
+            })
+            .collect(),
+        batches: batches
+            .iter()
+            .map(|batch| BatchVector {
+                transactions: batch.transactions.iter().map(Hex::encode).collect(),
+                transaction_metadata: batch.transaction_metadata.iter().map(Hex::encode).collect(),
+                created_at: batch.metadata.created_at,
+                digest: hex(batch.digest()),
+                encodings: encodings(batch),
+            })
+            .collect(),
+        headers: headers
+            .iter()
+            .map(|header| HeaderVector {
+                author: Hex::encode(&header.author),
+                round: header.round,
+                epoch: header.epoch,
+                created_at: header.created_at,
+                payload: header
+                    .payload
+                    .iter()
+                    .map(|(digest, worker_id)| (hex(*digest), *worker_id))
+                    .collect(),
+                parents: header.parents.iter().map(|parent| hex(*parent)).collect(),
+                digest: hex(header.digest()),
+                signature: Hex::encode(&header.signature),
+                encodings: encodings(header),
+            })
+            .collect(),
+        certificates: certificates
+            .iter()
+            .map(|certificate| CertificateVector {
+                header: hex(certificate.header.digest()),
+                signers: certificate
+                    .signed_authorities(&committee)
+                    .iter()
+                    .map(Hex::encode)
+                    .collect(),
+                created_at: certificate.metadata.created_at,
+                digest: hex(certificate.digest()),
+                aggregated_signature: Hex::encode(certificate.aggregated_signature()),
+                encodings: encodings(certificate),
+            })
+            .collect(),
+    }
+}
+
+/// The encodings of `value` with each of the layouts able to represent it.
+fn encodings<T: Versioned + Serialize>(value: &T) -> Vec<EncodingVector> {
+    (1..=T::LATEST_VERSION)
+        .filter_map(|version| {
+            let bytes = bincode::serialize(&Envelope { value, version }).ok()?;
+            Some(EncodingVector {
+                version,
+                bytes: Hex::encode(bytes),
+            })
+        })
+        .collect()
+}
+
+/// The key pair of the hex encoded `private_key`.
+fn key_pair<K: fastcrypto::traits::KeyPair>(private_key: &str) -> K {
+    let bytes = Hex::decode(private_key).unwrap();
+    K::from(K::PrivKey::from_bytes(&bytes).unwrap())
+}
+
+fn hex(digest: impl Into<Digest<{ crypto::DIGEST_LENGTH }>>) -> String {
+    Hex::encode(digest.into())
+}
@@ -0,0 +1,92 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{AuditEvent, AuditLog, Header, AUDIT_LOG_FILE};
+use config::AuditLogParameters;
+use crypto::KeyPair;
+use fastcrypto::traits::KeyPair as _;
+use rand::{rngs::StdRng, SeedableRng};
+use std::fs;
+
+fn read_lines(path: &std::path::Path) -> Vec<serde_json::Value> {
+    fs::read_to_string(path)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect()
+}
+
+#[test]
+fn records_events_as_json_lines() {
+    let dir = test_utils::temp_dir();
+    let name = KeyPair::generate(&mut StdRng::from_seed([1; 32]))
+        .public()
+        .clone();
+    let parameters = AuditLogParameters {
+        dir: Some(dir.clone()),
+        ..AuditLogParameters::default()
+    };
+    let log = AuditLog::open(&name, &parameters);
+    assert!(log.is_enabled());
+
+    let header = Header {
+        round: 3,
+        ..Header::default()
+    };
+    log.record(AuditEvent::header_proposed(&header));
+    // The components of the authority share the log.
+    log.clone().record(AuditEvent::vote_sent(&header));
+
+    let lines = read_lines(&dir.join(AUDIT_LOG_FILE));
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0]["event"], "header_proposed");
+    assert_eq!(lines[0]["round"], 3);
+    assert_eq!(lines[0]["batches"], 0);
+    assert_eq!(lines[1]["event"], "vote_sent");
+    assert_eq!(lines[0]["authority"], lines[1]["authority"]);
+    assert_eq!(lines[0]["header_digest"], lines[1]["header_digest"]);
+    assert!(lines[0]["time_ms"].as_u64().is_some());
+}
+
+#[test]
+fn rotates_files() {
+    let dir = test_utils::temp_dir();
+    let name = KeyPair::generate(&mut StdRng::from_seed([2; 32]))
+        .public()
+        .clone();
+    let parameters = AuditLogParameters {
+        dir: Some(dir.clone()),
+        max_file_size: 1,
+        max_files: 2,
+    };
+    let log = AuditLog::open(&name, &parameters);
+    for epoch in 0..4 {
+        log.record(AuditEvent::Reconfigured {
+            epoch,
+            notification: "NewEpoch".to_string(),
+        });
+    }
+
+    // Every file holds a single line, the oldest one having been deleted.
+    let epochs = |file: &str| -> Vec<_> {
+        read_lines(&dir.join(file))
+            .iter()
+            .map(|line| line["epoch"].as_u64().unwrap())
+            .collect()
+    };
+    assert_eq!(epochs(AUDIT_LOG_FILE), vec![3]);
+    assert_eq!(epochs(&format!("{AUDIT_LOG_FILE}.1")), vec![2]);
+    assert_eq!(epochs(&format!("{AUDIT_LOG_FILE}.2")), vec![1]);
+    assert!(!dir.join(format!("{AUDIT_LOG_FILE}.3")).exists());
+}
+
+#[test]
+fn disabled_without_directory() {
+    let name = KeyPair::generate(&mut StdRng::from_seed([3; 32]))
+        .public()
+        .clone();
+    let log = AuditLog::open(&name, &AuditLogParameters::default());
+    assert!(!log.is_enabled());
+    // Recording is a no-op.
+    log.record(AuditEvent::header_proposed(&Header::default()));
+}
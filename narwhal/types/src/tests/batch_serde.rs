@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{serialized_batch_digest, Batch, Metadata, WorkerBatchMessage};
+use bytes::Bytes;
 use fastcrypto::{
     encoding::{Encoding, Hex},
     hash::Hash,
@@ -11,13 +12,14 @@ use serde_test::{assert_tokens, Token};
 
 #[test]
 fn test_serde_batch() {
-    let tx = || vec![1; 5];
+    let tx = || Bytes::from(vec![1; 5]);
 
     let batch = Batch {
         transactions: (0..2).map(|_| tx()).collect(),
         metadata: Metadata {
             created_at: 1666205365890,
         },
+        transaction_metadata: vec![],
     };
 
     assert_tokens(
@@ -29,20 +31,8 @@ fn test_serde_batch() {
             },
             Token::Str("transactions"),
             Token::Seq { len: Some(2) },
-            Token::Seq { len: Some(5) },
-            Token::U8(1),
-            Token::U8(1),
-            Token::U8(1),
-            Token::U8(1),
-            Token::U8(1),
-            Token::SeqEnd,
-            Token::Seq { len: Some(5) },
-            Token::U8(1),
-            Token::U8(1),
-            Token::U8(1),
-            Token::U8(1),
-            Token::U8(1),
-            Token::SeqEnd,
+            Token::Bytes(&[1; 5]),
+            Token::Bytes(&[1; 5]),
             Token::SeqEnd,
             Token::Str("metadata"),
             Token::Struct {
@@ -59,13 +49,14 @@ fn test_serde_batch() {
 
 #[test]
 fn test_bincode_serde_batch() {
-    let tx = || vec![1; 5];
+    let tx = || Bytes::from(vec![1; 5]);
 
     let txes = Batch {
         transactions: (0..2).map(|_| tx()).collect(),
         metadata: Metadata {
             created_at: 1666205365890,
         },
+        transaction_metadata: vec![],
     };
 
     let txes_bytes = bincode::serialize(&txes).unwrap();
@@ -90,7 +81,7 @@ fn test_bincode_serde_batch() {
 
 #[test]
 fn test_bincode_serde_batch_message() {
-    let tx = || vec![1; 5];
+    let tx = || Bytes::from(vec![1; 5]);
 
     let txes = WorkerBatchMessage {
         batch: Batch {
@@ -98,6 +89,7 @@ fn test_bincode_serde_batch_message() {
             metadata: Metadata {
                 created_at: 1666205365890,
             },
+            transaction_metadata: vec![],
         },
     };
 
@@ -118,6 +110,31 @@ fn test_bincode_serde_batch_message() {
     );
 }
 
+#[test]
+fn test_batch_digest_separates_elements() {
+    let batch = |transactions: &[&'static [u8]], transaction_metadata: &[&'static [u8]]| Batch {
+        transactions: transactions.iter().map(|tx| Bytes::from(*tx)).collect(),
+        metadata: Metadata::default(),
+        transaction_metadata: transaction_metadata
+            .iter()
+            .map(|metadata| Bytes::from(*metadata))
+            .collect(),
+    };
+
+    // The same bytes, split differently between the transactions and their metadata.
+    let digests = [
+        batch(&[b"ab", b"c"], &[]),
+        batch(&[b"a", b"bc"], &[]),
+        batch(&[b"abc"], &[]),
+        batch(&[b"a", b"b"], &[b"c", b""]),
+        batch(&[b"a", b"b"], &[b"", b"c"]),
+    ]
+    .map(|batch| batch.digest());
+    for (i, digest) in digests.iter().enumerate() {
+        assert!(!digests[i + 1..].contains(digest), "{digests:?}");
+    }
+}
+
 proptest::proptest! {
 
     #[test]
@@ -0,0 +1,88 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{Batch, BatchProvenance, Certificate, CommittedSubDag, ConsensusOutput, Header};
+use bytes::Bytes;
+use fastcrypto::hash::Hash;
+use std::sync::Arc;
+
+#[test]
+fn test_output_provenance() {
+    let mut batches: Vec<_> = (0..3)
+        .map(|i| Batch::new(vec![Bytes::from(vec![i])]))
+        .collect();
+    batches[1] = Batch::with_metadata([(Bytes::from(vec![1]), Some(Bytes::from(vec![7])))]);
+    let certificate = |round, payload: &[(usize, u32)]| {
+        let mut certificate = Certificate::default();
+        certificate.header = Header {
+            round,
+            payload: payload
+                .iter()
+                .map(|(i, worker_id)| (batches[*i].digest(), *worker_id))
+                .collect(),
+            ..Header::default()
+        };
+        certificate
+    };
+    let (first, second) = (certificate(1, &[(0, 0), (1, 1)]), certificate(2, &[(2, 0)]));
+    let sub_dag = CommittedSubDag {
+        certificates: vec![first.clone(), second.clone()],
+        leader: second.clone(),
+        sub_dag_index: 1,
+    };
+
+    let output = ConsensusOutput::new(
+        Arc::new(sub_dag),
+        vec![
+            (first.clone(), batches[..2].to_vec()),
+            (second.clone(), batches[2..].to_vec()),
+        ],
+    );
+    assert_eq!(output.leader, second.digest());
+    assert_eq!(output.round, 2);
+    let provenance: Vec<_> = output
+        .batches_with_provenance()
+        .map(|(provenance, batch)| {
+            assert_eq!(provenance.digest, batch.digest());
+            provenance.clone()
+        })
+        .collect();
+    assert_eq!(
+        provenance,
+        vec![
+            BatchProvenance {
+                author: first.header.author.clone(),
+                worker_id: 0,
+                certificate: first.digest(),
+                digest: batches[0].digest(),
+            },
+            BatchProvenance {
+                author: first.header.author.clone(),
+                worker_id: 1,
+                certificate: first.digest(),
+                digest: batches[1].digest(),
+            },
+            BatchProvenance {
+                author: second.header.author.clone(),
+                worker_id: 0,
+                certificate: second.digest(),
+                digest: batches[2].digest(),
+            },
+        ]
+    );
+    assert_eq!(
+        output
+            .transactions()
+            .map(|(provenance, transaction, metadata)| (
+                provenance.worker_id,
+                transaction.to_vec(),
+                metadata.map(<[u8]>::to_vec)
+            ))
+            .collect::<Vec<_>>(),
+        vec![
+            (0, vec![0], None),
+            (1, vec![1], Some(vec![7])),
+            (0, vec![2], None)
+        ]
+    );
+}
@@ -0,0 +1,94 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{ComponentState, NodeHealth, NodeStatus};
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+use tokio::sync::oneshot;
+
+#[test]
+fn status_aggregates_components() {
+    let health = NodeHealth::default();
+    assert_eq!(health.report().status, NodeStatus::Healthy);
+
+    let connected = Arc::new(AtomicBool::new(false));
+    let probe = connected.clone();
+    health.register("primary", true, move || {
+        if probe.load(Ordering::Relaxed) {
+            ComponentState::Running
+        } else {
+            ComponentState::Starting
+        }
+    });
+    health.register("worker-0", false, || ComponentState::Running);
+    let report = health.report();
+    assert_eq!(report.status, NodeStatus::Booting);
+    assert!(report.status.is_live());
+    assert!(!report.status.is_ready());
+
+    connected.store(true, Ordering::Relaxed);
+    assert_eq!(health.report().status, NodeStatus::Healthy);
+
+    // A non-critical component down degrades the node.
+    health.mark_down("worker-0", "a task exited");
+    let report = health.report();
+    assert_eq!(report.status, NodeStatus::Degraded);
+    assert!(report.status.is_ready());
+    assert_eq!(report.components["worker-0"].state, ComponentState::Down);
+    assert_eq!(
+        report.components["worker-0"].reason.as_deref(),
+        Some("a task exited")
+    );
+
+    // A critical one makes it unhealthy.
+    health.mark_down("primary", "a task panicked");
+    let report = health.report();
+    assert_eq!(report.status, NodeStatus::Unhealthy);
+    assert!(!report.status.is_live());
+
+    health.set_shutting_down();
+    assert_eq!(health.report().status, NodeStatus::ShuttingDown);
+
+    // Registering the components again, as at the next epoch, ends the shutdown.
+    health.register("primary", true, || ComponentState::Running);
+    health.register("worker-0", false, || ComponentState::Running);
+    assert_eq!(health.report().status, NodeStatus::Healthy);
+}
+
+#[tokio::test]
+async fn supervised_tasks_mark_their_component_down() {
+    let health = NodeHealth::default();
+    let (tx_exit, rx_exit) = oneshot::channel::<()>();
+    let (tx_dropped, rx_dropped) = oneshot::channel::<()>();
+    let mut handles = health.supervise(
+        "consensus",
+        true,
+        vec![
+            tokio::spawn(async move {
+                let _ = rx_exit.await;
+            }),
+            tokio::spawn(async move {
+                let _tx_dropped = tx_dropped;
+                std::future::pending::<()>().await
+            }),
+        ],
+    );
+    assert_eq!(health.report().status, NodeStatus::Healthy);
+
+    tx_exit.send(()).unwrap();
+    handles.remove(0).await.unwrap();
+    let report = health.report();
+    assert_eq!(report.status, NodeStatus::Unhealthy);
+    assert_eq!(
+        report.components["consensus"].reason.as_deref(),
+        Some("a task exited")
+    );
+
+    // Aborting the supervising task aborts the supervised one.
+    let handle = handles.remove(0);
+    handle.abort();
+    assert!(handle.await.unwrap_err().is_cancelled());
+    assert!(rx_dropped.await.is_err());
+}
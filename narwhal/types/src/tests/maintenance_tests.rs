@@ -0,0 +1,105 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    JobState, MaintenanceError, MaintenanceJob, MaintenanceKind, MaintenanceTask,
+    StorageMaintenance,
+};
+use serde_json::json;
+use std::{
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+use tokio::sync::oneshot;
+
+/// Polls the job `id` until it is finished.
+async fn finished(maintenance: &StorageMaintenance, id: u64) -> MaintenanceJob {
+    loop {
+        let job = maintenance.job(id).unwrap();
+        if job.state != JobState::Running {
+            return job;
+        }
+        tokio::task::yield_now().await;
+    }
+}
+
+#[tokio::test]
+async fn jobs_run_the_registered_tasks() {
+    let maintenance = StorageMaintenance::default();
+    assert_eq!(
+        maintenance.start(MaintenanceTask::Compaction),
+        Err(MaintenanceError::Unavailable(MaintenanceKind::Compaction))
+    );
+
+    // A compaction waiting to be released.
+    let release = Arc::new(Mutex::new(None));
+    let rx_release = release.clone();
+    maintenance.register(MaintenanceKind::Compaction, move |_task| {
+        let (tx, rx) = oneshot::channel::<()>();
+        *rx_release.lock().unwrap() = Some(tx);
+        async move {
+            let _ = rx.await;
+            Ok(json!({ "tables": 2 }))
+        }
+    });
+    maintenance.register(MaintenanceKind::Snapshot, |task| async move {
+        match task {
+            MaintenanceTask::Snapshot { target } => {
+                Err(format!("{} is not empty", target.display()))
+            }
+            _ => unreachable!(),
+        }
+    });
+
+    let id = maintenance.start(MaintenanceTask::Compaction).unwrap();
+    assert_eq!(maintenance.job(id).unwrap().state, JobState::Running);
+    // A second compaction can't start while the first one runs.
+    assert_eq!(
+        maintenance.start(MaintenanceTask::Compaction),
+        Err(MaintenanceError::AlreadyRunning {
+            kind: MaintenanceKind::Compaction,
+            job_id: id
+        })
+    );
+
+    // The other tasks can.
+    let snapshot = MaintenanceTask::Snapshot {
+        target: PathBuf::from("/snapshot"),
+    };
+    let snapshot_id = maintenance.start(snapshot.clone()).unwrap();
+    let job = finished(&maintenance, snapshot_id).await;
+    assert_eq!(job.task, snapshot);
+    assert_eq!(job.state, JobState::Failed);
+    assert_eq!(job.error.as_deref(), Some("/snapshot is not empty"));
+    assert!(job.result.is_none());
+
+    // Wait for the compaction to take its release.
+    while release.lock().unwrap().is_none() {
+        tokio::task::yield_now().await;
+    }
+    release.lock().unwrap().take().unwrap().send(()).unwrap();
+    let job = finished(&maintenance, id).await;
+    assert_eq!(job.state, JobState::Succeeded);
+    assert_eq!(job.result, Some(json!({ "tables": 2 })));
+    assert!(job.finished_at.unwrap() >= job.started_at);
+
+    assert_eq!(maintenance.jobs().len(), 2);
+    assert!(maintenance.job(snapshot_id + 1).is_none());
+}
+
+#[tokio::test]
+async fn old_jobs_are_forgotten() {
+    let maintenance = StorageMaintenance::default();
+    maintenance.register(MaintenanceKind::Pruning, |_task| async { Ok(json!({})) });
+
+    let mut ids = Vec::new();
+    for _ in 0..70 {
+        let id = maintenance.start(MaintenanceTask::Pruning).unwrap();
+        finished(&maintenance, id).await;
+        ids.push(id);
+    }
+    let jobs = maintenance.jobs();
+    assert_eq!(jobs.len(), 64);
+    assert_eq!(jobs[0].id, ids[6]);
+    assert!(maintenance.job(ids[5]).is_none());
+}
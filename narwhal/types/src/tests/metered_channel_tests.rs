@@ -1,13 +1,13 @@
 // Copyright (c) 2021, Facebook, Inc. and its affiliates
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
-use super::{channel, channel_with_total};
+use super::{channel, channel_with_total, ChannelCollector};
 use futures::{
     executor::block_on,
     task::{noop_waker, Context, Poll},
     FutureExt,
 };
-use prometheus::{IntCounter, IntGauge};
+use prometheus::{IntCounter, IntGauge, Registry};
 use tokio::sync::mpsc::error::TrySendError;
 
 #[test]
@@ -201,3 +201,62 @@ fn test_try_send_full() {
     assert_eq!(received_item, item);
     assert_eq!(counter.get(), 0);
 }
+
+#[test]
+fn test_collector() {
+    let registry = Registry::new();
+    let primary = ChannelCollector::new("primary", &registry);
+    let executor = ChannelCollector::new("executor", &registry);
+
+    let counter = IntGauge::new("tx_a", "test").unwrap();
+    let counter_total = IntCounter::new("tx_a_total", "test_total").unwrap();
+    let (tx, mut rx) = channel_with_total(8, &counter, &counter_total);
+    primary.add("tx_a", &counter, Some(&counter_total));
+    let other_counter = IntGauge::new("tx_b", "test").unwrap();
+    let (other_tx, _other_rx) = channel(8, &other_counter);
+    executor.add("tx_b", &other_counter, None);
+
+    block_on(tx.send(1)).unwrap();
+    block_on(tx.send(2)).unwrap();
+    block_on(rx.recv()).unwrap();
+    block_on(other_tx.send(3)).unwrap();
+
+    let families = registry.gather();
+    let values = |name: &str| -> Vec<(String, String, f64)> {
+        let family = families.iter().find(|f| f.get_name() == name).unwrap();
+        family
+            .get_metric()
+            .iter()
+            .map(|metric| {
+                let label = |n: &str| {
+                    metric
+                        .get_label()
+                        .iter()
+                        .find(|l| l.get_name() == n)
+                        .unwrap()
+                        .get_value()
+                        .to_string()
+                };
+                let value = if metric.has_gauge() {
+                    metric.get_gauge().get_value()
+                } else {
+                    metric.get_counter().get_value()
+                };
+                (label("component"), label("channel"), value)
+            })
+            .collect()
+    };
+    let mut depths = values(ChannelCollector::NAME_DEPTH);
+    depths.sort_by(|a, b| a.0.cmp(&b.0));
+    assert_eq!(
+        depths,
+        vec![
+            ("executor".to_string(), "tx_b".to_string(), 1.0),
+            ("primary".to_string(), "tx_a".to_string(), 1.0),
+        ]
+    );
+    assert_eq!(
+        values(ChannelCollector::NAME_TOTAL),
+        vec![("primary".to_string(), "tx_a".to_string(), 1.0)]
+    );
+}
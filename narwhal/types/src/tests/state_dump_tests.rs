@@ -0,0 +1,35 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::StateDump;
+use serde_json::json;
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+
+#[test]
+fn sections_are_computed_at_every_snapshot() {
+    let dump = StateDump::default();
+    assert_eq!(dump.snapshot(), json!({}));
+
+    let round = Arc::new(AtomicU64::new(1));
+    let current = round.clone();
+    dump.register(
+        "proposer",
+        move || json!({ "round": current.load(Ordering::Relaxed) }),
+    );
+    dump.clone()
+        .register("channels", || json!({ "tx_headers": 0 }));
+    assert_eq!(
+        dump.snapshot(),
+        json!({ "channels": { "tx_headers": 0 }, "proposer": { "round": 1 } })
+    );
+
+    round.store(2, Ordering::Relaxed);
+    assert_eq!(dump.snapshot()["proposer"], json!({ "round": 2 }));
+
+    // A section of the same name is replaced.
+    dump.register("proposer", || json!(null));
+    assert_eq!(dump.snapshot()["proposer"], json!(null));
+}
@@ -0,0 +1,218 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use crate::{serialized_batch_digest, Header};
+use crypto::{AggregateSignature, PublicKey, Signature};
+use fastcrypto::traits::{AggregateAuthenticator, EncodeDecodeBase64, ToFromBytes, VerifyingKey};
+use roaring::RoaringBitmap;
+
+// The second batch of the vectors in both layouts, and its digest, as computed independently.
+const BATCH_V1: &str = "02000000000000000d000000000000007472616e73616374696f6e20310d000000000000007472616e73616374696f6e2032823694f183010000";
+const BATCH_V2: &str = "ffffffffffffffff0200000002000000000000000d000000000000007472616e73616374696f6e20310d000000000000007472616e73616374696f6e2032823694f1830100000000000000000000";
+const BATCH_DIGEST: &str = "37ac7c650cca6d7fe5c3384eb0fbc857bc64fadad68577fde66798a4ce62735d";
+
+fn decode(hex: &str) -> Vec<u8> {
+    Hex::decode(hex).unwrap()
+}
+
+fn public_key(hex: &str) -> PublicKey {
+    PublicKey::from_bytes(&decode(hex)).unwrap()
+}
+
+// Appends a byte string prefixed by its length.
+fn put_bytes(bytes: &mut Vec<u8>, value: &[u8]) {
+    bytes.extend((value.len() as u64).to_le_bytes());
+    bytes.extend(value);
+}
+
+#[test]
+fn test_vectors_are_deterministic() {
+    assert_eq!(test_vectors(), test_vectors());
+}
+
+#[test]
+fn test_pinned_batch() {
+    let vector = &test_vectors().batches[1];
+    assert_eq!(vector.digest, BATCH_DIGEST);
+    assert_eq!(
+        vector.encodings,
+        vec![
+            EncodingVector {
+                version: 1,
+                bytes: BATCH_V1.to_string()
+            },
+            EncodingVector {
+                version: 2,
+                bytes: BATCH_V2.to_string()
+            }
+        ]
+    );
+}
+
+#[test]
+fn test_batch_vectors() {
+    let vectors = test_vectors();
+    for vector in &vectors.batches {
+        for encoding in &vector.encodings {
+            let bytes = decode(&encoding.bytes);
+            let batch: Batch = bincode::deserialize(&bytes).unwrap();
+            assert_eq!(
+                batch
+                    .transactions
+                    .iter()
+                    .map(Hex::encode)
+                    .collect::<Vec<_>>(),
+                vector.transactions
+            );
+            assert_eq!(
+                batch
+                    .transaction_metadata
+                    .iter()
+                    .map(Hex::encode)
+                    .collect::<Vec<_>>(),
+                vector.transaction_metadata
+            );
+            assert_eq!(batch.metadata.created_at, vector.created_at);
+            assert_eq!(hex(batch.digest()), vector.digest);
+            assert_eq!(hex(serialized_batch_digest(&bytes).unwrap()), vector.digest);
+
+            let value = &batch;
+            let version = encoding.version;
+            assert_eq!(
+                bincode::serialize(&Envelope { value, version }).unwrap(),
+                bytes
+            );
+        }
+    }
+
+    // The batch with transaction metadata has no encoding in the first layout.
+    let versions = |vector: &BatchVector| {
+        vector
+            .encodings
+            .iter()
+            .map(|encoding| encoding.version)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(versions(&vectors.batches[1]), vec![1, 2]);
+    assert_eq!(versions(&vectors.batches[2]), vec![2]);
+}
+
+#[test]
+fn test_header_vectors() {
+    for vector in test_vectors().headers {
+        let author = public_key(&vector.author);
+        let signature = Signature::from_bytes(&decode(&vector.signature)).unwrap();
+        author.verify(&decode(&vector.digest), &signature).unwrap();
+
+        // The layout described in the specification.
+        let mut layout = Vec::new();
+        put_bytes(&mut layout, author.encode_base64().as_bytes());
+        for value in [vector.round, vector.epoch, vector.created_at] {
+            layout.extend(value.to_le_bytes());
+        }
+        layout.extend((vector.payload.len() as u64).to_le_bytes());
+        for (digest, worker_id) in &vector.payload {
+            layout.extend(decode(digest));
+            layout.extend(worker_id.to_le_bytes());
+        }
+        layout.extend((vector.parents.len() as u64).to_le_bytes());
+        for parent in &vector.parents {
+            layout.extend(decode(parent));
+        }
+        put_bytes(&mut layout, &decode(&vector.signature));
+
+        for encoding in &vector.encodings {
+            let bytes = decode(&encoding.bytes);
+            assert_eq!(bytes, layout);
+
+            let header: Header = bincode::deserialize(&bytes).unwrap();
+            assert_eq!(header.author, author);
+            assert_eq!(header.round, vector.round);
+            assert_eq!(header.epoch, vector.epoch);
+            assert_eq!(header.created_at, vector.created_at);
+            assert_eq!(
+                header
+                    .payload
+                    .iter()
+                    .map(|(digest, worker_id)| (hex(*digest), *worker_id))
+                    .collect::<Vec<_>>(),
+                vector.payload
+            );
+            assert_eq!(
+                header
+                    .parents
+                    .iter()
+                    .map(|parent| hex(*parent))
+                    .collect::<Vec<_>>(),
+                vector.parents
+            );
+            assert_eq!(header.signature, signature);
+            assert_eq!(hex(header.digest()), vector.digest);
+
+            let value = &header;
+            let version = encoding.version;
+            assert_eq!(
+                bincode::serialize(&Envelope { value, version }).unwrap(),
+                bytes
+            );
+        }
+    }
+}
+
+#[test]
+fn test_certificate_vectors() {
+    let vectors = test_vectors();
+    for vector in &vectors.certificates {
+        let header = vectors
+            .headers
+            .iter()
+            .find(|header| header.digest == vector.header)
+            .unwrap();
+        let signers: Vec<_> = vector.signers.iter().map(|key| public_key(key)).collect();
+        let aggregated_signature =
+            AggregateSignature::from_bytes(&decode(&vector.aggregated_signature)).unwrap();
+        aggregated_signature
+            .verify(&signers, &decode(&vector.digest))
+            .unwrap();
+
+        // The layout described in the specification.
+        let mut layout = decode(&header.encodings[0].bytes);
+        layout.push(1);
+        put_bytes(&mut layout, &decode(&vector.aggregated_signature));
+        let signed_authorities =
+            RoaringBitmap::from_sorted_iter(vector.signers.iter().map(|key| {
+                vectors
+                    .committee
+                    .iter()
+                    .position(|authority| &authority.public_key == key)
+                    .unwrap() as u32
+            }))
+            .unwrap();
+        let mut bitmap = Vec::new();
+        signed_authorities.serialize_into(&mut bitmap).unwrap();
+        put_bytes(&mut layout, &bitmap);
+        layout.extend(vector.created_at.to_le_bytes());
+
+        for encoding in &vector.encodings {
+            let bytes = decode(&encoding.bytes);
+            assert_eq!(bytes, layout);
+
+            let certificate: Certificate = bincode::deserialize(&bytes).unwrap();
+            assert_eq!(hex(certificate.header.digest()), vector.header);
+            assert_eq!(certificate.metadata.created_at, vector.created_at);
+            certificate
+                .aggregated_signature()
+                .verify(&signers, &decode(&vector.digest))
+                .unwrap();
+            assert_eq!(hex(certificate.digest()), vector.digest);
+
+            let value = &certificate;
+            let version = encoding.version;
+            assert_eq!(
+                bincode::serialize(&Envelope { value, version }).unwrap(),
+                bytes
+            );
+        }
+    }
+}
@@ -0,0 +1,145 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    serialized_batch_digest,
+    versioned::{Envelope, ENVELOPE_MARKER},
+    Batch, Certificate, DigestError, Header, Metadata,
+};
+use bytes::Bytes;
+use fastcrypto::{
+    encoding::{Encoding, Hex},
+    hash::Hash,
+};
+
+// A batch of two transactions of five bytes, as recorded from the releases before versioning.
+const BATCH_LEGACY: &str =
+    "02000000000000000500000000000000010101010105000000000000000101010101823694f183010000";
+
+// The same batch, as recorded in JSON from the releases before versioning.
+const BATCH_LEGACY_JSON: &str =
+    r#"{"transactions":[[1,1,1,1,1],[1,1,1,1,1]],"metadata":{"created_at":1666205365890}}"#;
+
+fn batch() -> Batch {
+    Batch {
+        transactions: vec![Bytes::from(vec![1; 5]); 2],
+        metadata: Metadata {
+            created_at: 1666205365890,
+        },
+        transaction_metadata: vec![],
+    }
+}
+
+// Wraps a layout in the envelope of `version`.
+fn envelope(version: u32, layout: &[u8]) -> Vec<u8> {
+    let mut bytes = ENVELOPE_MARKER.to_le_bytes().to_vec();
+    bytes.extend(version.to_le_bytes());
+    bytes.extend(layout);
+    bytes
+}
+
+#[test]
+fn test_read_and_write_legacy_batch() {
+    let bytes = Hex::decode(BATCH_LEGACY).unwrap();
+    let batch: Batch = bincode::deserialize(&bytes).unwrap();
+    assert_eq!(batch, self::batch());
+    assert_eq!(serialized_batch_digest(&bytes).unwrap(), batch.digest());
+    // The batches without transaction metadata are still written for the nodes not upgraded yet.
+    assert_eq!(bincode::serialize(&batch).unwrap(), bytes);
+
+    let batch: Batch = serde_json::from_str(BATCH_LEGACY_JSON).unwrap();
+    assert_eq!(batch, self::batch());
+}
+
+#[test]
+fn test_unknown_version_rejected() {
+    // Same batch, in the envelope of the next version.
+    let bytes = envelope(3, &Hex::decode(BATCH_LEGACY).unwrap());
+    assert!(bincode::deserialize::<Batch>(&bytes).is_err());
+    assert!(matches!(
+        serialized_batch_digest(&bytes),
+        Err(DigestError::UnsupportedVersion(3))
+    ));
+}
+
+#[test]
+fn test_batch_with_transaction_metadata() {
+    let batch = Batch::with_metadata([
+        (Bytes::from(vec![1; 5]), Some(Bytes::from(vec![7; 2]))),
+        (Bytes::from(vec![2; 5]), None),
+    ]);
+    assert_eq!(
+        batch.transactions_with_metadata().collect::<Vec<_>>(),
+        vec![
+            (&batch.transactions[0], Some(&[7u8; 2][..])),
+            (&batch.transactions[1], None)
+        ]
+    );
+    // The metadata is part of the digest.
+    assert_ne!(
+        batch.digest(),
+        Batch::new(batch.transactions.clone()).digest()
+    );
+
+    // Only the second layout represents the metadata, so the batch is written in its envelope.
+    let bytes = bincode::serialize(&batch).unwrap();
+    assert_eq!(bytes[..12], envelope(2, &[])[..]);
+    assert_eq!(bincode::deserialize::<Batch>(&bytes).unwrap(), batch);
+    assert_eq!(serialized_batch_digest(&bytes).unwrap(), batch.digest());
+    let json = serde_json::to_string(&batch).unwrap();
+    assert_eq!(serde_json::from_str::<Batch>(&json).unwrap(), batch);
+
+    // The legacy layout cannot drop the metadata.
+    assert!(bincode::serialize(&Envelope {
+        value: &batch,
+        version: 1,
+    })
+    .is_err());
+    // And the batches without metadata keep their digest in the second layout.
+    let bytes = bincode::serialize(&Envelope {
+        value: &self::batch(),
+        version: 2,
+    })
+    .unwrap();
+    assert_eq!(
+        bincode::deserialize::<Batch>(&bytes).unwrap(),
+        self::batch()
+    );
+    assert_eq!(
+        serialized_batch_digest(&bytes).unwrap(),
+        self::batch().digest()
+    );
+}
+
+#[test]
+fn test_write_latest_known_version() {
+    // Committees may select a layout this build does not know of yet only if every authority
+    // runs a build knowing it, still this build writes the latest layout it knows.
+    let header = Header::default();
+    let bytes = bincode::serialize(&Envelope {
+        value: &header,
+        version: 2,
+    })
+    .unwrap();
+    assert_eq!(bytes, bincode::serialize(&header).unwrap());
+}
+
+#[test]
+fn test_legacy_header_and_certificate() {
+    // Both start with the length of the base64 encoding of the author of the header, and not an
+    // envelope (see the test vectors for their full layout).
+    let header = Header::default();
+    let bytes = bincode::serialize(&header).unwrap();
+    let author = bincode::serialize(&header.author).unwrap();
+    assert_eq!(bytes[..author.len()], author[..]);
+    let read: Header = bincode::deserialize(&bytes).unwrap();
+    assert_eq!(read.digest(), header.digest());
+    assert_eq!(bincode::serialize(&read).unwrap(), bytes);
+
+    let certificate = Certificate::default();
+    let bytes = bincode::serialize(&certificate).unwrap();
+    assert_eq!(bytes[..author.len()], author[..]);
+    let read: Certificate = bincode::deserialize(&bytes).unwrap();
+    assert_eq!(read.digest(), certificate.digest());
+    assert_eq!(bincode::serialize(&read).unwrap(), bytes);
+}
@@ -0,0 +1,346 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Versioned serialization of the core types the nodes exchange and store: headers, certificates
+//! and batches, so the layout of their fields can change without breaking the nodes still running
+//! the previous release, nor the values already in the stores.
+//!
+//! The layout 1 of each type is its legacy, untagged layout, i.e. the one of the releases before
+//! versioning, which is read and written unchanged. A value is written with the oldest layout
+//! able to represent it, so a newer layout is only written for the values using its additions:
+//! e.g. the batches carrying the metadata of their transactions, which the workers only accept
+//! once the protocol config of their committee selects the batch layout supporting them (see
+//! [`config::ProtocolConfig::message_version`]), i.e. once all the authorities upgraded.
+//!
+//! In the binary encoding (bincode), a value written with a newer layout is wrapped in an
+//! envelope: `ENVELOPE_MARKER` as a `u64`, the version as a `u32`, then the fields of the
+//! layout. All the legacy layouts start with the length of a string or a sequence, which cannot be
+//! `ENVELOPE_MARKER`, so both are told apart when reading. The human-readable encodings (e.g.
+//! JSON) are not wrapped: the latest layout of a type reads the older ones, as the fields it adds
+//! have defaults.
+//!
+//! A new layout is added as a new branch of the `Versioned` implementation of the type, along a
+//! new `LATEST_VERSION`. As the envelope of the first field of a legacy layout (e.g. the header of
+//! a certificate) would be taken for the one of the value, a type must write a newer layout of its
+//! own whenever its first field does.
+use config::MessageVersion;
+use serde::{
+    de, forward_to_deserialize_any,
+    ser::{self, SerializeTuple},
+    Deserializer, Serialize, Serializer,
+};
+use std::{fmt, marker::PhantomData};
+
+#[cfg(test)]
+#[path = "tests/versioned_tests.rs"]
+mod versioned_tests;
+
+/// Starts the envelope of the values written with a layout newer than the legacy one.
+pub(crate) const ENVELOPE_MARKER: u64 = u64::MAX;
+
+/// A type serialized with versioned layouts.
+pub(crate) trait Versioned: Sized {
+    /// The name of the type, for the errors.
+    const NAME: &'static str;
+    /// The latest version of the layout of the type.
+    const LATEST_VERSION: MessageVersion;
+
+    /// The oldest version of the layouts able to represent the value.
+    fn min_version(&self) -> MessageVersion {
+        1
+    }
+
+    /// Serializes the fields of the value with the layout of `version`.
+    fn serialize_version<S: Serializer>(
+        &self,
+        version: MessageVersion,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>;
+
+    /// Deserializes a value with the layout of `version`.
+    fn deserialize_version<'de, D: Deserializer<'de>>(
+        version: MessageVersion,
+        deserializer: D,
+    ) -> Result<Self, D::Error>;
+}
+
+/// Serializes `value` with the oldest layout able to represent it.
+pub(crate) fn serialize<T: Versioned, S: Serializer>(
+    value: &T,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    Envelope {
+        value,
+        version: value.min_version(),
+    }
+    .serialize(serializer)
+}
+
+/// A value serialized with the layout of a given version, or the latest layout of the type if it
+/// is older.
+pub(crate) struct Envelope<'a, T> {
+    pub value: &'a T,
+    pub version: MessageVersion,
+}
+
+impl<T: Versioned> Serialize for Envelope<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let version = self.version.clamp(1, T::LATEST_VERSION);
+        if version < self.value.min_version() {
+            return Err(ser::Error::custom(format!(
+                "the layout {version} of {} cannot represent the value",
+                T::NAME
+            )));
+        }
+        if version == 1 || serializer.is_human_readable() {
+            return self.value.serialize_version(version, serializer);
+        }
+        let mut envelope = serializer.serialize_tuple(3)?;
+        envelope.serialize_element(&ENVELOPE_MARKER)?;
+        envelope.serialize_element(&version)?;
+        envelope.serialize_element(&Layout {
+            value: self.value,
+            version,
+        })?;
+        envelope.end()
+    }
+}
+
+/// Deserializes a value with any of the layouts of the type.
+pub(crate) fn deserialize<'de, T: Versioned, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<T, D::Error> {
+    if deserializer.is_human_readable() {
+        return T::deserialize_version(T::LATEST_VERSION, deserializer);
+    }
+    // In bincode the fields of the layouts follow each other, as the elements of a tuple.
+    deserializer.deserialize_tuple(usize::MAX, EnvelopeVisitor(PhantomData))
+}
+
+struct Layout<'a, T> {
+    value: &'a T,
+    version: MessageVersion,
+}
+
+impl<T: Versioned> Serialize for Layout<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.value.serialize_version(self.version, serializer)
+    }
+}
+
+struct EnvelopeVisitor<T>(PhantomData<T>);
+
+impl<'de, T: Versioned> de::Visitor<'de> for EnvelopeVisitor<T> {
+    type Value = T;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", T::NAME)
+    }
+
+    fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<T, A::Error> {
+        let prefix: u64 = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        if prefix != ENVELOPE_MARKER {
+            // The value has the legacy layout, and the prefix is the length of its first field.
+            return T::deserialize_version(
+                1,
+                Resume {
+                    seq: &mut seq,
+                    len: prefix,
+                },
+            );
+        }
+        let version: MessageVersion = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+        if !(2..=T::LATEST_VERSION).contains(&version) {
+            return Err(de::Error::custom(format!(
+                "unsupported version {version} of {}",
+                T::NAME
+            )));
+        }
+        seq.next_element_seed(LayoutSeed(version, PhantomData))?
+            .ok_or_else(|| de::Error::invalid_length(2, &self))
+    }
+}
+
+struct LayoutSeed<T>(MessageVersion, PhantomData<T>);
+
+impl<'de, T: Versioned> de::DeserializeSeed<'de> for LayoutSeed<T> {
+    type Value = T;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<T, D::Error> {
+        T::deserialize_version(self.0, deserializer)
+    }
+}
+
+/// Deserializes the rest of a value of which the length of the first field, the leading bytes
+/// of its encoding, was already read from `seq`. The elements of `seq` are the bytes, or values,
+/// following it.
+struct Resume<'a, A> {
+    seq: &'a mut A,
+    len: u64,
+}
+
+impl<'a, 'de, A: de::SeqAccess<'de>> Resume<'a, A> {
+    fn bytes(self) -> Result<Vec<u8>, A::Error> {
+        let mut bytes = Vec::new();
+        for _ in 0..self.len {
+            let byte = self
+                .seq
+                .next_element()?
+                .ok_or_else(|| de::Error::custom("unexpected end of the value"))?;
+            bytes.push(byte);
+        }
+        Ok(bytes)
+    }
+
+    fn fields(self, fields: usize) -> Fields<'a, A> {
+        Fields {
+            seq: self.seq,
+            first: Some(self.len),
+            remaining: fields,
+        }
+    }
+}
+
+impl<'a, 'de, A: de::SeqAccess<'de>> Deserializer<'de> for Resume<'a, A> {
+    type Error = A::Error;
+
+    fn deserialize_any<V: de::Visitor<'de>>(self, _visitor: V) -> Result<V::Value, A::Error> {
+        Err(de::Error::custom(
+            "the legacy layout does not start with a length",
+        ))
+    }
+
+    // The length itself, read again when the first field is a versioned value.
+    fn deserialize_u64<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, A::Error> {
+        visitor.visit_u64(self.len)
+    }
+
+    fn deserialize_seq<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, A::Error> {
+        visitor.visit_seq(Elements {
+            seq: self.seq,
+            remaining: self.len,
+        })
+    }
+
+    fn deserialize_str<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, A::Error> {
+        self.deserialize_string(visitor)
+    }
+
+    fn deserialize_string<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, A::Error> {
+        visitor.visit_string(String::from_utf8(self.bytes()?).map_err(de::Error::custom)?)
+    }
+
+    fn deserialize_bytes<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, A::Error> {
+        self.deserialize_byte_buf(visitor)
+    }
+
+    fn deserialize_byte_buf<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, A::Error> {
+        visitor.visit_byte_buf(self.bytes()?)
+    }
+
+    fn deserialize_newtype_struct<V: de::Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, A::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_tuple<V: de::Visitor<'de>>(
+        self,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, A::Error> {
+        visitor.visit_seq(self.fields(len))
+    }
+
+    fn deserialize_tuple_struct<V: de::Visitor<'de>>(
+        self,
+        _name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, A::Error> {
+        visitor.visit_seq(self.fields(len))
+    }
+
+    fn deserialize_struct<V: de::Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, A::Error> {
+        visitor.visit_seq(self.fields(fields.len()))
+    }
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u128 f32 f64 char option unit unit_struct map
+        enum identifier ignored_any
+    }
+}
+
+/// The fields of a struct or a tuple, the first of which starts with the length already read.
+struct Fields<'a, A> {
+    seq: &'a mut A,
+    first: Option<u64>,
+    remaining: usize,
+}
+
+impl<'de, A: de::SeqAccess<'de>> de::SeqAccess<'de> for Fields<'_, A> {
+    type Error = A::Error;
+
+    fn next_element_seed<T: de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, A::Error> {
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+        self.remaining -= 1;
+        match self.first.take() {
+            Some(len) => seed
+                .deserialize(Resume {
+                    seq: &mut *self.seq,
+                    len,
+                })
+                .map(Some),
+            None => self.seq.next_element_seed(seed),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.remaining)
+    }
+}
+
+/// The elements of a sequence of which the length was already read.
+struct Elements<'a, A> {
+    seq: &'a mut A,
+    remaining: u64,
+}
+
+impl<'de, A: de::SeqAccess<'de>> de::SeqAccess<'de> for Elements<'_, A> {
+    type Error = A::Error;
+
+    fn next_element_seed<T: de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, A::Error> {
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+        self.remaining -= 1;
+        self.seq.next_element_seed(seed)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        usize::try_from(self.remaining).ok()
+    }
+}
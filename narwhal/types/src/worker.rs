@@ -1,7 +1,7 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{Batch, BatchDigest};
+use crate::{primary::batch_digest, versioned::ENVELOPE_MARKER, Batch, BatchDigest};
 
 use fastcrypto::hash::HashFunction;
 use serde::{Deserialize, Serialize};
@@ -28,7 +28,9 @@ pub struct RequestBatchResponse {
     pub batch: Option<Batch>,
 }
 
-pub type TxResponse = tokio::sync::oneshot::Sender<BatchDigest>;
+/// Notified with the digest of the batch including the transaction, and its sequence among the
+/// batches sealed by the worker.
+pub type TxResponse = tokio::sync::oneshot::Sender<(BatchDigest, u64)>;
 pub type PrimaryResponse = Option<tokio::sync::oneshot::Sender<()>>;
 
 /// Hashes a serialized batch message without deserializing it into a batch.
@@ -40,16 +42,33 @@ pub type PrimaryResponse = Option<tokio::sync::oneshot::Sender<()>>;
 ///
 /// TODO: remove the expects in the below, making this return a `Result` and correspondingly
 /// doing error management at the callers. See #268
-/// TODO: update batch hashing to reflect hashing fixed sequences of transactions, see #87.
 pub fn serialized_batch_digest<K: AsRef<[u8]>>(sbm: K) -> Result<BatchDigest, DigestError> {
     let sbm = sbm.as_ref();
-    let mut offset = 0;
-    let num_transactions = u64::from_le_bytes(
-        sbm.get(offset..offset + 8)
+    // The batch is the only field of the message, so the message starts with the envelope of the
+    // batch, if any, or its legacy layout.
+    let prefix = read_u64(sbm, 0)?;
+    if prefix != ENVELOPE_MARKER {
+        let (transactions, _) = read_transactions(sbm, 0)?;
+        return Ok(batch_digest(transactions.into_iter(), [].into_iter()));
+    }
+    let version = u32::from_le_bytes(
+        sbm.get(8..12)
             .ok_or(DigestError::InvalidLengthError)?
             .try_into()
-            .map_err(|_| DigestError::InvalidArgumentError(offset))?,
+            .map_err(|_| DigestError::InvalidArgumentError(8))?,
     );
+    if version != 2 {
+        return Err(DigestError::UnsupportedVersion(version));
+    }
+    let (transactions, offset) = read_transactions(sbm, 12)?;
+    // Skip the batch metadata (its creation time) to the metadata of the transactions.
+    let (metadata, _) = read_transactions(sbm, offset + 8)?;
+    Ok(batch_digest(transactions.into_iter(), metadata.into_iter()))
+}
+
+/// Reads a sequence of byte strings, returning them along the offset right after them.
+fn read_transactions(sbm: &[u8], mut offset: usize) -> Result<(Vec<&[u8]>, usize), DigestError> {
+    let num_transactions = read_u64(sbm, offset)?;
     offset += 8;
     let mut transactions = Vec::new();
     for _i in 0..num_transactions {
@@ -57,9 +76,7 @@ pub fn serialized_batch_digest<K: AsRef<[u8]>>(sbm: K) -> Result<BatchDigest, Di
         transactions.push(tx_ref);
         offset = new_offset;
     }
-    Ok(BatchDigest::new(
-        crypto::DefaultHashFunction::digest_iterator(transactions.iter()).into(),
-    ))
+    Ok((transactions, offset))
 }
 
 #[derive(Debug, Error)]
@@ -69,6 +86,17 @@ pub enum DigestError {
     InvalidArgumentError(usize),
     #[error("Invalid length")]
     InvalidLengthError,
+    #[error("Unsupported batch version {0}")]
+    UnsupportedVersion(u32),
+}
+
+fn read_u64(sbm: &[u8], offset: usize) -> Result<u64, DigestError> {
+    Ok(u64::from_le_bytes(
+        sbm.get(offset..offset + 8)
+            .ok_or(DigestError::InvalidLengthError)?
+            .try_into()
+            .map_err(|_| DigestError::InvalidArgumentError(offset))?,
+    ))
 }
 
 fn read_one_transaction(sbm: &[u8], offset: usize) -> Result<(&[u8], usize), DigestError> {
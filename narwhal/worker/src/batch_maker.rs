@@ -4,13 +4,14 @@
 use crate::metrics::WorkerMetrics;
 #[cfg(feature = "trace_transaction")]
 use byteorder::{BigEndian, ReadBytesExt};
+use bytes::Bytes;
 use config::Committee;
 use fastcrypto::hash::Hash;
 use futures::stream::FuturesOrdered;
 use store::Store;
 
 use config::WorkerId;
-use tracing::error;
+use tracing::{error, Instrument};
 
 #[cfg(feature = "benchmark")]
 use std::convert::TryInto;
@@ -18,7 +19,11 @@ use std::convert::TryInto;
 use futures::{Future, StreamExt};
 
 use mysten_metrics::spawn_logged_monitored_task;
-use std::sync::Arc;
+use network::trace_id::{self, TraceId};
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
 use tokio::{
     sync::watch,
     task::JoinHandle,
@@ -27,13 +32,10 @@ use tokio::{
 use types::{
     error::DagError,
     metered_channel::{Receiver, Sender},
-    Batch, BatchDigest, PrimaryResponse, ReconfigureNotification, Transaction, TxResponse,
-    WorkerOurBatchMessage,
+    Batch, BatchDigest, PrimaryResponse, ReconfigureNotification, Transaction, TransactionMetadata,
+    TxResponse, WorkerOurBatchMessage,
 };
 
-// The number of batches to store / transmit in parallel.
-pub const MAX_PARALLEL_BATCH: usize = 25;
-
 #[cfg(test)]
 #[path = "tests/batch_maker_tests.rs"]
 pub mod batch_maker_tests;
@@ -48,12 +50,19 @@ pub struct BatchMaker {
     batch_size: usize,
     /// The maximum delay after which to seal the batch.
     max_batch_delay: Duration,
+    /// The number of batches to store / transmit in parallel.
+    max_parallel_batches: usize,
     /// Receive reconfiguration updates.
     rx_reconfigure: watch::Receiver<ReconfigureNotification>,
     /// Channel to receive transactions from the network.
-    rx_batch_maker: Receiver<(Transaction, TxResponse)>,
+    rx_batch_maker: Receiver<(
+        Transaction,
+        Option<TransactionMetadata>,
+        TraceId,
+        TxResponse,
+    )>,
     /// Output channel to deliver sealed batches to the `QuorumWaiter`.
-    tx_message: Sender<(Batch, Option<tokio::sync::oneshot::Sender<()>>)>,
+    tx_message: Sender<(Batch, TraceId, Option<tokio::sync::oneshot::Sender<()>>)>,
     /// Metrics handler
     node_metrics: Arc<WorkerMetrics>,
     /// The timestamp of the first transaction received
@@ -62,7 +71,9 @@ pub struct BatchMaker {
     /// The batch store to store our own batches.
     store: Store<BatchDigest, Batch>,
     // Output channel to send out batches' digests.
-    tx_digest: Sender<(WorkerOurBatchMessage, PrimaryResponse)>,
+    tx_digest: Sender<(WorkerOurBatchMessage, TraceId, PrimaryResponse)>,
+    /// The number of batches sealed so far.
+    sealed_batches: AtomicU64,
 }
 
 impl BatchMaker {
@@ -72,12 +83,18 @@ impl BatchMaker {
         committee: Committee,
         batch_size: usize,
         max_batch_delay: Duration,
+        max_parallel_batches: usize,
         rx_reconfigure: watch::Receiver<ReconfigureNotification>,
-        rx_batch_maker: Receiver<(Transaction, TxResponse)>,
-        tx_message: Sender<(Batch, Option<tokio::sync::oneshot::Sender<()>>)>,
+        rx_batch_maker: Receiver<(
+            Transaction,
+            Option<TransactionMetadata>,
+            TraceId,
+            TxResponse,
+        )>,
+        tx_message: Sender<(Batch, TraceId, Option<tokio::sync::oneshot::Sender<()>>)>,
         node_metrics: Arc<WorkerMetrics>,
         store: Store<BatchDigest, Batch>,
-        tx_digest: Sender<(WorkerOurBatchMessage, PrimaryResponse)>,
+        tx_digest: Sender<(WorkerOurBatchMessage, TraceId, PrimaryResponse)>,
     ) -> JoinHandle<()> {
         spawn_logged_monitored_task!(
             async move {
@@ -86,6 +103,7 @@ impl BatchMaker {
                     committee,
                     batch_size,
                     max_batch_delay,
+                    max_parallel_batches,
                     rx_reconfigure,
                     rx_batch_maker,
                     tx_message,
@@ -93,6 +111,7 @@ impl BatchMaker {
                     node_metrics,
                     store,
                     tx_digest,
+                    sealed_batches: AtomicU64::new(0),
                 }
                 .run()
                 .await;
@@ -108,6 +127,7 @@ impl BatchMaker {
 
         let mut current_batch = Batch::default();
         let mut current_responses = Vec::new();
+        let mut current_trace_ids = Vec::new();
         let mut current_batch_size = 0;
 
         let mut batch_pipeline = FuturesOrdered::new();
@@ -116,9 +136,9 @@ impl BatchMaker {
             tokio::select! {
                 // Assemble client transactions into batches of preset size.
                 // Note that transactions are only consumed when the number of batches
-                // 'in-flight' are below a certain number (MAX_PARALLEL_BATCH). This
+                // 'in-flight' are below a certain number (max_parallel_batches). This
                 // condition will be met eventually if the store and network are functioning.
-                Some((transaction, response_sender)) = self.rx_batch_maker.recv(), if batch_pipeline.len() < MAX_PARALLEL_BATCH => {
+                Some((transaction, metadata, trace_id, response_sender)) = self.rx_batch_maker.recv(), if batch_pipeline.len() < self.max_parallel_batches => {
 
                     if current_batch.transactions.is_empty() {
                         // We are interested to measure the time to seal a batch
@@ -130,9 +150,24 @@ impl BatchMaker {
 
                     current_batch_size += transaction.len();
                     current_batch.transactions.push(transaction);
+                    if let Some(metadata) = metadata {
+                        current_batch_size += metadata.len();
+                        // The transactions before the first one with metadata have none.
+                        current_batch
+                            .transaction_metadata
+                            .resize(current_batch.transactions.len() - 1, Bytes::new());
+                        current_batch.transaction_metadata.push(metadata);
+                    } else if !current_batch.transaction_metadata.is_empty() {
+                        current_batch.transaction_metadata.push(Bytes::new());
+                    }
                     current_responses.push(response_sender);
+                    // The transactions of a stream share its trace id.
+                    if current_trace_ids.last() != Some(&trace_id) {
+                        current_trace_ids.push(trace_id);
+                    }
                     if current_batch_size >= self.batch_size {
-                        if let Some(seal) = self.seal(false, current_batch, current_batch_size, current_responses).await{
+                        let trace_ids = std::mem::take(&mut current_trace_ids);
+                        if let Some(seal) = self.seal(false, current_batch, current_batch_size, current_responses, trace_ids).await{
                             batch_pipeline.push_back(seal);
                         }
                         self.node_metrics.parallel_worker_batches.set(batch_pipeline.len() as i64);
@@ -147,7 +182,8 @@ impl BatchMaker {
                 // If the timer triggers, seal the batch even if it contains few transactions.
                 () = &mut timer => {
                     if !current_batch.transactions.is_empty() {
-                        if let Some(seal) = self.seal(true, current_batch, current_batch_size, current_responses).await {
+                        let trace_ids = std::mem::take(&mut current_trace_ids);
+                        if let Some(seal) = self.seal(true, current_batch, current_batch_size, current_responses, trace_ids).await {
                             batch_pipeline.push_back(seal);
                         }
                         self.node_metrics.parallel_worker_batches.set(batch_pipeline.len() as i64);
@@ -179,7 +215,7 @@ impl BatchMaker {
 
                 // Process the pipeline of batches, this consumes items in the `batch_pipeline`
                 // list, and ensures the main loop in run will always be able to make progress
-                // by lowering it until condition batch_pipeline.len() < MAX_PARALLEL_BATCH is met.
+                // by lowering it until condition batch_pipeline.len() < max_parallel_batches is met.
                 _ = batch_pipeline.next(), if !batch_pipeline.is_empty() => {
                     self.node_metrics.parallel_worker_batches.set(batch_pipeline.len() as i64);
                 }
@@ -191,13 +227,15 @@ impl BatchMaker {
         }
     }
 
-    /// Seal and broadcast the current batch.
+    /// Seal and broadcast the current batch. The batch is disseminated under the trace id of its
+    /// first transaction, and logged along the trace ids of all its transactions.
     async fn seal(
         &self,
         timeout: bool,
         batch: Batch,
         size: usize,
         responses: Vec<TxResponse>,
+        trace_ids: Vec<TraceId>,
     ) -> Option<impl Future<Output = ()>> {
         #[cfg(feature = "benchmark")]
         {
@@ -249,6 +287,8 @@ impl BatchMaker {
         }
 
         let reason = if timeout { "timeout" } else { "size_reached" };
+        let trace_id = trace_ids.first().cloned().unwrap_or_else(TraceId::generate);
+        let sequence = self.sealed_batches.fetch_add(1, Ordering::Relaxed);
 
         self.node_metrics
             .created_batch_size
@@ -259,7 +299,7 @@ impl BatchMaker {
         let (notify_done, done_sending) = tokio::sync::oneshot::channel();
         if self
             .tx_message
-            .send((batch.clone(), Some(notify_done)))
+            .send((batch.clone(), trace_id.clone(), Some(notify_done)))
             .await
             .is_err()
         {
@@ -281,10 +321,21 @@ impl BatchMaker {
         let tx_digest = self.tx_digest.clone();
         let metadata = batch.metadata.clone();
 
-        Some(async move {
-            // Now save it to disk
-            let digest = batch.digest();
+        // The batch joins the trace of its first transaction, linked to the traces of the others.
+        let digest = batch.digest();
+        let span = tracing::info_span!("seal_batch", batch_digest = %digest, %trace_id, reason);
+        trace_id::join_all(&span, &trace_ids);
 
+        let seal = trace_id::scope(Some(trace_id.clone()), async move {
+            // Now save it to disk
+            tracing::debug!(
+                %trace_id,
+                "Batch {digest} contains the transactions traced as {:?}",
+                trace_ids.iter().map(TraceId::as_str).collect::<Vec<_>>()
+            );
+
+            // The batch may already be broadcast to the other workers at this point.
+            fail::fail_point!("batch-maker-before-store-batch");
             if let Err(e) = store.sync_write(digest, batch).await {
                 error!("Store failed with error: {:?}", e);
                 return;
@@ -299,6 +350,7 @@ impl BatchMaker {
             let _ = done_sending.await;
 
             // Finally send to primary
+            fail::fail_point!("batch-maker-before-report-batch");
             let (primary_response, batch_done) = tokio::sync::oneshot::channel();
             let message = WorkerOurBatchMessage {
                 digest,
@@ -306,7 +358,7 @@ impl BatchMaker {
                 metadata,
             };
             if tx_digest
-                .send((message, Some(primary_response)))
+                .send((message, trace_id, Some(primary_response)))
                 .await
                 .is_err()
             {
@@ -326,8 +378,9 @@ impl BatchMaker {
             // We now signal back to the transaction sender that the transaction is in a
             // batch and also the digest of the batch.
             for response in responses {
-                let _ = response.send(digest);
+                let _ = response.send((digest, sequence));
             }
-        })
+        });
+        Some(seal.instrument(span))
     }
 }
@@ -50,6 +50,12 @@ impl<V: TransactionValidator> WorkerToWorker for WorkerReceiverHandler<V> {
         request: anemo::Request<WorkerBatchMessage>,
     ) -> Result<anemo::Response<()>, anemo::rpc::Status> {
         let message = request.into_body();
+        if !message.batch.has_valid_metadata() {
+            return Err(anemo::rpc::Status::new_with_message(
+                StatusCode::BadRequest,
+                "Invalid batch: the metadata does not match the transactions",
+            ));
+        }
         if let Err(err) = self.validator.validate_batch(&message.batch) {
             // The batch is invalid, we don't want to process it.
             return Err(anemo::rpc::Status::new_with_message(
@@ -253,6 +259,12 @@ impl<V: TransactionValidator> PrimaryToWorker for PrimaryReceiverHandler<V> {
                 match result {
                     Ok(response) => {
                         if let Some(batch) = response.into_body().batch {
+                            if !batch.has_valid_metadata() {
+                                return Err(anemo::rpc::Status::new_with_message(
+                                    StatusCode::BadRequest,
+                                    "Invalid batch: the metadata does not match the transactions",
+                                ));
+                            }
                             if let Err(err) = self.validator.validate_batch(&batch) {
                                 // The batch is invalid, we don't want to process it.
                                 return Err(anemo::rpc::Status::new_with_message(
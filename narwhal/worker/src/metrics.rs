@@ -7,8 +7,9 @@ use prometheus::{
     register_int_counter_with_registry, register_int_gauge_with_registry, HistogramVec, IntCounter,
     IntCounterVec, IntGauge, Registry,
 };
-use std::time::Duration;
+use std::{collections::BTreeMap, time::Duration};
 use tonic::Code;
+use types::metered_channel::ChannelCollector;
 
 const LATENCY_SEC_BUCKETS: &[f64] = &[
     0.01, 0.05, 0.1, 0.2, 0.5, 1.0, 2.0, 3.0, 4.0, 5.0, 8.0, 10.0, 15.0, 20.0, 30.0, 50.0, 100.0,
@@ -132,11 +133,44 @@ pub struct WorkerChannelMetrics {
     pub tx_batch_maker_total: IntCounter,
     /// total received from the channel from the `worker::BatchMaker` to the `worker::QuorumWaiter`
     pub tx_quorum_waiter_total: IntCounter,
+
+    /// exports the occupancies and the totals above, labelled by channel
+    collector: ChannelCollector,
 }
 
 impl WorkerChannelMetrics {
+    /// Every channel by name, with its occupancy and its total received.
+    fn channels(&self) -> Vec<(&'static str, &IntGauge, &IntCounter)> {
+        vec![
+            ("tx_our_batch", &self.tx_our_batch, &self.tx_our_batch_total),
+            (
+                "tx_others_batch",
+                &self.tx_others_batch,
+                &self.tx_others_batch_total,
+            ),
+            (
+                "tx_batch_maker",
+                &self.tx_batch_maker,
+                &self.tx_batch_maker_total,
+            ),
+            (
+                "tx_quorum_waiter",
+                &self.tx_quorum_waiter,
+                &self.tx_quorum_waiter_total,
+            ),
+        ]
+    }
+
+    /// The occupancy of every channel, by name.
+    pub fn depths(&self) -> BTreeMap<&'static str, i64> {
+        self.channels()
+            .into_iter()
+            .map(|(name, gauge, _)| (name, gauge.get()))
+            .collect()
+    }
+
     pub fn new(registry: &Registry) -> Self {
-        Self {
+        let metrics = Self {
             tx_our_batch: register_int_gauge_with_registry!(
                 "tx_our_batch",
                 "occupancy of the channel from various handlers to the `worker::PrimaryConnector`",
@@ -180,7 +214,13 @@ impl WorkerChannelMetrics {
                 "total received from the channel from the `worker::BatchMaker` to the `worker::QuorumWaiter`",
                 registry
             ).unwrap(),
+
+            collector: ChannelCollector::new("worker", registry),
+        };
+        for (name, gauge, total) in metrics.channels() {
+            metrics.collector.add(name, gauge, Some(total));
         }
+        metrics
     }
 }
 
@@ -5,16 +5,16 @@
 use crypto::NetworkPublicKey;
 use futures::{stream::FuturesUnordered, StreamExt};
 use mysten_metrics::{monitored_future, spawn_logged_monitored_task};
-use network::{CancelOnDropHandler, ReliableNetwork};
+use network::{
+    trace_id::{self, TraceId},
+    CancelOnDropHandler, ReliableNetwork,
+};
 use tokio::{sync::watch, task::JoinHandle};
 use types::{
     metered_channel::Receiver, PrimaryResponse, ReconfigureNotification, WorkerOthersBatchMessage,
     WorkerOurBatchMessage,
 };
 
-/// The maximum number of digests kept in memory waiting to be sent to the primary.
-pub const MAX_PENDING_DIGESTS: usize = 10_000;
-
 // Send batches' digests to the primary.
 pub struct PrimaryConnector {
     /// The public key of this authority.
@@ -22,10 +22,12 @@ pub struct PrimaryConnector {
     /// Receive reconfiguration updates.
     rx_reconfigure: watch::Receiver<ReconfigureNotification>,
     /// Input channels to receive the messages to send to the primary.
-    rx_our_batch: Receiver<(WorkerOurBatchMessage, PrimaryResponse)>,
+    rx_our_batch: Receiver<(WorkerOurBatchMessage, TraceId, PrimaryResponse)>,
     rx_others_batch: Receiver<WorkerOthersBatchMessage>,
     /// A network sender to send the batches' digests to the primary.
     primary_client: anemo::Network,
+    /// The maximum number of digests kept in memory waiting to be sent to the primary.
+    max_pending_digests: usize,
 }
 
 impl PrimaryConnector {
@@ -33,9 +35,10 @@ impl PrimaryConnector {
     pub fn spawn(
         primary_name: NetworkPublicKey,
         rx_reconfigure: watch::Receiver<ReconfigureNotification>,
-        rx_our_batch: Receiver<(WorkerOurBatchMessage, PrimaryResponse)>,
+        rx_our_batch: Receiver<(WorkerOurBatchMessage, TraceId, PrimaryResponse)>,
         rx_others_batch: Receiver<WorkerOthersBatchMessage>,
         primary_client: anemo::Network,
+        max_pending_digests: usize,
     ) -> JoinHandle<()> {
         spawn_logged_monitored_task!(
             async move {
@@ -45,6 +48,7 @@ impl PrimaryConnector {
                     rx_our_batch,
                     rx_others_batch,
                     primary_client,
+                    max_pending_digests,
                 }
                 .run()
                 .await;
@@ -63,17 +67,20 @@ impl PrimaryConnector {
         loop {
             tokio::select! {
                 // Send the digest through the network.
-                Some((batch, response)) = self.rx_our_batch.recv() => {
-                    if futures.len() >= MAX_PENDING_DIGESTS {
+                Some((batch, trace_id, response)) = self.rx_our_batch.recv() => {
+                    if futures.len() >= self.max_pending_digests {
                         tracing::warn!("Primary unreachable: dropping {batch:?}");
                         continue;
                     }
 
-                    let handle = self.primary_client.send(self.primary_name.to_owned(), &batch);
+                    let handle = trace_id::scope(Some(trace_id), async {
+                        self.primary_client.send(self.primary_name.to_owned(), &batch)
+                    })
+                    .await;
                     futures.push( monitor(handle_future(handle, response)) );
                 },
                 Some(batch) = self.rx_others_batch.recv() => {
-                    if futures.len() >= MAX_PENDING_DIGESTS {
+                    if futures.len() >= self.max_pending_digests {
                         tracing::warn!("Primary unreachable: dropping {batch:?}");
                         continue;
                     }
@@ -2,13 +2,15 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::batch_maker::MAX_PARALLEL_BATCH;
 use config::{Committee, SharedWorkerCache, Stake, WorkerId};
 use crypto::PublicKey;
 use fastcrypto::hash::Hash;
 use futures::stream::{futures_unordered::FuturesUnordered, FuturesOrdered, StreamExt as _};
 use mysten_metrics::{monitored_future, spawn_logged_monitored_task};
-use network::{CancelOnDropHandler, ReliableNetwork};
+use network::{
+    trace_id::{self, TraceId},
+    CancelOnDropHandler, ReliableNetwork,
+};
 use std::time::Duration;
 use tokio::{sync::watch, task::JoinHandle, time::timeout};
 use tracing::{error, trace};
@@ -28,10 +30,12 @@ pub struct QuorumWaiter {
     committee: Committee,
     /// The worker information cache.
     worker_cache: SharedWorkerCache,
+    /// The number of batches to broadcast in parallel.
+    max_parallel_batches: usize,
     /// Receive reconfiguration updates.
     rx_reconfigure: watch::Receiver<ReconfigureNotification>,
     /// Input Channel to receive commands.
-    rx_message: Receiver<(Batch, Option<tokio::sync::oneshot::Sender<()>>)>,
+    rx_message: Receiver<(Batch, TraceId, Option<tokio::sync::oneshot::Sender<()>>)>,
     /// A network sender to broadcast the batches to the other workers.
     network: anemo::Network,
 }
@@ -44,8 +48,9 @@ impl QuorumWaiter {
         id: WorkerId,
         committee: Committee,
         worker_cache: SharedWorkerCache,
+        max_parallel_batches: usize,
         rx_reconfigure: watch::Receiver<ReconfigureNotification>,
-        rx_message: Receiver<(Batch, Option<tokio::sync::oneshot::Sender<()>>)>,
+        rx_message: Receiver<(Batch, TraceId, Option<tokio::sync::oneshot::Sender<()>>)>,
         network: anemo::Network,
     ) -> JoinHandle<()> {
         spawn_logged_monitored_task!(
@@ -55,6 +60,7 @@ impl QuorumWaiter {
                     id,
                     committee,
                     worker_cache,
+                    max_parallel_batches,
                     rx_reconfigure,
                     rx_message,
                     network,
@@ -86,9 +92,7 @@ impl QuorumWaiter {
 
                 // When a new batch is available, and the pipeline is not full, add a new
                 // task to the pipeline to send this batch to workers.
-                //
-                // TODO: make the constant a config parameter.
-                Some((batch, opt_channel)) = self.rx_message.recv(), if pipeline.len() < MAX_PARALLEL_BATCH => {
+                Some((batch, trace_id, opt_channel)) = self.rx_message.recv(), if pipeline.len() < self.max_parallel_batches => {
                     // Broadcast the batch to the other workers.
                     let workers: Vec<_> = self
                         .worker_cache
@@ -99,7 +103,10 @@ impl QuorumWaiter {
                         .collect();
                     let (primary_names, worker_names): (Vec<_>, _) = workers.into_iter().unzip();
                     let message  = WorkerBatchMessage{batch: batch.clone()};
-                    let handlers = self.network.broadcast(worker_names, &message);
+                    let handlers = trace_id::scope(Some(trace_id), async {
+                        self.network.broadcast(worker_names, &message)
+                    })
+                    .await;
 
                     // Collect all the handlers to receive acknowledgements.
                     let mut wait_for_quorum: FuturesUnordered<_> = primary_names
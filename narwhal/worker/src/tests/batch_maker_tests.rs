@@ -32,6 +32,7 @@ async fn make_batch() {
         /* max_batch_size */ 200,
         /* max_batch_delay */
         Duration::from_millis(1_000_000), // Ensure the timer is not triggered.
+        /* max_parallel_batches */ 25,
         rx_reconfiguration,
         rx_batch_maker,
         tx_message,
@@ -44,14 +45,23 @@ async fn make_batch() {
     let tx = transaction();
     let (s0, r0) = tokio::sync::oneshot::channel();
     let (s1, r1) = tokio::sync::oneshot::channel();
-    tx_batch_maker.send((tx.clone(), s0)).await.unwrap();
-    tx_batch_maker.send((tx.clone(), s1)).await.unwrap();
+    let trace_id = TraceId::generate();
+    tx_batch_maker
+        .send((tx.clone(), None, trace_id.clone(), s0))
+        .await
+        .unwrap();
+    tx_batch_maker
+        .send((tx.clone(), None, TraceId::generate(), s1))
+        .await
+        .unwrap();
 
-    // Ensure the batch is as expected.
+    // Ensure the batch is as expected, and disseminated under the trace id of its first
+    // transaction.
     let expected_batch = Batch::new(vec![tx.clone(), tx.clone()]);
-    let (batch, overall_response) = rx_message.recv().await.unwrap();
+    let (batch, batch_trace_id, overall_response) = rx_message.recv().await.unwrap();
 
     assert_eq!(batch.transactions, expected_batch.transactions);
+    assert_eq!(batch_trace_id, trace_id);
 
     // Eventually deliver message
     if let Some(resp) = overall_response {
@@ -59,7 +69,8 @@ async fn make_batch() {
     }
 
     // Now we send to primary
-    let (_message, respond) = rx_digest.recv().await.unwrap();
+    let (_message, batch_trace_id, respond) = rx_digest.recv().await.unwrap();
+    assert_eq!(batch_trace_id, trace_id);
     assert!(respond.unwrap().send(()).is_ok());
 
     assert!(r0.await.is_ok());
@@ -93,6 +104,7 @@ async fn batch_timeout() {
         /* max_batch_size */ 200,
         /* max_batch_delay */
         Duration::from_millis(50), // Ensure the timer is triggered.
+        /* max_parallel_batches */ 25,
         rx_reconfiguration,
         rx_batch_maker,
         tx_message,
@@ -104,10 +116,13 @@ async fn batch_timeout() {
     // Do not send enough transactions to seal a batch.
     let tx = transaction();
     let (s0, r0) = tokio::sync::oneshot::channel();
-    tx_batch_maker.send((tx.clone(), s0)).await.unwrap();
+    tx_batch_maker
+        .send((tx.clone(), None, TraceId::generate(), s0))
+        .await
+        .unwrap();
 
     // Ensure the batch is as expected.
-    let (batch, overall_response) = rx_message.recv().await.unwrap();
+    let (batch, _trace_id, overall_response) = rx_message.recv().await.unwrap();
     let expected_batch = Batch::new(vec![tx.clone()]);
     assert_eq!(batch.transactions, expected_batch.transactions);
 
@@ -117,7 +132,7 @@ async fn batch_timeout() {
     }
 
     // Now we send to primary
-    let (_message, respond) = rx_digest.recv().await.unwrap();
+    let (_message, _trace_id, respond) = rx_digest.recv().await.unwrap();
     assert!(respond.unwrap().send(()).is_ok());
 
     assert!(r0.await.is_ok());
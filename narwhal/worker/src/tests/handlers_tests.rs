@@ -169,3 +169,27 @@ async fn delete_batches() {
 
     assert!(store.read(digest).await.unwrap().is_none());
 }
+
+#[tokio::test]
+async fn reject_batch_with_mismatched_metadata() {
+    let store = test_utils::open_batch_store();
+    let gauge = prometheus::IntGauge::new("TEST_COUNTER", "test").unwrap();
+    let (tx_others_batch, _rx_others_batch) = types::metered_channel::channel(1, &gauge);
+    let handler = WorkerReceiverHandler {
+        id: 0,
+        tx_others_batch,
+        store: store.clone(),
+        validator: TrivialTransactionValidator,
+    };
+
+    // Metadata for only one of the two transactions.
+    let mut batch = test_utils::batch();
+    batch.transaction_metadata = vec![bytes::Bytes::from_static(b"metadata")];
+    assert_eq!(batch.transactions.len(), 2);
+    let digest = batch.digest();
+
+    let request = anemo::Request::new(WorkerBatchMessage { batch });
+    let status = handler.report_batch(request).await.unwrap_err();
+    assert_eq!(status.status(), StatusCode::BadRequest);
+    assert!(store.read(digest).await.unwrap().is_none());
+}
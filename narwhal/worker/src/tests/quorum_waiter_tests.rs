@@ -24,6 +24,7 @@ async fn wait_for_quorum() {
         /* worker_id */ 0,
         committee.clone(),
         worker_cache.clone(),
+        /* max_parallel_batches */ 25,
         rx_reconfiguration,
         rx_message,
         network.clone(),
@@ -51,7 +52,10 @@ async fn wait_for_quorum() {
 
     // Forward the batch along with the handlers to the `QuorumWaiter`.
     let (s, r) = tokio::sync::oneshot::channel();
-    tx_message.send((batch.clone(), Some(s))).await.unwrap();
+    tx_message
+        .send((batch.clone(), TraceId::generate(), Some(s)))
+        .await
+        .unwrap();
 
     // Wait for the `QuorumWaiter` to gather enough acknowledgements and output the batch.
     r.await.unwrap();
@@ -82,6 +86,7 @@ async fn pipeline_for_quorum() {
         /* worker_id */ 0,
         committee.clone(),
         worker_cache.clone(),
+        /* max_parallel_batches */ 25,
         rx_reconfiguration,
         rx_message,
         network.clone(),
@@ -109,11 +114,17 @@ async fn pipeline_for_quorum() {
 
     // Forward the batch along with the handlers to the `QuorumWaiter`.
     let (s0, r0) = tokio::sync::oneshot::channel();
-    tx_message.send((batch.clone(), Some(s0))).await.unwrap();
+    tx_message
+        .send((batch.clone(), TraceId::generate(), Some(s0)))
+        .await
+        .unwrap();
 
     // Forward the batch along with the handlers to the `QuorumWaiter`.
     let (s1, r1) = tokio::sync::oneshot::channel();
-    tx_message.send((batch.clone(), Some(s1))).await.unwrap();
+    tx_message
+        .send((batch.clone(), TraceId::generate(), Some(s1)))
+        .await
+        .unwrap();
 
     // Wait for the `QuorumWaiter` to gather enough acknowledgements and output the batch.
     r0.await.unwrap();
@@ -49,6 +49,10 @@ async fn reject_invalid_clients_transactions() {
 
     let parameters = Parameters {
         batch_size: 200, // Two transactions.
+        network: NetworkParameters {
+            max_transactions_message_size: 1_000,
+            ..NetworkParameters::default()
+        },
         ..Parameters::default()
     };
 
@@ -86,11 +90,23 @@ async fn reject_invalid_clients_transactions() {
     let tx = transaction();
     let txn = TransactionProto {
         transaction: Bytes::from(tx.clone()),
+        metadata: Bytes::new(),
     };
 
     // Check invalid transactions are rejected
     let res = client.submit_transaction(txn).await;
-    assert!(res.is_err());
+    let details = ErrorDetails::from_status(&res.unwrap_err()).unwrap();
+    assert_eq!(details.code(), ErrorCode::InvalidTransaction);
+    assert!(!details.retryable);
+
+    // Check transactions larger than the configured limit are rejected
+    let txn = TransactionProto {
+        transaction: Bytes::from(vec![0u8; 1_001]),
+        metadata: Bytes::new(),
+    };
+    let res = client.submit_transaction(txn).await;
+    let details = ErrorDetails::from_status(&res.unwrap_err()).unwrap();
+    assert_eq!(details.code(), ErrorCode::TransactionTooLarge);
 
     let worker_pk = worker_cache.load().worker(&name, &worker_id).unwrap().name;
 
@@ -206,18 +222,29 @@ async fn handle_clients_transactions() {
         for tx in batch.transactions {
             let txn = TransactionProto {
                 transaction: Bytes::from(tx.clone()),
+                metadata: Bytes::new(),
             };
 
             // Calls to submit_transaction are now blocking, so we need to drive them
             // all at the same time, rather than sequentially.
             let mut inner_client = client.clone();
             fut_list.push_back(async move {
-                inner_client.submit_transaction(txn).await.unwrap();
+                let ack = inner_client
+                    .submit_transaction(txn)
+                    .await
+                    .unwrap()
+                    .into_inner();
+                assert_eq!(ack.transaction_digest, transaction_digest(&tx).to_vec());
+                assert_eq!(ack.worker_id, worker_id);
+                ack
             });
         }
 
-        // Drive all sending in parallel.
-        while fut_list.next().await.is_some() {}
+        // Drive all sending in parallel. The transactions are all in the first batch.
+        while let Some(ack) = fut_list.next().await {
+            assert_eq!(ack.batch_digest, batch_digest.0.to_vec());
+            assert_eq!(ack.batch_sequence, 0);
+        }
     });
 
     // Ensure the primary received the batch's digest (ie. it did not panic).
@@ -17,28 +17,36 @@ use anemo_tower::{
     trace::{DefaultMakeSpan, TraceLayer},
 };
 use async_trait::async_trait;
-use config::{Parameters, SharedCommittee, SharedWorkerCache, WorkerId};
+use bytes::Bytes;
+use config::{NetworkParameters, Parameters, SharedCommittee, SharedWorkerCache, WorkerId};
 use crypto::{traits::KeyPair as _, NetworkKeyPair, NetworkPublicKey, PublicKey};
 use futures::StreamExt;
-use multiaddr::{Multiaddr, Protocol};
+use multiaddr::Multiaddr;
 use mysten_metrics::spawn_logged_monitored_task;
+use network::auth::{CommitteePeers, PeerGrace};
+use network::bandwidth::{EgressLimitLayer, EgressLimiter};
 use network::failpoints::FailpointsMakeCallbackHandler;
+use network::faults::FaultInjectionLayer;
+use network::handshake::{HandshakeGateLayer, HandshakeHandler, HandshakenPeers};
 use network::metrics::MetricsMakeCallbackHandler;
+use network::trace_id::{self, ExtractTraceIdLayer, PropagateTraceIdLayer, TraceId};
+use serde_json::json;
 use std::collections::HashMap;
-use std::{net::Ipv4Addr, sync::Arc};
+use std::sync::Arc;
 use store::Store;
 use tap::TapFallible;
 use tokio::sync::watch::Receiver;
 use tokio::{sync::watch, task::JoinHandle};
 use tonic::{Request, Response, Status};
 use tower::ServiceBuilder;
-use tracing::{error, info};
+use tracing::{debug, error, info, Instrument};
 use types::{
     error::DagError,
     metered_channel::{channel_with_total, Sender},
-    Batch, BatchDigest, Empty, PrimaryToWorkerServer, ReconfigureNotification, Transaction,
-    TransactionProto, Transactions, TransactionsServer, TxResponse, WorkerOurBatchMessage,
-    WorkerToWorkerServer,
+    transaction_digest, Batch, BatchDigest, Empty, ErrorCode, ErrorDetails, HandshakeServer,
+    NodeHealth, PrimaryToWorkerServer, ReconfigureNotification, StateDump, StorageMaintenance,
+    SubmitTransactionResponse, Transaction, TransactionMetadata, TransactionProto, Transactions,
+    TransactionsServer, TxResponse, WorkerOurBatchMessage, WorkerToWorkerServer,
 };
 
 #[cfg(test)]
@@ -48,7 +56,8 @@ pub mod worker_tests;
 /// The default channel capacity for each channel of the worker.
 pub const CHANNEL_CAPACITY: usize = 1_000;
 
-/// The maximum allowed size of transactions into Narwhal.
+/// The default maximum size of the transactions into Narwhal, see
+/// [`NetworkParameters::max_transactions_message_size`].
 pub const MAX_ALLOWED_TRANSACTION_SIZE: usize = 6 * 1024 * 1024;
 
 use crate::metrics::{Metrics, WorkerEndpointMetrics, WorkerMetrics};
@@ -88,6 +97,9 @@ impl Worker {
             PeerId(keypair.public().0.to_bytes())
         );
 
+        // Apply the overrides of this worker on top of the node-wide parameters.
+        let parameters = parameters.for_worker(id);
+
         // Define a worker instance.
         let worker = Self {
             primary_name: primary_name.clone(),
@@ -108,12 +120,12 @@ impl Worker {
 
         // Spawn all worker tasks.
         let (tx_our_batch, rx_our_batch) = channel_with_total(
-            CHANNEL_CAPACITY,
+            parameters.memory_budget.channel_capacity("tx_our_batch"),
             &channel_metrics.tx_our_batch,
             &channel_metrics.tx_our_batch_total,
         );
         let (tx_others_batch, rx_others_batch) = channel_with_total(
-            CHANNEL_CAPACITY,
+            parameters.memory_budget.channel_capacity("tx_others_batch"),
             &channel_metrics.tx_others_batch,
             &channel_metrics.tx_others_batch_total,
         );
@@ -147,9 +159,7 @@ impl Worker {
             .worker(&primary_name, &id)
             .expect("Our public key or worker id is not in the worker cache")
             .worker_address;
-        let address = address
-            .replace(0, |_protocol| Some(Protocol::Ip4(Ipv4Addr::UNSPECIFIED)))
-            .unwrap();
+        let address = network::listen_address(&address, worker.parameters.network.dual_stack);
         let addr = network::multiaddr_to_address(&address).unwrap();
 
         // Set up anemo Network.
@@ -164,40 +174,76 @@ impl Worker {
             .route_layer(RequireAuthorizationLayer::new(AllowedPeers::new([
                 our_primary_peer_id,
             ])));
+        let handshake_service = HandshakeServer::new(HandshakeHandler::new(
+            worker.committee.clone(),
+            worker.parameters.chain_id.clone(),
+        ));
+        // Only the peers of the committee are served.
+        let committee_peers = CommitteePeers::new(
+            worker.committee.clone(),
+            worker.worker_cache.clone(),
+            PeerGrace::of_authority(&worker.primary_name),
+        );
         let routes = anemo::Router::new()
             .add_rpc_service(worker_service)
-            .merge(primary_to_worker_router);
+            .add_rpc_service(handshake_service)
+            .merge(primary_to_worker_router)
+            .route_layer(RequireAuthorizationLayer::new(committee_peers.clone()));
+
+        // The responses and the requests of the node share its egress bandwidth.
+        let egress_limiter = EgressLimiter::new(
+            worker.parameters.network.egress_bandwidth,
+            worker.parameters.network.sync_egress_bandwidth_per_peer,
+            network_connection_metrics.clone(),
+        );
 
         let service = ServiceBuilder::new()
             .layer(
                 TraceLayer::new_for_server_errors()
                     .make_span_with(DefaultMakeSpan::new().level(tracing::Level::INFO)),
             )
+            .layer(ExtractTraceIdLayer::new())
+            .layer(EgressLimitLayer::inbound(egress_limiter.clone()))
             .layer(CallbackLayer::new(MetricsMakeCallbackHandler::new(
                 inbound_network_metrics,
             )))
             .layer(CallbackLayer::new(FailpointsMakeCallbackHandler::new()))
             .service(routes);
 
+        // The requests to a peer are held until its handshake succeeds.
+        let handshaken_peers = HandshakenPeers::new();
         let outbound_layer = ServiceBuilder::new()
             .layer(
                 TraceLayer::new_for_client_and_server_errors()
                     .make_span_with(DefaultMakeSpan::new().level(tracing::Level::INFO)),
             )
+            .layer(PropagateTraceIdLayer::new())
+            .layer(HandshakeGateLayer::new(handshaken_peers.clone()))
+            .layer(EgressLimitLayer::outbound(egress_limiter))
             .layer(CallbackLayer::new(MetricsMakeCallbackHandler::new(
                 outbound_network_metrics,
             )))
             .layer(CallbackLayer::new(FailpointsMakeCallbackHandler::new()))
+            .layer(FaultInjectionLayer::new(PeerId(
+                worker.keypair.public().0.to_bytes(),
+            )))
             .into_inner();
 
         let anemo_config = {
+            let network_parameters = &worker.parameters.network;
             let mut quic_config = anemo::QuicConfig::default();
-            // Enable keep alives every 5s
-            quic_config.keep_alive_interval_ms = Some(5_000);
+            quic_config.keep_alive_interval_ms =
+                Some(network_parameters.keep_alive_interval.as_millis() as u64);
+            quic_config.max_idle_timeout_ms = network_parameters
+                .idle_timeout
+                .map(|timeout| timeout.as_millis() as u64);
             let mut config = anemo::Config::default();
             config.quic = Some(quic_config);
-            // Set a default timeout of 30s for all outbound RPC requests
-            config.outbound_request_timeout_ms = Some(30_000);
+            // The timeout of all outbound RPC requests
+            config.outbound_request_timeout_ms =
+                Some(network_parameters.request_timeout.as_millis() as u64);
+            // Bounds the size of both the sent and received messages.
+            config.max_frame_size = Some(network_parameters.max_worker_message_size);
             config
         };
 
@@ -262,23 +308,47 @@ impl Worker {
 
         let connection_monitor_handle = network::connectivity::ConnectionMonitor::spawn(
             network.downgrade(),
-            network_connection_metrics,
+            network_connection_metrics.clone(),
             peer_types,
         );
 
-        let network_admin_server_base_port = parameters
-            .network_admin_server
-            .worker_network_admin_server_base_port
-            .checked_add(id as u16)
-            .unwrap();
+        let handshake_monitor_handle = network::handshake::HandshakeMonitor::spawn(
+            network.downgrade(),
+            worker.committee.clone(),
+            committee_peers,
+            worker.parameters.chain_id.clone(),
+            network_connection_metrics,
+            handshaken_peers,
+        );
+
+        let dns_refresher_handle = network::dns::DnsRefresher::spawn(
+            network.downgrade(),
+            worker.committee.clone(),
+            worker.worker_cache.clone(),
+            parameters.dns_refresh_interval,
+            rx_reconfigure.clone(),
+        );
+
+        let network_admin_server_address = parameters.network_admin_server.worker_address(id);
         info!(
-            "Worker {} listening to network admin messages on 127.0.0.1:{}",
-            id, network_admin_server_base_port
+            "Worker {} listening to network admin messages on {}",
+            id, network_admin_server_address
         );
 
+        let state_dump = StateDump::default();
+        let dump_metrics = channel_metrics.clone();
+        state_dump.register("channels", move || json!(dump_metrics.depths()));
+
         let admin_handles = network::admin::start_admin_server(
-            network_admin_server_base_port,
+            network_admin_server_address,
+            parameters.network_admin_server.auth.clone(),
             network.clone(),
+            worker.worker_cache.clone(),
+            state_dump,
+            // The health of the node is aggregated by the primary, and shared with the workers
+            // it runs in the same process.
+            NodeHealth::of_authority(&worker.primary_name),
+            StorageMaintenance::of_authority(&worker.primary_name),
             rx_reconfigure.clone(),
             None,
         );
@@ -289,6 +359,7 @@ impl Worker {
             rx_our_batch,
             rx_others_batch,
             network.clone(),
+            parameters.memory_budget.max_pending_digests,
         );
         let client_flow_handles = worker.handle_clients_transactions(
             rx_reconfigure.clone(),
@@ -317,6 +388,8 @@ impl Worker {
         let mut handles = vec![
             primary_connector_handle,
             connection_monitor_handle,
+            handshake_monitor_handle,
+            dns_refresher_handle,
             network_shutdown_handle,
         ];
         handles.extend(admin_handles);
@@ -371,6 +444,7 @@ impl Worker {
         rx_reconfigure: watch::Receiver<ReconfigureNotification>,
         tx_our_batch: Sender<(
             WorkerOurBatchMessage,
+            TraceId,
             Option<tokio::sync::oneshot::Sender<()>>,
         )>,
         node_metrics: Arc<WorkerMetrics>,
@@ -380,12 +454,16 @@ impl Worker {
         network: anemo::Network,
     ) -> Vec<JoinHandle<()>> {
         let (tx_batch_maker, rx_batch_maker) = channel_with_total(
-            CHANNEL_CAPACITY,
+            self.parameters
+                .memory_budget
+                .channel_capacity("tx_batch_maker"),
             &channel_metrics.tx_batch_maker,
             &channel_metrics.tx_batch_maker_total,
         );
         let (tx_quorum_waiter, rx_quorum_waiter) = channel_with_total(
-            CHANNEL_CAPACITY,
+            self.parameters
+                .memory_budget
+                .channel_capacity("tx_quorum_waiter"),
             &channel_metrics.tx_quorum_waiter,
             &channel_metrics.tx_quorum_waiter_total,
         );
@@ -397,14 +475,21 @@ impl Worker {
             .worker(&self.primary_name, &self.id)
             .expect("Our public key or worker id is not in the worker cache")
             .transactions;
-        let address = address
-            .replace(0, |_protocol| Some(Protocol::Ip4(Ipv4Addr::UNSPECIFIED)))
-            .unwrap();
+        let address = network::listen_address(&address, self.parameters.network.dual_stack);
         let tx_receiver_handle = TxReceiverHandler {
+            id: self.id,
             tx_batch_maker,
             validator,
+            max_transaction_size: self.parameters.network.max_transactions_message_size,
+            committee: self.committee.clone(),
         }
-        .spawn(address.clone(), rx_reconfigure.clone(), endpoint_metrics);
+        .spawn(
+            address.clone(),
+            self.parameters.max_concurrent_requests,
+            &self.parameters.network,
+            rx_reconfigure.clone(),
+            endpoint_metrics,
+        );
 
         // The transactions are sent to the `BatchMaker` that assembles them into batches. It then broadcasts
         // (in a reliable manner) the batches to all other workers that share the same `id` as us. Finally, it
@@ -414,6 +499,7 @@ impl Worker {
             (*(*(*self.committee).load()).clone()).clone(),
             self.parameters.batch_size,
             self.parameters.max_batch_delay,
+            self.parameters.memory_budget.max_parallel_batches,
             rx_reconfigure.clone(),
             rx_batch_maker,
             tx_quorum_waiter,
@@ -429,6 +515,7 @@ impl Worker {
             self.id,
             (*(*(*self.committee).load()).clone()).clone(),
             self.worker_cache.clone(),
+            self.parameters.memory_budget.max_parallel_batches,
             rx_reconfigure,
             /* rx_message */ rx_quorum_waiter,
             network,
@@ -446,8 +533,59 @@ impl Worker {
 /// Defines how the network receiver handles incoming transactions.
 #[derive(Clone)]
 struct TxReceiverHandler<V> {
-    tx_batch_maker: Sender<(Transaction, TxResponse)>,
+    id: WorkerId,
+    tx_batch_maker: Sender<(
+        Transaction,
+        Option<TransactionMetadata>,
+        TraceId,
+        TxResponse,
+    )>,
     validator: V,
+    /// The maximum size of the transactions, metadata included.
+    max_transaction_size: usize,
+    /// The committee, whose protocol decides whether the transactions can carry metadata.
+    committee: SharedCommittee,
+}
+
+/// The trace id of a submission, as set by the client in the metadata of its request or a new one.
+fn request_trace_id<T>(request: &Request<T>) -> TraceId {
+    request
+        .metadata()
+        .get(trace_id::TRACE_ID_HEADER)
+        .and_then(|id| id.to_str().ok())
+        .and_then(TraceId::parse)
+        .unwrap_or_else(TraceId::generate)
+}
+
+/// Rejects the transactions larger than `max_size`, metadata included.
+fn check_transaction_size(txn: &TransactionProto, max_size: usize) -> Result<(), Status> {
+    let size = txn.transaction.len() + txn.metadata.len();
+    if size > max_size {
+        return Err(ErrorDetails::new(ErrorCode::TransactionTooLarge)
+            .with_limit("max_transaction_size", max_size as u64, size as u64)
+            .status(format!(
+                "Transaction size is too large: {} > {}",
+                size, max_size
+            )));
+    }
+    Ok(())
+}
+
+/// The metadata of a submitted transaction, if any and if the committee protocol supports it.
+fn transaction_metadata(
+    txn: &TransactionProto,
+    committee: &SharedCommittee,
+) -> Result<Option<TransactionMetadata>, Status> {
+    if txn.metadata.is_empty() {
+        return Ok(None);
+    }
+    // The metadata needs the batch layout of the protocol configs supporting it, which the nodes
+    // that did not upgrade yet cannot read.
+    if !committee.load().protocol_config.transaction_metadata() {
+        return Err(ErrorCode::Unsupported
+            .status("Transaction metadata is not supported by the committee protocol version"));
+    }
+    Ok(Some(txn.metadata.clone()))
 }
 
 impl<V: TransactionValidator> TxReceiverHandler<V> {
@@ -466,13 +604,23 @@ impl<V: TransactionValidator> TxReceiverHandler<V> {
     fn spawn(
         self,
         address: Multiaddr,
+        max_concurrent_requests: usize,
+        network_parameters: &NetworkParameters,
         rx_reconfigure: watch::Receiver<ReconfigureNotification>,
         endpoint_metrics: WorkerEndpointMetrics,
     ) -> JoinHandle<()> {
+        let config = mysten_network::config::Config {
+            global_concurrency_limit: Some(max_concurrent_requests),
+            request_timeout: network_parameters.grpc_request_timeout,
+            http2_keepalive_interval: network_parameters.grpc_http2_keepalive_interval,
+            http2_keepalive_timeout: network_parameters.grpc_http2_keepalive_timeout,
+            tcp_keepalive: network_parameters.grpc_tcp_keepalive,
+            ..Default::default()
+        };
         spawn_logged_monitored_task!(
             async move {
                 tokio::select! {
-                    _result =  mysten_network::config::Config::new()
+                    _result = config
                         .server_builder_with_metrics(endpoint_metrics)
                         .add_service(TransactionsServer::new(self))
                         .bind(&address)
@@ -488,56 +636,85 @@ impl<V: TransactionValidator> TxReceiverHandler<V> {
     }
 }
 
-#[async_trait]
-impl<V: TransactionValidator> Transactions for TxReceiverHandler<V> {
-    async fn submit_transaction(
+impl<V: TransactionValidator> TxReceiverHandler<V> {
+    /// Submits the transaction to the batch maker, and waits for its batch to be reported to
+    /// the primary.
+    async fn submit(
         &self,
-        request: Request<TransactionProto>,
-    ) -> Result<Response<Empty>, Status> {
-        let message = request.into_inner().transaction;
-        if message.len() > MAX_ALLOWED_TRANSACTION_SIZE {
-            return Err(Status::resource_exhausted(format!(
-                "Transaction size is too large: {} > {}",
-                message.len(),
-                MAX_ALLOWED_TRANSACTION_SIZE
-            )));
-        }
+        txn: TransactionProto,
+        trace_id: TraceId,
+    ) -> Result<Response<SubmitTransactionResponse>, Status> {
+        let metadata = transaction_metadata(&txn, &self.committee)?;
+        check_transaction_size(&txn, self.max_transaction_size)?;
+        let message = txn.transaction;
         if self.validator.validate(message.as_ref()).is_err() {
-            return Err(Status::invalid_argument("Invalid transaction"));
+            return Err(ErrorCode::InvalidTransaction.status("Invalid transaction"));
         }
         // Send the transaction to the batch maker.
         let (notifier, when_done) = tokio::sync::oneshot::channel();
         self.tx_batch_maker
-            .send((message.to_vec(), notifier))
+            .send((message.clone(), metadata, trace_id.clone(), notifier))
             .await
-            .map_err(|_| DagError::ShuttingDown)
-            .map_err(|e| Status::not_found(e.to_string()))?;
-
-        // TODO: distingush between a digest being returned vs the channel closing
-        // suggesting an error.
-        let _digest = when_done.await;
+            .map_err(|_| ErrorCode::ShuttingDown.status(DagError::ShuttingDown.to_string()))?;
+
+        // The batch maker drops the notifier if the batch could not be stored or reported to
+        // the primary.
+        let (batch_digest, batch_sequence) = when_done.await.map_err(|_| {
+            ErrorCode::NotIncluded.status("The transaction was not included in a batch")
+        })?;
+        debug!(%trace_id, "Transaction included in batch {batch_digest}");
+
+        let mut response = Response::new(SubmitTransactionResponse {
+            transaction_digest: Bytes::from(transaction_digest(&message).to_vec()),
+            worker_id: self.id,
+            batch_digest: Bytes::from(batch_digest.0.to_vec()),
+            batch_sequence,
+        });
+        // Let the client correlate its submission with the logs of the nodes.
+        if let Ok(value) = trace_id.as_str().parse() {
+            response
+                .metadata_mut()
+                .insert(trace_id::TRACE_ID_HEADER, value);
+        }
+        Ok(response)
+    }
+}
 
-        Ok(Response::new(Empty {}))
+#[async_trait]
+impl<V: TransactionValidator> Transactions for TxReceiverHandler<V> {
+    async fn submit_transaction(
+        &self,
+        request: Request<TransactionProto>,
+    ) -> Result<Response<SubmitTransactionResponse>, Status> {
+        let trace_id = request_trace_id(&request);
+        let span = tracing::info_span!("submit_transaction", %trace_id);
+        trace_id::join(&span, &trace_id);
+        self.submit(request.into_inner(), trace_id)
+            .instrument(span)
+            .await
     }
 
     async fn submit_transaction_stream(
         &self,
         request: Request<tonic::Streaming<types::TransactionProto>>,
     ) -> Result<Response<types::Empty>, Status> {
+        // The transactions of a stream share its trace id.
+        let trace_id = request_trace_id(&request);
         let mut transactions = request.into_inner();
         let mut responses = Vec::new();
 
         while let Some(Ok(txn)) = transactions.next().await {
+            check_transaction_size(&txn, self.max_transaction_size)?;
             if let Err(err) = self.validator.validate(txn.transaction.as_ref()) {
                 // If the transaction is invalid (often cryptographically), better to drop the client
-                return Err(Status::invalid_argument(format!(
-                    "Stream contains an invalid transaction {err}"
-                )));
+                return Err(ErrorCode::InvalidTransaction
+                    .status(format!("Stream contains an invalid transaction {err}")));
             }
+            let metadata = transaction_metadata(&txn, &self.committee)?;
             // Send the transaction to the batch maker.
             let (notifier, when_done) = tokio::sync::oneshot::channel();
             self.tx_batch_maker
-                .send((txn.transaction.to_vec(), notifier))
+                .send((txn.transaction, metadata, trace_id.clone(), notifier))
                 .await
                 .expect("Failed to send transaction");
 